@@ -4,12 +4,16 @@
 use dashmap::DashMap;
 use std::future::Future;
 use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use std::task::{Context, Poll};
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 use once_cell::sync::OnceCell;
-use prometheus::{register_int_gauge_vec_with_registry, IntGaugeVec, Registry};
+use prometheus::{
+    register_int_counter_vec_with_registry, register_int_gauge_vec_with_registry, IntCounterVec,
+    IntGaugeVec, Registry,
+};
 use tap::TapFallible;
 use tracing::warn;
 
@@ -24,6 +28,7 @@ pub struct Metrics {
     pub futures: IntGaugeVec,
     pub scope_iterations: IntGaugeVec,
     pub scope_duration_ns: IntGaugeVec,
+    pub slow_polls: IntCounterVec,
 }
 
 impl Metrics {
@@ -57,6 +62,13 @@ impl Metrics {
                 registry,
             )
             .unwrap(),
+            slow_polls: register_int_counter_vec_with_registry!(
+                "monitored_slow_polls",
+                "Number of polls of a monitored future that took longer than the slow poll threshold, per callsite.",
+                &["callsite"],
+                registry,
+            )
+            .unwrap(),
         }
     }
 }
@@ -123,6 +135,21 @@ macro_rules! monitored_future {
     }};
 }
 
+/// Like [`monitored_future`], also reporting the polls of the future that take longer than the
+/// slow poll threshold, see [`set_slow_poll_threshold`].
+#[macro_export]
+macro_rules! slow_poll_monitored_future {
+    ($fut: expr) => {{
+        mysten_metrics::monitored_future!(
+            futures,
+            mysten_metrics::SlowPollDetector::new(concat!(file!(), ':', line!()), $fut),
+            "",
+            INFO,
+            false
+        )
+    }};
+}
+
 #[macro_export]
 macro_rules! spawn_monitored_task {
     ($fut: expr) => {
@@ -157,6 +184,21 @@ macro_rules! spawn_logged_monitored_task {
     };
 }
 
+/// Like [`spawn_monitored_task`], also reporting the polls of the task that take longer than the
+/// slow poll threshold, see [`set_slow_poll_threshold`].
+#[macro_export]
+macro_rules! spawn_slow_poll_monitored_task {
+    ($fut: expr) => {
+        tokio::task::spawn(mysten_metrics::monitored_future!(
+            tasks,
+            mysten_metrics::SlowPollDetector::new(concat!(file!(), ':', line!()), $fut),
+            "",
+            INFO,
+            false
+        ))
+    };
+}
+
 pub struct MonitoredScopeGuard {
     metrics: &'static Metrics,
     name: &'static str,
@@ -220,6 +262,57 @@ impl<F: Future> Future for MonitoredScopeFuture<F> {
     }
 }
 
+// In nanoseconds, 0 when slow polls are not reported.
+static SLOW_POLL_THRESHOLD_NS: AtomicU64 = AtomicU64::new(0);
+
+/// Makes the futures of [`slow_poll_monitored_future`] and [`spawn_slow_poll_monitored_task`]
+/// report each poll that takes longer than `threshold`, which means the future blocked the worker
+/// thread polling it for that long, e.g. with a synchronous database call. Such polls are logged
+/// along with the callsite of the future, and counted in the `monitored_slow_polls` metric.
+pub fn set_slow_poll_threshold(threshold: Duration) {
+    SLOW_POLL_THRESHOLD_NS.store(threshold.as_nanos() as u64, Ordering::Relaxed);
+}
+
+/// Wraps the futures of [`slow_poll_monitored_future`] and [`spawn_slow_poll_monitored_task`] to
+/// detect slow polls, see [`set_slow_poll_threshold`].
+pub struct SlowPollDetector<F: Sized> {
+    f: Pin<Box<F>>,
+    location: &'static str,
+}
+
+impl<F: Future> SlowPollDetector<F> {
+    pub fn new(location: &'static str, f: F) -> Self {
+        Self {
+            f: Box::pin(f),
+            location,
+        }
+    }
+}
+
+impl<F: Future> Future for SlowPollDetector<F> {
+    type Output = F::Output;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let threshold_ns = SLOW_POLL_THRESHOLD_NS.load(Ordering::Relaxed);
+        if threshold_ns == 0 {
+            return self.f.as_mut().poll(cx);
+        }
+        let start = Instant::now();
+        let result = self.f.as_mut().poll(cx);
+        let elapsed = start.elapsed();
+        if elapsed.as_nanos() > threshold_ns as u128 {
+            warn!(
+                location = self.location,
+                "Poll of a monitored future took {elapsed:?}, blocking its worker thread"
+            );
+            if let Some(m) = get_metrics() {
+                m.slow_polls.with_label_values(&[self.location]).inc();
+            }
+        }
+        result
+    }
+}
+
 pub type RegistryID = Uuid;
 
 /// A service to manage the prometheus registries. This service allow us to create
@@ -312,8 +405,39 @@ pub fn uptime_metric(version: &'static str) -> Box<dyn prometheus::core::Collect
 #[cfg(test)]
 mod tests {
     use crate::RegistryService;
+    use crate::{get_metrics, init_metrics, set_slow_poll_threshold, SlowPollDetector};
+    use futures::executor::block_on;
     use prometheus::IntCounter;
     use prometheus::Registry;
+    use std::time::Duration;
+
+    #[test]
+    fn slow_poll_detector() {
+        init_metrics(&Registry::new());
+        let location = "slow_poll_detector";
+        let slow_polls = || {
+            get_metrics()
+                .unwrap()
+                .slow_polls
+                .with_label_values(&[location])
+                .get()
+        };
+        let slow_poll = || async { std::thread::sleep(Duration::from_millis(20)) };
+
+        // Slow polls are not reported until a threshold is set.
+        block_on(SlowPollDetector::new(location, slow_poll()));
+        assert_eq!(slow_polls(), 0);
+
+        set_slow_poll_threshold(Duration::from_millis(10));
+        block_on(SlowPollDetector::new(location, async {}));
+        assert_eq!(slow_polls(), 0);
+        block_on(SlowPollDetector::new(location, slow_poll()));
+        assert_eq!(slow_polls(), 1);
+
+        set_slow_poll_threshold(Duration::ZERO);
+        block_on(SlowPollDetector::new(location, slow_poll()));
+        assert_eq!(slow_polls(), 1);
+    }
 
     #[test]
     fn registry_service() {
@@ -0,0 +1,315 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Exports the checkpoints of an archive, see [`sui_storage::archive`], as columnar datasets
+//! that can be queried in place by BigQuery, Athena and the like. The archive must have the
+//! transactions of its checkpoints. The datasets are written to a bucket or a local directory
+//! with the following layout:
+//!
+//! - `PROGRESS`: the JSON encoded [`ExportProgress`]
+//! - `<dataset>/epoch=<epoch>/date=<YYYY-MM-DD>/<first>_<last>.<parquet|csv>`: the rows of the
+//!   checkpoints from `<first>` to `<last>`, which are all in the epoch and the (UTC) day of the
+//!   partition
+//!
+//! where the datasets are `transactions`, `events`, `objects` (the changes made to objects) and
+//! `move_calls`, see [`tables`]. Files are written again with the same contents when the export
+//! resumes after a failure, so they are never duplicated.
+
+pub mod tables;
+
+use anyhow::{anyhow, Result};
+use chrono::NaiveDateTime;
+use parquet::arrow::ArrowWriter;
+use parquet::basic::Compression;
+use parquet::file::properties::WriterProperties;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+use std::time::Duration;
+use sui_storage::archive::{ArchiveReader, ArchiveStore, ArchivedCheckpoint};
+use sui_types::committee::EpochId;
+use sui_types::messages_checkpoint::CheckpointSequenceNumber;
+use tables::{CheckpointInfo, Rows, Table};
+use tracing::{info, warn};
+
+const PROGRESS_PATH: &str = "PROGRESS";
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum FileFormat {
+    #[default]
+    Parquet,
+    Csv,
+}
+
+impl FileFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            FileFormat::Parquet => "parquet",
+            FileFormat::Csv => "csv",
+        }
+    }
+}
+
+impl FromStr for FileFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "parquet" => Ok(FileFormat::Parquet),
+            "csv" => Ok(FileFormat::Csv),
+            _ => Err(anyhow!("Unknown file format {s}, expected parquet or csv")),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct AnalyticsConfig {
+    pub format: FileFormat,
+    /// The maximum number of checkpoints in a file. Files end early at the end of a partition,
+    /// or of the checkpoints archived so far.
+    pub checkpoints_per_file: u64,
+    /// How often the archive is checked for new checkpoints.
+    pub export_interval: Duration,
+}
+
+/// How far the export went, written after the files it accounts for.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct ExportProgress {
+    /// The checkpoints before this one are exported.
+    pub next_checkpoint: CheckpointSequenceNumber,
+}
+
+/// The partition of the datasets that the rows of a checkpoint are in.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Partition {
+    epoch: EpochId,
+    /// The UTC day of the checkpoint, as `YYYY-MM-DD`.
+    date: String,
+}
+
+pub struct AnalyticsExporter {
+    reader: ArchiveReader,
+    store: ArchiveStore,
+    config: AnalyticsConfig,
+}
+
+impl AnalyticsExporter {
+    /// Exports the checkpoints read by `reader` to `store`.
+    pub fn new(reader: ArchiveReader, store: ArchiveStore, config: AnalyticsConfig) -> Self {
+        Self {
+            reader,
+            store,
+            config,
+        }
+    }
+
+    /// How far the export went, from the start for a new export.
+    pub async fn progress(&self) -> Result<ExportProgress> {
+        match self.store.get(PROGRESS_PATH).await? {
+            Some(bytes) => Ok(serde_json::from_slice(&bytes)?),
+            None => Ok(ExportProgress::default()),
+        }
+    }
+
+    /// Exports the new archived checkpoints every export interval, forever. Fails only if the
+    /// progress of the export cannot be read.
+    pub async fn run(self) -> Result<()> {
+        let mut progress = self
+            .progress()
+            .await
+            .map_err(|e| anyhow!("Cannot read the progress of the export: {e:#}"))?;
+        info!(
+            next_checkpoint = progress.next_checkpoint,
+            "Exporting checkpoints"
+        );
+
+        let mut interval = tokio::time::interval(self.config.export_interval);
+        loop {
+            interval.tick().await;
+            if let Err(e) = self.export_new_checkpoints(&mut progress).await {
+                warn!(
+                    next_checkpoint = progress.next_checkpoint,
+                    "Failed to export checkpoints: {e:#}"
+                );
+            }
+        }
+    }
+
+    /// Exports the checkpoints archived from `progress.next_checkpoint` on, and advances it.
+    pub async fn export_new_checkpoints(&self, progress: &mut ExportProgress) -> Result<()> {
+        loop {
+            let checkpoints = self.next_file_checkpoints(progress.next_checkpoint).await?;
+            let Some(last) = checkpoints.last() else {
+                return Ok(());
+            };
+            let next_checkpoint = last.summary.summary.sequence_number + 1;
+            self.export_checkpoints(&checkpoints).await?;
+
+            let next = ExportProgress { next_checkpoint };
+            self.store
+                .put(PROGRESS_PATH, serde_json::to_vec_pretty(&next)?)
+                .await?;
+            *progress = next;
+        }
+    }
+
+    /// The archived checkpoints from `start` that go in the same files.
+    async fn next_file_checkpoints(
+        &self,
+        start: CheckpointSequenceNumber,
+    ) -> Result<Vec<ArchivedCheckpoint>> {
+        let mut checkpoints: Vec<ArchivedCheckpoint> = vec![];
+        for sequence_number in start..start + self.config.checkpoints_per_file.max(1) {
+            let Some(checkpoint) = self.reader.get_checkpoint(sequence_number).await? else {
+                break;
+            };
+            if let Some(first) = checkpoints.first() {
+                if partition_of(first)? != partition_of(&checkpoint)? {
+                    break;
+                }
+            }
+            checkpoints.push(checkpoint);
+        }
+        Ok(checkpoints)
+    }
+
+    /// Writes the rows of `checkpoints`, which are in the same partition, to a file per dataset.
+    async fn export_checkpoints(&self, checkpoints: &[ArchivedCheckpoint]) -> Result<()> {
+        let mut rows = Rows::default();
+        for checkpoint in checkpoints {
+            let summary = &checkpoint.summary.summary;
+            let sequence_number = summary.sequence_number;
+            let transactions =
+                futures::future::try_join_all(checkpoint.contents.iter().map(|execution| {
+                    let digest = execution.transaction;
+                    async move {
+                        self.reader.get_transaction(digest).await?.ok_or_else(|| {
+                            anyhow!(
+                                "Transaction {digest} of checkpoint {sequence_number} is not in \
+                                 the archive, which must be written with its transactions"
+                            )
+                        })
+                    }
+                }))
+                .await?;
+            let info = CheckpointInfo {
+                checkpoint: sequence_number,
+                epoch: summary.epoch,
+                timestamp_ms: summary.timestamp_ms,
+            };
+            for transaction in &transactions {
+                rows.add_transaction(info, transaction);
+            }
+        }
+
+        let (first, last) = (&checkpoints[0], &checkpoints[checkpoints.len() - 1]);
+        let partition = partition_of(first)?;
+        let file_name = format!(
+            "{}_{}.{}",
+            first.summary.summary.sequence_number,
+            last.summary.summary.sequence_number,
+            self.config.format.extension()
+        );
+        self.put_table(&partition, &file_name, &rows.transactions)
+            .await?;
+        self.put_table(&partition, &file_name, &rows.events).await?;
+        self.put_table(&partition, &file_name, &rows.objects)
+            .await?;
+        self.put_table(&partition, &file_name, &rows.move_calls)
+            .await?;
+        info!(
+            first = first.summary.summary.sequence_number,
+            last = last.summary.summary.sequence_number,
+            transactions = rows.transactions.len(),
+            "Exported checkpoints"
+        );
+        Ok(())
+    }
+
+    /// Writes the file of `rows`, unless there are none.
+    async fn put_table<T: Table>(
+        &self,
+        partition: &Partition,
+        file_name: &str,
+        rows: &[T],
+    ) -> Result<()> {
+        if rows.is_empty() {
+            return Ok(());
+        }
+        let path = format!(
+            "{}/epoch={}/date={}/{file_name}",
+            T::NAME,
+            partition.epoch,
+            partition.date
+        );
+        self.store
+            .put(&path, encode(rows, self.config.format)?)
+            .await
+    }
+}
+
+/// Encodes `rows` as a file of `format`, with a header for CSV.
+pub fn encode<T: Table>(rows: &[T], format: FileFormat) -> Result<Vec<u8>> {
+    let batch = T::to_record_batch(rows)?;
+    let mut bytes = vec![];
+    match format {
+        FileFormat::Parquet => {
+            let properties = WriterProperties::builder()
+                .set_compression(Compression::SNAPPY)
+                .build();
+            let mut writer = ArrowWriter::try_new(&mut bytes, batch.schema(), Some(properties))?;
+            writer.write(&batch)?;
+            writer.close()?;
+        }
+        FileFormat::Csv => arrow::csv::Writer::new(&mut bytes).write(&batch)?,
+    }
+    Ok(bytes)
+}
+
+fn partition_of(checkpoint: &ArchivedCheckpoint) -> Result<Partition> {
+    let summary = &checkpoint.summary.summary;
+    let time = NaiveDateTime::from_timestamp_opt((summary.timestamp_ms / 1000) as i64, 0)
+        .ok_or_else(|| {
+            anyhow!(
+                "Checkpoint {} has an invalid timestamp {}",
+                summary.sequence_number,
+                summary.timestamp_ms
+            )
+        })?;
+    Ok(Partition {
+        epoch: summary.epoch,
+        date: time.format("%Y-%m-%d").to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tables::MoveCallRow;
+
+    #[test]
+    fn test_encode() {
+        let rows = vec![MoveCallRow {
+            transaction_digest: "digest".to_string(),
+            call_index: 0,
+            checkpoint: 7,
+            epoch: 1,
+            timestamp_ms: 1_000,
+            sender: "0x1".to_string(),
+            package: "0x2".to_string(),
+            module: "coin".to_string(),
+            function: "split".to_string(),
+        }];
+
+        let csv = String::from_utf8(encode(&rows, FileFormat::Csv).unwrap()).unwrap();
+        assert_eq!(
+            csv,
+            "transaction_digest,call_index,checkpoint,epoch,timestamp_ms,sender,package,module,\
+             function\ndigest,0,7,1,1000,0x1,0x2,coin,split\n"
+        );
+
+        let parquet = encode(&rows, FileFormat::Parquet).unwrap();
+        assert!(parquet.starts_with(b"PAR1") && parquet.ends_with(b"PAR1"));
+    }
+}
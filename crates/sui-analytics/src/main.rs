@@ -0,0 +1,105 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{anyhow, bail, Result};
+use clap::Parser;
+use std::path::PathBuf;
+use std::time::Duration;
+use sui_analytics::{AnalyticsConfig, AnalyticsExporter, FileFormat};
+use sui_storage::archive::{ArchiveReader, ArchiveStore};
+
+#[derive(Parser)]
+#[clap(
+    name = "sui-analytics",
+    about = "Exports the checkpoints of an archive as datasets for analytics",
+    rename_all = "kebab-case"
+)]
+struct Args {
+    /// The URL of the archive that the checkpoints are read from, with their transactions
+    #[clap(long)]
+    archive_url: Option<String>,
+
+    /// The directory of the archive, instead of its URL
+    #[clap(long)]
+    archive_dir: Option<PathBuf>,
+
+    /// The URL of the bucket that the datasets are written to
+    #[clap(long)]
+    output_url: Option<String>,
+
+    /// The directory that the datasets are written to, instead of a bucket
+    #[clap(long)]
+    output_dir: Option<PathBuf>,
+
+    /// The environment variable holding the token that the writes to the bucket are authorized
+    /// with
+    #[clap(long)]
+    output_auth_token_env_var: Option<String>,
+
+    /// `parquet` or `csv`
+    #[clap(long, default_value = "parquet")]
+    format: FileFormat,
+
+    #[clap(long, default_value_t = 1000)]
+    checkpoints_per_file: u64,
+
+    #[clap(long, default_value_t = 60)]
+    export_interval_secs: u64,
+
+    #[clap(long, default_value_t = 30)]
+    request_timeout_secs: u64,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let _guard = telemetry_subscribers::TelemetryConfig::new()
+        .with_env()
+        .init();
+    let args = Args::parse();
+    let request_timeout = Duration::from_secs(args.request_timeout_secs);
+
+    let reader = ArchiveReader::from_store(open_store(
+        args.archive_dir,
+        args.archive_url.as_deref(),
+        None,
+        request_timeout,
+    )?);
+    let auth_token = args
+        .output_auth_token_env_var
+        .map(|var| {
+            std::env::var(&var)
+                .map_err(|e| anyhow!("Cannot read output auth token from {var}: {e}"))
+        })
+        .transpose()?;
+    let store = open_store(
+        args.output_dir,
+        args.output_url.as_deref(),
+        auth_token,
+        request_timeout,
+    )?;
+
+    AnalyticsExporter::new(
+        reader,
+        store,
+        AnalyticsConfig {
+            format: args.format,
+            checkpoints_per_file: args.checkpoints_per_file,
+            export_interval: Duration::from_secs(args.export_interval_secs),
+        },
+    )
+    .run()
+    .await
+}
+
+fn open_store(
+    dir: Option<PathBuf>,
+    url: Option<&str>,
+    auth_token: Option<String>,
+    request_timeout: Duration,
+) -> Result<ArchiveStore> {
+    match (dir, url) {
+        (Some(dir), None) => Ok(ArchiveStore::Directory(dir)),
+        (None, Some(url)) => ArchiveStore::http(url, auth_token, request_timeout),
+        _ => bail!("Exactly one of a directory and a URL must be given for each store"),
+    }
+}
@@ -0,0 +1,428 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! The rows of the datasets, as read from archived transactions, and their columns.
+
+use anyhow::Result;
+use arrow::array::{ArrayRef, BooleanArray, StringArray, UInt64Array};
+use arrow::datatypes::{DataType, Field, Schema};
+use arrow::record_batch::RecordBatch;
+use std::sync::Arc;
+use sui_storage::archive::ArchivedTransaction;
+use sui_types::base_types::{ObjectID, ObjectRef};
+use sui_types::event::Event;
+use sui_types::messages::{
+    Command, ExecutionStatus, SingleTransactionKind, TransactionDataAPI, TransactionEffectsAPI,
+    TransactionKind,
+};
+use sui_types::messages_checkpoint::CheckpointSequenceNumber;
+use sui_types::object::Owner;
+
+/// A dataset, written as one table per file.
+pub trait Table: Sized {
+    /// The name of the dataset, which is the directory its files are in.
+    const NAME: &'static str;
+
+    fn schema() -> Schema;
+
+    /// The columns of `rows`, in the order of the schema.
+    fn columns(rows: &[Self]) -> Vec<ArrayRef>;
+
+    fn to_record_batch(rows: &[Self]) -> Result<RecordBatch> {
+        Ok(RecordBatch::try_new(
+            Arc::new(Self::schema()),
+            Self::columns(rows),
+        )?)
+    }
+}
+
+/// The checkpoint a transaction was executed in.
+#[derive(Clone, Copy, Debug)]
+pub struct CheckpointInfo {
+    pub checkpoint: CheckpointSequenceNumber,
+    pub epoch: u64,
+    pub timestamp_ms: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TransactionRow {
+    pub digest: String,
+    pub checkpoint: u64,
+    pub epoch: u64,
+    pub timestamp_ms: u64,
+    pub sender: String,
+    /// The kind of the transaction, e.g. `ProgrammableTransaction`, or `Batch`.
+    pub kind: String,
+    pub is_system: bool,
+    pub success: bool,
+    pub gas_budget: u64,
+    pub gas_price: u64,
+    pub computation_cost: u64,
+    pub storage_cost: u64,
+    pub storage_rebate: u64,
+    pub objects_changed: u64,
+    pub events: u64,
+    pub move_calls: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EventRow {
+    pub transaction_digest: String,
+    /// The position of the event among the events of its transaction.
+    pub event_index: u64,
+    pub checkpoint: u64,
+    pub epoch: u64,
+    pub timestamp_ms: u64,
+    /// The kind of the event, e.g. `MoveEvent` or `NewObject`.
+    pub event_type: String,
+    /// The struct type of Move events.
+    pub move_event_type: Option<String>,
+    pub sender: Option<String>,
+    pub package: Option<String>,
+    pub module: Option<String>,
+    pub object_id: Option<String>,
+}
+
+/// A change made to an object by a transaction.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ObjectRow {
+    pub object_id: String,
+    /// The version of the object after the change.
+    pub version: u64,
+    pub digest: String,
+    pub transaction_digest: String,
+    pub checkpoint: u64,
+    pub epoch: u64,
+    pub timestamp_ms: u64,
+    /// One of `created`, `mutated`, `unwrapped`, `deleted`, `wrapped` and
+    /// `unwrapped_then_deleted`.
+    pub change: String,
+    /// One of `address`, `object`, `shared` and `immutable`, for the objects still in storage.
+    pub owner_type: Option<String>,
+    pub owner: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MoveCallRow {
+    pub transaction_digest: String,
+    /// The position of the call among the calls of its transaction.
+    pub call_index: u64,
+    pub checkpoint: u64,
+    pub epoch: u64,
+    pub timestamp_ms: u64,
+    pub sender: String,
+    pub package: String,
+    pub module: String,
+    pub function: String,
+}
+
+/// The rows of all the datasets for some transactions.
+#[derive(Clone, Debug, Default)]
+pub struct Rows {
+    pub transactions: Vec<TransactionRow>,
+    pub events: Vec<EventRow>,
+    pub objects: Vec<ObjectRow>,
+    pub move_calls: Vec<MoveCallRow>,
+}
+
+impl Rows {
+    pub fn add_transaction(&mut self, info: CheckpointInfo, archived: &ArchivedTransaction) {
+        let data = archived.transaction.data().transaction_data();
+        let effects = &archived.effects;
+        let digest = effects.transaction_digest().to_string();
+        let sender = data.sender().to_string();
+
+        let move_calls = move_calls(data.kind());
+        for (call_index, (package, module, function)) in move_calls.iter().enumerate() {
+            self.move_calls.push(MoveCallRow {
+                transaction_digest: digest.clone(),
+                call_index: call_index as u64,
+                checkpoint: info.checkpoint,
+                epoch: info.epoch,
+                timestamp_ms: info.timestamp_ms,
+                sender: sender.clone(),
+                package: package.to_string(),
+                module: module.to_string(),
+                function: function.to_string(),
+            });
+        }
+
+        for (event_index, event) in archived.events.data.iter().enumerate() {
+            let move_event_type = match event {
+                Event::MoveEvent { type_, .. } => Some(type_.to_string()),
+                _ => None,
+            };
+            self.events.push(EventRow {
+                transaction_digest: digest.clone(),
+                event_index: event_index as u64,
+                checkpoint: info.checkpoint,
+                epoch: info.epoch,
+                timestamp_ms: info.timestamp_ms,
+                event_type: format!("{:?}", event.event_type()),
+                move_event_type,
+                sender: event.sender().map(|sender| sender.to_string()),
+                package: event.package_id().map(|package| package.to_string()),
+                module: event.module_name().map(str::to_string),
+                object_id: event.object_id().map(|object_id| object_id.to_string()),
+            });
+        }
+
+        let objects_before = self.objects.len();
+        let object_row = |change: &str, object_ref: &ObjectRef, owner: Option<&Owner>| {
+            let (owner_type, owner) = match owner {
+                Some(Owner::AddressOwner(address)) => (Some("address"), Some(address.to_string())),
+                Some(Owner::ObjectOwner(address)) => (Some("object"), Some(address.to_string())),
+                Some(Owner::Shared { .. }) => (Some("shared"), None),
+                Some(Owner::Immutable) => (Some("immutable"), None),
+                None => (None, None),
+            };
+            ObjectRow {
+                object_id: object_ref.0.to_string(),
+                version: object_ref.1.value(),
+                digest: object_ref.2.to_string(),
+                transaction_digest: digest.clone(),
+                checkpoint: info.checkpoint,
+                epoch: info.epoch,
+                timestamp_ms: info.timestamp_ms,
+                change: change.to_string(),
+                owner_type: owner_type.map(str::to_string),
+                owner,
+            }
+        };
+        for (change, objects) in [
+            ("created", effects.created()),
+            ("mutated", effects.mutated()),
+            ("unwrapped", effects.unwrapped()),
+        ] {
+            for (object_ref, owner) in objects {
+                self.objects
+                    .push(object_row(change, object_ref, Some(owner)));
+            }
+        }
+        for (change, objects) in [
+            ("deleted", effects.deleted()),
+            ("wrapped", effects.wrapped()),
+            ("unwrapped_then_deleted", effects.unwrapped_then_deleted()),
+        ] {
+            for object_ref in objects {
+                self.objects.push(object_row(change, object_ref, None));
+            }
+        }
+
+        let gas_cost = effects.gas_cost_summary();
+        self.transactions.push(TransactionRow {
+            digest,
+            checkpoint: info.checkpoint,
+            epoch: info.epoch,
+            timestamp_ms: info.timestamp_ms,
+            sender,
+            kind: kind_name(data.kind()).to_string(),
+            is_system: data.is_system_tx(),
+            success: matches!(effects.status(), ExecutionStatus::Success),
+            gas_budget: data.gas_budget(),
+            gas_price: data.gas_price(),
+            computation_cost: gas_cost.computation_cost,
+            storage_cost: gas_cost.storage_cost,
+            storage_rebate: gas_cost.storage_rebate,
+            objects_changed: (self.objects.len() - objects_before) as u64,
+            events: archived.events.data.len() as u64,
+            move_calls: move_calls.len() as u64,
+        });
+    }
+}
+
+fn kind_name(kind: &TransactionKind) -> &'static str {
+    match kind {
+        TransactionKind::Single(single) => single.into(),
+        TransactionKind::Batch(_) => "Batch",
+    }
+}
+
+/// The Move functions called by a transaction, in order.
+fn move_calls(kind: &TransactionKind) -> Vec<(ObjectID, &str, &str)> {
+    let mut calls = vec![];
+    for single in kind.single_transactions() {
+        match single {
+            SingleTransactionKind::Call(call) => {
+                calls.push((call.package, call.module.as_str(), call.function.as_str()))
+            }
+            SingleTransactionKind::ProgrammableTransaction(programmable) => {
+                for command in &programmable.commands {
+                    if let Command::MoveCall(call) = command {
+                        calls.push((call.package, call.module.as_str(), call.function.as_str()));
+                    }
+                }
+            }
+            _ => (),
+        }
+    }
+    calls
+}
+
+impl Table for TransactionRow {
+    const NAME: &'static str = "transactions";
+
+    fn schema() -> Schema {
+        Schema::new(vec![
+            Field::new("digest", DataType::Utf8, false),
+            Field::new("checkpoint", DataType::UInt64, false),
+            Field::new("epoch", DataType::UInt64, false),
+            Field::new("timestamp_ms", DataType::UInt64, false),
+            Field::new("sender", DataType::Utf8, false),
+            Field::new("kind", DataType::Utf8, false),
+            Field::new("is_system", DataType::Boolean, false),
+            Field::new("success", DataType::Boolean, false),
+            Field::new("gas_budget", DataType::UInt64, false),
+            Field::new("gas_price", DataType::UInt64, false),
+            Field::new("computation_cost", DataType::UInt64, false),
+            Field::new("storage_cost", DataType::UInt64, false),
+            Field::new("storage_rebate", DataType::UInt64, false),
+            Field::new("objects_changed", DataType::UInt64, false),
+            Field::new("events", DataType::UInt64, false),
+            Field::new("move_calls", DataType::UInt64, false),
+        ])
+    }
+
+    fn columns(rows: &[Self]) -> Vec<ArrayRef> {
+        vec![
+            strings(rows, |row| row.digest.as_str()),
+            numbers(rows, |row| row.checkpoint),
+            numbers(rows, |row| row.epoch),
+            numbers(rows, |row| row.timestamp_ms),
+            strings(rows, |row| row.sender.as_str()),
+            strings(rows, |row| row.kind.as_str()),
+            booleans(rows, |row| row.is_system),
+            booleans(rows, |row| row.success),
+            numbers(rows, |row| row.gas_budget),
+            numbers(rows, |row| row.gas_price),
+            numbers(rows, |row| row.computation_cost),
+            numbers(rows, |row| row.storage_cost),
+            numbers(rows, |row| row.storage_rebate),
+            numbers(rows, |row| row.objects_changed),
+            numbers(rows, |row| row.events),
+            numbers(rows, |row| row.move_calls),
+        ]
+    }
+}
+
+impl Table for EventRow {
+    const NAME: &'static str = "events";
+
+    fn schema() -> Schema {
+        Schema::new(vec![
+            Field::new("transaction_digest", DataType::Utf8, false),
+            Field::new("event_index", DataType::UInt64, false),
+            Field::new("checkpoint", DataType::UInt64, false),
+            Field::new("epoch", DataType::UInt64, false),
+            Field::new("timestamp_ms", DataType::UInt64, false),
+            Field::new("event_type", DataType::Utf8, false),
+            Field::new("move_event_type", DataType::Utf8, true),
+            Field::new("sender", DataType::Utf8, true),
+            Field::new("package", DataType::Utf8, true),
+            Field::new("module", DataType::Utf8, true),
+            Field::new("object_id", DataType::Utf8, true),
+        ])
+    }
+
+    fn columns(rows: &[Self]) -> Vec<ArrayRef> {
+        vec![
+            strings(rows, |row| row.transaction_digest.as_str()),
+            numbers(rows, |row| row.event_index),
+            numbers(rows, |row| row.checkpoint),
+            numbers(rows, |row| row.epoch),
+            numbers(rows, |row| row.timestamp_ms),
+            strings(rows, |row| row.event_type.as_str()),
+            optional_strings(rows, |row| row.move_event_type.as_deref()),
+            optional_strings(rows, |row| row.sender.as_deref()),
+            optional_strings(rows, |row| row.package.as_deref()),
+            optional_strings(rows, |row| row.module.as_deref()),
+            optional_strings(rows, |row| row.object_id.as_deref()),
+        ]
+    }
+}
+
+impl Table for ObjectRow {
+    const NAME: &'static str = "objects";
+
+    fn schema() -> Schema {
+        Schema::new(vec![
+            Field::new("object_id", DataType::Utf8, false),
+            Field::new("version", DataType::UInt64, false),
+            Field::new("digest", DataType::Utf8, false),
+            Field::new("transaction_digest", DataType::Utf8, false),
+            Field::new("checkpoint", DataType::UInt64, false),
+            Field::new("epoch", DataType::UInt64, false),
+            Field::new("timestamp_ms", DataType::UInt64, false),
+            Field::new("change", DataType::Utf8, false),
+            Field::new("owner_type", DataType::Utf8, true),
+            Field::new("owner", DataType::Utf8, true),
+        ])
+    }
+
+    fn columns(rows: &[Self]) -> Vec<ArrayRef> {
+        vec![
+            strings(rows, |row| row.object_id.as_str()),
+            numbers(rows, |row| row.version),
+            strings(rows, |row| row.digest.as_str()),
+            strings(rows, |row| row.transaction_digest.as_str()),
+            numbers(rows, |row| row.checkpoint),
+            numbers(rows, |row| row.epoch),
+            numbers(rows, |row| row.timestamp_ms),
+            strings(rows, |row| row.change.as_str()),
+            optional_strings(rows, |row| row.owner_type.as_deref()),
+            optional_strings(rows, |row| row.owner.as_deref()),
+        ]
+    }
+}
+
+impl Table for MoveCallRow {
+    const NAME: &'static str = "move_calls";
+
+    fn schema() -> Schema {
+        Schema::new(vec![
+            Field::new("transaction_digest", DataType::Utf8, false),
+            Field::new("call_index", DataType::UInt64, false),
+            Field::new("checkpoint", DataType::UInt64, false),
+            Field::new("epoch", DataType::UInt64, false),
+            Field::new("timestamp_ms", DataType::UInt64, false),
+            Field::new("sender", DataType::Utf8, false),
+            Field::new("package", DataType::Utf8, false),
+            Field::new("module", DataType::Utf8, false),
+            Field::new("function", DataType::Utf8, false),
+        ])
+    }
+
+    fn columns(rows: &[Self]) -> Vec<ArrayRef> {
+        vec![
+            strings(rows, |row| row.transaction_digest.as_str()),
+            numbers(rows, |row| row.call_index),
+            numbers(rows, |row| row.checkpoint),
+            numbers(rows, |row| row.epoch),
+            numbers(rows, |row| row.timestamp_ms),
+            strings(rows, |row| row.sender.as_str()),
+            strings(rows, |row| row.package.as_str()),
+            strings(rows, |row| row.module.as_str()),
+            strings(rows, |row| row.function.as_str()),
+        ]
+    }
+}
+
+fn strings<T>(rows: &[T], column: impl Fn(&T) -> &str) -> ArrayRef {
+    Arc::new(StringArray::from_iter_values(rows.iter().map(column)))
+}
+
+fn optional_strings<T>(rows: &[T], column: impl Fn(&T) -> Option<&str>) -> ArrayRef {
+    Arc::new(rows.iter().map(column).collect::<StringArray>())
+}
+
+fn numbers<T>(rows: &[T], column: impl Fn(&T) -> u64) -> ArrayRef {
+    Arc::new(UInt64Array::from_iter_values(rows.iter().map(column)))
+}
+
+fn booleans<T>(rows: &[T], column: impl Fn(&T) -> bool) -> ArrayRef {
+    Arc::new(
+        rows.iter()
+            .map(|row| Some(column(row)))
+            .collect::<BooleanArray>(),
+    )
+}
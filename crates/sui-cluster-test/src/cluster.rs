@@ -263,9 +263,13 @@ pub async fn new_wallet_context_from_cluster(
             alias: "localnet".to_string(),
             rpc: fullnode_url.into(),
             ws: None,
+            faucet: None,
+            keystore: None,
+            active_address: None,
         }],
         active_address: Some(address),
         active_env: Some("localnet".to_string()),
+        shared_keystore: None,
     }
     .persisted(&wallet_config_path)
     .save()
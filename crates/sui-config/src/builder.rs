@@ -3,7 +3,8 @@
 
 use crate::node::{
     default_end_of_epoch_broadcast_channel_capacity, default_epoch_duration_ms,
-    AuthorityKeyPairWithPath, KeyPairWithPath, StateSnapshotConfig,
+    default_shutdown_drain_timeout_secs, AuthorityKeyPairWithPath, KeyPairWithPath,
+    StateSnapshotConfig,
 };
 use crate::{
     genesis,
@@ -424,6 +425,8 @@ impl<R: rand::RngCore + rand::CryptoRng> ConfigBuilder<R> {
                     network_key_pair: KeyPairWithPath::new(SuiKeyPair::Ed25519(
                         validator.genesis_info.network_key_pair,
                     )),
+                    next_network_key_pair: None,
+                    next_protocol_key_pair: None,
                     account_key_pair: KeyPairWithPath::new(validator.genesis_info.account_key_pair),
                     worker_key_pair: KeyPairWithPath::new(SuiKeyPair::Ed25519(
                         validator.genesis_info.worker_key_pair,
@@ -445,13 +448,38 @@ impl<R: rand::RngCore + rand::CryptoRng> ConfigBuilder<R> {
                     grpc_load_shed: initial_accounts_config.grpc_load_shed,
                     grpc_concurrency_limit: initial_accounts_config.grpc_concurrency_limit,
                     p2p_config,
-                    authority_store_pruning_config: AuthorityStorePruningConfig::validator_config(),
+                    pruning: AuthorityStorePruningConfig::validator_config(),
                     end_of_epoch_broadcast_channel_capacity:
                         default_end_of_epoch_broadcast_channel_capacity(),
                     checkpoint_executor_config: Default::default(),
                     metrics: None,
                     supported_protocol_versions: Some(supported_protocol_versions),
                     state_snapshot_config: StateSnapshotConfig::validator_config(),
+                    transaction_deny_config: Default::default(),
+                    health_check_config: Default::default(),
+                    shutdown_drain_timeout_secs: default_shutdown_drain_timeout_secs(),
+                    read_only_config: None,
+                    otlp_trace_config: None,
+                    runtime_config: Default::default(),
+                    archive_reader_config: None,
+                    profiling_config: None,
+                    db_backup_config: None,
+                    watchdog_config: None,
+                    graphql_config: None,
+                    name_service_config: None,
+                    rpc_rate_limit_config: None,
+                    rpc_server_config: None,
+                    indexer_fallback_config: None,
+                    fullnode_grpc_address: None,
+                    admin_rpc_config: None,
+                    archive_writer_config: None,
+                    formal_snapshot_writer_config: None,
+                    formal_snapshot_restore_config: None,
+                    db_options_path: None,
+                    commit_log_config: None,
+                    compaction_window_config: None,
+                    indirect_objects_threshold: None,
+                    enable_db_statistics: false,
                 }
             })
             .collect();
@@ -23,8 +23,9 @@ use sui_types::base_types::{ObjectID, SequenceNumber, SuiAddress};
 use sui_types::clock::Clock;
 use sui_types::crypto::PublicKey as AccountsPublicKey;
 use sui_types::crypto::{
-    AuthorityKeyPair, AuthorityPublicKeyBytes, AuthoritySignInfo, AuthoritySignature,
-    AuthorityStrongQuorumSignInfo, SuiAuthoritySignature, ToFromBytes,
+    verify_proof_of_possession, AuthorityKeyPair, AuthorityPublicKey, AuthorityPublicKeyBytes,
+    AuthoritySignInfo, AuthoritySignature, AuthorityStrongQuorumSignInfo, SuiAuthoritySignature,
+    ToFromBytes,
 };
 use sui_types::epoch_data::EpochData;
 use sui_types::gas::SuiGasStatus;
@@ -308,6 +309,31 @@ pub struct GenesisValidatorInfo {
     pub proof_of_possession: AuthoritySignature,
 }
 
+impl GenesisValidatorInfo {
+    /// Checks that the information can be added to genesis, and that the proof of possession was
+    /// generated with the protocol key of the validator for its account address.
+    pub fn validate(&self) -> Result<(), anyhow::Error> {
+        let name = self.info.name();
+        if name.is_empty() {
+            bail!("validator name must not be empty");
+        }
+        if name.starts_with('.') || name.contains(['/', '\\']) {
+            bail!("validator name {name:?} cannot be used as a file name");
+        }
+
+        let protocol_key = AuthorityPublicKey::try_from(self.info.protocol_key())
+            .map_err(|e| anyhow::anyhow!("invalid protocol key of validator {name}: {e}"))?;
+        verify_proof_of_possession(
+            &self.proof_of_possession,
+            &protocol_key,
+            self.info.sui_address(),
+        )
+        .map_err(|e| anyhow::anyhow!("invalid proof of possession of validator {name}: {e}"))?;
+
+        Ok(())
+    }
+}
+
 /// Initial set of parameters for a chain.
 #[derive(Serialize, Deserialize)]
 pub struct GenesisChainParameters {
@@ -474,6 +500,66 @@ impl Builder {
         self.built_genesis.clone()
     }
 
+    pub fn validators(&self) -> &BTreeMap<AuthorityPublicKeyBytes, GenesisValidatorInfo> {
+        &self.validators
+    }
+
+    pub fn objects(&self) -> &BTreeMap<ObjectID, Object> {
+        &self.objects
+    }
+
+    pub fn signatures(&self) -> &BTreeMap<AuthorityPublicKeyBytes, AuthoritySignInfo> {
+        &self.signatures
+    }
+
+    /// Checks that every validator information is valid, and that no two validators share a
+    /// name, as it names their files in the builder directory.
+    pub fn validate_validators(&self) -> Result<(), anyhow::Error> {
+        let mut names = BTreeMap::new();
+        for validator in self.validators.values() {
+            validator.validate()?;
+            if let Some(other) = names.insert(validator.info.name(), validator.info.protocol_key())
+            {
+                bail!(
+                    "validators {} and {} share the name {}",
+                    other.concise(),
+                    validator.info.protocol_key().concise(),
+                    validator.info.name()
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks that every signature was made by a validator over the unsigned genesis checkpoint,
+    /// which must have been built.
+    pub fn validate_signatures(&self) -> Result<(), anyhow::Error> {
+        let Some(GenesisTuple(checkpoint, ..)) = &self.built_genesis else {
+            bail!("the unsigned genesis checkpoint has not been built yet");
+        };
+        let intent_msg = IntentMessage::new(
+            Intent::default().with_scope(IntentScope::CheckpointSummary),
+            checkpoint.clone(),
+        );
+        for (name, signature) in &self.signatures {
+            let validator = self
+                .validators
+                .get(name)
+                .with_context(|| format!("signature from unknown validator {}", name.concise()))?;
+            signature
+                .signature
+                .verify_secure(&intent_msg, checkpoint.epoch, *name)
+                .with_context(|| {
+                    format!(
+                        "invalid signature of validator {} over checkpoint {}",
+                        validator.info.name(),
+                        checkpoint.digest()
+                    )
+                })?;
+        }
+        Ok(())
+    }
+
     pub fn build_unsigned_genesis_checkpoint(&mut self) -> GenesisTuple {
         if let Some(built_genesis) = &self.built_genesis {
             return built_genesis.clone();
@@ -639,10 +725,9 @@ impl Builder {
             let validators = committee.clone().into_values().collect::<Vec<_>>();
 
             let built = build_unsigned_genesis_data(&parameters, &validators, &objects);
-            assert_eq!(
-                &built, loaded_genesis,
-                "loaded genesis does not match built genesis"
-            );
+            if &built != loaded_genesis {
+                bail!("loaded genesis does not match built genesis");
+            }
         }
 
         Ok(Self {
@@ -14,8 +14,10 @@ pub mod genesis;
 pub mod genesis_config;
 pub mod node;
 pub mod p2p;
+pub mod reload;
 mod swarm;
 pub mod utils;
+pub mod validate;
 
 pub use node::{ConsensusConfig, NodeConfig, ValidatorInfo};
 pub use swarm::FullnodeConfigBuilder;
@@ -29,6 +31,7 @@ pub const SUI_CLIENT_CONFIG: &str = "client.yaml";
 pub const SUI_KEYSTORE_FILENAME: &str = "sui.keystore";
 pub const SUI_GENESIS_FILENAME: &str = "genesis.blob";
 pub const SUI_DEV_NET_URL: &str = "https://fullnode.devnet.sui.io:443";
+pub const SUI_DEV_NET_FAUCET_URL: &str = "https://faucet.devnet.sui.io/gas";
 
 pub const AUTHORITIES_DB_NAME: &str = "authorities_db";
 pub const CONSENSUS_DB_NAME: &str = "consensus_db";
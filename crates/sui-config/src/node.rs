@@ -4,7 +4,7 @@
 use crate::genesis;
 use crate::p2p::P2pConfig;
 use crate::Config;
-use anyhow::Result;
+use anyhow::{Context, Result};
 use multiaddr::Multiaddr;
 use narwhal_config::Parameters as ConsensusParameters;
 use once_cell::sync::OnceCell;
@@ -18,7 +18,8 @@ use std::sync::Arc;
 use std::usize;
 use sui_keys::keypair_file::{read_authority_keypair_from_file, read_keypair_from_file};
 use sui_protocol_config::SupportedProtocolVersions;
-use sui_types::base_types::SuiAddress;
+use sui_types::base_types::{ObjectID, SuiAddress};
+use sui_types::committee::EpochId;
 use sui_types::crypto::AuthorityPublicKeyBytes;
 use sui_types::crypto::KeypairTraits;
 use sui_types::crypto::NetworkKeyPair;
@@ -42,6 +43,17 @@ pub struct NodeConfig {
     pub account_key_pair: KeyPairWithPath,
     #[serde(default = "default_key_pair")]
     pub network_key_pair: KeyPairWithPath,
+    /// The network key pair published on chain with `sui client rotate-network-key`. The node
+    /// switches to it at the start of the first epoch in which it is the on-chain network key of
+    /// the validator, after which it can be moved to `network-key-pair`. It is read at startup
+    /// only, and is not picked up by a config reload.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_network_key_pair: Option<KeyPairWithPath>,
+    /// The protocol key pair published on chain with `sui client rotate-protocol-key`. The node
+    /// restarts to sign with it at the start of the first epoch in which it is the on-chain
+    /// protocol key of the validator, after which it can be moved to `protocol-key-pair`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_protocol_key_pair: Option<AuthorityKeyPairWithPath>,
 
     pub db_path: PathBuf,
     #[serde(default = "default_grpc_address")]
@@ -78,8 +90,13 @@ pub struct NodeConfig {
 
     pub genesis: Genesis,
 
-    #[serde(default = "default_authority_store_pruning_config")]
-    pub authority_store_pruning_config: AuthorityStorePruningConfig,
+    /// What the node prunes from its databases, and how fast. `authority-store-pruning-config`
+    /// is accepted as well, the name this section had before.
+    #[serde(
+        default = "default_authority_store_pruning_config",
+        alias = "authority-store-pruning-config"
+    )]
+    pub pruning: AuthorityStorePruningConfig,
 
     /// Size of the broadcast channel used for notifying other systems of end of epoch.
     ///
@@ -101,6 +118,133 @@ pub struct NodeConfig {
 
     #[serde(default)]
     pub state_snapshot_config: StateSnapshotConfig,
+
+    #[serde(default)]
+    pub transaction_deny_config: TransactionDenyConfig,
+
+    #[serde(default)]
+    pub health_check_config: HealthCheckConfig,
+
+    /// How long a node that received SIGTERM waits for in-flight work to finish before it
+    /// exits anyway.
+    ///
+    /// If unspecified, this will default to `30`.
+    #[serde(default = "default_shutdown_drain_timeout_secs")]
+    pub shutdown_drain_timeout_secs: u64,
+
+    /// If set, the node opens the databases under `db-path` read-only, next to the node that
+    /// owns them, and serves JSON-RPC reads from them. It does not execute or sync anything, and
+    /// does not connect to any peer.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub read_only_config: Option<ReadOnlyConfig>,
+
+    /// Export tracing spans to an OpenTelemetry collector. Spans of a transaction's lifecycle
+    /// carry its digest in a `tx_digest` field.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub otlp_trace_config: Option<OtlpTraceConfig>,
+
+    #[serde(default)]
+    pub runtime_config: RuntimeConfig,
+
+    /// A remote archive that the JSON-RPC read APIs fall back to for checkpoints, transactions
+    /// and object versions that are no longer in the local databases.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub archive_reader_config: Option<ArchiveReaderConfig>,
+
+    /// Enables the CPU and heap profiling routes of the admin interface.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub profiling_config: Option<ProfilingConfig>,
+
+    /// Enables backups of the authority store, taken on request through the admin interface and
+    /// optionally on a schedule, while the node keeps running.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub db_backup_config: Option<DbBackupConfig>,
+
+    /// Enables the detection of async tasks and runtimes blocked by synchronous work.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub watchdog_config: Option<WatchdogConfig>,
+
+    /// Enables the GraphQL read API of fullnodes, next to JSON-RPC.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub graphql_config: Option<GraphQlConfig>,
+
+    /// The name registry the name service methods of JSON-RPC resolve names with. The methods
+    /// are only served when it is set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name_service_config: Option<NameServiceConfig>,
+
+    /// Limits on the requests each client can make to the JSON-RPC server. Requests are not
+    /// limited when it is unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rpc_rate_limit_config: Option<RpcRateLimitConfig>,
+
+    /// HTTP settings of the JSON-RPC server, for fullnodes serving the public without a proxy in
+    /// front of them.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rpc_server_config: Option<RpcServerConfig>,
+
+    /// An indexer that the JSON-RPC server hands the expensive queries over to, keeping their
+    /// scans off the local databases. Reads of objects and transaction execution stay local.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub indexer_fallback_config: Option<IndexerFallbackConfig>,
+
+    /// The address a fullnode serves its gRPC read and execution service on, with BCS payloads.
+    /// The service is not started if unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fullnode_grpc_address: Option<Multiaddr>,
+
+    /// The JSON-RPC server of the `admin_` namespace, for operational actions such as forcing a
+    /// pruning run. The server is not started if unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub admin_rpc_config: Option<AdminRpcConfig>,
+
+    /// Uploads the executed checkpoints to an archive, which keeps the history of the network
+    /// after nodes prune it. Nothing is uploaded if unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub archive_writer_config: Option<ArchiveWriterConfig>,
+
+    /// Uploads a formal snapshot of the live object set at the end of each epoch that commits to
+    /// it, which new nodes can be restored from. The snapshots are taken from the copies of the
+    /// database made at reconfiguration, which requires `state-snapshot-config.enabled`.
+    /// Nothing is uploaded if unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub formal_snapshot_writer_config: Option<FormalSnapshotWriterConfig>,
+
+    /// Restores the state of a node with an empty database from a formal snapshot, instead of
+    /// syncing it from genesis. It is ignored once the database has been initialized.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub formal_snapshot_restore_config: Option<FormalSnapshotRestoreConfig>,
+
+    /// A YAML file overriding the RocksDB options of tables, such as their block cache size,
+    /// compression or compaction style. See `typed_store::rocks::table_options` for its format.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub db_options_path: Option<PathBuf>,
+
+    /// Uploads a log of the transactions committed to the authority store, from which a database
+    /// restored from a backup can be brought up to date with `sui-tool replay-commit-log`.
+    /// Nothing is logged if unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit_log_config: Option<CommitLogConfig>,
+
+    /// Compacts tables in full during a daily window, so that the heavy compactions happen
+    /// outside peak traffic. Tables can also be compacted on demand with `POST /compact` on the
+    /// admin server.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compaction_window_config: Option<CompactionWindowConfig>,
+
+    /// Move objects with contents larger than this many bytes are stored in a table of their
+    /// own, keeping only their metadata in the `objects` table, so that large payloads are not
+    /// rewritten by the compactions of the tables read when executing transactions. Unset,
+    /// objects are stored with their contents. Objects written before it was set are moved with
+    /// `sui-tool migrate-large-objects`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub indirect_objects_threshold: Option<usize>,
+
+    /// Collects the statistics of RocksDB, reported as the block cache, write stall and
+    /// compaction metrics of each database. Collecting them slows every database operation down
+    /// a little, so they are off by default.
+    #[serde(default)]
+    pub enable_db_statistics: bool,
 }
 
 fn default_authority_store_pruning_config() -> AuthorityStorePruningConfig {
@@ -155,6 +299,10 @@ pub fn default_end_of_epoch_broadcast_channel_capacity() -> usize {
     128
 }
 
+pub fn default_shutdown_drain_timeout_secs() -> u64 {
+    30
+}
+
 pub fn bool_true() -> bool {
     true
 }
@@ -166,6 +314,12 @@ impl NodeConfig {
         self.protocol_key_pair.authority_keypair()
     }
 
+    pub fn next_protocol_key_pair(&self) -> Option<&AuthorityKeyPair> {
+        self.next_protocol_key_pair
+            .as_ref()
+            .map(AuthorityKeyPairWithPath::authority_keypair)
+    }
+
     pub fn worker_key_pair(&self) -> &NetworkKeyPair {
         match self.worker_key_pair.keypair() {
             SuiKeyPair::Ed25519(kp) => kp,
@@ -186,6 +340,27 @@ impl NodeConfig {
         }
     }
 
+    pub fn next_network_key_pair(&self) -> Option<&NetworkKeyPair> {
+        self.next_network_key_pair
+            .as_ref()
+            .map(|key_pair| match key_pair.keypair() {
+                SuiKeyPair::Ed25519(kp) => kp,
+                other => panic!(
+                    "Invalid keypair type: {:?}, only Ed25519 is allowed for network key",
+                    other
+                ),
+            })
+    }
+
+    /// The network key pair to use in an epoch where `on_chain_key` is the network key of this
+    /// validator, preferring the next key pair once it has been published.
+    pub fn network_key_pair_for(&self, on_chain_key: Option<&NetworkPublicKey>) -> &NetworkKeyPair {
+        match self.next_network_key_pair() {
+            Some(next) if Some(next.public()) == on_chain_key => next,
+            _ => self.network_key_pair(),
+        }
+    }
+
     pub fn account_key_pair(&self) -> &SuiKeyPair {
         self.account_key_pair.keypair()
     }
@@ -281,15 +456,51 @@ impl Default for CheckpointExecutorConfig {
     }
 }
 
+/// The pruning policy of a node. Data is pruned by whole checkpoints, once every checkpoint of the
+/// epoch it was created in is old enough. Object versions are pruned first, as they are found
+/// through the effects of the transactions that replaced them, then transactions, and
+/// checkpoints last, so a retention shorter than the one of the data pruned before it has no
+/// effect.
 #[derive(Debug, Clone, Copy, Deserialize, Serialize)]
 #[serde(rename_all = "kebab-case")]
 pub struct AuthorityStorePruningConfig {
+    /// Number of epoch databases (holding the consensus and signing state of an epoch) kept on
+    /// disk, including the current one.
     pub num_latest_epoch_dbs_to_retain: usize,
+    /// How often old epoch databases are deleted.
     pub epoch_db_pruning_period_secs: u64,
+    /// Number of epochs of object versions to retain, besides the latest version of each object,
+    /// which is never pruned. `0` prunes as soon as a checkpoint is executed, `u64::MAX` disables
+    /// object pruning.
     pub num_epochs_to_retain: u64,
+    /// Largest number of checkpoints pruned in one database write.
     pub max_checkpoints_in_batch: usize,
+    /// Largest number of transactions pruned in one database write, checked after each
+    /// checkpoint.
     pub max_transactions_in_batch: usize,
+    /// Deletes the old versions of an object with one range deletion rather than one deletion
+    /// per version.
     pub use_range_deletion: bool,
+    /// Number of epochs of transactions, effects and events to retain. The node still knows
+    /// which transactions it executed once they are pruned. If unset, they are never pruned.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub num_epochs_to_retain_for_transactions: Option<u64>,
+    /// Number of epochs of checkpoint summaries and contents to retain. Peers cannot sync pruned
+    /// checkpoints from the node. If unset, they are never pruned.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub num_epochs_to_retain_for_checkpoints: Option<u64>,
+    /// How often the pruner runs. If unset, it runs twice per epoch, or every second when
+    /// `num_epochs_to_retain` is `0`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pruning_period_secs: Option<u64>,
+    /// How often transactions, effects, events and their indexes are pruned, which runs
+    /// separately from the rest of the pruning. If unset, they are pruned as often as the rest.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transaction_pruning_period_secs: Option<u64>,
+    /// Largest number of transactions pruned per second, to keep the I/O of pruning from slowing
+    /// down execution. If unset, transactions are pruned as fast as possible.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_transactions_pruned_per_sec: Option<u64>,
 }
 
 impl Default for AuthorityStorePruningConfig {
@@ -301,6 +512,11 @@ impl Default for AuthorityStorePruningConfig {
             max_checkpoints_in_batch: 200,
             max_transactions_in_batch: 1000,
             use_range_deletion: true,
+            num_epochs_to_retain_for_transactions: None,
+            num_epochs_to_retain_for_checkpoints: None,
+            pruning_period_secs: None,
+            transaction_pruning_period_secs: None,
+            max_transactions_pruned_per_sec: None,
         }
     }
 }
@@ -314,6 +530,11 @@ impl AuthorityStorePruningConfig {
             max_checkpoints_in_batch: 200,
             max_transactions_in_batch: 1000,
             use_range_deletion: true,
+            num_epochs_to_retain_for_transactions: None,
+            num_epochs_to_retain_for_checkpoints: None,
+            pruning_period_secs: None,
+            transaction_pruning_period_secs: None,
+            max_transactions_pruned_per_sec: None,
         }
     }
     pub fn fullnode_config() -> Self {
@@ -324,6 +545,11 @@ impl AuthorityStorePruningConfig {
             max_checkpoints_in_batch: 200,
             max_transactions_in_batch: 1000,
             use_range_deletion: true,
+            num_epochs_to_retain_for_transactions: None,
+            num_epochs_to_retain_for_checkpoints: None,
+            pruning_period_secs: None,
+            transaction_pruning_period_secs: None,
+            max_transactions_pruned_per_sec: None,
         }
     }
 }
@@ -352,6 +578,513 @@ impl StateSnapshotConfig {
     }
 }
 
+/// Operator controlled lists used by validators to reject transactions before
+/// doing any expensive work (signature verification, object loading) on them.
+#[derive(Default, Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct TransactionDenyConfig {
+    /// Transactions sent or sponsored by any of these addresses are rejected.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub address_deny_list: Vec<SuiAddress>,
+
+    /// Transactions that call into any of these packages are rejected.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub package_deny_list: Vec<ObjectID>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct HealthCheckConfig {
+    /// Address to serve the `/health` and `/ready` endpoints on.
+    ///
+    /// If unspecified, the endpoints are not served.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub listen_address: Option<SocketAddr>,
+
+    /// The node only reports itself as ready once its highest executed checkpoint is at most
+    /// this many checkpoints behind the highest checkpoint known to its peers.
+    ///
+    /// If unspecified, this will default to `20`.
+    #[serde(default = "default_max_checkpoint_lag")]
+    pub max_checkpoint_lag: u64,
+}
+
+fn default_max_checkpoint_lag() -> u64 {
+    20
+}
+
+impl Default for HealthCheckConfig {
+    fn default() -> Self {
+        Self {
+            listen_address: None,
+            max_checkpoint_lag: default_max_checkpoint_lag(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct ReadOnlyConfig {
+    /// Directory for the files of the node's own secondary instances of the databases. These
+    /// are small, but must not be shared with another node.
+    ///
+    /// If unspecified, a temporary directory is used, which is removed when the node stops.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub secondary_db_path: Option<PathBuf>,
+
+    /// How often to pick up the writes made by the node that owns the databases. Set this to `0`
+    /// to serve the databases as they were at startup, e.g. when inspecting a copy.
+    ///
+    /// If unspecified, this will default to `1000`.
+    #[serde(default = "default_catch_up_interval_ms")]
+    pub catch_up_interval_ms: u64,
+}
+
+fn default_catch_up_interval_ms() -> u64 {
+    1000
+}
+
+impl Default for ReadOnlyConfig {
+    fn default() -> Self {
+        Self {
+            secondary_db_path: None,
+            catch_up_interval_ms: default_catch_up_interval_ms(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct ArchiveReaderConfig {
+    /// Base URL of the archive bucket, e.g. `https://storage.googleapis.com/<bucket>` or
+    /// `https://<bucket>.s3.amazonaws.com`.
+    pub url: String,
+
+    /// If unspecified, this will default to `10000`.
+    #[serde(default = "default_archive_request_timeout_ms")]
+    pub request_timeout_ms: u64,
+
+    /// A local directory keeping the entries read from the archive, so that they are only
+    /// downloaded once. Nothing is cached if unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_dir: Option<PathBuf>,
+
+    /// The oldest cached entries are evicted beyond this size.
+    ///
+    /// If unspecified, this will default to `10737418240` (10 GiB).
+    #[serde(default = "default_archive_cache_size_bytes")]
+    pub cache_size_bytes: u64,
+}
+
+fn default_archive_request_timeout_ms() -> u64 {
+    10_000
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct ArchiveWriterConfig {
+    pub store: ArchiveStoreConfig,
+
+    /// Also uploads the transactions of the checkpoints, with their effects and events, and the
+    /// object versions they write. Object versions must be retained locally until they are
+    /// uploaded: archiving stops at the first written version missing from the local store.
+    #[serde(default)]
+    pub include_transactions: bool,
+
+    /// Compresses the uploaded entries with gzip. It cannot be changed once an archive is
+    /// written.
+    ///
+    /// If unspecified, this will default to `true`.
+    #[serde(default = "default_archive_compress")]
+    pub compress: bool,
+
+    /// If unspecified, this will default to `10000`.
+    #[serde(default = "default_archive_upload_interval_ms")]
+    pub upload_interval_ms: u64,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ArchiveStoreConfig {
+    /// A local directory, or a bucket mounted on the local filesystem.
+    Directory(PathBuf),
+    /// A bucket written over HTTP(S) with `PUT` requests, e.g. with the XML API of GCS.
+    Bucket {
+        /// Base URL of the archive bucket, e.g. `https://storage.googleapis.com/<bucket>`.
+        url: String,
+
+        /// The environment variable holding the bearer token the requests are authorized with.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        auth_token_env_var: Option<String>,
+
+        /// If unspecified, this will default to `10000`.
+        #[serde(default = "default_archive_request_timeout_ms")]
+        request_timeout_ms: u64,
+    },
+}
+
+fn default_archive_cache_size_bytes() -> u64 {
+    10 << 30
+}
+
+fn default_archive_compress() -> bool {
+    true
+}
+
+fn default_archive_upload_interval_ms() -> u64 {
+    10_000
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct FormalSnapshotWriterConfig {
+    /// Where the snapshots are uploaded, which can be the store of an archive.
+    pub store: ArchiveStoreConfig,
+
+    /// Compresses the uploaded entries with gzip.
+    ///
+    /// If unspecified, this will default to `true`.
+    #[serde(default = "default_archive_compress")]
+    pub compress: bool,
+
+    /// How often the node looks for the end of an epoch to upload a snapshot of.
+    ///
+    /// If unspecified, this will default to `10000`.
+    #[serde(default = "default_archive_upload_interval_ms")]
+    pub upload_interval_ms: u64,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct FormalSnapshotRestoreConfig {
+    /// Where the snapshot is downloaded from.
+    pub store: ArchiveStoreConfig,
+
+    /// The epoch whose end the snapshot was taken at. The node syncs the checkpoints of the
+    /// following epochs from its peers.
+    pub epoch: EpochId,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct CommitLogConfig {
+    /// Where the log is uploaded, which should not be the store of an archive.
+    pub store: ArchiveStoreConfig,
+
+    /// Compresses the uploaded segments with gzip. It cannot be changed once a log is written.
+    ///
+    /// If unspecified, this will default to `true`.
+    #[serde(default = "default_archive_compress")]
+    pub compress: bool,
+
+    /// How often the commits are uploaded, as one segment of the log. The commits made since
+    /// the last upload are lost if the node crashes.
+    ///
+    /// If unspecified, this will default to `1000`.
+    #[serde(default = "default_commit_log_segment_interval_ms")]
+    pub segment_interval_ms: u64,
+}
+
+fn default_commit_log_segment_interval_ms() -> u64 {
+    1_000
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct CompactionWindowConfig {
+    /// The hour of the day, in UTC, the window starts at.
+    pub start_hour_utc: u8,
+
+    /// The hour of the day, in UTC, the window ends at. A window ending at an earlier hour than
+    /// it starts spans midnight.
+    pub end_hour_utc: u8,
+
+    /// The tables compacted once during each window, by the struct holding them as named in
+    /// the DB options file, e.g. `AuthorityPerpetualTables: [objects]`. They are compacted one
+    /// after the other, so the last ones may be compacted after the window ends.
+    pub tables: BTreeMap<String, Vec<String>>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct ProfilingConfig {
+    /// Token that requests to the profiling routes must present as `Authorization: Bearer <token>`.
+    pub auth_token: String,
+
+    /// Longest profile that can be requested, in seconds.
+    ///
+    /// If unspecified, this will default to `60`.
+    #[serde(default = "default_max_profile_duration_secs")]
+    pub max_duration_secs: u64,
+}
+
+fn default_max_profile_duration_secs() -> u64 {
+    60
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct DbBackupConfig {
+    /// Directory the backups are created in. Backups are made of hard links to the files of the
+    /// database, so it must be on the same filesystem as `db-path`.
+    pub backup_dir: PathBuf,
+
+    /// If set, a backup is taken at this interval. Otherwise backups are only taken on request.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub backup_interval_secs: Option<u64>,
+
+    /// Number of most recent backups to keep, older ones are deleted.
+    ///
+    /// If unspecified, this will default to `2`.
+    #[serde(default = "default_num_backups_to_retain")]
+    pub num_backups_to_retain: usize,
+}
+
+fn default_num_backups_to_retain() -> usize {
+    2
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct GraphQlConfig {
+    /// Address the GraphQL server listens on, e.g. `0.0.0.0:9001`.
+    pub listen_address: SocketAddr,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct NameServiceConfig {
+    /// The table holding the record of each registered name, keyed by name. The record of a
+    /// name gives the address it points to in its `target_address` field.
+    pub registry_id: ObjectID,
+    /// The table holding the name each address has chosen to be known by, keyed by address.
+    pub reverse_registry_id: ObjectID,
+}
+
+/// Clients are told apart by API key if they send a known one, and otherwise by their address:
+/// the address requests come from, or the address forwarded by the proxies in front of the node
+/// when `num-trusted-proxies` is set.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct RpcRateLimitConfig {
+    /// Requests per second a client can make to each method not in
+    /// `method-requests-per-second`.
+    pub requests_per_second: u32,
+
+    /// Requests per second a client can make to particular methods, e.g. to limit expensive
+    /// queries further.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub method_requests_per_second: BTreeMap<String, u32>,
+
+    /// If set, the number of requests a client can have in flight at once.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_concurrent_requests: Option<u32>,
+
+    /// The known API keys, each with the multiple of the limits above that its client gets.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub api_keys: BTreeMap<String, u32>,
+
+    /// The header clients send their API key in.
+    ///
+    /// If unspecified, this will default to `x-api-key`.
+    #[serde(default = "default_api_key_header")]
+    pub api_key_header: String,
+
+    /// The header the proxies in front of the node forward the address of clients in, each
+    /// appending the address it received the request from. Only read when
+    /// `num-trusted-proxies` is set.
+    ///
+    /// If unspecified, this will default to `x-forwarded-for`.
+    #[serde(default = "default_client_address_header")]
+    pub client_address_header: String,
+
+    /// The number of proxies in front of the node that append to `client-address-header`. The
+    /// address of a client is the one that many addresses from the end of the header, since
+    /// any before it may be made up by the client. Requests with fewer addresses in the header
+    /// are told apart by the address they come from.
+    ///
+    /// If unspecified, this will default to 0: the header is ignored, and clients are told apart
+    /// by the address requests come from.
+    #[serde(default)]
+    pub num_trusted_proxies: usize,
+}
+
+pub fn default_api_key_header() -> String {
+    "x-api-key".to_string()
+}
+
+pub fn default_client_address_header() -> String {
+    "x-forwarded-for".to_string()
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct RpcServerConfig {
+    /// The origins browsers may call the server from, e.g. `https://example.com`.
+    ///
+    /// If empty, the origins in the `ACCESS_CONTROL_ALLOW_ORIGIN` environment variable are
+    /// allowed, or any origin if it is unset too.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allowed_origins: Vec<String>,
+
+    /// Compresses responses with gzip or brotli for clients that accept either.
+    #[serde(default)]
+    pub enable_compression: bool,
+
+    /// The largest request body the server accepts, in bytes.
+    ///
+    /// If unspecified, this will default to `2147483648`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_request_body_size: Option<u32>,
+
+    /// How long a connection is idle before TCP keepalive probes are sent on it, to drop the
+    /// connections of clients that went away without closing them. Connections are not probed
+    /// when it is unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tcp_keepalive_secs: Option<u64>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct IndexerFallbackConfig {
+    /// URL of the JSON-RPC server of the indexer, e.g. `http://127.0.0.1:3030`.
+    pub url: String,
+
+    /// The methods whose calls are handed over to the indexer. Requests are only handed over
+    /// when all of their calls are to these methods and they carry no consistency token, and
+    /// are served locally if the indexer fails to answer them, unless they ask for a page by a
+    /// cursor the indexer issued.
+    ///
+    /// If unspecified, this will default to the transaction, event and owned object queries.
+    #[serde(default = "default_indexer_fallback_methods")]
+    pub methods: Vec<String>,
+
+    /// If unspecified, this will default to `30000`.
+    #[serde(default = "default_indexer_fallback_request_timeout_ms")]
+    pub request_timeout_ms: u64,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct AdminRpcConfig {
+    /// If unspecified, this will default to `127.0.0.1:9185`.
+    #[serde(default = "default_admin_rpc_address")]
+    pub listen_address: SocketAddr,
+
+    /// The token requests must carry as `Authorization: Bearer <token>`. Required when the
+    /// server listens on an address other than loopback, and requests are not checked without
+    /// it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auth_token: Option<String>,
+}
+
+fn default_admin_rpc_address() -> SocketAddr {
+    use std::net::{IpAddr, Ipv4Addr};
+    SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 9185)
+}
+
+fn default_indexer_fallback_methods() -> Vec<String> {
+    [
+        "sui_getTransactions",
+        "sui_getEvents",
+        "sui_getOwnedObjects",
+        "sui_getObjectsOwnedByAddress",
+        "sui_getDynamicFields",
+    ]
+    .into_iter()
+    .map(String::from)
+    .collect()
+}
+
+fn default_indexer_fallback_request_timeout_ms() -> u64 {
+    30_000
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct WatchdogConfig {
+    /// How often each runtime of the node is checked for being blocked.
+    ///
+    /// If unspecified, this will default to `1000`.
+    #[serde(default = "default_watchdog_probe_interval_ms")]
+    pub probe_interval_ms: u64,
+
+    /// A runtime that has not started running a new task after this long is reported as blocked,
+    /// meaning all of its worker threads are stuck.
+    ///
+    /// If unspecified, this will default to `500`.
+    #[serde(default = "default_blocked_runtime_threshold_ms")]
+    pub blocked_runtime_threshold_ms: u64,
+
+    /// A poll of a task spawned with `spawn_slow_poll_monitored_task!` that takes longer than this
+    /// is reported, along with the place the task was spawned at.
+    ///
+    /// If unspecified, this will default to `50`.
+    #[serde(default = "default_slow_poll_threshold_ms")]
+    pub slow_poll_threshold_ms: u64,
+}
+
+fn default_watchdog_probe_interval_ms() -> u64 {
+    1000
+}
+
+fn default_blocked_runtime_threshold_ms() -> u64 {
+    500
+}
+
+fn default_slow_poll_threshold_ms() -> u64 {
+    50
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct OtlpTraceConfig {
+    /// gRPC endpoint of the collector, e.g. `http://localhost:4317`.
+    pub endpoint: String,
+
+    /// Fraction of transactions to trace, between `0.0` and `1.0`.
+    ///
+    /// If unspecified, this will default to `1.0`.
+    #[serde(default = "default_otlp_sample_ratio")]
+    pub sample_ratio: f64,
+
+    /// Name the node's spans are reported under.
+    ///
+    /// If unspecified, this will default to `sui-node`.
+    #[serde(default = "default_otlp_service_name")]
+    pub service_name: String,
+}
+
+fn default_otlp_sample_ratio() -> f64 {
+    1.0
+}
+
+fn default_otlp_service_name() -> String {
+    "sui-node".to_string()
+}
+
+/// Dedicated tokio runtimes for the subsystems of the node. A subsystem without a worker thread
+/// count shares the runtime of the rest of the node.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct RuntimeConfig {
+    /// Worker threads for Narwhal, on validators.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub consensus_worker_threads: Option<usize>,
+
+    /// Worker threads for executing certificates and checkpoints.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub execution_worker_threads: Option<usize>,
+
+    /// Worker threads for the JSON-RPC server.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub json_rpc_worker_threads: Option<usize>,
+
+    /// Worker threads for the p2p network, discovery and state sync.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub p2p_worker_threads: Option<usize>,
+}
+
 /// Publicly known information about a validator
 /// TODO read most of this from on-chain
 #[serde_as]
@@ -546,6 +1279,19 @@ impl KeyPairWithPath {
             })
             .as_ref()
     }
+
+    /// Like [`Self::keypair`], but returns an error instead of panicking if the keypair file
+    /// cannot be read.
+    pub fn try_keypair(&self) -> Result<&SuiKeyPair> {
+        self.keypair
+            .get_or_try_init(|| match &self.location {
+                KeyPairLocation::InPlace { value } => Ok(value.clone()),
+                KeyPairLocation::File { path } => read_keypair_from_file(path)
+                    .map(Arc::new)
+                    .with_context(|| format!("Invalid keypair file at path {:?}", path)),
+            })
+            .map(Arc::as_ref)
+    }
 }
 
 /// Wrapper struct for AuthorityKeyPair that can be deserialized from a file path.
@@ -608,6 +1354,19 @@ impl AuthorityKeyPairWithPath {
             })
             .as_ref()
     }
+
+    /// Like [`Self::authority_keypair`], but returns an error instead of panicking if the
+    /// keypair file cannot be read.
+    pub fn try_authority_keypair(&self) -> Result<&AuthorityKeyPair> {
+        self.keypair
+            .get_or_try_init(|| match &self.location {
+                AuthorityKeyPairLocation::InPlace { value } => Ok(value.clone()),
+                AuthorityKeyPairLocation::File { path } => read_authority_keypair_from_file(path)
+                    .map(Arc::new)
+                    .with_context(|| format!("Invalid authority keypair file {:?}", path)),
+            })
+            .map(Arc::as_ref)
+    }
 }
 
 #[cfg(test)]
@@ -621,7 +1380,7 @@ mod tests {
         get_key_pair_from_rng, AccountKeyPair, AuthorityKeyPair, NetworkKeyPair, SuiKeyPair,
     };
 
-    use super::Genesis;
+    use super::{Genesis, KeyPairWithPath};
     use crate::NodeConfig;
 
     #[test]
@@ -673,6 +1432,18 @@ mod tests {
         let _template: NodeConfig = serde_yaml::from_str(TEMPLATE).unwrap();
     }
 
+    #[test]
+    fn pruning_config_old_name() {
+        const TEMPLATE: &str = include_str!("../data/fullnode-template.yaml");
+
+        let template = TEMPLATE.replace(
+            "pruning:\n  num-latest-epoch-dbs-to-retain: 3",
+            "authority-store-pruning-config:\n  num-latest-epoch-dbs-to-retain: 5",
+        );
+        let config: NodeConfig = serde_yaml::from_str(&template).unwrap();
+        assert_eq!(config.pruning.num_latest_epoch_dbs_to_retain, 5);
+    }
+
     #[test]
     fn load_key_pairs_to_node_config() {
         let protocol_key_pair: AuthorityKeyPair =
@@ -718,4 +1489,33 @@ mod tests {
             worker_key_pair.public()
         );
     }
+
+    #[test]
+    fn network_key_pair_for_on_chain_key() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let network_config = crate::builder::ConfigBuilder::new(&dir).build();
+        let mut config = network_config.validator_configs[0].clone();
+        let current = config.network_key_pair().public().clone();
+
+        // Without a next key pair, the current one is used whatever the on-chain key is.
+        assert_eq!(config.network_key_pair_for(None).public(), &current);
+        assert_eq!(
+            config.network_key_pair_for(Some(&current)).public(),
+            &current
+        );
+
+        let next_key_pair: NetworkKeyPair =
+            get_key_pair_from_rng(&mut StdRng::from_seed([1; 32])).1;
+        let next = next_key_pair.public().clone();
+        config.next_network_key_pair =
+            Some(KeyPairWithPath::new(SuiKeyPair::Ed25519(next_key_pair)));
+
+        // The next key pair is only used once it is the on-chain key.
+        assert_eq!(config.network_key_pair_for(None).public(), &current);
+        assert_eq!(
+            config.network_key_pair_for(Some(&current)).public(),
+            &current
+        );
+        assert_eq!(config.network_key_pair_for(Some(&next)).public(), &next);
+    }
 }
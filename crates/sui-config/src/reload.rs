@@ -0,0 +1,218 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::NodeConfig;
+use anyhow::{Context, Result};
+use serde_yaml::{Mapping, Value};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Fields of a node config that can be changed while the node is running, as paths of keys in
+/// the config file. Changes to any other field only take effect after a restart.
+pub const RELOADABLE_FIELDS: &[&[&str]] = &[
+    &["p2p-config", "seed-peers"],
+    &["pruning", "num-epochs-to-retain"],
+    // The name of the `pruning` section before it was renamed.
+    &["authority-store-pruning-config", "num-epochs-to-retain"],
+    &["transaction-deny-config"],
+    // The RPC servers read the rate limits through a shared handle, so new limits take effect
+    // on the next request without restarting them.
+    &["rpc-rate-limit-config"],
+];
+
+/// The result of reading a node config file again.
+pub struct ReloadedNodeConfig {
+    /// The config as it is now on disk, including any changes that cannot be applied.
+    pub config: NodeConfig,
+    /// Top level fields that were changed but cannot be applied to a running node.
+    pub rejected_fields: Vec<String>,
+}
+
+/// Keeps track of the contents of a node config file, so that each reload can be compared with
+/// what the node is running with. The comparison is done on the file contents rather than on the
+/// parsed config, so that fields left out of the file (e.g. randomly generated key pairs) are
+/// not reported as changed.
+pub struct NodeConfigReloader {
+    path: PathBuf,
+    // The file contents the node is running with, as far as they can be applied at runtime.
+    applied: Value,
+}
+
+impl NodeConfigReloader {
+    pub fn new<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let applied = read_config_file(&path)?;
+        Ok(Self { path, applied })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Reads the config file again. Changes to fields outside of [`RELOADABLE_FIELDS`] are
+    /// reported in every reload until the node is restarted.
+    pub fn reload(&mut self) -> Result<ReloadedNodeConfig> {
+        let mut contents = read_config_file(&self.path)?;
+        let config: NodeConfig = serde_yaml::from_value(contents.clone())
+            .with_context(|| format!("Unable to parse config from {}", self.path.display()))?;
+
+        let rejected_fields = rejected_fields(&self.applied, &contents);
+        // Keep the values the node is running with for the fields that were not applied.
+        if let (Value::Mapping(applied), Value::Mapping(new)) = (&self.applied, &mut contents) {
+            for field in &rejected_fields {
+                let key = Value::String(field.clone());
+                match applied.get(&key) {
+                    Some(value) => new.insert(key, value.clone()),
+                    None => new.remove(&key),
+                };
+            }
+        }
+        self.applied = contents;
+
+        Ok(ReloadedNodeConfig {
+            config,
+            rejected_fields,
+        })
+    }
+}
+
+fn read_config_file(path: &Path) -> Result<Value> {
+    let contents = fs::read_to_string(path)
+        .with_context(|| format!("Unable to load config from {}", path.display()))?;
+    Ok(serde_yaml::from_str(&contents)?)
+}
+
+/// Returns the top level fields that differ between two config files, ignoring the
+/// reloadable fields.
+fn rejected_fields(old: &Value, new: &Value) -> Vec<String> {
+    let (old, new) = (
+        without_reloadable_fields(old),
+        without_reloadable_fields(new),
+    );
+    let (Value::Mapping(old), Value::Mapping(new)) = (&old, &new) else {
+        return if old == new { vec![] } else { vec!["<root>".to_string()] };
+    };
+    let mut fields: Vec<String> = old
+        .iter()
+        .chain(new.iter())
+        .filter(|(key, _)| old.get(key) != new.get(key))
+        .filter_map(|(key, _)| key.as_str().map(str::to_string))
+        .collect();
+    fields.sort();
+    fields.dedup();
+    fields
+}
+
+fn without_reloadable_fields(config: &Value) -> Value {
+    let mut config = config.clone();
+    for path in RELOADABLE_FIELDS {
+        remove_path(&mut config, path);
+    }
+    config
+}
+
+fn remove_path(value: &mut Value, path: &[&str]) {
+    let Value::Mapping(mapping) = value else {
+        return;
+    };
+    let key = Value::String(path[0].to_string());
+    if path.len() == 1 {
+        mapping.remove(&key);
+        return;
+    }
+    if let Some(child) = mapping.get_mut(&key) {
+        remove_path(child, &path[1..]);
+        // Treat a section left empty the same as a missing one.
+        if child.as_mapping().map_or(false, Mapping::is_empty) {
+            mapping.remove(&key);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::rejected_fields;
+    use serde_yaml::Value;
+
+    fn yaml(s: &str) -> Value {
+        serde_yaml::from_str(s).unwrap()
+    }
+
+    #[test]
+    fn test_rejected_fields() {
+        let old = yaml(
+            r#"
+db-path: /opt/sui/db
+p2p-config:
+  listen-address: 0.0.0.0:8084
+  seed-peers:
+    - address: /dns/a/udp/8084
+authority-store-pruning-config:
+  num-epochs-to-retain: 1
+  use-range-deletion: true
+"#,
+        );
+
+        let new = yaml(
+            r#"
+db-path: /opt/sui/db
+p2p-config:
+  listen-address: 0.0.0.0:8084
+authority-store-pruning-config:
+  num-epochs-to-retain: 5
+  use-range-deletion: true
+transaction-deny-config:
+  address-deny-list:
+    - "0x0000000000000000000000000000000000000001"
+rpc-rate-limit-config:
+  requests-per-second: 10
+"#,
+        );
+        assert!(rejected_fields(&old, &new).is_empty());
+
+        let new = yaml(
+            r#"
+db-path: /opt/sui/other-db
+p2p-config:
+  listen-address: 0.0.0.0:8085
+  seed-peers:
+    - address: /dns/a/udp/8084
+authority-store-pruning-config:
+  num-epochs-to-retain: 1
+  use-range-deletion: false
+enable-event-processing: true
+"#,
+        );
+        assert_eq!(
+            rejected_fields(&old, &new),
+            vec![
+                "authority-store-pruning-config",
+                "db-path",
+                "enable-event-processing",
+                "p2p-config",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_next_network_key_pair_is_not_reloaded() {
+        let old = yaml(
+            r#"
+db-path: /opt/sui/db
+network-key-pair:
+  path: /opt/sui/key-pairs/network.key
+"#,
+        );
+
+        let new = yaml(
+            r#"
+db-path: /opt/sui/db
+network-key-pair:
+  path: /opt/sui/key-pairs/network.key
+next-network-key-pair:
+  path: /opt/sui/key-pairs/next-network.key
+"#,
+        );
+        assert_eq!(rejected_fields(&old, &new), vec!["next-network-key-pair"]);
+    }
+}
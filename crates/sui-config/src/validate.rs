@@ -0,0 +1,490 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Checks of a node config that go beyond parsing it, to catch mistakes that would otherwise only
+//! show up once the node is (re)started.
+
+use crate::NodeConfig;
+use multiaddr::{Multiaddr, Protocol};
+use serde::Serialize;
+use std::fs;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::path::Path;
+use sui_types::crypto::SuiKeyPair;
+
+/// A problem found in a node config.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct ConfigIssue {
+    /// Path of the offending field in the config file, e.g. `p2p-config.external-address`.
+    pub field: String,
+    pub message: String,
+}
+
+impl ConfigIssue {
+    pub fn new(field: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            field: field.into(),
+            message: message.into(),
+        }
+    }
+}
+
+/// Runs every check that only needs the config itself, returning all the problems found.
+pub fn validate_node_config(config: &NodeConfig) -> Vec<ConfigIssue> {
+    let mut issues = vec![];
+    check_key_pairs(config, &mut issues);
+    check_addresses(config, &mut issues);
+    check_writable("db-path", &config.db_path, &mut issues);
+    if let Some(consensus_config) = &config.consensus_config {
+        check_writable(
+            "consensus-config.db-path",
+            &consensus_config.db_path,
+            &mut issues,
+        );
+    }
+    if let Err(e) = config.genesis() {
+        issues.push(ConfigIssue::new("genesis", format!("{e:#}")));
+    }
+    if let Some(profiling_config) = &config.profiling_config {
+        if profiling_config.auth_token.is_empty() {
+            issues.push(ConfigIssue::new(
+                "profiling-config.auth-token",
+                "must not be empty",
+            ));
+        }
+    }
+    if config.pruning.pruning_period_secs == Some(0) {
+        issues.push(ConfigIssue::new(
+            "pruning.pruning-period-secs",
+            "must be at least 1",
+        ));
+    }
+    if config.pruning.transaction_pruning_period_secs == Some(0) {
+        issues.push(ConfigIssue::new(
+            "pruning.transaction-pruning-period-secs",
+            "must be at least 1",
+        ));
+    }
+    if config.pruning.max_transactions_pruned_per_sec == Some(0) {
+        issues.push(ConfigIssue::new(
+            "pruning.max-transactions-pruned-per-sec",
+            "must be at least 1",
+        ));
+    }
+    if let Some(commit_log_config) = &config.commit_log_config {
+        if commit_log_config.segment_interval_ms == 0 {
+            issues.push(ConfigIssue::new(
+                "commit-log-config.segment-interval-ms",
+                "must be at least 1",
+            ));
+        }
+    }
+    if let Some(formal_snapshot_writer_config) = &config.formal_snapshot_writer_config {
+        if !config.state_snapshot_config.enabled {
+            issues.push(ConfigIssue::new(
+                "formal-snapshot-writer-config",
+                "requires state-snapshot-config.enabled",
+            ));
+        }
+        if formal_snapshot_writer_config.upload_interval_ms == 0 {
+            issues.push(ConfigIssue::new(
+                "formal-snapshot-writer-config.upload-interval-ms",
+                "must be at least 1",
+            ));
+        }
+    }
+    if let Some(compaction_window_config) = &config.compaction_window_config {
+        for (field, hour) in [
+            ("start-hour-utc", compaction_window_config.start_hour_utc),
+            ("end-hour-utc", compaction_window_config.end_hour_utc),
+        ] {
+            if hour >= 24 {
+                issues.push(ConfigIssue::new(
+                    format!("compaction-window-config.{field}"),
+                    "must be less than 24",
+                ));
+            }
+        }
+        if compaction_window_config.start_hour_utc == compaction_window_config.end_hour_utc {
+            issues.push(ConfigIssue::new(
+                "compaction-window-config.end-hour-utc",
+                "must differ from start-hour-utc",
+            ));
+        }
+    }
+    if let Some(db_backup_config) = &config.db_backup_config {
+        check_writable(
+            "db-backup-config.backup-dir",
+            &db_backup_config.backup_dir,
+            &mut issues,
+        );
+        if db_backup_config.backup_interval_secs == Some(0) {
+            issues.push(ConfigIssue::new(
+                "db-backup-config.backup-interval-secs",
+                "must be at least 1",
+            ));
+        }
+        if db_backup_config.num_backups_to_retain == 0 {
+            issues.push(ConfigIssue::new(
+                "db-backup-config.num-backups-to-retain",
+                "must be at least 1",
+            ));
+        }
+    }
+    if let Some(watchdog_config) = &config.watchdog_config {
+        for (field, value) in [
+            (
+                "watchdog-config.probe-interval-ms",
+                watchdog_config.probe_interval_ms,
+            ),
+            (
+                "watchdog-config.blocked-runtime-threshold-ms",
+                watchdog_config.blocked_runtime_threshold_ms,
+            ),
+            (
+                "watchdog-config.slow-poll-threshold-ms",
+                watchdog_config.slow_poll_threshold_ms,
+            ),
+        ] {
+            if value == 0 {
+                issues.push(ConfigIssue::new(field, "must be at least 1"));
+            }
+        }
+    }
+    for (field, worker_threads) in [
+        (
+            "runtime-config.consensus-worker-threads",
+            config.runtime_config.consensus_worker_threads,
+        ),
+        (
+            "runtime-config.execution-worker-threads",
+            config.runtime_config.execution_worker_threads,
+        ),
+        (
+            "runtime-config.json-rpc-worker-threads",
+            config.runtime_config.json_rpc_worker_threads,
+        ),
+        (
+            "runtime-config.p2p-worker-threads",
+            config.runtime_config.p2p_worker_threads,
+        ),
+    ] {
+        if worker_threads == Some(0) {
+            issues.push(ConfigIssue::new(field, "must be at least 1"));
+        }
+    }
+    issues
+}
+
+fn check_key_pairs(config: &NodeConfig, issues: &mut Vec<ConfigIssue>) {
+    let protocol_key_pair = config.protocol_key_pair.try_authority_keypair();
+    if let Err(e) = &protocol_key_pair {
+        issues.push(ConfigIssue::new("protocol-key-pair", format!("{e:#}")));
+    }
+    if let Some(key_pair) = &config.next_protocol_key_pair {
+        match (key_pair.try_authority_keypair(), protocol_key_pair) {
+            (Err(e), _) => {
+                issues.push(ConfigIssue::new("next-protocol-key-pair", format!("{e:#}")))
+            }
+            (Ok(next), Ok(current)) if next.public() == current.public() => issues.push(
+                ConfigIssue::new("next-protocol-key-pair", "is the same as protocol-key-pair"),
+            ),
+            _ => (),
+        }
+    }
+    if let Err(e) = config.account_key_pair.try_keypair() {
+        issues.push(ConfigIssue::new("account-key-pair", format!("{e:#}")));
+    }
+    for (field, key_pair) in [
+        ("worker-key-pair", &config.worker_key_pair),
+        ("network-key-pair", &config.network_key_pair),
+    ] {
+        match key_pair.try_keypair() {
+            Ok(SuiKeyPair::Ed25519(_)) => (),
+            Ok(other) => issues.push(ConfigIssue::new(
+                field,
+                format!("only Ed25519 is allowed, found {}", other.public().scheme()),
+            )),
+            Err(e) => issues.push(ConfigIssue::new(field, format!("{e:#}"))),
+        }
+    }
+    if let Some(key_pair) = &config.next_network_key_pair {
+        match key_pair.try_keypair() {
+            Ok(SuiKeyPair::Ed25519(_)) => (),
+            Ok(other) => issues.push(ConfigIssue::new(
+                "next-network-key-pair",
+                format!("only Ed25519 is allowed, found {}", other.public().scheme()),
+            )),
+            Err(e) => issues.push(ConfigIssue::new("next-network-key-pair", format!("{e:#}"))),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Transport {
+    Tcp,
+    Udp,
+}
+
+/// An address the node listens on.
+struct Listener {
+    field: &'static str,
+    transport: Transport,
+    // `None` for a DNS name, which can only be checked once resolved.
+    ip: Option<IpAddr>,
+    port: u16,
+}
+
+impl Listener {
+    fn from_socket_addr(field: &'static str, transport: Transport, addr: SocketAddr) -> Self {
+        Self {
+            field,
+            transport,
+            ip: Some(addr.ip()),
+            port: addr.port(),
+        }
+    }
+
+    fn conflicts_with(&self, other: &Listener) -> bool {
+        // Port 0 is assigned by the OS, so it never conflicts.
+        if self.transport != other.transport || self.port != other.port || self.port == 0 {
+            return false;
+        }
+        match (self.ip, other.ip) {
+            (Some(a), Some(b)) => a == b || a.is_unspecified() || b.is_unspecified(),
+            // A DNS name may well resolve to an address the other listener is bound to.
+            _ => true,
+        }
+    }
+}
+
+fn check_addresses(config: &NodeConfig, issues: &mut Vec<ConfigIssue>) {
+    let mut listeners = vec![
+        Listener::from_socket_addr("json-rpc-address", Transport::Tcp, config.json_rpc_address),
+        Listener::from_socket_addr("metrics-address", Transport::Tcp, config.metrics_address),
+        Listener::from_socket_addr(
+            "admin-interface-port",
+            Transport::Tcp,
+            SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), config.admin_interface_port),
+        ),
+        Listener::from_socket_addr(
+            "p2p-config.listen-address",
+            Transport::Udp,
+            config.p2p_config.listen_address,
+        ),
+    ];
+    if let Some(addr) = config.health_check_config.listen_address {
+        listeners.push(Listener::from_socket_addr(
+            "health-check-config.listen-address",
+            Transport::Tcp,
+            addr,
+        ));
+    }
+    if let Some(graphql_config) = &config.graphql_config {
+        listeners.push(Listener::from_socket_addr(
+            "graphql-config.listen-address",
+            Transport::Tcp,
+            graphql_config.listen_address,
+        ));
+    }
+    if let Some(admin_rpc_config) = &config.admin_rpc_config {
+        listeners.push(Listener::from_socket_addr(
+            "admin-rpc-config.listen-address",
+            Transport::Tcp,
+            admin_rpc_config.listen_address,
+        ));
+    }
+    let mut multiaddrs = vec![];
+    if let Some(address) = &config.fullnode_grpc_address {
+        multiaddrs.push(("fullnode-grpc-address", address));
+    }
+    // Only validators serve the validator API and run consensus.
+    if let Some(consensus_config) = &config.consensus_config {
+        multiaddrs.push(("network-address", &config.network_address));
+        multiaddrs.push(("consensus-config.address", &consensus_config.address));
+    }
+    for (field, address) in multiaddrs {
+        match parse_multiaddr(address) {
+            Ok((ip, transport, port)) => listeners.push(Listener {
+                field,
+                transport,
+                ip,
+                port,
+            }),
+            Err(message) => issues.push(ConfigIssue::new(field, message)),
+        }
+    }
+
+    for (i, a) in listeners.iter().enumerate() {
+        for b in &listeners[i + 1..] {
+            if a.conflicts_with(b) {
+                issues.push(ConfigIssue::new(
+                    b.field,
+                    format!("port {} is already used by {}", b.port, a.field),
+                ));
+            }
+        }
+    }
+
+    if let Some(external_address) = &config.p2p_config.external_address {
+        if let Err(message) = check_external_address(external_address) {
+            issues.push(ConfigIssue::new("p2p-config.external-address", message));
+        }
+    }
+}
+
+/// The other nodes use the external address to connect to the node over QUIC, so it must be a
+/// routable UDP address.
+fn check_external_address(address: &Multiaddr) -> Result<(), String> {
+    let (ip, transport, port) = parse_multiaddr(address)?;
+    if transport != Transport::Udp {
+        return Err(format!("{address} is not a UDP address"));
+    }
+    if port == 0 {
+        return Err(format!("{address} does not have a port"));
+    }
+    if ip.map_or(false, |ip| ip.is_unspecified()) {
+        return Err(format!("{address} cannot be reached by other nodes"));
+    }
+    Ok(())
+}
+
+fn parse_multiaddr(address: &Multiaddr) -> Result<(Option<IpAddr>, Transport, u16), String> {
+    let mut protocols = address.iter();
+    let ip = match protocols.next() {
+        Some(Protocol::Ip4(ip)) => Some(IpAddr::V4(ip)),
+        Some(Protocol::Ip6(ip)) => Some(IpAddr::V6(ip)),
+        Some(Protocol::Dns(_) | Protocol::Dns4(_) | Protocol::Dns6(_)) => None,
+        _ => {
+            return Err(format!(
+                "{address} does not start with an IP address or DNS name"
+            ))
+        }
+    };
+    match protocols.next() {
+        Some(Protocol::Tcp(port)) => Ok((ip, Transport::Tcp, port)),
+        Some(Protocol::Udp(port)) => Ok((ip, Transport::Udp, port)),
+        _ => Err(format!("{address} does not have a TCP or UDP port")),
+    }
+}
+
+/// Checks that the node will be able to create files under `path`, creating it if needed.
+fn check_writable(field: &str, path: &Path, issues: &mut Vec<ConfigIssue>) {
+    // The node creates missing directories, so look at the closest one that exists.
+    let Some(existing) = path.ancestors().find(|p| p.exists()) else {
+        issues.push(ConfigIssue::new(field, format!("{} does not exist", path.display())));
+        return;
+    };
+    if !existing.is_dir() {
+        issues.push(ConfigIssue::new(
+            field,
+            format!("{} is not a directory", existing.display()),
+        ));
+        return;
+    }
+    let probe = existing.join(format!(".sui-node-write-check-{}", std::process::id()));
+    match fs::write(&probe, []) {
+        Ok(()) => {
+            let _ = fs::remove_file(&probe);
+        }
+        Err(e) => issues.push(ConfigIssue::new(
+            field,
+            format!("{} is not writable: {e}", existing.display()),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::node::{
+        AdminRpcConfig, ArchiveStoreConfig, CompactionWindowConfig, FormalSnapshotWriterConfig,
+    };
+
+    fn field_issues(config: &NodeConfig, field: &str) -> Vec<ConfigIssue> {
+        validate_node_config(config)
+            .into_iter()
+            .filter(|issue| issue.field == field)
+            .collect()
+    }
+
+    #[test]
+    fn test_validate_node_config() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let network_config = crate::builder::ConfigBuilder::new(&dir).build();
+        let mut config = network_config.validator_configs()[0].clone();
+        assert_eq!(validate_node_config(&config), vec![]);
+
+        let json_rpc_address = config.json_rpc_address;
+        config.fullnode_grpc_address = Some(
+            format!(
+                "/ip4/{}/tcp/{}",
+                json_rpc_address.ip(),
+                json_rpc_address.port()
+            )
+            .parse()
+            .unwrap(),
+        );
+        assert_eq!(field_issues(&config, "fullnode-grpc-address").len(), 1);
+        config.fullnode_grpc_address = None;
+
+        config.admin_rpc_config = Some(AdminRpcConfig {
+            listen_address: json_rpc_address,
+            auth_token: None,
+        });
+        assert_eq!(
+            field_issues(&config, "admin-rpc-config.listen-address").len(),
+            1
+        );
+        config.admin_rpc_config = None;
+
+        config.metrics_address = config.json_rpc_address;
+        assert_eq!(field_issues(&config, "metrics-address").len(), 1);
+
+        config.p2p_config.external_address = Some("/ip4/0.0.0.0/udp/8084".parse().unwrap());
+        assert_eq!(
+            field_issues(&config, "p2p-config.external-address").len(),
+            1
+        );
+        config.p2p_config.external_address = Some("/ip4/10.0.0.1/tcp/8084".parse().unwrap());
+        assert_eq!(
+            field_issues(&config, "p2p-config.external-address").len(),
+            1
+        );
+
+        config.compaction_window_config = Some(CompactionWindowConfig {
+            start_hour_utc: 24,
+            end_hour_utc: 24,
+            tables: Default::default(),
+        });
+        assert_eq!(
+            field_issues(&config, "compaction-window-config.start-hour-utc").len(),
+            1
+        );
+        assert_eq!(
+            field_issues(&config, "compaction-window-config.end-hour-utc").len(),
+            2
+        );
+
+        config.formal_snapshot_writer_config = Some(FormalSnapshotWriterConfig {
+            store: ArchiveStoreConfig::Directory(dir.path().join("snapshots")),
+            compress: true,
+            upload_interval_ms: 0,
+        });
+        config.state_snapshot_config.enabled = false;
+        assert_eq!(
+            field_issues(&config, "formal-snapshot-writer-config").len(),
+            1
+        );
+        assert_eq!(
+            field_issues(&config, "formal-snapshot-writer-config.upload-interval-ms").len(),
+            1
+        );
+        config.formal_snapshot_writer_config = None;
+
+        let file = dir.path().join("file");
+        fs::write(&file, "").unwrap();
+        config.db_path = file.join("db");
+        assert_eq!(field_issues(&config, "db-path").len(), 1);
+    }
+}
@@ -24,7 +24,9 @@ use prometheus::{
     IntCounterVec, IntGauge, Registry,
 };
 use serde::de::DeserializeOwned;
-use std::path::PathBuf;
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
 use std::sync::Arc;
 use std::time::Duration;
@@ -42,6 +44,7 @@ use sui_types::SUI_FRAMEWORK_OBJECT_ID;
 use tap::TapFallible;
 use tokio::sync::mpsc::unbounded_channel;
 use tokio::sync::oneshot;
+use tokio::sync::watch;
 use tokio_retry::strategy::{jitter, ExponentialBackoff};
 use tracing::{debug, error, error_span, info, instrument, trace, warn, Instrument};
 
@@ -54,12 +57,16 @@ use narwhal_config::{
 use sui_adapter::{adapter, execution_mode};
 use sui_config::genesis::Genesis;
 use sui_json_rpc_types::{
-    type_and_fields_from_move_struct, DevInspectResults, DryRunTransactionResponse, SuiEvent,
-    SuiEventEnvelope, SuiMoveValue, SuiTransactionEvents,
+    type_and_fields_from_move_struct, BalanceChange, CheckpointReexecution, DevInspectArgs,
+    DevInspectResults, DryRunTransactionResponse, GasEstimate, SuiEvent, SuiEventEnvelope,
+    SuiExecutionStatus, SuiGasProfile, SuiMoveValue, SuiTransactionEvents,
 };
 use sui_macros::nondeterministic;
 use sui_protocol_config::{ProtocolConfig, SupportedProtocolVersions};
-use sui_storage::indexes::{ObjectIndexChanges, MAX_GET_OWNED_OBJECT_SIZE};
+use sui_storage::indexes::{
+    coin_balance_index_key, is_of_type, ObjectIndexChanges, MAX_GET_OWNED_OBJECT_SIZE,
+    MAX_TX_RANGE_SIZE,
+};
 use sui_storage::write_ahead_log::WriteAheadLog;
 use sui_storage::{
     event_store::{EventStore, EventStoreType, StoredEvent},
@@ -70,7 +77,8 @@ use sui_types::committee::{EpochId, ProtocolVersion};
 use sui_types::crypto::{sha3_hash, AuthorityKeyPair, NetworkKeyPair, Signer};
 use sui_types::dynamic_field::{DynamicFieldInfo, DynamicFieldName, DynamicFieldType};
 use sui_types::event::{Event, EventID};
-use sui_types::gas::{GasCostSummary, GasPrice, SuiCostTable, SuiGasStatus};
+use sui_types::gas::{get_gas_balance, GasCostSummary, GasPrice, SuiCostTable, SuiGasStatus};
+use sui_types::gas_coin::GasCoin;
 use sui_types::messages_checkpoint::{
     CheckpointContents, CheckpointContentsDigest, CheckpointDigest, CheckpointSequenceNumber,
     CheckpointSummary, CheckpointTimestamp, VerifiedCheckpoint,
@@ -97,7 +105,9 @@ use crate::authority::authority_per_epoch_store::{
     AuthorityPerEpochStore, EpochStartConfiguration,
 };
 use crate::authority::authority_per_epoch_store_pruner::AuthorityPerEpochStorePruner;
-use crate::authority::authority_store::{ExecutionLockReadGuard, InputKey, ObjectLockStatus};
+use crate::authority::authority_store::{
+    ExecutionLockReadGuard, ExecutionLockWriteGuard, InputKey, ObjectLockStatus,
+};
 use crate::authority::authority_store_pruner::AuthorityStorePruner;
 use crate::checkpoints::CheckpointStore;
 use crate::epoch::committee_store::CommitteeStore;
@@ -105,11 +115,15 @@ use crate::epoch::epoch_metrics::EpochMetrics;
 use crate::execution_driver::execution_process;
 use crate::module_cache_metrics::ResolverMetrics;
 use crate::stake_aggregator::StakeAggregator;
+use crate::state_accumulator::StateAccumulator;
 use crate::{
-    event_handler::EventHandler, transaction_input_checker, transaction_manager::TransactionManager,
+    event_handler::{event_checkpoints_process, EventHandler},
+    transaction_input_checker,
+    transaction_manager::TransactionManager,
 };
 use sui_adapter::execution_engine;
 use sui_types::digests::TransactionEventsDigest;
+use typed_store::rocks::RocksDB;
 
 #[cfg(test)]
 #[path = "unit_tests/authority_tests.rs"]
@@ -134,6 +148,8 @@ mod tbls_tests;
 pub mod authority_per_epoch_store;
 pub mod authority_per_epoch_store_pruner;
 
+pub mod authority_store_backend;
+pub mod authority_store_migrations;
 pub mod authority_store_pruner;
 pub mod authority_store_tables;
 pub mod authority_store_types;
@@ -190,6 +206,7 @@ pub struct AuthorityMetrics {
     pub(crate) transaction_manager_num_pending_certificates: IntGauge,
     pub(crate) transaction_manager_num_executing_certificates: IntGauge,
     pub(crate) transaction_manager_num_ready: IntGauge,
+    pub(crate) transaction_manager_pending_certificate_wait_latency: Histogram,
 
     pub(crate) execution_driver_executed_transactions: IntCounter,
 
@@ -208,6 +225,14 @@ pub struct AuthorityMetrics {
     pub consensus_handler_processed: IntCounterVec,
 }
 
+/// How much more than the gas used in a dry run `AuthorityState::estimate_gas` recommends as the
+/// budget.
+pub const GAS_BUDGET_SAFETY_MARGIN_PERCENT: u64 = 20;
+
+/// How many checkpoints old a snapshot of the objects of the owners can be, as each page read
+/// against it reads the effects of the checkpoints executed since.
+pub const MAX_OWNED_OBJECTS_SNAPSHOT_AGE: CheckpointSequenceNumber = 1_000;
+
 // Override default Prom buckets for positive numbers in 0-50k range
 const POSITIVE_INT_BUCKETS: &[f64] = &[
     1., 2., 5., 10., 20., 50., 100., 200., 500., 1000., 2000., 5000., 10000., 20000., 50000.,
@@ -358,6 +383,13 @@ impl AuthorityMetrics {
                 registry,
             )
             .unwrap(),
+            transaction_manager_pending_certificate_wait_latency: register_histogram_with_registry!(
+                "transaction_manager_pending_certificate_wait_latency",
+                "Time certificates spent in TransactionManager waiting for missing input objects",
+                LATENCY_SEC_BUCKETS.to_vec(),
+                registry,
+            )
+            .unwrap(),
             execution_driver_executed_transactions: register_int_counter_with_registry!(
                 "execution_driver_executed_transactions",
                 "Cumulative number of transaction executed by execution driver",
@@ -445,7 +477,7 @@ pub struct AuthorityState {
     tx_execution_shutdown: Mutex<Option<oneshot::Sender<()>>>,
 
     pub metrics: Arc<AuthorityMetrics>,
-    _objects_pruner: AuthorityStorePruner,
+    objects_pruner: AuthorityStorePruner,
     _authority_per_epoch_pruner: AuthorityPerEpochStorePruner,
 
     /// Take snapshot of the live object set at the end of epoch
@@ -685,7 +717,7 @@ impl AuthorityState {
     /// If this cannot be satisfied by the caller, execute_certificate() should be called instead.
     ///
     /// Should only be called within sui-core.
-    #[instrument(level = "trace", skip_all)]
+    #[instrument(level = "trace", skip_all, fields(tx_digest = ?certificate.digest()))]
     pub async fn try_execute_immediately(
         &self,
         certificate: &VerifiedExecutableTransaction,
@@ -980,9 +1012,11 @@ impl AuthorityState {
             .certificate_executed(digest, epoch_store)
     }
 
+    /// A transaction without gas payment is run with gas coins picked by
+    /// [`Self::select_dry_run_gas`].
     pub async fn dry_exec_transaction(
         &self,
-        transaction: TransactionData,
+        mut transaction: TransactionData,
         transaction_digest: TransactionDigest,
     ) -> Result<DryRunTransactionResponse, anyhow::Error> {
         let epoch_store = self.load_epoch_store_one_call_per_task();
@@ -990,12 +1024,30 @@ impl AuthorityState {
             return Err(anyhow!("dry-exec is only support on fullnodes"));
         }
 
-        let (gas_status, input_objects) = transaction_input_checker::check_transaction_input(
-            &self.database,
-            epoch_store.as_ref(),
-            &transaction,
-        )
-        .await?;
+        let synthetic_gas = if transaction.gas().is_empty() {
+            self.select_dry_run_gas(&epoch_store, &mut transaction)?
+        } else {
+            None
+        };
+        let (gas_status, input_objects) = match synthetic_gas {
+            Some(gas_object) => {
+                transaction_input_checker::check_dry_run_input(
+                    &self.database,
+                    epoch_store.as_ref(),
+                    &transaction,
+                    gas_object,
+                )
+                .await?
+            }
+            None => {
+                transaction_input_checker::check_transaction_input(
+                    &self.database,
+                    epoch_store.as_ref(),
+                    &transaction,
+                )
+                .await?
+            }
+        };
         let shared_object_refs = input_objects.filter_shared_objects();
 
         let transaction_dependencies = input_objects.transaction_dependencies();
@@ -1036,12 +1088,84 @@ impl AuthorityState {
         })
     }
 
+    /// Fills in the gas payment of a dry run sent without one, with the largest gas coins of the
+    /// gas owner that the transaction does not already use, until they cover the budget. If the
+    /// owner does not have enough, the payment is a synthetic coin that does, which is returned
+    /// as it is not in the store.
+    fn select_dry_run_gas(
+        &self,
+        epoch_store: &AuthorityPerEpochStore,
+        transaction: &mut TransactionData,
+    ) -> Result<Option<Object>, anyhow::Error> {
+        // Pay SUI transactions pay for gas with their first input coin, which has to be given.
+        if transaction.is_system_tx() || transaction.kind().is_pay_sui_tx() {
+            return Err(UserInputError::MissingGasPayment.into());
+        }
+        let gas_price = std::cmp::max(
+            transaction.gas_price(),
+            epoch_store.protocol_config().storage_gas_price(),
+        );
+        let extra_amount = match transaction.kind() {
+            TransactionKind::Single(SingleTransactionKind::TransferSui(t)) => {
+                t.amount.unwrap_or_default()
+            }
+            _ => 0,
+        };
+        let required = transaction.gas_budget() as u128 * gas_price as u128 + extra_amount as u128;
+
+        let owner = transaction.gas_owner();
+        let used: HashSet<_> = transaction
+            .input_objects()?
+            .iter()
+            .map(|kind| kind.object_id())
+            .collect();
+        let mut coins = vec![];
+        for info in self.get_owner_objects_iterator(owner)? {
+            let is_gas_coin =
+                matches!(&info.type_, ObjectType::Struct(type_) if type_ == &GasCoin::type_());
+            if !is_gas_coin || used.contains(&info.object_id) {
+                continue;
+            }
+            if let Some(object) = self.database.get_object(&info.object_id)? {
+                coins.push((get_gas_balance(&object)?, object.compute_object_reference()));
+            }
+        }
+        coins.sort_by_key(|(balance, (id, _, _))| (Reverse(*balance), *id));
+
+        let mut total = 0;
+        let mut payment = vec![];
+        for (balance, object_ref) in coins {
+            if total >= required {
+                break;
+            }
+            total += balance as u128;
+            payment.push(object_ref);
+        }
+        if total >= required {
+            transaction.gas_data_mut().payment = payment;
+            return Ok(None);
+        }
+
+        let gas_object = Object::new_move(
+            MoveObject::new_gas_coin(
+                SequenceNumber::new(),
+                ObjectID::random(),
+                u64::try_from(required).unwrap_or(u64::MAX),
+            ),
+            Owner::AddressOwner(owner),
+            TransactionDigest::genesis(),
+        );
+        transaction.gas_data_mut().payment = vec![gas_object.compute_object_reference()];
+        Ok(Some(gas_object))
+    }
+
     /// The object ID for gas can be any object ID, even for an uncreated object
     pub async fn dev_inspect_transaction(
         &self,
         sender: SuiAddress,
         transaction_kind: TransactionKind,
         gas_price: Option<u64>,
+        args: DevInspectArgs,
     ) -> Result<DevInspectResults, anyhow::Error> {
         let epoch_store = self.load_epoch_store_one_call_per_task();
         if !self.is_fullnode(&epoch_store) {
@@ -1056,10 +1180,18 @@ impl AuthorityState {
 
         let max_tx_gas = protocol_config.max_tx_gas();
         let storage_gas_price = protocol_config.storage_gas_price();
+        let skip_checks = args.skip_checks.unwrap_or_default();
+        let gas_budget = args.gas_budget.unwrap_or(max_tx_gas);
+        // Bounded even when checks are skipped, since anyone can ask for a run.
+        if gas_budget > max_tx_gas {
+            return Err(anyhow!(
+                "Gas budget {gas_budget} is above the maximum of {max_tx_gas}"
+            ));
+        }
 
         let gas_object_id = ObjectID::random();
         let gas_object = Object::new_move(
-            MoveObject::new_gas_coin(SequenceNumber::new(), gas_object_id, max_tx_gas),
+            MoveObject::new_gas_coin(SequenceNumber::new(), gas_object_id, gas_budget),
             Owner::AddressOwner(sender),
             TransactionDigest::genesis(),
         );
@@ -1068,13 +1200,13 @@ impl AuthorityState {
             protocol_config,
             &transaction_kind,
             gas_object,
+            skip_checks,
         )
         .await?;
         let shared_object_refs = input_objects.filter_shared_objects();
 
         // TODO should we error instead for 0?
         let gas_price = std::cmp::max(gas_price, 1);
-        let gas_budget = max_tx_gas;
         let data = TransactionData::new(
             transaction_kind,
             sender,
@@ -1082,6 +1214,12 @@ impl AuthorityState {
             gas_price,
             gas_budget,
         );
+        let show_raw = args.show_raw_txn_data_and_effects.unwrap_or_default();
+        let raw_txn_data = if show_raw {
+            bcs::to_bytes(&data)?
+        } else {
+            vec![]
+        };
         let transaction_digest = TransactionDigest::new(sha3_hash(&data));
         let transaction_kind = data.into_kind();
         let transaction_dependencies = input_objects.transaction_dependencies();
@@ -1092,12 +1230,16 @@ impl AuthorityState {
             protocol_config,
         );
         let mut gas_status = SuiGasStatus::new_with_budget(
-            max_tx_gas,
+            gas_budget,
             GasPrice::from(gas_price),
             storage_gas_price.into(),
             SuiCostTable::new(protocol_config),
         );
         gas_status.charge_min_tx_gas()?;
+        let gas_profile = args
+            .profile_gas
+            .unwrap_or_default()
+            .then(|| gas_status.enable_profiling());
         let move_vm = Arc::new(
             adapter::new_move_vm(
                 epoch_store.native_functions().clone(),
@@ -1119,12 +1261,67 @@ impl AuthorityState {
                 &epoch_store.epoch_start_configuration().epoch_data(),
                 protocol_config,
             );
-        DevInspectResults::new(
+        let raw_effects = if show_raw {
+            bcs::to_bytes(&effects)?
+        } else {
+            vec![]
+        };
+        let mut results = DevInspectResults::new(
             effects,
             inner_temp_store.events,
             execution_result,
             epoch_store.module_cache().as_ref(),
-        )
+        )?;
+        results.raw_txn_data = raw_txn_data;
+        results.raw_effects = raw_effects;
+        results.gas_profile = gas_profile.map(|profile| {
+            let profile = profile.lock().unwrap().clone();
+            SuiGasProfile::from(profile)
+        });
+        Ok(results)
+    }
+
+    /// Recommends a gas budget for `transaction_kind`, from a dev-inspect run of it plus
+    /// `GAS_BUDGET_SAFETY_MARGIN_PERCENT`. The gas used can vary between the estimate and the
+    /// actual run, e.g. if shared objects it touches change size in between.
+    pub async fn estimate_gas(
+        &self,
+        sender: SuiAddress,
+        transaction_kind: TransactionKind,
+        gas_price: Option<u64>,
+    ) -> Result<GasEstimate, anyhow::Error> {
+        let epoch_store = self.load_epoch_store_one_call_per_task();
+        let gas_price = std::cmp::max(
+            gas_price.unwrap_or_else(|| epoch_store.reference_gas_price()),
+            1,
+        );
+        let results = self
+            .dev_inspect_transaction(
+                sender,
+                transaction_kind,
+                Some(gas_price),
+                DevInspectArgs::default(),
+            )
+            .await?;
+        if let SuiExecutionStatus::Failure { error } = results.effects.status {
+            return Err(anyhow!("Transaction failed in the dry run: {error}"));
+        }
+
+        let gas_used = results.effects.gas_used;
+        // The budget has to cover the whole cost up front, the rebate is only paid out after.
+        let cost = gas_used.computation_cost as u128 + gas_used.storage_cost as u128;
+        let with_margin = (cost * (100 + GAS_BUDGET_SAFETY_MARGIN_PERCENT as u128) + 99) / 100;
+
+        let cost_table = SuiCostTable::new(epoch_store.protocol_config());
+        let min_budget = cost_table.min_gas_budget_external() as u128 * gas_price as u128;
+        let max_budget = cost_table.max_gas_budget as u128 * gas_price as u128;
+        let gas_budget = with_margin.max(min_budget).min(max_budget);
+
+        Ok(GasEstimate {
+            gas_budget: u64::try_from(gas_budget).unwrap_or(u64::MAX),
+            gas_price,
+            gas_used,
+        })
     }
 
     pub fn is_tx_already_executed(&self, digest: &TransactionDigest) -> SuiResult<bool> {
@@ -1183,10 +1380,13 @@ impl AuthorityState {
 
         let mut deleted_owners = vec![];
         let mut deleted_dynamic_fields = vec![];
+        let mut deleted_coins = vec![];
         for (id, _, _) in effects.deleted() {
             let old_version = modified_at_version.get(id).unwrap();
+            let old_object = self.get_object_at_version(id, *old_version)?;
+            deleted_coins.extend(coin_balance_index_key(&old_object));
 
-            match self.get_owner_at_version(id, *old_version)? {
+            match old_object.owner {
                 Owner::AddressOwner(addr) => deleted_owners.push((addr, *id)),
                 Owner::ObjectOwner(object_id) => {
                     deleted_dynamic_fields.push((ObjectID::from(object_id), *id))
@@ -1197,6 +1397,7 @@ impl AuthorityState {
 
         let mut new_owners = vec![];
         let mut new_dynamic_fields = vec![];
+        let mut new_coins = vec![];
 
         for (oref, owner, kind) in effects.all_mutated() {
             let id = &oref.0;
@@ -1210,6 +1411,8 @@ impl AuthorityState {
                         error!("Error processing object owner index for tx [{:?}], cannot find object [{id}] at version [{old_version}].", effects.transaction_digest());
                         continue;
                     };
+                // The coin is indexed by its balance, which may change along with the owner.
+                deleted_coins.extend(coin_balance_index_key(&old_object));
                 if &old_object.owner != owner {
                     match old_object.owner {
                         Owner::AddressOwner(addr) => {
@@ -1234,6 +1437,7 @@ impl AuthorityState {
                         .type_()
                         .map(|type_| ObjectType::Struct(type_.clone()))
                         .unwrap_or(ObjectType::Package);
+                    new_coins.extend(coin_balance_index_key(&o));
 
                     new_owners.push((
                         (*addr, *id),
@@ -1266,6 +1470,8 @@ impl AuthorityState {
             deleted_dynamic_fields,
             new_owners,
             new_dynamic_fields,
+            deleted_coins,
+            new_coins,
         })
     }
 
@@ -1388,6 +1594,15 @@ impl AuthorityState {
             }
         };
 
+        if let Some(event_handler) = &self.event_handler {
+            event_handler
+                .process_transaction(
+                    certificate.data().intent_message.value.clone(),
+                    effects.clone(),
+                )
+                .await;
+        }
+
         Ok(())
     }
 
@@ -1526,14 +1741,13 @@ impl AuthorityState {
         checkpoint_store: Arc<CheckpointStore>,
         prometheus_registry: &Registry,
         pruning_config: AuthorityStorePruningConfig,
-        genesis_objects: &[Object],
         epoch_duration_ms: u64,
         state_snapshot_config: &StateSnapshotConfig,
     ) -> Arc<Self> {
         Self::check_protocol_version(supported_protocol_versions, epoch_store.protocol_version());
 
         let event_handler = event_store.map(|es| {
-            let handler = EventHandler::new(es);
+            let handler = EventHandler::new(es, prometheus_registry);
             handler.regular_cleanup_task();
             Arc::new(handler)
         });
@@ -1549,9 +1763,10 @@ impl AuthorityState {
 
         let _authority_per_epoch_pruner =
             AuthorityPerEpochStorePruner::new(epoch_store.get_parent_path(), &pruning_config);
-        let _objects_pruner = AuthorityStorePruner::new(
+        let objects_pruner = AuthorityStorePruner::new(
             store.perpetual_tables.clone(),
             checkpoint_store.clone(),
+            indexes.clone(),
             pruning_config,
             epoch_duration_ms,
         );
@@ -1568,7 +1783,7 @@ impl AuthorityState {
             transaction_manager,
             tx_execution_shutdown: Mutex::new(Some(tx_execution_shutdown)),
             metrics,
-            _objects_pruner,
+            objects_pruner,
             _authority_per_epoch_pruner,
             enable_state_snapshot: state_snapshot_config.enabled,
         });
@@ -1588,9 +1803,14 @@ impl AuthorityState {
             rx_execution_shutdown
         ));
 
+        // Start a task to record the checkpoints of transactions, for querying events by checkpoint.
+        if state.event_handler.is_some() && state.indexes.is_some() {
+            spawn_monitored_task!(event_checkpoints_process(Arc::downgrade(&state)));
+        }
+
         state
-            .create_owner_index_if_empty(genesis_objects, &epoch_store)
-            .expect("Error indexing genesis objects.");
+            .create_owner_index_if_empty(&epoch_store)
+            .expect("Error indexing the live objects.");
 
         state
     }
@@ -1659,16 +1879,13 @@ impl AuthorityState {
             checkpoint_store,
             &registry,
             AuthorityStorePruningConfig::default(),
-            genesis.objects(),
             10000,
             &StateSnapshotConfig::default(),
         )
         .await;
 
         let epoch_store = state.epoch_store_for_testing();
-        state
-            .create_owner_index_if_empty(genesis.objects(), &epoch_store)
-            .unwrap();
+        state.create_owner_index_if_empty(&epoch_store).unwrap();
 
         state
     }
@@ -1738,11 +1955,9 @@ impl AuthorityState {
         Ok(())
     }
 
-    fn create_owner_index_if_empty(
-        &self,
-        genesis_objects: &[Object],
-        epoch_store: &Arc<AuthorityPerEpochStore>,
-    ) -> SuiResult {
+    /// Indexes the live objects of the store if the index is empty, i.e. the objects of genesis,
+    /// or of the formal snapshot the store was restored from.
+    fn create_owner_index_if_empty(&self, epoch_store: &Arc<AuthorityPerEpochStore>) -> SuiResult {
         let Some(index_store) = &self.indexes else{
             return Ok(())
         };
@@ -1752,15 +1967,20 @@ impl AuthorityState {
 
         let mut new_owners = vec![];
         let mut new_dynamic_fields = vec![];
-        for o in genesis_objects.iter() {
+        let mut new_coins = vec![];
+        for (object_id, version, _) in self.database.iter_live_object_set() {
+            let Some(o) = self.database.get_object_by_key(&object_id, version)? else {
+                continue;
+            };
+            new_coins.extend(coin_balance_index_key(&o));
             match o.owner {
                 Owner::AddressOwner(addr) => new_owners.push((
                     (addr, o.id()),
-                    ObjectInfo::new(&o.compute_object_reference(), o),
+                    ObjectInfo::new(&o.compute_object_reference(), &o),
                 )),
                 Owner::ObjectOwner(object_id) => {
                     let id = o.id();
-                    let Some(info) = self.try_create_dynamic_field_info(o, epoch_store)? else{
+                    let Some(info) = self.try_create_dynamic_field_info(&o, epoch_store)? else{
                         continue;
                     };
                     new_dynamic_fields.push(((ObjectID::from(object_id), id), info));
@@ -1774,6 +1994,8 @@ impl AuthorityState {
             deleted_dynamic_fields: vec![],
             new_owners,
             new_dynamic_fields,
+            deleted_coins: vec![],
+            new_coins,
         })
     }
 
@@ -1794,10 +2016,20 @@ impl AuthorityState {
         let mut execution_lock = db.execution_lock_for_reconfiguration().await;
         self.revert_uncommitted_epoch_transactions(cur_epoch_store)
             .await?;
+        let new_protocol_config =
+            ProtocolConfig::get_for_version(epoch_start_configuration.protocol_version());
+        if new_protocol_config.commit_root_state_digest()
+            && !cur_epoch_store.protocol_config().commit_root_state_digest()
+        {
+            // The new epoch is the first one whose last checkpoint commits to the root state
+            // digest, which is accumulated from the root state hash of the epoch ending here. The
+            // live object set is only scanned once the execution lock is released.
+            StateAccumulator::new(db.clone()).reset_root_state_hash(&cur_epoch_store.epoch())?;
+        }
         if self.enable_state_snapshot {
-            let _checkpointed_db_path = self.checkpoint_perpetual_db()?;
-            // TODO: Start a background task to persist live object set in
-            // checkpointed db to canonical storage format
+            // The formal snapshot uploader of the node, if configured, writes the live object set
+            // of the checkpointed database to a formal snapshot.
+            self.checkpoint_perpetual_db()?;
         }
         let new_epoch = new_committee.epoch;
         let new_epoch_store = self
@@ -1812,6 +2044,100 @@ impl AuthorityState {
         Ok(new_epoch_store)
     }
 
+    /// Changes how many epochs of old object versions are retained by the objects pruner.
+    pub fn set_objects_pruning_retention(&self, num_epochs_to_retain: u64) {
+        self.objects_pruner
+            .set_num_epochs_to_retain(num_epochs_to_retain);
+    }
+
+    /// Starts a run of the objects pruner without waiting for its next period.
+    pub fn force_prune(&self) {
+        self.objects_pruner.force_prune();
+    }
+
+    /// Writes the in-memory tables of the perpetual database out to disk.
+    pub fn flush_memtables(&self) -> SuiResult {
+        self.database.perpetual_tables.flush_memtables()
+    }
+
+    /// Enqueues for execution the transactions of the checkpoint `sequence_number` that the node
+    /// has not executed, without waiting for them, and reports the ones it executed with effects
+    /// other than the checkpoint's. Only checkpoints of the current epoch can be re-executed.
+    pub async fn reexecute_checkpoint(
+        &self,
+        sequence_number: CheckpointSequenceNumber,
+    ) -> SuiResult<CheckpointReexecution> {
+        let epoch_store = self.load_epoch_store_one_call_per_task();
+        let checkpoint = self
+            .checkpoint_store
+            .get_checkpoint_by_sequence_number(sequence_number)?
+            .ok_or_else(|| SuiError::GenericAuthorityError {
+                error: format!("Checkpoint {sequence_number} not found"),
+            })?;
+        if checkpoint.epoch() != epoch_store.epoch() {
+            return Err(SuiError::GenericAuthorityError {
+                error: format!(
+                    "Checkpoint {sequence_number} is from epoch {}, only checkpoints of the current epoch {} can be re-executed",
+                    checkpoint.epoch(),
+                    epoch_store.epoch()
+                ),
+            });
+        }
+        let contents = self
+            .checkpoint_store
+            .get_checkpoint_contents(&checkpoint.content_digest())?
+            .ok_or_else(|| SuiError::GenericAuthorityError {
+                error: format!("Contents of checkpoint {sequence_number} not found"),
+            })?;
+        let digests: Vec<_> = contents.iter().copied().collect();
+        let tx_digests: Vec<_> = digests.iter().map(|digests| digests.transaction).collect();
+
+        let mut reexecution = CheckpointReexecution::default();
+        let mut missing = vec![];
+        for (digests, executed) in digests
+            .iter()
+            .zip(self.database.multi_get_executed_effects(&tx_digests)?)
+        {
+            match executed {
+                Some(effects) if effects.digest() == digests.effects => {
+                    reexecution.executed.push(digests.transaction)
+                }
+                Some(_) => reexecution.mismatched.push(digests.transaction),
+                None => missing.push(*digests),
+            }
+        }
+
+        let mut executable_txns = vec![];
+        for digests in missing {
+            let transaction = self.database.get_transaction(&digests.transaction)?.ok_or(
+                SuiError::TransactionNotFound {
+                    digest: digests.transaction,
+                },
+            )?;
+            let transaction = VerifiedExecutableTransaction::new_from_checkpoint(
+                transaction,
+                epoch_store.epoch(),
+                sequence_number,
+            );
+            if transaction.contains_shared_object() {
+                let effects = self
+                    .database
+                    .get_effects(&digests.effects)?
+                    .ok_or_else(|| SuiError::GenericAuthorityError {
+                        error: format!("Effects of transaction {} not found", digests.transaction),
+                    })?;
+                epoch_store
+                    .acquire_shared_locks_from_effects(&transaction, &effects, &self.database)
+                    .await?;
+            }
+            reexecution.enqueued.push(digests.transaction);
+            executable_txns.push(transaction);
+        }
+        self.transaction_manager
+            .enqueue(executable_txns, &epoch_store)?;
+        Ok(reexecution)
+    }
+
     pub fn db(&self) -> Arc<AuthorityStore> {
         self.database.clone()
     }
@@ -2003,11 +2329,11 @@ impl AuthorityState {
         }
     }
 
-    fn get_owner_at_version(
+    fn get_object_at_version(
         &self,
         object_id: &ObjectID,
         version: SequenceNumber,
-    ) -> Result<Owner, SuiError> {
+    ) -> Result<Object, SuiError> {
         self.database
             .get_object_by_key(object_id, version)?
             .ok_or_else(|| {
@@ -2016,7 +2342,6 @@ impl AuthorityState {
                     version: Some(version),
                 })
             })
-            .map(|o| o.owner)
     }
 
     pub fn get_owner_objects(&self, owner: SuiAddress) -> SuiResult<Vec<ObjectInfo>> {
@@ -2038,6 +2363,171 @@ impl AuthorityState {
         }
     }
 
+    /// Like [`Self::get_owner_objects_iterator`], for the objects of type `type_`, or of any
+    /// instantiation of it if `type_` has no type parameters.
+    pub fn get_owner_objects_of_type_iterator(
+        &self,
+        owner: SuiAddress,
+        type_: &StructTag,
+    ) -> SuiResult<impl Iterator<Item = ObjectInfo> + '_> {
+        if let Some(indexes) = &self.indexes {
+            Ok(indexes
+                .owner_objects_of_type_from(owner, type_, ObjectID::ZERO)?
+                .take(MAX_GET_OWNED_OBJECT_SIZE))
+        } else {
+            Err(SuiError::IndexStoreNotAvailable)
+        }
+    }
+
+    /// Like [`IndexStore::coins_by_balance_from`], `None` until the coin balance index is
+    /// backfilled.
+    pub fn get_coins_by_balance_iterator(
+        &self,
+        owner: SuiAddress,
+        coin_type: &StructTag,
+        min_balance: u64,
+        start: Option<(u64, ObjectID)>,
+    ) -> SuiResult<Option<impl Iterator<Item = (u64, ObjectID)> + '_>> {
+        if let Some(indexes) = &self.indexes {
+            indexes.coins_by_balance_from(owner, coin_type, min_balance, start)
+        } else {
+            Err(SuiError::IndexStoreNotAvailable)
+        }
+    }
+
+    /// The highest executed checkpoint, as the snapshot of the objects of each owner to be passed
+    /// to [`Self::get_owner_objects_at_snapshot`].
+    pub fn get_owner_objects_snapshot(&self) -> Result<CheckpointSequenceNumber, anyhow::Error> {
+        self.checkpoint_store
+            .get_highest_executed_checkpoint_seq_number()?
+            .ok_or_else(|| anyhow!("No checkpoint has been executed yet"))
+    }
+
+    /// Returns up to `limit` objects of `owner` from `cursor` on, only those of type `type_` if
+    /// given, as they were at the end of checkpoint `snapshot`. A type without type parameters
+    /// matches all its instantiations. The objects changed since are read at the version they
+    /// had before their first change, so that pages read against the same snapshot list the
+    /// objects of the owner without duplicates or gaps, however they change in between. A
+    /// snapshot can be read while it is at most [`MAX_OWNED_OBJECTS_SNAPSHOT_AGE`] checkpoints
+    /// old, and as long as the effects and versions it needs are not pruned.
+    pub fn get_owner_objects_at_snapshot(
+        &self,
+        owner: SuiAddress,
+        type_: Option<&StructTag>,
+        snapshot: CheckpointSequenceNumber,
+        cursor: ObjectID,
+        limit: usize,
+    ) -> Result<Vec<ObjectInfo>, anyhow::Error> {
+        let indexes = self.get_indexes()?;
+        let highest_executed = self.get_owner_objects_snapshot()?;
+        let too_old = || {
+            anyhow!(
+                "Snapshot {snapshot} is too old or unknown to this node, restart from the first page"
+            )
+        };
+        if snapshot > highest_executed
+            || highest_executed - snapshot > MAX_OWNED_OBJECTS_SNAPSHOT_AGE
+        {
+            return Err(too_old());
+        }
+
+        // The effects of the transactions executed since the snapshot: those of the checkpoints
+        // executed since, then those not included in an executed checkpoint yet, which are among
+        // the latest indexed ones.
+        let mut changes = vec![];
+        for sequence_number in snapshot + 1..=highest_executed {
+            let checkpoint = self
+                .checkpoint_store
+                .get_checkpoint_by_sequence_number(sequence_number)?
+                .ok_or_else(too_old)?;
+            let contents = self
+                .checkpoint_store
+                .get_checkpoint_contents(&checkpoint.content_digest())?
+                .ok_or_else(too_old)?;
+            for effects in self
+                .database
+                .perpetual_tables
+                .effects
+                .multi_get(contents.iter().map(|digests| digests.effects))?
+            {
+                changes.push(effects.ok_or_else(too_old)?);
+            }
+        }
+        let end = indexes.first_unindexed_sequence_number();
+        let recent: Vec<_> = indexes
+            .get_transactions_in_range(end.saturating_sub(MAX_TX_RANGE_SIZE), end)?
+            .into_iter()
+            .map(|(_, digest)| digest)
+            .collect();
+        let not_finalized: Vec<_> = recent
+            .iter()
+            .zip(self.database.multi_get_transaction_checkpoint(&recent)?)
+            .filter(|(_, checkpoint)| {
+                checkpoint.map_or(true, |(_, checkpoint)| checkpoint > highest_executed)
+            })
+            .map(|(digest, _)| *digest)
+            .collect();
+        changes.extend(
+            self.database
+                .multi_get_executed_effects(&not_finalized)?
+                .into_iter()
+                .flatten(),
+        );
+
+        // The version each object changed since the snapshot had at the snapshot, or None if it
+        // did not exist yet, by object ID.
+        let mut snapshot_versions = BTreeMap::new();
+        for effects in &changes {
+            for (object_id, version) in effects.modified_at_versions() {
+                snapshot_versions
+                    .entry(*object_id)
+                    .or_insert(Some(*version));
+            }
+            for ((object_id, _, _), _) in effects.created().iter().chain(effects.unwrapped()) {
+                snapshot_versions.entry(*object_id).or_insert(None);
+            }
+        }
+
+        // Both the index and the changed objects are read from the cursor on, only as far as
+        // the page goes.
+        let current_objects: Box<dyn Iterator<Item = ObjectInfo> + '_> = match type_ {
+            Some(type_) => indexes.owner_objects_of_type_from(owner, type_, cursor)?,
+            None => Box::new(indexes.owner_objects_from(owner, cursor)?),
+        };
+        let mut objects: Vec<_> = current_objects
+            .filter(|info| !snapshot_versions.contains_key(&info.object_id))
+            .take(limit)
+            .collect();
+        let last_current = match objects.len() == limit {
+            true => objects.last().map(|info| info.object_id),
+            false => None,
+        };
+        let mut num_changed = 0;
+        for (object_id, version) in snapshot_versions.range(cursor..) {
+            if num_changed == limit || last_current.map_or(false, |last| *object_id > last) {
+                break;
+            }
+            let Some(version) = version else { continue };
+            let object = self
+                .database
+                .get_object_by_key(object_id, *version)?
+                .ok_or(UserInputError::ObjectNotFound {
+                    object_id: *object_id,
+                    version: Some(*version),
+                })
+                .map_err(SuiError::from)?;
+            let matches_type =
+                type_.map_or(true, |type_| is_of_type(type_, &ObjectType::from(&object)));
+            if object.owner == Owner::AddressOwner(owner) && matches_type {
+                objects.push(ObjectInfo::new(&object.compute_object_reference(), &object));
+                num_changed += 1;
+            }
+        }
+        objects.sort_by_key(|info| info.object_id);
+        objects.truncate(limit);
+        Ok(objects)
+    }
+
     pub async fn get_move_objects<T>(
         &self,
         owner: SuiAddress,
@@ -2047,8 +2537,7 @@ impl AuthorityState {
         T: DeserializeOwned,
     {
         let object_ids = self
-            .get_owner_objects_iterator(owner)?
-            .filter(move |o| Self::matches_type(&ObjectType::Struct(type_.clone()), &o.type_))
+            .get_owner_objects_of_type_iterator(owner, type_)?
             .map(|info| info.object_id);
         let mut move_objects = vec![];
         for id in object_ids {
@@ -2057,19 +2546,6 @@ impl AuthorityState {
         Ok(move_objects)
     }
 
-    fn matches_type(type_: &ObjectType, other_type: &ObjectType) -> bool {
-        match (type_, other_type) {
-            (ObjectType::Package, ObjectType::Package) => true,
-            (ObjectType::Struct(type_), ObjectType::Struct(other_type)) => {
-                type_.address == other_type.address
-                    && type_.module == other_type.module
-                    && type_.name == other_type.name
-                    && (type_.type_params.is_empty() || type_.type_params == other_type.type_params)
-            }
-            _ => false,
-        }
-    }
-
     pub fn get_dynamic_fields(
         &self,
         owner: ObjectID,
@@ -2205,6 +2681,14 @@ impl AuthorityState {
             .ok_or_else(|| anyhow!("Latest checkpoint sequence number not found"))
     }
 
+    /// Receives the sequence number of the highest executed checkpoint each time it changes.
+    pub fn subscribe_latest_checkpoint_sequence_number(
+        &self,
+    ) -> watch::Receiver<Option<CheckpointSequenceNumber>> {
+        self.checkpoint_store
+            .subscribe_highest_executed_checkpoint()
+    }
+
     pub fn get_checkpoint_summary_by_sequence_number(
         &self,
         sequence_number: CheckpointSequenceNumber,
@@ -2272,6 +2756,161 @@ impl AuthorityState {
         Ok(self.get_indexes()?.get_timestamp_ms(digest)?)
     }
 
+    /// Returns the net changes that at most `limit` transactions made to the balances of the coins
+    /// owned by `owner`, only of coins of `coin_type` if given, in the order the transactions
+    /// were executed in, starting from the transaction `cursor`.
+    pub async fn get_coin_balance_changes(
+        &self,
+        owner: SuiAddress,
+        coin_type: Option<String>,
+        cursor: Option<TransactionDigest>,
+        limit: usize,
+        descending: bool,
+    ) -> Result<Vec<(TransactionDigest, Vec<BalanceChange>)>, anyhow::Error> {
+        let es = self.get_event_store().ok_or(SuiError::NoEventStore)?;
+        let owner = Owner::AddressOwner(owner);
+        let coin_type = coin_type
+            .map(|coin_type| parse_sui_struct_tag(&coin_type))
+            .transpose()?
+            .map(|coin_type| coin_type.to_string());
+
+        let (mut tx_num, mut event_num) = if let Some(cursor) = cursor {
+            let tx_seq = self
+                .get_indexes()?
+                .get_transaction_seq(&cursor)?
+                .ok_or_else(|| anyhow!("Transaction [{:?}] not found.", cursor))?;
+            (tx_seq as i64, if descending { i64::MAX } else { 0 })
+        } else if descending {
+            (i64::MAX, i64::MAX)
+        } else {
+            (0, 0)
+        };
+
+        // A transaction can change several balances, so events are read until they are known to
+        // hold all the changes of `limit` transactions.
+        let mut changes: Vec<(TransactionDigest, Vec<BalanceChange>)> = vec![];
+        loop {
+            let stored_events = es
+                .coin_balance_changes_by_owner(
+                    &owner,
+                    coin_type.as_deref(),
+                    tx_num,
+                    event_num,
+                    limit + 1,
+                    descending,
+                )
+                .await?;
+            let exhausted = stored_events.len() <= limit;
+            if let Some(last) = stored_events.last() {
+                // Events are read from the cursor on, inclusive, so the next read starts past the
+                // last event read.
+                let (last_tx_num, last_event_num) = last.position();
+                tx_num = last_tx_num;
+                event_num = if descending {
+                    last_event_num - 1
+                } else {
+                    last_event_num + 1
+                };
+            }
+            for (_, event) in StoredEvent::into_event_envelopes(stored_events)? {
+                let (coin_type, amount) = match event.event {
+                    SuiEvent::CoinBalanceChange {
+                        coin_type, amount, ..
+                    } => (coin_type, amount),
+                    _ => continue,
+                };
+                if changes.last().map(|(digest, _)| digest) != Some(&event.tx_digest) {
+                    if changes.len() == limit {
+                        return Ok(changes);
+                    }
+                    changes.push((event.tx_digest, vec![]));
+                }
+                let (_, tx_changes) = changes.last_mut().unwrap();
+                match tx_changes
+                    .iter_mut()
+                    .find(|change| change.coin_type == coin_type)
+                {
+                    Some(change) => change.amount += amount,
+                    None => tx_changes.push(BalanceChange {
+                        tx_digest: event.tx_digest,
+                        timestamp_ms: event.timestamp,
+                        coin_type,
+                        amount,
+                    }),
+                }
+            }
+            if exhausted {
+                return Ok(changes);
+            }
+        }
+    }
+
+    /// The checkpoint to start recording the checkpoints of transactions in the event store from.
+    pub(crate) async fn get_event_checkpoints_start(
+        &self,
+    ) -> Result<CheckpointSequenceNumber, anyhow::Error> {
+        let es = self.get_event_store().ok_or(SuiError::NoEventStore)?;
+        if let Some(highest_recorded) = es.highest_recorded_checkpoint().await? {
+            return Ok(highest_recorded);
+        }
+        Ok(self
+            .get_checkpoint_store()
+            .get_highest_executed_checkpoint_seq_number()?
+            .unwrap_or_default())
+    }
+
+    /// Records the checkpoint of each transaction of the executed checkpoints from `next` on in
+    /// the event store, and returns the checkpoint to continue from.
+    pub(crate) async fn record_event_checkpoints(
+        &self,
+        mut next: CheckpointSequenceNumber,
+    ) -> Result<CheckpointSequenceNumber, anyhow::Error> {
+        let es = self.get_event_store().ok_or(SuiError::NoEventStore)?;
+        let indexes = self.get_indexes()?;
+        let highest_executed = match self
+            .get_checkpoint_store()
+            .get_highest_executed_checkpoint_seq_number()?
+        {
+            Some(highest_executed) => highest_executed,
+            None => return Ok(next),
+        };
+
+        while next <= highest_executed {
+            let checkpoint = match self
+                .get_checkpoint_store()
+                .get_checkpoint_by_sequence_number(next)?
+            {
+                Some(checkpoint) => checkpoint,
+                None => {
+                    // Pruned, or never synced by this node.
+                    next += 1;
+                    continue;
+                }
+            };
+            let contents = match self
+                .get_checkpoint_store()
+                .get_checkpoint_contents(&checkpoint.into_inner().content_digest())?
+            {
+                Some(contents) => contents,
+                None => {
+                    next += 1;
+                    continue;
+                }
+            };
+            let mut checkpoints = Vec::with_capacity(contents.size());
+            for digests in contents.iter() {
+                match indexes.get_transaction_seq(&digests.transaction)? {
+                    Some(seq) => checkpoints.push((seq, next)),
+                    // Not indexed yet, so the checkpoint is retried later.
+                    None => return Ok(next),
+                }
+            }
+            es.add_transaction_checkpoints(&checkpoints).await?;
+            next += 1;
+        }
+        Ok(next)
+    }
+
     /// Returns a full handle to the event store, including inserts... so be careful!
     fn get_event_store(&self) -> Option<Arc<EventStoreType>> {
         self.event_handler
@@ -2345,6 +2984,20 @@ impl AuthorityState {
                 es.event_iterator(start_time, end_time, tx_num, event_num, limit, descending)
                     .await?
             }
+            EventQuery::Checkpoint {
+                start_checkpoint,
+                end_checkpoint,
+            } => {
+                es.events_by_checkpoint(
+                    start_checkpoint,
+                    end_checkpoint,
+                    tx_num,
+                    event_num,
+                    limit,
+                    descending,
+                )
+                .await?
+            }
             EventQuery::EventType(event_type) => {
                 es.events_by_type(event_type, tx_num, event_num, limit, descending)
                     .await?
@@ -3109,20 +3762,91 @@ impl AuthorityState {
         Ok(new_epoch_store)
     }
 
+    /// Moves a node that serves reads from a secondary instance of another node's databases to
+    /// the epoch that node has reconfigured to. Unlike [`Self::reconfigure`], nothing is written.
+    pub async fn switch_secondary_epoch_store(&self, new_epoch_store: Arc<AuthorityPerEpochStore>) {
+        info!(
+            new_epoch = ?new_epoch_store.epoch(),
+            "switching to the epoch store of the primary"
+        );
+        let cur_epoch_store = self.epoch_store.swap(new_epoch_store);
+        cur_epoch_store.epoch_terminated().await;
+    }
+
     pub fn checkpoint_perpetual_db(&self) -> SuiResult<PathBuf> {
         let _metrics_guard = self.metrics.state_snapshot_checkpoint_latency.start_timer();
         let checkpoint_path = PathBuf::from(format!(
             "perpetual_store_snapshot_epoch_{}",
             self.db().perpetual_tables.get_recovery_epoch_at_restart()?
         ));
+        self.checkpoint_perpetual_db_to(&checkpoint_path)
+    }
+
+    /// Creates a RocksDB checkpoint of the perpetual tables at `path`, a consistent copy of the
+    /// database made of hard links to its files, while the node keeps writing to it. A relative
+    /// `path` is resolved against the directory of the database.
+    pub fn checkpoint_perpetual_db_to(&self, path: &Path) -> SuiResult<PathBuf> {
         self.database
             .perpetual_tables
             .objects
             .rocksdb
-            .checkpoint(&checkpoint_path)
+            .checkpoint(path)
             .map_err(SuiError::StorageError)
     }
 
+    /// The databases the node restarts from, in the order they must be backed up: the data of
+    /// each one may be ahead of that of the ones before it, which the node catches up with when
+    /// it starts, but not behind. The current epoch is only that of the backup if the execution
+    /// lock is held, see [`AuthorityStore::execution_lock_for_backup`].
+    pub fn rocksdbs_to_back_up(&self) -> Vec<Arc<RocksDB>> {
+        let epoch_store = self.load_epoch_store_one_call_per_task();
+        let mut dbs = vec![
+            self.committee_store.rocksdb().clone(),
+            self.checkpoint_store.rocksdb().clone(),
+        ];
+        dbs.extend(epoch_store.rocksdbs());
+        dbs.push(self.database.perpetual_tables.objects.rocksdb.clone());
+        // Indexes are written after the perpetual tables, when a transaction is committed.
+        if let Some(indexes) = &self.indexes {
+            dbs.push(indexes.rocksdb().clone());
+        }
+        dbs
+    }
+
+    /// Stops the execution driver from picking up more certificates, and waits for the
+    /// executions already in progress to commit their outputs. No further execution can start
+    /// while the returned guard is held.
+    pub async fn shutdown_execution(&self) -> ExecutionLockWriteGuard<'_> {
+        if let Some(tx_execution_shutdown) = self.tx_execution_shutdown.lock().take() {
+            // The execution driver may have exited already.
+            let _ = tx_execution_shutdown.send(());
+        }
+        self.database.execution_lock_for_reconfiguration().await
+    }
+
+    /// Flushes the memtables of the perpetual tables to disk.
+    pub fn flush_db(&self) -> SuiResult {
+        Ok(self.database.perpetual_tables.flush()?)
+    }
+
+    /// Compacts the whole of table `table_name` of `db_name`, the struct holding the table as
+    /// named in the DB options file, blocking until it is done. `AuthorityEpochTables` are the
+    /// tables of the current epoch.
+    pub fn compact_table(&self, db_name: &str, table_name: &str) -> SuiResult {
+        match db_name {
+            "AuthorityPerpetualTables" => {
+                Ok(self.database.perpetual_tables.compact_table(table_name)?)
+            }
+            "AuthorityEpochTables" => Ok(self
+                .load_epoch_store_one_call_per_task()
+                .compact_table(table_name)?),
+            "IndexStoreTables" => self.get_indexes()?.compact_table(table_name),
+            _ => Err(SuiError::GenericStorageError(format!(
+                "No such tables: {db_name}"
+            ))),
+        }
+    }
+
     #[cfg(test)]
     pub(crate) fn shutdown_execution_for_test(&self) {
         self.tx_execution_shutdown
@@ -30,7 +30,7 @@ use sui_types::messages::{
 };
 use sui_types::signature::GenericSignature;
 use tracing::{debug, info, trace, warn};
-use typed_store::rocks::{DBBatch, DBMap, DBOptions, MetricConf, TypedStoreError};
+use typed_store::rocks::{DBBatch, DBMap, DBOptions, MetricConf, RocksDB, TypedStoreError};
 use typed_store::traits::{TableSummary, TypedStoreDebug};
 
 use crate::authority::authority_notify_read::NotifyRead;
@@ -106,6 +106,9 @@ pub struct AuthorityPerEpochStore {
     // needed for re-opening epoch db.
     parent_path: PathBuf,
     db_options: Option<Options>,
+    /// Set if the tables are a secondary instance of another node's tables, opened with
+    /// [`AuthorityPerEpochStore::new_secondary`].
+    is_secondary: bool,
 
     /// In-memory cache of the content from the reconfig_state db table.
     reconfig_state_mem: RwLock<ReconfigState>,
@@ -387,16 +390,89 @@ impl AuthorityPerEpochStore {
         store: Arc<AuthorityStore>,
         cache_metrics: Arc<ResolverMetrics>,
     ) -> Arc<Self> {
-        let current_time = Instant::now();
         let epoch_id = committee.epoch;
         let tables = AuthorityEpochTables::open(epoch_id, parent_path, db_options.clone());
+        let wal_path = AuthorityEpochTables::path(epoch_id, parent_path).join("recovery_log");
+        let wal = Arc::new(DBWriteAheadLog::new(wal_path));
+        Self::new_with_tables(
+            name,
+            committee,
+            tables,
+            wal,
+            parent_path,
+            db_options,
+            false,
+            metrics,
+            epoch_start_configuration,
+            store,
+            cache_metrics,
+        )
+    }
+
+    /// Opens the epoch store of another node as a secondary instance, for serving reads only.
+    /// The files of the secondary instance are kept under `secondary_parent_path`.
+    pub fn new_secondary(
+        name: AuthorityName,
+        committee: Committee,
+        parent_path: &Path,
+        secondary_parent_path: &Path,
+        metrics: Arc<EpochMetrics>,
+        epoch_start_configuration: EpochStartConfiguration,
+        store: Arc<AuthorityStore>,
+        cache_metrics: Arc<ResolverMetrics>,
+    ) -> Arc<Self> {
+        let epoch_id = committee.epoch;
+        let secondary_path = AuthorityEpochTables::path(epoch_id, secondary_parent_path);
+        let tables = AuthorityEpochTables::open_tables_secondary(
+            AuthorityEpochTables::path(epoch_id, parent_path),
+            secondary_path.clone(),
+            MetricConf::with_db_name("epoch"),
+            None,
+        );
+        let wal = Arc::new(DBWriteAheadLog::open_secondary(
+            AuthorityEpochTables::path(epoch_id, parent_path).join("recovery_log"),
+            secondary_path.join("recovery_log"),
+        ));
+        Self::new_with_tables(
+            name,
+            committee,
+            tables,
+            wal,
+            parent_path,
+            None,
+            true,
+            metrics,
+            epoch_start_configuration,
+            store,
+            cache_metrics,
+        )
+    }
+
+    fn new_with_tables(
+        name: AuthorityName,
+        committee: Committee,
+        tables: AuthorityEpochTables,
+        wal: Arc<
+            DBWriteAheadLog<
+                TrustedExecutableTransaction,
+                (InnerTemporaryStore, TransactionEffects),
+            >,
+        >,
+        parent_path: &Path,
+        db_options: Option<Options>,
+        is_secondary: bool,
+        metrics: Arc<EpochMetrics>,
+        epoch_start_configuration: EpochStartConfiguration,
+        store: Arc<AuthorityStore>,
+        cache_metrics: Arc<ResolverMetrics>,
+    ) -> Arc<Self> {
+        let current_time = Instant::now();
+        let epoch_id = committee.epoch;
         let end_of_publish =
             StakeAggregator::from_iter(Arc::new(committee.clone()), tables.end_of_publish.iter());
         let reconfig_state = tables
             .load_reconfig_state()
             .expect("Load reconfig state at initialization cannot fail");
-        let wal_path = AuthorityEpochTables::path(epoch_id, parent_path).join("recovery_log");
-        let wal = Arc::new(DBWriteAheadLog::new(wal_path));
         let epoch_alive_notify = NotifyOnce::new();
         let pending_consensus_transactions = tables.get_all_pending_consensus_transactions();
         let pending_consensus_certificates: HashSet<_> = pending_consensus_transactions
@@ -425,6 +501,7 @@ impl AuthorityPerEpochStore {
             tables,
             parent_path: parent_path.to_path_buf(),
             db_options,
+            is_secondary,
             reconfig_state_mem: RwLock::new(reconfig_state),
             epoch_alive_notify,
             epoch_alive: tokio::sync::RwLock::new(true),
@@ -441,6 +518,12 @@ impl AuthorityPerEpochStore {
         })
     }
 
+    /// Makes the writes of the primary instance visible, when opened with
+    /// [`Self::new_secondary`].
+    pub fn try_catch_up_with_primary(&self) -> SuiResult {
+        Ok(self.tables.transactions.try_catch_up_with_primary()?)
+    }
+
     pub fn get_parent_path(&self) -> PathBuf {
         self.parent_path.clone()
     }
@@ -497,6 +580,20 @@ impl AuthorityPerEpochStore {
         self.committee.epoch
     }
 
+    /// Compacts the whole of table `table_name` of the epoch, blocking until it is done.
+    pub fn compact_table(&self, table_name: &str) -> Result<(), TypedStoreError> {
+        self.tables.transactions.rocksdb.compact_cf(table_name)
+    }
+
+    /// The databases holding the tables and the write-ahead log of the epoch, e.g. to back them
+    /// up. The log lives in a directory of the tables.
+    pub fn rocksdbs(&self) -> [Arc<RocksDB>; 2] {
+        [
+            self.tables.transactions.rocksdb.clone(),
+            self.wal.rocksdb().clone(),
+        ]
+    }
+
     pub fn get_state_hash_for_checkpoint(
         &self,
         checkpoint: &CheckpointSequenceNumber,
@@ -728,6 +825,10 @@ impl AuthorityPerEpochStore {
 
     /// Gets all pending certificates. Used during recovery.
     pub fn all_pending_execution(&self) -> SuiResult<Vec<VerifiedExecutableTransaction>> {
+        if self.is_secondary {
+            // These are left to the node that owns the tables to execute.
+            return Ok(vec![]);
+        }
         Ok(self
             .tables
             .pending_execution
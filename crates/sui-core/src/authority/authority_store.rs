@@ -4,17 +4,19 @@
 use super::authority_notify_read::NotifyRead;
 use super::{authority_store_tables::AuthorityPerpetualTables, *};
 use crate::authority::authority_per_epoch_store::AuthorityPerEpochStore;
-use crate::authority::authority_store_types::StoreObjectPair;
+use crate::authority::authority_store_backend::{RocksDBBackend, StorageBackend};
+use crate::authority::authority_store_types::{get_store_object_pair, StoreData, StoreObjectPair};
 use either::Either;
 use move_core_types::resolver::ModuleResolver;
 use once_cell::sync::OnceCell;
 use rocksdb::Options;
 use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
-use std::collections::BTreeSet;
+use std::collections::{BTreeMap, BTreeSet};
 use std::iter;
 use std::path::Path;
 use std::sync::Arc;
+use sui_storage::commit_log::CommitLogRecord;
 use sui_storage::mutex_table::{LockGuard, MutexTable};
 use sui_types::accumulator::Accumulator;
 use sui_types::digests::TransactionEventsDigest;
@@ -26,6 +28,7 @@ use sui_types::storage::{
 };
 use sui_types::sui_system_state::get_sui_system_state;
 use sui_types::{base_types::SequenceNumber, fp_bail, fp_ensure, storage::ParentSync};
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
 use tokio::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
 use tracing::{debug, info, trace};
 use typed_store::rocks::{DBBatch, TypedStoreError};
@@ -34,6 +37,14 @@ use typed_store::traits::Map;
 const NUM_SHARDS: usize = 4096;
 const SHARD_SIZE: usize = 128;
 
+/// Move objects are stored with their contents in the `objects` table unless configured
+/// otherwise. The unit tests store all of them in the `indirect_move_objects` table instead, to
+/// cover reading them back from there.
+const DEFAULT_INDIRECT_OBJECTS_THRESHOLD: usize = if cfg!(test) { 0 } else { usize::MAX };
+
+/// The number of objects rewritten per batch when migrating large objects.
+const MIGRATE_OBJECTS_BATCH_SIZE: usize = 10_000;
+
 /// ALL_OBJ_VER determines whether we want to store all past
 /// versions of every object in the store. Authority doesn't store
 /// them, but other entities such as replicas will.
@@ -55,6 +66,13 @@ pub struct AuthorityStore {
     /// Reconfiguration acquires write lock, changes the epoch and revert all transactions
     /// from previous epoch that are executed but did not make into checkpoint.
     execution_lock: RwLock<EpochId>,
+
+    /// Receives a record of each commit and revert, once subscribed to.
+    commit_log: OnceCell<UnboundedSender<CommitLogRecord>>,
+
+    /// Move objects with contents larger than this many bytes are written to the
+    /// `indirect_move_objects` table, keeping only their metadata in the `objects` table.
+    indirect_objects_threshold: usize,
 }
 
 pub type ExecutionLockReadGuard<'a> = RwLockReadGuard<'a, EpochId>;
@@ -63,13 +81,41 @@ pub type ExecutionLockWriteGuard<'a> = RwLockWriteGuard<'a, EpochId>;
 impl AuthorityStore {
     /// Open an authority store by directory path.
     /// If the store is empty, initialize it using genesis.
+    ///
+    /// Move objects with contents larger than `indirect_objects_threshold` bytes, genesis objects
+    /// included, are written to the `indirect_move_objects` table.
     pub async fn open(
         path: &Path,
         db_options: Option<Options>,
         genesis: &Genesis,
         committee_store: &Arc<CommitteeStore>,
+        indirect_objects_threshold: usize,
+    ) -> SuiResult<Self> {
+        Self::open_with_backend(
+            path,
+            db_options,
+            genesis,
+            committee_store,
+            indirect_objects_threshold,
+            &RocksDBBackend,
+        )
+        .await
+    }
+
+    /// Opens an authority store whose tables are kept by `backend`.
+    pub async fn open_with_backend(
+        path: &Path,
+        db_options: Option<Options>,
+        genesis: &Genesis,
+        committee_store: &Arc<CommitteeStore>,
+        indirect_objects_threshold: usize,
+        backend: &dyn StorageBackend,
     ) -> SuiResult<Self> {
-        let perpetual_tables = Arc::new(AuthorityPerpetualTables::open(path, db_options.clone()));
+        info!(
+            "Opening authority store with the {} backend",
+            backend.name()
+        );
+        let perpetual_tables = Arc::new(backend.open_perpetual_tables(path, db_options)?);
         if perpetual_tables.database_is_empty()? {
             let epoch_start_configuration = EpochStartConfiguration::new(
                 genesis.sui_system_object().into_epoch_start_state(),
@@ -83,7 +129,129 @@ impl AuthorityStore {
         let committee = committee_store
             .get_committee(&cur_epoch)?
             .expect("Committee of the current epoch must exist");
-        Self::open_inner(genesis, perpetual_tables, committee).await
+        Self::open_inner(
+            genesis,
+            perpetual_tables,
+            committee,
+            indirect_objects_threshold,
+        )
+        .await
+    }
+
+    /// Opens the store of another node as a secondary instance, for serving reads only. The
+    /// database must have been initialized by that node already.
+    pub fn open_secondary(path: &Path, secondary_path: &Path) -> SuiResult<Self> {
+        let perpetual_tables = Arc::new(AuthorityPerpetualTables::open_secondary(
+            path,
+            secondary_path,
+        ));
+        if perpetual_tables.database_is_empty()? {
+            return Err(SuiError::GenericStorageError(format!(
+                "No database to open read-only at {}",
+                path.display()
+            )));
+        }
+        let epoch = perpetual_tables.get_recovery_epoch_at_restart()?;
+        Ok(Self {
+            mutex_table: MutexTable::new(NUM_SHARDS, SHARD_SIZE),
+            perpetual_tables,
+            executed_effects_notify_read: NotifyRead::new(),
+            root_state_notify_read:
+                NotifyRead::<EpochId, (CheckpointSequenceNumber, Accumulator)>::new(),
+            execution_lock: RwLock::new(epoch),
+            commit_log: OnceCell::new(),
+            indirect_objects_threshold: DEFAULT_INDIRECT_OBJECTS_THRESHOLD,
+        })
+    }
+
+    /// Opens a store that was initialized by a node already, to write to it outside of the node,
+    /// e.g. to replay a commit log into a restored database.
+    pub fn open_existing(path: &Path, db_options: Option<Options>) -> SuiResult<Self> {
+        let perpetual_tables = Arc::new(RocksDBBackend.open_perpetual_tables(path, db_options)?);
+        if perpetual_tables.database_is_empty()? {
+            return Err(SuiError::GenericStorageError(format!(
+                "No database to open at {}",
+                path.display()
+            )));
+        }
+        let epoch = perpetual_tables.get_recovery_epoch_at_restart()?;
+        Ok(Self {
+            mutex_table: MutexTable::new(NUM_SHARDS, SHARD_SIZE),
+            perpetual_tables,
+            executed_effects_notify_read: NotifyRead::new(),
+            root_state_notify_read:
+                NotifyRead::<EpochId, (CheckpointSequenceNumber, Accumulator)>::new(),
+            execution_lock: RwLock::new(epoch),
+            commit_log: OnceCell::new(),
+            indirect_objects_threshold: DEFAULT_INDIRECT_OBJECTS_THRESHOLD,
+        })
+    }
+
+    /// Opens the store at `path` to restore a formal snapshot into, with
+    /// [`Self::insert_restored_objects`] and [`Self::finish_restore`], unless the store was
+    /// initialized already, by genesis or by a restore that finished. Until it finishes, a
+    /// restore only wrote objects, which are written again when the restore starts over.
+    pub async fn open_for_restore(
+        path: &Path,
+        db_options: Option<Options>,
+        genesis: &Genesis,
+        committee_store: &Arc<CommitteeStore>,
+        indirect_objects_threshold: usize,
+    ) -> SuiResult<Self> {
+        let perpetual_tables = Arc::new(RocksDBBackend.open_perpetual_tables(path, db_options)?);
+        if perpetual_tables
+            .epoch_start_configuration
+            .get(&())?
+            .is_some()
+        {
+            let cur_epoch = perpetual_tables.get_recovery_epoch_at_restart()?;
+            let committee = committee_store
+                .get_committee(&cur_epoch)?
+                .expect("Committee of the current epoch must exist");
+            return Self::open_inner(
+                genesis,
+                perpetual_tables,
+                committee,
+                indirect_objects_threshold,
+            )
+            .await;
+        }
+        Ok(Self {
+            mutex_table: MutexTable::new(NUM_SHARDS, SHARD_SIZE),
+            perpetual_tables,
+            executed_effects_notify_read: NotifyRead::new(),
+            root_state_notify_read:
+                NotifyRead::<EpochId, (CheckpointSequenceNumber, Accumulator)>::new(),
+            execution_lock: RwLock::new(0),
+            commit_log: OnceCell::new(),
+            indirect_objects_threshold,
+        })
+    }
+
+    /// Writes live objects restored from a formal snapshot, see [`Self::open_for_restore`].
+    pub async fn insert_restored_objects(&self, objects: &[Object]) -> SuiResult {
+        self.bulk_object_insert(&objects.iter().collect::<Vec<_>>())
+            .await
+    }
+
+    /// Finishes restoring the state at the end of `epoch`, whose last checkpoint is
+    /// `last_checkpoint_of_epoch`, and whose live objects accumulate to `root_state_hash`. The
+    /// store starts from the next epoch, with `epoch_start_configuration`.
+    pub async fn finish_restore(
+        &self,
+        epoch: EpochId,
+        last_checkpoint_of_epoch: CheckpointSequenceNumber,
+        root_state_hash: Accumulator,
+        epoch_start_configuration: &EpochStartConfiguration,
+    ) -> SuiResult {
+        self.perpetual_tables
+            .root_state_hash_by_epoch
+            .insert(&epoch, &(last_checkpoint_of_epoch, root_state_hash))?;
+        // Written last, since it marks the store as initialized.
+        self.set_epoch_start_configuration(epoch_start_configuration)
+            .await?;
+        *self.execution_lock.write().await = epoch_start_configuration.epoch();
+        Ok(())
     }
 
     pub async fn open_with_committee_for_testing(
@@ -91,18 +259,42 @@ impl AuthorityStore {
         db_options: Option<Options>,
         committee: &Committee,
         genesis: &Genesis,
+    ) -> SuiResult<Self> {
+        Self::open_with_committee_and_backend_for_testing(
+            path,
+            db_options,
+            committee,
+            genesis,
+            &RocksDBBackend,
+        )
+        .await
+    }
+
+    pub async fn open_with_committee_and_backend_for_testing(
+        path: &Path,
+        db_options: Option<Options>,
+        committee: &Committee,
+        genesis: &Genesis,
+        backend: &dyn StorageBackend,
     ) -> SuiResult<Self> {
         // TODO: Since we always start at genesis, the committee should be technically the same
         // as the genesis committee.
         assert_eq!(committee.epoch, 0);
-        let perpetual_tables = Arc::new(AuthorityPerpetualTables::open(path, db_options.clone()));
-        Self::open_inner(genesis, perpetual_tables, committee.clone()).await
+        let perpetual_tables = Arc::new(backend.open_perpetual_tables(path, db_options)?);
+        Self::open_inner(
+            genesis,
+            perpetual_tables,
+            committee.clone(),
+            DEFAULT_INDIRECT_OBJECTS_THRESHOLD,
+        )
+        .await
     }
 
     async fn open_inner(
         genesis: &Genesis,
         perpetual_tables: Arc<AuthorityPerpetualTables>,
         committee: Committee,
+        indirect_objects_threshold: usize,
     ) -> SuiResult<Self> {
         let epoch = committee.epoch;
 
@@ -113,6 +305,8 @@ impl AuthorityStore {
             root_state_notify_read:
                 NotifyRead::<EpochId, (CheckpointSequenceNumber, Accumulator)>::new(),
             execution_lock: RwLock::new(epoch),
+            commit_log: OnceCell::new(),
+            indirect_objects_threshold,
         };
         // Only initialize an empty database.
         if store
@@ -155,6 +349,11 @@ impl AuthorityStore {
         self.perpetual_tables.get_recovery_epoch_at_restart()
     }
 
+    /// Makes the writes of the primary instance visible, when opened with [`Self::open_secondary`].
+    pub fn try_catch_up_with_primary(&self) -> SuiResult {
+        Ok(self.perpetual_tables.try_catch_up_with_primary()?)
+    }
+
     pub fn get_effects(
         &self,
         effects_digest: &TransactionEffectsDigest,
@@ -436,6 +635,12 @@ impl AuthorityStore {
         }
     }
 
+    /// Acquires the execution lock whatever the epoch, so that reconfiguration cannot start while
+    /// the guard is held, e.g. while the databases are being backed up.
+    pub async fn execution_lock_for_backup(&self) -> ExecutionLockReadGuard {
+        self.execution_lock.read().await
+    }
+
     pub async fn execution_lock_for_reconfiguration(&self) -> ExecutionLockWriteGuard {
         self.execution_lock.write().await
     }
@@ -551,7 +756,8 @@ impl AuthorityStore {
         let mut write_batch = self.perpetual_tables.objects.batch();
 
         // Insert object
-        let StoreObjectPair(store_object, indirect_object) = object.clone().into();
+        let StoreObjectPair(store_object, indirect_object) =
+            get_store_object_pair(object.clone(), self.indirect_objects_threshold);
         write_batch = write_batch.insert_batch(
             &self.perpetual_tables.objects,
             std::iter::once((ObjectKey::from(object_ref), store_object)),
@@ -582,7 +788,8 @@ impl AuthorityStore {
         Ok(())
     }
 
-    /// This function should only be used for initializing genesis and should remain private.
+    /// This function should only be used for initializing genesis, or restoring a formal snapshot,
+    /// and should remain private.
     async fn bulk_object_insert(&self, objects: &[&Object]) -> SuiResult<()> {
         let mut batch = self.perpetual_tables.objects.batch();
         let ref_and_objects: Vec<_> = objects
@@ -596,14 +803,15 @@ impl AuthorityStore {
                 ref_and_objects.iter().map(|(oref, o)| {
                     (
                         ObjectKey::from(oref),
-                        StoreObjectPair::from((**o).clone()).0,
+                        get_store_object_pair((**o).clone(), self.indirect_objects_threshold).0,
                     )
                 }),
             )?
             .insert_batch(
                 &self.perpetual_tables.indirect_move_objects,
                 ref_and_objects.iter().filter_map(|(_, o)| {
-                    let StoreObjectPair(_, indirect_object) = (**o).clone().into();
+                    let StoreObjectPair(_, indirect_object) =
+                        get_store_object_pair((**o).clone(), self.indirect_objects_threshold);
                     indirect_object.map(|obj| (obj.digest(), obj))
                 }),
             )?
@@ -645,6 +853,11 @@ impl AuthorityStore {
         Ok(self.perpetual_tables.epoch_start_configuration.get(&())?)
     }
 
+    /// The size of the SST files of each perpetual table, by table name.
+    pub fn perpetual_table_sizes(&self) -> SuiResult<BTreeMap<String, u64>> {
+        Ok(self.perpetual_tables.table_sizes()?)
+    }
+
     /// Updates the state resulting from the execution of a certificate.
     ///
     /// Internally it checks that all locks for active inputs are at the correct
@@ -655,6 +868,8 @@ impl AuthorityStore {
         transaction: &VerifiedTransaction,
         effects: &TransactionEffects,
     ) -> SuiResult {
+        let commit_log_store = self.commit_log.get().map(|_| inner_temporary_store.clone());
+
         // Extract the new state from the execution
         // TODO: events are already stored in the TxDigest -> TransactionEffects store. Is that enough?
         let mut write_batch = self.perpetual_tables.transactions.batch();
@@ -691,6 +906,16 @@ impl AuthorityStore {
         // Commit.
         write_batch.write()?;
 
+        // Logged before the transactions depending on this one can be executed, so that the log
+        // is in an order the commits can be replayed in.
+        if let Some(store) = commit_log_store {
+            self.log_commit(CommitLogRecord::Commit {
+                transaction: transaction.clone().into_inner(),
+                effects: effects.clone(),
+                store,
+            });
+        }
+
         self.executed_effects_notify_read
             .notify(transaction_digest, effects);
 
@@ -753,7 +978,8 @@ impl AuthorityStore {
             .iter()
             .map(|(_, (obj_ref, new_object, _))| {
                 debug!(?obj_ref, "writing object");
-                let StoreObjectPair(store_object, indirect_object) = new_object.clone().into();
+                let StoreObjectPair(store_object, indirect_object) =
+                    get_store_object_pair(new_object.clone(), self.indirect_objects_threshold);
                 (
                     (ObjectKey::from(obj_ref), store_object),
                     indirect_object.map(|obj| (obj.digest(), obj)),
@@ -1151,10 +1377,107 @@ impl AuthorityStore {
         )?;
 
         write_batch.write()?;
+        self.log_commit(CommitLogRecord::Revert(*tx_digest));
 
         Ok(())
     }
 
+    /// Moves the contents of the Move objects in the `objects` table that are larger than
+    /// `indirect_objects_threshold` bytes to the `indirect_move_objects` table, returning the
+    /// number of objects moved. Must not run while a node writes to the store.
+    pub fn migrate_large_objects(&self, indirect_objects_threshold: usize) -> SuiResult<u64> {
+        let tables = &self.perpetual_tables;
+        let mut num_migrated = 0;
+        let mut iter = tables.objects.iter();
+        loop {
+            let mut batch = tables.objects.batch();
+            let mut num_in_batch = 0;
+            for (key, store_object) in iter.by_ref() {
+                let StoreData::Move(move_obj) = &store_object.data else {
+                    continue;
+                };
+                if move_obj.contents().len() <= indirect_objects_threshold {
+                    continue;
+                }
+                let object: Object = StoreObjectPair(store_object, None).try_into()?;
+                let StoreObjectPair(store_object, indirect_object) =
+                    get_store_object_pair(object, indirect_objects_threshold);
+                batch = batch
+                    .insert_batch(&tables.objects, [(key, store_object)])?
+                    .merge_batch(
+                        &tables.indirect_move_objects,
+                        indirect_object.map(|obj| (obj.digest(), obj)),
+                    )?;
+                num_in_batch += 1;
+                if num_in_batch == MIGRATE_OBJECTS_BATCH_SIZE {
+                    break;
+                }
+            }
+            if num_in_batch == 0 {
+                return Ok(num_migrated);
+            }
+            batch.write()?;
+            num_migrated += num_in_batch as u64;
+        }
+    }
+
+    /// Returns the records of the commits and reverts made to the store from now on, in an
+    /// order they can be replayed in. The store can only be subscribed to once.
+    pub fn subscribe_commit_log(&self) -> SuiResult<UnboundedReceiver<CommitLogRecord>> {
+        let (sender, receiver) = unbounded_channel();
+        self.commit_log.set(sender).map_err(|_| {
+            SuiError::GenericStorageError("The commit log is already subscribed to".to_string())
+        })?;
+        Ok(receiver)
+    }
+
+    fn log_commit(&self, record: CommitLogRecord) {
+        if let Some(sender) = self.commit_log.get() {
+            // The receiver is only dropped when the node shuts down.
+            let _ = sender.send(record);
+        }
+    }
+
+    /// Applies a record of a commit log to the store, returning whether it changed the store.
+    /// Commits of transactions the store has executed already are skipped. Fails without
+    /// writing anything if a commit read object versions the store does not have, which happens
+    /// when the records between the store and this one are missing from the log.
+    pub async fn replay_commit_log_record(&self, record: CommitLogRecord) -> SuiResult<bool> {
+        match record {
+            CommitLogRecord::Commit {
+                transaction,
+                effects,
+                store,
+            } => {
+                let transaction = VerifiedTransaction::new_unchecked(transaction);
+                if self.is_tx_already_executed(transaction.digest())? {
+                    return Ok(false);
+                }
+                for object in store.objects.values() {
+                    let key = ObjectKey(object.id(), object.version());
+                    if self.perpetual_tables.objects.get(&key)?.is_none() {
+                        return Err(SuiError::GenericStorageError(format!(
+                            "Version {} of object {} read by transaction {} is missing, the \
+                            commit log has a gap",
+                            object.version(),
+                            object.id(),
+                            transaction.digest()
+                        )));
+                    }
+                }
+                self.update_state(store, &transaction, &effects).await?;
+                Ok(true)
+            }
+            CommitLogRecord::Revert(digest) => {
+                if !self.is_tx_already_executed(&digest)? {
+                    return Ok(false);
+                }
+                self.revert_state_update(&digest).await?;
+                Ok(true)
+            }
+        }
+    }
+
     /// Return the object with version less then or eq to the provided seq number.
     /// This is used by indexer to find the correct version of dynamic field child object.
     /// We do not store the version of the child object, but because of lamport timestamp,
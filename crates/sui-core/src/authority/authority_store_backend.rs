@@ -0,0 +1,128 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Backends keeping the tables of the authority store. [`AuthorityStore`] only reads and writes
+//! the tables through [`AuthorityPerpetualTables`], so where they are kept is up to the backend it
+//! is opened with: [`RocksDBBackend`] on disk by default, [`InMemoryBackend`] for tests.
+//!
+//! [`AuthorityStore`]: super::authority_store::AuthorityStore
+
+use crate::authority::authority_store_migrations::SchemaRegistry;
+use crate::authority::authority_store_tables::AuthorityPerpetualTables;
+use rocksdb::{Env, Options};
+use std::fmt::{Debug, Formatter};
+use std::path::Path;
+use sui_types::error::{SuiError, SuiResult};
+use typed_store::rocks::default_db_options;
+
+pub trait StorageBackend: Send + Sync + Debug {
+    /// The name of the backend, for logs.
+    fn name(&self) -> &'static str;
+
+    /// Opens the tables of the authority store at `path`, creating them if they do not exist.
+    /// `db_options` are the options the node is configured with, if any.
+    fn open_perpetual_tables(
+        &self,
+        path: &Path,
+        db_options: Option<Options>,
+    ) -> SuiResult<AuthorityPerpetualTables>;
+}
+
+/// Keeps the tables in a RocksDB database on disk, migrated to the current schema when opened.
+#[derive(Debug, Default)]
+pub struct RocksDBBackend;
+
+impl StorageBackend for RocksDBBackend {
+    fn name(&self) -> &'static str {
+        "rocksdb"
+    }
+
+    fn open_perpetual_tables(
+        &self,
+        path: &Path,
+        db_options: Option<Options>,
+    ) -> SuiResult<AuthorityPerpetualTables> {
+        SchemaRegistry::authority_store().migrate(&AuthorityPerpetualTables::path(path))?;
+        Ok(AuthorityPerpetualTables::open(path, db_options))
+    }
+}
+
+/// Keeps the tables in a RocksDB database in memory, which is lost when the backend is dropped.
+/// Nothing is written to `path`, which only tells the stores opened with the same backend apart:
+/// reopening a store with the same backend sees what was written before.
+pub struct InMemoryBackend {
+    env: Env,
+}
+
+impl InMemoryBackend {
+    pub fn new() -> SuiResult<Self> {
+        let env = Env::mem_env().map_err(|e| {
+            SuiError::GenericStorageError(format!("Cannot create in-memory environment: {e}"))
+        })?;
+        Ok(Self { env })
+    }
+}
+
+impl Debug for InMemoryBackend {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("InMemoryBackend").finish_non_exhaustive()
+    }
+}
+
+impl StorageBackend for InMemoryBackend {
+    fn name(&self) -> &'static str {
+        "in-memory"
+    }
+
+    fn open_perpetual_tables(
+        &self,
+        path: &Path,
+        db_options: Option<Options>,
+    ) -> SuiResult<AuthorityPerpetualTables> {
+        // A database in memory is always created at the current schema, so there is nothing to
+        // migrate.
+        let mut options = db_options.unwrap_or_else(|| default_db_options().options);
+        options.set_env(&self.env);
+        Ok(AuthorityPerpetualTables::open(path, Some(options)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sui_types::base_types::TransactionDigest;
+    use sui_types::digests::TransactionEffectsDigest;
+    use typed_store::traits::Map;
+
+    #[test]
+    fn test_in_memory_backend() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("store");
+        let backend = InMemoryBackend::new().unwrap();
+        let transaction = TransactionDigest::random();
+        let effects = TransactionEffectsDigest::random();
+
+        {
+            let tables = backend.open_perpetual_tables(&path, None).unwrap();
+            assert!(tables.database_is_empty().unwrap());
+            tables
+                .executed_effects
+                .insert(&transaction, &effects)
+                .unwrap();
+        }
+        assert!(!path.exists());
+
+        let tables = backend.open_perpetual_tables(&path, None).unwrap();
+        assert_eq!(
+            tables.executed_effects.get(&transaction).unwrap(),
+            Some(effects)
+        );
+
+        // Another backend does not share the tables.
+        let tables = InMemoryBackend::new()
+            .unwrap()
+            .open_perpetual_tables(&path, None)
+            .unwrap();
+        assert_eq!(tables.executed_effects.get(&transaction).unwrap(), None);
+    }
+}
@@ -0,0 +1,447 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Versioned schema of the authority store. The schema version of a database is stored in its
+//! default column family, which the typed tables do not use, and is brought up to date at
+//! startup by the migrations of the [`SchemaRegistry`], before the tables are opened.
+//!
+//! A database is checkpointed before it is migrated, and restored from the checkpoint if a
+//! migration fails, so that it is either fully migrated or left as it was. A checkpoint left
+//! behind by a node that stopped while migrating is restored at the next startup, and the
+//! migrations are run again. Once the migrations succeed, the checkpoint is renamed to a discard
+//! name before it is deleted, which commits the migration: RocksDB only names a checkpoint once
+//! it is complete, and the rename is atomic, so a checkpoint at the pre-migration path is always
+//! complete and always that of a migration that did not commit.
+
+use rocksdb::checkpoint::Checkpoint;
+use rocksdb::{IteratorMode, Options, WriteBatch, DB};
+use std::ffi::OsString;
+use std::fs;
+use std::path::{Path, PathBuf};
+use sui_types::error::{SuiError, SuiResult};
+use tracing::{info, warn};
+
+pub type SchemaVersion = u32;
+
+/// The key of the schema version in the default column family.
+const SCHEMA_VERSION_KEY: &[u8] = b"schema_version";
+
+/// The number of entries written at once when re-keying a table.
+const REKEY_BATCH_SIZE: usize = 10_000;
+
+/// Computes the new key and value of an entry of a table being re-keyed, from its old ones.
+pub type RekeyFn = fn(&[u8], &[u8]) -> SuiResult<(Vec<u8>, Vec<u8>)>;
+
+pub enum MigrationStep {
+    /// Creates a table, which is then opened empty.
+    AddTable(&'static str),
+    /// Rewrites every entry of a table, typically to change the encoding of its keys.
+    RekeyTable { table: &'static str, rekey: RekeyFn },
+    /// Deletes a table, with all its entries.
+    DropTable(&'static str),
+}
+
+/// The steps that bring the schema of a database from the version before `version` to
+/// `version`.
+pub struct Migration {
+    pub version: SchemaVersion,
+    pub description: &'static str,
+    pub steps: Vec<MigrationStep>,
+}
+
+pub struct SchemaRegistry {
+    migrations: Vec<Migration>,
+}
+
+impl SchemaRegistry {
+    /// Creates a registry of migrations, which must bring the schema from version 0 up one
+    /// version at a time.
+    pub fn new(migrations: Vec<Migration>) -> Self {
+        for (i, migration) in migrations.iter().enumerate() {
+            assert_eq!(
+                migration.version as usize,
+                i + 1,
+                "Migrations must be numbered consecutively from 1"
+            );
+        }
+        Self { migrations }
+    }
+
+    /// The migrations of the perpetual tables of the authority store. Version 0 is the schema of
+    /// the databases created before the schema was versioned.
+    ///
+    /// To change the schema, append a migration with the next version, and update the tables
+    /// in `authority_store_tables` to match it.
+    pub fn authority_store() -> Self {
+        Self::new(vec![])
+    }
+
+    /// The schema version the registry brings databases to.
+    pub fn current_version(&self) -> SchemaVersion {
+        self.migrations.len() as SchemaVersion
+    }
+
+    /// Brings the schema of the database at `path` to the current version, returning the version
+    /// it was at. A database that does not exist yet is created at the current version.
+    pub fn migrate(&self, path: &Path) -> SuiResult<SchemaVersion> {
+        let checkpoint_path = checkpoint_path(path);
+        let discarded_path = discarded_checkpoint_path(path);
+        if discarded_path.exists() {
+            // Left behind by a node that stopped while deleting the checkpoint of a migration
+            // that committed.
+            fs::remove_dir_all(&discarded_path).map_err(storage_error)?;
+        }
+        if checkpoint_path.exists() {
+            warn!(
+                ?path,
+                "Found the checkpoint of an interrupted schema migration, restoring it"
+            );
+            restore_checkpoint(path, &checkpoint_path)?;
+        }
+
+        let mut options = Options::default();
+        if !path.exists() {
+            options.create_if_missing(true);
+            let db = DB::open(&options, path).map_err(storage_error)?;
+            write_version(&db, self.current_version())?;
+            return Ok(self.current_version());
+        }
+
+        let column_families = DB::list_cf(&options, path).map_err(storage_error)?;
+        let version = read_version(
+            &DB::open_cf_for_read_only(&options, path, &column_families, false)
+                .map_err(storage_error)?,
+        )?;
+        if version > self.current_version() {
+            return Err(SuiError::GenericStorageError(format!(
+                "The database at {} has schema version {version}, which is newer than the \
+                latest version {} this binary supports",
+                path.display(),
+                self.current_version()
+            )));
+        }
+        if version == self.current_version() {
+            return Ok(version);
+        }
+
+        info!(
+            ?path,
+            from = version,
+            to = self.current_version(),
+            "Migrating the database schema"
+        );
+        // Tables are opened without the merge operators and compaction filters of the typed
+        // tables, so they must not be compacted while migrating.
+        options.set_disable_auto_compactions(true);
+        let mut db = DB::open_cf(&options, path, &column_families).map_err(storage_error)?;
+        Checkpoint::new(&db)
+            .and_then(|checkpoint| checkpoint.create_checkpoint(&checkpoint_path))
+            .map_err(storage_error)?;
+        let result = self.migrations[version as usize..]
+            .iter()
+            .try_for_each(|migration| run_migration(&mut db, migration));
+        drop(db);
+        match result {
+            Ok(()) => {
+                fs::rename(&checkpoint_path, &discarded_path).map_err(storage_error)?;
+                fs::remove_dir_all(&discarded_path).map_err(storage_error)?;
+                info!(
+                    ?path,
+                    version = self.current_version(),
+                    "Migrated the database"
+                );
+                Ok(version)
+            }
+            Err(e) => {
+                warn!(
+                    ?path,
+                    "Schema migration failed, rolling the database back: {e}"
+                );
+                restore_checkpoint(path, &checkpoint_path)?;
+                Err(e)
+            }
+        }
+    }
+}
+
+fn run_migration(db: &mut DB, migration: &Migration) -> SuiResult {
+    info!(
+        version = migration.version,
+        "Running schema migration: {}", migration.description
+    );
+    for step in &migration.steps {
+        match step {
+            MigrationStep::AddTable(table) => {
+                if db.cf_handle(table).is_none() {
+                    db.create_cf(table, &Options::default())
+                        .map_err(storage_error)?;
+                }
+            }
+            MigrationStep::RekeyTable { table, rekey } => rekey_table(db, table, *rekey)?,
+            MigrationStep::DropTable(table) => {
+                if db.cf_handle(table).is_some() {
+                    db.drop_cf(table).map_err(storage_error)?;
+                }
+            }
+        }
+    }
+    write_version(db, migration.version)
+}
+
+/// Re-keys `table` into a temporary table, then replaces `table` by it. Entries are copied
+/// rather than re-keyed in place, so that new keys cannot collide with old ones.
+fn rekey_table(db: &mut DB, table: &str, rekey: RekeyFn) -> SuiResult {
+    let temporary = format!("{table}.migrating");
+    if db.cf_handle(&temporary).is_some() {
+        db.drop_cf(&temporary).map_err(storage_error)?;
+    }
+    db.create_cf(&temporary, &Options::default())
+        .map_err(storage_error)?;
+    copy_table(db, table, &temporary, rekey)?;
+    db.drop_cf(table).map_err(storage_error)?;
+    db.create_cf(table, &Options::default())
+        .map_err(storage_error)?;
+    copy_table(db, &temporary, table, |key, value| {
+        Ok((key.to_vec(), value.to_vec()))
+    })?;
+    db.drop_cf(&temporary).map_err(storage_error)
+}
+
+fn copy_table(db: &DB, from: &str, to: &str, rekey: RekeyFn) -> SuiResult {
+    let (Some(from_cf), Some(to_cf)) = (db.cf_handle(from), db.cf_handle(to)) else {
+        return Err(SuiError::GenericStorageError(format!(
+            "Cannot copy table {from} to {to}, which do not both exist"
+        )));
+    };
+    let estimated_entries = db
+        .property_int_value_cf(from_cf, "rocksdb.estimate-num-keys")
+        .map_err(storage_error)?
+        .unwrap_or_default();
+    let mut batch = WriteBatch::default();
+    let mut copied = 0;
+    for entry in db.iterator_cf(from_cf, IteratorMode::Start) {
+        let (key, value) = entry.map_err(storage_error)?;
+        let (key, value) = rekey(&key, &value)?;
+        batch.put_cf(to_cf, key, value);
+        copied += 1;
+        if batch.len() == REKEY_BATCH_SIZE {
+            db.write(std::mem::take(&mut batch))
+                .map_err(storage_error)?;
+            info!("Copied {copied} of about {estimated_entries} entries of {from} to {to}");
+        }
+    }
+    db.write(batch).map_err(storage_error)?;
+    info!("Copied {copied} entries of {from} to {to}");
+    Ok(())
+}
+
+fn read_version(db: &DB) -> SuiResult<SchemaVersion> {
+    let Some(bytes) = db.get(SCHEMA_VERSION_KEY).map_err(storage_error)? else {
+        return Ok(0);
+    };
+    let bytes = bytes.try_into().map_err(|_| {
+        SuiError::GenericStorageError("The schema version of the database is invalid".into())
+    })?;
+    Ok(SchemaVersion::from_be_bytes(bytes))
+}
+
+fn write_version(db: &DB, version: SchemaVersion) -> SuiResult {
+    db.put(SCHEMA_VERSION_KEY, version.to_be_bytes())
+        .map_err(storage_error)?;
+    db.flush().map_err(storage_error)
+}
+
+fn checkpoint_path(path: &Path) -> PathBuf {
+    let mut checkpoint_path = OsString::from(path.as_os_str());
+    checkpoint_path.push(".pre-migration");
+    checkpoint_path.into()
+}
+
+fn discarded_checkpoint_path(path: &Path) -> PathBuf {
+    let mut discarded_path = OsString::from(path.as_os_str());
+    discarded_path.push(".pre-migration.discarded");
+    discarded_path.into()
+}
+
+fn restore_checkpoint(path: &Path, checkpoint_path: &Path) -> SuiResult {
+    if path.exists() {
+        fs::remove_dir_all(path).map_err(storage_error)?;
+    }
+    fs::rename(checkpoint_path, path).map_err(storage_error)
+}
+
+fn storage_error(e: impl std::fmt::Display) -> SuiError {
+    SuiError::GenericStorageError(e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_db(path: &Path, version: Option<SchemaVersion>) {
+        let mut options = Options::default();
+        options.create_if_missing(true);
+        options.create_missing_column_families(true);
+        let db = DB::open_cf(&options, path, ["keyed", "unused"]).unwrap();
+        let cf = db.cf_handle("keyed").unwrap();
+        for i in 0u32..100 {
+            db.put_cf(cf, i.to_le_bytes(), i.to_string()).unwrap();
+        }
+        if let Some(version) = version {
+            write_version(&db, version).unwrap();
+        }
+    }
+
+    fn open_db(path: &Path) -> DB {
+        let column_families = DB::list_cf(&Options::default(), path).unwrap();
+        DB::open_cf(&Options::default(), path, column_families).unwrap()
+    }
+
+    fn registry(rekey: RekeyFn) -> SchemaRegistry {
+        SchemaRegistry::new(vec![
+            Migration {
+                version: 1,
+                description: "Add a table",
+                steps: vec![MigrationStep::AddTable("added")],
+            },
+            Migration {
+                version: 2,
+                description: "Encode keys in big endian, and drop a table",
+                steps: vec![
+                    MigrationStep::RekeyTable {
+                        table: "keyed",
+                        rekey,
+                    },
+                    MigrationStep::DropTable("unused"),
+                ],
+            },
+        ])
+    }
+
+    fn to_big_endian(key: &[u8], value: &[u8]) -> SuiResult<(Vec<u8>, Vec<u8>)> {
+        let mut key = key.to_vec();
+        key.reverse();
+        Ok((key, value.to_vec()))
+    }
+
+    #[test]
+    fn test_migrate_creates_new_database_at_current_version() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("db");
+        let registry = registry(to_big_endian);
+
+        assert_eq!(registry.migrate(&path).unwrap(), 2);
+        assert_eq!(read_version(&open_db(&path)).unwrap(), 2);
+        assert_eq!(registry.migrate(&path).unwrap(), 2);
+    }
+
+    #[test]
+    fn test_migrate_runs_pending_migrations() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("db");
+        create_db(&path, None);
+
+        assert_eq!(registry(to_big_endian).migrate(&path).unwrap(), 0);
+        assert!(!checkpoint_path(&path).exists());
+        assert!(!discarded_checkpoint_path(&path).exists());
+
+        let db = open_db(&path);
+        assert_eq!(read_version(&db).unwrap(), 2);
+        assert!(db.cf_handle("added").is_some());
+        assert!(db.cf_handle("unused").is_none());
+        assert!(db.cf_handle("keyed.migrating").is_none());
+        let keys: Vec<_> = db
+            .iterator_cf(db.cf_handle("keyed").unwrap(), IteratorMode::Start)
+            .map(|entry| {
+                let (key, value) = entry.unwrap();
+                let key = u32::from_be_bytes((*key).try_into().unwrap());
+                assert_eq!(*value, *key.to_string().as_bytes());
+                key
+            })
+            .collect();
+        assert_eq!(keys, (0..100).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_failed_migration_is_rolled_back() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("db");
+        create_db(&path, Some(1));
+
+        let result = registry(|key, value| {
+            if key[0] == 50 {
+                return Err(SuiError::GenericStorageError("Cannot re-key".into()));
+            }
+            to_big_endian(key, value)
+        })
+        .migrate(&path);
+        assert!(result.is_err());
+        assert!(!checkpoint_path(&path).exists());
+
+        let db = open_db(&path);
+        assert_eq!(read_version(&db).unwrap(), 1);
+        assert!(db.cf_handle("unused").is_some());
+        assert!(db.cf_handle("keyed.migrating").is_none());
+        let cf = db.cf_handle("keyed").unwrap();
+        assert_eq!(
+            db.get_cf(cf, 1u32.to_le_bytes()).unwrap().unwrap(),
+            b"1".to_vec()
+        );
+    }
+
+    #[test]
+    fn test_migrate_rejects_newer_schema() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("db");
+        create_db(&path, Some(3));
+
+        assert!(registry(to_big_endian).migrate(&path).is_err());
+    }
+
+    #[test]
+    fn test_interrupted_migration_is_restored() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("db");
+        create_db(&path, None);
+        Checkpoint::new(&open_db(&path))
+            .unwrap()
+            .create_checkpoint(checkpoint_path(&path))
+            .unwrap();
+        // A migration that stopped half way.
+        let mut db = open_db(&path);
+        db.drop_cf("keyed").unwrap();
+        drop(db);
+
+        assert_eq!(registry(to_big_endian).migrate(&path).unwrap(), 0);
+        let db = open_db(&path);
+        assert_eq!(read_version(&db).unwrap(), 2);
+        let cf = db.cf_handle("keyed").unwrap();
+        assert_eq!(
+            db.get_cf(cf, 1u32.to_be_bytes()).unwrap().unwrap(),
+            b"1".to_vec()
+        );
+    }
+
+    #[test]
+    fn test_discarded_checkpoint_is_not_restored() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("db");
+        create_db(&path, None);
+        let registry = registry(to_big_endian);
+        let checkpoint = dir.path().join("checkpoint");
+        Checkpoint::new(&open_db(&path))
+            .unwrap()
+            .create_checkpoint(&checkpoint)
+            .unwrap();
+        registry.migrate(&path).unwrap();
+        // A node that stopped while deleting the checkpoint of a committed migration.
+        fs::rename(&checkpoint, discarded_checkpoint_path(&path)).unwrap();
+        fs::remove_file(discarded_checkpoint_path(&path).join("CURRENT")).unwrap();
+
+        assert_eq!(registry.migrate(&path).unwrap(), 2);
+        assert!(!discarded_checkpoint_path(&path).exists());
+        let db = open_db(&path);
+        assert_eq!(read_version(&db).unwrap(), 2);
+        assert!(db.cf_handle("added").is_some());
+    }
+}
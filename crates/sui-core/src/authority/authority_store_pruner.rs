@@ -6,23 +6,35 @@ use crate::checkpoints::CheckpointStore;
 use mysten_metrics::monitored_scope;
 use std::cmp::{max, min};
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::{sync::Arc, time::Duration};
 use sui_config::node::AuthorityStorePruningConfig;
+use sui_storage::indexes::{IndexStore, IndexedTransaction};
 use sui_types::digests::CheckpointDigest;
-use sui_types::messages::{TransactionEffects, TransactionEffectsAPI};
+use sui_types::messages::{
+    TransactionDataAPI, TransactionEffects, TransactionEffectsAPI, TrustedTransaction,
+};
+use sui_types::messages_checkpoint::CheckpointSequenceNumber;
 use sui_types::{
     base_types::{ObjectID, VersionNumber},
     storage::ObjectKey,
 };
 use tokio::sync::oneshot::{self, Sender};
+use tokio::sync::Notify;
 use tokio::time::Instant;
-use tracing::log::{debug, error};
+use tracing::log::{debug, error, info};
 use typed_store::Map;
 
 use super::authority_store_tables::AuthorityPerpetualTables;
 
 pub struct AuthorityStorePruner {
-    _objects_pruner_cancel_handle: oneshot::Sender<()>,
+    _pruner_cancel_handle: oneshot::Sender<()>,
+    /// Transactions are pruned by a task of their own, so that it can be throttled without
+    /// holding back the rest of the pruning.
+    _transaction_pruner_cancel_handle: Option<oneshot::Sender<()>>,
+    num_epochs_to_retain: Arc<AtomicU64>,
+    force_prune: Arc<Notify>,
+    force_prune_transactions: Arc<Notify>,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -118,6 +130,7 @@ impl AuthorityStorePruner {
             current_epoch, checkpoint_number
         );
         let iter = checkpoint_store
+            .tables
             .certified_checkpoints
             .iter()
             .skip_to(&(checkpoint_number + 1))?;
@@ -170,34 +183,282 @@ impl AuthorityStorePruner {
         Ok(())
     }
 
-    fn setup_objects_pruning(
+    /// Prunes the transactions, effects and events of the checkpoints from epochs eligible for
+    /// pruning, once their old object versions have been pruned, along with their entries in
+    /// `indexes`. The index entries are deleted first, as they are found through the transactions
+    /// and effects.
+    fn prune_transactions_for_eligible_epochs(
+        perpetual_db: &Arc<AuthorityPerpetualTables>,
+        checkpoint_store: &Arc<CheckpointStore>,
+        indexes: Option<&IndexStore>,
+        config: AuthorityStorePruningConfig,
+        num_epochs_to_retain: u64,
+    ) -> anyhow::Result<()> {
+        // Object pruning finds the object versions to prune through the effects.
+        let max_eligible_checkpoint = match Self::highest_prunable_checkpoint(
+            checkpoint_store,
+            checkpoint_store.get_highest_pruned_checkpoint_seq_number()?,
+        )? {
+            Some(checkpoint) => checkpoint,
+            None => return Ok(()),
+        };
+        let current_epoch = checkpoint_store
+            .get_highest_executed_checkpoint()?
+            .map(|c| c.epoch())
+            .unwrap_or_default();
+        let start = checkpoint_store
+            .get_highest_pruned_transactions_checkpoint_seq_number()?
+            .unwrap_or_default()
+            + 1;
+
+        let mut batch = perpetual_db.transactions.batch();
+        let mut indexed_transactions = vec![];
+        let mut last_checkpoint = None;
+        let mut checkpoints_in_batch = 0;
+        let mut transactions_in_batch = 0;
+        let mut batch_start = std::time::Instant::now();
+        for (sequence_number, checkpoint) in checkpoint_store
+            .tables
+            .certified_checkpoints
+            .iter()
+            .skip_to(&start)?
+        {
+            if sequence_number > max_eligible_checkpoint
+                || current_epoch < checkpoint.epoch() + num_epochs_to_retain
+            {
+                break;
+            }
+            let content = checkpoint_store
+                .get_checkpoint_contents(&checkpoint.content_digest())?
+                .ok_or_else(|| anyhow::anyhow!("checkpoint content data is missing"))?;
+            let effects = perpetual_db
+                .effects
+                .multi_get(content.iter().map(|tx| tx.effects))?;
+            if indexes.is_some() {
+                let transactions = perpetual_db
+                    .transactions
+                    .multi_get(content.iter().map(|tx| tx.transaction))?;
+                // Transactions and effects pruned by an interrupted run have no index entries
+                // left either.
+                for (transaction, effects) in transactions.iter().zip(&effects) {
+                    if let (Some(transaction), Some(effects)) = (transaction, effects) {
+                        indexed_transactions.push(Self::indexed_transaction(transaction, effects)?);
+                    }
+                }
+            }
+            batch = batch
+                .delete_batch(
+                    &perpetual_db.transactions,
+                    content.iter().map(|tx| tx.transaction),
+                )?
+                .delete_batch(&perpetual_db.effects, content.iter().map(|tx| tx.effects))?
+                .delete_batch(
+                    &perpetual_db.events,
+                    effects
+                        .iter()
+                        .flatten()
+                        .filter_map(|effects| effects.events_digest().copied()),
+                )?;
+            last_checkpoint = Some((sequence_number, checkpoint.digest()));
+            checkpoints_in_batch += 1;
+            transactions_in_batch += content.size();
+
+            if transactions_in_batch >= config.max_transactions_in_batch
+                || checkpoints_in_batch >= config.max_checkpoints_in_batch
+            {
+                if let Some(indexes) = indexes {
+                    indexes.prune_transactions(&indexed_transactions)?;
+                }
+                batch.write()?;
+                checkpoint_store.update_highest_pruned_transactions_checkpoint(
+                    sequence_number,
+                    checkpoint.digest(),
+                )?;
+                Self::throttle(
+                    batch_start,
+                    transactions_in_batch,
+                    config.max_transactions_pruned_per_sec,
+                );
+                batch = perpetual_db.transactions.batch();
+                indexed_transactions.clear();
+                last_checkpoint = None;
+                checkpoints_in_batch = 0;
+                transactions_in_batch = 0;
+                batch_start = std::time::Instant::now();
+            }
+        }
+        if let Some((sequence_number, digest)) = last_checkpoint {
+            if let Some(indexes) = indexes {
+                indexes.prune_transactions(&indexed_transactions)?;
+            }
+            batch.write()?;
+            checkpoint_store
+                .update_highest_pruned_transactions_checkpoint(sequence_number, digest)?;
+        }
+        Ok(())
+    }
+
+    /// What `transaction` was indexed under, see `AuthorityState::index_tx`.
+    fn indexed_transaction(
+        transaction: &TrustedTransaction,
+        effects: &TransactionEffects,
+    ) -> anyhow::Result<IndexedTransaction> {
+        let data = &transaction.inner().data().intent_message.value;
+        Ok(IndexedTransaction {
+            digest: *transaction.inner().digest(),
+            sender: data.sender(),
+            input_objects: data
+                .input_objects()?
+                .iter()
+                .map(|o| o.object_id())
+                .collect(),
+            mutated_objects: effects
+                .all_mutated()
+                .into_iter()
+                .map(|(obj_ref, owner, _kind)| (obj_ref.0, *owner))
+                .collect(),
+            move_functions: data
+                .legacy_move_calls()
+                .iter()
+                .map(|mc| (mc.package, mc.module.to_string(), mc.function.to_string()))
+                .collect(),
+        })
+    }
+
+    /// Sleeps for as long as pruning `transactions` since `start` is ahead of
+    /// `max_transactions_per_sec`.
+    fn throttle(
+        start: std::time::Instant,
+        transactions: usize,
+        max_transactions_per_sec: Option<u64>,
+    ) {
+        let Some(max_transactions_per_sec) = max_transactions_per_sec else {
+            return;
+        };
+        let target = Duration::from_secs_f64(transactions as f64 / max_transactions_per_sec as f64);
+        if let Some(delay) = target.checked_sub(start.elapsed()) {
+            std::thread::sleep(delay);
+        }
+    }
+
+    /// Prunes the summaries and contents of the checkpoints from epochs eligible for pruning,
+    /// once everything else pruned through them has been.
+    fn prune_checkpoints_for_eligible_epochs(
+        checkpoint_store: &Arc<CheckpointStore>,
+        config: AuthorityStorePruningConfig,
+        num_epochs_to_retain: u64,
+    ) -> anyhow::Result<()> {
+        let mut max_eligible_checkpoint = match Self::highest_prunable_checkpoint(
+            checkpoint_store,
+            checkpoint_store.get_highest_pruned_checkpoint_seq_number()?,
+        )? {
+            Some(checkpoint) => checkpoint,
+            None => return Ok(()),
+        };
+        if config.num_epochs_to_retain_for_transactions.is_some() {
+            match checkpoint_store.get_highest_pruned_transactions_checkpoint_seq_number()? {
+                Some(checkpoint) => {
+                    max_eligible_checkpoint = min(max_eligible_checkpoint, checkpoint)
+                }
+                None => return Ok(()),
+            }
+        }
+        let current_epoch = checkpoint_store
+            .get_highest_executed_checkpoint()?
+            .map(|c| c.epoch())
+            .unwrap_or_default();
+        // The genesis checkpoint is never pruned, it is how the node recognizes its database.
+        let start = max(checkpoint_store.get_lowest_available_checkpoint()?, 1);
+
+        let mut batch = vec![];
+        for (sequence_number, checkpoint) in checkpoint_store
+            .tables
+            .certified_checkpoints
+            .iter()
+            .skip_to(&start)?
+        {
+            if sequence_number > max_eligible_checkpoint
+                || current_epoch < checkpoint.epoch() + num_epochs_to_retain
+            {
+                break;
+            }
+            batch.push(checkpoint);
+            if batch.len() >= config.max_checkpoints_in_batch {
+                checkpoint_store.prune_checkpoints(&batch)?;
+                batch.clear();
+            }
+        }
+        checkpoint_store.prune_checkpoints(&batch)?;
+        Ok(())
+    }
+
+    /// Bounds `checkpoint` by the highest executed checkpoint, as checkpoints that have not been
+    /// executed yet are still needed whatever the retention.
+    fn highest_prunable_checkpoint(
+        checkpoint_store: &CheckpointStore,
+        checkpoint: Option<CheckpointSequenceNumber>,
+    ) -> anyhow::Result<Option<CheckpointSequenceNumber>> {
+        let highest_executed = checkpoint_store.get_highest_executed_checkpoint_seq_number()?;
+        Ok(checkpoint.zip(highest_executed).map(|(a, b)| min(a, b)))
+    }
+
+    /// Runs the pruning of objects and checkpoints enabled by `config` once.
+    fn prune(
+        perpetual_db: &Arc<AuthorityPerpetualTables>,
+        checkpoint_store: &Arc<CheckpointStore>,
         config: AuthorityStorePruningConfig,
+        num_epochs_to_retain: &AtomicU64,
+    ) {
+        // The retention can be changed while the node is running.
+        let config = AuthorityStorePruningConfig {
+            num_epochs_to_retain: num_epochs_to_retain.load(Ordering::Relaxed),
+            ..config
+        };
+        if config.num_epochs_to_retain != u64::MAX {
+            if let Err(err) =
+                Self::prune_objects_for_eligible_epochs(perpetual_db, checkpoint_store, config)
+            {
+                error!("Failed to prune objects: {:?}", err);
+            }
+        }
+        if let Some(num_epochs_to_retain) = config.num_epochs_to_retain_for_checkpoints {
+            if let Err(err) = Self::prune_checkpoints_for_eligible_epochs(
+                checkpoint_store,
+                config,
+                num_epochs_to_retain,
+            ) {
+                error!("Failed to prune checkpoints: {:?}", err);
+            }
+        }
+    }
+
+    fn setup_pruning(
+        config: AuthorityStorePruningConfig,
+        num_epochs_to_retain: Arc<AtomicU64>,
+        force_prune: Arc<Notify>,
         epoch_duration_ms: u64,
         perpetual_db: Arc<AuthorityPerpetualTables>,
         checkpoint_store: Arc<CheckpointStore>,
     ) -> Sender<()> {
         let (sender, mut recv) = tokio::sync::oneshot::channel();
         debug!(
-            "Starting object pruning service with num_epochs_to_retain={}",
-            config.num_epochs_to_retain
+            "Starting pruning service with num_epochs_to_retain={}, num_epochs_to_retain_for_transactions={:?}, num_epochs_to_retain_for_checkpoints={:?}",
+            config.num_epochs_to_retain,
+            config.num_epochs_to_retain_for_transactions,
+            config.num_epochs_to_retain_for_checkpoints,
         );
-        let tick_duration = if config.num_epochs_to_retain > 0 {
-            Duration::from_millis(epoch_duration_ms / 2)
-        } else {
-            Duration::from_secs(1)
-        };
-
-        let pruning_initial_delay = min(tick_duration, Duration::from_secs(300));
         let mut prune_interval =
-            tokio::time::interval_at(Instant::now() + pruning_initial_delay, tick_duration);
+            Self::prune_interval(Self::pruning_period(&config, epoch_duration_ms));
 
         tokio::task::spawn(async move {
             loop {
                 tokio::select! {
-                    _ = prune_interval.tick(), if config.num_epochs_to_retain != u64::MAX => {
-                        if let Err(err) = Self::prune_objects_for_eligible_epochs(&perpetual_db, &checkpoint_store, config) {
-                            error!("Failed to prune objects: {:?}", err);
-                        }
+                    _ = prune_interval.tick() => {
+                        Self::prune(&perpetual_db, &checkpoint_store, config, &num_epochs_to_retain);
+                    },
+                    _ = force_prune.notified() => {
+                        info!("Pruning on request");
+                        Self::prune(&perpetual_db, &checkpoint_store, config, &num_epochs_to_retain);
                     },
                     _ = &mut recv => break,
                 }
@@ -205,21 +466,126 @@ impl AuthorityStorePruner {
         });
         sender
     }
+
+    /// Starts the pruning of transactions, if `config` retains them for a limited number of
+    /// epochs. Each run happens on a blocking thread, where it sleeps between batches to stay
+    /// under `max_transactions_pruned_per_sec`.
+    fn setup_transaction_pruning(
+        config: AuthorityStorePruningConfig,
+        force_prune: Arc<Notify>,
+        epoch_duration_ms: u64,
+        perpetual_db: Arc<AuthorityPerpetualTables>,
+        checkpoint_store: Arc<CheckpointStore>,
+        indexes: Option<Arc<IndexStore>>,
+    ) -> Option<Sender<()>> {
+        let num_epochs_to_retain = config.num_epochs_to_retain_for_transactions?;
+        let (sender, mut recv) = tokio::sync::oneshot::channel();
+        debug!(
+            "Starting transaction pruning service with num_epochs_to_retain={}, max_transactions_pruned_per_sec={:?}",
+            num_epochs_to_retain, config.max_transactions_pruned_per_sec,
+        );
+        let tick_duration = match config.transaction_pruning_period_secs {
+            Some(period_secs) => Duration::from_secs(period_secs),
+            None => Self::pruning_period(&config, epoch_duration_ms),
+        };
+        let mut prune_interval = Self::prune_interval(tick_duration);
+
+        let prune = move || {
+            let perpetual_db = perpetual_db.clone();
+            let checkpoint_store = checkpoint_store.clone();
+            let indexes = indexes.clone();
+            tokio::task::spawn_blocking(move || {
+                if let Err(err) = Self::prune_transactions_for_eligible_epochs(
+                    &perpetual_db,
+                    &checkpoint_store,
+                    indexes.as_deref(),
+                    config,
+                    num_epochs_to_retain,
+                ) {
+                    error!("Failed to prune transactions: {:?}", err);
+                }
+            })
+        };
+        tokio::task::spawn(async move {
+            loop {
+                tokio::select! {
+                    _ = prune_interval.tick() => {
+                        prune().await.ok();
+                    },
+                    _ = force_prune.notified() => {
+                        info!("Pruning transactions on request");
+                        prune().await.ok();
+                    },
+                    _ = &mut recv => break,
+                }
+            }
+        });
+        Some(sender)
+    }
+
+    fn pruning_period(config: &AuthorityStorePruningConfig, epoch_duration_ms: u64) -> Duration {
+        match config.pruning_period_secs {
+            Some(pruning_period_secs) => Duration::from_secs(pruning_period_secs),
+            None if config.num_epochs_to_retain > 0 => Duration::from_millis(epoch_duration_ms / 2),
+            None => Duration::from_secs(1),
+        }
+    }
+
+    fn prune_interval(period: Duration) -> tokio::time::Interval {
+        let initial_delay = min(period, Duration::from_secs(300));
+        tokio::time::interval_at(Instant::now() + initial_delay, period)
+    }
+
     pub fn new(
         perpetual_db: Arc<AuthorityPerpetualTables>,
         checkpoint_store: Arc<CheckpointStore>,
+        indexes: Option<Arc<IndexStore>>,
         pruning_config: AuthorityStorePruningConfig,
         epoch_duration_ms: u64,
     ) -> Self {
+        let num_epochs_to_retain = Arc::new(AtomicU64::new(pruning_config.num_epochs_to_retain));
+        let force_prune = Arc::new(Notify::new());
+        let force_prune_transactions = Arc::new(Notify::new());
         AuthorityStorePruner {
-            _objects_pruner_cancel_handle: Self::setup_objects_pruning(
+            _pruner_cancel_handle: Self::setup_pruning(
+                pruning_config,
+                num_epochs_to_retain.clone(),
+                force_prune.clone(),
+                epoch_duration_ms,
+                perpetual_db.clone(),
+                checkpoint_store.clone(),
+            ),
+            _transaction_pruner_cancel_handle: Self::setup_transaction_pruning(
                 pruning_config,
+                force_prune_transactions.clone(),
                 epoch_duration_ms,
                 perpetual_db,
                 checkpoint_store,
+                indexes,
             ),
+            num_epochs_to_retain,
+            force_prune,
+            force_prune_transactions,
         }
     }
+
+    /// Changes the number of epochs of object versions to retain, starting from the next pruning
+    /// run. The pruning period is still derived from the retention the pruner was started with.
+    pub fn set_num_epochs_to_retain(&self, num_epochs_to_retain: u64) {
+        info!(
+            "Changing object pruning num_epochs_to_retain to {}",
+            num_epochs_to_retain
+        );
+        self.num_epochs_to_retain
+            .store(num_epochs_to_retain, Ordering::Relaxed);
+    }
+
+    /// Starts a pruning run without waiting for the next period, or right after the run in
+    /// progress if there is one.
+    pub fn force_prune(&self) {
+        self.force_prune.notify_one();
+        self.force_prune_transactions.notify_one();
+    }
 }
 
 #[cfg(test)]
@@ -233,7 +599,9 @@ mod tests {
 
     use crate::authority::authority_store_pruner::DeletionMethod;
     use crate::authority::authority_store_tables::AuthorityPerpetualTables;
-    use crate::authority::authority_store_types::{StoreData, StoreObject, StoreObjectPair};
+    use crate::authority::authority_store_types::{
+        get_store_object_pair, StoreData, StoreObject, StoreObjectPair,
+    };
     #[cfg(not(target_env = "msvc"))]
     use pprof::Symbol;
     use sui_types::base_types::{ObjectDigest, VersionNumber};
@@ -303,7 +671,8 @@ mod tests {
         let ids = ObjectID::in_range(ObjectID::ZERO, total_unique_object_ids)?;
         for id in ids {
             for i in (0..num_versions_per_object).rev() {
-                let StoreObjectPair(obj, _) = Object::immutable_with_id_for_testing(id).into();
+                let StoreObjectPair(obj, _) =
+                    get_store_object_pair(Object::immutable_with_id_for_testing(id), 0);
                 objects.insert(&ObjectKey(id, SequenceNumber::from(i)), &obj)?;
                 if i < num_versions_per_object - num_versions_to_keep {
                     to_delete.push((id, SequenceNumber::from(i)));
@@ -349,7 +718,7 @@ mod tests {
                     to_delete.push(object_key);
                 }
                 let StoreObjectPair(obj, indirect_obj) =
-                    Object::immutable_with_id_for_testing(id).into();
+                    get_store_object_pair(Object::immutable_with_id_for_testing(id), 0);
                 batch = batch.insert_batch(
                     &db.objects,
                     [(ObjectKey(id, SequenceNumber::from(i)), obj.clone())],
@@ -415,6 +784,17 @@ mod tests {
         .await;
     }
 
+    #[test]
+    fn test_throttle() {
+        let start = std::time::Instant::now();
+        AuthorityStorePruner::throttle(start, 1000, None);
+        AuthorityStorePruner::throttle(start, 10, Some(1000));
+        ma::assert_lt!(start.elapsed(), Duration::from_millis(100));
+
+        AuthorityStorePruner::throttle(start, 30, Some(100));
+        ma::assert_ge!(start.elapsed(), Duration::from_millis(300));
+    }
+
     #[tokio::test]
     async fn test_ref_count_pruning() {
         let path = tempfile::tempdir().unwrap().into_path();
@@ -458,7 +838,8 @@ mod tests {
                 if i < num_versions_per_object - 2 {
                     to_delete.push((id, SequenceNumber::from(i)));
                 }
-                let StoreObjectPair(obj, _) = Object::immutable_with_id_for_testing(id).into();
+                let StoreObjectPair(obj, _) =
+                    get_store_object_pair(Object::immutable_with_id_for_testing(id), 0);
                 perpetual_db
                     .objects
                     .insert(&ObjectKey(id, SequenceNumber::from(i)), &obj)?;
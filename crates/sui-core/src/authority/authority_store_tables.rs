@@ -4,6 +4,7 @@
 use super::*;
 use crate::authority::authority_store::LockDetails;
 use rocksdb::Options;
+use std::collections::BTreeMap;
 use std::path::Path;
 use sui_storage::default_db_options;
 use sui_types::accumulator::Accumulator;
@@ -12,7 +13,7 @@ use sui_types::digests::TransactionEventsDigest;
 use sui_types::storage::ObjectStore;
 use typed_store::metrics::SamplingInterval;
 use typed_store::rocks::util::{empty_compaction_filter, reference_count_merge_operator};
-use typed_store::rocks::{DBMap, DBOptions, MetricConf, ReadWriteOptions};
+use typed_store::rocks::{DBMap, DBOptions, MetricConf, ReadWriteOptions, TypedStoreError};
 use typed_store::traits::{Map, TableSummary, TypedStoreDebug};
 
 use crate::authority::authority_store_types::{
@@ -116,6 +117,60 @@ impl AuthorityPerpetualTables {
         Self::get_read_only_handle(Self::path(parent_path), None, None, MetricConf::default())
     }
 
+    /// Opens the tables as a secondary instance, see [`Self::try_catch_up_with_primary`].
+    pub fn open_secondary(parent_path: &Path, secondary_parent_path: &Path) -> Self {
+        Self::open_tables_secondary(
+            Self::path(parent_path),
+            Self::path(secondary_parent_path),
+            MetricConf::with_sampling(SamplingInterval::new(Duration::from_secs(60), 0)),
+            None,
+        )
+    }
+
+    /// Opens the tables of a checkpoint of the database at `path`, as made at the end of each
+    /// epoch by `AuthorityState::checkpoint_perpetual_db`.
+    pub fn open_checkpoint(path: &Path) -> Self {
+        Self::open_tables_read_write(path.to_path_buf(), MetricConf::default(), None, None)
+    }
+
+    /// Makes the writes of the primary instance visible, when opened with [`Self::open_secondary`].
+    pub fn try_catch_up_with_primary(&self) -> Result<(), TypedStoreError> {
+        self.objects.try_catch_up_with_primary()
+    }
+
+    /// Flushes the memtables of all tables to disk, so that nothing needs to be recovered from
+    /// the write-ahead log on the next start.
+    pub fn flush(&self) -> Result<(), TypedStoreError> {
+        self.objects.flush()?;
+        self.indirect_move_objects.flush()?;
+        self.owned_object_transaction_locks.flush()?;
+        self.transactions.flush()?;
+        self.parent_sync.flush()?;
+        self.effects.flush()?;
+        self.executed_effects.flush()?;
+        self.events.flush()?;
+        self.executed_transactions_to_checkpoint.flush()?;
+        self.root_state_hash_by_epoch.flush()?;
+        self.epoch_start_configuration.flush()?;
+        Ok(())
+    }
+
+    /// Compacts the whole of table `table_name`, blocking until it is done.
+    pub fn compact_table(&self, table_name: &str) -> Result<(), TypedStoreError> {
+        self.objects.rocksdb.compact_cf(table_name)
+    }
+
+    /// The size of the SST files of each table, by table name.
+    pub fn table_sizes(&self) -> Result<BTreeMap<String, u64>, TypedStoreError> {
+        Self::describe_tables()
+            .into_keys()
+            .map(|table_name| {
+                let size = self.objects.rocksdb.cf_sst_files_size(&table_name)?;
+                Ok((table_name, size))
+            })
+            .collect()
+    }
+
     // This is used by indexer to find the correct version of dynamic field child object.
     // We do not store the version of the child object, but because of lamport timestamp,
     // we know the child must have version number less then or eq to the parent.
@@ -182,6 +237,14 @@ impl AuthorityPerpetualTables {
         Ok(())
     }
 
+    /// The root state hash of `epoch` and the last checkpoint of the epoch, once accumulated.
+    pub fn get_root_state_hash(
+        &self,
+        epoch: EpochId,
+    ) -> SuiResult<Option<(CheckpointSequenceNumber, Accumulator)>> {
+        Ok(self.root_state_hash_by_epoch.get(&epoch)?)
+    }
+
     pub fn database_is_empty(&self) -> SuiResult<bool> {
         Ok(self
             .objects
@@ -191,6 +254,27 @@ impl AuthorityPerpetualTables {
             .is_none())
     }
 
+    /// Writes the memtables of all the tables out to disk.
+    pub fn flush_memtables(&self) -> SuiResult {
+        let flushes = [
+            self.objects.flush(),
+            self.indirect_move_objects.flush(),
+            self.owned_object_transaction_locks.flush(),
+            self.transactions.flush(),
+            self.parent_sync.flush(),
+            self.effects.flush(),
+            self.executed_effects.flush(),
+            self.events.flush(),
+            self.executed_transactions_to_checkpoint.flush(),
+            self.root_state_hash_by_epoch.flush(),
+            self.epoch_start_configuration.flush(),
+        ];
+        for flush in flushes {
+            flush.map_err(|e| TypedStoreError::RocksDBError(e.to_string()))?;
+        }
+        Ok(())
+    }
+
     pub fn iter_live_object_set(&self) -> LiveSetIter<'_> {
         LiveSetIter {
             iter: self.parent_sync.keys(),
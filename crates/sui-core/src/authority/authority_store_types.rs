@@ -5,7 +5,7 @@ use move_core_types::language_storage::StructTag;
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
 use serde_with::Bytes;
-use std::convert::{From, TryFrom};
+use std::convert::TryFrom;
 use sui_types::base_types::{ObjectDigest, SequenceNumber, TransactionDigest};
 use sui_types::crypto::{sha3_hash, Signable};
 use sui_types::error::SuiError;
@@ -69,40 +69,36 @@ impl StoreMoveObject {
 
 pub struct StoreObjectPair(pub StoreObject, pub Option<StoreMoveObject>);
 
-impl From<Object> for StoreObjectPair {
-    fn from(object: Object) -> Self {
-        let mut indirect_object = None;
+/// Splits `object` into its stored representation, moving the contents of a Move object to a
+/// [`StoreMoveObject`] if they are larger than `indirect_objects_threshold` bytes.
+pub fn get_store_object_pair(object: Object, indirect_objects_threshold: usize) -> StoreObjectPair {
+    let mut indirect_object = None;
 
-        let data = match object.data {
-            Data::Package(package) => StoreData::Package(package),
-            Data::Move(move_obj) => {
-                // TODO: add real heuristic to decide if object needs to be stored indirectly
-                if cfg!(test) {
-                    let move_object = StoreMoveObject {
-                        type_: move_obj.type_.clone(),
-                        has_public_transfer: move_obj.has_public_transfer(),
-                        contents: move_obj.contents().to_vec(),
-                        ref_count: 1,
-                    };
-                    let digest = move_object.digest();
-                    indirect_object = Some(move_object);
-                    StoreData::IndirectObject(IndirectObjectMetadata {
-                        version: move_obj.version(),
-                        digest,
-                    })
-                } else {
-                    StoreData::Move(move_obj)
-                }
-            }
-        };
-        let store_object = StoreObject {
-            data,
-            owner: object.owner,
-            previous_transaction: object.previous_transaction,
-            storage_rebate: object.storage_rebate,
-        };
-        Self(store_object, indirect_object)
-    }
+    let data = match object.data {
+        Data::Package(package) => StoreData::Package(package),
+        Data::Move(move_obj) if move_obj.contents().len() > indirect_objects_threshold => {
+            let move_object = StoreMoveObject {
+                type_: move_obj.type_.clone(),
+                has_public_transfer: move_obj.has_public_transfer(),
+                contents: move_obj.contents().to_vec(),
+                ref_count: 1,
+            };
+            let digest = move_object.digest();
+            indirect_object = Some(move_object);
+            StoreData::IndirectObject(IndirectObjectMetadata {
+                version: move_obj.version(),
+                digest,
+            })
+        }
+        Data::Move(move_obj) => StoreData::Move(move_obj),
+    };
+    let store_object = StoreObject {
+        data,
+        owner: object.owner,
+        previous_transaction: object.previous_transaction,
+        storage_rebate: object.storage_rebate,
+    };
+    StoreObjectPair(store_object, indirect_object)
 }
 
 impl TryFrom<StoreObjectPair> for Object {
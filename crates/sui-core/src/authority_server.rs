@@ -16,11 +16,8 @@ use sui_network::{
     api::{Validator, ValidatorServer},
     tonic,
 };
+use sui_types::messages_checkpoint::{CheckpointRequest, CheckpointResponse};
 use sui_types::{error::*, messages::*, sui_system_state::SuiSystemStateInnerBenchmark};
-use sui_types::{
-    fp_ensure,
-    messages_checkpoint::{CheckpointRequest, CheckpointResponse},
-};
 use tap::TapFallible;
 use tokio::task::JoinHandle;
 use tracing::{error_span, info, Instrument};
@@ -29,6 +26,7 @@ use crate::consensus_adapter::ConnectionMonitorStatusForTests;
 use crate::{
     authority::{AuthorityState, MAX_PER_OBJECT_EXECUTION_QUEUE_LENGTH},
     consensus_adapter::{ConsensusAdapter, ConsensusAdapterMetrics},
+    transaction_precheck::TransactionPrechecker,
 };
 
 #[cfg(test)]
@@ -119,6 +117,7 @@ impl AuthorityServer {
                 state: self.state,
                 consensus_adapter: self.consensus_adapter,
                 metrics: self.metrics.clone(),
+                prechecker: Arc::new(TransactionPrechecker::new_for_tests()),
             }))
             .bind(&address)
             .await
@@ -227,18 +226,21 @@ pub struct ValidatorService {
     state: Arc<AuthorityState>,
     consensus_adapter: Arc<ConsensusAdapter>,
     metrics: Arc<ValidatorServiceMetrics>,
+    prechecker: Arc<TransactionPrechecker>,
 }
 
 impl ValidatorService {
     pub async fn new(
         state: Arc<AuthorityState>,
         consensus_adapter: Arc<ConsensusAdapter>,
+        prechecker: Arc<TransactionPrechecker>,
         prometheus_registry: &Registry,
     ) -> Result<Self> {
         Ok(Self {
             state,
             consensus_adapter,
             metrics: Arc::new(ValidatorServiceMetrics::new(prometheus_registry)),
+            prechecker,
         })
     }
 
@@ -246,20 +248,13 @@ impl ValidatorService {
         state: Arc<AuthorityState>,
         request: tonic::Request<Transaction>,
         metrics: Arc<ValidatorServiceMetrics>,
+        prechecker: Arc<TransactionPrechecker>,
     ) -> Result<tonic::Response<HandleTransactionResponse>, tonic::Status> {
         let transaction = request.into_inner();
         let epoch_store = state.load_epoch_store_one_call_per_task();
 
-        // Enforce overall transaction size limit.
-        let tx_size = bcs::serialized_size(&transaction)
-            .map_err(|e| tonic::Status::internal(e.to_string()))?;
-        let max_tx_size = epoch_store.protocol_config().max_tx_size();
-        fp_ensure!(
-            tx_size <= max_tx_size,
-            tonic::Status::resource_exhausted(format!(
-                "serialized transaction size ({tx_size}) exceeded maximum of {max_tx_size}"
-            ))
-        );
+        // Reject obviously invalid transactions before doing any expensive work on them.
+        prechecker.check(&transaction, epoch_store.protocol_config())?;
 
         let _metrics_guard = metrics.handle_transaction_latency.start_timer();
         let tx_verif_metrics_guard = metrics.tx_verification_latency.start_timer();
@@ -433,9 +428,12 @@ impl Validator for ValidatorService {
         // Spawns a task which handles the transaction. The task will unconditionally continue
         // processing in the event that the client connection is dropped.
         let metrics = self.metrics.clone();
-        spawn_monitored_task!(Self::handle_transaction(state, request, metrics))
-            .await
-            .unwrap()
+        let prechecker = self.prechecker.clone();
+        spawn_monitored_task!(Self::handle_transaction(
+            state, request, metrics, prechecker
+        ))
+        .await
+        .unwrap()
     }
 
     async fn handle_certificate(
@@ -26,7 +26,7 @@ use std::{
 
 use futures::stream::FuturesOrdered;
 use itertools::izip;
-use mysten_metrics::{spawn_monitored_task, MonitoredFutureExt};
+use mysten_metrics::{spawn_slow_poll_monitored_task, MonitoredFutureExt};
 use prometheus::Registry;
 use sui_config::node::CheckpointExecutorConfig;
 use sui_types::error::SuiResult;
@@ -44,7 +44,7 @@ use tokio::{
     time::timeout,
 };
 use tokio_stream::StreamExt;
-use tracing::{debug, error, info, instrument, warn};
+use tracing::{debug, error, field, info, instrument, warn, Span};
 use typed_store::Map;
 
 use crate::authority::authority_per_epoch_store::AuthorityPerEpochStore;
@@ -110,6 +110,12 @@ impl CheckpointExecutor {
         }
     }
 
+    /// Replaces the stream of synced checkpoints, e.g. after StateSync has been restarted. Must
+    /// not be called while an epoch is running.
+    pub fn set_mailbox(&mut self, mailbox: broadcast::Receiver<VerifiedCheckpoint>) {
+        self.mailbox = mailbox;
+    }
+
     /// Ensure that all checkpoints in the current epoch will be executed.
     /// We don't technically need &mut on self, but passing it to make sure only one instance is
     /// running at one time.
@@ -306,7 +312,7 @@ impl CheckpointExecutor {
         let tx_manager = self.tx_manager.clone();
         let accumulator = self.accumulator.clone();
 
-        pending.push_back(spawn_monitored_task!(async move {
+        pending.push_back(spawn_slow_poll_monitored_task!(async move {
             let epoch_store = epoch_store.clone();
             while let Err(err) = execute_checkpoint(
                 checkpoint.clone(),
@@ -371,7 +377,7 @@ impl CheckpointExecutor {
     }
 }
 
-#[instrument(level = "error", skip_all, fields(seq = ?checkpoint.sequence_number(), epoch = ?epoch_store.epoch()))]
+#[instrument(level = "error", skip_all, fields(seq = ?checkpoint.sequence_number(), epoch = ?epoch_store.epoch(), tx_digests = field::Empty))]
 pub async fn execute_checkpoint(
     checkpoint: VerifiedCheckpoint,
     authority_store: Arc<AuthorityStore>,
@@ -395,6 +401,11 @@ pub async fn execute_checkpoint(
             )
         })
         .into_inner();
+    // Lets a transaction be followed by its digest up to the checkpoint that finalized it.
+    Span::current().record(
+        "tx_digests",
+        field::debug(txes.iter().map(|tx| tx.transaction).collect::<Vec<_>>()),
+    );
 
     let tx_count = txes.len();
     debug!(
@@ -156,6 +156,7 @@ pub async fn test_checkpoint_executor_cross_epoch() {
 
     authority_state
         .checkpoint_store
+        .tables
         .epoch_last_checkpoint_map
         .insert(
             &end_of_epoch_0_checkpoint.summary.epoch,
@@ -164,6 +165,7 @@ pub async fn test_checkpoint_executor_cross_epoch() {
         .unwrap();
     authority_state
         .checkpoint_store
+        .tables
         .certified_checkpoints
         .insert(
             &end_of_epoch_0_checkpoint.sequence_number(),
@@ -17,7 +17,7 @@ use crate::stake_aggregator::{InsertResult, StakeAggregator};
 use crate::state_accumulator::StateAccumulator;
 use futures::future::{select, Either};
 use futures::FutureExt;
-use mysten_metrics::{monitored_scope, spawn_monitored_task, MonitoredFutureExt};
+use mysten_metrics::{monitored_scope, spawn_slow_poll_monitored_task, MonitoredFutureExt};
 use parking_lot::Mutex;
 use serde::{Deserialize, Serialize};
 
@@ -49,7 +49,7 @@ use tokio::{
     time::timeout,
 };
 use tracing::{debug, error, info, warn};
-use typed_store::rocks::{DBMap, MetricConf, TypedStoreError};
+use typed_store::rocks::{DBMap, MetricConf, RocksDB, TypedStoreError};
 use typed_store::traits::{TableSummary, TypedStoreDebug};
 use typed_store::Map;
 use typed_store_derive::DBMapUtils;
@@ -76,7 +76,7 @@ pub struct PendingCheckpoint {
 }
 
 #[derive(DBMapUtils)]
-pub struct CheckpointStore {
+pub struct CheckpointStoreTables {
     /// Maps checkpoint contents digest to checkpoint contents
     checkpoint_content: DBMap<CheckpointContentsDigest, CheckpointContents>,
 
@@ -93,18 +93,40 @@ pub struct CheckpointStore {
     watermarks: DBMap<CheckpointWatermark, (CheckpointSequenceNumber, CheckpointDigest)>,
 }
 
+pub struct CheckpointStore {
+    pub(crate) tables: CheckpointStoreTables,
+    /// The highest executed checkpoint, for waiting on checkpoints to be executed.
+    highest_executed: watch::Sender<Option<CheckpointSequenceNumber>>,
+}
+
 impl CheckpointStore {
     pub fn new(path: &Path) -> Arc<Self> {
-        Arc::new(Self::open_tables_read_write(
+        Arc::new(Self::from_tables(
+            CheckpointStoreTables::open_tables_read_write(
+                path.to_path_buf(),
+                MetricConf::default(),
+                None,
+                None,
+            ),
+        ))
+    }
+
+    pub fn open_readonly(path: &Path) -> CheckpointStoreTablesReadOnly {
+        CheckpointStoreTables::get_read_only_handle(
             path.to_path_buf(),
-            MetricConf::default(),
             None,
             None,
-        ))
+            MetricConf::default(),
+        )
     }
 
-    pub fn open_readonly(path: &Path) -> CheckpointStoreReadOnly {
-        Self::get_read_only_handle(path.to_path_buf(), None, None, MetricConf::default())
+    fn from_tables(tables: CheckpointStoreTables) -> Self {
+        let store = Self {
+            tables,
+            highest_executed: watch::channel(None).0,
+        };
+        store.notify_highest_executed().unwrap();
+        store
     }
 
     pub fn insert_genesis_checkpoint(
@@ -147,11 +169,46 @@ impl CheckpointStore {
         }
     }
 
+    /// Starts an empty store at the end of the epoch restored from a formal snapshot.
+    /// `epoch_checkpoints` are the last checkpoints of the epochs up to the restored one, whose
+    /// last checkpoint has `contents`. The checkpoints before it count as executed and pruned,
+    /// along with their transactions and the object versions they replaced, so that state sync
+    /// and execution resume from the next one.
+    pub fn insert_formal_snapshot_checkpoints(
+        &self,
+        epoch_checkpoints: &[VerifiedCheckpoint],
+        contents: CheckpointContents,
+    ) -> Result<(), TypedStoreError> {
+        let Some(checkpoint) = epoch_checkpoints.last() else {
+            return Ok(());
+        };
+        for epoch_checkpoint in epoch_checkpoints {
+            self.insert_certified_checkpoint(epoch_checkpoint.inner())?;
+        }
+        self.insert_checkpoint_contents(contents)?;
+        self.insert_verified_checkpoint(checkpoint.clone())?;
+        self.update_highest_synced_checkpoint(checkpoint)?;
+        self.update_highest_executed_checkpoint(checkpoint)?;
+        self.update_highest_pruned_checkpoint(checkpoint.sequence_number(), checkpoint.digest())?;
+        self.update_highest_pruned_transactions_checkpoint(
+            checkpoint.sequence_number(),
+            checkpoint.digest(),
+        )?;
+        if let Some(previous_digest) = checkpoint.summary.previous_digest {
+            self.tables.watermarks.insert(
+                &CheckpointWatermark::HighestPrunedCheckpoint,
+                &(checkpoint.sequence_number() - 1, previous_digest),
+            )?;
+        }
+        Ok(())
+    }
+
     pub fn get_checkpoint_by_digest(
         &self,
         digest: &CheckpointDigest,
     ) -> Result<Option<VerifiedCheckpoint>, TypedStoreError> {
-        self.checkpoint_by_digest
+        self.tables
+            .checkpoint_by_digest
             .get(digest)
             .map(|maybe_checkpoint| maybe_checkpoint.map(VerifiedCheckpoint::new_unchecked))
     }
@@ -160,13 +217,15 @@ impl CheckpointStore {
         &self,
         sequence_number: CheckpointSequenceNumber,
     ) -> Result<Option<VerifiedCheckpoint>, TypedStoreError> {
-        self.certified_checkpoints
+        self.tables
+            .certified_checkpoints
             .get(&sequence_number)
             .map(|maybe_checkpoint| maybe_checkpoint.map(VerifiedCheckpoint::new_unchecked))
     }
 
     pub fn get_latest_certified_checkpoint(&self) -> Option<VerifiedCheckpoint> {
-        self.certified_checkpoints
+        self.tables
+            .certified_checkpoints
             .iter()
             .skip_to_last()
             .next()
@@ -178,6 +237,7 @@ impl CheckpointStore {
         sequence_numbers: &[CheckpointSequenceNumber],
     ) -> Result<Vec<Option<VerifiedCheckpoint>>, TypedStoreError> {
         let checkpoints = self
+            .tables
             .certified_checkpoints
             .multi_get(sequence_numbers)?
             .into_iter()
@@ -190,8 +250,10 @@ impl CheckpointStore {
     pub fn get_highest_verified_checkpoint(
         &self,
     ) -> Result<Option<VerifiedCheckpoint>, TypedStoreError> {
-        let highest_verified = if let Some(highest_verified) =
-            self.watermarks.get(&CheckpointWatermark::HighestVerified)?
+        let highest_verified = if let Some(highest_verified) = self
+            .tables
+            .watermarks
+            .get(&CheckpointWatermark::HighestVerified)?
         {
             highest_verified
         } else {
@@ -203,7 +265,11 @@ impl CheckpointStore {
     pub fn get_highest_synced_checkpoint_seq_number(
         &self,
     ) -> Result<Option<CheckpointSequenceNumber>, TypedStoreError> {
-        if let Some(highest_synced) = self.watermarks.get(&CheckpointWatermark::HighestSynced)? {
+        if let Some(highest_synced) = self
+            .tables
+            .watermarks
+            .get(&CheckpointWatermark::HighestSynced)?
+        {
             Ok(Some(highest_synced.0))
         } else {
             Ok(None)
@@ -213,8 +279,10 @@ impl CheckpointStore {
     pub fn get_highest_synced_checkpoint(
         &self,
     ) -> Result<Option<VerifiedCheckpoint>, TypedStoreError> {
-        let highest_synced = if let Some(highest_synced) =
-            self.watermarks.get(&CheckpointWatermark::HighestSynced)?
+        let highest_synced = if let Some(highest_synced) = self
+            .tables
+            .watermarks
+            .get(&CheckpointWatermark::HighestSynced)?
         {
             highest_synced
         } else {
@@ -226,8 +294,10 @@ impl CheckpointStore {
     pub fn get_highest_executed_checkpoint_seq_number(
         &self,
     ) -> Result<Option<CheckpointSequenceNumber>, TypedStoreError> {
-        if let Some(highest_executed) =
-            self.watermarks.get(&CheckpointWatermark::HighestExecuted)?
+        if let Some(highest_executed) = self
+            .tables
+            .watermarks
+            .get(&CheckpointWatermark::HighestExecuted)?
         {
             Ok(Some(highest_executed.0))
         } else {
@@ -238,8 +308,10 @@ impl CheckpointStore {
     pub fn get_highest_executed_checkpoint(
         &self,
     ) -> Result<Option<VerifiedCheckpoint>, TypedStoreError> {
-        let highest_executed = if let Some(highest_executed) =
-            self.watermarks.get(&CheckpointWatermark::HighestExecuted)?
+        let highest_executed = if let Some(highest_executed) = self
+            .tables
+            .watermarks
+            .get(&CheckpointWatermark::HighestExecuted)?
         {
             highest_executed
         } else {
@@ -251,7 +323,8 @@ impl CheckpointStore {
     pub fn get_highest_pruned_checkpoint_seq_number(
         &self,
     ) -> Result<Option<CheckpointSequenceNumber>, TypedStoreError> {
-        self.watermarks
+        self.tables
+            .watermarks
             .get(&CheckpointWatermark::HighestPruned)?
             .map(|(sequence_number, _)| Ok(sequence_number))
             .transpose()
@@ -261,7 +334,7 @@ impl CheckpointStore {
         &self,
         digest: &CheckpointContentsDigest,
     ) -> Result<Option<CheckpointContents>, TypedStoreError> {
-        self.checkpoint_content.get(digest)
+        self.tables.checkpoint_content.get(digest)
     }
 
     pub fn insert_certified_checkpoint(
@@ -269,19 +342,20 @@ impl CheckpointStore {
         checkpoint: &CertifiedCheckpointSummary,
     ) -> Result<(), TypedStoreError> {
         let mut batch = self
+            .tables
             .certified_checkpoints
             .batch()
             .insert_batch(
-                &self.certified_checkpoints,
+                &self.tables.certified_checkpoints,
                 [(&checkpoint.sequence_number(), checkpoint)],
             )?
             .insert_batch(
-                &self.checkpoint_by_digest,
+                &self.tables.checkpoint_by_digest,
                 [(&checkpoint.digest(), checkpoint)],
             )?;
         if checkpoint.next_epoch_committee().is_some() {
             batch = batch.insert_batch(
-                &self.epoch_last_checkpoint_map,
+                &self.tables.epoch_last_checkpoint_map,
                 [(&checkpoint.epoch(), &checkpoint.sequence_number())],
             )?;
         }
@@ -300,7 +374,7 @@ impl CheckpointStore {
                 .get_highest_verified_checkpoint()?
                 .map(|x| x.sequence_number())
         {
-            self.watermarks.insert(
+            self.tables.watermarks.insert(
                 &CheckpointWatermark::HighestVerified,
                 &(checkpoint.sequence_number(), checkpoint.digest()),
             )?;
@@ -313,7 +387,7 @@ impl CheckpointStore {
         &self,
         checkpoint: &VerifiedCheckpoint,
     ) -> Result<(), TypedStoreError> {
-        self.watermarks.insert(
+        self.tables.watermarks.insert(
             &CheckpointWatermark::HighestSynced,
             &(checkpoint.sequence_number(), checkpoint.digest()),
         )
@@ -325,29 +399,164 @@ impl CheckpointStore {
     ) -> Result<(), TypedStoreError> {
         match self.get_highest_executed_checkpoint_seq_number()? {
             Some(seq_number) if seq_number > checkpoint.sequence_number() => Ok(()),
-            _ => self.watermarks.insert(
-                &CheckpointWatermark::HighestExecuted,
-                &(checkpoint.sequence_number(), checkpoint.digest()),
-            ),
+            _ => {
+                self.tables.watermarks.insert(
+                    &CheckpointWatermark::HighestExecuted,
+                    &(checkpoint.sequence_number(), checkpoint.digest()),
+                )?;
+                self.highest_executed
+                    .send_replace(Some(checkpoint.sequence_number()));
+                Ok(())
+            }
         }
     }
 
+    /// Receives the sequence number of the highest executed checkpoint each time it changes, or
+    /// `None` before the first one is executed.
+    pub fn subscribe_highest_executed_checkpoint(
+        &self,
+    ) -> watch::Receiver<Option<CheckpointSequenceNumber>> {
+        self.highest_executed.subscribe()
+    }
+
+    /// Passes the highest executed checkpoint of the tables on to the subscribers, e.g. after
+    /// catching up with the writes of the primary instance.
+    fn notify_highest_executed(&self) -> Result<(), TypedStoreError> {
+        let highest_executed = self.get_highest_executed_checkpoint_seq_number()?;
+        self.highest_executed.send_if_modified(|current| {
+            let modified = *current != highest_executed;
+            *current = highest_executed;
+            modified
+        });
+        Ok(())
+    }
+
+    /// Opens the checkpoint store of another node as a secondary instance, for serving reads
+    /// only.
+    pub fn open_secondary(path: &Path, secondary_path: &Path) -> Arc<Self> {
+        Arc::new(Self::from_tables(
+            CheckpointStoreTables::open_tables_secondary(
+                path.to_path_buf(),
+                secondary_path.to_path_buf(),
+                MetricConf::default(),
+                None,
+            ),
+        ))
+    }
+
+    /// Makes the writes of the primary instance visible, when opened with
+    /// [`Self::open_secondary`].
+    pub fn try_catch_up_with_primary(&self) -> Result<(), TypedStoreError> {
+        self.tables.watermarks.try_catch_up_with_primary()?;
+        self.notify_highest_executed()
+    }
+
+    /// Flushes the memtables of all tables to disk.
+    pub fn flush(&self) -> Result<(), TypedStoreError> {
+        self.tables.checkpoint_content.flush()?;
+        self.tables.certified_checkpoints.flush()?;
+        self.tables.checkpoint_by_digest.flush()?;
+        self.tables.epoch_last_checkpoint_map.flush()?;
+        self.tables.watermarks.flush()?;
+        Ok(())
+    }
+
+    /// Compacts the whole of table `table_name`, blocking until it is done.
+    pub fn compact_table(&self, table_name: &str) -> Result<(), TypedStoreError> {
+        self.tables
+            .checkpoint_content
+            .rocksdb
+            .compact_cf(table_name)
+    }
+
+    /// The database holding the store, e.g. to back it up.
+    pub fn rocksdb(&self) -> &Arc<RocksDB> {
+        &self.tables.checkpoint_content.rocksdb
+    }
+
     pub fn update_highest_pruned_checkpoint(
         &self,
         sequence_number: CheckpointSequenceNumber,
         digest: CheckpointDigest,
     ) -> Result<(), TypedStoreError> {
-        self.watermarks.insert(
+        self.tables.watermarks.insert(
             &CheckpointWatermark::HighestPruned,
             &(sequence_number, digest),
         )
     }
 
+    pub fn get_highest_pruned_transactions_checkpoint_seq_number(
+        &self,
+    ) -> Result<Option<CheckpointSequenceNumber>, TypedStoreError> {
+        self.tables
+            .watermarks
+            .get(&CheckpointWatermark::HighestPrunedTransactions)?
+            .map(|(sequence_number, _)| Ok(sequence_number))
+            .transpose()
+    }
+
+    pub fn update_highest_pruned_transactions_checkpoint(
+        &self,
+        sequence_number: CheckpointSequenceNumber,
+        digest: CheckpointDigest,
+    ) -> Result<(), TypedStoreError> {
+        self.tables.watermarks.insert(
+            &CheckpointWatermark::HighestPrunedTransactions,
+            &(sequence_number, digest),
+        )
+    }
+
+    /// Lowest checkpoint whose summary and contents have not been pruned.
+    pub fn get_lowest_available_checkpoint(
+        &self,
+    ) -> Result<CheckpointSequenceNumber, TypedStoreError> {
+        Ok(self
+            .tables
+            .watermarks
+            .get(&CheckpointWatermark::HighestPrunedCheckpoint)?
+            .map_or(0, |(sequence_number, _)| sequence_number + 1))
+    }
+
+    /// Deletes the summaries and contents of `checkpoints`, which must be consecutive and follow
+    /// the checkpoints pruned so far.
+    pub fn prune_checkpoints(
+        &self,
+        checkpoints: &[CertifiedCheckpointSummary],
+    ) -> Result<(), TypedStoreError> {
+        let Some(last) = checkpoints.last() else {
+            return Ok(());
+        };
+        self.tables
+            .certified_checkpoints
+            .batch()
+            .delete_batch(
+                &self.tables.certified_checkpoints,
+                checkpoints.iter().map(|c| c.sequence_number()),
+            )?
+            .delete_batch(
+                &self.tables.checkpoint_by_digest,
+                checkpoints.iter().map(|c| c.digest()),
+            )?
+            .delete_batch(
+                &self.tables.checkpoint_content,
+                checkpoints.iter().map(|c| c.content_digest()),
+            )?
+            .insert_batch(
+                &self.tables.watermarks,
+                [(
+                    CheckpointWatermark::HighestPrunedCheckpoint,
+                    (last.sequence_number(), last.digest()),
+                )],
+            )?
+            .write()
+    }
+
     pub fn insert_checkpoint_contents(
         &self,
         contents: CheckpointContents,
     ) -> Result<(), TypedStoreError> {
-        self.checkpoint_content
+        self.tables
+            .checkpoint_content
             .insert(&contents.digest(), &contents)
     }
 
@@ -355,7 +564,7 @@ impl CheckpointStore {
         &self,
         epoch_id: EpochId,
     ) -> SuiResult<Option<VerifiedCheckpoint>> {
-        let seq = self.epoch_last_checkpoint_map.get(&epoch_id)?;
+        let seq = self.tables.epoch_last_checkpoint_map.get(&epoch_id)?;
         let checkpoint = match seq {
             Some(seq) => self.get_checkpoint_by_sequence_number(seq)?,
             None => None,
@@ -395,7 +604,12 @@ pub enum CheckpointWatermark {
     HighestVerified,
     HighestSynced,
     HighestExecuted,
+    /// Highest checkpoint whose old object versions have been pruned.
     HighestPruned,
+    /// Highest checkpoint whose transactions, effects and events have been pruned.
+    HighestPrunedTransactions,
+    /// Highest checkpoint that has been pruned itself.
+    HighestPrunedCheckpoint,
 }
 
 pub struct CheckpointBuilder {
@@ -738,22 +952,23 @@ impl CheckpointBuilder {
                     .in_monitored_scope("CheckpointBuilder::digest_epoch")
                     .await?;
                 self.metrics.highest_accumulated_epoch.set(epoch as i64);
-
-                // for now, just log this value. Later it must be included in
-                // EndOfEpochData::epoch_commitments
                 info!("Epoch {epoch} root state hash digest: {root_state_digest:?}");
 
+                let epoch_commitments = if self
+                    .epoch_store
+                    .protocol_config()
+                    .commit_root_state_digest()
+                {
+                    vec![root_state_digest.into()]
+                } else {
+                    vec![]
+                };
                 Some(EndOfEpochData {
                     next_epoch_committee: committee.voting_rights,
                     next_epoch_protocol_version: ProtocolVersion::new(
                         system_state_obj.protocol_version(),
                     ),
-                    // TODO: This should become
-                    //
-                    //   epoch_commitments: vec![root_state_digest.into()]
-                    //
-                    // When the accumulator is deemed stable.
-                    epoch_commitments: vec![],
+                    epoch_commitments,
                 })
             } else {
                 None
@@ -773,7 +988,7 @@ impl CheckpointBuilder {
                 .unwrap_or(num_txns);
 
             let previous_digest = last_checkpoint.as_ref().map(|(_, c)| c.digest());
-            let summary = CheckpointSummary::new(
+            let mut summary = CheckpointSummary::new(
                 epoch,
                 sequence_number,
                 network_total_transactions,
@@ -783,6 +998,15 @@ impl CheckpointBuilder {
                 end_of_epoch_data,
                 timestamp_ms,
             );
+            if self
+                .epoch_store
+                .protocol_config()
+                .commit_to_transactions_merkle_root()
+            {
+                summary
+                    .checkpoint_commitments
+                    .push(contents.transactions_merkle_root().into());
+            }
             if last_checkpoint_of_epoch {
                 info!(
                     ?sequence_number,
@@ -1133,7 +1357,7 @@ impl CheckpointService {
             max_checkpoint_size,
         );
 
-        spawn_monitored_task!(builder.run());
+        spawn_slow_poll_monitored_task!(builder.run());
 
         let aggregator = CheckpointAggregator::new(
             checkpoint_store.clone(),
@@ -1144,7 +1368,7 @@ impl CheckpointService {
             metrics.clone(),
         );
 
-        spawn_monitored_task!(aggregator.run());
+        spawn_slow_poll_monitored_task!(aggregator.run());
 
         let last_signature_index = epoch_store.get_last_checkpoint_signature_index();
         let last_signature_index = Mutex::new(last_signature_index);
@@ -1489,6 +1713,34 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_subscribe_highest_executed_checkpoint() {
+        let tempdir = tempdir().unwrap();
+        let dir = tempfile::TempDir::new().unwrap();
+        let network_config = sui_config::builder::ConfigBuilder::new(&dir).build();
+        let checkpoint = network_config.genesis.checkpoint();
+
+        let store = CheckpointStore::new(tempdir.path());
+        let mut highest_executed = store.subscribe_highest_executed_checkpoint();
+        assert_eq!(*highest_executed.borrow(), None);
+        store
+            .insert_verified_checkpoint(checkpoint.clone())
+            .unwrap();
+        store
+            .update_highest_executed_checkpoint(&checkpoint)
+            .unwrap();
+        highest_executed.changed().await.unwrap();
+        assert_eq!(*highest_executed.borrow(), Some(0));
+
+        // A reopened store starts from the highest executed checkpoint in its tables.
+        drop(store);
+        let store = CheckpointStore::new(tempdir.path());
+        assert_eq!(
+            *store.subscribe_highest_executed_checkpoint().borrow(),
+            Some(0)
+        );
+    }
+
     #[async_trait::async_trait]
     impl CertifiedCheckpointOutput for mpsc::Sender<CertifiedCheckpointSummary> {
         async fn certified_checkpoint_created(
@@ -46,7 +46,7 @@ use sui_simulator::narwhal_network::connectivity::ConnectionStatus;
 use sui_types::base_types::AuthorityName;
 use sui_types::messages::ConsensusTransactionKind;
 use tokio::time::Duration;
-use tracing::{debug, error, info, warn};
+use tracing::{debug, error, error_span, info, warn, Instrument};
 
 #[cfg(test)]
 #[path = "unit_tests/consensus_tests.rs"]
@@ -190,8 +190,9 @@ pub trait CheckConnection: Send + Sync {
 }
 
 pub struct ConnectionMonitorStatus {
-    /// Current connection statuses forwarded from the connection monitor
-    pub connection_statuses: Arc<DashMap<PeerId, ConnectionStatus>>,
+    /// Current connection statuses forwarded from the connection monitor. Replaced when the
+    /// p2p network is restarted with a new connection monitor.
+    pub connection_statuses: ArcSwap<DashMap<PeerId, ConnectionStatus>>,
     /// A map from authority name to peer id
     pub authority_names_to_peer_ids: ArcSwap<HashMap<AuthorityName, PeerId>>,
 }
@@ -348,9 +349,16 @@ impl ConsensusAdapter {
         epoch_store: &Arc<AuthorityPerEpochStore>,
     ) -> JoinHandle<()> {
         // Reconfiguration lock is dropped when pending_consensus_transactions is persisted, before it is handled by consensus
+        let span = match &transaction.kind {
+            ConsensusTransactionKind::UserTransaction(certificate) => {
+                error_span!("consensus_adapter", tx_digest = ?certificate.digest())
+            }
+            _ => error_span!("consensus_adapter", key = ?transaction.key()),
+        };
         let async_stage = self
             .clone()
-            .submit_and_wait(transaction, epoch_store.clone());
+            .submit_and_wait(transaction, epoch_store.clone())
+            .instrument(span);
         // Number of this tasks is limited by `sequencing_certificate_inflight` limit
         let join_handle = spawn_monitored_task!(async_stage);
         join_handle
@@ -533,7 +541,8 @@ impl CheckConnection for ConnectionMonitorStatus {
             }
         };
 
-        let res = match self.connection_statuses.try_get(peer_id) {
+        let connection_statuses = self.connection_statuses.load();
+        let res = match connection_statuses.try_get(peer_id) {
             TryResult::Present(c) => Some(c.value().clone()),
             TryResult::Absent => None,
             TryResult::Locked => {
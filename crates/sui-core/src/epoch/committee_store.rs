@@ -3,11 +3,12 @@
 
 use rocksdb::Options;
 use std::path::PathBuf;
+use std::sync::Arc;
 use sui_storage::default_db_options;
 use sui_types::base_types::ObjectID;
 use sui_types::committee::{Committee, EpochId};
 use sui_types::error::{SuiError, SuiResult};
-use typed_store::rocks::{DBMap, DBOptions, MetricConf};
+use typed_store::rocks::{DBMap, DBOptions, MetricConf, RocksDB};
 use typed_store::traits::{TableSummary, TypedStoreDebug};
 
 use typed_store::Map;
@@ -39,6 +40,21 @@ impl CommitteeStore {
         committee_store
     }
 
+    /// Opens the committee store of another node as a secondary instance, for serving reads only.
+    pub fn open_secondary(path: PathBuf, secondary_path: PathBuf) -> Self {
+        Self::open_tables_secondary(path, secondary_path, MetricConf::default(), None)
+    }
+
+    /// Makes the writes of the primary instance visible, when opened with [`Self::open_secondary`].
+    pub fn try_catch_up_with_primary(&self) -> SuiResult {
+        Ok(self.committee_map.try_catch_up_with_primary()?)
+    }
+
+    /// The database holding the store, e.g. to back it up.
+    pub fn rocksdb(&self) -> &Arc<RocksDB> {
+        &self.committee_map.rocksdb
+    }
+
     pub fn new_for_testing(genesis_committee: &Committee) -> Self {
         let dir = std::env::temp_dir();
         let path = dir.join(format!("DB_{:?}", nondeterministic!(ObjectID::random())));
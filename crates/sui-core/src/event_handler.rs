@@ -3,41 +3,51 @@
 
 use core::time::Duration;
 use move_bytecode_utils::module_cache::SyncModuleCache;
-use std::sync::Arc;
+use std::sync::{Arc, Weak};
 
-use tokio_stream::Stream;
-use tracing::{debug, error, instrument, trace, warn};
+use futures::stream::BoxStream;
+use prometheus::Registry;
+use tracing::{debug, error, info, instrument, trace, warn};
 
 use sui_json_rpc_types::SuiMoveStruct;
 use sui_storage::event_store::{EventStore, EventStoreType};
 use sui_types::base_types::TransactionDigest;
-use sui_types::filter::EventFilter;
-use sui_types::messages::TransactionEvents;
+use sui_types::filter::{EventFilter, ExecutedTransaction, TransactionFilter};
+use sui_types::messages::{TransactionData, TransactionEvents};
 use sui_types::{
     error::{SuiError, SuiResult},
     event::{Event, EventEnvelope},
     messages::{TransactionEffects, TransactionEffectsAPI},
 };
 
-use crate::authority::{AuthorityStore, ResolverWrapper};
-use crate::streamer::Streamer;
+use crate::authority::{AuthorityState, AuthorityStore, ResolverWrapper};
+use crate::streamer::{SlowSubscriberPolicy, Streamer, StreamerMetrics};
 
 #[cfg(test)]
 #[path = "unit_tests/event_handler_tests.rs"]
 mod event_handler_tests;
 
 pub const EVENT_DISPATCH_BUFFER_SIZE: usize = 1000;
+/// How often newly executed checkpoints are recorded in the event store.
+const EVENT_CHECKPOINTS_INTERVAL: Duration = Duration::from_secs(1);
 
 pub struct EventHandler {
     event_streamer: Streamer<EventEnvelope, EventFilter>,
+    transaction_streamer: Streamer<ExecutedTransaction, TransactionFilter>,
     pub(crate) event_store: Arc<EventStoreType>,
 }
 
 impl EventHandler {
-    pub fn new(event_store: Arc<EventStoreType>) -> Self {
-        let streamer = Streamer::spawn(EVENT_DISPATCH_BUFFER_SIZE);
+    pub fn new(event_store: Arc<EventStoreType>, registry: &Registry) -> Self {
+        let metrics = StreamerMetrics::new(registry);
+        let streamer = Streamer::spawn(EVENT_DISPATCH_BUFFER_SIZE, &metrics, "event");
         Self {
             event_streamer: streamer,
+            transaction_streamer: Streamer::spawn(
+                EVENT_DISPATCH_BUFFER_SIZE,
+                &metrics,
+                "transaction",
+            ),
             event_store,
         }
     }
@@ -142,7 +152,67 @@ impl EventHandler {
         ))
     }
 
-    pub fn subscribe(&self, filter: EventFilter) -> impl Stream<Item = EventEnvelope> {
-        self.event_streamer.subscribe(filter)
+    pub fn subscribe(
+        &self,
+        filter: EventFilter,
+        policy: SlowSubscriberPolicy,
+    ) -> BoxStream<'static, EventEnvelope> {
+        self.event_streamer.subscribe(filter, policy)
+    }
+
+    /// Dispatches an executed transaction to the matching transaction subscriptions.
+    pub async fn process_transaction(&self, data: TransactionData, effects: TransactionEffects) {
+        if let Err(e) = self
+            .transaction_streamer
+            .send(ExecutedTransaction { data, effects })
+            .await
+        {
+            error!(error =? e, "Failed to send ExecutedTransaction to dispatch");
+        }
+    }
+
+    pub fn subscribe_transactions(
+        &self,
+        filter: TransactionFilter,
+        policy: SlowSubscriberPolicy,
+    ) -> BoxStream<'static, ExecutedTransaction> {
+        self.transaction_streamer.subscribe(filter, policy)
+    }
+}
+
+/// Records the checkpoint of each executed transaction in the event store, so that events can be
+/// queried by checkpoint.
+pub async fn event_checkpoints_process(authority_state: Weak<AuthorityState>) {
+    info!("Starting event checkpoints process.");
+    let mut next = None;
+    loop {
+        tokio::time::sleep(EVENT_CHECKPOINTS_INTERVAL).await;
+        let authority = if let Some(authority) = authority_state.upgrade() {
+            authority
+        } else {
+            info!("Authority state has shutdown. Exiting ...");
+            return;
+        };
+
+        let start = match next {
+            Some(next) => next,
+            // Recording is idempotent, so the highest recorded checkpoint is recorded again in
+            // case it was only recorded in part. Checkpoints executed before events were recorded
+            // at all are left out.
+            None => match authority.get_event_checkpoints_start().await {
+                Ok(start) => start,
+                Err(e) => {
+                    warn!("Failed to find the checkpoint to record events from: {e}");
+                    continue;
+                }
+            },
+        };
+        match authority.record_event_checkpoints(start).await {
+            Ok(recorded_to) => next = Some(recorded_to),
+            Err(e) => {
+                warn!(start, "Failed to record the checkpoints of events: {e}");
+                next = Some(start);
+            }
+        }
     }
 }
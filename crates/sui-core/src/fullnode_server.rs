@@ -0,0 +1,122 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use async_trait::async_trait;
+use std::sync::Arc;
+use sui_network::{api::Fullnode, tonic};
+use sui_types::messages::{
+    ExecuteTransactionRequest, ExecuteTransactionResponse, GetObjectRequest, GetObjectResponse,
+    GetTransactionResponse, TransactionEffectsAPI, TransactionEvents, TransactionInfoRequest,
+};
+use sui_types::messages_checkpoint::{CheckpointRequest, CheckpointResponse};
+use sui_types::quorum_driver_types::QuorumDriverError;
+
+use crate::authority::AuthorityState;
+use crate::authority_client::NetworkAuthorityClient;
+use crate::transaction_orchestrator::TransactiondOrchestrator;
+
+/// Serves the reads of the JSON-RPC API most used by programs, and transaction execution, over
+/// gRPC with BCS payloads, which spares consumers the cost of JSON serialization.
+pub struct FullnodeService {
+    state: Arc<AuthorityState>,
+    transaction_orchestrator: Arc<TransactiondOrchestrator<NetworkAuthorityClient>>,
+}
+
+impl FullnodeService {
+    pub fn new(
+        state: Arc<AuthorityState>,
+        transaction_orchestrator: Arc<TransactiondOrchestrator<NetworkAuthorityClient>>,
+    ) -> Self {
+        Self {
+            state,
+            transaction_orchestrator,
+        }
+    }
+}
+
+#[async_trait]
+impl Fullnode for FullnodeService {
+    async fn get_object(
+        &self,
+        request: tonic::Request<GetObjectRequest>,
+    ) -> Result<tonic::Response<GetObjectResponse>, tonic::Status> {
+        let GetObjectRequest { object_id, version } = request.into_inner();
+
+        let store = self.state.db();
+        let object = match version {
+            Some(version) => store.get_object_by_key(&object_id, version)?,
+            None => store.get_object(&object_id)?,
+        };
+
+        Ok(tonic::Response::new(GetObjectResponse { object }))
+    }
+
+    async fn get_transaction(
+        &self,
+        request: tonic::Request<TransactionInfoRequest>,
+    ) -> Result<tonic::Response<GetTransactionResponse>, tonic::Status> {
+        let digest = request.into_inner().transaction_digest;
+
+        let (transaction, effects) = self
+            .state
+            .get_executed_transaction_and_effects(digest)
+            .await
+            .map_err(|e| tonic::Status::not_found(e.to_string()))?;
+        let events = match effects.events_digest() {
+            Some(digest) => self.state.get_transaction_events(*digest).await?,
+            None => TransactionEvents::default(),
+        };
+        let checkpoint = self
+            .state
+            .get_transaction_checkpoint(&digest)?
+            .map(|checkpoint| checkpoint.summary.sequence_number);
+
+        Ok(tonic::Response::new(GetTransactionResponse {
+            transaction: transaction.into_inner(),
+            effects,
+            events,
+            checkpoint,
+        }))
+    }
+
+    async fn get_checkpoint(
+        &self,
+        request: tonic::Request<CheckpointRequest>,
+    ) -> Result<tonic::Response<CheckpointResponse>, tonic::Status> {
+        let request = request.into_inner();
+
+        let response = self.state.handle_checkpoint_request(&request)?;
+
+        Ok(tonic::Response::new(response))
+    }
+
+    async fn execute_transaction(
+        &self,
+        request: tonic::Request<ExecuteTransactionRequest>,
+    ) -> Result<tonic::Response<ExecuteTransactionResponse>, tonic::Status> {
+        let request = request.into_inner();
+
+        let response = self
+            .transaction_orchestrator
+            .execute_transaction(request)
+            .await
+            .map_err(quorum_driver_status)?;
+
+        Ok(tonic::Response::new(response))
+    }
+}
+
+/// The status of a failed execution, with the error in BCS as its details for clients to decode.
+fn quorum_driver_status(error: QuorumDriverError) -> tonic::Status {
+    let code = match error {
+        QuorumDriverError::InvalidUserSignature(_) => tonic::Code::InvalidArgument,
+        QuorumDriverError::TimeoutBeforeFinality => tonic::Code::DeadlineExceeded,
+        _ => tonic::Code::Internal,
+    };
+    match bcs::to_bytes(&error) {
+        Ok(bytes) => tonic::Status::with_details(code, error.to_string(), bytes.into()),
+        Err(e) => {
+            tonic::Status::internal(format!("Unable to serialize execution error {error}: {e}"))
+        }
+    }
+}
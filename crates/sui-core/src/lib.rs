@@ -15,6 +15,7 @@ pub mod consensus_validator;
 pub mod epoch;
 pub mod event_handler;
 mod execution_driver;
+pub mod fullnode_server;
 pub mod metrics;
 pub mod module_cache_metrics;
 pub mod narwhal_manager;
@@ -28,8 +29,9 @@ pub mod streamer;
 pub mod tbls;
 pub mod test_utils;
 pub mod transaction_input_checker;
-mod transaction_manager;
+pub mod transaction_manager;
 pub mod transaction_orchestrator;
+pub mod transaction_precheck;
 
 #[cfg(test)]
 #[path = "unit_tests/pay_sui_tests.rs"]
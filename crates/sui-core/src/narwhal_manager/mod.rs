@@ -17,6 +17,7 @@ use prometheus::{register_int_gauge_with_registry, IntGauge, Registry};
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Instant;
+use sui_protocol_config::ProtocolConfig;
 use sui_types::crypto::{AuthorityKeyPair, NetworkKeyPair};
 use tokio::sync::Mutex;
 
@@ -110,11 +111,18 @@ impl NarwhalManager {
         }
     }
 
+    // Replaces the network key pair of the primary, which takes effect the next time Narwhal is
+    // started.
+    pub fn set_network_keypair(&mut self, network_keypair: NetworkKeyPair) {
+        self.network_keypair = network_keypair;
+    }
+
     // Starts the Narwhal (primary & worker(s)) - if not already running.
     pub async fn start<State, TxValidator: TransactionValidator>(
         &self,
         committee: Committee,
         worker_cache: WorkerCache,
+        protocol_config: ProtocolConfig,
         execution_state: Arc<State>,
         tx_validator: TxValidator,
     ) where
@@ -152,6 +160,7 @@ impl NarwhalManager {
                     committee.clone(),
                     worker_cache.clone(),
                     &store,
+                    protocol_config.clone(),
                     execution_state.clone(),
                 )
                 .await
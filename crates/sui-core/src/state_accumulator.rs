@@ -3,21 +3,27 @@
 
 use mysten_metrics::monitored_scope;
 use sui_types::committee::EpochId;
-use tracing::debug;
+use tracing::{debug, info};
 use typed_store::Map;
 
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 use fastcrypto::hash::MultisetHash;
 use sui_types::accumulator::Accumulator;
-use sui_types::error::SuiResult;
+use sui_types::error::{SuiError, SuiResult};
 use sui_types::messages::{TransactionEffects, TransactionEffectsAPI};
 use sui_types::messages_checkpoint::{CheckpointSequenceNumber, ECMHLiveObjectSetDigest};
 use typed_store::rocks::TypedStoreError;
 
 use crate::authority::authority_per_epoch_store::AuthorityPerEpochStore;
+use crate::authority::authority_store_tables::AuthorityPerpetualTables;
 use crate::authority::AuthorityStore;
 
+/// The prefix of the name of the checkpoints of the database whose live object set the root state
+/// hash of an epoch is reset to, followed by the epoch.
+const ROOT_STATE_RESET_PREFIX: &str = "root_state_reset_epoch_";
+
 pub struct StateAccumulator {
     authority_store: Arc<AuthorityStore>,
 }
@@ -40,25 +46,33 @@ impl StateAccumulator {
             return Ok(acc);
         }
 
+        // The live object set gains the versions written by each transaction, and loses the
+        // versions it modified, whether it mutated, wrapped or deleted them.
         let mut acc = Accumulator::default();
-
-        acc.insert_all(
-            effects
-                .iter()
-                .flat_map(|fx| fx.created().iter().map(|(obj_ref, _)| obj_ref.2)),
-        );
-        acc.remove_all(
-            effects
-                .iter()
-                .flat_map(|fx| fx.deleted().iter().map(|obj_ref| obj_ref.2)),
-        );
-
-        // TODO almost certainly not currectly handling "mutated" effects.
-        acc.insert_all(
-            effects
-                .iter()
-                .flat_map(|fx| fx.mutated().iter().map(|(obj_ref, _)| obj_ref.2)),
-        );
+        acc.insert_all(effects.iter().flat_map(|fx| {
+            fx.all_mutated()
+                .into_iter()
+                .map(|(obj_ref, _, _)| obj_ref.2)
+        }));
+        // The digests of the modified versions are read from the parent sync index, since the
+        // pruner removes old versions of objects, and a store restored from a formal snapshot
+        // only has the versions that were live.
+        for fx in &effects {
+            for (object_id, version) in fx.modified_at_versions() {
+                let ((_, _, digest), _) = self
+                    .authority_store
+                    .get_parent_iterator(*object_id, Some(*version))?
+                    .next()
+                    .ok_or_else(|| {
+                        SuiError::GenericStorageError(format!(
+                            "Object {object_id} at version {version} modified by transaction {} \
+                             is not in the parent sync index",
+                            fx.transaction_digest()
+                        ))
+                    })?;
+                acc.remove(digest);
+            }
+        }
 
         epoch_store.insert_state_hash_for_checkpoint(&checkpoint_seq_num, &acc)?;
         debug!("Accumulated checkpoint {}", checkpoint_seq_num);
@@ -89,6 +103,8 @@ impl StateAccumulator {
             return Ok(acc);
         }
 
+        self.wait_for_root_state_hash_reset().await;
+
         // Get the next checkpoint to accumulate (first checkpoint of the epoch)
         // by adding 1 to the highest checkpoint of the previous epoch
         let (_, (next_to_accumulate, mut root_state_hash)) = self
@@ -155,6 +171,129 @@ impl StateAccumulator {
         Ok(root_state_hash)
     }
 
+    /// Accumulates the live object set of the store from scratch, which scans all of its objects.
+    pub fn accumulate_live_object_set(&self) -> Accumulator {
+        let mut acc = Accumulator::default();
+        acc.insert_all(
+            self.authority_store
+                .iter_live_object_set()
+                .map(|obj_ref| obj_ref.2),
+        );
+        acc
+    }
+
+    /// Replaces the root state hash of `epoch`, which must have been accumulated, with the
+    /// accumulation of the live object set, which must be the state at the end of `epoch`.
+    /// Epochs accumulated before the modified versions of objects were removed from the
+    /// accumulators of checkpoints have a root state hash that does not match the live object
+    /// set, so it is reset before the first epoch that commits to it.
+    ///
+    /// Only a checkpoint of the database is taken here, under the execution lock. Its live object
+    /// set is accumulated in the background, and [`Self::accumulate_epoch`] waits for it before
+    /// accumulating the next epoch.
+    pub fn reset_root_state_hash(&self, epoch: &EpochId) -> SuiResult {
+        if self
+            .authority_store
+            .perpetual_tables
+            .root_state_hash_by_epoch
+            .get(epoch)?
+            .is_none()
+        {
+            return Err(SuiError::GenericStorageError(format!(
+                "The root state hash of epoch {epoch} has not been accumulated"
+            )));
+        }
+        let path = self
+            .authority_store
+            .perpetual_tables
+            .objects
+            .rocksdb
+            .checkpoint(Path::new(&format!("{ROOT_STATE_RESET_PREFIX}{epoch}")))?;
+        self.spawn_root_state_hash_reset(*epoch, path);
+        Ok(())
+    }
+
+    /// Resumes the reset of the root state hash of the last accumulated epoch, if the node
+    /// stopped before it was written.
+    pub fn resume_root_state_hash_reset(&self) {
+        if let Some(epoch) = self.last_accumulated_epoch() {
+            let path = self.root_state_reset_path(epoch);
+            if path.exists() {
+                info!("Resuming the reset of the root state hash of epoch {epoch}");
+                self.spawn_root_state_hash_reset(epoch, path);
+            }
+        }
+    }
+
+    /// Accumulates the live object set of the checkpoint of the database at `path`, writes it as
+    /// the root state hash of `epoch`, and then deletes the checkpoint.
+    fn spawn_root_state_hash_reset(&self, epoch: EpochId, path: PathBuf) {
+        let authority_store = self.authority_store.clone();
+        tokio::task::spawn_blocking(move || {
+            let _scope = monitored_scope("ResetRootStateHash");
+            let mut root_state_hash = Accumulator::default();
+            root_state_hash.insert_all(
+                AuthorityPerpetualTables::open_checkpoint(&path)
+                    .iter_live_object_set()
+                    .map(|obj_ref| obj_ref.2),
+            );
+            let root_state_hash_by_epoch =
+                &authority_store.perpetual_tables.root_state_hash_by_epoch;
+            let (last_checkpoint_of_epoch, _) = root_state_hash_by_epoch
+                .get(&epoch)
+                .expect("Failed to read the root state hash")
+                .expect("The root state hash being reset has been accumulated");
+            root_state_hash_by_epoch
+                .insert(&epoch, &(last_checkpoint_of_epoch, root_state_hash.clone()))
+                .expect("Failed to reset the root state hash");
+            // Deleted once the reset is written, as the reset is pending while it exists.
+            std::fs::remove_dir_all(&path).unwrap_or_else(|e| {
+                panic!("Failed to delete {}: {e}", path.display());
+            });
+            authority_store
+                .root_state_notify_read
+                .notify(&epoch, &(last_checkpoint_of_epoch, root_state_hash));
+            debug!("Reset the root state hash of epoch {epoch} to the live object set");
+        });
+    }
+
+    /// Waits for the reset of the root state hash of the last accumulated epoch, if one is
+    /// pending, see [`Self::reset_root_state_hash`].
+    async fn wait_for_root_state_hash_reset(&self) {
+        let Some(epoch) = self.last_accumulated_epoch() else {
+            return;
+        };
+        // Registered before checking for the checkpoint of the reset, which is deleted once the
+        // reset is written and before it is notified.
+        let registration = self
+            .authority_store
+            .root_state_notify_read
+            .register_one(&epoch);
+        if self.root_state_reset_path(epoch).exists() {
+            debug!("Awaiting the reset of the root state hash of epoch {epoch}");
+            registration.await;
+        }
+    }
+
+    fn last_accumulated_epoch(&self) -> Option<EpochId> {
+        self.authority_store
+            .perpetual_tables
+            .root_state_hash_by_epoch
+            .iter()
+            .skip_to_last()
+            .next()
+            .map(|(epoch, _)| epoch)
+    }
+
+    fn root_state_reset_path(&self, epoch: EpochId) -> PathBuf {
+        self.authority_store
+            .perpetual_tables
+            .objects
+            .rocksdb
+            .path()
+            .join(format!("{ROOT_STATE_RESET_PREFIX}{epoch}"))
+    }
+
     pub async fn digest_epoch(
         &self,
         epoch: &EpochId,
@@ -1,28 +1,93 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::event_handler::EVENT_DISPATCH_BUFFER_SIZE;
-use futures::Stream;
+use futures::stream::BoxStream;
+use futures::StreamExt;
 use mysten_metrics::spawn_monitored_task;
+use prometheus::{register_int_counter_vec_with_registry, IntCounter, IntCounterVec, Registry};
 use std::collections::BTreeMap;
 use std::fmt::Debug;
+use std::str::FromStr;
 use std::sync::Arc;
 use sui_types::base_types::ObjectID;
 use sui_types::error::SuiError;
 use sui_types::filter::Filter;
 use tokio::runtime::Handle;
+use tokio::sync::broadcast::error::RecvError;
 use tokio::sync::mpsc::Sender;
-use tokio::sync::{mpsc, RwLock};
-use tokio_stream::wrappers::ReceiverStream;
+use tokio::sync::{broadcast, mpsc, RwLock};
 use tracing::{debug, warn};
 
-type Subscribers<T, F> = Arc<RwLock<BTreeMap<String, (Sender<T>, F)>>>;
+#[cfg(test)]
+#[path = "unit_tests/streamer_tests.rs"]
+mod streamer_tests;
 
-/// The Streamer splits a mpsc channel into multiple mpsc channels using the subscriber's `Filter<T>` object.
-/// Data will be sent to the subscribers in parallel and the subscription will be dropped if it received a send error.
+type Subscribers<T, F> = Arc<RwLock<BTreeMap<String, (broadcast::Sender<T>, F)>>>;
+
+/// What happens to a subscriber that falls so far behind that its buffer fills up.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SlowSubscriberPolicy {
+    /// The oldest items in the buffer are dropped to make room for new ones, and the subscriber
+    /// carries on from the oldest item left.
+    #[default]
+    DropOldest,
+    /// The subscription is closed, so that the subscriber can tell it missed items and
+    /// resubscribe, catching up by querying.
+    Disconnect,
+}
+
+impl FromStr for SlowSubscriberPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "drop-oldest" => Ok(Self::DropOldest),
+            "disconnect" => Ok(Self::Disconnect),
+            _ => Err(format!(
+                "Unknown slow subscriber policy `{s}`, expected `drop-oldest` or `disconnect`"
+            )),
+        }
+    }
+}
+
+/// Counts the actions taken against slow subscribers, by the stream subscribed to.
+#[derive(Clone)]
+pub struct StreamerMetrics {
+    items_dropped: IntCounterVec,
+    subscribers_disconnected: IntCounterVec,
+}
+
+impl StreamerMetrics {
+    pub fn new(registry: &Registry) -> Self {
+        Self {
+            items_dropped: register_int_counter_vec_with_registry!(
+                "subscription_items_dropped",
+                "Items dropped from the buffers of slow subscribers under the drop-oldest policy",
+                &["stream"],
+                registry,
+            )
+            .unwrap(),
+            subscribers_disconnected: register_int_counter_vec_with_registry!(
+                "subscription_slow_subscribers_disconnected",
+                "Slow subscribers disconnected under the disconnect policy",
+                &["stream"],
+                registry,
+            )
+            .unwrap(),
+        }
+    }
+}
+
+/// The Streamer splits a mpsc channel into multiple channels using the subscriber's `Filter<T>` object.
+/// Data is sent to each subscriber's buffer without waiting for the subscriber, whose
+/// `SlowSubscriberPolicy` decides what happens when it falls behind by a full buffer. A
+/// subscription is dropped once its subscriber goes away.
 pub struct Streamer<T, F: Filter<T>> {
     streamer_queue: Sender<T>,
     subscribers: Subscribers<T, F>,
+    buffer: usize,
+    items_dropped: IntCounter,
+    subscribers_disconnected: IntCounter,
 }
 
 impl<T, F> Streamer<T, F>
@@ -30,11 +95,18 @@ where
     T: Clone + Debug + Send + Sync + 'static,
     F: Filter<T> + Clone + Send + Sync + 'static + Clone,
 {
-    pub fn spawn(buffer: usize) -> Self {
+    /// `buffer` is the size of the queue of data to send, and of the buffer of each subscriber.
+    /// `stream` labels the metrics of the streamer.
+    pub fn spawn(buffer: usize, metrics: &StreamerMetrics, stream: &str) -> Self {
         let (tx, rx) = mpsc::channel::<T>(buffer);
         let streamer = Self {
             streamer_queue: tx,
             subscribers: Default::default(),
+            buffer,
+            items_dropped: metrics.items_dropped.with_label_values(&[stream]),
+            subscribers_disconnected: metrics
+                .subscribers_disconnected
+                .with_label_values(&[stream]),
         };
         let mut rx = rx;
         let subscribers = streamer.subscribers.clone();
@@ -47,34 +119,63 @@ where
     }
 
     async fn send_to_all_subscribers(subscribers: Subscribers<T, F>, data: T) {
-        for (id, (subscriber, filter)) in subscribers.read().await.clone() {
+        let mut closed = vec![];
+        for (id, (subscriber, filter)) in subscribers.read().await.iter() {
             if !(filter.matches(&data)) {
                 continue;
             }
-            let data = data.clone();
-            let subscribers = subscribers.clone();
-            spawn_monitored_task!(async move {
-                match subscriber.send(data).await {
-                    Ok(_) => {
-                        debug!("Sending Move event to subscriber [{id}].")
-                    }
-                    Err(e) => {
-                        subscribers.write().await.remove(&id);
-                        warn!("Error sending event, removing subscriber [{id}] from subscriber list. Error: {e}");
-                    }
-                }
-            });
+            // Sending only fails once the subscriber has gone away.
+            match subscriber.send(data.clone()) {
+                Ok(_) => debug!("Sending Move event to subscriber [{id}]."),
+                Err(_) => closed.push(id.clone()),
+            }
+        }
+        if !closed.is_empty() {
+            let mut subscribers = subscribers.write().await;
+            for id in closed {
+                subscribers.remove(&id);
+                debug!("Subscriber [{id}] is gone, removing it from subscriber list.");
+            }
         }
     }
 
-    /// Subscribe to the data stream filtered by the filter object.
-    pub fn subscribe(&self, filter: F) -> impl Stream<Item = T> {
+    /// Subscribe to the data stream filtered by the filter object, with `policy` deciding what
+    /// happens when the subscriber falls behind.
+    pub fn subscribe(&self, filter: F, policy: SlowSubscriberPolicy) -> BoxStream<'static, T> {
         let handle = Handle::current();
         let _ = handle.enter();
         let mut subscribers = futures::executor::block_on(async { self.subscribers.write().await });
-        let (tx, rx) = mpsc::channel::<T>(EVENT_DISPATCH_BUFFER_SIZE);
-        subscribers.insert(ObjectID::random().to_string(), (tx, filter));
-        ReceiverStream::new(rx)
+        let (tx, rx) = broadcast::channel::<T>(self.buffer);
+        let id = ObjectID::random().to_string();
+        subscribers.insert(id.clone(), (tx, filter));
+
+        let items_dropped = self.items_dropped.clone();
+        let subscribers_disconnected = self.subscribers_disconnected.clone();
+        futures::stream::unfold(rx, move |mut rx| {
+            let id = id.clone();
+            let items_dropped = items_dropped.clone();
+            let subscribers_disconnected = subscribers_disconnected.clone();
+            async move {
+                loop {
+                    match rx.recv().await {
+                        Ok(data) => return Some((data, rx)),
+                        Err(RecvError::Lagged(skipped)) => match policy {
+                            SlowSubscriberPolicy::DropOldest => {
+                                items_dropped.inc_by(skipped);
+                                debug!("Subscriber [{id}] fell behind, dropped {skipped} items.");
+                            }
+                            SlowSubscriberPolicy::Disconnect => {
+                                subscribers_disconnected.inc();
+                                warn!("Subscriber [{id}] fell behind, disconnecting it.");
+                                return None;
+                            }
+                        },
+                        Err(RecvError::Closed) => return None,
+                    }
+                }
+            }
+        })
+        .boxed()
     }
 
     pub async fn send(&self, data: T) -> Result<(), SuiError> {
@@ -80,15 +80,19 @@ pub async fn check_transaction_input(
 }
 
 /// WARNING! This should only be used for the dev-inspect transaction. This transaction type
-/// bypasses many of the normal object checks
+/// bypasses many of the normal object checks, and with `skip_checks` also the validity checks of
+/// the transaction
 pub(crate) async fn check_dev_inspect_input(
     store: &AuthorityStore,
     config: &ProtocolConfig,
     kind: &TransactionKind,
     gas_object: Object,
+    skip_checks: bool,
 ) -> Result<(ObjectRef, InputObjects), anyhow::Error> {
     let gas_object_ref = gas_object.compute_object_reference();
-    kind.validity_check(config, &[gas_object_ref])?;
+    if !skip_checks {
+        kind.validity_check(config, &[gas_object_ref])?;
+    }
     for k in kind.single_transactions() {
         match k {
             SingleTransactionKind::TransferObject(_)
@@ -126,6 +130,27 @@ pub(crate) async fn check_dev_inspect_input(
     Ok((gas_object_ref, input_objects))
 }
 
+/// WARNING! This should only be used for dry runs. The gas object is taken as given rather than
+/// read from the store, so that it can be a synthetic coin
+pub(crate) async fn check_dry_run_input(
+    store: &AuthorityStore,
+    epoch_store: &AuthorityPerEpochStore,
+    transaction: &TransactionData,
+    gas_object: Object,
+) -> SuiResult<(SuiGasStatus<'static>, InputObjects)> {
+    transaction.check_version_supported(epoch_store.protocol_config())?;
+    transaction.validity_check(epoch_store.protocol_config())?;
+    let gas_object_ref = gas_object.compute_object_reference();
+    let mut input_objects = transaction.input_objects()?;
+    input_objects.retain(|kind| kind.object_id() != gas_object_ref.0);
+    let mut objects = store.check_input_objects(&input_objects)?;
+    input_objects.push(InputObjectKind::ImmOrOwnedMoveObject(gas_object_ref));
+    objects.push(gas_object);
+    let gas_status = get_gas_status(&objects, epoch_store, transaction).await?;
+    let input_objects = check_objects(transaction, input_objects, objects)?;
+    Ok((gas_status, input_objects))
+}
+
 pub async fn check_certificate_input(
     store: &AuthorityStore,
     epoch_store: &AuthorityPerEpochStore,
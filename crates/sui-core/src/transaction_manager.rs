@@ -4,15 +4,17 @@
 use std::{
     collections::{BTreeSet, HashMap, HashSet},
     sync::Arc,
+    time::Instant,
 };
 
 use parking_lot::RwLock;
+use serde::Serialize;
+use sui_types::{base_types::TransactionDigest, error::SuiResult};
 use sui_types::{
-    base_types::ObjectID,
+    base_types::{ObjectID, SequenceNumber},
     committee::EpochId,
     messages::{TransactionDataAPI, VerifiedCertificate, VerifiedExecutableTransaction},
 };
-use sui_types::{base_types::TransactionDigest, error::SuiResult};
 use tokio::sync::mpsc::UnboundedSender;
 use tracing::{debug, error, warn};
 
@@ -38,6 +40,54 @@ pub struct TransactionManager {
 struct PendingCertificate {
     certificate: VerifiedExecutableTransaction,
     missing: BTreeSet<InputKey>,
+    // Time when the certificate was enqueued into TransactionManager.
+    enqueue_time: Instant,
+}
+
+/// A point-in-time view of the TransactionManager queues, for debugging stuck transactions.
+#[derive(Debug, Serialize)]
+pub struct TransactionManagerSnapshot {
+    pub epoch: EpochId,
+    /// Number of certificates sent to the execution driver but not yet picked up by it.
+    pub num_ready: i64,
+    pub num_executing: usize,
+    pub num_pending: usize,
+    pub num_missing_inputs: usize,
+    /// Certificates with all inputs available that have not finished execution.
+    pub executing: Vec<TransactionDigest>,
+    /// Certificates waiting on missing inputs, the longest waiting first.
+    pub pending: Vec<PendingCertificateSnapshot>,
+    /// Missing inputs, the ones blocking the most certificates first.
+    pub missing_inputs: Vec<MissingInputSnapshot>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PendingCertificateSnapshot {
+    pub digest: TransactionDigest,
+    pub waiting_ms: u64,
+    pub missing_inputs: Vec<InputKeySnapshot>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MissingInputSnapshot {
+    pub input: InputKeySnapshot,
+    pub waiting_certificates: Vec<TransactionDigest>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct InputKeySnapshot {
+    pub object_id: ObjectID,
+    /// None for packages, which are not versioned as transaction inputs.
+    pub version: Option<SequenceNumber>,
+}
+
+impl From<&InputKey> for InputKeySnapshot {
+    fn from(key: &InputKey) -> Self {
+        InputKeySnapshot {
+            object_id: key.0,
+            version: key.1,
+        }
+    }
 }
 
 #[derive(Default)]
@@ -143,6 +193,7 @@ impl TransactionManager {
             }
             pending.push(PendingCertificate {
                 certificate: cert,
+                enqueue_time: Instant::now(),
                 missing: input_object_keys
                     .into_iter()
                     .filter(|key| {
@@ -325,6 +376,9 @@ impl TransactionManager {
                 if pending_cert.missing.is_empty() {
                     debug!(tx_digest = ?digest, "certificate ready");
                     let pending_cert = inner.pending_certificates.remove(&digest).unwrap();
+                    self.metrics
+                        .transaction_manager_pending_certificate_wait_latency
+                        .observe(pending_cert.enqueue_time.elapsed().as_secs_f64());
                     assert!(inner.executing_certificates.insert(digest));
                     ready_digests.push(digest);
                     self.certificate_ready(pending_cert.certificate);
@@ -371,6 +425,56 @@ impl TransactionManager {
         let _ = self.tx_ready_certificates.send(certificate);
     }
 
+    /// Notifies TransactionManager that the execution driver has picked up a ready certificate.
+    pub(crate) fn certificate_dequeued(&self) {
+        self.metrics.transaction_manager_num_ready.dec();
+    }
+
+    /// Returns a snapshot of the internal queues. At most `limit` entries are returned in each
+    /// of the lists, while the counts always cover all of them.
+    pub fn snapshot(&self, limit: usize) -> TransactionManagerSnapshot {
+        let inner = self.inner.read();
+
+        let mut pending: Vec<_> = inner.pending_certificates.iter().collect();
+        pending.sort_by_key(|(_, cert)| cert.enqueue_time);
+        let pending = pending
+            .into_iter()
+            .take(limit)
+            .map(|(digest, cert)| PendingCertificateSnapshot {
+                digest: *digest,
+                waiting_ms: cert.enqueue_time.elapsed().as_millis() as u64,
+                missing_inputs: cert.missing.iter().map(Into::into).collect(),
+            })
+            .collect();
+
+        let mut missing_inputs: Vec<_> = inner.missing_inputs.iter().collect();
+        missing_inputs.sort_by(|(k1, d1), (k2, d2)| d2.len().cmp(&d1.len()).then(k1.cmp(k2)));
+        let missing_inputs = missing_inputs
+            .into_iter()
+            .take(limit)
+            .map(|(key, digests)| MissingInputSnapshot {
+                input: key.into(),
+                waiting_certificates: digests.iter().copied().collect(),
+            })
+            .collect();
+
+        TransactionManagerSnapshot {
+            epoch: inner.epoch,
+            num_ready: self.metrics.transaction_manager_num_ready.get(),
+            num_executing: inner.executing_certificates.len(),
+            num_pending: inner.pending_certificates.len(),
+            num_missing_inputs: inner.missing_inputs.len(),
+            executing: inner
+                .executing_certificates
+                .iter()
+                .take(limit)
+                .copied()
+                .collect(),
+            pending,
+            missing_inputs,
+        }
+    }
+
     // Returns the number of transactions waiting on each object ID.
     pub(crate) fn objects_queue_len(&self, keys: Vec<ObjectID>) -> Vec<(ObjectID, usize)> {
         let inner = self.inner.read();
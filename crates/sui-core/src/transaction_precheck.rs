@@ -0,0 +1,271 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Cheap, stateless checks that are run on every transaction submitted to a validator
+//! before its signatures are verified or any of its input objects are loaded. The gas budget
+//! bounds and the validity checks of the transaction data are run again by the transaction input
+//! checker, so for those this only saves work. The size limit, the deny lists of the node config
+//! and the argument checks of programmable transactions are only enforced here.
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use arc_swap::ArcSwap;
+use prometheus::{register_int_counter_vec_with_registry, IntCounterVec, Registry};
+use sui_config::node::TransactionDenyConfig;
+use sui_protocol_config::ProtocolConfig;
+use sui_types::{
+    base_types::{ObjectID, SuiAddress},
+    error::{SuiResult, UserInputError, UserInputResult},
+    fp_ensure,
+    gas::{self, SuiCostTable},
+    messages::{
+        Argument, CallArg, Command, ProgrammableTransaction, SingleTransactionKind, Transaction,
+        TransactionDataAPI,
+    },
+};
+
+/// Reasons a transaction can be rejected by the [`TransactionPrechecker`]. These are used as
+/// label values of the rejection counter, so they must stay stable.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PrecheckRejectReason {
+    TransactionTooLarge,
+    GasBudgetTooLow,
+    GasBudgetTooHigh,
+    DeniedAddress,
+    DeniedPackage,
+    MalformedCommand,
+    InvalidTransaction,
+}
+
+impl PrecheckRejectReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::TransactionTooLarge => "transaction_too_large",
+            Self::GasBudgetTooLow => "gas_budget_too_low",
+            Self::GasBudgetTooHigh => "gas_budget_too_high",
+            Self::DeniedAddress => "denied_address",
+            Self::DeniedPackage => "denied_package",
+            Self::MalformedCommand => "malformed_command",
+            Self::InvalidTransaction => "invalid_transaction",
+        }
+    }
+}
+
+pub struct TransactionPrecheckMetrics {
+    pub num_rejected: IntCounterVec,
+}
+
+impl TransactionPrecheckMetrics {
+    pub fn new(registry: &Registry) -> Self {
+        Self {
+            num_rejected: register_int_counter_vec_with_registry!(
+                "validator_service_precheck_rejected_transactions",
+                "Number of transactions rejected before signature verification, by reason",
+                &["reason"],
+                registry,
+            )
+            .unwrap(),
+        }
+    }
+
+    pub fn new_for_tests() -> Self {
+        Self::new(&Registry::new())
+    }
+}
+
+#[derive(Default)]
+struct DenyLists {
+    addresses: HashSet<SuiAddress>,
+    packages: HashSet<ObjectID>,
+}
+
+impl DenyLists {
+    fn new(config: &TransactionDenyConfig) -> Self {
+        Self {
+            addresses: config.address_deny_list.iter().copied().collect(),
+            packages: config.package_deny_list.iter().copied().collect(),
+        }
+    }
+}
+
+pub struct TransactionPrechecker {
+    deny_lists: ArcSwap<DenyLists>,
+    metrics: TransactionPrecheckMetrics,
+}
+
+impl TransactionPrechecker {
+    pub fn new(config: &TransactionDenyConfig, metrics: TransactionPrecheckMetrics) -> Self {
+        Self {
+            deny_lists: ArcSwap::from_pointee(DenyLists::new(config)),
+            metrics,
+        }
+    }
+
+    pub fn new_for_tests() -> Self {
+        Self::new(
+            &TransactionDenyConfig::default(),
+            TransactionPrecheckMetrics::new_for_tests(),
+        )
+    }
+
+    /// Replaces the deny lists, e.g. after the node config has been reloaded. Transactions
+    /// already past the prechecks are not affected.
+    pub fn update_deny_config(&self, config: &TransactionDenyConfig) {
+        self.deny_lists.store(Arc::new(DenyLists::new(config)));
+    }
+
+    /// Runs all prechecks against `transaction`, bumping the rejection counter for the
+    /// first check that fails.
+    pub fn check(&self, transaction: &Transaction, config: &ProtocolConfig) -> SuiResult {
+        self.check_impl(transaction, config)
+            .map_err(|(reason, error)| {
+                self.metrics
+                    .num_rejected
+                    .with_label_values(&[reason.as_str()])
+                    .inc();
+                error.into()
+            })
+    }
+
+    fn check_impl(
+        &self,
+        transaction: &Transaction,
+        config: &ProtocolConfig,
+    ) -> Result<(), (PrecheckRejectReason, UserInputError)> {
+        use PrecheckRejectReason as R;
+
+        // Enforce overall transaction size limit. A transaction that has no BCS size, e.g.
+        // because a sequence is longer than BCS allows, could not have been decoded from BCS.
+        let tx_size = bcs::serialized_size(transaction).map_err(|e| {
+            (
+                R::InvalidTransaction,
+                UserInputError::TransactionDeserializationError {
+                    error: e.to_string(),
+                },
+            )
+        })?;
+        let max_tx_size = config.max_tx_size();
+        if tx_size > max_tx_size {
+            return Err((
+                R::TransactionTooLarge,
+                UserInputError::SizeLimitExceeded {
+                    limit: "serialized transaction size".to_string(),
+                    value: max_tx_size.to_string(),
+                },
+            ));
+        }
+
+        let data = transaction.data().transaction_data();
+        let deny_lists = self.deny_lists.load();
+
+        for address in [data.sender(), data.gas_owner()] {
+            if deny_lists.addresses.contains(&address) {
+                return Err((R::DeniedAddress, UserInputError::AddressDenied { address }));
+            }
+        }
+
+        if !data.is_system_tx() {
+            check_gas_budget(data.gas_budget(), data.gas_price(), config)?;
+        }
+
+        for kind in data.kind().single_transactions() {
+            if let SingleTransactionKind::ProgrammableTransaction(pt) = kind {
+                check_programmable_arguments(pt).map_err(|e| (R::MalformedCommand, e))?;
+            }
+            if let Some(package_id) = called_packages(kind)
+                .into_iter()
+                .find(|p| deny_lists.packages.contains(p))
+            {
+                return Err((
+                    R::DeniedPackage,
+                    UserInputError::PackageDenied { package_id },
+                ));
+            }
+        }
+
+        data.validity_check(config)
+            .map_err(|e| (R::InvalidTransaction, e))
+    }
+}
+
+/// Checks the budget bounds of `sui_types::gas::check_gas_balance`, without needing to load the
+/// gas objects.
+fn check_gas_budget(
+    gas_budget: u64,
+    gas_price: u64,
+    config: &ProtocolConfig,
+) -> Result<(), (PrecheckRejectReason, UserInputError)> {
+    gas::check_gas_budget(gas_budget, gas_price, &SuiCostTable::new(config)).map_err(|e| {
+        let reason = match e {
+            UserInputError::GasBudgetTooHigh { .. } => PrecheckRejectReason::GasBudgetTooHigh,
+            _ => PrecheckRejectReason::GasBudgetTooLow,
+        };
+        (reason, e)
+    })
+}
+
+fn called_packages(kind: &SingleTransactionKind) -> Vec<ObjectID> {
+    match kind {
+        SingleTransactionKind::Call(call) => vec![call.package],
+        SingleTransactionKind::ProgrammableTransaction(pt) => pt
+            .commands
+            .iter()
+            .filter_map(|command| match command {
+                Command::MoveCall(call) => Some(call.package),
+                _ => None,
+            })
+            .collect(),
+        _ => vec![],
+    }
+}
+
+/// Checks that every argument of every command refers to something that exists at the point
+/// the command is run: an input that was provided, or the result of an earlier command.
+fn check_programmable_arguments(pt: &ProgrammableTransaction) -> UserInputResult {
+    fp_ensure!(
+        !pt.commands.is_empty(),
+        UserInputError::MalformedProgrammableTransaction {
+            error: "transaction has no commands".to_string(),
+        }
+    );
+    fp_ensure!(
+        !pt.inputs.iter().any(|i| matches!(i, CallArg::ObjVec(_))),
+        UserInputError::MalformedProgrammableTransaction {
+            error: "object vectors are not supported as inputs, use MakeMoveVec".to_string(),
+        }
+    );
+    for (idx, command) in pt.commands.iter().enumerate() {
+        for arg in command_arguments(command) {
+            let valid = match arg {
+                Argument::GasCoin => true,
+                Argument::Input(i) => (*i as usize) < pt.inputs.len(),
+                Argument::Result(i) | Argument::NestedResult(i, _) => (*i as usize) < idx,
+            };
+            fp_ensure!(
+                valid,
+                UserInputError::MalformedProgrammableTransaction {
+                    error: format!("command {idx} has an out of bounds argument {arg:?}"),
+                }
+            );
+        }
+    }
+    Ok(())
+}
+
+fn command_arguments(command: &Command) -> Vec<&Argument> {
+    match command {
+        Command::MoveCall(call) => call.arguments.iter().collect(),
+        Command::TransferObjects(objects, recipient) => {
+            objects.iter().chain(std::iter::once(recipient)).collect()
+        }
+        Command::SplitCoin(coin, amount) => vec![coin, amount],
+        Command::MergeCoins(target, coins) => std::iter::once(target).chain(coins).collect(),
+        Command::MakeMoveVec(_, elements) => elements.iter().collect(),
+        Command::Publish(_) => vec![],
+    }
+}
+
+#[cfg(test)]
+#[path = "unit_tests/transaction_precheck_tests.rs"]
+mod transaction_precheck_tests;
@@ -12,6 +12,7 @@ use crate::{
     test_utils::init_state_parameters_from_rng,
 };
 use bcs;
+use fastcrypto::hash::MultisetHash;
 use futures::{stream::FuturesUnordered, StreamExt};
 use move_binary_format::{
     file_format::{self, AddressIdentifierIndex, IdentifierIndex, ModuleHandle},
@@ -35,6 +36,7 @@ use std::task::{Context, Poll};
 use sui_json_rpc_types::{
     SuiExecutionResult, SuiExecutionStatus, SuiGasCostSummary, SuiTransactionEffectsAPI,
 };
+use sui_types::accumulator::Accumulator;
 use sui_types::error::UserInputError;
 use sui_types::utils::{
     make_committee_key, mock_certified_checkpoint, to_sender_signed_transaction,
@@ -52,6 +54,7 @@ use sui_macros::sim_test;
 use sui_protocol_config::{ProtocolConfig, SupportedProtocolVersions};
 use sui_types::dynamic_field::DynamicFieldType;
 use sui_types::epoch_data::EpochData;
+use sui_types::gas::ENTRY_FRAME;
 use sui_types::object::Data;
 use sui_types::sui_system_state::epoch_start_sui_system_state::EpochStartSystemState;
 use sui_types::sui_system_state::{SuiSystemStateWrapper, SUI_SYSTEM_STATE_TESTING_VERSION1};
@@ -65,6 +68,7 @@ use sui_types::{
     SUI_SYSTEM_STATE_OBJECT_ID,
 };
 use tracing::info;
+use typed_store::Map;
 
 pub enum TestCallArg {
     Pure(Vec<u8>),
@@ -745,12 +749,150 @@ async fn test_dev_inspect_uses_unbound_object() {
     }));
 
     let result = fullnode
-        .dev_inspect_transaction(sender, kind, Some(1))
+        .dev_inspect_transaction(sender, kind, Some(1), DevInspectArgs::default())
         .await;
     let Err(err) = result else { panic!() };
     assert!(err.to_string().contains("ObjectNotFound"));
 }
 
+#[tokio::test]
+async fn test_dev_inspect_additional_args() {
+    let (sender, _sender_key): (_, AccountKeyPair) = get_key_pair();
+    let gas_object_id = ObjectID::random();
+    let (_validator, fullnode, object_basics) =
+        init_state_with_ids_and_object_basics_with_fullnode(vec![(sender, gas_object_id)]).await;
+
+    let kind = TransactionKind::Single(SingleTransactionKind::Call(MoveCall {
+        package: object_basics.0,
+        module: Identifier::new("object_basics").unwrap(),
+        function: Identifier::new("create").unwrap(),
+        type_arguments: vec![],
+        arguments: vec![
+            CallArg::Pure(bcs::to_bytes(&(16_u64)).unwrap()),
+            CallArg::Pure(bcs::to_bytes(&sender).unwrap()),
+        ],
+    }));
+    let max_tx_gas = fullnode
+        .load_epoch_store_one_call_per_task()
+        .protocol_config()
+        .max_tx_gas();
+
+    // the gas budget cannot go over the maximum, even when checks are skipped
+    for skip_checks in [None, Some(true)] {
+        let args = DevInspectArgs {
+            gas_budget: Some(max_tx_gas + 1),
+            skip_checks,
+            ..Default::default()
+        };
+        let result = fullnode
+            .dev_inspect_transaction(sender, kind.clone(), Some(1), args)
+            .await;
+        assert!(result.is_err());
+    }
+
+    let args = DevInspectArgs {
+        gas_budget: Some(max_tx_gas),
+        skip_checks: Some(true),
+        show_raw_txn_data_and_effects: Some(true),
+        profile_gas: None,
+    };
+    let DevInspectResults {
+        effects,
+        raw_txn_data,
+        raw_effects,
+        ..
+    } = fullnode
+        .dev_inspect_transaction(sender, kind.clone(), Some(1), args)
+        .await
+        .unwrap();
+    assert_eq!(effects.status(), &SuiExecutionStatus::Success);
+    let txn_data: TransactionData = bcs::from_bytes(&raw_txn_data).unwrap();
+    assert_eq!(txn_data.gas_budget(), max_tx_gas);
+    let raw_effects: TransactionEffects = bcs::from_bytes(&raw_effects).unwrap();
+    assert_eq!(
+        raw_effects.transaction_digest(),
+        effects.transaction_digest()
+    );
+
+    // raw results are only given when asked for
+    let DevInspectResults {
+        raw_txn_data,
+        raw_effects,
+        ..
+    } = fullnode
+        .dev_inspect_transaction(sender, kind, Some(1), DevInspectArgs::default())
+        .await
+        .unwrap();
+    assert!(raw_txn_data.is_empty());
+    assert!(raw_effects.is_empty());
+}
+
+#[tokio::test]
+async fn test_dev_inspect_gas_profile() {
+    let (sender, _sender_key): (_, AccountKeyPair) = get_key_pair();
+    let gas_object_id = ObjectID::random();
+    let (_validator, fullnode, object_basics) =
+        init_state_with_ids_and_object_basics_with_fullnode(vec![(sender, gas_object_id)]).await;
+
+    let kind = TransactionKind::Single(SingleTransactionKind::Call(MoveCall {
+        package: object_basics.0,
+        module: Identifier::new("object_basics").unwrap(),
+        function: Identifier::new("create").unwrap(),
+        type_arguments: vec![],
+        arguments: vec![
+            CallArg::Pure(bcs::to_bytes(&(16_u64)).unwrap()),
+            CallArg::Pure(bcs::to_bytes(&sender).unwrap()),
+        ],
+    }));
+
+    let args = DevInspectArgs {
+        profile_gas: Some(true),
+        ..Default::default()
+    };
+    let DevInspectResults {
+        effects,
+        gas_profile,
+        ..
+    } = fullnode
+        .dev_inspect_transaction(sender, kind.clone(), Some(1), args)
+        .await
+        .unwrap();
+    assert_eq!(effects.status(), &SuiExecutionStatus::Success);
+    let gas_profile = gas_profile.unwrap();
+    assert!(gas_profile.total_internal_gas() > 0);
+    assert!(gas_profile.instructions.iter().any(|i| i.name == "CALL"));
+    // entry functions are not called, so they are counted separately from the functions they call
+    let entry = gas_profile
+        .functions
+        .iter()
+        .find(|f| f.name == ENTRY_FRAME)
+        .unwrap();
+    assert_eq!(entry.count, 0);
+    assert!(entry.internal_gas > 0);
+    let new_object = gas_profile
+        .functions
+        .iter()
+        .find(|f| f.name.ends_with("::object::new"))
+        .unwrap();
+    assert_eq!(new_object.count, 1);
+    // the gas of all functions adds up to that of all instructions
+    assert_eq!(
+        gas_profile
+            .functions
+            .iter()
+            .map(|f| f.internal_gas)
+            .sum::<u64>(),
+        gas_profile.total_internal_gas()
+    );
+
+    // the profile is only given when asked for
+    let DevInspectResults { gas_profile, .. } = fullnode
+        .dev_inspect_transaction(sender, kind, Some(1), DevInspectArgs::default())
+        .await
+        .unwrap();
+    assert!(gas_profile.is_none());
+}
+
 #[tokio::test]
 async fn test_dev_inspect_on_validator() {
     let (sender, _sender_key): (_, AccountKeyPair) = get_key_pair();
@@ -2101,6 +2243,67 @@ async fn test_handle_confirmation_transaction_ok() {
     );
 }
 
+#[tokio::test]
+async fn test_accumulate_checkpoint_after_pruning() {
+    let (sender, sender_key): (_, AccountKeyPair) = get_key_pair();
+    let object_id = ObjectID::random();
+    let gas_object_id = ObjectID::random();
+    let authority_state =
+        init_state_with_ids(vec![(sender, object_id), (sender, gas_object_id)]).await;
+    let object = authority_state
+        .get_object(&object_id)
+        .await
+        .unwrap()
+        .unwrap();
+    let gas_object = authority_state
+        .get_object(&gas_object_id)
+        .await
+        .unwrap()
+        .unwrap();
+    let certified_transfer_transaction = init_certified_transfer_transaction(
+        sender,
+        &sender_key,
+        dbg_addr(2),
+        object.compute_object_reference(),
+        gas_object.compute_object_reference(),
+        &authority_state,
+    );
+    let epoch_store = authority_state.epoch_store_for_testing().clone();
+    let effects = authority_state
+        .execute_certificate(&certified_transfer_transaction, &epoch_store)
+        .await
+        .unwrap()
+        .into_message();
+
+    // The pruner removes the versions the transaction modified.
+    for (object_id, version) in effects.modified_at_versions() {
+        authority_state
+            .database
+            .perpetual_tables
+            .objects
+            .remove(&ObjectKey(*object_id, *version))
+            .unwrap();
+    }
+    assert!(authority_state
+        .database
+        .get_object_by_key(&object_id, object.version())
+        .unwrap()
+        .is_none());
+
+    let mut expected = Accumulator::default();
+    expected.insert_all(
+        effects
+            .all_mutated()
+            .into_iter()
+            .map(|(obj_ref, _, _)| obj_ref.2),
+    );
+    expected.remove_all([object.digest(), gas_object.digest()]);
+    let accumulator = StateAccumulator::new(authority_state.database.clone())
+        .accumulate_checkpoint(vec![effects], 0, epoch_store)
+        .unwrap();
+    assert_eq!(accumulator, expected);
+}
+
 struct LimitedPoll<F: Future> {
     inner: Pin<Box<F>>,
     count: u64,
@@ -2734,7 +2937,6 @@ async fn test_authority_persist() {
             checkpoint_store,
             &registry,
             AuthorityStorePruningConfig::default(),
-            &[], // no genesis objects
             10000,
             &StateSnapshotConfig::default(),
         )
@@ -2790,6 +2992,46 @@ async fn test_authority_persist() {
     assert_eq!(obj2.owner, recipient);
 }
 
+#[tokio::test]
+async fn test_migrate_large_objects() {
+    let (genesis, _) = init_state_parameters_from_rng(&mut StdRng::from_seed([1u8; 32]));
+    let committee = genesis.committee().unwrap();
+    let open_store = |indirect_objects_threshold| {
+        let path = env::temp_dir().join(format!("DB_{:?}", ObjectID::random()));
+        fs::create_dir(&path).unwrap();
+        let committee_store = Arc::new(CommitteeStore::new(path.join("epochs"), &committee, None));
+        let genesis = &genesis;
+        async move {
+            AuthorityStore::open(
+                &path.join("store"),
+                None,
+                genesis,
+                &committee_store,
+                indirect_objects_threshold,
+            )
+            .await
+            .unwrap()
+        }
+    };
+
+    // The genesis objects are written with the threshold the store is opened with.
+    let store = open_store(0).await;
+    assert_eq!(store.migrate_large_objects(0).unwrap(), 0);
+
+    let store = open_store(usize::MAX).await;
+    let object = Object::with_id_owner_for_testing(ObjectID::random(), dbg_addr(2));
+    store.insert_genesis_object(object.clone()).await.unwrap();
+    let num_indirect_objects = || store.perpetual_tables.indirect_move_objects.keys().count();
+    assert_eq!(num_indirect_objects(), 0);
+
+    // The genesis objects and the object inserted since are moved.
+    let num_migrated = store.migrate_large_objects(0).unwrap();
+    assert!(num_migrated > 1);
+    assert_eq!(store.migrate_large_objects(0).unwrap(), 0);
+    assert_eq!(num_indirect_objects(), num_migrated as usize);
+    assert_eq!(store.get_object(&object.id()).unwrap(), Some(object));
+}
+
 #[tokio::test]
 async fn test_idempotent_reversed_confirmation() {
     // In this test we exercise the case where an authority first receive the certificate,
@@ -4406,7 +4648,7 @@ pub async fn call_dev_inspect(
         arguments,
     }));
     authority
-        .dev_inspect_transaction(*sender, kind, Some(1))
+        .dev_inspect_transaction(*sender, kind, Some(1), DevInspectArgs::default())
         .await
 }
 
@@ -543,6 +543,20 @@ async fn test_per_object_overload() {
         shared_certs.push(shared_cert);
     }
 
+    // All but the first txn are waiting on a version of the shared counter that does not exist yet.
+    let snapshot = authorities[3].transaction_manager().snapshot(10);
+    assert_eq!(snapshot.num_pending, num_txns - 1);
+    assert_eq!(snapshot.num_missing_inputs, num_txns - 1);
+    assert_eq!(snapshot.pending.len(), 10);
+    assert!(snapshot
+        .pending
+        .windows(2)
+        .all(|w| w[0].waiting_ms >= w[1].waiting_ms));
+    for missing in &snapshot.missing_inputs {
+        assert_eq!(missing.input.object_id, shared_counter_ref.0);
+        assert_eq!(missing.waiting_certificates.len(), 1);
+    }
+
     // Trying to sign a new transaction would now fail.
     let gas_ref = get_latest_ref(authority_clients[0], gas_objects[num_txns].id()).await;
     let shared_txn = make_counter_increment_transaction(
@@ -128,6 +128,7 @@ async fn test_narwhal_manager() {
             .start(
                 narwhal_committee.clone(),
                 worker_cache.clone(),
+                state.epoch_store_for_testing().protocol_config().clone(),
                 Arc::new(execution_state.clone()),
                 TrivialTransactionValidator::default(),
             )
@@ -193,6 +194,7 @@ async fn test_narwhal_manager() {
             .start(
                 narwhal_committee.clone(),
                 worker_cache.clone(),
+                state.epoch_store_for_testing().protocol_config().clone(),
                 Arc::new(execution_state.clone()),
                 TrivialTransactionValidator::default(),
             )
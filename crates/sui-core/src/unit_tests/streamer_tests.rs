@@ -0,0 +1,95 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+
+#[derive(Clone)]
+struct AllFilter;
+
+impl Filter<u64> for AllFilter {
+    fn matches(&self, _: &u64) -> bool {
+        true
+    }
+}
+
+const BUFFER: usize = 4;
+
+/// Subscribes a receiver with room for every item the tests send, so that `fill` can tell when
+/// the streamer has dispatched them.
+async fn probe(streamer: &Streamer<u64, AllFilter>) -> broadcast::Receiver<u64> {
+    let (tx, rx) = broadcast::channel(64);
+    streamer
+        .subscribers
+        .write()
+        .await
+        .insert("probe".to_string(), (tx, AllFilter));
+    rx
+}
+
+async fn fill(
+    streamer: &Streamer<u64, AllFilter>,
+    probe: &mut broadcast::Receiver<u64>,
+    items: u64,
+) {
+    for i in 0..items {
+        streamer.send(i).await.unwrap();
+    }
+    // Items are dispatched to all subscribers at once, so once the probe has them all, so does
+    // every other subscriber.
+    for _ in 0..items {
+        probe.recv().await.unwrap();
+    }
+}
+
+#[tokio::test]
+async fn test_slow_subscriber_drop_oldest() {
+    let metrics = StreamerMetrics::new(&Registry::new());
+    let streamer = Streamer::spawn(BUFFER, &metrics, "test");
+    let mut stream = streamer.subscribe(AllFilter, SlowSubscriberPolicy::DropOldest);
+    let mut probe = probe(&streamer).await;
+
+    fill(&streamer, &mut probe, 10).await;
+    let mut received = vec![];
+    for _ in 0..BUFFER {
+        received.push(stream.next().await.unwrap());
+    }
+    // Only the newest items fit the buffer.
+    assert_eq!(vec![6, 7, 8, 9], received);
+    assert_eq!(6, metrics.items_dropped.with_label_values(&["test"]).get());
+
+    // The subscriber keeps receiving new items.
+    streamer.send(10).await.unwrap();
+    assert_eq!(Some(10), stream.next().await);
+}
+
+#[tokio::test]
+async fn test_slow_subscriber_disconnect() {
+    let metrics = StreamerMetrics::new(&Registry::new());
+    let streamer = Streamer::spawn(BUFFER, &metrics, "test");
+    let mut stream = streamer.subscribe(AllFilter, SlowSubscriberPolicy::Disconnect);
+    let mut probe = probe(&streamer).await;
+
+    fill(&streamer, &mut probe, 10).await;
+    assert_eq!(None, stream.next().await);
+    assert_eq!(
+        1,
+        metrics
+            .subscribers_disconnected
+            .with_label_values(&["test"])
+            .get()
+    );
+
+    // The subscription is removed with the next item sent, after that item is dispatched.
+    fill(&streamer, &mut probe, 1).await;
+    while streamer.subscribers.read().await.len() > 1 {
+        tokio::task::yield_now().await;
+    }
+    assert!(streamer.subscribers.read().await.contains_key("probe"));
+}
+
+#[test]
+fn test_slow_subscriber_policy_from_str() {
+    assert_eq!(Ok(SlowSubscriberPolicy::DropOldest), "drop-oldest".parse());
+    assert_eq!(Ok(SlowSubscriberPolicy::Disconnect), "disconnect".parse());
+    assert!("drop-newest".parse::<SlowSubscriberPolicy>().is_err());
+}
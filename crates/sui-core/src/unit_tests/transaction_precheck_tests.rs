@@ -0,0 +1,176 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use crate::test_utils::MAX_GAS;
+use sui_types::base_types::{dbg_addr, dbg_object_id, random_object_ref};
+use sui_types::crypto::{get_key_pair, AccountKeyPair};
+use sui_types::error::SuiError;
+use sui_types::messages::{TransactionData, DUMMY_GAS_PRICE};
+use sui_types::utils::to_sender_signed_transaction;
+
+fn transfer_sui(sender: SuiAddress, key: &AccountKeyPair, gas_budget: u64) -> Transaction {
+    let data = TransactionData::new_transfer_sui(
+        dbg_addr(2),
+        sender,
+        None,
+        random_object_ref(),
+        gas_budget,
+        DUMMY_GAS_PRICE,
+    );
+    to_sender_signed_transaction(data, key).into_inner()
+}
+
+fn rejected_count(checker: &TransactionPrechecker, reason: PrecheckRejectReason) -> u64 {
+    checker
+        .metrics
+        .num_rejected
+        .with_label_values(&[reason.as_str()])
+        .get()
+}
+
+#[test]
+fn test_precheck_accepts_valid_transaction() {
+    let (sender, key): (_, AccountKeyPair) = get_key_pair();
+    let checker = TransactionPrechecker::new_for_tests();
+    let config = ProtocolConfig::get_for_max_version();
+
+    checker
+        .check(&transfer_sui(sender, &key, MAX_GAS), &config)
+        .unwrap();
+}
+
+#[test]
+fn test_precheck_rejects_denied_sender() {
+    let (sender, key): (_, AccountKeyPair) = get_key_pair();
+    let checker = TransactionPrechecker::new(
+        &TransactionDenyConfig {
+            address_deny_list: vec![sender],
+            ..Default::default()
+        },
+        TransactionPrecheckMetrics::new_for_tests(),
+    );
+    let config = ProtocolConfig::get_for_max_version();
+
+    let err = checker
+        .check(&transfer_sui(sender, &key, MAX_GAS), &config)
+        .unwrap_err();
+    assert_eq!(
+        err,
+        SuiError::UserInputError {
+            error: UserInputError::AddressDenied { address: sender }
+        }
+    );
+    assert_eq!(
+        rejected_count(&checker, PrecheckRejectReason::DeniedAddress),
+        1
+    );
+}
+
+#[test]
+fn test_precheck_update_deny_config() {
+    let (sender, key): (_, AccountKeyPair) = get_key_pair();
+    let checker = TransactionPrechecker::new_for_tests();
+    let config = ProtocolConfig::get_for_max_version();
+    let tx = transfer_sui(sender, &key, MAX_GAS);
+
+    checker.check(&tx, &config).unwrap();
+
+    checker.update_deny_config(&TransactionDenyConfig {
+        address_deny_list: vec![sender],
+        ..Default::default()
+    });
+    checker.check(&tx, &config).unwrap_err();
+
+    checker.update_deny_config(&TransactionDenyConfig::default());
+    checker.check(&tx, &config).unwrap();
+}
+
+#[test]
+fn test_precheck_rejects_gas_budget_too_high() {
+    let (sender, key): (_, AccountKeyPair) = get_key_pair();
+    let checker = TransactionPrechecker::new_for_tests();
+    let config = ProtocolConfig::get_for_max_version();
+
+    let err = checker
+        .check(&transfer_sui(sender, &key, u64::MAX), &config)
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        SuiError::UserInputError {
+            error: UserInputError::GasBudgetTooHigh { .. }
+        }
+    ));
+    assert_eq!(
+        rejected_count(&checker, PrecheckRejectReason::GasBudgetTooHigh),
+        1
+    );
+}
+
+#[test]
+fn test_precheck_rejects_denied_package_and_bad_arguments() {
+    let (sender, key): (_, AccountKeyPair) = get_key_pair();
+    let package = dbg_object_id(1);
+    let checker = TransactionPrechecker::new(
+        &TransactionDenyConfig {
+            package_deny_list: vec![package],
+            ..Default::default()
+        },
+        TransactionPrecheckMetrics::new_for_tests(),
+    );
+    let config = ProtocolConfig::get_for_max_version();
+
+    let make_tx = |arguments: Vec<Argument>| {
+        let pt = ProgrammableTransaction {
+            inputs: vec![CallArg::Pure(vec![])],
+            commands: vec![Command::MoveCall(Box::new(
+                sui_types::messages::ProgrammableMoveCall {
+                    package,
+                    module: move_core_types::identifier::Identifier::new("m").unwrap(),
+                    function: move_core_types::identifier::Identifier::new("f").unwrap(),
+                    type_arguments: vec![],
+                    arguments,
+                },
+            ))],
+        };
+        let data = TransactionData::new_programmable(
+            sender,
+            random_object_ref(),
+            pt,
+            MAX_GAS,
+            DUMMY_GAS_PRICE,
+        );
+        to_sender_signed_transaction(data, &key).into_inner()
+    };
+
+    // Referring to the result of the command itself is malformed.
+    let err = checker
+        .check(&make_tx(vec![Argument::Result(0)]), &config)
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        SuiError::UserInputError {
+            error: UserInputError::MalformedProgrammableTransaction { .. }
+        }
+    ));
+
+    let err = checker
+        .check(&make_tx(vec![Argument::Input(0)]), &config)
+        .unwrap_err();
+    assert_eq!(
+        err,
+        SuiError::UserInputError {
+            error: UserInputError::PackageDenied {
+                package_id: package
+            }
+        }
+    );
+    assert_eq!(
+        rejected_count(&checker, PrecheckRejectReason::MalformedCommand),
+        1
+    );
+    assert_eq!(
+        rejected_count(&checker, PrecheckRejectReason::DeniedPackage),
+        1
+    );
+}
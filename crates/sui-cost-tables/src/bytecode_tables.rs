@@ -15,6 +15,7 @@ use move_vm_types::gas::{GasMeter, SimpleInstruction};
 use move_vm_types::views::{TypeView, ValueView};
 use once_cell::sync::Lazy;
 
+use crate::gas_profiler::{GasProfile, SharedGasProfile};
 use crate::units_types::{CostTable, Gas, GasCost};
 use move_binary_format::{
     file_format::{
@@ -53,6 +54,7 @@ pub struct GasStatus<'a> {
     cost_table: &'a CostTable,
     gas_left: InternalGas,
     charge: bool,
+    profile: Option<SharedGasProfile>,
 }
 
 impl<'a> GasStatus<'a> {
@@ -65,6 +67,7 @@ impl<'a> GasStatus<'a> {
             gas_left: gas_left.to_unit(),
             cost_table,
             charge: true,
+            profile: None,
         }
     }
 
@@ -81,6 +84,7 @@ impl<'a> GasStatus<'a> {
             gas_left: InternalGas::new(0),
             cost_table: &ZERO_COST_SCHEDULE,
             charge: false,
+            profile: None,
         }
     }
 
@@ -112,13 +116,31 @@ impl<'a> GasStatus<'a> {
         }
     }
 
+    /// Record the gas charged from now on in a profile, by instruction and by function, and
+    /// return that profile.
+    pub fn enable_profiling(&mut self) -> SharedGasProfile {
+        let profile = GasProfile::new_shared();
+        self.profile = Some(profile.clone());
+        profile
+    }
+
+    fn with_profile(&self, f: impl FnOnce(&mut GasProfile)) {
+        if !self.charge {
+            return;
+        }
+        if let Some(profile) = &self.profile {
+            f(&mut profile.lock().unwrap())
+        }
+    }
+
     fn charge_instr(&mut self, opcode: Opcodes) -> PartialVMResult<()> {
-        self.deduct_gas(
-            self.cost_table
-                .instruction_cost(opcode as u8)
-                .total()
-                .into(),
-        )
+        let amount = self
+            .cost_table
+            .instruction_cost(opcode as u8)
+            .total()
+            .into();
+        self.with_profile(|profile| profile.record_instruction(opcode, amount));
+        self.deduct_gas(amount)
     }
 
     /// Charge an instruction over data with a given size and fail if not enough gas units are left.
@@ -130,12 +152,12 @@ impl<'a> GasStatus<'a> {
         // Make sure that the size is always non-zero
         let size = std::cmp::max(1.into(), size);
         debug_assert!(size > 0.into());
-        self.deduct_gas(
-            InternalGasPerAbstractMemoryUnit::new(
-                self.cost_table.instruction_cost(opcode as u8).total(),
-            )
-            .mul(size),
+        let amount = InternalGasPerAbstractMemoryUnit::new(
+            self.cost_table.instruction_cost(opcode as u8).total(),
         )
+        .mul(size);
+        self.with_profile(|profile| profile.record_instruction(opcode, amount));
+        self.deduct_gas(amount)
     }
 
     pub fn set_metering(&mut self, enabled: bool) {
@@ -207,24 +229,31 @@ fn get_simple_instruction_opcode(instr: SimpleInstruction) -> Opcodes {
 impl<'b> GasMeter for GasStatus<'b> {
     /// Charge an instruction and fail if not enough gas units are left.
     fn charge_simple_instr(&mut self, instr: SimpleInstruction) -> PartialVMResult<()> {
-        self.charge_instr(get_simple_instruction_opcode(instr))
+        let is_ret = matches!(instr, SimpleInstruction::Ret);
+        let result = self.charge_instr(get_simple_instruction_opcode(instr));
+        if is_ret {
+            self.with_profile(|profile| profile.exit_function());
+        }
+        result
     }
 
     fn charge_call(
         &mut self,
-        _module_id: &ModuleId,
-        _func_name: &str,
+        module_id: &ModuleId,
+        func_name: &str,
         args: impl ExactSizeIterator<Item = impl ValueView>,
         _num_locals: NumArgs,
     ) -> PartialVMResult<()> {
         // TODO (Gas Maintenance)
-        self.charge_instr_with_size(Opcodes::CALL, (args.len() as u64 + 1).into())
+        self.charge_instr_with_size(Opcodes::CALL, (args.len() as u64 + 1).into())?;
+        self.with_profile(|profile| profile.enter_function(module_id, func_name));
+        Ok(())
     }
 
     fn charge_call_generic(
         &mut self,
-        _module_id: &ModuleId,
-        _func_name: &str,
+        module_id: &ModuleId,
+        func_name: &str,
         ty_args: impl ExactSizeIterator<Item = impl TypeView>,
         args: impl ExactSizeIterator<Item = impl ValueView>,
         _num_locals: NumArgs,
@@ -233,7 +262,9 @@ impl<'b> GasMeter for GasStatus<'b> {
         self.charge_instr_with_size(
             Opcodes::CALL_GENERIC,
             ((ty_args.len() + args.len() + 1) as u64).into(),
-        )
+        )?;
+        self.with_profile(|profile| profile.enter_function(module_id, func_name));
+        Ok(())
     }
 
     fn charge_ld_const(&mut self, size: NumBytes) -> PartialVMResult<()> {
@@ -441,6 +472,11 @@ impl<'b> GasMeter for GasStatus<'b> {
         _ret_vals: Option<impl ExactSizeIterator<Item = impl ValueView>>,
     ) -> PartialVMResult<()> {
         // TODO (Gas Maintenance)
+        // Natives are called without running a `Ret`, so they return here.
+        self.with_profile(|profile| {
+            profile.record_native(amount);
+            profile.exit_function();
+        });
         self.deduct_gas(amount)
     }
 
@@ -0,0 +1,101 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+
+use move_binary_format::file_format_common::Opcodes;
+use move_core_types::gas_algebra::InternalGas;
+use move_core_types::language_storage::ModuleId;
+use serde::{Deserialize, Serialize};
+
+/// The function that gas is attributed to outside of any call, i.e. the entry functions of a
+/// transaction, which the Move VM invokes without charging for a call.
+pub const ENTRY_FRAME: &str = "<entry>";
+
+/// The key that the gas charged by native functions is recorded under, among instructions.
+pub const NATIVE_INSTRUCTION: &str = "NATIVE";
+
+/// A profile shared between a `GasStatus` and whoever reads it once execution is over, as the
+/// gas status itself is consumed by execution.
+pub type SharedGasProfile = Arc<Mutex<GasProfile>>;
+
+/// The count of, and gas charged by, one instruction or function of a profile.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GasProfileEntry {
+    /// How many times the instruction ran, or the function was called.
+    pub count: u64,
+    /// The gas charged, in internal gas units.
+    pub internal_gas: u64,
+}
+
+/// The gas charged by the Move VM during a run, broken down by instruction and by function.
+///
+/// The gas of a function only covers the instructions it runs itself, not those of the
+/// functions it calls.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GasProfile {
+    pub instructions: BTreeMap<String, GasProfileEntry>,
+    pub functions: BTreeMap<String, GasProfileEntry>,
+    #[serde(skip)]
+    call_stack: Vec<String>,
+}
+
+impl GasProfile {
+    pub fn new_shared() -> SharedGasProfile {
+        Arc::new(Mutex::new(Self::default()))
+    }
+
+    /// The total gas charged across all instructions, in internal gas units.
+    pub fn total_internal_gas(&self) -> u64 {
+        self.instructions
+            .values()
+            .map(|entry| entry.internal_gas)
+            .sum()
+    }
+
+    pub(crate) fn record_instruction(&mut self, opcode: Opcodes, amount: InternalGas) {
+        self.record(format!("{opcode:?}"), amount)
+    }
+
+    pub(crate) fn record_native(&mut self, amount: InternalGas) {
+        self.record(NATIVE_INSTRUCTION.to_string(), amount)
+    }
+
+    pub(crate) fn enter_function(&mut self, module_id: &ModuleId, func_name: &str) {
+        let function = format!("{}::{}", module_id.short_str_lossless(), func_name);
+        self.functions.entry(function.clone()).or_default().count += 1;
+        self.call_stack.push(function);
+    }
+
+    /// Returns from the innermost function called. Returns from entry functions, which were
+    /// never entered, leave the call stack empty.
+    pub(crate) fn exit_function(&mut self) {
+        self.call_stack.pop();
+    }
+
+    fn record(&mut self, instruction: String, amount: InternalGas) {
+        let amount = u64::from(amount);
+        let entry = self.instructions.entry(instruction).or_default();
+        entry.count += 1;
+        entry.internal_gas += amount;
+
+        let function = self
+            .call_stack
+            .last()
+            .map(String::as_str)
+            .unwrap_or(ENTRY_FRAME);
+        match self.functions.get_mut(function) {
+            Some(entry) => entry.internal_gas += amount,
+            None => {
+                self.functions.insert(
+                    function.to_string(),
+                    GasProfileEntry {
+                        count: 0,
+                        internal_gas: amount,
+                    },
+                );
+            }
+        }
+    }
+}
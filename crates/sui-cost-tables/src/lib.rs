@@ -2,5 +2,6 @@
 // SPDX-License-Identifier: Apache-2.0
 
 pub mod bytecode_tables;
+pub mod gas_profiler;
 pub mod natives_tables;
 pub mod units_types;
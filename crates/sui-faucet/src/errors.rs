@@ -28,6 +28,15 @@ pub enum FaucetError {
     #[error("Coin Transfer Failed `{0}`")]
     Transfer(String),
 
+    #[error("Too many requests: {0}")]
+    TooManyRequests(String),
+
+    #[error("Quota exceeded: {0}")]
+    QuotaExceeded(String),
+
+    #[error("Verification failed: {0}")]
+    VerificationFailed(String),
+
     #[error("Internal error: {0}")]
     Internal(String),
 }
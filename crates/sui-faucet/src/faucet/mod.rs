@@ -8,7 +8,7 @@ use uuid::Uuid;
 
 mod simple_faucet;
 mod write_ahead_log;
-pub use self::simple_faucet::SimpleFaucet;
+pub use self::simple_faucet::{GasPoolConfig, SimpleFaucet};
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct FaucetReceipt {
@@ -31,4 +31,11 @@ pub trait Faucet {
         recipient: SuiAddress,
         amounts: &[u64],
     ) -> Result<FaucetReceipt, FaucetError>;
+
+    /// Send `Coin<SUI>` of the specified amounts to each recipient, in a single transaction
+    async fn batch_send(
+        &self,
+        id: Uuid,
+        requests: &[(SuiAddress, Vec<u64>)],
+    ) -> Result<Vec<FaucetReceipt>, FaucetError>;
 }
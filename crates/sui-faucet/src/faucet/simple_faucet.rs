@@ -9,12 +9,13 @@ use tap::tap::TapFallible;
 
 #[cfg(test)]
 use std::collections::HashSet;
+use std::collections::{HashMap, VecDeque};
 use std::default::Default;
 use std::path::Path;
 
 use sui::client_commands::WalletContext;
 use sui_json_rpc_types::{
-    SuiObjectDataOptions, SuiObjectResponse, SuiPaySui, SuiTransactionDataAPI,
+    SuiExecutionStatus, SuiObjectDataOptions, SuiObjectResponse, SuiPaySui, SuiTransactionDataAPI,
     SuiTransactionEffectsAPI, SuiTransactionKind, SuiTransactionResponse,
 };
 use sui_keys::keystore::AccountKeystore;
@@ -44,6 +45,21 @@ pub struct SimpleFaucet {
     consumer: Mutex<Receiver<ObjectID>>,
     metrics: FaucetMetrics,
     wal: Mutex<WriteAheadLog>,
+    /// Coins taken out of the queue for being too small to serve requests, to be merged into the
+    /// coins split for the pool.
+    dust_coins: Mutex<Vec<ObjectID>>,
+}
+
+/// How the pool of gas coins is kept replenished, see [`SimpleFaucet::replenish_gas_pool`].
+#[derive(Clone, Debug)]
+pub struct GasPoolConfig {
+    /// The number of coins that the pool is topped up to.
+    pub pool_size: usize,
+    /// The balance of the coins split for the pool.
+    pub coin_balance: u64,
+    /// The balance below which coins are too small to serve requests.
+    pub min_coin_balance: u64,
+    pub replenish_interval: Duration,
 }
 
 enum GasCoinResponse {
@@ -54,10 +70,17 @@ enum GasCoinResponse {
     ValidGasCoin(ObjectID),
 }
 
-const DEFAULT_GAS_BUDGET: u64 = 1000;
 const PAY_SUI_GAS: u64 = 1000;
 const LOCK_TIMEOUT: Duration = Duration::from_secs(10);
 const RECV_TIMEOUT: Duration = Duration::from_secs(5);
+/// The gas budget of PaySui transactions grows with the coins they create, past
+/// `PAY_SUI_GAS`.
+const PAY_SUI_GAS_PER_COIN: u64 = 200;
+/// The maximum number of coins that the pool is topped up to, above the coins the faucet starts
+/// with.
+const MAX_GAS_POOL_SIZE: usize = 1000;
+/// The maximum number of coins split, and of coins merged, by one replenishing transaction.
+const MAX_COINS_PER_REPLENISH: usize = 100;
 
 impl SimpleFaucet {
     pub async fn new(
@@ -84,7 +107,7 @@ impl SimpleFaucet {
         let wal = WriteAheadLog::open(wal_path);
         let mut pending = vec![];
 
-        let (producer, consumer) = mpsc::channel(coins.len());
+        let (producer, consumer) = mpsc::channel(coins.len() + MAX_GAS_POOL_SIZE);
         for coin in &coins {
             let coin_id = *coin.id();
             if let Some(write_ahead_log::Entry {
@@ -116,6 +139,7 @@ impl SimpleFaucet {
             consumer: Mutex::new(consumer),
             metrics,
             wal: Mutex::new(wal),
+            dust_coins: Mutex::new(vec![]),
         };
 
         // Retrying all the pending transactions from the WAL, before continuing.  Ignore return
@@ -154,16 +178,32 @@ impl SimpleFaucet {
         Some(coin)
     }
 
+    /// Takes all the coins from the queue, without waiting for those in use.
+    async fn take_gas_coins(&self) -> Vec<ObjectID> {
+        let mut consumer = self.consumer.lock().await;
+        let mut coins = vec![];
+        while let Ok(coin) = consumer.try_recv() {
+            self.metrics.total_available_coins.dec();
+            coins.push(coin);
+        }
+        coins
+    }
+
     /// Pulls a coin from the queue and makes sure it is fit for use (belongs to the faucet, has
     /// sufficient balance).
-    async fn prepare_gas_coin(&self, total_amount: u64, uuid: Uuid) -> GasCoinResponse {
+    async fn prepare_gas_coin(
+        &self,
+        total_amount: u64,
+        gas_budget: u64,
+        uuid: Uuid,
+    ) -> GasCoinResponse {
         let Some(coin_id) = self.pop_gas_coin(uuid).await else {
             warn!("Failed getting gas coin, try later!");
             return GasCoinResponse::NoGasCoinAvailable;
         };
 
         match self.get_gas_coin(coin_id).await {
-            Ok(Some(gas_coin)) if gas_coin.value() >= total_amount + PAY_SUI_GAS => {
+            Ok(Some(gas_coin)) if gas_coin.value() >= total_amount + gas_budget => {
                 info!(?uuid, ?coin_id, "balance: {}", gas_coin.value());
                 GasCoinResponse::ValidGasCoin(coin_id)
             }
@@ -276,26 +316,32 @@ impl SimpleFaucet {
         }
     }
 
+    /// Sends a coin of `amounts[i]` to `recipients[i]` for each `i`, in one transaction, and
+    /// returns the coins created with their owners.
     async fn transfer_gases(
         &self,
+        recipients: &[SuiAddress],
         amounts: &[u64],
-        recipient: SuiAddress,
         uuid: Uuid,
-    ) -> Result<(TransactionDigest, Vec<ObjectID>, Vec<u64>), FaucetError> {
+    ) -> Result<(TransactionDigest, Vec<(SuiAddress, ObjectID)>), FaucetError> {
         let number_of_coins = amounts.len();
         let total_amount: u64 = amounts.iter().sum();
+        let Some(recipient) = recipients.first().copied() else {
+            return Err(FaucetError::internal("No coins requested"));
+        };
 
-        let gas_coin_response = self.prepare_gas_coin(total_amount, uuid).await;
+        let gas_budget = pay_sui_gas_budget(number_of_coins);
+        let gas_coin_response = self.prepare_gas_coin(total_amount, gas_budget, uuid).await;
 
         match gas_coin_response {
             GasCoinResponse::ValidGasCoin(coin_id) => {
                 let tx_data = self
                     .build_pay_sui_txn(
-                        coin_id,
+                        vec![coin_id],
                         self.active_address,
-                        recipient,
+                        recipients.to_vec(),
                         amounts,
-                        DEFAULT_GAS_BUDGET,
+                        gas_budget,
                     )
                     .await
                     .map_err(FaucetError::internal)?;
@@ -313,7 +359,7 @@ impl SimpleFaucet {
                     .sign_and_execute_txn(uuid, recipient, coin_id, tx_data)
                     .await?;
 
-                self.check_and_map_transfer_gas_result(response, number_of_coins, &recipient)
+                self.check_and_map_transfer_gas_result(response, number_of_coins, recipients)
                     .await
             }
 
@@ -325,6 +371,7 @@ impl SimpleFaucet {
             GasCoinResponse::GasCoinWithInsufficientBalance(coin_id) => {
                 warn!(?uuid, ?coin_id, "Insufficient balance, removing from pool");
                 self.metrics.total_discarded_coins.inc();
+                self.dust_coins.lock().await.push(coin_id);
                 Err(FaucetError::GasCoinWithInsufficientBalance(
                     coin_id.to_hex_literal(),
                 ))
@@ -417,20 +464,20 @@ impl SimpleFaucet {
             })?)
     }
 
+    /// Builds a PaySui transaction merging `input_coins` into the first one, which pays for gas.
     async fn build_pay_sui_txn(
         &self,
-        coin_id: ObjectID,
+        input_coins: Vec<ObjectID>,
         signer: SuiAddress,
-        recipient: SuiAddress,
+        recipients: Vec<SuiAddress>,
         amounts: &[u64],
         budget: u64,
     ) -> Result<TransactionData, anyhow::Error> {
-        let recipients: Vec<SuiAddress> =
-            std::iter::repeat(recipient).take(amounts.len()).collect();
+        let coin_id = input_coins[0];
         let client = self.wallet.get_client().await?;
         client
             .transaction_builder()
-            .pay_sui(signer, vec![coin_id], recipients, amounts.to_vec(), budget)
+            .pay_sui(signer, input_coins, recipients, amounts.to_vec(), budget)
             .await
             .map_err(|e| {
                 anyhow::anyhow!(
@@ -445,8 +492,8 @@ impl SimpleFaucet {
         &self,
         res: SuiTransactionResponse,
         number_of_coins: usize,
-        recipient: &SuiAddress,
-    ) -> Result<(TransactionDigest, Vec<ObjectID>, Vec<u64>), FaucetError> {
+        expected_recipients: &[SuiAddress],
+    ) -> Result<(TransactionDigest, Vec<(SuiAddress, ObjectID)>), FaucetError> {
         let txns = res.transaction.data.transactions();
         if txns.len() != 1 {
             panic!(
@@ -466,19 +513,187 @@ impl SimpleFaucet {
             // coins here are input coins, rather than the created coins under recipients.
             coins: _,
             recipients,
-            amounts,
+            amounts: _,
         }) = txn
         {
-            assert!(recipients
-                .iter()
-                .all(|sent_recipient| sent_recipient == recipient));
-            let coin_ids: Vec<ObjectID> = created
+            assert_eq!(recipients, expected_recipients);
+            let coins = created
                 .iter()
-                .map(|created_coin_owner_ref| created_coin_owner_ref.reference.object_id)
-                .collect();
-            Ok((*res.effects.transaction_digest(), coin_ids, amounts.clone()))
+                .map(|created_coin_owner_ref| {
+                    let owner = created_coin_owner_ref
+                        .owner
+                        .get_owner_address()
+                        .map_err(FaucetError::internal)?;
+                    Ok((owner, created_coin_owner_ref.reference.object_id))
+                })
+                .collect::<Result<Vec<_>, FaucetError>>()?;
+            Ok((*res.effects.transaction_digest(), coins))
         } else {
-            panic!("Expect SuiTransactionKind::PaySui(SuiPaySui) to send coins to address {} but got txn {:?}", recipient, txn);
+            panic!("Expect SuiTransactionKind::PaySui(SuiPaySui) to send coins to addresses {:?} but got txn {:?}", expected_recipients, txn);
+        }
+    }
+
+    /// Tops the pool of gas coins up to `config.pool_size` coins, by splitting coins of
+    /// `config.coin_balance` out of the largest coin of the pool. The coins too small to serve
+    /// requests are merged into it in the same transaction.
+    pub async fn replenish_gas_pool(&self, config: &GasPoolConfig) -> Result<(), FaucetError> {
+        let uuid = Uuid::new_v4();
+        let coin_ids = self.take_gas_coins().await;
+        let coins =
+            futures::future::join_all(coin_ids.iter().map(|coin_id| self.get_gas_coin(*coin_id)))
+                .await;
+
+        let mut usable_coins = vec![];
+        let mut dust_coins = std::mem::take(&mut *self.dust_coins.lock().await);
+        for (coin_id, coin) in coin_ids.into_iter().zip(coins) {
+            match coin {
+                Ok(Some(coin)) if coin.value() >= config.min_coin_balance => {
+                    usable_coins.push((coin_id, coin.value()))
+                }
+                Ok(Some(_)) => {
+                    self.metrics.total_discarded_coins.inc();
+                    dust_coins.push(coin_id);
+                }
+                Ok(None) => {
+                    warn!(?uuid, ?coin_id, "Invalid, removing from pool");
+                    self.metrics.total_discarded_coins.inc();
+                }
+                Err(e) => {
+                    error!(?uuid, ?coin_id, "Fullnode read error: {e:?}");
+                    usable_coins.push((coin_id, 0));
+                }
+            }
+        }
+
+        // The coins in use are not counted, as they may not be usable once back.
+        let missing = config
+            .pool_size
+            .min(MAX_GAS_POOL_SIZE)
+            .saturating_sub(usable_coins.len())
+            .min(MAX_COINS_PER_REPLENISH);
+        usable_coins.sort_by_key(|(_, balance)| *balance);
+        let source = if missing > 0 {
+            usable_coins.pop()
+        } else {
+            None
+        };
+        for (coin_id, _) in usable_coins {
+            self.recycle_gas_coin(coin_id, uuid).await;
+        }
+        let merged_coins: Vec<_> = dust_coins
+            .drain(..dust_coins.len().min(MAX_COINS_PER_REPLENISH))
+            .collect();
+        self.dust_coins.lock().await.extend(dust_coins);
+        let Some((source, balance)) = source else {
+            self.dust_coins.lock().await.extend(merged_coins);
+            return Ok(());
+        };
+
+        // The balance of the merged coins is left out, as it is not known.
+        let gas_budget = pay_sui_gas_budget(missing + merged_coins.len());
+        let count =
+            missing.min((balance.saturating_sub(gas_budget) / config.coin_balance) as usize);
+        if count == 0 {
+            warn!(?uuid, coin_id = ?source, balance, "Not enough balance to replenish the gas pool");
+            self.recycle_gas_coin(source, uuid).await;
+            self.dust_coins.lock().await.extend(merged_coins);
+            return Ok(());
+        }
+
+        let result = self
+            .execute_replenish_txn(
+                uuid,
+                source,
+                &merged_coins,
+                count,
+                config.coin_balance,
+                gas_budget,
+            )
+            .await;
+        let created = match result {
+            Ok(created) => created,
+            Err(e) => {
+                // The merged coins are left as they were, the source coin is recycled unless its
+                // transaction is still pending.
+                self.dust_coins.lock().await.extend(merged_coins);
+                return Err(e);
+            }
+        };
+        for coin_id in &created {
+            self.recycle_gas_coin(*coin_id, uuid).await;
+        }
+        info!(
+            ?uuid,
+            created = created.len(),
+            merged = merged_coins.len(),
+            "Replenished gas pool"
+        );
+        Ok(())
+    }
+
+    /// Splits `count` coins of `coin_balance` out of `source`, after merging `merged_coins` into
+    /// it, and returns the coins created.
+    async fn execute_replenish_txn(
+        &self,
+        uuid: Uuid,
+        source: ObjectID,
+        merged_coins: &[ObjectID],
+        count: usize,
+        coin_balance: u64,
+        gas_budget: u64,
+    ) -> Result<Vec<ObjectID>, FaucetError> {
+        let mut input_coins = vec![source];
+        input_coins.extend(merged_coins);
+        let tx_data = match self
+            .build_pay_sui_txn(
+                input_coins,
+                self.active_address,
+                vec![self.active_address; count],
+                &vec![coin_balance; count],
+                gas_budget,
+            )
+            .await
+        {
+            Ok(tx_data) => tx_data,
+            Err(e) => {
+                self.recycle_gas_coin(source, uuid).await;
+                return Err(FaucetError::internal(e));
+            }
+        };
+
+        let reserved =
+            self.wal
+                .lock()
+                .await
+                .reserve(uuid, source, self.active_address, tx_data.clone());
+        if let Err(e) = reserved {
+            self.recycle_gas_coin(source, uuid).await;
+            return Err(FaucetError::internal(e));
+        }
+
+        // The source coin is recycled once the transaction is executed.
+        let response = self
+            .sign_and_execute_txn(uuid, self.active_address, source, tx_data)
+            .await?;
+        if let SuiExecutionStatus::Failure { error } = response.effects.status() {
+            return Err(FaucetError::Transfer(error.clone()));
+        }
+        Ok(response
+            .effects
+            .created()
+            .iter()
+            .map(|created| created.reference.object_id)
+            .collect())
+    }
+
+    /// Replenishes the pool of gas coins every `config.replenish_interval`, forever.
+    pub async fn run_gas_pool_replenisher(&self, config: GasPoolConfig) {
+        let mut interval = tokio::time::interval(config.replenish_interval);
+        loop {
+            interval.tick().await;
+            if let Err(e) = self.replenish_gas_pool(&config).await {
+                warn!("Failed to replenish the gas pool: {e}");
+            }
         }
     }
 
@@ -518,34 +733,80 @@ impl Faucet for SimpleFaucet {
     ) -> Result<FaucetReceipt, FaucetError> {
         info!(?recipient, uuid = ?id, "Getting faucet requests");
 
-        let (digest, coin_ids, sent_amounts) = self.transfer_gases(amounts, recipient, id).await?;
-        if coin_ids.len() != amounts.len() {
+        let recipients = vec![recipient; amounts.len()];
+        let (digest, coins) = self.transfer_gases(&recipients, amounts, id).await?;
+        if coins.len() != amounts.len() {
             error!(
                 uuid = ?id, ?recipient,
                 "Requested {} coins but got {}",
                 amounts.len(),
-                coin_ids.len()
+                coins.len()
             );
         }
 
         info!(uuid = ?id, ?recipient, ?digest, "PaySui txn succeeded");
         Ok(FaucetReceipt {
-            sent: coin_ids
+            sent: coins
                 .iter()
-                .zip(sent_amounts)
-                .map(|(coin_id, sent_amount)| CoinInfo {
+                .zip(amounts)
+                .map(|((_, coin_id), sent_amount)| CoinInfo {
                     transfer_tx_digest: digest,
-                    amount: sent_amount,
+                    amount: *sent_amount,
                     id: *coin_id,
                 })
                 .collect(),
         })
     }
+
+    async fn batch_send(
+        &self,
+        id: Uuid,
+        requests: &[(SuiAddress, Vec<u64>)],
+    ) -> Result<Vec<FaucetReceipt>, FaucetError> {
+        info!(uuid = ?id, "Getting {} batched faucet requests", requests.len());
+
+        let (recipients, amounts): (Vec<_>, Vec<_>) = requests
+            .iter()
+            .flat_map(|(recipient, amounts)| amounts.iter().map(|amount| (*recipient, *amount)))
+            .unzip();
+        let (digest, coins) = self.transfer_gases(&recipients, &amounts, id).await?;
+
+        // The coins created for a recipient are in the order of its amounts.
+        let mut coins_by_recipient: HashMap<SuiAddress, VecDeque<ObjectID>> = HashMap::new();
+        for (owner, coin_id) in coins {
+            coins_by_recipient
+                .entry(owner)
+                .or_default()
+                .push_back(coin_id);
+        }
+        info!(uuid = ?id, ?digest, "Batched PaySui txn succeeded");
+        Ok(requests
+            .iter()
+            .map(|(recipient, amounts)| FaucetReceipt {
+                sent: amounts
+                    .iter()
+                    .filter_map(|amount| {
+                        let coin_id = coins_by_recipient.get_mut(recipient)?.pop_front()?;
+                        Some(CoinInfo {
+                            transfer_tx_digest: digest,
+                            amount: *amount,
+                            id: coin_id,
+                        })
+                    })
+                    .collect(),
+            })
+            .collect())
+    }
+}
+
+/// The gas budget of a PaySui transaction creating `number_of_coins` coins.
+fn pay_sui_gas_budget(number_of_coins: usize) -> u64 {
+    PAY_SUI_GAS.max(PAY_SUI_GAS_PER_COIN * number_of_coins as u64)
 }
 
 #[cfg(test)]
 mod tests {
-    use sui::client_commands::{SuiClientCommandResult, SuiClientCommands};
+    use sui::client_commands::{SuiClientCommandResult, SuiClientCommands, TransactionOptions};
     use sui_json_rpc_types::SuiExecutionStatus;
     use test_utils::network::TestClusterBuilder;
 
@@ -659,8 +920,9 @@ mod tests {
         // Now we transfer one gas out
         let res = SuiClientCommands::PayAllSui {
             input_coins: vec![*bad_gas.id()],
-            recipient: SuiAddress::random_for_testing_only(),
+            recipient: SuiAddress::random_for_testing_only().into(),
             gas_budget: 50000,
+            options: TransactionOptions::default(),
         }
         .execute(faucet.wallet_mut())
         .await
@@ -718,6 +980,7 @@ mod tests {
             gas_budget: 50000,
             gas: None,
             count: None,
+            options: TransactionOptions::default(),
         }
         .execute(&mut context)
         .await
@@ -775,6 +1038,73 @@ mod tests {
         assert!(candidates.get(&tiny_coin_id).is_none());
     }
 
+    #[tokio::test]
+    async fn test_batch_send() {
+        let test_cluster = TestClusterBuilder::new().build().await.unwrap();
+
+        let tmp = tempfile::tempdir().unwrap();
+        let prom_registry = Registry::new();
+        let faucet = SimpleFaucet::new(
+            test_cluster.wallet,
+            &prom_registry,
+            &tmp.path().join("faucet.wal"),
+        )
+        .await
+        .unwrap();
+
+        let requests: Vec<_> = (1..=3)
+            .map(|i| (SuiAddress::random_for_testing_only(), vec![i; i as usize]))
+            .collect();
+        let receipts = faucet.batch_send(Uuid::new_v4(), &requests).await.unwrap();
+
+        // All the coins are sent in one transaction, each request getting its amounts.
+        assert_eq!(receipts.len(), requests.len());
+        let digest = receipts[0].sent[0].transfer_tx_digest;
+        for ((_, amounts), receipt) in requests.iter().zip(receipts) {
+            let sent: Vec<u64> = receipt.sent.iter().map(|coin| coin.amount).collect();
+            assert_eq!(&sent, amounts);
+            assert!(receipt
+                .sent
+                .iter()
+                .all(|coin| coin.transfer_tx_digest == digest));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_replenish_gas_pool() {
+        let test_cluster = TestClusterBuilder::new().build().await.unwrap();
+        let address = test_cluster.get_address_0();
+        let mut context = test_cluster.wallet;
+        let gases = get_current_gases(address, &mut context).await;
+
+        let tmp = tempfile::tempdir().unwrap();
+        let prom_registry = Registry::new();
+        let mut faucet = SimpleFaucet::new(context, &prom_registry, &tmp.path().join("faucet.wal"))
+            .await
+            .unwrap();
+
+        let config = GasPoolConfig {
+            pool_size: gases.len() + 5,
+            coin_balance: 100_000,
+            min_coin_balance: 1,
+            replenish_interval: Duration::from_secs(1),
+        };
+        faucet.replenish_gas_pool(&config).await.unwrap();
+        let candidates = faucet.drain_gas_queue(config.pool_size).await;
+        let new_coins = candidates
+            .iter()
+            .filter(|coin_id| gases.iter().all(|gas| gas.id() != *coin_id))
+            .count();
+        assert_eq!(new_coins, 5);
+
+        // The coins taken out of the queue are not split again.
+        for coin_id in candidates {
+            faucet.recycle_gas_coin(coin_id, Uuid::new_v4()).await;
+        }
+        faucet.replenish_gas_pool(&config).await.unwrap();
+        faucet.drain_gas_queue(config.pool_size).await;
+    }
+
     async fn test_basic_interface(faucet: &impl Faucet) {
         let recipient = SuiAddress::random_for_testing_only();
         let amounts = vec![1, 2, 3];
@@ -791,7 +1121,8 @@ mod tests {
     async fn get_current_gases(address: SuiAddress, context: &mut WalletContext) -> Vec<GasCoin> {
         // Get the latest list of gas
         let results = SuiClientCommands::Gas {
-            address: Some(address),
+            address: Some(address.into()),
+            cmd: None,
         }
         .execute(context)
         .await
@@ -3,7 +3,8 @@
 
 use axum::{
     error_handling::HandleErrorLayer,
-    http::StatusCode,
+    extract::{ConnectInfo, Path},
+    http::{HeaderMap, StatusCode},
     response::IntoResponse,
     routing::{get, post},
     BoxError, Extension, Json, Router,
@@ -13,20 +14,29 @@ use http::Method;
 use mysten_metrics::spawn_monitored_task;
 use std::{
     borrow::Cow,
+    collections::HashMap,
     net::{IpAddr, Ipv4Addr, SocketAddr},
-    sync::Arc,
-    time::Duration,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
 };
 use std::{env, path::PathBuf};
 use sui::client_commands::WalletContext;
 use sui_config::{sui_config_dir, SUI_CLIENT_CONFIG};
-use sui_faucet::{Faucet, FaucetRequest, FaucetResponse, RequestMetricsLayer, SimpleFaucet};
+use sui_faucet::{
+    BatchFaucetResponse, BatchSendStatus, BatchSendStatusType, BatchStatusFaucetResponse, Faucet,
+    FaucetError, FaucetRequest, FaucetResponse, GasPoolConfig, RequestLimitConfig, RequestLimiter,
+    RequestMetricsLayer, SimpleFaucet, VERIFICATION_TOKEN_HEADER,
+};
+use sui_types::base_types::SuiAddress;
+use tokio::sync::mpsc;
 use tower::{limit::RateLimitLayer, ServiceBuilder};
 use tower_http::cors::{Any, CorsLayer};
 use tracing::{info, warn};
 use uuid::Uuid;
 
 const CONCURRENCY_LIMIT: usize = 30;
+/// How long the status of a request of the v1 API is kept after it is served or discarded.
+const TASK_RETENTION: Duration = Duration::from_secs(3600);
 
 #[derive(Parser)]
 #[clap(
@@ -58,11 +68,75 @@ struct FaucetConfig {
 
     #[clap(long)]
     write_ahead_log: PathBuf,
+
+    /// The window that the numbers of requests per IP and per address are limited within
+    #[clap(long, default_value_t = 3600)]
+    rate_limit_window_secs: u64,
+
+    #[clap(long)]
+    max_requests_per_ip: Option<usize>,
+
+    #[clap(long)]
+    max_requests_per_address: Option<usize>,
+
+    /// The amount of MIST given to an IP per day (UTC)
+    #[clap(long)]
+    daily_quota_per_ip: Option<u64>,
+
+    /// The amount of MIST given to an address per day (UTC)
+    #[clap(long)]
+    daily_quota_per_address: Option<u64>,
+
+    /// Where the verification tokens of the requests are verified, no token is needed when unset
+    #[clap(long)]
+    verification_url: Option<String>,
+
+    /// The header that the proxy in front of the faucet appends the client IP to, such as
+    /// `X-Forwarded-For`. The IP that the requests come from is used when unset.
+    #[clap(long)]
+    client_ip_header: Option<String>,
+
+    /// The maximum number of requests of the v1 API served by one transaction
+    #[clap(long, default_value_t = 10)]
+    batch_request_size: usize,
+
+    /// How often the queued requests of the v1 API are served
+    #[clap(long, default_value_t = 500)]
+    batch_interval_ms: u64,
+
+    /// The number of gas coins kept in the pool by splitting new ones in the background, the
+    /// pool is not replenished when unset
+    #[clap(long)]
+    gas_pool_size: Option<usize>,
+
+    /// The balance of the coins split for the pool, twice the amount of a full batch by default
+    #[clap(long)]
+    gas_pool_coin_balance: Option<u64>,
+
+    #[clap(long, default_value_t = 10)]
+    gas_pool_replenish_interval_secs: u64,
+}
+
+/// A request of the v1 API waiting to be served in a batch.
+struct QueuedRequest {
+    id: Uuid,
+    ip: IpAddr,
+    recipient: SuiAddress,
+}
+
+struct Task {
+    status: BatchSendStatus,
+    /// When the request was served or discarded, after which it is forgotten in a while.
+    done_at: Option<Instant>,
 }
 
 struct AppState<F = SimpleFaucet> {
     faucet: F,
     config: FaucetConfig,
+    limiter: RequestLimiter,
+    queue: mpsc::UnboundedSender<QueuedRequest>,
+    /// The status of the requests of the v1 API, by task ID.
+    tasks: Mutex<HashMap<Uuid, Task>>,
     // TODO: add counter
 }
 
@@ -99,13 +173,46 @@ async fn main() -> Result<(), anyhow::Error> {
     let registry_service = sui_node::metrics::start_prometheus_server(prom_binding);
     let prometheus_registry = registry_service.default_registry();
 
+    let limiter = RequestLimiter::new(
+        RequestLimitConfig {
+            window: Duration::from_secs(config.rate_limit_window_secs),
+            max_requests_per_ip: config.max_requests_per_ip,
+            max_requests_per_address: config.max_requests_per_address,
+            daily_quota_per_ip: config.daily_quota_per_ip,
+            daily_quota_per_address: config.daily_quota_per_address,
+            verification_url: config.verification_url.clone(),
+        },
+        &prometheus_registry,
+    );
+    let gas_pool_config = config.gas_pool_size.map(|pool_size| {
+        let batch_amount = total_amount(&config) * config.batch_request_size as u64;
+        GasPoolConfig {
+            pool_size,
+            coin_balance: config.gas_pool_coin_balance.unwrap_or(2 * batch_amount),
+            // The coins that cannot serve a full batch are merged into the new ones.
+            min_coin_balance: batch_amount,
+            replenish_interval: Duration::from_secs(config.gas_pool_replenish_interval_secs),
+        }
+    });
+    let (queue, queued_requests) = mpsc::unbounded_channel();
     let app_state = Arc::new(AppState {
         faucet: SimpleFaucet::new(context, &prometheus_registry, write_ahead_log)
             .await
             .unwrap(),
         config,
+        limiter,
+        queue,
+        tasks: Mutex::new(HashMap::new()),
     });
 
+    spawn_monitored_task!(serve_batches(app_state.clone(), queued_requests));
+    if let Some(gas_pool_config) = gas_pool_config {
+        let state = app_state.clone();
+        spawn_monitored_task!(async move {
+            state.faucet.run_gas_pool_replenisher(gas_pool_config).await
+        });
+    }
+
     // TODO: restrict access if needed
     let cors = CorsLayer::new()
         .allow_methods(vec![Method::GET, Method::POST])
@@ -115,6 +222,8 @@ async fn main() -> Result<(), anyhow::Error> {
     let app = Router::new()
         .route("/", get(health))
         .route("/gas", post(request_gas))
+        .route("/v1/gas", post(batch_request_gas))
+        .route("/v1/status/:task", get(request_status))
         .layer(
             ServiceBuilder::new()
                 .layer(HandleErrorLayer::new(handle_error))
@@ -134,7 +243,7 @@ async fn main() -> Result<(), anyhow::Error> {
     let addr = SocketAddr::new(IpAddr::V4(host_ip), port);
     info!("listening on {}", addr);
     axum::Server::bind(&addr)
-        .serve(app.into_make_service())
+        .serve(app.into_make_service_with_connect_info::<SocketAddr>())
         .await?;
     Ok(())
 }
@@ -147,15 +256,33 @@ async fn health() -> &'static str {
 /// handler for all the request_gas requests
 async fn request_gas(
     Extension(state): Extension<Arc<AppState>>,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
     Json(payload): Json<FaucetRequest>,
 ) -> impl IntoResponse {
     // ID for traceability
     let id = Uuid::new_v4();
     info!(uuid = ?id, "Got new gas request.");
+    let FaucetRequest::FixedAmountRequest(request) = &payload;
+    let (ip, recipient, amount) = (
+        client_ip(&state.config, peer, &headers),
+        request.recipient,
+        total_amount(&state.config),
+    );
+    if let Err(e) = state
+        .limiter
+        .admit(ip, recipient, amount, verification_token(&headers))
+        .await
+    {
+        warn!(uuid = ?id, "Rejected gas request from {ip}: {e}");
+        return (rejection_status(&e), Json(FaucetResponse::from(e)));
+    }
+
     let result = match payload {
         FaucetRequest::FixedAmountRequest(requests) => {
             // We spawn a tokio task for this such that connection drop will not interrupt
             // it and impact the reclycing of coins
+            let state = state.clone();
             spawn_monitored_task!(async move {
                 state
                     .faucet
@@ -173,10 +300,12 @@ async fn request_gas(
     match result {
         Ok(v) => {
             info!(uuid =?id, "Request is successfully served");
+            state.limiter.granted(amount);
             (StatusCode::CREATED, Json(FaucetResponse::from(v)))
         }
         Err(v) => {
             warn!(uuid =?id, "Failed to request gas: {:?}", v);
+            state.limiter.release(ip, recipient, amount);
             (
                 StatusCode::INTERNAL_SERVER_ERROR,
                 Json(FaucetResponse::from(v)),
@@ -185,6 +314,193 @@ async fn request_gas(
     }
 }
 
+/// handler for the requests of the v1 API, which are queued and served in the background
+async fn batch_request_gas(
+    Extension(state): Extension<Arc<AppState>>,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    Json(payload): Json<FaucetRequest>,
+) -> impl IntoResponse {
+    let id = Uuid::new_v4();
+    info!(uuid = ?id, "Got new batch gas request.");
+    let FaucetRequest::FixedAmountRequest(request) = payload;
+    let (ip, amount) = (
+        client_ip(&state.config, peer, &headers),
+        total_amount(&state.config),
+    );
+    if let Err(e) = state
+        .limiter
+        .admit(ip, request.recipient, amount, verification_token(&headers))
+        .await
+    {
+        warn!(uuid = ?id, "Rejected batch gas request from {ip}: {e}");
+        return (
+            rejection_status(&e),
+            Json(BatchFaucetResponse {
+                task: None,
+                error: Some(e.to_string()),
+            }),
+        );
+    }
+    state.tasks.lock().unwrap().insert(
+        id,
+        Task {
+            status: BatchSendStatus {
+                status: BatchSendStatusType::Inprogress,
+                transferred_gas_objects: None,
+            },
+            done_at: None,
+        },
+    );
+    // The receiver lives as long as the server.
+    let _ = state.queue.send(QueuedRequest {
+        id,
+        ip,
+        recipient: request.recipient,
+    });
+    (
+        StatusCode::ACCEPTED,
+        Json(BatchFaucetResponse {
+            task: Some(id.to_string()),
+            error: None,
+        }),
+    )
+}
+
+/// Serves the queued requests of the v1 API every batch interval, up to `batch_request_size` of
+/// them per transaction.
+async fn serve_batches(
+    state: Arc<AppState>,
+    mut queued_requests: mpsc::UnboundedReceiver<QueuedRequest>,
+) {
+    let mut interval = tokio::time::interval(Duration::from_millis(state.config.batch_interval_ms));
+    loop {
+        interval.tick().await;
+        state.tasks.lock().unwrap().retain(|_, task| {
+            task.done_at
+                .map_or(true, |at| at.elapsed() < TASK_RETENTION)
+        });
+        let mut batch = vec![];
+        while let Ok(request) = queued_requests.try_recv() {
+            batch.push(request);
+            if batch.len() == state.config.batch_request_size.max(1) {
+                spawn_monitored_task!(serve_batch(state.clone(), std::mem::take(&mut batch)));
+            }
+        }
+        if !batch.is_empty() {
+            spawn_monitored_task!(serve_batch(state.clone(), batch));
+        }
+    }
+}
+
+async fn serve_batch(state: Arc<AppState>, batch: Vec<QueuedRequest>) {
+    let batch_id = Uuid::new_v4();
+    let amounts = vec![state.config.amount; state.config.num_coins];
+    let requests: Vec<_> = batch
+        .iter()
+        .map(|request| (request.recipient, amounts.clone()))
+        .collect();
+    let result = state.faucet.batch_send(batch_id, &requests).await;
+    let amount = total_amount(&state.config);
+    let mut tasks = state.tasks.lock().unwrap();
+    match result {
+        Ok(receipts) => {
+            for (request, receipt) in batch.iter().zip(receipts) {
+                info!(uuid = ?request.id, ?batch_id, "Request is successfully served");
+                state.limiter.granted(amount);
+                tasks.insert(
+                    request.id,
+                    Task {
+                        status: BatchSendStatus {
+                            status: BatchSendStatusType::Succeeded,
+                            transferred_gas_objects: Some(receipt),
+                        },
+                        done_at: Some(Instant::now()),
+                    },
+                );
+            }
+        }
+        Err(e) => {
+            for request in &batch {
+                warn!(uuid = ?request.id, ?batch_id, "Failed to request gas: {:?}", e);
+                state.limiter.release(request.ip, request.recipient, amount);
+                tasks.insert(
+                    request.id,
+                    Task {
+                        status: BatchSendStatus {
+                            status: BatchSendStatusType::Discarded,
+                            transferred_gas_objects: None,
+                        },
+                        done_at: Some(Instant::now()),
+                    },
+                );
+            }
+        }
+    }
+}
+
+/// handler for the status of the requests of the v1 API
+async fn request_status(
+    Extension(state): Extension<Arc<AppState>>,
+    Path(task): Path<String>,
+) -> impl IntoResponse {
+    let status = Uuid::parse_str(&task).ok().and_then(|id| {
+        let tasks = state.tasks.lock().unwrap();
+        tasks.get(&id).map(|task| task.status.clone())
+    });
+    match status {
+        Some(status) => (
+            StatusCode::OK,
+            Json(BatchStatusFaucetResponse {
+                status: Some(status),
+                error: None,
+            }),
+        ),
+        None => (
+            StatusCode::NOT_FOUND,
+            Json(BatchStatusFaucetResponse {
+                status: None,
+                error: Some(format!("Unknown task {task}")),
+            }),
+        ),
+    }
+}
+
+/// The amount of MIST given to each request.
+fn total_amount(config: &FaucetConfig) -> u64 {
+    config.amount * config.num_coins as u64
+}
+
+/// The IP of the client, the last address of the client IP header when the faucet is configured
+/// with one, as the addresses before it are set by the client.
+fn client_ip(config: &FaucetConfig, peer: SocketAddr, headers: &HeaderMap) -> IpAddr {
+    config
+        .client_ip_header
+        .as_ref()
+        .and_then(|header| headers.get(header))
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.rsplit(',').next())
+        .and_then(|ip| ip.trim().parse().ok())
+        .unwrap_or_else(|| peer.ip())
+}
+
+fn verification_token(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get(VERIFICATION_TOKEN_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string)
+}
+
+fn rejection_status(error: &FaucetError) -> StatusCode {
+    match error {
+        FaucetError::TooManyRequests(_) | FaucetError::QuotaExceeded(_) => {
+            StatusCode::TOO_MANY_REQUESTS
+        }
+        FaucetError::VerificationFailed(_) => StatusCode::FORBIDDEN,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
 async fn create_wallet_context(timeout_secs: u64) -> Result<WalletContext, anyhow::Error> {
     let wallet_conf = sui_config_dir()?.join(SUI_CLIENT_CONFIG);
     info!("Initialize wallet from config path: {:?}", wallet_conf);
@@ -3,8 +3,9 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use prometheus::{
-    register_histogram_with_registry, register_int_counter_with_registry,
-    register_int_gauge_with_registry, Histogram, IntCounter, IntGauge, Registry,
+    register_histogram_with_registry, register_int_counter_vec_with_registry,
+    register_int_counter_with_registry, register_int_gauge_with_registry, Histogram, IntCounter,
+    IntCounterVec, IntGauge, Registry,
 };
 
 /// Prometheus metrics which can be displayed in Grafana, queried and alerted on
@@ -29,6 +30,14 @@ pub struct FaucetMetrics {
     pub(crate) total_discarded_coins: IntGauge,
 }
 
+/// Metrics relevant to the limits on the gas given out
+#[derive(Clone, Debug)]
+pub struct RequestLimitMetrics {
+    pub(crate) total_grants: IntCounter,
+    pub(crate) total_amount_granted: IntCounter,
+    pub(crate) total_requests_rejected: IntCounterVec,
+}
+
 const LATENCY_SEC_BUCKETS: &[f64] = &[
     0.001, 0.005, 0.01, 0.05, 0.1, 0.5, 1., 2.5, 5., 10., 20., 30., 60., 90.,
 ];
@@ -108,3 +117,29 @@ impl FaucetMetrics {
         }
     }
 }
+
+impl RequestLimitMetrics {
+    pub fn new(registry: &Registry) -> Self {
+        Self {
+            total_grants: register_int_counter_with_registry!(
+                "total_grants",
+                "Total number of requests that were given gas",
+                registry,
+            )
+            .unwrap(),
+            total_amount_granted: register_int_counter_with_registry!(
+                "total_amount_granted",
+                "Total amount of MIST given out",
+                registry,
+            )
+            .unwrap(),
+            total_requests_rejected: register_int_counter_vec_with_registry!(
+                "total_requests_rejected",
+                "Total number of requests rejected by the rate limits, quotas or verification",
+                &["reason"],
+                registry,
+            )
+            .unwrap(),
+        }
+    }
+}
@@ -0,0 +1,358 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Limits the gas given out by the faucet, per client IP and per recipient address: the number of
+//! requests within a rolling window, and the amount given within a day (UTC). Requests can also
+//! be required to carry a token, such as the response to a captcha, that an external service
+//! verifies.
+
+use crate::metrics::RequestLimitMetrics;
+use crate::FaucetError;
+use prometheus::Registry;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::hash::Hash;
+use std::net::IpAddr;
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use sui_types::base_types::SuiAddress;
+use tracing::warn;
+
+const SECS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// The header that requests carry their verification token in.
+pub const VERIFICATION_TOKEN_HEADER: &str = "X-Verification-Token";
+
+/// How long the external verification service has to answer.
+const VERIFICATION_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Clone, Debug, Default)]
+pub struct RequestLimitConfig {
+    /// The rolling window the numbers of requests are limited within.
+    pub window: Duration,
+    pub max_requests_per_ip: Option<usize>,
+    pub max_requests_per_address: Option<usize>,
+    /// The amount of MIST given to an IP within a day.
+    pub daily_quota_per_ip: Option<u64>,
+    /// The amount of MIST given to an address within a day.
+    pub daily_quota_per_address: Option<u64>,
+    /// Where the tokens of the requests are verified, see [`VerificationRequest`]. Requests do
+    /// not need a token when unset.
+    pub verification_url: Option<String>,
+}
+
+/// What the faucet posts to the verification service, which answers with a
+/// [`VerificationResponse`].
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct VerificationRequest {
+    pub token: String,
+    pub ip: IpAddr,
+    pub recipient: SuiAddress,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct VerificationResponse {
+    pub success: bool,
+}
+
+pub struct RequestLimiter {
+    config: RequestLimitConfig,
+    http_client: reqwest::Client,
+    usage: Mutex<Usage>,
+    metrics: RequestLimitMetrics,
+}
+
+#[derive(Default)]
+struct Usage {
+    by_ip: HashMap<IpAddr, ClientUsage>,
+    by_address: HashMap<SuiAddress, ClientUsage>,
+    last_pruned: Option<Instant>,
+}
+
+#[derive(Default)]
+struct ClientUsage {
+    /// When the requests within the window were admitted.
+    requests: VecDeque<Instant>,
+    /// The day, as days since the Unix epoch, that `amount` was given in.
+    day: u64,
+    amount: u64,
+}
+
+impl RequestLimiter {
+    pub fn new(config: RequestLimitConfig, prometheus_registry: &Registry) -> Self {
+        Self {
+            config,
+            http_client: reqwest::Client::new(),
+            usage: Mutex::new(Usage::default()),
+            metrics: RequestLimitMetrics::new(prometheus_registry),
+        }
+    }
+
+    /// Admits the request of `amount` from `ip` to `recipient` if the limits allow it, and counts
+    /// it against them. Requests that fail to be served should be given back with
+    /// [`Self::release`].
+    pub async fn admit(
+        &self,
+        ip: IpAddr,
+        recipient: SuiAddress,
+        amount: u64,
+        token: Option<String>,
+    ) -> Result<(), FaucetError> {
+        if let Err(e) = self.verify(ip, recipient, token).await {
+            self.metrics
+                .total_requests_rejected
+                .with_label_values(&["verification"])
+                .inc();
+            return Err(e);
+        }
+        let now = Instant::now();
+        let result = self.admit_at(now, day_of(SystemTime::now()), ip, recipient, amount);
+        if let Err(e) = &result {
+            let reason = match e {
+                FaucetError::TooManyRequests(_) => "rate_limit",
+                _ => "quota",
+            };
+            self.metrics
+                .total_requests_rejected
+                .with_label_values(&[reason])
+                .inc();
+        }
+        result
+    }
+
+    /// Records that the request admitted for `amount` was served.
+    pub fn granted(&self, amount: u64) {
+        self.metrics.total_grants.inc();
+        self.metrics.total_amount_granted.inc_by(amount);
+    }
+
+    /// Gives back the `amount` of a request admitted today that could not be served, which
+    /// still counts towards the number of requests.
+    pub fn release(&self, ip: IpAddr, recipient: SuiAddress, amount: u64) {
+        let day = day_of(SystemTime::now());
+        let usage = &mut *self.usage.lock().unwrap();
+        for client in [
+            usage.by_ip.get_mut(&ip),
+            usage.by_address.get_mut(&recipient),
+        ]
+        .into_iter()
+        .flatten()
+        {
+            if client.day == day {
+                client.amount = client.amount.saturating_sub(amount);
+            }
+        }
+    }
+
+    fn admit_at(
+        &self,
+        now: Instant,
+        day: u64,
+        ip: IpAddr,
+        recipient: SuiAddress,
+        amount: u64,
+    ) -> Result<(), FaucetError> {
+        let window = self.config.window;
+        let mut usage = self.usage.lock().unwrap();
+        if usage.last_pruned.map_or(true, |last_pruned| {
+            now.duration_since(last_pruned) >= window
+        }) {
+            prune(&mut usage.by_ip, now, day, window);
+            prune(&mut usage.by_address, now, day, window);
+            usage.last_pruned = Some(now);
+        }
+
+        let Usage {
+            by_ip, by_address, ..
+        } = &mut *usage;
+        let ip_usage = by_ip.entry(ip).or_default();
+        let address_usage = by_address.entry(recipient).or_default();
+        for client in [&mut *ip_usage, &mut *address_usage] {
+            client.forget_before(now, day, window);
+        }
+
+        if exceeds(ip_usage.requests.len(), self.config.max_requests_per_ip) {
+            return Err(FaucetError::TooManyRequests(format!(
+                "{ip} made too many requests, try again later"
+            )));
+        }
+        if exceeds(
+            address_usage.requests.len(),
+            self.config.max_requests_per_address,
+        ) {
+            return Err(FaucetError::TooManyRequests(format!(
+                "Too many requests for {recipient}, try again later"
+            )));
+        }
+        if exceeds_quota(ip_usage.amount, amount, self.config.daily_quota_per_ip) {
+            return Err(FaucetError::QuotaExceeded(format!(
+                "{ip} reached its daily quota, try again tomorrow"
+            )));
+        }
+        if exceeds_quota(
+            address_usage.amount,
+            amount,
+            self.config.daily_quota_per_address,
+        ) {
+            return Err(FaucetError::QuotaExceeded(format!(
+                "{recipient} reached its daily quota, try again tomorrow"
+            )));
+        }
+
+        for client in [ip_usage, address_usage] {
+            client.requests.push_back(now);
+            client.amount += amount;
+        }
+        Ok(())
+    }
+
+    async fn verify(
+        &self,
+        ip: IpAddr,
+        recipient: SuiAddress,
+        token: Option<String>,
+    ) -> Result<(), FaucetError> {
+        let Some(url) = &self.config.verification_url else {
+            return Ok(());
+        };
+        let token = token.ok_or_else(|| {
+            FaucetError::VerificationFailed("The request has no verification token".to_string())
+        })?;
+        let response = self
+            .http_client
+            .post(url)
+            .timeout(VERIFICATION_TIMEOUT)
+            .json(&VerificationRequest {
+                token,
+                ip,
+                recipient,
+            })
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| {
+                warn!("Failed to verify request from {ip}: {e}");
+                FaucetError::internal("Cannot verify the request")
+            })?
+            .json::<VerificationResponse>()
+            .await
+            .map_err(|e| {
+                warn!("Invalid response from the verification service: {e}");
+                FaucetError::internal("Cannot verify the request")
+            })?;
+        if response.success {
+            Ok(())
+        } else {
+            Err(FaucetError::VerificationFailed(
+                "The verification token was rejected".to_string(),
+            ))
+        }
+    }
+}
+
+impl ClientUsage {
+    fn forget_before(&mut self, now: Instant, day: u64, window: Duration) {
+        while let Some(admitted) = self.requests.front() {
+            if now.duration_since(*admitted) < window {
+                break;
+            }
+            self.requests.pop_front();
+        }
+        if self.day != day {
+            self.day = day;
+            self.amount = 0;
+        }
+    }
+}
+
+/// Forgets the clients that have no request within the window nor any amount given today.
+fn prune<K: Eq + Hash>(
+    clients: &mut HashMap<K, ClientUsage>,
+    now: Instant,
+    day: u64,
+    window: Duration,
+) {
+    clients.retain(|_, client| {
+        client.forget_before(now, day, window);
+        !client.requests.is_empty() || client.amount > 0
+    });
+}
+
+fn exceeds(requests: usize, max_requests: Option<usize>) -> bool {
+    max_requests.map_or(false, |max_requests| requests >= max_requests)
+}
+
+fn exceeds_quota(given: u64, amount: u64, quota: Option<u64>) -> bool {
+    quota.map_or(false, |quota| given.saturating_add(amount) > quota)
+}
+
+fn day_of(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .expect("Time is before the Unix epoch")
+        .as_secs()
+        / SECS_PER_DAY
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limiter(config: RequestLimitConfig) -> RequestLimiter {
+        RequestLimiter::new(config, &Registry::new())
+    }
+
+    #[test]
+    fn test_rate_limits() {
+        let limiter = limiter(RequestLimitConfig {
+            window: Duration::from_secs(60),
+            max_requests_per_ip: Some(2),
+            max_requests_per_address: Some(1),
+            ..Default::default()
+        });
+        let (ip, other_ip) = ([10, 0, 0, 1].into(), [10, 0, 0, 2].into());
+        let (address, other_address) = (SuiAddress::random_for_testing_only(), SuiAddress::ZERO);
+        let start = Instant::now();
+
+        limiter.admit_at(start, 0, ip, address, 1).unwrap();
+        assert!(matches!(
+            limiter.admit_at(start, 0, other_ip, address, 1),
+            Err(FaucetError::TooManyRequests(_))
+        ));
+        limiter.admit_at(start, 0, ip, other_address, 1).unwrap();
+        assert!(matches!(
+            limiter.admit_at(start, 0, ip, SuiAddress::random_for_testing_only(), 1),
+            Err(FaucetError::TooManyRequests(_))
+        ));
+
+        // The requests are forgotten once out of the window.
+        let later = start + Duration::from_secs(60);
+        limiter.admit_at(later, 0, ip, address, 1).unwrap();
+    }
+
+    #[test]
+    fn test_daily_quotas() {
+        let limiter = limiter(RequestLimitConfig {
+            window: Duration::from_secs(60),
+            daily_quota_per_ip: Some(100),
+            daily_quota_per_address: Some(50),
+            ..Default::default()
+        });
+        let ip = [10, 0, 0, 1].into();
+        let (address, other_address) = (SuiAddress::random_for_testing_only(), SuiAddress::ZERO);
+        let now = Instant::now();
+
+        limiter.admit_at(now, 0, ip, address, 50).unwrap();
+        assert!(matches!(
+            limiter.admit_at(now, 0, ip, address, 1),
+            Err(FaucetError::QuotaExceeded(_))
+        ));
+        limiter.admit_at(now, 0, ip, other_address, 50).unwrap();
+        assert!(matches!(
+            limiter.admit_at(now, 0, ip, SuiAddress::random_for_testing_only(), 1),
+            Err(FaucetError::QuotaExceeded(_))
+        ));
+
+        // The quotas are renewed the next day.
+        limiter.admit_at(now, 1, ip, address, 50).unwrap();
+    }
+}
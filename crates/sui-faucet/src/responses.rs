@@ -27,3 +27,31 @@ impl From<FaucetReceipt> for FaucetResponse {
         }
     }
 }
+
+/// The response to a request of the v1 API, which queues the request instead of serving it
+/// before responding. Its status is polled with the task ID.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BatchFaucetResponse {
+    pub task: Option<String>,
+    pub error: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BatchStatusFaucetResponse {
+    pub status: Option<BatchSendStatus>,
+    pub error: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BatchSendStatus {
+    pub status: BatchSendStatusType,
+    pub transferred_gas_objects: Option<FaucetReceipt>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum BatchSendStatusType {
+    Inprogress,
+    Succeeded,
+    Discarded,
+}
@@ -4,10 +4,13 @@
 #[cfg(test)]
 mod test {
     use move_cli::base::test::UnitTestResult;
+    use move_unit_test::UnitTestingConfig;
     use std::path::{Path, PathBuf};
     use sui_framework::build_move_package;
     use sui_framework_build::compiled_package::BuildConfig;
-    use sui_move::unit_test::run_move_unit_tests;
+    use sui_move::unit_test::{
+        run_move_unit_tests, run_move_unit_tests_with_report, TestNamePattern, TestStatus,
+    };
 
     #[test]
     #[cfg_attr(msim, ignore)]
@@ -50,7 +53,53 @@ mod test {
         });
     }
 
+    #[test]
+    #[cfg_attr(msim, ignore)]
+    fn run_move_unit_tests_matching_pattern() {
+        let path = {
+            let mut buf = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+            buf.extend(["..", "..", "sui_programmability", "examples", "basics"]);
+            buf
+        };
+        let move_config = build_tests(&path);
+
+        let (output, report) = run_move_unit_tests_with_report(
+            &path,
+            move_config,
+            UnitTestingConfig {
+                report_statistics: Some(None),
+                ..UnitTestingConfig::default_with_bound(Some(1_000_000))
+            },
+            Some(&TestNamePattern::new("lock::test_*")),
+            false,
+        )
+        .unwrap();
+
+        assert_eq!(report.result(), UnitTestResult::Success);
+        assert_eq!((report.passed, report.failed), (1, 0));
+        let test = &report.tests[0];
+        assert!(test.name.ends_with("::lock::test_lock"), "{}", test.name);
+        assert_eq!(test.status, TestStatus::Pass);
+        assert!(test.gas_used.is_some());
+        assert!(test.time_secs.is_some());
+
+        assert!(!output.contains("test_counter"), "{output}");
+        assert!(
+            output.contains("Total tests: 1; passed: 1; failed: 0"),
+            "{output}"
+        );
+    }
+
     fn check_move_unit_tests(path: &Path) {
+        let move_config = build_tests(path);
+
+        assert_eq!(
+            run_move_unit_tests(path, move_config, None, false).unwrap(),
+            UnitTestResult::Success
+        );
+    }
+
+    fn build_tests(path: &Path) -> move_package::BuildConfig {
         let mut config = BuildConfig::new_for_testing();
         // Make sure to verify tests
         config.config.dev_mode = true;
@@ -62,10 +111,6 @@ mod test {
         // build tests first to enable Sui-specific test code verification
         build_move_package(path, config)
             .unwrap_or_else(|e| panic!("Building tests at {}.\nWith error {e}", path.display()));
-
-        assert_eq!(
-            run_move_unit_tests(path, move_config, None, false).unwrap(),
-            UnitTestResult::Success
-        );
+        move_config
     }
 }
@@ -0,0 +1,76 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! A GraphQL read API, served by fullnodes alongside JSON-RPC. It exposes the same data as the
+//! JSON-RPC read methods, but lets a client fetch an entity along with the entities it refers to,
+//! e.g. the objects of an address with their Display and the transactions that last changed
+//! them, in a single request.
+
+use async_graphql::http::GraphiQLSource;
+use async_graphql::{EmptyMutation, EmptySubscription, Schema};
+use axum::extract::Extension;
+use axum::response::{Html, IntoResponse};
+use axum::routing::get;
+use axum::{Json, Router};
+use mysten_metrics::spawn_monitored_task;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use sui_json_rpc::event_api::EventReadApi;
+use sui_json_rpc::read_api::ReadApi;
+use tokio::task::JoinHandle;
+use tracing::{error, info};
+
+pub mod schema;
+
+#[cfg(test)]
+#[path = "unit_tests/schema_tests.rs"]
+mod schema_tests;
+
+const GRAPHQL_ROUTE: &str = "/graphql";
+
+// Bounds on the work a single query can ask for, as each nested field may read the store.
+const MAX_QUERY_DEPTH: usize = 10;
+const MAX_QUERY_COMPLEXITY: usize = 1000;
+
+pub type SuiSchema = Schema<schema::Query, EmptyMutation, EmptySubscription>;
+
+/// Builds the schema on top of the JSON-RPC read APIs of the node. Queries of events fail when
+/// `event_api` is not given, as on nodes without an event store.
+pub fn build_schema(read_api: ReadApi, event_api: Option<EventReadApi>) -> SuiSchema {
+    Schema::build(schema::Query, EmptyMutation, EmptySubscription)
+        .data(Arc::new(read_api))
+        .data(event_api.map(Arc::new))
+        .limit_depth(MAX_QUERY_DEPTH)
+        .limit_complexity(MAX_QUERY_COMPLEXITY)
+        .finish()
+}
+
+/// Serves `schema` on `address`, at `/graphql`. Opening the same route in a browser gives a
+/// GraphiQL editor to explore the schema. The returned task only completes if the server fails.
+pub fn start_graphql_server(
+    address: SocketAddr,
+    schema: SuiSchema,
+) -> anyhow::Result<JoinHandle<anyhow::Result<()>>> {
+    let app = Router::new()
+        .route(GRAPHQL_ROUTE, get(graphiql).post(graphql))
+        .layer(Extension(schema));
+    let server = axum::Server::try_bind(&address)?.serve(app.into_make_service());
+    info!(address =% server.local_addr(), "starting GraphQL server");
+    Ok(spawn_monitored_task!(async move {
+        server.await.map_err(|e| {
+            error!("GraphQL server failed: {e}");
+            e.into()
+        })
+    }))
+}
+
+async fn graphql(
+    Extension(schema): Extension<SuiSchema>,
+    Json(request): Json<async_graphql::Request>,
+) -> Json<async_graphql::Response> {
+    Json(schema.execute(request).await)
+}
+
+async fn graphiql() -> impl IntoResponse {
+    Html(GraphiQLSource::build().endpoint(GRAPHQL_ROUTE).finish())
+}
@@ -0,0 +1,583 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! The types of the GraphQL schema. Each type wraps the response of the corresponding JSON-RPC
+//! read method, and resolves the entities it refers to (e.g. the owner of an object, or the
+//! checkpoint of a transaction) through the same methods, only when the query asks for them.
+
+use async_graphql::{Context, Error, Json, Object, OutputType, Result, SimpleObject};
+use futures::future::try_join_all;
+use std::collections::BTreeMap;
+use std::str::FromStr;
+use std::sync::Arc;
+use sui_json_rpc::api::{EventReadApiServer, ReadApiServer};
+use sui_json_rpc::event_api::EventReadApi;
+use sui_json_rpc::read_api::ReadApi;
+use sui_json_rpc_types::{
+    Checkpoint, CheckpointId, OwnedObjectsCursor, SuiEvent, SuiEventEnvelope, SuiExecutionStatus,
+    SuiGasCostSummary, SuiObjectData, SuiObjectDataOptions, SuiObjectResponse, SuiParsedData,
+    SuiPastObjectResponse, SuiTransaction, SuiTransactionDataAPI, SuiTransactionEffects,
+    SuiTransactionEffectsAPI, SuiTransactionEvents, SuiTransactionResponse,
+};
+use sui_types::base_types::{ObjectID, SequenceNumber, SuiAddress, TransactionDigest};
+use sui_types::dynamic_field::{DynamicFieldInfo, DynamicFieldName};
+use sui_types::event::EventID;
+use sui_types::object::Owner;
+use sui_types::query::{EventQuery, TransactionQuery};
+
+const DEFAULT_PAGE_SIZE: usize = 20;
+const MAX_PAGE_SIZE: usize = 50;
+
+/// A page of a list, following the pagination of the JSON-RPC API: the cursor of a page is the
+/// first item it contains.
+#[derive(SimpleObject)]
+#[graphql(
+    concrete(name = "ObjectPage", params(ObjectNode)),
+    concrete(name = "TransactionPage", params(TransactionNode)),
+    concrete(name = "DynamicFieldPage", params(DynamicFieldNode)),
+    concrete(name = "EventPage", params(EventNode))
+)]
+pub struct Page<T: OutputType> {
+    pub nodes: Vec<T>,
+    /// Cursor of the next page, `null` on the last page.
+    pub next_cursor: Option<String>,
+}
+
+pub struct Query;
+
+#[Object]
+impl Query {
+    /// The object with the given ID, at the given version or otherwise at its latest version.
+    async fn object(
+        &self,
+        ctx: &Context<'_>,
+        id: String,
+        version: Option<u64>,
+    ) -> Result<Option<ObjectNode>> {
+        load_object(
+            ctx,
+            ObjectID::from_str(&id)?,
+            version.map(SequenceNumber::from),
+        )
+        .await
+    }
+
+    async fn transaction(&self, ctx: &Context<'_>, digest: String) -> Result<TransactionNode> {
+        load_transaction(ctx, TransactionDigest::from_str(&digest)?).await
+    }
+
+    /// The checkpoint with the given sequence number, or otherwise the latest executed one.
+    async fn checkpoint(
+        &self,
+        ctx: &Context<'_>,
+        sequence_number: Option<u64>,
+    ) -> Result<CheckpointNode> {
+        let sequence_number = match sequence_number {
+            Some(sequence_number) => sequence_number,
+            None => {
+                read_api(ctx)
+                    .get_latest_checkpoint_sequence_number()
+                    .await?
+            }
+        };
+        load_checkpoint(ctx, sequence_number).await
+    }
+
+    async fn address(&self, address: String) -> Result<AddressNode> {
+        Ok(AddressNode(SuiAddress::from_str(&address)?))
+    }
+
+    /// The Move events of the given type, e.g. `0x2::devnet_nft::MintNFTEvent`, newest first.
+    #[graphql(complexity = "page_complexity(limit, child_complexity)")]
+    async fn events(
+        &self,
+        ctx: &Context<'_>,
+        event_type: String,
+        limit: Option<usize>,
+        cursor: Option<String>,
+    ) -> Result<Page<EventNode>> {
+        let event_api = ctx
+            .data_unchecked::<Option<Arc<EventReadApi>>>()
+            .as_ref()
+            .ok_or_else(|| Error::new("This node does not serve events"))?;
+        let cursor = cursor.map(|c| parse_event_id(&c)).transpose()?;
+        let page = event_api
+            .get_events(
+                EventQuery::MoveEvent(event_type),
+                cursor,
+                Some(page_size(limit)),
+                Some(true),
+            )
+            .await?;
+        Ok(Page {
+            nodes: page.data.into_iter().map(EventNode).collect(),
+            next_cursor: page.next_cursor.map(|id| format_event_id(&id)),
+        })
+    }
+}
+
+pub struct ObjectNode(SuiObjectData);
+
+#[Object(name = "Object")]
+impl ObjectNode {
+    async fn id(&self) -> String {
+        self.0.object_id.to_string()
+    }
+
+    async fn version(&self) -> u64 {
+        self.0.version.value()
+    }
+
+    async fn digest(&self) -> String {
+        self.0.digest.to_string()
+    }
+
+    #[graphql(name = "type")]
+    async fn type_(&self) -> Option<String> {
+        self.0.type_.as_ref().map(|t| t.to_string())
+    }
+
+    async fn owner(&self) -> Option<Json<Owner>> {
+        self.0.owner.map(Json)
+    }
+
+    /// The address owning the object, if it is owned by an address.
+    async fn owner_address(&self) -> Option<AddressNode> {
+        match self.0.owner {
+            Some(Owner::AddressOwner(address)) => Some(AddressNode(address)),
+            _ => None,
+        }
+    }
+
+    /// The object owning the object, if it is a child object.
+    async fn owner_object(&self, ctx: &Context<'_>) -> Result<Option<ObjectNode>> {
+        match self.0.owner {
+            Some(Owner::ObjectOwner(address)) => load_object(ctx, address.into(), None).await,
+            _ => Ok(None),
+        }
+    }
+
+    /// The transaction that created or last mutated the object.
+    async fn previous_transaction(&self, ctx: &Context<'_>) -> Result<Option<TransactionNode>> {
+        match self.0.previous_transaction {
+            Some(digest) => Ok(Some(load_transaction(ctx, digest).await?)),
+            None => Ok(None),
+        }
+    }
+
+    async fn storage_rebate(&self) -> Option<u64> {
+        self.0.storage_rebate
+    }
+
+    /// The fields of the Display of the object type, rendered for this object.
+    async fn display(&self) -> Option<Json<BTreeMap<String, String>>> {
+        self.0.display.clone().map(Json)
+    }
+
+    async fn content(&self) -> Option<Json<SuiParsedData>> {
+        self.0.content.clone().map(Json)
+    }
+
+    #[graphql(complexity = "page_complexity(limit, child_complexity)")]
+    async fn dynamic_fields(
+        &self,
+        ctx: &Context<'_>,
+        limit: Option<usize>,
+        cursor: Option<String>,
+    ) -> Result<Page<DynamicFieldNode>> {
+        let cursor = cursor.map(|c| ObjectID::from_str(&c)).transpose()?;
+        let page = read_api(ctx)
+            .get_dynamic_fields(self.0.object_id, cursor, Some(page_size(limit)))
+            .await?;
+        Ok(Page {
+            nodes: page.data.into_iter().map(DynamicFieldNode).collect(),
+            next_cursor: page.next_cursor.map(|id| id.to_string()),
+        })
+    }
+}
+
+pub struct TransactionNode(SuiTransactionResponse);
+
+#[Object(name = "Transaction")]
+impl TransactionNode {
+    async fn digest(&self) -> String {
+        self.0.effects.transaction_digest().to_string()
+    }
+
+    async fn sender(&self) -> AddressNode {
+        AddressNode(*self.0.transaction.data.sender())
+    }
+
+    async fn status(&self) -> Json<SuiExecutionStatus> {
+        Json(self.0.effects.status().clone())
+    }
+
+    async fn gas_used(&self) -> Json<SuiGasCostSummary> {
+        Json(self.0.effects.gas_used().clone())
+    }
+
+    async fn timestamp_ms(&self) -> Option<u64> {
+        self.0.timestamp_ms
+    }
+
+    /// The checkpoint the transaction was included in, once it is.
+    async fn checkpoint(&self, ctx: &Context<'_>) -> Result<Option<CheckpointNode>> {
+        match self.0.checkpoint {
+            Some(sequence_number) => Ok(Some(load_checkpoint(ctx, sequence_number).await?)),
+            None => Ok(None),
+        }
+    }
+
+    async fn data(&self) -> Json<SuiTransaction> {
+        Json(self.0.transaction.clone())
+    }
+
+    async fn effects(&self) -> Json<SuiTransactionEffects> {
+        Json(self.0.effects.clone())
+    }
+
+    async fn events(&self) -> Json<SuiTransactionEvents> {
+        Json(self.0.events.clone())
+    }
+
+    /// The objects created, mutated or unwrapped by the transaction, at the version it left them
+    /// in.
+    async fn changed_objects(&self, ctx: &Context<'_>) -> Result<Vec<ObjectNode>> {
+        let effects = &self.0.effects;
+        let objects = try_join_all(
+            effects
+                .created()
+                .iter()
+                .chain(effects.mutated())
+                .chain(effects.unwrapped())
+                .map(|o| load_object(ctx, o.reference.object_id, Some(o.reference.version))),
+        )
+        .await?;
+        Ok(objects.into_iter().flatten().collect())
+    }
+}
+
+pub struct CheckpointNode(Checkpoint);
+
+#[Object(name = "Checkpoint")]
+impl CheckpointNode {
+    async fn sequence_number(&self) -> u64 {
+        self.0.sequence_number
+    }
+
+    async fn digest(&self) -> String {
+        self.0.digest.to_string()
+    }
+
+    async fn previous_digest(&self) -> Option<String> {
+        self.0.previous_digest.as_ref().map(|d| d.to_string())
+    }
+
+    async fn epoch(&self) -> u64 {
+        self.0.epoch
+    }
+
+    async fn timestamp_ms(&self) -> u64 {
+        self.0.timestamp_ms
+    }
+
+    /// Total number of transactions committed since genesis, including those in this
+    /// checkpoint.
+    async fn network_total_transactions(&self) -> u64 {
+        self.0.network_total_transactions
+    }
+
+    #[graphql(complexity = "page_complexity(limit, child_complexity)")]
+    async fn transactions(
+        &self,
+        ctx: &Context<'_>,
+        limit: Option<usize>,
+        cursor: Option<String>,
+    ) -> Result<Page<TransactionNode>> {
+        let (digests, next_cursor) = paginate(
+            self.0.transactions.clone(),
+            |d| d.to_string(),
+            cursor,
+            page_size(limit),
+        )?;
+        Ok(Page {
+            nodes: load_transactions(ctx, digests).await?,
+            next_cursor,
+        })
+    }
+}
+
+pub struct AddressNode(SuiAddress);
+
+#[Object(name = "Address")]
+impl AddressNode {
+    async fn address(&self) -> String {
+        self.0.to_string()
+    }
+
+    /// The objects owned by the address, by object ID. All the pages of a listing show the
+    /// objects as they were at the end of the checkpoint the first page was read at.
+    #[graphql(complexity = "page_complexity(limit, child_complexity)")]
+    async fn objects(
+        &self,
+        ctx: &Context<'_>,
+        limit: Option<usize>,
+        cursor: Option<String>,
+    ) -> Result<Page<ObjectNode>> {
+        let cursor = cursor.map(|c| parse_owned_objects_cursor(&c)).transpose()?;
+        let page = read_api(ctx)
+            .get_owned_objects(self.0, cursor, Some(page_size(limit)), None)
+            .await?;
+        let objects = try_join_all(
+            page.data
+                .iter()
+                .map(|o| load_object(ctx, o.object_id, None)),
+        )
+        .await?;
+        Ok(Page {
+            nodes: objects.into_iter().flatten().collect(),
+            next_cursor: page.next_cursor.map(|c| format_owned_objects_cursor(&c)),
+        })
+    }
+
+    /// The transactions sent by the address, newest first.
+    #[graphql(complexity = "page_complexity(limit, child_complexity)")]
+    async fn sent_transactions(
+        &self,
+        ctx: &Context<'_>,
+        limit: Option<usize>,
+        cursor: Option<String>,
+    ) -> Result<Page<TransactionNode>> {
+        query_transactions(ctx, TransactionQuery::FromAddress(self.0), limit, cursor).await
+    }
+
+    /// The transactions that left an object owned by the address, newest first.
+    #[graphql(complexity = "page_complexity(limit, child_complexity)")]
+    async fn received_transactions(
+        &self,
+        ctx: &Context<'_>,
+        limit: Option<usize>,
+        cursor: Option<String>,
+    ) -> Result<Page<TransactionNode>> {
+        query_transactions(ctx, TransactionQuery::ToAddress(self.0), limit, cursor).await
+    }
+}
+
+pub struct DynamicFieldNode(DynamicFieldInfo);
+
+#[Object(name = "DynamicField")]
+impl DynamicFieldNode {
+    async fn name(&self) -> Json<DynamicFieldName> {
+        Json(self.0.name.clone())
+    }
+
+    async fn object_type(&self) -> String {
+        self.0.object_type.clone()
+    }
+
+    /// The object holding the value of the field, or the object stored in the field for dynamic
+    /// object fields.
+    async fn object(&self, ctx: &Context<'_>) -> Result<Option<ObjectNode>> {
+        load_object(ctx, self.0.object_id, Some(self.0.version)).await
+    }
+}
+
+pub struct EventNode(SuiEventEnvelope);
+
+#[Object(name = "Event")]
+impl EventNode {
+    async fn id(&self) -> String {
+        format_event_id(&self.0.id)
+    }
+
+    async fn timestamp_ms(&self) -> u64 {
+        self.0.timestamp
+    }
+
+    async fn event(&self) -> Json<SuiEvent> {
+        Json(self.0.event.clone())
+    }
+
+    async fn transaction(&self, ctx: &Context<'_>) -> Result<TransactionNode> {
+        load_transaction(ctx, self.0.tx_digest).await
+    }
+}
+
+fn read_api<'a>(ctx: &Context<'a>) -> &'a ReadApi {
+    ctx.data_unchecked::<Arc<ReadApi>>()
+}
+
+fn object_options() -> SuiObjectDataOptions {
+    SuiObjectDataOptions {
+        show_type: true,
+        show_owner: true,
+        show_previous_transaction: true,
+        show_display: true,
+        show_content: true,
+        show_bcs: false,
+        show_storage_rebate: true,
+    }
+}
+
+async fn load_object(
+    ctx: &Context<'_>,
+    object_id: ObjectID,
+    version: Option<SequenceNumber>,
+) -> Result<Option<ObjectNode>> {
+    let data = match version {
+        Some(version) => match read_api(ctx)
+            .try_get_past_object(object_id, version, Some(object_options()))
+            .await?
+        {
+            SuiPastObjectResponse::VersionFound(data) => Some(data),
+            _ => None,
+        },
+        None => match read_api(ctx)
+            .get_object_with_options(object_id, Some(object_options()))
+            .await?
+        {
+            SuiObjectResponse::Exists(data) => Some(data),
+            _ => None,
+        },
+    };
+    Ok(data.map(ObjectNode))
+}
+
+async fn load_transaction(ctx: &Context<'_>, digest: TransactionDigest) -> Result<TransactionNode> {
+    Ok(TransactionNode(
+        read_api(ctx).get_transaction(digest).await?,
+    ))
+}
+
+async fn load_transactions(
+    ctx: &Context<'_>,
+    digests: Vec<TransactionDigest>,
+) -> Result<Vec<TransactionNode>> {
+    if digests.is_empty() {
+        return Ok(vec![]);
+    }
+    let transactions = read_api(ctx).multi_get_transactions(digests).await?;
+    Ok(transactions.into_iter().map(TransactionNode).collect())
+}
+
+async fn load_checkpoint(ctx: &Context<'_>, sequence_number: u64) -> Result<CheckpointNode> {
+    let checkpoint = read_api(ctx)
+        .get_checkpoint(CheckpointId::SequenceNumber(sequence_number))
+        .await?;
+    Ok(CheckpointNode(checkpoint))
+}
+
+async fn query_transactions(
+    ctx: &Context<'_>,
+    query: TransactionQuery,
+    limit: Option<usize>,
+    cursor: Option<String>,
+) -> Result<Page<TransactionNode>> {
+    let cursor = cursor
+        .map(|c| TransactionDigest::from_str(&c))
+        .transpose()?;
+    let page = read_api(ctx)
+        .get_transactions(query, cursor, Some(page_size(limit)), Some(true))
+        .await?;
+    Ok(Page {
+        nodes: load_transactions(ctx, page.data).await?,
+        next_cursor: page.next_cursor.map(|d| d.to_string()),
+    })
+}
+
+fn page_size(limit: Option<usize>) -> usize {
+    limit.unwrap_or(DEFAULT_PAGE_SIZE).min(MAX_PAGE_SIZE)
+}
+
+/// Complexity of a list field: the fields asked for its nodes are resolved once per node, for
+/// up to `limit` nodes.
+fn page_complexity(limit: Option<usize>, child_complexity: usize) -> usize {
+    page_size(limit).saturating_mul(child_complexity)
+}
+
+/// Returns the `limit` items starting at the one whose key is `cursor`, along with the key of
+/// the item following them.
+fn paginate<T>(
+    items: Vec<T>,
+    key: impl Fn(&T) -> String,
+    cursor: Option<String>,
+    limit: usize,
+) -> Result<(Vec<T>, Option<String>)> {
+    let start = match cursor {
+        Some(cursor) => items
+            .iter()
+            .position(|item| key(item) == cursor)
+            .ok_or_else(|| Error::new(format!("Unknown cursor {cursor}")))?,
+        None => 0,
+    };
+    let mut items = items.into_iter().skip(start);
+    let page = items.by_ref().take(limit).collect();
+    Ok((page, items.next().map(|item| key(&item))))
+}
+
+fn format_event_id(id: &EventID) -> String {
+    format!("{}:{}", id.tx_digest, id.event_seq)
+}
+
+fn parse_event_id(s: &str) -> Result<EventID> {
+    let (tx_digest, event_seq) = s
+        .split_once(':')
+        .ok_or_else(|| Error::new(format!("Invalid event cursor {s}")))?;
+    Ok(EventID {
+        tx_digest: TransactionDigest::from_str(tx_digest)?,
+        event_seq: event_seq.parse()?,
+    })
+}
+
+fn format_owned_objects_cursor(cursor: &OwnedObjectsCursor) -> String {
+    format!("{}:{}", cursor.checkpoint, cursor.object_id)
+}
+
+fn parse_owned_objects_cursor(s: &str) -> Result<OwnedObjectsCursor> {
+    let (checkpoint, object_id) = s
+        .split_once(':')
+        .ok_or_else(|| Error::new(format!("Invalid object cursor {s}")))?;
+    Ok(OwnedObjectsCursor {
+        checkpoint: checkpoint.parse()?,
+        object_id: ObjectID::from_str(object_id)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_page_complexity() {
+        assert_eq!(page_complexity(None, 3), DEFAULT_PAGE_SIZE * 3);
+        assert_eq!(page_complexity(Some(2), 3), 6);
+        assert_eq!(page_complexity(Some(usize::MAX), 3), MAX_PAGE_SIZE * 3);
+    }
+
+    #[test]
+    fn test_owned_objects_cursor() {
+        let cursor = OwnedObjectsCursor {
+            checkpoint: 42,
+            object_id: ObjectID::random(),
+        };
+        let formatted = format_owned_objects_cursor(&cursor);
+        assert_eq!(parse_owned_objects_cursor(&formatted).unwrap(), cursor);
+        assert!(parse_owned_objects_cursor(&cursor.object_id.to_string()).is_err());
+    }
+
+    #[test]
+    fn test_paginate() {
+        let items: Vec<u32> = (0..5).collect();
+        let key = |i: &u32| i.to_string();
+
+        let (page, next_cursor) = paginate(items.clone(), key, None, 2).unwrap();
+        assert_eq!(page, vec![0, 1]);
+        assert_eq!(next_cursor.as_deref(), Some("2"));
+
+        let (page, next_cursor) = paginate(items.clone(), key, next_cursor, 10).unwrap();
+        assert_eq!(page, vec![2, 3, 4]);
+        assert_eq!(next_cursor, None);
+
+        assert!(paginate(items, key, Some("7".to_string()), 2).is_err());
+    }
+}
@@ -0,0 +1,144 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{build_schema, SuiSchema};
+use serde_json::Value;
+use std::collections::BTreeSet;
+use sui_json_rpc::api::ReadApiClient;
+use sui_json_rpc::event_api::EventReadApi;
+use sui_json_rpc::read_api::{BuildInfo, ReadApi};
+use sui_protocol_config::SupportedProtocolVersions;
+use test_utils::network::{TestCluster, TestClusterBuilder};
+
+fn schema(cluster: &TestCluster) -> SuiSchema {
+    let state = cluster.fullnode_handle.sui_node.state();
+    let build_info = BuildInfo {
+        version: "test",
+        git_revision: "test",
+        supported_protocol_versions: SupportedProtocolVersions::SYSTEM_DEFAULT,
+    };
+    build_schema(
+        ReadApi::new(state.clone(), None, build_info),
+        state
+            .event_handler
+            .clone()
+            .map(|event_handler| EventReadApi::new(state.clone(), event_handler)),
+    )
+}
+
+async fn execute(schema: &SuiSchema, query: String) -> Value {
+    let response = schema.execute(query).await;
+    assert!(response.errors.is_empty(), "{:?}", response.errors);
+    response.data.into_json().unwrap()
+}
+
+#[tokio::test]
+async fn test_address_objects_pages() -> Result<(), anyhow::Error> {
+    let cluster = TestClusterBuilder::new().build().await?;
+    let schema = schema(&cluster);
+    let address = cluster.accounts[0];
+
+    let mut object_ids = BTreeSet::new();
+    let mut cursor = Value::Null;
+    loop {
+        let data = execute(
+            &schema,
+            format!(
+                r#"{{ address(address: "{address}") {{
+                    objects(limit: 2, cursor: {cursor}) {{ nodes {{ id ownerAddress {{ address }} }} nextCursor }}
+                }} }}"#
+            ),
+        )
+        .await;
+        let page = &data["address"]["objects"];
+        let nodes = page["nodes"].as_array().unwrap();
+        assert!(nodes.len() <= 2);
+        for node in nodes {
+            assert_eq!(node["ownerAddress"]["address"], address.to_string());
+            assert!(object_ids.insert(node["id"].as_str().unwrap().to_string()));
+        }
+        cursor = page["nextCursor"].clone();
+        if cursor.is_null() {
+            break;
+        }
+    }
+
+    let expected: BTreeSet<_> = cluster
+        .rpc_client()
+        .get_objects_owned_by_address(address)
+        .await?
+        .into_iter()
+        .map(|o| o.object_id.to_string())
+        .collect();
+    assert_eq!(object_ids, expected);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_object_transaction_and_checkpoint() -> Result<(), anyhow::Error> {
+    let cluster = TestClusterBuilder::new().build().await?;
+    let schema = schema(&cluster);
+    let object_id = cluster
+        .rpc_client()
+        .get_objects_owned_by_address(cluster.accounts[0])
+        .await?[0]
+        .object_id;
+
+    let data = execute(
+        &schema,
+        format!(
+            r#"{{ object(id: "{object_id}") {{
+                id
+                previousTransaction {{ digest checkpoint {{ sequenceNumber }} }}
+            }} }}"#
+        ),
+    )
+    .await;
+    let object = &data["object"];
+    assert_eq!(object["id"], object_id.to_string());
+    let transaction = &object["previousTransaction"];
+    let digest = transaction["digest"].as_str().unwrap();
+    let sequence_number = transaction["checkpoint"]["sequenceNumber"]
+        .as_u64()
+        .unwrap();
+
+    // The transaction is listed among those of its checkpoint.
+    let data = execute(
+        &schema,
+        format!(
+            r#"{{ checkpoint(sequenceNumber: {sequence_number}) {{
+                transactions(limit: 50) {{ nodes {{ digest }} }}
+            }} }}"#
+        ),
+    )
+    .await;
+    let digests = data["checkpoint"]["transactions"]["nodes"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|node| node["digest"].as_str().unwrap())
+        .collect::<Vec<_>>();
+    assert!(digests.contains(&digest));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_nested_pages_are_too_complex() -> Result<(), anyhow::Error> {
+    let cluster = TestClusterBuilder::new().build().await?;
+    let schema = schema(&cluster);
+    let address = cluster.accounts[0];
+
+    // Each object of the page may have up to 50 dynamic fields, each resolving an object.
+    let response = schema
+        .execute(format!(
+            r#"{{ address(address: "{address}") {{
+                objects(limit: 50) {{ nodes {{
+                    dynamicFields(limit: 50) {{ nodes {{ object {{ id }} }} }}
+                }} }}
+            }} }}"#
+        ))
+        .await;
+    assert_eq!(response.errors.len(), 1);
+    assert!(response.errors[0].message.contains("too complex"));
+    Ok(())
+}
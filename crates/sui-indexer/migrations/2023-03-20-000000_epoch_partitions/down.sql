@@ -0,0 +1,65 @@
+CREATE TABLE transactions_unpartitioned
+(
+    id                          BIGINT    NOT NULL DEFAULT nextval('transactions_id_seq') PRIMARY KEY,
+    transaction_digest          VARCHAR(255) NOT NULL,
+    sender                      VARCHAR(255) NOT NULL,
+    recipients                  TEXT[]    NOT NULL,
+    checkpoint_sequence_number  BIGINT    NOT NULL,
+    transaction_time            TIMESTAMP,
+    transaction_kinds           TEXT[]    NOT NULL,
+    created                     TEXT[]    NOT NULL,
+    mutated                     TEXT[]    NOT NULL,
+    deleted                     TEXT[]    NOT NULL,
+    unwrapped                   TEXT[]    NOT NULL,
+    wrapped                     TEXT[]    NOT NULL,
+    move_calls                  TEXT[]    NOT NULL,
+    gas_object_id               VARCHAR(255) NOT NULL,
+    gas_object_sequence         BIGINT    NOT NULL,
+    gas_object_digest           VARCHAR(255) NOT NULL,
+    gas_budget                  BIGINT    NOT NULL,
+    total_gas_cost              BIGINT    NOT NULL,
+    computation_cost            BIGINT    NOT NULL,
+    storage_cost                BIGINT    NOT NULL,
+    storage_rebate              BIGINT    NOT NULL,
+    gas_price                   BIGINT    NOT NULL,
+    transaction_content         TEXT      NOT NULL,
+    transaction_effects_content TEXT      NOT NULL,
+    confirmed_local_execution   BOOLEAN,
+    UNIQUE (transaction_digest)
+);
+
+CREATE TABLE events_unpartitioned
+(
+    id                 BIGINT       NOT NULL DEFAULT nextval('events_id_seq') PRIMARY KEY,
+    transaction_digest VARCHAR(255) NOT NULL,
+    event_sequence     BIGINT       NOT NULL,
+    event_time         TIMESTAMP,
+    event_type         VARCHAR      NOT NULL,
+    event_content      VARCHAR      NOT NULL
+);
+
+INSERT INTO transactions_unpartitioned
+SELECT id, transaction_digest, sender, recipients, checkpoint_sequence_number, transaction_time,
+       transaction_kinds, created, mutated, deleted, unwrapped, wrapped, move_calls, gas_object_id,
+       gas_object_sequence, gas_object_digest, gas_budget, total_gas_cost, computation_cost,
+       storage_cost, storage_rebate, gas_price, transaction_content, transaction_effects_content,
+       confirmed_local_execution
+FROM transactions;
+ALTER SEQUENCE transactions_id_seq OWNED BY transactions_unpartitioned.id;
+DROP TABLE transactions;
+ALTER TABLE transactions_unpartitioned RENAME TO transactions;
+
+INSERT INTO events_unpartitioned
+SELECT id, transaction_digest, event_sequence, event_time, event_type, event_content
+FROM events;
+ALTER SEQUENCE events_id_seq OWNED BY events_unpartitioned.id;
+DROP TABLE events;
+ALTER TABLE events_unpartitioned RENAME TO events;
+
+CREATE INDEX transactions_transaction_digest ON transactions (transaction_digest);
+CREATE INDEX transactions_transaction_time ON transactions (transaction_time);
+CREATE INDEX transactions_sender ON transactions (sender);
+CREATE INDEX transactions_gas_object_id ON transactions (gas_object_id);
+CREATE INDEX transactions_checkpoint_sequence_number ON transactions (checkpoint_sequence_number);
+CREATE INDEX events_transaction_digest ON events (transaction_digest);
+CREATE INDEX events_event_time ON events (event_time);
@@ -0,0 +1,83 @@
+-- Partition transactions and events by epoch, so that the data of old epochs can be dropped a
+-- partition at a time. The epoch of the existing rows is the epoch of their checkpoint. The
+-- indexer creates the partitions of later epochs as it reaches them.
+CREATE TABLE transactions_partitioned
+(
+    id                          BIGINT    NOT NULL DEFAULT nextval('transactions_id_seq'),
+    transaction_digest          VARCHAR(255) NOT NULL,
+    sender                      VARCHAR(255) NOT NULL,
+    recipients                  TEXT[]    NOT NULL,
+    checkpoint_sequence_number  BIGINT    NOT NULL,
+    transaction_time            TIMESTAMP,
+    transaction_kinds           TEXT[]    NOT NULL,
+    created                     TEXT[]    NOT NULL,
+    mutated                     TEXT[]    NOT NULL,
+    deleted                     TEXT[]    NOT NULL,
+    unwrapped                   TEXT[]    NOT NULL,
+    wrapped                     TEXT[]    NOT NULL,
+    move_calls                  TEXT[]    NOT NULL,
+    gas_object_id               VARCHAR(255) NOT NULL,
+    gas_object_sequence         BIGINT    NOT NULL,
+    gas_object_digest           VARCHAR(255) NOT NULL,
+    gas_budget                  BIGINT    NOT NULL,
+    total_gas_cost              BIGINT    NOT NULL,
+    computation_cost            BIGINT    NOT NULL,
+    storage_cost                BIGINT    NOT NULL,
+    storage_rebate              BIGINT    NOT NULL,
+    gas_price                   BIGINT    NOT NULL,
+    transaction_content         TEXT      NOT NULL,
+    transaction_effects_content TEXT      NOT NULL,
+    confirmed_local_execution   BOOLEAN,
+    epoch                       BIGINT    NOT NULL,
+    CONSTRAINT transactions_pk PRIMARY KEY (id, epoch),
+    CONSTRAINT transactions_digest_unique UNIQUE (transaction_digest, epoch)
+) PARTITION BY RANGE (epoch);
+
+CREATE TABLE events_partitioned
+(
+    id                 BIGINT       NOT NULL DEFAULT nextval('events_id_seq'),
+    transaction_digest VARCHAR(255) NOT NULL,
+    event_sequence     BIGINT       NOT NULL,
+    event_time         TIMESTAMP,
+    event_type         VARCHAR      NOT NULL,
+    event_content      VARCHAR      NOT NULL,
+    epoch              BIGINT       NOT NULL,
+    CONSTRAINT events_pk PRIMARY KEY (id, epoch)
+) PARTITION BY RANGE (epoch);
+
+DO
+$body$
+DECLARE
+    epoch_id BIGINT;
+BEGIN
+    FOR epoch_id IN SELECT generate_series(0, COALESCE(MAX(epoch), 0)) FROM checkpoints
+    LOOP
+        EXECUTE format('CREATE TABLE transactions_partition_%s PARTITION OF transactions_partitioned FOR VALUES FROM (%s) TO (%s);', epoch_id, epoch_id, epoch_id + 1);
+        EXECUTE format('CREATE TABLE events_partition_%s PARTITION OF events_partitioned FOR VALUES FROM (%s) TO (%s);', epoch_id, epoch_id, epoch_id + 1);
+    END LOOP;
+END;
+$body$;
+
+INSERT INTO transactions_partitioned
+SELECT t.*, c.epoch
+FROM transactions t
+         JOIN checkpoints c ON t.checkpoint_sequence_number = c.sequence_number;
+ALTER SEQUENCE transactions_id_seq OWNED BY transactions_partitioned.id;
+DROP TABLE transactions;
+ALTER TABLE transactions_partitioned RENAME TO transactions;
+
+INSERT INTO events_partitioned
+SELECT e.*, t.epoch
+FROM events e
+         JOIN transactions t ON e.transaction_digest = t.transaction_digest;
+ALTER SEQUENCE events_id_seq OWNED BY events_partitioned.id;
+DROP TABLE events;
+ALTER TABLE events_partitioned RENAME TO events;
+
+CREATE INDEX transactions_transaction_digest ON transactions (transaction_digest);
+CREATE INDEX transactions_transaction_time ON transactions (transaction_time);
+CREATE INDEX transactions_sender ON transactions (sender);
+CREATE INDEX transactions_gas_object_id ON transactions (gas_object_id);
+CREATE INDEX transactions_checkpoint_sequence_number ON transactions (checkpoint_sequence_number);
+CREATE INDEX events_transaction_digest ON events (transaction_digest);
+CREATE INDEX events_event_time ON events (event_time);
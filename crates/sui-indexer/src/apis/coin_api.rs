@@ -8,10 +8,12 @@ use jsonrpsee::RpcModule;
 use sui_json_rpc::api::CoinReadApiClient;
 use sui_json_rpc::api::CoinReadApiServer;
 use sui_json_rpc::SuiRpcModule;
-use sui_json_rpc_types::{Balance, CoinPage, SuiCoinMetadata};
+use sui_json_rpc_types::{
+    Balance, BalanceChangePage, CoinBalanceCursor, CoinPage, CoinsByBalancePage, SuiCoinMetadata,
+};
 use sui_open_rpc::Module;
 use sui_types::balance::Supply;
-use sui_types::base_types::{ObjectID, SuiAddress};
+use sui_types::base_types::{ObjectID, SuiAddress, TransactionDigest};
 
 pub(crate) struct CoinReadApi {
     fullnode: HttpClient,
@@ -39,6 +41,19 @@ impl CoinReadApiServer for CoinReadApi {
             .await
     }
 
+    async fn get_coins_by_balance(
+        &self,
+        owner: SuiAddress,
+        coin_type: Option<String>,
+        min_balance: Option<u64>,
+        cursor: Option<CoinBalanceCursor>,
+        limit: Option<usize>,
+    ) -> RpcResult<CoinsByBalancePage> {
+        self.fullnode
+            .get_coins_by_balance(owner, coin_type, min_balance, cursor, limit)
+            .await
+    }
+
     async fn get_all_coins(
         &self,
         owner: SuiAddress,
@@ -60,6 +75,19 @@ impl CoinReadApiServer for CoinReadApi {
         self.fullnode.get_all_balances(owner).await
     }
 
+    async fn get_balance_changes(
+        &self,
+        owner: SuiAddress,
+        coin_type: Option<String>,
+        cursor: Option<TransactionDigest>,
+        limit: Option<usize>,
+        descending_order: Option<bool>,
+    ) -> RpcResult<BalanceChangePage> {
+        self.fullnode
+            .get_balance_changes(owner, coin_type, cursor, limit, descending_order)
+            .await
+    }
+
     async fn get_coin_metadata(&self, coin_type: String) -> RpcResult<SuiCoinMetadata> {
         self.fullnode.get_coin_metadata(coin_type).await
     }
@@ -1,32 +1,94 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::errors::IndexerError;
+use crate::models::events::Event;
+use crate::store::IndexerStore;
 use async_trait::async_trait;
+use chrono::NaiveDateTime;
 use jsonrpsee::core::RpcResult;
 use jsonrpsee::http_client::HttpClient;
 use jsonrpsee::types::{SubscriptionEmptyError, SubscriptionResult};
 use jsonrpsee::{RpcModule, SubscriptionSink};
-use sui_json_rpc::api::{EventReadApiClient, EventReadApiServer};
+use sui_json_rpc::api::{cap_page_limit, cap_page_size, EventReadApiClient, EventReadApiServer};
 use sui_json_rpc::SuiRpcModule;
-use sui_json_rpc_types::{EventPage, SuiEventFilter};
+use sui_json_rpc_types::{EventPage, SuiEventEnvelope, SuiEventFilter};
 use sui_open_rpc::Module;
+use sui_types::digests::TransactionDigest;
 use sui_types::event::EventID;
+use sui_types::filter::TransactionFilter;
 use sui_types::query::EventQuery;
 
-pub(crate) struct EventReadApi {
+pub(crate) struct EventReadApi<S> {
     fullnode: HttpClient,
+    state: S,
 }
 
-impl EventReadApi {
-    pub fn new(fullnode_client: HttpClient) -> Self {
+impl<S: IndexerStore> EventReadApi<S> {
+    pub fn new(state: S, fullnode_client: HttpClient) -> Self {
         Self {
+            state,
             fullnode: fullnode_client,
         }
     }
+
+    async fn get_events(
+        &self,
+        query: EventQuery,
+        cursor: Option<EventID>,
+        limit: Option<usize>,
+        descending_order: Option<bool>,
+    ) -> RpcResult<EventPage> {
+        let limit = cap_page_limit(limit);
+        let is_descending = descending_order.unwrap_or_default();
+        let event_seq_number = self.state.get_event_sequence_by_id(cursor)?;
+
+        let events_from_db = match query {
+            EventQuery::All => {
+                self.state
+                    .get_all_events_page(event_seq_number, limit + 1, is_descending)
+            }
+            EventQuery::Transaction(digest) => self.state.get_events_page_by_transaction(
+                digest.to_string(),
+                event_seq_number,
+                limit + 1,
+                is_descending,
+            ),
+            EventQuery::TimeRange {
+                start_time,
+                end_time,
+            } => self.state.get_events_page_by_time_range(
+                to_date_time(start_time)?,
+                to_date_time(end_time)?,
+                event_seq_number,
+                limit + 1,
+                is_descending,
+            ),
+            _ => unreachable!("served by the fullnode"),
+        }?;
+
+        // events here are of size (limit + 1), where the last one is the cursor for the next page
+        let mut events = events_from_db
+            .into_iter()
+            .map(to_event_envelope)
+            .collect::<Result<Vec<_>, _>>()?;
+        let next_cursor = events.get(limit).map(|event| event.id.clone());
+        events.truncate(limit);
+        let next_cursor = cap_page_size(&mut events, next_cursor, |event| event.id.clone());
+
+        Ok(EventPage {
+            data: events,
+            has_next_page: next_cursor.is_some(),
+            next_cursor,
+        })
+    }
 }
 
 #[async_trait]
-impl EventReadApiServer for EventReadApi {
+impl<S> EventReadApiServer for EventReadApi<S>
+where
+    S: IndexerStore + Sync + Send + 'static,
+{
     async fn get_events(
         &self,
         query: EventQuery,
@@ -34,8 +96,18 @@ impl EventReadApiServer for EventReadApi {
         limit: Option<usize>,
         descending_order: Option<bool>,
     ) -> RpcResult<EventPage> {
-        self.fullnode
-            .get_events(query, cursor, limit, descending_order)
+        // Only events are indexed, not the objects, senders or modules they relate to, fall back
+        // to the fullnode for the queries on those.
+        if !matches!(
+            query,
+            EventQuery::All | EventQuery::Transaction(_) | EventQuery::TimeRange { .. }
+        ) {
+            return self
+                .fullnode
+                .get_events(query, cursor, limit, descending_order)
+                .await;
+        }
+        self.get_events(query, cursor, limit, descending_order)
             .await
     }
 
@@ -47,9 +119,21 @@ impl EventReadApiServer for EventReadApi {
         // subscription not supported by subscription yet
         Err(SubscriptionEmptyError)
     }
+
+    fn subscribe_transaction(
+        &self,
+        mut _sink: SubscriptionSink,
+        _filter: TransactionFilter,
+    ) -> SubscriptionResult {
+        // subscription not supported by subscription yet
+        Err(SubscriptionEmptyError)
+    }
 }
 
-impl SuiRpcModule for EventReadApi {
+impl<S> SuiRpcModule for EventReadApi<S>
+where
+    S: IndexerStore + Sync + Send + 'static,
+{
     fn rpc(self) -> RpcModule<Self> {
         self.into_rpc()
     }
@@ -58,3 +142,41 @@ impl SuiRpcModule for EventReadApi {
         sui_json_rpc::api::EventReadApiOpenRpc::module_doc()
     }
 }
+
+fn to_date_time(timestamp_ms: u64) -> Result<NaiveDateTime, IndexerError> {
+    NaiveDateTime::from_timestamp_millis(timestamp_ms as i64).ok_or_else(|| {
+        IndexerError::DateTimeParsingError(format!(
+            "Cannot convert timestamp {} to NaiveDateTime",
+            timestamp_ms
+        ))
+    })
+}
+
+fn to_event_envelope(event: Event) -> Result<SuiEventEnvelope, IndexerError> {
+    let tx_digest = event
+        .transaction_digest
+        .parse::<TransactionDigest>()
+        .map_err(|e| {
+            IndexerError::EventDeserializationError(format!(
+                "Failed to decode transaction digest: {:?} with err: {:?}",
+                event.transaction_digest, e
+            ))
+        })?;
+    let sui_event = serde_json::from_str(&event.event_content).map_err(|e| {
+        IndexerError::EventDeserializationError(format!(
+            "Failed to deserialize event {:?} of transaction {:?} with err: {:?}",
+            event.event_sequence, event.transaction_digest, e
+        ))
+    })?;
+    Ok(SuiEventEnvelope {
+        timestamp: event
+            .event_time
+            .map_or(0, |event_time| event_time.timestamp_millis() as u64),
+        tx_digest,
+        id: EventID {
+            tx_digest,
+            event_seq: event.event_sequence,
+        },
+        event: sui_event,
+    })
+}
@@ -11,6 +11,7 @@ use sui_json_rpc_types::{SuiCommittee, SuiSystemStateRpc};
 use sui_open_rpc::Module;
 use sui_types::base_types::{EpochId, SuiAddress};
 use sui_types::governance::DelegatedStake;
+use sui_types::sui_system_state::gas_price_survey::SuiGasPriceSurvey;
 use sui_types::sui_system_state::sui_system_state_inner_v1::ValidatorMetadataV1;
 use sui_types::sui_system_state::sui_system_state_summary::SuiSystemStateSummary;
 
@@ -51,6 +52,10 @@ impl GovernanceReadApiServer for GovernanceReadApi {
     async fn get_reference_gas_price(&self) -> RpcResult<u64> {
         self.fullnode.get_reference_gas_price().await
     }
+
+    async fn get_gas_price_survey(&self) -> RpcResult<SuiGasPriceSurvey> {
+        self.fullnode.get_gas_price_survey().await
+    }
 }
 
 impl SuiRpcModule for GovernanceReadApi {
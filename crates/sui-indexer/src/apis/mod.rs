@@ -5,6 +5,7 @@ mod bls_api;
 mod coin_api;
 mod event_api;
 mod governance_api;
+mod name_service_api;
 mod read_api;
 mod transaction_builder_api;
 mod write_api;
@@ -13,6 +14,7 @@ pub(crate) use bls_api::ThresholdBlsApi;
 pub(crate) use coin_api::CoinReadApi;
 pub(crate) use event_api::EventReadApi;
 pub(crate) use governance_api::GovernanceReadApi;
+pub(crate) use name_service_api::NameServiceApi;
 pub(crate) use read_api::ReadApi;
 pub(crate) use transaction_builder_api::TransactionBuilderApi;
 pub(crate) use write_api::WriteApi;
@@ -0,0 +1,45 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use async_trait::async_trait;
+use jsonrpsee::core::RpcResult;
+use jsonrpsee::http_client::HttpClient;
+use jsonrpsee::RpcModule;
+use sui_json_rpc::api::NameServiceApiClient;
+use sui_json_rpc::api::NameServiceApiServer;
+use sui_json_rpc::SuiRpcModule;
+use sui_open_rpc::Module;
+use sui_types::base_types::SuiAddress;
+
+pub(crate) struct NameServiceApi {
+    fullnode: HttpClient,
+}
+
+impl NameServiceApi {
+    pub fn new(fullnode_client: HttpClient) -> Self {
+        Self {
+            fullnode: fullnode_client,
+        }
+    }
+}
+
+#[async_trait]
+impl NameServiceApiServer for NameServiceApi {
+    async fn resolve_name_service_address(&self, name: String) -> RpcResult<Option<SuiAddress>> {
+        self.fullnode.resolve_name_service_address(name).await
+    }
+
+    async fn resolve_name_service_names(&self, address: SuiAddress) -> RpcResult<Vec<String>> {
+        self.fullnode.resolve_name_service_names(address).await
+    }
+}
+
+impl SuiRpcModule for NameServiceApi {
+    fn rpc(self) -> RpcModule<Self> {
+        self.into_rpc()
+    }
+
+    fn rpc_doc_module() -> Module {
+        sui_json_rpc::api::NameServiceApiOpenRpc::module_doc()
+    }
+}
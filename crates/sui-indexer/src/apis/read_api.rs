@@ -2,23 +2,27 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::errors::IndexerError;
+use crate::models::objects::ObjectStatus;
 use crate::store::IndexerStore;
 use async_trait::async_trait;
 use jsonrpsee::core::RpcResult;
 use jsonrpsee::http_client::HttpClient;
-use jsonrpsee::RpcModule;
+use jsonrpsee::types::{SubscriptionEmptyError, SubscriptionResult};
+use jsonrpsee::{RpcModule, SubscriptionSink};
 use std::collections::BTreeMap;
 use sui_json_rpc::api::{cap_page_limit, ReadApiClient, ReadApiServer};
 use sui_json_rpc::SuiRpcModule;
 use sui_json_rpc_types::{
-    Checkpoint, CheckpointId, DynamicFieldPage, MoveFunctionArgType, Page,
-    SuiMoveNormalizedFunction, SuiMoveNormalizedModule, SuiMoveNormalizedStruct,
-    SuiObjectDataOptions, SuiObjectInfo, SuiObjectResponse, SuiPastObjectResponse,
-    SuiTransactionResponse, TransactionsPage,
+    Checkpoint, CheckpointId, CheckpointPage, DynamicFieldPage, MoveFunctionArgType, NodeInfo,
+    OwnedObjectsCursor, OwnedObjectsPage, Page, ProtocolConfigResponse, SuiGetPastObjectRequest,
+    SuiMoveNormalizedFunction, SuiMoveNormalizedModule, SuiMoveNormalizedStruct, SuiObjectData,
+    SuiObjectDataOptions, SuiObjectHistoryEntry, SuiObjectInfo, SuiObjectResponse,
+    SuiPastObjectResponse, SuiTransactionInclusionProof, SuiTransactionResponse,
+    SuiTransactionResponseOptions, SuiTransactionResult, TransactionsPage,
 };
 use sui_open_rpc::Module;
 use sui_types::base_types::{ObjectID, SequenceNumber, SuiAddress, TxSequenceNumber};
-use sui_types::digests::TransactionDigest;
+use sui_types::digests::{ObjectDigest, TransactionDigest};
 use sui_types::dynamic_field::DynamicFieldName;
 use sui_types::messages_checkpoint::CheckpointSequenceNumber;
 use sui_types::query::TransactionQuery;
@@ -62,6 +66,16 @@ impl<S: IndexerStore> ReadApi<S> {
         limit: Option<usize>,
         descending_order: Option<bool>,
     ) -> RpcResult<TransactionsPage> {
+        // Time ranges and compound queries are not indexed yet, fall back to the fullnode.
+        if matches!(
+            query,
+            TransactionQuery::TimeRange { .. } | TransactionQuery::MatchAll(_)
+        ) {
+            return self
+                .fullnode
+                .get_transactions(query, cursor, limit, descending_order)
+                .await;
+        }
         let limit = cap_page_limit(limit);
         let is_descending = descending_order.unwrap_or_default();
         let cursor_str = cursor.map(|digest| digest.to_string());
@@ -129,6 +143,9 @@ impl<S: IndexerStore> ReadApi<S> {
                     is_descending,
                 )
             }
+            TransactionQuery::TimeRange { .. } | TransactionQuery::MatchAll(_) => {
+                unreachable!("served by the fullnode")
+            }
         }?;
 
         // digests here are of size (limit + 1), where the last one is the cursor for the next page
@@ -150,10 +167,99 @@ impl<S: IndexerStore> ReadApi<S> {
 
         Ok(Page {
             data: txn_digests,
+            has_next_page: next_cursor.is_some(),
+            next_cursor,
+        })
+    }
+
+    async fn get_owned_objects(
+        &self,
+        address: SuiAddress,
+        cursor: Option<OwnedObjectsCursor>,
+        limit: Option<usize>,
+        object_type: Option<String>,
+    ) -> RpcResult<OwnedObjectsPage> {
+        let limit = cap_page_limit(limit);
+        // Pages are read as of the latest committed checkpoint, the checkpoint of the cursor is
+        // only passed along.
+        let checkpoint = match &cursor {
+            Some(cursor) => cursor.checkpoint,
+            None => self.state.get_latest_checkpoint_sequence_number()? as u64,
+        };
+        let objects = self.state.get_owned_objects_page(
+            address.to_string(),
+            object_type,
+            cursor.map(|cursor| cursor.object_id.to_string()),
+            limit + 1,
+        )?;
+        let mut object_infos = objects
+            .into_iter()
+            .map(SuiObjectInfo::try_from)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let next_cursor = object_infos
+            .get(limit)
+            .map(|object_info| OwnedObjectsCursor {
+                checkpoint,
+                object_id: object_info.object_id,
+            });
+        object_infos.truncate(limit);
+
+        Ok(Page {
+            data: object_infos,
+            has_next_page: next_cursor.is_some(),
             next_cursor,
         })
     }
 
+    async fn get_object_with_options(
+        &self,
+        object_id: ObjectID,
+        options: Option<SuiObjectDataOptions>,
+    ) -> RpcResult<SuiObjectResponse> {
+        let options = options.unwrap_or_default();
+        // The contents of objects are not indexed, fall back to the fullnode.
+        if options.show_content
+            || options.show_display
+            || options.show_bcs
+            || options.show_storage_rebate
+        {
+            return self
+                .fullnode
+                .get_object_with_options(object_id, Some(options))
+                .await;
+        }
+        let Some(object) = self.state.get_object(object_id.to_string())? else {
+            return Ok(SuiObjectResponse::NotExists(object_id));
+        };
+
+        let deleted_digest = match object.object_status {
+            ObjectStatus::Deleted | ObjectStatus::UnwrappedThenDeleted => {
+                Some(ObjectDigest::OBJECT_DIGEST_DELETED)
+            }
+            ObjectStatus::Wrapped => Some(ObjectDigest::OBJECT_DIGEST_WRAPPED),
+            ObjectStatus::Created | ObjectStatus::Mutated | ObjectStatus::Unwrapped => None,
+        };
+        if let Some(digest) = deleted_digest {
+            let version = SequenceNumber::from_u64(object.version as u64);
+            return Ok(SuiObjectResponse::Deleted(
+                (object_id, version, digest).into(),
+            ));
+        }
+
+        let mut object_data = SuiObjectData::try_from(object)?;
+        if !options.show_type {
+            object_data.type_ = None;
+        }
+        if !options.show_owner {
+            object_data.owner = None;
+        }
+        if !options.show_previous_transaction {
+            object_data.previous_transaction = None;
+        }
+        Ok(SuiObjectResponse::Exists(object_data))
+    }
+
     async fn get_latest_checkpoint_sequence_number(&self) -> Result<i64, IndexerError> {
         self.state.get_latest_checkpoint_sequence_number()
     }
@@ -176,6 +282,26 @@ where
         self.fullnode.get_objects_owned_by_address(address).await
     }
 
+    async fn get_owned_objects(
+        &self,
+        address: SuiAddress,
+        cursor: Option<OwnedObjectsCursor>,
+        limit: Option<usize>,
+        object_type: Option<String>,
+    ) -> RpcResult<OwnedObjectsPage> {
+        if self
+            .method_to_be_forwarded
+            .contains(&"get_owned_objects".to_string())
+        {
+            return self
+                .fullnode
+                .get_owned_objects(address, cursor, limit, object_type)
+                .await;
+        }
+        self.get_owned_objects(address, cursor, limit, object_type)
+            .await
+    }
+
     async fn get_dynamic_fields(
         &self,
         parent_object_id: ObjectID,
@@ -192,9 +318,16 @@ where
         object_id: ObjectID,
         options: Option<SuiObjectDataOptions>,
     ) -> RpcResult<SuiObjectResponse> {
-        self.fullnode
-            .get_object_with_options(object_id, options)
-            .await
+        if self
+            .method_to_be_forwarded
+            .contains(&"get_object_with_options".to_string())
+        {
+            return self
+                .fullnode
+                .get_object_with_options(object_id, options)
+                .await;
+        }
+        self.get_object_with_options(object_id, options).await
     }
 
     async fn get_dynamic_field_object(
@@ -271,6 +404,16 @@ where
         self.multi_get_transactions(digests).await
     }
 
+    async fn multi_get_transactions_with_options(
+        &self,
+        digests: Vec<TransactionDigest>,
+        options: Option<SuiTransactionResponseOptions>,
+    ) -> RpcResult<Vec<SuiTransactionResult>> {
+        self.fullnode
+            .multi_get_transactions_with_options(digests, options)
+            .await
+    }
+
     async fn get_normalized_move_modules_by_package(
         &self,
         package: ObjectID,
@@ -334,6 +477,28 @@ where
             .await
     }
 
+    async fn try_multi_get_past_objects(
+        &self,
+        past_objects: Vec<SuiGetPastObjectRequest>,
+        options: Option<SuiObjectDataOptions>,
+    ) -> RpcResult<Vec<SuiPastObjectResponse>> {
+        self.fullnode
+            .try_multi_get_past_objects(past_objects, options)
+            .await
+    }
+
+    async fn try_get_past_object_history(
+        &self,
+        object_id: ObjectID,
+        start_version: SequenceNumber,
+        end_version: SequenceNumber,
+        options: Option<SuiObjectDataOptions>,
+    ) -> RpcResult<Vec<SuiObjectHistoryEntry>> {
+        self.fullnode
+            .try_get_past_object_history(object_id, start_version, end_version, options)
+            .await
+    }
+
     async fn get_latest_checkpoint_sequence_number(&self) -> RpcResult<CheckpointSequenceNumber> {
         if self
             .method_to_be_forwarded
@@ -353,6 +518,51 @@ where
         }
         Ok(self.get_checkpoint(id).await?)
     }
+
+    async fn get_checkpoints(
+        &self,
+        start: Option<CheckpointSequenceNumber>,
+        end: Option<CheckpointSequenceNumber>,
+        cursor: Option<CheckpointSequenceNumber>,
+        limit: Option<usize>,
+        descending_order: Option<bool>,
+        include_transactions: Option<bool>,
+    ) -> RpcResult<CheckpointPage> {
+        self.fullnode
+            .get_checkpoints(
+                start,
+                end,
+                cursor,
+                limit,
+                descending_order,
+                include_transactions,
+            )
+            .await
+    }
+
+    async fn get_transaction_inclusion_proof(
+        &self,
+        digest: TransactionDigest,
+    ) -> RpcResult<SuiTransactionInclusionProof> {
+        self.fullnode.get_transaction_inclusion_proof(digest).await
+    }
+
+    fn subscribe_checkpoint_data(
+        &self,
+        mut _sink: SubscriptionSink,
+        _start: Option<CheckpointSequenceNumber>,
+    ) -> SubscriptionResult {
+        // subscription not supported by subscription yet
+        Err(SubscriptionEmptyError)
+    }
+
+    async fn get_node_info(&self) -> RpcResult<NodeInfo> {
+        self.fullnode.get_node_info().await
+    }
+
+    async fn get_protocol_config(&self, version: Option<u64>) -> RpcResult<ProtocolConfigResponse> {
+        self.fullnode.get_protocol_config(version).await
+    }
 }
 
 impl<S> SuiRpcModule for ReadApi<S>
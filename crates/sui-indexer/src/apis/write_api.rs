@@ -8,7 +8,10 @@ use jsonrpsee::http_client::HttpClient;
 use jsonrpsee::RpcModule;
 use sui_json_rpc::api::{WriteApiClient, WriteApiServer};
 use sui_json_rpc::SuiRpcModule;
-use sui_json_rpc_types::{DevInspectResults, DryRunTransactionResponse, SuiTransactionResponse};
+use sui_json_rpc_types::{
+    DevInspectArgs, DevInspectResults, DryRunTransactionResponse, GasEstimate,
+    SuiTransactionResponse,
+};
 use sui_open_rpc::Module;
 use sui_types::base_types::{EpochId, SuiAddress};
 use sui_types::messages::ExecuteTransactionRequestType;
@@ -33,7 +36,7 @@ impl WriteApiServer for WriteApi {
         signature: Base64,
         request_type: ExecuteTransactionRequestType,
     ) -> RpcResult<SuiTransactionResponse> {
-        self.submit_transaction(tx_bytes, vec![signature], request_type)
+        self.submit_transaction(tx_bytes, vec![signature], request_type, None)
             .await
     }
 
@@ -52,9 +55,10 @@ impl WriteApiServer for WriteApi {
         tx_bytes: Base64,
         signatures: Vec<Base64>,
         request_type: ExecuteTransactionRequestType,
+        wait_for_checkpoint: Option<bool>,
     ) -> RpcResult<SuiTransactionResponse> {
         self.fullnode
-            .submit_transaction(tx_bytes, signatures, request_type)
+            .submit_transaction(tx_bytes, signatures, request_type, wait_for_checkpoint)
             .await
     }
 
@@ -64,15 +68,27 @@ impl WriteApiServer for WriteApi {
         tx_bytes: Base64,
         gas_price: Option<u64>,
         epoch: Option<EpochId>,
+        additional_args: Option<DevInspectArgs>,
     ) -> RpcResult<DevInspectResults> {
         self.fullnode
-            .dev_inspect_transaction(sender_address, tx_bytes, gas_price, epoch)
+            .dev_inspect_transaction(sender_address, tx_bytes, gas_price, epoch, additional_args)
             .await
     }
 
     async fn dry_run_transaction(&self, tx_bytes: Base64) -> RpcResult<DryRunTransactionResponse> {
         self.fullnode.dry_run_transaction(tx_bytes).await
     }
+
+    async fn estimate_gas(
+        &self,
+        sender_address: SuiAddress,
+        tx_bytes: Base64,
+        gas_price: Option<u64>,
+    ) -> RpcResult<GasEstimate> {
+        self.fullnode
+            .estimate_gas(sender_address, tx_bytes, gas_price)
+            .await
+    }
 }
 
 impl SuiRpcModule for WriteApi {
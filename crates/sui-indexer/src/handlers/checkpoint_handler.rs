@@ -16,7 +16,7 @@ use crate::store::{
 };
 use chrono::NaiveDateTime;
 use futures::future::join_all;
-use futures::FutureExt;
+use futures::{FutureExt, StreamExt};
 use mysten_metrics::spawn_monitored_task;
 use prometheus::Registry;
 use std::collections::BTreeMap;
@@ -28,26 +28,54 @@ use sui_sdk::error::Error;
 use sui_sdk::SuiClient;
 use sui_types::messages_checkpoint::CheckpointSequenceNumber;
 use sui_types::object::Owner;
+use tokio::sync::mpsc;
 use tokio::task::JoinHandle;
 use tracing::{info, warn};
 
 const HANDLER_RETRY_INTERVAL_IN_SECS: u64 = 10;
 const MULTI_GET_CHUNK_SIZE: usize = 500;
 
+/// How checkpoints flow through the handler: up to `download_concurrency` checkpoints are
+/// downloaded from the Full node at once, then indexed in order, and the indexed checkpoints are
+/// committed to the store in batches of up to `commit_batch_size` while the next ones download.
+#[derive(Clone, Copy, Debug)]
+pub struct CheckpointHandlerConfig {
+    pub download_concurrency: usize,
+    pub commit_batch_size: usize,
+}
+
+impl Default for CheckpointHandlerConfig {
+    fn default() -> Self {
+        Self {
+            download_concurrency: 10,
+            commit_batch_size: 50,
+        }
+    }
+}
+
+type IndexedCheckpoint = (TemporaryCheckpointStore, Option<TemporaryEpochStore>);
+
 pub struct CheckpointHandler<S> {
     state: S,
     rpc_client: SuiClient,
+    config: CheckpointHandlerConfig,
     metrics: IndexerCheckpointHandlerMetrics,
 }
 
 impl<S> CheckpointHandler<S>
 where
-    S: IndexerStore + Sync + Send + 'static,
+    S: IndexerStore + Clone + Sync + Send + 'static,
 {
-    pub fn new(state: S, rpc_client: SuiClient, prometheus_registry: &Registry) -> Self {
+    pub fn new(
+        state: S,
+        rpc_client: SuiClient,
+        config: CheckpointHandlerConfig,
+        prometheus_registry: &Registry,
+    ) -> Self {
         Self {
             state,
             rpc_client,
+            config,
             metrics: IndexerCheckpointHandlerMetrics::new(prometheus_registry),
         }
     }
@@ -69,6 +97,9 @@ where
         })
     }
 
+    /// Indexes the checkpoints after the latest one in the store. As checkpoints are committed
+    /// in order, the latest checkpoint in the store is the watermark up to which all checkpoints
+    /// are committed, which a restart resumes from.
     async fn start(&self) -> Result<(), IndexerError> {
         info!("Indexer checkpoint handler started...");
         let mut next_cursor_sequence_number = self.state.get_latest_checkpoint_sequence_number()?;
@@ -77,49 +108,105 @@ where
         }
         next_cursor_sequence_number += 1;
 
-        loop {
-            self.metrics.total_checkpoint_requested.inc();
-            let request_guard = self.metrics.full_node_read_request_latency.start_timer();
+        let (sender, receiver) = mpsc::channel(self.config.commit_batch_size);
+        let (index_result, commit_result) = futures::join!(
+            self.index_checkpoints(next_cursor_sequence_number as u64, sender),
+            self.commit_checkpoints(receiver),
+        );
+        // Whichever stage fails first stops the other one.
+        commit_result.and(index_result)
+    }
 
-            let mut checkpoint = self
-                .download_checkpoint_data(next_cursor_sequence_number as u64)
-                .await;
-            // this happens very often b/c checkpoint indexing is faster than checkpoint
-            // generation. Ideally we will want to differentiate between a real error and
-            // a checkpoint not generated yet.
-            while checkpoint.is_err() {
-                tokio::time::sleep(std::time::Duration::from_secs(1)).await;
-                checkpoint = self
-                    .download_checkpoint_data(next_cursor_sequence_number as u64)
-                    .await
+    /// Downloads checkpoints concurrently from `from`, and indexes them in order.
+    async fn index_checkpoints(
+        &self,
+        from: CheckpointSequenceNumber,
+        sender: mpsc::Sender<IndexedCheckpoint>,
+    ) -> Result<(), IndexerError> {
+        let mut previous_cp = if from == 0 {
+            Checkpoint::default()
+        } else {
+            self.state.get_checkpoint((from - 1).into())?
+        };
+        let mut checkpoints = futures::stream::iter(from..)
+            .map(|seq| self.download_checkpoint_data_with_retry(seq))
+            .buffered(self.config.download_concurrency);
+        while let Some(checkpoint) = checkpoints.next().await {
+            let indexed = self.index_checkpoint(checkpoint, &previous_cp)?;
+            previous_cp = indexed.0.checkpoint.clone();
+            if sender.send(indexed).await.is_err() {
+                // The commits failed, which is reported by them.
+                return Ok(());
             }
-            // unwrap here is safe because we checked for error above
-            let checkpoint = checkpoint.unwrap();
-            request_guard.stop_and_record();
-            self.metrics.total_checkpoint_received.inc();
+        }
+        Ok(())
+    }
 
-            // Index checkpoint data
-            // TODO: Metrics
-            let (indexed_checkpoint, indexed_epoch) = self.index_checkpoint(checkpoint)?;
+    /// Commits the indexed checkpoints in order, batching those that are waiting to be committed
+    /// into one write. A batch ends at the end of an epoch, so that the epoch is committed
+    /// before the checkpoints of the next one.
+    async fn commit_checkpoints(
+        &self,
+        mut receiver: mpsc::Receiver<IndexedCheckpoint>,
+    ) -> Result<(), IndexerError> {
+        while let Some(mut indexed) = receiver.recv().await {
+            let mut batch = vec![];
+            let indexed_epoch = loop {
+                let (indexed_checkpoint, indexed_epoch) = indexed;
+                batch.push(indexed_checkpoint);
+                if indexed_epoch.is_some() || batch.len() >= self.config.commit_batch_size {
+                    break indexed_epoch;
+                }
+                match receiver.try_recv() {
+                    Ok(next) => indexed = next,
+                    Err(_) => break None,
+                }
+            };
 
-            // Write to DB
-            let db_guard = self.metrics.db_write_request_latency.start_timer();
-            let tx_count = indexed_checkpoint.transactions.len();
-            let object_count = indexed_checkpoint.objects_changes.len();
+            let checkpoint_count = batch.len();
+            let tx_count: usize = batch.iter().map(|cp| cp.transactions.len()).sum();
+            let last_committed = batch[checkpoint_count - 1].checkpoint.sequence_number;
 
-            self.state.persist_checkpoint(&indexed_checkpoint)?;
+            let db_guard = self.metrics.db_write_request_latency.start_timer();
+            let state = self.state.clone();
+            tokio::task::spawn_blocking(move || state.persist_checkpoints(&batch))
+                .await
+                .map_err(|e| IndexerError::PostgresWriteError(e.to_string()))??;
+            db_guard.stop_and_record();
             info!(
-                "Checkpoint {} committed with {tx_count} transactions and {object_count} objects.",
-                next_cursor_sequence_number
+                "Checkpoints up to {last_committed} committed, the last {checkpoint_count} with {tx_count} transactions."
             );
-            self.metrics.total_checkpoint_processed.inc();
-            db_guard.stop_and_record();
+            self.metrics
+                .total_checkpoint_processed
+                .inc_by(checkpoint_count as u64);
+            self.metrics.latest_committed_checkpoint.set(last_committed);
 
             if let Some(indexed_epoch) = indexed_epoch {
                 self.state.persist_epoch(&indexed_epoch)?;
             }
-            next_cursor_sequence_number += 1;
         }
+        Ok(())
+    }
+
+    /// Downloads a checkpoint, retrying until it exists.
+    async fn download_checkpoint_data_with_retry(
+        &self,
+        seq: CheckpointSequenceNumber,
+    ) -> CheckpointData {
+        self.metrics.total_checkpoint_requested.inc();
+        let request_guard = self.metrics.full_node_read_request_latency.start_timer();
+        let mut checkpoint = self.download_checkpoint_data(seq).await;
+        // this happens very often b/c checkpoint indexing is faster than checkpoint
+        // generation. Ideally we will want to differentiate between a real error and
+        // a checkpoint not generated yet.
+        while checkpoint.is_err() {
+            tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+            checkpoint = self.download_checkpoint_data(seq).await
+        }
+        request_guard.stop_and_record();
+        self.metrics.total_checkpoint_received.inc();
+        // unwrap here is safe because we checked for error above
+        checkpoint.unwrap()
     }
 
     /// Download all the data we need for one checkpoint.
@@ -190,6 +277,7 @@ where
     fn index_checkpoint(
         &self,
         data: CheckpointData,
+        previous_cp: &Checkpoint,
     ) -> Result<(TemporaryCheckpointStore, Option<TemporaryEpochStore>), IndexerError> {
         let CheckpointData {
             checkpoint,
@@ -197,13 +285,6 @@ where
             all_mutated_objects,
         } = data;
 
-        let previous_cp = if checkpoint.sequence_number == 0 {
-            Checkpoint::default()
-        } else {
-            self.state
-                .get_checkpoint((checkpoint.sequence_number - 1).into())?
-        };
-
         // Index transaction
         let db_transactions = transactions
             .iter()
@@ -232,6 +313,7 @@ where
                             .and_then(|t| NaiveDateTime::from_timestamp_millis(t as i64)),
                         event_type: event.get_event_type(),
                         event_content,
+                        epoch: tx.effects.executed_epoch() as i64,
                     };
                     event_sequence += 1;
                     Ok::<_, IndexerError>(event)
@@ -357,12 +439,13 @@ where
                 .end_of_epoch_data
                 .as_ref()
                 .map(|_epoch_change| TemporaryEpochStore {
+                    next_epoch: checkpoint.epoch + 1,
                     owner_index: vec![],
                 });
 
         Ok((
             TemporaryCheckpointStore {
-                checkpoint: Checkpoint::from(&checkpoint, &previous_cp)?,
+                checkpoint: Checkpoint::from(&checkpoint, previous_cp)?,
                 transactions: db_transactions,
                 events,
                 objects_changes,
@@ -9,7 +9,7 @@ use diesel::prelude::*;
 use diesel::r2d2::{ConnectionManager, Pool, PooledConnection};
 use jsonrpsee::http_client::{HeaderMap, HeaderValue, HttpClientBuilder};
 use prometheus::Registry;
-use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::net::SocketAddr;
 use sui_json_rpc::{JsonRpcServerBuilder, ServerHandle, CLIENT_SDK_TYPE_HEADER};
 use sui_sdk::{SuiClient, SuiClientBuilder};
 use tracing::{info, warn};
@@ -28,10 +28,11 @@ pub type PgConnectionPool = Pool<ConnectionManager<PgConnection>>;
 pub type PgPoolConnection = PooledConnection<ConnectionManager<PgConnection>>;
 
 use crate::apis::{
-    CoinReadApi, EventReadApi, GovernanceReadApi, ReadApi, ThresholdBlsApi, TransactionBuilderApi,
-    WriteApi,
+    CoinReadApi, EventReadApi, GovernanceReadApi, NameServiceApi, ReadApi, ThresholdBlsApi,
+    TransactionBuilderApi, WriteApi,
 };
 use crate::handlers::checkpoint_handler::CheckpointHandler;
+pub use crate::handlers::checkpoint_handler::CheckpointHandlerConfig;
 use crate::store::IndexerStore;
 use errors::IndexerError;
 use mysten_metrics::spawn_monitored_task;
@@ -46,10 +47,13 @@ impl Indexer {
         fullnode_url: &str,
         registry: &Registry,
         store: S,
+        rpc_server_address: SocketAddr,
+        handler_config: CheckpointHandlerConfig,
     ) -> Result<(), IndexerError> {
-        let handle = build_json_rpc_server(registry, store.clone(), fullnode_url)
-            .await
-            .expect("Json rpc server should not run into errors upon start.");
+        let handle =
+            build_json_rpc_server(registry, store.clone(), fullnode_url, rpc_server_address)
+                .await
+                .expect("Json rpc server should not run into errors upon start.");
         // let JSON RPC server run forever.
         spawn_monitored_task!(handle.stopped());
         info!("Sui indexer started...");
@@ -58,7 +62,8 @@ impl Indexer {
             let rpc_client = new_rpc_client(fullnode_url).await?;
             // NOTE: Each handler is responsible for one type of data from nodes,like transactions and events;
             // Handler orchestrator runs these handlers in parallel and manage them upon errors etc.
-            let cp = CheckpointHandler::new(store.clone(), rpc_client.clone(), registry);
+            let cp =
+                CheckpointHandler::new(store.clone(), rpc_client.clone(), handler_config, registry);
             cp.spawn()
                 .await
                 .expect("Indexer main should not run into errors.");
@@ -66,6 +71,21 @@ impl Indexer {
         })
         .await
     }
+
+    /// Serves the JSON-RPC API from `store` without indexing, for instances reading from a
+    /// replica of the database that another instance indexes to.
+    pub async fn start_reader<S: IndexerStore + Sync + Send + Clone + 'static>(
+        fullnode_url: &str,
+        registry: &Registry,
+        store: S,
+        rpc_server_address: SocketAddr,
+    ) -> Result<(), IndexerError> {
+        let handle =
+            build_json_rpc_server(registry, store, fullnode_url, rpc_server_address).await?;
+        info!("Sui indexer reader started...");
+        handle.stopped().await;
+        Ok(())
+    }
 }
 
 pub async fn new_rpc_client(http_url: &str) -> Result<SuiClient, IndexerError> {
@@ -110,10 +130,11 @@ pub fn get_pg_pool_connection(pool: &PgConnectionPool) -> Result<PgPoolConnectio
     })
 }
 
-pub async fn build_json_rpc_server<S: IndexerStore + Sync + Send + 'static>(
+pub async fn build_json_rpc_server<S: IndexerStore + Sync + Send + Clone + 'static>(
     prometheus_registry: &Registry,
     state: S,
     fullnode_url: &str,
+    rpc_server_address: SocketAddr,
 ) -> Result<ServerHandle, IndexerError> {
     let mut builder = JsonRpcServerBuilder::new(FAKE_PKG_VERSION, prometheus_registry);
 
@@ -127,14 +148,13 @@ pub async fn build_json_rpc_server<S: IndexerStore + Sync + Send + 'static>(
         .build(fullnode_url)
         .map_err(|e| IndexerError::RpcClientInitError(e.to_string()))?;
 
-    builder.register_module(ReadApi::new(state, http_client.clone()))?;
+    builder.register_module(ReadApi::new(state.clone(), http_client.clone()))?;
     builder.register_module(CoinReadApi::new(http_client.clone()))?;
     builder.register_module(ThresholdBlsApi::new(http_client.clone()))?;
     builder.register_module(TransactionBuilderApi::new(http_client.clone()))?;
     builder.register_module(GovernanceReadApi::new(http_client.clone()))?;
-    builder.register_module(EventReadApi::new(http_client.clone()))?;
+    builder.register_module(EventReadApi::new(state, http_client.clone()))?;
+    builder.register_module(NameServiceApi::new(http_client.clone()))?;
     builder.register_module(WriteApi::new(http_client))?;
-    // TODO: placeholder, read from env or config file.
-    let default_socket_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 3030);
-    Ok(builder.start(default_socket_addr).await?)
+    Ok(builder.start(rpc_server_address).await?)
 }
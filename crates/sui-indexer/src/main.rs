@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use sui_indexer::errors::IndexerError;
-use sui_indexer::{new_pg_connection_pool, Indexer};
+use sui_indexer::{new_pg_connection_pool, CheckpointHandlerConfig, Indexer};
 use sui_node::metrics::start_prometheus_server;
 
 use clap::Parser;
@@ -29,9 +29,41 @@ async fn main() -> Result<(), IndexerError> {
 
     let registry = registry_service.default_registry();
     let pg_connection_pool = new_pg_connection_pool(&indexer_config.db_url).await?;
-    let store = PgIndexerStore::new(pg_connection_pool);
+    let mut store = PgIndexerStore::new(pg_connection_pool);
+    if let Some(epochs_to_keep) = indexer_config.epochs_to_keep {
+        store = store.with_epochs_to_keep(epochs_to_keep);
+    }
 
-    Indexer::start(&indexer_config.rpc_client_url, &registry, store).await
+    // NOTE: this parses the input host addr and port number for socket addr,
+    // so unwrap() is safe here.
+    let rpc_server_address = format!(
+        "{}:{}",
+        indexer_config.rpc_server_host, indexer_config.rpc_server_port
+    )
+    .parse()
+    .unwrap();
+    if indexer_config.rpc_server_only {
+        return Indexer::start_reader(
+            &indexer_config.rpc_client_url,
+            &registry,
+            store,
+            rpc_server_address,
+        )
+        .await;
+    }
+
+    let handler_config = CheckpointHandlerConfig {
+        download_concurrency: indexer_config.checkpoint_download_concurrency,
+        commit_batch_size: indexer_config.checkpoint_commit_batch_size,
+    };
+    Indexer::start(
+        &indexer_config.rpc_client_url,
+        &registry,
+        store,
+        rpc_server_address,
+        handler_config,
+    )
+    .await
 }
 
 #[derive(Parser)]
@@ -49,4 +81,21 @@ pub struct IndexerConfig {
     pub client_metric_host: String,
     #[clap(long, default_value = "9184", global = true)]
     pub client_metric_port: u16,
+    #[clap(long, default_value = "127.0.0.1")]
+    pub rpc_server_host: String,
+    #[clap(long, default_value = "3030")]
+    pub rpc_server_port: u16,
+    /// Only serve the JSON-RPC API from the DB, without indexing checkpoints into it
+    #[clap(long)]
+    pub rpc_server_only: bool,
+    /// Number of checkpoints downloaded from the Full node at the same time
+    #[clap(long, default_value = "10")]
+    pub checkpoint_download_concurrency: usize,
+    /// Maximum number of checkpoints committed to the DB in one transaction
+    #[clap(long, default_value = "50")]
+    pub checkpoint_commit_batch_size: usize,
+    /// Number of epochs whose transactions, events and history are kept, the current one
+    /// included. All are kept when unset.
+    #[clap(long)]
+    pub epochs_to_keep: Option<u64>,
 }
@@ -2,8 +2,8 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use prometheus::{
-    register_histogram_with_registry, register_int_counter_with_registry, Histogram, IntCounter,
-    Registry,
+    register_histogram_with_registry, register_int_counter_with_registry,
+    register_int_gauge_with_registry, Histogram, IntCounter, IntGauge, Registry,
 };
 
 /// Prometheus metrics for sui-indexer.
@@ -252,6 +252,7 @@ pub struct IndexerCheckpointHandlerMetrics {
     pub total_checkpoint_received: IntCounter,
     pub total_checkpoint_processed: IntCounter,
     pub total_checkpoint_handler_error: IntCounter,
+    pub latest_committed_checkpoint: IntGauge,
     pub db_write_request_latency: Histogram,
     pub full_node_read_request_latency: Histogram,
 }
@@ -283,9 +284,15 @@ impl IndexerCheckpointHandlerMetrics {
                 registry,
             )
             .unwrap(),
+            latest_committed_checkpoint: register_int_gauge_with_registry!(
+                "latest_committed_checkpoint",
+                "Sequence number of the checkpoint up to which all checkpoints are committed",
+                registry,
+            )
+            .unwrap(),
             db_write_request_latency: register_histogram_with_registry!(
                 "checkpoint_db_write_request_latency",
-                "Time spent commiting a checkpoint to the db",
+                "Time spent commiting a batch of checkpoints to the db",
                 LATENCY_SEC_BUCKETS.to_vec(),
                 registry,
             )
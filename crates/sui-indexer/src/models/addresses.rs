@@ -7,7 +7,7 @@ use crate::schema::addresses;
 use chrono::NaiveDateTime;
 use diesel::prelude::*;
 
-#[derive(Queryable, Insertable, Debug)]
+#[derive(Queryable, Insertable, Debug, Clone)]
 #[diesel(table_name = addresses, primary_key(account_address))]
 pub struct Address {
     pub account_address: String,
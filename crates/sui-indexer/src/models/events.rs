@@ -5,7 +5,7 @@ use crate::schema::events;
 use chrono::NaiveDateTime;
 use diesel::prelude::*;
 
-#[derive(Queryable, Insertable, Debug)]
+#[derive(Queryable, Insertable, Debug, Clone)]
 #[diesel(table_name = events)]
 pub struct Event {
     #[diesel(deserialize_as = i64)]
@@ -15,4 +15,5 @@ pub struct Event {
     pub event_time: Option<NaiveDateTime>,
     pub event_type: String,
     pub event_content: String,
+    pub epoch: i64,
 }
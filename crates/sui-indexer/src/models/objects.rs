@@ -1,13 +1,14 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::errors::IndexerError;
 use crate::models::owners::OwnerType;
 use crate::schema::objects;
 use diesel::prelude::*;
 use diesel_derive_enum::DbEnum;
-use sui_json_rpc_types::{SuiObjectData, SuiObjectRef};
-use sui_types::base_types::EpochId;
-use sui_types::digests::TransactionDigest;
+use sui_json_rpc_types::{SuiObjectData, SuiObjectInfo, SuiObjectRef};
+use sui_types::base_types::{EpochId, ObjectID, ObjectRef, ObjectType, SequenceNumber, SuiAddress};
+use sui_types::digests::{ObjectDigest, TransactionDigest};
 use sui_types::messages_checkpoint::CheckpointSequenceNumber;
 use sui_types::object::Owner;
 
@@ -88,6 +89,103 @@ impl Object {
     }
 }
 
+impl TryFrom<Object> for SuiObjectInfo {
+    type Error = IndexerError;
+
+    fn try_from(o: Object) -> Result<Self, Self::Error> {
+        let (object_id, version, digest) = o.object_ref()?;
+        Ok(SuiObjectInfo {
+            object_id,
+            version,
+            digest,
+            owner: o.owner()?,
+            previous_transaction: o.previous_transaction_digest()?,
+            type_: o.object_type,
+        })
+    }
+}
+
+impl TryFrom<Object> for SuiObjectData {
+    type Error = IndexerError;
+
+    /// Only the fields kept by the indexer are set: the reference, type, owner and previous
+    /// transaction of the object.
+    fn try_from(o: Object) -> Result<Self, Self::Error> {
+        let (object_id, version, digest) = o.object_ref()?;
+        let type_ = o.object_type.parse::<ObjectType>().map_err(|e| {
+            IndexerError::JsonSerdeError(format!(
+                "Failed to decode object type: {:?} with err: {:?}",
+                o.object_type, e
+            ))
+        })?;
+        Ok(SuiObjectData {
+            object_id,
+            version,
+            digest,
+            type_: Some(type_),
+            owner: Some(o.owner()?),
+            previous_transaction: Some(o.previous_transaction_digest()?),
+            storage_rebate: None,
+            display: None,
+            content: None,
+            bcs: None,
+        })
+    }
+}
+
+impl Object {
+    pub fn object_ref(&self) -> Result<ObjectRef, IndexerError> {
+        let object_id = self.object_id.parse::<ObjectID>().map_err(|e| {
+            IndexerError::JsonSerdeError(format!(
+                "Failed to decode object ID: {:?} with err: {:?}",
+                self.object_id, e
+            ))
+        })?;
+        let digest = self.object_digest.parse::<ObjectDigest>().map_err(|e| {
+            IndexerError::JsonSerdeError(format!(
+                "Failed to decode object digest: {:?} with err: {:?}",
+                self.object_digest, e
+            ))
+        })?;
+        Ok((
+            object_id,
+            SequenceNumber::from_u64(self.version as u64),
+            digest,
+        ))
+    }
+
+    pub fn owner(&self) -> Result<Owner, IndexerError> {
+        let address = || {
+            let address = self.owner_address.as_deref().unwrap_or_default();
+            address.parse::<SuiAddress>().map_err(|e| {
+                IndexerError::JsonSerdeError(format!(
+                    "Failed to decode owner address: {:?} of object {} with err: {:?}",
+                    address, self.object_id, e
+                ))
+            })
+        };
+        Ok(match self.owner_type {
+            OwnerType::AddressOwner => Owner::AddressOwner(address()?),
+            OwnerType::ObjectOwner => Owner::ObjectOwner(address()?),
+            OwnerType::Shared => Owner::Shared {
+                initial_shared_version: SequenceNumber::from_u64(
+                    self.initial_shared_version.unwrap_or_default() as u64,
+                ),
+            },
+            OwnerType::Immutable => Owner::Immutable,
+        })
+    }
+
+    pub fn previous_transaction_digest(&self) -> Result<TransactionDigest, IndexerError> {
+        self.previous_transaction.parse().map_err(|e| {
+            IndexerError::JsonSerdeError(format!(
+                "Failed to decode transaction digest: {:?} with err: {:?}",
+                self.previous_transaction, e
+            ))
+        })
+    }
+}
+
 impl DeletedObject {
     pub fn from(
         epoch: &EpochId,
@@ -13,7 +13,7 @@ use sui_json_rpc_types::{
 };
 
 use crate::errors::IndexerError;
-use crate::schema::transactions::transaction_digest;
+use crate::schema::transactions::{epoch, transaction_digest};
 use crate::PgPoolConnection;
 
 #[derive(Clone, Debug, Queryable, Insertable)]
@@ -45,6 +45,7 @@ pub struct Transaction {
     pub transaction_content: String,
     pub transaction_effects_content: String,
     pub confirmed_local_execution: Option<bool>,
+    pub epoch: i64,
 }
 
 pub fn commit_transactions(
@@ -65,7 +66,7 @@ pub fn commit_transactions(
         .run::<_, Error, _>(|conn| {
         diesel::insert_into(transactions::table)
             .values(&new_txns)
-            .on_conflict(transaction_digest)
+            .on_conflict((transaction_digest, epoch))
             .do_nothing()
             .execute(conn)
     });
@@ -211,6 +212,7 @@ impl TryFrom<SuiTransactionResponse> for Transaction {
             transaction_content: txn_json,
             transaction_effects_content: txn_effect_json,
             confirmed_local_execution: tx_resp.confirmed_local_execution,
+            epoch: tx_resp.effects.executed_epoch() as i64,
         })
     }
 }
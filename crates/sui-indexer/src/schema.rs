@@ -60,6 +60,7 @@ diesel::table! {
         event_time -> Nullable<Timestamp>,
         event_type -> Varchar,
         event_content -> Varchar,
+        epoch -> Int8,
     }
 }
 
@@ -205,6 +206,7 @@ diesel::table! {
         transaction_content -> Text,
         transaction_effects_content -> Text,
         confirmed_local_execution -> Nullable<Bool>,
+        epoch -> Int8,
     }
 }
 
@@ -12,9 +12,12 @@ use crate::models::packages::Package;
 use crate::models::recipients::Recipient;
 use crate::models::transactions::Transaction;
 use async_trait::async_trait;
+use chrono::NaiveDateTime;
 use sui_json_rpc_types::{
     Checkpoint as RpcCheckpoint, CheckpointId, SuiObjectData, SuiTransactionResponse,
 };
+use sui_types::committee::EpochId;
+use sui_types::event::EventID;
 
 #[async_trait]
 pub trait IndexerStore {
@@ -85,6 +88,48 @@ pub trait IndexerStore {
         is_descending: bool,
     ) -> Result<Option<i64>, IndexerError>;
 
+    fn get_event_sequence_by_id(
+        &self,
+        event_id: Option<EventID>,
+    ) -> Result<Option<i64>, IndexerError>;
+
+    fn get_all_events_page(
+        &self,
+        start_sequence: Option<i64>,
+        limit: usize,
+        is_descending: bool,
+    ) -> Result<Vec<Event>, IndexerError>;
+
+    fn get_events_page_by_transaction(
+        &self,
+        txn_digest: String,
+        start_sequence: Option<i64>,
+        limit: usize,
+        is_descending: bool,
+    ) -> Result<Vec<Event>, IndexerError>;
+
+    fn get_events_page_by_time_range(
+        &self,
+        start_time: NaiveDateTime,
+        end_time: NaiveDateTime,
+        start_sequence: Option<i64>,
+        limit: usize,
+        is_descending: bool,
+    ) -> Result<Vec<Event>, IndexerError>;
+
+    fn get_object(&self, object_id: String) -> Result<Option<Object>, IndexerError>;
+
+    /// Reads the live objects owned by `owner_address` in the order of their IDs, from
+    /// `start_object_id` on, inclusive. `object_type` without type parameters matches all of
+    /// its instantiations.
+    fn get_owned_objects_page(
+        &self,
+        owner_address: String,
+        object_type: Option<String>,
+        start_object_id: Option<String>,
+        limit: usize,
+    ) -> Result<Vec<Object>, IndexerError>;
+
     fn read_transactions(
         &self,
         last_processed_id: i64,
@@ -92,6 +137,16 @@ pub trait IndexerStore {
     ) -> Result<Vec<Transaction>, IndexerError>;
 
     fn persist_checkpoint(&self, data: &TemporaryCheckpointStore) -> Result<usize, IndexerError>;
+    /// Persists consecutive checkpoints, in order. Stores that can write them all at once
+    /// override this to do so.
+    fn persist_checkpoints(
+        &self,
+        data: &[TemporaryCheckpointStore],
+    ) -> Result<usize, IndexerError> {
+        data.iter().try_fold(0, |count, checkpoint| {
+            Ok(count + self.persist_checkpoint(checkpoint)?)
+        })
+    }
     fn persist_epoch(&self, data: &TemporaryEpochStore) -> Result<usize, IndexerError>;
 
     fn log_errors(&self, errors: Vec<IndexerError>) -> Result<(), IndexerError>;
@@ -122,5 +177,7 @@ pub struct TransactionObjectChanges {
 
 // Per epoch indexing
 pub struct TemporaryEpochStore {
+    /// The epoch starting after the checkpoint that ended the previous one.
+    pub next_epoch: EpochId,
     pub owner_index: Vec<ObjectOwner>,
 }
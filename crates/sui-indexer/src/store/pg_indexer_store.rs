@@ -4,10 +4,14 @@
 use crate::errors::IndexerError;
 use crate::models::checkpoints::Checkpoint;
 use crate::models::error_logs::commit_error_logs;
+use crate::models::events::Event;
+use crate::models::objects::{Object, ObjectStatus};
+use crate::models::owners::OwnerType;
 use crate::models::transactions::Transaction;
 use crate::schema::addresses::account_address;
 use crate::schema::checkpoints::dsl::checkpoints as checkpoints_table;
 use crate::schema::checkpoints::{checkpoint_digest, sequence_number};
+use crate::schema::events::dsl as events_dsl;
 use crate::schema::move_calls::dsl as move_calls_dsl;
 use crate::schema::packages::{author, module_names, package_content, package_id};
 use crate::schema::recipients::dsl as recipients_dsl;
@@ -17,20 +21,26 @@ use crate::store::indexer_store::TemporaryCheckpointStore;
 use crate::store::{IndexerStore, TemporaryEpochStore};
 use crate::{get_pg_pool_connection, PgConnectionPool};
 use async_trait::async_trait;
+use chrono::NaiveDateTime;
 use diesel::dsl::{count, max};
+use diesel::pg::Pg;
 use diesel::sql_types::VarChar;
 use diesel::upsert::excluded;
-use diesel::QueryableByName;
-use diesel::{ExpressionMethods, PgArrayExpressionMethods};
+use diesel::{
+    BoolExpressionMethods, ExpressionMethods, OptionalExtension, PgArrayExpressionMethods,
+    TextExpressionMethods,
+};
+use diesel::{PgConnection, QueryableByName};
 use diesel::{QueryDsl, RunQueryDsl};
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 use sui_json_rpc_types::CheckpointId;
 use sui_types::committee::EpochId;
+use sui_types::event::EventID;
 use tracing::{error, info};
 
 const GET_PARTITION_SQL: &str = r#"
 SELECT parent.relname                           AS table_name,
-       MAX(SUBSTRING(child.relname FROM '\d$')) AS last_partition
+       MAX(SUBSTRING(child.relname FROM '\d+$')) AS last_partition
 FROM pg_inherits
          JOIN pg_class parent ON pg_inherits.inhparent = parent.oid
          JOIN pg_class child ON pg_inherits.inhrelid = child.oid
@@ -40,6 +50,17 @@ WHERE parent.relkind = 'p'
 GROUP BY table_name;
 "#;
 
+const GET_TABLE_PARTITIONS_SQL: &str = r#"
+SELECT child.relname AS partition_name
+FROM pg_inherits
+         JOIN pg_class parent ON pg_inherits.inhparent = parent.oid
+         JOIN pg_class child ON pg_inherits.inhrelid = child.oid
+WHERE parent.relname = $1;
+"#;
+
+/// The maximum number of rows inserted by one statement when committing checkpoints.
+const PG_INSERT_CHUNK_SIZE: usize = 1000;
+
 #[derive(Clone)]
 pub struct PgIndexerStore {
     cp: PgConnectionPool,
@@ -53,6 +74,50 @@ impl PgIndexerStore {
             partition_manager: PartitionManager::new(cp).unwrap(),
         }
     }
+
+    /// Reads `limit` events matched by `filter` in the order of their sequence, from
+    /// `start_sequence` on, inclusive.
+    fn get_events_page(
+        &self,
+        filter: impl FnOnce(events::BoxedQuery<'static, Pg>) -> events::BoxedQuery<'static, Pg>,
+        start_sequence: Option<i64>,
+        limit: usize,
+        is_descending: bool,
+    ) -> Result<Vec<Event>, IndexerError> {
+        let mut pg_pool_conn = get_pg_pool_connection(&self.cp)?;
+        pg_pool_conn
+            .build_transaction()
+            .read_only()
+            .run(|conn| {
+                let mut boxed_query = filter(events_dsl::events.into_boxed());
+                if let Some(start_sequence) = start_sequence {
+                    if is_descending {
+                        boxed_query = boxed_query.filter(events_dsl::id.le(start_sequence));
+                    } else {
+                        boxed_query = boxed_query.filter(events_dsl::id.ge(start_sequence));
+                    }
+                }
+                if is_descending {
+                    boxed_query = boxed_query.order(events_dsl::id.desc());
+                } else {
+                    boxed_query = boxed_query.order(events_dsl::id.asc());
+                }
+                boxed_query.limit(limit as i64).load::<Event>(conn)
+            })
+            .map_err(|e| {
+                IndexerError::PostgresReadError(format!(
+                    "Failed reading events with start_sequence {:?} and limit {} and err: {:?}",
+                    start_sequence, limit, e
+                ))
+            })
+    }
+
+    /// Drops the partitions of the epochs before the last `epochs_to_keep` ones, the current
+    /// epoch included, as epochs end. The current epoch is always kept.
+    pub fn with_epochs_to_keep(mut self, epochs_to_keep: u64) -> Self {
+        self.partition_manager.epochs_to_keep = Some(epochs_to_keep.max(1));
+        self
+    }
 }
 
 #[async_trait]
@@ -458,6 +523,144 @@ impl IndexerStore for PgIndexerStore {
         })?.into_iter().map(|table: TempDigestTable| table.digest_name ).collect())
     }
 
+    fn get_event_sequence_by_id(
+        &self,
+        event_id: Option<EventID>,
+    ) -> Result<Option<i64>, IndexerError> {
+        event_id
+            .map(|event_id| {
+                let mut pg_pool_conn = get_pg_pool_connection(&self.cp)?;
+                pg_pool_conn
+                    .build_transaction()
+                    .read_only()
+                    .run(|conn| {
+                        events_dsl::events
+                            .filter(
+                                events_dsl::transaction_digest.eq(event_id.tx_digest.to_string()),
+                            )
+                            .filter(events_dsl::event_sequence.eq(event_id.event_seq))
+                            .select(events_dsl::id)
+                            .first::<i64>(conn)
+                    })
+                    .map_err(|e| {
+                        IndexerError::PostgresReadError(format!(
+                            "Failed reading event sequence with event ID {:?} and err: {:?}",
+                            event_id, e
+                        ))
+                    })
+            })
+            .transpose()
+    }
+
+    fn get_all_events_page(
+        &self,
+        start_sequence: Option<i64>,
+        limit: usize,
+        is_descending: bool,
+    ) -> Result<Vec<Event>, IndexerError> {
+        self.get_events_page(|query| query, start_sequence, limit, is_descending)
+    }
+
+    fn get_events_page_by_transaction(
+        &self,
+        txn_digest: String,
+        start_sequence: Option<i64>,
+        limit: usize,
+        is_descending: bool,
+    ) -> Result<Vec<Event>, IndexerError> {
+        self.get_events_page(
+            |query| query.filter(events_dsl::transaction_digest.eq(txn_digest)),
+            start_sequence,
+            limit,
+            is_descending,
+        )
+    }
+
+    fn get_events_page_by_time_range(
+        &self,
+        start_time: NaiveDateTime,
+        end_time: NaiveDateTime,
+        start_sequence: Option<i64>,
+        limit: usize,
+        is_descending: bool,
+    ) -> Result<Vec<Event>, IndexerError> {
+        self.get_events_page(
+            |query| {
+                query
+                    .filter(events_dsl::event_time.ge(start_time))
+                    .filter(events_dsl::event_time.lt(end_time))
+            },
+            start_sequence,
+            limit,
+            is_descending,
+        )
+    }
+
+    fn get_object(&self, object_id: String) -> Result<Option<Object>, IndexerError> {
+        let mut pg_pool_conn = get_pg_pool_connection(&self.cp)?;
+        pg_pool_conn
+            .build_transaction()
+            .read_only()
+            .run(|conn| {
+                objects::table
+                    .filter(objects::object_id.eq(object_id.clone()))
+                    .first::<Object>(conn)
+                    .optional()
+            })
+            .map_err(|e| {
+                IndexerError::PostgresReadError(format!(
+                    "Failed reading object {} and err: {:?}",
+                    object_id, e
+                ))
+            })
+    }
+
+    fn get_owned_objects_page(
+        &self,
+        owner_address: String,
+        object_type: Option<String>,
+        start_object_id: Option<String>,
+        limit: usize,
+    ) -> Result<Vec<Object>, IndexerError> {
+        let mut pg_pool_conn = get_pg_pool_connection(&self.cp)?;
+        pg_pool_conn
+            .build_transaction()
+            .read_only()
+            .run(|conn| {
+                let mut boxed_query = objects::table
+                    .filter(objects::owner_type.eq(OwnerType::AddressOwner))
+                    .filter(objects::owner_address.eq(owner_address.clone()))
+                    .filter(objects::object_status.ne(ObjectStatus::Deleted))
+                    .filter(objects::object_status.ne(ObjectStatus::Wrapped))
+                    .filter(objects::object_status.ne(ObjectStatus::UnwrappedThenDeleted))
+                    .into_boxed();
+                if let Some(object_type) = &object_type {
+                    if object_type.contains('<') {
+                        boxed_query = boxed_query.filter(objects::object_type.eq(object_type));
+                    } else {
+                        boxed_query = boxed_query.filter(
+                            objects::object_type
+                                .eq(object_type)
+                                .or(objects::object_type.like(format!("{object_type}<%"))),
+                        );
+                    }
+                }
+                if let Some(start_object_id) = &start_object_id {
+                    boxed_query = boxed_query.filter(objects::object_id.ge(start_object_id));
+                }
+                boxed_query
+                    .order(objects::object_id.asc())
+                    .limit(limit as i64)
+                    .load::<Object>(conn)
+            })
+            .map_err(|e| {
+                IndexerError::PostgresReadError(format!(
+                    "Failed reading objects owned by {} with object type {:?}, start object ID {:?} and limit {} and err: {:?}",
+                    owner_address, object_type, start_object_id, limit, e
+                ))
+            })
+    }
+
     fn read_transactions(
         &self,
         last_processed_id: i64,
@@ -482,40 +685,65 @@ impl IndexerStore for PgIndexerStore {
     }
 
     fn persist_checkpoint(&self, data: &TemporaryCheckpointStore) -> Result<usize, IndexerError> {
-        let TemporaryCheckpointStore {
-            checkpoint,
-            transactions,
-            events,
-            objects_changes,
-            addresses,
-            packages,
-            move_calls,
-            recipients, // TODO: store raw object
-        } = data;
+        self.persist_checkpoints(std::slice::from_ref(data))
+    }
+
+    fn persist_checkpoints(
+        &self,
+        data: &[TemporaryCheckpointStore],
+    ) -> Result<usize, IndexerError> {
+        // The rows of all the checkpoints are inserted together, in statements of a bounded
+        // number of rows to stay under the limit of bind parameters of Postgres.
+        fn rows<T: Clone>(
+            data: &[TemporaryCheckpointStore],
+            rows: impl Fn(&TemporaryCheckpointStore) -> &Vec<T>,
+        ) -> Vec<T> {
+            data.iter()
+                .flat_map(|cp| rows(cp).iter().cloned())
+                .collect()
+        }
+        let checkpoints: Vec<_> = data.iter().map(|cp| cp.checkpoint.clone()).collect();
+        let transactions = rows(data, |cp| &cp.transactions);
+        let events = rows(data, |cp| &cp.events);
+        let addresses = rows(data, |cp| &cp.addresses);
+        let move_calls = rows(data, |cp| &cp.move_calls);
+        let recipients = rows(data, |cp| &cp.recipients);
+        let epochs: BTreeSet<_> = checkpoints.iter().map(|cp| cp.epoch as EpochId).collect();
 
         let mut pg_pool_conn = get_pg_pool_connection(&self.cp)?;
 
-        // Commit indexed checkpoint in one transaction
+        // Commit indexed checkpoints in one transaction
         pg_pool_conn
             .build_transaction()
             .serializable()
             .read_write()
             .run(|conn| {
-                diesel::insert_into(checkpoints_table)
-                    .values(checkpoint)
-                    .execute(conn)?;
+                // Usually created at the end of the previous epoch already
+                for epoch in &epochs {
+                    self.partition_manager.create_partitions(conn, *epoch)?;
+                }
 
-                diesel::insert_into(transactions::table)
-                    .values(transactions)
-                    .execute(conn)?;
+                for chunk in checkpoints.chunks(PG_INSERT_CHUNK_SIZE) {
+                    diesel::insert_into(checkpoints_table)
+                        .values(chunk)
+                        .execute(conn)?;
+                }
 
-                diesel::insert_into(events::table)
-                    .values(events)
-                    .execute(conn)?;
+                for chunk in transactions.chunks(PG_INSERT_CHUNK_SIZE) {
+                    diesel::insert_into(transactions::table)
+                        .values(chunk)
+                        .execute(conn)?;
+                }
+
+                for chunk in events.chunks(PG_INSERT_CHUNK_SIZE) {
+                    diesel::insert_into(events::table)
+                        .values(chunk)
+                        .execute(conn)?;
+                }
 
                 // Object need to bulk insert by transaction to prevent same object mutated twice in the same sql call,
                 // which will result in "ON CONFLICT DO UPDATE command cannot affect row a second time" error
-                for changes in objects_changes {
+                for changes in data.iter().flat_map(|cp| &cp.objects_changes) {
                     diesel::insert_into(objects::table)
                         .values(&changes.mutated_objects)
                         .on_conflict(objects::object_id)
@@ -525,8 +753,12 @@ impl IndexerStore for PgIndexerStore {
                             objects::checkpoint.eq(excluded(objects::checkpoint)),
                             objects::version.eq(excluded(objects::version)),
                             objects::object_digest.eq(excluded(objects::object_digest)),
+                            objects::owner_type.eq(excluded(objects::owner_type)),
                             objects::owner_address.eq(excluded(objects::owner_address)),
-                            objects::previous_transaction.eq(excluded(objects::previous_transaction)),
+                            objects::initial_shared_version
+                                .eq(excluded(objects::initial_shared_version)),
+                            objects::previous_transaction
+                                .eq(excluded(objects::previous_transaction)),
                             objects::object_status.eq(excluded(objects::object_status)),
                         ))
                         .execute(conn)?;
@@ -539,49 +771,63 @@ impl IndexerStore for PgIndexerStore {
                             objects::epoch.eq(excluded(objects::epoch)),
                             objects::checkpoint.eq(excluded(objects::checkpoint)),
                             objects::version.eq(excluded(objects::version)),
-                            objects::previous_transaction.eq(excluded(objects::previous_transaction)),
+                            objects::previous_transaction
+                                .eq(excluded(objects::previous_transaction)),
                             objects::object_status.eq(excluded(objects::object_status)),
                         ))
                         .execute(conn)?;
                 }
 
                 // Only insert once for address, skip if conflict
-                diesel::insert_into(addresses::table)
-                    .values(addresses)
-                    .on_conflict(account_address)
-                    .do_nothing()
-                    .execute(conn)?;
-
-                diesel::insert_into(packages::table)
-                    .values(packages)
-                    .on_conflict(package_id)
-                    .do_update()
-                    .set((
-                        author.eq(excluded(author)),
-                        module_names.eq(excluded(module_names)),
-                        package_content.eq(excluded(package_content)),
-                    ))
-                    .execute(conn)?;
-
-                diesel::insert_into(move_calls::table)
-                    .values(move_calls)
-                    .execute(conn)?;
-
-                diesel::insert_into(recipients::table)
-                    .values(recipients)
-                    .execute(conn)
+                for chunk in addresses.chunks(PG_INSERT_CHUNK_SIZE) {
+                    diesel::insert_into(addresses::table)
+                        .values(chunk)
+                        .on_conflict(account_address)
+                        .do_nothing()
+                        .execute(conn)?;
+                }
+
+                // By checkpoint, as a package upserted twice in one statement is an error
+                for packages in data.iter().map(|cp| &cp.packages) {
+                    diesel::insert_into(packages::table)
+                        .values(packages)
+                        .on_conflict(package_id)
+                        .do_update()
+                        .set((
+                            author.eq(excluded(author)),
+                            module_names.eq(excluded(module_names)),
+                            package_content.eq(excluded(package_content)),
+                        ))
+                        .execute(conn)?;
+                }
+
+                for chunk in move_calls.chunks(PG_INSERT_CHUNK_SIZE) {
+                    diesel::insert_into(move_calls::table)
+                        .values(chunk)
+                        .execute(conn)?;
+                }
+
+                for chunk in recipients.chunks(PG_INSERT_CHUNK_SIZE) {
+                    diesel::insert_into(recipients::table)
+                        .values(chunk)
+                        .execute(conn)?;
+                }
+                Ok::<_, diesel::result::Error>(checkpoints.len())
             })
             .map_err(|e| {
                 IndexerError::PostgresWriteError(format!(
-                    "Failed writing checkpoint to PostgresDB with transactions {:?} and error: {:?}",
-                    transactions, e
+                    "Failed writing checkpoints {:?} to PostgresDB with error: {:?}",
+                    checkpoints
+                        .iter()
+                        .map(|cp| cp.sequence_number)
+                        .collect::<Vec<_>>(),
+                    e
                 ))
             })
     }
 
-    fn persist_epoch(&self, _data: &TemporaryEpochStore) -> Result<usize, IndexerError> {
-        // TODO: create new partition on epoch change
-        self.partition_manager.advance_epoch(1)
+    fn persist_epoch(&self, data: &TemporaryEpochStore) -> Result<usize, IndexerError> {
+        self.partition_manager.advance_epoch(data.next_epoch)
     }
 
     fn log_errors(&self, errors: Vec<IndexerError>) -> Result<(), IndexerError> {
@@ -596,16 +842,27 @@ impl IndexerStore for PgIndexerStore {
     }
 }
 
+#[derive(QueryableByName, Debug)]
+struct TablePartition {
+    #[diesel(sql_type = VarChar)]
+    partition_name: String,
+}
+
 #[derive(Clone)]
 struct PartitionManager {
     cp: PgConnectionPool,
     tables: Vec<String>,
+    epochs_to_keep: Option<u64>,
 }
 
 impl PartitionManager {
     fn new(cp: PgConnectionPool) -> Result<Self, IndexerError> {
         // Find all tables with partition
-        let mut manager = Self { cp, tables: vec![] };
+        let mut manager = Self {
+            cp,
+            tables: vec![],
+            epochs_to_keep: None,
+        };
         let tables = manager.get_table_partitions()?;
         info!(
             "Found {} tables with partitions : [{:?}]",
@@ -617,19 +874,61 @@ impl PartitionManager {
         }
         Ok(manager)
     }
-    fn advance_epoch(&self, next_epoch_id: EpochId) -> Result<usize, IndexerError> {
+
+    /// Creates the partitions of `epoch` of the partitioned tables that do not have them yet.
+    fn create_partitions(
+        &self,
+        conn: &mut PgConnection,
+        epoch: EpochId,
+    ) -> Result<(), diesel::result::Error> {
+        for table in &self.tables {
+            let sql = format!(
+                "CREATE TABLE IF NOT EXISTS {table}_partition_{epoch} PARTITION OF {table} FOR VALUES FROM ({epoch}) TO ({});",
+                epoch + 1
+            );
+            diesel::sql_query(sql).execute(conn)?;
+        }
+        Ok(())
+    }
+
+    /// Creates the partitions of `next_epoch`, and drops the partitions of the epochs that are
+    /// no longer kept.
+    fn advance_epoch(&self, next_epoch: EpochId) -> Result<usize, IndexerError> {
         let mut pg_pool_conn = get_pg_pool_connection(&self.cp)?;
         pg_pool_conn
             .build_transaction()
-            .read_write().serializable()
+            .read_write()
+            .serializable()
             .run(|conn| {
+                self.create_partitions(conn, next_epoch)?;
+                let Some(epochs_to_keep) = self.epochs_to_keep else {
+                    return Ok(0);
+                };
+                let first_kept_epoch = (next_epoch + 1).saturating_sub(epochs_to_keep);
+                let mut dropped = 0;
                 for table in &self.tables {
-                    let sql = format!("CREATE TABLE {table}_partition_{next_epoch_id} PARTITION OF {table} FOR VALUES FROM ({next_epoch_id}) TO ({});", next_epoch_id+1);
-                    diesel::sql_query(sql).execute(conn)?;
+                    let partitions: Vec<TablePartition> =
+                        diesel::sql_query(GET_TABLE_PARTITIONS_SQL)
+                            .bind::<VarChar, _>(table)
+                            .load(conn)?;
+                    for partition in partitions {
+                        let epoch = partition
+                            .partition_name
+                            .strip_prefix(&format!("{table}_partition_"))
+                            .and_then(|epoch| epoch.parse::<EpochId>().ok());
+                        if matches!(epoch, Some(epoch) if epoch < first_kept_epoch) {
+                            let sql = format!("DROP TABLE {};", partition.partition_name);
+                            diesel::sql_query(sql).execute(conn)?;
+                            dropped += 1;
+                        }
+                    }
+                }
+                if dropped > 0 {
+                    info!("Dropped {dropped} partitions of epochs before {first_kept_epoch}");
                 }
-                Ok::<_, diesel::result::Error>(self.tables.len())
+                Ok::<_, diesel::result::Error>(dropped)
             })
-            .map_err(|e| IndexerError::PostgresReadError(e.to_string()))
+            .map_err(|e| IndexerError::PostgresWriteError(e.to_string()))
     }
 
     fn get_table_partitions(&self) -> Result<BTreeMap<String, String>, IndexerError> {
@@ -1,16 +1,19 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use chrono::NaiveDateTime;
 use prometheus::Registry;
 use std::sync::{Arc, RwLock};
 use std::time::Duration;
 use sui_indexer::errors::IndexerError;
 use sui_indexer::models::checkpoints::Checkpoint;
+use sui_indexer::models::events::Event;
 use sui_indexer::models::objects::Object;
 use sui_indexer::models::transactions::Transaction;
 use sui_indexer::store::{IndexerStore, TemporaryCheckpointStore, TemporaryEpochStore};
-use sui_indexer::Indexer;
+use sui_indexer::{CheckpointHandlerConfig, Indexer};
 use sui_json_rpc_types::CheckpointId;
+use sui_types::event::EventID;
 use test_utils::network::TestClusterBuilder;
 
 #[tokio::test]
@@ -20,7 +23,14 @@ async fn test_genesis() {
 
     let s = store.clone();
     let _handle = tokio::task::spawn(async move {
-        Indexer::start(test_cluster.rpc_url(), &Registry::default(), s).await
+        Indexer::start(
+            test_cluster.rpc_url(),
+            &Registry::default(),
+            s,
+            "127.0.0.1:3030".parse().unwrap(),
+            CheckpointHandlerConfig::default(),
+        )
+        .await
     });
 
     // Allow indexer to process the data
@@ -158,6 +168,57 @@ impl IndexerStore for InMemoryIndexerStore {
         todo!()
     }
 
+    fn get_event_sequence_by_id(
+        &self,
+        _event_id: Option<EventID>,
+    ) -> Result<Option<i64>, IndexerError> {
+        todo!()
+    }
+
+    fn get_all_events_page(
+        &self,
+        _start_sequence: Option<i64>,
+        _limit: usize,
+        _is_descending: bool,
+    ) -> Result<Vec<Event>, IndexerError> {
+        todo!()
+    }
+
+    fn get_events_page_by_transaction(
+        &self,
+        _txn_digest: String,
+        _start_sequence: Option<i64>,
+        _limit: usize,
+        _is_descending: bool,
+    ) -> Result<Vec<Event>, IndexerError> {
+        todo!()
+    }
+
+    fn get_events_page_by_time_range(
+        &self,
+        _start_time: NaiveDateTime,
+        _end_time: NaiveDateTime,
+        _start_sequence: Option<i64>,
+        _limit: usize,
+        _is_descending: bool,
+    ) -> Result<Vec<Event>, IndexerError> {
+        todo!()
+    }
+
+    fn get_object(&self, _object_id: String) -> Result<Option<Object>, IndexerError> {
+        todo!()
+    }
+
+    fn get_owned_objects_page(
+        &self,
+        _owner_address: String,
+        _object_type: Option<String>,
+        _start_object_id: Option<String>,
+        _limit: usize,
+    ) -> Result<Vec<Object>, IndexerError> {
+        todo!()
+    }
+
     fn read_transactions(
         &self,
         _last_processed_id: i64,
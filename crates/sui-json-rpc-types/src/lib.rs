@@ -3,8 +3,12 @@
 
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
 
+use sui_protocol_config::{ProtocolConfig, SupportedProtocolVersions};
 use sui_types::base_types::ObjectID;
+use sui_types::committee::EpochId;
 use sui_types::dynamic_field::DynamicFieldInfo;
 
 pub use sui_event::*;
@@ -31,10 +35,90 @@ mod sui_object;
 mod sui_transaction;
 
 pub type DynamicFieldPage = Page<DynamicFieldInfo, ObjectID>;
+pub type OwnedObjectsPage = Page<SuiObjectInfo, OwnedObjectsCursor>;
 
 #[derive(Clone, Debug, JsonSchema, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Page<T, C> {
     pub data: Vec<T>,
     pub next_cursor: Option<C>,
+    /// Whether there are items after those of this page, listed from `next_cursor` on. A page
+    /// may be cut short of its limit to keep the response within the maximum response size.
+    pub has_next_page: bool,
+}
+
+/// The build of the node serving the request, and the protocol versions it can run.
+#[derive(Clone, Debug, JsonSchema, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NodeInfo {
+    /// Version of the node binary
+    pub version: String,
+    /// Git revision the node binary was built from
+    pub git_revision: String,
+    /// Lowest protocol version the node binary supports
+    pub min_supported_protocol_version: u64,
+    /// Highest protocol version the node binary supports
+    pub max_supported_protocol_version: u64,
+    /// Protocol version of the current epoch
+    pub protocol_version: u64,
+    /// The current epoch
+    pub epoch: EpochId,
+}
+
+/// The protocol config of a protocol version: the constants limiting and pricing transactions,
+/// and the features enabled.
+#[derive(Clone, Debug, JsonSchema, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProtocolConfigResponse {
+    /// Lowest protocol version the node binary supports
+    pub min_supported_protocol_version: u64,
+    /// Highest protocol version the node binary supports
+    pub max_supported_protocol_version: u64,
+    /// Protocol version of the config
+    pub protocol_version: u64,
+    /// Whether each feature is enabled, by feature name
+    pub feature_flags: BTreeMap<String, bool>,
+    /// The constants defined in the protocol version, by name
+    pub attributes: BTreeMap<String, Value>,
+}
+
+impl ProtocolConfigResponse {
+    pub fn new(config: &ProtocolConfig, supported_versions: SupportedProtocolVersions) -> Self {
+        // The constants are private to the config, and only reachable through its serialization,
+        // which leaves out those undefined in its version.
+        let mut attributes: BTreeMap<String, Value> = match serde_json::to_value(config) {
+            Ok(Value::Object(attributes)) => attributes.into_iter().collect(),
+            _ => unreachable!("the protocol config serializes to a map"),
+        };
+        attributes.remove("version");
+        let feature_flags = match attributes.remove("feature_flags") {
+            Some(Value::Object(flags)) => flags
+                .into_iter()
+                .filter_map(|(name, enabled)| Some((name, enabled.as_bool()?)))
+                .collect(),
+            _ => BTreeMap::new(),
+        };
+        Self {
+            min_supported_protocol_version: supported_versions.min.as_u64(),
+            max_supported_protocol_version: supported_versions.max.as_u64(),
+            protocol_version: config.version.as_u64(),
+            feature_flags,
+            attributes,
+        }
+    }
+}
+
+/// The limits on the requests each client can make to the JSON-RPC server. Clients with an API key
+/// get the multiple of these limits their key is configured with.
+#[derive(Clone, Debug, JsonSchema, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RateLimits {
+    /// Requests per second a client can make to each method not in `methodRequestsPerSecond`
+    pub requests_per_second: u32,
+    /// Requests per second a client can make to particular methods
+    #[serde(default)]
+    pub method_requests_per_second: BTreeMap<String, u32>,
+    /// If set, the number of requests a client can have in flight at once
+    #[serde(default)]
+    pub max_concurrent_requests: Option<u32>,
 }
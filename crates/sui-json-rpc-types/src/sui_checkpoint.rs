@@ -1,8 +1,11 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::{Page, SuiObjectData, SuiTransaction, SuiTransactionEffects, SuiTransactionEvents};
+use fastcrypto::encoding::Base64;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
 use sui_types::base_types::TransactionDigest;
 use sui_types::committee::EpochId;
 use sui_types::digests::CheckpointDigest;
@@ -12,6 +15,8 @@ use sui_types::messages_checkpoint::{
     CheckpointTimestamp, EndOfEpochData,
 };
 
+pub type CheckpointPage = Page<Checkpoint, CheckpointSequenceNumber>;
+
 #[derive(Clone, Debug, JsonSchema, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Checkpoint {
@@ -42,6 +47,53 @@ pub struct Checkpoint {
     pub checkpoint_commitments: Vec<CheckpointCommitment>,
 }
 
+/// What re-executing a checkpoint found for each of its transactions.
+#[derive(Clone, Debug, Default, JsonSchema, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct CheckpointReexecution {
+    /// Transactions the node had not executed, enqueued for execution.
+    pub enqueued: Vec<TransactionDigest>,
+    /// Transactions the node executed with the effects of the checkpoint.
+    pub executed: Vec<TransactionDigest>,
+    /// Transactions the node executed with effects other than those of the checkpoint.
+    pub mismatched: Vec<TransactionDigest>,
+}
+
+/// A checkpoint with the data of all of its transactions, in the order they were executed.
+#[derive(Clone, Debug, JsonSchema, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SuiCheckpointData {
+    pub checkpoint: Checkpoint,
+    pub transactions: Vec<SuiCheckpointTransaction>,
+}
+
+#[derive(Clone, Debug, JsonSchema, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SuiCheckpointTransaction {
+    pub transaction: SuiTransaction,
+    pub effects: SuiTransactionEffects,
+    pub events: SuiTransactionEvents,
+    /// The objects modified by the transaction, at the versions it took them as input.
+    pub input_objects: Vec<SuiObjectData>,
+    /// The objects created, mutated or unwrapped by the transaction, at their new versions.
+    pub output_objects: Vec<SuiObjectData>,
+}
+
+/// A proof that a transaction was executed in a certified checkpoint, for clients that do not run
+/// a node.
+#[serde_as]
+#[derive(Clone, Debug, JsonSchema, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SuiTransactionInclusionProof {
+    pub digest: TransactionDigest,
+    pub checkpoint: CheckpointSequenceNumber,
+    /// The BCS encoded `TransactionInclusionProof`, verified against the committee of the epoch of
+    /// the checkpoint and the effects of the transaction.
+    #[serde_as(as = "Base64")]
+    #[schemars(with = "Base64")]
+    pub proof: Vec<u8>,
+}
+
 impl From<(CheckpointSummary, CheckpointContents)> for Checkpoint {
     fn from((summary, contents): (CheckpointSummary, CheckpointContents)) -> Self {
         let digest = summary.digest();
@@ -17,14 +17,62 @@ use sui_types::object::Object;
 use crate::Page;
 
 pub type CoinPage = Page<Coin, ObjectID>;
+pub type BalanceChangePage = Page<BalanceChange, TransactionDigest>;
 
 #[derive(Serialize, Deserialize, Debug, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Balance {
     pub coin_type: String,
+    /// Number of coin objects, locked or not.
     pub coin_object_count: usize,
+    /// Total balance of the coins that are not locked.
     pub total_balance: u128,
+    /// Total balance of the locked coins, by the epoch they are locked until.
     pub locked_balance: HashMap<EpochId, u128>,
+    /// Total balance of the locked coins.
+    #[serde(default)]
+    pub total_locked_balance: u128,
+    /// The unlocked coin with the largest balance, e.g. to pay gas with.
+    #[serde(default)]
+    pub largest_coin: Option<Coin>,
+}
+
+impl Balance {
+    pub fn new(coin_type: String) -> Self {
+        Self {
+            coin_type,
+            coin_object_count: 0,
+            total_balance: 0,
+            locked_balance: HashMap::new(),
+            total_locked_balance: 0,
+            largest_coin: None,
+        }
+    }
+
+    /// Counts `coin`, which must be of the coin type of the balance, towards its totals.
+    pub fn add_coin(&mut self, coin: Coin) {
+        self.coin_object_count += 1;
+        if let Some(lock) = coin.locked_until_epoch {
+            *self.locked_balance.entry(lock).or_default() += coin.balance as u128;
+            self.total_locked_balance += coin.balance as u128;
+        } else {
+            self.total_balance += coin.balance as u128;
+            if !matches!(&self.largest_coin, Some(largest) if largest.balance >= coin.balance) {
+                self.largest_coin = Some(coin);
+            }
+        }
+    }
+}
+
+/// The net change a transaction made to the balance of a coin type owned by an address.
+#[derive(Serialize, Deserialize, Debug, JsonSchema, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct BalanceChange {
+    pub tx_digest: TransactionDigest,
+    pub timestamp_ms: u64,
+    pub coin_type: String,
+    /// Negative when the balance decreased.
+    pub amount: i128,
 }
 
 #[derive(Serialize, Deserialize, Debug, JsonSchema)]
@@ -45,6 +93,26 @@ impl Coin {
     }
 }
 
+/// Position in a listing of coins by descending balance.
+#[derive(Serialize, Deserialize, Debug, JsonSchema, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct CoinBalanceCursor {
+    pub balance: u64,
+    pub coin_object_id: ObjectID,
+}
+
+/// A page of coins listed by descending balance, along with the number and total balance of the
+/// coins in all pages.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CoinsByBalancePage {
+    pub data: Vec<Coin>,
+    pub next_cursor: Option<CoinBalanceCursor>,
+    pub has_next_page: bool,
+    pub total_count: usize,
+    pub total_balance: u128,
+}
+
 #[derive(Serialize, Deserialize, Debug, JsonSchema, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct SuiCoinMetadata {
@@ -629,6 +629,8 @@ pub enum SuiEventFilter {
     Any(Vec<SuiEventFilter>),
     And(Box<SuiEventFilter>, Box<SuiEventFilter>),
     Or(Box<SuiEventFilter>, Box<SuiEventFilter>),
+    /// Matches the events that the inner filter does not match.
+    Not(Box<SuiEventFilter>),
 }
 
 impl TryInto<EventFilter> for SuiEventFilter {
@@ -660,6 +662,7 @@ impl TryInto<EventFilter> for SuiEventFilter {
             ),
             And(filter_a, filter_b) => All(vec![*filter_a, *filter_b]).try_into()?,
             Or(filter_a, filter_b) => Any(vec![*filter_a, *filter_b]).try_into()?,
+            Not(filter) => EventFilter::Not(Box::new((*filter).try_into()?)),
             EventType(type_) => EventFilter::EventType(type_),
         })
     }
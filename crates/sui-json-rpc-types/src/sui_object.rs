@@ -24,6 +24,7 @@ use sui_types::base_types::{
 use sui_types::error::{UserInputError, UserInputResult};
 use sui_types::gas_coin::GasCoin;
 use sui_types::messages::MoveModulePublish;
+use sui_types::messages_checkpoint::CheckpointSequenceNumber;
 use sui_types::move_package::{disassemble_modules, MovePackage};
 use sui_types::object::{Data, MoveObject, Object, ObjectFormatOptions, ObjectRead, Owner};
 use sui_types::parse_sui_struct_tag;
@@ -754,6 +755,28 @@ impl From<MovePackage> for SuiRawMovePackage {
     }
 }
 
+/// An object and one of its past versions.
+#[derive(Serialize, Deserialize, Debug, JsonSchema, Clone)]
+#[serde(rename_all = "camelCase", rename = "GetPastObjectRequest")]
+pub struct SuiGetPastObjectRequest {
+    /// the ID of the queried object
+    pub object_id: ObjectID,
+    /// the version of the queried object
+    pub version: SequenceNumber,
+}
+
+/// A version of an object in its history.
+#[derive(Serialize, Deserialize, Debug, JsonSchema, Clone)]
+#[serde(tag = "status", content = "details", rename = "ObjectHistoryEntry")]
+pub enum SuiObjectHistoryEntry {
+    /// The object as it was at this version
+    VersionFound(SuiObjectData),
+    /// The object was deleted or wrapped at this version, as told by the digest
+    ObjectDeleted(SuiObjectRef),
+    /// The object had this version, but the node has pruned it since
+    VersionPruned(ObjectID, SequenceNumber),
+}
+
 #[derive(Serialize, Deserialize, Debug, JsonSchema, Clone)]
 #[serde(tag = "status", content = "details", rename = "ObjectRead")]
 pub enum SuiPastObjectResponse {
@@ -874,3 +897,13 @@ impl From<ObjectInfo> for SuiObjectInfo {
         }
     }
 }
+
+/// Position in the objects of an owner, as they were at the end of a checkpoint.
+#[derive(Clone, Copy, Serialize, Deserialize, JsonSchema, Eq, PartialEq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct OwnedObjectsCursor {
+    /// The checkpoint at the end of which the objects are listed
+    pub checkpoint: CheckpointSequenceNumber,
+    /// The object to resume the listing from, inclusive
+    pub object_id: ObjectID,
+}
@@ -18,7 +18,7 @@ use sui_types::base_types::{
 };
 use sui_types::digests::TransactionEventsDigest;
 use sui_types::error::ExecutionError;
-use sui_types::gas::GasCostSummary;
+use sui_types::gas::{GasCostSummary, GasProfile, GasProfileEntry};
 use sui_types::messages::{
     Argument, CallArg, Command, ExecutionStatus, GenesisObject, InputObjectKind, ObjectArg, Pay,
     PayAllSui, PaySui, ProgrammableMoveCall, ProgrammableTransaction, SenderSignedData,
@@ -62,6 +62,118 @@ impl PartialEq for SuiTransactionResponse {
     }
 }
 
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, Eq, PartialEq, Default)]
+#[serde(
+    rename_all = "camelCase",
+    rename = "TransactionResponseOptions",
+    default
+)]
+pub struct SuiTransactionResponseOptions {
+    /// Whether to show the transaction input data. Default to be False
+    pub show_input: bool,
+    /// Whether to show the transaction effects. Default to be False
+    pub show_effects: bool,
+    /// Whether to show the transaction events. Default to be False
+    pub show_events: bool,
+    /// Whether to show the BCS encoded transaction input data, e.g. to execute it again. Default
+    /// to be False
+    pub show_raw_input: bool,
+}
+
+impl SuiTransactionResponseOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// return the input data, effects and events
+    pub fn full_content() -> Self {
+        Self {
+            show_input: true,
+            show_effects: true,
+            show_events: true,
+            show_raw_input: false,
+        }
+    }
+
+    pub fn with_input(mut self) -> Self {
+        self.show_input = true;
+        self
+    }
+
+    pub fn with_effects(mut self) -> Self {
+        self.show_effects = true;
+        self
+    }
+
+    pub fn with_events(mut self) -> Self {
+        self.show_events = true;
+        self
+    }
+
+    pub fn with_raw_input(mut self) -> Self {
+        self.show_raw_input = true;
+        self
+    }
+}
+
+/// The result of reading one of the transactions of a batch, holding the parts of the
+/// transaction asked for by the [SuiTransactionResponseOptions], or the reason it could not be
+/// read.
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, JsonSchema, Clone, PartialEq)]
+#[serde(rename_all = "camelCase", rename = "TransactionResult")]
+pub struct SuiTransactionResult {
+    pub digest: TransactionDigest,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transaction: Option<SuiTransaction>,
+    /// The BCS encoded `SenderSignedData` of the transaction.
+    #[serde_as(as = "Option<Base64>")]
+    #[schemars(with = "Option<Base64>")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub raw_transaction: Option<Vec<u8>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub effects: Option<SuiTransactionEffects>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub events: Option<SuiTransactionEvents>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamp_ms: Option<u64>,
+    /// The checkpoint number when this transaction was included and hence finalized.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub checkpoint: Option<CheckpointSequenceNumber>,
+    /// Why the transaction could not be read, e.g. it is not known to the node.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl SuiTransactionResult {
+    pub fn new(response: SuiTransactionResponse, options: &SuiTransactionResponseOptions) -> Self {
+        Self {
+            digest: *response.effects.transaction_digest(),
+            transaction: options.show_input.then_some(response.transaction),
+            // The raw input is not kept in the response.
+            raw_transaction: None,
+            effects: options.show_effects.then_some(response.effects),
+            events: options.show_events.then_some(response.events),
+            timestamp_ms: response.timestamp_ms,
+            checkpoint: response.checkpoint,
+            error: None,
+        }
+    }
+
+    pub fn error(digest: TransactionDigest, error: String) -> Self {
+        Self {
+            digest,
+            transaction: None,
+            raw_transaction: None,
+            effects: None,
+            events: None,
+            timestamp_ms: None,
+            checkpoint: None,
+            error: Some(error),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
 #[serde(rename = "TransactionKind")]
 pub enum SuiTransactionKind {
@@ -538,6 +650,19 @@ impl SuiTransactionEvents {
     }
 }
 
+/// A gas budget and price recommended for a transaction, from a dry run of it.
+#[derive(Eq, PartialEq, Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(rename = "GasEstimate", rename_all = "camelCase")]
+pub struct GasEstimate {
+    /// The gas budget to set on the transaction, which is the gas used in the dry run plus a
+    /// safety margin.
+    pub gas_budget: u64,
+    /// The gas price the estimate was made at.
+    pub gas_price: u64,
+    /// The gas used in the dry run.
+    pub gas_used: SuiGasCostSummary,
+}
+
 /// The response from processing a dev inspect transaction
 #[derive(Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename = "DevInspectResults", rename_all = "camelCase")]
@@ -551,16 +676,102 @@ pub struct DevInspectResults {
     /// Execution results (including return values) from executing the transactions
     /// Currently contains only return values from Move calls
     pub results: Result<Vec<(usize, SuiExecutionResult)>, String>,
+    /// The BCS bytes of the transaction data that was run, if asked for with
+    /// `showRawTxnDataAndEffects`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub raw_txn_data: Vec<u8>,
+    /// The BCS bytes of the effects of the run, if asked for with `showRawTxnDataAndEffects`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub raw_effects: Vec<u8>,
+    /// The gas charged by Move execution, by instruction and by function, if asked for with
+    /// `profileGas`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gas_profile: Option<SuiGasProfile>,
+}
+
+/// Additional options of dev-inspect runs, for using them to evaluate Move rather than to preview
+/// transactions.
+#[derive(Debug, Default, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename = "DevInspectArgs", rename_all = "camelCase")]
+pub struct DevInspectArgs {
+    /// The gas budget of the run, up to the maximum gas of a transaction, which it defaults to.
+    pub gas_budget: Option<u64>,
+    /// Skips the validity checks of the transaction, e.g. of its size limits. Dev-inspect never
+    /// checks that the sender owns the objects used, nor the visibility of the functions called.
+    pub skip_checks: Option<bool>,
+    /// Returns the BCS bytes of the transaction data and of the effects of the run, next to their
+    /// JSON forms.
+    pub show_raw_txn_data_and_effects: Option<bool>,
+    /// Breaks down the gas charged by Move execution by instruction and by function.
+    pub profile_gas: Option<bool>,
+}
+
+/// The gas charged by Move execution, by instruction and by function. It is in internal gas
+/// units, a thousandth of a gas unit, and leaves out the gas charged for storage and the minimum
+/// transaction fee.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename = "GasProfile", rename_all = "camelCase")]
+pub struct SuiGasProfile {
+    /// The instructions run, most expensive first. The gas charged by native functions is
+    /// recorded under `NATIVE`.
+    pub instructions: Vec<SuiGasProfileEntry>,
+    /// The functions called, most expensive first. The gas of a function only covers the
+    /// instructions it runs itself, and that of entry functions is recorded under `<entry>`.
+    pub functions: Vec<SuiGasProfileEntry>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename = "GasProfileEntry", rename_all = "camelCase")]
+pub struct SuiGasProfileEntry {
+    pub name: String,
+    /// How many times the instruction ran, or the function was called.
+    pub count: u64,
+    pub internal_gas: u64,
+}
+
+impl SuiGasProfile {
+    pub fn total_internal_gas(&self) -> u64 {
+        self.instructions
+            .iter()
+            .map(|entry| entry.internal_gas)
+            .sum()
+    }
+}
+
+impl From<GasProfile> for SuiGasProfile {
+    fn from(profile: GasProfile) -> Self {
+        fn sorted(
+            entries: impl Iterator<Item = (String, GasProfileEntry)>,
+        ) -> Vec<SuiGasProfileEntry> {
+            let mut entries: Vec<_> = entries
+                .map(|(name, entry)| SuiGasProfileEntry {
+                    name,
+                    count: entry.count,
+                    internal_gas: entry.internal_gas,
+                })
+                .collect();
+            entries.sort_by(|a, b| {
+                b.internal_gas
+                    .cmp(&a.internal_gas)
+                    .then(a.name.cmp(&b.name))
+            });
+            entries
+        }
+        Self {
+            instructions: sorted(profile.instructions.into_iter()),
+            functions: sorted(profile.functions.into_iter()),
+        }
+    }
 }
 
 #[derive(Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename = "SuiExecutionResult", rename_all = "camelCase")]
 pub struct SuiExecutionResult {
-    /// The value of any arguments that were mutably borrowed.
+    /// The value of any arguments that were mutably borrowed, as BCS bytes with their types.
     /// Non-mut borrowed values are not included
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub mutable_reference_outputs: Vec<(/* local index */ u8, Vec<u8>, SuiTypeTag)>,
-    /// The return values from the function
+    /// The return values from the function, as BCS bytes with their types
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub return_values: Vec<(Vec<u8>, SuiTypeTag)>,
 }
@@ -603,6 +814,9 @@ impl DevInspectResults {
             effects: effects.try_into()?,
             events: SuiTransactionEvents::try_from(events, resolver)?,
             results,
+            raw_txn_data: vec![],
+            raw_effects: vec![],
+            gas_profile: None,
         })
     }
 }
@@ -1237,6 +1451,12 @@ impl From<TypeTag> for SuiTypeTag {
     }
 }
 
+impl Display for SuiTypeTag {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
 #[derive(Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub enum RPCTransactionRequestParams {
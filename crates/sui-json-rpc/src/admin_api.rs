@@ -0,0 +1,113 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use jsonrpsee::core::RpcResult;
+use jsonrpsee::RpcModule;
+use tracing::info;
+
+use sui_config::node::{default_api_key_header, default_client_address_header, RpcRateLimitConfig};
+use sui_core::authority::AuthorityState;
+use sui_json_rpc_types::{CheckpointReexecution, RateLimits};
+use sui_open_rpc::Module;
+use sui_types::messages_checkpoint::CheckpointSequenceNumber;
+
+use crate::api::AdminApiServer;
+use crate::error::Error;
+use crate::{RateLimitHandle, SuiRpcModule};
+
+pub struct AdminApi {
+    state: Arc<AuthorityState>,
+    rate_limits: RateLimitHandle,
+}
+
+impl AdminApi {
+    /// `rate_limits` are the limits of the public JSON-RPC server of the node.
+    pub fn new(state: Arc<AuthorityState>, rate_limits: RateLimitHandle) -> Self {
+        Self { state, rate_limits }
+    }
+}
+
+#[async_trait]
+impl AdminApiServer for AdminApi {
+    async fn force_prune(&self) -> RpcResult<()> {
+        info!("Forcing a pruning run");
+        self.state.force_prune();
+        Ok(())
+    }
+
+    async fn reexecute_checkpoint(
+        &self,
+        sequence_number: CheckpointSequenceNumber,
+    ) -> RpcResult<CheckpointReexecution> {
+        let reexecution = self
+            .state
+            .reexecute_checkpoint(sequence_number)
+            .await
+            .map_err(Error::from)?;
+        info!(
+            sequence_number,
+            enqueued = reexecution.enqueued.len(),
+            mismatched = reexecution.mismatched.len(),
+            "Re-executing checkpoint"
+        );
+        Ok(reexecution)
+    }
+
+    async fn flush_memtables(&self) -> RpcResult<()> {
+        info!("Flushing the database memtables");
+        Ok(self.state.flush_memtables().map_err(Error::from)?)
+    }
+
+    async fn get_rate_limits(&self) -> RpcResult<Option<RateLimits>> {
+        Ok(self.rate_limits.config().map(|config| RateLimits {
+            requests_per_second: config.requests_per_second,
+            method_requests_per_second: config.method_requests_per_second,
+            max_concurrent_requests: config.max_concurrent_requests,
+        }))
+    }
+
+    async fn set_rate_limits(&self, limits: Option<RateLimits>) -> RpcResult<()> {
+        info!(?limits, "Changing the JSON-RPC rate limits");
+        let config = limits.map(|limits| {
+            let (api_keys, api_key_header, client_address_header, num_trusted_proxies) =
+                match self.rate_limits.config() {
+                    Some(config) => (
+                        config.api_keys,
+                        config.api_key_header,
+                        config.client_address_header,
+                        config.num_trusted_proxies,
+                    ),
+                    None => (
+                        Default::default(),
+                        default_api_key_header(),
+                        default_client_address_header(),
+                        0,
+                    ),
+                };
+            RpcRateLimitConfig {
+                requests_per_second: limits.requests_per_second,
+                method_requests_per_second: limits.method_requests_per_second,
+                max_concurrent_requests: limits.max_concurrent_requests,
+                api_keys,
+                api_key_header,
+                client_address_header,
+                num_trusted_proxies,
+            }
+        });
+        self.rate_limits.set(config);
+        Ok(())
+    }
+}
+
+impl SuiRpcModule for AdminApi {
+    fn rpc(self) -> RpcModule<Self> {
+        self.into_rpc()
+    }
+
+    fn rpc_doc_module() -> Module {
+        crate::api::AdminApiOpenRpc::module_doc()
+    }
+}
@@ -0,0 +1,44 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use jsonrpsee::core::RpcResult;
+use jsonrpsee_proc_macros::rpc;
+
+use sui_json_rpc_types::{CheckpointReexecution, RateLimits};
+use sui_open_rpc_macros::open_rpc;
+use sui_types::messages_checkpoint::CheckpointSequenceNumber;
+
+/// Operational actions on the node, served by a separate server only its operators can reach.
+#[open_rpc(namespace = "admin", tag = "Admin API")]
+#[rpc(server, client, namespace = "admin")]
+pub trait AdminApi {
+    /// Start a pruning run without waiting for the pruning period.
+    #[method(name = "forcePrune")]
+    async fn force_prune(&self) -> RpcResult<()>;
+
+    /// Enqueue for execution the transactions of a checkpoint of the current epoch that the node
+    /// has not executed, and report those it executed with effects other than the checkpoint's.
+    #[method(name = "reexecuteCheckpoint")]
+    async fn reexecute_checkpoint(
+        &self,
+        /// The sequence number of the checkpoint.
+        sequence_number: CheckpointSequenceNumber,
+    ) -> RpcResult<CheckpointReexecution>;
+
+    /// Write the in-memory tables (memtables) of the perpetual database out to disk.
+    #[method(name = "flushMemtables")]
+    async fn flush_memtables(&self) -> RpcResult<()>;
+
+    /// Return the rate limits of the JSON-RPC server, or null if its requests are unlimited.
+    #[method(name = "getRateLimits")]
+    async fn get_rate_limits(&self) -> RpcResult<Option<RateLimits>>;
+
+    /// Replace the rate limits of the JSON-RPC server until it restarts. API keys and the headers
+    /// clients are told apart by stay as configured.
+    #[method(name = "setRateLimits")]
+    async fn set_rate_limits(
+        &self,
+        /// The new limits, or null to lift the limits.
+        limits: Option<RateLimits>,
+    ) -> RpcResult<()>;
+}
@@ -3,10 +3,12 @@
 
 use jsonrpsee::core::RpcResult;
 use jsonrpsee_proc_macros::rpc;
-use sui_json_rpc_types::{Balance, CoinPage, SuiCoinMetadata};
+use sui_json_rpc_types::{
+    Balance, BalanceChangePage, CoinBalanceCursor, CoinPage, CoinsByBalancePage, SuiCoinMetadata,
+};
 use sui_open_rpc_macros::open_rpc;
 use sui_types::balance::Supply;
-use sui_types::base_types::{ObjectID, SuiAddress};
+use sui_types::base_types::{ObjectID, SuiAddress, TransactionDigest};
 
 #[open_rpc(namespace = "sui", tag = "Coin Query API")]
 #[rpc(server, client, namespace = "sui")]
@@ -25,6 +27,25 @@ pub trait CoinReadApi {
         limit: Option<usize>,
     ) -> RpcResult<CoinPage>;
 
+    /// Return the spendable Coin<`coin_type`> objects owned by an address, largest balance first,
+    /// leaving out locked coins and the coins below `min_balance`. Each page also tells the number
+    /// and total balance of the coins across all pages, so that a wallet can select coins to pay
+    /// an amount without reading all of them.
+    #[method(name = "getCoinsByBalance")]
+    async fn get_coins_by_balance(
+        &self,
+        /// the owner's Sui address
+        owner: SuiAddress,
+        /// optional type name for the coin (e.g., 0x168da5bf1f48dafc111b0a488fa454aca95e0b5e::usdc::USDC), default to 0x2::sui::SUI if not specified.
+        coin_type: Option<String>,
+        /// optional minimum balance of the coins, default to 0
+        min_balance: Option<u64>,
+        /// optional paging cursor
+        cursor: Option<CoinBalanceCursor>,
+        /// maximum number of items per page
+        limit: Option<usize>,
+    ) -> RpcResult<CoinsByBalancePage>;
+
     /// Return all Coin objects owned by an address.
     #[method(name = "getAllCoins")]
     async fn get_all_coins(
@@ -37,7 +58,8 @@ pub trait CoinReadApi {
         limit: Option<usize>,
     ) -> RpcResult<CoinPage>;
 
-    /// Return the total coin balance for one coin type, owned by the address owner.
+    /// Return the total coin balance for one coin type, owned by the address owner, with the
+    /// locked and unlocked totals, the number of coin objects and the largest unlocked coin.
     #[method(name = "getBalance")]
     async fn get_balance(
         &self,
@@ -47,7 +69,8 @@ pub trait CoinReadApi {
         coin_type: Option<String>,
     ) -> RpcResult<Balance>;
 
-    /// Return the total coin balance for all coin type, owned by the address owner.
+    /// Return the total coin balance for all coin type, owned by the address owner, with the
+    /// same totals as `getBalance` for each coin type, ordered by coin type.
     #[method(name = "getAllBalances")]
     async fn get_all_balances(
         &self,
@@ -55,6 +78,24 @@ pub trait CoinReadApi {
         owner: SuiAddress,
     ) -> RpcResult<Vec<Balance>>;
 
+    /// Return the history of the balances owned by an address: the net change each transaction
+    /// made to the balance of each coin type. A page holds the changes of up to `limit`
+    /// transactions.
+    #[method(name = "getBalanceChanges")]
+    async fn get_balance_changes(
+        &self,
+        /// the owner's Sui address
+        owner: SuiAddress,
+        /// optional type name for the coin (e.g., 0x168da5bf1f48dafc111b0a488fa454aca95e0b5e::usdc::USDC), default to all coin types if not specified.
+        coin_type: Option<String>,
+        /// optional paging cursor
+        cursor: Option<TransactionDigest>,
+        /// maximum number of transactions per page, default to [QUERY_MAX_RESULT_LIMIT] if not specified.
+        limit: Option<usize>,
+        /// query result ordering, default to false (ascending order), oldest record first.
+        descending_order: Option<bool>,
+    ) -> RpcResult<BalanceChangePage>;
+
     /// Return metadata(e.g., symbol, decimals) for a coin
     #[method(name = "getCoinMetadata")]
     async fn get_coin_metadata(
@@ -63,7 +104,7 @@ pub trait CoinReadApi {
         coin_type: String,
     ) -> RpcResult<SuiCoinMetadata>;
 
-    /// Return total supply for a coin
+    /// Return total supply for a coin, as tracked by its treasury cap. The supply of SUI is fixed.
     #[method(name = "getTotalSupply")]
     async fn get_total_supply(
         &self,
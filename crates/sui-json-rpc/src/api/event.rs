@@ -8,7 +8,8 @@ use sui_json_rpc_types::{EventPage, SuiEventFilter};
 use sui_open_rpc_macros::open_rpc;
 use sui_types::event::EventID;
 
-use sui_json_rpc_types::SuiEventEnvelope;
+use sui_json_rpc_types::{SuiEventEnvelope, SuiTransactionEffects};
+use sui_types::filter::TransactionFilter;
 use sui_types::query::EventQuery;
 
 #[open_rpc(namespace = "sui", tag = "Event Read API")]
@@ -35,4 +36,13 @@ pub trait EventReadApi {
         /// the filter criteria of the event stream, see the [Sui docs](https://docs.sui.io/build/pubsub#event-filters) for detailed examples.
         filter: SuiEventFilter,
     );
+
+    /// Subscribe to a stream of the effects of the transactions matching the filter, as they are
+    /// executed by the node.
+    #[subscription(name = "subscribeTransaction", item = SuiTransactionEffects)]
+    fn subscribe_transaction(
+        &self,
+        /// the filter criteria of the transaction stream.
+        filter: TransactionFilter,
+    );
 }
@@ -11,6 +11,7 @@ use sui_types::base_types::SuiAddress;
 use sui_types::committee::EpochId;
 use sui_types::governance::DelegatedStake;
 
+use sui_types::sui_system_state::gas_price_survey::SuiGasPriceSurvey;
 use sui_types::sui_system_state::sui_system_state_inner_v1::ValidatorMetadataV1;
 use sui_types::sui_system_state::sui_system_state_summary::SuiSystemStateSummary;
 
@@ -45,4 +46,9 @@ pub trait GovernanceReadApi {
     /// Return the reference gas price for the network
     #[method(name = "getReferenceGasPrice")]
     async fn get_reference_gas_price(&self) -> RpcResult<u64>;
+
+    /// Return the gas price quotes of the active validators for the next epoch, along with the
+    /// reference gas price they would result in if the epoch ended now.
+    #[method(name = "getGasPriceSurvey")]
+    async fn get_gas_price_survey(&self) -> RpcResult<SuiGasPriceSurvey>;
 }
@@ -1,14 +1,27 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::env;
+use std::str::FromStr;
+use tap::TapFallible;
+use tracing::warn;
+
+mod admin;
 mod bls;
 mod coin;
 mod event;
 mod governance;
+mod name_service;
 mod read;
 mod transaction_builder;
 mod write;
 
+pub use admin::AdminApiClient;
+pub use admin::AdminApiOpenRpc;
+pub use admin::AdminApiServer;
+
 pub use bls::ThresholdBlsApiClient;
 pub use bls::ThresholdBlsApiOpenRpc;
 pub use bls::ThresholdBlsApiServer;
@@ -29,6 +42,10 @@ pub use governance::GovernanceReadApiClient;
 pub use governance::GovernanceReadApiOpenRpc;
 pub use governance::GovernanceReadApiServer;
 
+pub use name_service::NameServiceApiClient;
+pub use name_service::NameServiceApiOpenRpc;
+pub use name_service::NameServiceApiServer;
+
 pub use read::ReadApiClient;
 pub use read::ReadApiOpenRpc;
 pub use read::ReadApiServer;
@@ -43,6 +60,24 @@ pub use transaction_builder::TransactionBuilderServer;
 /// for document purposes.
 pub const QUERY_MAX_RESULT_LIMIT: usize = 1000;
 
+/// Default maximum size of a serialized response, in bytes.
+pub const DEFAULT_MAX_RESPONSE_SIZE: usize = 64 << 20;
+/// Room left in the maximum response size for the parts of a page other than its items.
+const PAGE_ENVELOPE_SIZE: usize = 1 << 10;
+
+/// Maximum size of a serialized response, in bytes, set with the `RPC_MAX_RESPONSE_SIZE`
+/// environment variable. Larger responses are turned away, and pages are cut short to fit.
+pub static MAX_RESPONSE_SIZE: Lazy<usize> = Lazy::new(|| {
+    env::var("RPC_MAX_RESPONSE_SIZE")
+        .ok()
+        .and_then(|o| {
+            usize::from_str(&o)
+                .tap_err(|e| warn!("Cannot parse RPC_MAX_RESPONSE_SIZE to usize: {e}"))
+                .ok()
+        })
+        .unwrap_or(DEFAULT_MAX_RESPONSE_SIZE)
+});
+
 pub fn cap_page_limit(limit: Option<usize>) -> usize {
     let limit = limit.unwrap_or_default();
     if limit > QUERY_MAX_RESULT_LIMIT || limit == 0 {
@@ -51,3 +86,25 @@ pub fn cap_page_limit(limit: Option<usize>) -> usize {
         limit
     }
 }
+
+/// Cuts `data` short before the first item that would take the page over [MAX_RESPONSE_SIZE],
+/// keeping at least one item, and returns the cursor of the page that follows: that of the first
+/// item cut, or `next_cursor` if none was.
+pub fn cap_page_size<T: Serialize, C>(
+    data: &mut Vec<T>,
+    next_cursor: Option<C>,
+    cursor_of: impl Fn(&T) -> C,
+) -> Option<C> {
+    let max_size = MAX_RESPONSE_SIZE.saturating_sub(PAGE_ENVELOPE_SIZE);
+    let mut size = 0;
+    for (i, item) in data.iter().enumerate() {
+        // Items are separated by a comma.
+        size += serde_json::to_vec(item).map_or(0, |item| item.len()) + 1;
+        if size > max_size && i > 0 {
+            let cursor = cursor_of(item);
+            data.truncate(i);
+            return Some(cursor);
+        }
+    }
+    next_cursor
+}
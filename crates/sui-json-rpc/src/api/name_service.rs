@@ -0,0 +1,29 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use jsonrpsee::core::RpcResult;
+use jsonrpsee_proc_macros::rpc;
+
+use sui_open_rpc_macros::open_rpc;
+use sui_types::base_types::SuiAddress;
+
+#[open_rpc(namespace = "sui", tag = "Name Service API")]
+#[rpc(server, client, namespace = "sui")]
+pub trait NameServiceApi {
+    /// Return the address a name points to in the name registry, if the name is registered.
+    #[method(name = "resolveNameServiceAddress")]
+    async fn resolve_name_service_address(
+        &self,
+        /// the name to resolve, e.g. `example.sui`
+        name: String,
+    ) -> RpcResult<Option<SuiAddress>>;
+
+    /// Return the names an address has chosen to be known by in the name registry, empty if it
+    /// has not chosen any.
+    #[method(name = "resolveNameServiceNames")]
+    async fn resolve_name_service_names(
+        &self,
+        /// the address to resolve
+        address: SuiAddress,
+    ) -> RpcResult<Vec<String>>;
+}
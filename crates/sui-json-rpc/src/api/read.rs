@@ -5,9 +5,12 @@ use jsonrpsee::core::RpcResult;
 use jsonrpsee_proc_macros::rpc;
 use std::collections::BTreeMap;
 use sui_json_rpc_types::{
-    Checkpoint, CheckpointId, DynamicFieldPage, MoveFunctionArgType, SuiMoveNormalizedFunction,
-    SuiMoveNormalizedModule, SuiMoveNormalizedStruct, SuiObjectDataOptions, SuiObjectInfo,
-    SuiObjectResponse, SuiPastObjectResponse, SuiTransactionResponse, TransactionsPage,
+    Checkpoint, CheckpointId, CheckpointPage, DynamicFieldPage, MoveFunctionArgType, NodeInfo,
+    OwnedObjectsCursor, OwnedObjectsPage, ProtocolConfigResponse, SuiCheckpointData,
+    SuiGetPastObjectRequest, SuiMoveNormalizedFunction, SuiMoveNormalizedModule,
+    SuiMoveNormalizedStruct, SuiObjectDataOptions, SuiObjectHistoryEntry, SuiObjectInfo,
+    SuiObjectResponse, SuiPastObjectResponse, SuiTransactionInclusionProof, SuiTransactionResponse,
+    SuiTransactionResponseOptions, SuiTransactionResult, TransactionsPage,
 };
 use sui_open_rpc_macros::open_rpc;
 use sui_types::base_types::{
@@ -28,6 +31,22 @@ pub trait ReadApi {
         address: SuiAddress,
     ) -> RpcResult<Vec<SuiObjectInfo>>;
 
+    /// Return a page of the objects owned by an address. The pages following the first one list
+    /// the objects as they were at the end of the checkpoint the first page was read at, so that
+    /// objects changing hands in between are neither listed twice nor missed.
+    #[method(name = "getOwnedObjects")]
+    async fn get_owned_objects(
+        &self,
+        /// the owner's Sui address
+        address: SuiAddress,
+        /// Optional paging cursor, returned by the previous page
+        cursor: Option<OwnedObjectsCursor>,
+        /// Maximum item returned per page, default to [QUERY_MAX_RESULT_LIMIT] if not specified.
+        limit: Option<usize>,
+        /// optional type of the objects to return (e.g., 0x2::coin::Coin<0x2::sui::SUI>), all the instantiations of the type if it has no type parameters (e.g., 0x2::coin::Coin).
+        object_type: Option<String>,
+    ) -> RpcResult<OwnedObjectsPage>;
+
     /// Return the list of dynamic field objects owned by an object.
     #[method(name = "getDynamicFields")]
     async fn get_dynamic_fields(
@@ -145,6 +164,18 @@ pub trait ReadApi {
         digests: Vec<TransactionDigest>,
     ) -> RpcResult<Vec<SuiTransactionResponse>>;
 
+    /// Return the requested parts of each of the given transactions. A transaction that can not
+    /// be read, e.g. as it is not known to the node, gets an error in place of its parts rather
+    /// than failing the whole batch.
+    #[method(name = "multiGetTransactionsWithOptions")]
+    async fn multi_get_transactions_with_options(
+        &self,
+        /// the digests of the queried transactions, at most [QUERY_MAX_RESULT_LIMIT]
+        digests: Vec<TransactionDigest>,
+        /// options for specifying the parts of the transactions to return, only the digests by default
+        options: Option<SuiTransactionResponseOptions>,
+    ) -> RpcResult<Vec<SuiTransactionResult>>;
+
     /// Note there is no software-level guarantee/SLA that objects with past versions
     /// can be retrieved by this API, even if the object and version exists/existed.
     /// The result may vary across nodes depending on their pruning policies.
@@ -160,6 +191,37 @@ pub trait ReadApi {
         options: Option<SuiObjectDataOptions>,
     ) -> RpcResult<SuiPastObjectResponse>;
 
+    /// Note there is no software-level guarantee/SLA that objects with past versions
+    /// can be retrieved by this API, even if the object and version exists/existed.
+    /// The result may vary across nodes depending on their pruning policies.
+    /// Return the object information for each of the specified versions, in the same order
+    #[method(name = "tryMultiGetPastObjects")]
+    async fn try_multi_get_past_objects(
+        &self,
+        /// the objects and versions to query, at most [QUERY_MAX_RESULT_LIMIT]
+        past_objects: Vec<SuiGetPastObjectRequest>,
+        /// options for specifying the content to be returned
+        options: Option<SuiObjectDataOptions>,
+    ) -> RpcResult<Vec<SuiPastObjectResponse>>;
+
+    /// Return the versions of an object from `start_version` to `end_version`, both included,
+    /// oldest first. Versions at which the object was deleted or wrapped, and versions the node
+    /// has pruned, are listed with markers in place of the object. At most
+    /// [QUERY_MAX_RESULT_LIMIT] versions are returned; the rest of the range can be read by
+    /// starting from the version following the last one returned.
+    #[method(name = "tryGetPastObjectHistory")]
+    async fn try_get_past_object_history(
+        &self,
+        /// the ID of the queried object
+        object_id: ObjectID,
+        /// the first version of the range
+        start_version: SequenceNumber,
+        /// the last version of the range
+        end_version: SequenceNumber,
+        /// options for specifying the content to be returned
+        options: Option<SuiObjectDataOptions>,
+    ) -> RpcResult<Vec<SuiObjectHistoryEntry>>;
+
     /// Return the sequence number of the latest checkpoint that has been executed
     #[method(name = "getLatestCheckpointSequenceNumber")]
     async fn get_latest_checkpoint_sequence_number(&self) -> RpcResult<CheckpointSequenceNumber>;
@@ -171,4 +233,56 @@ pub trait ReadApi {
         /// Checkpoint identifier, can use either checkpoint digest, or checkpoint sequence number as input.
         id: CheckpointId,
     ) -> RpcResult<Checkpoint>;
+
+    /// Return a page of the checkpoints with sequence numbers in the range `[start, end]`, both
+    /// ends included.
+    #[method(name = "getCheckpoints")]
+    async fn get_checkpoints(
+        &self,
+        /// The first checkpoint of the range, default to the genesis checkpoint if not specified.
+        start: Option<CheckpointSequenceNumber>,
+        /// The last checkpoint of the range, default to the latest checkpoint if not specified.
+        end: Option<CheckpointSequenceNumber>,
+        /// Optional paging cursor, the sequence number of the first checkpoint of the page
+        cursor: Option<CheckpointSequenceNumber>,
+        /// Maximum item returned per page, default to [QUERY_MAX_RESULT_LIMIT] if not specified.
+        limit: Option<usize>,
+        /// query result ordering, default to false (ascending order), oldest record first.
+        descending_order: Option<bool>,
+        /// Whether to list the digests of the transactions of each checkpoint, default to true.
+        /// The list is left empty otherwise.
+        include_transactions: Option<bool>,
+    ) -> RpcResult<CheckpointPage>;
+
+    /// Return a proof that an executed transaction is in a certified checkpoint, which light
+    /// clients verify against the committee of the epoch of the checkpoint.
+    #[method(name = "getTransactionInclusionProof")]
+    async fn get_transaction_inclusion_proof(
+        &self,
+        /// the digest of the transaction
+        digest: TransactionDigest,
+    ) -> RpcResult<SuiTransactionInclusionProof>;
+
+    /// Subscribe to a stream of the data of executed checkpoints, each with its transactions,
+    /// their effects, events, and input and output objects, in order of sequence number.
+    #[subscription(name = "subscribeCheckpointData", item = SuiCheckpointData)]
+    fn subscribe_checkpoint_data(
+        &self,
+        /// The first checkpoint of the stream, default to the checkpoint after the latest executed
+        /// one if not specified. Checkpoints already executed are streamed before the new ones.
+        start: Option<CheckpointSequenceNumber>,
+    );
+
+    /// Return the version of the node and the protocol versions it supports
+    #[method(name = "getNodeInfo")]
+    async fn get_node_info(&self) -> RpcResult<NodeInfo>;
+
+    /// Return the protocol config of a protocol version the node supports: the constants limiting
+    /// and pricing transactions, and the feature flags.
+    #[method(name = "getProtocolConfig")]
+    async fn get_protocol_config(
+        &self,
+        /// optional protocol version, default to the protocol version of the current epoch if not specified.
+        version: Option<u64>,
+    ) -> RpcResult<ProtocolConfigResponse>;
 }
@@ -4,7 +4,10 @@
 use fastcrypto::encoding::Base64;
 use jsonrpsee::core::RpcResult;
 use jsonrpsee_proc_macros::rpc;
-use sui_json_rpc_types::{DevInspectResults, DryRunTransactionResponse, SuiTransactionResponse};
+use sui_json_rpc_types::{
+    DevInspectArgs, DevInspectResults, DryRunTransactionResponse, GasEstimate,
+    SuiTransactionResponse,
+};
 
 use sui_open_rpc_macros::open_rpc;
 use sui_types::base_types::{EpochId, SuiAddress};
@@ -46,6 +49,11 @@ pub trait WriteApi {
     ) -> RpcResult<SuiTransactionResponse>;
 
     // TODO: migrate above two rpc calls to this one eventually.
+    /// Execute the transaction and wait for results if desired, as with executeTransaction.
+    /// With WaitForLocalExecution and wait_for_checkpoint, the response also waits a bounded
+    /// time for the checkpoint including the transaction. The checkpoint is given as the
+    /// consistency token of the response, which clients can pass in the `sui-consistency-token`
+    /// header of subsequent reads so that any node answering them has executed the transaction.
     #[method(name = "submitTransaction")]
     async fn submit_transaction(
         &self,
@@ -55,6 +63,8 @@ pub trait WriteApi {
         signatures: Vec<Base64>,
         /// The request type
         request_type: ExecuteTransactionRequestType,
+        /// Whether to wait for the checkpoint including the transaction, with WaitForLocalExecution. Defaults to false.
+        wait_for_checkpoint: Option<bool>,
     ) -> RpcResult<SuiTransactionResponse>;
 
     /// Runs the transaction in dev-inspect mode. Which allows for nearly any
@@ -70,10 +80,26 @@ pub trait WriteApi {
         gas_price: Option<u64>,
         /// The epoch to perform the call. Will be set from the system state object if not provided
         epoch: Option<EpochId>,
+        /// Overrides the gas budget, skips checks, and asks for raw BCS results or a gas profile
+        additional_args: Option<DevInspectArgs>,
     ) -> RpcResult<DevInspectResults>;
 
     /// Return transaction execution effects including the gas cost summary,
-    /// while the effects are not committed to the chain.
+    /// while the effects are not committed to the chain. If the transaction has no gas payment,
+    /// gas coins of the gas owner that cover the budget are used, or a synthetic coin if the
+    /// owner does not have enough.
     #[method(name = "dryRunTransaction")]
     async fn dry_run_transaction(&self, tx_bytes: Base64) -> RpcResult<DryRunTransactionResponse>;
+
+    /// Return a gas budget and gas price to set on the transaction, from the gas it used in a dry
+    /// run plus a safety margin. Errors if the transaction fails in the dry run.
+    #[method(name = "estimateGas")]
+    async fn estimate_gas(
+        &self,
+        sender_address: SuiAddress,
+        /// BCS encoded TransactionKind(as opposed to TransactionData, which include gasBudget and gasPrice)
+        tx_bytes: Base64,
+        /// The gas price to estimate at. Default to use reference gas price
+        gas_price: Option<u64>,
+    ) -> RpcResult<GasEstimate>;
 }
@@ -0,0 +1,168 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::routing_layer::response;
+use hyper::header::{AUTHORIZATION, ORIGIN};
+use hyper::{Body, Request, Response};
+use std::error::Error;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use subtle::ConstantTimeEq;
+use tower::{Layer, Service};
+
+/// Turns away requests not carrying `Authorization: Bearer <token>` with the configured token,
+/// and, if configured, requests made by browsers on behalf of web pages.
+#[derive(Clone)]
+pub struct AuthLayer {
+    expected: Option<Arc<String>>,
+    deny_browsers: bool,
+}
+
+impl AuthLayer {
+    /// Requests are let through unchecked when `auth_token` is `None`. Requests carrying an
+    /// `Origin` header, which browsers add to those of web pages, are turned away if
+    /// `deny_browsers`.
+    pub fn new(auth_token: Option<String>, deny_browsers: bool) -> Self {
+        Self {
+            expected: auth_token.map(|token| Arc::new(format!("Bearer {token}"))),
+            deny_browsers,
+        }
+    }
+}
+
+impl<S> Layer<S> for AuthLayer {
+    type Service = AuthService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        AuthService {
+            inner,
+            expected: self.expected.clone(),
+            deny_browsers: self.deny_browsers,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct AuthService<S> {
+    inner: S,
+    expected: Option<Arc<String>>,
+    deny_browsers: bool,
+}
+
+impl<S> Service<Request<Body>> for AuthService<S>
+where
+    S: Service<Request<Body>, Response = Response<Body>> + Clone + Send + 'static,
+    S::Response: 'static,
+    S::Error: Into<Box<dyn Error + Send + Sync>> + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = Box<dyn Error + Send + Sync + 'static>;
+    type Future =
+        Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send + 'static>>;
+
+    #[inline]
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx).map_err(Into::into)
+    }
+
+    fn call(&mut self, req: Request<Body>) -> Self::Future {
+        let clone = self.inner.clone();
+        // take the service that was ready
+        // https://docs.rs/tower/latest/tower/trait.Service.html#be-careful-when-cloning-inner-services
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        if self.deny_browsers && req.headers().contains_key(ORIGIN) {
+            return Box::pin(async { Ok(response::forbidden_origin()) });
+        }
+        if let Some(expected) = &self.expected {
+            let authorized = req
+                .headers()
+                .get(AUTHORIZATION)
+                // Compared in constant time, so that the time taken tells nothing of the token.
+                .map_or(false, |value| {
+                    value.as_bytes().ct_eq(expected.as_bytes()).into()
+                });
+            if !authorized {
+                return Box::pin(async { Ok(response::unauthorized()) });
+            }
+        }
+        Box::pin(async move { inner.call(req).await.map_err(|err| err.into()) })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hyper::StatusCode;
+    use std::convert::Infallible;
+    use tower::ServiceExt;
+
+    async fn status(layer: &AuthLayer, headers: &[(&str, &str)]) -> StatusCode {
+        let service = layer.layer(tower::service_fn(|_: Request<Body>| async {
+            Ok::<_, Infallible>(Response::new(Body::empty()))
+        }));
+        let mut req = Request::builder();
+        for (name, value) in headers {
+            req = req.header(*name, *value);
+        }
+        service
+            .oneshot(req.body(Body::empty()).unwrap())
+            .await
+            .unwrap()
+            .status()
+    }
+
+    #[tokio::test]
+    async fn test_auth_token() {
+        let layer = AuthLayer::new(Some("test-token".to_string()), false);
+        assert_eq!(status(&layer, &[]).await, StatusCode::UNAUTHORIZED);
+        assert_eq!(
+            status(&layer, &[("authorization", "Bearer other-token")]).await,
+            StatusCode::UNAUTHORIZED
+        );
+        assert_eq!(
+            status(&layer, &[("authorization", "Bearer test-token-suffix")]).await,
+            StatusCode::UNAUTHORIZED
+        );
+        assert_eq!(
+            status(&layer, &[("authorization", "test-token")]).await,
+            StatusCode::UNAUTHORIZED
+        );
+        assert_eq!(
+            status(&layer, &[("authorization", "Bearer test-token")]).await,
+            StatusCode::OK
+        );
+    }
+
+    #[tokio::test]
+    async fn test_no_auth_token() {
+        let layer = AuthLayer::new(None, false);
+        assert_eq!(status(&layer, &[]).await, StatusCode::OK);
+        assert_eq!(
+            status(&layer, &[("origin", "https://example.com")]).await,
+            StatusCode::OK
+        );
+    }
+
+    #[tokio::test]
+    async fn test_deny_browsers() {
+        let layer = AuthLayer::new(Some("test-token".to_string()), true);
+        assert_eq!(
+            status(
+                &layer,
+                &[
+                    ("authorization", "Bearer test-token"),
+                    ("origin", "https://example.com")
+                ]
+            )
+            .await,
+            StatusCode::FORBIDDEN
+        );
+        assert_eq!(
+            status(&layer, &[("authorization", "Bearer test-token")]).await,
+            StatusCode::OK
+        );
+    }
+}
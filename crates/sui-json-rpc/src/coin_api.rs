@@ -1,6 +1,7 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use std::cmp::Reverse;
 use std::collections::HashMap;
 use std::sync::Arc;
 
@@ -8,24 +9,24 @@ use anyhow::anyhow;
 use async_trait::async_trait;
 use jsonrpsee::core::RpcResult;
 use jsonrpsee::RpcModule;
-use move_core_types::language_storage::{StructTag, TypeTag};
+use move_core_types::language_storage::StructTag;
 use tracing::debug;
 
 use sui_core::authority::AuthorityState;
-use sui_json_rpc_types::{Balance, Coin as SuiCoin};
-use sui_json_rpc_types::{CoinPage, SuiCoinMetadata};
+use sui_json_rpc_types::{Balance, BalanceChangePage, Coin as SuiCoin};
+use sui_json_rpc_types::{CoinBalanceCursor, CoinPage, CoinsByBalancePage, SuiCoinMetadata};
 use sui_open_rpc::Module;
 use sui_types::balance::Supply;
-use sui_types::base_types::{ObjectID, ObjectType, SuiAddress};
+use sui_types::base_types::{ObjectID, SuiAddress, TransactionDigest};
 use sui_types::coin::{Coin, CoinMetadata, LockedCoin, TreasuryCap};
 use sui_types::error::SuiError;
 use sui_types::event::Event;
-use sui_types::gas_coin::GAS;
+use sui_types::gas_coin::{GAS, TOTAL_SUPPLY_MIST};
 use sui_types::messages::{TransactionEffectsAPI, TransactionEvents};
 use sui_types::object::Object;
 use sui_types::parse_sui_struct_tag;
 
-use crate::api::{cap_page_limit, CoinReadApiServer};
+use crate::api::{cap_page_limit, cap_page_size, CoinReadApiServer};
 use crate::error::Error;
 use crate::SuiRpcModule;
 
@@ -103,7 +104,12 @@ impl CoinReadApi {
         for coin in coins {
             data.push(self.get_coin(&coin).await?)
         }
-        Ok(CoinPage { data, next_cursor })
+        let next_cursor = cap_page_size(&mut data, next_cursor, |coin| coin.coin_object_id);
+        Ok(CoinPage {
+            data,
+            has_next_page: next_cursor.is_some(),
+            next_cursor,
+        })
     }
 
     fn get_owner_coin_iterator<'a>(
@@ -111,11 +117,69 @@ impl CoinReadApi {
         owner: SuiAddress,
         coin_type: &'a Option<StructTag>,
     ) -> Result<impl Iterator<Item = ObjectID> + '_, Error> {
+        // Without a coin type, the coin types without type parameters match coins of any type.
+        let [coins, locked_coins] = [Coin::type_ as fn(StructTag) -> StructTag, LockedCoin::type_]
+            .map(|type_| match coin_type {
+                Some(coin_type) => type_(coin_type.clone()),
+                None => StructTag {
+                    type_params: vec![],
+                    ..type_(GAS::type_())
+                },
+            });
         Ok(self
             .state
-            .get_owner_objects_iterator(owner)?
-            .filter(move |o| matches!(&o.type_, ObjectType::Struct(type_) if is_coin_type(type_, coin_type)))
-            .map(|info|info.object_id))
+            .get_owner_objects_of_type_iterator(owner, &coins)?
+            .chain(
+                self.state
+                    .get_owner_objects_of_type_iterator(owner, &locked_coins)?,
+            )
+            .map(|info| info.object_id))
+    }
+
+    /// [`CoinReadApiServer::get_coins_by_balance`] on a database whose coin balance index is
+    /// not backfilled yet, which reads all the coins of the owner for every page.
+    async fn scan_coins_by_balance(
+        &self,
+        owner: SuiAddress,
+        coin_type: StructTag,
+        min_balance: u64,
+        cursor: Option<CoinBalanceCursor>,
+        limit: usize,
+    ) -> Result<CoinsByBalancePage, Error> {
+        let coin_type = Some(coin_type);
+        let mut coins = vec![];
+        for coin in self.get_owner_coin_iterator(owner, &coin_type)? {
+            let coin = self.get_coin(&coin).await?;
+            if coin.locked_until_epoch.is_none() && coin.balance >= min_balance {
+                coins.push(coin);
+            }
+        }
+        let total_count = coins.len();
+        let total_balance = coins.iter().map(|coin| coin.balance as u128).sum();
+
+        let sort_key = |balance: u64, coin_object_id: ObjectID| (Reverse(balance), coin_object_id);
+        coins.sort_by_key(|coin| sort_key(coin.balance, coin.coin_object_id));
+        if let Some(cursor) = cursor {
+            let start = sort_key(cursor.balance, cursor.coin_object_id);
+            coins.retain(|coin| sort_key(coin.balance, coin.coin_object_id) >= start);
+        }
+        let next_cursor = coins.get(limit).map(|coin| CoinBalanceCursor {
+            balance: coin.balance,
+            coin_object_id: coin.coin_object_id,
+        });
+        coins.truncate(limit);
+        let next_cursor = cap_page_size(&mut coins, next_cursor, |coin| CoinBalanceCursor {
+            balance: coin.balance,
+            coin_object_id: coin.coin_object_id,
+        });
+
+        Ok(CoinsByBalancePage {
+            data: coins,
+            has_next_page: next_cursor.is_some(),
+            next_cursor,
+            total_count,
+            total_balance,
+        })
     }
 
     async fn find_package_object(
@@ -185,6 +249,75 @@ impl CoinReadApiServer for CoinReadApi {
             .await?)
     }
 
+    async fn get_coins_by_balance(
+        &self,
+        owner: SuiAddress,
+        coin_type: Option<String>,
+        min_balance: Option<u64>,
+        cursor: Option<CoinBalanceCursor>,
+        limit: Option<usize>,
+    ) -> RpcResult<CoinsByBalancePage> {
+        let coin_type = match coin_type {
+            Some(c) => parse_sui_struct_tag(&c)?,
+            None => GAS::type_(),
+        };
+        let min_balance = min_balance.unwrap_or_default();
+        let limit = cap_page_limit(limit);
+        let coin_object_type = Coin::type_(coin_type.clone());
+
+        // The totals only read the keys of the index, and the page seeks to the cursor in it.
+        let totals = self
+            .state
+            .get_coins_by_balance_iterator(owner, &coin_object_type, min_balance, None)
+            .map_err(Error::from)?
+            .map(|coins| {
+                coins.fold((0, 0), |(count, sum), (balance, _)| {
+                    (count + 1, sum + balance as u128)
+                })
+            });
+        let Some((total_count, total_balance)) = totals else {
+            return Ok(self
+                .scan_coins_by_balance(owner, coin_type, min_balance, cursor, limit)
+                .await?);
+        };
+        let mut page: Vec<_> = self
+            .state
+            .get_coins_by_balance_iterator(
+                owner,
+                &coin_object_type,
+                min_balance,
+                cursor.map(|cursor| (cursor.balance, cursor.coin_object_id)),
+            )
+            .map_err(Error::from)?
+            .into_iter()
+            .flatten()
+            .take(limit + 1)
+            .collect();
+        let next_cursor = page
+            .get(limit)
+            .map(|(balance, coin_object_id)| CoinBalanceCursor {
+                balance: *balance,
+                coin_object_id: *coin_object_id,
+            });
+        page.truncate(limit);
+        let mut coins = vec![];
+        for (_, coin_object_id) in page {
+            coins.push(self.get_coin(&coin_object_id).await?);
+        }
+        let next_cursor = cap_page_size(&mut coins, next_cursor, |coin| CoinBalanceCursor {
+            balance: coin.balance,
+            coin_object_id: coin.coin_object_id,
+        });
+
+        Ok(CoinsByBalancePage {
+            data: coins,
+            has_next_page: next_cursor.is_some(),
+            next_cursor,
+            total_count,
+            total_balance,
+        })
+    }
+
     async fn get_all_coins(
         &self,
         owner: SuiAddress,
@@ -206,26 +339,11 @@ impl CoinReadApiServer for CoinReadApi {
 
         // TODO: Add index to improve performance?
         let coins = self.get_owner_coin_iterator(owner, &coin_type)?;
-        let mut total_balance = 0u128;
-        let mut locked_balance = HashMap::new();
-        let mut coin_object_count = 0;
-
+        let mut balance = Balance::new(coin_type.unwrap().to_string());
         for coin in coins {
-            let coin = self.get_coin(&coin).await?;
-            if let Some(lock) = coin.locked_until_epoch {
-                *locked_balance.entry(lock).or_default() += coin.balance as u128
-            } else {
-                total_balance += coin.balance as u128;
-            }
-            coin_object_count += 1;
+            balance.add_coin(self.get_coin(&coin).await?);
         }
-
-        Ok(Balance {
-            coin_type: coin_type.unwrap().to_string(),
-            coin_object_count,
-            total_balance,
-            locked_balance,
-        })
+        Ok(balance)
     }
 
     async fn get_all_balances(&self, owner: SuiAddress) -> RpcResult<Vec<Balance>> {
@@ -235,21 +353,43 @@ impl CoinReadApiServer for CoinReadApi {
 
         for coin in coins {
             let coin = self.get_coin(&coin).await?;
-            let balance = balances.entry(coin.coin_type.clone()).or_insert(Balance {
-                coin_type: coin.coin_type,
-                coin_object_count: 0,
-                total_balance: 0,
-                locked_balance: Default::default(),
-            });
-            if let Some(lock) = coin.locked_until_epoch {
-                *balance.locked_balance.entry(lock).or_default() += coin.balance as u128
-            } else {
-                balance.total_balance += coin.balance as u128;
-            }
-            balance.coin_object_count += 1;
+            balances
+                .entry(coin.coin_type.clone())
+                .or_insert_with(|| Balance::new(coin.coin_type.clone()))
+                .add_coin(coin);
         }
 
-        Ok(balances.into_values().collect())
+        let mut balances: Vec<_> = balances.into_values().collect();
+        balances.sort_by(|a, b| a.coin_type.cmp(&b.coin_type));
+        Ok(balances)
+    }
+
+    async fn get_balance_changes(
+        &self,
+        owner: SuiAddress,
+        coin_type: Option<String>,
+        cursor: Option<TransactionDigest>,
+        limit: Option<usize>,
+        descending_order: Option<bool>,
+    ) -> RpcResult<BalanceChangePage> {
+        let descending = descending_order.unwrap_or_default();
+        let limit = cap_page_limit(limit);
+        // Retrieve 1 extra transaction for next cursor
+        let mut changes = self
+            .state
+            .get_coin_balance_changes(owner, coin_type, cursor, limit + 1, descending)
+            .await?;
+        let next_cursor = changes.get(limit).map(|(digest, _)| *digest);
+        changes.truncate(limit);
+        let next_cursor = cap_page_size(&mut changes, next_cursor, |(digest, _)| *digest);
+        Ok(BalanceChangePage {
+            data: changes
+                .into_iter()
+                .flat_map(|(_, changes)| changes)
+                .collect(),
+            has_next_page: next_cursor.is_some(),
+            next_cursor,
+        })
     }
 
     async fn get_coin_metadata(&self, coin_type: String) -> RpcResult<SuiCoinMetadata> {
@@ -288,7 +428,10 @@ impl CoinReadApiServer for CoinReadApi {
         let coin_struct = parse_sui_struct_tag(&coin_type)?;
 
         Ok(if GAS::is_gas(&coin_struct) {
-            Supply { value: 0 }
+            // The supply of SUI is fixed at genesis, and its treasury cap is held by the system.
+            Supply {
+                value: TOTAL_SUPPLY_MIST,
+            }
         } else {
             let treasury_cap_object = self
                 .find_package_object(&coin_struct.address.into(), TreasuryCap::type_(coin_struct))
@@ -302,14 +445,3 @@ impl CoinReadApiServer for CoinReadApi {
         })
     }
 }
-
-fn is_coin_type(type_: &StructTag, coin_type: &Option<StructTag>) -> bool {
-    if Coin::is_coin(type_) || LockedCoin::is_locked_coin(type_) {
-        return if let Some(coin_type) = coin_type {
-            matches!(type_.type_params.first(), Some(TypeTag::Struct(type_)) if type_.to_canonical_string() == coin_type.to_canonical_string())
-        } else {
-            true
-        };
-    }
-    false
-}
@@ -0,0 +1,207 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::routing_layer::{is_json, read_request_body, response};
+use crate::CONSISTENCY_TOKEN_HEADER;
+use hyper::header::HeaderValue;
+use hyper::{Body, Method, Request, Response};
+use jsonrpsee::core::__reexports::serde_json;
+use jsonrpsee::types::Request as RpcRequest;
+use serde::Deserialize;
+use std::error::Error;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Duration;
+use sui_core::authority::AuthorityState;
+use sui_types::messages_checkpoint::CheckpointSequenceNumber;
+use tower::{Layer, Service};
+
+/// The methods executing transactions, whose tokens are the checkpoints of their transactions
+/// rather than the highest checkpoint executed by the node.
+const WRITE_METHODS: [&str; 3] = [
+    "sui_executeTransaction",
+    "sui_executeTransactionSerializedSig",
+    "sui_submitTransaction",
+];
+
+/// Tags responses with a consistency token, the sequence number of a checkpoint whose effects the
+/// response reflects, and holds back requests carrying a token until the node has executed the
+/// checkpoint, so that reads following a write see the write even when served by another node.
+#[derive(Clone)]
+pub struct ConsistencyLayer {
+    state: Option<Arc<AuthorityState>>,
+    max_wait: Duration,
+}
+
+impl ConsistencyLayer {
+    /// Tokens are neither given nor waited for when `state` is `None`.
+    pub fn new(state: Option<Arc<AuthorityState>>, max_wait: Duration) -> Self {
+        Self { state, max_wait }
+    }
+}
+
+impl<S> Layer<S> for ConsistencyLayer {
+    type Service = ConsistencyService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        ConsistencyService {
+            inner,
+            state: self.state.clone(),
+            max_wait: self.max_wait,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct ConsistencyService<S> {
+    inner: S,
+    state: Option<Arc<AuthorityState>>,
+    max_wait: Duration,
+}
+
+impl<S> Service<Request<Body>> for ConsistencyService<S>
+where
+    S: Service<Request<Body>, Response = Response<Body>> + Clone + Send + 'static,
+    S::Response: 'static,
+    S::Error: Into<Box<dyn Error + Send + Sync>> + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = Box<dyn Error + Send + Sync + 'static>;
+    type Future =
+        Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send + 'static>>;
+
+    #[inline]
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx).map_err(Into::into)
+    }
+
+    fn call(&mut self, req: Request<Body>) -> Self::Future {
+        let clone = self.inner.clone();
+        let state = self.state.clone();
+        let max_wait = self.max_wait;
+        // take the service that was ready
+        // https://docs.rs/tower/latest/tower/trait.Service.html#be-careful-when-cloning-inner-services
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        let res_fut = async move {
+            let state = match state {
+                Some(state) => state,
+                None => return inner.call(req).await.map_err(|err| err.into()),
+            };
+
+            if let Some(token) = req.headers().get(CONSISTENCY_TOKEN_HEADER) {
+                let token = match token.to_str().ok().and_then(|t| t.parse().ok()) {
+                    Some(token) => token,
+                    None => return Ok(response::invalid_consistency_token()),
+                };
+                if !wait_for_checkpoint(&state, token, max_wait).await {
+                    return Ok(response::behind_consistency_token(token));
+                }
+            }
+
+            let (req, writes) = if req.method() == Method::POST && is_json(&req) {
+                let (parts, body) = req.into_parts();
+                let (body, is_single) = match read_request_body(&parts.headers, body).await {
+                    Ok(r) => r,
+                    Err(response) => return Ok(response),
+                };
+                let writes = calls_write_method(&body, is_single);
+                (Request::from_parts(parts, Body::from(body)), writes)
+            } else {
+                (req, false)
+            };
+
+            let response = inner.call(req).await.map_err(|err| err.into())?;
+            if !writes {
+                let token = state.get_latest_checkpoint_sequence_number().ok();
+                return Ok(with_token(response, token));
+            }
+            // Responses are built whole by the server, so nothing is lost by buffering them here.
+            let (parts, body) = response.into_parts();
+            let body = hyper::body::to_bytes(body).await?;
+            let token = write_checkpoint(&body);
+            Ok(with_token(
+                Response::from_parts(parts, Body::from(body)),
+                token,
+            ))
+        };
+        Box::pin(res_fut)
+    }
+}
+
+/// Waits for the node to execute the checkpoint `token` for up to `max_wait`, and returns whether
+/// it did.
+async fn wait_for_checkpoint(
+    state: &AuthorityState,
+    token: CheckpointSequenceNumber,
+    max_wait: Duration,
+) -> bool {
+    let mut highest_executed = state.subscribe_latest_checkpoint_sequence_number();
+    let reached = async {
+        loop {
+            if matches!(*highest_executed.borrow_and_update(), Some(seq) if seq >= token) {
+                return true;
+            }
+            if highest_executed.changed().await.is_err() {
+                return false;
+            }
+        }
+    };
+    tokio::time::timeout(max_wait, reached)
+        .await
+        .unwrap_or(false)
+}
+
+fn with_token(
+    mut response: Response<Body>,
+    token: Option<CheckpointSequenceNumber>,
+) -> Response<Body> {
+    if let Some(token) = token {
+        response
+            .headers_mut()
+            .insert(CONSISTENCY_TOKEN_HEADER, HeaderValue::from(token));
+    }
+    response
+}
+
+/// Whether any call of the request executes a transaction.
+fn calls_write_method(body: &[u8], is_single: bool) -> bool {
+    let is_write = |request: RpcRequest| WRITE_METHODS.contains(&request.method.as_ref());
+    if is_single {
+        serde_json::from_slice::<RpcRequest>(body).map_or(false, is_write)
+    } else {
+        serde_json::from_slice::<Vec<RpcRequest>>(body)
+            .map_or(false, |requests| requests.into_iter().any(is_write))
+    }
+}
+
+#[derive(Deserialize)]
+struct WriteResponse {
+    result: Option<WriteResult>,
+}
+
+#[derive(Deserialize)]
+struct WriteResult {
+    checkpoint: Option<CheckpointSequenceNumber>,
+}
+
+/// The latest of the checkpoints of the transactions executed by the calls answered in `body`, or
+/// `None` if any of the transactions is not known to be in a checkpoint yet.
+fn write_checkpoint(body: &[u8]) -> Option<CheckpointSequenceNumber> {
+    let checkpoints = |responses: Vec<WriteResponse>| {
+        responses
+            .into_iter()
+            .filter_map(|response| response.result)
+            .map(|result| result.checkpoint)
+            .collect::<Option<Vec<_>>>()
+            .and_then(|checkpoints| checkpoints.into_iter().max())
+    };
+    match serde_json::from_slice::<WriteResponse>(body) {
+        Ok(response) => checkpoints(vec![response]),
+        Err(_) => serde_json::from_slice::<Vec<WriteResponse>>(body)
+            .ok()
+            .and_then(checkpoints),
+    }
+}
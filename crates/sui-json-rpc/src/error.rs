@@ -1,7 +1,7 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use hyper::header::InvalidHeaderValue;
+use hyper::header::{InvalidHeaderName, InvalidHeaderValue};
 use jsonrpsee::core::Error as RpcError;
 use jsonrpsee::types::error::CallError;
 use sui_types::error::SuiError;
@@ -26,6 +26,9 @@ pub enum Error {
 
     #[error(transparent)]
     InvalidHeaderValue(#[from] InvalidHeaderValue),
+
+    #[error(transparent)]
+    InvalidHeaderName(#[from] InvalidHeaderName),
 }
 
 impl From<Error> for RpcError {
@@ -1,7 +1,9 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use std::env;
 use std::fmt::Display;
+use std::str::FromStr;
 use std::sync::Arc;
 
 use async_trait::async_trait;
@@ -13,20 +15,25 @@ use jsonrpsee::types::SubscriptionResult;
 use jsonrpsee::{RpcModule, SubscriptionSink};
 use mysten_metrics::spawn_monitored_task;
 use serde::Serialize;
-use tracing::{debug, warn};
+use tap::TapFallible;
+use tracing::{debug, info, warn};
 
 use sui_core::authority::AuthorityState;
 use sui_core::event_handler::EventHandler;
-use sui_json_rpc_types::{EventPage, SuiEvent, SuiEventEnvelope, SuiEventFilter};
+use sui_core::streamer::SlowSubscriberPolicy;
+use sui_json_rpc_types::{
+    EventPage, SuiEvent, SuiEventEnvelope, SuiEventFilter, SuiTransactionEffects,
+};
 use sui_open_rpc::Module;
 use sui_types::event::{EventEnvelope, EventID};
+use sui_types::filter::{ExecutedTransaction, TransactionFilter};
 use sui_types::query::EventQuery;
 
-use crate::api::cap_page_limit;
 use crate::api::EventReadApiServer;
+use crate::api::{cap_page_limit, cap_page_size};
 use crate::SuiRpcModule;
 
-fn spawn_subscription<S, T, E>(mut sink: SubscriptionSink, rx: S)
+pub(crate) fn spawn_subscription<S, T, E>(mut sink: SubscriptionSink, rx: S)
 where
     S: TryStream<Ok = T, Error = E> + Unpin + Send + 'static,
     T: Serialize,
@@ -49,13 +56,26 @@ where
 pub struct EventReadApi {
     state: Arc<AuthorityState>,
     event_handler: Arc<EventHandler>,
+    slow_subscriber_policy: SlowSubscriberPolicy,
 }
 
 impl EventReadApi {
     pub fn new(state: Arc<AuthorityState>, event_handler: Arc<EventHandler>) -> Self {
+        // Subscribers falling behind lose the oldest items in their buffers by default, or have
+        // their subscriptions closed with `RPC_SLOW_SUBSCRIBER_POLICY=disconnect`.
+        let slow_subscriber_policy = env::var("RPC_SLOW_SUBSCRIBER_POLICY")
+            .ok()
+            .and_then(|o| {
+                SlowSubscriberPolicy::from_str(&o)
+                    .tap_err(|e| warn!("Cannot parse RPC_SLOW_SUBSCRIBER_POLICY: {e}"))
+                    .ok()
+            })
+            .unwrap_or_default();
+        info!(?slow_subscriber_policy, "Slow subscriber policy");
         Self {
             state,
             event_handler,
+            slow_subscriber_policy,
         }
     }
 }
@@ -85,8 +105,13 @@ impl EventReadApiServer for EventReadApi {
             .await?;
         let next_cursor = data.get(limit).map(|(id, _)| id.clone());
         data.truncate(limit);
-        let data = data.into_iter().map(|(_, event)| event).collect();
-        Ok(EventPage { data, next_cursor })
+        let mut data: Vec<SuiEventEnvelope> = data.into_iter().map(|(_, event)| event).collect();
+        let next_cursor = cap_page_size(&mut data, next_cursor, |event| event.id.clone());
+        Ok(EventPage {
+            data,
+            has_next_page: next_cursor.is_some(),
+            next_cursor,
+        })
     }
 
     fn subscribe_event(
@@ -104,7 +129,9 @@ impl EventReadApiServer for EventReadApi {
         };
 
         let state = self.state.clone();
-        let stream = self.event_handler.subscribe(filter);
+        let stream = self
+            .event_handler
+            .subscribe(filter, self.slow_subscriber_policy);
         let stream = stream.map(move |e: EventEnvelope| {
             let event = SuiEvent::try_from(
                 e.event,
@@ -128,6 +155,19 @@ impl EventReadApiServer for EventReadApi {
         spawn_subscription(sink, stream);
         Ok(())
     }
+
+    fn subscribe_transaction(
+        &self,
+        sink: SubscriptionSink,
+        filter: TransactionFilter,
+    ) -> SubscriptionResult {
+        let stream = self
+            .event_handler
+            .subscribe_transactions(filter, self.slow_subscriber_policy)
+            .map(|tx: ExecutedTransaction| SuiTransactionEffects::try_from(tx.effects));
+        spawn_subscription(sink, stream);
+        Ok(())
+    }
 }
 
 impl SuiRpcModule for EventReadApi {
@@ -5,6 +5,7 @@ use jsonrpsee::core::RpcResult;
 use std::collections::HashMap;
 use std::sync::Arc;
 use sui_json_rpc_types::{SuiCommittee, SuiSystemStateRpc};
+use sui_types::sui_system_state::gas_price_survey::SuiGasPriceSurvey;
 use sui_types::sui_system_state::sui_system_state_inner_v1::ValidatorMetadataV1;
 use sui_types::sui_system_state::sui_system_state_summary::SuiSystemStateSummary;
 
@@ -115,6 +116,16 @@ impl GovernanceReadApiServer for GovernanceReadApi {
             .map_err(Error::from)?
             .reference_gas_price())
     }
+
+    async fn get_gas_price_survey(&self) -> RpcResult<SuiGasPriceSurvey> {
+        Ok(self
+            .state
+            .database
+            .get_sui_system_state_object()
+            .map_err(Error::from)?
+            .into_sui_system_state_summary()
+            .gas_price_survey())
+    }
 }
 
 impl SuiRpcModule for GovernanceReadApi {
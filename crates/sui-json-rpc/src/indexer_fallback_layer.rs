@@ -0,0 +1,272 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::routing_layer::{is_json, read_request_body, response};
+use crate::{CLIENT_SDK_TYPE_HEADER, CONSISTENCY_TOKEN_HEADER};
+use hyper::client::HttpConnector;
+use hyper::header::{CONTENT_LENGTH, HOST};
+use hyper::{Body, Client, Method, Request, Response, Uri};
+use jsonrpsee::core::__reexports::serde_json;
+use jsonrpsee::types::Request as RpcRequest;
+use prometheus::{register_int_counter_vec_with_registry, IntCounterVec, Registry};
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Duration;
+use sui_config::node::IndexerFallbackConfig;
+use sui_open_rpc::ContentDescriptor;
+use tower::{Layer, Service};
+use tracing::warn;
+
+/// The SDK type the indexer sends its own requests to the fullnode with, which are never handed
+/// back to it.
+const INDEXER_SDK_TYPE: &str = "indexer";
+
+/// Hands requests calling only the configured methods over to an indexer, and serves them
+/// locally if the indexer fails to answer. Requests are served locally when no indexer is
+/// configured.
+///
+/// The indexer may lag behind the node, so requests with a consistency token are always served
+/// locally, and so are the next pages of clients that pass on the token of each response. Pages
+/// whose cursor was issued by the indexer are never served locally, since cursors of the indexer
+/// mean nothing to the node.
+#[derive(Clone)]
+pub struct IndexerFallbackLayer {
+    fallback: Option<Arc<IndexerFallback>>,
+}
+
+struct IndexerFallback {
+    client: Client<HttpConnector>,
+    url: Uri,
+    methods: HashSet<String>,
+    /// The position of the `cursor` param of each of `methods` that has one.
+    cursor_params: HashMap<String, usize>,
+    request_timeout: Duration,
+    requests: IntCounterVec,
+}
+
+impl IndexerFallbackLayer {
+    pub fn new(
+        config: Option<IndexerFallbackConfig>,
+        method_params: &HashMap<String, Vec<ContentDescriptor>>,
+        registry: &Registry,
+    ) -> Result<Self, crate::error::Error> {
+        let Some(config) = config else {
+            return Ok(Self { fallback: None });
+        };
+        let url = config
+            .url
+            .parse()
+            .map_err(|e| anyhow::anyhow!("Invalid indexer URL {}: {e}", config.url))?;
+        let cursor_params = config
+            .methods
+            .iter()
+            .filter_map(|method| {
+                let position = method_params
+                    .get(method)?
+                    .iter()
+                    .position(|param| param.name == "cursor")?;
+                Some((method.clone(), position))
+            })
+            .collect();
+        Ok(Self {
+            fallback: Some(Arc::new(IndexerFallback {
+                client: Client::new(),
+                url,
+                methods: config.methods.into_iter().collect(),
+                cursor_params,
+                request_timeout: Duration::from_millis(config.request_timeout_ms),
+                requests: register_int_counter_vec_with_registry!(
+                    "rpc_indexer_fallback_requests",
+                    "Number of requests handed over to the indexer, by whether it answered them",
+                    &["outcome"],
+                    registry,
+                )
+                .unwrap(),
+            })),
+        })
+    }
+}
+
+impl<S> Layer<S> for IndexerFallbackLayer {
+    type Service = IndexerFallbackService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        IndexerFallbackService {
+            inner,
+            fallback: self.fallback.clone(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct IndexerFallbackService<S> {
+    inner: S,
+    fallback: Option<Arc<IndexerFallback>>,
+}
+
+impl<S> Service<Request<Body>> for IndexerFallbackService<S>
+where
+    S: Service<Request<Body>, Response = Response<Body>> + Clone + Send + 'static,
+    S::Response: 'static,
+    S::Error: Into<Box<dyn Error + Send + Sync>> + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = Box<dyn Error + Send + Sync + 'static>;
+    type Future =
+        Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send + 'static>>;
+
+    #[inline]
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx).map_err(Into::into)
+    }
+
+    fn call(&mut self, req: Request<Body>) -> Self::Future {
+        let clone = self.inner.clone();
+        let fallback = self.fallback.clone();
+        // take the service that was ready
+        // https://docs.rs/tower/latest/tower/trait.Service.html#be-careful-when-cloning-inner-services
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        let res_fut = async move {
+            let fallback = match fallback {
+                // Requests from the indexer are answered here, or they would go round in circles.
+                Some(fallback) if !is_from_indexer(&req) && !has_consistency_token(&req) => {
+                    fallback
+                }
+                _ => return inner.call(req).await.map_err(|err| err.into()),
+            };
+            if !(req.method() == Method::POST && is_json(&req)) {
+                return inner.call(req).await.map_err(|err| err.into());
+            }
+            let (parts, body) = req.into_parts();
+            let (body, is_single) = match read_request_body(&parts.headers, body).await {
+                Ok(r) => r,
+                Err(response) => return Ok(response),
+            };
+            if calls_only(&body, is_single, &fallback.methods) {
+                match fallback.forward(&parts.headers, body.clone()).await {
+                    Ok(response) => {
+                        fallback.requests.with_label_values(&["answered"]).inc();
+                        return Ok(response);
+                    }
+                    Err(e) => {
+                        fallback.requests.with_label_values(&["failed"]).inc();
+                        if calls_with_cursor(&body, is_single, &fallback.cursor_params) {
+                            warn!("Indexer failed to answer request for a page: {e}");
+                            return Ok(response::indexer_unavailable());
+                        }
+                        warn!("Indexer failed to answer request, serving it locally: {e}");
+                    }
+                }
+            }
+            let req = Request::from_parts(parts, Body::from(body));
+            inner.call(req).await.map_err(|err| err.into())
+        };
+        Box::pin(res_fut)
+    }
+}
+
+impl IndexerFallback {
+    async fn forward(
+        &self,
+        headers: &hyper::HeaderMap,
+        body: Vec<u8>,
+    ) -> Result<Response<Body>, anyhow::Error> {
+        let mut request = Request::post(self.url.clone()).body(Body::from(body))?;
+        for (name, value) in headers {
+            if name != HOST && name != CONTENT_LENGTH {
+                request.headers_mut().append(name, value.clone());
+            }
+        }
+        let response = tokio::time::timeout(self.request_timeout, self.client.request(request))
+            .await
+            .map_err(|_| anyhow::anyhow!("timed out after {:?}", self.request_timeout))??;
+        if !response.status().is_success() {
+            return Err(anyhow::anyhow!("answered with {}", response.status()));
+        }
+        Ok(response)
+    }
+}
+
+fn is_from_indexer(req: &Request<Body>) -> bool {
+    req.headers()
+        .get(CLIENT_SDK_TYPE_HEADER)
+        .map_or(false, |sdk_type| sdk_type == INDEXER_SDK_TYPE)
+}
+
+fn has_consistency_token(req: &Request<Body>) -> bool {
+    req.headers().contains_key(CONSISTENCY_TOKEN_HEADER)
+}
+
+/// Whether any call of the request passes a cursor, by position or by name, to a method with a
+/// `cursor` param at the position given in `cursor_params`.
+fn calls_with_cursor(body: &[u8], is_single: bool, cursor_params: &HashMap<String, usize>) -> bool {
+    let has_cursor = |request: RpcRequest| {
+        let Some(position) = cursor_params.get(request.method.as_ref()) else {
+            return false;
+        };
+        let params = request
+            .params
+            .and_then(|params| serde_json::from_str::<serde_json::Value>(params.get()).ok());
+        let cursor = match &params {
+            Some(serde_json::Value::Array(params)) => params.get(*position),
+            Some(serde_json::Value::Object(params)) => params.get("cursor"),
+            _ => None,
+        };
+        cursor.map_or(false, |cursor| !cursor.is_null())
+    };
+    if is_single {
+        serde_json::from_slice::<RpcRequest>(body).map_or(false, has_cursor)
+    } else {
+        serde_json::from_slice::<Vec<RpcRequest>>(body)
+            .map_or(false, |requests| requests.into_iter().any(has_cursor))
+    }
+}
+
+/// Whether the request has calls, and all of them are to `methods`.
+fn calls_only(body: &[u8], is_single: bool, methods: &HashSet<String>) -> bool {
+    let is_handed_over = |request: RpcRequest| methods.contains(request.method.as_ref());
+    if is_single {
+        serde_json::from_slice::<RpcRequest>(body).map_or(false, is_handed_over)
+    } else {
+        serde_json::from_slice::<Vec<RpcRequest>>(body).map_or(false, |requests| {
+            !requests.is_empty() && requests.into_iter().all(is_handed_over)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_calls_with_cursor() {
+        let cursor_params = HashMap::from([("sui_getEvents".to_string(), 1)]);
+        let calls_with_cursor =
+            |body: &str, is_single| calls_with_cursor(body.as_bytes(), is_single, &cursor_params);
+
+        let first_page = r#"{"jsonrpc":"2.0","id":1,"method":"sui_getEvents","params":["All"]}"#;
+        assert!(!calls_with_cursor(first_page, true));
+        let first_page =
+            r#"{"jsonrpc":"2.0","id":1,"method":"sui_getEvents","params":["All",null,10]}"#;
+        assert!(!calls_with_cursor(first_page, true));
+        let next_page =
+            r#"{"jsonrpc":"2.0","id":1,"method":"sui_getEvents","params":["All",{"txSeq":1}]}"#;
+        assert!(calls_with_cursor(next_page, true));
+        let next_page = r#"{"jsonrpc":"2.0","id":1,"method":"sui_getEvents","params":{"query":"All","cursor":{"txSeq":1}}}"#;
+        assert!(calls_with_cursor(next_page, true));
+
+        // Params of other methods at the same position are not cursors.
+        let other = r#"{"jsonrpc":"2.0","id":1,"method":"sui_getObject","params":["0x5","0x6"]}"#;
+        assert!(!calls_with_cursor(other, true));
+        assert!(calls_with_cursor(&format!("[{other},{next_page}]"), false));
+        assert!(!calls_with_cursor(
+            &format!("[{other},{first_page}]"),
+            false
+        ));
+    }
+}
@@ -2,9 +2,12 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use std::env;
-use std::net::SocketAddr;
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr};
 use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
 
+use anyhow::anyhow;
 use hyper::header::HeaderName;
 use hyper::header::HeaderValue;
 use hyper::Method;
@@ -12,23 +15,49 @@ pub use jsonrpsee::server::ServerHandle;
 use jsonrpsee::server::{AllowHosts, ServerBuilder};
 use jsonrpsee::RpcModule;
 use prometheus::Registry;
+use socket2::{Socket, TcpKeepalive};
 use tap::TapFallible;
+use tokio::net::TcpStream;
+use tokio::sync::watch;
+use tower_http::compression::CompressionLayer;
 use tower_http::cors::{AllowOrigin, CorsLayer};
 use tracing::{info, warn};
 
 use crate::error::Error;
+use sui_config::node::{IndexerFallbackConfig, RpcRateLimitConfig, RpcServerConfig};
+use sui_core::authority::AuthorityState;
 use sui_open_rpc::{Module, Project};
 
+use crate::api::MAX_RESPONSE_SIZE;
+use crate::auth_layer::AuthLayer;
+use crate::consistency_layer::ConsistencyLayer;
+use crate::indexer_fallback_layer::IndexerFallbackLayer;
 use crate::metrics::MetricsLogger;
+use crate::params_validation_layer::ParamsValidationLayer;
+use crate::peer_address_layer::{PeerAddressLayer, PeerAddressLogger};
+use crate::query_metrics_layer::QueryMetricsLayer;
+pub use crate::rate_limit_layer::RateLimitHandle;
+use crate::rate_limit_layer::RateLimitLayer;
+use crate::request_size_layer::RequestSizeLayer;
 use crate::routing_layer::RoutingLayer;
 
+pub mod admin_api;
 pub mod api;
+mod auth_layer;
 pub mod coin_api;
+mod consistency_layer;
 pub mod error;
 pub mod event_api;
 pub mod governance_api;
+mod indexer_fallback_layer;
 mod metrics;
+pub mod name_service_api;
+mod params_validation_layer;
+mod peer_address_layer;
+mod query_metrics_layer;
+mod rate_limit_layer;
 pub mod read_api;
+mod request_size_layer;
 mod routing_layer;
 pub mod threshold_bls_api;
 pub mod transaction_builder_api;
@@ -41,8 +70,21 @@ pub const CLIENT_SDK_VERSION_HEADER: &str = "client-sdk-version";
 /// API version.
 pub const CLIENT_TARGET_API_VERSION_HEADER: &str = "client-target-api-version";
 pub const APP_NAME_HEADER: &str = "app-name";
+/// The checkpoint a response reflects, given with each response. Requests carrying one are held
+/// back until the node has executed that checkpoint, so that they see everything the response did.
+pub const CONSISTENCY_TOKEN_HEADER: &str = "sui-consistency-token";
 
 pub const MAX_REQUEST_SIZE: u32 = 2 << 30;
+/// How long a request waits for the node to reach its consistency token by default.
+const DEFAULT_CONSISTENCY_MAX_WAIT: Duration = Duration::from_secs(5);
+/// How many subscriptions each WebSocket connection can have open by default.
+const DEFAULT_MAX_SUBSCRIPTIONS_PER_CONNECTION: u32 = 1024;
+/// How often the server pings WebSocket clients by default, to keep idle connections open through
+/// proxies and to notice clients that went away.
+const DEFAULT_WS_PING_INTERVAL: Duration = Duration::from_secs(30);
+
+/// How long the server waits, as it starts, for a connection of its own to be accepted.
+const PEER_ADDRESS_CHECK_TIMEOUT: Duration = Duration::from_secs(10);
 
 #[cfg(test)]
 #[path = "unit_tests/rpc_server_tests.rs"]
@@ -52,6 +94,12 @@ pub struct JsonRpcServerBuilder {
     module: RpcModule<()>,
     rpc_doc: Project,
     registry: Registry,
+    rate_limits: RateLimitHandle,
+    server_config: RpcServerConfig,
+    indexer_fallback_config: Option<IndexerFallbackConfig>,
+    state: Option<Arc<AuthorityState>>,
+    auth_token: Option<String>,
+    deny_browsers: bool,
 }
 
 pub fn sui_rpc_doc(version: &str) -> Project {
@@ -73,42 +121,118 @@ impl JsonRpcServerBuilder {
             module: RpcModule::new(()),
             rpc_doc: sui_rpc_doc(version),
             registry: prometheus_registry.clone(),
+            rate_limits: RateLimitHandle::default(),
+            server_config: RpcServerConfig::default(),
+            indexer_fallback_config: None,
+            state: None,
+            auth_token: None,
+            deny_browsers: false,
         }
     }
 
+    /// Limits the requests each client can make, which are otherwise unlimited.
+    pub fn set_rate_limit_config(&mut self, config: RpcRateLimitConfig) {
+        self.rate_limits.set(Some(config));
+    }
+
+    /// Limits the requests each client can make by `rate_limits`, which can be changed while the
+    /// server runs. The API key header of the limits the server starts with stays the one browsers
+    /// are allowed to send.
+    pub fn set_rate_limits(&mut self, rate_limits: RateLimitHandle) {
+        self.rate_limits = rate_limits;
+    }
+
+    /// Sets the allowed origins, compression, request size limit and TCP keepalive of the server.
+    pub fn set_server_config(&mut self, config: RpcServerConfig) {
+        self.server_config = config;
+    }
+
+    /// Hands the calls of the configured methods over to an indexer, which are otherwise served
+    /// locally.
+    pub fn set_indexer_fallback_config(&mut self, config: IndexerFallbackConfig) {
+        self.indexer_fallback_config = Some(config);
+    }
+
+    /// Tags responses with consistency tokens from the checkpoints executed by `state`, and holds
+    /// back requests carrying a token until `state` has executed its checkpoint.
+    pub fn set_consistency_state(&mut self, state: Arc<AuthorityState>) {
+        self.state = Some(state);
+    }
+
+    /// Requires requests to carry `Authorization: Bearer <auth_token>`, which are otherwise served
+    /// to anyone who can reach the server.
+    pub fn set_auth_token(&mut self, auth_token: String) {
+        self.auth_token = Some(auth_token);
+    }
+
+    /// Turns away requests made by browsers on behalf of web pages, whatever their origin, and
+    /// gives no CORS headers, for servers only operators are meant to call.
+    pub fn deny_browsers(&mut self) {
+        self.deny_browsers = true;
+    }
+
     pub fn register_module<T: SuiRpcModule>(&mut self, module: T) -> Result<(), Error> {
         self.rpc_doc.add_module(T::rpc_doc_module());
         Ok(self.module.merge(module.rpc())?)
     }
 
     pub async fn start(mut self, listen_address: SocketAddr) -> Result<ServerHandle, Error> {
-        let acl = match env::var("ACCESS_CONTROL_ALLOW_ORIGIN") {
-            Ok(value) => {
-                let allow_hosts = value
-                    .split(',')
-                    .into_iter()
-                    .map(HeaderValue::from_str)
-                    .collect::<Result<Vec<_>, _>>()?;
-                AllowOrigin::list(allow_hosts)
+        let server_config = self.server_config.clone();
+        let acl = if !server_config.allowed_origins.is_empty() {
+            let allow_hosts = server_config
+                .allowed_origins
+                .iter()
+                .map(|origin| HeaderValue::from_str(origin))
+                .collect::<Result<Vec<_>, _>>()?;
+            AllowOrigin::list(allow_hosts)
+        } else {
+            match env::var("ACCESS_CONTROL_ALLOW_ORIGIN") {
+                Ok(value) => {
+                    let allow_hosts = value
+                        .split(',')
+                        .into_iter()
+                        .map(HeaderValue::from_str)
+                        .collect::<Result<Vec<_>, _>>()?;
+                    AllowOrigin::list(allow_hosts)
+                }
+                _ => AllowOrigin::any(),
             }
-            _ => AllowOrigin::any(),
         };
         info!(?acl);
 
-        let cors = CorsLayer::new()
-            // Allow `POST` when accessing the resource
-            .allow_methods([Method::POST])
-            // Allow requests from any origin
-            .allow_origin(acl)
-            .allow_headers([
-                hyper::header::CONTENT_TYPE,
-                HeaderName::from_static(CLIENT_SDK_TYPE_HEADER),
-                HeaderName::from_static(CLIENT_SDK_VERSION_HEADER),
-                HeaderName::from_static(CLIENT_TARGET_API_VERSION_HEADER),
-                HeaderName::from_static(APP_NAME_HEADER),
-            ]);
+        let mut allow_headers = vec![
+            hyper::header::CONTENT_TYPE,
+            HeaderName::from_static(CLIENT_SDK_TYPE_HEADER),
+            HeaderName::from_static(CLIENT_SDK_VERSION_HEADER),
+            HeaderName::from_static(CLIENT_TARGET_API_VERSION_HEADER),
+            HeaderName::from_static(APP_NAME_HEADER),
+            HeaderName::from_static(CONSISTENCY_TOKEN_HEADER),
+        ];
+        if let Some(rate_limit_config) = self.rate_limits.config() {
+            allow_headers.push(HeaderName::from_str(&rate_limit_config.api_key_header)?);
+        }
+        if self.auth_token.is_some() {
+            allow_headers.push(hyper::header::AUTHORIZATION);
+        }
+
+        let cors = (!self.deny_browsers).then(|| {
+            CorsLayer::new()
+                // Allow `POST` when accessing the resource
+                .allow_methods([Method::POST])
+                // Allow requests from any origin
+                .allow_origin(acl)
+                .allow_headers(allow_headers)
+                // Allow clients to read the warnings about calls to deprecated methods, and the
+                // consistency tokens of responses
+                .expose_headers([
+                    hyper::header::WARNING,
+                    HeaderName::from_static(CONSISTENCY_TOKEN_HEADER),
+                ])
+        });
 
         let routing = self.rpc_doc.method_routing.clone();
+        let deprecated_methods = self.rpc_doc.deprecated_methods();
+        let method_params = self.rpc_doc.method_params();
 
         self.module
             .register_method("rpc.discover", move |_, _| Ok(self.rpc_doc.clone()))?;
@@ -123,8 +247,58 @@ impl JsonRpcServerBuilder {
             })
             .unwrap_or(u32::MAX);
 
+        // Connections made for subscriptions count against the connection limit too.
+        let max_subscriptions_per_connection = env::var("RPC_MAX_SUBSCRIPTIONS_PER_CONNECTION")
+            .ok()
+            .and_then(|o| {
+                u32::from_str(&o)
+                    .tap_err(|e| {
+                        warn!("Cannot parse RPC_MAX_SUBSCRIPTIONS_PER_CONNECTION to u32: {e}")
+                    })
+                    .ok()
+            })
+            .unwrap_or(DEFAULT_MAX_SUBSCRIPTIONS_PER_CONNECTION);
+
+        let ws_ping_interval = env::var("RPC_WS_PING_INTERVAL_MS")
+            .ok()
+            .and_then(|o| {
+                u64::from_str(&o)
+                    .tap_err(|e| warn!("Cannot parse RPC_WS_PING_INTERVAL_MS to u64: {e}"))
+                    .ok()
+            })
+            .map_or(DEFAULT_WS_PING_INTERVAL, Duration::from_millis);
+        info!(
+            max_connection,
+            max_subscriptions_per_connection,
+            ?ws_ping_interval,
+            "JSON-RPC connection limits"
+        );
+
         let metrics_logger = MetricsLogger::new(&self.registry, &methods_names);
 
+        let slow_query_threshold = env::var("RPC_SLOW_QUERY_THRESHOLD_MS")
+            .ok()
+            .and_then(|o| {
+                u64::from_str(&o)
+                    .tap_err(|e| warn!("Cannot parse RPC_SLOW_QUERY_THRESHOLD_MS to u64: {e}"))
+                    .ok()
+            })
+            .map(Duration::from_millis);
+        info!(?slow_query_threshold, "Slow JSON-RPC query logging");
+        let query_metrics_layer =
+            QueryMetricsLayer::new(&self.registry, &methods_names, slow_query_threshold);
+
+        let consistency_max_wait = env::var("RPC_CONSISTENCY_MAX_WAIT_MS")
+            .ok()
+            .and_then(|o| {
+                u64::from_str(&o)
+                    .tap_err(|e| warn!("Cannot parse RPC_CONSISTENCY_MAX_WAIT_MS to u64: {e}"))
+                    .ok()
+            })
+            .map_or(DEFAULT_CONSISTENCY_MAX_WAIT, Duration::from_millis);
+        // Requests waiting for their consistency token count as in flight against the rate limits.
+        let consistency_layer = ConsistencyLayer::new(self.state.clone(), consistency_max_wait);
+
         let disable_routing = env::var("DISABLE_BACKWARD_COMPATIBILITY")
             .ok()
             .and_then(|v| bool::from_str(&v).ok())
@@ -137,23 +311,90 @@ impl JsonRpcServerBuilder {
                 "enabled"
             }
         );
+        // Placed before the routing layer, so calls reach the indexer under the method names the
+        // client used, which the indexer routes itself.
+        let indexer_fallback_layer = IndexerFallbackLayer::new(
+            self.indexer_fallback_config.clone(),
+            &method_params,
+            &self.registry,
+        )?;
+
         // We need to use the routing layer to block access to the old methods when routing is disabled.
-        let routing_layer = RoutingLayer::new(routing, disable_routing);
+        // It also warns about calls to deprecated methods, or blocks them when routing is disabled.
+        let routing_layer = RoutingLayer::new(routing, deprecated_methods, disable_routing);
+
+        // Requests are counted against the rate limits by the methods clients call, before any
+        // routing.
+        let rate_limit_layer = RateLimitLayer::new(self.rate_limits.clone());
+
+        // Params are checked against the methods calls are routed to, since old clients are routed
+        // to the versions of the methods they know the params of.
+        let params_validation_layer = ParamsValidationLayer::new(method_params);
+
+        // Compression is always layered so that the response body has the same type either way, but
+        // compresses nothing when disabled.
+        let enable_compression = server_config.enable_compression;
+        let compression = CompressionLayer::new()
+            .gzip(enable_compression)
+            .br(enable_compression)
+            .deflate(false);
+
+        let max_request_body_size = server_config
+            .max_request_body_size
+            .unwrap_or(MAX_REQUEST_SIZE);
+        let tcp_keepalive = server_config.tcp_keepalive_secs.map(Duration::from_secs);
+        info!(
+            enable_compression,
+            max_request_body_size,
+            ?tcp_keepalive,
+            "JSON-RPC HTTP settings"
+        );
 
         let middleware = tower::ServiceBuilder::new()
-            .layer(cors)
-            .layer(routing_layer);
+            .layer(compression)
+            .option_layer(cors)
+            .layer(AuthLayer::new(self.auth_token.clone(), self.deny_browsers))
+            // Ahead of the layers that read request bodies whole.
+            .layer(RequestSizeLayer::new(max_request_body_size))
+            .layer(rate_limit_layer)
+            .layer(consistency_layer)
+            .layer(query_metrics_layer)
+            .layer(indexer_fallback_layer)
+            .layer(routing_layer)
+            .layer(params_validation_layer);
+        // Adds the address of the peer of each connection to its requests, ahead of the layers.
+        let peer_address_layer = PeerAddressLayer::new(middleware.into_inner());
+        let probed = peer_address_layer.probed();
+        let middleware = tower::ServiceBuilder::new().layer(peer_address_layer);
+
+        // Connections accepted by the listener inherit its keepalive settings.
+        let listener =
+            Socket::from(std::net::TcpListener::bind(listen_address).map_err(anyhow::Error::from)?);
+        listener
+            .set_nonblocking(true)
+            .map_err(anyhow::Error::from)?;
+        if let Some(keepalive) = tcp_keepalive {
+            listener
+                .set_tcp_keepalive(&TcpKeepalive::new().with_time(keepalive))
+                .map_err(anyhow::Error::from)?;
+        }
 
         let server = ServerBuilder::default()
-            .max_response_body_size(MAX_REQUEST_SIZE)
+            .max_request_body_size(max_request_body_size)
+            .max_response_body_size(u32::try_from(*MAX_RESPONSE_SIZE).unwrap_or(u32::MAX))
             .max_connections(max_connection)
+            .max_subscriptions_per_connection(max_subscriptions_per_connection)
+            .ping_interval(ws_ping_interval)
             .set_host_filtering(AllowHosts::Any)
             .set_middleware(middleware)
-            .set_logger(metrics_logger)
-            .build(listen_address)
-            .await?;
+            .set_logger(PeerAddressLogger(metrics_logger))
+            .build_from_tcp(listener)?;
         let addr = server.local_addr()?;
         let handle = server.start(self.module)?;
+        if let Err(e) = check_peer_address(addr, probed).await {
+            let _ = handle.stop();
+            return Err(e);
+        }
 
         info!(local_addr =? addr, "Sui JSON-RPC server listening on {addr}");
         info!("Available JSON-RPC methods : {:?}", methods_names);
@@ -162,6 +403,41 @@ impl JsonRpcServerBuilder {
     }
 }
 
+/// Connects to the server listening on `addr`, and fails unless the server reads the address of
+/// the peer of the connection, which rate limiting relies on, see [`PeerAddressLayer`].
+async fn check_peer_address(
+    mut addr: SocketAddr,
+    mut probed: watch::Receiver<Option<bool>>,
+) -> Result<(), Error> {
+    if addr.ip().is_unspecified() {
+        addr.set_ip(match addr {
+            SocketAddr::V4(_) => Ipv4Addr::LOCALHOST.into(),
+            SocketAddr::V6(_) => Ipv6Addr::LOCALHOST.into(),
+        });
+    }
+    let check = async {
+        let _connection = TcpStream::connect(addr).await?;
+        while probed.borrow().is_none() {
+            probed.changed().await?;
+        }
+        anyhow::Ok(*probed.borrow() == Some(true))
+    };
+    match tokio::time::timeout(PEER_ADDRESS_CHECK_TIMEOUT, check).await {
+        Ok(Ok(true)) => Ok(()),
+        Ok(Ok(false)) => Err(anyhow!(
+            "The JSON-RPC server cannot read the address of the peers of its connections"
+        )
+        .into()),
+        Ok(Err(e)) => Err(e
+            .context("Failed to check that the JSON-RPC server reads the address of its peers")
+            .into()),
+        Err(_) => Err(anyhow!(
+            "Timed out checking that the JSON-RPC server reads the address of its peers"
+        )
+        .into()),
+    }
+}
+
 pub trait SuiRpcModule
 where
     Self: Sized,
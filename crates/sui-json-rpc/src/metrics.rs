@@ -14,7 +14,7 @@ use prometheus::{
 };
 use tokio::time::Instant;
 
-const SPAM_LABEL: &str = "SPAM";
+pub(crate) const SPAM_LABEL: &str = "SPAM";
 const LATENCY_SEC_BUCKETS: &[f64] = &[
     0.001, 0.005, 0.01, 0.05, 0.1, 0.25, 0.5, 1., 2.5, 5., 10., 20., 30., 60., 90.,
 ];
@@ -39,7 +39,7 @@ pub struct Metrics {
     rpc_response_size: HistogramVec,
 }
 
-#[derive(Clone)]
+#[derive(Debug, Clone)]
 pub struct MetricsLogger {
     metrics: Metrics,
     method_whitelist: HashSet<String>,
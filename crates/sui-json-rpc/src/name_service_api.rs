@@ -0,0 +1,118 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::sync::Arc;
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use jsonrpsee::core::RpcResult;
+use jsonrpsee::RpcModule;
+use move_core_types::ident_str;
+use move_core_types::language_storage::{StructTag, TypeTag};
+use serde_json::Value;
+
+use sui_config::node::NameServiceConfig;
+use sui_core::authority::AuthorityState;
+use sui_json_rpc_types::{SuiMoveStruct, SuiMoveValue};
+use sui_open_rpc::Module;
+use sui_types::base_types::{ObjectID, SuiAddress};
+use sui_types::dynamic_field::DynamicFieldName;
+use sui_types::object::ObjectRead;
+use sui_types::MOVE_STDLIB_ADDRESS;
+
+use crate::api::NameServiceApiServer;
+use crate::error::Error;
+use crate::SuiRpcModule;
+
+/// Resolves names with the registry of the config, whose tables are read through the dynamic
+/// field index of the node.
+pub struct NameServiceApi {
+    state: Arc<AuthorityState>,
+    config: NameServiceConfig,
+}
+
+impl NameServiceApi {
+    pub fn new(state: Arc<AuthorityState>, config: NameServiceConfig) -> Self {
+        Self { state, config }
+    }
+
+    /// Reads the value of the entry of `table` under `key`, as JSON.
+    async fn get_table_value(
+        &self,
+        table: ObjectID,
+        key_type: TypeTag,
+        key: SuiMoveValue,
+    ) -> Result<Option<Value>, Error> {
+        let name = DynamicFieldName {
+            type_: key_type,
+            value: key.to_json_value(),
+        };
+        let Some(field_id) = self.state.get_dynamic_field_object_id(table, &name)? else {
+            return Ok(None);
+        };
+        let ObjectRead::Exists(_, object, Some(layout)) =
+            self.state.get_object_read(&field_id).await?
+        else {
+            return Ok(None);
+        };
+        let move_object = object
+            .data
+            .try_as_move()
+            .ok_or_else(|| anyhow!("Table entry [{field_id}] is not a Move object"))?;
+        let mut field = SuiMoveStruct::from(move_object.to_move_struct(&layout)?).to_json_value();
+        Ok(field.get_mut("value").map(Value::take))
+    }
+}
+
+fn string_type() -> TypeTag {
+    TypeTag::Struct(Box::new(StructTag {
+        address: MOVE_STDLIB_ADDRESS,
+        module: ident_str!("string").to_owned(),
+        name: ident_str!("String").to_owned(),
+        type_params: vec![],
+    }))
+}
+
+impl SuiRpcModule for NameServiceApi {
+    fn rpc(self) -> RpcModule<Self> {
+        self.into_rpc()
+    }
+
+    fn rpc_doc_module() -> Module {
+        crate::api::NameServiceApiOpenRpc::module_doc()
+    }
+}
+
+#[async_trait]
+impl NameServiceApiServer for NameServiceApi {
+    async fn resolve_name_service_address(&self, name: String) -> RpcResult<Option<SuiAddress>> {
+        let record = self
+            .get_table_value(
+                self.config.registry_id,
+                string_type(),
+                SuiMoveValue::String(name),
+            )
+            .await?;
+        // The target address is optional, and shows as null when unset.
+        Ok(record
+            .and_then(|mut record| record.get_mut("target_address").map(Value::take))
+            .filter(|target| !target.is_null())
+            .map(serde_json::from_value)
+            .transpose()
+            .map_err(|e| anyhow!("Malformed name record: {e}"))?)
+    }
+
+    async fn resolve_name_service_names(&self, address: SuiAddress) -> RpcResult<Vec<String>> {
+        let name = self
+            .get_table_value(
+                self.config.reverse_registry_id,
+                TypeTag::Address,
+                SuiMoveValue::Address(address),
+            )
+            .await?;
+        Ok(name
+            .and_then(|name| name.as_str().map(String::from))
+            .into_iter()
+            .collect())
+    }
+}
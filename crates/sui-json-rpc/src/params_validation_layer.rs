@@ -0,0 +1,271 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::routing_layer::{is_json, read_request_body, response};
+use fastcrypto::encoding::{Base64, Encoding};
+use hyper::{Body, Method, Request, Response};
+use jsonrpsee::core::JsonRawValue;
+use jsonrpsee::core::__reexports::serde_json;
+use jsonrpsee::types::error::{ErrorCode, ErrorObject};
+use jsonrpsee::types::{ErrorResponse, Request as RpcRequest};
+use schemars::schema::{InstanceType, SchemaObject, SingleOrVec};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::error::Error;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use sui_open_rpc::ContentDescriptor;
+use sui_types::base_types::{ObjectID, SuiAddress};
+use sui_types::digests::TransactionDigest;
+use tower::{Layer, Service};
+
+const SCHEMA_PREFIX: &str = "#/components/schemas/";
+const ADDRESS_EXAMPLE: &str = "0x8f9ba4e5e6d1d9a41c4d1c3a3b0b7b1e0f1ab8c9a7d3f3b2b4c0f1c8e2d7a6b5";
+const DIGEST_EXAMPLE: &str = "DxJxf3yJYN74x3saUWa3pxQ2HWETkpwv1pKWHjbZ3n2u";
+
+/// Checks the params of each call against the params the method documents, and answers calls
+/// with invalid params with an error that names the param and the format it expects. Params
+/// whose schema is not one of the checked formats are left for the method to deserialize.
+#[derive(Clone)]
+pub struct ParamsValidationLayer {
+    method_params: Arc<HashMap<String, Vec<ContentDescriptor>>>,
+}
+
+impl ParamsValidationLayer {
+    pub fn new(method_params: HashMap<String, Vec<ContentDescriptor>>) -> Self {
+        Self {
+            method_params: Arc::new(method_params),
+        }
+    }
+}
+
+impl<S> Layer<S> for ParamsValidationLayer {
+    type Service = ParamsValidationService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        ParamsValidationService {
+            inner,
+            method_params: self.method_params.clone(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct ParamsValidationService<S> {
+    inner: S,
+    method_params: Arc<HashMap<String, Vec<ContentDescriptor>>>,
+}
+
+impl<S> Service<Request<Body>> for ParamsValidationService<S>
+where
+    S: Service<Request<Body>, Response = Response<Body>> + Clone + Send + 'static,
+    S::Response: 'static,
+    S::Error: Into<Box<dyn Error + Send + Sync>> + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = Box<dyn Error + Send + Sync + 'static>;
+    type Future =
+        Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send + 'static>>;
+
+    #[inline]
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx).map_err(Into::into)
+    }
+
+    fn call(&mut self, req: Request<Body>) -> Self::Future {
+        let clone = self.inner.clone();
+        let method_params = self.method_params.clone();
+        // take the service that was ready
+        // https://docs.rs/tower/latest/tower/trait.Service.html#be-careful-when-cloning-inner-services
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        let res_fut = async move {
+            if !(req.method() == Method::POST && is_json(&req)) {
+                return inner.call(req).await.map_err(|err| err.into());
+            }
+            let (parts, body) = req.into_parts();
+            let (body, is_single) = match read_request_body(&parts.headers, body).await {
+                Ok(r) => r,
+                Err(response) => return Ok(response),
+            };
+
+            if is_single {
+                if let Some(error) = validate_request(&body, &method_params) {
+                    return Ok(response::ok(error));
+                }
+                let req = Request::from_parts(parts, Body::from(body));
+                return inner.call(req).await.map_err(|err| err.into());
+            }
+
+            // Calls of a batch with invalid params are answered here, and the rest are passed on.
+            let requests = match serde_json::from_slice::<Vec<&JsonRawValue>>(&body) {
+                Ok(requests) => requests,
+                Err(_) => {
+                    let req = Request::from_parts(parts, Body::from(body));
+                    return inner.call(req).await.map_err(|err| err.into());
+                }
+            };
+            let mut valid = vec![];
+            let mut errors = vec![];
+            for request in requests {
+                match validate_request(request.get().as_bytes(), &method_params) {
+                    Some(error) => errors.push(error),
+                    None => valid.push(request),
+                }
+            }
+            if errors.is_empty() {
+                let req = Request::from_parts(parts, Body::from(body));
+                return inner.call(req).await.map_err(|err| err.into());
+            }
+            if valid.is_empty() {
+                return Ok(response::ok(format!("[{}]", errors.join(","))));
+            }
+
+            let req = Request::from_parts(parts, Body::from(serde_json::to_vec(&valid)?));
+            let response = inner.call(req).await.map_err(|err| err.into())?;
+            let (parts, body) = response.into_parts();
+            let body = hyper::body::to_bytes(body).await?;
+            let body = match serde_json::from_slice::<Vec<&JsonRawValue>>(&body) {
+                Ok(responses) => {
+                    let responses = responses.iter().map(|response| response.get().to_string());
+                    format!(
+                        "[{}]",
+                        responses.chain(errors).collect::<Vec<_>>().join(",")
+                    )
+                }
+                // The batch as a whole failed, which is the answer to the invalid calls too.
+                Err(_) => return Ok(Response::from_parts(parts, Body::from(body))),
+            };
+            Ok(Response::from_parts(parts, Body::from(body)))
+        };
+        Box::pin(res_fut)
+    }
+}
+
+#[derive(Serialize)]
+struct InvalidParam<'a> {
+    param: &'a str,
+    expected: &'a str,
+    example: &'a str,
+}
+
+/// Returns the error response to the call in `body` if its params are invalid.
+fn validate_request(
+    body: &[u8],
+    method_params: &HashMap<String, Vec<ContentDescriptor>>,
+) -> Option<String> {
+    let request = serde_json::from_slice::<RpcRequest>(body).ok()?;
+    let params = method_params.get(request.method.as_ref())?;
+    let values: Value = match request.params {
+        Some(params) => serde_json::from_str(params.get()).ok()?,
+        None => Value::Null,
+    };
+
+    for (position, param) in params.iter().enumerate() {
+        let value = match &values {
+            Value::Array(values) => values.get(position),
+            Value::Object(values) => values.get(param.name()),
+            _ => None,
+        }
+        .filter(|value| !value.is_null());
+
+        let format = param_format(param.schema());
+        let (message, format) = match (value, format) {
+            (None, _) if param.required() => {
+                (format!("Missing required param `{}`", param.name()), format)
+            }
+            (Some(value), Some(format)) if !(format.is_valid)(value) => (
+                format!(
+                    "Invalid param `{}`: expected {}, e.g. {}",
+                    param.name(),
+                    format.expected,
+                    format.example
+                ),
+                Some(format),
+            ),
+            _ => continue,
+        };
+        let data = InvalidParam {
+            param: param.name(),
+            expected: format.as_ref().map_or("", |f| f.expected),
+            example: format.as_ref().map_or("", |f| f.example),
+        };
+        let data = serde_json::value::to_raw_value(&data).ok()?;
+        let error = ErrorObject::borrowed(
+            ErrorCode::InvalidParams.code(),
+            &message,
+            Some(data.as_ref()),
+        );
+        return serde_json::to_string(&ErrorResponse::borrowed(error, request.id)).ok();
+    }
+    None
+}
+
+struct ParamFormat {
+    expected: &'static str,
+    example: &'static str,
+    is_valid: fn(&Value) -> bool,
+}
+
+/// The format of the values of params with `schema`, if it is one of those checked.
+fn param_format(schema: &SchemaObject) -> Option<ParamFormat> {
+    if let Some(reference) = &schema.reference {
+        let format = match reference.trim_start_matches(SCHEMA_PREFIX) {
+            "ObjectID" => ParamFormat {
+                expected: "a 32-byte hex ObjectID with 0x prefix",
+                example: ADDRESS_EXAMPLE,
+                is_valid: deserializes::<ObjectID>,
+            },
+            "SuiAddress" => ParamFormat {
+                expected: "a 32-byte hex address with 0x prefix",
+                example: ADDRESS_EXAMPLE,
+                is_valid: deserializes::<SuiAddress>,
+            },
+            "TransactionDigest" => ParamFormat {
+                expected: "a base58 encoded 32-byte transaction digest",
+                example: DIGEST_EXAMPLE,
+                is_valid: deserializes::<TransactionDigest>,
+            },
+            "Base64" => ParamFormat {
+                expected: "a base64 encoded string",
+                example: "AQID",
+                is_valid: |value| value.as_str().map_or(false, |s| Base64::decode(s).is_ok()),
+            },
+            _ => return None,
+        };
+        return Some(format);
+    }
+    let instance_type = match &schema.instance_type {
+        Some(SingleOrVec::Single(instance_type)) => **instance_type,
+        _ => return None,
+    };
+    let format = match instance_type {
+        InstanceType::Integer if matches!(&schema.format, Some(format) if format.starts_with("uint")) => {
+            ParamFormat {
+                expected: "an unsigned integer",
+                example: "100",
+                is_valid: |value| value.is_u64(),
+            }
+        }
+        InstanceType::Boolean => ParamFormat {
+            expected: "a boolean",
+            example: "true",
+            is_valid: Value::is_boolean,
+        },
+        InstanceType::String => ParamFormat {
+            expected: "a string",
+            example: "\"0x2::sui::SUI\"",
+            is_valid: Value::is_string,
+        },
+        _ => return None,
+    };
+    Some(format)
+}
+
+fn deserializes<T: DeserializeOwned>(value: &Value) -> bool {
+    serde_json::from_value::<T>(value.clone()).is_ok()
+}
@@ -0,0 +1,323 @@
+//! jsonrpsee accepts the connections of clients itself, and only tells the address of the peer of
+//! a connection to the service the middleware wraps, which hands it to the logger of the server
+//! as it starts serving each request. The middleware of each connection therefore starts by
+//! passing that service a probe request, which the logger answers with the address of the peer.
+//!
+//! This relies on how jsonrpsee serves requests, so its version is pinned, and the server checks
+//! that the probe of a first connection of its own is answered before it starts, see
+//! [`PeerAddressLayer::probed`]. Connections whose probe is not answered are served errors only,
+//! rather than rate limited together as a single client.
+
+use crate::routing_layer::response;
+use hyper::header::{HeaderValue, HOST};
+use hyper::{Body, Request, Response};
+use jsonrpsee::server::logger::{HttpRequest, Logger, MethodKind, TransportProtocol};
+use jsonrpsee::types::Params;
+use std::error::Error;
+use std::future::Future;
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use tokio::sync::watch;
+use tower::{Layer, Service};
+use tracing::error;
+
+/// The address of the peer a request was received from, in the extensions of the request.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PeerAddress(pub SocketAddr);
+
+/// Marks a probe request, and receives the address of the peer of its connection from
+/// [`PeerAddressLogger`]. Extensions cannot be set by clients, so they cannot send probes.
+#[derive(Clone, Default)]
+struct PeerAddressProbe(Arc<Mutex<Option<SocketAddr>>>);
+
+/// Wraps the middleware of the server, which jsonrpsee builds for each connection, and adds the
+/// [`PeerAddress`] of the connection to its requests. The server must log with a
+/// [`PeerAddressLogger`].
+#[derive(Clone)]
+pub struct PeerAddressLayer<L> {
+    middleware: L,
+    /// Whether the probe of the first connection was answered, once it is accepted.
+    probed: Arc<watch::Sender<Option<bool>>>,
+}
+
+impl<L> PeerAddressLayer<L> {
+    pub fn new(middleware: L) -> Self {
+        Self {
+            middleware,
+            probed: Arc::new(watch::channel(None).0),
+        }
+    }
+
+    /// Receives whether the probe of the first connection accepted by the server was answered.
+    pub fn probed(&self) -> watch::Receiver<Option<bool>> {
+        self.probed.subscribe()
+    }
+}
+
+impl<L, S> Layer<S> for PeerAddressLayer<L>
+where
+    L: Layer<S>,
+    S: Service<Request<Body>> + Clone,
+{
+    type Service = PeerAddressService<L::Service>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        let peer_address = probe_peer_address(inner.clone());
+        if peer_address.is_none() {
+            error!("Cannot read the address of the peer of a JSON-RPC connection");
+        }
+        self.probed.send_if_modified(|probed| {
+            let first = probed.is_none();
+            if first {
+                *probed = Some(peer_address.is_some());
+            }
+            first
+        });
+        PeerAddressService {
+            inner: self.middleware.layer(inner),
+            peer_address,
+        }
+    }
+}
+
+/// Passes a probe request to `service`, the jsonrpsee service of a connection, which reports the
+/// address of the peer of the connection to the logger before returning the future serving the
+/// request. That future is dropped without being polled, so the probe is never served. The
+/// service is only called once ready, as services require, and it is not probed otherwise.
+fn probe_peer_address<S: Service<Request<Body>>>(mut service: S) -> Option<SocketAddr> {
+    let mut cx = Context::from_waker(futures::task::noop_waker_ref());
+    if !matches!(service.poll_ready(&mut cx), Poll::Ready(Ok(()))) {
+        return None;
+    }
+    let probe = PeerAddressProbe::default();
+    let mut request = Request::new(Body::empty());
+    request
+        .headers_mut()
+        .insert(HOST, HeaderValue::from_static("localhost"));
+    request.extensions_mut().insert(probe.clone());
+    drop(service.call(request));
+    let peer_address = probe.0.lock().unwrap();
+    *peer_address
+}
+
+#[derive(Clone)]
+pub struct PeerAddressService<S> {
+    inner: S,
+    peer_address: Option<SocketAddr>,
+}
+
+impl<S> Service<Request<Body>> for PeerAddressService<S>
+where
+    S: Service<Request<Body>, Response = Response<Body>> + Clone + Send + 'static,
+    S::Response: 'static,
+    S::Error: Into<Box<dyn Error + Send + Sync>> + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = Box<dyn Error + Send + Sync + 'static>;
+    type Future =
+        Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send + 'static>>;
+
+    #[inline]
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx).map_err(Into::into)
+    }
+
+    fn call(&mut self, mut req: Request<Body>) -> Self::Future {
+        let clone = self.inner.clone();
+        // take the service that was ready
+        // https://docs.rs/tower/latest/tower/trait.Service.html#be-careful-when-cloning-inner-services
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        let Some(address) = self.peer_address else {
+            // Without the address of the client, the client cannot be rate limited.
+            return Box::pin(async { Ok(response::internal_error()) });
+        };
+        req.extensions_mut().insert(PeerAddress(address));
+        Box::pin(async move { inner.call(req).await.map_err(|err| err.into()) })
+    }
+}
+
+/// Answers the probes of [`PeerAddressLayer`] with the address of the peer of their connection,
+/// and logs everything else with the wrapped logger.
+#[derive(Clone)]
+pub struct PeerAddressLogger<L>(pub L);
+
+impl<L: Logger> Logger for PeerAddressLogger<L> {
+    type Instant = L::Instant;
+
+    fn on_connect(&self, remote_addr: SocketAddr, request: &HttpRequest, t: TransportProtocol) {
+        match request.extensions().get::<PeerAddressProbe>() {
+            Some(probe) => *probe.0.lock().unwrap() = Some(remote_addr),
+            None => self.0.on_connect(remote_addr, request, t),
+        }
+    }
+
+    fn on_request(&self, transport: TransportProtocol) -> Self::Instant {
+        self.0.on_request(transport)
+    }
+
+    fn on_call(
+        &self,
+        method_name: &str,
+        params: Params,
+        kind: MethodKind,
+        transport: TransportProtocol,
+    ) {
+        self.0.on_call(method_name, params, kind, transport)
+    }
+
+    fn on_result(
+        &self,
+        method_name: &str,
+        success: bool,
+        started_at: Self::Instant,
+        transport: TransportProtocol,
+    ) {
+        self.0
+            .on_result(method_name, success, started_at, transport)
+    }
+
+    fn on_response(&self, result: &str, started_at: Self::Instant, transport: TransportProtocol) {
+        self.0.on_response(result, started_at, transport)
+    }
+
+    fn on_disconnect(&self, remote_addr: SocketAddr, transport: TransportProtocol) {
+        self.0.on_disconnect(remote_addr, transport)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hyper::StatusCode;
+    use jsonrpsee::server::{RpcModule, ServerBuilder};
+    use std::convert::Infallible;
+    use std::error::Error;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Counts the connections reported to it.
+    #[derive(Clone, Default)]
+    struct CountingLogger(Arc<AtomicUsize>);
+
+    impl Logger for CountingLogger {
+        type Instant = ();
+
+        fn on_connect(&self, _: SocketAddr, _: &HttpRequest, _: TransportProtocol) {
+            self.0.fetch_add(1, Ordering::SeqCst);
+        }
+        fn on_request(&self, _: TransportProtocol) {}
+        fn on_call(&self, _: &str, _: Params, _: MethodKind, _: TransportProtocol) {}
+        fn on_result(&self, _: &str, _: bool, _: (), _: TransportProtocol) {}
+        fn on_response(&self, _: &str, _: (), _: TransportProtocol) {}
+        fn on_disconnect(&self, _: SocketAddr, _: TransportProtocol) {}
+    }
+
+    /// Middleware answering each request with its [`PeerAddress`] instead of serving it.
+    #[derive(Clone)]
+    struct EchoPeerAddress;
+
+    impl<S> Layer<S> for EchoPeerAddress {
+        type Service = EchoPeerAddress;
+
+        fn layer(&self, _inner: S) -> Self::Service {
+            EchoPeerAddress
+        }
+    }
+
+    impl Service<Request<Body>> for EchoPeerAddress {
+        type Response = Response<Body>;
+        type Error = Box<dyn Error + Send + Sync + 'static>;
+        type Future = futures::future::Ready<Result<Self::Response, Self::Error>>;
+
+        fn poll_ready(&mut self, _: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn call(&mut self, req: Request<Body>) -> Self::Future {
+            let peer = req.extensions().get::<PeerAddress>().copied();
+            futures::future::ready(Ok(Response::new(Body::from(
+                peer.map_or("none".to_string(), |PeerAddress(peer)| peer.to_string()),
+            ))))
+        }
+    }
+
+    /// A service that is never ready, and reports any request it is called with anyway.
+    struct NeverReady(PeerAddressLogger<CountingLogger>);
+
+    impl Service<Request<Body>> for NeverReady {
+        type Response = ();
+        type Error = Infallible;
+        type Future = futures::future::Ready<Result<(), Infallible>>;
+
+        fn poll_ready(&mut self, _: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+            Poll::Pending
+        }
+
+        fn call(&mut self, req: Request<Body>) -> Self::Future {
+            let peer = "10.0.0.1:40000".parse().unwrap();
+            self.0.on_connect(peer, &req, TransportProtocol::Http);
+            futures::future::ready(Ok(()))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_peer_address_of_connection() {
+        let logger = CountingLogger::default();
+        let layer = PeerAddressLayer::new(EchoPeerAddress);
+        let probed = layer.probed();
+        let server = ServerBuilder::default()
+            .set_middleware(tower::ServiceBuilder::new().layer(layer))
+            .set_logger(PeerAddressLogger(logger.clone()))
+            .build("127.0.0.1:0")
+            .await
+            .unwrap();
+        let address = server.local_addr().unwrap();
+        let _handle = server.start(RpcModule::new(())).unwrap();
+
+        let body = reqwest::Client::new()
+            .post(format!("http://{address}"))
+            .send()
+            .await
+            .unwrap()
+            .text()
+            .await
+            .unwrap();
+        let peer: SocketAddr = body.parse().unwrap();
+        assert_eq!(peer.ip(), address.ip());
+        // The probe is not logged.
+        assert_eq!(logger.0.load(Ordering::SeqCst), 0);
+        assert_eq!(*probed.borrow(), Some(true));
+    }
+
+    #[tokio::test]
+    async fn test_unprobed_connection_is_refused() {
+        let mut service = PeerAddressService {
+            inner: EchoPeerAddress,
+            peer_address: None,
+        };
+        let response = service.call(Request::new(Body::empty())).await.unwrap();
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    #[test]
+    fn test_probe_is_answered_by_logger() {
+        let peer: SocketAddr = "10.0.0.1:40000".parse().unwrap();
+        let logger = PeerAddressLogger(CountingLogger::default());
+        let service_logger = logger.clone();
+        let service = tower::service_fn(move |req: Request<Body>| {
+            service_logger.on_connect(peer, &req, TransportProtocol::Http);
+            futures::future::ready(Ok::<_, Infallible>(()))
+        });
+        assert_eq!(probe_peer_address(service), Some(peer));
+
+        // A service that does not report the peer to the logger.
+        let service =
+            tower::service_fn(|_: Request<Body>| futures::future::ready(Ok::<_, Infallible>(())));
+        assert_eq!(probe_peer_address(service), None);
+
+        // A service that is not ready is not called.
+        assert_eq!(probe_peer_address(NeverReady(logger)), None);
+    }
+}
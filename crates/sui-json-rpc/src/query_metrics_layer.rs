@@ -0,0 +1,216 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::metrics::SPAM_LABEL;
+use crate::routing_layer::{is_json, read_request_body};
+use hyper::{Body, Method, Request, Response};
+use jsonrpsee::core::__reexports::serde_json;
+use jsonrpsee::core::__reexports::serde_json::value::RawValue;
+use jsonrpsee::types::Request as RpcRequest;
+use prometheus::{register_histogram_vec_with_registry, HistogramVec, Registry};
+use std::collections::HashSet;
+use std::error::Error;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Duration;
+use tokio::time::Instant;
+use tower::{Layer, Service};
+use tracing::warn;
+
+/// The route label of batched requests, whose results can't be told apart.
+const BATCH_LABEL: &str = "batch";
+/// How much of the params of a call the slow query log shows.
+const MAX_LOGGED_PARAMS_LEN: usize = 256;
+
+/// Records the size of the results of each method, and logs the calls of requests that take
+/// longer than the slow query threshold.
+#[derive(Clone)]
+pub struct QueryMetricsLayer {
+    inner: Arc<QueryMetrics>,
+}
+
+struct QueryMetrics {
+    result_size_by_route: HistogramVec,
+    method_whitelist: HashSet<String>,
+    slow_query_threshold: Option<Duration>,
+}
+
+impl QueryMetricsLayer {
+    pub fn new(
+        registry: &Registry,
+        method_whitelist: &[&str],
+        slow_query_threshold: Option<Duration>,
+    ) -> Self {
+        Self {
+            inner: Arc::new(QueryMetrics {
+                result_size_by_route: register_histogram_vec_with_registry!(
+                    "rpc_result_size_by_route",
+                    "Size of the response to a request by route",
+                    &["route"],
+                    prometheus::exponential_buckets(32.0, 2.0, 24)
+                        .unwrap()
+                        .to_vec(),
+                    registry,
+                )
+                .unwrap(),
+                method_whitelist: method_whitelist.iter().map(|s| (*s).into()).collect(),
+                slow_query_threshold,
+            }),
+        }
+    }
+}
+
+impl<S> Layer<S> for QueryMetricsLayer {
+    type Service = QueryMetricsService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        QueryMetricsService {
+            inner,
+            metrics: self.inner.clone(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct QueryMetricsService<S> {
+    inner: S,
+    metrics: Arc<QueryMetrics>,
+}
+
+impl<S> Service<Request<Body>> for QueryMetricsService<S>
+where
+    S: Service<Request<Body>, Response = Response<Body>> + Clone + Send + 'static,
+    S::Response: 'static,
+    S::Error: Into<Box<dyn Error + Send + Sync>> + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = Box<dyn Error + Send + Sync + 'static>;
+    type Future =
+        Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send + 'static>>;
+
+    #[inline]
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx).map_err(Into::into)
+    }
+
+    fn call(&mut self, req: Request<Body>) -> Self::Future {
+        let clone = self.inner.clone();
+        let metrics = self.metrics.clone();
+        // take the service that was ready
+        // https://docs.rs/tower/latest/tower/trait.Service.html#be-careful-when-cloning-inner-services
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        let res_fut = async move {
+            if !(req.method() == Method::POST && is_json(&req)) {
+                return inner.call(req).await.map_err(|err| err.into());
+            }
+            let (parts, body) = req.into_parts();
+            let (body, is_single) = match read_request_body(&parts.headers, body).await {
+                Ok(r) => r,
+                Err(response) => return Ok(response),
+            };
+            let calls = request_calls(&body, is_single);
+            let req = Request::from_parts(parts, Body::from(body));
+
+            let started_at = Instant::now();
+            let response = inner.call(req).await.map_err(|err| err.into())?;
+            let (parts, body) = response.into_parts();
+            // Responses are built whole by the server, so nothing is lost by buffering them here.
+            let body = hyper::body::to_bytes(body).await?;
+            let elapsed = started_at.elapsed();
+
+            let route = match &calls[..] {
+                [(method, _)] if is_single => metrics.check_spam(method),
+                _ if is_single => SPAM_LABEL,
+                _ => BATCH_LABEL,
+            };
+            metrics
+                .result_size_by_route
+                .with_label_values(&[route])
+                .observe(body.len() as f64);
+
+            if matches!(metrics.slow_query_threshold, Some(threshold) if elapsed >= threshold) {
+                for (method, params) in &calls {
+                    warn!(
+                        method = %method,
+                        params = %params,
+                        duration_ms = elapsed.as_millis() as u64,
+                        batch_size = calls.len(),
+                        response_size = body.len(),
+                        "Slow JSON-RPC query"
+                    );
+                }
+            }
+            Ok(Response::from_parts(parts, Body::from(body)))
+        };
+        Box::pin(res_fut)
+    }
+}
+
+impl QueryMetrics {
+    fn check_spam<'a>(&'a self, method_name: &'a str) -> &'a str {
+        if self.method_whitelist.contains(method_name) {
+            method_name
+        } else {
+            SPAM_LABEL
+        }
+    }
+}
+
+/// The method and a summary of the params of each call of the request.
+fn request_calls(body: &[u8], is_single: bool) -> Vec<(String, String)> {
+    let call = |request: RpcRequest| {
+        let params = request.params.map(|p| p.get()).unwrap_or_default();
+        let mut summary: String = params.chars().take(MAX_LOGGED_PARAMS_LEN).collect();
+        if summary.len() < params.len() {
+            summary.push_str("...");
+        }
+        (request.method.into_owned(), summary)
+    };
+    if is_single {
+        serde_json::from_slice::<RpcRequest>(body)
+            .map(|request| vec![call(request)])
+            .unwrap_or_default()
+    } else {
+        serde_json::from_slice::<Vec<&RawValue>>(body)
+            .map(|requests| {
+                requests
+                    .into_iter()
+                    .filter_map(|request| serde_json::from_str::<RpcRequest>(request.get()).ok())
+                    .map(call)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_calls() {
+        let single = br#"{"jsonrpc":"2.0","id":1,"method":"sui_getObject","params":["0x5"]}"#;
+        assert_eq!(
+            request_calls(single, true),
+            vec![("sui_getObject".to_string(), r#"["0x5"]"#.to_string())]
+        );
+
+        // Calls that can't be read are left out of a batch.
+        let long_param = "a".repeat(MAX_LOGGED_PARAMS_LEN);
+        let batch = format!(
+            r#"[
+                {{"jsonrpc":"2.0","id":1,"method":"sui_getObject","params":["0x5"]}},
+                {{"jsonrpc":"2.0","id":2}},
+                {{"jsonrpc":"2.0","id":3,"method":"sui_getEvents","params":["{long_param}"]}}
+            ]"#
+        );
+        let calls = request_calls(batch.as_bytes(), false);
+        assert_eq!(calls.len(), 2);
+        assert_eq!(calls[0].0, "sui_getObject");
+        assert_eq!(calls[1].0, "sui_getEvents");
+        assert_eq!(calls[1].1.len(), MAX_LOGGED_PARAMS_LEN + "...".len());
+    }
+}
@@ -0,0 +1,475 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::peer_address_layer::PeerAddress;
+use crate::routing_layer::{is_json, read_request_body, response};
+use hyper::{Body, Method, Request, Response};
+use jsonrpsee::core::__reexports::serde_json;
+use jsonrpsee::core::__reexports::serde_json::value::RawValue;
+use jsonrpsee::types::Request as RpcRequest;
+use std::collections::HashMap;
+use std::error::Error;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, RwLock};
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+use sui_config::node::RpcRateLimitConfig;
+use tower::{Layer, Service};
+
+/// The method requests are counted under when their body can't be read as JSON-RPC.
+const UNKNOWN_METHOD: &str = "unknown";
+/// The client requests with neither a known API key nor an address are counted under. The server
+/// refuses the requests of connections it cannot read the address of the peer of, so it only
+/// counts those of layers used without [`crate::peer_address_layer::PeerAddressLayer`].
+const UNKNOWN_CLIENT: &str = "unknown";
+
+#[derive(Clone)]
+pub struct RateLimitLayer {
+    limits: RateLimitHandle,
+}
+
+impl RateLimitLayer {
+    pub fn new(limits: RateLimitHandle) -> Self {
+        Self { limits }
+    }
+}
+
+/// The rate limits of a server, which can be changed while it runs.
+#[derive(Clone, Default)]
+pub struct RateLimitHandle {
+    limiter: Arc<RwLock<Option<Arc<RateLimiter>>>>,
+}
+
+impl RateLimitHandle {
+    /// Requests are let through unlimited when `config` is `None`.
+    pub fn new(config: Option<RpcRateLimitConfig>) -> Self {
+        let handle = Self::default();
+        handle.set(config);
+        handle
+    }
+
+    pub fn config(&self) -> Option<RpcRateLimitConfig> {
+        self.limiter
+            .read()
+            .unwrap()
+            .as_ref()
+            .map(|limiter| limiter.config.clone())
+    }
+
+    /// Replaces the limits, or lifts them when `config` is `None`. Clients start over with the
+    /// new limits, but requests already in flight don't count against them.
+    pub fn set(&self, config: Option<RpcRateLimitConfig>) {
+        *self.limiter.write().unwrap() = config.map(|config| Arc::new(RateLimiter::new(config)));
+    }
+
+    fn limiter(&self) -> Option<Arc<RateLimiter>> {
+        self.limiter.read().unwrap().clone()
+    }
+}
+
+impl<S> Layer<S> for RateLimitLayer {
+    type Service = RateLimitService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RateLimitService {
+            inner,
+            limits: self.limits.clone(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct RateLimitService<S> {
+    inner: S,
+    limits: RateLimitHandle,
+}
+
+impl<S> Service<Request<Body>> for RateLimitService<S>
+where
+    S: Service<Request<Body>, Response = Response<Body>> + Clone + Send + 'static,
+    S::Response: 'static,
+    S::Error: Into<Box<dyn Error + Send + Sync>> + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = Box<dyn Error + Send + Sync + 'static>;
+    type Future =
+        Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send + 'static>>;
+
+    #[inline]
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx).map_err(Into::into)
+    }
+
+    fn call(&mut self, req: Request<Body>) -> Self::Future {
+        let clone = self.inner.clone();
+        let limiter = self.limits.limiter();
+        // take the service that was ready
+        // https://docs.rs/tower/latest/tower/trait.Service.html#be-careful-when-cloning-inner-services
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        let res_fut = async move {
+            let limiter = match limiter {
+                Some(limiter) => limiter,
+                None => return inner.call(req).await.map_err(|err| err.into()),
+            };
+            let (client, multiplier) = limiter.client(&req);
+            // Held until the response is ready, so that the request counts as in flight until then.
+            let _in_flight = match limiter.start_request(&client, multiplier) {
+                Some(guard) => guard,
+                None => return Ok(response::too_many_requests()),
+            };
+
+            let (req, methods) = if req.method() == Method::POST && is_json(&req) {
+                let (parts, body) = req.into_parts();
+                let (body, is_single) = match read_request_body(&parts.headers, body).await {
+                    Ok(r) => r,
+                    Err(response) => return Ok(response),
+                };
+                let methods = request_methods(&body, is_single);
+                (Request::from_parts(parts, Body::from(body)), methods)
+            } else {
+                (req, vec![])
+            };
+
+            if !limiter.take(&client, multiplier, &methods) {
+                return Ok(response::too_many_requests());
+            }
+            inner.call(req).await.map_err(|err| err.into())
+        };
+        Box::pin(res_fut)
+    }
+}
+
+/// The methods called by the request, one for each call in a batch.
+fn request_methods(body: &[u8], is_single: bool) -> Vec<String> {
+    if is_single {
+        serde_json::from_slice::<RpcRequest>(body)
+            .map(|request| vec![request.method.into_owned()])
+            .unwrap_or_default()
+    } else {
+        serde_json::from_slice::<Vec<&RawValue>>(body)
+            .map(|requests| {
+                requests
+                    .into_iter()
+                    .map(
+                        |request| match serde_json::from_str::<RpcRequest>(request.get()) {
+                            Ok(request) => request.method.into_owned(),
+                            Err(_) => UNKNOWN_METHOD.to_string(),
+                        },
+                    )
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+/// The address `num_trusted_proxies` from the end of the `addresses` forwarded by the proxies a
+/// request went through, which the last of the trusted proxies received it from. Addresses
+/// before it can't be trusted, since the client may send the header itself.
+fn forwarded_address(addresses: Vec<&str>, num_trusted_proxies: usize) -> Option<&str> {
+    let index = addresses.len().checked_sub(num_trusted_proxies)?;
+    Some(addresses[index]).filter(|address| !address.is_empty())
+}
+
+/// A token bucket, holding up to a second worth of requests.
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+struct RateLimiter {
+    config: RpcRateLimitConfig,
+    /// The bucket of each client and method.
+    buckets: Mutex<Buckets>,
+    /// The number of requests of each client in flight.
+    in_flight: Mutex<HashMap<String, u32>>,
+}
+
+struct Buckets {
+    buckets: HashMap<(String, String), Bucket>,
+    pruned: Instant,
+}
+
+impl RateLimiter {
+    fn new(config: RpcRateLimitConfig) -> Self {
+        Self {
+            config,
+            buckets: Mutex::new(Buckets {
+                buckets: HashMap::new(),
+                pruned: Instant::now(),
+            }),
+            in_flight: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// The client making the request, with the multiple of the limits it gets.
+    fn client<B>(&self, req: &Request<B>) -> (String, u32) {
+        let key = req
+            .headers()
+            .get(&self.config.api_key_header)
+            .and_then(|h| h.to_str().ok());
+        if let Some(key) = key {
+            if let Some(multiplier) = self.config.api_keys.get(key) {
+                return (format!("key:{key}"), *multiplier);
+            }
+        }
+        let forwarded = match self.config.num_trusted_proxies {
+            0 => None,
+            num_trusted_proxies => forwarded_address(
+                req.headers()
+                    .get_all(&self.config.client_address_header)
+                    .iter()
+                    .filter_map(|h| h.to_str().ok())
+                    .flat_map(|addresses| addresses.split(','))
+                    .map(str::trim)
+                    .collect(),
+                num_trusted_proxies,
+            ),
+        };
+        // Ports differ from one connection of a client to the next.
+        let peer = || {
+            req.extensions()
+                .get::<PeerAddress>()
+                .map(|PeerAddress(address)| address.ip().to_string())
+        };
+        match forwarded.map(str::to_string).or_else(peer) {
+            Some(address) => (format!("address:{address}"), 1),
+            None => (UNKNOWN_CLIENT.to_string(), 1),
+        }
+    }
+
+    /// Counts a request of `client` as in flight until the returned guard is dropped, or returns
+    /// `None` if the client already has as many requests in flight as it can.
+    fn start_request(self: &Arc<Self>, client: &str, multiplier: u32) -> Option<InFlightGuard> {
+        let mut in_flight = self.in_flight.lock().unwrap();
+        let count = in_flight.entry(client.to_string()).or_default();
+        if let Some(max) = self.config.max_concurrent_requests {
+            if *count >= max.saturating_mul(multiplier) {
+                return None;
+            }
+        }
+        *count += 1;
+        Some(InFlightGuard {
+            limiter: self.clone(),
+            client: client.to_string(),
+        })
+    }
+
+    /// Takes a token from the bucket of `client` for each of `methods`, or none at all and returns
+    /// false if any of the buckets doesn't have enough.
+    fn take(&self, client: &str, multiplier: u32, methods: &[String]) -> bool {
+        let mut needed: HashMap<&str, f64> = HashMap::new();
+        if methods.is_empty() {
+            needed.insert(UNKNOWN_METHOD, 1.0);
+        }
+        for method in methods {
+            *needed.entry(method.as_str()).or_default() += 1.0;
+        }
+
+        let now = Instant::now();
+        let mut buckets = self.buckets.lock().unwrap();
+        // A bucket left alone for a second is full again, and the same as no bucket at all.
+        if now.duration_since(buckets.pruned) > Duration::from_secs(1) {
+            buckets
+                .buckets
+                .retain(|_, bucket| now.duration_since(bucket.updated) < Duration::from_secs(1));
+            buckets.pruned = now;
+        }
+
+        let mut refilled = Vec::with_capacity(needed.len());
+        for (method, count) in needed {
+            let capacity = self.requests_per_second(method) as f64 * multiplier as f64;
+            let tokens = match buckets
+                .buckets
+                .get(&(client.to_string(), method.to_string()))
+            {
+                Some(bucket) => {
+                    let elapsed = now.duration_since(bucket.updated).as_secs_f64();
+                    (bucket.tokens + elapsed * capacity).min(capacity)
+                }
+                None => capacity,
+            };
+            if tokens < count {
+                return false;
+            }
+            refilled.push((method, tokens - count));
+        }
+        for (method, tokens) in refilled {
+            buckets.buckets.insert(
+                (client.to_string(), method.to_string()),
+                Bucket {
+                    tokens,
+                    updated: now,
+                },
+            );
+        }
+        true
+    }
+
+    fn requests_per_second(&self, method: &str) -> u32 {
+        self.config
+            .method_requests_per_second
+            .get(method)
+            .copied()
+            .unwrap_or(self.config.requests_per_second)
+    }
+}
+
+struct InFlightGuard {
+    limiter: Arc<RateLimiter>,
+    client: String,
+}
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        let mut in_flight = self.limiter.in_flight.lock().unwrap();
+        if let Some(count) = in_flight.get_mut(&self.client) {
+            *count -= 1;
+            if *count == 0 {
+                in_flight.remove(&self.client);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    fn limiter(num_trusted_proxies: usize) -> RateLimiter {
+        RateLimiter::new(RpcRateLimitConfig {
+            requests_per_second: 1,
+            method_requests_per_second: BTreeMap::new(),
+            max_concurrent_requests: None,
+            api_keys: BTreeMap::from([("test-key".to_string(), 2)]),
+            api_key_header: "x-api-key".to_string(),
+            client_address_header: "x-forwarded-for".to_string(),
+            num_trusted_proxies,
+        })
+    }
+
+    fn request(peer: Option<&str>, headers: &[(&str, &str)]) -> Request<()> {
+        let mut builder = Request::builder();
+        for (name, value) in headers {
+            builder = builder.header(*name, *value);
+        }
+        let mut req = builder.body(()).unwrap();
+        if let Some(peer) = peer {
+            req.extensions_mut()
+                .insert(PeerAddress(peer.parse().unwrap()));
+        }
+        req
+    }
+
+    fn client(num_trusted_proxies: usize, req: &Request<()>) -> (String, u32) {
+        limiter(num_trusted_proxies).client(req)
+    }
+
+    #[test]
+    fn test_request_methods() {
+        let single = br#"{"jsonrpc":"2.0","id":1,"method":"sui_getObject","params":[]}"#;
+        assert_eq!(request_methods(single, true), vec!["sui_getObject"]);
+
+        let batch = br#"[
+            {"jsonrpc":"2.0","id":1,"method":"sui_getObject","params":[]},
+            {"jsonrpc":"2.0","id":2},
+            {"jsonrpc":"2.0","id":3,"method":"sui_getEvents","params":["0x1"]}
+        ]"#;
+        assert_eq!(
+            request_methods(batch, false),
+            vec!["sui_getObject", UNKNOWN_METHOD, "sui_getEvents"]
+        );
+    }
+
+    #[test]
+    fn test_client_by_peer_address() {
+        let peer = Some("10.0.0.1:40000");
+        assert_eq!(
+            client(0, &request(peer, &[])),
+            ("address:10.0.0.1".to_string(), 1)
+        );
+        // The forwarded address is made up by the client unless proxies are trusted.
+        assert_eq!(
+            client(0, &request(peer, &[("x-forwarded-for", "1.1.1.1")])),
+            ("address:10.0.0.1".to_string(), 1)
+        );
+        // Connections of a client differ in port only.
+        assert_eq!(
+            client(0, &request(Some("10.0.0.1:40001"), &[])),
+            ("address:10.0.0.1".to_string(), 1)
+        );
+        assert_eq!(
+            client(0, &request(None, &[])),
+            (UNKNOWN_CLIENT.to_string(), 1)
+        );
+    }
+
+    #[test]
+    fn test_client_by_forwarded_address() {
+        let peer = Some("10.0.0.1:40000");
+        assert_eq!(
+            client(1, &request(peer, &[("x-forwarded-for", "2.2.2.2")])),
+            ("address:2.2.2.2".to_string(), 1)
+        );
+        // Addresses ahead of those appended by the trusted proxies are ignored.
+        assert_eq!(
+            client(
+                1,
+                &request(peer, &[("x-forwarded-for", "1.1.1.1, 2.2.2.2")])
+            ),
+            ("address:2.2.2.2".to_string(), 1)
+        );
+        assert_eq!(
+            client(
+                2,
+                &request(peer, &[("x-forwarded-for", "1.1.1.1, 2.2.2.2, 3.3.3.3")])
+            ),
+            ("address:2.2.2.2".to_string(), 1)
+        );
+        // Proxies appending a header of their own.
+        assert_eq!(
+            client(
+                2,
+                &request(
+                    peer,
+                    &[
+                        ("x-forwarded-for", "1.1.1.1, 2.2.2.2"),
+                        ("x-forwarded-for", "3.3.3.3")
+                    ]
+                )
+            ),
+            ("address:2.2.2.2".to_string(), 1)
+        );
+        // Requests that didn't go through all the proxies.
+        assert_eq!(
+            client(2, &request(peer, &[("x-forwarded-for", "3.3.3.3")])),
+            ("address:10.0.0.1".to_string(), 1)
+        );
+        assert_eq!(
+            client(1, &request(peer, &[])),
+            ("address:10.0.0.1".to_string(), 1)
+        );
+    }
+
+    #[test]
+    fn test_client_by_api_key() {
+        let peer = Some("10.0.0.1:40000");
+        assert_eq!(
+            client(0, &request(peer, &[("x-api-key", "test-key")])),
+            ("key:test-key".to_string(), 2)
+        );
+        // Unknown keys are ignored.
+        assert_eq!(
+            client(
+                1,
+                &request(
+                    peer,
+                    &[("x-api-key", "other-key"), ("x-forwarded-for", "2.2.2.2")]
+                )
+            ),
+            ("address:2.2.2.2".to_string(), 1)
+        );
+    }
+}
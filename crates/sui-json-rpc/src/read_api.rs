@@ -4,6 +4,8 @@
 use anyhow::anyhow;
 use async_trait::async_trait;
 use jsonrpsee::core::RpcResult;
+use jsonrpsee::types::SubscriptionResult;
+use jsonrpsee::SubscriptionSink;
 use move_binary_format::normalized::{Module as NormalizedModule, Type};
 use move_core_types::identifier::Identifier;
 use move_core_types::language_storage::StructTag;
@@ -14,49 +16,279 @@ use sui_types::collection_types::VecMap;
 use sui_types::display::{DisplayCreatedEvent, DisplayObject};
 use sui_types::error::UserInputError;
 use sui_types::intent::{AppId, Intent, IntentMessage, IntentScope, IntentVersion};
-use tap::TapFallible;
+use tokio::sync::watch;
 
 use crate::api::ReadApiServer;
 use fastcrypto::encoding::Base64;
+use futures::{stream, StreamExt};
 use jsonrpsee::RpcModule;
 use sui_core::authority::AuthorityState;
 use sui_json_rpc_types::{
-    Checkpoint, CheckpointId, DynamicFieldPage, MoveFunctionArgType, ObjectValueKind, Page,
-    SuiEvent, SuiMoveNormalizedFunction, SuiMoveNormalizedModule, SuiMoveNormalizedStruct,
-    SuiMoveStruct, SuiMoveValue, SuiObjectDataOptions, SuiObjectInfo, SuiObjectResponse,
-    SuiPastObjectResponse, SuiTransactionEvents, SuiTransactionResponse, TransactionsPage,
+    Checkpoint, CheckpointId, CheckpointPage, DynamicFieldPage, MoveFunctionArgType, NodeInfo,
+    ObjectValueKind, OwnedObjectsCursor, OwnedObjectsPage, Page, ProtocolConfigResponse,
+    SuiCheckpointData, SuiCheckpointTransaction, SuiEvent, SuiGetPastObjectRequest,
+    SuiMoveNormalizedFunction, SuiMoveNormalizedModule, SuiMoveNormalizedStruct, SuiMoveStruct,
+    SuiMoveValue, SuiObjectData, SuiObjectDataOptions, SuiObjectHistoryEntry, SuiObjectInfo,
+    SuiObjectResponse, SuiPastObjectResponse, SuiTransactionEvents, SuiTransactionInclusionProof,
+    SuiTransactionResponse, SuiTransactionResponseOptions, SuiTransactionResult, TransactionsPage,
 };
 use sui_open_rpc::Module;
+use sui_protocol_config::{ProtocolConfig, ProtocolVersion, SupportedProtocolVersions};
+use sui_storage::archive::{
+    verify_object_written_by, ArchiveReader, ArchivedCheckpoint, ArchivedTransaction,
+};
 use sui_types::base_types::{
     ObjectID, SequenceNumber, SuiAddress, TransactionDigest, TxSequenceNumber,
 };
 use sui_types::crypto::sha3_hash;
-use sui_types::messages::{TransactionData, TransactionEffectsAPI};
-use sui_types::messages_checkpoint::CheckpointSequenceNumber;
+use sui_types::messages::{TransactionData, TransactionEffectsAPI, TransactionEvents};
+use sui_types::messages_checkpoint::{CheckpointSequenceNumber, TransactionInclusionProof};
 use sui_types::move_package::normalize_modules;
-use sui_types::object::{Data, Object, ObjectRead, PastObjectRead};
+use sui_types::object::{Data, Object, ObjectFormatOptions, ObjectRead, PastObjectRead};
+use sui_types::parse_sui_struct_tag;
 use sui_types::query::{EventQuery, TransactionQuery};
 
 use sui_types::dynamic_field::DynamicFieldName;
 use tracing::debug;
 
-use crate::api::cap_page_limit;
+use crate::api::{cap_page_limit, cap_page_size};
 use crate::error::Error;
+use crate::event_api::spawn_subscription;
 use crate::SuiRpcModule;
 
 use crate::api::QUERY_MAX_RESULT_LIMIT;
 
 const MAX_DISPLAY_NESTED_LEVEL: usize = 10;
 
+// Bound on the reads of a batch of transactions in flight at once.
+const MAX_CONCURRENT_TRANSACTION_READS: usize = 50;
+
 // An implementation of the read portion of the JSON-RPC interface intended for use in
 // Fullnodes.
 pub struct ReadApi {
     pub state: Arc<AuthorityState>,
+    /// Consulted for checkpoints, transactions and object versions that have been pruned.
+    archive: Option<Arc<ArchiveReader>>,
+    build_info: BuildInfo,
+}
+
+/// The build of the node binary serving the API.
+#[derive(Clone, Copy, Debug)]
+pub struct BuildInfo {
+    pub version: &'static str,
+    pub git_revision: &'static str,
+    pub supported_protocol_versions: SupportedProtocolVersions,
 }
 
 impl ReadApi {
-    pub fn new(state: Arc<AuthorityState>) -> Self {
-        Self { state }
+    pub fn new(
+        state: Arc<AuthorityState>,
+        archive: Option<Arc<ArchiveReader>>,
+        build_info: BuildInfo,
+    ) -> Self {
+        Self {
+            state,
+            archive,
+            build_info,
+        }
+    }
+
+    /// Reads a checkpoint from the archive, verified against the committee of its epoch.
+    async fn get_verified_archived_checkpoint(
+        archive: &ArchiveReader,
+        state: &AuthorityState,
+        seq: CheckpointSequenceNumber,
+    ) -> Result<Option<ArchivedCheckpoint>, Error> {
+        let Some(checkpoint) = archive.get_checkpoint(seq).await? else {
+            return Ok(None);
+        };
+        let epoch = checkpoint.summary.summary.epoch;
+        let committee = state
+            .committee_store()
+            .get_committee(&epoch)?
+            .ok_or_else(|| anyhow!("Committee of epoch {epoch} not found"))?;
+        checkpoint.verify(&committee)?;
+        Ok(Some(checkpoint))
+    }
+
+    /// Reads a transaction from the archive, verified to be included in its archived checkpoint.
+    async fn get_verified_archived_transaction(
+        archive: &ArchiveReader,
+        state: &AuthorityState,
+        digest: TransactionDigest,
+    ) -> Result<Option<ArchivedTransaction>, Error> {
+        let Some(transaction) = archive.get_transaction(digest).await? else {
+            return Ok(None);
+        };
+        let Some((seq, _)) = transaction.checkpoint else {
+            return Err(anyhow!("Archived transaction {digest} is not part of a checkpoint").into());
+        };
+        let checkpoint = Self::get_verified_archived_checkpoint(archive, state, seq)
+            .await?
+            .ok_or_else(|| anyhow!("Checkpoint {seq} of transaction {digest} is not archived"))?;
+        transaction.verify_inclusion(&checkpoint)?;
+        Ok(Some(transaction))
+    }
+
+    async fn get_archived_checkpoint(&self, id: CheckpointId) -> Result<Option<Checkpoint>, Error> {
+        // The archive is indexed by sequence number only.
+        let (Some(archive), CheckpointId::SequenceNumber(seq)) = (&self.archive, id) else {
+            return Ok(None);
+        };
+        let Some(checkpoint) =
+            Self::get_verified_archived_checkpoint(archive, &self.state, seq).await?
+        else {
+            return Ok(None);
+        };
+        Ok(Some(
+            (checkpoint.summary.summary, checkpoint.contents).into(),
+        ))
+    }
+
+    async fn get_archived_transaction(
+        &self,
+        digest: TransactionDigest,
+    ) -> RpcResult<Option<SuiTransactionResponse>> {
+        let Some(archive) = &self.archive else {
+            return Ok(None);
+        };
+        let ArchivedTransaction {
+            transaction,
+            effects,
+            events,
+            checkpoint,
+        } = match Self::get_verified_archived_transaction(archive, &self.state, digest).await? {
+            Some(archived) => archived,
+            None => return Ok(None),
+        };
+        Ok(Some(SuiTransactionResponse {
+            transaction: transaction.into_data().try_into()?,
+            effects: effects.try_into()?,
+            events: SuiTransactionEvents::try_from(
+                events,
+                self.state
+                    .load_epoch_store_one_call_per_task()
+                    .module_cache()
+                    .as_ref(),
+            )?,
+            timestamp_ms: checkpoint.map(|(_, timestamp_ms)| timestamp_ms),
+            confirmed_local_execution: None,
+            checkpoint: checkpoint.map(|(sequence_number, _)| sequence_number),
+        }))
+    }
+
+    /// Reads the parts of a transaction asked for by `options`, from the archive if it has
+    /// been pruned from the store.
+    async fn get_transaction_result(
+        &self,
+        digest: TransactionDigest,
+        options: &SuiTransactionResponseOptions,
+    ) -> RpcResult<SuiTransactionResult> {
+        let (transaction, effects) = match self
+            .state
+            .get_executed_transaction_and_effects(digest)
+            .await
+        {
+            Ok(result) => result,
+            Err(err) => {
+                debug!(tx_digest=?digest, "Failed to get transaction: {:?}", err);
+                return match self.get_archived_transaction(digest).await? {
+                    Some(response) => Ok(SuiTransactionResult::new(response, options)),
+                    None => Err(err.into()),
+                };
+            }
+        };
+        let checkpoint = self
+            .state
+            .get_transaction_checkpoint(&digest)
+            .map_err(|e| anyhow!("{e}"))?;
+
+        let events = match effects.events_digest() {
+            Some(events_digest) if options.show_events => {
+                let events = self
+                    .state
+                    .get_transaction_events(*events_digest)
+                    .await
+                    .map_err(Error::from)?;
+                Some(SuiTransactionEvents::try_from(
+                    events,
+                    self.state
+                        .load_epoch_store_one_call_per_task()
+                        .module_cache()
+                        .as_ref(),
+                )?)
+            }
+            None if options.show_events => Some(SuiTransactionEvents::default()),
+            _ => None,
+        };
+
+        let raw_transaction = if options.show_raw_input {
+            Some(bcs::to_bytes(transaction.data()).map_err(|e| anyhow!("{e}"))?)
+        } else {
+            None
+        };
+        Ok(SuiTransactionResult {
+            digest,
+            transaction: if options.show_input {
+                Some(transaction.into_message().try_into()?)
+            } else {
+                None
+            },
+            raw_transaction,
+            effects: if options.show_effects {
+                Some(effects.try_into()?)
+            } else {
+                None
+            },
+            events,
+            timestamp_ms: checkpoint.as_ref().map(|c| c.summary.timestamp_ms),
+            checkpoint: checkpoint.map(|c| c.summary.sequence_number),
+            error: None,
+        })
+    }
+
+    async fn get_archived_object(
+        &self,
+        object_id: ObjectID,
+        version: SequenceNumber,
+    ) -> RpcResult<Option<PastObjectRead>> {
+        let Some(archive) = &self.archive else {
+            return Ok(None);
+        };
+        let object = match archive
+            .get_object(object_id, version)
+            .await
+            .map_err(Error::from)?
+        {
+            Some(object) => object,
+            None => return Ok(None),
+        };
+        let previous_transaction = object.previous_transaction;
+        let transaction =
+            Self::get_verified_archived_transaction(archive, &self.state, previous_transaction)
+                .await?
+                .ok_or_else(|| {
+                    anyhow!(
+                        "Transaction {previous_transaction} that wrote archived object \
+                         {object_id} is not archived"
+                    )
+                })
+                .map_err(Error::from)?;
+        verify_object_written_by(&object, &transaction).map_err(Error::from)?;
+        let layout = object
+            .get_layout(
+                ObjectFormatOptions::default(),
+                self.state
+                    .load_epoch_store_one_call_per_task()
+                    .module_cache()
+                    .as_ref(),
+            )
+            .map_err(Error::from)?;
+        Ok(Some(PastObjectRead::VersionFound(
+            object.compute_object_reference(),
+            object,
+            layout,
+        )))
     }
 
     fn get_checkpoint_internal(&self, id: CheckpointId) -> Result<Checkpoint, Error> {
@@ -90,6 +322,48 @@ impl ReadApiServer for ReadApi {
             .collect())
     }
 
+    async fn get_owned_objects(
+        &self,
+        address: SuiAddress,
+        cursor: Option<OwnedObjectsCursor>,
+        limit: Option<usize>,
+        object_type: Option<String>,
+    ) -> RpcResult<OwnedObjectsPage> {
+        let limit = cap_page_limit(limit);
+        let object_type = object_type
+            .map(|type_| parse_sui_struct_tag(&type_))
+            .transpose()?;
+        let (snapshot, cursor) = match cursor {
+            Some(OwnedObjectsCursor {
+                checkpoint,
+                object_id,
+            }) => (checkpoint, object_id),
+            None => (self.state.get_owner_objects_snapshot()?, ObjectID::ZERO),
+        };
+        let mut data = self.state.get_owner_objects_at_snapshot(
+            address,
+            object_type.as_ref(),
+            snapshot,
+            cursor,
+            limit + 1,
+        )?;
+        let next_cursor = data.get(limit).map(|info| OwnedObjectsCursor {
+            checkpoint: snapshot,
+            object_id: info.object_id,
+        });
+        data.truncate(limit);
+        let mut data: Vec<SuiObjectInfo> = data.into_iter().map(SuiObjectInfo::from).collect();
+        let next_cursor = cap_page_size(&mut data, next_cursor, |info| OwnedObjectsCursor {
+            checkpoint: snapshot,
+            object_id: info.object_id,
+        });
+        Ok(OwnedObjectsPage {
+            data,
+            has_next_page: next_cursor.is_some(),
+            next_cursor,
+        })
+    }
+
     async fn get_dynamic_fields(
         &self,
         parent_object_id: ObjectID,
@@ -103,7 +377,12 @@ impl ReadApiServer for ReadApi {
             .map_err(|e| anyhow!("{e}"))?;
         let next_cursor = data.get(limit).map(|info| info.object_id);
         data.truncate(limit);
-        Ok(DynamicFieldPage { data, next_cursor })
+        let next_cursor = cap_page_size(&mut data, next_cursor, |info| info.object_id);
+        Ok(DynamicFieldPage {
+            data,
+            has_next_page: next_cursor.is_some(),
+            next_cursor,
+        })
     }
 
     async fn get_object_with_options(
@@ -139,11 +418,17 @@ impl ReadApiServer for ReadApi {
         version: SequenceNumber,
         options: Option<SuiObjectDataOptions>,
     ) -> RpcResult<SuiPastObjectResponse> {
-        let past_read = self
+        let mut past_read = self
             .state
             .get_past_object_read(&object_id, version)
             .await
             .map_err(|e| anyhow!("{e}"))?;
+        if let PastObjectRead::VersionNotFound(..) = past_read {
+            // The version may have been pruned.
+            if let Some(archived_read) = self.get_archived_object(object_id, version).await? {
+                past_read = archived_read;
+            }
+        }
         let options = options.unwrap_or_default();
         match past_read {
             PastObjectRead::ObjectNotExists(id) => Ok(SuiPastObjectResponse::ObjectNotExists(id)),
@@ -175,6 +460,77 @@ impl ReadApiServer for ReadApi {
         }
     }
 
+    async fn try_multi_get_past_objects(
+        &self,
+        past_objects: Vec<SuiGetPastObjectRequest>,
+        options: Option<SuiObjectDataOptions>,
+    ) -> RpcResult<Vec<SuiPastObjectResponse>> {
+        if past_objects.len() > QUERY_MAX_RESULT_LIMIT {
+            return Err(anyhow!(UserInputError::SizeLimitExceeded {
+                limit: "input limit".to_string(),
+                value: QUERY_MAX_RESULT_LIMIT.to_string()
+            })
+            .into());
+        }
+        let mut responses = Vec::with_capacity(past_objects.len());
+        for SuiGetPastObjectRequest { object_id, version } in past_objects {
+            responses.push(
+                self.try_get_past_object(object_id, version, options.clone())
+                    .await?,
+            );
+        }
+        Ok(responses)
+    }
+
+    async fn try_get_past_object_history(
+        &self,
+        object_id: ObjectID,
+        start_version: SequenceNumber,
+        end_version: SequenceNumber,
+        options: Option<SuiObjectDataOptions>,
+    ) -> RpcResult<Vec<SuiObjectHistoryEntry>> {
+        // The parent entries of an object list each of its versions, including those at which it
+        // was deleted or wrapped, and are kept when the objects themselves are pruned.
+        let object_refs: Vec<_> = self
+            .state
+            .get_parent_iterator(object_id, None)
+            .await
+            .map_err(Error::from)?
+            .map(|(object_ref, _)| object_ref)
+            .skip_while(|(_, version, _)| *version < start_version)
+            .take_while(|(_, version, _)| *version <= end_version)
+            .take(QUERY_MAX_RESULT_LIMIT)
+            .collect();
+
+        let mut history = Vec::with_capacity(object_refs.len());
+        for object_ref in object_refs {
+            let (_, version, digest) = object_ref;
+            if !digest.is_alive() {
+                history.push(SuiObjectHistoryEntry::ObjectDeleted(object_ref.into()));
+                continue;
+            }
+            let entry = match self
+                .try_get_past_object(object_id, version, options.clone())
+                .await?
+            {
+                SuiPastObjectResponse::VersionFound(object) => {
+                    SuiObjectHistoryEntry::VersionFound(object)
+                }
+                SuiPastObjectResponse::VersionNotFound(..) => {
+                    SuiObjectHistoryEntry::VersionPruned(object_id, version)
+                }
+                response => {
+                    return Err(anyhow!(
+                        "Object {object_id} at version {version} is in the parent entries, but reads as {response:?}"
+                    )
+                    .into())
+                }
+            };
+            history.push(entry);
+        }
+        Ok(history)
+    }
+
     async fn get_dynamic_field_object(
         &self,
         parent_object_id: ObjectID,
@@ -213,11 +569,20 @@ impl ReadApiServer for ReadApi {
         &self,
         digest: TransactionDigest,
     ) -> RpcResult<SuiTransactionResponse> {
-        let (transaction, effects) = self
+        let (transaction, effects) = match self
             .state
             .get_executed_transaction_and_effects(digest)
             .await
-            .tap_err(|err| debug!(tx_digest=?digest, "Failed to get transaction: {:?}", err))?;
+        {
+            Ok(result) => result,
+            Err(err) => {
+                debug!(tx_digest=?digest, "Failed to get transaction: {:?}", err);
+                if let Some(response) = self.get_archived_transaction(digest).await? {
+                    return Ok(response);
+                }
+                return Err(err.into());
+            }
+        };
         let checkpoint = self
             .state
             .get_transaction_checkpoint(&digest)
@@ -268,11 +633,23 @@ impl ReadApiServer for ReadApi {
                 .collect();
             tx_digests.dedup();
 
-            let txn_batch = self
-                .state
-                .multi_get_transactions(&tx_digests)
-                .await
-                .tap_err(|err| debug!(txs_digests=?tx_digests, "Failed to get batch: {:?}", err))?;
+            let txn_batch = match self.state.multi_get_transactions(&tx_digests).await {
+                Ok(txn_batch) => txn_batch,
+                Err(err) if self.archive.is_some() => {
+                    debug!(txs_digests=?tx_digests, "Failed to get batch: {:?}", err);
+                    // Some of the transactions may have been pruned, look them up one by one
+                    // to fill the gaps from the archive.
+                    let mut responses = Vec::with_capacity(tx_digests.len());
+                    for digest in tx_digests {
+                        responses.push(self.get_transaction(digest).await?);
+                    }
+                    return Ok(responses);
+                }
+                Err(err) => {
+                    debug!(txs_digests=?tx_digests, "Failed to get batch: {:?}", err);
+                    return Err(err.into());
+                }
+            };
 
             let mut responses: Vec<SuiTransactionResponse> = Vec::new();
             for (txn, digest) in txn_batch.into_iter().zip(tx_digests.iter()) {
@@ -307,6 +684,33 @@ impl ReadApiServer for ReadApi {
         }
     }
 
+    async fn multi_get_transactions_with_options(
+        &self,
+        digests: Vec<TransactionDigest>,
+        options: Option<SuiTransactionResponseOptions>,
+    ) -> RpcResult<Vec<SuiTransactionResult>> {
+        if digests.len() > QUERY_MAX_RESULT_LIMIT {
+            return Err(anyhow!(UserInputError::SizeLimitExceeded {
+                limit: "input limit".to_string(),
+                value: QUERY_MAX_RESULT_LIMIT.to_string()
+            })
+            .into());
+        }
+        let options = options.unwrap_or_default();
+        Ok(stream::iter(digests)
+            .map(|digest| {
+                let options = &options;
+                async move {
+                    self.get_transaction_result(digest, options)
+                        .await
+                        .unwrap_or_else(|e| SuiTransactionResult::error(digest, e.to_string()))
+                }
+            })
+            .buffered(MAX_CONCURRENT_TRANSACTION_READS)
+            .collect::<Vec<_>>()
+            .await)
+    }
+
     async fn get_normalized_move_modules_by_package(
         &self,
         package: ObjectID,
@@ -432,7 +836,12 @@ impl ReadApiServer for ReadApi {
         // extract next cursor
         let next_cursor = data.get(limit).cloned();
         data.truncate(limit);
-        Ok(Page { data, next_cursor })
+        let next_cursor = cap_page_size(&mut data, next_cursor, |digest| *digest);
+        Ok(Page {
+            data,
+            has_next_page: next_cursor.is_some(),
+            next_cursor,
+        })
     }
 
     async fn get_latest_checkpoint_sequence_number(&self) -> RpcResult<CheckpointSequenceNumber> {
@@ -445,8 +854,276 @@ impl ReadApiServer for ReadApi {
     }
 
     async fn get_checkpoint(&self, id: CheckpointId) -> RpcResult<Checkpoint> {
-        Ok(self.get_checkpoint_internal(id)?)
+        // Checkpoints below the pruning watermark are only in the archive.
+        if let CheckpointId::SequenceNumber(seq) = id {
+            let lowest_available = self
+                .state
+                .get_checkpoint_store()
+                .get_lowest_available_checkpoint()
+                .map_err(|e| anyhow!("{e}"))?;
+            if seq < lowest_available {
+                if let Some(checkpoint) = self.get_archived_checkpoint(id.clone()).await? {
+                    return Ok(checkpoint);
+                }
+            }
+        }
+        match self.get_checkpoint_internal(id.clone()) {
+            Ok(checkpoint) => Ok(checkpoint),
+            Err(err) => match self.get_archived_checkpoint(id).await? {
+                Some(checkpoint) => Ok(checkpoint),
+                None => Err(err.into()),
+            },
+        }
+    }
+
+    async fn get_checkpoints(
+        &self,
+        start: Option<CheckpointSequenceNumber>,
+        end: Option<CheckpointSequenceNumber>,
+        cursor: Option<CheckpointSequenceNumber>,
+        limit: Option<usize>,
+        descending_order: Option<bool>,
+        include_transactions: Option<bool>,
+    ) -> RpcResult<CheckpointPage> {
+        let limit = cap_page_limit(limit);
+        let descending = descending_order.unwrap_or_default();
+        let include_transactions = include_transactions.unwrap_or(true);
+
+        let latest = self
+            .state
+            .get_latest_checkpoint_sequence_number()
+            .map_err(|e| {
+                anyhow!("Latest checkpoint sequence number was not found with error :{e}")
+            })?;
+        let start = start.unwrap_or_default();
+        let end = end.map_or(latest, |end| end.min(latest));
+        if start > end {
+            return Ok(Page {
+                data: vec![],
+                next_cursor: None,
+                has_next_page: false,
+            });
+        }
+        if matches!(cursor, Some(cursor) if cursor < start || cursor > end) {
+            return Err(
+                anyhow!("Cursor {cursor:?} is outside of the range [{start}, {end}]").into(),
+            );
+        }
+
+        // Retrieve 1 extra item for next cursor
+        let sequence_numbers: Vec<_> = if descending {
+            (start..=cursor.unwrap_or(end))
+                .rev()
+                .take(limit + 1)
+                .collect()
+        } else {
+            (cursor.unwrap_or(start)..=end).take(limit + 1).collect()
+        };
+        let next_cursor = sequence_numbers.get(limit).cloned();
+
+        let mut data = Vec::with_capacity(limit);
+        for seq in sequence_numbers.into_iter().take(limit) {
+            let mut checkpoint = self.get_checkpoint(seq.into()).await?;
+            if !include_transactions {
+                checkpoint.transactions.clear();
+            }
+            data.push(checkpoint);
+        }
+        let next_cursor = cap_page_size(&mut data, next_cursor, |c| c.sequence_number);
+        Ok(Page {
+            data,
+            has_next_page: next_cursor.is_some(),
+            next_cursor,
+        })
+    }
+
+    async fn get_transaction_inclusion_proof(
+        &self,
+        digest: TransactionDigest,
+    ) -> RpcResult<SuiTransactionInclusionProof> {
+        let (_, effects) = self
+            .state
+            .get_executed_transaction_and_effects(digest)
+            .await?;
+        let checkpoint = self
+            .state
+            .get_transaction_checkpoint(&digest)
+            .map_err(|e| anyhow!("{e}"))?
+            .ok_or_else(|| anyhow!("Transaction {digest} is not in a checkpoint yet"))?
+            .into_inner();
+        let contents = self
+            .state
+            .get_checkpoint_contents(checkpoint.content_digest())?;
+        let sequence_number = checkpoint.sequence_number();
+        let proof =
+            TransactionInclusionProof::new(checkpoint, contents, &effects.execution_digests())
+                .ok_or_else(|| {
+                    anyhow!("Transaction {digest} is not in checkpoint {sequence_number}")
+                })?;
+        Ok(SuiTransactionInclusionProof {
+            digest,
+            checkpoint: sequence_number,
+            proof: bcs::to_bytes(&proof).map_err(|e| anyhow!("{e}"))?,
+        })
+    }
+
+    fn subscribe_checkpoint_data(
+        &self,
+        sink: SubscriptionSink,
+        start: Option<CheckpointSequenceNumber>,
+    ) -> SubscriptionResult {
+        let start = start.unwrap_or_else(|| {
+            self.state
+                .get_latest_checkpoint_sequence_number()
+                .map_or(0, |latest| latest + 1)
+        });
+        let state = self.state.clone();
+        let highest_executed = self.state.subscribe_latest_checkpoint_sequence_number();
+        // Checkpoints are read one at a time as the subscriber takes them, so a slow subscriber
+        // falls behind the node rather than losing checkpoints.
+        let stream = stream::try_unfold(
+            (start, highest_executed),
+            move |(seq, mut highest_executed)| {
+                let state = state.clone();
+                async move {
+                    if !wait_for_checkpoint(&mut highest_executed, seq).await {
+                        // The node is shutting down.
+                        return Ok(None);
+                    }
+                    let data = get_checkpoint_data(&state, seq).await?;
+                    Ok::<_, Error>(Some((data, (seq + 1, highest_executed))))
+                }
+            },
+        );
+        spawn_subscription(sink, Box::pin(stream));
+        Ok(())
+    }
+
+    async fn get_node_info(&self) -> RpcResult<NodeInfo> {
+        let epoch_store = self.state.load_epoch_store_one_call_per_task();
+        let BuildInfo {
+            version,
+            git_revision,
+            supported_protocol_versions,
+        } = self.build_info;
+        Ok(NodeInfo {
+            version: version.to_string(),
+            git_revision: git_revision.to_string(),
+            min_supported_protocol_version: supported_protocol_versions.min.as_u64(),
+            max_supported_protocol_version: supported_protocol_versions.max.as_u64(),
+            protocol_version: epoch_store.protocol_version().as_u64(),
+            epoch: epoch_store.epoch(),
+        })
+    }
+
+    async fn get_protocol_config(&self, version: Option<u64>) -> RpcResult<ProtocolConfigResponse> {
+        let epoch_store = self.state.load_epoch_store_one_call_per_task();
+        let supported_versions = self.build_info.supported_protocol_versions;
+        let config = match version.map(ProtocolVersion::new) {
+            // The config of the current epoch is the one the node runs with.
+            Some(version) if version != epoch_store.protocol_version() => {
+                if !supported_versions.is_version_supported(version) {
+                    return Err(anyhow!(
+                        "Protocol version {} is not supported by the node, which supports versions {} to {}",
+                        version.as_u64(),
+                        supported_versions.min.as_u64(),
+                        supported_versions.max.as_u64()
+                    )
+                    .into());
+                }
+                ProtocolConfig::get_for_version(version)
+            }
+            _ => epoch_store.protocol_config().clone(),
+        };
+        Ok(ProtocolConfigResponse::new(&config, supported_versions))
+    }
+}
+
+/// Waits for the node to execute the checkpoint `seq`, and returns whether it did before the
+/// checkpoint store was dropped.
+async fn wait_for_checkpoint(
+    highest_executed: &mut watch::Receiver<Option<CheckpointSequenceNumber>>,
+    seq: CheckpointSequenceNumber,
+) -> bool {
+    loop {
+        if matches!(*highest_executed.borrow_and_update(), Some(latest) if latest >= seq) {
+            return true;
+        }
+        if highest_executed.changed().await.is_err() {
+            return false;
+        }
+    }
+}
+
+/// The data of the executed checkpoint `seq` and all of its transactions.
+async fn get_checkpoint_data(
+    state: &AuthorityState,
+    seq: CheckpointSequenceNumber,
+) -> Result<SuiCheckpointData, Error> {
+    let summary = state.get_checkpoint_summary_by_sequence_number(seq)?;
+    let contents = state.get_checkpoint_contents(summary.content_digest)?;
+    let checkpoint = Checkpoint::from((summary, contents));
+
+    let mut transactions = Vec::with_capacity(checkpoint.transactions.len());
+    for digest in &checkpoint.transactions {
+        let (transaction, effects) = state.get_executed_transaction_and_effects(*digest).await?;
+        let events = match effects.events_digest() {
+            Some(digest) => state.get_transaction_events(*digest).await?,
+            None => TransactionEvents::default(),
+        };
+        let input_objects =
+            get_objects_at_versions(state, effects.modified_at_versions().iter().copied()).await?;
+        let output_objects = get_objects_at_versions(
+            state,
+            effects
+                .created()
+                .iter()
+                .chain(effects.mutated())
+                .chain(effects.unwrapped())
+                .map(|((id, version, _), _)| (*id, *version)),
+        )
+        .await?;
+        transactions.push(SuiCheckpointTransaction {
+            transaction: transaction.into_message().try_into()?,
+            effects: effects.try_into()?,
+            events: SuiTransactionEvents::try_from(
+                events,
+                state
+                    .load_epoch_store_one_call_per_task()
+                    .module_cache()
+                    .as_ref(),
+            )?,
+            input_objects,
+            output_objects,
+        });
+    }
+    Ok(SuiCheckpointData {
+        checkpoint,
+        transactions,
+    })
+}
+
+async fn get_objects_at_versions(
+    state: &AuthorityState,
+    versions: impl Iterator<Item = (ObjectID, SequenceNumber)>,
+) -> Result<Vec<SuiObjectData>, Error> {
+    let mut objects = vec![];
+    for (id, version) in versions {
+        let PastObjectRead::VersionFound(object_ref, object, layout) =
+            state.get_past_object_read(&id, version).await? else {
+            return Err(anyhow!("Object {id} is not available at version {version}").into());
+        };
+        objects.push(
+            (
+                object_ref,
+                object,
+                layout,
+                SuiObjectDataOptions::full_content(),
+            )
+                .try_into()?,
+        );
     }
+    Ok(objects)
 }
 
 impl SuiRpcModule for ReadApi {
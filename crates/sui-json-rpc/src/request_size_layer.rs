@@ -0,0 +1,79 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::routing_layer::{is_json, read_request_body_up_to};
+use hyper::{Body, Method, Request, Response};
+use std::error::Error;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tower::{Layer, Service};
+
+/// Turns away requests with bodies larger than the maximum request size, before the layers
+/// inside it read them whole.
+#[derive(Clone)]
+pub struct RequestSizeLayer {
+    max_size: u32,
+}
+
+impl RequestSizeLayer {
+    pub fn new(max_size: u32) -> Self {
+        Self { max_size }
+    }
+}
+
+impl<S> Layer<S> for RequestSizeLayer {
+    type Service = RequestSizeService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RequestSizeService {
+            inner,
+            max_size: self.max_size,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct RequestSizeService<S> {
+    inner: S,
+    max_size: u32,
+}
+
+impl<S> Service<Request<Body>> for RequestSizeService<S>
+where
+    S: Service<Request<Body>, Response = Response<Body>> + Clone + Send + 'static,
+    S::Response: 'static,
+    S::Error: Into<Box<dyn Error + Send + Sync>> + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = Box<dyn Error + Send + Sync + 'static>;
+    type Future =
+        Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send + 'static>>;
+
+    #[inline]
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx).map_err(Into::into)
+    }
+
+    fn call(&mut self, req: Request<Body>) -> Self::Future {
+        let clone = self.inner.clone();
+        let max_size = self.max_size;
+        // take the service that was ready
+        // https://docs.rs/tower/latest/tower/trait.Service.html#be-careful-when-cloning-inner-services
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        let res_fut = async move {
+            if !(req.method() == Method::POST && is_json(&req)) {
+                return inner.call(req).await.map_err(|err| err.into());
+            }
+            let (parts, body) = req.into_parts();
+            let (body, _) = match read_request_body_up_to(&parts.headers, body, max_size).await {
+                Ok(r) => r,
+                Err(response) => return Ok(response),
+            };
+            let req = Request::from_parts(parts, Body::from(body));
+            inner.call(req).await.map_err(|err| err.into())
+        };
+        Box::pin(res_fut)
+    }
+}
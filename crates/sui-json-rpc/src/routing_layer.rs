@@ -2,7 +2,8 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{CLIENT_TARGET_API_VERSION_HEADER, MAX_REQUEST_SIZE};
-use hyper::{http, Body, Method, Request, Response};
+use hyper::header::{self, HeaderValue};
+use hyper::{http, Body, HeaderMap, Method, Request, Response};
 use jsonrpsee::core::__reexports::serde_json;
 use jsonrpsee::core::error::GenericTransportError;
 use jsonrpsee::core::http_helpers::read_body;
@@ -11,6 +12,7 @@ use std::collections::{HashMap, HashSet};
 use std::error::Error;
 use std::future::Future;
 use std::pin::Pin;
+use std::sync::Arc;
 use std::task::{Context, Poll};
 use sui_open_rpc::MethodRouting;
 use tower::{Layer, Service};
@@ -18,13 +20,21 @@ use tower::{Layer, Service};
 #[derive(Debug, Clone)]
 pub struct RoutingLayer {
     routes: HashMap<String, MethodRouting>,
+    deprecated_methods: Arc<HashSet<String>>,
     disable_routing: bool,
 }
 
 impl RoutingLayer {
-    pub fn new(routes: HashMap<String, MethodRouting>, disable_routing: bool) -> Self {
+    /// Calls to `deprecated_methods` are answered with a warning, or rejected like the old
+    /// versions of methods are when routing is disabled.
+    pub fn new(
+        routes: HashMap<String, MethodRouting>,
+        deprecated_methods: HashSet<String>,
+        disable_routing: bool,
+    ) -> Self {
         Self {
             routes,
+            deprecated_methods: Arc::new(deprecated_methods),
             disable_routing,
         }
     }
@@ -34,7 +44,12 @@ impl<S> Layer<S> for RoutingLayer {
     type Service = RpcRoutingService<S>;
 
     fn layer(&self, inner: S) -> Self::Service {
-        RpcRoutingService::new(inner, self.routes.clone(), self.disable_routing)
+        RpcRoutingService::new(
+            inner,
+            self.routes.clone(),
+            self.deprecated_methods.clone(),
+            self.disable_routing,
+        )
     }
 }
 
@@ -43,16 +58,23 @@ pub struct RpcRoutingService<S> {
     inner: S,
     routes: HashMap<String, MethodRouting>,
     route_to_methods: HashSet<String>,
+    deprecated_methods: Arc<HashSet<String>>,
     disable_routing: bool,
 }
 
 impl<S> RpcRoutingService<S> {
-    pub fn new(inner: S, routes: HashMap<String, MethodRouting>, disable_routing: bool) -> Self {
+    pub fn new(
+        inner: S,
+        routes: HashMap<String, MethodRouting>,
+        deprecated_methods: Arc<HashSet<String>>,
+        disable_routing: bool,
+    ) -> Self {
         let route_to_methods = routes.values().map(|v| v.route_to.clone()).collect();
         Self {
             inner,
             routes,
             route_to_methods,
+            deprecated_methods,
             disable_routing,
         }
     }
@@ -79,6 +101,7 @@ where
         let clone = self.inner.clone();
         let routes = self.routes.clone();
         let route_to_methods = self.route_to_methods.clone();
+        let deprecated_methods = self.deprecated_methods.clone();
         let disable_routing = self.disable_routing;
         // take the service that was ready
         // https://docs.rs/tower/latest/tower/trait.Service.html#be-careful-when-cloning-inner-services
@@ -91,28 +114,22 @@ where
                 .as_ref()
                 .and_then(|h| h.to_str().ok().map(|s| s.to_string()));
 
+            let mut deprecated_calls = vec![];
             let req = if req.method() == Method::POST && is_json(&req) {
                 let (parts, body) = req.into_parts();
-                let (body, is_single) =
-                    // The body will be consumed if anything goes wrong here, returning error response if failed.
-                    match read_body(&parts.headers, body, MAX_REQUEST_SIZE).await {
-                        Ok(r) => r,
-                        Err(GenericTransportError::TooLarge) => {
-                            return Ok(response::too_large(MAX_REQUEST_SIZE))
-                        }
-                        Err(GenericTransportError::Malformed) => return Ok(response::malformed()),
-                        Err(GenericTransportError::Inner(e)) => {
-                            tracing::error!("Internal error reading request body: {}", e);
-                            return Ok(response::internal_error());
-                        }
-                    };
+                let (body, is_single) = match read_request_body(&parts.headers, body).await {
+                    Ok(r) => r,
+                    Err(response) => return Ok(response),
+                };
                 let body = if is_single {
                     process_single_request(
                         &body,
                         &version,
                         &routes,
                         &route_to_methods,
+                        &deprecated_methods,
                         disable_routing,
+                        &mut deprecated_calls,
                     )
                 } else {
                     process_batched_requests(
@@ -120,33 +137,78 @@ where
                         &version,
                         &routes,
                         &route_to_methods,
+                        &deprecated_methods,
                         disable_routing,
+                        &mut deprecated_calls,
                     )
                 };
                 Request::from_parts(parts, Body::from(body))
             } else {
                 req
             };
-            inner.call(req).await.map_err(|err| err.into())
+            let mut response = inner.call(req).await.map_err(|err| err.into())?;
+            for method in deprecated_calls {
+                if let Ok(warning) = HeaderValue::from_str(&format!(
+                    "299 - \"Method {method} is deprecated and will be removed\""
+                )) {
+                    response.headers_mut().append(header::WARNING, warning);
+                }
+            }
+            Ok(response)
         };
         Box::pin(res_fut)
     }
 }
 
+/// Reads the body of a JSON-RPC request, and whether it holds a single call rather than a batch.
+/// The body is consumed if anything goes wrong, and the error response to answer with is returned.
+pub(crate) async fn read_request_body(
+    headers: &HeaderMap,
+    body: Body,
+) -> Result<(Vec<u8>, bool), Response<Body>> {
+    read_request_body_up_to(headers, body, MAX_REQUEST_SIZE).await
+}
+
+/// Like [read_request_body], turning away bodies larger than `max_size` bytes.
+pub(crate) async fn read_request_body_up_to(
+    headers: &HeaderMap,
+    body: Body,
+    max_size: u32,
+) -> Result<(Vec<u8>, bool), Response<Body>> {
+    match read_body(headers, body, max_size).await {
+        Ok(r) => Ok(r),
+        Err(GenericTransportError::TooLarge) => Err(response::too_large(max_size)),
+        Err(GenericTransportError::Malformed) => Err(response::malformed()),
+        Err(GenericTransportError::Inner(e)) => {
+            tracing::error!("Internal error reading request body: {}", e);
+            Err(response::internal_error())
+        }
+    }
+}
+
 fn process_batched_requests(
     body: &[u8],
     version: &Option<String>,
     routes: &HashMap<String, MethodRouting>,
     route_to_methods: &HashSet<String>,
+    deprecated_methods: &HashSet<String>,
     disable_routing: bool,
+    deprecated_calls: &mut Vec<String>,
 ) -> Vec<u8> {
     let Ok(requests) = serde_json::from_slice::<Vec<&[u8]>>(body) else{
         return body.to_vec();
     };
     let mut processed_reqs = Vec::new();
     for request in requests {
-        let req =
-            process_single_request(request, version, routes, route_to_methods, disable_routing);
+        let req = process_single_request(
+            request,
+            version,
+            routes,
+            route_to_methods,
+            deprecated_methods,
+            disable_routing,
+            deprecated_calls,
+        );
         processed_reqs.push(req);
     }
     if let Ok(request) = serde_json::to_vec(&processed_reqs) {
@@ -157,12 +219,15 @@ fn process_batched_requests(
 }
 
 // try to process the rpc request, return the original values if fail to parse the request.
+// Calls to deprecated methods that are let through are added to `deprecated_calls`.
 fn process_single_request(
     body: &[u8],
     version: &Option<String>,
     routes: &HashMap<String, MethodRouting>,
     route_to_methods: &HashSet<String>,
+    deprecated_methods: &HashSet<String>,
     disable_routing: bool,
+    deprecated_calls: &mut Vec<String>,
 ) -> Vec<u8> {
     let Ok(mut request) = serde_json::from_slice::<RpcRequest>(body) else{
         return body.to_vec();
@@ -170,11 +235,16 @@ fn process_single_request(
 
     let mut modified = false;
 
-    // Reject direct access to the old methods
-    if route_to_methods.contains(request.method.as_ref()) {
+    // Reject direct access to the old methods, and to deprecated methods along with them when
+    // routing is disabled.
+    let is_deprecated = deprecated_methods.contains(request.method.as_ref());
+    if route_to_methods.contains(request.method.as_ref()) || (is_deprecated && disable_routing) {
         request.method = "INVALID_ROUTING".into();
         modified = true;
     } else {
+        if is_deprecated {
+            deprecated_calls.push(request.method.to_string());
+        }
         // Modify the method name if routing is enabled
         if !disable_routing {
             if let Some(version) = version {
@@ -200,12 +270,25 @@ fn process_single_request(
 }
 
 // error responses borrowed from jsonrpsee
-mod response {
+pub(crate) mod response {
     use jsonrpsee::core::__reexports::serde_json;
-    use jsonrpsee::types::error::{reject_too_big_request, ErrorCode};
+    use jsonrpsee::types::error::{reject_too_big_request, ErrorCode, ErrorObject};
     use jsonrpsee::types::{ErrorResponse, Id};
     const JSON: &str = "application/json; charset=utf-8";
 
+    /// Error code of the responses to requests over the rate limits of their client.
+    pub(crate) const TOO_MANY_REQUESTS_CODE: i32 = -32029;
+    /// Error code of the responses to requests whose consistency token the node did not reach in
+    /// time.
+    pub(crate) const BEHIND_CONSISTENCY_TOKEN_CODE: i32 = -32030;
+    /// Error code of the responses to requests without the auth token the server requires.
+    pub(crate) const UNAUTHORIZED_CODE: i32 = -32031;
+    /// Error code of the responses to requests from web pages, on servers that don't allow them.
+    pub(crate) const FORBIDDEN_ORIGIN_CODE: i32 = -32032;
+    /// Error code of the responses to requests for pages the indexer issued the cursor of, when
+    /// it fails to answer them.
+    pub(crate) const INDEXER_UNAVAILABLE_CODE: i32 = -32033;
+
     pub(crate) fn too_large(limit: u32) -> hyper::Response<hyper::Body> {
         let error = serde_json::to_string(&ErrorResponse::borrowed(
             reject_too_big_request(limit),
@@ -235,6 +318,104 @@ mod response {
         from_template(hyper::StatusCode::BAD_REQUEST, error, JSON)
     }
 
+    pub(crate) fn too_many_requests() -> hyper::Response<hyper::Body> {
+        let error = serde_json::to_string(&ErrorResponse::borrowed(
+            ErrorObject::borrowed(TOO_MANY_REQUESTS_CODE, &"Too many requests", None),
+            Id::Null,
+        ))
+        .expect("built from known-good data; qed");
+
+        let mut response = from_template(hyper::StatusCode::TOO_MANY_REQUESTS, error, JSON);
+        response.headers_mut().insert(
+            hyper::header::RETRY_AFTER,
+            hyper::header::HeaderValue::from_static("1"),
+        );
+        response
+    }
+
+    pub(crate) fn invalid_consistency_token() -> hyper::Response<hyper::Body> {
+        let error = serde_json::to_string(&ErrorResponse::borrowed(
+            ErrorObject::borrowed(
+                ErrorCode::InvalidRequest.code(),
+                &"Invalid consistency token, expected a checkpoint sequence number",
+                None,
+            ),
+            Id::Null,
+        ))
+        .expect("built from known-good data; qed");
+
+        from_template(hyper::StatusCode::BAD_REQUEST, error, JSON)
+    }
+
+    pub(crate) fn behind_consistency_token(token: u64) -> hyper::Response<hyper::Body> {
+        let message = format!("The node has not caught up to checkpoint {token} yet");
+        let error = serde_json::to_string(&ErrorResponse::borrowed(
+            ErrorObject::borrowed(BEHIND_CONSISTENCY_TOKEN_CODE, &message, None),
+            Id::Null,
+        ))
+        .expect("built from known-good data; qed");
+
+        let mut response = from_template(hyper::StatusCode::SERVICE_UNAVAILABLE, error, JSON);
+        response.headers_mut().insert(
+            hyper::header::RETRY_AFTER,
+            hyper::header::HeaderValue::from_static("1"),
+        );
+        response
+    }
+
+    pub(crate) fn unauthorized() -> hyper::Response<hyper::Body> {
+        let error = serde_json::to_string(&ErrorResponse::borrowed(
+            ErrorObject::borrowed(UNAUTHORIZED_CODE, &"Invalid auth token", None),
+            Id::Null,
+        ))
+        .expect("built from known-good data; qed");
+
+        let mut response = from_template(hyper::StatusCode::UNAUTHORIZED, error, JSON);
+        response.headers_mut().insert(
+            hyper::header::WWW_AUTHENTICATE,
+            hyper::header::HeaderValue::from_static("Bearer"),
+        );
+        response
+    }
+
+    pub(crate) fn forbidden_origin() -> hyper::Response<hyper::Body> {
+        let error = serde_json::to_string(&ErrorResponse::borrowed(
+            ErrorObject::borrowed(
+                FORBIDDEN_ORIGIN_CODE,
+                &"Requests from web pages are not allowed",
+                None,
+            ),
+            Id::Null,
+        ))
+        .expect("built from known-good data; qed");
+
+        from_template(hyper::StatusCode::FORBIDDEN, error, JSON)
+    }
+
+    pub(crate) fn indexer_unavailable() -> hyper::Response<hyper::Body> {
+        let error = serde_json::to_string(&ErrorResponse::borrowed(
+            ErrorObject::borrowed(
+                INDEXER_UNAVAILABLE_CODE,
+                &"The indexer that issued the cursor is unavailable",
+                None,
+            ),
+            Id::Null,
+        ))
+        .expect("built from known-good data; qed");
+
+        let mut response = from_template(hyper::StatusCode::SERVICE_UNAVAILABLE, error, JSON);
+        response.headers_mut().insert(
+            hyper::header::RETRY_AFTER,
+            hyper::header::HeaderValue::from_static("1"),
+        );
+        response
+    }
+
+    /// A response to calls answered without reaching the server, such as those with invalid params.
+    pub(crate) fn ok(body: String) -> hyper::Response<hyper::Body> {
+        from_template(hyper::StatusCode::OK, body, JSON)
+    }
+
     fn from_template<S: Into<hyper::Body>>(
         status: hyper::StatusCode,
         body: S,
@@ -12,21 +12,28 @@ use jsonrpsee::core::RpcResult;
 use jsonrpsee::RpcModule;
 use mysten_metrics::spawn_monitored_task;
 use std::sync::Arc;
+use std::time::Duration;
 use sui_core::authority::AuthorityState;
 use sui_core::authority_client::NetworkAuthorityClient;
 use sui_core::transaction_orchestrator::TransactiondOrchestrator;
 use sui_json_rpc_types::{
-    DevInspectResults, DryRunTransactionResponse, SuiTransactionEvents, SuiTransactionResponse,
+    DevInspectArgs, DevInspectResults, DryRunTransactionResponse, GasEstimate,
+    SuiTransactionEvents, SuiTransactionResponse,
 };
 use sui_open_rpc::Module;
-use sui_types::base_types::{EpochId, SuiAddress};
+use sui_types::base_types::{EpochId, SuiAddress, TransactionDigest};
 use sui_types::intent::Intent;
 use sui_types::messages::{
     ExecuteTransactionRequest, ExecuteTransactionRequestType, TransactionKind,
 };
-use sui_types::messages::{ExecuteTransactionResponse, Transaction};
+use sui_types::messages::{ExecuteTransactionResponse, Transaction, TransactionEffectsAPI};
+use sui_types::messages_checkpoint::VerifiedCheckpoint;
 use sui_types::signature::GenericSignature;
 
+/// How long the checkpoint of a transaction executed locally is waited for, so that the response
+/// carries the checkpoint for clients to pass as a consistency token to subsequent reads.
+const CHECKPOINT_WAIT_TIMEOUT: Duration = Duration::from_secs(5);
+
 pub struct TransactionExecutionApi {
     state: Arc<AuthorityState>,
     transaction_orchestrator: Arc<TransactiondOrchestrator<NetworkAuthorityClient>>,
@@ -42,6 +49,25 @@ impl TransactionExecutionApi {
             transaction_orchestrator,
         }
     }
+
+    /// Waits for the node to execute the checkpoint including the transaction, for up to
+    /// [CHECKPOINT_WAIT_TIMEOUT].
+    async fn wait_for_checkpoint(&self, digest: &TransactionDigest) -> Option<VerifiedCheckpoint> {
+        // Subscribed to before looking, so that no checkpoint executed in between is missed.
+        let mut highest_executed = self.state.subscribe_latest_checkpoint_sequence_number();
+        let checkpoint = async {
+            loop {
+                if let Ok(Some(checkpoint)) = self.state.get_transaction_checkpoint(digest) {
+                    return Some(checkpoint);
+                }
+                highest_executed.changed().await.ok()?;
+            }
+        };
+        tokio::time::timeout(CHECKPOINT_WAIT_TIMEOUT, checkpoint)
+            .await
+            .ok()
+            .flatten()
+    }
 }
 
 #[async_trait]
@@ -52,7 +78,7 @@ impl WriteApiServer for TransactionExecutionApi {
         signature: Base64,
         request_type: ExecuteTransactionRequestType,
     ) -> RpcResult<SuiTransactionResponse> {
-        self.submit_transaction(tx_bytes, vec![signature], request_type)
+        self.submit_transaction(tx_bytes, vec![signature], request_type, None)
             .await
     }
 
@@ -72,6 +98,7 @@ impl WriteApiServer for TransactionExecutionApi {
         tx_bytes: Base64,
         signatures: Vec<Base64>,
         request_type: ExecuteTransactionRequestType,
+        wait_for_checkpoint: Option<bool>,
     ) -> RpcResult<SuiTransactionResponse> {
         let tx_data =
             bcs::from_bytes(&tx_bytes.to_vec().map_err(|e| anyhow!(e))?).map_err(|e| anyhow!(e))?;
@@ -101,6 +128,12 @@ impl WriteApiServer for TransactionExecutionApi {
         match response {
             ExecuteTransactionResponse::EffectsCert(cert) => {
                 let (effects, events, is_executed_locally) = *cert;
+                let checkpoint = if is_executed_locally && wait_for_checkpoint.unwrap_or_default() {
+                    self.wait_for_checkpoint(effects.effects.transaction_digest())
+                        .await
+                } else {
+                    None
+                };
                 let module_cache = self
                     .state
                     .load_epoch_store_one_call_per_task()
@@ -110,9 +143,9 @@ impl WriteApiServer for TransactionExecutionApi {
                     transaction: tx,
                     effects: effects.effects.try_into()?,
                     events: SuiTransactionEvents::try_from(events, module_cache.as_ref())?,
-                    timestamp_ms: None,
+                    timestamp_ms: checkpoint.as_ref().map(|c| c.summary.timestamp_ms),
                     confirmed_local_execution: Some(is_executed_locally),
-                    checkpoint: None,
+                    checkpoint: checkpoint.map(|c| c.summary.sequence_number),
                 })
             }
         }
@@ -124,12 +157,18 @@ impl WriteApiServer for TransactionExecutionApi {
         tx_bytes: Base64,
         gas_price: Option<u64>,
         _epoch: Option<EpochId>,
+        additional_args: Option<DevInspectArgs>,
     ) -> RpcResult<DevInspectResults> {
         let tx_kind: TransactionKind =
             bcs::from_bytes(&tx_bytes.to_vec().map_err(|e| anyhow!(e))?).map_err(|e| anyhow!(e))?;
         Ok(self
             .state
-            .dev_inspect_transaction(sender_address, tx_kind, gas_price)
+            .dev_inspect_transaction(
+                sender_address,
+                tx_kind,
+                gas_price,
+                additional_args.unwrap_or_default(),
+            )
             .await?)
     }
 
@@ -140,6 +179,20 @@ impl WriteApiServer for TransactionExecutionApi {
             .dry_exec_transaction(txn_data, txn_digest)
             .await?)
     }
+
+    async fn estimate_gas(
+        &self,
+        sender_address: SuiAddress,
+        tx_bytes: Base64,
+        gas_price: Option<u64>,
+    ) -> RpcResult<GasEstimate> {
+        let tx_kind: TransactionKind =
+            bcs::from_bytes(&tx_bytes.to_vec().map_err(|e| anyhow!(e))?).map_err(|e| anyhow!(e))?;
+        Ok(self
+            .state
+            .estimate_gas(sender_address, tx_kind, gas_price)
+            .await?)
+    }
 }
 
 impl SuiRpcModule for TransactionExecutionApi {
@@ -0,0 +1,39 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+/// A minimal name registry, laid out like the SuiNS registries the name service API reads: a
+/// table of records by name, and a table of names by address.
+module name_service::registry {
+    use std::option::{Self, Option};
+    use std::string::{Self, String};
+    use sui::table::{Self, Table};
+    use sui::transfer;
+    use sui::tx_context::{Self, TxContext};
+
+    struct NameRecord has store {
+        target_address: Option<address>,
+    }
+
+    fun init(ctx: &mut TxContext) {
+        transfer::share_object(table::new<String, NameRecord>(ctx));
+        transfer::share_object(table::new<address, String>(ctx));
+    }
+
+    /// Registers `name` pointing at the sender, and makes it the name of the sender.
+    public entry fun register(
+        records: &mut Table<String, NameRecord>,
+        reverse_records: &mut Table<address, String>,
+        name: vector<u8>,
+        ctx: &mut TxContext,
+    ) {
+        let name = string::utf8(name);
+        let sender = tx_context::sender(ctx);
+        table::add(records, name, NameRecord { target_address: option::some(sender) });
+        table::add(reverse_records, sender, name);
+    }
+
+    /// Registers `name` without pointing it at any address.
+    public entry fun reserve(records: &mut Table<String, NameRecord>, name: vector<u8>) {
+        table::add(records, string::utf8(name), NameRecord { target_address: option::none() });
+    }
+}
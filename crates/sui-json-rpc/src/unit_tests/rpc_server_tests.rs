@@ -2,38 +2,49 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::api::{
-    CoinReadApiClient, GovernanceReadApiClient, ReadApiClient, ThresholdBlsApiClient,
-    TransactionBuilderClient, WriteApiClient,
+    CoinReadApiClient, GovernanceReadApiClient, NameServiceApiClient, ReadApiClient,
+    ThresholdBlsApiClient, TransactionBuilderClient, WriteApiClient,
 };
+use crate::CONSISTENCY_TOKEN_HEADER;
+use fastcrypto::encoding::Base64;
+use jsonrpsee::http_client::HttpClient;
+use move_core_types::language_storage::TypeTag;
 use std::path::Path;
 
 #[cfg(not(msim))]
 use std::str::FromStr;
+use sui_config::node::NameServiceConfig;
 use sui_config::SUI_KEYSTORE_FILENAME;
 use sui_framework_build::compiled_package::BuildConfig;
 use sui_json::SuiJsonValue;
 
 use sui_json_rpc_types::{
-    Balance, CoinPage, SuiCoinMetadata, SuiEvent, SuiExecutionStatus, SuiObjectResponse,
-    SuiTBlsSignObjectCommitmentType, SuiTransactionEffectsAPI, SuiTransactionResponse,
-    TransactionBytes,
+    Balance, CoinPage, SuiCoinMetadata, SuiEvent, SuiExecutionStatus, SuiGetPastObjectRequest,
+    SuiObjectHistoryEntry, SuiObjectResponse, SuiPastObjectResponse,
+    SuiTBlsSignObjectCommitmentType, SuiTransactionEffects, SuiTransactionEffectsAPI,
+    SuiTransactionResponse, SuiTransactionResponseOptions, TransactionBytes,
 };
 use sui_json_rpc_types::{SuiObjectDataOptions, SuiObjectInfo};
 use sui_keys::keystore::{AccountKeystore, FileBasedKeystore, Keystore};
 use sui_types::balance::Supply;
 use sui_types::base_types::ObjectID;
+use sui_types::base_types::ObjectType;
+use sui_types::base_types::SequenceNumber;
+use sui_types::base_types::SuiAddress;
 use sui_types::base_types::TransactionDigest;
 use sui_types::coin::{TreasuryCap, COIN_MODULE_NAME, LOCKED_COIN_MODULE_NAME};
-use sui_types::gas_coin::GAS;
-use sui_types::messages::ExecuteTransactionRequestType;
+use sui_types::gas_coin::{GAS, TOTAL_SUPPLY_MIST};
+use sui_types::messages::{ExecuteTransactionRequestType, TransactionDataAPI};
 use sui_types::object::Owner;
 use sui_types::query::{EventQuery, TransactionQuery};
 use sui_types::sui_system_state::sui_system_state_inner_v1::ValidatorMetadataV1;
 use sui_types::utils::to_sender_signed_transaction;
 use sui_types::{parse_sui_struct_tag, parse_sui_type_tag, SUI_FRAMEWORK_ADDRESS};
-use test_utils::network::TestClusterBuilder;
+use test_utils::network::{start_fullnode_from_config, TestClusterBuilder};
+use test_utils::transaction::wait_for_tx;
 
 use sui_macros::sim_test;
+use sui_protocol_config::ProtocolConfig;
 use sui_types::governance::{DelegatedStake, DelegationStatus};
 
 use tokio::time::{sleep, Duration};
@@ -50,6 +61,99 @@ async fn test_get_objects() -> Result<(), anyhow::Error> {
     Ok(())
 }
 
+#[sim_test]
+async fn test_get_protocol_config() -> Result<(), anyhow::Error> {
+    let cluster = TestClusterBuilder::new().build().await?;
+    let http_client = cluster.rpc_client();
+
+    let current = http_client.get_protocol_config(None).await?;
+    let expected = ProtocolConfig::get_for_version(current.protocol_version.into());
+    assert_eq!(
+        Some(expected.max_tx_gas()),
+        current.attributes["max_tx_gas"].as_u64()
+    );
+    assert!(!current.attributes.contains_key("version"));
+    assert!(!current.attributes.contains_key("feature_flags"));
+
+    let min = current.min_supported_protocol_version;
+    let config = http_client.get_protocol_config(Some(min)).await?;
+    assert_eq!(min, config.protocol_version);
+
+    let unsupported = current.max_supported_protocol_version + 1;
+    assert!(http_client
+        .get_protocol_config(Some(unsupported))
+        .await
+        .is_err());
+    Ok(())
+}
+
+#[sim_test]
+async fn test_get_owned_objects_while_transferring() -> Result<(), anyhow::Error> {
+    let cluster = TestClusterBuilder::new().build().await?;
+    let http_client = cluster.rpc_client();
+    let address = cluster.accounts.first().unwrap();
+    let recipient = cluster.accounts.last().unwrap();
+
+    let objects = http_client.get_objects_owned_by_address(*address).await?;
+    let mut object_ids: Vec<_> = objects.iter().map(|o| o.object_id).collect();
+    object_ids.sort();
+
+    let mut page = http_client
+        .get_owned_objects(*address, None, Some(2), None)
+        .await?;
+    let mut listed: Vec<_> = page.data.iter().map(|o| o.object_id).collect();
+
+    // Give away an object that is not listed yet, paying with one that is.
+    let transaction_bytes: TransactionBytes = http_client
+        .transfer_object(
+            *address,
+            *object_ids.last().unwrap(),
+            Some(object_ids[0]),
+            1000,
+            *recipient,
+        )
+        .await?;
+    let keystore_path = cluster.swarm.dir().join(SUI_KEYSTORE_FILENAME);
+    let keystore = Keystore::from(FileBasedKeystore::new(&keystore_path)?);
+    let tx = to_sender_signed_transaction(transaction_bytes.to_data()?, keystore.get_key(address)?);
+    let (tx_bytes, signatures) = tx.to_tx_bytes_and_signatures();
+    http_client
+        .submit_transaction(
+            tx_bytes,
+            signatures,
+            ExecuteTransactionRequestType::WaitForLocalExecution,
+            None,
+        )
+        .await?;
+
+    while let Some(cursor) = page.next_cursor {
+        page = http_client
+            .get_owned_objects(*address, Some(cursor), Some(2), None)
+            .await?;
+        listed.extend(page.data.iter().map(|o| o.object_id));
+    }
+    // The listing is as of the first page.
+    assert_eq!(object_ids, listed);
+
+    // A new listing no longer has the object.
+    let page = http_client
+        .get_owned_objects(*address, None, None, None)
+        .await?;
+    assert_eq!(object_ids.len() - 1, page.data.len());
+    assert!(page.next_cursor.is_none());
+
+    // The objects of the address are all gas coins.
+    let coins = http_client
+        .get_owned_objects(*address, None, None, Some("0x2::coin::Coin".into()))
+        .await?;
+    assert_eq!(page.data, coins.data);
+    let publishers = http_client
+        .get_owned_objects(*address, None, None, Some("0x2::package::Publisher".into()))
+        .await?;
+    assert!(publishers.data.is_empty());
+    Ok(())
+}
+
 #[sim_test]
 async fn test_public_transfer_object() -> Result<(), anyhow::Error> {
     let cluster = TestClusterBuilder::new().build().await?;
@@ -80,6 +184,7 @@ async fn test_public_transfer_object() -> Result<(), anyhow::Error> {
             tx_bytes1,
             signatures,
             ExecuteTransactionRequestType::WaitForLocalExecution,
+            None,
         )
         .await?;
 
@@ -91,6 +196,53 @@ async fn test_public_transfer_object() -> Result<(), anyhow::Error> {
     Ok(())
 }
 
+#[sim_test]
+async fn test_dry_run_without_gas_payment() -> Result<(), anyhow::Error> {
+    let cluster = TestClusterBuilder::new().build().await?;
+    let http_client = cluster.rpc_client();
+    let address = cluster.accounts.first().unwrap();
+
+    let objects = http_client.get_objects_owned_by_address(*address).await?;
+    let object_id = objects.first().unwrap().object_id;
+
+    let transaction_bytes: TransactionBytes = http_client
+        .transfer_object(
+            *address,
+            object_id,
+            Some(objects.last().unwrap().object_id),
+            1000,
+            *address,
+        )
+        .await?;
+    let mut data = transaction_bytes.to_data()?;
+    data.gas_data_mut().payment = vec![];
+
+    // The gas is paid with a coin of the sender other than the one transferred.
+    let dryrun_response = http_client
+        .dry_run_transaction(Base64::from_bytes(&bcs::to_bytes(&data)?))
+        .await?;
+    assert_eq!(
+        SuiExecutionStatus::Success,
+        *dryrun_response.effects.status()
+    );
+    let gas_object_id = dryrun_response.effects.gas_object().reference.object_id;
+    assert_ne!(gas_object_id, object_id);
+    assert!(objects.iter().any(|o| o.object_id == gas_object_id));
+
+    // A gas owner without coins has the gas paid with a synthetic coin.
+    data.gas_data_mut().owner = SuiAddress::random_for_testing_only();
+    let dryrun_response = http_client
+        .dry_run_transaction(Base64::from_bytes(&bcs::to_bytes(&data)?))
+        .await?;
+    assert_eq!(
+        SuiExecutionStatus::Success,
+        *dryrun_response.effects.status()
+    );
+    let gas_object_id = dryrun_response.effects.gas_object().reference.object_id;
+    assert!(objects.iter().all(|o| o.object_id != gas_object_id));
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_tbls_sign_randomness_object() -> Result<(), anyhow::Error> {
     let cluster = TestClusterBuilder::new().build().await?;
@@ -131,6 +283,7 @@ async fn test_tbls_sign_randomness_object() -> Result<(), anyhow::Error> {
             tx_bytes,
             signatures,
             ExecuteTransactionRequestType::WaitForLocalExecution,
+            None,
         )
         .await?;
 
@@ -178,6 +331,7 @@ async fn test_tbls_sign_randomness_object() -> Result<(), anyhow::Error> {
             tx_bytes,
             signatures,
             ExecuteTransactionRequestType::WaitForLocalExecution,
+            None,
         )
         .await?;
 
@@ -238,6 +392,7 @@ async fn test_tbls_sign_randomness_object() -> Result<(), anyhow::Error> {
             tx_bytes,
             signatures,
             ExecuteTransactionRequestType::WaitForEffectsCert,
+            None,
         )
         .await?;
     let SuiTransactionResponse { effects, .. } = tx_response;
@@ -273,6 +428,7 @@ async fn test_publish() -> Result<(), anyhow::Error> {
             tx_bytes,
             signatures,
             ExecuteTransactionRequestType::WaitForLocalExecution,
+            None,
         )
         .await?;
     matches!(tx_response, SuiTransactionResponse {effects, ..} if effects.created().len() == 6);
@@ -324,12 +480,76 @@ async fn test_move_call() -> Result<(), anyhow::Error> {
             tx_bytes,
             signatures,
             ExecuteTransactionRequestType::WaitForLocalExecution,
+            None,
         )
         .await?;
     matches!(tx_response, SuiTransactionResponse {effects, ..} if effects.created().len() == 1);
     Ok(())
 }
 
+#[sim_test]
+async fn test_estimate_gas() -> Result<(), anyhow::Error> {
+    let cluster = TestClusterBuilder::new().build().await?;
+    let http_client = cluster.rpc_client();
+    let address = cluster.accounts.first().unwrap();
+
+    let objects = http_client.get_objects_owned_by_address(*address).await?;
+    let gas = objects.first().unwrap();
+    let coin = &objects[1];
+
+    let package_id = ObjectID::new(SUI_FRAMEWORK_ADDRESS.into_bytes());
+    let json_args = vec![
+        SuiJsonValue::from_object_id(coin.object_id),
+        SuiJsonValue::from_str("\"10\"")?,
+    ];
+    let move_call = |gas_budget| {
+        http_client.move_call(
+            *address,
+            package_id,
+            "pay".to_string(),
+            "split".to_string(),
+            vec![GAS::type_tag().into()],
+            json_args.clone(),
+            Some(gas.object_id),
+            gas_budget,
+            None,
+        )
+    };
+
+    let tx_kind = move_call(10_000).await?.to_data()?.into_kind();
+    let estimate = http_client
+        .estimate_gas(
+            *address,
+            Base64::from_bytes(&bcs::to_bytes(&tx_kind)?),
+            None,
+        )
+        .await?;
+    assert_eq!(
+        estimate.gas_price,
+        http_client.get_reference_gas_price().await?
+    );
+    assert!(
+        estimate.gas_budget > estimate.gas_used.computation_cost + estimate.gas_used.storage_cost
+    );
+
+    // The estimated budget is enough to run the transaction for real.
+    let transaction_bytes = move_call(estimate.gas_budget).await?;
+    let keystore_path = cluster.swarm.dir().join(SUI_KEYSTORE_FILENAME);
+    let keystore = Keystore::from(FileBasedKeystore::new(&keystore_path)?);
+    let tx = to_sender_signed_transaction(transaction_bytes.to_data()?, keystore.get_key(address)?);
+    let (tx_bytes, signatures) = tx.to_tx_bytes_and_signatures();
+    let tx_response = http_client
+        .submit_transaction(
+            tx_bytes,
+            signatures,
+            ExecuteTransactionRequestType::WaitForLocalExecution,
+            None,
+        )
+        .await?;
+    assert_eq!(SuiExecutionStatus::Success, *tx_response.effects.status());
+    Ok(())
+}
+
 #[sim_test]
 async fn test_get_object_info() -> Result<(), anyhow::Error> {
     let cluster = TestClusterBuilder::new().build().await?;
@@ -378,6 +598,7 @@ async fn test_get_coins() -> Result<(), anyhow::Error> {
         .await?;
     assert_eq!(3, result.data.len());
     assert!(result.next_cursor.is_some());
+    assert!(result.has_next_page);
 
     let result: CoinPage = http_client
         .get_coins(
@@ -389,6 +610,43 @@ async fn test_get_coins() -> Result<(), anyhow::Error> {
         .await?;
     assert_eq!(2, result.data.len());
     assert!(result.next_cursor.is_none());
+    assert!(!result.has_next_page);
+
+    Ok(())
+}
+
+#[sim_test]
+async fn test_get_coins_by_balance() -> Result<(), anyhow::Error> {
+    let cluster = TestClusterBuilder::new().build().await?;
+    let http_client = cluster.rpc_client();
+    let address = cluster.accounts.first().unwrap();
+
+    let first = http_client
+        .get_coins_by_balance(*address, None, None, None, Some(3))
+        .await?;
+    assert_eq!(3, first.data.len());
+    assert_eq!(5, first.total_count);
+    let second = http_client
+        .get_coins_by_balance(*address, None, None, first.next_cursor, Some(3))
+        .await?;
+    assert_eq!(2, second.data.len());
+    assert!(second.next_cursor.is_none());
+
+    let coins: Vec<_> = first.data.iter().chain(&second.data).collect();
+    assert!(coins.windows(2).all(|w| w[0].balance >= w[1].balance));
+    let total_balance: u128 = coins.iter().map(|coin| coin.balance as u128).sum();
+    assert_eq!(total_balance, first.total_balance);
+
+    // Only the coins with at least the balance of the largest one.
+    let result = http_client
+        .get_coins_by_balance(*address, None, Some(coins[0].balance), None, None)
+        .await?;
+    assert!(!result.data.is_empty());
+    assert_eq!(result.data.len(), result.total_count);
+    assert!(result
+        .data
+        .iter()
+        .all(|coin| coin.balance == coins[0].balance));
 
     Ok(())
 }
@@ -436,6 +694,7 @@ async fn test_get_metadata() -> Result<(), anyhow::Error> {
             tx_bytes,
             signatures,
             ExecuteTransactionRequestType::WaitForLocalExecution,
+            None,
         )
         .await?;
 
@@ -494,6 +753,7 @@ async fn test_get_total_supply() -> Result<(), anyhow::Error> {
             tx_bytes,
             signatures,
             ExecuteTransactionRequestType::WaitForLocalExecution,
+            None,
         )
         .await?;
 
@@ -511,6 +771,11 @@ async fn test_get_total_supply() -> Result<(), anyhow::Error> {
         })
         .unwrap();
 
+    let result: Supply = http_client
+        .get_total_supply("0x2::sui::SUI".to_string())
+        .await?;
+    assert_eq!(TOTAL_SUPPLY_MIST, result.value);
+
     let coin_name = format!("{package_id}::trusted_coin::TRUSTED_COIN");
     let coin_type = parse_sui_type_tag(&coin_name).unwrap();
     let result: Supply = http_client.get_total_supply(coin_name.clone()).await?;
@@ -570,6 +835,7 @@ async fn test_get_total_supply() -> Result<(), anyhow::Error> {
             tx_bytes,
             signatures,
             ExecuteTransactionRequestType::WaitForLocalExecution,
+            None,
         )
         .await?;
 
@@ -610,6 +876,7 @@ async fn test_get_transaction() -> Result<(), anyhow::Error> {
                 tx_bytes,
                 signatures,
                 ExecuteTransactionRequestType::WaitForLocalExecution,
+                None,
             )
             .await?;
 
@@ -620,7 +887,8 @@ async fn test_get_transaction() -> Result<(), anyhow::Error> {
     assert_eq!(5, tx.len());
 
     //test get_transaction_batch
-    let response: Vec<SuiTransactionResponse> = http_client.multi_get_transactions(tx).await?;
+    let response: Vec<SuiTransactionResponse> =
+        http_client.multi_get_transactions(tx.clone()).await?;
 
     assert_eq!(5, response.len());
 
@@ -630,6 +898,28 @@ async fn test_get_transaction() -> Result<(), anyhow::Error> {
         ))
     }
 
+    // test multi_get_transactions_with_options, with a digest unknown to the node
+    let unknown = TransactionDigest::random();
+    let mut digests = tx;
+    digests.push(unknown);
+    let results = http_client
+        .multi_get_transactions_with_options(
+            digests.clone(),
+            Some(SuiTransactionResponseOptions::new().with_effects()),
+        )
+        .await?;
+    assert_eq!(
+        digests,
+        results.iter().map(|r| r.digest).collect::<Vec<_>>()
+    );
+    let (unknown_result, results) = results.split_last().unwrap();
+    assert!(unknown_result.error.is_some());
+    for r in results {
+        assert!(r.error.is_none());
+        assert!(r.transaction.is_none() && r.events.is_none());
+        assert_eq!(r.effects.as_ref().unwrap().transaction_digest(), &r.digest);
+    }
+
     // test get_transactions_in_range with smaller range
     let tx: Vec<TransactionDigest> = http_client.get_transactions_in_range(1, 3).await?;
     assert_eq!(2, tx.len());
@@ -778,6 +1068,79 @@ async fn test_get_fullnode_transaction() -> Result<(), anyhow::Error> {
     Ok(())
 }
 
+#[sim_test]
+async fn test_get_balance_changes() -> Result<(), anyhow::Error> {
+    let cluster = TestClusterBuilder::new()
+        .enable_fullnode_events()
+        .build()
+        .await?;
+    let client = cluster.wallet.get_client().await?;
+    let keystore_path = cluster.swarm.dir().join(SUI_KEYSTORE_FILENAME);
+    let keystore = Keystore::from(FileBasedKeystore::new(&keystore_path)?);
+    let address = cluster.accounts.first().unwrap();
+    let recipient = cluster.accounts.last().unwrap();
+
+    let objects = client
+        .read_api()
+        .get_objects_owned_by_address(*address)
+        .await?;
+    let data = client
+        .transaction_builder()
+        .transfer_sui(*address, objects[0].object_id, 1000, *recipient, Some(100))
+        .await?;
+    let tx = to_sender_signed_transaction(data, keystore.get_key(address)?);
+    let response = client
+        .quorum_driver()
+        .execute_transaction(
+            tx,
+            Some(ExecuteTransactionRequestType::WaitForLocalExecution),
+        )
+        .await?;
+    let digest = *response.effects.transaction_digest();
+
+    // Add a delay to ensure event processing is done after transaction commits.
+    sleep(Duration::from_millis(100)).await;
+
+    let page = client
+        .coin_read_api()
+        .get_balance_changes(*recipient, None, None, Some(1), Some(true))
+        .await?;
+    assert_eq!(1, page.data.len());
+    assert_eq!(digest, page.data[0].tx_digest);
+    assert_eq!("0x2::sui::SUI", page.data[0].coin_type);
+    assert_eq!(100, page.data[0].amount);
+
+    // The sender paid for gas too, which is netted into a single change.
+    let page = client
+        .coin_read_api()
+        .get_balance_changes(
+            *address,
+            Some("0x2::sui::SUI".to_string()),
+            None,
+            Some(1),
+            Some(true),
+        )
+        .await?;
+    assert_eq!(1, page.data.len());
+    assert_eq!(digest, page.data[0].tx_digest);
+    assert!(page.data[0].amount < -100);
+
+    // No other coin type was changed.
+    let page = client
+        .coin_read_api()
+        .get_balance_changes(
+            *address,
+            Some("0x2::test::TEST".to_string()),
+            None,
+            None,
+            None,
+        )
+        .await?;
+    assert!(page.data.is_empty());
+    assert!(!page.has_next_page);
+    Ok(())
+}
+
 #[sim_test]
 async fn test_get_fullnode_events() -> Result<(), anyhow::Error> {
     let cluster = TestClusterBuilder::new()
@@ -942,6 +1305,11 @@ async fn test_locked_sui() -> Result<(), anyhow::Error> {
 
     assert_eq!(1, balance.len());
     assert!(balance[0].locked_balance.is_empty());
+    assert_eq!(0, balance[0].total_locked_balance);
+    assert_eq!(
+        coins.data.iter().map(|coin| coin.balance).max(),
+        balance[0].largest_coin.as_ref().map(|coin| coin.balance)
+    );
 
     // lock one coin
     let transaction_bytes: TransactionBytes = http_client
@@ -972,6 +1340,7 @@ async fn test_locked_sui() -> Result<(), anyhow::Error> {
             tx_bytes,
             signatures,
             ExecuteTransactionRequestType::WaitForLocalExecution,
+            None,
         )
         .await?;
 
@@ -984,6 +1353,15 @@ async fn test_locked_sui() -> Result<(), anyhow::Error> {
     assert_eq!(5, balance.coin_object_count);
     assert_eq!(1, balance.locked_balance.len());
     assert!(balance.locked_balance.contains_key(&20));
+    assert_eq!(coins.data[0].balance as u128, balance.total_locked_balance);
+    // Locked coins can't pay for gas, so they are never the largest coin.
+    assert_ne!(
+        Some(coins.data[0].coin_object_id),
+        balance
+            .largest_coin
+            .as_ref()
+            .map(|coin| coin.coin_object_id)
+    );
 
     Ok(())
 }
@@ -1026,6 +1404,7 @@ async fn test_delegation() -> Result<(), anyhow::Error> {
             tx_bytes,
             signatures,
             ExecuteTransactionRequestType::WaitForLocalExecution,
+            None,
         )
         .await?;
 
@@ -1085,6 +1464,7 @@ async fn test_delegation_multiple_coins() -> Result<(), anyhow::Error> {
             tx_bytes,
             signatures,
             ExecuteTransactionRequestType::WaitForLocalExecution,
+            None,
         )
         .await?;
 
@@ -1152,6 +1532,7 @@ async fn test_delegation_with_locked_sui() -> Result<(), anyhow::Error> {
             tx_bytes,
             signatures,
             ExecuteTransactionRequestType::WaitForLocalExecution,
+            None,
         )
         .await?;
 
@@ -1183,6 +1564,7 @@ async fn test_delegation_with_locked_sui() -> Result<(), anyhow::Error> {
             tx_bytes,
             signatures,
             ExecuteTransactionRequestType::WaitForLocalExecution,
+            None,
         )
         .await?;
 
@@ -1199,3 +1581,344 @@ async fn test_delegation_with_locked_sui() -> Result<(), anyhow::Error> {
 
     Ok(())
 }
+
+#[sim_test]
+async fn test_get_checkpoints() -> Result<(), anyhow::Error> {
+    let cluster = TestClusterBuilder::new().build().await?;
+    let http_client = cluster.rpc_client();
+
+    while http_client.get_latest_checkpoint_sequence_number().await? < 3 {
+        sleep(Duration::from_millis(100)).await;
+    }
+
+    let page = http_client
+        .get_checkpoints(Some(1), Some(3), None, Some(2), None, None)
+        .await?;
+    let sequence_numbers: Vec<_> = page.data.iter().map(|c| c.sequence_number).collect();
+    assert_eq!(vec![1, 2], sequence_numbers);
+    assert_eq!(Some(3), page.next_cursor);
+
+    let page = http_client
+        .get_checkpoints(Some(1), Some(3), page.next_cursor, Some(2), None, None)
+        .await?;
+    let sequence_numbers: Vec<_> = page.data.iter().map(|c| c.sequence_number).collect();
+    assert_eq!(vec![3], sequence_numbers);
+    assert_eq!(None, page.next_cursor);
+
+    // The latest checkpoints first, without their transactions.
+    let page = http_client
+        .get_checkpoints(None, Some(3), None, Some(2), Some(true), Some(false))
+        .await?;
+    let sequence_numbers: Vec<_> = page.data.iter().map(|c| c.sequence_number).collect();
+    assert_eq!(vec![3, 2], sequence_numbers);
+    assert_eq!(Some(1), page.next_cursor);
+    assert!(page.data.iter().all(|c| c.transactions.is_empty()));
+
+    let checkpoint = http_client.get_checkpoint(0.into()).await?;
+    assert!(!checkpoint.transactions.is_empty());
+
+    Ok(())
+}
+
+#[sim_test]
+async fn test_get_past_object_history() -> Result<(), anyhow::Error> {
+    let cluster = TestClusterBuilder::new().build().await?;
+    let http_client = cluster.rpc_client();
+    let address = cluster.accounts.first().unwrap();
+
+    let objects = http_client.get_objects_owned_by_address(*address).await?;
+    let (gas, primary_coin, coin_to_merge) = (&objects[0], &objects[1], &objects[2]);
+
+    let transaction_bytes: TransactionBytes = http_client
+        .merge_coin(
+            *address,
+            primary_coin.object_id,
+            coin_to_merge.object_id,
+            Some(gas.object_id),
+            1000,
+        )
+        .await?;
+    let keystore_path = cluster.swarm.dir().join(SUI_KEYSTORE_FILENAME);
+    let keystore = Keystore::from(FileBasedKeystore::new(&keystore_path)?);
+    let tx = to_sender_signed_transaction(transaction_bytes.to_data()?, keystore.get_key(address)?);
+    let (tx_bytes, signatures) = tx.to_tx_bytes_and_signatures();
+    http_client
+        .submit_transaction(
+            tx_bytes,
+            signatures,
+            ExecuteTransactionRequestType::WaitForLocalExecution,
+            None,
+        )
+        .await?;
+
+    // Both coins as they were before the merge.
+    let responses = http_client
+        .try_multi_get_past_objects(
+            vec![
+                SuiGetPastObjectRequest {
+                    object_id: primary_coin.object_id,
+                    version: primary_coin.version,
+                },
+                SuiGetPastObjectRequest {
+                    object_id: coin_to_merge.object_id,
+                    version: coin_to_merge.version,
+                },
+            ],
+            None,
+        )
+        .await?;
+    assert_eq!(2, responses.len());
+    for (response, coin) in responses.iter().zip([primary_coin, coin_to_merge]) {
+        assert!(
+            matches!(response, SuiPastObjectResponse::VersionFound(object) if object.object_id == coin.object_id && object.version == coin.version)
+        );
+    }
+
+    // The merged coin was deleted after its first version.
+    let history = http_client
+        .try_get_past_object_history(
+            coin_to_merge.object_id,
+            SequenceNumber::MIN,
+            SequenceNumber::MAX,
+            None,
+        )
+        .await?;
+    assert_eq!(2, history.len());
+    assert!(
+        matches!(&history[0], SuiObjectHistoryEntry::VersionFound(object) if object.version == coin_to_merge.version)
+    );
+    assert!(
+        matches!(&history[1], SuiObjectHistoryEntry::ObjectDeleted(oref) if oref.version > coin_to_merge.version)
+    );
+
+    // The range leaves out the versions outside of it.
+    let history = http_client
+        .try_get_past_object_history(
+            coin_to_merge.object_id,
+            SequenceNumber::from_u64(coin_to_merge.version.value() + 1),
+            SequenceNumber::MAX,
+            None,
+        )
+        .await?;
+    assert_eq!(1, history.len());
+    Ok(())
+}
+
+#[sim_test]
+async fn test_consistency_token() -> Result<(), anyhow::Error> {
+    let cluster = TestClusterBuilder::new().build().await?;
+    let http_client = cluster.rpc_client();
+    let address = cluster.accounts.first().unwrap();
+    let recipient = cluster.accounts.last().unwrap();
+
+    let objects = http_client.get_objects_owned_by_address(*address).await?;
+    let transaction_bytes: TransactionBytes = http_client
+        .transfer_object(
+            *address,
+            objects[1].object_id,
+            Some(objects[0].object_id),
+            1000,
+            *recipient,
+        )
+        .await?;
+    let keystore_path = cluster.swarm.dir().join(SUI_KEYSTORE_FILENAME);
+    let keystore = Keystore::from(FileBasedKeystore::new(&keystore_path)?);
+    let tx = to_sender_signed_transaction(transaction_bytes.to_data()?, keystore.get_key(address)?);
+    let (tx_bytes, signatures) = tx.to_tx_bytes_and_signatures();
+    let response = http_client
+        .submit_transaction(
+            tx_bytes,
+            signatures,
+            ExecuteTransactionRequestType::WaitForLocalExecution,
+            Some(true),
+        )
+        .await?;
+    // The checkpoint of the transaction is the token of the write.
+    let token = response.checkpoint.unwrap();
+
+    let read = |token: String| {
+        reqwest::Client::new()
+            .post(cluster.rpc_url())
+            .header(CONSISTENCY_TOKEN_HEADER, token)
+            .json(&serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "sui_getObject",
+                "params": [objects[1].object_id],
+            }))
+            .send()
+    };
+
+    // Reads with the token are answered once the node has executed its checkpoint, and carry a
+    // token of their own.
+    let response = read(token.to_string()).await?;
+    assert_eq!(reqwest::StatusCode::OK, response.status());
+    let read_token: u64 = response
+        .headers()
+        .get(CONSISTENCY_TOKEN_HEADER)
+        .unwrap()
+        .to_str()?
+        .parse()?;
+    assert!(read_token >= token);
+
+    // Reads with tokens the node does not reach in time are turned away.
+    let response = read(u64::MAX.to_string()).await?;
+    assert_eq!(reqwest::StatusCode::SERVICE_UNAVAILABLE, response.status());
+    let response = read("not a checkpoint".to_string()).await?;
+    assert_eq!(reqwest::StatusCode::BAD_REQUEST, response.status());
+    Ok(())
+}
+
+async fn sign_and_execute(
+    http_client: &HttpClient,
+    keystore: &Keystore,
+    address: &SuiAddress,
+    transaction_bytes: TransactionBytes,
+) -> Result<SuiTransactionEffects, anyhow::Error> {
+    let tx = to_sender_signed_transaction(transaction_bytes.to_data()?, keystore.get_key(address)?);
+    let (tx_bytes, signatures) = tx.to_tx_bytes_and_signatures();
+    let SuiTransactionResponse { effects, .. } = http_client
+        .submit_transaction(
+            tx_bytes,
+            signatures,
+            ExecuteTransactionRequestType::WaitForLocalExecution,
+            None,
+        )
+        .await?;
+    assert_eq!(SuiExecutionStatus::Success, *effects.status());
+    Ok(effects)
+}
+
+#[sim_test]
+async fn test_name_service() -> Result<(), anyhow::Error> {
+    let cluster = TestClusterBuilder::new().build().await?;
+    let http_client = cluster.rpc_client();
+    let address = cluster.accounts.first().unwrap();
+    let keystore_path = cluster.swarm.dir().join(SUI_KEYSTORE_FILENAME);
+    let keystore = Keystore::from(FileBasedKeystore::new(&keystore_path)?);
+    let gas = http_client.get_objects_owned_by_address(*address).await?[0].object_id;
+
+    // Publish a registry with a table of records by name and a table of names by address.
+    let compiled_modules = BuildConfig::new_for_testing()
+        .build(Path::new("src/unit_tests/data/name_service").to_path_buf())?
+        .get_package_base64(false);
+    let transaction_bytes = http_client
+        .publish(*address, compiled_modules, Some(gas), 10000)
+        .await?;
+    let effects = sign_and_execute(http_client, &keystore, address, transaction_bytes).await?;
+    let package_id = effects
+        .created()
+        .iter()
+        .find(|o| o.owner == Owner::Immutable)
+        .unwrap()
+        .reference
+        .object_id;
+    let mut registry_id = None;
+    let mut reverse_registry_id = None;
+    for table in effects
+        .created()
+        .iter()
+        .filter(|o| matches!(o.owner, Owner::Shared { .. }))
+    {
+        let table_id = table.reference.object_id;
+        let SuiObjectResponse::Exists(table) = http_client
+            .get_object_with_options(table_id, Some(SuiObjectDataOptions::new().with_type()))
+            .await?
+        else {
+            panic!("Table {table_id} not found");
+        };
+        let Some(ObjectType::Struct(type_)) = table.type_ else {
+            panic!("Table {table_id} has no struct type");
+        };
+        if type_.type_params[0] == TypeTag::Address {
+            reverse_registry_id = Some(table_id);
+        } else {
+            registry_id = Some(table_id);
+        }
+    }
+    let registry_id = registry_id.unwrap();
+    let reverse_registry_id = reverse_registry_id.unwrap();
+
+    // Register a name for the address, and reserve another one pointing nowhere.
+    let register = http_client
+        .move_call(
+            *address,
+            package_id,
+            "registry".to_string(),
+            "register".to_string(),
+            vec![],
+            vec![
+                SuiJsonValue::from_object_id(registry_id),
+                SuiJsonValue::from_object_id(reverse_registry_id),
+                SuiJsonValue::new(serde_json::json!("example.sui"))?,
+            ],
+            Some(gas),
+            10_000,
+            None,
+        )
+        .await?;
+    sign_and_execute(http_client, &keystore, address, register).await?;
+    let reserve = http_client
+        .move_call(
+            *address,
+            package_id,
+            "registry".to_string(),
+            "reserve".to_string(),
+            vec![],
+            vec![
+                SuiJsonValue::from_object_id(registry_id),
+                SuiJsonValue::new(serde_json::json!("reserved.sui"))?,
+            ],
+            Some(gas),
+            10_000,
+            None,
+        )
+        .await?;
+    let effects = sign_and_execute(http_client, &keystore, address, reserve).await?;
+
+    // Serve the name service from a fullnode reading that registry.
+    let mut config = cluster.fullnode_config_builder().build().unwrap();
+    config.name_service_config = Some(NameServiceConfig {
+        registry_id,
+        reverse_registry_id,
+    });
+    let fullnode = start_fullnode_from_config(config).await?;
+    wait_for_tx(*effects.transaction_digest(), fullnode.sui_node.state()).await;
+    let fullnode_client = &fullnode.rpc_client;
+    // Transactions are indexed right after they are executed.
+    let mut resolved = None;
+    for _ in 0..50 {
+        resolved = fullnode_client
+            .resolve_name_service_address("example.sui".to_string())
+            .await?;
+        if resolved.is_some() {
+            break;
+        }
+        sleep(Duration::from_millis(100)).await;
+    }
+    assert_eq!(resolved, Some(*address));
+
+    assert_eq!(
+        fullnode_client.resolve_name_service_names(*address).await?,
+        vec!["example.sui".to_string()]
+    );
+    // A reserved name resolves to no address, as does a name that is not registered.
+    assert_eq!(
+        fullnode_client
+            .resolve_name_service_address("reserved.sui".to_string())
+            .await?,
+        None
+    );
+    assert_eq!(
+        fullnode_client
+            .resolve_name_service_address("unknown.sui".to_string())
+            .await?,
+        None
+    );
+    // Addresses without a name resolve to none.
+    assert!(fullnode_client
+        .resolve_name_service_names(cluster.accounts[1])
+        .await?
+        .is_empty());
+    Ok(())
+}
@@ -2,24 +2,29 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use async_trait::async_trait;
-use hyper::header::HeaderValue;
+use hyper::header::{HeaderValue, AUTHORIZATION};
 use hyper::HeaderMap;
 use jsonrpsee::core::__reexports::serde_json;
 use jsonrpsee::core::__reexports::serde_json::json;
 use jsonrpsee::core::client::ClientT;
 use jsonrpsee::core::params::BatchRequestBuilder;
 use jsonrpsee::core::{JsonRawValue, RpcResult};
-use jsonrpsee::http_client::HttpClientBuilder;
+use jsonrpsee::http_client::{HttpClient, HttpClientBuilder};
 use jsonrpsee::rpc_params;
 use jsonrpsee::types::{ErrorResponse, Id, Request, Response};
 use jsonrpsee::RpcModule;
 use jsonrpsee_proc_macros::rpc;
 use prometheus::Registry;
 use reqwest::Client;
+use std::collections::BTreeMap;
 use std::env;
 use std::net::{Ipv4Addr, SocketAddr, SocketAddrV4};
+use sui_config::node::{IndexerFallbackConfig, RpcRateLimitConfig, RpcServerConfig};
 use sui_config::utils::get_available_port;
-use sui_json_rpc::{JsonRpcServerBuilder, SuiRpcModule, CLIENT_TARGET_API_VERSION_HEADER};
+use sui_json_rpc::{
+    JsonRpcServerBuilder, RateLimitHandle, SuiRpcModule, CLIENT_SDK_TYPE_HEADER,
+    CLIENT_TARGET_API_VERSION_HEADER, CONSISTENCY_TOKEN_HEADER,
+};
 use sui_open_rpc::Module;
 use sui_open_rpc_macros::open_rpc;
 
@@ -102,6 +107,28 @@ async fn test_rpc_backward_compatibility() {
         .unwrap();
     assert_eq!("Some string from old method", response);
 
+    // Calls to deprecated methods are answered with a warning
+    let response = Client::new()
+        .post(format!("http://127.0.0.1:{}/", port))
+        .json(&json!(&Request {
+            jsonrpc: Default::default(),
+            id: Id::Number(1),
+            method: "test_baz".into(),
+            params: None,
+        }))
+        .send()
+        .await
+        .unwrap();
+    let warning = response.headers().get(reqwest::header::WARNING).cloned();
+    let response = serde_json::from_str::<Response<String>>(&response.text().await.unwrap());
+    assert!(
+        matches!(response, Ok(result) if result.result == "Some string from deprecated method")
+    );
+    assert_eq!(
+        Some("299 - \"Method test_baz is deprecated and will be removed\""),
+        warning.as_ref().and_then(|w| w.to_str().ok())
+    );
+
     handle.stop().unwrap()
 }
 
@@ -143,6 +170,10 @@ async fn test_disable_routing() {
         .await;
     assert!(response.is_err());
 
+    // Deprecated methods are removed when routing is disabled.
+    let response: RpcResult<String> = client.request("test_baz", rpc_params!()).await;
+    assert!(response.is_err());
+
     handle.stop().unwrap()
 }
 
@@ -212,6 +243,396 @@ async fn test_rpc_backward_compatibility_batched_request() {
     handle.stop().unwrap()
 }
 
+#[tokio::test]
+async fn test_rate_limit() {
+    let mut builder = JsonRpcServerBuilder::new("1.5", &Registry::new());
+    builder.register_module(TestApiModule).unwrap();
+    builder.set_rate_limit_config(RpcRateLimitConfig {
+        requests_per_second: 100,
+        method_requests_per_second: BTreeMap::from([("test_foo".to_string(), 2)]),
+        max_concurrent_requests: None,
+        api_keys: BTreeMap::from([("test-key".to_string(), 2)]),
+        api_key_header: "x-api-key".to_string(),
+        client_address_header: "x-forwarded-for".to_string(),
+        num_trusted_proxies: 1,
+    });
+
+    let port = get_available_port("0.0.0.0");
+    let handle = builder
+        .start(SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, port)))
+        .await
+        .unwrap();
+    let url = format!("http://0.0.0.0:{}", port);
+
+    let client_with_header = |name: &'static str, value: &'static str| {
+        let mut headers = HeaderMap::new();
+        headers.insert(name, HeaderValue::from_static(value));
+        HttpClientBuilder::default()
+            .set_headers(headers)
+            .build(&url)
+            .unwrap()
+    };
+    let successful_calls = |client: HttpClient, calls: usize| async move {
+        let mut successful = 0;
+        for _ in 0..calls {
+            let response: RpcResult<String> = client.request("test_foo", rpc_params!(true)).await;
+            successful += response.is_ok() as usize;
+        }
+        successful
+    };
+
+    // Each address forwarded by the proxy is a client of its own, whatever the client adds.
+    let client = client_with_header("x-forwarded-for", "10.0.0.2, 10.0.0.1");
+    assert_eq!(2, successful_calls(client, 3).await);
+    let client = client_with_header("x-forwarded-for", "10.0.0.2");
+    assert_eq!(2, successful_calls(client, 3).await);
+
+    // Known API keys get their multiple of the limits, unknown ones are ignored.
+    let client = client_with_header("x-api-key", "test-key");
+    assert_eq!(4, successful_calls(client, 5).await);
+    // Requests that don't come through the proxy are told apart by the address they come from.
+    let client = client_with_header("x-api-key", "unknown-key");
+    assert_eq!(2, successful_calls(client, 3).await);
+
+    // Other methods have the default limit.
+    let client = client_with_header("x-forwarded-for", "10.0.0.1");
+    let response: RpcResult<String> = client.request("test_foo", rpc_params!(true)).await;
+    assert!(response.is_err());
+    let response: RpcResult<String> = client.request("rpc.discover", rpc_params!()).await;
+    assert!(response.is_ok());
+
+    // Requests over the limits are answered with 429.
+    let response = Client::new()
+        .post(format!("http://127.0.0.1:{}/", port))
+        .header("x-forwarded-for", "10.0.0.1")
+        .json(&json!(&Request {
+            jsonrpc: Default::default(),
+            id: Id::Number(1),
+            method: "test_foo".into(),
+            params: Some(&JsonRawValue::from_string("[true]".into()).unwrap()),
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(reqwest::StatusCode::TOO_MANY_REQUESTS, response.status());
+
+    handle.stop().unwrap()
+}
+
+#[tokio::test]
+async fn test_rate_limits_change() {
+    let rate_limits = RateLimitHandle::default();
+    let mut builder = JsonRpcServerBuilder::new("1.5", &Registry::new());
+    builder.register_module(TestApiModule).unwrap();
+    builder.set_rate_limits(rate_limits.clone());
+
+    let port = get_available_port("0.0.0.0");
+    let handle = builder
+        .start(SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, port)))
+        .await
+        .unwrap();
+    let url = format!("http://0.0.0.0:{}", port);
+    let client = HttpClientBuilder::default().build(&url).unwrap();
+    let successful_calls = |calls: usize| {
+        let client = &client;
+        async move {
+            let mut successful = 0;
+            for _ in 0..calls {
+                let response: RpcResult<String> =
+                    client.request("test_foo", rpc_params!(true)).await;
+                successful += response.is_ok() as usize;
+            }
+            successful
+        }
+    };
+
+    // Unlimited until limits are set, and again once they are lifted.
+    assert_eq!(5, successful_calls(5).await);
+    rate_limits.set(Some(RpcRateLimitConfig {
+        requests_per_second: 2,
+        method_requests_per_second: BTreeMap::new(),
+        max_concurrent_requests: None,
+        api_keys: BTreeMap::new(),
+        api_key_header: "x-api-key".to_string(),
+        client_address_header: "x-forwarded-for".to_string(),
+        num_trusted_proxies: 0,
+    }));
+    assert_eq!(2, successful_calls(3).await);
+    rate_limits.set(None);
+    assert_eq!(5, successful_calls(5).await);
+
+    handle.stop().unwrap()
+}
+
+#[tokio::test]
+async fn test_auth_token() {
+    let mut builder = JsonRpcServerBuilder::new("1.5", &Registry::new());
+    builder.register_module(TestApiModule).unwrap();
+    builder.set_auth_token("test-token".to_string());
+
+    let port = get_available_port("0.0.0.0");
+    let handle = builder
+        .start(SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, port)))
+        .await
+        .unwrap();
+    let url = format!("http://0.0.0.0:{}", port);
+
+    let client_with_authorization = |value: &'static str| {
+        let mut headers = HeaderMap::new();
+        headers.insert(AUTHORIZATION, HeaderValue::from_static(value));
+        HttpClientBuilder::default()
+            .set_headers(headers)
+            .build(&url)
+            .unwrap()
+    };
+
+    let client = client_with_authorization("Bearer test-token");
+    let response: String = client.request("test_foo", rpc_params!(true)).await.unwrap();
+    assert_eq!("Some string", response);
+
+    let client = client_with_authorization("Bearer other-token");
+    let response: RpcResult<String> = client.request("test_foo", rpc_params!(true)).await;
+    assert!(response.is_err());
+
+    // Requests without the token are answered with 401.
+    let response = Client::new()
+        .post(format!("http://127.0.0.1:{}/", port))
+        .json(&json!(&Request {
+            jsonrpc: Default::default(),
+            id: Id::Number(1),
+            method: "test_foo".into(),
+            params: Some(&JsonRawValue::from_string("[true]".into()).unwrap()),
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(reqwest::StatusCode::UNAUTHORIZED, response.status());
+
+    handle.stop().unwrap()
+}
+
+#[tokio::test]
+async fn test_params_validation() {
+    let mut builder = JsonRpcServerBuilder::new("1.5", &Registry::new());
+    builder.register_module(TestApiModule).unwrap();
+
+    let port = get_available_port("0.0.0.0");
+    let handle = builder
+        .start(SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, port)))
+        .await
+        .unwrap();
+    let url = format!("http://0.0.0.0:{}", port);
+    let client = HttpClientBuilder::default().build(&url).unwrap();
+
+    // Invalid params are named in the error, with the format expected.
+    let response: RpcResult<String> = client.request("test_foo", rpc_params!("true")).await;
+    let error = response.unwrap_err().to_string();
+    assert!(error.contains("Invalid param `some_bool`: expected a boolean"));
+
+    let response: RpcResult<String> = client.request("test_foo", rpc_params!()).await;
+    let error = response.unwrap_err().to_string();
+    assert!(error.contains("Missing required param `some_bool`"));
+
+    // Old clients are checked against the params of the method they are routed to.
+    let mut versioned_header = HeaderMap::new();
+    versioned_header.insert(
+        CLIENT_TARGET_API_VERSION_HEADER,
+        HeaderValue::from_static("1.5"),
+    );
+    let client_with_old_header = HttpClientBuilder::default()
+        .set_headers(versioned_header)
+        .build(&url)
+        .unwrap();
+    let response: String = client_with_old_header
+        .request("test_foo", rpc_params!("string"))
+        .await
+        .unwrap();
+    assert_eq!("Some string from old method", response);
+
+    // Only the calls of a batch with invalid params fail.
+    let response = Client::new()
+        .post(format!("http://127.0.0.1:{}/", port))
+        .json(&vec![
+            json!(&Request {
+                jsonrpc: Default::default(),
+                id: Id::Number(1),
+                method: "test_foo".into(),
+                params: Some(&JsonRawValue::from_string("[true]".into()).unwrap()),
+            }),
+            json!(&Request {
+                jsonrpc: Default::default(),
+                id: Id::Number(2),
+                method: "test_foo".into(),
+                params: Some(&JsonRawValue::from_string("[1]".into()).unwrap()),
+            }),
+        ])
+        .send()
+        .await
+        .unwrap();
+    let responses = response.text().await.unwrap();
+    let responses: Vec<&JsonRawValue> = serde_json::from_str(&responses).unwrap();
+    assert_eq!(2, responses.len());
+
+    let response = serde_json::from_str::<Response<String>>(responses[0].get());
+    assert!(matches!(response, Ok(result) if result.result == "Some string"));
+    let response = serde_json::from_str::<ErrorResponse>(responses[1].get()).unwrap();
+    assert_eq!(-32602, response.error_object().code());
+    assert!(response.error_object().message().contains("`some_bool`"));
+
+    handle.stop().unwrap()
+}
+
+#[tokio::test]
+async fn test_server_config() {
+    let mut builder = JsonRpcServerBuilder::new("1.5", &Registry::new());
+    builder.register_module(TestApiModule).unwrap();
+    builder.set_server_config(RpcServerConfig {
+        allowed_origins: vec!["https://example.com".to_string()],
+        enable_compression: true,
+        max_request_body_size: Some(1024),
+        tcp_keepalive_secs: Some(60),
+    });
+
+    let port = get_available_port("0.0.0.0");
+    let handle = builder
+        .start(SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, port)))
+        .await
+        .unwrap();
+    let url = format!("http://127.0.0.1:{}/", port);
+    let request = |method: &str, params: String| {
+        json!(&Request {
+            jsonrpc: Default::default(),
+            id: Id::Number(1),
+            method: method.into(),
+            params: Some(&JsonRawValue::from_string(params).unwrap()),
+        })
+    };
+
+    // Only the allowed origins are allowed.
+    let response = Client::new()
+        .post(&url)
+        .header("origin", "https://example.com")
+        .json(&request("test_foo", "[true]".into()))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(
+        Some("https://example.com"),
+        response
+            .headers()
+            .get("access-control-allow-origin")
+            .and_then(|origin| origin.to_str().ok())
+    );
+    let response = Client::new()
+        .post(&url)
+        .header("origin", "https://example.org")
+        .json(&request("test_foo", "[true]".into()))
+        .send()
+        .await
+        .unwrap();
+    assert!(response
+        .headers()
+        .get("access-control-allow-origin")
+        .is_none());
+
+    // Responses are compressed for clients that accept it.
+    let response = Client::new()
+        .post(&url)
+        .header("accept-encoding", "gzip")
+        .json(&request("rpc.discover", "[]".into()))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(
+        Some("gzip"),
+        response
+            .headers()
+            .get("content-encoding")
+            .and_then(|encoding| encoding.to_str().ok())
+    );
+
+    // Requests over the maximum body size are turned away.
+    let response = Client::new()
+        .post(&url)
+        .json(&request(
+            "test_foo_1_5",
+            format!("[\"{}\"]", "a".repeat(2048)),
+        ))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(reqwest::StatusCode::PAYLOAD_TOO_LARGE, response.status());
+
+    handle.stop().unwrap()
+}
+
+#[tokio::test]
+async fn test_indexer_fallback() {
+    // The indexer serves the test api, and the node none of it, so only calls handed over to the
+    // indexer are answered.
+    let mut indexer = JsonRpcServerBuilder::new("1.5", &Registry::new());
+    indexer.register_module(TestApiModule).unwrap();
+    let indexer_port = get_available_port("0.0.0.0");
+    let indexer_handle = indexer
+        .start(SocketAddr::V4(SocketAddrV4::new(
+            Ipv4Addr::LOCALHOST,
+            indexer_port,
+        )))
+        .await
+        .unwrap();
+
+    let mut builder = JsonRpcServerBuilder::new("1.5", &Registry::new());
+    builder.set_indexer_fallback_config(IndexerFallbackConfig {
+        url: format!("http://127.0.0.1:{}", indexer_port),
+        methods: vec!["test_foo".to_string()],
+        request_timeout_ms: 1_000,
+    });
+    let port = get_available_port("0.0.0.0");
+    let handle = builder
+        .start(SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, port)))
+        .await
+        .unwrap();
+    let url = format!("http://127.0.0.1:{}", port);
+
+    let client = HttpClientBuilder::default().build(&url).unwrap();
+    let response: String = client.request("test_foo", rpc_params!(true)).await.unwrap();
+    assert_eq!("Some string", response);
+
+    // Calls to other methods are served locally.
+    let response: RpcResult<String> = client.request("test_baz", rpc_params!()).await;
+    assert!(response.is_err());
+
+    // So are calls from the indexer itself.
+    let mut headers = HeaderMap::new();
+    headers.insert(CLIENT_SDK_TYPE_HEADER, HeaderValue::from_static("indexer"));
+    let indexer_client = HttpClientBuilder::default()
+        .set_headers(headers)
+        .build(&url)
+        .unwrap();
+    let response: RpcResult<String> = indexer_client.request("test_foo", rpc_params!(true)).await;
+    assert!(response.is_err());
+
+    // And calls with a consistency token, which the indexer may not have caught up to.
+    let mut headers = HeaderMap::new();
+    headers.insert(CONSISTENCY_TOKEN_HEADER, HeaderValue::from_static("1"));
+    let consistent_client = HttpClientBuilder::default()
+        .set_headers(headers)
+        .build(&url)
+        .unwrap();
+    let response: RpcResult<String> = consistent_client
+        .request("test_foo", rpc_params!(true))
+        .await;
+    assert!(response.is_err());
+
+    // And calls the indexer fails to answer.
+    indexer_handle.stop().unwrap();
+    indexer_handle.stopped().await;
+    let response: RpcResult<String> = client.request("test_foo", rpc_params!(true)).await;
+    assert!(response.is_err());
+
+    handle.stop().unwrap()
+}
+
 #[open_rpc(namespace = "test")]
 #[rpc(server, client, namespace = "test")]
 trait TestApi {
@@ -220,6 +641,9 @@ trait TestApi {
 
     #[method(name = "foo", version <= "1.5")]
     async fn bar(&self, some_str: String) -> RpcResult<String>;
+
+    #[method(name = "baz", deprecated)]
+    async fn baz(&self) -> RpcResult<String>;
 }
 
 struct TestApiModule;
@@ -233,6 +657,10 @@ impl TestApiServer for TestApiModule {
     async fn bar(&self, _some_str: String) -> RpcResult<String> {
         Ok("Some string from old method".into())
     }
+
+    async fn baz(&self) -> RpcResult<String> {
+        Ok("Some string from deprecated method".into())
+    }
 }
 
 impl SuiRpcModule for TestApiModule {
@@ -631,14 +631,18 @@ fn resolve_call_arg(
 fn resolve_call_args(
     view: &BinaryIndexedView,
     type_args: &[TypeTag],
-    json_args: &[SuiJsonValue],
+    json_args: &[Option<SuiJsonValue>],
     parameter_types: &[SignatureToken],
-) -> Result<Vec<SuiJsonCallArg>, anyhow::Error> {
+) -> Result<Vec<Option<SuiJsonCallArg>>, anyhow::Error> {
     json_args
         .iter()
         .zip(parameter_types)
         .enumerate()
-        .map(|(idx, (arg, param))| resolve_call_arg(view, type_args, idx, arg, param))
+        .map(|(idx, (arg, param))| {
+            arg.as_ref()
+                .map(|arg| resolve_call_arg(view, type_args, idx, arg, param))
+                .transpose()
+        })
         .collect()
 }
 
@@ -652,6 +656,28 @@ pub fn resolve_move_function_args(
     combined_args_json: Vec<SuiJsonValue>,
     allow_arbitrary_function_call: bool,
 ) -> Result<Vec<SuiJsonCallArg>, anyhow::Error> {
+    let args = resolve_move_function_args_with_results(
+        package,
+        module_ident,
+        function,
+        type_args,
+        combined_args_json.into_iter().map(Some).collect(),
+        allow_arbitrary_function_call,
+    )?;
+    Ok(args.into_iter().flatten().collect())
+}
+
+/// Like `resolve_move_function_args`, but the args that are `None` are left unresolved. They stand
+/// for the results of earlier commands of a programmable transaction, which are only known once it
+/// executes.
+pub fn resolve_move_function_args_with_results(
+    package: &MovePackage,
+    module_ident: Identifier,
+    function: Identifier,
+    type_args: &[TypeTag],
+    combined_args_json: Vec<Option<SuiJsonValue>>,
+    allow_arbitrary_function_call: bool,
+) -> Result<Vec<Option<SuiJsonCallArg>>, anyhow::Error> {
     // Extract the expected function signature
     let module = package.deserialize_module(&module_ident)?;
     let function_str = function.as_ident_str();
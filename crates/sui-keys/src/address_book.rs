@@ -0,0 +1,171 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{anyhow, bail, ensure};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use sui_types::base_types::SuiAddress;
+
+/// The extension of the address book file, stored alongside the keystore it belongs to, as in
+/// `sui.aliases` for `sui.keystore`.
+pub const ADDRESS_BOOK_EXTENSION: &str = "aliases";
+
+const MAX_ALIAS_LENGTH: usize = 64;
+
+/// The path of the address book of the keystore at `keystore_path`.
+pub fn address_book_path(keystore_path: &Path) -> PathBuf {
+    keystore_path.with_extension(ADDRESS_BOOK_EXTENSION)
+}
+
+/// Names given to addresses, so that they can be referred to by name instead of in hex.
+/// Saved as a JSON object from aliases to addresses.
+#[derive(Debug, Default)]
+pub struct AddressBook {
+    path: Option<PathBuf>,
+    aliases: BTreeMap<String, SuiAddress>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(transparent)]
+struct AddressBookFile(BTreeMap<String, SuiAddress>);
+
+impl AddressBook {
+    /// Reads the address book at `path`, which is empty if the file does not exist.
+    pub fn load(path: &Path) -> Result<Self, anyhow::Error> {
+        let aliases = if path.exists() {
+            let content = fs::read_to_string(path)?;
+            let AddressBookFile(aliases) = serde_json::from_str(&content)
+                .map_err(|e| anyhow!("Invalid address book file {:?}: {e}", path))?;
+            aliases
+        } else {
+            BTreeMap::new()
+        };
+        Ok(Self {
+            path: Some(path.to_path_buf()),
+            aliases,
+        })
+    }
+
+    pub fn save(&self) -> Result<(), anyhow::Error> {
+        if let Some(path) = &self.path {
+            let content = serde_json::to_string_pretty(&AddressBookFile(self.aliases.clone()))?;
+            fs::write(path, content)?;
+        }
+        Ok(())
+    }
+
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    /// Names `address` `alias`, replacing the address the alias was for, if any, which is
+    /// returned.
+    pub fn set(
+        &mut self,
+        alias: &str,
+        address: SuiAddress,
+    ) -> Result<Option<SuiAddress>, anyhow::Error> {
+        validate_alias(alias)?;
+        Ok(self.aliases.insert(alias.to_string(), address))
+    }
+
+    /// Removes `alias`, returning the address it was for.
+    pub fn remove(&mut self, alias: &str) -> Result<SuiAddress, anyhow::Error> {
+        self.aliases
+            .remove(alias)
+            .ok_or_else(|| anyhow!("No address is aliased {alias}"))
+    }
+
+    pub fn get(&self, alias: &str) -> Option<SuiAddress> {
+        self.aliases.get(alias).copied()
+    }
+
+    /// The aliases of `address`, in alphabetical order.
+    pub fn aliases_of(&self, address: &SuiAddress) -> Vec<&str> {
+        self.aliases
+            .iter()
+            .filter(|(_, a)| *a == address)
+            .map(|(alias, _)| alias.as_str())
+            .collect()
+    }
+
+    /// The aliases and the addresses they are for, in alphabetical order of aliases.
+    pub fn aliases(&self) -> impl Iterator<Item = (&str, &SuiAddress)> {
+        self.aliases.iter().map(|(alias, a)| (alias.as_str(), a))
+    }
+
+    /// The address `address` stands for, looking aliases up in the address book.
+    pub fn resolve(&self, address: &AddressOrAlias) -> Result<SuiAddress, anyhow::Error> {
+        match address {
+            AddressOrAlias::Address(address) => Ok(*address),
+            AddressOrAlias::Alias(alias) => self.get(alias).ok_or_else(|| {
+                anyhow!(
+                    "Unknown address alias {alias}, see `sui keytool alias list` for the known \
+                    aliases"
+                )
+            }),
+        }
+    }
+}
+
+/// Checks that `alias` can be told apart from an address: it must start with a letter, and be
+/// made of letters, digits, `-` and `_`.
+pub fn validate_alias(alias: &str) -> Result<(), anyhow::Error> {
+    ensure!(
+        !alias.is_empty() && alias.len() <= MAX_ALIAS_LENGTH,
+        "Aliases must be between 1 and {MAX_ALIAS_LENGTH} characters long"
+    );
+    ensure!(
+        alias.starts_with(|c: char| c.is_ascii_alphabetic()),
+        "Alias {alias} must start with a letter"
+    );
+    ensure!(
+        alias
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'),
+        "Alias {alias} must only contain letters, digits, '-' and '_'"
+    );
+    if SuiAddress::from_str(alias).is_ok() {
+        bail!("Alias {alias} is a valid address");
+    }
+    Ok(())
+}
+
+/// An address given either in hex, or by an alias of the address book.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AddressOrAlias {
+    Address(SuiAddress),
+    Alias(String),
+}
+
+impl FromStr for AddressOrAlias {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Ok(address) = SuiAddress::from_str(s) {
+            return Ok(Self::Address(address));
+        }
+        validate_alias(s)
+            .map(|_| Self::Alias(s.to_string()))
+            .map_err(|_| anyhow!("{s} is neither an address nor an address alias"))
+    }
+}
+
+impl Display for AddressOrAlias {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Address(address) => write!(f, "{address}"),
+            Self::Alias(alias) => write!(f, "{alias}"),
+        }
+    }
+}
+
+impl From<SuiAddress> for AddressOrAlias {
+    fn from(address: SuiAddress) -> Self {
+        Self::Address(address)
+    }
+}
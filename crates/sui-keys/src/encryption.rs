@@ -0,0 +1,204 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use aes_gcm::aead::{Aead, KeyInit, Payload};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use anyhow::{anyhow, ensure};
+use argon2::{Algorithm, Argon2, Params, Version};
+use fastcrypto::encoding::{Base64, Encoding};
+use rand::rngs::OsRng;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use sui_types::crypto::{EncodeDecodeBase64, PublicKey, SuiKeyPair};
+use zeroize::Zeroizing;
+
+/// The environment variable read for the passphrase of an encrypted keystore before prompting
+/// for it, for use where there is no terminal.
+pub const SUI_KEYSTORE_PASSPHRASE: &str = "SUI_KEYSTORE_PASSPHRASE";
+
+const ENCRYPTED_KEYSTORE_VERSION: u8 = 1;
+const KDF_ALGORITHM: &str = "argon2id";
+const CIPHER_ALGORITHM: &str = "aes-256-gcm";
+const SALT_LENGTH: usize = 16;
+const NONCE_LENGTH: usize = 12;
+const KEY_LENGTH: usize = 32;
+
+/// A keystore file whose private keys are encrypted with a key derived from a passphrase with
+/// Argon2id, using AES-256-GCM. The public keys are kept in the clear, so that the addresses of the
+/// keystore can be listed without the passphrase, and authenticated as the associated data of the
+/// encryption, so that the keystore can't be decrypted once they are tampered with.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct EncryptedKeystore {
+    pub version: u8,
+    /// The Base64 encoded public keys, `flag || pk`.
+    pub public_keys: Vec<String>,
+    pub kdf: KdfParams,
+    pub cipher: String,
+    /// Base64 encoded.
+    pub nonce: String,
+    /// The Base64 encoded encryption of the JSON array of the Base64 encoded key pairs, as stored
+    /// in a plaintext keystore.
+    pub ciphertext: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct KdfParams {
+    pub algorithm: String,
+    /// Base64 encoded.
+    pub salt: String,
+    /// Memory size in KiB.
+    pub m_cost: u32,
+    /// Number of iterations.
+    pub t_cost: u32,
+    /// Degree of parallelism.
+    pub p_cost: u32,
+}
+
+impl Default for KdfParams {
+    fn default() -> Self {
+        Self {
+            algorithm: KDF_ALGORITHM.to_string(),
+            salt: String::new(),
+            m_cost: Params::DEFAULT_M_COST,
+            t_cost: Params::DEFAULT_T_COST,
+            p_cost: Params::DEFAULT_P_COST,
+        }
+    }
+}
+
+impl EncryptedKeystore {
+    pub fn encrypt<'a>(
+        keys: impl IntoIterator<Item = &'a SuiKeyPair>,
+        passphrase: &str,
+    ) -> Result<Self, anyhow::Error> {
+        let keys: Vec<_> = keys.into_iter().collect();
+        let mut salt = [0u8; SALT_LENGTH];
+        OsRng.fill_bytes(&mut salt);
+        let mut nonce = [0u8; NONCE_LENGTH];
+        OsRng.fill_bytes(&mut nonce);
+        let kdf = KdfParams {
+            salt: Base64::encode(salt),
+            ..Default::default()
+        };
+
+        let plaintext = Zeroizing::new(serde_json::to_vec(
+            &keys
+                .iter()
+                .map(|key| key.encode_base64())
+                .collect::<Vec<_>>(),
+        )?);
+        let public_keys: Vec<_> = keys
+            .iter()
+            .map(|key| key.public().encode_base64())
+            .collect();
+        let ciphertext = cipher(&kdf, passphrase)?
+            .encrypt(
+                Nonce::from_slice(&nonce),
+                Payload {
+                    msg: plaintext.as_slice(),
+                    aad: &associated_data(&public_keys)?,
+                },
+            )
+            .map_err(|_| anyhow!("Cannot encrypt keystore"))?;
+
+        Ok(Self {
+            version: ENCRYPTED_KEYSTORE_VERSION,
+            public_keys,
+            kdf,
+            cipher: CIPHER_ALGORITHM.to_string(),
+            nonce: Base64::encode(nonce),
+            ciphertext: Base64::encode(ciphertext),
+        })
+    }
+
+    pub fn decrypt(&self, passphrase: &str) -> Result<Vec<SuiKeyPair>, anyhow::Error> {
+        self.check_algorithms()?;
+        let nonce = decode(&self.nonce)?;
+        ensure!(nonce.len() == NONCE_LENGTH, "Invalid keystore nonce");
+        let plaintext = Zeroizing::new(
+            cipher(&self.kdf, passphrase)?
+                .decrypt(
+                    Nonce::from_slice(&nonce),
+                    Payload {
+                        msg: decode(&self.ciphertext)?.as_slice(),
+                        aad: &associated_data(&self.public_keys)?,
+                    },
+                )
+                .map_err(|_| {
+                    anyhow!("Wrong passphrase for keystore, or its public keys were modified")
+                })?,
+        );
+        let kp_strings: Vec<String> = serde_json::from_slice(&plaintext)?;
+        kp_strings
+            .iter()
+            .map(|kpstr| SuiKeyPair::decode_base64(kpstr))
+            .collect::<Result<_, _>>()
+            .map_err(|e| anyhow!("Invalid key pair in keystore: {e:?}"))
+    }
+
+    pub fn public_keys(&self) -> Result<Vec<PublicKey>, anyhow::Error> {
+        self.public_keys
+            .iter()
+            .map(|pk| PublicKey::decode_base64(pk))
+            .collect::<Result<_, _>>()
+            .map_err(|e| anyhow!("Invalid public key in keystore: {e:?}"))
+    }
+
+    fn check_algorithms(&self) -> Result<(), anyhow::Error> {
+        ensure!(
+            self.version == ENCRYPTED_KEYSTORE_VERSION,
+            "Unsupported keystore version {}",
+            self.version
+        );
+        ensure!(
+            self.kdf.algorithm == KDF_ALGORITHM && self.cipher == CIPHER_ALGORITHM,
+            "Unsupported keystore encryption {} with {}",
+            self.cipher,
+            self.kdf.algorithm
+        );
+        Ok(())
+    }
+}
+
+/// Reads the passphrase of the keystore at `path` from the environment, or from the terminal.
+pub fn read_passphrase(path: &Path) -> Result<Zeroizing<String>, anyhow::Error> {
+    if let Ok(passphrase) = std::env::var(SUI_KEYSTORE_PASSPHRASE) {
+        return Ok(Zeroizing::new(passphrase));
+    }
+    Ok(Zeroizing::new(rpassword::prompt_password(format!(
+        "Enter passphrase for keystore {}: ",
+        path.display()
+    ))?))
+}
+
+/// Prompts for a new passphrase, twice to catch typos.
+pub fn read_new_passphrase() -> Result<Zeroizing<String>, anyhow::Error> {
+    if let Ok(passphrase) = std::env::var(SUI_KEYSTORE_PASSPHRASE) {
+        return Ok(Zeroizing::new(passphrase));
+    }
+    let passphrase = Zeroizing::new(rpassword::prompt_password("Enter new passphrase: ")?);
+    ensure!(!passphrase.is_empty(), "Passphrase cannot be empty");
+    let confirmation = Zeroizing::new(rpassword::prompt_password("Confirm new passphrase: ")?);
+    ensure!(passphrase == confirmation, "Passphrases do not match");
+    Ok(passphrase)
+}
+
+fn cipher(kdf: &KdfParams, passphrase: &str) -> Result<Aes256Gcm, anyhow::Error> {
+    let params = Params::new(kdf.m_cost, kdf.t_cost, kdf.p_cost, Some(KEY_LENGTH))
+        .map_err(|e| anyhow!("Invalid key derivation parameters: {e}"))?;
+    let mut key = Zeroizing::new([0u8; KEY_LENGTH]);
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+        .hash_password_into(passphrase.as_bytes(), &decode(&kdf.salt)?, key.as_mut())
+        .map_err(|e| anyhow!("Cannot derive keystore key: {e}"))?;
+    Ok(Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key.as_ref())))
+}
+
+/// The public keys as authenticated along with the encrypted key pairs.
+fn associated_data(public_keys: &[String]) -> Result<Vec<u8>, anyhow::Error> {
+    Ok(serde_json::to_vec(public_keys)?)
+}
+
+fn decode(s: &str) -> Result<Vec<u8>, anyhow::Error> {
+    Base64::decode(s).map_err(|e| anyhow!("Invalid Base64 in keystore: {e:?}"))
+}
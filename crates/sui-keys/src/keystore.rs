@@ -4,6 +4,7 @@
 use anyhow::anyhow;
 use bip32::DerivationPath;
 use bip39::{Language, Mnemonic, Seed};
+use once_cell::sync::OnceCell;
 use rand::{rngs::StdRng, SeedableRng};
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::collections::BTreeMap;
@@ -14,6 +15,7 @@ use std::fs::File;
 use std::io::BufReader;
 use std::path::{Path, PathBuf};
 use sui_types::intent::{Intent, IntentMessage};
+use zeroize::Zeroizing;
 
 use sui_types::base_types::SuiAddress;
 use sui_types::crypto::{
@@ -21,13 +23,16 @@ use sui_types::crypto::{
     SignatureScheme, Signer, SuiKeyPair,
 };
 
+use crate::encryption::{read_passphrase, EncryptedKeystore};
 use crate::key_derive::{derive_key_pair_from_path, generate_new_key};
+use crate::ledger::LedgerKeystore;
 
 #[derive(Serialize, Deserialize)]
 #[enum_dispatch(AccountKeystore)]
 pub enum Keystore {
     File(FileBasedKeystore),
     InMem(InMemKeystore),
+    Ledger(LedgerKeystore),
 }
 #[enum_dispatch]
 pub trait AccountKeystore: Send + Sync {
@@ -84,20 +89,33 @@ impl Display for Keystore {
         match self {
             Keystore::File(file) => {
                 writeln!(writer, "Keystore Type : File")?;
-                write!(writer, "Keystore Path : {:?}", file.path)?;
+                writeln!(writer, "Keystore Path : {:?}", file.path)?;
+                write!(writer, "Encrypted : {}", file.is_encrypted())?;
                 write!(f, "{}", writer)
             }
             Keystore::InMem(_) => {
                 writeln!(writer, "Keystore Type : InMem")?;
                 write!(f, "{}", writer)
             }
+            Keystore::Ledger(ledger) => {
+                writeln!(writer, "Keystore Type : Ledger")?;
+                write!(writer, "Accounts : {}", ledger.accounts().len())?;
+                write!(f, "{}", writer)
+            }
         }
     }
 }
 
 #[derive(Default)]
 pub struct FileBasedKeystore {
-    keys: BTreeMap<SuiAddress, SuiKeyPair>,
+    /// The public keys, known without the passphrase of an encrypted keystore.
+    public_keys: BTreeMap<SuiAddress, PublicKey>,
+    /// The key pairs. Those of an encrypted keystore are only decrypted when first needed.
+    keys: OnceCell<BTreeMap<SuiAddress, SuiKeyPair>>,
+    /// The content of the file when it is encrypted, `None` for a plaintext keystore.
+    encrypted: Option<EncryptedKeystore>,
+    /// The passphrase an encrypted keystore was unlocked with, to encrypt it again on changes.
+    passphrase: OnceCell<Zeroizing<String>>,
     path: Option<PathBuf>,
 }
 
@@ -131,11 +149,8 @@ impl AccountKeystore for FileBasedKeystore {
     #[warn(deprecated)]
     fn sign(&self, address: &SuiAddress, msg: &[u8]) -> Result<Signature, signature::Error> {
         Ok(self
-            .keys
-            .get(address)
-            .ok_or_else(|| {
-                signature::Error::from_source(format!("Cannot find key for address: [{address}]"))
-            })?
+            .get_key(address)
+            .map_err(|e| signature::Error::from_source(e.to_string()))?
             .sign(msg))
     }
 
@@ -150,25 +165,29 @@ impl AccountKeystore for FileBasedKeystore {
     {
         Ok(Signature::new_secure(
             &IntentMessage::new(intent, msg),
-            self.keys.get(address).ok_or_else(|| {
-                signature::Error::from_source(format!("Cannot find key for address: [{address}]"))
-            })?,
+            self.get_key(address)
+                .map_err(|e| signature::Error::from_source(e.to_string()))?,
         ))
     }
 
     fn add_key(&mut self, keypair: SuiKeyPair) -> Result<(), anyhow::Error> {
         let address: SuiAddress = (&keypair.public()).into();
-        self.keys.insert(address, keypair);
+        self.unlocked_keys()?;
+        self.public_keys.insert(address, keypair.public());
+        self.keys.get_mut().unwrap().insert(address, keypair);
         self.save()?;
         Ok(())
     }
 
     fn keys(&self) -> Vec<PublicKey> {
-        self.keys.values().map(|key| key.public()).collect()
+        self.public_keys.values().cloned().collect()
     }
 
     fn get_key(&self, address: &SuiAddress) -> Result<&SuiKeyPair, anyhow::Error> {
-        match self.keys.get(address) {
+        if !self.public_keys.contains_key(address) {
+            return Err(anyhow!("Cannot find key for address: [{address}]"));
+        }
+        match self.unlocked_keys()?.get(address) {
             Some(key) => Ok(key),
             None => Err(anyhow!("Cannot find key for address: [{address}]")),
         }
@@ -176,50 +195,153 @@ impl AccountKeystore for FileBasedKeystore {
 }
 
 impl FileBasedKeystore {
+    /// Reads the keystore at `path`, either in plaintext, as a JSON array of Base64 encoded key
+    /// pairs, or encrypted. The key pairs of an encrypted keystore are only decrypted when they
+    /// are first used, with a passphrase prompted for then.
     pub fn new(path: &PathBuf) -> Result<Self, anyhow::Error> {
-        let keys = if path.exists() {
-            let reader = BufReader::new(File::open(path)?);
-            let kp_strings: Vec<String> = serde_json::from_reader(reader)?;
-            kp_strings
-                .iter()
-                .map(|kpstr| {
-                    let key = SuiKeyPair::decode_base64(kpstr);
-                    key.map(|k| (Into::<SuiAddress>::into(&k.public()), k))
-                })
-                .collect::<Result<BTreeMap<_, _>, _>>()
-                .map_err(|e| anyhow::anyhow!("Invalid Keypair file {:#?} {:?}", e, path))?
-        } else {
-            BTreeMap::new()
-        };
-
-        Ok(Self {
-            keys,
+        let mut keystore = Self {
             path: Some(path.to_path_buf()),
-        })
+            ..Default::default()
+        };
+        if !path.exists() {
+            keystore.set_keys(BTreeMap::new());
+            return Ok(keystore);
+        }
+        let reader = BufReader::new(File::open(path)?);
+        let content: KeystoreFile = serde_json::from_reader(reader)
+            .map_err(|e| anyhow::anyhow!("Invalid Keypair file {:#?} {:?}", e, path))?;
+        match content {
+            KeystoreFile::Plaintext(kp_strings) => {
+                let keys = kp_strings
+                    .iter()
+                    .map(|kpstr| {
+                        let key = SuiKeyPair::decode_base64(kpstr);
+                        key.map(|k| (Into::<SuiAddress>::into(&k.public()), k))
+                    })
+                    .collect::<Result<BTreeMap<_, _>, _>>()
+                    .map_err(|e| anyhow::anyhow!("Invalid Keypair file {:#?} {:?}", e, path))?;
+                keystore.set_keys(keys);
+            }
+            KeystoreFile::Encrypted(encrypted) => {
+                keystore.public_keys = encrypted
+                    .public_keys()?
+                    .into_iter()
+                    .map(|pk| ((&pk).into(), pk))
+                    .collect();
+                keystore.encrypted = Some(encrypted);
+            }
+        }
+        Ok(keystore)
     }
 
     pub fn set_path(&mut self, path: &Path) {
         self.path = Some(path.to_path_buf());
     }
 
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    pub fn is_encrypted(&self) -> bool {
+        self.encrypted.is_some()
+    }
+
+    /// Decrypts the key pairs of an encrypted keystore with `passphrase`, if they are not already.
+    pub fn unlock(&self, passphrase: &str) -> Result<(), anyhow::Error> {
+        let Some(encrypted) = &self.encrypted else {
+            return Ok(());
+        };
+        self.keys.get_or_try_init(|| {
+            let keys = encrypted
+                .decrypt(passphrase)?
+                .into_iter()
+                .map(|k| ((&k.public()).into(), k))
+                .collect();
+            let _ = self.passphrase.set(Zeroizing::new(passphrase.to_string()));
+            Ok::<_, anyhow::Error>(keys)
+        })?;
+        Ok(())
+    }
+
+    /// Encrypts the keystore with `passphrase` and saves it. An encrypted keystore must be
+    /// unlocked first, and gets its passphrase changed.
+    pub fn encrypt(&mut self, passphrase: &str) -> Result<(), anyhow::Error> {
+        let keys = self.unlocked_keys()?;
+        let encrypted = EncryptedKeystore::encrypt(keys.values(), passphrase)?;
+        self.encrypted = Some(encrypted);
+        self.passphrase = OnceCell::with_value(Zeroizing::new(passphrase.to_string()));
+        self.save()
+    }
+
+    /// Saves the keystore in plaintext, decrypting its key pairs if it is encrypted.
+    pub fn decrypt(&mut self) -> Result<(), anyhow::Error> {
+        self.unlocked_keys()?;
+        self.encrypted = None;
+        self.passphrase = OnceCell::new();
+        self.save()
+    }
+
     pub fn save(&self) -> Result<(), anyhow::Error> {
         if let Some(path) = &self.path {
-            let store = serde_json::to_string_pretty(
-                &self
-                    .keys
-                    .values()
-                    .map(EncodeDecodeBase64::encode_base64)
-                    .collect::<Vec<_>>(),
-            )
+            // The key pairs of a locked keystore have not changed since it was read.
+            let Some(keys) = self.keys.get() else {
+                return Ok(());
+            };
+            let store = match self.passphrase.get() {
+                Some(passphrase) => serde_json::to_string_pretty(&EncryptedKeystore::encrypt(
+                    keys.values(),
+                    passphrase,
+                )?),
+                None => serde_json::to_string_pretty(
+                    &keys
+                        .values()
+                        .map(EncodeDecodeBase64::encode_base64)
+                        .collect::<Vec<_>>(),
+                ),
+            }
             .unwrap();
             fs::write(path, store)?
         }
         Ok(())
     }
 
-    pub fn key_pairs(&self) -> Vec<&SuiKeyPair> {
-        self.keys.values().collect()
+    /// The key pairs, prompting for the passphrase of an encrypted keystore that is not unlocked.
+    pub fn key_pairs(&self) -> Result<Vec<&SuiKeyPair>, anyhow::Error> {
+        Ok(self.unlocked_keys()?.values().collect())
     }
+
+    /// Takes the key pairs, prompting for the passphrase of an encrypted keystore that is not
+    /// unlocked.
+    pub fn into_key_pairs(self) -> Result<Vec<SuiKeyPair>, anyhow::Error> {
+        self.unlocked_keys()?;
+        Ok(self
+            .keys
+            .into_inner()
+            .unwrap_or_default()
+            .into_values()
+            .collect())
+    }
+
+    fn unlocked_keys(&self) -> Result<&BTreeMap<SuiAddress, SuiKeyPair>, anyhow::Error> {
+        if self.keys.get().is_none() && self.is_encrypted() {
+            let path = self.path.clone().unwrap_or_default();
+            self.unlock(&read_passphrase(&path)?)?;
+        }
+        Ok(self.keys.get_or_init(BTreeMap::new))
+    }
+
+    fn set_keys(&mut self, keys: BTreeMap<SuiAddress, SuiKeyPair>) {
+        self.public_keys = keys.iter().map(|(a, k)| (*a, k.public())).collect();
+        self.keys = OnceCell::with_value(keys);
+    }
+}
+
+/// The content of a keystore file, in either of its formats.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum KeystoreFile {
+    Plaintext(Vec<String>),
+    Encrypted(EncryptedKeystore),
 }
 
 #[derive(Default, Serialize, Deserialize)]
@@ -0,0 +1,346 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{anyhow, bail, ensure};
+use bip32::DerivationPath;
+use fastcrypto::ed25519::Ed25519PublicKey;
+use fastcrypto::hash::{HashFunction, Sha256};
+use fastcrypto::traits::ToFromBytes;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt::{Debug, Formatter};
+use std::sync::Arc;
+use sui_types::base_types::SuiAddress;
+use sui_types::crypto::{
+    Ed25519SuiSignature, PublicKey, Signature, SignatureScheme, SuiKeyPair, SuiSignatureInner,
+};
+use sui_types::intent::{Intent, IntentMessage};
+use tracing::info;
+
+use crate::key_derive::{
+    validate_path, DERIVATION_PATH_COIN_TYPE, DERVIATION_PATH_PURPOSE_ED25519,
+};
+use crate::keystore::AccountKeystore;
+
+/// The class of the instructions of the Sui Ledger app.
+const CLA: u8 = 0x00;
+const INS_GET_PUBLIC_KEY: u8 = 0x02;
+const INS_SIGN_TRANSACTION: u8 = 0x03;
+
+/// The Sui app reads the params of an instruction as chains of blocks, each starting with the
+/// SHA-256 hash of the next one, the last with zeros. It is sent the hash of the first block of
+/// each param, and asks for the blocks by hash as it reads them.
+const BLOCK_DATA_LENGTH: usize = 180;
+const HOST_START: u8 = 0x00;
+const HOST_GET_CHUNK_RESPONSE_SUCCESS: u8 = 0x01;
+const HOST_GET_CHUNK_RESPONSE_FAILURE: u8 = 0x02;
+const HOST_PUT_CHUNK_RESPONSE: u8 = 0x03;
+const HOST_RESULT_ACCUMULATING_RESPONSE: u8 = 0x04;
+const LEDGER_RESULT_ACCUMULATING: u8 = 0x00;
+const LEDGER_RESULT_FINAL: u8 = 0x01;
+const LEDGER_GET_CHUNK: u8 = 0x02;
+const LEDGER_PUT_CHUNK: u8 = 0x03;
+
+const SW_OK: u16 = 0x9000;
+const SW_DENIED: u16 = 0x6985;
+const SW_APP_NOT_OPEN: u16 = 0x6e00;
+
+/// Sends APDUs to a Ledger device running the Sui app, and returns its answers, status word
+/// included.
+pub trait LedgerTransport: Send + Sync {
+    fn exchange(&self, apdu: &[u8]) -> Result<Vec<u8>, anyhow::Error>;
+}
+
+/// The accounts of a Ledger device, whose keys never leave it. Their public keys are kept in the
+/// client config, so that their addresses are known without the device, which is only needed to
+/// sign.
+#[derive(Serialize, Deserialize, Default)]
+pub struct LedgerKeystore {
+    accounts: Vec<LedgerAccount>,
+    /// The device connection, opened over USB when first needed if not set.
+    #[serde(skip)]
+    transport: Option<Arc<dyn LedgerTransport>>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct LedgerAccount {
+    pub derivation_path: String,
+    pub public_key: PublicKey,
+}
+
+impl Debug for LedgerKeystore {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LedgerKeystore")
+            .field("accounts", &self.accounts.len())
+            .finish()
+    }
+}
+
+impl LedgerKeystore {
+    /// A keystore talking to the device over `transport` rather than over USB.
+    pub fn new_with_transport(transport: Arc<dyn LedgerTransport>) -> Self {
+        Self {
+            accounts: vec![],
+            transport: Some(transport),
+        }
+    }
+
+    pub fn accounts(&self) -> &[LedgerAccount] {
+        &self.accounts
+    }
+
+    /// Adds the account of the device at `derivation_path`, or at the next unused account index
+    /// of the Sui Ed25519 path `m/44'/784'/{index}'/0'/0'`.
+    pub fn add_account(
+        &mut self,
+        derivation_path: Option<DerivationPath>,
+    ) -> Result<SuiAddress, anyhow::Error> {
+        let derivation_path = match derivation_path {
+            Some(path) => path,
+            None => format!(
+                "m/{DERVIATION_PATH_PURPOSE_ED25519}'/{DERIVATION_PATH_COIN_TYPE}'/{}'/0'/0'",
+                self.accounts.len()
+            )
+            .parse()
+            .map_err(|e| anyhow!("Cannot parse path: {e}"))?,
+        };
+        let derivation_path = validate_path(&SignatureScheme::ED25519, Some(derivation_path))?;
+        let public_key = self.get_public_key(&derivation_path)?;
+        let address = (&public_key).into();
+        if !self.accounts.iter().any(|a| a.public_key == public_key) {
+            self.accounts.push(LedgerAccount {
+                derivation_path: derivation_path.to_string(),
+                public_key,
+            });
+        }
+        Ok(address)
+    }
+
+    fn get_public_key(&self, derivation_path: &DerivationPath) -> Result<PublicKey, anyhow::Error> {
+        // The key, then the address, each after its length.
+        let result = self.send_params(INS_GET_PUBLIC_KEY, &[serialize_path(derivation_path)])?;
+        let key = result
+            .split_first()
+            .and_then(|(&length, rest)| rest.get(..length as usize))
+            .ok_or_else(|| anyhow!("Invalid answer from the Ledger device"))?;
+        let public_key = Ed25519PublicKey::from_bytes(key)
+            .map_err(|e| anyhow!("Invalid public key from the Ledger device: {e}"))?;
+        Ok(PublicKey::Ed25519(public_key))
+    }
+
+    /// Signs `msg` on the device with the key of `address`, once the user approves it there.
+    fn sign_on_device(&self, address: &SuiAddress, msg: &[u8]) -> Result<Signature, anyhow::Error> {
+        let account = self
+            .accounts
+            .iter()
+            .find(|a| SuiAddress::from(&a.public_key) == *address)
+            .ok_or_else(|| anyhow!("Cannot find key for address: [{address}]"))?;
+        let path: DerivationPath = account
+            .derivation_path
+            .parse()
+            .map_err(|e| anyhow!("Invalid derivation path {}: {e}", account.derivation_path))?;
+
+        let mut transaction = u32::try_from(msg.len())?.to_le_bytes().to_vec();
+        transaction.extend_from_slice(msg);
+        info!("Confirm the transaction on the Ledger device");
+        let signature =
+            self.send_params(INS_SIGN_TRANSACTION, &[transaction, serialize_path(&path)])?;
+
+        let mut bytes = vec![Ed25519SuiSignature::SCHEME.flag()];
+        bytes.extend_from_slice(&signature);
+        bytes.extend_from_slice(account.public_key.as_ref());
+        Ok(Signature::from(
+            <Ed25519SuiSignature as ToFromBytes>::from_bytes(&bytes)
+                .map_err(|_| anyhow!("Invalid signature from the Ledger device"))?,
+        ))
+    }
+
+    /// Runs the instruction `ins` with `params` over the block protocol of the Sui app, and
+    /// returns its result.
+    fn send_params(&self, ins: u8, params: &[Vec<u8>]) -> Result<Vec<u8>, anyhow::Error> {
+        let mut blocks = HashMap::new();
+        let mut payload = vec![HOST_START];
+        for param in params {
+            let mut next = [0u8; 32];
+            for data in param.chunks(BLOCK_DATA_LENGTH).rev() {
+                let mut block = next.to_vec();
+                block.extend_from_slice(data);
+                next = Sha256::digest(&block).digest;
+                blocks.insert(next, block);
+            }
+            payload.extend_from_slice(&next);
+        }
+
+        let mut result = vec![];
+        loop {
+            let answer = self.exchange(ins, 0, 0, &payload)?;
+            let (&command, data) = answer
+                .split_first()
+                .ok_or_else(|| anyhow!("Invalid answer from the Ledger device"))?;
+            payload = match command {
+                LEDGER_RESULT_ACCUMULATING => {
+                    result.extend_from_slice(data);
+                    vec![HOST_RESULT_ACCUMULATING_RESPONSE]
+                }
+                LEDGER_RESULT_FINAL => {
+                    result.extend_from_slice(data);
+                    return Ok(result);
+                }
+                LEDGER_GET_CHUNK => {
+                    let block = <[u8; 32]>::try_from(data)
+                        .ok()
+                        .and_then(|hash| blocks.get(&hash));
+                    match block {
+                        Some(block) => [&[HOST_GET_CHUNK_RESPONSE_SUCCESS], &block[..]].concat(),
+                        None => vec![HOST_GET_CHUNK_RESPONSE_FAILURE],
+                    }
+                }
+                LEDGER_PUT_CHUNK => {
+                    blocks.insert(Sha256::digest(data).digest, data.to_vec());
+                    vec![HOST_PUT_CHUNK_RESPONSE]
+                }
+                command => bail!("Unknown command {command} from the Ledger device"),
+            };
+        }
+    }
+
+    fn exchange(&self, ins: u8, p1: u8, p2: u8, data: &[u8]) -> Result<Vec<u8>, anyhow::Error> {
+        let mut apdu = vec![CLA, ins, p1, p2, data.len() as u8];
+        apdu.extend_from_slice(data);
+        let answer = match &self.transport {
+            Some(transport) => transport.exchange(&apdu)?,
+            None => hid::connect()?.exchange(&apdu)?,
+        };
+        ensure!(answer.len() >= 2, "Invalid answer from the Ledger device");
+        let (data, status) = answer.split_at(answer.len() - 2);
+        match u16::from_be_bytes([status[0], status[1]]) {
+            SW_OK => Ok(data.to_vec()),
+            SW_DENIED => bail!("Rejected on the Ledger device"),
+            SW_APP_NOT_OPEN => bail!("Open the Sui app on the Ledger device"),
+            status => bail!("Ledger device error {status:#06x}"),
+        }
+    }
+}
+
+impl AccountKeystore for LedgerKeystore {
+    fn sign(&self, address: &SuiAddress, msg: &[u8]) -> Result<Signature, signature::Error> {
+        self.sign_on_device(address, msg)
+            .map_err(|e| signature::Error::from_source(e.to_string()))
+    }
+
+    fn sign_secure<T>(
+        &self,
+        address: &SuiAddress,
+        msg: &T,
+        intent: Intent,
+    ) -> Result<Signature, signature::Error>
+    where
+        T: Serialize,
+    {
+        let msg = bcs::to_bytes(&IntentMessage::new(intent, msg))
+            .map_err(|e| signature::Error::from_source(e.to_string()))?;
+        self.sign(address, &msg)
+    }
+
+    fn add_key(&mut self, _keypair: SuiKeyPair) -> Result<(), anyhow::Error> {
+        Err(anyhow!(
+            "Key pairs cannot be added to a Ledger keystore, only accounts of the device"
+        ))
+    }
+
+    fn keys(&self) -> Vec<PublicKey> {
+        self.accounts.iter().map(|a| a.public_key.clone()).collect()
+    }
+
+    fn get_key(&self, address: &SuiAddress) -> Result<&SuiKeyPair, anyhow::Error> {
+        Err(anyhow!(
+            "The private key of [{address}] is kept on the Ledger device"
+        ))
+    }
+
+    /// Adds the next account of the device, as Ledger keys cannot be generated elsewhere. There is
+    /// no recovery phrase to return, the device has it.
+    fn generate_and_add_new_key(
+        &mut self,
+        key_scheme: SignatureScheme,
+        derivation_path: Option<DerivationPath>,
+    ) -> Result<(SuiAddress, String, SignatureScheme), anyhow::Error> {
+        ensure!(
+            key_scheme == SignatureScheme::ED25519,
+            "The Sui Ledger app only supports ed25519 keys"
+        );
+        let address = self.add_account(derivation_path)?;
+        Ok((address, String::new(), key_scheme))
+    }
+
+    fn import_from_mnemonic(
+        &mut self,
+        _phrase: &str,
+        _key_scheme: SignatureScheme,
+        _derivation_path: Option<DerivationPath>,
+    ) -> Result<SuiAddress, anyhow::Error> {
+        Err(anyhow!(
+            "Mnemonics cannot be imported into a Ledger keystore, restore them on the device"
+        ))
+    }
+}
+
+/// Serializes a derivation path as the Sui app expects it: the number of levels, then each level
+/// as a little endian u32, hardened levels having their top bit set.
+fn serialize_path(path: &DerivationPath) -> Vec<u8> {
+    let levels = path.as_ref();
+    let mut bytes = vec![levels.len() as u8];
+    for level in levels {
+        bytes.extend_from_slice(&u32::from(*level).to_le_bytes());
+    }
+    bytes
+}
+
+#[cfg(feature = "ledger")]
+mod hid {
+    use super::LedgerTransport;
+    use anyhow::anyhow;
+    use ledger_transport::APDUCommand;
+    use ledger_transport_hid::hidapi::HidApi;
+    use ledger_transport_hid::TransportNativeHID;
+
+    /// The Ledger device plugged in over USB.
+    pub struct HidTransport(TransportNativeHID);
+
+    impl LedgerTransport for HidTransport {
+        fn exchange(&self, apdu: &[u8]) -> Result<Vec<u8>, anyhow::Error> {
+            let command = APDUCommand {
+                cla: apdu[0],
+                ins: apdu[1],
+                p1: apdu[2],
+                p2: apdu[3],
+                data: &apdu[5..],
+            };
+            let answer = self
+                .0
+                .exchange(&command)
+                .map_err(|e| anyhow!("Cannot talk to the Ledger device: {e}"))?;
+            let mut bytes = answer.data().to_vec();
+            bytes.extend_from_slice(&answer.retcode().to_be_bytes());
+            Ok(bytes)
+        }
+    }
+
+    pub fn connect() -> Result<HidTransport, anyhow::Error> {
+        let api = HidApi::new().map_err(|e| anyhow!("Cannot access USB devices: {e}"))?;
+        let transport = TransportNativeHID::new(&api)
+            .map_err(|e| anyhow!("Cannot find a Ledger device: {e}"))?;
+        Ok(HidTransport(transport))
+    }
+}
+
+#[cfg(not(feature = "ledger"))]
+mod hid {
+    use super::LedgerTransport;
+
+    pub fn connect() -> Result<Box<dyn LedgerTransport>, anyhow::Error> {
+        Err(anyhow::anyhow!(
+            "This binary was built without Ledger support, build it with the `ledger` feature"
+        ))
+    }
+}
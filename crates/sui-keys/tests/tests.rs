@@ -1,12 +1,21 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
+use std::fs;
 use std::str::FromStr;
+use std::sync::{Arc, Mutex};
 
-use fastcrypto::hash::{HashFunction, Sha3_256};
+use fastcrypto::hash::{HashFunction, Sha256, Sha3_256};
 use tempfile::TempDir;
 
+use sui_keys::encryption::EncryptedKeystore;
+use sui_keys::key_derive::derive_key_pair_from_path;
 use sui_keys::keystore::{AccountKeystore, FileBasedKeystore, Keystore};
-use sui_types::crypto::{SignatureScheme, SuiSignatureInner};
+use sui_keys::ledger::{LedgerKeystore, LedgerTransport};
+use sui_types::crypto::{
+    EncodeDecodeBase64, Signature, SignatureScheme, Signer, SuiKeyPair, SuiSignature,
+    SuiSignatureInner,
+};
+use sui_types::intent::{Intent, IntentMessage};
 use sui_types::{
     base_types::{SuiAddress, SUI_ADDRESS_LENGTH},
     crypto::Ed25519SuiSignature,
@@ -69,3 +78,209 @@ fn keystore_display_test() -> Result<(), anyhow::Error> {
     assert!(!keystore.to_string().contains("keys:"));
     Ok(())
 }
+
+#[test]
+fn encrypted_keystore_test() -> Result<(), anyhow::Error> {
+    let temp_dir = TempDir::new().unwrap();
+    let keystore_path = temp_dir.path().join("sui.keystore");
+    let mut keystore = FileBasedKeystore::new(&keystore_path)?;
+    let (address, _, _) = keystore.generate_and_add_new_key(SignatureScheme::ED25519, None)?;
+    let key_pair = keystore.get_key(&address)?.encode_base64();
+
+    keystore.encrypt("passphrase")?;
+    assert!(!fs::read_to_string(&keystore_path)?.contains(&key_pair));
+
+    // Addresses are known without the passphrase, but signing needs it.
+    let mut keystore = FileBasedKeystore::new(&keystore_path)?;
+    assert!(keystore.is_encrypted());
+    assert_eq!(keystore.addresses(), vec![address]);
+    assert!(keystore.unlock("wrong passphrase").is_err());
+    keystore.unlock("passphrase")?;
+    assert_eq!(keystore.get_key(&address)?.encode_base64(), key_pair);
+    keystore.sign_secure(&address, &"message", Intent::default())?;
+
+    // Keys added to an encrypted keystore are saved encrypted.
+    let (address2, _, _) = keystore.generate_and_add_new_key(SignatureScheme::Secp256k1, None)?;
+    let keystore = FileBasedKeystore::new(&keystore_path)?;
+    assert!(keystore.is_encrypted());
+    assert_eq!(keystore.addresses().len(), 2);
+    assert!(keystore.addresses().contains(&address2));
+
+    // Decrypting saves the keystore in the plaintext format again.
+    let mut keystore = keystore;
+    keystore.unlock("passphrase")?;
+    keystore.decrypt()?;
+    let keystore = FileBasedKeystore::new(&keystore_path)?;
+    assert!(!keystore.is_encrypted());
+    assert_eq!(keystore.get_key(&address)?.encode_base64(), key_pair);
+    assert_eq!(keystore.addresses().len(), 2);
+    Ok(())
+}
+
+#[test]
+fn encrypted_keystore_public_keys_test() -> Result<(), anyhow::Error> {
+    let temp_dir = TempDir::new().unwrap();
+    let keystore_path = temp_dir.path().join("sui.keystore");
+    let mut keystore = FileBasedKeystore::new(&keystore_path)?;
+    keystore.generate_and_add_new_key(SignatureScheme::ED25519, None)?;
+    keystore.encrypt("passphrase")?;
+    let mut other_keystore = FileBasedKeystore::new(&temp_dir.path().join("other.keystore"))?;
+    let (other_address, _, _) =
+        other_keystore.generate_and_add_new_key(SignatureScheme::ED25519, None)?;
+
+    // The public keys are kept in the clear, but can't be replaced without the keystore failing
+    // to unlock, e.g. to have funds sent to another address.
+    let mut encrypted: EncryptedKeystore =
+        serde_json::from_str(&fs::read_to_string(&keystore_path)?)?;
+    encrypted.public_keys = vec![other_keystore
+        .get_key(&other_address)?
+        .public()
+        .encode_base64()];
+    fs::write(&keystore_path, serde_json::to_string_pretty(&encrypted)?)?;
+
+    let keystore = FileBasedKeystore::new(&keystore_path)?;
+    assert_eq!(keystore.addresses(), vec![other_address]);
+    assert!(keystore.unlock("passphrase").is_err());
+    Ok(())
+}
+
+/// A Ledger device running the Sui app, holding the keys derived from `seed`.
+struct MockLedger {
+    seed: Vec<u8>,
+    state: Mutex<MockLedgerState>,
+}
+
+/// The params of the instruction the device is reading.
+#[derive(Default)]
+struct MockLedgerState {
+    ins: u8,
+    hashes: Vec<[u8; 32]>,
+    params: Vec<Vec<u8>>,
+    param: Vec<u8>,
+    next: [u8; 32],
+}
+
+impl MockLedger {
+    fn new(seed: Vec<u8>) -> Self {
+        Self {
+            seed,
+            state: Mutex::new(MockLedgerState::default()),
+        }
+    }
+
+    fn key_pair(&self, path: &[u8]) -> SuiKeyPair {
+        let levels = path[0] as usize;
+        let path = path[1..1 + 4 * levels]
+            .chunks(4)
+            .map(|level| {
+                let index = u32::from_le_bytes(level.try_into().unwrap());
+                format!("/{}'", index & !0x8000_0000)
+            })
+            .collect::<String>();
+        let (_, key_pair) = derive_key_pair_from_path(
+            &self.seed,
+            Some(format!("m{path}").parse().unwrap()),
+            &SignatureScheme::ED25519,
+        )
+        .unwrap();
+        key_pair
+    }
+
+    /// Asks for the next block of the params, or answers once all are read.
+    fn next_command(&self, state: &mut MockLedgerState) -> Vec<u8> {
+        while state.next == [0; 32] {
+            state.params.push(std::mem::take(&mut state.param));
+            match state.hashes.get(state.params.len()) {
+                Some(hash) => state.next = *hash,
+                None => return [&[0x01], &self.result(state)[..]].concat(),
+            }
+        }
+        [&[0x02], &state.next[..]].concat()
+    }
+
+    fn result(&self, state: &MockLedgerState) -> Vec<u8> {
+        match state.ins {
+            0x02 => {
+                let public_key = self.key_pair(&state.params[0]).public();
+                let address = SuiAddress::from(&public_key);
+                [
+                    &[public_key.as_ref().len() as u8],
+                    public_key.as_ref(),
+                    &[SUI_ADDRESS_LENGTH as u8],
+                    address.as_ref(),
+                ]
+                .concat()
+            }
+            _ => {
+                let transaction = &state.params[0];
+                let length = u32::from_le_bytes(transaction[..4].try_into().unwrap()) as usize;
+                assert_eq!(length, transaction.len() - 4);
+                let signature: Signature = self.key_pair(&state.params[1]).sign(&transaction[4..]);
+                signature.signature_bytes().to_vec()
+            }
+        }
+    }
+}
+
+impl LedgerTransport for MockLedger {
+    fn exchange(&self, apdu: &[u8]) -> Result<Vec<u8>, anyhow::Error> {
+        let (cla, ins, data) = (apdu[0], apdu[1], &apdu[5..]);
+        assert_eq!(cla, 0x00);
+        assert_eq!(apdu[4] as usize, data.len());
+        let mut state = self.state.lock().unwrap();
+        let mut answer = match data[0] {
+            // The start of an instruction, with the hash of the first block of each param.
+            0x00 => {
+                anyhow::ensure!(ins == 0x02 || ins == 0x03, "Unexpected instruction {ins}");
+                let hashes: Vec<[u8; 32]> = data[1..]
+                    .chunks(32)
+                    .map(|hash| hash.try_into().unwrap())
+                    .collect();
+                *state = MockLedgerState {
+                    ins,
+                    next: hashes[0],
+                    hashes,
+                    ..Default::default()
+                };
+                self.next_command(&mut state)
+            }
+            // The block asked for.
+            0x01 => {
+                let block = &data[1..];
+                assert_eq!(Sha256::digest(block).digest, state.next);
+                state.param.extend_from_slice(&block[32..]);
+                state.next = block[..32].try_into().unwrap();
+                self.next_command(&mut state)
+            }
+            command => anyhow::bail!("Unexpected command {command}"),
+        };
+        answer.extend_from_slice(&[0x90, 0x00]);
+        Ok(answer)
+    }
+}
+
+#[test]
+fn ledger_keystore_test() -> Result<(), anyhow::Error> {
+    let seed = vec![7u8; 64];
+    let mut keystore = Keystore::from(LedgerKeystore::new_with_transport(Arc::new(
+        MockLedger::new(seed.clone()),
+    )));
+    let (address, phrase, _) = keystore.generate_and_add_new_key(SignatureScheme::ED25519, None)?;
+    assert!(phrase.is_empty());
+    let (expected, _) = derive_key_pair_from_path(&seed, None, &SignatureScheme::ED25519)?;
+    assert_eq!(address, expected);
+    let (address2, _, _) = keystore.generate_and_add_new_key(SignatureScheme::ED25519, None)?;
+    assert_ne!(address, address2);
+    assert_eq!(keystore.addresses(), vec![address, address2]);
+
+    assert!(keystore
+        .generate_and_add_new_key(SignatureScheme::Secp256k1, None)
+        .is_err());
+    assert!(keystore.get_key(&address).is_err());
+
+    // A message longer than a block is sent in several blocks.
+    let msg = vec![1u8; 600];
+    let signature = keystore.sign_secure(&address2, &msg, Intent::default())?;
+    signature.verify_secure(&IntentMessage::new(Intent::default(), msg), address2)?;
+    Ok(())
+}
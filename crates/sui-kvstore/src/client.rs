@@ -0,0 +1,130 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::Key;
+use anyhow::{anyhow, Result};
+use serde::de::DeserializeOwned;
+use std::time::Duration;
+use sui_storage::archive::ArchiveStore;
+use sui_types::base_types::TransactionDigest;
+use sui_types::digests::{CheckpointContentsDigest, CheckpointDigest};
+use sui_types::messages::{Transaction, TransactionEffects, TransactionEffectsAPI};
+use sui_types::messages_checkpoint::{
+    CertifiedCheckpointSummary, CheckpointContents, CheckpointSequenceNumber,
+};
+
+/// Reads the entries of the store, checking that they are the ones that were requested.
+/// Checkpoints carry the signatures of the committee and can be verified by the caller, while
+/// transactions and effects are only checked against their digests.
+pub struct KvStoreClient {
+    store: ArchiveStore,
+}
+
+impl KvStoreClient {
+    /// A client of the service, or of the bucket, at `url`.
+    pub fn new(url: &str, request_timeout: Duration) -> Result<Self> {
+        Ok(Self::from_store(ArchiveStore::http(
+            url,
+            None,
+            request_timeout,
+        )?))
+    }
+
+    pub fn from_store(store: ArchiveStore) -> Self {
+        Self { store }
+    }
+
+    pub async fn get_transaction(&self, digest: TransactionDigest) -> Result<Option<Transaction>> {
+        let Some(transaction) = self.get::<Transaction>(Key::Transaction(digest)).await? else {
+            return Ok(None);
+        };
+        if *transaction.digest() != digest {
+            return Err(anyhow!(
+                "The store returned a different transaction for {digest}"
+            ));
+        }
+        Ok(Some(transaction))
+    }
+
+    /// The effects of the transaction with `digest`.
+    pub async fn get_effects(
+        &self,
+        digest: TransactionDigest,
+    ) -> Result<Option<TransactionEffects>> {
+        let Some(effects) = self.get::<TransactionEffects>(Key::Effects(digest)).await? else {
+            return Ok(None);
+        };
+        if *effects.transaction_digest() != digest {
+            return Err(anyhow!(
+                "The store returned the effects of {} for {digest}",
+                effects.transaction_digest()
+            ));
+        }
+        Ok(Some(effects))
+    }
+
+    pub async fn get_checkpoint(
+        &self,
+        digest: CheckpointDigest,
+    ) -> Result<Option<CertifiedCheckpointSummary>> {
+        let Some(checkpoint) = self
+            .get::<CertifiedCheckpointSummary>(Key::Checkpoint(digest))
+            .await?
+        else {
+            return Ok(None);
+        };
+        if checkpoint.digest() != digest {
+            return Err(anyhow!(
+                "The store returned a different checkpoint for {digest}"
+            ));
+        }
+        Ok(Some(checkpoint))
+    }
+
+    pub async fn get_checkpoint_by_sequence_number(
+        &self,
+        sequence_number: CheckpointSequenceNumber,
+    ) -> Result<Option<CertifiedCheckpointSummary>> {
+        let Some(digest) = self
+            .get::<CheckpointDigest>(Key::CheckpointDigest(sequence_number))
+            .await?
+        else {
+            return Ok(None);
+        };
+        let checkpoint = self.get_checkpoint(digest).await?.ok_or_else(|| {
+            anyhow!("Checkpoint {sequence_number} has digest {digest}, which is not in the store")
+        })?;
+        if checkpoint.sequence_number() != sequence_number {
+            return Err(anyhow!(
+                "The store returned checkpoint {} for checkpoint {sequence_number}",
+                checkpoint.sequence_number()
+            ));
+        }
+        Ok(Some(checkpoint))
+    }
+
+    pub async fn get_checkpoint_contents(
+        &self,
+        digest: CheckpointContentsDigest,
+    ) -> Result<Option<CheckpointContents>> {
+        let Some(contents) = self
+            .get::<CheckpointContents>(Key::CheckpointContents(digest))
+            .await?
+        else {
+            return Ok(None);
+        };
+        if contents.digest() != digest {
+            return Err(anyhow!(
+                "The store returned different checkpoint contents for {digest}"
+            ));
+        }
+        Ok(Some(contents))
+    }
+
+    async fn get<T: DeserializeOwned>(&self, key: Key) -> Result<Option<T>> {
+        let Some(bytes) = self.store.get(&key.to_string()).await? else {
+            return Ok(None);
+        };
+        Ok(Some(bcs::from_bytes(&bytes)?))
+    }
+}
@@ -0,0 +1,105 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! A key-value store of the transactions, effects and checkpoints of the network, kept in a
+//! bucket or a local directory, for fullnodes and SDKs to fall back on when their peers or RPC
+//! providers have pruned the data. Each entry is BCS encoded, with the following keys:
+//!
+//! - `transactions/<transaction digest>`: a [`Transaction`]
+//! - `effects/<transaction digest>`: the [`TransactionEffects`] of the transaction
+//! - `checkpoints/<checkpoint digest>`: a [`CertifiedCheckpointSummary`]
+//! - `checkpoint_digests/<sequence number>`: the [`CheckpointDigest`] of the checkpoint
+//! - `checkpoint_contents/<contents digest>`: a [`CheckpointContents`]
+//!
+//! The store is filled from an archive, see [`writer`], and served over HTTP with the same paths,
+//! see [`server`]. The [`client`] reads either the service or the bucket, and checks that the
+//! entries are the ones that were requested.
+//!
+//! [`Transaction`]: sui_types::messages::Transaction
+//! [`TransactionEffects`]: sui_types::messages::TransactionEffects
+//! [`CertifiedCheckpointSummary`]: sui_types::messages_checkpoint::CertifiedCheckpointSummary
+//! [`CheckpointContents`]: sui_types::messages_checkpoint::CheckpointContents
+
+pub mod client;
+pub mod server;
+pub mod writer;
+
+use fastcrypto::encoding::{Base58, Encoding};
+use std::fmt::{self, Display, Formatter};
+use sui_types::base_types::TransactionDigest;
+use sui_types::digests::{CheckpointContentsDigest, CheckpointDigest};
+use sui_types::messages_checkpoint::CheckpointSequenceNumber;
+
+/// The key of an entry of the store.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Key {
+    Transaction(TransactionDigest),
+    Effects(TransactionDigest),
+    Checkpoint(CheckpointDigest),
+    CheckpointDigest(CheckpointSequenceNumber),
+    CheckpointContents(CheckpointContentsDigest),
+}
+
+impl Key {
+    /// Parses the key with the path `<kind>/<key>`, if it is one.
+    pub fn parse(kind: &str, key: &str) -> Option<Self> {
+        match kind {
+            "transactions" => Some(Key::Transaction(TransactionDigest::new(parse_digest(key)?))),
+            "effects" => Some(Key::Effects(TransactionDigest::new(parse_digest(key)?))),
+            "checkpoints" => Some(Key::Checkpoint(CheckpointDigest::new(parse_digest(key)?))),
+            "checkpoint_digests" => Some(Key::CheckpointDigest(key.parse().ok()?)),
+            "checkpoint_contents" => Some(Key::CheckpointContents(CheckpointContentsDigest::new(
+                parse_digest(key)?,
+            ))),
+            _ => None,
+        }
+    }
+}
+
+/// The path of the entry in the store and in the service.
+impl Display for Key {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Key::Transaction(digest) => write!(f, "transactions/{digest}"),
+            Key::Effects(digest) => write!(f, "effects/{digest}"),
+            Key::Checkpoint(digest) => write!(f, "checkpoints/{digest}"),
+            Key::CheckpointDigest(sequence_number) => {
+                write!(f, "checkpoint_digests/{sequence_number}")
+            }
+            Key::CheckpointContents(digest) => write!(f, "checkpoint_contents/{digest}"),
+        }
+    }
+}
+
+/// Decodes a Base58 digest. Unlike the `FromStr` of the digests, it does not panic on an input of
+/// the wrong length.
+fn parse_digest(s: &str) -> Option<[u8; 32]> {
+    Base58::decode(s).ok()?.try_into().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key_paths() {
+        let keys = [
+            Key::Transaction(TransactionDigest::random()),
+            Key::Effects(TransactionDigest::random()),
+            Key::Checkpoint(CheckpointDigest::random()),
+            Key::CheckpointDigest(42),
+            Key::CheckpointContents(CheckpointContentsDigest::random()),
+        ];
+        for key in keys {
+            let path = key.to_string();
+            let (kind, key_part) = path.split_once('/').unwrap();
+            assert_eq!(Key::parse(kind, key_part), Some(key));
+        }
+
+        assert_eq!(Key::parse("objects", "42"), None);
+        assert_eq!(Key::parse("checkpoint_digests", "latest"), None);
+        // Not 32 bytes long.
+        assert_eq!(Key::parse("transactions", "3yndxsmJNXFr"), None);
+        assert_eq!(Key::parse("transactions", "../MANIFEST"), None);
+    }
+}
@@ -0,0 +1,140 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{anyhow, bail, Result};
+use clap::Parser;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use sui_kvstore::server::app;
+use sui_kvstore::writer::{KvStoreIngester, KvStoreWriter};
+use sui_storage::archive::{ArchiveReader, ArchiveStore};
+use tracing::info;
+
+#[derive(Parser)]
+#[clap(
+    name = "sui-kvstore",
+    about = "Serves transactions, effects and checkpoints by digest from a bucket",
+    rename_all = "kebab-case"
+)]
+enum Command {
+    /// Serves the entries of the store over HTTP
+    Serve {
+        #[clap(long, default_value = "0.0.0.0:9300")]
+        listen_address: SocketAddr,
+
+        /// The URL of the bucket of the store
+        #[clap(long)]
+        store_url: Option<String>,
+
+        /// The directory of the store, instead of a bucket
+        #[clap(long)]
+        store_dir: Option<PathBuf>,
+
+        #[clap(long, default_value_t = 30)]
+        request_timeout_secs: u64,
+    },
+    /// Fills the store with the checkpoints of an archive and their transactions
+    Ingest {
+        /// The URL of the archive that the checkpoints are read from, with their transactions
+        #[clap(long)]
+        archive_url: Option<String>,
+
+        /// The directory of the archive, instead of its URL
+        #[clap(long)]
+        archive_dir: Option<PathBuf>,
+
+        /// The URL of the bucket of the store
+        #[clap(long)]
+        store_url: Option<String>,
+
+        /// The directory of the store, instead of a bucket
+        #[clap(long)]
+        store_dir: Option<PathBuf>,
+
+        /// The environment variable holding the token that the writes to the bucket are
+        /// authorized with
+        #[clap(long)]
+        store_auth_token_env_var: Option<String>,
+
+        #[clap(long, default_value_t = 60)]
+        ingest_interval_secs: u64,
+
+        #[clap(long, default_value_t = 30)]
+        request_timeout_secs: u64,
+    },
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let _guard = telemetry_subscribers::TelemetryConfig::new()
+        .with_env()
+        .init();
+
+    match Command::parse() {
+        Command::Serve {
+            listen_address,
+            store_url,
+            store_dir,
+            request_timeout_secs,
+        } => {
+            let store = open_store(
+                store_dir,
+                store_url.as_deref(),
+                None,
+                Duration::from_secs(request_timeout_secs),
+            )?;
+            info!("Listening on {listen_address}");
+            axum::Server::bind(&listen_address)
+                .serve(app(Arc::new(store)).into_make_service())
+                .await?;
+        }
+        Command::Ingest {
+            archive_url,
+            archive_dir,
+            store_url,
+            store_dir,
+            store_auth_token_env_var,
+            ingest_interval_secs,
+            request_timeout_secs,
+        } => {
+            let request_timeout = Duration::from_secs(request_timeout_secs);
+            let reader = ArchiveReader::from_store(open_store(
+                archive_dir,
+                archive_url.as_deref(),
+                None,
+                request_timeout,
+            )?);
+            let auth_token = store_auth_token_env_var
+                .map(|var| {
+                    std::env::var(&var)
+                        .map_err(|e| anyhow!("Cannot read store auth token from {var}: {e}"))
+                })
+                .transpose()?;
+            let writer = KvStoreWriter::new(open_store(
+                store_dir,
+                store_url.as_deref(),
+                auth_token,
+                request_timeout,
+            )?);
+            KvStoreIngester::new(reader, writer, Duration::from_secs(ingest_interval_secs))
+                .run()
+                .await;
+        }
+    }
+    Ok(())
+}
+
+fn open_store(
+    dir: Option<PathBuf>,
+    url: Option<&str>,
+    auth_token: Option<String>,
+    request_timeout: Duration,
+) -> Result<ArchiveStore> {
+    match (dir, url) {
+        (Some(dir), None) => Ok(ArchiveStore::Directory(dir)),
+        (None, Some(url)) => ArchiveStore::http(url, auth_token, request_timeout),
+        _ => bail!("Exactly one of a directory and a URL must be given for each store"),
+    }
+}
@@ -0,0 +1,54 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Serves the entries of the store at their paths, e.g. `GET /transactions/<digest>`, as BCS
+//! encoded bodies. The entries never change, so they are cached for as long as clients like.
+
+use crate::Key;
+use axum::body::Bytes;
+use axum::extract::Path;
+use axum::http::{header, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::{Extension, Router};
+use std::sync::Arc;
+use sui_storage::archive::ArchiveStore;
+use tracing::warn;
+
+const CACHE_CONTROL: &str = "public, max-age=31536000, immutable";
+
+pub fn app(store: Arc<ArchiveStore>) -> Router {
+    Router::new()
+        .route("/", get(health))
+        .route("/:kind/:key", get(get_entry))
+        .layer(Extension(store))
+}
+
+async fn health() -> &'static str {
+    "OK"
+}
+
+async fn get_entry(
+    Extension(store): Extension<Arc<ArchiveStore>>,
+    Path((kind, key)): Path<(String, String)>,
+) -> Response {
+    // Only the paths of keys are read, and nothing else in the bucket.
+    let Some(key) = Key::parse(&kind, &key) else {
+        return (StatusCode::BAD_REQUEST, format!("Invalid key {kind}/{key}")).into_response();
+    };
+    match store.get(&key.to_string()).await {
+        Ok(Some(bytes)) => (
+            [
+                (header::CONTENT_TYPE, "application/octet-stream"),
+                (header::CACHE_CONTROL, CACHE_CONTROL),
+            ],
+            Bytes::from(bytes),
+        )
+            .into_response(),
+        Ok(None) => (StatusCode::NOT_FOUND, format!("{key} is not in the store")).into_response(),
+        Err(e) => {
+            warn!("Failed to read {key}: {e:#}");
+            (StatusCode::BAD_GATEWAY, format!("Failed to read {key}")).into_response()
+        }
+    }
+}
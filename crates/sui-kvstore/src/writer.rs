@@ -0,0 +1,199 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Fills the store from an archive, see [`sui_storage::archive`], which must have the
+//! transactions of its checkpoints. The progress is kept in the store, at `PROGRESS`.
+
+use crate::Key;
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use sui_storage::archive::{ArchiveReader, ArchiveStore, ArchivedCheckpoint, ArchivedTransaction};
+use sui_types::messages_checkpoint::CheckpointSequenceNumber;
+use tracing::{info, warn};
+
+const PROGRESS_PATH: &str = "PROGRESS";
+
+/// How far the ingestion went, written after the entries it accounts for.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct IngestProgress {
+    /// The checkpoints before this one are in the store.
+    pub next_checkpoint: CheckpointSequenceNumber,
+}
+
+pub struct KvStoreWriter {
+    store: ArchiveStore,
+}
+
+impl KvStoreWriter {
+    pub fn new(store: ArchiveStore) -> Self {
+        Self { store }
+    }
+
+    /// How far the ingestion went, from the start for a new store.
+    pub async fn progress(&self) -> Result<IngestProgress> {
+        match self.store.get(PROGRESS_PATH).await? {
+            Some(bytes) => Ok(serde_json::from_slice(&bytes)?),
+            None => Ok(IngestProgress::default()),
+        }
+    }
+
+    pub async fn put_progress(&self, progress: &IngestProgress) -> Result<()> {
+        self.store
+            .put(PROGRESS_PATH, serde_json::to_vec_pretty(progress)?)
+            .await
+    }
+
+    pub async fn put_transaction(&self, transaction: &ArchivedTransaction) -> Result<()> {
+        let digest = *transaction.transaction.digest();
+        self.put(Key::Transaction(digest), &transaction.transaction)
+            .await?;
+        self.put(Key::Effects(digest), &transaction.effects).await
+    }
+
+    /// Writes the checkpoint, with the digest of its sequence number written last, so that it is
+    /// only found by sequence number once it is all written.
+    pub async fn put_checkpoint(&self, checkpoint: &ArchivedCheckpoint) -> Result<()> {
+        let summary = &checkpoint.summary;
+        self.put(
+            Key::CheckpointContents(summary.content_digest()),
+            &checkpoint.contents,
+        )
+        .await?;
+        self.put(Key::Checkpoint(summary.digest()), summary).await?;
+        self.put(
+            Key::CheckpointDigest(summary.sequence_number()),
+            &summary.digest(),
+        )
+        .await
+    }
+
+    async fn put<T: Serialize>(&self, key: Key, value: &T) -> Result<()> {
+        self.store
+            .put(&key.to_string(), bcs::to_bytes(value)?)
+            .await
+    }
+}
+
+pub struct KvStoreIngester {
+    reader: ArchiveReader,
+    writer: KvStoreWriter,
+    ingest_interval: Duration,
+}
+
+impl KvStoreIngester {
+    /// Ingests the checkpoints read by `reader` with `writer`, checking for new ones every
+    /// `ingest_interval`.
+    pub fn new(reader: ArchiveReader, writer: KvStoreWriter, ingest_interval: Duration) -> Self {
+        Self {
+            reader,
+            writer,
+            ingest_interval,
+        }
+    }
+
+    /// Ingests the new archived checkpoints every ingest interval, forever.
+    pub async fn run(self) {
+        let mut progress = match self.writer.progress().await {
+            Ok(progress) => progress,
+            Err(e) => {
+                warn!("Cannot read the progress of the ingestion, not ingesting: {e:#}");
+                return;
+            }
+        };
+        info!(
+            next_checkpoint = progress.next_checkpoint,
+            "Ingesting checkpoints"
+        );
+
+        let mut interval = tokio::time::interval(self.ingest_interval);
+        loop {
+            interval.tick().await;
+            if let Err(e) = self.ingest_new_checkpoints(&mut progress).await {
+                warn!(
+                    next_checkpoint = progress.next_checkpoint,
+                    "Failed to ingest checkpoints: {e:#}"
+                );
+            }
+        }
+    }
+
+    /// Ingests the checkpoints archived from `progress.next_checkpoint` on, and advances it.
+    pub async fn ingest_new_checkpoints(&self, progress: &mut IngestProgress) -> Result<()> {
+        while let Some(checkpoint) = self.reader.get_checkpoint(progress.next_checkpoint).await? {
+            let sequence_number = progress.next_checkpoint;
+            futures::future::try_join_all(checkpoint.contents.iter().map(|execution| {
+                let digest = execution.transaction;
+                async move {
+                    let transaction =
+                        self.reader.get_transaction(digest).await?.ok_or_else(|| {
+                            anyhow!(
+                            "Transaction {digest} of checkpoint {sequence_number} is not in the \
+                             archive, which must be written with its transactions"
+                        )
+                        })?;
+                    self.writer.put_transaction(&transaction).await
+                }
+            }))
+            .await?;
+            self.writer.put_checkpoint(&checkpoint).await?;
+
+            let next = IngestProgress {
+                next_checkpoint: sequence_number + 1,
+            };
+            self.writer.put_progress(&next).await?;
+            *progress = next;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::KvStoreClient;
+    use sui_types::base_types::ExecutionDigests;
+    use sui_types::committee::Committee;
+    use sui_types::messages_checkpoint::CheckpointContents;
+    use sui_types::utils::mock_certified_checkpoint;
+
+    #[tokio::test]
+    async fn test_checkpoint_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let writer = KvStoreWriter::new(ArchiveStore::Directory(dir.path().to_path_buf()));
+        let client = KvStoreClient::from_store(ArchiveStore::Directory(dir.path().to_path_buf()));
+        let (committee, keys) = Committee::new_simple_test_committee();
+        let summary = mock_certified_checkpoint(keys.iter(), committee, 7);
+        // Not the contents that the mock checkpoint commits to.
+        let contents = CheckpointContents::new_with_causally_ordered_transactions(
+            [ExecutionDigests::random()].into_iter(),
+        );
+        writer
+            .put_checkpoint(&ArchivedCheckpoint {
+                summary: summary.clone(),
+                contents,
+            })
+            .await
+            .unwrap();
+
+        let by_digest = client.get_checkpoint(summary.digest()).await.unwrap();
+        assert_eq!(by_digest.unwrap().digest(), summary.digest());
+        let by_sequence_number = client.get_checkpoint_by_sequence_number(7).await.unwrap();
+        assert_eq!(by_sequence_number.unwrap().digest(), summary.digest());
+        assert!(client
+            .get_checkpoint_by_sequence_number(8)
+            .await
+            .unwrap()
+            .is_none());
+        assert!(client
+            .get_checkpoint_contents(summary.content_digest())
+            .await
+            .is_err());
+
+        assert_eq!(writer.progress().await.unwrap(), IngestProgress::default());
+        let progress = IngestProgress { next_checkpoint: 8 };
+        writer.put_progress(&progress).await.unwrap();
+        assert_eq!(writer.progress().await.unwrap(), progress);
+    }
+}
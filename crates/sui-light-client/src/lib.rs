@@ -0,0 +1,410 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Verification of checkpoints and of the transactions in them, for clients that do not run a
+//! node. A light client only trusts the committee of genesis: the committee of each next epoch
+//! is handed off in the last checkpoint of the epoch before, certified by the committee that it
+//! replaces, see [`verify_committee_handoff`].
+//!
+//! The crate carries its own [`types`] rather than depending on `sui-types`, which brings the
+//! network and the storage of a node with it.
+
+pub mod types;
+
+use fastcrypto::bls12381::min_sig::BLS12381PublicKey;
+use fastcrypto::traits::{AggregateAuthenticator, ToFromBytes};
+use thiserror::Error;
+use types::{
+    CertifiedCheckpointSummary, CheckpointContents, CheckpointContentsDigest,
+    CheckpointSequenceNumber, Committee, EpochId, ExecutionDigests, StakeUnit,
+    TransactionInclusion, TransactionInclusionProof,
+};
+
+/// The intent that authorities sign checkpoint summaries with: the checkpoint summary scope, the
+/// first version of intents, and the Sui app.
+const CHECKPOINT_SUMMARY_INTENT: [u8; 3] = [2, 0, 0];
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum LightClientError {
+    #[error("Checkpoint {sequence_number} is of epoch {actual}, not of epoch {expected}")]
+    WrongEpoch {
+        sequence_number: CheckpointSequenceNumber,
+        expected: EpochId,
+        actual: EpochId,
+    },
+    #[error("Signer {index} of checkpoint {sequence_number} is not in the committee")]
+    UnknownSigner {
+        sequence_number: CheckpointSequenceNumber,
+        index: u32,
+    },
+    #[error(
+        "Checkpoint {sequence_number} is signed by a stake of {stake}, below the quorum of \
+         {threshold}"
+    )]
+    InsufficientStake {
+        sequence_number: CheckpointSequenceNumber,
+        stake: StakeUnit,
+        threshold: StakeUnit,
+    },
+    #[error("Invalid signature of checkpoint {sequence_number}: {error}")]
+    InvalidSignature {
+        sequence_number: CheckpointSequenceNumber,
+        error: String,
+    },
+    #[error("Checkpoint {sequence_number} does not commit to contents {contents}")]
+    ContentsMismatch {
+        sequence_number: CheckpointSequenceNumber,
+        contents: CheckpointContentsDigest,
+    },
+    #[error("Checkpoint {sequence_number} is not the last checkpoint of epoch {epoch}")]
+    NotEndOfEpoch {
+        sequence_number: CheckpointSequenceNumber,
+        epoch: EpochId,
+    },
+    #[error("Invalid committee: {0}")]
+    InvalidCommittee(String),
+    #[error(
+        "Transaction {} with effects {} is not in checkpoint {sequence_number}",
+        .digests.transaction,
+        .digests.effects
+    )]
+    TransactionNotIncluded {
+        sequence_number: CheckpointSequenceNumber,
+        digests: ExecutionDigests,
+    },
+}
+
+/// Checks that `checkpoint` is certified by a quorum of `committee`, and that `contents` are the
+/// contents that it commits to, if given.
+pub fn verify_checkpoint(
+    committee: &Committee,
+    checkpoint: &CertifiedCheckpointSummary,
+    contents: Option<&CheckpointContents>,
+) -> Result<(), LightClientError> {
+    let sequence_number = checkpoint.sequence_number();
+    let signature = &checkpoint.auth_signature;
+    for epoch in [checkpoint.epoch(), signature.epoch] {
+        if epoch != committee.epoch {
+            return Err(LightClientError::WrongEpoch {
+                sequence_number,
+                expected: committee.epoch,
+                actual: epoch,
+            });
+        }
+    }
+
+    let mut stake = 0;
+    let mut public_keys = vec![];
+    for index in signature.signers_map.iter() {
+        let (name, votes) = committee
+            .voting_rights
+            .get(index as usize)
+            .filter(|(_, votes)| *votes > 0)
+            .ok_or(LightClientError::UnknownSigner {
+                sequence_number,
+                index,
+            })?;
+        stake += votes;
+        public_keys.push(BLS12381PublicKey::from_bytes(&name.0).map_err(|e| {
+            LightClientError::InvalidSignature {
+                sequence_number,
+                error: e.to_string(),
+            }
+        })?);
+    }
+    let threshold = committee.quorum_threshold();
+    if stake < threshold {
+        return Err(LightClientError::InsufficientStake {
+            sequence_number,
+            stake,
+            threshold,
+        });
+    }
+    let mut message = CHECKPOINT_SUMMARY_INTENT.to_vec();
+    message
+        .extend(bcs::to_bytes(&checkpoint.summary).expect("Message serialization should not fail"));
+    message.extend(bcs::to_bytes(&signature.epoch).expect("Message serialization should not fail"));
+    signature
+        .signature
+        .verify(&public_keys, &message)
+        .map_err(|e| LightClientError::InvalidSignature {
+            sequence_number,
+            error: e.to_string(),
+        })?;
+
+    if let Some(contents) = contents {
+        let digest = contents.digest();
+        if digest != checkpoint.summary.content_digest {
+            return Err(LightClientError::ContentsMismatch {
+                sequence_number,
+                contents: digest,
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Checks that `checkpoint` is the last checkpoint of the epoch of `committee`, certified by it,
+/// and returns the committee of the next epoch that it hands off to.
+pub fn verify_committee_handoff(
+    committee: &Committee,
+    checkpoint: &CertifiedCheckpointSummary,
+) -> Result<Committee, LightClientError> {
+    verify_checkpoint(committee, checkpoint, None)?;
+    let next_committee =
+        checkpoint
+            .next_epoch_committee()
+            .ok_or(LightClientError::NotEndOfEpoch {
+                sequence_number: checkpoint.sequence_number(),
+                epoch: checkpoint.epoch(),
+            })?;
+    Committee::new(
+        committee.epoch + 1,
+        next_committee.iter().cloned().collect(),
+    )
+}
+
+/// Checks that the transaction with `digests` was executed in `checkpoint`, which must be
+/// certified by `committee` and have `contents`. The digest of the effects commits to the
+/// transaction, so both are checked at once.
+pub fn verify_transaction_inclusion(
+    committee: &Committee,
+    checkpoint: &CertifiedCheckpointSummary,
+    contents: &CheckpointContents,
+    digests: &ExecutionDigests,
+) -> Result<(), LightClientError> {
+    verify_checkpoint(committee, checkpoint, Some(contents))?;
+    if !contents.iter().any(|executed| executed == digests) {
+        return Err(LightClientError::TransactionNotIncluded {
+            sequence_number: checkpoint.sequence_number(),
+            digests: *digests,
+        });
+    }
+    Ok(())
+}
+
+/// Checks that the transaction with `digests` was executed in the checkpoint of `proof`, which
+/// must be certified by `committee`, as served by `sui_getTransactionInclusionProof`.
+pub fn verify_transaction_inclusion_proof(
+    committee: &Committee,
+    proof: &TransactionInclusionProof,
+    digests: &ExecutionDigests,
+) -> Result<(), LightClientError> {
+    let checkpoint = &proof.checkpoint;
+    match &proof.inclusion {
+        TransactionInclusion::Contents(contents) => {
+            verify_transaction_inclusion(committee, checkpoint, contents, digests)
+        }
+        TransactionInclusion::MerkleProof(merkle_proof) => {
+            verify_checkpoint(committee, checkpoint, None)?;
+            let included = checkpoint
+                .summary
+                .transactions_merkle_root()
+                .map_or(false, |root| {
+                    merkle_proof.root_of(digests).as_ref() == Some(&root.digest)
+                });
+            if !included {
+                return Err(LightClientError::TransactionNotIncluded {
+                    sequence_number: checkpoint.sequence_number(),
+                    digests: *digests,
+                });
+            }
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::CheckpointDigest;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use serde::de::DeserializeOwned;
+    use serde::Serialize;
+    use sui_types::base_types::{ExecutionDigests as SuiExecutionDigests, TransactionDigest};
+    use sui_types::committee::{Committee as SuiCommittee, ProtocolVersion};
+    use sui_types::crypto::{get_key_pair_from_rng, AuthorityKeyPair, KeypairTraits};
+    use sui_types::gas::GasCostSummary;
+    use sui_types::messages::{TransactionEffects, TransactionEffectsV1};
+    use sui_types::messages_checkpoint::{
+        CertifiedCheckpointSummary as SuiCertifiedCheckpointSummary,
+        CheckpointContents as SuiCheckpointContents, CheckpointSummary, EndOfEpochData,
+        SignedCheckpointSummary, TransactionInclusionProof as SuiTransactionInclusionProof,
+    };
+
+    /// Decodes the BCS encoding of a value of `sui-types` as its counterpart in [`types`].
+    fn convert<T: Serialize, U: DeserializeOwned>(value: &T) -> U {
+        bcs::from_bytes(&bcs::to_bytes(value).unwrap()).unwrap()
+    }
+
+    fn committee(epoch: u64, seed: u8) -> (SuiCommittee, Vec<AuthorityKeyPair>) {
+        let mut rng = StdRng::from_seed([seed; 32]);
+        let keys: Vec<AuthorityKeyPair> =
+            (0..4).map(|_| get_key_pair_from_rng(&mut rng).1).collect();
+        let voting_rights = keys.iter().map(|k| (k.public().into(), 1)).collect();
+        (SuiCommittee::new(epoch, voting_rights).unwrap(), keys)
+    }
+
+    fn certify(
+        committee: &SuiCommittee,
+        keys: &[AuthorityKeyPair],
+        contents: &SuiCheckpointContents,
+        end_of_epoch_data: Option<EndOfEpochData>,
+        commit_to_merkle_root: bool,
+    ) -> SuiCertifiedCheckpointSummary {
+        let mut summary = CheckpointSummary::new(
+            committee.epoch(),
+            1,
+            contents.size() as u64,
+            contents,
+            None,
+            GasCostSummary::default(),
+            end_of_epoch_data,
+            0,
+        );
+        if commit_to_merkle_root {
+            summary
+                .checkpoint_commitments
+                .push(contents.transactions_merkle_root().into());
+        }
+        let signed = keys
+            .iter()
+            .map(|k| {
+                SignedCheckpointSummary::new_from_summary(summary.clone(), k.public().into(), k)
+            })
+            .collect();
+        SuiCertifiedCheckpointSummary::aggregate(signed, committee).unwrap()
+    }
+
+    fn random_effects() -> TransactionEffects {
+        TransactionEffects::V1(TransactionEffectsV1 {
+            transaction_digest: TransactionDigest::random(),
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn test_committee_handoff() {
+        let (genesis_committee, genesis_keys) = committee(0, 0);
+        let (next_committee, next_keys) = committee(1, 1);
+        let contents = SuiCheckpointContents::new_with_causally_ordered_transactions(
+            [SuiExecutionDigests::random()].into_iter(),
+        );
+        let end_of_epoch = certify(
+            &genesis_committee,
+            &genesis_keys,
+            &contents,
+            Some(EndOfEpochData {
+                next_epoch_committee: next_committee.voting_rights.clone(),
+                next_epoch_protocol_version: ProtocolVersion::MIN,
+                epoch_commitments: vec![],
+            }),
+            false,
+        );
+        let checkpoint = certify(&next_committee, &next_keys, &contents, None, false);
+        // The digests are the ones that sui-types computes.
+        let light_checkpoint: CertifiedCheckpointSummary = convert(&checkpoint);
+        let digest: CheckpointDigest = convert(&checkpoint.digest());
+        assert_eq!(light_checkpoint.digest(), digest);
+        let light_contents: CheckpointContents = convert(&contents);
+        let digest: CheckpointContentsDigest = convert(&contents.digest());
+        assert_eq!(light_contents.digest(), digest);
+
+        let genesis_committee: Committee = convert(&genesis_committee);
+        let handed_off =
+            verify_committee_handoff(&genesis_committee, &convert(&end_of_epoch)).unwrap();
+        assert_eq!(handed_off, convert::<_, Committee>(&next_committee));
+        // The next committee certifies the checkpoints of its epoch, and only those.
+        verify_checkpoint(&handed_off, &light_checkpoint, Some(&light_contents)).unwrap();
+        assert!(matches!(
+            verify_checkpoint(&genesis_committee, &light_checkpoint, None),
+            Err(LightClientError::WrongEpoch { .. })
+        ));
+        // Only the last checkpoint of an epoch hands off.
+        assert!(matches!(
+            verify_committee_handoff(&handed_off, &light_checkpoint),
+            Err(LightClientError::NotEndOfEpoch { .. })
+        ));
+
+        // A certificate does not carry over to another checkpoint.
+        let mut forged = light_checkpoint;
+        forged.summary.timestamp_ms += 1;
+        assert!(matches!(
+            verify_checkpoint(&handed_off, &forged, None),
+            Err(LightClientError::InvalidSignature { .. })
+        ));
+        // Nor is one signer a quorum.
+        let mut forged: CertifiedCheckpointSummary = convert(&checkpoint);
+        forged.auth_signature.signers_map = [0].into_iter().collect();
+        assert!(matches!(
+            verify_checkpoint(&handed_off, &forged, None),
+            Err(LightClientError::InsufficientStake { .. })
+        ));
+    }
+
+    #[test]
+    fn test_transaction_inclusion() {
+        let (committee, keys) = committee(0, 0);
+        let effects = random_effects();
+        let contents = SuiCheckpointContents::new_with_causally_ordered_transactions(
+            [SuiExecutionDigests::random(), effects.execution_digests()].into_iter(),
+        );
+        let checkpoint = certify(&committee, &keys, &contents, None, false);
+        let digests: ExecutionDigests = convert(&effects.execution_digests());
+        let other_digests: ExecutionDigests = convert(&random_effects().execution_digests());
+        let light_committee: Committee = convert(&committee);
+        let light_checkpoint: CertifiedCheckpointSummary = convert(&checkpoint);
+        let light_contents: CheckpointContents = convert(&contents);
+
+        verify_transaction_inclusion(
+            &light_committee,
+            &light_checkpoint,
+            &light_contents,
+            &digests,
+        )
+        .unwrap();
+        assert!(matches!(
+            verify_transaction_inclusion(
+                &light_committee,
+                &light_checkpoint,
+                &light_contents,
+                &other_digests
+            ),
+            Err(LightClientError::TransactionNotIncluded { .. })
+        ));
+        // The contents must be the ones that the checkpoint commits to.
+        let other_contents = SuiCheckpointContents::new_with_causally_ordered_transactions(
+            [effects.execution_digests()].into_iter(),
+        );
+        assert!(matches!(
+            verify_transaction_inclusion(
+                &light_committee,
+                &light_checkpoint,
+                &convert(&other_contents),
+                &digests
+            ),
+            Err(LightClientError::ContentsMismatch { .. })
+        ));
+
+        for commit_to_merkle_root in [false, true] {
+            let checkpoint = certify(&committee, &keys, &contents, None, commit_to_merkle_root);
+            let proof = SuiTransactionInclusionProof::new(
+                checkpoint,
+                contents.clone(),
+                &effects.execution_digests(),
+            )
+            .unwrap();
+            let proof: TransactionInclusionProof = convert(&proof);
+            assert_eq!(
+                matches!(proof.inclusion, TransactionInclusion::MerkleProof(_)),
+                commit_to_merkle_root
+            );
+            verify_transaction_inclusion_proof(&light_committee, &proof, &digests).unwrap();
+            assert!(
+                verify_transaction_inclusion_proof(&light_committee, &proof, &other_digests)
+                    .is_err()
+            );
+        }
+    }
+}
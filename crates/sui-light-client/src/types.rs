@@ -0,0 +1,324 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! The checkpoints and committees of Sui, as light clients receive them. Each type has the same
+//! BCS encoding as its counterpart in `sui-types`, which the light client does not depend on, so
+//! the checkpoints that fullnodes serve decode into them with `bcs::from_bytes`.
+
+use crate::LightClientError;
+use fastcrypto::bls12381::min_sig::BLS12381AggregateSignature;
+use fastcrypto::encoding::{Base58, Encoding};
+use fastcrypto::hash::{Digest, HashFunction, Sha3_256};
+use roaring::RoaringBitmap;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_with::{serde_as, Bytes, DeserializeAs, SerializeAs};
+use std::collections::BTreeMap;
+use std::fmt;
+
+pub type EpochId = u64;
+pub type StakeUnit = u64;
+pub type CheckpointSequenceNumber = u64;
+pub type CheckpointTimestamp = u64;
+
+/// A SHA3-256 digest, of a transaction, of its effects, of a checkpoint or of its contents.
+#[serde_as]
+#[derive(Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct Sha3Digest(#[serde_as(as = "Bytes")] pub [u8; 32]);
+
+impl fmt::Display for Sha3Digest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&Base58::encode(self.0))
+    }
+}
+
+impl fmt::Debug for Sha3Digest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+pub type TransactionDigest = Sha3Digest;
+pub type TransactionEffectsDigest = Sha3Digest;
+pub type CheckpointDigest = Sha3Digest;
+pub type CheckpointContentsDigest = Sha3Digest;
+
+/// The digest of `value` as Sui computes it for the type `name`: over the name of the type
+/// followed by the BCS encoding of the value.
+fn digest_of<T: Serialize>(name: &str, value: &T) -> Sha3Digest {
+    let mut hasher = Sha3_256::default();
+    hasher.update(format!("{name}::"));
+    hasher.update(bcs::to_bytes(value).expect("Message serialization should not fail"));
+    Sha3Digest(hasher.finalize().digest)
+}
+
+/// The digests of a transaction and of its effects, which commit to the transaction as well.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ExecutionDigests {
+    pub transaction: TransactionDigest,
+    pub effects: TransactionEffectsDigest,
+}
+
+/// The BLS12-381 public key of an authority, which names it.
+#[serde_as]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct AuthorityName(#[serde_as(as = "Bytes")] pub [u8; 96]);
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Committee {
+    pub epoch: EpochId,
+    /// Sorted by name. Authorities sign checkpoints by their index in it.
+    pub voting_rights: Vec<(AuthorityName, StakeUnit)>,
+    pub total_votes: StakeUnit,
+}
+
+impl Committee {
+    pub fn new(
+        epoch: EpochId,
+        voting_rights: BTreeMap<AuthorityName, StakeUnit>,
+    ) -> Result<Self, LightClientError> {
+        if voting_rights.is_empty() {
+            return Err(LightClientError::InvalidCommittee(
+                "committee has 0 members".into(),
+            ));
+        }
+        if voting_rights.values().all(|votes| *votes == 0) {
+            return Err(LightClientError::InvalidCommittee(
+                "at least one committee member must have non-zero stake.".into(),
+            ));
+        }
+        let total_votes = voting_rights.values().sum();
+        Ok(Self {
+            epoch,
+            voting_rights: voting_rights.into_iter().collect(),
+            total_votes,
+        })
+    }
+
+    /// The stake that certifies a checkpoint.
+    pub fn quorum_threshold(&self) -> StakeUnit {
+        2 * self.total_votes / 3 + 1
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GasCostSummary {
+    pub computation_cost: u64,
+    pub storage_cost: u64,
+    pub storage_rebate: u64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct ProtocolVersion(pub u64);
+
+/// The Sha256 digest of an EllipticCurveMultisetHash committing to the live object set.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ECMHLiveObjectSetDigest {
+    pub digest: Digest<32>,
+}
+
+/// The root of the Merkle tree over the execution digests of the transactions of a checkpoint.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TransactionsMerkleRoot {
+    pub digest: Digest<32>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CheckpointCommitment {
+    ECMHLiveObjectSetDigest(ECMHLiveObjectSetDigest),
+    TransactionsMerkleRoot(TransactionsMerkleRoot),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EndOfEpochData {
+    /// The committee of the next epoch, sorted by name.
+    pub next_epoch_committee: Vec<(AuthorityName, StakeUnit)>,
+    pub next_epoch_protocol_version: ProtocolVersion,
+    pub epoch_commitments: Vec<CheckpointCommitment>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CheckpointSummary {
+    pub epoch: EpochId,
+    pub sequence_number: CheckpointSequenceNumber,
+    pub network_total_transactions: u64,
+    pub content_digest: CheckpointContentsDigest,
+    pub previous_digest: Option<CheckpointDigest>,
+    pub epoch_rolling_gas_cost_summary: GasCostSummary,
+    pub timestamp_ms: CheckpointTimestamp,
+    pub checkpoint_commitments: Vec<CheckpointCommitment>,
+    /// Present only on the last checkpoint of the epoch.
+    pub end_of_epoch_data: Option<EndOfEpochData>,
+    pub version_specific_data: Vec<u8>,
+}
+
+impl CheckpointSummary {
+    pub fn digest(&self) -> CheckpointDigest {
+        digest_of("CheckpointSummary", self)
+    }
+
+    /// The Merkle root of the transactions, committed to from the protocol version that enables
+    /// it.
+    pub fn transactions_merkle_root(&self) -> Option<&TransactionsMerkleRoot> {
+        self.checkpoint_commitments
+            .iter()
+            .find_map(|commitment| match commitment {
+                CheckpointCommitment::TransactionsMerkleRoot(root) => Some(root),
+                _ => None,
+            })
+    }
+}
+
+/// The aggregate signature of a quorum of a committee, whose members are given by their index in
+/// the committee.
+#[serde_as]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AuthorityStrongQuorumSignInfo {
+    pub epoch: EpochId,
+    pub signature: BLS12381AggregateSignature,
+    #[serde_as(as = "SignersMap")]
+    pub signers_map: RoaringBitmap,
+}
+
+/// Encodes the signers of an [`AuthorityStrongQuorumSignInfo`] as the bytes of their bitmap.
+struct SignersMap;
+
+impl SerializeAs<RoaringBitmap> for SignersMap {
+    fn serialize_as<S: Serializer>(
+        source: &RoaringBitmap,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let mut bytes = vec![];
+        source
+            .serialize_into(&mut bytes)
+            .map_err(serde::ser::Error::custom)?;
+        Bytes::serialize_as(&bytes, serializer)
+    }
+}
+
+impl<'de> DeserializeAs<'de, RoaringBitmap> for SignersMap {
+    fn deserialize_as<D: Deserializer<'de>>(deserializer: D) -> Result<RoaringBitmap, D::Error> {
+        let bytes: Vec<u8> = Bytes::deserialize_as(deserializer)?;
+        RoaringBitmap::deserialize_from(&bytes[..]).map_err(serde::de::Error::custom)
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CertifiedCheckpointSummary {
+    pub summary: CheckpointSummary,
+    pub auth_signature: AuthorityStrongQuorumSignInfo,
+}
+
+impl CertifiedCheckpointSummary {
+    pub fn digest(&self) -> CheckpointDigest {
+        self.summary.digest()
+    }
+
+    pub fn epoch(&self) -> EpochId {
+        self.summary.epoch
+    }
+
+    pub fn sequence_number(&self) -> CheckpointSequenceNumber {
+        self.summary.sequence_number
+    }
+
+    pub fn next_epoch_committee(&self) -> Option<&[(AuthorityName, StakeUnit)]> {
+        self.summary
+            .end_of_epoch_data
+            .as_ref()
+            .map(|e| e.next_epoch_committee.as_slice())
+    }
+}
+
+/// The transactions of a checkpoint, in the order they were executed in.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CheckpointContents {
+    transactions: Vec<ExecutionDigests>,
+    /// The encoded signatures of the senders of each transaction, which the light client does not
+    /// check but which the digest of the contents covers.
+    user_signatures: Vec<Vec<Vec<u8>>>,
+}
+
+impl CheckpointContents {
+    pub fn iter(&self) -> impl Iterator<Item = &ExecutionDigests> + '_ {
+        self.transactions.iter()
+    }
+
+    pub fn digest(&self) -> CheckpointContentsDigest {
+        digest_of("CheckpointContents", self)
+    }
+}
+
+const LEAF_PREFIX: u8 = 0;
+const NODE_PREFIX: u8 = 1;
+
+fn leaf_hash(digests: &ExecutionDigests) -> Digest<32> {
+    let mut hasher = Sha3_256::default();
+    hasher.update([LEAF_PREFIX]);
+    hasher.update(digests.transaction.0);
+    hasher.update(digests.effects.0);
+    hasher.finalize()
+}
+
+fn node_hash(left: &Digest<32>, right: &Digest<32>) -> Digest<32> {
+    let mut hasher = Sha3_256::default();
+    hasher.update([NODE_PREFIX]);
+    hasher.update(left.digest);
+    hasher.update(right.digest);
+    hasher.finalize()
+}
+
+/// The path from the execution digests of a transaction to the Merkle root of the transactions of
+/// a checkpoint. The last node of a level with an odd number of nodes is promoted to the next
+/// level as is, so it has no sibling.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MerkleProof {
+    pub index: u64,
+    pub leaf_count: u64,
+    /// The siblings of the nodes on the path, from the leaf up.
+    pub siblings: Vec<Digest<32>>,
+}
+
+impl MerkleProof {
+    /// The root of the tree that `leaf` is in, according to the proof, or `None` if the proof is
+    /// malformed.
+    pub fn root_of(&self, leaf: &ExecutionDigests) -> Option<Digest<32>> {
+        if self.index >= self.leaf_count {
+            return None;
+        }
+        let mut hash = leaf_hash(leaf);
+        let mut siblings = self.siblings.iter();
+        let (mut position, mut count) = (self.index, self.leaf_count);
+        while count > 1 {
+            let promoted = position % 2 == 0 && position + 1 == count;
+            if !promoted {
+                let sibling = siblings.next()?;
+                hash = if position % 2 == 0 {
+                    node_hash(&hash, sibling)
+                } else {
+                    node_hash(sibling, &hash)
+                };
+            }
+            position /= 2;
+            count = (count + 1) / 2;
+        }
+        siblings.next().is_none().then_some(hash)
+    }
+}
+
+/// How a transaction is proven to be in a checkpoint.
+#[allow(clippy::large_enum_variant)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum TransactionInclusion {
+    /// The path to the Merkle root of the transactions that the checkpoint commits to.
+    MerkleProof(MerkleProof),
+    /// All the contents of the checkpoint, for checkpoints without a Merkle root.
+    Contents(CheckpointContents),
+}
+
+/// A proof that a transaction was executed in a certified checkpoint, as served by
+/// `sui_getTransactionInclusionProof`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TransactionInclusionProof {
+    pub checkpoint: CertifiedCheckpointSummary,
+    pub inclusion: TransactionInclusion,
+}
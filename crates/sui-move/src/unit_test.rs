@@ -2,7 +2,9 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use super::build;
-use clap::Parser;
+use anyhow::bail;
+use clap::{ArgEnum, Parser};
+use fastcrypto::hash::{HashFunction, Sha3_256};
 use move_cli::base::{
     self,
     test::{self, UnitTestResult},
@@ -12,9 +14,12 @@ use move_unit_test::{extensions::set_extension_hook, UnitTestingConfig};
 use move_vm_runtime::native_extensions::NativeContextExtensions;
 use move_vm_test_utils::gas_schedule::INITIAL_COST_SCHEDULE;
 use once_cell::sync::Lazy;
+use serde::Serialize;
 use std::{
     collections::BTreeMap,
+    io::Write,
     path::{Path, PathBuf},
+    sync::Mutex,
 };
 use sui_core::authority::TemporaryStore;
 use sui_framework::natives::{self, object_runtime::ObjectRuntime, NativesCostTable};
@@ -27,10 +32,28 @@ use sui_types::{
 // Move unit tests will halt after executing this many steps. This is a protection to avoid divergence
 const MAX_UNIT_TEST_INSTRUCTIONS: u64 = 100_000;
 
+/// The seed the digest of the transaction the tests run in is derived from, which is random
+/// without one.
+static TEST_SEED: Mutex<Option<u64>> = Mutex::new(None);
+
 #[derive(Parser)]
 pub struct Test {
     #[clap(flatten)]
     pub test: test::Test,
+    /// Seed of the test runtime, which otherwise runs the tests in a transaction with a random
+    /// digest. Runs with the same seed are reproducible.
+    #[clap(long)]
+    pub seed: Option<u64>,
+    /// Format of the results: `text`, or `json` for a report of the status, time and gas used of
+    /// every test.
+    #[clap(long, arg_enum, default_value = "text", ignore_case = true)]
+    pub format: ReportFormat,
+}
+
+#[derive(ArgEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReportFormat {
+    Text,
+    Json,
 }
 
 impl Test {
@@ -56,12 +79,48 @@ impl Test {
             dump_bytecode_as_base64,
             generate_struct_layouts,
         )?;
-        run_move_unit_tests(
+        *TEST_SEED.lock().unwrap() = self.seed;
+
+        // Patterns and JSON reports need the results, which are read from the output.
+        let pattern = unit_test_config
+            .filter
+            .as_deref()
+            .filter(|filter| TestNamePattern::is_pattern(filter))
+            .map(TestNamePattern::new);
+        if pattern.is_none() && self.format == ReportFormat::Text {
+            return run_move_unit_tests(
+                &rerooted_path,
+                build_config,
+                Some(unit_test_config),
+                self.test.compute_coverage,
+            );
+        }
+        let unit_test_config = match self.format {
+            // The time and gas used by tests are read from their statistics.
+            ReportFormat::Json => UnitTestingConfig {
+                report_statistics: unit_test_config.report_statistics.or(Some(None)),
+                ..unit_test_config
+            },
+            ReportFormat::Text => unit_test_config,
+        };
+        let (output, report) = run_move_unit_tests_with_report(
             &rerooted_path,
             build_config,
-            Some(unit_test_config),
+            unit_test_config,
+            pattern.as_ref(),
             self.test.compute_coverage,
-        )
+        )?;
+        match self.format {
+            ReportFormat::Text => print!("{output}"),
+            ReportFormat::Json => println!(
+                "{}",
+                serde_json::to_string_pretty(&TestReport {
+                    seed: self.seed,
+                    ..report.clone()
+                })?
+            ),
+        }
+        Ok(report.result())
     }
 }
 
@@ -81,7 +140,60 @@ pub fn run_move_unit_tests(
 
     let config = config
         .unwrap_or_else(|| UnitTestingConfig::default_with_bound(Some(MAX_UNIT_TEST_INSTRUCTIONS)));
+    run_move_unit_tests_to(
+        path,
+        build_config,
+        config,
+        compute_coverage,
+        &mut std::io::stdout(),
+    )
+}
+
+/// Runs the unit tests whose names match `pattern`, or all those selected by the filter of
+/// `config` without one. Returns the output of the run, without the tests that do not match
+/// `pattern`, with a report of the results, which have their time and gas used when `config`
+/// reports statistics.
+pub fn run_move_unit_tests_with_report(
+    path: &Path,
+    build_config: BuildConfig,
+    config: UnitTestingConfig,
+    pattern: Option<&TestNamePattern>,
+    compute_coverage: bool,
+) -> anyhow::Result<(String, TestReport)> {
+    Lazy::force(&SET_EXTENSION_HOOK);
+
+    let config = UnitTestingConfig {
+        // Move only filters by substring, so run the tests containing the longest literal part
+        // of the pattern, and leave out those that do not match the whole pattern.
+        filter: match pattern {
+            Some(pattern) => Some(pattern.literal().to_string()),
+            None => config.filter,
+        },
+        ..config
+    };
+    let mut output = vec![];
+    // The output is parsed, and printed as is only when it is not colored.
+    colored::control::set_override(false);
+    let result = run_move_unit_tests_to(path, build_config, config, compute_coverage, &mut output);
+    colored::control::unset_override();
+    let result = result?;
+
+    let output = String::from_utf8(output)?;
+    let report = TestReport::parse(&output, pattern, result)?;
+    let output = match pattern {
+        Some(pattern) => filter_output(&output, pattern, &report),
+        None => output,
+    };
+    Ok((output, report))
+}
 
+fn run_move_unit_tests_to<W: Write + Send>(
+    path: &Path,
+    build_config: BuildConfig,
+    config: UnitTestingConfig,
+    compute_coverage: bool,
+    writer: &mut W,
+) -> anyhow::Result<UnitTestResult> {
     move_cli::base::test::run_move_unit_tests(
         path,
         build_config,
@@ -92,16 +204,306 @@ pub fn run_move_unit_tests(
         natives::all_natives(MOVE_STDLIB_ADDRESS, SUI_FRAMEWORK_ADDRESS),
         Some(INITIAL_COST_SCHEDULE.clone()),
         compute_coverage,
-        &mut std::io::stdout(),
+        writer,
     )
 }
 
+/// A pattern the fully qualified names of tests are matched against, where `*` matches any
+/// characters and `?` any single character. A pattern without an address, or without a module,
+/// matches the names without them, so that `coin::test_*` and `test_*` both match
+/// `0x2::coin::test_split`.
+#[derive(Clone, Debug)]
+pub struct TestNamePattern {
+    pattern: String,
+}
+
+impl TestNamePattern {
+    pub fn new(pattern: &str) -> Self {
+        Self {
+            pattern: pattern.to_string(),
+        }
+    }
+
+    /// Whether `filter` has wildcards, rather than being a substring of the names to match.
+    pub fn is_pattern(filter: &str) -> bool {
+        filter.contains(['*', '?'])
+    }
+
+    pub fn matches(&self, name: &str) -> bool {
+        let mut suffix = name;
+        loop {
+            if glob_match(self.pattern.as_bytes(), suffix.as_bytes()) {
+                return true;
+            }
+            match suffix.split_once("::") {
+                Some((_, rest)) => suffix = rest,
+                None => return false,
+            }
+        }
+    }
+
+    /// The longest part of the pattern without wildcards, which the names it matches contain.
+    fn literal(&self) -> &str {
+        self.pattern
+            .split(['*', '?'])
+            .max_by_key(|part| part.len())
+            .unwrap_or_default()
+    }
+}
+
+fn glob_match(pattern: &[u8], name: &[u8]) -> bool {
+    match pattern.split_first() {
+        None => name.is_empty(),
+        Some((b'*', rest)) => (0..=name.len()).any(|i| glob_match(rest, &name[i..])),
+        Some((b'?', rest)) => !name.is_empty() && glob_match(rest, &name[1..]),
+        Some((c, rest)) => name.first() == Some(c) && glob_match(rest, &name[1..]),
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TestStatus {
+    Pass,
+    Fail,
+    Timeout,
+}
+
+/// The result of a unit test.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TestResult {
+    /// The fully qualified name of the test, `<address>::<module>::<function>`.
+    pub name: String,
+    pub status: TestStatus,
+    /// The time the test took to run, in seconds.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time_secs: Option<f64>,
+    /// The gas used by the test, in instructions executed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gas_used: Option<u64>,
+    /// What went wrong, for a test that did not pass.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub failure: Option<String>,
+}
+
+/// The results of a run of unit tests, in the order the tests are reported.
+#[derive(Clone, Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TestReport {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
+    pub passed: usize,
+    pub failed: usize,
+    pub tests: Vec<TestResult>,
+}
+
+impl TestReport {
+    /// Reads the results of the tests matching `pattern` from the output of a run, which lists
+    /// them as `[ PASS    ] <name>`, then their time and gas in the statistics table, then what
+    /// went wrong in a box per failed test, under the module of the test.
+    ///
+    /// The results Move keeps of a run are private to its test runner, which only returns
+    /// whether all tests passed. The output is therefore checked against that `result`, and
+    /// against the totals of its summary, so that a change of its format fails the run rather
+    /// than producing a wrong report.
+    pub fn parse(
+        output: &str,
+        pattern: Option<&TestNamePattern>,
+        result: UnitTestResult,
+    ) -> anyhow::Result<Self> {
+        let mut tests: Vec<TestResult> = vec![];
+        let mut failure: Option<(String, Vec<&str>)> = None;
+        let mut module = "";
+        // The results of all tests that ran, whether they match the pattern or not.
+        let (mut all_passed, mut all_failed) = (0, 0);
+        let mut summary = None;
+        for line in output.lines() {
+            if let Some((name, status)) = parse_result_line(line) {
+                if status == TestStatus::Pass {
+                    all_passed += 1;
+                } else {
+                    all_failed += 1;
+                }
+                if pattern.map_or(true, |pattern| pattern.matches(name)) {
+                    tests.push(TestResult {
+                        name: name.to_string(),
+                        status,
+                        time_secs: None,
+                        gas_used: None,
+                        failure: None,
+                    });
+                }
+            } else if let Some((name, time_secs, gas_used)) = parse_statistics_row(line) {
+                if let Some(test) = tests.iter_mut().find(|test| test.name == name) {
+                    test.time_secs = time_secs;
+                    test.gas_used = gas_used;
+                }
+            } else if let Some(name) = line
+                .strip_prefix("Failures in ")
+                .and_then(|line| line.strip_suffix(':'))
+            {
+                module = name;
+            } else if let Some(function) = parse_failure_header(line) {
+                failure = Some((format!("{module}::{function}"), vec![]));
+            } else if let Some((name, lines)) = &mut failure {
+                if line.starts_with('└') {
+                    if let Some(test) = tests.iter_mut().find(|test| &test.name == name) {
+                        test.failure = Some(lines.join("\n"));
+                    }
+                    failure = None;
+                } else {
+                    lines.push(line.strip_prefix('│').unwrap_or(line).trim_start());
+                }
+            } else if let Some(totals) = parse_summary(line) {
+                summary = Some(totals);
+            }
+        }
+        let Some((total, passed, failed)) = summary else {
+            bail!("The output of the Move test runner has no summary, its format may have changed");
+        };
+        if (all_passed + all_failed, all_passed, all_failed) != (total, passed, failed)
+            || (all_failed == 0) != (result == UnitTestResult::Success)
+        {
+            bail!(
+                "Read {all_passed} passed and {all_failed} failed tests from the output of the \
+                 Move test runner, which reports {passed} passed and {failed} failed tests, its \
+                 format may have changed"
+            );
+        }
+        let passed = tests
+            .iter()
+            .filter(|test| test.status == TestStatus::Pass)
+            .count();
+        Ok(Self {
+            seed: None,
+            passed,
+            failed: tests.len() - passed,
+            tests,
+        })
+    }
+
+    pub fn result(&self) -> UnitTestResult {
+        if self.failed == 0 {
+            UnitTestResult::Success
+        } else {
+            UnitTestResult::Failure
+        }
+    }
+
+    /// The last line of the output of a run, as printed by Move.
+    fn summary(&self) -> String {
+        format!(
+            "Test result: {}. Total tests: {}; passed: {}; failed: {}",
+            if self.failed == 0 { "OK" } else { "FAILED" },
+            self.tests.len(),
+            self.passed,
+            self.failed
+        )
+    }
+}
+
+fn parse_result_line(line: &str) -> Option<(&str, TestStatus)> {
+    let (status, name) = line.strip_prefix("[ ")?.split_once(" ]")?;
+    let status = match status.trim() {
+        "PASS" => TestStatus::Pass,
+        "FAIL" => TestStatus::Fail,
+        "TIMEOUT" => TestStatus::Timeout,
+        _ => return None,
+    };
+    Some((name.trim(), status))
+}
+
+/// The totals of the summary of a run, `Test result: OK. Total tests: <total>; passed: <passed>;
+/// failed: <failed>`.
+fn parse_summary(line: &str) -> Option<(usize, usize, usize)> {
+    let (_, totals) = line.strip_prefix("Test result: ")?.split_once(". ")?;
+    let mut totals = totals.split("; ").map(|total| total.split_once(": "));
+    match (totals.next()??, totals.next()??, totals.next()??) {
+        (("Total tests", total), ("passed", passed), ("failed", failed)) => Some((
+            total.parse().ok()?,
+            passed.parse().ok()?,
+            failed.trim_end().parse().ok()?,
+        )),
+        _ => None,
+    }
+}
+
+/// A row of the statistics table, `│ <name> │ <time> │ <gas> │`.
+fn parse_statistics_row(line: &str) -> Option<(&str, Option<f64>, Option<u64>)> {
+    let cells: Vec<_> = line.split('│').map(str::trim).collect();
+    match cells.as_slice() {
+        ["", name, time, gas, ""] => Some((name, time.parse().ok(), gas.parse().ok())),
+        _ => None,
+    }
+}
+
+/// The function of the box of a failed test, `┌── <function> ──────`.
+fn parse_failure_header(line: &str) -> Option<&str> {
+    let function = line.strip_prefix("┌──")?.trim_end_matches('─').trim();
+    (!function.is_empty()).then_some(function)
+}
+
+/// Leaves the tests that do not match `pattern` out of `output`, whose summary is replaced by
+/// that of `report`.
+fn filter_output(output: &str, pattern: &TestNamePattern, report: &TestReport) -> String {
+    let mut filtered = String::new();
+    let mut module = "";
+    // The header of the failures of a module, written before its first failure that matches.
+    let mut module_header = None;
+    let mut skip_failure = false;
+    for line in output.lines() {
+        let keep = if let Some((name, _)) = parse_result_line(line) {
+            pattern.matches(name)
+        } else if let Some((name, ..)) = parse_statistics_row(line) {
+            // The header row of the table is not a test.
+            !name.contains("::") || pattern.matches(name)
+        } else if let Some(name) = line.strip_suffix(": test") {
+            pattern.matches(name)
+        } else if let Some(name) = line
+            .strip_prefix("Failures in ")
+            .and_then(|line| line.strip_suffix(':'))
+        {
+            module = name;
+            module_header = Some(line);
+            false
+        } else if let Some(function) = parse_failure_header(line) {
+            skip_failure = !pattern.matches(&format!("{module}::{function}"));
+            if !skip_failure {
+                if let Some(header) = module_header.take() {
+                    filtered.push_str(header);
+                    filtered.push_str("\n\n");
+                }
+            }
+            !skip_failure
+        } else if skip_failure {
+            skip_failure = !line.starts_with('└');
+            false
+        } else if line.starts_with("Test result: ") {
+            filtered.push_str(&report.summary());
+            filtered.push('\n');
+            false
+        } else {
+            // Skip the blank line after the header of a module that is left out.
+            !(line.is_empty() && module_header.is_some() && filtered.ends_with("\n\n"))
+        };
+        if keep {
+            filtered.push_str(line);
+            filtered.push('\n');
+        }
+    }
+    filtered
+}
+
 fn new_testing_object_and_natives_cost_runtime(ext: &mut NativeContextExtensions) {
     let store = InMemoryStorage::new(vec![]);
+    let digest = match *TEST_SEED.lock().unwrap() {
+        Some(seed) => TransactionDigest::new(Sha3_256::digest(seed.to_le_bytes()).digest),
+        None => TransactionDigest::random(),
+    };
     let state_view = TemporaryStore::new(
         store,
         InputObjects::new(vec![]),
-        TransactionDigest::random(),
+        digest,
         &ProtocolConfig::get_for_min_version(),
     );
     ext.add(ObjectRuntime::new(
@@ -78,9 +78,51 @@ fn main() -> Result<()> {
         )
         .build();
 
+    let fullnode_service = Service::builder()
+        .name("Fullnode")
+        .package("sui.fullnode")
+        .comment("The read and execution interface of a fullnode, with BCS payloads")
+        .method(
+            Method::builder()
+                .name("get_object")
+                .route_name("GetObject")
+                .input_type("sui_types::messages::GetObjectRequest")
+                .output_type("sui_types::messages::GetObjectResponse")
+                .codec_path(codec_path)
+                .build(),
+        )
+        .method(
+            Method::builder()
+                .name("get_transaction")
+                .route_name("GetTransaction")
+                .input_type("sui_types::messages::TransactionInfoRequest")
+                .output_type("sui_types::messages::GetTransactionResponse")
+                .codec_path(codec_path)
+                .build(),
+        )
+        .method(
+            Method::builder()
+                .name("get_checkpoint")
+                .route_name("GetCheckpoint")
+                .input_type("sui_types::messages_checkpoint::CheckpointRequest")
+                .output_type("sui_types::messages_checkpoint::CheckpointResponse")
+                .codec_path(codec_path)
+                .build(),
+        )
+        .method(
+            Method::builder()
+                .name("execute_transaction")
+                .route_name("ExecuteTransaction")
+                .input_type("sui_types::messages::ExecuteTransactionRequest")
+                .output_type("sui_types::messages::ExecuteTransactionResponse")
+                .codec_path(codec_path)
+                .build(),
+        )
+        .build();
+
     Builder::new()
         .out_dir(&out_dir)
-        .compile(&[validator_service]);
+        .compile(&[validator_service, fullnode_service]);
 
     build_anemo_services(&out_dir);
 
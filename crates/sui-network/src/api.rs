@@ -1,10 +1,18 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+mod fullnode {
+    include!(concat!(env!("OUT_DIR"), "/sui.fullnode.Fullnode.rs"));
+}
+
 mod validator {
     include!(concat!(env!("OUT_DIR"), "/sui.validator.Validator.rs"));
 }
 
+pub use fullnode::{
+    fullnode_client::FullnodeClient,
+    fullnode_server::{Fullnode, FullnodeServer},
+};
 pub use validator::{
     validator_client::ValidatorClient,
     validator_server::{Validator, ValidatorServer},
@@ -8,11 +8,11 @@ use std::{
     collections::HashMap,
     sync::{Arc, RwLock},
 };
-use sui_config::p2p::P2pConfig;
+use sui_config::p2p::{P2pConfig, SeedPeer};
 use sui_types::committee::{CommitteeWithNetworkMetadata, ProtocolVersion};
 use tap::Pipe;
 use tokio::{
-    sync::{broadcast::Receiver, oneshot},
+    sync::{broadcast::Receiver, oneshot, watch},
     task::JoinSet,
 };
 
@@ -66,9 +66,11 @@ impl Builder {
         } = self;
         let config = config.unwrap();
         let (sender, receiver) = oneshot::channel();
+        let (seed_peers_sender, seed_peers_receiver) = watch::channel(config.seed_peers.clone());
 
         let handle = Handle {
             _shutdown_handle: Arc::new(sender),
+            seed_peers_sender: Arc::new(seed_peers_sender),
         };
 
         let state = State {
@@ -90,6 +92,7 @@ impl Builder {
                 shutdown_handle: receiver,
                 state,
                 reconfig_receiver,
+                seed_peers_receiver,
             },
             server,
         )
@@ -103,6 +106,7 @@ pub struct UnstartedDiscovery {
     pub(super) shutdown_handle: oneshot::Receiver<()>,
     pub(super) state: Arc<RwLock<State>>,
     pub(super) reconfig_receiver: Receiver<(CommitteeWithNetworkMetadata, ProtocolVersion)>,
+    pub(super) seed_peers_receiver: watch::Receiver<Vec<SeedPeer>>,
 }
 
 impl UnstartedDiscovery {
@@ -113,6 +117,7 @@ impl UnstartedDiscovery {
             shutdown_handle,
             state,
             reconfig_receiver,
+            seed_peers_receiver,
         } = self;
 
         let discovery_config = config.discovery.clone().unwrap_or_default();
@@ -128,6 +133,7 @@ impl UnstartedDiscovery {
                 shutdown_handle,
                 state,
                 reconfig_receiver,
+                seed_peers_receiver,
             },
             handle,
         )
@@ -145,4 +151,14 @@ impl UnstartedDiscovery {
 /// been dropped.
 pub struct Handle {
     _shutdown_handle: Arc<oneshot::Sender<()>>,
+    seed_peers_sender: Arc<watch::Sender<Vec<SeedPeer>>>,
+}
+
+impl Handle {
+    /// Replaces the seed peers. New seed peers with a known PeerId become preferred peers right
+    /// away, and the full list is used the next time the node has to dial its seed peers.
+    pub fn update_seed_peers(&self, seed_peers: Vec<SeedPeer>) {
+        // Sending only fails once the Discovery system has shutdown.
+        let _ = self.seed_peers_sender.send(seed_peers);
+    }
 }
@@ -15,7 +15,7 @@ use sui_types::committee::{CommitteeWithNetworkMetadata, ProtocolVersion};
 use tap::{Pipe, TapFallible};
 use tokio::sync::broadcast::error::RecvError;
 use tokio::{
-    sync::{broadcast::Receiver, oneshot},
+    sync::{broadcast::Receiver, oneshot, watch},
     task::{AbortHandle, JoinSet},
 };
 use tracing::{debug, info, trace};
@@ -70,6 +70,7 @@ struct DiscoveryEventLoop {
     shutdown_handle: oneshot::Receiver<()>,
     state: Arc<RwLock<State>>,
     reconfig_receiver: Receiver<(CommitteeWithNetworkMetadata, ProtocolVersion)>,
+    seed_peers_receiver: watch::Receiver<Vec<SeedPeer>>,
 }
 
 impl DiscoveryEventLoop {
@@ -97,6 +98,9 @@ impl DiscoveryEventLoop {
                 reconfig_event = self.reconfig_receiver.recv() => {
                     self.handle_reconfig_event(reconfig_event);
                 }
+                Ok(()) = self.seed_peers_receiver.changed() => {
+                    self.handle_seed_peers_update();
+                }
                 Some(task_result) = self.tasks.join_next() => {
                     task_result.unwrap();
                 },
@@ -172,6 +176,15 @@ impl DiscoveryEventLoop {
         }
     }
 
+    // Seed peers that were removed stay known to the network until the node restarts, but are no
+    // longer dialed when the node has lost all of its connections.
+    fn handle_seed_peers_update(&mut self) {
+        let seed_peers = self.seed_peers_receiver.borrow().clone();
+        info!("Updating seed peers, {} configured", seed_peers.len());
+        self.config.seed_peers = seed_peers;
+        self.configure_preferred_peers();
+    }
+
     fn update_our_info_timestamp(&mut self, now_unix: u64) {
         if let Some(our_info) = &mut self.state.write().unwrap().our_info {
             our_info.timestamp_ms = now_unix;
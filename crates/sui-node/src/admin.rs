@@ -1,26 +1,118 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::db_usage::{measure_db_usage, DbPaths};
+use crate::{profiling, SuiNode};
+use arc_swap::ArcSwapOption;
 use axum::{
-    extract::Extension,
-    http::StatusCode,
-    routing::{get, post},
-    Router,
+    extract::{Extension, Query},
+    http::header::{AUTHORIZATION, CONTENT_TYPE},
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    routing::{delete, get, post},
+    Json, Router,
 };
 use mysten_metrics::spawn_monitored_task;
+use serde::Serialize;
+use std::collections::HashMap;
 use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use subtle::ConstantTimeEq;
+use sui_config::node::ProfilingConfig;
+use sui_types::base_types::EpochId;
+use sui_types::messages_checkpoint::CheckpointSequenceNumber;
 use telemetry_subscribers::FilterHandle;
-use tracing::info;
+use tracing::{info, warn};
 
+const BACKUP_ROUTE: &str = "/backup";
+const COMPACT_ROUTE: &str = "/compact";
+const DB_USAGE_ROUTE: &str = "/db-usage";
 const LOGGING_ROUTE: &str = "/logging";
+const CPU_PROFILE_ROUTE: &str = "/profile/cpu";
+const HEAP_PROFILE_ROUTE: &str = "/profile/heap";
+const STATUS_ROUTE: &str = "/status";
+const TRANSACTION_MANAGER_ROUTE: &str = "/transaction-manager";
 
-pub fn start_admin_server(port: u16, filter_handle: FilterHandle) {
+// Default number of entries returned in each list of the transaction manager snapshot.
+const DEFAULT_TRANSACTION_MANAGER_LIMIT: usize = 100;
+
+// Default duration of a profile, when the request does not specify one.
+const DEFAULT_PROFILE_DURATION_SECS: u64 = 30;
+
+// The log filter the node was started with, restored by `DELETE /logging`.
+#[derive(Clone)]
+struct InitialFilter(String);
+
+// Held while a profile is being captured, as the profilers are process wide.
+#[derive(Clone, Default)]
+struct ProfilingLock(Arc<tokio::sync::Mutex<()>>);
+
+#[derive(Clone)]
+struct BuildInfo {
+    version: &'static str,
+    started_at: Instant,
+}
+
+/// A summary of the state of the node, so that dashboards do not need to assemble it from
+/// metrics.
+#[derive(Serialize)]
+struct NodeStatus {
+    version: &'static str,
+    uptime_secs: u64,
+    epoch: EpochId,
+    protocol_version: u64,
+    is_validator: bool,
+    highest_synced_checkpoint: Option<CheckpointSequenceNumber>,
+    highest_executed_checkpoint: Option<CheckpointSequenceNumber>,
+    num_connected_peers: usize,
+    /// Certificates accepted for execution that have not finished executing yet.
+    num_pending_transactions: usize,
+    /// Total size of the files under `db-path`, `None` if it could not be read.
+    db_size_bytes: Option<u64>,
+}
+
+/// Starts the admin server, which only listens on localhost. The log filter can be changed as
+/// soon as the server is started, while the routes inspecting the node only become available
+/// once the node is published in the returned slot. The profiling routes are only served when
+/// `profiling_config` is set with a non-empty auth token.
+pub fn start_admin_server(
+    port: u16,
+    filter_handle: FilterHandle,
+    version: &'static str,
+    profiling_config: Option<ProfilingConfig>,
+) -> Arc<ArcSwapOption<SuiNode>> {
     let filter = filter_handle.get().unwrap();
+    let node: Arc<ArcSwapOption<SuiNode>> = Default::default();
 
-    let app = Router::new()
+    let mut app = Router::new()
+        .route(BACKUP_ROUTE, post(create_backup))
+        .route(COMPACT_ROUTE, post(compact_table))
+        .route(DB_USAGE_ROUTE, get(get_db_usage))
         .route(LOGGING_ROUTE, get(get_filter))
         .route(LOGGING_ROUTE, post(set_filter))
-        .layer(Extension(filter_handle));
+        .route(LOGGING_ROUTE, delete(reset_filter))
+        .route(STATUS_ROUTE, get(get_status))
+        .route(TRANSACTION_MANAGER_ROUTE, get(get_transaction_manager));
+    if let Some(profiling_config) = profiling_config {
+        if profiling_config.auth_token.is_empty() {
+            warn!("profiling-config.auth-token is empty, not serving the profiling routes");
+        } else {
+            app = app
+                .route(CPU_PROFILE_ROUTE, get(get_cpu_profile))
+                .route(HEAP_PROFILE_ROUTE, get(get_heap_profile))
+                .layer(Extension(profiling_config))
+                .layer(Extension(ProfilingLock::default()));
+        }
+    }
+    let app = app
+        .layer(Extension(filter_handle))
+        .layer(Extension(BuildInfo {
+            version,
+            started_at: Instant::now(),
+        }))
+        .layer(Extension(InitialFilter(filter.clone())))
+        .layer(Extension(node.clone()));
 
     let socket_address = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), port);
     info!(
@@ -35,6 +127,8 @@ pub fn start_admin_server(port: u16, filter_handle: FilterHandle) {
             .await
             .unwrap();
     });
+
+    node
 }
 
 async fn get_filter(Extension(filter_handle): Extension<FilterHandle>) -> (StatusCode, String) {
@@ -56,3 +150,322 @@ async fn set_filter(
         Err(err) => (StatusCode::BAD_REQUEST, err.to_string()),
     }
 }
+
+async fn reset_filter(
+    Extension(filter_handle): Extension<FilterHandle>,
+    Extension(InitialFilter(initial_filter)): Extension<InitialFilter>,
+) -> (StatusCode, String) {
+    match filter_handle.update(&initial_filter) {
+        Ok(()) => {
+            info!(filter =% initial_filter, "Log filter reset");
+            (StatusCode::OK, initial_filter)
+        }
+        Err(err) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()),
+    }
+}
+
+async fn get_status(
+    Extension(node): Extension<Arc<ArcSwapOption<SuiNode>>>,
+    Extension(build_info): Extension<BuildInfo>,
+) -> Response {
+    let Some(node) = node.load_full() else {
+        return (StatusCode::SERVICE_UNAVAILABLE, "node is starting").into_response();
+    };
+    let state = node.state();
+    let (epoch, protocol_version, is_validator) = {
+        let epoch_store = state.load_epoch_store_one_call_per_task();
+        (
+            epoch_store.epoch(),
+            epoch_store.protocol_version().as_u64(),
+            state.is_validator(&epoch_store),
+        )
+    };
+    let checkpoint_store = node.checkpoint_store();
+    let transaction_manager = state.transaction_manager().snapshot(0);
+    // Walking the database directory can take a while.
+    let db_path = node.config().db_path().to_path_buf();
+    let db_size_bytes = tokio::task::spawn_blocking(move || fs_extra::dir::get_size(db_path).ok())
+        .await
+        .ok()
+        .flatten();
+
+    Json(NodeStatus {
+        version: build_info.version,
+        uptime_secs: build_info.started_at.elapsed().as_secs(),
+        epoch,
+        protocol_version,
+        is_validator,
+        highest_synced_checkpoint: checkpoint_store
+            .get_highest_synced_checkpoint_seq_number()
+            .ok()
+            .flatten(),
+        highest_executed_checkpoint: checkpoint_store
+            .get_highest_executed_checkpoint_seq_number()
+            .ok()
+            .flatten(),
+        num_connected_peers: node.num_connected_peers(),
+        num_pending_transactions: transaction_manager.num_executing
+            + transaction_manager.num_pending,
+        db_size_bytes,
+    })
+    .into_response()
+}
+
+async fn create_backup(Extension(node): Extension<Arc<ArcSwapOption<SuiNode>>>) -> Response {
+    let Some(node) = node.load_full() else {
+        return (StatusCode::SERVICE_UNAVAILABLE, "node is starting").into_response();
+    };
+    let Some(db_backups) = node.db_backups() else {
+        return (StatusCode::NOT_FOUND, "db-backup-config is not set").into_response();
+    };
+    match db_backups.create().await {
+        Ok(path) => (StatusCode::OK, path.display().to_string()).into_response(),
+        Err(err) => (StatusCode::INTERNAL_SERVER_ERROR, format!("{err:#}")).into_response(),
+    }
+}
+
+async fn compact_table(
+    Extension(node): Extension<Arc<ArcSwapOption<SuiNode>>>,
+    Query(params): Query<HashMap<String, String>>,
+) -> Response {
+    let Some(node) = node.load_full() else {
+        return (StatusCode::SERVICE_UNAVAILABLE, "node is starting").into_response();
+    };
+    let (Some(db_name), Some(table_name)) = (params.get("db"), params.get("table")) else {
+        return (StatusCode::BAD_REQUEST, "db and table are required").into_response();
+    };
+    match node
+        .table_compactor()
+        .compact(db_name.clone(), table_name.clone())
+        .await
+    {
+        Ok(elapsed) => (StatusCode::OK, format!("compacted in {elapsed:?}")).into_response(),
+        Err(err) => (StatusCode::BAD_REQUEST, format!("{err:#}")).into_response(),
+    }
+}
+
+async fn get_db_usage(Extension(node): Extension<Arc<ArcSwapOption<SuiNode>>>) -> Response {
+    let Some(node) = node.load_full() else {
+        return (StatusCode::SERVICE_UNAVAILABLE, "node is starting").into_response();
+    };
+    let (state, paths) = (node.state(), DbPaths::new(node.config()));
+    match tokio::task::spawn_blocking(move || measure_db_usage(&state, &paths)).await {
+        Ok(Ok(usage)) => Json(usage).into_response(),
+        Ok(Err(err)) => (StatusCode::INTERNAL_SERVER_ERROR, format!("{err:#}")).into_response(),
+        Err(err) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()).into_response(),
+    }
+}
+
+async fn get_transaction_manager(
+    Extension(node): Extension<Arc<ArcSwapOption<SuiNode>>>,
+    Query(params): Query<HashMap<String, String>>,
+) -> Response {
+    let Some(node) = node.load_full() else {
+        return (StatusCode::SERVICE_UNAVAILABLE, "node is starting").into_response();
+    };
+    let limit = match params.get("limit").map(|limit| limit.parse::<usize>()) {
+        None => DEFAULT_TRANSACTION_MANAGER_LIMIT,
+        Some(Ok(limit)) => limit,
+        Some(Err(err)) => {
+            return (StatusCode::BAD_REQUEST, format!("invalid limit: {err}")).into_response()
+        }
+    };
+    Json(node.state().transaction_manager().snapshot(limit)).into_response()
+}
+
+async fn get_cpu_profile(
+    Extension(profiling_config): Extension<ProfilingConfig>,
+    Extension(lock): Extension<ProfilingLock>,
+    headers: HeaderMap,
+    Query(params): Query<HashMap<String, String>>,
+) -> Response {
+    capture_profile(
+        &profiling_config,
+        &lock,
+        &headers,
+        &params,
+        "image/svg+xml",
+        profiling::cpu_profile,
+    )
+    .await
+}
+
+async fn get_heap_profile(
+    Extension(profiling_config): Extension<ProfilingConfig>,
+    Extension(lock): Extension<ProfilingLock>,
+    headers: HeaderMap,
+    Query(params): Query<HashMap<String, String>>,
+) -> Response {
+    capture_profile(
+        &profiling_config,
+        &lock,
+        &headers,
+        &params,
+        "application/octet-stream",
+        profiling::heap_profile,
+    )
+    .await
+}
+
+async fn capture_profile(
+    profiling_config: &ProfilingConfig,
+    lock: &ProfilingLock,
+    headers: &HeaderMap,
+    params: &HashMap<String, String>,
+    content_type: &'static str,
+    profile: fn(Duration) -> anyhow::Result<Vec<u8>>,
+) -> Response {
+    let expected = format!("Bearer {}", profiling_config.auth_token);
+    // An empty token would let through anyone sending `Authorization: Bearer `.
+    let authorized = !profiling_config.auth_token.is_empty()
+        && headers.get(AUTHORIZATION).map_or(false, |value| {
+            value.as_bytes().ct_eq(expected.as_bytes()).into()
+        });
+    if !authorized {
+        return (StatusCode::UNAUTHORIZED, "invalid auth token").into_response();
+    }
+    let seconds = match params.get("seconds").map(|seconds| seconds.parse::<u64>()) {
+        None => DEFAULT_PROFILE_DURATION_SECS.min(profiling_config.max_duration_secs),
+        Some(Ok(seconds)) if seconds > 0 && seconds <= profiling_config.max_duration_secs => {
+            seconds
+        }
+        Some(Ok(_)) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                format!(
+                    "seconds must be between 1 and {}",
+                    profiling_config.max_duration_secs
+                ),
+            )
+                .into_response()
+        }
+        Some(Err(err)) => {
+            return (StatusCode::BAD_REQUEST, format!("invalid seconds: {err}")).into_response()
+        }
+    };
+    let Ok(_guard) = lock.0.try_lock() else {
+        return (StatusCode::CONFLICT, "a profile is already being captured").into_response();
+    };
+
+    info!(seconds, "Capturing profile");
+    match tokio::task::spawn_blocking(move || profile(Duration::from_secs(seconds))).await {
+        Ok(Ok(bytes)) => ([(CONTENT_TYPE, content_type)], bytes).into_response(),
+        Ok(Err(err)) => (StatusCode::INTERNAL_SERVER_ERROR, format!("{err:#}")).into_response(),
+        Err(err) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()).into_response(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{capture_profile, ProfilingLock, DEFAULT_PROFILE_DURATION_SECS};
+    use axum::body::HttpBody;
+    use axum::http::header::AUTHORIZATION;
+    use axum::http::{HeaderMap, StatusCode};
+    use axum::response::Response;
+    use std::collections::HashMap;
+    use std::time::Duration;
+    use sui_config::node::ProfilingConfig;
+
+    // Stands in for a profiler, returning the duration it was asked to run for.
+    fn fake_profile(duration: Duration) -> anyhow::Result<Vec<u8>> {
+        Ok(duration.as_secs().to_string().into_bytes())
+    }
+
+    fn config() -> ProfilingConfig {
+        ProfilingConfig {
+            auth_token: "secret".to_string(),
+            max_duration_secs: 5,
+        }
+    }
+
+    fn authorized() -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(AUTHORIZATION, "Bearer secret".parse().unwrap());
+        headers
+    }
+
+    async fn profile(
+        config: &ProfilingConfig,
+        lock: &ProfilingLock,
+        headers: &HeaderMap,
+        seconds: Option<&str>,
+    ) -> Response {
+        let params: HashMap<_, _> = seconds
+            .map(|seconds| ("seconds".to_string(), seconds.to_string()))
+            .into_iter()
+            .collect();
+        capture_profile(config, lock, headers, &params, "text/plain", fake_profile).await
+    }
+
+    async fn body(response: Response) -> String {
+        let bytes = response.into_body().data().await.unwrap().unwrap();
+        String::from_utf8(bytes.to_vec()).unwrap()
+    }
+
+    #[test]
+    fn test_profiling_config_defaults() {
+        let config: ProfilingConfig = serde_json::from_str(r#"{"auth-token": "secret"}"#).unwrap();
+        assert_eq!(config.auth_token, "secret");
+        assert_eq!(config.max_duration_secs, 60);
+    }
+
+    #[tokio::test]
+    async fn test_profile_requires_auth_token() {
+        let lock = ProfilingLock::default();
+        let response = profile(&config(), &lock, &HeaderMap::new(), None).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let mut headers = HeaderMap::new();
+        headers.insert(AUTHORIZATION, "Bearer other".parse().unwrap());
+        let response = profile(&config(), &lock, &headers, None).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let empty_token = ProfilingConfig {
+            auth_token: String::new(),
+            ..config()
+        };
+        let mut headers = HeaderMap::new();
+        headers.insert(AUTHORIZATION, "Bearer ".parse().unwrap());
+        let response = profile(&empty_token, &lock, &headers, None).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn test_profile_duration() {
+        let lock = ProfilingLock::default();
+        let response = profile(&config(), &lock, &authorized(), Some("3")).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(body(response).await, "3");
+
+        // The default duration is capped by the longest allowed one.
+        let response = profile(&config(), &lock, &authorized(), None).await;
+        assert_eq!(body(response).await, "5");
+        let long_config = ProfilingConfig {
+            max_duration_secs: 600,
+            ..config()
+        };
+        let response = profile(&long_config, &lock, &authorized(), None).await;
+        assert_eq!(
+            body(response).await,
+            DEFAULT_PROFILE_DURATION_SECS.to_string()
+        );
+
+        for seconds in ["0", "6", "soon"] {
+            let response = profile(&config(), &lock, &authorized(), Some(seconds)).await;
+            assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_one_profile_at_a_time() {
+        let lock = ProfilingLock::default();
+        let guard = lock.0.lock().await;
+        let response = profile(&config(), &lock, &authorized(), None).await;
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+
+        // The lock is released once the profile in progress is done.
+        drop(guard);
+        let response = profile(&config(), &lock, &authorized(), None).await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+}
@@ -0,0 +1,333 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Uploads the checkpoints executed by the node to an archive, see [`sui_storage::archive`].
+//! The checkpoints are uploaded in order, and the manifest of the archive records how far the
+//! uploads went, so that they resume from there after a restart. A checkpoint pruned from the
+//! local store before it was uploaded stops the uploads, since the archive would have a gap.
+//! With transactions included, the object versions each transaction writes are uploaded too, so
+//! that pruned past objects can be read from the archive.
+
+use anyhow::{anyhow, Result};
+use std::sync::Arc;
+use std::time::Duration;
+use sui_config::node::{ArchiveStoreConfig, ArchiveWriterConfig};
+use sui_core::authority::AuthorityState;
+use sui_core::checkpoints::CheckpointStore;
+use sui_storage::archive::{
+    ArchiveManifest, ArchiveStore, ArchiveWriter, ArchivedCheckpoint, ArchivedTransaction,
+    Compression,
+};
+use sui_types::messages::TransactionEffectsAPI;
+use sui_types::messages_checkpoint::CheckpointSequenceNumber;
+use tracing::{error, info, warn};
+
+/// The number of checkpoints uploaded between updates of the manifest.
+const MAX_CHECKPOINTS_PER_ROUND: u64 = 100;
+
+pub struct ArchiveUploader {
+    state: Arc<AuthorityState>,
+    checkpoint_store: Arc<CheckpointStore>,
+    writer: ArchiveWriter,
+    include_transactions: bool,
+    upload_interval: Duration,
+}
+
+impl ArchiveUploader {
+    pub fn new(
+        state: Arc<AuthorityState>,
+        checkpoint_store: Arc<CheckpointStore>,
+        config: &ArchiveWriterConfig,
+    ) -> Result<Self> {
+        Ok(Self {
+            state,
+            checkpoint_store,
+            writer: ArchiveWriter::new(open_store(&config.store)?, compression(config.compress)),
+            include_transactions: config.include_transactions,
+            upload_interval: Duration::from_millis(config.upload_interval_ms),
+        })
+    }
+
+    /// Uploads the new executed checkpoints every upload interval, until the node is dropped.
+    pub async fn run(self) {
+        let mut manifest = match self.writer.manifest().await {
+            Ok(manifest) => manifest,
+            Err(e) => {
+                error!("Cannot read the manifest of the archive, not archiving: {e:#}");
+                return;
+            }
+        };
+        if self.include_transactions && manifest.transactions_from_checkpoint.is_none() {
+            manifest.transactions_from_checkpoint = Some(manifest.next_checkpoint);
+        } else if !self.include_transactions && manifest.transactions_from_checkpoint.is_some() {
+            error!("The archive has transactions, but the node is not configured to upload them");
+            return;
+        }
+        info!(
+            next_checkpoint = manifest.next_checkpoint,
+            "Archiving checkpoints"
+        );
+
+        let mut interval = tokio::time::interval(self.upload_interval);
+        loop {
+            interval.tick().await;
+            match self.upload_new_checkpoints(&mut manifest).await {
+                Ok(()) => (),
+                Err(e) if self.is_pruned(manifest.next_checkpoint) => {
+                    error!(
+                        "Checkpoint {} was pruned before it was archived, not archiving: {e:#}",
+                        manifest.next_checkpoint
+                    );
+                    return;
+                }
+                Err(e) => warn!("Failed to archive checkpoints: {e:#}"),
+            }
+        }
+    }
+
+    async fn upload_new_checkpoints(&self, manifest: &mut ArchiveManifest) -> Result<()> {
+        let Some(highest_executed) = self
+            .checkpoint_store
+            .get_highest_executed_checkpoint_seq_number()?
+        else {
+            return Ok(());
+        };
+        while manifest.next_checkpoint <= highest_executed {
+            let end = highest_executed.min(manifest.next_checkpoint + MAX_CHECKPOINTS_PER_ROUND);
+            for sequence_number in manifest.next_checkpoint..=end {
+                self.upload_checkpoint(sequence_number).await?;
+            }
+            manifest.next_checkpoint = end + 1;
+            self.writer.put_manifest(manifest).await?;
+        }
+        Ok(())
+    }
+
+    /// Uploads the checkpoint after its transactions and the objects they write, so that an
+    /// archived checkpoint always has its transactions and objects archived.
+    async fn upload_checkpoint(&self, sequence_number: CheckpointSequenceNumber) -> Result<()> {
+        let summary = self
+            .checkpoint_store
+            .get_checkpoint_by_sequence_number(sequence_number)?
+            .ok_or_else(|| anyhow!("Checkpoint {sequence_number} is not in the local store"))?
+            .into_inner();
+        let contents = self
+            .checkpoint_store
+            .get_checkpoint_contents(&summary.summary.content_digest)?
+            .ok_or_else(|| {
+                anyhow!("Contents of checkpoint {sequence_number} are not in the local store")
+            })?;
+
+        if self.include_transactions {
+            let checkpoint = Some((sequence_number, summary.summary.timestamp_ms));
+            for execution in contents.iter() {
+                let digest = execution.transaction;
+                let (transaction, effects) = self
+                    .state
+                    .get_executed_transaction_and_effects(digest)
+                    .await?;
+                for (&(object_id, version, _), _, _) in effects.all_mutated() {
+                    let object = self
+                        .state
+                        .db()
+                        .get_object_by_key(&object_id, version)?
+                        .ok_or_else(|| {
+                            anyhow!(
+                                "Object {object_id} at version {version} written by transaction \
+                                 {digest} is not in the local store"
+                            )
+                        })?;
+                    self.writer.put_object(&object).await?;
+                }
+                let events = match effects.events_digest() {
+                    Some(events) => self.state.get_transaction_events(*events).await?,
+                    None => Default::default(),
+                };
+                self.writer
+                    .put_transaction(&ArchivedTransaction {
+                        transaction: transaction.into_inner(),
+                        effects,
+                        events,
+                        checkpoint,
+                    })
+                    .await?;
+            }
+        }
+
+        self.writer
+            .put_checkpoint(&ArchivedCheckpoint { summary, contents })
+            .await
+    }
+
+    fn is_pruned(&self, sequence_number: CheckpointSequenceNumber) -> bool {
+        matches!(
+            self.checkpoint_store
+                .get_checkpoint_by_sequence_number(sequence_number),
+            Ok(None)
+        )
+    }
+}
+
+pub(crate) fn open_store(config: &ArchiveStoreConfig) -> Result<ArchiveStore> {
+    match config {
+        ArchiveStoreConfig::Directory(path) => Ok(ArchiveStore::Directory(path.clone())),
+        ArchiveStoreConfig::Bucket {
+            url,
+            auth_token_env_var,
+            request_timeout_ms,
+        } => {
+            let auth_token = auth_token_env_var
+                .as_ref()
+                .map(|var| {
+                    std::env::var(var)
+                        .map_err(|e| anyhow!("Cannot read archive auth token from {var}: {e}"))
+                })
+                .transpose()?;
+            ArchiveStore::http(url, auth_token, Duration::from_millis(*request_timeout_ms))
+        }
+    }
+}
+
+pub(crate) fn compression(compress: bool) -> Compression {
+    if compress {
+        Compression::Gzip
+    } else {
+        Compression::None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ops::Range;
+    use sui_config::builder::ConfigBuilder;
+    use sui_config::node::ArchiveStoreConfig;
+    use sui_storage::archive::ArchiveReader;
+    use sui_types::base_types::ExecutionDigests;
+    use sui_types::committee::Committee;
+    use sui_types::crypto::{AuthorityKeyPair, KeypairTraits};
+    use sui_types::gas::GasCostSummary;
+    use sui_types::messages_checkpoint::{
+        CertifiedCheckpointSummary, CheckpointContents, CheckpointSummary, SignedCheckpointSummary,
+        VerifiedCheckpoint,
+    };
+
+    /// Inserts the checkpoints of `sequence_numbers`, following the ones already in `store`, as
+    /// executed.
+    fn insert_executed_checkpoints(
+        store: &CheckpointStore,
+        committee: &Committee,
+        keys: &[AuthorityKeyPair],
+        sequence_numbers: Range<CheckpointSequenceNumber>,
+    ) {
+        for sequence_number in sequence_numbers {
+            let previous_digest = sequence_number.checked_sub(1).map(|previous| {
+                store
+                    .get_checkpoint_by_sequence_number(previous)
+                    .unwrap()
+                    .unwrap()
+                    .digest()
+            });
+            let contents = CheckpointContents::new_with_causally_ordered_transactions([
+                ExecutionDigests::random(),
+            ]);
+            let summary = CheckpointSummary::new(
+                committee.epoch,
+                sequence_number,
+                sequence_number + 1,
+                &contents,
+                previous_digest,
+                GasCostSummary::default(),
+                None,
+                1000 * sequence_number,
+            );
+            let signed = keys
+                .iter()
+                .map(|k| {
+                    SignedCheckpointSummary::new_from_summary(summary.clone(), k.public().into(), k)
+                })
+                .collect();
+            let checkpoint = VerifiedCheckpoint::new_unchecked(
+                CertifiedCheckpointSummary::aggregate(signed, committee).unwrap(),
+            );
+            store.insert_checkpoint_contents(contents).unwrap();
+            store
+                .insert_verified_checkpoint(checkpoint.clone())
+                .unwrap();
+            store
+                .update_highest_executed_checkpoint(&checkpoint)
+                .unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_upload_checkpoints() {
+        let dir = tempfile::tempdir().unwrap();
+        let network_config = ConfigBuilder::new(&dir).build();
+        let genesis = &network_config.genesis;
+        let state = AuthorityState::new_for_testing(
+            genesis.committee().unwrap(),
+            network_config.validator_configs[0].protocol_key_pair(),
+            None,
+            genesis,
+        )
+        .await;
+        let checkpoint_store = CheckpointStore::new(&dir.path().join("checkpoints"));
+        let archive_dir = dir.path().join("archive");
+        let config = ArchiveWriterConfig {
+            store: ArchiveStoreConfig::Directory(archive_dir.clone()),
+            include_transactions: false,
+            compress: true,
+            upload_interval_ms: 10,
+        };
+        let (committee, keys) = Committee::new_simple_test_committee();
+
+        insert_executed_checkpoints(&checkpoint_store, &committee, &keys, 0..3);
+        let uploader =
+            ArchiveUploader::new(state.clone(), checkpoint_store.clone(), &config).unwrap();
+        let mut manifest = uploader.writer.manifest().await.unwrap();
+        uploader
+            .upload_new_checkpoints(&mut manifest)
+            .await
+            .unwrap();
+        assert_eq!(manifest.next_checkpoint, 3);
+
+        // After a restart, the uploads resume from the manifest.
+        insert_executed_checkpoints(&checkpoint_store, &committee, &keys, 3..5);
+        let uploader = ArchiveUploader::new(state, checkpoint_store.clone(), &config).unwrap();
+        let mut manifest = uploader.writer.manifest().await.unwrap();
+        assert_eq!(manifest.next_checkpoint, 3);
+        uploader
+            .upload_new_checkpoints(&mut manifest)
+            .await
+            .unwrap();
+        assert_eq!(manifest.next_checkpoint, 5);
+
+        // Entries are compressed, and read back as they were executed.
+        let entry = std::fs::read(archive_dir.join("checkpoints/0")).unwrap();
+        assert_eq!(entry[..2], [0x1f, 0x8b]);
+        let reader = ArchiveReader::from_store(ArchiveStore::Directory(archive_dir));
+        assert_eq!(reader.manifest().await.unwrap(), &manifest);
+        assert_eq!(
+            reader.manifest().await.unwrap().compression,
+            Compression::Gzip
+        );
+        let mut previous_digest = None;
+        for sequence_number in 0..5 {
+            let checkpoint = reader
+                .get_checkpoint(sequence_number)
+                .await
+                .unwrap()
+                .unwrap();
+            checkpoint.verify(&committee).unwrap();
+            assert_eq!(checkpoint.summary.summary.previous_digest, previous_digest);
+            let executed = checkpoint_store
+                .get_checkpoint_by_sequence_number(sequence_number)
+                .unwrap()
+                .unwrap();
+            assert_eq!(checkpoint.summary.digest(), executed.digest());
+            previous_digest = Some(checkpoint.summary.digest());
+        }
+        assert!(reader.get_checkpoint(5).await.unwrap().is_none());
+    }
+}
@@ -0,0 +1,184 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Backups of the databases of the node taken while it is running, with the [`BackupManager`]
+//! of sui-storage under the backup directory of the config. A backup has the layout of
+//! `db-path`, with the committee, checkpoint, current epoch and perpetual stores, and the indexes
+//! of a full node: the node is restored from a backup with `sui-tool restore`.
+
+use anyhow::{ensure, Result};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use sui_config::node::DbBackupConfig;
+use sui_core::authority::AuthorityState;
+use sui_storage::backup::BackupManager;
+use tokio::sync::{watch, Mutex};
+use tracing::warn;
+
+pub struct DbBackups {
+    state: Arc<AuthorityState>,
+    config: DbBackupConfig,
+    db_path: PathBuf,
+    // Backups taken on request and on schedule must not run at the same time.
+    lock: Mutex<()>,
+    stopped: watch::Sender<bool>,
+}
+
+impl DbBackups {
+    pub fn new(state: Arc<AuthorityState>, config: DbBackupConfig, db_path: PathBuf) -> Self {
+        Self {
+            state,
+            config,
+            db_path,
+            lock: Mutex::new(()),
+            stopped: watch::channel(false).0,
+        }
+    }
+
+    /// Takes a backup and deletes the backups beyond `num_backups_to_retain`, returning the path
+    /// of the new backup.
+    pub async fn create(&self) -> Result<PathBuf> {
+        let _guard = self.lock.lock().await;
+        ensure!(!*self.stopped.borrow(), "The node is shutting down");
+        let backups = Arc::new(BackupManager::new(&self.config.backup_dir));
+        let backup_id = {
+            // Keeps reconfiguration from replacing the epoch store while it is checkpointed.
+            let _execution_lock = self.state.database.execution_lock_for_backup().await;
+            let dbs = self.state.rocksdbs_to_back_up();
+            let db_path = self.db_path.clone();
+            let backups = backups.clone();
+            tokio::task::spawn_blocking(move || backups.checkpoint(&dbs, &db_path)).await??
+        };
+        let num_backups_to_retain = self.config.num_backups_to_retain;
+        tokio::task::spawn_blocking(move || {
+            backups.write_manifest(backup_id)?;
+            backups.purge_old_backups(num_backups_to_retain)?;
+            Ok(backups.backup_path(backup_id))
+        })
+        .await?
+    }
+
+    /// Takes a backup every `interval`, until [`Self::stop`] is called.
+    pub async fn run_periodically(self: Arc<Self>, interval: Duration) {
+        let mut stopped = self.stopped.subscribe();
+        let mut interval = tokio::time::interval(interval);
+        // The first tick completes immediately, do not take a backup at startup.
+        interval.tick().await;
+        while !*stopped.borrow() {
+            tokio::select! {
+                _ = interval.tick() => {}
+                _ = stopped.changed() => break,
+            }
+            if let Err(e) = self.create().await {
+                warn!("Failed to back up the databases: {e:#}");
+            }
+        }
+    }
+
+    /// Stops taking backups, waiting for the one in progress, if any, to complete.
+    pub async fn stop(&self) {
+        self.stopped.send_replace(true);
+        let _guard = self.lock.lock().await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::path::Path;
+    use sui_config::builder::ConfigBuilder;
+    use sui_config::NetworkConfig;
+    use sui_types::base_types::ObjectID;
+    use sui_types::object::Object;
+
+    async fn open_state(network_config: &NetworkConfig, db_path: &Path) -> Arc<AuthorityState> {
+        let genesis = &network_config.genesis;
+        fs::create_dir_all(db_path).unwrap();
+        AuthorityState::new_for_testing(
+            genesis.committee().unwrap(),
+            network_config.validator_configs[0].protocol_key_pair(),
+            Some(db_path.to_path_buf()),
+            genesis,
+        )
+        .await
+    }
+
+    fn backup_config(dir: &Path) -> DbBackupConfig {
+        DbBackupConfig {
+            backup_dir: dir.join("backups"),
+            backup_interval_secs: None,
+            num_backups_to_retain: 1,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_backup_and_restore() {
+        let dir = tempfile::tempdir().unwrap();
+        let network_config = ConfigBuilder::new(&dir).build();
+        let db_path = dir.path().join("db");
+        let state = open_state(&network_config, &db_path).await;
+        let backups = DbBackups::new(state.clone(), backup_config(dir.path()), db_path.clone());
+
+        let backed_up = Object::immutable_with_id_for_testing(ObjectID::random());
+        state.insert_genesis_object(backed_up.clone()).await;
+        let first = backups.create().await.unwrap();
+        for store in [
+            "epochs",
+            "checkpoints",
+            "store/epoch_0",
+            "store/epoch_0/recovery_log",
+            "store/perpetual",
+            "indexes",
+        ] {
+            assert!(first.join(store).is_dir(), "{store} is not backed up");
+        }
+
+        let not_backed_up = Object::immutable_with_id_for_testing(ObjectID::random());
+        state.insert_genesis_object(not_backed_up.clone()).await;
+
+        // Restores the backup as the db-path of another node.
+        let restored_db_path = dir.path().join("restored");
+        let manifest = BackupManager::new(dir.path().join("backups"))
+            .restore(None, &restored_db_path)
+            .unwrap();
+        assert!(manifest.dbs.contains_key("store/perpetual"));
+        let restored = open_state(&network_config, &restored_db_path).await;
+        assert_eq!(
+            restored.database.get_object(&backed_up.id()).unwrap(),
+            Some(backed_up)
+        );
+        assert_eq!(
+            restored.database.get_object(&not_backed_up.id()).unwrap(),
+            None
+        );
+        assert!(restored
+            .committee_store()
+            .get_committee(&0)
+            .unwrap()
+            .is_some());
+
+        // Only the latest backup is retained.
+        let second = backups.create().await.unwrap();
+        assert!(second.is_dir());
+        assert!(!first.exists());
+    }
+
+    #[tokio::test]
+    async fn test_stop() {
+        let dir = tempfile::tempdir().unwrap();
+        let network_config = ConfigBuilder::new(&dir).build();
+        let db_path = dir.path().join("db");
+        let state = open_state(&network_config, &db_path).await;
+        let backups = Arc::new(DbBackups::new(state, backup_config(dir.path()), db_path));
+
+        let periodic = tokio::spawn(backups.clone().run_periodically(Duration::from_millis(10)));
+        backups.stop().await;
+        tokio::time::timeout(Duration::from_secs(10), periodic)
+            .await
+            .expect("Periodic backups did not stop")
+            .unwrap();
+        assert!(backups.create().await.is_err());
+    }
+}
@@ -0,0 +1,88 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Uploads the commits made to the authority store as a commit log, see
+//! [`sui_storage::commit_log`]. The commits made since the last upload are uploaded as one
+//! segment every segment interval, and kept in memory until their upload succeeds.
+
+use crate::archive::{compression, open_store};
+use anyhow::Result;
+use std::time::Duration;
+use sui_config::node::CommitLogConfig;
+use sui_storage::commit_log::{
+    CommitLogManifest, CommitLogRecord, CommitLogSegment, CommitLogWriter,
+};
+use tokio::sync::mpsc::UnboundedReceiver;
+use tracing::{error, info, warn};
+
+pub struct CommitLogUploader {
+    records: UnboundedReceiver<CommitLogRecord>,
+    writer: CommitLogWriter,
+    segment_interval: Duration,
+}
+
+impl CommitLogUploader {
+    /// `records` are the commits to upload, as subscribed to from the authority store.
+    pub fn new(
+        records: UnboundedReceiver<CommitLogRecord>,
+        config: &CommitLogConfig,
+    ) -> Result<Self> {
+        Ok(Self {
+            records,
+            writer: CommitLogWriter::new(open_store(&config.store)?, compression(config.compress)),
+            segment_interval: Duration::from_millis(config.segment_interval_ms),
+        })
+    }
+
+    /// Uploads the new commits every segment interval, until the node is dropped.
+    pub async fn run(mut self) {
+        let mut manifest = match self.writer.manifest().await {
+            Ok(manifest) => manifest,
+            Err(e) => {
+                error!("Cannot read the manifest of the commit log, not logging commits: {e:#}");
+                return;
+            }
+        };
+        info!(
+            next_segment = manifest.next_segment,
+            "Uploading the commit log"
+        );
+
+        let mut segment = CommitLogSegment::default();
+        let mut interval = tokio::time::interval(self.segment_interval);
+        loop {
+            interval.tick().await;
+            while let Ok(record) = self.records.try_recv() {
+                segment.records.push(record);
+            }
+            if segment.records.is_empty() {
+                continue;
+            }
+            match self.upload_segment(&mut manifest, &segment).await {
+                Ok(()) => segment.records.clear(),
+                // The segment is uploaded again with the commits made in the meantime.
+                Err(e) => warn!(
+                    num_records = segment.records.len(),
+                    "Failed to upload the commit log: {e:#}"
+                ),
+            }
+        }
+    }
+
+    async fn upload_segment(
+        &self,
+        manifest: &mut CommitLogManifest,
+        segment: &CommitLogSegment,
+    ) -> Result<()> {
+        self.writer
+            .put_segment(manifest.next_segment, segment)
+            .await?;
+        let next = CommitLogManifest {
+            next_segment: manifest.next_segment + 1,
+            ..manifest.clone()
+        };
+        self.writer.put_manifest(&next).await?;
+        *manifest = next;
+        Ok(())
+    }
+}
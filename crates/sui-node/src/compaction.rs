@@ -0,0 +1,113 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Compacts tables of the node in full, on demand from the admin server or once during a daily
+//! window, so that the heavy compactions can be kept away from peak traffic.
+
+use anyhow::Result;
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use sui_config::node::CompactionWindowConfig;
+use sui_core::authority::AuthorityState;
+use sui_core::checkpoints::CheckpointStore;
+use tracing::{info, warn};
+
+const SECS_PER_HOUR: u64 = 60 * 60;
+const SECS_PER_DAY: u64 = 24 * SECS_PER_HOUR;
+
+/// How often the start of a window is checked for.
+const WINDOW_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+#[derive(Clone)]
+pub struct TableCompactor {
+    state: Arc<AuthorityState>,
+    checkpoint_store: Arc<CheckpointStore>,
+}
+
+impl TableCompactor {
+    pub fn new(state: Arc<AuthorityState>, checkpoint_store: Arc<CheckpointStore>) -> Self {
+        Self {
+            state,
+            checkpoint_store,
+        }
+    }
+
+    /// Compacts the whole of table `table_name` of `db_name`, as named by
+    /// [`AuthorityState::compact_table`] or `CheckpointStore`, and returns how long it took.
+    pub async fn compact(&self, db_name: String, table_name: String) -> Result<Duration> {
+        let compactor = self.clone();
+        tokio::task::spawn_blocking(move || -> Result<Duration> {
+            let start = Instant::now();
+            match db_name.as_str() {
+                "CheckpointStore" => compactor.checkpoint_store.compact_table(&table_name)?,
+                _ => compactor.state.compact_table(&db_name, &table_name)?,
+            }
+            Ok(start.elapsed())
+        })
+        .await?
+    }
+
+    /// Compacts the tables of `config` once during each window, until the node is dropped.
+    pub async fn run_in_window(self, config: CompactionWindowConfig) {
+        let mut last_window = None;
+        let mut interval = tokio::time::interval(WINDOW_CHECK_INTERVAL);
+        loop {
+            interval.tick().await;
+            let now = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .expect("Time is before the Unix epoch")
+                .as_secs();
+            let window = window_start(config.start_hour_utc, config.end_hour_utc, now);
+            if window.is_none() || window == last_window {
+                continue;
+            }
+            last_window = window;
+            for (db_name, table_names) in &config.tables {
+                for table_name in table_names {
+                    match self.compact(db_name.clone(), table_name.clone()).await {
+                        Ok(elapsed) => info!(db_name, table_name, ?elapsed, "Compacted table"),
+                        Err(e) => warn!(db_name, table_name, "Failed to compact table: {e:#}"),
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// When the window from `start_hour` to `end_hour` of a day that `now` is in started, in
+/// seconds since the Unix epoch, or `None` if `now` is outside of the windows.
+fn window_start(start_hour: u8, end_hour: u8, now: u64) -> Option<u64> {
+    let day = now - now % SECS_PER_DAY;
+    let hour = now % SECS_PER_DAY / SECS_PER_HOUR;
+    let (start_hour, end_hour) = (u64::from(start_hour), u64::from(end_hour));
+    let spans_midnight = end_hour < start_hour;
+    if start_hour <= hour && (hour < end_hour || spans_midnight) {
+        Some(day + start_hour * SECS_PER_HOUR)
+    } else if spans_midnight && hour < end_hour {
+        Some(day - SECS_PER_DAY + start_hour * SECS_PER_HOUR)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_window_start() {
+        let day = 100 * SECS_PER_DAY;
+        let at = |hour: u64| day + hour * SECS_PER_HOUR + 30;
+
+        assert_eq!(window_start(2, 5, at(1)), None);
+        assert_eq!(window_start(2, 5, at(2)), Some(day + 2 * SECS_PER_HOUR));
+        assert_eq!(window_start(2, 5, at(4)), Some(day + 2 * SECS_PER_HOUR));
+        assert_eq!(window_start(2, 5, at(5)), None);
+
+        // Windows spanning midnight started the day before in the early hours.
+        assert_eq!(window_start(22, 3, at(23)), Some(day + 22 * SECS_PER_HOUR));
+        assert_eq!(window_start(22, 3, at(1)), Some(day - 2 * SECS_PER_HOUR));
+        assert_eq!(window_start(22, 3, at(3)), None);
+        assert_eq!(window_start(22, 3, at(12)), None);
+    }
+}
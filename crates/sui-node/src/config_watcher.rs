@@ -0,0 +1,45 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::SuiNode;
+use std::sync::Arc;
+use sui_config::reload::NodeConfigReloader;
+
+/// Re-reads the node config file every time the process receives SIGHUP, and applies the
+/// changes to the fields that can be changed at runtime. Changes to any other field are logged
+/// and ignored until the node is restarted.
+#[cfg(unix)]
+pub fn start_config_watcher(node: Arc<SuiNode>, mut reloader: NodeConfigReloader) {
+    use mysten_metrics::spawn_monitored_task;
+    use tokio::signal::unix::{signal, SignalKind};
+    use tracing::{error, info};
+
+    let mut hangup = signal(SignalKind::hangup()).expect("Failed to install SIGHUP handler");
+
+    spawn_monitored_task!(async move {
+        while hangup.recv().await.is_some() {
+            info!(path = ?reloader.path(), "Received SIGHUP, reloading config");
+            let reloaded = match reloader.reload() {
+                Ok(reloaded) => reloaded,
+                Err(err) => {
+                    error!("Failed to reload config, keeping the current one: {err:?}");
+                    continue;
+                }
+            };
+            if !reloaded.rejected_fields.is_empty() {
+                error!(
+                    fields = ?reloaded.rejected_fields,
+                    "Config fields cannot be changed while the node is running, \
+                     the changes will only take effect after a restart"
+                );
+            }
+            node.apply_reloaded_config(&reloaded.config);
+            info!("Config reloaded");
+        }
+    });
+}
+
+#[cfg(not(unix))]
+pub fn start_config_watcher(_node: Arc<SuiNode>, _reloader: NodeConfigReloader) {
+    tracing::warn!("Config reloading is only supported on unix platforms");
+}
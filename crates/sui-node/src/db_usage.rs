@@ -0,0 +1,306 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Accounts for the disk space used by the databases of the node, by domain. The tables of the
+//! perpetual store are measured by the size of their SST files, the databases holding a single
+//! domain by the size of their directory, and the rest of `db-path` is reported as `other`.
+
+use anyhow::Result;
+use prometheus::{register_int_gauge_vec_with_registry, IntGaugeVec, Registry};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use sui_config::NodeConfig;
+use sui_core::authority::authority_per_epoch_store::EPOCH_DB_PREFIX;
+use sui_core::authority::AuthorityState;
+use tracing::warn;
+
+/// How often the disk usage is reported, as measuring it walks the database directories.
+pub const DB_USAGE_REPORT_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+/// The domain of the tables of the perpetual store, the others being accounted as `other`.
+const PERPETUAL_TABLE_DOMAINS: &[(&str, &str)] = &[
+    ("objects", "objects"),
+    ("indirect_move_objects", "objects"),
+    ("owned_object_transaction_locks", "objects"),
+    ("parent_sync", "objects"),
+    ("transactions", "transactions"),
+    ("executed_transactions_to_checkpoint", "transactions"),
+    ("effects", "effects"),
+    ("executed_effects", "effects"),
+    ("events", "events"),
+];
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct DbUsage {
+    /// Bytes used by each domain: `objects`, `transactions`, `effects`, `events`,
+    /// `checkpoints`, `indices`, `epochs`, `consensus` and `other`.
+    pub domains: BTreeMap<&'static str, u64>,
+    /// Bytes used under `db-path` and by the consensus database.
+    pub total_bytes: u64,
+}
+
+/// Where the databases of the node are.
+#[derive(Clone)]
+pub struct DbPaths {
+    db_path: PathBuf,
+    consensus_db_path: Option<PathBuf>,
+}
+
+impl DbPaths {
+    pub fn new(config: &NodeConfig) -> Self {
+        Self {
+            db_path: config.db_path().to_path_buf(),
+            consensus_db_path: config
+                .consensus_config()
+                .map(|consensus_config| consensus_config.db_path().to_path_buf()),
+        }
+    }
+}
+
+/// Measures the disk usage of the databases of the node, which blocks while walking their
+/// directories.
+pub fn measure_db_usage(state: &AuthorityState, paths: &DbPaths) -> Result<DbUsage> {
+    Ok(aggregate_db_usage(
+        state.database.perpetual_table_sizes()?,
+        paths,
+    ))
+}
+
+/// Accounts for the disk usage of the databases at `paths` by domain, given the size of each
+/// table of the perpetual store.
+fn aggregate_db_usage(perpetual_table_sizes: BTreeMap<String, u64>, paths: &DbPaths) -> DbUsage {
+    let mut domains: BTreeMap<&'static str, u64> = BTreeMap::new();
+    for (table_name, size) in perpetual_table_sizes {
+        if let Some((_, domain)) = PERPETUAL_TABLE_DOMAINS
+            .iter()
+            .find(|(name, _)| *name == table_name)
+        {
+            *domains.entry(domain).or_default() += size;
+        }
+    }
+
+    let db_path = &paths.db_path;
+    *domains.entry("events").or_default() += size_on_disk(&db_path.join("events.db"));
+    domains.insert("checkpoints", size_on_disk(&db_path.join("checkpoints")));
+    domains.insert("indices", size_on_disk(&db_path.join("indexes")));
+    let mut epochs = 0;
+    if let Ok(entries) = std::fs::read_dir(db_path.join("store")) {
+        for entry in entries.flatten() {
+            if entry
+                .file_name()
+                .to_string_lossy()
+                .starts_with(EPOCH_DB_PREFIX)
+            {
+                epochs += size_on_disk(&entry.path());
+            }
+        }
+    }
+    domains.insert("epochs", epochs);
+
+    let mut total_bytes = size_on_disk(db_path);
+    let mut other = total_bytes.saturating_sub(domains.values().sum());
+    if let Some(consensus_db_path) = &paths.consensus_db_path {
+        let consensus = size_on_disk(consensus_db_path);
+        if consensus_db_path.starts_with(db_path) {
+            other = other.saturating_sub(consensus);
+        } else {
+            total_bytes += consensus;
+        }
+        domains.insert("consensus", consensus);
+    }
+    domains.insert("other", other);
+    DbUsage {
+        domains,
+        total_bytes,
+    }
+}
+
+pub struct DbUsageReporter {
+    state: Arc<AuthorityState>,
+    paths: DbPaths,
+    db_usage_bytes: IntGaugeVec,
+}
+
+impl DbUsageReporter {
+    pub fn new(state: Arc<AuthorityState>, paths: DbPaths, registry: &Registry) -> Self {
+        Self {
+            state,
+            paths,
+            db_usage_bytes: register_int_gauge_vec_with_registry!(
+                "db_usage_bytes",
+                "Disk space used by the databases of the node, by domain",
+                &["domain"],
+                registry,
+            )
+            .unwrap(),
+        }
+    }
+
+    /// Reports the disk usage as metrics every [`DB_USAGE_REPORT_INTERVAL`], until the node is
+    /// dropped.
+    pub async fn run(self) {
+        let mut interval = tokio::time::interval(DB_USAGE_REPORT_INTERVAL);
+        loop {
+            interval.tick().await;
+            let (state, paths) = (self.state.clone(), self.paths.clone());
+            match tokio::task::spawn_blocking(move || measure_db_usage(&state, &paths)).await {
+                Ok(Ok(usage)) => self.report(&usage),
+                Ok(Err(e)) => warn!("Failed to measure the disk usage of the databases: {e:#}"),
+                Err(e) => warn!("Failed to measure the disk usage of the databases: {e}"),
+            }
+        }
+    }
+
+    fn report(&self, usage: &DbUsage) {
+        for (domain, bytes) in &usage.domains {
+            self.db_usage_bytes
+                .with_label_values(&[*domain])
+                .set(*bytes as i64);
+        }
+    }
+}
+
+/// The size of the file or directory at `path`, 0 if it does not exist.
+fn size_on_disk(path: &Path) -> u64 {
+    fs_extra::dir::get_size(path).unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use sui_config::builder::ConfigBuilder;
+
+    /// Writes a file of `len` bytes at `path`, creating its directory.
+    fn write_file(path: &Path, len: usize) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, vec![0; len]).unwrap();
+    }
+
+    #[test]
+    fn test_aggregate_db_usage() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("db");
+        write_file(&db_path.join("store/perpetual/000001.sst"), 1000);
+        write_file(&db_path.join("store/epoch_0/000001.sst"), 100);
+        write_file(&db_path.join("store/epoch_1/000001.sst"), 200);
+        write_file(&db_path.join("checkpoints/000001.sst"), 300);
+        write_file(&db_path.join("indexes/000001.sst"), 400);
+        write_file(&db_path.join("events.db/000001.sst"), 500);
+        write_file(&db_path.join("consensus_db/000001.sst"), 600);
+        write_file(&db_path.join("LOG"), 700);
+        let table_sizes: BTreeMap<_, _> = [
+            ("objects", 10),
+            ("parent_sync", 20),
+            ("transactions", 30),
+            ("effects", 40),
+            ("executed_effects", 50),
+            ("events", 60),
+            ("epoch_start_configuration", 70),
+        ]
+        .into_iter()
+        .map(|(table_name, size)| (table_name.to_string(), size))
+        .collect();
+
+        let paths = DbPaths {
+            db_path: db_path.clone(),
+            consensus_db_path: Some(db_path.join("consensus_db")),
+        };
+        let usage = aggregate_db_usage(table_sizes.clone(), &paths);
+        // The tables of the perpetual store are accounted by domain, the others as `other`.
+        assert_eq!(usage.domains["objects"], 30);
+        assert_eq!(usage.domains["transactions"], 30);
+        assert_eq!(usage.domains["effects"], 90);
+        assert_eq!(
+            usage.domains["events"],
+            60 + size_on_disk(&db_path.join("events.db"))
+        );
+        assert_eq!(
+            usage.domains["epochs"],
+            size_on_disk(&db_path.join("store/epoch_0"))
+                + size_on_disk(&db_path.join("store/epoch_1"))
+        );
+        assert_eq!(
+            usage.domains["checkpoints"],
+            size_on_disk(&db_path.join("checkpoints"))
+        );
+        assert_eq!(
+            usage.domains["indices"],
+            size_on_disk(&db_path.join("indexes"))
+        );
+        assert_eq!(
+            usage.domains["consensus"],
+            size_on_disk(&db_path.join("consensus_db"))
+        );
+        // Every byte under `db-path` is accounted for once.
+        assert_eq!(usage.total_bytes, size_on_disk(&db_path));
+        assert_eq!(usage.domains.values().sum::<u64>(), usage.total_bytes);
+
+        // A consensus database outside of `db-path` adds to the total.
+        let consensus_db_path = dir.path().join("consensus_db");
+        fs::rename(db_path.join("consensus_db"), &consensus_db_path).unwrap();
+        let paths = DbPaths {
+            db_path: db_path.clone(),
+            consensus_db_path: Some(consensus_db_path.clone()),
+        };
+        let outside = aggregate_db_usage(table_sizes, &paths);
+        assert_eq!(outside.domains["other"], usage.domains["other"]);
+        assert_eq!(
+            outside.total_bytes,
+            size_on_disk(&db_path) + size_on_disk(&consensus_db_path)
+        );
+        assert_eq!(outside.domains.values().sum::<u64>(), outside.total_bytes);
+    }
+
+    #[tokio::test]
+    async fn test_report_db_usage() {
+        let dir = tempfile::tempdir().unwrap();
+        let network_config = ConfigBuilder::new(&dir).build();
+        let genesis = &network_config.genesis;
+        let db_path = dir.path().join("db");
+        fs::create_dir_all(&db_path).unwrap();
+        let state = AuthorityState::new_for_testing(
+            genesis.committee().unwrap(),
+            network_config.validator_configs[0].protocol_key_pair(),
+            Some(db_path.clone()),
+            genesis,
+        )
+        .await;
+        let paths = DbPaths {
+            db_path,
+            consensus_db_path: None,
+        };
+
+        let usage = measure_db_usage(&state, &paths).unwrap();
+        assert!(usage.total_bytes > 0);
+        assert_eq!(usage.domains.values().sum::<u64>(), usage.total_bytes);
+        for domain in [
+            "objects",
+            "transactions",
+            "effects",
+            "events",
+            "checkpoints",
+            "indices",
+            "epochs",
+            "other",
+        ] {
+            assert!(
+                usage.domains.contains_key(domain),
+                "{domain} is not reported"
+            );
+        }
+
+        let registry = Registry::new();
+        let reporter = DbUsageReporter::new(state, paths, &registry);
+        reporter.report(&usage);
+        for (domain, bytes) in &usage.domains {
+            assert_eq!(
+                reporter.db_usage_bytes.with_label_values(&[*domain]).get(),
+                *bytes as i64
+            );
+        }
+    }
+}
@@ -0,0 +1,322 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Formal snapshots of the live object set at the end of each epoch, see
+//! [`sui_storage::formal_snapshot`]. A node with state snapshots enabled takes a checkpoint of its
+//! database at the end of each epoch, whose live objects the [`FormalSnapshotUploader`] uploads,
+//! along with the last checkpoint of each epoch that they are verified against. A new node can
+//! start from the end of the epoch of a snapshot instead of genesis, see [`open_restored_store`],
+//! and state sync resumes from the first checkpoint of the next epoch.
+
+use crate::archive::{compression, open_store};
+use anyhow::{anyhow, ensure, Result};
+use fastcrypto::hash::MultisetHash;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use sui_config::genesis::Genesis;
+use sui_config::node::{
+    ArchiveStoreConfig, FormalSnapshotRestoreConfig, FormalSnapshotWriterConfig,
+};
+use sui_core::authority::authority_per_epoch_store::EpochStartConfiguration;
+use sui_core::authority::authority_store_tables::AuthorityPerpetualTables;
+use sui_core::authority::AuthorityStore;
+use sui_core::checkpoints::CheckpointStore;
+use sui_core::epoch::committee_store::CommitteeStore;
+use sui_core::state_accumulator::StateAccumulator;
+use sui_storage::archive::{ArchivedCheckpoint, Compression};
+use sui_storage::formal_snapshot::{
+    verify_live_object_set, FormalSnapshotReader, FormalSnapshotWriter,
+};
+use sui_types::accumulator::Accumulator;
+use sui_types::committee::EpochId;
+use sui_types::object::Object;
+use sui_types::sui_system_state::SuiSystemStateTrait;
+use tokio::sync::mpsc;
+use tracing::{info, warn};
+
+/// The prefix of the name of the checkpoints of the database taken at the end of each epoch,
+/// followed by the epoch.
+const DB_CHECKPOINT_PREFIX: &str = "perpetual_store_snapshot_epoch_";
+
+/// The number of objects in each part of a snapshot.
+const OBJECTS_PER_PART: usize = 10_000;
+
+pub struct FormalSnapshotUploader {
+    checkpoint_store: Arc<CheckpointStore>,
+    /// The directory of the perpetual database, where its checkpoints are taken.
+    perpetual_db_path: PathBuf,
+    store: ArchiveStoreConfig,
+    compression: Compression,
+    upload_interval: Duration,
+}
+
+impl FormalSnapshotUploader {
+    pub fn new(
+        checkpoint_store: Arc<CheckpointStore>,
+        store_path: &Path,
+        config: &FormalSnapshotWriterConfig,
+    ) -> Self {
+        Self {
+            checkpoint_store,
+            perpetual_db_path: AuthorityPerpetualTables::path(store_path),
+            store: config.store.clone(),
+            compression: compression(config.compress),
+            upload_interval: Duration::from_millis(config.upload_interval_ms),
+        }
+    }
+
+    /// Uploads the snapshots of the checkpoints of the database every upload interval, and
+    /// deletes the checkpoints once uploaded, until the node is dropped.
+    pub async fn run(self) {
+        info!("Uploading formal snapshots");
+        let mut interval = tokio::time::interval(self.upload_interval);
+        loop {
+            interval.tick().await;
+            let db_checkpoints = match self.db_checkpoints() {
+                Ok(db_checkpoints) => db_checkpoints,
+                Err(e) => {
+                    warn!("Failed to list the checkpoints of the database: {e:#}");
+                    continue;
+                }
+            };
+            for (epoch, path) in db_checkpoints {
+                if let Err(e) = self.upload_snapshot(epoch, &path).await {
+                    warn!("Failed to upload the formal snapshot of epoch {epoch}: {e:#}");
+                    continue;
+                }
+                if let Err(e) = tokio::fs::remove_dir_all(&path).await {
+                    warn!("Failed to delete {}: {e:#}", path.display());
+                }
+            }
+        }
+    }
+
+    /// The checkpoints of the database, by epoch. Checkpoints being taken have another name
+    /// until they are complete.
+    fn db_checkpoints(&self) -> Result<Vec<(EpochId, PathBuf)>> {
+        let mut db_checkpoints = vec![];
+        for entry in std::fs::read_dir(&self.perpetual_db_path)? {
+            let entry = entry?;
+            let epoch = entry
+                .file_name()
+                .to_str()
+                .and_then(|name| name.strip_prefix(DB_CHECKPOINT_PREFIX))
+                .and_then(|epoch| epoch.parse().ok());
+            if let Some(epoch) = epoch {
+                db_checkpoints.push((epoch, entry.path()));
+            }
+        }
+        db_checkpoints.sort();
+        Ok(db_checkpoints)
+    }
+
+    /// Uploads the snapshot of the live objects of the checkpoint of the database at `path`,
+    /// taken at the end of `epoch`, unless it is uploaded already or the epoch does not commit to
+    /// its live objects. The manifest is uploaded last, once the objects are verified.
+    async fn upload_snapshot(&self, epoch: EpochId, path: &Path) -> Result<()> {
+        let mut writer =
+            FormalSnapshotWriter::new(open_store(&self.store)?, self.compression, epoch);
+        if writer.exists().await? {
+            info!("The formal snapshot of epoch {epoch} is uploaded already");
+            return Ok(());
+        }
+        let epoch_checkpoints = (0..=epoch)
+            .map(|epoch| {
+                self.checkpoint_store
+                    .get_epoch_last_checkpoint(epoch)?
+                    .map(|checkpoint| checkpoint.into_inner())
+                    .ok_or_else(|| {
+                        anyhow!("The last checkpoint of epoch {epoch} is not in the local store")
+                    })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let summary = epoch_checkpoints
+            .last()
+            .expect("There is at least one epoch")
+            .clone();
+        let Some(root_state_digest) = summary.root_state_digest().cloned() else {
+            info!("Epoch {epoch} does not commit to its live objects, not uploading a snapshot");
+            return Ok(());
+        };
+        let contents = self
+            .checkpoint_store
+            .get_checkpoint_contents(&summary.content_digest())?
+            .ok_or_else(|| {
+                anyhow!(
+                    "Contents of checkpoint {} are not in the local store",
+                    summary.sequence_number()
+                )
+            })?;
+        writer.put_epochs(&epoch_checkpoints).await?;
+        writer
+            .put_checkpoint(&ArchivedCheckpoint { summary, contents })
+            .await?;
+
+        let (sender, mut receiver) = mpsc::channel(1);
+        let db_path = path.to_path_buf();
+        let reader = tokio::task::spawn_blocking(move || read_live_objects(&db_path, sender));
+        while let Some(objects) = receiver.recv().await {
+            writer.put_objects(&objects).await?;
+        }
+        verify_live_object_set(&reader.await??, &root_state_digest)?;
+        let manifest = writer.finish().await?;
+        info!(
+            num_objects = manifest.num_objects,
+            "Uploaded the formal snapshot of epoch {epoch}"
+        );
+        Ok(())
+    }
+}
+
+/// Sends the live objects of the checkpoint of the database at `path` in parts, and returns their
+/// accumulation.
+fn read_live_objects(path: &Path, sender: mpsc::Sender<Vec<Object>>) -> Result<Accumulator> {
+    let tables = AuthorityPerpetualTables::open_checkpoint(path);
+    let mut accumulator = Accumulator::default();
+    let mut objects = Vec::with_capacity(OBJECTS_PER_PART);
+    for (object_id, version, _) in tables.iter_live_object_set() {
+        let object = tables
+            .find_object_lt_or_eq_version(object_id, version)
+            .filter(|object| object.version() == version)
+            .ok_or_else(|| anyhow!("Live object {object_id} at version {version} is missing"))?;
+        accumulator.insert(object.digest());
+        objects.push(object);
+        if objects.len() == OBJECTS_PER_PART {
+            sender.blocking_send(std::mem::take(&mut objects))?;
+        }
+    }
+    if !objects.is_empty() {
+        sender.blocking_send(objects)?;
+    }
+    Ok(accumulator)
+}
+
+/// Opens the store of the node at `path`, restored from the formal snapshot of `config` unless
+/// the store was initialized already. The snapshot is verified from the committee of genesis,
+/// and the node starts from the epoch after it.
+pub(crate) async fn open_restored_store(
+    config: &FormalSnapshotRestoreConfig,
+    path: &Path,
+    genesis: &Genesis,
+    committee_store: &Arc<CommitteeStore>,
+    checkpoint_store: &Arc<CheckpointStore>,
+    indirect_objects_threshold: usize,
+) -> Result<Arc<AuthorityStore>> {
+    let store = Arc::new(
+        AuthorityStore::open_for_restore(
+            path,
+            None,
+            genesis,
+            committee_store,
+            indirect_objects_threshold,
+        )
+        .await?,
+    );
+    if store.get_epoch_start_configuration()?.is_some() {
+        info!(
+            "The store is initialized already, not restoring the formal snapshot of epoch {}",
+            config.epoch
+        );
+        return Ok(store);
+    }
+
+    info!("Restoring the formal snapshot of epoch {}", config.epoch);
+    let reader = FormalSnapshotReader::open(open_store(&config.store)?, config.epoch).await?;
+    let snapshot = reader.verify_epochs(&genesis.committee()?).await?;
+    for part in 0..reader.manifest().num_parts {
+        store
+            .insert_restored_objects(&reader.get_objects(part).await?)
+            .await?;
+    }
+    // The objects are verified as they are in the store, which includes any that a restore that
+    // did not finish left behind.
+    let accumulator = StateAccumulator::new(store.clone());
+    let root_state_hash =
+        tokio::task::spawn_blocking(move || accumulator.accumulate_live_object_set()).await?;
+    verify_live_object_set(&root_state_hash, &snapshot.root_state_digest)?;
+
+    // The last transaction of the epoch advanced the system state to the next epoch.
+    let system_state = store.get_sui_system_state_object()?;
+    ensure!(
+        system_state.epoch() == config.epoch + 1,
+        "The system state of the formal snapshot of epoch {} is of epoch {}",
+        config.epoch,
+        system_state.epoch()
+    );
+    let last_checkpoint = snapshot
+        .epoch_checkpoints
+        .last()
+        .expect("There is at least one epoch");
+    let epoch_start_configuration = EpochStartConfiguration::new(
+        system_state.into_epoch_start_state(),
+        last_checkpoint.digest(),
+    );
+    for committee in &snapshot.committees[1..] {
+        committee_store.insert_new_committee(committee)?;
+    }
+    // The genesis checkpoint goes in first, so that the node does not take it for the highest
+    // synced checkpoint when it starts.
+    checkpoint_store.insert_checkpoint_contents(genesis.checkpoint_contents().clone())?;
+    checkpoint_store.insert_certified_checkpoint(genesis.checkpoint().inner())?;
+    checkpoint_store.insert_formal_snapshot_checkpoints(
+        &snapshot.epoch_checkpoints,
+        snapshot.checkpoint.contents,
+    )?;
+    store
+        .finish_restore(
+            config.epoch,
+            last_checkpoint.sequence_number(),
+            root_state_hash,
+            &epoch_start_configuration,
+        )
+        .await?;
+    info!(
+        num_objects = reader.manifest().num_objects,
+        "Restored the formal snapshot of epoch {}", config.epoch
+    );
+    Ok(store)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_db_checkpoints() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = FormalSnapshotWriterConfig {
+            store: ArchiveStoreConfig::Directory(dir.path().join("snapshots")),
+            compress: true,
+            upload_interval_ms: 10,
+        };
+        let uploader = FormalSnapshotUploader::new(
+            CheckpointStore::new(&dir.path().join("checkpoints")),
+            &dir.path().join("store"),
+            &config,
+        );
+        let perpetual_db_path = dir.path().join("store").join("perpetual");
+        for name in [
+            "perpetual_store_snapshot_epoch_10",
+            "perpetual_store_snapshot_epoch_9",
+            // Being taken.
+            "perpetual_store_snapshot_epoch_11.tmp",
+            "other",
+        ] {
+            std::fs::create_dir_all(perpetual_db_path.join(name)).unwrap();
+        }
+        assert_eq!(
+            uploader.db_checkpoints().unwrap(),
+            vec![
+                (
+                    9,
+                    perpetual_db_path.join("perpetual_store_snapshot_epoch_9")
+                ),
+                (
+                    10,
+                    perpetual_db_path.join("perpetual_store_snapshot_epoch_10")
+                ),
+            ]
+        );
+    }
+}
@@ -0,0 +1,99 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::SuiNode;
+use arc_swap::ArcSwapOption;
+use axum::{extract::Extension, http::StatusCode, routing::get, Router};
+use mysten_metrics::spawn_monitored_task;
+use std::sync::Arc;
+use sui_config::node::HealthCheckConfig;
+use sui_types::messages_checkpoint::CheckpointSequenceNumber;
+use tracing::info;
+
+const HEALTH_ROUTE: &str = "/health";
+const READY_ROUTE: &str = "/ready";
+
+/// Serves `/health`, which succeeds as long as the process is up, and `/ready`, which only
+/// succeeds once the node published in the returned slot is ready to receive traffic.
+/// The server is started before the node so that liveness probes pass during startup.
+pub fn start_health_server(config: &HealthCheckConfig) -> Option<Arc<ArcSwapOption<SuiNode>>> {
+    let address = config.listen_address?;
+    let node: Arc<ArcSwapOption<SuiNode>> = Default::default();
+
+    let app = Router::new()
+        .route(HEALTH_ROUTE, get(health))
+        .route(READY_ROUTE, get(ready))
+        .layer(Extension(node.clone()))
+        .layer(Extension(config.clone()));
+
+    info!(address =% address, "starting health check server");
+
+    spawn_monitored_task!(async move {
+        axum::Server::bind(&address)
+            .serve(app.into_make_service())
+            .await
+            .unwrap();
+    });
+
+    Some(node)
+}
+
+async fn health() -> (StatusCode, String) {
+    (StatusCode::OK, "ok".into())
+}
+
+async fn ready(
+    Extension(node): Extension<Arc<ArcSwapOption<SuiNode>>>,
+    Extension(config): Extension<HealthCheckConfig>,
+) -> (StatusCode, String) {
+    let Some(node) = node.load_full() else {
+        return (StatusCode::SERVICE_UNAVAILABLE, "node is starting".into());
+    };
+    match node.check_ready(config.max_checkpoint_lag) {
+        Ok(()) => (StatusCode::OK, "ok".into()),
+        Err(reason) => (StatusCode::SERVICE_UNAVAILABLE, reason),
+    }
+}
+
+/// Checks that the highest executed checkpoint is at most `max_lag` checkpoints behind the
+/// highest checkpoint known to peers. A node that has not heard from any peer is considered
+/// synced, so that a single node network can become ready.
+pub(crate) fn check_checkpoint_lag(
+    highest_executed: Option<CheckpointSequenceNumber>,
+    highest_known: Option<CheckpointSequenceNumber>,
+    max_lag: u64,
+) -> Result<(), String> {
+    let Some(highest_known) = highest_known else {
+        return Ok(());
+    };
+    let lag = match highest_executed {
+        Some(highest_executed) => highest_known.saturating_sub(highest_executed),
+        None => highest_known.saturating_add(1),
+    };
+    if lag > max_lag {
+        return Err(format!(
+            "highest executed checkpoint {highest_executed:?} is {lag} checkpoints behind \
+             the highest known checkpoint {highest_known}"
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::check_checkpoint_lag;
+
+    #[test]
+    fn test_check_checkpoint_lag() {
+        assert!(check_checkpoint_lag(None, None, 0).is_ok());
+        assert!(check_checkpoint_lag(Some(5), None, 0).is_ok());
+        assert!(check_checkpoint_lag(Some(10), Some(10), 0).is_ok());
+        // Peers may lag behind us.
+        assert!(check_checkpoint_lag(Some(10), Some(5), 0).is_ok());
+        assert!(check_checkpoint_lag(Some(10), Some(30), 20).is_ok());
+        assert!(check_checkpoint_lag(Some(10), Some(31), 20).is_err());
+        // Checkpoint 0 not executed yet counts as one checkpoint behind.
+        assert!(check_checkpoint_lag(None, Some(0), 1).is_ok());
+        assert!(check_checkpoint_lag(None, Some(0), 0).is_err());
+    }
+}
@@ -1,7 +1,14 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::archive::ArchiveUploader;
+use crate::backup::DbBackups;
+use crate::commit_log::CommitLogUploader;
+use crate::compaction::TableCompactor;
+use crate::db_usage::{DbPaths, DbUsageReporter};
+use crate::formal_snapshot::FormalSnapshotUploader;
 use crate::metrics::GrpcMetrics;
+use crate::runtime::SuiRuntimes;
 use anemo::Network;
 use anemo_tower::callback::CallbackLayer;
 use anemo_tower::trace::DefaultMakeSpan;
@@ -12,7 +19,8 @@ use anyhow::Result;
 use arc_swap::ArcSwap;
 use checkpoint_executor::CheckpointExecutor;
 use futures::TryFutureExt;
-use mysten_metrics::{spawn_monitored_task, RegistryService};
+use multiaddr::Multiaddr;
+use mysten_metrics::{spawn_monitored_task, RegistryID, RegistryService};
 use mysten_network::server::ServerBuilder;
 use narwhal_network::metrics::MetricsMakeCallbackHandler;
 use narwhal_network::metrics::{NetworkConnectionMetrics, NetworkMetrics};
@@ -21,24 +29,30 @@ use std::collections::HashMap;
 use std::fmt;
 use std::sync::Arc;
 use std::time::Duration;
+use sui_config::node::AdminRpcConfig;
+use sui_config::p2p::SeedPeer;
 use sui_config::{ConsensusConfig, NodeConfig};
 use sui_core::authority_aggregator::AuthorityAggregator;
 use sui_core::authority_server::ValidatorService;
 use sui_core::checkpoints::checkpoint_executor;
 use sui_core::epoch::committee_store::CommitteeStore;
+use sui_core::fullnode_server::FullnodeService;
 use sui_core::state_accumulator::StateAccumulator;
 use sui_core::storage::RocksDbStore;
 use sui_core::transaction_orchestrator::TransactiondOrchestrator;
+use sui_core::transaction_precheck::{TransactionPrecheckMetrics, TransactionPrechecker};
 use sui_core::{
     authority::{AuthorityState, AuthorityStore},
     authority_client::NetworkAuthorityClient,
 };
+use sui_json_rpc::admin_api::AdminApi;
 use sui_json_rpc::event_api::EventReadApi;
-use sui_json_rpc::read_api::ReadApi;
+use sui_json_rpc::name_service_api::NameServiceApi;
+use sui_json_rpc::read_api::{BuildInfo, ReadApi};
 use sui_json_rpc::transaction_builder_api::TransactionBuilderApi;
 use sui_json_rpc::transaction_execution_api::TransactionExecutionApi;
-use sui_json_rpc::{JsonRpcServerBuilder, ServerHandle};
-use sui_network::api::ValidatorServer;
+use sui_json_rpc::{JsonRpcServerBuilder, RateLimitHandle, ServerHandle};
+use sui_network::api::{FullnodeServer, ValidatorServer};
 use sui_network::discovery;
 use sui_network::{state_sync, DEFAULT_CONNECT_TIMEOUT_SEC, DEFAULT_HTTP2_KEEPALIVE_SEC};
 use sui_types::committee::CommitteeWithNetworkMetadata;
@@ -49,21 +63,37 @@ use tracing::debug;
 use sui_protocol_config::{ProtocolConfig, ProtocolVersion, SupportedProtocolVersions};
 
 use sui_storage::{
+    archive::{ArchiveCache, ArchiveReader},
     event_store::{EventStoreType, SqlEventStore},
     IndexStore,
 };
 use sui_types::committee::Committee;
-use sui_types::crypto::KeypairTraits;
+use sui_types::crypto::{KeypairTraits, NetworkKeyPair};
 use sui_types::quorum_driver_types::QuorumDriverEffectsQueueResult;
+use tokio::runtime::Handle;
 use tokio::sync::broadcast;
-use tokio::sync::{watch, Mutex};
+use tokio::sync::{oneshot, watch, Mutex, Notify};
 use tokio::task::JoinHandle;
 use tower::ServiceBuilder;
-use tracing::{error_span, info, Instrument};
+use tracing::{error_span, info, warn, Instrument};
+use typed_store::rocks::set_db_statistics_enabled;
+use typed_store::rocks::table_options::TableOptionsOverrides;
 use typed_store::DBMetrics;
 pub mod admin;
+pub mod archive;
+pub mod backup;
+pub mod commit_log;
+pub mod compaction;
+pub mod config_watcher;
+pub mod db_usage;
+pub mod formal_snapshot;
 mod handle;
+pub mod health;
 pub mod metrics;
+pub mod profiling;
+pub mod read_only;
+pub mod runtime;
+pub mod watchdog;
 pub use handle::SuiNodeHandle;
 use narwhal_types::TransactionsClient;
 use sui_core::authority::authority_per_epoch_store::{
@@ -88,10 +118,31 @@ use sui_json_rpc::threshold_bls_api::ThresholdBlsApi;
 use sui_types::base_types::{AuthorityName, EpochId, TransactionDigest};
 use sui_types::error::{SuiError, SuiResult};
 use sui_types::messages::{AuthorityCapabilities, ConsensusTransaction};
-use sui_types::sui_system_state::epoch_start_sui_system_state::EpochStartSystemState;
+use sui_types::sui_system_state::epoch_start_sui_system_state::{
+    EpochStartSystemState, EpochStartValidatorInfo,
+};
+
+/// The git revision the node binary was built from.
+pub const GIT_REVISION: &str = {
+    if let Some(revision) = option_env!("GIT_REVISION") {
+        revision
+    } else {
+        let version = git_version::git_version!(
+            args = ["--always", "--dirty", "--exclude", "*"],
+            fallback = ""
+        );
+
+        if version.is_empty() {
+            panic!("unable to query git revision");
+        }
+        version
+    }
+};
+/// The version of the node binary, including the git revision.
+pub const VERSION: &str = const_str::concat!(env!("CARGO_PKG_VERSION"), "-", GIT_REVISION);
 
 pub struct ValidatorComponents {
-    validator_server_handle: JoinHandle<Result<()>>,
+    validator_server_handle: ValidatorServerHandle,
     narwhal_manager: NarwhalManager,
     narwhal_epoch_data_remover: EpochDataRemover,
     consensus_adapter: Arc<ConsensusAdapter>,
@@ -104,23 +155,62 @@ pub struct ValidatorComponents {
 }
 use sui_json_rpc::governance_api::GovernanceReadApi;
 
+/// The validator gRPC server. Dropping the handle stops the server from accepting connections.
+struct ValidatorServerHandle {
+    server: JoinHandle<Result<()>>,
+    cancel: oneshot::Sender<()>,
+}
+
+impl ValidatorServerHandle {
+    /// Stops accepting connections, and returns the server task, which exits once the requests
+    /// in progress have been answered.
+    fn stop(self) -> JoinHandle<Result<()>> {
+        let _ = self.cancel.send(());
+        self.server
+    }
+}
+
+/// The anemo network of the node and the services running on it. They are replaced together
+/// when the network key of the node is rotated.
+struct P2pComponents {
+    network: Network,
+    discovery: discovery::Handle,
+    state_sync: state_sync::Handle,
+    // The metrics of the network are kept in their own registry, which is replaced along with it.
+    registry_id: RegistryID,
+}
+
 pub struct SuiNode {
     config: NodeConfig,
     validator_components: Mutex<Option<ValidatorComponents>>,
-    _json_rpc_service: Option<ServerHandle>,
+    json_rpc_service: Option<ServerHandle>,
+    graphql_service: Option<JoinHandle<Result<()>>>,
+    _fullnode_grpc_service: Option<JoinHandle<Result<()>>>,
+    _admin_rpc_service: Option<ServerHandle>,
+    rpc_rate_limits: RateLimitHandle,
     state: Arc<AuthorityState>,
     transaction_orchestrator: Option<Arc<TransactiondOrchestrator<NetworkAuthorityClient>>>,
     registry_service: RegistryService,
 
-    _discovery: discovery::Handle,
-    state_sync: state_sync::Handle,
+    p2p: ArcSwap<P2pComponents>,
+    state_sync_store: RocksDbStore,
+    network_connection_metrics: NetworkConnectionMetrics,
     checkpoint_store: Arc<CheckpointStore>,
     accumulator: Arc<StateAccumulator>,
     connection_monitor_status: Arc<ConnectionMonitorStatus>,
+    transaction_prechecker: Arc<TransactionPrechecker>,
 
     /// Broadcast channel to send the committee and protocol version for the next epoch.
     end_of_epoch_channel: broadcast::Sender<(CommitteeWithNetworkMetadata, ProtocolVersion)>,
 
+    db_backups: Option<Arc<DbBackups>>,
+
+    /// Notified when the protocol key of the validator has been rotated to the next protocol key
+    /// pair from the config, which the node can only sign with after a restart.
+    protocol_key_rotation: Notify,
+
+    runtimes: SuiRuntimes,
+
     #[cfg(msim)]
     sim_node: sui_simulator::runtime::NodeHandle,
 }
@@ -151,6 +241,7 @@ impl SuiNode {
         let is_validator = config.consensus_config().is_some();
         let is_full_node = !is_validator;
         let prometheus_registry = registry_service.default_registry();
+        let runtimes = SuiRuntimes::new(&config.runtime_config)?;
 
         info!(node =? config.protocol_public_key(),
             "Initializing sui-node listening on {}", config.network_address
@@ -159,25 +250,66 @@ impl SuiNode {
         // Initialize metrics to track db usage before creating any stores
         DBMetrics::init(&prometheus_registry);
         mysten_metrics::init_metrics(&prometheus_registry);
+        if let Some(db_options_path) = &config.db_options_path {
+            TableOptionsOverrides::load(db_options_path)
+                .map_err(|e| anyhow!("{e}"))?
+                .install();
+        }
+        set_db_statistics_enabled(config.enable_db_statistics);
+        if let Some(watchdog_config) = &config.watchdog_config {
+            let mut watched_runtimes = vec![("main", Handle::current())];
+            for (name, handle) in [
+                ("consensus", runtimes.consensus()),
+                ("execution", runtimes.execution()),
+                ("json-rpc", runtimes.json_rpc()),
+                ("p2p", runtimes.p2p()),
+            ] {
+                if let Some(handle) = handle {
+                    watched_runtimes.push((name, handle.clone()));
+                }
+            }
+            watchdog::start_watchdog(watchdog_config, watched_runtimes, &prometheus_registry);
+        }
 
         let genesis = config.genesis()?;
 
-        let secret = Arc::pin(config.protocol_key_pair().copy());
         let genesis_committee = genesis.committee()?;
         let committee_store = Arc::new(CommitteeStore::new(
             config.db_path().join("epochs"),
             &genesis_committee,
             None,
         ));
-        let store = Arc::new(
-            AuthorityStore::open(
-                &config.db_path().join("store"),
-                None,
-                genesis,
-                &committee_store,
-            )
-            .await?,
-        );
+        let checkpoint_store = CheckpointStore::new(&config.db_path().join("checkpoints"));
+        let indirect_objects_threshold = config.indirect_objects_threshold.unwrap_or(usize::MAX);
+        let store = match &config.formal_snapshot_restore_config {
+            Some(restore_config) => {
+                formal_snapshot::open_restored_store(
+                    restore_config,
+                    &config.db_path().join("store"),
+                    genesis,
+                    &committee_store,
+                    &checkpoint_store,
+                    indirect_objects_threshold,
+                )
+                .await?
+            }
+            None => Arc::new(
+                AuthorityStore::open(
+                    &config.db_path().join("store"),
+                    None,
+                    genesis,
+                    &committee_store,
+                    indirect_objects_threshold,
+                )
+                .await?,
+            ),
+        };
+        // Subscribed to before anything is executed, so that the log has every commit.
+        let commit_log_records = config
+            .commit_log_config
+            .as_ref()
+            .map(|_| store.subscribe_commit_log())
+            .transpose()?;
         let cur_epoch = store.get_recovery_epoch_at_restart()?;
         let committee = committee_store
             .get_committee(&cur_epoch)?
@@ -185,6 +317,8 @@ impl SuiNode {
         let epoch_start_configuration = store
             .get_epoch_start_configuration()?
             .expect("EpochStartConfiguration of the current epoch must exist");
+        Self::select_protocol_key_pair(&mut config, epoch_start_configuration.epoch_start_state())?;
+        let secret = Arc::pin(config.protocol_key_pair().copy());
         let cache_metrics = Arc::new(ResolverMetrics::new(&prometheus_registry));
         let epoch_store = AuthorityPerEpochStore::new(
             config.protocol_public_key(),
@@ -197,7 +331,6 @@ impl SuiNode {
             cache_metrics,
         );
 
-        let checkpoint_store = CheckpointStore::new(&config.db_path().join("checkpoints"));
         checkpoint_store.insert_genesis_checkpoint(
             genesis.checkpoint(),
             genesis.checkpoint_contents().clone(),
@@ -237,30 +370,35 @@ impl SuiNode {
             );
 
         // Create network
-        let (p2p_network, discovery_handle, state_sync_handle) = Self::create_p2p_network(
-            &config,
-            epoch_store.epoch_start_state(),
-            state_sync_store,
-            end_of_epoch_channel.subscribe(),
-            &prometheus_registry,
-        )?;
+        let p2p = {
+            let _guard = runtime::enter(runtimes.p2p());
+            Self::create_p2p_network(
+                &config,
+                epoch_store.epoch_start_state(),
+                state_sync_store.clone(),
+                end_of_epoch_channel.subscribe(),
+                &registry_service,
+            )?
+        };
 
         // Create Authority State
-        let state = AuthorityState::new(
-            config.protocol_public_key(),
-            secret,
-            config.supported_protocol_versions.unwrap(),
-            store.clone(),
-            epoch_store.clone(),
-            committee_store.clone(),
-            index_store.clone(),
-            event_store,
-            checkpoint_store.clone(),
-            &prometheus_registry,
-            config.authority_store_pruning_config,
-            genesis.objects(),
-            config.epoch_duration_ms,
-            &config.state_snapshot_config,
+        let state = runtime::run_in(
+            runtimes.execution(),
+            AuthorityState::new(
+                config.protocol_public_key(),
+                secret,
+                config.supported_protocol_versions.unwrap(),
+                store.clone(),
+                epoch_store.clone(),
+                committee_store.clone(),
+                index_store.clone(),
+                event_store,
+                checkpoint_store.clone(),
+                &prometheus_registry,
+                config.pruning,
+                config.epoch_duration_ms,
+                &config.state_snapshot_config,
+            ),
         )
         .await;
 
@@ -295,15 +433,56 @@ impl SuiNode {
             None
         };
 
-        let json_rpc_service = build_server(
-            state.clone(),
-            &transaction_orchestrator.clone(),
-            &config,
-            &prometheus_registry,
+        let rpc_rate_limits = RateLimitHandle::new(config.rpc_rate_limit_config.clone());
+        let rpc_services = runtime::run_in(
+            runtimes.json_rpc(),
+            build_server(
+                state.clone(),
+                &transaction_orchestrator.clone(),
+                &config,
+                rpc_rate_limits.clone(),
+                &prometheus_registry,
+            ),
         )
         .await?;
+        let (json_rpc_service, graphql_service) = match rpc_services {
+            Some(rpc_services) => (Some(rpc_services.json_rpc), rpc_services.graphql),
+            None => (None, None),
+        };
+
+        let admin_rpc_service = match &config.admin_rpc_config {
+            Some(admin_rpc_config) => Some(
+                runtime::run_in(
+                    runtimes.json_rpc(),
+                    Self::start_admin_rpc_service(
+                        admin_rpc_config,
+                        state.clone(),
+                        rpc_rate_limits.clone(),
+                        &registry_service,
+                    ),
+                )
+                .await?,
+            ),
+            None => None,
+        };
+
+        let fullnode_grpc_service = match (&config.fullnode_grpc_address, &transaction_orchestrator)
+        {
+            (Some(address), Some(transaction_orchestrator)) => Some(
+                Self::start_grpc_fullnode_service(
+                    &config,
+                    address,
+                    state.clone(),
+                    transaction_orchestrator.clone(),
+                    &registry_service,
+                )
+                .await?,
+            ),
+            _ => None,
+        };
 
         let accumulator = Arc::new(StateAccumulator::new(store));
+        accumulator.resume_root_state_hash_reset();
 
         let authority_names_to_peer_ids = epoch_store
             .epoch_start_configuration()
@@ -315,30 +494,39 @@ impl SuiNode {
 
         let authority_names_to_peer_ids = ArcSwap::from_pointee(authority_names_to_peer_ids);
 
-        let (_connection_monitor_handle, connection_statuses) =
+        let (_connection_monitor_handle, connection_statuses) = {
+            let _guard = runtime::enter(runtimes.p2p());
             narwhal_network::connectivity::ConnectionMonitor::spawn(
-                p2p_network.downgrade(),
-                network_connection_metrics,
+                p2p.network.downgrade(),
+                network_connection_metrics.clone(),
                 HashMap::new(),
-            );
+            )
+        };
 
         let connection_monitor_status = ConnectionMonitorStatus {
-            connection_statuses,
+            connection_statuses: ArcSwap::new(connection_statuses),
             authority_names_to_peer_ids,
         };
 
         let connection_monitor_status = Arc::new(connection_monitor_status);
 
+        let transaction_prechecker = Arc::new(TransactionPrechecker::new(
+            &config.transaction_deny_config,
+            TransactionPrecheckMetrics::new(&prometheus_registry),
+        ));
+
         let validator_components = if state.is_validator(&epoch_store) {
             let components = Self::construct_validator_components(
                 &config,
                 state.clone(),
                 epoch_store.clone(),
                 checkpoint_store.clone(),
-                state_sync_handle.clone(),
+                p2p.state_sync.clone(),
                 accumulator.clone(),
                 connection_monitor_status.clone(),
+                transaction_prechecker.clone(),
                 &registry_service,
+                &runtimes,
             )
             .await?;
             // This is only needed during cold start.
@@ -349,20 +537,79 @@ impl SuiNode {
             None
         };
 
+        let db_backups = config.db_backup_config.clone().map(|db_backup_config| {
+            let interval_secs = db_backup_config.backup_interval_secs;
+            let db_backups = Arc::new(DbBackups::new(
+                state.clone(),
+                db_backup_config,
+                config.db_path().to_path_buf(),
+            ));
+            if let Some(interval_secs) = interval_secs {
+                let db_backups = db_backups.clone();
+                spawn_monitored_task!(
+                    db_backups.run_periodically(Duration::from_secs(interval_secs))
+                );
+            }
+            db_backups
+        });
+
+        if let Some(archive_writer_config) = &config.archive_writer_config {
+            let uploader = ArchiveUploader::new(
+                state.clone(),
+                checkpoint_store.clone(),
+                archive_writer_config,
+            )?;
+            spawn_monitored_task!(uploader.run());
+        }
+
+        if let Some(formal_snapshot_writer_config) = &config.formal_snapshot_writer_config {
+            let uploader = FormalSnapshotUploader::new(
+                checkpoint_store.clone(),
+                &config.db_path().join("store"),
+                formal_snapshot_writer_config,
+            );
+            spawn_monitored_task!(uploader.run());
+        }
+
+        if let (Some(commit_log_config), Some(records)) =
+            (&config.commit_log_config, commit_log_records)
+        {
+            let uploader = CommitLogUploader::new(records, commit_log_config)?;
+            spawn_monitored_task!(uploader.run());
+        }
+
+        if let Some(compaction_window_config) = &config.compaction_window_config {
+            let compactor = TableCompactor::new(state.clone(), checkpoint_store.clone());
+            spawn_monitored_task!(compactor.run_in_window(compaction_window_config.clone()));
+        }
+
+        let db_usage_reporter =
+            DbUsageReporter::new(state.clone(), DbPaths::new(&config), &prometheus_registry);
+        spawn_monitored_task!(db_usage_reporter.run());
+
         let node = Self {
             config,
             validator_components: Mutex::new(validator_components),
-            _json_rpc_service: json_rpc_service,
+            json_rpc_service,
+            graphql_service,
+            _fullnode_grpc_service: fullnode_grpc_service,
+            _admin_rpc_service: admin_rpc_service,
+            rpc_rate_limits,
             state,
             transaction_orchestrator,
             registry_service,
 
-            _discovery: discovery_handle,
-            state_sync: state_sync_handle,
+            p2p: ArcSwap::from_pointee(p2p),
+            state_sync_store,
+            network_connection_metrics,
             checkpoint_store,
             accumulator,
             end_of_epoch_channel,
+            db_backups,
+            protocol_key_rotation: Notify::new(),
             connection_monitor_status,
+            transaction_prechecker,
+            runtimes,
 
             #[cfg(msim)]
             sim_node: sui_simulator::runtime::NodeHandle::current(),
@@ -371,7 +618,11 @@ impl SuiNode {
         info!("SuiNode started!");
         let node = Arc::new(node);
         let node_copy = node.clone();
-        spawn_monitored_task!(async move { Self::monitor_reconfiguration(node_copy).await });
+        {
+            // Checkpoint execution, and everything restarted on reconfiguration, runs from here.
+            let _guard = runtime::enter(node.runtimes.execution());
+            spawn_monitored_task!(async move { Self::monitor_reconfiguration(node_copy).await });
+        }
 
         Ok(node)
     }
@@ -382,6 +633,13 @@ impl SuiNode {
         self.end_of_epoch_channel.subscribe()
     }
 
+    /// Completes once the protocol key of the validator has been rotated to the next protocol key
+    /// pair from the config. By then the node has stopped signing with the old key, and it has to
+    /// be restarted to sign with the new one.
+    pub async fn wait_for_protocol_key_rotation(&self) {
+        self.protocol_key_rotation.notified().await
+    }
+
     pub fn current_epoch_for_testing(&self) -> EpochId {
         self.state.current_epoch_for_testing()
     }
@@ -406,6 +664,78 @@ impl SuiNode {
         self.close_epoch(&epoch_store).await
     }
 
+    /// Shuts the node down without interrupting any write: stops serving JSON-RPC and validator
+    /// requests, lets the transactions being executed finish, flushes the databases and closes
+    /// the connections to peers. Waiting for in-flight work is bounded by `drain_timeout`.
+    pub async fn shutdown(&self, drain_timeout: Duration) {
+        info!(?drain_timeout, "Shutting down SuiNode");
+
+        if let Some(json_rpc_service) = &self.json_rpc_service {
+            // Stops accepting connections, requests in progress are left to finish below.
+            let _ = json_rpc_service.stop();
+        }
+        if let Some(graphql_service) = &self.graphql_service {
+            if graphql_service.is_finished() {
+                warn!("GraphQL server had already stopped");
+            }
+            graphql_service.abort();
+        }
+
+        let mut validator_server = None;
+        let mut narwhal_manager = None;
+        if let Some(components) = self.validator_components.lock().await.take() {
+            // Stops accepting validator requests, the ones in progress are left to finish below.
+            validator_server = Some(components.validator_server_handle.stop());
+            drop(components.checkpoint_service_exit);
+            narwhal_manager = Some(components.narwhal_manager);
+        }
+        if let Some(db_backups) = &self.db_backups {
+            db_backups.stop().await;
+        }
+
+        let execution_lock = tokio::time::timeout(drain_timeout, async {
+            if let Some(json_rpc_service) = self.json_rpc_service.clone() {
+                json_rpc_service.stopped().await;
+            }
+            if let Some(validator_server) = &mut validator_server {
+                if let Ok(Err(e)) = validator_server.await {
+                    warn!("Validator gRPC server failed: {e}");
+                }
+            }
+            // Validator requests may be waiting on consensus, which is only stopped once they
+            // are done.
+            if let Some(narwhal_manager) = &narwhal_manager {
+                narwhal_manager.shutdown().await;
+            }
+            self.state.shutdown_execution().await
+        })
+        .await;
+        if execution_lock.is_err() {
+            warn!("In-flight work did not finish within {drain_timeout:?}, exiting anyway");
+            if let Some(validator_server) = validator_server {
+                validator_server.abort();
+            }
+            if let Some(narwhal_manager) = &narwhal_manager {
+                narwhal_manager.shutdown().await;
+            }
+        }
+
+        if let Err(e) = self.state.flush_db() {
+            warn!("Failed to flush authority store: {e}");
+        }
+        if let Err(e) = self.checkpoint_store.flush() {
+            warn!("Failed to flush checkpoint store: {e}");
+        }
+        drop(execution_lock);
+
+        // Closes the connections to peers, letting them know this node is going away.
+        let p2p_network = self.p2p.load().network.clone();
+        if let Err(e) = p2p_network.shutdown().await {
+            warn!("Failed to shut down p2p network: {e}");
+        }
+        info!("SuiNode shut down");
+    }
+
     pub fn is_transaction_executed_in_checkpoint(
         &self,
         digest: &TransactionDigest,
@@ -420,31 +750,19 @@ impl SuiNode {
         sui_system: &EpochStartSystemState,
         state_sync_store: RocksDbStore,
         reconfig_channel: Receiver<(CommitteeWithNetworkMetadata, ProtocolVersion)>,
-        prometheus_registry: &Registry,
-    ) -> Result<(Network, discovery::Handle, state_sync::Handle)> {
+        registry_service: &RegistryService,
+    ) -> Result<P2pComponents> {
+        let prometheus_registry = Registry::new();
         let (state_sync, state_sync_server) = state_sync::Builder::new()
             .config(config.p2p_config.state_sync.clone().unwrap_or_default())
             .store(state_sync_store)
-            .with_metrics(prometheus_registry)
+            .with_metrics(&prometheus_registry)
             .build();
 
         // TODO only configure validators as seed/preferred peers for validators and not for
         // fullnodes once we've had a chance to re-work fullnode configuration generation.
         let mut p2p_config = config.p2p_config.clone();
-        let network_kp = config.network_key_pair();
-        let our_network_public_key = network_kp.public();
-        let other_validators = sui_system
-            .get_sui_committee()
-            .network_metadata
-            .into_iter()
-            .filter(|(_name, network_metadata)| {
-                &network_metadata.network_pubkey != our_network_public_key
-            })
-            .map(|(_name, network_metadata)| sui_config::p2p::SeedPeer {
-                peer_id: Some(anemo::PeerId(network_metadata.network_pubkey.0.to_bytes())),
-                address: network_metadata.p2p_address,
-            });
-        p2p_config.seed_peers.extend(other_validators);
+        p2p_config.seed_peers = Self::seed_peers(config, sui_system);
 
         let (discovery, discovery_server) = discovery::Builder::new(reconfig_channel)
             .config(p2p_config)
@@ -456,9 +774,9 @@ impl SuiNode {
                 .add_rpc_service(state_sync_server);
 
             let inbound_network_metrics =
-                NetworkMetrics::new("sui", "inbound", prometheus_registry);
+                NetworkMetrics::new("sui", "inbound", &prometheus_registry);
             let outbound_network_metrics =
-                NetworkMetrics::new("sui", "outbound", prometheus_registry);
+                NetworkMetrics::new("sui", "outbound", &prometheus_registry);
 
             let service = ServiceBuilder::new()
                 .layer(
@@ -491,18 +809,127 @@ impl SuiNode {
 
             let network = Network::bind(config.p2p_config.listen_address)
                 .server_name("sui")
-                .private_key(config.network_key_pair().copy().private().0.to_bytes())
+                .private_key(
+                    Self::network_key_pair(config, sui_system)
+                        .copy()
+                        .private()
+                        .0
+                        .to_bytes(),
+                )
                 .config(anemo_config)
                 .outbound_request_layer(outbound_layer)
                 .start(service)?;
-            info!("P2p network started on {}", network.local_addr());
+            info!(
+                peer_id = ?network.peer_id(),
+                "P2p network started on {}",
+                network.local_addr()
+            );
 
             network
         };
 
-        let discovery_handle = discovery.start(p2p_network.clone());
-        let state_sync_handle = state_sync.start(p2p_network.clone());
-        Ok((p2p_network, discovery_handle, state_sync_handle))
+        let discovery = discovery.start(p2p_network.clone());
+        let state_sync = state_sync.start(p2p_network.clone());
+        Ok(P2pComponents {
+            network: p2p_network,
+            discovery,
+            state_sync,
+            registry_id: registry_service.add(prometheus_registry),
+        })
+    }
+
+    /// The network key pair of the node in the epoch starting with `sui_system`. This is the
+    /// next network key pair from the config once it is the on-chain network key of the
+    /// validator, and the current one otherwise.
+    fn network_key_pair<'a>(
+        config: &'a NodeConfig,
+        sui_system: &EpochStartSystemState,
+    ) -> &'a NetworkKeyPair {
+        let on_chain_key = Self::own_validator(config, sui_system)
+            .map(|validator| &validator.narwhal_network_pubkey);
+        config.network_key_pair_for(on_chain_key)
+    }
+
+    /// The validator of this node in the epoch starting with `sui_system`, if it is one. It is
+    /// looked up by address rather than by protocol key, as the protocol key may be rotated.
+    fn own_validator<'a>(
+        config: &NodeConfig,
+        sui_system: &'a EpochStartSystemState,
+    ) -> Option<&'a EpochStartValidatorInfo> {
+        let address = config.sui_address();
+        sui_system
+            .active_validators
+            .iter()
+            .find(|validator| validator.sui_address == address)
+    }
+
+    /// Switches the config to the next protocol key pair once it is the on-chain protocol key of
+    /// the validator. A validator whose on-chain protocol key is neither key pair from the config
+    /// is not started, as it could otherwise sign with a key the committee no longer uses.
+    fn select_protocol_key_pair(
+        config: &mut NodeConfig,
+        sui_system: &EpochStartSystemState,
+    ) -> Result<()> {
+        let address = config.sui_address();
+        let on_chain_name = match Self::own_validator(config, sui_system) {
+            Some(validator) => validator.authority_name(),
+            None => return Ok(()),
+        };
+        if on_chain_name == config.protocol_public_key() {
+            return Ok(());
+        }
+        match config.next_protocol_key_pair.clone() {
+            Some(next)
+                if AuthorityName::from(next.authority_keypair().public()) == on_chain_name =>
+            {
+                info!(
+                    epoch = sui_system.epoch,
+                    "Switching to the next protocol key pair, which is now the on-chain key"
+                );
+                config.protocol_key_pair = next;
+                config.next_protocol_key_pair = None;
+                Ok(())
+            }
+            _ if config.consensus_config().is_some() => Err(anyhow!(
+                "The on-chain protocol key of validator {address} is {}, which is neither \
+                 protocol-key-pair nor next-protocol-key-pair",
+                on_chain_name.concise()
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /// Whether the protocol key of the validator has been rotated to the next protocol key pair
+    /// from the config in the epoch starting with `sui_system`.
+    fn protocol_key_rotated(&self, sui_system: &EpochStartSystemState) -> bool {
+        let Some(next) = self.config.next_protocol_key_pair() else {
+            return false;
+        };
+        let next_name = AuthorityName::from(next.public());
+        next_name != self.state.name
+            && Self::own_validator(&self.config, sui_system)
+                .map_or(false, |validator| validator.authority_name() == next_name)
+    }
+
+    /// The seed peers from the config, followed by the other validators of the current epoch.
+    fn seed_peers(config: &NodeConfig, sui_system: &EpochStartSystemState) -> Vec<SeedPeer> {
+        let our_name = config.protocol_public_key();
+        let other_validators = sui_system
+            .get_sui_committee()
+            .network_metadata
+            .into_iter()
+            .filter(|(name, _network_metadata)| *name != our_name)
+            .map(|(_name, network_metadata)| SeedPeer {
+                peer_id: Some(anemo::PeerId(network_metadata.network_pubkey.0.to_bytes())),
+                address: network_metadata.p2p_address,
+            });
+        config
+            .p2p_config
+            .seed_peers
+            .iter()
+            .cloned()
+            .chain(other_validators)
+            .collect()
     }
 
     async fn construct_validator_components(
@@ -513,7 +940,9 @@ impl SuiNode {
         state_sync_handle: state_sync::Handle,
         accumulator: Arc<StateAccumulator>,
         connection_monitor_status: Arc<ConnectionMonitorStatus>,
+        transaction_prechecker: Arc<TransactionPrechecker>,
         registry_service: &RegistryService,
+        runtimes: &SuiRuntimes,
     ) -> Result<ValidatorComponents> {
         let consensus_config = config
             .consensus_config()
@@ -530,12 +959,17 @@ impl SuiNode {
             config,
             state.clone(),
             consensus_adapter.clone(),
+            transaction_prechecker,
             &registry_service.default_registry(),
         )
         .await?;
 
-        let narwhal_manager =
-            Self::construct_narwhal_manager(config, consensus_config, registry_service)?;
+        let narwhal_manager = Self::construct_narwhal_manager(
+            config,
+            consensus_config,
+            Self::network_key_pair(config, epoch_store.epoch_start_state()),
+            registry_service,
+        )?;
 
         let mut narwhal_epoch_data_remover =
             EpochDataRemover::new(narwhal_manager.get_storage_base_path());
@@ -559,6 +993,7 @@ impl SuiNode {
             accumulator,
             checkpoint_metrics,
             sui_tx_validator_metrics,
+            runtimes,
         )
         .await
     }
@@ -572,10 +1007,11 @@ impl SuiNode {
         state_sync_handle: state_sync::Handle,
         narwhal_manager: NarwhalManager,
         narwhal_epoch_data_remover: EpochDataRemover,
-        validator_server_handle: JoinHandle<Result<()>>,
+        validator_server_handle: ValidatorServerHandle,
         accumulator: Arc<StateAccumulator>,
         checkpoint_metrics: Arc<CheckpointMetrics>,
         sui_tx_validator_metrics: Arc<SuiTxValidatorMetrics>,
+        runtimes: &SuiRuntimes,
     ) -> Result<ValidatorComponents> {
         let (checkpoint_service, checkpoint_service_exit) = Self::start_checkpoint_service(
             config,
@@ -606,18 +1042,21 @@ impl SuiNode {
             .address;
         let worker_cache = new_epoch_start_state.get_narwhal_worker_cache(transactions_addr);
 
-        narwhal_manager
-            .start(
+        runtime::run_in(
+            runtimes.consensus(),
+            narwhal_manager.start(
                 committee.clone(),
                 worker_cache,
+                epoch_store.protocol_config().clone(),
                 consensus_handler,
                 SuiTxValidator::new(
                     epoch_store,
                     state.transaction_manager().clone(),
                     sui_tx_validator_metrics.clone(),
                 ),
-            )
-            .await;
+            ),
+        )
+        .await;
 
         Ok(ValidatorComponents {
             validator_server_handle,
@@ -682,11 +1121,12 @@ impl SuiNode {
     fn construct_narwhal_manager(
         config: &NodeConfig,
         consensus_config: &ConsensusConfig,
+        network_key_pair: &NetworkKeyPair,
         registry_service: &RegistryService,
     ) -> Result<NarwhalManager> {
         let narwhal_config = NarwhalConfiguration {
             primary_keypair: config.protocol_key_pair().copy(),
-            network_keypair: config.network_key_pair().copy(),
+            network_keypair: network_key_pair.copy(),
             worker_ids_and_keypairs: vec![(0, config.worker_key_pair().copy())],
             storage_base_path: consensus_config.db_path().to_path_buf(),
             parameters: consensus_config.narwhal_config().to_owned(),
@@ -735,10 +1175,16 @@ impl SuiNode {
         config: &NodeConfig,
         state: Arc<AuthorityState>,
         consensus_adapter: Arc<ConsensusAdapter>,
+        transaction_prechecker: Arc<TransactionPrechecker>,
         prometheus_registry: &Registry,
-    ) -> Result<tokio::task::JoinHandle<Result<()>>> {
-        let validator_service =
-            ValidatorService::new(state.clone(), consensus_adapter, prometheus_registry).await?;
+    ) -> Result<ValidatorServerHandle> {
+        let validator_service = ValidatorService::new(
+            state.clone(),
+            consensus_adapter,
+            transaction_prechecker,
+            prometheus_registry,
+        )
+        .await?;
 
         let mut server_conf = mysten_network::config::Config::new();
         server_conf.global_concurrency_limit = config.grpc_concurrency_limit;
@@ -748,21 +1194,167 @@ impl SuiNode {
 
         server_builder = server_builder.add_service(ValidatorServer::new(validator_service));
 
-        let server = server_builder
+        let mut server = server_builder
             .bind(config.network_address())
             .await
             .map_err(|err| anyhow!(err.to_string()))?;
+        let cancel = server
+            .take_cancel_handle()
+            .expect("the cancel handle of a new server is available");
         let local_addr = server.local_addr();
         info!("Listening to traffic on {local_addr}");
         let grpc_server = spawn_monitored_task!(server.serve().map_err(Into::into));
 
+        Ok(ValidatorServerHandle {
+            server: grpc_server,
+            cancel,
+        })
+    }
+
+    async fn start_grpc_fullnode_service(
+        config: &NodeConfig,
+        address: &Multiaddr,
+        state: Arc<AuthorityState>,
+        transaction_orchestrator: Arc<TransactiondOrchestrator<NetworkAuthorityClient>>,
+        registry_service: &RegistryService,
+    ) -> Result<tokio::task::JoinHandle<Result<()>>> {
+        let fullnode_service = FullnodeService::new(state, transaction_orchestrator);
+
+        // The metrics are prefixed to tell them from those of the validator service, which the
+        // node starts alongside if it is promoted to a validator.
+        let prometheus_registry = Registry::new_custom(Some("fullnode".to_string()), None)?;
+        let mut server_conf = mysten_network::config::Config::new();
+        server_conf.global_concurrency_limit = config.grpc_concurrency_limit;
+        server_conf.load_shed = config.grpc_load_shed;
+        let mut server_builder =
+            ServerBuilder::from_config(&server_conf, GrpcMetrics::new(&prometheus_registry));
+        registry_service.add(prometheus_registry);
+
+        server_builder = server_builder.add_service(FullnodeServer::new(fullnode_service));
+
+        let server = server_builder
+            .bind(address)
+            .await
+            .map_err(|err| anyhow!(err.to_string()))?;
+        let local_addr = server.local_addr();
+        info!("Fullnode gRPC service listening on {local_addr}");
+        let grpc_server = spawn_monitored_task!(server.serve().map_err(Into::into));
+
         Ok(grpc_server)
     }
 
+    async fn start_admin_rpc_service(
+        admin_rpc_config: &AdminRpcConfig,
+        state: Arc<AuthorityState>,
+        rpc_rate_limits: RateLimitHandle,
+        registry_service: &RegistryService,
+    ) -> Result<ServerHandle> {
+        // Anyone who can reach the server can act on the node, so only the node's own host can
+        // without a token.
+        if !admin_rpc_config.listen_address.ip().is_loopback()
+            && admin_rpc_config.auth_token.is_none()
+        {
+            return Err(anyhow!(
+                "Admin RPC server listening on {} requires an auth token",
+                admin_rpc_config.listen_address
+            ));
+        }
+
+        // The metrics are prefixed to tell them from those of the public JSON-RPC server.
+        let prometheus_registry = Registry::new_custom(Some("admin".to_string()), None)?;
+        let mut server = JsonRpcServerBuilder::new(env!("CARGO_PKG_VERSION"), &prometheus_registry);
+        registry_service.add(prometheus_registry);
+        // Web pages the operators visit must not be able to act on the node through their browsers.
+        server.deny_browsers();
+        if let Some(auth_token) = &admin_rpc_config.auth_token {
+            server.set_auth_token(auth_token.clone());
+        }
+        server.register_module(AdminApi::new(state, rpc_rate_limits))?;
+
+        Ok(server.start(admin_rpc_config.listen_address).await?)
+    }
+
     pub fn state(&self) -> Arc<AuthorityState> {
         self.state.clone()
     }
 
+    pub fn config(&self) -> &NodeConfig {
+        &self.config
+    }
+
+    pub fn checkpoint_store(&self) -> &Arc<CheckpointStore> {
+        &self.checkpoint_store
+    }
+
+    /// `None` if backups are not enabled in the config.
+    pub fn db_backups(&self) -> Option<&Arc<DbBackups>> {
+        self.db_backups.as_ref()
+    }
+
+    pub fn table_compactor(&self) -> TableCompactor {
+        TableCompactor::new(self.state.clone(), self.checkpoint_store.clone())
+    }
+
+    /// The number of peers the p2p network is connected to.
+    pub fn num_connected_peers(&self) -> usize {
+        self.p2p.load().network.peers().len()
+    }
+
+    fn state_sync(&self) -> state_sync::Handle {
+        self.p2p.load().state_sync.clone()
+    }
+
+    /// Returns an error describing why the node should not receive traffic yet, if any: its
+    /// database cannot be read, it is more than `max_checkpoint_lag` checkpoints behind its
+    /// peers, or it is a validator whose consensus is not running. A validator that is
+    /// reconfiguring is reported as not ready rather than waiting for reconfiguration to finish.
+    pub fn check_ready(&self, max_checkpoint_lag: u64) -> Result<(), String> {
+        let highest_executed = self
+            .checkpoint_store
+            .get_highest_executed_checkpoint_seq_number()
+            .map_err(|e| format!("failed to read checkpoint store: {e}"))?;
+        health::check_checkpoint_lag(
+            highest_executed,
+            self.state_sync().highest_known_checkpoint_sequence_number(),
+            max_checkpoint_lag,
+        )?;
+
+        let (is_validator, epoch) = {
+            let epoch_store = self.state.load_epoch_store_one_call_per_task();
+            (self.state.is_validator(&epoch_store), epoch_store.epoch())
+        };
+        if is_validator {
+            // The lock is held for the whole of reconfiguration and shutdown, so a probe must not
+            // wait on it.
+            let Ok(validator_components) = self.validator_components.try_lock() else {
+                return Err(format!("validator is reconfiguring or shutting down in epoch {epoch}"));
+            };
+            if validator_components.is_none() {
+                return Err(format!("consensus is not running for epoch {epoch}"));
+            }
+        }
+        Ok(())
+    }
+
+    /// Applies the fields of a reloaded config that can be changed while the node is running,
+    /// see [`sui_config::reload::RELOADABLE_FIELDS`]. All other fields are ignored.
+    pub fn apply_reloaded_config(&self, config: &NodeConfig) {
+        let seed_peers = {
+            let epoch_store = self.state.load_epoch_store_one_call_per_task();
+            Self::seed_peers(config, epoch_store.epoch_start_state())
+        };
+        self.p2p.load().discovery.update_seed_peers(seed_peers);
+        self.state
+            .set_objects_pruning_retention(config.pruning.num_epochs_to_retain);
+        self.transaction_prechecker
+            .update_deny_config(&config.transaction_deny_config);
+        // Setting the limits starts every client over, so they are only set when changed.
+        if self.rpc_rate_limits.config() != config.rpc_rate_limit_config {
+            self.rpc_rate_limits
+                .set(config.rpc_rate_limit_config.clone());
+        }
+    }
+
     pub fn clone_committee_store(&self) -> Arc<CommitteeStore> {
         self.state.committee_store().clone()
     }
@@ -803,7 +1395,7 @@ impl SuiNode {
     /// epoch has changed. Upon receiving such signal, we reconfigure the entire system.
     pub async fn monitor_reconfiguration(self: Arc<Self>) -> Result<()> {
         let mut checkpoint_executor = CheckpointExecutor::new(
-            self.state_sync.subscribe_to_synced_checkpoints(),
+            self.state_sync().subscribe_to_synced_checkpoints(),
             self.checkpoint_store.clone(),
             self.state.database.clone(),
             self.state.transaction_manager().clone(),
@@ -871,7 +1463,7 @@ impl SuiNode {
             // in the new epoch.
             let new_validator_components = if let Some(ValidatorComponents {
                 validator_server_handle,
-                narwhal_manager,
+                mut narwhal_manager,
                 narwhal_epoch_data_remover,
                 consensus_adapter,
                 checkpoint_service_exit,
@@ -888,6 +1480,8 @@ impl SuiNode {
                 let new_epoch_store = self
                     .reconfigure_state(&cur_epoch_store, next_epoch_committee, new_system_state)
                     .await;
+                self.rotate_network_key_if_needed(&new_epoch_store, &mut checkpoint_executor)
+                    .await?;
 
                 narwhal_epoch_data_remover
                     .remove_old_data(next_epoch - 1)
@@ -895,6 +1489,10 @@ impl SuiNode {
 
                 if self.state.is_validator(&new_epoch_store) {
                     // Only restart Narwhal if this node is still a validator in the new epoch.
+                    narwhal_manager.set_network_keypair(
+                        Self::network_key_pair(&self.config, new_epoch_store.epoch_start_state())
+                            .copy(),
+                    );
                     Some(
                         Self::start_epoch_specific_validator_components(
                             &self.config,
@@ -902,13 +1500,14 @@ impl SuiNode {
                             consensus_adapter,
                             self.checkpoint_store.clone(),
                             new_epoch_store.clone(),
-                            self.state_sync.clone(),
+                            self.state_sync(),
                             narwhal_manager,
                             narwhal_epoch_data_remover,
                             validator_server_handle,
                             self.accumulator.clone(),
                             checkpoint_metrics,
                             sui_tx_validator_metrics,
+                            &self.runtimes,
                         )
                         .await?,
                     )
@@ -920,6 +1519,8 @@ impl SuiNode {
                 let new_epoch_store = self
                     .reconfigure_state(&cur_epoch_store, next_epoch_committee, new_system_state)
                     .await;
+                self.rotate_network_key_if_needed(&new_epoch_store, &mut checkpoint_executor)
+                    .await?;
 
                 if self.state.is_validator(&new_epoch_store) {
                     info!("Promoting the node from fullnode to validator, starting grpc server");
@@ -930,10 +1531,12 @@ impl SuiNode {
                             self.state.clone(),
                             new_epoch_store.clone(),
                             self.checkpoint_store.clone(),
-                            self.state_sync.clone(),
+                            self.state_sync(),
                             self.accumulator.clone(),
                             self.connection_monitor_status.clone(),
+                            self.transaction_prechecker.clone(),
                             &self.registry_service,
+                            &self.runtimes,
                         )
                         .await?,
                     )
@@ -943,9 +1546,89 @@ impl SuiNode {
             };
             *self.validator_components.lock().await = new_validator_components;
             info!("Reconfiguration finished");
+
+            let protocol_key_rotated = {
+                let new_epoch_store = self.state.load_epoch_store_one_call_per_task();
+                self.protocol_key_rotated(new_epoch_store.epoch_start_state())
+            };
+            if protocol_key_rotated {
+                // The committee of the new epoch does not include the old key, so the node no
+                // longer signs anything as a validator until it restarts with the new key.
+                info!("Protocol key rotated, the node has to restart to sign with the new key");
+                self.protocol_key_rotation.notify_one();
+            }
         }
     }
 
+    /// Restarts the p2p network with the network key pair of the epoch of `epoch_store`, if the
+    /// network key of the node was rotated at the start of that epoch. The peers learn about the
+    /// new PeerId from the committee of the epoch.
+    async fn rotate_network_key_if_needed(
+        &self,
+        epoch_store: &AuthorityPerEpochStore,
+        checkpoint_executor: &mut CheckpointExecutor,
+    ) -> Result<()> {
+        let network_key_pair =
+            Self::network_key_pair(&self.config, epoch_store.epoch_start_state());
+        let peer_id = anemo::PeerId(network_key_pair.public().0.to_bytes());
+        let old_p2p = self.p2p.load_full();
+        if old_p2p.network.peer_id() == peer_id {
+            return Ok(());
+        }
+        info!(
+            old_peer_id = ?old_p2p.network.peer_id(),
+            new_peer_id = ?peer_id,
+            "Network key rotated, restarting the p2p network"
+        );
+
+        if let Err(e) = old_p2p.network.shutdown().await {
+            warn!("Failed to shut down p2p network: {e}");
+        }
+        self.registry_service.remove(old_p2p.registry_id);
+
+        // The socket of the old network may take a moment to be released.
+        const MAX_P2P_RETRIES: u32 = 5;
+        let mut p2p_retries = 0;
+        let p2p = loop {
+            let result = {
+                let _guard = runtime::enter(self.runtimes.p2p());
+                Self::create_p2p_network(
+                    &self.config,
+                    epoch_store.epoch_start_state(),
+                    self.state_sync_store.clone(),
+                    self.end_of_epoch_channel.subscribe(),
+                    &self.registry_service,
+                )
+            };
+            match result {
+                Ok(p2p) => break p2p,
+                Err(e) => {
+                    p2p_retries += 1;
+                    if p2p_retries >= MAX_P2P_RETRIES {
+                        return Err(e.context("Unable to restart the p2p network"));
+                    }
+                    warn!("Unable to restart the p2p network: {e:?}, retrying");
+                    tokio::time::sleep(Duration::from_secs(1)).await;
+                }
+            }
+        };
+
+        let (_connection_monitor_handle, connection_statuses) = {
+            let _guard = runtime::enter(self.runtimes.p2p());
+            narwhal_network::connectivity::ConnectionMonitor::spawn(
+                p2p.network.downgrade(),
+                self.network_connection_metrics.clone(),
+                HashMap::new(),
+            )
+        };
+        self.connection_monitor_status
+            .connection_statuses
+            .store(connection_statuses);
+        checkpoint_executor.set_mailbox(p2p.state_sync.subscribe_to_synced_checkpoints());
+        self.p2p.store(Arc::new(p2p));
+        Ok(())
+    }
+
     async fn reconfigure_state(
         &self,
         cur_epoch_store: &AuthorityPerEpochStore,
@@ -978,25 +1661,69 @@ impl SuiNode {
     }
 }
 
+/// The servers of the APIs a fullnode exposes to clients.
+pub struct RpcServices {
+    pub json_rpc: ServerHandle,
+    /// Only completes if the GraphQL server fails.
+    pub graphql: Option<JoinHandle<Result<()>>>,
+}
+
 pub async fn build_server(
     state: Arc<AuthorityState>,
     transaction_orchestrator: &Option<Arc<TransactiondOrchestrator<NetworkAuthorityClient>>>,
     config: &NodeConfig,
+    rate_limits: RateLimitHandle,
     prometheus_registry: &Registry,
-) -> Result<Option<ServerHandle>> {
+) -> Result<Option<RpcServices>> {
     // Validators do not expose these APIs
     if config.consensus_config().is_some() {
         return Ok(None);
     }
 
     let mut server = JsonRpcServerBuilder::new(env!("CARGO_PKG_VERSION"), prometheus_registry);
-
-    server.register_module(ReadApi::new(state.clone()))?;
+    server.set_rate_limits(rate_limits);
+    if let Some(server_config) = &config.rpc_server_config {
+        server.set_server_config(server_config.clone());
+    }
+    if let Some(indexer_fallback_config) = &config.indexer_fallback_config {
+        server.set_indexer_fallback_config(indexer_fallback_config.clone());
+    }
+    server.set_consistency_state(state.clone());
+
+    let archive = match &config.archive_reader_config {
+        Some(archive_config) => {
+            let mut archive = ArchiveReader::new(
+                &archive_config.url,
+                Duration::from_millis(archive_config.request_timeout_ms),
+            )?;
+            if let Some(cache_dir) = &archive_config.cache_dir {
+                archive = archive.with_cache(ArchiveCache::new(
+                    cache_dir.clone(),
+                    archive_config.cache_size_bytes,
+                )?);
+            }
+            Some(Arc::new(archive))
+        }
+        None => None,
+    };
+    let build_info = BuildInfo {
+        version: env!("CARGO_PKG_VERSION"),
+        git_revision: GIT_REVISION,
+        supported_protocol_versions: config.supported_protocol_versions.unwrap(),
+    };
+    server.register_module(ReadApi::new(state.clone(), archive.clone(), build_info))?;
     server.register_module(CoinReadApi::new(state.clone()))?;
     server.register_module(ThresholdBlsApi::new(state.clone()))?;
     server.register_module(TransactionBuilderApi::new(state.clone()))?;
     server.register_module(GovernanceReadApi::new(state.clone()))?;
 
+    if let Some(name_service_config) = &config.name_service_config {
+        server.register_module(NameServiceApi::new(
+            state.clone(),
+            name_service_config.clone(),
+        ))?;
+    }
+
     if let Some(transaction_orchestrator) = transaction_orchestrator {
         server.register_module(TransactionExecutionApi::new(
             state.clone(),
@@ -1010,7 +1737,27 @@ pub async fn build_server(
 
     let rpc_server_handle = server.start(config.json_rpc_address).await?;
 
-    Ok(Some(rpc_server_handle))
+    let graphql_server_handle = match &config.graphql_config {
+        Some(graphql_config) => {
+            let schema = sui_graphql::build_schema(
+                ReadApi::new(state.clone(), archive, build_info),
+                state
+                    .event_handler
+                    .clone()
+                    .map(|event_handler| EventReadApi::new(state.clone(), event_handler)),
+            );
+            Some(sui_graphql::start_graphql_server(
+                graphql_config.listen_address,
+                schema,
+            )?)
+        }
+        None => None,
+    };
+
+    Ok(Some(RpcServices {
+        json_rpc: rpc_server_handle,
+        graphql: graphql_server_handle,
+    }))
 }
 
 #[cfg(not(test))]
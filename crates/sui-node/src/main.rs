@@ -4,32 +4,24 @@
 use anyhow::Result;
 use clap::Parser;
 use multiaddr::Multiaddr;
-use std::path::PathBuf;
+use std::future::Future;
+use std::path::{Path, PathBuf};
 use std::time::Duration;
+use sui_config::reload::NodeConfigReloader;
+use sui_config::validate::{validate_node_config, ConfigIssue};
 use sui_config::{Config, NodeConfig};
-use sui_node::metrics;
+use sui_core::checkpoints::CheckpointStore;
+use sui_node::{metrics, GIT_REVISION, VERSION};
 use sui_protocol_config::SupportedProtocolVersions;
 use sui_telemetry::send_telemetry_event;
 use tokio::task;
 use tokio::time::sleep;
 use tracing::info;
 
-const GIT_REVISION: &str = {
-    if let Some(revision) = option_env!("GIT_REVISION") {
-        revision
-    } else {
-        let version = git_version::git_version!(
-            args = ["--always", "--dirty", "--exclude", "*"],
-            fallback = ""
-        );
-
-        if version.is_empty() {
-            panic!("unable to query git revision");
-        }
-        version
-    }
-};
-const VERSION: &str = const_str::concat!(env!("CARGO_PKG_VERSION"), "-", GIT_REVISION);
+// Heap profiles are captured through jemalloc, see `sui_node::profiling`.
+#[cfg(not(target_env = "msvc"))]
+#[global_allocator]
+static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
 
 #[derive(Parser)]
 #[clap(rename_all = "kebab-case")]
@@ -41,6 +33,12 @@ struct Args {
 
     #[clap(long, help = "Specify address to listen on")]
     listen_address: Option<Multiaddr>,
+
+    #[clap(
+        long,
+        help = "Check the config and print the problems found as JSON, without starting the node"
+    )]
+    validate_config: bool,
 }
 
 #[tokio::main]
@@ -50,7 +48,11 @@ async fn main() -> Result<()> {
     // ProtocolConfig::poison_get_for_min_version();
 
     let args = Args::parse();
+    if args.validate_config {
+        return validate_config(&args.config_path, args.listen_address);
+    }
     let mut config = NodeConfig::load(&args.config_path)?;
+    let config_reloader = NodeConfigReloader::new(&args.config_path)?;
     assert!(
         config.supported_protocol_versions.is_none(),
         "supported_protocol_versions cannot be read from the config file"
@@ -62,12 +64,25 @@ async fn main() -> Result<()> {
     prometheus_registry
         .register(mysten_metrics::uptime_metric(VERSION))
         .unwrap();
+    metrics::register_build_info_metric(
+        &prometheus_registry,
+        env!("CARGO_PKG_VERSION"),
+        GIT_REVISION,
+        config.supported_protocol_versions.unwrap(),
+    );
 
     // Initialize logging
-    let (_guard, filter_handle) = telemetry_subscribers::TelemetryConfig::new()
+    let mut telemetry_config = telemetry_subscribers::TelemetryConfig::new()
         .with_env()
-        .with_prom_registry(&prometheus_registry)
-        .init();
+        .with_prom_registry(&prometheus_registry);
+    if let Some(otlp) = &config.otlp_trace_config {
+        telemetry_config = telemetry_config.with_otlp(telemetry_subscribers::OtlpConfig {
+            endpoint: otlp.endpoint.clone(),
+            sample_ratio: otlp.sample_ratio,
+            service_name: otlp.service_name.clone(),
+        });
+    }
+    let (_guard, filter_handle) = telemetry_config.init();
 
     info!("Sui Node version: {VERSION}");
     info!(
@@ -94,11 +109,186 @@ async fn main() -> Result<()> {
         }
     });
 
-    sui_node::admin::start_admin_server(config.admin_interface_port, filter_handle);
+    let admin_node = sui_node::admin::start_admin_server(
+        config.admin_interface_port,
+        filter_handle,
+        VERSION,
+        config.profiling_config.clone(),
+    );
+
+    if config.read_only_config.is_some() {
+        let _node = sui_node::read_only::ReadOnlyNode::start(&config, registry_service).await?;
+        wait_for_shutdown_signal().await;
+        return Ok(());
+    }
+
+    let ready_node = sui_node::health::start_health_server(&config.health_check_config);
+
+    let node = sui_node::SuiNode::start(&config, registry_service).await?;
+    if let Some(ready_node) = ready_node {
+        ready_node.store(Some(node.clone()));
+    }
+    sui_node::config_watcher::start_config_watcher(node.clone(), config_reloader);
+    admin_node.store(Some(node.clone()));
+
+    let restart = run_until_stopped(
+        wait_for_shutdown_signal(),
+        node.wait_for_protocol_key_rotation(),
+        node.shutdown(Duration::from_secs(config.shutdown_drain_timeout_secs)),
+    )
+    .await;
+    if restart {
+        restart_process()?;
+    }
+    Ok(())
+}
+
+/// Waits for a shutdown signal or for the protocol key of the validator to be rotated, then
+/// runs `shutdown` to completion. Returns whether the process has to be restarted, which is only
+/// safe once the node has been shut down, as the new instance opens the same databases.
+async fn run_until_stopped(
+    shutdown_signal: impl Future<Output = ()>,
+    protocol_key_rotation: impl Future<Output = ()>,
+    shutdown: impl Future<Output = ()>,
+) -> bool {
+    let restart = tokio::select! {
+        _ = shutdown_signal => false,
+        _ = protocol_key_rotation => {
+            info!("Protocol key rotated, restarting to sign with the new key");
+            true
+        }
+    };
+    shutdown.await;
+    restart
+}
+
+/// Replaces the process with a new instance of the node, started with the same arguments.
+#[cfg(unix)]
+fn restart_process() -> Result<()> {
+    use std::os::unix::process::CommandExt;
+
+    let program = std::env::current_exe()?;
+    // Only returns if the new instance could not be started.
+    let error = std::process::Command::new(program)
+        .args(std::env::args_os().skip(1))
+        .exec();
+    Err(error.into())
+}
+
+/// Exits with an error, so that the process manager starts the node again.
+#[cfg(not(unix))]
+fn restart_process() -> Result<()> {
+    Err(anyhow::anyhow!(
+        "The node has to be restarted to use its new protocol key"
+    ))
+}
+
+/// Prints the problems found in the config at `config_path` as a JSON array, and exits with a
+/// non-zero status if there are any.
+fn validate_config(config_path: &Path, listen_address: Option<Multiaddr>) -> Result<()> {
+    let issues = match NodeConfig::load(config_path) {
+        Ok(mut config) => {
+            if let Some(listen_address) = listen_address {
+                config.network_address = listen_address;
+            }
+            let mut issues = validate_node_config(&config);
+            issues.extend(check_genesis_matches_db(&config));
+            issues
+        }
+        Err(e) => vec![ConfigIssue::new("", format!("{e:#}"))],
+    };
+    println!("{}", serde_json::to_string_pretty(&issues)?);
+    if !issues.is_empty() {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+/// A node refuses to start on a database created from a different genesis. The database is opened
+/// as a secondary instance, so this works while the node is running.
+fn check_genesis_matches_db(config: &NodeConfig) -> Option<ConfigIssue> {
+    // A genesis that cannot be loaded has been reported already.
+    let genesis = config.genesis().ok()?;
+    let checkpoints_path = config.db_path().join("checkpoints");
+    if !checkpoints_path.exists() {
+        return None;
+    }
+    let secondary_path = match tempfile::tempdir() {
+        Ok(dir) => dir,
+        Err(e) => {
+            return Some(ConfigIssue::new(
+                "db-path",
+                format!("Unable to create a directory to open the database in: {e}"),
+            ))
+        }
+    };
+    let checkpoint_store =
+        CheckpointStore::open_secondary(&checkpoints_path, secondary_path.path());
+    let db_genesis_checkpoint = match checkpoint_store.get_checkpoint_by_sequence_number(0) {
+        Ok(checkpoint) => checkpoint?,
+        Err(e) => {
+            return Some(ConfigIssue::new(
+                "db-path",
+                format!("Unable to read the database: {e}"),
+            ))
+        }
+    };
+    let genesis_digest = genesis.checkpoint().digest();
+    (db_genesis_checkpoint.digest() != genesis_digest).then(|| {
+        ConfigIssue::new(
+            "genesis",
+            format!(
+                "The database was created from a genesis with checkpoint {}, not {}",
+                db_genesis_checkpoint.digest(),
+                genesis_digest,
+            ),
+        )
+    })
+}
+
+#[cfg(unix)]
+async fn wait_for_shutdown_signal() {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    let mut sigterm = signal(SignalKind::terminate()).expect("Cannot listen for SIGTERM");
+    tokio::select! {
+        _ = sigterm.recv() => info!("Received SIGTERM"),
+        _ = tokio::signal::ctrl_c() => info!("Received SIGINT"),
+    }
+}
+
+#[cfg(not(unix))]
+async fn wait_for_shutdown_signal() {
+    let _ = tokio::signal::ctrl_c().await;
+    info!("Received ctrl-c");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::run_until_stopped;
+    use std::future::pending;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::time::Duration;
+
+    async fn shutdown(shut_down: &AtomicBool) {
+        // Shutting down takes a while, the caller must wait for it.
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        shut_down.store(true, Ordering::SeqCst);
+    }
+
+    #[tokio::test]
+    async fn test_restart_after_protocol_key_rotation() {
+        let shut_down = AtomicBool::new(false);
+        let restart = run_until_stopped(pending(), async {}, shutdown(&shut_down)).await;
+        assert!(restart);
+        assert!(shut_down.load(Ordering::SeqCst));
+    }
 
-    let _node = sui_node::SuiNode::start(&config, registry_service).await?;
-    // TODO: Do we want to provide a way for the node to gracefully shutdown?
-    loop {
-        tokio::time::sleep(Duration::from_secs(1000)).await;
+    #[tokio::test]
+    async fn test_no_restart_after_shutdown_signal() {
+        let shut_down = AtomicBool::new(false);
+        let restart = run_until_stopped(async {}, pending(), shutdown(&shut_down)).await;
+        assert!(!restart);
+        assert!(shut_down.load(Ordering::SeqCst));
     }
 }
@@ -10,6 +10,7 @@ use prometheus::{
 use std::net::SocketAddr;
 use std::time::Duration;
 use sui_network::tonic::Code;
+use sui_protocol_config::SupportedProtocolVersions;
 
 use mysten_metrics::RegistryService;
 use tracing::warn;
@@ -158,6 +159,37 @@ pub fn start_metrics_push_task(config: &sui_config::NodeConfig, registry: Regist
     });
 }
 
+/// Registers a `build_info` gauge that is always 1, with the build of the binary and the range of
+/// protocol versions it supports as labels, so that the versions running across a fleet can be
+/// inventoried ahead of a protocol upgrade.
+pub fn register_build_info_metric(
+    registry: &Registry,
+    version: &str,
+    git_revision: &str,
+    supported_protocol_versions: SupportedProtocolVersions,
+) {
+    let build_info = register_int_gauge_vec_with_registry!(
+        "build_info",
+        "Build of the node binary and the protocol versions it supports",
+        &[
+            "version",
+            "git_sha",
+            "min_supported_protocol_version",
+            "max_supported_protocol_version"
+        ],
+        registry,
+    )
+    .unwrap();
+    build_info
+        .with_label_values(&[
+            version,
+            git_revision,
+            &supported_protocol_versions.min.as_u64().to_string(),
+            &supported_protocol_versions.max.as_u64().to_string(),
+        ])
+        .set(1);
+}
+
 #[derive(Clone)]
 pub struct GrpcMetrics {
     inflight_grpc: IntGaugeVec,
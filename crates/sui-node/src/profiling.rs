@@ -0,0 +1,97 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! CPU and heap profiles of the running node, captured on demand through the admin interface.
+//! Both profilers are process wide, so callers must not capture more than one profile at a time.
+//! The functions block for the whole duration of the profile.
+
+use anyhow::Result;
+use std::time::Duration;
+
+/// Samples the stacks of every thread for `duration`, returning the result as a flamegraph SVG.
+#[cfg(not(target_env = "msvc"))]
+pub fn cpu_profile(duration: Duration) -> Result<Vec<u8>> {
+    // 99Hz rather than 100Hz, so that sampling does not run in lockstep with periodic tasks.
+    let guard = pprof::ProfilerGuardBuilder::default()
+        .frequency(99)
+        .blocklist(&["libc", "libgcc", "pthread", "vdso"])
+        .build()?;
+    std::thread::sleep(duration);
+    let report = guard.report().build()?;
+    let mut svg = vec![];
+    report.flamegraph(&mut svg)?;
+    Ok(svg)
+}
+
+/// Records the allocations sampled by jemalloc for `duration`, returning a heap profile of those
+/// still live at the end, in the format read by `jeprof`.
+///
+/// Requires the node to be started with `_RJEM_MALLOC_CONF=prof:true`.
+#[cfg(not(target_env = "msvc"))]
+pub fn heap_profile(duration: Duration) -> Result<Vec<u8>> {
+    use anyhow::anyhow;
+    use jemalloc_ctl::raw;
+    use std::ffi::CString;
+
+    // SAFETY: the names and value types passed to `raw` below are the ones documented by
+    // jemalloc for these mallctl entries.
+    let enabled: bool = unsafe { raw::read(b"opt.prof\0") }?;
+    if !enabled {
+        return Err(anyhow!(
+            "Heap profiling is not enabled, start the node with _RJEM_MALLOC_CONF=prof:true"
+        ));
+    }
+    let was_active: bool = unsafe { raw::read(b"prof.active\0") }?;
+    let lg_sample: usize = unsafe { raw::read(b"prof.lg_sample\0") }?;
+    // Drop the samples taken so far, so that the profile only covers `duration`.
+    unsafe { raw::write(b"prof.reset\0", lg_sample) }?;
+    unsafe { raw::write(b"prof.active\0", true) }?;
+    std::thread::sleep(duration);
+    unsafe { raw::write(b"prof.active\0", was_active) }?;
+
+    let path = std::env::temp_dir().join(format!("sui-node-{}.heap", std::process::id()));
+    let c_path = CString::new(path.to_string_lossy().as_bytes())?;
+    unsafe { raw::write(b"prof.dump\0", c_path.as_ptr()) }?;
+    let profile = std::fs::read(&path);
+    let _ = std::fs::remove_file(&path);
+    Ok(profile?)
+}
+
+#[cfg(target_env = "msvc")]
+pub fn cpu_profile(_duration: Duration) -> Result<Vec<u8>> {
+    Err(anyhow::anyhow!(
+        "CPU profiling is not supported on this platform"
+    ))
+}
+
+#[cfg(target_env = "msvc")]
+pub fn heap_profile(_duration: Duration) -> Result<Vec<u8>> {
+    Err(anyhow::anyhow!(
+        "Heap profiling is not supported on this platform"
+    ))
+}
+
+#[cfg(all(test, not(target_env = "msvc")))]
+mod tests {
+    use super::{cpu_profile, heap_profile};
+    use std::time::Duration;
+
+    #[test]
+    fn test_cpu_profile() {
+        let svg = cpu_profile(Duration::from_millis(100)).unwrap();
+        assert!(String::from_utf8_lossy(&svg).contains("<svg"));
+
+        // The profiler is stopped once the profile is taken, so another one can be started.
+        assert!(cpu_profile(Duration::from_millis(100)).is_ok());
+    }
+
+    #[test]
+    fn test_heap_profile_requires_prof() {
+        // Unit tests do not run with `_RJEM_MALLOC_CONF=prof:true`.
+        if std::env::var_os("_RJEM_MALLOC_CONF").is_some() {
+            return;
+        }
+        let err = heap_profile(Duration::from_millis(100)).unwrap_err();
+        assert!(err.to_string().contains("_RJEM_MALLOC_CONF=prof:true"));
+    }
+}
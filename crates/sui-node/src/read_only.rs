@@ -0,0 +1,250 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! A node that serves JSON-RPC reads from the databases of another node, opened as RocksDB
+//! secondary instances. It never writes to the databases, executes nothing and does not take
+//! part in state sync, so any number of them can share the database volume of a single synced
+//! fullnode, or inspect a copy of a database.
+
+use crate::{build_server, RpcServices};
+use anyhow::{anyhow, Result};
+use mysten_metrics::{spawn_monitored_task, RegistryService};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use sui_config::node::{AuthorityStorePruningConfig, StateSnapshotConfig};
+use sui_config::NodeConfig;
+use sui_core::authority::authority_per_epoch_store::{
+    AuthorityEpochTables, AuthorityPerEpochStore,
+};
+use sui_core::authority::{AuthorityState, AuthorityStore};
+use sui_core::checkpoints::CheckpointStore;
+use sui_core::epoch::committee_store::CommitteeStore;
+use sui_core::epoch::epoch_metrics::EpochMetrics;
+use sui_core::module_cache_metrics::ResolverMetrics;
+use sui_json_rpc::RateLimitHandle;
+use sui_storage::IndexStore;
+use sui_types::crypto::KeypairTraits;
+use sui_types::error::SuiResult;
+use tempfile::TempDir;
+use tracing::{info, warn};
+use typed_store::rocks::set_db_statistics_enabled;
+use typed_store::rocks::table_options::TableOptionsOverrides;
+use typed_store::DBMetrics;
+
+pub struct ReadOnlyNode {
+    config: NodeConfig,
+    state: Arc<AuthorityState>,
+    committee_store: Arc<CommitteeStore>,
+    checkpoint_store: Arc<CheckpointStore>,
+    index_store: Option<Arc<IndexStore>>,
+    epoch_metrics: Arc<EpochMetrics>,
+    cache_metrics: Arc<ResolverMetrics>,
+    secondary_db_path: PathBuf,
+    _rpc_services: Option<RpcServices>,
+    // Removes the files of the secondary instances when the node is dropped, if no
+    // secondary-db-path is configured.
+    _secondary_tempdir: Option<TempDir>,
+}
+
+impl ReadOnlyNode {
+    pub async fn start(
+        config: &NodeConfig,
+        registry_service: RegistryService,
+    ) -> Result<Arc<ReadOnlyNode>> {
+        let config = config.clone();
+        let read_only_config = config
+            .read_only_config
+            .clone()
+            .ok_or_else(|| anyhow!("read-only-config is not set"))?;
+        let prometheus_registry = registry_service.default_registry();
+
+        info!(
+            db_path = ?config.db_path(),
+            "Opening databases read-only"
+        );
+
+        DBMetrics::init(&prometheus_registry);
+        mysten_metrics::init_metrics(&prometheus_registry);
+        if let Some(db_options_path) = &config.db_options_path {
+            TableOptionsOverrides::load(db_options_path)
+                .map_err(|e| anyhow!("{e}"))?
+                .install();
+        }
+        set_db_statistics_enabled(config.enable_db_statistics);
+
+        let db_path = config.db_path();
+        let (secondary_db_path, secondary_tempdir) = match &read_only_config.secondary_db_path {
+            Some(path) => (path.clone(), None),
+            None => {
+                let tempdir = tempfile::tempdir()?;
+                (tempdir.path().to_path_buf(), Some(tempdir))
+            }
+        };
+        let secondary_path = |name: &str| secondary_db_path.join(name);
+
+        let committee_store = Arc::new(CommitteeStore::open_secondary(
+            db_path.join("epochs"),
+            secondary_path("epochs"),
+        ));
+        let store = Arc::new(AuthorityStore::open_secondary(
+            &db_path.join("store"),
+            &secondary_path("store"),
+        )?);
+        let epoch_metrics = EpochMetrics::new(&prometheus_registry);
+        let cache_metrics = Arc::new(ResolverMetrics::new(&prometheus_registry));
+        let epoch_store = Self::open_epoch_store(
+            &config,
+            &secondary_db_path,
+            &store,
+            &committee_store,
+            epoch_metrics.clone(),
+            cache_metrics.clone(),
+        )?
+        .ok_or_else(|| anyhow!("The current epoch is missing from the database"))?;
+        let checkpoint_store = CheckpointStore::open_secondary(
+            &db_path.join("checkpoints"),
+            &secondary_path("checkpoints"),
+        );
+        // Validators do not keep indexes.
+        let index_store = db_path.join("indexes").exists().then(|| {
+            Arc::new(IndexStore::open_secondary(
+                db_path.join("indexes"),
+                secondary_path("indexes"),
+            ))
+        });
+
+        let secret = Arc::pin(config.protocol_key_pair().copy());
+        let state = AuthorityState::new(
+            config.protocol_public_key(),
+            secret,
+            config.supported_protocol_versions.unwrap(),
+            store,
+            epoch_store,
+            committee_store.clone(),
+            index_store.clone(),
+            // Opening the SQLite event store sets up its schema and journal, which are writes.
+            None,
+            checkpoint_store.clone(),
+            &prometheus_registry,
+            // Pruning is left to the node that owns the databases.
+            AuthorityStorePruningConfig {
+                num_latest_epoch_dbs_to_retain: usize::MAX,
+                num_epochs_to_retain: u64::MAX,
+                num_epochs_to_retain_for_transactions: None,
+                num_epochs_to_retain_for_checkpoints: None,
+                ..config.pruning
+            },
+            config.epoch_duration_ms,
+            &StateSnapshotConfig::default(),
+        )
+        .await;
+
+        let rpc_services = build_server(
+            state.clone(),
+            &None,
+            &config,
+            RateLimitHandle::new(config.rpc_rate_limit_config.clone()),
+            &prometheus_registry,
+        )
+        .await?;
+
+        let node = Arc::new(Self {
+            config,
+            state,
+            committee_store,
+            checkpoint_store,
+            index_store,
+            epoch_metrics,
+            cache_metrics,
+            secondary_db_path,
+            _rpc_services: rpc_services,
+            _secondary_tempdir: secondary_tempdir,
+        });
+
+        if read_only_config.catch_up_interval_ms > 0 {
+            let interval = Duration::from_millis(read_only_config.catch_up_interval_ms);
+            let node = node.clone();
+            spawn_monitored_task!(async move { node.catch_up_with_primary(interval).await });
+        }
+
+        info!("Read-only SuiNode started!");
+        Ok(node)
+    }
+
+    pub fn state(&self) -> Arc<AuthorityState> {
+        self.state.clone()
+    }
+
+    /// Opens the epoch store of the epoch the primary is currently in, or returns `None` if the
+    /// primary is still in the middle of creating it.
+    fn open_epoch_store(
+        config: &NodeConfig,
+        secondary_db_path: &Path,
+        store: &Arc<AuthorityStore>,
+        committee_store: &CommitteeStore,
+        epoch_metrics: Arc<EpochMetrics>,
+        cache_metrics: Arc<ResolverMetrics>,
+    ) -> SuiResult<Option<Arc<AuthorityPerEpochStore>>> {
+        let parent_path = config.db_path().join("store");
+        let Some(epoch_start_configuration) = store.get_epoch_start_configuration()? else {
+            return Ok(None);
+        };
+        let epoch = epoch_start_configuration.epoch();
+        let Some(committee) = committee_store.get_committee(&epoch)? else {
+            return Ok(None);
+        };
+        if !AuthorityEpochTables::path(epoch, &parent_path).exists() {
+            return Ok(None);
+        }
+        Ok(Some(AuthorityPerEpochStore::new_secondary(
+            config.protocol_public_key(),
+            committee,
+            &parent_path,
+            &secondary_db_path.join("store"),
+            epoch_metrics,
+            epoch_start_configuration,
+            store.clone(),
+            cache_metrics,
+        )))
+    }
+
+    async fn catch_up_with_primary(self: Arc<Self>, interval: Duration) {
+        let mut interval = tokio::time::interval(interval);
+        loop {
+            interval.tick().await;
+            if let Err(e) = self.try_catch_up_with_primary().await {
+                warn!("Failed to catch up with the primary databases: {e}");
+            }
+        }
+    }
+
+    async fn try_catch_up_with_primary(&self) -> SuiResult {
+        let store = self.state.db();
+        store.try_catch_up_with_primary()?;
+        self.committee_store.try_catch_up_with_primary()?;
+        self.checkpoint_store.try_catch_up_with_primary()?;
+        if let Some(index_store) = &self.index_store {
+            index_store.try_catch_up_with_primary()?;
+        }
+
+        let epoch_store = self.state.load_epoch_store_one_call_per_task().clone();
+        if store.get_recovery_epoch_at_restart()? == epoch_store.epoch() {
+            return epoch_store.try_catch_up_with_primary();
+        }
+        // The primary has moved on to a new epoch.
+        if let Some(new_epoch_store) = Self::open_epoch_store(
+            &self.config,
+            &self.secondary_db_path,
+            &store,
+            &self.committee_store,
+            self.epoch_metrics.clone(),
+            self.cache_metrics.clone(),
+        )? {
+            self.state
+                .switch_secondary_epoch_store(new_epoch_store)
+                .await;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,111 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Dedicated tokio runtimes for the subsystems of a node, so that e.g. a burst of JSON-RPC
+//! requests cannot take the worker threads consensus needs. Subsystems without a dedicated
+//! runtime run on the runtime the node was started on.
+
+use anyhow::{anyhow, Result};
+use futures::future::poll_fn;
+use futures::pin_mut;
+use std::future::Future;
+use sui_config::node::RuntimeConfig;
+use tokio::runtime::{Builder, EnterGuard, Handle, Runtime};
+
+#[derive(Default)]
+pub struct SuiRuntimes {
+    consensus: Option<Runtime>,
+    execution: Option<Runtime>,
+    json_rpc: Option<Runtime>,
+    p2p: Option<Runtime>,
+}
+
+impl SuiRuntimes {
+    pub fn new(config: &RuntimeConfig) -> Result<Self> {
+        // The simulator runs every node on a single deterministic runtime.
+        if cfg!(msim) {
+            return Ok(Self::default());
+        }
+        Ok(Self {
+            consensus: build_runtime("consensus", config.consensus_worker_threads)?,
+            execution: build_runtime("execution", config.execution_worker_threads)?,
+            json_rpc: build_runtime("json-rpc", config.json_rpc_worker_threads)?,
+            p2p: build_runtime("p2p", config.p2p_worker_threads)?,
+        })
+    }
+
+    /// Runs Narwhal primary and workers.
+    pub fn consensus(&self) -> Option<&Handle> {
+        self.consensus.as_ref().map(Runtime::handle)
+    }
+
+    /// Runs certificate and checkpoint execution, checkpoint building and reconfiguration.
+    pub fn execution(&self) -> Option<&Handle> {
+        self.execution.as_ref().map(Runtime::handle)
+    }
+
+    /// Runs the JSON-RPC server and the handling of its requests.
+    pub fn json_rpc(&self) -> Option<&Handle> {
+        self.json_rpc.as_ref().map(Runtime::handle)
+    }
+
+    /// Runs the anemo network, discovery and state sync.
+    pub fn p2p(&self) -> Option<&Handle> {
+        self.p2p.as_ref().map(Runtime::handle)
+    }
+}
+
+impl Drop for SuiRuntimes {
+    fn drop(&mut self) {
+        // A runtime cannot be dropped from within an async context, which is where nodes are
+        // usually dropped.
+        for runtime in [
+            self.consensus.take(),
+            self.execution.take(),
+            self.json_rpc.take(),
+            self.p2p.take(),
+        ]
+        .into_iter()
+        .flatten()
+        {
+            runtime.shutdown_background();
+        }
+    }
+}
+
+fn build_runtime(name: &str, worker_threads: Option<usize>) -> Result<Option<Runtime>> {
+    let Some(worker_threads) = worker_threads else {
+        return Ok(None);
+    };
+    if worker_threads == 0 {
+        return Err(anyhow!(
+            "The {name} runtime needs at least one worker thread"
+        ));
+    }
+    let runtime = Builder::new_multi_thread()
+        .worker_threads(worker_threads)
+        .thread_name(format!("sui-{name}"))
+        .enable_all()
+        .build()?;
+    Ok(Some(runtime))
+}
+
+/// Makes tasks spawned by the calling thread go to the runtime of `handle` until the guard is
+/// dropped. The guard must not be held across an await point, use [`run_in`] for that.
+pub fn enter(handle: Option<&Handle>) -> Option<EnterGuard<'_>> {
+    handle.map(Handle::enter)
+}
+
+/// Drives `future` to completion, with every task it spawns (as well as its timers and IO
+/// resources) going to the runtime of `handle`.
+pub async fn run_in<F: Future>(handle: Option<&Handle>, future: F) -> F::Output {
+    let Some(handle) = handle else {
+        return future.await;
+    };
+    pin_mut!(future);
+    poll_fn(|cx| {
+        let _guard = handle.enter();
+        future.as_mut().poll(cx)
+    })
+    .await
+}
@@ -0,0 +1,191 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Detection of synchronous work blocking the async runtimes of the node, e.g. a database call or
+//! a lock taken without `spawn_blocking`. Such work goes unnoticed until enough of it piles up to
+//! stall the node, so it is reported as soon as it happens, in two ways:
+//!
+//! - Monitored tasks time each of their polls, and report the polls that take longer than the
+//!   slow poll threshold along with the place the task was spawned at.
+//! - A thread outside of the runtimes periodically spawns an empty task on each of them, and
+//!   reports the runtimes that do not get to run it in time, as all their worker threads are
+//!   blocked.
+
+use prometheus::{
+    register_histogram_vec_with_registry, register_int_counter_vec_with_registry, HistogramVec,
+    IntCounterVec, Registry,
+};
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+use sui_config::node::WatchdogConfig;
+use tokio::runtime::Handle;
+use tracing::{error, warn};
+
+#[derive(Clone)]
+struct WatchdogMetrics {
+    runtime_probe_latency: HistogramVec,
+    blocked_runtimes: IntCounterVec,
+}
+
+impl WatchdogMetrics {
+    fn new(registry: &Registry) -> Self {
+        Self {
+            runtime_probe_latency: register_histogram_vec_with_registry!(
+                "watchdog_runtime_probe_latency",
+                "Time between spawning an empty task on a runtime and the task running, in seconds",
+                &["runtime"],
+                vec![0.0001, 0.0005, 0.001, 0.005, 0.01, 0.05, 0.1, 0.5, 1.0, 5.0],
+                registry,
+            )
+            .unwrap(),
+            blocked_runtimes: register_int_counter_vec_with_registry!(
+                "watchdog_blocked_runtimes",
+                "Number of times all the worker threads of a runtime were found blocked",
+                &["runtime"],
+                registry,
+            )
+            .unwrap(),
+        }
+    }
+}
+
+/// Starts watching `runtimes`, each given with the name it is reported under. The watch of a
+/// runtime stops once it is shut down.
+pub fn start_watchdog(
+    config: &WatchdogConfig,
+    runtimes: Vec<(&'static str, Handle)>,
+    registry: &Registry,
+) {
+    mysten_metrics::set_slow_poll_threshold(Duration::from_millis(config.slow_poll_threshold_ms));
+    // Time does not pass in the simulator while a task runs, so runtimes are never blocked.
+    if cfg!(msim) {
+        return;
+    }
+    let metrics = WatchdogMetrics::new(registry);
+    let probe_interval = Duration::from_millis(config.probe_interval_ms);
+    let threshold = Duration::from_millis(config.blocked_runtime_threshold_ms);
+    for (name, handle) in runtimes {
+        let metrics = metrics.clone();
+        let result = std::thread::Builder::new()
+            .name(format!("watchdog-{name}"))
+            .spawn(move || watch_runtime(name, handle, probe_interval, threshold, metrics));
+        if let Err(e) = result {
+            error!("Failed to start the watchdog of the {name} runtime: {e}");
+        }
+    }
+}
+
+fn watch_runtime(
+    name: &'static str,
+    handle: Handle,
+    probe_interval: Duration,
+    threshold: Duration,
+    metrics: WatchdogMetrics,
+) {
+    loop {
+        std::thread::sleep(probe_interval);
+        let (tx, rx) = mpsc::sync_channel(1);
+        let start = Instant::now();
+        handle.spawn(async move {
+            let _ = tx.send(());
+        });
+        match rx.recv_timeout(threshold) {
+            Ok(()) => (),
+            Err(mpsc::RecvTimeoutError::Timeout) => {
+                metrics.blocked_runtimes.with_label_values(&[name]).inc();
+                warn!(
+                    runtime = name,
+                    "All worker threads of the {name} runtime have been blocked for over {threshold:?}"
+                );
+                // Wait for the runtime to recover, so that a single stall is reported once.
+                if rx.recv().is_err() {
+                    return;
+                }
+                warn!(
+                    runtime = name,
+                    "The {name} runtime was blocked for {:?}",
+                    start.elapsed()
+                );
+            }
+            // The runtime was shut down, dropping the probe without running it.
+            Err(mpsc::RecvTimeoutError::Disconnected) => return,
+        }
+        metrics
+            .runtime_probe_latency
+            .with_label_values(&[name])
+            .observe(start.elapsed().as_secs_f64());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    fn start_watch(
+        runtime: &tokio::runtime::Runtime,
+        metrics: &WatchdogMetrics,
+    ) -> thread::JoinHandle<()> {
+        let handle = runtime.handle().clone();
+        let metrics = metrics.clone();
+        thread::spawn(move || {
+            watch_runtime(
+                "test",
+                handle,
+                Duration::from_millis(10),
+                Duration::from_millis(50),
+                metrics,
+            )
+        })
+    }
+
+    fn single_worker_runtime() -> tokio::runtime::Runtime {
+        tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(1)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_blocked_runtime_is_reported_once() {
+        let metrics = WatchdogMetrics::new(&Registry::new());
+        let runtime = single_worker_runtime();
+        runtime.spawn(async { thread::sleep(Duration::from_millis(300)) });
+        let watch = start_watch(&runtime, &metrics);
+
+        thread::sleep(Duration::from_millis(500));
+        // The watch stops once the runtime is shut down.
+        drop(runtime);
+        watch.join().unwrap();
+
+        assert_eq!(
+            metrics.blocked_runtimes.with_label_values(&["test"]).get(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_responsive_runtime_is_not_reported() {
+        let metrics = WatchdogMetrics::new(&Registry::new());
+        let runtime = single_worker_runtime();
+        // Polls shorter than the threshold do not make the runtime blocked.
+        runtime.spawn(async { thread::sleep(Duration::from_millis(20)) });
+        let watch = start_watch(&runtime, &metrics);
+
+        thread::sleep(Duration::from_millis(200));
+        drop(runtime);
+        watch.join().unwrap();
+
+        assert_eq!(
+            metrics.blocked_runtimes.with_label_values(&["test"]).get(),
+            0
+        );
+        assert!(
+            metrics
+                .runtime_probe_latency
+                .with_label_values(&["test"])
+                .get_sample_count()
+                > 0
+        );
+    }
+}
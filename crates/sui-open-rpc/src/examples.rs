@@ -360,7 +360,11 @@ impl RpcExampleProvider {
         let mut data = self.get_transaction_digests(5..9);
         let next_cursor = data.pop();
 
-        let result = TransactionsPage { data, next_cursor };
+        let result = TransactionsPage {
+            data,
+            next_cursor,
+            has_next_page: true,
+        };
         Examples::new(
             "sui_getTransactions",
             vec![ExamplePairing::new(
@@ -491,6 +495,7 @@ impl RpcExampleProvider {
         let page = EventPage {
             data: events.clone(),
             next_cursor: Some((tx_dig, 5).into()),
+            has_next_page: true,
         };
         Examples::new(
             "sui_getEvents",
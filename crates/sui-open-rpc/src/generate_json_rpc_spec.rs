@@ -12,6 +12,7 @@ use sui_core::SUI_CORE_VERSION;
 use sui_json_rpc::coin_api::CoinReadApi;
 use sui_json_rpc::event_api::EventReadApi;
 use sui_json_rpc::governance_api::GovernanceReadApi;
+use sui_json_rpc::name_service_api::NameServiceApi;
 use sui_json_rpc::read_api::ReadApi;
 use sui_json_rpc::sui_rpc_doc;
 use sui_json_rpc::threshold_bls_api::ThresholdBlsApi;
@@ -54,6 +55,7 @@ async fn main() {
     open_rpc.add_module(TransactionBuilderApi::rpc_doc_module());
     open_rpc.add_module(GovernanceReadApi::rpc_doc_module());
     open_rpc.add_module(ThresholdBlsApi::rpc_doc_module());
+    open_rpc.add_module(NameServiceApi::rpc_doc_module());
 
     open_rpc.add_examples(RpcExampleProvider::new().examples());
 
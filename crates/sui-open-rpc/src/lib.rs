@@ -4,7 +4,7 @@
 extern crate core;
 
 use std::collections::btree_map::Entry::Occupied;
-use std::collections::{BTreeMap, HashMap};
+use std::collections::{BTreeMap, HashMap, HashSet};
 
 use schemars::gen::{SchemaGenerator, SchemaSettings};
 use schemars::schema::SchemaObject;
@@ -77,6 +77,23 @@ impl Project {
         self.method_routing.extend(module.method_routing);
     }
 
+    /// The names of the methods marked deprecated, which are to be removed in a later version.
+    pub fn deprecated_methods(&self) -> HashSet<String> {
+        self.methods
+            .iter()
+            .filter(|method| method.deprecated)
+            .map(|method| method.name.clone())
+            .collect()
+    }
+
+    /// The params of each method, in order.
+    pub fn method_params(&self) -> HashMap<String, Vec<ContentDescriptor>> {
+        self.methods
+            .iter()
+            .map(|method| (method.name.clone(), method.params.clone()))
+            .collect()
+    }
+
     pub fn add_examples(&mut self, mut example_provider: BTreeMap<String, Vec<ExamplePairing>>) {
         for method in &mut self.methods {
             if let Occupied(entry) = example_provider.entry(method.name.clone()) {
@@ -133,6 +150,20 @@ pub struct ContentDescriptor {
     deprecated: bool,
 }
 
+impl ContentDescriptor {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn required(&self) -> bool {
+        self.required
+    }
+
+    pub fn schema(&self) -> &SchemaObject {
+        &self.schema
+    }
+}
+
 #[derive(Serialize, Deserialize, Default, Clone)]
 struct Method {
     name: String,
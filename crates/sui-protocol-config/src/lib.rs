@@ -10,11 +10,14 @@ use tracing::{info, warn};
 
 /// The minimum and maximum protocol versions supported by this build.
 const MIN_PROTOCOL_VERSION: u64 = 1;
-const MAX_PROTOCOL_VERSION: u64 = 1;
+const MAX_PROTOCOL_VERSION: u64 = 4;
 
 // Record history of protocol version allocations here:
 //
 // Version 1: Original version.
+// Version 2: Checkpoints commit to the Merkle root of their transactions.
+// Version 3: Narwhal swaps low-reputation authorities out of its leader schedule.
+// Version 4: The last checkpoint of each epoch commits to the live object set.
 
 #[derive(
     Copy, Clone, Debug, Hash, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, JsonSchema,
@@ -113,6 +116,27 @@ pub struct Error(pub String);
 struct FeatureFlags {
     // Add feature flags here, e.g.:
     // new_protocol_feature: bool,
+
+    // If true, checkpoints commit to the Merkle root of their transactions, which light clients
+    // verify transactions against without the whole contents of the checkpoint.
+    #[serde(skip_serializing_if = "is_false")]
+    commit_to_transactions_merkle_root: bool,
+
+    // If true, narwhal swaps the authorities with the lowest reputation scores out of its leader
+    // schedule, for the ones with the highest, at every schedule change.
+    #[serde(skip_serializing_if = "is_false")]
+    narwhal_reputation_based_leader_schedule: bool,
+
+    // If true, the last checkpoint of each epoch commits to the root state digest, the ECMH of the
+    // live object set at the end of the epoch, which formal snapshots are verified against.
+    #[serde(skip_serializing_if = "is_false")]
+    commit_root_state_digest: bool,
+}
+
+// Keeps the flags that are off out of the snapshots, so that adding a flag does not change the
+// snapshots of the versions before it.
+fn is_false(b: &bool) -> bool {
+    !b
 }
 
 /// Constants that change the behavior of the protocol.
@@ -365,6 +389,18 @@ impl ProtocolConfig {
     //         )))
     //     }
     // }
+
+    pub fn commit_to_transactions_merkle_root(&self) -> bool {
+        self.feature_flags.commit_to_transactions_merkle_root
+    }
+
+    pub fn narwhal_reputation_based_leader_schedule(&self) -> bool {
+        self.feature_flags.narwhal_reputation_based_leader_schedule
+    }
+
+    pub fn commit_root_state_digest(&self) -> bool {
+        self.feature_flags.commit_root_state_digest
+    }
 }
 
 // getters
@@ -753,6 +789,31 @@ impl ProtocolConfig {
                 // new_constant: None,
             },
 
+            2 => {
+                let mut cfg = Self {
+                    version,
+                    ..Self::get_for_version_impl(version - 1)
+                };
+                cfg.feature_flags.commit_to_transactions_merkle_root = true;
+                cfg
+            }
+            3 => {
+                let mut cfg = Self {
+                    version,
+                    ..Self::get_for_version_impl(version - 1)
+                };
+                cfg.feature_flags.narwhal_reputation_based_leader_schedule = true;
+                cfg
+            }
+            4 => {
+                let mut cfg = Self {
+                    version,
+                    ..Self::get_for_version_impl(version - 1)
+                };
+                cfg.feature_flags.commit_root_state_digest = true;
+                cfg
+            }
+
             // Use this template when making changes:
             //
             // NEW_VERSION => Self {
@@ -796,6 +857,15 @@ impl ProtocolConfig {
     pub fn set_buffer_stake_for_protocol_upgrade_bps_for_testing(&mut self, b: u64) {
         self.buffer_stake_for_protocol_upgrade_bps = Some(b)
     }
+    pub fn set_commit_to_transactions_merkle_root_for_testing(&mut self, val: bool) {
+        self.feature_flags.commit_to_transactions_merkle_root = val
+    }
+    pub fn set_narwhal_reputation_based_leader_schedule_for_testing(&mut self, val: bool) {
+        self.feature_flags.narwhal_reputation_based_leader_schedule = val
+    }
+    pub fn set_commit_root_state_digest_for_testing(&mut self, val: bool) {
+        self.feature_flags.commit_root_state_digest = val
+    }
 }
 
 type OverrideFn = dyn Fn(ProtocolVersion, ProtocolConfig) -> ProtocolConfig + Send;
@@ -0,0 +1,133 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! A relay for the metrics of validators. Validators send their metrics over mutual TLS,
+//! authenticated with their network keys, either as Prometheus remote-write requests to
+//! `/api/v1/write` or as `MetricFamily`s encoded by [`prometheus::ProtobufEncoder`] to
+//! `/api/v1/push`. The series are labelled with the name of the validator and forwarded with
+//! remote-write to a central store.
+
+pub mod peers;
+pub mod remote_write;
+
+use anyhow::{anyhow, Result};
+use axum::body::Bytes;
+use axum::http::StatusCode;
+use axum::routing::{get, post};
+use axum::{Extension, Router};
+use peers::ValidatorPeers;
+use remote_write::WriteRequest;
+use std::sync::Arc;
+use std::time::Duration;
+use sui_tls::TlsConnectionInfo;
+use tracing::warn;
+
+/// Where the series are forwarded to.
+pub struct RemoteWriteClient {
+    client: reqwest::Client,
+    url: String,
+    /// Basic auth credentials, as username and password.
+    credentials: Option<(String, String)>,
+}
+
+impl RemoteWriteClient {
+    pub fn new(
+        url: String,
+        credentials: Option<(String, String)>,
+        request_timeout: Duration,
+    ) -> Result<Self> {
+        Ok(Self {
+            client: reqwest::Client::builder()
+                .timeout(request_timeout)
+                .build()?,
+            url,
+            credentials,
+        })
+    }
+
+    pub async fn write(&self, request: &WriteRequest) -> Result<()> {
+        let mut builder = self
+            .client
+            .post(&self.url)
+            .header(reqwest::header::CONTENT_ENCODING, "snappy")
+            .header(reqwest::header::CONTENT_TYPE, "application/x-protobuf")
+            .header("X-Prometheus-Remote-Write-Version", "0.1.0")
+            .body(request.encode_snappy()?);
+        if let Some((username, password)) = &self.credentials {
+            builder = builder.basic_auth(username, Some(password));
+        }
+        let response = builder.send().await?;
+        if !response.status().is_success() {
+            return Err(anyhow!(
+                "The remote store answered {}: {}",
+                response.status(),
+                response.text().await.unwrap_or_default()
+            ));
+        }
+        Ok(())
+    }
+}
+
+pub struct ProxyState {
+    pub peers: ValidatorPeers,
+    pub remote_write: RemoteWriteClient,
+}
+
+pub fn app(state: Arc<ProxyState>) -> Router {
+    Router::new()
+        .route("/", get(health))
+        .route("/api/v1/write", post(write))
+        .route("/api/v1/push", post(push))
+        .layer(Extension(state))
+}
+
+async fn health() -> &'static str {
+    "OK"
+}
+
+/// Forwards a remote-write request.
+async fn write(
+    Extension(state): Extension<Arc<ProxyState>>,
+    Extension(tls_info): Extension<TlsConnectionInfo>,
+    body: Bytes,
+) -> (StatusCode, String) {
+    forward(&state, &tls_info, WriteRequest::decode_snappy(&body)).await
+}
+
+/// Forwards pushed metric families.
+async fn push(
+    Extension(state): Extension<Arc<ProxyState>>,
+    Extension(tls_info): Extension<TlsConnectionInfo>,
+    body: Bytes,
+) -> (StatusCode, String) {
+    forward(&state, &tls_info, WriteRequest::from_pushed_metrics(&body)).await
+}
+
+async fn forward(
+    state: &ProxyState,
+    tls_info: &TlsConnectionInfo,
+    request: Result<WriteRequest>,
+) -> (StatusCode, String) {
+    // The TLS handshake only accepts the validators, but they may have left the committee since.
+    let Some(validator) = tls_info
+        .public_key()
+        .and_then(|network_key| state.peers.name_of(network_key))
+    else {
+        return (StatusCode::FORBIDDEN, "Unknown validator".to_string());
+    };
+    let mut request = match request {
+        Ok(request) => request,
+        Err(e) => return (StatusCode::BAD_REQUEST, format!("Invalid metrics: {e:#}")),
+    };
+    request.label_validator(&validator);
+    match state.remote_write.write(&request).await {
+        Ok(()) => (StatusCode::ACCEPTED, String::new()),
+        Err(e) => {
+            warn!(validator, "Failed to forward metrics: {e:#}");
+            (
+                StatusCode::BAD_GATEWAY,
+                "Failed to forward metrics".to_string(),
+            )
+        }
+    }
+}
@@ -0,0 +1,109 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{anyhow, Result};
+use clap::Parser;
+use fastcrypto::traits::KeyPair;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use sui_keys::keypair_file::read_network_keypair_from_file;
+use sui_proxy::peers::ValidatorPeers;
+use sui_proxy::{app, ProxyState, RemoteWriteClient};
+use sui_sdk::SuiClientBuilder;
+use sui_tls::{SelfSignedCertificate, TlsAcceptor, ValidatorCertVerifier};
+use tracing::info;
+
+#[derive(Parser)]
+#[clap(
+    name = "sui-proxy",
+    about = "Relays the metrics of validators to a central store",
+    rename_all = "kebab-case"
+)]
+struct Args {
+    #[clap(long, default_value = "0.0.0.0:8443")]
+    listen_address: SocketAddr,
+
+    /// The Ed25519 key file that the TLS certificate of the proxy is signed with
+    #[clap(long)]
+    tls_keypair: PathBuf,
+
+    /// The name that the TLS certificate of the proxy is for
+    #[clap(long, default_value = "localhost")]
+    server_name: String,
+
+    /// The fullnode that the validators of the latest epoch are read from
+    #[clap(long)]
+    fullnode_url: String,
+
+    #[clap(long, default_value_t = 60)]
+    peer_refresh_interval_secs: u64,
+
+    /// The remote-write endpoint of the central store
+    #[clap(long)]
+    remote_write_url: String,
+
+    #[clap(long)]
+    remote_write_username: Option<String>,
+
+    /// The environment variable holding the password of the central store
+    #[clap(long)]
+    remote_write_password_env_var: Option<String>,
+
+    #[clap(long, default_value_t = 30)]
+    remote_write_timeout_secs: u64,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let _guard = telemetry_subscribers::TelemetryConfig::new()
+        .with_env()
+        .init();
+    let args = Args::parse();
+
+    let keypair = read_network_keypair_from_file(&args.tls_keypair)?;
+    let certificate = SelfSignedCertificate::new(keypair.private(), &args.server_name);
+    let (tls_config, allowlist) = ValidatorCertVerifier::rustls_server_config(
+        vec![certificate.rustls_certificate()],
+        certificate.rustls_private_key(),
+    )?;
+
+    let peers = ValidatorPeers::new(allowlist);
+    let client = SuiClientBuilder::default()
+        .build(&args.fullnode_url)
+        .await?;
+    // Validators are only accepted once they are known.
+    peers.refresh(&client).await?;
+    tokio::spawn(
+        peers
+            .clone()
+            .run(client, Duration::from_secs(args.peer_refresh_interval_secs)),
+    );
+
+    let password = args
+        .remote_write_password_env_var
+        .map(|var| {
+            std::env::var(&var).map_err(|e| anyhow!("Cannot read the password from {var}: {e}"))
+        })
+        .transpose()?;
+    let credentials = args
+        .remote_write_username
+        .map(|username| (username, password.unwrap_or_default()));
+    let remote_write = RemoteWriteClient::new(
+        args.remote_write_url,
+        credentials,
+        Duration::from_secs(args.remote_write_timeout_secs),
+    )?;
+
+    let app = app(Arc::new(ProxyState {
+        peers,
+        remote_write,
+    }));
+    info!("Listening on {}", args.listen_address);
+    axum_server::bind(args.listen_address)
+        .acceptor(TlsAcceptor::new(tls_config))
+        .serve(app.into_make_service())
+        .await?;
+    Ok(())
+}
@@ -0,0 +1,84 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! The validators allowed to send metrics: the active validators of the latest epoch, known by
+//! the network keys that their TLS certificates are signed with.
+
+use anyhow::Result;
+use fastcrypto::ed25519::Ed25519PublicKey;
+use fastcrypto::traits::ToFromBytes;
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use sui_sdk::SuiClient;
+use sui_tls::ValidatorAllowlist;
+use tracing::{info, warn};
+
+#[derive(Clone)]
+pub struct ValidatorPeers {
+    /// The keys that the TLS handshakes accept.
+    allowlist: ValidatorAllowlist,
+    /// The names of the validators, by network key.
+    names: Arc<RwLock<HashMap<Ed25519PublicKey, String>>>,
+}
+
+impl ValidatorPeers {
+    pub fn new(allowlist: ValidatorAllowlist) -> Self {
+        Self {
+            allowlist,
+            names: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    /// The name of the validator with `network_key`, if it is allowed.
+    pub fn name_of(&self, network_key: &Ed25519PublicKey) -> Option<String> {
+        self.names.read().unwrap().get(network_key).cloned()
+    }
+
+    /// Allows exactly `validators`, as network keys and names.
+    pub fn update(&self, validators: HashMap<Ed25519PublicKey, String>) {
+        let mut allowlist = self.allowlist.write().unwrap();
+        allowlist.clear();
+        allowlist.extend(validators.keys().cloned());
+        *self.names.write().unwrap() = validators;
+    }
+
+    /// Allows the active validators of the latest epoch, as read from a fullnode.
+    pub async fn refresh(&self, client: &SuiClient) -> Result<()> {
+        let system_state = client
+            .governance_api()
+            .get_latest_sui_system_state()
+            .await?;
+        let mut validators = HashMap::new();
+        for validator in system_state.active_validators {
+            match Ed25519PublicKey::from_bytes(&validator.network_pubkey_bytes) {
+                Ok(network_key) => {
+                    validators.insert(network_key, validator.name);
+                }
+                Err(e) => warn!(
+                    "Validator {} has an invalid network key: {e}",
+                    validator.name
+                ),
+            }
+        }
+        info!(
+            epoch = system_state.epoch,
+            "Allowing {} validators",
+            validators.len()
+        );
+        self.update(validators);
+        Ok(())
+    }
+
+    /// Refreshes the validators every `interval`, forever. The validators last read stay allowed
+    /// while the fullnode cannot be read.
+    pub async fn run(self, client: SuiClient, interval: Duration) {
+        let mut interval = tokio::time::interval(interval);
+        loop {
+            interval.tick().await;
+            if let Err(e) = self.refresh(&client).await {
+                warn!("Failed to read the validators: {e:#}");
+            }
+        }
+    }
+}
@@ -0,0 +1,272 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! The Prometheus remote-write protocol: snappy compressed protobuf `WriteRequest`s, see
+//! <https://prometheus.io/docs/concepts/remote_write_spec/>. Metrics pushed as protobuf
+//! `MetricFamily`s, as encoded by [`prometheus::ProtobufEncoder`], are converted to it.
+
+use anyhow::{anyhow, Result};
+use prometheus::proto::{MetricFamily, MetricType};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The label that the series are labelled with the name of the validator that sent them.
+pub const VALIDATOR_LABEL: &str = "validator";
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct WriteRequest {
+    #[prost(message, repeated, tag = "1")]
+    pub timeseries: Vec<TimeSeries>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct TimeSeries {
+    /// Sorted by name, including `__name__`.
+    #[prost(message, repeated, tag = "1")]
+    pub labels: Vec<Label>,
+    #[prost(message, repeated, tag = "2")]
+    pub samples: Vec<Sample>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Label {
+    #[prost(string, tag = "1")]
+    pub name: String,
+    #[prost(string, tag = "2")]
+    pub value: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Sample {
+    #[prost(double, tag = "1")]
+    pub value: f64,
+    /// Milliseconds since the Unix epoch.
+    #[prost(int64, tag = "2")]
+    pub timestamp: i64,
+}
+
+impl WriteRequest {
+    /// Decodes a request from the body of a remote-write request.
+    pub fn decode_snappy(body: &[u8]) -> Result<Self> {
+        let bytes = snap::raw::Decoder::new().decompress_vec(body)?;
+        Ok(<Self as prost::Message>::decode(bytes.as_slice())?)
+    }
+
+    /// Encodes the request as the body of a remote-write request.
+    pub fn encode_snappy(&self) -> Result<Vec<u8>> {
+        let bytes = prost::Message::encode_to_vec(self);
+        Ok(snap::raw::Encoder::new().compress_vec(&bytes)?)
+    }
+
+    /// Decodes the length delimited `MetricFamily`s pushed in `body`, with the samples without
+    /// a timestamp taken now.
+    pub fn from_pushed_metrics(body: &[u8]) -> Result<Self> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("Time is before the Unix epoch")
+            .as_millis() as i64;
+        let mut input = protobuf::CodedInputStream::from_bytes(body);
+        let mut timeseries = vec![];
+        while !input.eof()? {
+            let family: MetricFamily = input
+                .read_message()
+                .map_err(|e| anyhow!("Invalid pushed metrics: {e}"))?;
+            timeseries.extend(series_of_family(&family, now));
+        }
+        Ok(Self { timeseries })
+    }
+
+    /// Labels all the series with `validator`, replacing the label the sender may have set.
+    pub fn label_validator(&mut self, validator: &str) {
+        for series in &mut self.timeseries {
+            series.labels.retain(|label| label.name != VALIDATOR_LABEL);
+            let position = series
+                .labels
+                .partition_point(|label| label.name.as_str() < VALIDATOR_LABEL);
+            series.labels.insert(
+                position,
+                Label {
+                    name: VALIDATOR_LABEL.to_string(),
+                    value: validator.to_string(),
+                },
+            );
+        }
+    }
+}
+
+/// The series of a metric family, with the histograms and summaries split into a series per
+/// bucket or quantile, and their sums and counts, as Prometheus scrapes them.
+fn series_of_family(family: &MetricFamily, now: i64) -> Vec<TimeSeries> {
+    let name = family.get_name();
+    let mut timeseries = vec![];
+    for metric in family.get_metric() {
+        let timestamp = match metric.get_timestamp_ms() {
+            0 => now,
+            timestamp_ms => timestamp_ms,
+        };
+        let labels: Vec<(&str, String)> = metric
+            .get_label()
+            .iter()
+            .map(|label| (label.get_name(), label.get_value().to_string()))
+            .collect();
+        let mut push = |suffix: &str, extra_label: Option<(&'static str, String)>, value: f64| {
+            let mut series_labels = labels.clone();
+            series_labels.extend(extra_label);
+            timeseries.push(series(
+                &format!("{name}{suffix}"),
+                series_labels,
+                value,
+                timestamp,
+            ));
+        };
+        match family.get_field_type() {
+            MetricType::COUNTER => push("", None, metric.get_counter().get_value()),
+            MetricType::GAUGE => push("", None, metric.get_gauge().get_value()),
+            MetricType::UNTYPED => push("", None, metric.get_untyped().get_value()),
+            MetricType::HISTOGRAM => {
+                let histogram = metric.get_histogram();
+                for bucket in histogram.get_bucket() {
+                    push(
+                        "_bucket",
+                        Some(("le", bucket.get_upper_bound().to_string())),
+                        bucket.get_cumulative_count() as f64,
+                    );
+                }
+                // The bucket of all the samples is implicit in the buckets of the client library.
+                let has_inf_bucket = histogram
+                    .get_bucket()
+                    .last()
+                    .map_or(false, |bucket| bucket.get_upper_bound().is_infinite());
+                if !has_inf_bucket {
+                    push(
+                        "_bucket",
+                        Some(("le", "+Inf".to_string())),
+                        histogram.get_sample_count() as f64,
+                    );
+                }
+                push("_sum", None, histogram.get_sample_sum());
+                push("_count", None, histogram.get_sample_count() as f64);
+            }
+            MetricType::SUMMARY => {
+                let summary = metric.get_summary();
+                for quantile in summary.get_quantile() {
+                    push(
+                        "",
+                        Some(("quantile", quantile.get_quantile().to_string())),
+                        quantile.get_value(),
+                    );
+                }
+                push("_sum", None, summary.get_sample_sum());
+                push("_count", None, summary.get_sample_count() as f64);
+            }
+        }
+    }
+    timeseries
+}
+
+fn series(name: &str, labels: Vec<(&str, String)>, value: f64, timestamp: i64) -> TimeSeries {
+    let mut labels: Vec<Label> = labels
+        .into_iter()
+        .map(|(name, value)| Label {
+            name: name.to_string(),
+            value,
+        })
+        .chain(std::iter::once(Label {
+            name: "__name__".to_string(),
+            value: name.to_string(),
+        }))
+        .collect();
+    labels.sort_by(|a, b| a.name.cmp(&b.name));
+    TimeSeries {
+        labels,
+        samples: vec![Sample { value, timestamp }],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use prometheus::{Encoder, HistogramOpts, IntCounterVec, Opts, Registry};
+
+    fn label_names(series: &TimeSeries) -> Vec<&str> {
+        series
+            .labels
+            .iter()
+            .map(|label| label.name.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_pushed_metrics() {
+        let registry = Registry::new();
+        let counter = IntCounterVec::new(Opts::new("requests", "Requests"), &["method"]).unwrap();
+        let histogram = prometheus::Histogram::with_opts(
+            HistogramOpts::new("latency", "Latency").buckets(vec![1.0, 2.0]),
+        )
+        .unwrap();
+        registry.register(Box::new(counter.clone())).unwrap();
+        registry.register(Box::new(histogram.clone())).unwrap();
+        counter.with_label_values(&["get"]).inc_by(3);
+        histogram.observe(1.5);
+
+        let mut body = vec![];
+        prometheus::ProtobufEncoder::new()
+            .encode(&registry.gather(), &mut body)
+            .unwrap();
+        let mut request = WriteRequest::from_pushed_metrics(&body).unwrap();
+        request.label_validator("validator-0");
+
+        let names: Vec<&str> = request
+            .timeseries
+            .iter()
+            .map(|series| series.labels[0].value.as_str())
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                "latency_bucket",
+                "latency_bucket",
+                "latency_bucket",
+                "latency_sum",
+                "latency_count",
+                "requests"
+            ]
+        );
+        assert_eq!(
+            label_names(&request.timeseries[0]),
+            vec!["__name__", "le", "validator"]
+        );
+        let requests = &request.timeseries[5];
+        assert_eq!(
+            label_names(requests),
+            vec!["__name__", "method", "validator"]
+        );
+        assert_eq!(requests.labels[2].value, "validator-0");
+        assert_eq!(requests.samples[0].value, 3.0);
+
+        let encoded = request.encode_snappy().unwrap();
+        assert_eq!(WriteRequest::decode_snappy(&encoded).unwrap(), request);
+    }
+
+    #[test]
+    fn test_label_validator_replaces_label() {
+        let mut request = WriteRequest {
+            timeseries: vec![series(
+                "up",
+                vec![
+                    (VALIDATOR_LABEL, "spoofed".to_string()),
+                    ("zone", "a".to_string()),
+                ],
+                1.0,
+                0,
+            )],
+        };
+        request.label_validator("validator-0");
+
+        let labels = &request.timeseries[0].labels;
+        assert_eq!(
+            label_names(&request.timeseries[0]),
+            vec!["__name__", "validator", "zone"]
+        );
+        assert_eq!(labels[1].value, "validator-0");
+    }
+}
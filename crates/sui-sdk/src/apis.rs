@@ -13,11 +13,14 @@ use std::sync::Arc;
 use std::time::{Duration, Instant};
 use sui_json_rpc::api::GovernanceReadApiClient;
 use sui_json_rpc_types::{
-    Balance, Checkpoint, CheckpointId, Coin, CoinPage, DryRunTransactionResponse, DynamicFieldPage,
-    EventPage, SuiCoinMetadata, SuiCommittee, SuiEventEnvelope, SuiEventFilter,
-    SuiMoveNormalizedModule, SuiObjectDataOptions, SuiObjectInfo, SuiObjectResponse,
-    SuiPastObjectResponse, SuiSystemStateRpc, SuiTransactionEffectsAPI, SuiTransactionResponse,
-    TransactionsPage,
+    Balance, BalanceChangePage, Checkpoint, CheckpointId, CheckpointPage, Coin, CoinBalanceCursor,
+    CoinPage, CoinsByBalancePage, DevInspectArgs, DevInspectResults, DryRunTransactionResponse,
+    DynamicFieldPage, EventPage, GasEstimate, NodeInfo, OwnedObjectsCursor, OwnedObjectsPage,
+    ProtocolConfigResponse, SuiCheckpointData, SuiCoinMetadata, SuiCommittee, SuiEventEnvelope,
+    SuiEventFilter, SuiGetPastObjectRequest, SuiMoveNormalizedModule, SuiObjectDataOptions,
+    SuiObjectHistoryEntry, SuiObjectInfo, SuiObjectResponse, SuiPastObjectResponse,
+    SuiSystemStateRpc, SuiTransactionEffects, SuiTransactionEffectsAPI, SuiTransactionResponse,
+    SuiTransactionResponseOptions, SuiTransactionResult, TransactionsPage,
 };
 use sui_types::balance::Supply;
 use sui_types::base_types::{
@@ -26,10 +29,14 @@ use sui_types::base_types::{
 use sui_types::committee::EpochId;
 use sui_types::error::TRANSACTION_NOT_FOUND_MSG_PREFIX;
 use sui_types::event::EventID;
-use sui_types::messages::{ExecuteTransactionRequestType, TransactionData, VerifiedTransaction};
+use sui_types::filter::TransactionFilter;
+use sui_types::messages::{
+    ExecuteTransactionRequestType, TransactionData, TransactionKind, VerifiedTransaction,
+};
 use sui_types::messages_checkpoint::CheckpointSequenceNumber;
 use sui_types::query::{EventQuery, TransactionQuery};
 use sui_types::sui_system_state::sui_system_state_inner_v1::ValidatorMetadataV1;
+use sui_types::sui_system_state::sui_system_state_summary::SuiSystemStateSummary;
 
 use futures::StreamExt;
 use sui_json_rpc::api::{CoinReadApiClient, EventReadApiClient, ReadApiClient, WriteApiClient};
@@ -52,6 +59,20 @@ impl ReadApi {
         Ok(self.api.http.get_objects_owned_by_address(address).await?)
     }
 
+    pub async fn get_owned_objects(
+        &self,
+        address: SuiAddress,
+        cursor: Option<OwnedObjectsCursor>,
+        limit: Option<usize>,
+        object_type: Option<String>,
+    ) -> SuiRpcResult<OwnedObjectsPage> {
+        Ok(self
+            .api
+            .http
+            .get_owned_objects(address, cursor, limit, object_type)
+            .await?)
+    }
+
     pub async fn get_dynamic_fields(
         &self,
         object_id: ObjectID,
@@ -78,6 +99,34 @@ impl ReadApi {
             .await?)
     }
 
+    pub async fn try_multi_get_parsed_past_objects(
+        &self,
+        past_objects: Vec<SuiGetPastObjectRequest>,
+        options: SuiObjectDataOptions,
+    ) -> SuiRpcResult<Vec<SuiPastObjectResponse>> {
+        Ok(self
+            .api
+            .http
+            .try_multi_get_past_objects(past_objects, Some(options))
+            .await?)
+    }
+
+    /// Return the versions of an object from `start_version` to `end_version`, both included,
+    /// with markers for the versions at which it was deleted and those that have been pruned.
+    pub async fn try_get_past_object_history(
+        &self,
+        object_id: ObjectID,
+        start_version: SequenceNumber,
+        end_version: SequenceNumber,
+        options: SuiObjectDataOptions,
+    ) -> SuiRpcResult<Vec<SuiObjectHistoryEntry>> {
+        Ok(self
+            .api
+            .http
+            .try_get_past_object_history(object_id, start_version, end_version, Some(options))
+            .await?)
+    }
+
     pub async fn get_object_with_options(
         &self,
         object_id: ObjectID,
@@ -116,6 +165,18 @@ impl ReadApi {
         Ok(self.api.http.multi_get_transactions(digests).await?)
     }
 
+    pub async fn multi_get_transactions_with_options(
+        &self,
+        digests: Vec<TransactionDigest>,
+        options: SuiTransactionResponseOptions,
+    ) -> SuiRpcResult<Vec<SuiTransactionResult>> {
+        Ok(self
+            .api
+            .http
+            .multi_get_transactions_with_options(digests, Some(options))
+            .await?)
+    }
+
     pub async fn get_committee_info(&self, epoch: Option<EpochId>) -> SuiRpcResult<SuiCommittee> {
         Ok(self.api.http.get_committee_info(epoch).await?)
     }
@@ -139,6 +200,31 @@ impl ReadApi {
         Ok(self.api.http.get_checkpoint(id).await?)
     }
 
+    /// Return a page of the checkpoints with sequence numbers in the range `[start, end]`, both
+    /// ends included.
+    pub async fn get_checkpoints(
+        &self,
+        start: Option<CheckpointSequenceNumber>,
+        end: Option<CheckpointSequenceNumber>,
+        cursor: Option<CheckpointSequenceNumber>,
+        limit: Option<usize>,
+        descending_order: bool,
+        include_transactions: bool,
+    ) -> SuiRpcResult<CheckpointPage> {
+        Ok(self
+            .api
+            .http
+            .get_checkpoints(
+                start,
+                end,
+                cursor,
+                limit,
+                Some(descending_order),
+                Some(include_transactions),
+            )
+            .await?)
+    }
+
     /// Return the sequence number of the latest checkpoint that has been executed
     pub async fn get_latest_checkpoint_sequence_number(
         &self,
@@ -150,6 +236,36 @@ impl ReadApi {
             .await?)
     }
 
+    /// Subscribe to the data of executed checkpoints in order of sequence number, from `start`
+    /// or the checkpoint after the latest executed one.
+    pub async fn subscribe_checkpoint_data(
+        &self,
+        start: Option<CheckpointSequenceNumber>,
+    ) -> SuiRpcResult<impl Stream<Item = SuiRpcResult<SuiCheckpointData>>> {
+        match &self.api.ws {
+            Some(c) => {
+                let subscription: Subscription<SuiCheckpointData> =
+                    c.subscribe_checkpoint_data(start).await?;
+                Ok(subscription.map(|item| Ok(item?)))
+            }
+            _ => Err(Error::Subscription(
+                "Subscription only supported by WebSocket client.".to_string(),
+            )),
+        }
+    }
+
+    /// Return the version of the node and the protocol versions it supports
+    pub async fn get_node_info(&self) -> SuiRpcResult<NodeInfo> {
+        Ok(self.api.http.get_node_info().await?)
+    }
+
+    pub async fn get_protocol_config(
+        &self,
+        version: Option<u64>,
+    ) -> SuiRpcResult<ProtocolConfigResponse> {
+        Ok(self.api.http.get_protocol_config(version).await?)
+    }
+
     pub fn get_transactions_stream(
         &self,
         query: TransactionQuery,
@@ -206,6 +322,52 @@ impl ReadApi {
             .dry_run_transaction(Base64::from_bytes(&bcs::to_bytes(&tx)?))
             .await?)
     }
+
+    pub async fn dev_inspect_transaction(
+        &self,
+        sender: SuiAddress,
+        tx: TransactionKind,
+        gas_price: Option<u64>,
+        epoch: Option<EpochId>,
+    ) -> SuiRpcResult<DevInspectResults> {
+        self.dev_inspect_transaction_with_args(sender, tx, gas_price, epoch, None)
+            .await
+    }
+
+    /// Runs `tx` in dev-inspect mode with additional options, e.g. to profile its gas.
+    pub async fn dev_inspect_transaction_with_args(
+        &self,
+        sender: SuiAddress,
+        tx: TransactionKind,
+        gas_price: Option<u64>,
+        epoch: Option<EpochId>,
+        args: Option<DevInspectArgs>,
+    ) -> SuiRpcResult<DevInspectResults> {
+        Ok(self
+            .api
+            .http
+            .dev_inspect_transaction(
+                sender,
+                Base64::from_bytes(&bcs::to_bytes(&tx)?),
+                gas_price,
+                epoch,
+                args,
+            )
+            .await?)
+    }
+
+    pub async fn estimate_gas(
+        &self,
+        sender: SuiAddress,
+        tx: TransactionKind,
+        gas_price: Option<u64>,
+    ) -> SuiRpcResult<GasEstimate> {
+        Ok(self
+            .api
+            .http
+            .estimate_gas(sender, Base64::from_bytes(&bcs::to_bytes(&tx)?), gas_price)
+            .await?)
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -232,6 +394,21 @@ impl CoinReadApi {
             .await?)
     }
 
+    pub async fn get_coins_by_balance(
+        &self,
+        owner: SuiAddress,
+        coin_type: Option<String>,
+        min_balance: Option<u64>,
+        cursor: Option<CoinBalanceCursor>,
+        limit: Option<usize>,
+    ) -> SuiRpcResult<CoinsByBalancePage> {
+        Ok(self
+            .api
+            .http
+            .get_coins_by_balance(owner, coin_type, min_balance, cursor, limit)
+            .await?)
+    }
+
     pub async fn get_all_coins(
         &self,
         owner: SuiAddress,
@@ -310,6 +487,21 @@ impl CoinReadApi {
         Ok(self.api.http.get_all_balances(owner).await?)
     }
 
+    pub async fn get_balance_changes(
+        &self,
+        owner: SuiAddress,
+        coin_type: Option<String>,
+        cursor: Option<TransactionDigest>,
+        limit: Option<usize>,
+        descending_order: Option<bool>,
+    ) -> SuiRpcResult<BalanceChangePage> {
+        Ok(self
+            .api
+            .http
+            .get_balance_changes(owner, coin_type, cursor, limit, descending_order)
+            .await?)
+    }
+
     pub async fn get_coin_metadata(&self, coin_type: String) -> SuiRpcResult<SuiCoinMetadata> {
         Ok(self.api.http.get_coin_metadata(coin_type).await?)
     }
@@ -345,6 +537,22 @@ impl EventApi {
         }
     }
 
+    pub async fn subscribe_transaction(
+        &self,
+        filter: TransactionFilter,
+    ) -> SuiRpcResult<impl Stream<Item = SuiRpcResult<SuiTransactionEffects>>> {
+        match &self.api.ws {
+            Some(c) => {
+                let subscription: Subscription<SuiTransactionEffects> =
+                    c.subscribe_transaction(filter).await?;
+                Ok(subscription.map(|item| Ok(item?)))
+            }
+            _ => Err(Error::Subscription(
+                "Subscription only supported by WebSocket client.".to_string(),
+            )),
+        }
+    }
+
     pub async fn get_events(
         &self,
         query: EventQuery,
@@ -415,7 +623,7 @@ impl QuorumDriver {
         let mut response: SuiTransactionResponse = self
             .api
             .http
-            .submit_transaction(tx_bytes, signatures, request_type.clone())
+            .submit_transaction(tx_bytes, signatures, request_type.clone(), None)
             .await?;
 
         Ok(match request_type {
@@ -496,4 +704,9 @@ impl GovernanceApi {
     pub async fn get_sui_system_state(&self) -> SuiRpcResult<SuiSystemStateRpc> {
         Ok(self.api.http.get_sui_system_state().await?)
     }
+
+    /// Return the [SuiSystemStateSummary] of the latest epoch, with its active validators.
+    pub async fn get_latest_sui_system_state(&self) -> SuiRpcResult<SuiSystemStateSummary> {
+        Ok(self.api.http.get_latest_sui_system_state().await?)
+    }
 }
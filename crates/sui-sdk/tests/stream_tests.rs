@@ -3,7 +3,7 @@
 
 use futures::StreamExt;
 use std::future;
-use sui::client_commands::SuiClientCommands;
+use sui::client_commands::{SuiClientCommands, TransactionOptions};
 use sui_sdk::{SuiClientBuilder, SUI_COIN_TYPE};
 use sui_types::event::EventType;
 use sui_types::query::{EventQuery, TransactionQuery};
@@ -30,6 +30,7 @@ async fn test_transactions_stream() -> Result<(), anyhow::Error> {
         url: None,
         gas: None,
         gas_budget: None,
+        options: TransactionOptions::default(),
     }
     .execute(&mut test_cluster.wallet)
     .await?;
@@ -88,6 +89,7 @@ async fn test_events_stream() -> Result<(), anyhow::Error> {
         url: None,
         gas: None,
         gas_budget: None,
+        options: TransactionOptions::default(),
     }
     .execute(&mut test_cluster.wallet)
     .await?;
@@ -5,7 +5,10 @@ use core::fmt;
 use futures::future;
 use move_binary_format::access::ModuleAccess;
 use move_binary_format::CompiledModule;
-use std::{collections::HashMap, fmt::Debug};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::Debug,
+};
 use sui_types::error::UserInputError;
 use thiserror::Error;
 
@@ -195,14 +198,25 @@ impl<'a> BytecodeSourceVerifier<'a> {
             // compare local bytecode to on-chain bytecode to ensure integrity of our
             // dependencies
             if local_bytes != on_chain_bytes {
+                if self.verbose {
+                    println!(
+                        "{}::{} - {} bytes locally, {} bytes on-chain, code does not match",
+                        package.as_ref(),
+                        module.as_ref(),
+                        local_bytes.len(),
+                        on_chain_bytes.len()
+                    );
+                    for difference in module_differences(&local_bytes, &on_chain_bytes) {
+                        println!("  - {difference}");
+                    }
+                }
+
                 errors.push(SourceVerificationError::ModuleBytecodeMismatch {
                     address,
                     package,
                     module,
                 });
-            }
-
-            if self.verbose {
+            } else if self.verbose {
                 println!(
                     "{}::{} - {} bytes, code matches",
                     package.as_ref(),
@@ -212,7 +226,9 @@ impl<'a> BytecodeSourceVerifier<'a> {
             }
         }
 
-        if let Some(((address, module), _)) = on_chain_modules.into_iter().next() {
+        let mut missing_locally: Vec<_> = on_chain_modules.into_keys().collect();
+        missing_locally.sort();
+        for (address, module) in missing_locally {
             errors.push(SourceVerificationError::LocalDependencyNotFound { address, module });
         }
 
@@ -278,6 +294,108 @@ impl<'a> BytecodeSourceVerifier<'a> {
     }
 }
 
+/// Describes how the local bytecode of a module differs from its on-chain bytecode: which of its
+/// structs and functions were added, removed or changed. Functions whose code only refers to
+/// different constants or handles are reported as changed too.
+fn module_differences(local_bytes: &[u8], on_chain_bytes: &[u8]) -> Vec<String> {
+    let (local, on_chain) = match (
+        CompiledModule::deserialize(local_bytes),
+        CompiledModule::deserialize(on_chain_bytes),
+    ) {
+        (Ok(local), Ok(on_chain)) => (local, on_chain),
+        (Err(e), _) => return vec![format!("local bytecode is invalid: {e:?}")],
+        (_, Err(e)) => return vec![format!("on-chain bytecode is invalid: {e:?}")],
+    };
+
+    let mut differences = vec![];
+    let local_structs = struct_definitions(&local);
+    let on_chain_structs = struct_definitions(&on_chain);
+    compare_definitions(
+        "struct",
+        &local_structs,
+        &on_chain_structs,
+        &mut differences,
+    );
+    let local_functions = function_definitions(&local);
+    let on_chain_functions = function_definitions(&on_chain);
+    compare_definitions(
+        "function",
+        &local_functions,
+        &on_chain_functions,
+        &mut differences,
+    );
+
+    if differences.is_empty() {
+        if local.constant_pool != on_chain.constant_pool {
+            differences.push("constants differ".to_string());
+        } else {
+            differences.push("module metadata or tables differ".to_string());
+        }
+    }
+    differences
+}
+
+fn compare_definitions(
+    kind: &str,
+    local: &BTreeMap<String, String>,
+    on_chain: &BTreeMap<String, String>,
+    differences: &mut Vec<String>,
+) {
+    for (name, definition) in local {
+        match on_chain.get(name) {
+            None => differences.push(format!("{kind} {name} only exists locally")),
+            Some(on_chain) if on_chain != definition => {
+                differences.push(format!("{kind} {name} differs"))
+            }
+            Some(_) => {}
+        }
+    }
+    for name in on_chain.keys().filter(|name| !local.contains_key(*name)) {
+        differences.push(format!("{kind} {name} only exists on-chain"));
+    }
+}
+
+/// The definitions of the structs of `module`, by name.
+fn struct_definitions(module: &CompiledModule) -> BTreeMap<String, String> {
+    module
+        .struct_defs()
+        .iter()
+        .map(|def| {
+            let handle = module.struct_handle_at(def.struct_handle);
+            (
+                module.identifier_at(handle.name).to_string(),
+                format!(
+                    "{:?} {:?} {:?}",
+                    handle.abilities, handle.type_parameters, def.field_information
+                ),
+            )
+        })
+        .collect()
+}
+
+/// The definitions of the functions of `module`, by name, bodies included.
+fn function_definitions(module: &CompiledModule) -> BTreeMap<String, String> {
+    module
+        .function_defs()
+        .iter()
+        .map(|def| {
+            let handle = module.function_handle_at(def.function);
+            (
+                module.identifier_at(handle.name).to_string(),
+                format!(
+                    "{:?} {:?} {:?} {:?} {:?} {:?}",
+                    module.signature_at(handle.parameters),
+                    module.signature_at(handle.return_),
+                    handle.type_parameters,
+                    def.visibility,
+                    def.is_entry,
+                    def.code,
+                ),
+            )
+        })
+        .collect()
+}
+
 fn substitute_root_address(
     named_module: &NamedCompiledModule,
     root: AccountAddress,
@@ -0,0 +1,737 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Archives of checkpoints and transactions, kept in a bucket (e.g. on GCS or S3) or a local
+//! directory with the following layout, each entry BCS encoded and compressed as the manifest
+//! says:
+//!
+//! - `MANIFEST`: the JSON encoded [`ArchiveManifest`]
+//! - `checkpoints/<sequence number>`: an [`ArchivedCheckpoint`]
+//! - `transactions/<digest>`: an [`ArchivedTransaction`]
+//! - `objects/<object id>/<version>`: an [`Object`]
+//!
+//! Archives without a manifest are read as uncompressed.
+//!
+//! The reader checks that an entry is the one that was requested. Checkpoints carry the
+//! signatures of the committee and are verified with [`ArchivedCheckpoint::verify`]. Transactions
+//! and objects are only as trustworthy as the archive itself until they have been checked against
+//! a verified checkpoint, with [`ArchivedTransaction::verify_inclusion`] and
+//! [`verify_object_written_by`].
+
+use anyhow::{anyhow, bail, Result};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use reqwest::{Client, StatusCode};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::{ErrorKind, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering as AtomicOrdering};
+use std::time::{Duration, SystemTime};
+use sui_types::base_types::{ObjectID, SequenceNumber, TransactionDigest};
+use sui_types::committee::Committee;
+use sui_types::message_envelope::Message;
+use sui_types::messages::{
+    Transaction, TransactionEffects, TransactionEffectsAPI, TransactionEvents,
+};
+use sui_types::messages_checkpoint::{
+    CertifiedCheckpointSummary, CheckpointContents, CheckpointSequenceNumber,
+};
+use sui_types::object::Object;
+use tokio::sync::OnceCell;
+use tracing::warn;
+
+const MANIFEST_PATH: &str = "MANIFEST";
+
+/// The version of the layout of archives, recorded in their manifest.
+pub const ARCHIVE_FORMAT_VERSION: u32 = 1;
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ArchivedCheckpoint {
+    pub summary: CertifiedCheckpointSummary,
+    pub contents: CheckpointContents,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ArchivedTransaction {
+    pub transaction: Transaction,
+    pub effects: TransactionEffects,
+    pub events: TransactionEvents,
+    /// The checkpoint the transaction was executed in, and its timestamp.
+    pub checkpoint: Option<(CheckpointSequenceNumber, u64)>,
+}
+
+impl ArchivedCheckpoint {
+    /// Checks that the checkpoint is certified by `committee`, the committee of its epoch, and
+    /// that its contents are the ones the certificate commits to.
+    pub fn verify(&self, committee: &Committee) -> Result<()> {
+        self.summary.verify(committee, Some(&self.contents))?;
+        Ok(())
+    }
+}
+
+impl ArchivedTransaction {
+    /// Checks that the transaction, its effects and its events are the ones `checkpoint`
+    /// includes. `checkpoint` must have been verified with [`ArchivedCheckpoint::verify`].
+    pub fn verify_inclusion(&self, checkpoint: &ArchivedCheckpoint) -> Result<()> {
+        let digest = *self.transaction.digest();
+        let summary = &checkpoint.summary.summary;
+        if self.checkpoint != Some((summary.sequence_number, summary.timestamp_ms)) {
+            bail!(
+                "Transaction {digest} is archived as part of checkpoint {:?}, not {}",
+                self.checkpoint,
+                summary.sequence_number
+            );
+        }
+        let effects_digest = self.effects.digest();
+        if !checkpoint
+            .contents
+            .iter()
+            .any(|d| d.transaction == digest && d.effects == effects_digest)
+        {
+            bail!(
+                "Transaction {digest} with effects {effects_digest} is not in checkpoint {}",
+                summary.sequence_number
+            );
+        }
+        let events_match = match self.effects.events_digest() {
+            Some(events_digest) => self.events.digest() == *events_digest,
+            None => self.events.data.is_empty(),
+        };
+        if !events_match {
+            bail!("The events of transaction {digest} do not match its effects");
+        }
+        Ok(())
+    }
+}
+
+/// Checks that `object` is a version written by `transaction`, whose inclusion in a checkpoint
+/// must have been verified with [`ArchivedTransaction::verify_inclusion`].
+pub fn verify_object_written_by(object: &Object, transaction: &ArchivedTransaction) -> Result<()> {
+    let object_ref = object.compute_object_reference();
+    if object.previous_transaction != *transaction.transaction.digest()
+        || !transaction
+            .effects
+            .all_mutated()
+            .into_iter()
+            .any(|(written, _, _)| *written == object_ref)
+    {
+        bail!(
+            "Object {:?} was not written by transaction {}",
+            object_ref,
+            transaction.transaction.digest()
+        );
+    }
+    Ok(())
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Compression {
+    #[default]
+    None,
+    Gzip,
+}
+
+impl Compression {
+    pub fn compress(&self, bytes: Vec<u8>) -> Result<Vec<u8>> {
+        match self {
+            Compression::None => Ok(bytes),
+            Compression::Gzip => {
+                let mut encoder = GzEncoder::new(Vec::new(), flate2::Compression::default());
+                encoder.write_all(&bytes)?;
+                Ok(encoder.finish()?)
+            }
+        }
+    }
+
+    pub fn decompress(&self, bytes: Vec<u8>) -> Result<Vec<u8>> {
+        match self {
+            Compression::None => Ok(bytes),
+            Compression::Gzip => {
+                let mut decompressed = Vec::new();
+                GzDecoder::new(bytes.as_slice()).read_to_end(&mut decompressed)?;
+                Ok(decompressed)
+            }
+        }
+    }
+}
+
+/// Describes the contents of an archive. It is written after the entries it accounts for, so
+/// everything it accounts for is in the archive.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct ArchiveManifest {
+    pub version: u32,
+    /// How the entries are compressed.
+    pub compression: Compression,
+    /// The checkpoints before this one are archived.
+    pub next_checkpoint: CheckpointSequenceNumber,
+    /// If set, the transactions of the checkpoints from this one are archived too.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transactions_from_checkpoint: Option<CheckpointSequenceNumber>,
+}
+
+impl ArchiveManifest {
+    pub fn new(compression: Compression) -> Self {
+        Self {
+            version: ARCHIVE_FORMAT_VERSION,
+            compression,
+            next_checkpoint: 0,
+            transactions_from_checkpoint: None,
+        }
+    }
+}
+
+/// Where an archive is kept.
+pub enum ArchiveStore {
+    /// A directory of the local filesystem, or of a mounted bucket.
+    Directory(PathBuf),
+    /// A bucket served over HTTP(S), written with `PUT` requests authorized by a bearer token.
+    Http {
+        client: Client,
+        url: String,
+        auth_token: Option<String>,
+    },
+}
+
+impl ArchiveStore {
+    pub fn http(url: &str, auth_token: Option<String>, request_timeout: Duration) -> Result<Self> {
+        let client = Client::builder().timeout(request_timeout).build()?;
+        Ok(Self::Http {
+            client,
+            url: url.trim_end_matches('/').to_string(),
+            auth_token,
+        })
+    }
+
+    /// Reads the entry at `path`, if it exists.
+    pub async fn get(&self, path: &str) -> Result<Option<Vec<u8>>> {
+        match self {
+            ArchiveStore::Directory(dir) => match tokio::fs::read(dir.join(path)).await {
+                Ok(bytes) => Ok(Some(bytes)),
+                Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+                Err(e) => Err(e.into()),
+            },
+            ArchiveStore::Http {
+                client,
+                url,
+                auth_token,
+            } => {
+                let mut request = client.get(format!("{url}/{path}"));
+                if let Some(token) = auth_token {
+                    request = request.bearer_auth(token);
+                }
+                let response = request.send().await?;
+                if response.status() == StatusCode::NOT_FOUND {
+                    return Ok(None);
+                }
+                Ok(Some(response.error_for_status()?.bytes().await?.to_vec()))
+            }
+        }
+    }
+
+    /// Writes the entry at `path`, replacing it if it exists. Readers never see a partial entry.
+    pub async fn put(&self, path: &str, bytes: Vec<u8>) -> Result<()> {
+        match self {
+            ArchiveStore::Directory(dir) => {
+                let target = dir.join(path);
+                if let Some(parent) = target.parent() {
+                    tokio::fs::create_dir_all(parent).await?;
+                }
+                let staging = target.with_extension("partial");
+                tokio::fs::write(&staging, bytes).await?;
+                tokio::fs::rename(&staging, &target).await?;
+                Ok(())
+            }
+            ArchiveStore::Http {
+                client,
+                url,
+                auth_token,
+            } => {
+                let mut request = client.put(format!("{url}/{path}")).body(bytes);
+                if let Some(token) = auth_token {
+                    request = request.bearer_auth(token);
+                }
+                request.send().await?.error_for_status()?;
+                Ok(())
+            }
+        }
+    }
+}
+
+/// A local copy of the entries read from an archive, bounded in size. Archived entries never
+/// change, so cached entries are never stale. The entries cached first are evicted first.
+pub struct ArchiveCache {
+    dir: PathBuf,
+    max_size_bytes: u64,
+    size_bytes: AtomicU64,
+}
+
+impl ArchiveCache {
+    pub fn new(dir: PathBuf, max_size_bytes: u64) -> Result<Self> {
+        fs::create_dir_all(&dir)?;
+        let size_bytes = cached_files(&dir)?.iter().map(|(_, size, _)| size).sum();
+        Ok(Self {
+            dir,
+            max_size_bytes,
+            size_bytes: AtomicU64::new(size_bytes),
+        })
+    }
+
+    /// The current size of the cached entries.
+    pub fn size_bytes(&self) -> u64 {
+        self.size_bytes.load(AtomicOrdering::Relaxed)
+    }
+
+    async fn get(&self, path: &str) -> Result<Option<Vec<u8>>> {
+        ArchiveStore::Directory(self.dir.clone()).get(path).await
+    }
+
+    async fn put(&self, path: &str, bytes: Vec<u8>) -> Result<()> {
+        let len = bytes.len() as u64;
+        ArchiveStore::Directory(self.dir.clone())
+            .put(path, bytes)
+            .await?;
+        if self.size_bytes.fetch_add(len, AtomicOrdering::Relaxed) + len > self.max_size_bytes {
+            let dir = self.dir.clone();
+            // Evict down to 90% of the limit, so as not to evict on every new entry.
+            let target_size_bytes = self.max_size_bytes / 10 * 9;
+            let size_bytes =
+                tokio::task::spawn_blocking(move || evict(&dir, target_size_bytes)).await??;
+            self.size_bytes.store(size_bytes, AtomicOrdering::Relaxed);
+        }
+        Ok(())
+    }
+}
+
+/// The files under `dir`, with their size and modification time.
+fn cached_files(dir: &Path) -> Result<Vec<(PathBuf, u64, SystemTime)>> {
+    let mut files = vec![];
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let metadata = entry.metadata()?;
+        if metadata.is_dir() {
+            files.extend(cached_files(&entry.path())?);
+        } else {
+            files.push((entry.path(), metadata.len(), metadata.modified()?));
+        }
+    }
+    Ok(files)
+}
+
+/// Deletes the oldest files under `dir` until they take at most `target_size_bytes`, returning
+/// their size.
+fn evict(dir: &Path, target_size_bytes: u64) -> Result<u64> {
+    let mut files = cached_files(dir)?;
+    files.sort_by_key(|(_, _, modified)| *modified);
+    let mut size_bytes: u64 = files.iter().map(|(_, size, _)| size).sum();
+    for (path, size, _) in files {
+        if size_bytes <= target_size_bytes {
+            break;
+        }
+        fs::remove_file(path)?;
+        size_bytes -= size;
+    }
+    Ok(size_bytes)
+}
+
+pub struct ArchiveReader {
+    store: ArchiveStore,
+    cache: Option<ArchiveCache>,
+    manifest: OnceCell<ArchiveManifest>,
+}
+
+impl ArchiveReader {
+    pub fn new(url: &str, request_timeout: Duration) -> Result<Self> {
+        Ok(Self::from_store(ArchiveStore::http(
+            url,
+            None,
+            request_timeout,
+        )?))
+    }
+
+    pub fn from_store(store: ArchiveStore) -> Self {
+        Self {
+            store,
+            cache: None,
+            manifest: OnceCell::new(),
+        }
+    }
+
+    /// Keeps the entries read from the archive in `cache`, and reads them from there next time.
+    pub fn with_cache(mut self, cache: ArchiveCache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// The manifest of the archive, read once.
+    pub async fn manifest(&self) -> Result<&ArchiveManifest> {
+        self.manifest
+            .get_or_try_init(|| async {
+                Ok(read_manifest(&self.store)
+                    .await?
+                    .unwrap_or_else(|| ArchiveManifest::new(Compression::None)))
+            })
+            .await
+    }
+
+    pub async fn get_checkpoint(
+        &self,
+        sequence_number: CheckpointSequenceNumber,
+    ) -> Result<Option<ArchivedCheckpoint>> {
+        let checkpoint = match self
+            .get::<ArchivedCheckpoint>(&format!("checkpoints/{sequence_number}"))
+            .await?
+        {
+            Some(checkpoint) => checkpoint,
+            None => return Ok(None),
+        };
+        if checkpoint.summary.summary.sequence_number != sequence_number {
+            return Err(anyhow!(
+                "Archive returned checkpoint {} for checkpoint {sequence_number}",
+                checkpoint.summary.summary.sequence_number
+            ));
+        }
+        Ok(Some(checkpoint))
+    }
+
+    pub async fn get_transaction(
+        &self,
+        digest: TransactionDigest,
+    ) -> Result<Option<ArchivedTransaction>> {
+        let transaction = match self
+            .get::<ArchivedTransaction>(&format!("transactions/{digest}"))
+            .await?
+        {
+            Some(transaction) => transaction,
+            None => return Ok(None),
+        };
+        if *transaction.transaction.digest() != digest
+            || *transaction.effects.transaction_digest() != digest
+        {
+            return Err(anyhow!(
+                "Archive returned a different transaction for {digest}"
+            ));
+        }
+        Ok(Some(transaction))
+    }
+
+    pub async fn get_object(
+        &self,
+        object_id: ObjectID,
+        version: SequenceNumber,
+    ) -> Result<Option<Object>> {
+        let object = match self
+            .get::<Object>(&format!("objects/{object_id}/{}", version.value()))
+            .await?
+        {
+            Some(object) => object,
+            None => return Ok(None),
+        };
+        if object.id() != object_id || object.version() != version {
+            return Err(anyhow!(
+                "Archive returned a different object for {object_id} at version {version}"
+            ));
+        }
+        Ok(Some(object))
+    }
+
+    async fn get<T: DeserializeOwned>(&self, path: &str) -> Result<Option<T>> {
+        let compression = self.manifest().await?.compression;
+        let Some(bytes) = self.get_bytes(path).await? else {
+            return Ok(None);
+        };
+        Ok(Some(bcs::from_bytes(&compression.decompress(bytes)?)?))
+    }
+
+    async fn get_bytes(&self, path: &str) -> Result<Option<Vec<u8>>> {
+        let Some(cache) = &self.cache else {
+            return self.store.get(path).await;
+        };
+        match cache.get(path).await {
+            Ok(Some(bytes)) => return Ok(Some(bytes)),
+            Ok(None) => (),
+            Err(e) => warn!("Failed to read {path} from the archive cache: {e:#}"),
+        }
+        let bytes = self.store.get(path).await?;
+        if let Some(bytes) = &bytes {
+            if let Err(e) = cache.put(path, bytes.clone()).await {
+                warn!("Failed to cache {path} from the archive: {e:#}");
+            }
+        }
+        Ok(bytes)
+    }
+}
+
+/// Writes checkpoints, their transactions and the objects those write to an archive. The caller
+/// writes the manifest once the entries it accounts for are written.
+pub struct ArchiveWriter {
+    store: ArchiveStore,
+    compression: Compression,
+}
+
+impl ArchiveWriter {
+    pub fn new(store: ArchiveStore, compression: Compression) -> Self {
+        Self { store, compression }
+    }
+
+    /// The manifest of the archive, or a new one for an empty archive. Fails if the archive was
+    /// written with another compression, since its entries must all be compressed the same way.
+    pub async fn manifest(&self) -> Result<ArchiveManifest> {
+        let Some(manifest) = read_manifest(&self.store).await? else {
+            return Ok(ArchiveManifest::new(self.compression));
+        };
+        if manifest.compression != self.compression {
+            bail!(
+                "The archive is compressed with {:?}, not {:?}",
+                manifest.compression,
+                self.compression
+            );
+        }
+        Ok(manifest)
+    }
+
+    pub async fn put_manifest(&self, manifest: &ArchiveManifest) -> Result<()> {
+        self.store
+            .put(MANIFEST_PATH, serde_json::to_vec_pretty(manifest)?)
+            .await
+    }
+
+    pub async fn put_checkpoint(&self, checkpoint: &ArchivedCheckpoint) -> Result<()> {
+        let sequence_number = checkpoint.summary.summary.sequence_number;
+        self.put(&format!("checkpoints/{sequence_number}"), checkpoint)
+            .await
+    }
+
+    pub async fn put_transaction(&self, transaction: &ArchivedTransaction) -> Result<()> {
+        let digest = transaction.transaction.digest();
+        self.put(&format!("transactions/{digest}"), transaction)
+            .await
+    }
+
+    pub async fn put_object(&self, object: &Object) -> Result<()> {
+        self.put(
+            &format!("objects/{}/{}", object.id(), object.version().value()),
+            object,
+        )
+        .await
+    }
+
+    async fn put<T: Serialize>(&self, path: &str, value: &T) -> Result<()> {
+        let bytes = self.compression.compress(bcs::to_bytes(value)?)?;
+        self.store.put(path, bytes).await
+    }
+}
+
+async fn read_manifest(store: &ArchiveStore) -> Result<Option<ArchiveManifest>> {
+    let Some(bytes) = store.get(MANIFEST_PATH).await? else {
+        return Ok(None);
+    };
+    let manifest: ArchiveManifest = serde_json::from_slice(&bytes)?;
+    if manifest.version > ARCHIVE_FORMAT_VERSION {
+        bail!(
+            "The archive has format version {}, this binary reads up to version \
+            {ARCHIVE_FORMAT_VERSION}",
+            manifest.version
+        );
+    }
+    Ok(Some(manifest))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sui_types::base_types::ExecutionDigests;
+    use sui_types::crypto::KeypairTraits;
+    use sui_types::event::Event;
+    use sui_types::gas::GasCostSummary;
+    use sui_types::messages::TransactionEffectsV1;
+    use sui_types::messages_checkpoint::{CheckpointSummary, SignedCheckpointSummary};
+    use sui_types::utils::create_fake_transaction;
+
+    #[tokio::test]
+    async fn test_directory_store() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = ArchiveStore::Directory(dir.path().to_path_buf());
+        assert_eq!(store.get("checkpoints/0").await.unwrap(), None);
+        store.put("checkpoints/0", vec![1, 2, 3]).await.unwrap();
+        store.put("checkpoints/0", vec![4, 5]).await.unwrap();
+        assert_eq!(store.get("checkpoints/0").await.unwrap(), Some(vec![4, 5]));
+    }
+
+    #[tokio::test]
+    async fn test_cache() {
+        let archive_dir = tempfile::tempdir().unwrap();
+        let cache_dir = tempfile::tempdir().unwrap();
+        let store = ArchiveStore::Directory(archive_dir.path().to_path_buf());
+        store.put("objects/a", vec![1; 100]).await.unwrap();
+        store.put("objects/b", vec![2; 100]).await.unwrap();
+
+        let cache = ArchiveCache::new(cache_dir.path().to_path_buf(), 150).unwrap();
+        let reader = ArchiveReader::from_store(store).with_cache(cache);
+        assert_eq!(
+            reader.get_bytes("objects/a").await.unwrap(),
+            Some(vec![1; 100])
+        );
+        assert!(cache_dir.path().join("objects/a").exists());
+
+        // Entries are read from the cache once cached.
+        fs::remove_file(archive_dir.path().join("objects/a")).unwrap();
+        assert_eq!(
+            reader.get_bytes("objects/a").await.unwrap(),
+            Some(vec![1; 100])
+        );
+
+        // Caching another entry goes over the limit, and evicts the oldest one.
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        assert_eq!(
+            reader.get_bytes("objects/b").await.unwrap(),
+            Some(vec![2; 100])
+        );
+        assert!(!cache_dir.path().join("objects/a").exists());
+        assert_eq!(reader.cache.as_ref().unwrap().size_bytes(), 100);
+        assert_eq!(reader.get_bytes("objects/a").await.unwrap(), None);
+    }
+
+    #[test]
+    fn test_compression() {
+        let bytes = vec![7; 1024];
+        for compression in [Compression::None, Compression::Gzip] {
+            let compressed = compression.compress(bytes.clone()).unwrap();
+            assert_eq!(compression.decompress(compressed).unwrap(), bytes);
+        }
+        assert!(Compression::Gzip.compress(bytes).unwrap().len() < 1024);
+    }
+
+    #[tokio::test]
+    async fn test_manifest() {
+        let dir = tempfile::tempdir().unwrap();
+        let writer = ArchiveWriter::new(
+            ArchiveStore::Directory(dir.path().to_path_buf()),
+            Compression::Gzip,
+        );
+        let mut manifest = writer.manifest().await.unwrap();
+        assert_eq!(manifest, ArchiveManifest::new(Compression::Gzip));
+
+        manifest.next_checkpoint = 10;
+        manifest.transactions_from_checkpoint = Some(5);
+        writer.put_manifest(&manifest).await.unwrap();
+        assert_eq!(writer.manifest().await.unwrap(), manifest);
+
+        let reader = ArchiveReader::from_store(ArchiveStore::Directory(dir.path().to_path_buf()));
+        assert_eq!(reader.manifest().await.unwrap(), &manifest);
+
+        // Entries already written cannot be read with another compression.
+        let writer = ArchiveWriter::new(
+            ArchiveStore::Directory(dir.path().to_path_buf()),
+            Compression::None,
+        );
+        assert!(writer.manifest().await.is_err());
+    }
+
+    /// A checkpoint whose only transaction creates `object`, certified by a test committee.
+    fn certified_transaction() -> (Committee, ArchivedCheckpoint, ArchivedTransaction, Object) {
+        let (committee, keys) = Committee::new_simple_test_committee();
+        let transaction = create_fake_transaction().into_inner();
+        let mut object = Object::immutable_with_id_for_testing(ObjectID::random());
+        object.previous_transaction = *transaction.digest();
+        let effects = TransactionEffects::V1(TransactionEffectsV1 {
+            transaction_digest: *transaction.digest(),
+            created: vec![(object.compute_object_reference(), object.owner)],
+            ..Default::default()
+        });
+
+        let contents =
+            CheckpointContents::new_with_causally_ordered_transactions([ExecutionDigests::new(
+                *transaction.digest(),
+                effects.digest(),
+            )]);
+        let summary = CheckpointSummary::new(
+            committee.epoch,
+            0,
+            1,
+            &contents,
+            None,
+            GasCostSummary::default(),
+            None,
+            1000,
+        );
+        let signed = keys
+            .iter()
+            .map(|k| {
+                SignedCheckpointSummary::new_from_summary(summary.clone(), k.public().into(), k)
+            })
+            .collect();
+        let checkpoint = ArchivedCheckpoint {
+            summary: CertifiedCheckpointSummary::aggregate(signed, &committee).unwrap(),
+            contents,
+        };
+        let transaction = ArchivedTransaction {
+            transaction,
+            effects,
+            events: TransactionEvents::default(),
+            checkpoint: Some((0, 1000)),
+        };
+        (committee, checkpoint, transaction, object)
+    }
+
+    #[tokio::test]
+    async fn test_verify_archived_entries() {
+        let (committee, checkpoint, transaction, object) = certified_transaction();
+        let dir = tempfile::tempdir().unwrap();
+        let writer = ArchiveWriter::new(
+            ArchiveStore::Directory(dir.path().to_path_buf()),
+            Compression::None,
+        );
+        writer.put_checkpoint(&checkpoint).await.unwrap();
+        writer.put_transaction(&transaction).await.unwrap();
+        writer.put_object(&object).await.unwrap();
+
+        // What a node falls back to once the entries are pruned from its databases.
+        let reader = ArchiveReader::from_store(ArchiveStore::Directory(dir.path().to_path_buf()));
+        let checkpoint = reader.get_checkpoint(0).await.unwrap().unwrap();
+        checkpoint.verify(&committee).unwrap();
+        let transaction = reader
+            .get_transaction(*transaction.transaction.digest())
+            .await
+            .unwrap()
+            .unwrap();
+        transaction.verify_inclusion(&checkpoint).unwrap();
+        let object = reader
+            .get_object(object.id(), object.version())
+            .await
+            .unwrap()
+            .unwrap();
+        verify_object_written_by(&object, &transaction).unwrap();
+
+        // A checkpoint certified by another committee, or whose contents were replaced.
+        let (other_committee, other_checkpoint, _, _) = certified_transaction();
+        assert!(checkpoint.verify(&other_committee).is_err());
+        let tampered_checkpoint = ArchivedCheckpoint {
+            summary: checkpoint.summary.clone(),
+            contents: other_checkpoint.contents,
+        };
+        assert!(tampered_checkpoint.verify(&committee).is_err());
+
+        // A transaction whose effects or events differ from the ones in the checkpoint.
+        let mut tampered_transaction = transaction.clone();
+        let TransactionEffects::V1(effects) = &mut tampered_transaction.effects;
+        effects.created.clear();
+        assert!(tampered_transaction.verify_inclusion(&checkpoint).is_err());
+        let mut tampered_transaction = transaction.clone();
+        tampered_transaction.checkpoint = Some((0, 2000));
+        assert!(tampered_transaction.verify_inclusion(&checkpoint).is_err());
+        let mut tampered_transaction = transaction.clone();
+        tampered_transaction.events.data.push(Event::EpochChange(1));
+        assert!(tampered_transaction.verify_inclusion(&checkpoint).is_err());
+
+        // An object version the transaction did not write.
+        let mut tampered_object = object.clone();
+        tampered_object.previous_transaction = TransactionDigest::random();
+        assert!(verify_object_written_by(&tampered_object, &transaction).is_err());
+        let other_object = Object::immutable_with_id_for_testing(ObjectID::random());
+        assert!(verify_object_written_by(&other_object, &transaction).is_err());
+    }
+}
@@ -0,0 +1,492 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Backups of the RocksDB databases of a node, taken while it is running. A backup directory has
+//! a `db-<backup id>/` directory per backup, the backup id being the time it was taken at in
+//! milliseconds since the Unix epoch. A backup has the layout of the `db-path` of the node, with
+//! a RocksDB checkpoint of each database it backs up, and a `backup-manifest.json` listing the
+//! column families of those databases with their sizes and checksums.
+//!
+//! Checkpoints are made of hard links to the SST files of the databases, which are immutable, so
+//! a backup only takes space for the files that changed since the previous one. A backup without
+//! a manifest is incomplete, and cannot be restored. A restore copies a backup next to the
+//! `db-path` it restores, and only moves it in place once its column families match the manifest.
+
+use anyhow::{anyhow, bail, ensure, Context, Result};
+use fastcrypto::encoding::{Encoding, Hex};
+use fastcrypto::hash::{HashFunction, Sha3_256};
+use rocksdb::{IteratorMode, Options, DB};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::info;
+use typed_store::rocks::RocksDB;
+
+pub type BackupId = u64;
+
+/// The version of the manifest format, bumped when it changes incompatibly.
+pub const BACKUP_MANIFEST_VERSION: u32 = 1;
+
+const BACKUP_PREFIX: &str = "db-";
+const MANIFEST_FILE: &str = "backup-manifest.json";
+
+/// What a backup contains, to check a restored database against.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct BackupManifest {
+    pub version: u32,
+    pub backup_id: BackupId,
+    /// The column families of each database of the backup, by the path of the database relative
+    /// to the root of the backup.
+    pub dbs: BTreeMap<String, BTreeMap<String, ColumnFamilyManifest>>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ColumnFamilyManifest {
+    pub num_keys: u64,
+    /// The size of the keys and values of the column family.
+    pub data_size: u64,
+    /// The size of the SST files of the column family.
+    pub sst_size: u64,
+    /// The SHA3-256 of the keys and values of the column family, in order, each prefixed by its
+    /// length, in hex.
+    pub checksum: String,
+}
+
+pub struct BackupManager {
+    backup_dir: PathBuf,
+}
+
+impl BackupManager {
+    pub fn new(backup_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            backup_dir: backup_dir.into(),
+        }
+    }
+
+    pub fn backup_dir(&self) -> &Path {
+        &self.backup_dir
+    }
+
+    pub fn backup_path(&self, backup_id: BackupId) -> PathBuf {
+        self.backup_dir.join(format!("{BACKUP_PREFIX}{backup_id}"))
+    }
+
+    /// Backs `dbs` up, each at its path relative to `db_path`, under which they must all be.
+    pub fn create_backup(
+        &self,
+        dbs: &[Arc<RocksDB>],
+        db_path: &Path,
+    ) -> Result<(PathBuf, BackupManifest)> {
+        let backup_id = self.checkpoint(dbs, db_path)?;
+        let manifest = self.write_manifest(backup_id)?;
+        Ok((self.backup_path(backup_id), manifest))
+    }
+
+    /// Starts a backup of `dbs` with RocksDB checkpoints of them, which is only complete once
+    /// [`Self::write_manifest`] is called. The databases are checkpointed one after the other, so
+    /// the caller must keep them from being written to in ways that would make the checkpoints
+    /// inconsistent with each other, until this returns.
+    pub fn checkpoint(&self, dbs: &[Arc<RocksDB>], db_path: &Path) -> Result<BackupId> {
+        fs::create_dir_all(&self.backup_dir)?;
+        let backup_id = SystemTime::now().duration_since(UNIX_EPOCH)?.as_millis() as BackupId;
+        let path = self.backup_path(backup_id);
+        ensure!(!path.exists(), "Backup {backup_id} already exists");
+        if let Err(e) = checkpoint_dbs(dbs, db_path, &path) {
+            fs::remove_dir_all(&path).ok();
+            return Err(e);
+        }
+        Ok(backup_id)
+    }
+
+    /// Completes a backup started with [`Self::checkpoint`], by writing the manifest of the
+    /// databases it checkpointed. This reads all of them, which the checkpoints keep from
+    /// changing, so it does not need the databases to be kept from being written to.
+    pub fn write_manifest(&self, backup_id: BackupId) -> Result<BackupManifest> {
+        let path = self.backup_path(backup_id);
+        let result = find_dbs(&path, Path::new("")).and_then(|relative_paths| {
+            let manifest = BackupManifest {
+                version: BACKUP_MANIFEST_VERSION,
+                backup_id,
+                dbs: relative_paths
+                    .into_iter()
+                    .map(|relative_path| {
+                        let db = open_db(&path.join(&relative_path))?;
+                        Ok((relative_path, column_family_manifests(&db)?))
+                    })
+                    .collect::<Result<_>>()?,
+            };
+            // The manifest is written last, and atomically, as it marks the backup complete.
+            let tmp_path = path.join(format!("{MANIFEST_FILE}.tmp"));
+            fs::write(&tmp_path, serde_json::to_string_pretty(&manifest)?)?;
+            fs::rename(&tmp_path, path.join(MANIFEST_FILE))?;
+            Ok(manifest)
+        });
+        match result {
+            Ok(manifest) => {
+                info!(backup_id, path = %path.display(), "Created database backup");
+                Ok(manifest)
+            }
+            Err(e) => {
+                fs::remove_dir_all(&path).ok();
+                Err(e)
+            }
+        }
+    }
+
+    /// The ids of the backups, complete or not, from the oldest to the newest.
+    pub fn list_backups(&self) -> Result<Vec<BackupId>> {
+        if !self.backup_dir.exists() {
+            return Ok(vec![]);
+        }
+        let mut backup_ids = vec![];
+        for entry in fs::read_dir(&self.backup_dir)? {
+            let backup_id = entry?
+                .file_name()
+                .to_str()
+                .and_then(|name| name.strip_prefix(BACKUP_PREFIX))
+                .and_then(|id| id.parse::<BackupId>().ok());
+            backup_ids.extend(backup_id);
+        }
+        backup_ids.sort_unstable();
+        Ok(backup_ids)
+    }
+
+    /// Deletes all backups but the `num_to_keep` newest.
+    pub fn purge_old_backups(&self, num_to_keep: usize) -> Result<()> {
+        let backup_ids = self.list_backups()?;
+        let num_to_delete = backup_ids.len().saturating_sub(num_to_keep);
+        for backup_id in backup_ids.into_iter().take(num_to_delete) {
+            let path = self.backup_path(backup_id);
+            info!(path = %path.display(), "Deleting old database backup");
+            fs::remove_dir_all(&path)?;
+        }
+        Ok(())
+    }
+
+    /// Checks that a backup is complete, and that its databases match its manifest.
+    pub fn verify_backup(&self, backup_id: BackupId) -> Result<BackupManifest> {
+        let path = self.backup_path(backup_id);
+        ensure!(path.is_dir(), "There is no backup {backup_id}");
+        let manifest = self.read_manifest(backup_id)?;
+        ensure!(
+            manifest.version == BACKUP_MANIFEST_VERSION,
+            "The manifest of backup {backup_id} has version {}, expected {}",
+            manifest.version,
+            BACKUP_MANIFEST_VERSION
+        );
+        ensure!(
+            manifest.backup_id == backup_id,
+            "The manifest of backup {backup_id} is that of backup {}",
+            manifest.backup_id
+        );
+        check_dbs(&path, &manifest).with_context(|| format!("Backup {backup_id} is corrupted"))?;
+        Ok(manifest)
+    }
+
+    /// Restores a backup, or the newest one, as the `db-path` of a node, which must not exist.
+    /// The backup is copied next to `db_path` first, and only moved there once its databases
+    /// match the manifest of the backup.
+    pub fn restore(&self, backup_id: Option<BackupId>, db_path: &Path) -> Result<BackupManifest> {
+        ensure!(
+            !db_path.exists(),
+            "Cannot restore into {}, which already exists",
+            db_path.display()
+        );
+        let backup_id = match backup_id {
+            Some(backup_id) => backup_id,
+            None => self
+                .list_backups()?
+                .into_iter()
+                .rev()
+                .find(|backup_id| self.backup_path(*backup_id).join(MANIFEST_FILE).exists())
+                .ok_or_else(|| {
+                    anyhow!(
+                        "There is no complete backup in {}",
+                        self.backup_dir.display()
+                    )
+                })?,
+        };
+        let manifest = self.verify_backup(backup_id)?;
+
+        let mut staging_path = db_path.as_os_str().to_owned();
+        staging_path.push(".restoring");
+        let staging_path = PathBuf::from(staging_path);
+        if staging_path.exists() {
+            fs::remove_dir_all(&staging_path)?;
+        }
+        let result = copy_dir(&self.backup_path(backup_id), &staging_path)
+            .and_then(|()| fs::remove_file(staging_path.join(MANIFEST_FILE)).map_err(Into::into))
+            .and_then(|()| check_dbs(&staging_path, &manifest));
+        if result.is_err() {
+            fs::remove_dir_all(&staging_path).ok();
+        }
+        result?;
+        fs::rename(&staging_path, db_path)?;
+        info!(backup_id, path = ?db_path, "Restored database backup");
+        Ok(manifest)
+    }
+
+    fn read_manifest(&self, backup_id: BackupId) -> Result<BackupManifest> {
+        let path = self.backup_path(backup_id).join(MANIFEST_FILE);
+        let content = fs::read_to_string(&path).with_context(|| {
+            format!("Cannot read the manifest of backup {backup_id}, which may be incomplete")
+        })?;
+        serde_json::from_str(&content)
+            .with_context(|| format!("Invalid backup manifest {}", path.display()))
+    }
+}
+
+/// Creates RocksDB checkpoints of `dbs`, in order, under `backup_path`, each at its path relative
+/// to `db_path`.
+fn checkpoint_dbs(dbs: &[Arc<RocksDB>], db_path: &Path, backup_path: &Path) -> Result<()> {
+    for db in dbs {
+        let relative_path = db.path().strip_prefix(db_path).with_context(|| {
+            format!(
+                "Database {} is not under {}",
+                db.path().display(),
+                db_path.display()
+            )
+        })?;
+        let path = backup_path.join(relative_path);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        db.checkpoint(&path)
+            .map_err(|e| anyhow!("Cannot checkpoint {}: {e}", db.path().display()))?;
+    }
+    Ok(())
+}
+
+/// The paths, relative to `root`, of the databases under `root.join(relative_path)`, which may
+/// be nested in each other.
+fn find_dbs(root: &Path, relative_path: &Path) -> Result<Vec<String>> {
+    let path = root.join(relative_path);
+    let mut dbs = vec![];
+    if path.join("CURRENT").is_file() {
+        dbs.push(
+            relative_path
+                .to_str()
+                .ok_or_else(|| anyhow!("Invalid database path {}", relative_path.display()))?
+                .to_string(),
+        );
+    }
+    for entry in fs::read_dir(&path)? {
+        let entry = entry?;
+        if entry.file_type()?.is_dir() {
+            dbs.extend(find_dbs(root, &relative_path.join(entry.file_name()))?);
+        }
+    }
+    Ok(dbs)
+}
+
+/// Checks that the databases under `root` match `manifest`.
+fn check_dbs(root: &Path, manifest: &BackupManifest) -> Result<()> {
+    for (relative_path, expected) in &manifest.dbs {
+        let db = open_db(&root.join(relative_path))
+            .with_context(|| format!("Cannot open database {relative_path}"))?;
+        let column_families = column_family_manifests(&db)?;
+        for (name, expected) in expected {
+            let Some(actual) = column_families.get(name) else {
+                bail!("Column family {name} is missing from database {relative_path}");
+            };
+            ensure!(
+                actual.num_keys == expected.num_keys
+                    && actual.data_size == expected.data_size
+                    && actual.checksum == expected.checksum,
+                "Column family {name} of database {relative_path} does not match the manifest"
+            );
+        }
+        if let Some(name) = column_families
+            .keys()
+            .find(|name| !expected.contains_key(*name))
+        {
+            bail!("Column family {name} of database {relative_path} is not in the manifest");
+        }
+    }
+    Ok(())
+}
+
+fn copy_dir(from: &Path, to: &Path) -> Result<()> {
+    fs::create_dir_all(to)?;
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        let target = to.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_dir(&entry.path(), &target)?;
+        } else {
+            fs::copy(entry.path(), target)?;
+        }
+    }
+    Ok(())
+}
+
+fn open_db(path: &Path) -> Result<DB> {
+    let options = Options::default();
+    let column_families = DB::list_cf(&options, path)?;
+    Ok(DB::open_cf_for_read_only(
+        &options,
+        path,
+        &column_families,
+        false,
+    )?)
+}
+
+fn column_family_manifests(db: &DB) -> Result<BTreeMap<String, ColumnFamilyManifest>> {
+    let path = db.path().to_path_buf();
+    DB::list_cf(&Options::default(), path)?
+        .into_iter()
+        .map(|name| {
+            let cf = db
+                .cf_handle(&name)
+                .ok_or_else(|| anyhow!("Column family {name} is not open"))?;
+            let mut hasher = Sha3_256::default();
+            let (mut num_keys, mut data_size) = (0, 0);
+            for entry in db.iterator_cf(cf, IteratorMode::Start) {
+                let (key, value) = entry?;
+                for bytes in [&key, &value] {
+                    hasher.update((bytes.len() as u64).to_le_bytes());
+                    hasher.update(bytes);
+                }
+                num_keys += 1;
+                data_size += (key.len() + value.len()) as u64;
+            }
+            let sst_size = db
+                .property_int_value_cf(cf, "rocksdb.total-sst-files-size")?
+                .unwrap_or_default();
+            let manifest = ColumnFamilyManifest {
+                num_keys,
+                data_size,
+                sst_size,
+                checksum: Hex::encode(hasher.finalize().digest),
+            };
+            Ok((name, manifest))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rocksdb::WriteOptions;
+    use typed_store::rocks::{open_cf, MetricConf};
+
+    const DBS: [&str; 2] = ["store", "store/epoch_0"];
+
+    fn write_dbs(db_path: &Path, keys: std::ops::Range<u32>) -> Vec<Arc<RocksDB>> {
+        DBS.iter()
+            .map(|relative_path| {
+                let db = open_cf(
+                    db_path.join(relative_path),
+                    None,
+                    MetricConf::default(),
+                    &["first", "second"],
+                )
+                .unwrap();
+                for name in ["first", "second"] {
+                    let cf = db.cf_handle(name).unwrap();
+                    for key in keys.clone() {
+                        db.put_cf(
+                            &cf,
+                            key.to_be_bytes(),
+                            format!("{relative_path} {name} {key}"),
+                            &WriteOptions::default(),
+                        )
+                        .unwrap();
+                    }
+                }
+                db
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_backup_and_restore() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("db");
+        let dbs = write_dbs(&db_path, 0..100);
+        let backups = BackupManager::new(dir.path().join("backups"));
+
+        let (first_path, first) = backups.create_backup(&dbs, &db_path).unwrap();
+        assert_eq!(first_path, backups.backup_path(first.backup_id));
+        assert_eq!(
+            first.dbs.keys().map(String::as_str).collect::<Vec<_>>(),
+            DBS
+        );
+        assert_eq!(first.dbs["store"]["first"].num_keys, 100);
+        assert!(first.dbs["store/epoch_0"].contains_key("default"));
+
+        drop(dbs);
+        std::thread::sleep(std::time::Duration::from_millis(2));
+        let dbs = write_dbs(&db_path, 100..150);
+        let (_, second) = backups.create_backup(&dbs, &db_path).unwrap();
+        assert_eq!(second.dbs["store/epoch_0"]["second"].num_keys, 150);
+        assert_ne!(
+            first.dbs["store"]["second"].checksum,
+            second.dbs["store"]["second"].checksum
+        );
+        assert_eq!(
+            backups.list_backups().unwrap(),
+            vec![first.backup_id, second.backup_id]
+        );
+
+        let restored = dir.path().join("restored");
+        assert_eq!(
+            backups.restore(Some(first.backup_id), &restored).unwrap(),
+            first
+        );
+        assert!(!restored.join(MANIFEST_FILE).exists());
+        for (relative_path, column_families) in &first.dbs {
+            let db = open_db(&restored.join(relative_path)).unwrap();
+            assert_eq!(&column_family_manifests(&db).unwrap(), column_families);
+        }
+
+        // The database a backup is restored into must not exist.
+        assert!(backups.restore(None, &restored).is_err());
+
+        backups.purge_old_backups(1).unwrap();
+        assert!(backups.verify_backup(first.backup_id).is_err());
+        assert_eq!(backups.list_backups().unwrap(), vec![second.backup_id]);
+    }
+
+    #[test]
+    fn test_restore_rejects_mismatched_manifest() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("db");
+        let dbs = write_dbs(&db_path, 0..10);
+        let backups = BackupManager::new(dir.path().join("backups"));
+        let (path, mut manifest) = backups.create_backup(&dbs, &db_path).unwrap();
+
+        manifest
+            .dbs
+            .get_mut("store")
+            .unwrap()
+            .get_mut("first")
+            .unwrap()
+            .checksum = Hex::encode([0; 32]);
+        fs::write(
+            path.join(MANIFEST_FILE),
+            serde_json::to_string(&manifest).unwrap(),
+        )
+        .unwrap();
+
+        let restored = dir.path().join("restored");
+        assert!(backups.restore(None, &restored).is_err());
+        assert!(!restored.exists());
+    }
+
+    #[test]
+    fn test_incomplete_backup_is_not_restored() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("db");
+        let dbs = write_dbs(&db_path, 0..10);
+        let backups = BackupManager::new(dir.path().join("backups"));
+        let (path, manifest) = backups.create_backup(&dbs, &db_path).unwrap();
+        fs::remove_file(path.join(MANIFEST_FILE)).unwrap();
+
+        assert!(backups.verify_backup(manifest.backup_id).is_err());
+        assert!(backups.restore(None, &dir.path().join("restored")).is_err());
+    }
+}
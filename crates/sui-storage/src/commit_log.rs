@@ -0,0 +1,196 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! A log of the transactions committed to the authority store, archived continuously so that a
+//! node whose database is lost or corrupted can be restored from its last backup and the log
+//! written since, rather than synced again from genesis. The log is kept in an [`ArchiveStore`]
+//! with the following layout, each segment BCS encoded and compressed as the manifest says:
+//!
+//! - `MANIFEST`: the JSON encoded [`CommitLogManifest`]
+//! - `segments/<segment number>`: a [`CommitLogSegment`]
+//!
+//! Each record holds everything the commit wrote, so replaying the records in order, from any
+//! point before the backup, brings the database to the state it had when the last segment was
+//! written. Records already reflected in the database are skipped when replayed.
+
+use crate::archive::{ArchiveStore, Compression};
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+use sui_types::base_types::TransactionDigest;
+use sui_types::messages::{Transaction, TransactionEffects};
+use sui_types::temporary_store::InnerTemporaryStore;
+
+const MANIFEST_PATH: &str = "MANIFEST";
+
+/// The version of the format of the log, bumped when it changes incompatibly.
+pub const COMMIT_LOG_FORMAT_VERSION: u32 = 1;
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub enum CommitLogRecord {
+    /// A transaction was executed and its effects written to the store.
+    Commit {
+        transaction: Transaction,
+        effects: TransactionEffects,
+        store: InnerTemporaryStore,
+    },
+    /// A transaction that did not make it into a checkpoint was reverted at the end of the epoch.
+    Revert(TransactionDigest),
+}
+
+/// The records committed between two uploads, in the order they were committed.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct CommitLogSegment {
+    pub records: Vec<CommitLogRecord>,
+}
+
+/// Describes the contents of a log. It is written after the segments it accounts for.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct CommitLogManifest {
+    pub version: u32,
+    /// How the segments are compressed.
+    pub compression: Compression,
+    /// The segments before this one are in the log.
+    pub next_segment: u64,
+}
+
+impl CommitLogManifest {
+    pub fn new(compression: Compression) -> Self {
+        Self {
+            version: COMMIT_LOG_FORMAT_VERSION,
+            compression,
+            next_segment: 0,
+        }
+    }
+}
+
+/// Appends segments to a log. The caller writes the manifest once the segments it accounts for
+/// are written.
+pub struct CommitLogWriter {
+    store: ArchiveStore,
+    compression: Compression,
+}
+
+impl CommitLogWriter {
+    pub fn new(store: ArchiveStore, compression: Compression) -> Self {
+        Self { store, compression }
+    }
+
+    /// The manifest of the log, or a new one for an empty log. Fails if the log was written
+    /// with another compression.
+    pub async fn manifest(&self) -> Result<CommitLogManifest> {
+        let Some(manifest) = read_manifest(&self.store).await? else {
+            return Ok(CommitLogManifest::new(self.compression));
+        };
+        if manifest.compression != self.compression {
+            bail!(
+                "The commit log is compressed with {:?}, not {:?}",
+                manifest.compression,
+                self.compression
+            );
+        }
+        Ok(manifest)
+    }
+
+    pub async fn put_manifest(&self, manifest: &CommitLogManifest) -> Result<()> {
+        self.store
+            .put(MANIFEST_PATH, serde_json::to_vec_pretty(manifest)?)
+            .await
+    }
+
+    pub async fn put_segment(&self, segment_number: u64, segment: &CommitLogSegment) -> Result<()> {
+        let bytes = self.compression.compress(bcs::to_bytes(segment)?)?;
+        self.store
+            .put(&format!("segments/{segment_number}"), bytes)
+            .await
+    }
+}
+
+pub struct CommitLogReader {
+    store: ArchiveStore,
+}
+
+impl CommitLogReader {
+    pub fn new(store: ArchiveStore) -> Self {
+        Self { store }
+    }
+
+    /// The manifest of the log, failing if there is none.
+    pub async fn manifest(&self) -> Result<CommitLogManifest> {
+        match read_manifest(&self.store).await? {
+            Some(manifest) => Ok(manifest),
+            None => bail!("The commit log has no manifest"),
+        }
+    }
+
+    pub async fn get_segment(
+        &self,
+        manifest: &CommitLogManifest,
+        segment_number: u64,
+    ) -> Result<CommitLogSegment> {
+        if segment_number >= manifest.next_segment {
+            bail!("Segment {segment_number} is not in the commit log");
+        }
+        let Some(bytes) = self.store.get(&format!("segments/{segment_number}")).await? else {
+            bail!("Segment {segment_number} is missing from the commit log");
+        };
+        Ok(bcs::from_bytes(&manifest.compression.decompress(bytes)?)?)
+    }
+}
+
+async fn read_manifest(store: &ArchiveStore) -> Result<Option<CommitLogManifest>> {
+    let Some(bytes) = store.get(MANIFEST_PATH).await? else {
+        return Ok(None);
+    };
+    let manifest: CommitLogManifest = serde_json::from_slice(&bytes)?;
+    if manifest.version > COMMIT_LOG_FORMAT_VERSION {
+        bail!(
+            "The commit log has format version {}, this binary reads up to version \
+            {COMMIT_LOG_FORMAT_VERSION}",
+            manifest.version
+        );
+    }
+    Ok(Some(manifest))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_commit_log() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = || ArchiveStore::Directory(dir.path().to_path_buf());
+        let writer = CommitLogWriter::new(store(), Compression::Gzip);
+        let reader = CommitLogReader::new(store());
+        assert!(reader.manifest().await.is_err());
+
+        let mut manifest = writer.manifest().await.unwrap();
+        assert_eq!(manifest, CommitLogManifest::new(Compression::Gzip));
+        let digests = [TransactionDigest::random(), TransactionDigest::random()];
+        let segment = CommitLogSegment {
+            records: digests
+                .iter()
+                .copied()
+                .map(CommitLogRecord::Revert)
+                .collect(),
+        };
+        writer.put_segment(0, &segment).await.unwrap();
+
+        // Segments are only read once the manifest accounts for them.
+        assert!(reader.get_segment(&manifest, 0).await.is_err());
+        manifest.next_segment = 1;
+        writer.put_manifest(&manifest).await.unwrap();
+        let manifest = reader.manifest().await.unwrap();
+        let records = reader.get_segment(&manifest, 0).await.unwrap().records;
+        assert!(matches!(
+            records.as_slice(),
+            [CommitLogRecord::Revert(a), CommitLogRecord::Revert(b)] if [*a, *b] == digests
+        ));
+
+        assert!(CommitLogWriter::new(store(), Compression::None)
+            .manifest()
+            .await
+            .is_err());
+    }
+}
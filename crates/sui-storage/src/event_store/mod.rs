@@ -36,6 +36,7 @@ use sui_types::error::SuiError;
 use sui_types::error::SuiError::{StorageCorruptedFieldError, StorageMissingFieldError};
 use sui_types::event::{BalanceChangeType, Event, EventID};
 use sui_types::event::{EventEnvelope, EventType};
+use sui_types::messages_checkpoint::CheckpointSequenceNumber;
 use sui_types::object::Owner;
 
 pub mod sql;
@@ -94,6 +95,12 @@ pub struct StoredEvent {
 }
 
 impl StoredEvent {
+    /// The sequence number of the transaction that emitted the event, and the number of the
+    /// event in the transaction, which together are the position of the event in queries.
+    pub fn position(&self) -> (i64, i64) {
+        (self.seq_num, self.id.event_seq)
+    }
+
     pub fn into_move_event(self) -> Result<SuiEvent, anyhow::Error> {
         let package_id = self.package_id()?;
         let transaction_module = self.transaction_module()?;
@@ -527,6 +534,46 @@ pub trait EventStore {
         limit: usize,
         descending: bool,
     ) -> Result<Vec<StoredEvent>, SuiError>;
+
+    /// Returns at most `limit` coin balance change events of the coins owned by `owner`, only of
+    /// coins of `coin_type` if given, sorted in time order defined by the descending parameter.
+    async fn coin_balance_changes_by_owner(
+        &self,
+        owner: &Owner,
+        coin_type: Option<&str>,
+        tx_seq: i64,
+        event_seq: i64,
+        limit: usize,
+        descending: bool,
+    ) -> Result<Vec<StoredEvent>, SuiError>;
+
+    /// Records the checkpoints of a batch of transactions, given by their sequence numbers, so
+    /// that the events they emitted can be queried by checkpoint. Transactions already recorded
+    /// are ignored.
+    ///
+    /// Returns Ok(rows_affected).
+    async fn add_transaction_checkpoints(
+        &self,
+        checkpoints: &[(u64, CheckpointSequenceNumber)],
+    ) -> Result<u64, SuiError>;
+
+    /// Returns the highest checkpoint recorded with `add_transaction_checkpoints`, if any.
+    async fn highest_recorded_checkpoint(
+        &self,
+    ) -> Result<Option<CheckpointSequenceNumber>, SuiError>;
+
+    /// Returns at most `limit` events emitted by the transactions of checkpoints
+    /// `[start_checkpoint, end_checkpoint)`, sorted in time order defined by the descending
+    /// parameter.
+    async fn events_by_checkpoint(
+        &self,
+        start_checkpoint: CheckpointSequenceNumber,
+        end_checkpoint: CheckpointSequenceNumber,
+        tx_seq: i64,
+        event_seq: i64,
+        limit: usize,
+        descending: bool,
+    ) -> Result<Vec<StoredEvent>, SuiError>;
 }
 
 /// EventStoreType contains different implementations of EventStores, but implements the EventStore trait.
@@ -23,6 +23,7 @@ use tracing::{info, instrument, log, warn};
 use sui_types::base_types::SuiAddress;
 use sui_types::error::SuiError;
 use sui_types::event::Event;
+use sui_types::messages_checkpoint::CheckpointSequenceNumber;
 use sui_types::object::Owner;
 
 use super::*;
@@ -88,6 +89,18 @@ const SQL_INSERT_TX: &str =
     package_id, module_name, object_id, object_type, fields, move_event_name, contents, sender,  \
     recipient) ";
 
+/// The checkpoints of transactions, by their sequence numbers, which events are queried by
+/// checkpoint through.
+const SQL_CREATE_TRANSACTION_CHECKPOINTS: &str =
+    "CREATE TABLE IF NOT EXISTS transaction_checkpoints(seq_num INTEGER PRIMARY KEY, \
+    checkpoint INTEGER NOT NULL);";
+
+const SQL_INSERT_TRANSACTION_CHECKPOINTS: &str =
+    "INSERT OR IGNORE INTO transaction_checkpoints (seq_num, checkpoint) ";
+
+const CHECKPOINT_RANGE_CONDITION: &str = "seq_num IN (SELECT seq_num FROM \
+    transaction_checkpoints WHERE checkpoint >= ? AND checkpoint < ?)";
+
 const INDEXED_COLUMNS: &[&str] = &[
     "seq_num",
     "event_num",
@@ -221,6 +234,18 @@ impl SqlEventStore {
             .await
             .map_err(convert_sqlx_err)?;
 
+        self.pool
+            .execute(SQL_CREATE_TRANSACTION_CHECKPOINTS)
+            .await
+            .map_err(convert_sqlx_err)?;
+        self.pool
+            .execute(
+                "CREATE INDEX IF NOT EXISTS checkpoint_idx on transaction_checkpoints (checkpoint)",
+            )
+            .await
+            .map_err(convert_sqlx_err)?;
+        info!("SQLite transaction_checkpoints table is initialized");
+
         Ok(())
     }
 
@@ -670,6 +695,115 @@ impl EventStore for SqlEventStore {
             .map_err(convert_sqlx_err)?;
         Ok(rows)
     }
+
+    #[instrument(level = "debug", skip_all, err)]
+    async fn coin_balance_changes_by_owner(
+        &self,
+        owner: &Owner,
+        coin_type: Option<&str>,
+        tx_seq: i64,
+        event_seq: i64,
+        limit: usize,
+        descending: bool,
+    ) -> Result<Vec<StoredEvent>, SuiError> {
+        let _guard = self.query_lock.read().await;
+        let mut causes = vec![
+            ("event_type", Comparator::Equal),
+            ("recipient", Comparator::Equal),
+        ];
+        if coin_type.is_some() {
+            causes.push(("object_type", Comparator::Equal));
+        }
+        let query = get_event_query(causes, descending);
+        let owner_str =
+            serde_json::to_string(owner).map_err(|e| SuiError::OwnerFailedToSerialize {
+                error: (e.to_string()),
+            })?;
+        let mut query = sqlx::query(&query)
+            .persistent(true)
+            .bind(tx_seq)
+            .bind(tx_seq)
+            .bind(event_seq)
+            .bind(EventType::CoinBalanceChange as u16)
+            .bind(owner_str);
+        if let Some(coin_type) = coin_type {
+            query = query.bind(coin_type);
+        }
+        let rows = query
+            .bind(limit as i64)
+            .map(StoredEvent::from)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(convert_sqlx_err)?;
+        Ok(rows)
+    }
+
+    #[instrument(level = "debug", skip_all, err)]
+    async fn add_transaction_checkpoints(
+        &self,
+        checkpoints: &[(u64, CheckpointSequenceNumber)],
+    ) -> Result<u64, SuiError> {
+        let _guard = self.query_lock.read().await;
+        let mut rows_affected = 0;
+
+        for chunk in checkpoints.chunks(MAX_INSERT_BATCH) {
+            let mut query_builder = QueryBuilder::new(SQL_INSERT_TRANSACTION_CHECKPOINTS);
+            query_builder.push_values(chunk, |mut b, (seq_num, checkpoint)| {
+                b.push_bind(*seq_num as i64).push_bind(*checkpoint as i64);
+            });
+            let res = query_builder
+                .build()
+                .execute(&self.pool)
+                .await
+                .map_err(convert_sqlx_err)?;
+            rows_affected += res.rows_affected();
+        }
+
+        Ok(rows_affected)
+    }
+
+    #[instrument(level = "debug", skip_all, err)]
+    async fn highest_recorded_checkpoint(
+        &self,
+    ) -> Result<Option<CheckpointSequenceNumber>, SuiError> {
+        let _guard = self.query_lock.read().await;
+        let result = sqlx::query("SELECT MAX(checkpoint) FROM transaction_checkpoints")
+            .fetch_one(&self.pool)
+            .await
+            .map_err(convert_sqlx_err)?;
+        let checkpoint: Option<i64> = result.get(0);
+        Ok(checkpoint.map(|checkpoint| checkpoint as CheckpointSequenceNumber))
+    }
+
+    #[instrument(level = "debug", skip_all, err)]
+    async fn events_by_checkpoint(
+        &self,
+        start_checkpoint: CheckpointSequenceNumber,
+        end_checkpoint: CheckpointSequenceNumber,
+        tx_seq: i64,
+        event_seq: i64,
+        limit: usize,
+        descending: bool,
+    ) -> Result<Vec<StoredEvent>, SuiError> {
+        let _guard = self.query_lock.read().await;
+        let query = get_event_query_with_conditions(
+            vec![CHECKPOINT_RANGE_CONDITION.to_string()],
+            descending,
+        );
+        let rows = sqlx::query(&query)
+            .persistent(true)
+            .bind(tx_seq)
+            .bind(tx_seq)
+            .bind(event_seq)
+            .bind(start_checkpoint as i64)
+            .bind(end_checkpoint as i64)
+            .bind(limit as i64)
+            .map(StoredEvent::from)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(convert_sqlx_err)?;
+        Ok(rows)
+    }
 }
 
 fn convert_sqlx_err(err: sqlx::Error) -> SuiError {
@@ -677,6 +811,14 @@ fn convert_sqlx_err(err: sqlx::Error) -> SuiError {
 }
 
 fn get_event_query(causes: Vec<(&str, Comparator)>, descending: bool) -> String {
+    let conditions = causes
+        .iter()
+        .map(|(cause, cmp)| format!("{cause} {cmp} ?"))
+        .collect();
+    get_event_query_with_conditions(conditions, descending)
+}
+
+fn get_event_query_with_conditions(conditions: Vec<String>, descending: bool) -> String {
     let (seq_num_cmp, event_num_cmp, order) = if descending {
         (Comparator::LessThan, Comparator::LessThanOrEq, "DESC")
     } else {
@@ -685,15 +827,10 @@ fn get_event_query(causes: Vec<(&str, Comparator)>, descending: bool) -> String
     let mut query = format!(
         "SELECT * FROM events WHERE (seq_num {seq_num_cmp} ? OR (seq_num = ? AND event_num {event_num_cmp} ?))"
     );
-    if !causes.is_empty() {
+    if !conditions.is_empty() {
         query.push_str(" AND ");
     }
-    let causes = causes
-        .iter()
-        .map(|(cause, cmp)| format!("{cause} {cmp} ?"))
-        .collect::<Vec<_>>()
-        .join(" AND ");
-    query.push_str(&causes);
+    query.push_str(&conditions.join(" AND "));
     query.push_str(&format!(
         " ORDER BY seq_num {order}, event_num {order} LIMIT ?"
     ));
@@ -727,6 +864,7 @@ mod tests {
     use move_core_types::{account_address::AccountAddress, identifier::Identifier};
 
     use sui_types::event::EventEnvelope;
+    use sui_types::gas_coin::GAS;
 
     use super::test_utils;
     use super::*;
@@ -886,6 +1024,121 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_eventstore_query_by_checkpoint() -> Result<(), SuiError> {
+        telemetry_subscribers::init_for_testing();
+
+        // Initialize store
+        let db = SqlEventStore::new_memory_only_not_prod().await?;
+        db.initialize().await?;
+
+        // Ten transactions with two events each
+        let to_insert = (1..11u64)
+            .into_iter()
+            .flat_map(|seq_num| {
+                (0..2u64).into_iter().map(move |event_num| {
+                    test_utils::new_test_newobj_event(
+                        1_000_000,
+                        TransactionDigest::random(),
+                        seq_num,
+                        event_num, // event_num
+                        None,
+                        None,
+                        None,
+                    )
+                })
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(db.add_events(&to_insert).await?, 20);
+        assert_eq!(db.highest_recorded_checkpoint().await?, None);
+
+        // Two transactions per checkpoint, in checkpoints 0 to 4
+        let checkpoints = (1..11u64)
+            .map(|seq_num| (seq_num, (seq_num - 1) / 2))
+            .collect::<Vec<_>>();
+        assert_eq!(db.add_transaction_checkpoints(&checkpoints).await?, 10);
+        // Recording the same transactions again is a no-op
+        assert_eq!(db.add_transaction_checkpoints(&checkpoints[8..]).await?, 0);
+        assert_eq!(db.highest_recorded_checkpoint().await?, Some(4));
+
+        // Checkpoints 1 and 2 hold transactions 3 to 6
+        let events = db.events_by_checkpoint(1, 3, -1, 0, 100, false).await?;
+        assert_eq!(events.len(), 8);
+        assert_eq!(events.first().unwrap().seq_num, 3);
+        assert_eq!(events.last().unwrap().seq_num, 6);
+
+        let events = db
+            .events_by_checkpoint(1, 3, i64::MAX, 0, 100, true)
+            .await?;
+        assert_eq!(events.len(), 8);
+        assert_eq!(events.first().unwrap().seq_num, 6);
+
+        // Paging from the cursor
+        let events = db.events_by_checkpoint(1, 3, 4, 1, 100, false).await?;
+        assert_eq!(events.len(), 5);
+        let events = db.events_by_checkpoint(1, 3, -1, 0, 3, false).await?;
+        assert_eq!(events.len(), 3);
+
+        // The end of the range is exclusive
+        assert!(db
+            .events_by_checkpoint(5, 10, -1, 0, 100, false)
+            .await?
+            .is_empty());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_eventstore_query_coin_balance_changes_by_owner() -> Result<(), SuiError> {
+        telemetry_subscribers::init_for_testing();
+
+        // Initialize store
+        let db = SqlEventStore::new_memory_only_not_prod().await?;
+        db.initialize().await?;
+
+        let owner = Owner::AddressOwner(SuiAddress::random_for_testing_only());
+        let to_insert = vec![
+            test_utils::new_test_balance_change_event(1_000_000, 1, 0, None, None, Some(owner)),
+            test_utils::new_test_balance_change_event(1_001_000, 2, 0, None, None, None),
+            test_utils::new_test_transfer_event(
+                1_002_000,
+                TransactionDigest::random(),
+                3,
+                0, // event_num
+                1,
+                "0x2::test:Object",
+                None,
+                None,
+                Some(owner),
+            ),
+            test_utils::new_test_balance_change_event(1_003_000, 3, 1, None, None, Some(owner)),
+        ];
+        assert_eq!(db.add_events(&to_insert).await?, 4);
+
+        let events = db
+            .coin_balance_changes_by_owner(&owner, None, 0, 0, 10, false)
+            .await?;
+        assert_eq!(events.len(), 2);
+        test_queried_event_vs_test_envelope(&events[0], &to_insert[0]);
+        test_queried_event_vs_test_envelope(&events[1], &to_insert[3]);
+
+        let events = db
+            .coin_balance_changes_by_owner(&owner, None, i64::MAX, i64::MAX, 1, true)
+            .await?;
+        assert_eq!(events.len(), 1);
+        test_queried_event_vs_test_envelope(&events[0], &to_insert[3]);
+
+        let sui = GAS::type_().to_string();
+        let events = db
+            .coin_balance_changes_by_owner(&owner, Some(&sui), 0, 0, 10, false)
+            .await?;
+        assert_eq!(events.len(), 2);
+        let events = db
+            .coin_balance_changes_by_owner(&owner, Some("0x2::test::TEST"), 0, 0, 10, false)
+            .await?;
+        assert!(events.is_empty());
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_eventstore_transfers_tx_read() -> Result<(), SuiError> {
         telemetry_subscribers::init_for_testing();
@@ -0,0 +1,386 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Formal snapshots of the live object set at the end of an epoch, kept in an [`ArchiveStore`]
+//! with the following layout, each entry BCS encoded and compressed as the manifest says:
+//!
+//! - `snapshots/<epoch>/MANIFEST`: the JSON encoded [`FormalSnapshotManifest`]
+//! - `snapshots/<epoch>/epochs`: the last checkpoint of each epoch up to the snapshot's
+//! - `snapshots/<epoch>/checkpoint`: the last checkpoint of the epoch, with its contents
+//! - `snapshots/<epoch>/objects/<part>`: a part of the live objects
+//!
+//! A snapshot is only written in full once its manifest is. It needs no more trust than the
+//! committee of genesis: [`FormalSnapshotReader::verify_epochs`] verifies the chain of
+//! committees handed off from genesis to the epoch of the snapshot, and the live objects are
+//! verified against the root state digest that the last checkpoint of the epoch commits to, with
+//! [`verify_live_object_set`].
+
+use crate::archive::{ArchiveStore, ArchivedCheckpoint, Compression};
+use anyhow::{anyhow, bail, ensure, Result};
+use fastcrypto::hash::MultisetHash;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use sui_types::accumulator::Accumulator;
+use sui_types::committee::{Committee, EpochId};
+use sui_types::messages_checkpoint::{
+    CertifiedCheckpointSummary, ECMHLiveObjectSetDigest, VerifiedCheckpoint,
+};
+use sui_types::object::Object;
+
+/// The version of the layout of formal snapshots, recorded in their manifest.
+pub const FORMAL_SNAPSHOT_FORMAT_VERSION: u32 = 1;
+
+/// Describes a formal snapshot. It is written after the entries it accounts for.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct FormalSnapshotManifest {
+    pub version: u32,
+    /// The live objects are the ones at the end of this epoch.
+    pub epoch: EpochId,
+    /// How the entries are compressed.
+    pub compression: Compression,
+    /// The live objects are in the parts numbered from 0 to this one, excluded.
+    pub num_parts: u64,
+    pub num_objects: u64,
+}
+
+/// A formal snapshot whose epochs have been verified from the committee of genesis.
+pub struct VerifiedFormalSnapshot {
+    /// The last checkpoint of each epoch up to the one of the snapshot.
+    pub epoch_checkpoints: Vec<VerifiedCheckpoint>,
+    /// The committees of the epochs from genesis to the one after the snapshot.
+    pub committees: Vec<Committee>,
+    /// The last checkpoint of the epoch of the snapshot.
+    pub checkpoint: ArchivedCheckpoint,
+    /// The digest of the live objects that the last checkpoint commits to.
+    pub root_state_digest: ECMHLiveObjectSetDigest,
+}
+
+/// Checks that `accumulator`, the accumulation of the digests of the live objects of a
+/// snapshot, matches the root state digest of its epoch.
+pub fn verify_live_object_set(
+    accumulator: &Accumulator,
+    root_state_digest: &ECMHLiveObjectSetDigest,
+) -> Result<()> {
+    let digest = ECMHLiveObjectSetDigest::from(accumulator.digest());
+    ensure!(
+        digest == *root_state_digest,
+        "The live objects have digest {:?}, the checkpoint commits to {:?}",
+        digest.digest,
+        root_state_digest.digest
+    );
+    Ok(())
+}
+
+fn snapshot_path(epoch: EpochId, entry: &str) -> String {
+    format!("snapshots/{epoch}/{entry}")
+}
+
+/// Writes a formal snapshot of `epoch`. [`Self::finish`] writes the manifest once everything
+/// else is written.
+pub struct FormalSnapshotWriter {
+    store: ArchiveStore,
+    compression: Compression,
+    epoch: EpochId,
+    num_parts: u64,
+    num_objects: u64,
+}
+
+impl FormalSnapshotWriter {
+    pub fn new(store: ArchiveStore, compression: Compression, epoch: EpochId) -> Self {
+        Self {
+            store,
+            compression,
+            epoch,
+            num_parts: 0,
+            num_objects: 0,
+        }
+    }
+
+    /// Whether the snapshot of the epoch is already written in full.
+    pub async fn exists(&self) -> Result<bool> {
+        Ok(self
+            .store
+            .get(&snapshot_path(self.epoch, "MANIFEST"))
+            .await?
+            .is_some())
+    }
+
+    pub async fn put_epochs(&self, epoch_checkpoints: &[CertifiedCheckpointSummary]) -> Result<()> {
+        self.put("epochs", &epoch_checkpoints).await
+    }
+
+    pub async fn put_checkpoint(&self, checkpoint: &ArchivedCheckpoint) -> Result<()> {
+        self.put("checkpoint", checkpoint).await
+    }
+
+    /// Writes the next part of the live objects.
+    pub async fn put_objects(&mut self, objects: &[Object]) -> Result<()> {
+        self.put(&format!("objects/{}", self.num_parts), &objects)
+            .await?;
+        self.num_parts += 1;
+        self.num_objects += objects.len() as u64;
+        Ok(())
+    }
+
+    pub async fn finish(self) -> Result<FormalSnapshotManifest> {
+        let manifest = FormalSnapshotManifest {
+            version: FORMAL_SNAPSHOT_FORMAT_VERSION,
+            epoch: self.epoch,
+            compression: self.compression,
+            num_parts: self.num_parts,
+            num_objects: self.num_objects,
+        };
+        self.store
+            .put(
+                &snapshot_path(self.epoch, "MANIFEST"),
+                serde_json::to_vec_pretty(&manifest)?,
+            )
+            .await?;
+        Ok(manifest)
+    }
+
+    async fn put<T: Serialize>(&self, entry: &str, value: &T) -> Result<()> {
+        let bytes = self.compression.compress(bcs::to_bytes(value)?)?;
+        self.store
+            .put(&snapshot_path(self.epoch, entry), bytes)
+            .await
+    }
+}
+
+pub struct FormalSnapshotReader {
+    store: ArchiveStore,
+    manifest: FormalSnapshotManifest,
+}
+
+impl FormalSnapshotReader {
+    /// Opens the snapshot of `epoch`, which fails unless it is written in full.
+    pub async fn open(store: ArchiveStore, epoch: EpochId) -> Result<Self> {
+        let bytes = store
+            .get(&snapshot_path(epoch, "MANIFEST"))
+            .await?
+            .ok_or_else(|| anyhow!("There is no formal snapshot of epoch {epoch}"))?;
+        let manifest: FormalSnapshotManifest = serde_json::from_slice(&bytes)?;
+        if manifest.version > FORMAL_SNAPSHOT_FORMAT_VERSION {
+            bail!(
+                "The formal snapshot has format version {}, this binary reads up to version \
+                {FORMAL_SNAPSHOT_FORMAT_VERSION}",
+                manifest.version
+            );
+        }
+        ensure!(
+            manifest.epoch == epoch,
+            "The formal snapshot of epoch {epoch} is of epoch {}",
+            manifest.epoch
+        );
+        Ok(Self { store, manifest })
+    }
+
+    pub fn manifest(&self) -> &FormalSnapshotManifest {
+        &self.manifest
+    }
+
+    /// Verifies the last checkpoint of each epoch of the snapshot, starting from
+    /// `genesis_committee`, each certified by the committee that the one before handed off to.
+    pub async fn verify_epochs(
+        &self,
+        genesis_committee: &Committee,
+    ) -> Result<VerifiedFormalSnapshot> {
+        let epoch = self.manifest.epoch;
+        let epoch_checkpoints: Vec<CertifiedCheckpointSummary> = self.get("epochs").await?;
+        ensure!(
+            epoch_checkpoints.len() as u64 == epoch + 1,
+            "The formal snapshot of epoch {epoch} has the last checkpoints of {} epochs",
+            epoch_checkpoints.len()
+        );
+        let mut committees = vec![genesis_committee.clone()];
+        for checkpoint in &epoch_checkpoints {
+            let committee = committees
+                .last()
+                .expect("There is the committee of genesis");
+            ensure!(
+                checkpoint.epoch() == committee.epoch(),
+                "Checkpoint {} of epoch {} is not of epoch {}",
+                checkpoint.sequence_number(),
+                checkpoint.epoch(),
+                committee.epoch()
+            );
+            checkpoint.verify(committee, None)?;
+            let next_committee = checkpoint.next_epoch_committee().ok_or_else(|| {
+                anyhow!(
+                    "Checkpoint {} is not the last checkpoint of epoch {}",
+                    checkpoint.sequence_number(),
+                    checkpoint.epoch()
+                )
+            })?;
+            let next_committee = Committee::new(
+                committee.epoch() + 1,
+                next_committee.iter().cloned().collect(),
+            )?;
+            committees.push(next_committee);
+        }
+
+        let last_checkpoint = epoch_checkpoints
+            .last()
+            .expect("There is at least one epoch");
+        let checkpoint: ArchivedCheckpoint = self.get("checkpoint").await?;
+        ensure!(
+            checkpoint.summary.digest() == last_checkpoint.digest(),
+            "The checkpoint of the formal snapshot is not the last checkpoint of epoch {epoch}"
+        );
+        checkpoint.verify(&committees[epoch as usize])?;
+        let root_state_digest = last_checkpoint
+            .root_state_digest()
+            .cloned()
+            .ok_or_else(|| {
+                anyhow!("The last checkpoint of epoch {epoch} does not commit to the live objects")
+            })?;
+        Ok(VerifiedFormalSnapshot {
+            epoch_checkpoints: epoch_checkpoints
+                .into_iter()
+                .map(VerifiedCheckpoint::new_unchecked)
+                .collect(),
+            committees,
+            checkpoint,
+            root_state_digest,
+        })
+    }
+
+    /// Reads a part of the live objects, which are only as trustworthy as the snapshot until
+    /// they have all been checked with [`verify_live_object_set`].
+    pub async fn get_objects(&self, part: u64) -> Result<Vec<Object>> {
+        ensure!(
+            part < self.manifest.num_parts,
+            "The formal snapshot has {} parts of objects",
+            self.manifest.num_parts
+        );
+        self.get(&format!("objects/{part}")).await
+    }
+
+    async fn get<T: DeserializeOwned>(&self, entry: &str) -> Result<T> {
+        let path = snapshot_path(self.manifest.epoch, entry);
+        let bytes = self
+            .store
+            .get(&path)
+            .await?
+            .ok_or_else(|| anyhow!("The formal snapshot is missing {path}"))?;
+        Ok(bcs::from_bytes(
+            &self.manifest.compression.decompress(bytes)?,
+        )?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sui_types::base_types::ObjectID;
+    use sui_types::committee::ProtocolVersion;
+    use sui_types::crypto::{AuthorityKeyPair, KeypairTraits};
+    use sui_types::gas::GasCostSummary;
+    use sui_types::messages_checkpoint::{
+        CheckpointContents, CheckpointSummary, EndOfEpochData, SignedCheckpointSummary,
+    };
+
+    /// The last checkpoint of the epoch of `committee`, handing off to itself and committing to
+    /// `root_state_digest`.
+    fn last_checkpoint_of_epoch(
+        committee: &Committee,
+        keys: &[AuthorityKeyPair],
+        root_state_digest: ECMHLiveObjectSetDigest,
+    ) -> ArchivedCheckpoint {
+        let contents = CheckpointContents::new_with_causally_ordered_transactions([]);
+        let summary = CheckpointSummary::new(
+            committee.epoch,
+            committee.epoch,
+            0,
+            &contents,
+            None,
+            GasCostSummary::default(),
+            Some(EndOfEpochData {
+                next_epoch_committee: committee.voting_rights.clone(),
+                next_epoch_protocol_version: ProtocolVersion::MAX,
+                epoch_commitments: vec![root_state_digest.into()],
+            }),
+            1000,
+        );
+        let signed = keys
+            .iter()
+            .map(|k| {
+                SignedCheckpointSummary::new_from_summary(summary.clone(), k.public().into(), k)
+            })
+            .collect();
+        ArchivedCheckpoint {
+            summary: CertifiedCheckpointSummary::aggregate(signed, committee).unwrap(),
+            contents,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_formal_snapshot() {
+        let (genesis_committee, keys) = Committee::new_simple_test_committee();
+        let objects: Vec<_> = (0..5)
+            .map(|_| Object::immutable_with_id_for_testing(ObjectID::random()))
+            .collect();
+        let mut accumulator = Accumulator::default();
+        accumulator.insert_all(objects.iter().map(|o| o.digest()));
+        let root_state_digest = ECMHLiveObjectSetDigest::from(accumulator.digest());
+
+        // The committee of epoch 1 hands off to itself.
+        let epoch_0 =
+            last_checkpoint_of_epoch(&genesis_committee, &keys, root_state_digest.clone());
+        let committee_1 =
+            Committee::new(1, genesis_committee.voting_rights.iter().cloned().collect()).unwrap();
+        let epoch_1 = last_checkpoint_of_epoch(&committee_1, &keys, root_state_digest.clone());
+
+        let dir = tempfile::tempdir().unwrap();
+        let mut writer = FormalSnapshotWriter::new(
+            ArchiveStore::Directory(dir.path().to_path_buf()),
+            Compression::Gzip,
+            1,
+        );
+        writer
+            .put_epochs(&[epoch_0.summary.clone(), epoch_1.summary.clone()])
+            .await
+            .unwrap();
+        writer.put_checkpoint(&epoch_1).await.unwrap();
+        writer.put_objects(&objects[..3]).await.unwrap();
+        writer.put_objects(&objects[3..]).await.unwrap();
+        // A snapshot is not readable until its manifest is written.
+        assert!(!writer.exists().await.unwrap());
+        assert!(
+            FormalSnapshotReader::open(ArchiveStore::Directory(dir.path().to_path_buf()), 1)
+                .await
+                .is_err()
+        );
+        let manifest = writer.finish().await.unwrap();
+        assert_eq!(manifest.num_parts, 2);
+        assert_eq!(manifest.num_objects, 5);
+
+        let reader =
+            FormalSnapshotReader::open(ArchiveStore::Directory(dir.path().to_path_buf()), 1)
+                .await
+                .unwrap();
+        assert_eq!(reader.manifest(), &manifest);
+        let snapshot = reader.verify_epochs(&genesis_committee).await.unwrap();
+        assert_eq!(snapshot.epoch_checkpoints.len(), 2);
+        assert_eq!(snapshot.committees.len(), 3);
+        assert_eq!(snapshot.committees[2].epoch(), 2);
+        assert_eq!(snapshot.root_state_digest, root_state_digest);
+
+        let mut restored = Accumulator::default();
+        for part in 0..manifest.num_parts {
+            let objects = reader.get_objects(part).await.unwrap();
+            restored.insert_all(objects.iter().map(|o| o.digest()));
+        }
+        assert!(reader.get_objects(manifest.num_parts).await.is_err());
+        verify_live_object_set(&restored, &snapshot.root_state_digest).unwrap();
+
+        // A live object set missing an object.
+        restored.remove(objects[0].digest());
+        assert!(verify_live_object_set(&restored, &snapshot.root_state_digest).is_err());
+
+        // Epochs certified by a committee that genesis did not hand off to.
+        let (other_committee, _) = Committee::new_simple_test_committee();
+        assert!(reader.verify_epochs(&other_committee).await.is_err());
+    }
+}
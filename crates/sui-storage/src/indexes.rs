@@ -5,40 +5,97 @@
 //! The main user of this data is the explorer.
 
 use anyhow::anyhow;
+use itertools::Itertools;
 use move_core_types::identifier::Identifier;
-use serde::{de::DeserializeOwned, Serialize};
+use move_core_types::language_storage::StructTag;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use std::cmp::min;
 use std::path::PathBuf;
 use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
 use tracing::debug;
 use typed_store::rocks::DBOptions;
-use typed_store::rocks::{DBMap, MetricConf};
+use typed_store::rocks::{DBMap, MetricConf, RocksDB};
 use typed_store::traits::Map;
 use typed_store::traits::{TableSummary, TypedStoreDebug};
 use typed_store_derive::DBMapUtils;
 
 use sui_types::base_types::{ObjectID, SuiAddress, TransactionDigest, TxSequenceNumber};
-use sui_types::base_types::{ObjectInfo, ObjectRef};
+use sui_types::base_types::{ObjectInfo, ObjectRef, ObjectType};
+use sui_types::coin::Coin;
 use sui_types::dynamic_field::{DynamicFieldInfo, DynamicFieldName};
 use sui_types::error::{SuiError, SuiResult};
 use sui_types::fp_ensure;
-use sui_types::object::Owner;
+use sui_types::object::{Object, Owner};
 use sui_types::query::TransactionQuery;
 
 use crate::default_db_options;
 
 type OwnerIndexKey = (SuiAddress, ObjectID);
+/// The owner, the type without its type parameters and the id of an object.
+type OwnerTypeIndexKey = (SuiAddress, String, ObjectID);
 type DynamicFieldKey = (ObjectID, ObjectID);
+/// The owner and type of a coin, `u64::MAX` minus its balance so that the coins of an owner are
+/// ordered by descending balance, and its id.
+pub type CoinBalanceIndexKey = (SuiAddress, String, u64, ObjectID);
 
 pub const MAX_TX_RANGE_SIZE: u64 = 4096;
 
 pub const MAX_GET_OWNED_OBJECT_SIZE: usize = 256;
 
+/// The number of objects written at once when backfilling the owner type index.
+const BACKFILL_BATCH_SIZE: usize = 10_000;
+
+/// How far apart in time transactions indexed concurrently can be, see
+/// [`IndexStore::scan_time_range`].
+const TIME_RANGE_SLACK_MS: u64 = 10_000;
+
+/// The most transactions a [`TransactionQuery::MatchAll`] or [`TransactionQuery::TimeRange`]
+/// query scans for a page, after which it fails rather than keep scanning for matches.
+pub const MAX_TX_SCANNED: usize = 100_000;
+
+/// What a transaction was indexed under, as given to [`IndexStore::index_tx`], for pruning its
+/// index entries.
+#[derive(Clone, Debug)]
+pub struct IndexedTransaction {
+    pub digest: TransactionDigest,
+    pub sender: SuiAddress,
+    pub input_objects: Vec<ObjectID>,
+    pub mutated_objects: Vec<(ObjectID, Owner)>,
+    pub move_functions: Vec<(ObjectID, String, String)>,
+}
+
+type TransactionIter<'a> = Box<dyn Iterator<Item = (TxSequenceNumber, TransactionDigest)> + 'a>;
+
+/// A check of a transaction against one of the queries of a [`TransactionQuery::MatchAll`].
+enum TransactionPredicate {
+    All,
+    InputObject(ObjectID),
+    MutatedObject(ObjectID),
+    FromAddress(SuiAddress),
+    ToAddress(SuiAddress),
+    /// Calls to any of the functions of a Move package or module.
+    MoveFunctions(Vec<(ObjectID, String, String)>),
+    TimeRange {
+        start_time: u64,
+        end_time: u64,
+    },
+}
+
+/// The indexes added to databases that existed before them, recorded once they index everything.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub enum BackfilledIndex {
+    OwnerType,
+    CoinBalance,
+}
+
 pub struct ObjectIndexChanges {
     pub deleted_owners: Vec<OwnerIndexKey>,
     pub deleted_dynamic_fields: Vec<DynamicFieldKey>,
     pub new_owners: Vec<(OwnerIndexKey, ObjectInfo)>,
     pub new_dynamic_fields: Vec<(DynamicFieldKey, DynamicFieldInfo)>,
+    pub deleted_coins: Vec<CoinBalanceIndexKey>,
+    pub new_coins: Vec<CoinBalanceIndexKey>,
 }
 
 #[derive(DBMapUtils)]
@@ -92,6 +149,20 @@ pub struct IndexStoreTables {
     /// by a specific object, and their object reference.
     #[default_options_override_fn = "dynamic_field_index_table_default_config"]
     dynamic_field_index: DBMap<DynamicFieldKey, DynamicFieldInfo>,
+
+    /// The objects of `owner_index` indexed by owner and type, the type without its type
+    /// parameters so that the objects of every instantiation of a type are listed together.
+    /// It is only read once backfilled, see [`IndexStore::backfill_owner_type_index`].
+    #[default_options_override_fn = "owner_type_index_table_default_config"]
+    owner_type_index: DBMap<OwnerTypeIndexKey, ObjectInfo>,
+
+    /// The `Coin` objects owned by addresses, by owner, coin type and descending balance, so that
+    /// the coins of an owner can be paged by balance without reading them all. It is only read
+    /// once backfilled, see [`IndexStore::backfill_coin_balance_index`].
+    #[default_options_override_fn = "coin_balance_index_table_default_config"]
+    coin_balance_index: DBMap<CoinBalanceIndexKey, ()>,
+
+    backfilled_indexes: DBMap<BackfilledIndex, ()>,
 }
 
 pub struct IndexStore {
@@ -130,19 +201,131 @@ fn owner_index_table_default_config() -> DBOptions {
 fn dynamic_field_index_table_default_config() -> DBOptions {
     default_db_options(None, Some(1_000_000)).0
 }
+fn owner_type_index_table_default_config() -> DBOptions {
+    default_db_options(None, Some(1_000_000)).0
+}
+fn coin_balance_index_table_default_config() -> DBOptions {
+    default_db_options(None, Some(1_000_000)).0
+}
+
+/// `type_` without its type parameters, as keyed in the owner type index.
+fn base_type(type_: &StructTag) -> String {
+    StructTag {
+        type_params: vec![],
+        ..type_.clone()
+    }
+    .to_canonical_string()
+}
+
+fn owner_type_index_key(
+    (owner, object_id): &OwnerIndexKey,
+    info: &ObjectInfo,
+) -> Option<OwnerTypeIndexKey> {
+    match &info.type_ {
+        ObjectType::Struct(type_) => Some((*owner, base_type(type_), *object_id)),
+        ObjectType::Package => None,
+    }
+}
+
+/// The key of `object` in the coin balance index, if it is a `Coin` owned by an address.
+pub fn coin_balance_index_key(object: &Object) -> Option<CoinBalanceIndexKey> {
+    let Owner::AddressOwner(owner) = object.owner else {
+        return None;
+    };
+    let type_ = object.type_()?;
+    let balance = Coin::extract_balance_if_coin(object).ok()??;
+    Some((
+        owner,
+        type_.to_canonical_string(),
+        u64::MAX - balance,
+        object.id(),
+    ))
+}
+
+/// Whether an object of type `object_type` is of type `type_`, or of any instantiation of it if
+/// `type_` has no type parameters.
+pub fn is_of_type(type_: &StructTag, object_type: &ObjectType) -> bool {
+    match object_type {
+        ObjectType::Struct(object_type) => {
+            type_.address == object_type.address
+                && type_.module == object_type.module
+                && type_.name == object_type.name
+                && (type_.type_params.is_empty() || type_.type_params == object_type.type_params)
+        }
+        ObjectType::Package => false,
+    }
+}
+
+/// Inlines nested [`TransactionQuery::MatchAll`] queries and drops the queries matching all
+/// transactions.
+fn flatten_queries(queries: Vec<TransactionQuery>) -> Vec<TransactionQuery> {
+    queries
+        .into_iter()
+        .flat_map(|query| match query {
+            TransactionQuery::MatchAll(queries) => flatten_queries(queries),
+            TransactionQuery::All => vec![],
+            query => vec![query],
+        })
+        .collect()
+}
+
+/// Ranks queries by how few transactions their index is expected to hold, lowest first.
+fn selectivity(query: &TransactionQuery) -> u8 {
+    match query {
+        TransactionQuery::InputObject(_) | TransactionQuery::MutatedObject(_) => 0,
+        TransactionQuery::MoveFunction {
+            module: Some(_),
+            function: Some(_),
+            ..
+        } => 1,
+        TransactionQuery::FromAddress(_) | TransactionQuery::ToAddress(_) => 2,
+        TransactionQuery::MoveFunction { .. } => 3,
+        TransactionQuery::TimeRange { .. } => 4,
+        TransactionQuery::All | TransactionQuery::MatchAll(_) => 5,
+    }
+}
+
+fn first_unused_sequence_number(tables: &IndexStoreTables) -> TxSequenceNumber {
+    tables
+        .transaction_order
+        .iter()
+        .skip_to_last()
+        .next()
+        .map(|(seq, _)| seq + 1)
+        .unwrap_or(0)
+}
 
 impl IndexStore {
     pub fn new(path: PathBuf) -> Self {
         let tables =
             IndexStoreTables::open_tables_read_write(path, MetricConf::default(), None, None);
-        let next_sequence_number = tables
-            .transaction_order
-            .iter()
-            .skip_to_last()
-            .next()
-            .map(|(seq, _)| seq + 1)
-            .unwrap_or(0)
-            .into();
+        // The indexes of a new database are maintained from the start.
+        if tables.owner_index.is_empty() && tables.transaction_order.is_empty() {
+            tables
+                .backfilled_indexes
+                .multi_insert([
+                    (BackfilledIndex::OwnerType, ()),
+                    (BackfilledIndex::CoinBalance, ()),
+                ])
+                .expect("Cannot write to the index store");
+        }
+        let next_sequence_number = first_unused_sequence_number(&tables).into();
+
+        Self {
+            tables,
+            next_sequence_number,
+        }
+    }
+
+    /// Opens the indexes of another node as a secondary instance, for serving reads only.
+    pub fn open_secondary(path: PathBuf, secondary_path: PathBuf) -> Self {
+        let tables = IndexStoreTables::open_tables_secondary(
+            path,
+            secondary_path,
+            MetricConf::default(),
+            None,
+        );
+        let next_sequence_number = first_unused_sequence_number(&tables).into();
 
         Self {
             tables,
@@ -150,6 +333,15 @@ impl IndexStore {
         }
     }
 
+    /// Makes the writes of the primary instance visible, when opened with
+    /// [`Self::open_secondary`].
+    pub fn try_catch_up_with_primary(&self) -> SuiResult {
+        self.tables.transaction_order.try_catch_up_with_primary()?;
+        self.next_sequence_number
+            .store(first_unused_sequence_number(&self.tables), Ordering::SeqCst);
+        Ok(())
+    }
+
     pub fn index_tx(
         &self,
         sender: SuiAddress,
@@ -216,33 +408,113 @@ impl IndexStore {
             std::iter::once((*digest, timestamp_ms)),
         )?;
 
-        // Owner index
+        // Owner index, the owner type index entries being deleted along with the entries they
+        // were added with.
+        let deleted_owner_types: Vec<_> = self
+            .tables
+            .owner_index
+            .multi_get(&object_index_changes.deleted_owners)?
+            .iter()
+            .zip(&object_index_changes.deleted_owners)
+            .filter_map(|(info, key)| owner_type_index_key(key, info.as_ref()?))
+            .collect();
+        let new_owner_types: Vec<_> = object_index_changes
+            .new_owners
+            .iter()
+            .filter_map(|(key, info)| Some((owner_type_index_key(key, info)?, info)))
+            .collect();
         let batch = batch.delete_batch(
             &self.tables.owner_index,
-            object_index_changes.deleted_owners.into_iter(),
+            object_index_changes.deleted_owners.iter(),
         )?;
+        let batch = batch.delete_batch(&self.tables.owner_type_index, deleted_owner_types)?;
         let batch = batch.delete_batch(
             &self.tables.dynamic_field_index,
             object_index_changes.deleted_dynamic_fields.into_iter(),
         )?;
+        let batch = batch.delete_batch(
+            &self.tables.coin_balance_index,
+            object_index_changes.deleted_coins.iter(),
+        )?;
         let batch = batch.insert_batch(
             &self.tables.owner_index,
-            object_index_changes.new_owners.into_iter(),
+            object_index_changes
+                .new_owners
+                .iter()
+                .map(|(key, info)| (key, info)),
         )?;
+        let batch = batch.insert_batch(&self.tables.owner_type_index, new_owner_types)?;
         let batch = batch.insert_batch(
             &self.tables.dynamic_field_index,
             object_index_changes.new_dynamic_fields.into_iter(),
         )?;
+        let batch = batch.insert_batch(
+            &self.tables.coin_balance_index,
+            object_index_changes
+                .new_coins
+                .into_iter()
+                .map(|key| (key, ())),
+        )?;
 
         batch.write()?;
 
         Ok(sequence)
     }
 
+    /// Deletes the index entries of `transactions`, skipping the ones that are not indexed.
+    /// The owned objects and dynamic fields indexes are about the latest state and are kept.
+    pub fn prune_transactions(&self, transactions: &[IndexedTransaction]) -> SuiResult {
+        let sequences = self
+            .tables
+            .transactions_seq
+            .multi_get(transactions.iter().map(|tx| tx.digest))?;
+        let mut batch = self.tables.transactions_from_addr.batch();
+        for (tx, sequence) in transactions.iter().zip(sequences) {
+            let Some(sequence) = sequence else {
+                continue;
+            };
+            batch = batch
+                .delete_batch(&self.tables.transaction_order, std::iter::once(sequence))?
+                .delete_batch(&self.tables.transactions_seq, std::iter::once(tx.digest))?
+                .delete_batch(&self.tables.timestamps, std::iter::once(tx.digest))?
+                .delete_batch(
+                    &self.tables.transactions_from_addr,
+                    std::iter::once((tx.sender, sequence)),
+                )?
+                .delete_batch(
+                    &self.tables.transactions_by_input_object_id,
+                    tx.input_objects.iter().map(|id| (*id, sequence)),
+                )?
+                .delete_batch(
+                    &self.tables.transactions_by_mutated_object_id,
+                    tx.mutated_objects.iter().map(|(id, _)| (*id, sequence)),
+                )?
+                .delete_batch(
+                    &self.tables.transactions_by_move_function,
+                    tx.move_functions.iter().map(|(package, module, function)| {
+                        (*package, module.clone(), function.clone(), sequence)
+                    }),
+                )?
+                .delete_batch(
+                    &self.tables.transactions_to_addr,
+                    tx.mutated_objects.iter().filter_map(|(_, owner)| {
+                        owner.get_owner_address().ok().map(|addr| (addr, sequence))
+                    }),
+                )?;
+        }
+        batch.write()?;
+        Ok(())
+    }
+
     pub fn next_sequence_number(&self) -> TxSequenceNumber {
         self.next_sequence_number.load(Ordering::SeqCst) + 1
     }
 
+    /// The sequence number the next indexed transaction will get.
+    pub fn first_unindexed_sequence_number(&self) -> TxSequenceNumber {
+        self.next_sequence_number.load(Ordering::SeqCst)
+    }
+
     pub fn get_transactions(
         &self,
         query: TransactionQuery,
@@ -281,6 +553,16 @@ impl IndexStore {
             TransactionQuery::ToAddress(address) => {
                 self.get_transactions_to_addr(address, cursor, limit, reverse)?
             }
+            TransactionQuery::TimeRange { .. } => self.get_transactions_matching_all(
+                vec![query],
+                cursor,
+                limit,
+                reverse,
+                MAX_TX_SCANNED,
+            )?,
+            TransactionQuery::MatchAll(queries) => {
+                self.get_transactions_matching_all(queries, cursor, limit, reverse, MAX_TX_SCANNED)?
+            }
             TransactionQuery::All => {
                 let iter = self.tables.transaction_order.iter();
 
@@ -306,6 +588,361 @@ impl IndexStore {
         })
     }
 
+    /// Returns the transactions matching all of `queries`. The transactions of the query with
+    /// the most selective index are scanned, and each of them is checked against the other
+    /// queries with point lookups, so the cost of the query follows the size of that index
+    /// rather than the number of transactions. Fails once `max_scanned` transactions are scanned
+    /// without filling the page.
+    fn get_transactions_matching_all(
+        &self,
+        queries: Vec<TransactionQuery>,
+        cursor: TxSequenceNumber,
+        limit: Option<usize>,
+        reverse: bool,
+        max_scanned: usize,
+    ) -> SuiResult<Vec<TransactionDigest>> {
+        let mut queries = flatten_queries(queries);
+        queries.sort_by_key(selectivity);
+        let mut queries = queries.into_iter();
+        let driver = queries.next().unwrap_or(TransactionQuery::All);
+
+        // A scan by time range yields transactions slightly outside of the range.
+        let mut predicates = vec![];
+        if let TransactionQuery::TimeRange {
+            start_time,
+            end_time,
+        } = driver
+        {
+            predicates.push(TransactionPredicate::TimeRange {
+                start_time,
+                end_time,
+            });
+        }
+        for query in queries {
+            predicates.push(self.predicate(query)?);
+        }
+
+        let mut digests = vec![];
+        for (scanned, (seq, digest)) in self.scan_transactions(driver, cursor, reverse)?.enumerate()
+        {
+            if limit.map_or(false, |limit| digests.len() >= limit) {
+                break;
+            }
+            fp_ensure!(
+                scanned < max_scanned,
+                SuiError::FullNodeInvalidTxRangeQuery {
+                    error: format!(
+                        "Number of transactions scanned for a page must not exceed {max_scanned}, \
+                         use more selective queries"
+                    ),
+                }
+            );
+            if self.matches_all(&predicates, seq, &digest)? {
+                digests.push(digest);
+            }
+        }
+        Ok(digests)
+    }
+
+    /// Iterates over the transactions matched by `query` from `cursor`, using its index.
+    fn scan_transactions(
+        &self,
+        query: TransactionQuery,
+        cursor: TxSequenceNumber,
+        reverse: bool,
+    ) -> SuiResult<TransactionIter<'_>> {
+        Ok(match query {
+            TransactionQuery::InputObject(object_id) => Self::scan_index(
+                &self.tables.transactions_by_input_object_id,
+                object_id,
+                cursor,
+                reverse,
+            )?,
+            TransactionQuery::MutatedObject(object_id) => Self::scan_index(
+                &self.tables.transactions_by_mutated_object_id,
+                object_id,
+                cursor,
+                reverse,
+            )?,
+            TransactionQuery::FromAddress(address) => Self::scan_index(
+                &self.tables.transactions_from_addr,
+                address,
+                cursor,
+                reverse,
+            )?,
+            TransactionQuery::ToAddress(address) => {
+                Self::scan_index(&self.tables.transactions_to_addr, address, cursor, reverse)?
+            }
+            // The index is ordered by function first, so the transactions of a package or module
+            // are merged from those of each of its functions.
+            TransactionQuery::MoveFunction {
+                package,
+                module,
+                function,
+            } => {
+                let iters = self
+                    .move_functions(package, module, function)?
+                    .into_iter()
+                    .map(|(module, function)| {
+                        self.scan_move_function(package, module, function, cursor, reverse)
+                    })
+                    .collect::<SuiResult<Vec<_>>>()?;
+                Box::new(iters.into_iter().kmerge_by(
+                    move |a: &(TxSequenceNumber, _), b: &(TxSequenceNumber, _)| {
+                        if reverse {
+                            a.0 > b.0
+                        } else {
+                            a.0 < b.0
+                        }
+                    },
+                ))
+            }
+            TransactionQuery::TimeRange {
+                start_time,
+                end_time,
+            } => self.scan_time_range(start_time, end_time, cursor, reverse)?,
+            // Nested queries are flattened before planning.
+            TransactionQuery::All | TransactionQuery::MatchAll(_) => {
+                let iter = self.tables.transaction_order.iter();
+                if reverse {
+                    Box::new(iter.skip_prior_to(&cursor)?.reverse())
+                } else {
+                    Box::new(iter.skip_to(&cursor)?)
+                }
+            }
+        })
+    }
+
+    fn scan_index<KeyT: Clone + Serialize + DeserializeOwned + PartialEq + 'static>(
+        index: &DBMap<(KeyT, TxSequenceNumber), TransactionDigest>,
+        key: KeyT,
+        cursor: TxSequenceNumber,
+        reverse: bool,
+    ) -> SuiResult<TransactionIter<'_>> {
+        let start = (key.clone(), cursor);
+        Ok(if reverse {
+            Box::new(
+                index
+                    .iter()
+                    .skip_prior_to(&start)?
+                    .reverse()
+                    .take_while(move |((id, _), _)| *id == key)
+                    .map(|((_, seq), digest)| (seq, digest)),
+            )
+        } else {
+            Box::new(
+                index
+                    .iter()
+                    .skip_to(&start)?
+                    .take_while(move |((id, _), _)| *id == key)
+                    .map(|((_, seq), digest)| (seq, digest)),
+            )
+        })
+    }
+
+    /// Iterates over the calls to a Move function from `cursor`.
+    fn scan_move_function(
+        &self,
+        package: ObjectID,
+        module: String,
+        function: String,
+        cursor: TxSequenceNumber,
+        reverse: bool,
+    ) -> SuiResult<TransactionIter<'_>> {
+        let start = (package, module.clone(), function.clone(), cursor);
+        let iter = self.tables.transactions_by_move_function.iter();
+        let iter: Box<dyn Iterator<Item = _>> = if reverse {
+            Box::new(iter.skip_prior_to(&start)?.reverse())
+        } else {
+            Box::new(iter.skip_to(&start)?)
+        };
+        Ok(Box::new(
+            iter.take_while(move |((id, m, f, _), _)| {
+                *id == package && *m == module && *f == function
+            })
+            .map(|((_, _, _, seq), digest)| (seq, digest)),
+        ))
+    }
+
+    /// Returns the modules and functions of `package` with indexed calls, only those of `module`
+    /// and named `function` if given. The index is read one function at a time, seeking past the
+    /// calls to each function.
+    fn move_functions(
+        &self,
+        package: ObjectID,
+        module: Option<String>,
+        function: Option<String>,
+    ) -> SuiResult<Vec<(String, String)>> {
+        if let (Some(module), Some(function)) = (&module, &function) {
+            return Ok(vec![(module.clone(), function.clone())]);
+        }
+        let mut functions = vec![];
+        let mut start = (
+            package,
+            module.clone().unwrap_or_default(),
+            String::new(),
+            TxSequenceNumber::MIN,
+        );
+        while let Some(((id, m, f, _), _)) = self
+            .tables
+            .transactions_by_move_function
+            .iter()
+            .skip_to(&start)?
+            .next()
+        {
+            if id != package || module.as_ref().map_or(false, |module| *module != m) {
+                break;
+            }
+            start = (package, m.clone(), f.clone(), TxSequenceNumber::MAX);
+            if function.as_ref().map_or(true, |function| *function == f) {
+                functions.push((m, f));
+            }
+        }
+        Ok(functions)
+    }
+
+    /// Iterates over the transactions indexed around [start_time, end_time), relying on the
+    /// timestamps following the index order. As transactions are indexed concurrently, the scan
+    /// covers [`TIME_RANGE_SLACK_MS`] more on each side of the range.
+    fn scan_time_range(
+        &self,
+        start_time: u64,
+        end_time: u64,
+        cursor: TxSequenceNumber,
+        reverse: bool,
+    ) -> SuiResult<TransactionIter<'_>> {
+        let iter = self.tables.transaction_order.iter();
+        let scan_start = start_time.saturating_sub(TIME_RANGE_SLACK_MS);
+        let scan_end = end_time.saturating_add(TIME_RANGE_SLACK_MS);
+        Ok(if reverse {
+            let start = cursor.min(self.first_transaction_indexed_at(scan_end)?);
+            Box::new(
+                iter.skip_prior_to(&start)?
+                    .reverse()
+                    .take_while(move |(_, digest)| {
+                        self.timestamp_ms(digest)
+                            .map_or(true, |ts| ts >= scan_start)
+                    }),
+            )
+        } else {
+            let start = cursor.max(self.first_transaction_indexed_at(scan_start)?);
+            Box::new(iter.skip_to(&start)?.take_while(move |(_, digest)| {
+                self.timestamp_ms(digest).map_or(true, |ts| ts < scan_end)
+            }))
+        })
+    }
+
+    /// Binary searches the sequence number of the first transaction indexed at or after
+    /// `time_ms`.
+    fn first_transaction_indexed_at(&self, time_ms: u64) -> SuiResult<TxSequenceNumber> {
+        let mut low = TxSequenceNumber::MIN;
+        let mut high = self.next_sequence_number.load(Ordering::SeqCst);
+        while low < high {
+            let mid = low + (high - low) / 2;
+            let timestamp_ms = match self.tables.transaction_order.get(&mid)? {
+                Some(digest) => self.tables.timestamps.get(&digest)?,
+                None => None,
+            };
+            match timestamp_ms {
+                Some(timestamp_ms) if timestamp_ms < time_ms => low = mid + 1,
+                _ => high = mid,
+            }
+        }
+        Ok(low)
+    }
+
+    fn timestamp_ms(&self, digest: &TransactionDigest) -> Option<u64> {
+        self.tables.timestamps.get(digest).ok().flatten()
+    }
+
+    fn predicate(&self, query: TransactionQuery) -> SuiResult<TransactionPredicate> {
+        Ok(match query {
+            TransactionQuery::InputObject(object_id) => {
+                TransactionPredicate::InputObject(object_id)
+            }
+            TransactionQuery::MutatedObject(object_id) => {
+                TransactionPredicate::MutatedObject(object_id)
+            }
+            TransactionQuery::FromAddress(address) => TransactionPredicate::FromAddress(address),
+            TransactionQuery::ToAddress(address) => TransactionPredicate::ToAddress(address),
+            TransactionQuery::MoveFunction {
+                package,
+                module,
+                function,
+            } => TransactionPredicate::MoveFunctions(
+                self.move_functions(package, module, function)?
+                    .into_iter()
+                    .map(|(module, function)| (package, module, function))
+                    .collect(),
+            ),
+            TransactionQuery::TimeRange {
+                start_time,
+                end_time,
+            } => TransactionPredicate::TimeRange {
+                start_time,
+                end_time,
+            },
+            // Nested queries are flattened before planning.
+            TransactionQuery::All | TransactionQuery::MatchAll(_) => TransactionPredicate::All,
+        })
+    }
+
+    fn matches_all(
+        &self,
+        predicates: &[TransactionPredicate],
+        seq: TxSequenceNumber,
+        digest: &TransactionDigest,
+    ) -> SuiResult<bool> {
+        for predicate in predicates {
+            let matches = match predicate {
+                TransactionPredicate::All => true,
+                TransactionPredicate::InputObject(object_id) => self
+                    .tables
+                    .transactions_by_input_object_id
+                    .contains_key(&(*object_id, seq))?,
+                TransactionPredicate::MutatedObject(object_id) => self
+                    .tables
+                    .transactions_by_mutated_object_id
+                    .contains_key(&(*object_id, seq))?,
+                TransactionPredicate::FromAddress(address) => self
+                    .tables
+                    .transactions_from_addr
+                    .contains_key(&(*address, seq))?,
+                TransactionPredicate::ToAddress(address) => self
+                    .tables
+                    .transactions_to_addr
+                    .contains_key(&(*address, seq))?,
+                TransactionPredicate::MoveFunctions(functions) => {
+                    let mut matches = false;
+                    for (package, module, function) in functions {
+                        if self.tables.transactions_by_move_function.contains_key(&(
+                            *package,
+                            module.clone(),
+                            function.clone(),
+                            seq,
+                        ))? {
+                            matches = true;
+                            break;
+                        }
+                    }
+                    matches
+                }
+                TransactionPredicate::TimeRange {
+                    start_time,
+                    end_time,
+                } => self
+                    .tables
+                    .timestamps
+                    .get(digest)?
+                    .map_or(false, |ts| *start_time <= ts && ts < *end_time),
+            };
+            if !matches {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
     pub fn get_transactions_in_range(
         &self,
         start: TxSequenceNumber,
@@ -587,6 +1224,17 @@ impl IndexStore {
     ) -> SuiResult<impl Iterator<Item = ObjectInfo> + '_> {
         let count = min(count, MAX_GET_OWNED_OBJECT_SIZE);
         debug!(?owner, ?count, ?starting_object_id, "get_owner_objects");
+        Ok(self
+            .owner_objects_from(owner, starting_object_id)?
+            .take(count))
+    }
+
+    /// Iterates over all the objects of `owner`, from `starting_object_id` on.
+    pub fn owner_objects_from(
+        &self,
+        owner: SuiAddress,
+        starting_object_id: ObjectID,
+    ) -> SuiResult<impl Iterator<Item = ObjectInfo> + '_> {
         Ok(self
             .tables
             .owner_index
@@ -594,20 +1242,156 @@ impl IndexStore {
             // The object id 0 is the smallest possible
             .skip_to(&(owner, starting_object_id))?
             .take_while(move |((object_owner, _), _)| (object_owner == &owner))
-            .take(count)
             .map(|(_, object_info)| object_info))
     }
 
+    /// Iterates over the objects of `owner` of type `type_`, or of any instantiation of it if
+    /// `type_` has no type parameters, from `starting_object_id` on. Until the owner type index
+    /// is backfilled, this scans all the objects of the owner.
+    pub fn owner_objects_of_type_from(
+        &self,
+        owner: SuiAddress,
+        type_: &StructTag,
+        starting_object_id: ObjectID,
+    ) -> SuiResult<Box<dyn Iterator<Item = ObjectInfo> + '_>> {
+        let type_ = type_.clone();
+        if !self.is_backfilled(BackfilledIndex::OwnerType)? {
+            return Ok(Box::new(
+                self.owner_objects_from(owner, starting_object_id)?
+                    .filter(move |info| is_of_type(&type_, &info.type_)),
+            ));
+        }
+        let base = base_type(&type_);
+        Ok(Box::new(
+            self.tables
+                .owner_type_index
+                .iter()
+                .skip_to(&(owner, base.clone(), starting_object_id))?
+                .take_while(move |((object_owner, object_base, _), _)| {
+                    object_owner == &owner && object_base == &base
+                })
+                .map(|(_, object_info)| object_info)
+                .filter(move |info| is_of_type(&type_, &info.type_)),
+        ))
+    }
+
+    /// Iterates over the coins of `owner` of type `coin_type`, e.g.
+    /// `0x2::coin::Coin<0x2::sui::SUI>`, with a balance of at least `min_balance`, by descending
+    /// balance and then by id, from the coin at `start` on. Yields the balance and id of each
+    /// coin, or returns `None` until the coin balance index is backfilled.
+    pub fn coins_by_balance_from(
+        &self,
+        owner: SuiAddress,
+        coin_type: &StructTag,
+        min_balance: u64,
+        start: Option<(u64, ObjectID)>,
+    ) -> SuiResult<Option<impl Iterator<Item = (u64, ObjectID)> + '_>> {
+        if !self.is_backfilled(BackfilledIndex::CoinBalance)? {
+            return Ok(None);
+        }
+        let coin_type = coin_type.to_canonical_string();
+        let (balance, object_id) = start.unwrap_or((u64::MAX, ObjectID::ZERO));
+        Ok(Some(
+            self.tables
+                .coin_balance_index
+                .iter()
+                .skip_to(&(owner, coin_type.clone(), u64::MAX - balance, object_id))?
+                .map(|((coin_owner, type_, inverted_balance, object_id), _)| {
+                    (coin_owner, type_, u64::MAX - inverted_balance, object_id)
+                })
+                .take_while(move |(coin_owner, type_, balance, _)| {
+                    coin_owner == &owner && type_ == &coin_type && *balance >= min_balance
+                })
+                .map(|(_, _, balance, object_id)| (balance, object_id)),
+        ))
+    }
+
+    pub fn is_backfilled(&self, index: BackfilledIndex) -> SuiResult<bool> {
+        Ok(self.tables.backfilled_indexes.contains_key(&index)?)
+    }
+
+    /// Adds the objects of the owner index to the owner type index, which is read from then on.
+    /// The node must not be running, since the objects changing while this runs are not
+    /// accounted for. Returns the number of objects added.
+    pub fn backfill_owner_type_index(&self) -> SuiResult<u64> {
+        let mut num_objects = 0;
+        let mut entries = self.tables.owner_index.iter().peekable();
+        while entries.peek().is_some() {
+            let chunk: Vec<_> = entries
+                .by_ref()
+                .take(BACKFILL_BATCH_SIZE)
+                .filter_map(|(key, info)| Some((owner_type_index_key(&key, &info)?, info)))
+                .collect();
+            num_objects += chunk.len() as u64;
+            self.tables.owner_type_index.multi_insert(chunk)?;
+        }
+        self.tables
+            .backfilled_indexes
+            .insert(&BackfilledIndex::OwnerType, &())?;
+        Ok(num_objects)
+    }
+
+    /// Adds the coins of the owner index to the coin balance index, which is read from then on,
+    /// reading them with `get_object`. The node must not be running, since the objects changing
+    /// while this runs are not accounted for. Returns the number of coins added.
+    pub fn backfill_coin_balance_index(
+        &self,
+        get_object: impl Fn(&ObjectID) -> SuiResult<Option<Object>>,
+    ) -> SuiResult<u64> {
+        let mut num_coins = 0;
+        let mut coins = self
+            .tables
+            .owner_index
+            .iter()
+            .filter(|(_, info)| {
+                matches!(&info.type_, ObjectType::Struct(type_) if Coin::is_coin(type_))
+            })
+            .peekable();
+        while coins.peek().is_some() {
+            let mut chunk = vec![];
+            for (_, info) in coins.by_ref().take(BACKFILL_BATCH_SIZE) {
+                if let Some(key) = get_object(&info.object_id)?
+                    .as_ref()
+                    .and_then(coin_balance_index_key)
+                {
+                    chunk.push((key, ()));
+                }
+            }
+            num_coins += chunk.len() as u64;
+            self.tables.coin_balance_index.multi_insert(chunk)?;
+        }
+        self.tables
+            .backfilled_indexes
+            .insert(&BackfilledIndex::CoinBalance, &())?;
+        Ok(num_coins)
+    }
+
     pub fn insert_genesis_objects(&self, object_index_changes: ObjectIndexChanges) -> SuiResult {
+        let new_owner_types: Vec<_> = object_index_changes
+            .new_owners
+            .iter()
+            .filter_map(|(key, info)| Some((owner_type_index_key(key, info)?, info)))
+            .collect();
         let batch = self.tables.owner_index.batch();
         let batch = batch.insert_batch(
             &self.tables.owner_index,
-            object_index_changes.new_owners.into_iter(),
+            object_index_changes
+                .new_owners
+                .iter()
+                .map(|(key, info)| (key, info)),
         )?;
+        let batch = batch.insert_batch(&self.tables.owner_type_index, new_owner_types)?;
         let batch = batch.insert_batch(
             &self.tables.dynamic_field_index,
             object_index_changes.new_dynamic_fields.into_iter(),
         )?;
+        let batch = batch.insert_batch(
+            &self.tables.coin_balance_index,
+            object_index_changes
+                .new_coins
+                .into_iter()
+                .map(|key| (key, ())),
+        )?;
         batch.write()?;
         Ok(())
     }
@@ -615,4 +1399,620 @@ impl IndexStore {
     pub fn is_empty(&self) -> bool {
         self.tables.owner_index.is_empty()
     }
+
+    /// Compacts the whole of table `table_name`, blocking until it is done.
+    pub fn compact_table(&self, table_name: &str) -> SuiResult {
+        Ok(self.tables.owner_index.rocksdb.compact_cf(table_name)?)
+    }
+
+    /// The database holding the indexes, e.g. to back it up.
+    pub fn rocksdb(&self) -> &Arc<RocksDB> {
+        &self.tables.owner_index.rocksdb
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use move_core_types::ident_str;
+    use std::collections::HashMap;
+    use sui_types::base_types::random_object_ref;
+    use sui_types::gas_coin::GAS;
+    use sui_types::parse_sui_struct_tag;
+
+    fn index_tx(
+        store: &IndexStore,
+        sender: SuiAddress,
+        input: ObjectID,
+        function: &str,
+        timestamp_ms: u64,
+    ) -> TransactionDigest {
+        let digest = TransactionDigest::random();
+        let package = ObjectID::from_single_byte(0x2);
+        store
+            .index_tx(
+                sender,
+                std::iter::once(input),
+                std::iter::once((random_object_ref(), Owner::AddressOwner(sender))),
+                std::iter::once((
+                    package,
+                    ident_str!("coin").to_owned(),
+                    Identifier::new(function).unwrap(),
+                )),
+                ObjectIndexChanges {
+                    deleted_owners: vec![],
+                    deleted_dynamic_fields: vec![],
+                    new_owners: vec![],
+                    new_dynamic_fields: vec![],
+                    deleted_coins: vec![],
+                    new_coins: vec![],
+                },
+                &digest,
+                timestamp_ms,
+            )
+            .unwrap();
+        digest
+    }
+
+    #[test]
+    fn test_prune_transactions() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = IndexStore::new(dir.path().to_path_buf());
+        let (alice, bob) = (
+            SuiAddress::random_for_testing_only(),
+            SuiAddress::random_for_testing_only(),
+        );
+        let object = ObjectID::random();
+        let package = ObjectID::from_single_byte(0x2);
+
+        let t0 = index_tx(&store, alice, object, "split", 1_000);
+        let t1 = index_tx(&store, bob, object, "split", 2_000);
+        let indexed = |digest, sender| IndexedTransaction {
+            digest,
+            sender,
+            input_objects: vec![object],
+            mutated_objects: vec![],
+            move_functions: vec![(package, "coin".into(), "split".into())],
+        };
+        store
+            .prune_transactions(&[
+                indexed(t0, alice),
+                indexed(TransactionDigest::random(), bob),
+            ])
+            .unwrap();
+
+        let get = |query| store.get_transactions(query, None, None, false).unwrap();
+        assert_eq!(get(TransactionQuery::All), vec![t1]);
+        assert_eq!(get(TransactionQuery::FromAddress(alice)), vec![]);
+        assert_eq!(get(TransactionQuery::InputObject(object)), vec![t1]);
+        assert_eq!(
+            get(TransactionQuery::MoveFunction {
+                package,
+                module: Some("coin".into()),
+                function: Some("split".into()),
+            }),
+            vec![t1]
+        );
+        assert_eq!(store.get_transaction_seq(&t0).unwrap(), None);
+        assert_eq!(store.get_timestamp_ms(&t0).unwrap(), None);
+    }
+
+    #[test]
+    fn test_match_all_transactions() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = IndexStore::new(dir.path().to_path_buf());
+        let (alice, bob) = (
+            SuiAddress::random_for_testing_only(),
+            SuiAddress::random_for_testing_only(),
+        );
+        let object = ObjectID::random();
+        let package = ObjectID::from_single_byte(0x2);
+
+        let t0 = index_tx(&store, alice, object, "split", 1_000);
+        let t1 = index_tx(&store, bob, object, "split", 2_000);
+        let t2 = index_tx(&store, alice, ObjectID::random(), "join", 3_000);
+        let t3 = index_tx(&store, alice, object, "join", 4_000);
+
+        let query = TransactionQuery::MatchAll;
+        let get = |query, cursor: Option<TransactionDigest>, limit: Option<usize>, reverse| {
+            store
+                .get_transactions(query, cursor, limit, reverse)
+                .unwrap()
+        };
+
+        // Sender and input object.
+        let from_alice_with_object = query(vec![
+            TransactionQuery::FromAddress(alice),
+            TransactionQuery::InputObject(object),
+        ]);
+        assert_eq!(
+            get(from_alice_with_object.clone(), None, None, false),
+            vec![t0, t3]
+        );
+        assert_eq!(
+            get(from_alice_with_object.clone(), None, None, true),
+            vec![t3, t0]
+        );
+        assert_eq!(
+            get(from_alice_with_object.clone(), None, Some(1), false),
+            vec![t0]
+        );
+        assert_eq!(get(from_alice_with_object, Some(t3), None, false), vec![t3]);
+
+        // Sender and Move module, which is checked from the whole index of the module.
+        let module = TransactionQuery::MoveFunction {
+            package,
+            module: Some("coin".into()),
+            function: None,
+        };
+        assert_eq!(
+            get(
+                query(vec![TransactionQuery::FromAddress(alice), module]),
+                None,
+                None,
+                false
+            ),
+            vec![t0, t2, t3]
+        );
+
+        // Move function and time range, nested.
+        let join = TransactionQuery::MoveFunction {
+            package,
+            module: Some("coin".into()),
+            function: Some("join".into()),
+        };
+        let time_range = TransactionQuery::TimeRange {
+            start_time: 1_000,
+            end_time: 4_000,
+        };
+        assert_eq!(
+            get(
+                query(vec![query(vec![join]), time_range.clone()]),
+                None,
+                None,
+                false
+            ),
+            vec![t2]
+        );
+
+        // Time range on its own.
+        assert_eq!(get(time_range.clone(), None, None, true), vec![t2, t1, t0]);
+        assert_eq!(get(time_range, Some(t1), None, false), vec![t1, t2]);
+    }
+
+    #[test]
+    fn test_owner_type_index() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = IndexStore::new(dir.path().to_path_buf());
+        let owner = SuiAddress::random_for_testing_only();
+        let owned = |type_: &str| {
+            let (object_id, version, digest) = random_object_ref();
+            let info = ObjectInfo {
+                object_id,
+                version,
+                digest,
+                type_: ObjectType::Struct(parse_sui_struct_tag(type_).unwrap()),
+                owner: Owner::AddressOwner(owner),
+                previous_transaction: TransactionDigest::random(),
+            };
+            ((owner, object_id), info)
+        };
+        let sui = owned("0x2::coin::Coin<0x2::sui::SUI>");
+        let usdc = owned("0x2::coin::Coin<0x3::usdc::USDC>");
+        let nft = owned("0x3::nft::Nft");
+        store
+            .insert_genesis_objects(ObjectIndexChanges {
+                deleted_owners: vec![],
+                deleted_dynamic_fields: vec![],
+                new_owners: vec![sui.clone(), usdc.clone(), nft.clone()],
+                new_dynamic_fields: vec![],
+                deleted_coins: vec![],
+                new_coins: vec![],
+            })
+            .unwrap();
+
+        let get = |type_: &str, start| {
+            let type_ = parse_sui_struct_tag(type_).unwrap();
+            let mut ids: Vec<_> = store
+                .owner_objects_of_type_from(owner, &type_, start)
+                .unwrap()
+                .map(|info| info.object_id)
+                .collect();
+            ids.sort();
+            ids
+        };
+        let mut coins = vec![sui.0 .1, usdc.0 .1];
+        coins.sort();
+        let check = || {
+            assert_eq!(get("0x2::coin::Coin", ObjectID::ZERO), coins);
+            assert_eq!(get("0x2::coin::Coin", coins[1]), vec![coins[1]]);
+            assert_eq!(
+                get("0x2::coin::Coin<0x2::sui::SUI>", ObjectID::ZERO),
+                vec![sui.0 .1]
+            );
+            assert_eq!(get("0x3::nft::Nft", ObjectID::ZERO), vec![nft.0 .1]);
+            assert_eq!(get("0x3::nft::Other", ObjectID::ZERO), vec![]);
+        };
+        assert!(store.is_backfilled(BackfilledIndex::OwnerType).unwrap());
+        check();
+
+        // Until backfilled, the objects of the owner are scanned instead.
+        store
+            .tables
+            .backfilled_indexes
+            .remove(&BackfilledIndex::OwnerType)
+            .unwrap();
+        store.tables.owner_type_index.clear().unwrap();
+        check();
+        assert_eq!(store.backfill_owner_type_index().unwrap(), 3);
+        assert!(store.is_backfilled(BackfilledIndex::OwnerType).unwrap());
+        check();
+
+        // Transferring an object away removes it from the index.
+        store
+            .index_tx(
+                owner,
+                std::iter::empty(),
+                std::iter::empty(),
+                std::iter::empty(),
+                ObjectIndexChanges {
+                    deleted_owners: vec![usdc.0],
+                    deleted_dynamic_fields: vec![],
+                    new_owners: vec![],
+                    new_dynamic_fields: vec![],
+                    deleted_coins: vec![],
+                    new_coins: vec![],
+                },
+                &TransactionDigest::random(),
+                0,
+            )
+            .unwrap();
+        assert_eq!(get("0x2::coin::Coin", ObjectID::ZERO), vec![sui.0 .1]);
+    }
+
+    #[test]
+    fn test_coin_balance_index() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = IndexStore::new(dir.path().to_path_buf());
+        let owner = SuiAddress::random_for_testing_only();
+        let balances = [5, 20, 10, 20];
+        let coins: Vec<_> = balances
+            .into_iter()
+            .map(|balance| Object::new_gas_with_balance_and_owner_for_testing(balance, owner))
+            .collect();
+        let objects: HashMap<_, _> = coins.iter().map(|o| (o.id(), o.clone())).collect();
+        store
+            .insert_genesis_objects(ObjectIndexChanges {
+                deleted_owners: vec![],
+                deleted_dynamic_fields: vec![],
+                new_owners: coins
+                    .iter()
+                    .map(|o| {
+                        (
+                            (owner, o.id()),
+                            ObjectInfo::new(&o.compute_object_reference(), o),
+                        )
+                    })
+                    .collect(),
+                new_dynamic_fields: vec![],
+                deleted_coins: vec![],
+                new_coins: coins.iter().filter_map(coin_balance_index_key).collect(),
+            })
+            .unwrap();
+
+        let sui = Coin::type_(GAS::type_());
+        let get = |min_balance, start| {
+            store
+                .coins_by_balance_from(owner, &sui, min_balance, start)
+                .unwrap()
+                .unwrap()
+                .collect::<Vec<_>>()
+        };
+        let mut expected: Vec<_> = balances
+            .into_iter()
+            .zip(coins.iter().map(|o| o.id()))
+            .collect();
+        expected.sort_by_key(|(balance, id)| (std::cmp::Reverse(*balance), *id));
+        let check = || {
+            assert_eq!(get(0, None), expected);
+            // Seeking to a coin starts the listing at it.
+            assert_eq!(get(0, Some(expected[1])), expected[1..]);
+            // The listing stops at the minimum balance.
+            assert_eq!(get(10, None), expected[..3]);
+            // Other coin types are not listed.
+            let usdc = Coin::type_(parse_sui_struct_tag("0x3::usdc::USDC").unwrap());
+            assert_eq!(
+                store
+                    .coins_by_balance_from(owner, &usdc, 0, None)
+                    .unwrap()
+                    .unwrap()
+                    .count(),
+                0
+            );
+        };
+        check();
+
+        // The index of a database that predates it is only read once backfilled.
+        store
+            .tables
+            .backfilled_indexes
+            .remove(&BackfilledIndex::CoinBalance)
+            .unwrap();
+        store.tables.coin_balance_index.clear().unwrap();
+        assert!(store
+            .coins_by_balance_from(owner, &sui, 0, None)
+            .unwrap()
+            .is_none());
+        assert_eq!(
+            store
+                .backfill_coin_balance_index(|id| Ok(objects.get(id).cloned()))
+                .unwrap(),
+            4
+        );
+        check();
+
+        // Spending from a coin moves it to its new balance.
+        let spent = &coins[1];
+        let changed = Object::with_id_owner_gas_for_testing(spent.id(), owner, 1);
+        store
+            .index_tx(
+                owner,
+                std::iter::empty(),
+                std::iter::empty(),
+                std::iter::empty(),
+                ObjectIndexChanges {
+                    deleted_owners: vec![],
+                    deleted_dynamic_fields: vec![],
+                    new_owners: vec![],
+                    new_dynamic_fields: vec![],
+                    deleted_coins: coin_balance_index_key(spent).into_iter().collect(),
+                    new_coins: coin_balance_index_key(&changed).into_iter().collect(),
+                },
+                &TransactionDigest::random(),
+                0,
+            )
+            .unwrap();
+        expected.retain(|(_, id)| *id != spent.id());
+        expected.push((1, spent.id()));
+        assert_eq!(get(0, None), expected);
+    }
+
+    /// A transaction with what it is indexed under.
+    struct Call {
+        digest: TransactionDigest,
+        sender: SuiAddress,
+        recipient: SuiAddress,
+        input: ObjectID,
+        mutated: ObjectID,
+        module: &'static str,
+        function: &'static str,
+        timestamp_ms: u64,
+    }
+
+    /// Indexes calls to 0x2 varying what they are indexed under, so that each query matches some
+    /// of them and each combination of two queries matches a different subset.
+    fn index_calls(
+        store: &IndexStore,
+        addresses: &[SuiAddress],
+        objects: &[ObjectID],
+    ) -> Vec<Call> {
+        let package = ObjectID::from_single_byte(0x2);
+        let functions = [("coin", "split"), ("coin", "join"), ("pay", "split")];
+        (0..24)
+            .map(|i| {
+                let (module, function) = functions[i / 2 % 3];
+                let call = Call {
+                    digest: TransactionDigest::random(),
+                    sender: addresses[i % 2],
+                    recipient: addresses[i / 4 % 2],
+                    input: objects[i % 3],
+                    mutated: objects[i / 3 % 3],
+                    module,
+                    function,
+                    timestamp_ms: 1_000 * i as u64,
+                };
+                let mut mutated = random_object_ref();
+                mutated.0 = call.mutated;
+                store
+                    .index_tx(
+                        call.sender,
+                        std::iter::once(call.input),
+                        std::iter::once((mutated, Owner::AddressOwner(call.recipient))),
+                        std::iter::once((
+                            package,
+                            Identifier::new(module).unwrap(),
+                            Identifier::new(function).unwrap(),
+                        )),
+                        ObjectIndexChanges {
+                            deleted_owners: vec![],
+                            deleted_dynamic_fields: vec![],
+                            new_owners: vec![],
+                            new_dynamic_fields: vec![],
+                            deleted_coins: vec![],
+                            new_coins: vec![],
+                        },
+                        &call.digest,
+                        call.timestamp_ms,
+                    )
+                    .unwrap();
+                call
+            })
+            .collect()
+    }
+
+    /// Each kind of query, with the calls it matches.
+    fn queries(
+        addresses: &[SuiAddress],
+        objects: &[ObjectID],
+    ) -> Vec<(TransactionQuery, Box<dyn Fn(&Call) -> bool>)> {
+        let package = ObjectID::from_single_byte(0x2);
+        let (sender, recipient) = (addresses[0], addresses[1]);
+        let (input, mutated) = (objects[0], objects[1]);
+        vec![
+            (
+                TransactionQuery::FromAddress(sender),
+                Box::new(move |call: &Call| call.sender == sender),
+            ),
+            (
+                TransactionQuery::ToAddress(recipient),
+                Box::new(move |call: &Call| call.recipient == recipient),
+            ),
+            (
+                TransactionQuery::InputObject(input),
+                Box::new(move |call: &Call| call.input == input),
+            ),
+            (
+                TransactionQuery::MutatedObject(mutated),
+                Box::new(move |call: &Call| call.mutated == mutated),
+            ),
+            (
+                TransactionQuery::MoveFunction {
+                    package,
+                    module: Some("coin".into()),
+                    function: Some("split".into()),
+                },
+                Box::new(|call: &Call| call.module == "coin" && call.function == "split"),
+            ),
+            (
+                TransactionQuery::MoveFunction {
+                    package,
+                    module: Some("coin".into()),
+                    function: None,
+                },
+                Box::new(|call: &Call| call.module == "coin"),
+            ),
+            (
+                TransactionQuery::MoveFunction {
+                    package,
+                    module: None,
+                    function: Some("split".into()),
+                },
+                Box::new(|call: &Call| call.function == "split"),
+            ),
+            (
+                TransactionQuery::MoveFunction {
+                    package,
+                    module: None,
+                    function: None,
+                },
+                Box::new(|_: &Call| true),
+            ),
+            (
+                TransactionQuery::TimeRange {
+                    start_time: 5_000,
+                    end_time: 17_000,
+                },
+                Box::new(|call: &Call| (5_000..17_000).contains(&call.timestamp_ms)),
+            ),
+        ]
+    }
+
+    fn random_addresses_and_objects() -> (Vec<SuiAddress>, Vec<ObjectID>) {
+        let addresses = (0..2)
+            .map(|_| SuiAddress::random_for_testing_only())
+            .collect();
+        let objects = (0..3).map(|_| ObjectID::random()).collect();
+        (addresses, objects)
+    }
+
+    #[test]
+    fn test_match_all_combinations() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = IndexStore::new(dir.path().to_path_buf());
+        let (addresses, objects) = random_addresses_and_objects();
+        let calls = index_calls(&store, &addresses, &objects);
+        let queries = queries(&addresses, &objects);
+
+        for (i, (first, first_matches)) in queries.iter().enumerate() {
+            for (second, second_matches) in &queries[i..] {
+                let query = TransactionQuery::MatchAll(vec![first.clone(), second.clone()]);
+                let expected: Vec<_> = calls
+                    .iter()
+                    .filter(|call| first_matches(call) && second_matches(call))
+                    .map(|call| call.digest)
+                    .collect();
+                let get = |reverse| {
+                    store
+                        .get_transactions(query.clone(), None, None, reverse)
+                        .unwrap()
+                };
+                assert_eq!(get(false), expected, "{query:?}");
+                assert_eq!(
+                    get(true),
+                    expected.iter().rev().copied().collect::<Vec<_>>(),
+                    "{query:?} in reverse"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_match_all_pagination() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = IndexStore::new(dir.path().to_path_buf());
+        let (addresses, objects) = random_addresses_and_objects();
+        index_calls(&store, &addresses, &objects);
+        let queries = queries(&addresses, &objects);
+
+        // Pages as JSON-RPC does, fetching one more transaction than the limit as the cursor of
+        // the next page.
+        let paginate = |query: &TransactionQuery, limit: usize, reverse| {
+            let mut transactions = vec![];
+            let mut cursor = None;
+            loop {
+                let mut page = store
+                    .get_transactions(query.clone(), cursor, Some(limit + 1), reverse)
+                    .unwrap();
+                cursor = if page.len() > limit { page.pop() } else { None };
+                transactions.extend(page);
+                if cursor.is_none() {
+                    return transactions;
+                }
+            }
+        };
+        for (first, _) in &queries {
+            for (second, _) in &queries {
+                let query = TransactionQuery::MatchAll(vec![first.clone(), second.clone()]);
+                for reverse in [false, true] {
+                    let all = store
+                        .get_transactions(query.clone(), None, None, reverse)
+                        .unwrap();
+                    for limit in 1..=3 {
+                        assert_eq!(
+                            paginate(&query, limit, reverse),
+                            all,
+                            "{query:?} by {limit}, reverse: {reverse}"
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_match_all_scan_limit() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = IndexStore::new(dir.path().to_path_buf());
+        let (addresses, objects) = random_addresses_and_objects();
+        let calls = index_calls(&store, &addresses, &objects);
+        let get = |queries, limit| {
+            store.get_transactions_matching_all(queries, TxSequenceNumber::MIN, limit, false, 5)
+        };
+
+        // A page filled before the limit.
+        assert_eq!(
+            get(vec![TransactionQuery::FromAddress(addresses[0])], Some(2)).unwrap(),
+            vec![calls[0].digest, calls[2].digest]
+        );
+        // No transaction is sent from two addresses, the whole index of the first one is
+        // scanned without a match.
+        assert!(get(
+            vec![
+                TransactionQuery::FromAddress(addresses[0]),
+                TransactionQuery::FromAddress(addresses[1]),
+            ],
+            Some(2)
+        )
+        .is_err());
+    }
 }
@@ -4,7 +4,11 @@
 pub mod indexes;
 pub use indexes::{IndexStore, IndexStoreTables};
 
+pub mod archive;
+pub mod backup;
+pub mod commit_log;
 pub mod event_store;
+pub mod formal_snapshot;
 pub mod mutex_table;
 pub mod write_ahead_log;
 pub mod write_path_pending_tx_log;
@@ -11,7 +11,7 @@ use crate::mutex_table::{LockGuard, MutexTable};
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use std::fmt::Debug;
 use std::path::PathBuf;
-use std::sync::Mutex;
+use std::sync::{Arc, Mutex};
 use sui_types::base_types::TransactionDigest;
 use typed_store::traits::{TableSummary, TypedStoreDebug};
 use typed_store_derive::DBMapUtils;
@@ -23,7 +23,7 @@ use typed_store::{rocks::DBMap, traits::Map};
 use tracing::{debug, error, instrument, trace, warn};
 
 use tap::TapFallible;
-use typed_store::rocks::MetricConf;
+use typed_store::rocks::{MetricConf, RocksDB};
 
 /// TxGuard is a handle on an in-progress transaction.
 ///
@@ -244,6 +244,28 @@ where
         }
     }
 
+    /// Opens the log of another node as a secondary instance. Transactions left in the log are
+    /// not reported as recoverable, since they belong to the other node.
+    pub fn open_secondary(path: PathBuf, secondary_path: PathBuf) -> Self {
+        let tables = DBWriteAheadLogTables::open_tables_secondary(
+            path,
+            secondary_path,
+            MetricConf::default(),
+            None,
+        );
+
+        Self {
+            tables,
+            recoverable_txes: Mutex::new(vec![]),
+            mutex_table: MutexTable::new(MUTEX_TABLE_SIZE, MUTEX_TABLE_SHARD_SIZE),
+        }
+    }
+
+    /// The database holding the log, e.g. to back it up.
+    pub fn rocksdb(&self) -> &Arc<RocksDB> {
+        &self.tables.log.rocksdb
+    }
+
     pub fn get_tx(
         &self,
         tx: &TransactionDigest,
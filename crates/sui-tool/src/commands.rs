@@ -2,14 +2,22 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    db_tool::{execute_db_tool_command, print_db_all_tables, DbToolCommand},
+    db_tool::{
+        commit_log::replay_commit_log, db_check::check_db, execute_db_tool_command,
+        print_db_all_tables, DbToolCommand,
+    },
     get_object, get_transaction, make_clients, ConciseObjectOutput, GroupedObjectOutput,
     VerboseObjectOutput,
 };
-use anyhow::Result;
+use anyhow::{anyhow, bail, Result};
 use std::path::PathBuf;
+use std::time::Duration;
 use sui_config::genesis::Genesis;
+use sui_core::authority::AuthorityStore;
 use sui_core::authority_client::AuthorityAPI;
+use sui_storage::archive::ArchiveStore;
+use sui_storage::backup::{BackupId, BackupManager};
+use sui_storage::indexes::{BackfilledIndex, IndexStore};
 
 use sui_types::{base_types::*, object::Owner};
 
@@ -97,6 +105,92 @@ pub enum ToolCommand {
         cmd: Option<DbToolCommand>,
     },
 
+    /// Check that the tables of a node's database are consistent with each other, and print what
+    /// is not with how to repair it. Exits with an error if the database is inconsistent.
+    #[clap(name = "db-check")]
+    DbCheck {
+        /// Path of the DB of the node, the `db-path` of its config
+        #[clap(long = "db-path")]
+        db_path: PathBuf,
+        /// The maximum number of issues of each kind to print
+        #[clap(long = "max-issues", default_value = "20")]
+        max_issues: usize,
+    },
+
+    /// Restore the databases of a node from a backup it took, after checking that the backup and
+    /// its manifest are intact. The databases are only written to `db-path` once their column
+    /// families match the manifest.
+    #[clap(name = "restore")]
+    Restore {
+        /// Directory of the backups, the `backup-dir` of the `db-backup-config` of the node
+        #[clap(long = "backup-dir")]
+        backup_dir: PathBuf,
+        /// Path to restore the databases to, the `db-path` of the node, which must not exist
+        #[clap(long = "db-path")]
+        db_path: PathBuf,
+        /// The backup to restore, the newest one if not specified
+        #[clap(long = "backup-id")]
+        backup_id: Option<BackupId>,
+        /// List the backups, and verify them, instead of restoring one
+        #[clap(long = "list")]
+        list: bool,
+    },
+
+    /// Replay the commit log uploaded by a node into its database, to bring a database restored
+    /// from a backup up to date. Stops with an error at the first commit that cannot be
+    /// replayed, such as when the log has a gap; the node syncs the rest from its peers.
+    #[clap(name = "replay-commit-log")]
+    ReplayCommitLog {
+        /// Path of the DB of the node, the `db-path` of its config
+        #[clap(long = "db-path")]
+        db_path: PathBuf,
+        /// Directory of the log
+        #[clap(long = "log-dir", required_unless_present = "log_url")]
+        log_dir: Option<PathBuf>,
+        /// Base URL of the bucket of the log
+        #[clap(long = "log-url", conflicts_with = "log_dir")]
+        log_url: Option<String>,
+        /// The environment variable holding the bearer token to read the bucket with
+        #[clap(long = "auth-token-env-var", requires = "log_url")]
+        auth_token_env_var: Option<String>,
+        /// The first segment to replay. Segments uploaded before the backup are skipped anyway,
+        /// but still downloaded.
+        #[clap(long = "from-segment", default_value = "0")]
+        from_segment: u64,
+    },
+
+    /// Index the objects of a database created before the owner and type index was added, so
+    /// that listing the objects of an owner by type no longer scans all the objects of the
+    /// owner. The node must be stopped.
+    #[clap(name = "backfill-owner-type-index")]
+    BackfillOwnerTypeIndex {
+        /// Path of the DB of the node, the `db-path` of its config
+        #[clap(long = "db-path")]
+        db_path: PathBuf,
+    },
+
+    /// Index the coins of a database created before the coin balance index was added, so that
+    /// listing the coins of an owner by balance no longer reads all the coins of the owner for
+    /// every page. The node must be stopped.
+    #[clap(name = "backfill-coin-balance-index")]
+    BackfillCoinBalanceIndex {
+        /// Path of the DB of the node, the `db-path` of its config
+        #[clap(long = "db-path")]
+        db_path: PathBuf,
+    },
+
+    /// Move the contents of the Move objects larger than a threshold out of the `objects` table,
+    /// to migrate a database to a lower `indirect-objects-threshold`. The node must be stopped.
+    #[clap(name = "migrate-large-objects")]
+    MigrateLargeObjects {
+        /// Path of the DB of the node, the `db-path` of its config
+        #[clap(long = "db-path")]
+        db_path: PathBuf,
+        /// The `indirect-objects-threshold` of the node, in bytes
+        #[clap(long = "threshold")]
+        threshold: usize,
+    },
+
     #[clap(name = "dump-validators")]
     DumpValidators {
         #[clap(long = "genesis")]
@@ -225,6 +319,106 @@ impl ToolCommand {
                     None => print_db_all_tables(path)?,
                 }
             }
+            ToolCommand::DbCheck {
+                db_path,
+                max_issues,
+            } => {
+                let report = check_db(&db_path)?;
+                report.print(max_issues);
+                if !report.is_consistent() {
+                    anyhow::bail!("The database at {} is inconsistent", db_path.display());
+                }
+            }
+            ToolCommand::Restore {
+                backup_dir,
+                db_path,
+                backup_id,
+                list,
+            } => {
+                let backups = BackupManager::new(backup_dir);
+                if list {
+                    for backup_id in backups.list_backups()? {
+                        match backups.verify_backup(backup_id) {
+                            Ok(manifest) => {
+                                println!("{backup_id}\tok\t{} databases", manifest.dbs.len())
+                            }
+                            Err(e) => println!("{backup_id}\tcorrupted: {e:#}"),
+                        }
+                    }
+                } else {
+                    let manifest = backups.restore(backup_id, &db_path)?;
+                    println!(
+                        "Restored backup {} into {}",
+                        manifest.backup_id,
+                        db_path.display()
+                    );
+                    for (db, column_families) in &manifest.dbs {
+                        for (name, cf) in column_families {
+                            println!(
+                                "{db} {name}: {} keys, checksum {}",
+                                cf.num_keys, cf.checksum
+                            );
+                        }
+                    }
+                }
+            }
+            ToolCommand::ReplayCommitLog {
+                db_path,
+                log_dir,
+                log_url,
+                auth_token_env_var,
+                from_segment,
+            } => {
+                let store = match (log_dir, log_url) {
+                    (Some(dir), _) => ArchiveStore::Directory(dir),
+                    (None, Some(url)) => {
+                        let auth_token = auth_token_env_var
+                            .map(|var| {
+                                std::env::var(&var)
+                                    .map_err(|e| anyhow!("Cannot read auth token from {var}: {e}"))
+                            })
+                            .transpose()?;
+                        ArchiveStore::http(&url, auth_token, Duration::from_secs(60))?
+                    }
+                    (None, None) => bail!("Either --log-dir or --log-url is required"),
+                };
+                let summary = replay_commit_log(&db_path, store, from_segment).await?;
+                println!(
+                    "Replayed {} segments: {} records applied, {} already in the database",
+                    summary.num_segments, summary.num_applied, summary.num_skipped
+                );
+            }
+            ToolCommand::BackfillOwnerTypeIndex { db_path } => {
+                if !db_path.join("indexes").exists() {
+                    bail!("{} has no indexes", db_path.display());
+                }
+                let indexes = IndexStore::new(db_path.join("indexes"));
+                if indexes.is_backfilled(BackfilledIndex::OwnerType)? {
+                    println!("The owner type index is already backfilled");
+                } else {
+                    let num_objects = indexes.backfill_owner_type_index()?;
+                    println!("Indexed {num_objects} objects by owner and type");
+                }
+            }
+            ToolCommand::BackfillCoinBalanceIndex { db_path } => {
+                if !db_path.join("indexes").exists() {
+                    bail!("{} has no indexes", db_path.display());
+                }
+                let indexes = IndexStore::new(db_path.join("indexes"));
+                if indexes.is_backfilled(BackfilledIndex::CoinBalance)? {
+                    println!("The coin balance index is already backfilled");
+                } else {
+                    let store = AuthorityStore::open_existing(&db_path.join("store"), None)?;
+                    let num_coins =
+                        indexes.backfill_coin_balance_index(|id| store.get_object(id))?;
+                    println!("Indexed {num_coins} coins by owner and balance");
+                }
+            }
+            ToolCommand::MigrateLargeObjects { db_path, threshold } => {
+                let store = AuthorityStore::open_existing(&db_path.join("store"), None)?;
+                let num_objects = store.migrate_large_objects(threshold)?;
+                println!("Moved {num_objects} objects larger than {threshold} bytes");
+            }
             ToolCommand::DumpValidators { genesis, concise } => {
                 let genesis = Genesis::load(genesis).unwrap();
                 if !concise {
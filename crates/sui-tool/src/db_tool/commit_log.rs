@@ -0,0 +1,51 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Replays the commit log uploaded by a node into its database, to bring a database restored
+//! from a backup up to date without syncing it again.
+
+use anyhow::{Context, Result};
+use std::path::Path;
+use sui_core::authority::AuthorityStore;
+use sui_storage::archive::ArchiveStore;
+use sui_storage::commit_log::CommitLogReader;
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct ReplaySummary {
+    pub num_segments: u64,
+    /// Records that changed the database.
+    pub num_applied: u64,
+    /// Records already reflected in the database.
+    pub num_skipped: u64,
+}
+
+/// Replays the segments of the log in `store` from `from_segment` into the database of a node
+/// at `db_path`. Stops with an error at the first record that cannot be replayed, leaving the
+/// records before it replayed.
+pub async fn replay_commit_log(
+    db_path: &Path,
+    store: ArchiveStore,
+    from_segment: u64,
+) -> Result<ReplaySummary> {
+    let authority_store = AuthorityStore::open_existing(&db_path.join("store"), None)?;
+    let reader = CommitLogReader::new(store);
+    let manifest = reader.manifest().await?;
+
+    let mut summary = ReplaySummary::default();
+    for segment_number in from_segment..manifest.next_segment {
+        let segment = reader.get_segment(&manifest, segment_number).await?;
+        for record in segment.records {
+            let applied = authority_store
+                .replay_commit_log_record(record)
+                .await
+                .with_context(|| format!("Cannot replay segment {segment_number}"))?;
+            if applied {
+                summary.num_applied += 1;
+            } else {
+                summary.num_skipped += 1;
+            }
+        }
+        summary.num_segments += 1;
+    }
+    Ok(summary)
+}
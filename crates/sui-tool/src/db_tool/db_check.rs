@@ -0,0 +1,372 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Checks that the tables of a node's database agree with each other, to find corruption before
+//! the node panics on it.
+
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::path::Path;
+use sui_core::authority::authority_store_tables::AuthorityPerpetualTables;
+use sui_core::authority::authority_store_types::StoreData;
+use sui_core::checkpoints::CheckpointStore;
+use sui_types::messages::TransactionEffectsAPI;
+use sui_types::storage::ObjectKey;
+use typed_store::traits::Map;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum IssueKind {
+    /// Effects of a transaction that is not in the `transactions` table.
+    EffectsWithoutTransaction,
+    /// An executed transaction whose effects are not in the `effects` table.
+    ExecutedWithoutEffects,
+    /// Effects whose events are not in the `events` table.
+    EffectsWithoutEvents,
+    /// A lock on an object version that is not in the `objects` table.
+    LockedObjectMissing,
+    /// An object stored indirectly, whose contents are not in the `indirect_move_objects` table.
+    IndirectObjectMissing,
+    /// A certified checkpoint whose contents are not in the `checkpoint_content` table.
+    CheckpointContentsMissing,
+    /// Checkpoint contents whose digest is not the one they are stored under.
+    CheckpointContentsDigestMismatch,
+    /// A transaction of a checkpoint that was executed with other effects than the checkpoint's.
+    CheckpointEffectsMismatch,
+    /// A certified checkpoint that does not follow the one before it.
+    CheckpointChainBroken,
+}
+
+impl IssueKind {
+    /// How to repair the database.
+    pub fn repair(&self) -> &'static str {
+        match self {
+            IssueKind::EffectsWithoutTransaction | IssueKind::ExecutedWithoutEffects => {
+                "re-sync the transactions from a peer with state sync, or restore a backup"
+            }
+            IssueKind::EffectsWithoutEvents => {
+                "re-execute the transactions, or re-sync them from a peer with state sync"
+            }
+            IssueKind::LockedObjectMissing => {
+                "delete the locks, which are re-created when the objects are re-synced"
+            }
+            IssueKind::IndirectObjectMissing => {
+                "re-sync the transactions that wrote the objects, or restore a backup"
+            }
+            IssueKind::CheckpointContentsMissing
+            | IssueKind::CheckpointContentsDigestMismatch
+            | IssueKind::CheckpointChainBroken => {
+                "delete the checkpoints from the first affected one, and re-sync them from a peer"
+            }
+            IssueKind::CheckpointEffectsMismatch => {
+                "revert the transactions, and re-execute them from the checkpoints"
+            }
+        }
+    }
+}
+
+impl Display for IssueKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let description = match self {
+            IssueKind::EffectsWithoutTransaction => "effects without their transaction",
+            IssueKind::ExecutedWithoutEffects => "executed transactions without their effects",
+            IssueKind::EffectsWithoutEvents => "effects without their events",
+            IssueKind::LockedObjectMissing => "locked objects missing",
+            IssueKind::IndirectObjectMissing => "indirect object contents missing",
+            IssueKind::CheckpointContentsMissing => "checkpoint contents missing",
+            IssueKind::CheckpointContentsDigestMismatch => "checkpoint contents digest mismatches",
+            IssueKind::CheckpointEffectsMismatch => "checkpoint effects mismatches",
+            IssueKind::CheckpointChainBroken => "broken checkpoint chain links",
+        };
+        write!(f, "{description}")
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct DbCheckReport {
+    /// The number of entries scanned per table.
+    pub scanned: BTreeMap<&'static str, usize>,
+    /// What is wrong, per kind of issue.
+    pub issues: BTreeMap<IssueKind, Vec<String>>,
+}
+
+impl DbCheckReport {
+    pub fn is_consistent(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    fn report(&mut self, kind: IssueKind, issue: String) {
+        self.issues.entry(kind).or_default().push(issue);
+    }
+
+    /// Prints the report, with at most `max_issues` issues of each kind.
+    pub fn print(&self, max_issues: usize) {
+        for (table, count) in &self.scanned {
+            println!("Scanned {count} entries of {table}");
+        }
+        if self.is_consistent() {
+            println!("No inconsistency found");
+            return;
+        }
+        for (kind, issues) in &self.issues {
+            println!("\n{} {kind}:", issues.len());
+            for issue in issues.iter().take(max_issues) {
+                println!("  {issue}");
+            }
+            if issues.len() > max_issues {
+                println!("  ... and {} more", issues.len() - max_issues);
+            }
+            println!("Repair: {}", kind.repair());
+        }
+    }
+}
+
+/// Checks the database of a node, whose path is the `db-path` of its config.
+pub fn check_db(db_path: &Path) -> anyhow::Result<DbCheckReport> {
+    let mut report = DbCheckReport::default();
+    check_authority_store(&db_path.join("store"), &mut report)?;
+    check_checkpoints(db_path, &mut report)?;
+    Ok(report)
+}
+
+fn check_authority_store(path: &Path, report: &mut DbCheckReport) -> anyhow::Result<()> {
+    let tables = AuthorityPerpetualTables::open_readonly(path);
+
+    let mut count = 0;
+    for (digest, effects) in tables.effects.iter() {
+        count += 1;
+        let transaction = effects.transaction_digest();
+        if !tables.transactions.contains_key(transaction)? {
+            report.report(
+                IssueKind::EffectsWithoutTransaction,
+                format!("effects {digest} of transaction {transaction}"),
+            );
+        }
+        if let Some(events) = effects.events_digest() {
+            if !tables.events.contains_key(events)? {
+                report.report(
+                    IssueKind::EffectsWithoutEvents,
+                    format!("effects {digest} of transaction {transaction}, events {events:?}"),
+                );
+            }
+        }
+    }
+    report.scanned.insert("effects", count);
+
+    let mut count = 0;
+    for (transaction, effects) in tables.executed_effects.iter() {
+        count += 1;
+        if !tables.effects.contains_key(&effects)? {
+            report.report(
+                IssueKind::ExecutedWithoutEffects,
+                format!("transaction {transaction}, effects {effects}"),
+            );
+        }
+    }
+    report.scanned.insert("executed_effects", count);
+
+    let mut count = 0;
+    for ((id, version, digest), _) in tables.owned_object_transaction_locks.iter() {
+        count += 1;
+        if !tables.objects.contains_key(&ObjectKey(id, version))? {
+            report.report(
+                IssueKind::LockedObjectMissing,
+                format!("object {id} at version {version}, digest {digest}"),
+            );
+        }
+    }
+    report
+        .scanned
+        .insert("owned_object_transaction_locks", count);
+
+    let mut count = 0;
+    for (ObjectKey(id, version), object) in tables.objects.iter() {
+        count += 1;
+        if let StoreData::IndirectObject(metadata) = object.data {
+            if !tables
+                .indirect_move_objects
+                .contains_key(&metadata.digest)?
+            {
+                report.report(
+                    IssueKind::IndirectObjectMissing,
+                    format!(
+                        "object {id} at version {version}, contents {}",
+                        metadata.digest
+                    ),
+                );
+            }
+        }
+    }
+    report.scanned.insert("objects", count);
+    Ok(())
+}
+
+fn check_checkpoints(db_path: &Path, report: &mut DbCheckReport) -> anyhow::Result<()> {
+    let store = CheckpointStore::open_readonly(&db_path.join("checkpoints"));
+    let perpetual = AuthorityPerpetualTables::open_readonly(&db_path.join("store"));
+
+    let mut count = 0;
+    for (digest, contents) in store.checkpoint_content.iter() {
+        count += 1;
+        if contents.digest() != digest {
+            report.report(
+                IssueKind::CheckpointContentsDigestMismatch,
+                format!("contents {digest} have digest {}", contents.digest()),
+            );
+        }
+    }
+    report.scanned.insert("checkpoint_content", count);
+
+    let mut count = 0;
+    let mut previous = None;
+    for (sequence_number, checkpoint) in store.certified_checkpoints.iter() {
+        count += 1;
+        if let Some((previous_sequence_number, previous_digest)) = previous {
+            if previous_sequence_number + 1 == sequence_number
+                && checkpoint.previous_digest() != Some(previous_digest)
+            {
+                report.report(
+                    IssueKind::CheckpointChainBroken,
+                    format!(
+                        "checkpoint {sequence_number} does not follow checkpoint \
+                        {previous_sequence_number} ({previous_digest})"
+                    ),
+                );
+            }
+        }
+        previous = Some((sequence_number, checkpoint.digest()));
+
+        let content_digest = checkpoint.content_digest();
+        let Some(contents) = store.checkpoint_content.get(&content_digest)? else {
+            report.report(
+                IssueKind::CheckpointContentsMissing,
+                format!("checkpoint {sequence_number}, contents {content_digest}"),
+            );
+            continue;
+        };
+        for execution in contents.iter() {
+            let Some(executed) = perpetual.executed_effects.get(&execution.transaction)? else {
+                continue;
+            };
+            if executed != execution.effects {
+                report.report(
+                    IssueKind::CheckpointEffectsMismatch,
+                    format!(
+                        "checkpoint {sequence_number}, transaction {}: effects {executed} were \
+                        executed, the checkpoint has {}",
+                        execution.transaction, execution.effects
+                    ),
+                );
+            }
+        }
+    }
+    report.scanned.insert("certified_checkpoints", count);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::de::DeserializeOwned;
+    use serde::Serialize;
+    use std::sync::Arc;
+    use sui_config::builder::ConfigBuilder;
+    use sui_config::genesis::Genesis;
+    use sui_core::authority::AuthorityStore;
+    use sui_core::epoch::committee_store::CommitteeStore;
+    use sui_types::base_types::TransactionDigest;
+    use sui_types::message_envelope::Message;
+    use sui_types::messages::TrustedTransaction;
+    use sui_types::messages_checkpoint::{CheckpointContents, CheckpointContentsDigest};
+    use typed_store::rocks::{open_cf, DBMap, MetricConf, ReadWriteOptions};
+
+    /// Creates the database of a node at `db_path`, holding the genesis of a new network.
+    async fn create_db(db_path: &Path) -> Genesis {
+        let config_dir = tempfile::tempdir().unwrap();
+        let genesis = ConfigBuilder::new(&config_dir).build().genesis;
+        let committee_store = Arc::new(CommitteeStore::new(
+            db_path.join("epochs"),
+            &genesis.committee().unwrap(),
+            None,
+        ));
+        AuthorityStore::open(
+            &db_path.join("store"),
+            None,
+            &genesis,
+            &committee_store,
+            usize::MAX,
+        )
+        .await
+        .unwrap();
+        let checkpoint_store = CheckpointStore::new(&db_path.join("checkpoints"));
+        checkpoint_store
+            .insert_checkpoint_contents(genesis.checkpoint_contents().clone())
+            .unwrap();
+        checkpoint_store
+            .insert_verified_checkpoint(genesis.checkpoint())
+            .unwrap();
+        genesis
+    }
+
+    /// Removes `key` from `table` of the database at `path`, as a corruption would.
+    fn remove<K, V>(path: &Path, table: &str, key: &K)
+    where
+        K: Serialize + DeserializeOwned,
+        V: Serialize + DeserializeOwned,
+    {
+        let tables = rocksdb::DB::list_cf(&rocksdb::Options::default(), path).unwrap();
+        let tables: Vec<_> = tables.iter().map(String::as_str).collect();
+        let db = open_cf(path, None, MetricConf::default(), &tables).unwrap();
+        DBMap::<K, V>::reopen(&db, Some(table), &ReadWriteOptions::default())
+            .unwrap()
+            .remove(key)
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_consistent_db() {
+        let db_path = tempfile::tempdir().unwrap();
+        create_db(db_path.path()).await;
+
+        let report = check_db(db_path.path()).unwrap();
+        assert!(report.is_consistent(), "{:?}", report.issues);
+        assert_eq!(report.scanned["effects"], 1);
+        assert!(report.scanned["objects"] > 0);
+        assert_eq!(report.scanned["checkpoint_content"], 1);
+        assert_eq!(report.scanned["certified_checkpoints"], 1);
+    }
+
+    #[tokio::test]
+    async fn test_corrupted_db() {
+        let db_path = tempfile::tempdir().unwrap();
+        let genesis = create_db(db_path.path()).await;
+        remove::<TransactionDigest, TrustedTransaction>(
+            &AuthorityPerpetualTables::path(&db_path.path().join("store")),
+            "transactions",
+            genesis.effects().transaction_digest(),
+        );
+        remove::<CheckpointContentsDigest, CheckpointContents>(
+            &db_path.path().join("checkpoints"),
+            "checkpoint_content",
+            &genesis.checkpoint_contents().digest(),
+        );
+
+        let report = check_db(db_path.path()).unwrap();
+        assert!(!report.is_consistent());
+        assert_eq!(
+            report.issues.keys().copied().collect::<Vec<_>>(),
+            vec![
+                IssueKind::EffectsWithoutTransaction,
+                IssueKind::CheckpointContentsMissing
+            ]
+        );
+        assert_eq!(
+            report.issues[&IssueKind::EffectsWithoutTransaction],
+            vec![format!(
+                "effects {} of transaction {}",
+                genesis.effects().digest(),
+                genesis.effects().transaction_digest()
+            )]
+        );
+        assert_eq!(report.scanned["checkpoint_content"], 0);
+    }
+}
@@ -5,7 +5,7 @@ use anyhow::anyhow;
 use clap::{Parser, ValueEnum};
 use eyre::eyre;
 use rocksdb::MultiThreaded;
-use std::collections::{BTreeMap, HashMap};
+use std::collections::HashMap;
 use std::path::PathBuf;
 use strum_macros::EnumString;
 use sui_core::authority::authority_per_epoch_store::AuthorityEpochTables;
@@ -18,7 +18,7 @@ use sui_storage::IndexStoreTables;
 use sui_types::base_types::{EpochId, ObjectID};
 use sui_types::messages::{SignedTransactionEffects, TrustedCertificate};
 use sui_types::temporary_store::InnerTemporaryStore;
-use typed_store::rocks::MetricConf;
+use typed_store::rocks::{DumpFilter, DumpFormat, MetricConf};
 use typed_store::traits::{Map, TableSummary};
 
 #[derive(EnumString, Clone, Parser, Debug, ValueEnum)]
@@ -125,43 +125,31 @@ pub fn dump_table(
     epoch: Option<EpochId>,
     db_path: PathBuf,
     table_name: &str,
-    page_size: u16,
-    page_number: usize,
-) -> anyhow::Result<BTreeMap<String, String>> {
+    filter: &DumpFilter,
+    format: DumpFormat,
+) -> anyhow::Result<Vec<(String, String)>> {
     match store_name {
         StoreName::Validator => {
             let epoch_tables = AuthorityEpochTables::describe_tables();
             if epoch_tables.contains_key(table_name) {
                 let epoch = epoch.ok_or_else(|| anyhow!("--epoch is required"))?;
-                AuthorityEpochTables::open_readonly(epoch, &db_path).dump(
-                    table_name,
-                    page_size,
-                    page_number,
-                )
+                AuthorityEpochTables::open_readonly(epoch, &db_path)
+                    .dump_entries(table_name, filter, format)
             } else {
-                AuthorityPerpetualTables::open_readonly(&db_path).dump(
-                    table_name,
-                    page_size,
-                    page_number,
-                )
+                AuthorityPerpetualTables::open_readonly(&db_path)
+                    .dump_entries(table_name, filter, format)
             }
         }
         StoreName::Index => {
-            IndexStoreTables::get_read_only_handle(db_path, None, None, MetricConf::default()).dump(
-                table_name,
-                page_size,
-                page_number,
-            )
+            IndexStoreTables::get_read_only_handle(db_path, None, None, MetricConf::default())
+                .dump_entries(table_name, filter, format)
         }
         StoreName::Wal => Err(eyre!(
             "Dumping WAL not yet supported. It requires kmowing the value type"
         )),
         StoreName::Epoch => {
-            CommitteeStore::get_read_only_handle(db_path, None, None, MetricConf::default()).dump(
-                table_name,
-                page_size,
-                page_number,
-            )
+            CommitteeStore::get_read_only_handle(db_path, None, None, MetricConf::default())
+                .dump_entries(table_name, filter, format)
         }
     }
     .map_err(|err| anyhow!(err.to_string()))
@@ -173,6 +161,9 @@ mod test {
     use sui_core::authority::authority_store_tables::AuthorityPerpetualTables;
 
     use crate::db_tool::db_dump::{dump_table, list_tables, StoreName};
+    use sui_core::epoch::committee_store::CommitteeStore;
+    use sui_types::committee::Committee;
+    use typed_store::rocks::{DumpFilter, DumpFormat};
 
     #[tokio::test]
     async fn db_dump_population() -> Result<(), anyhow::Error> {
@@ -200,8 +191,8 @@ mod test {
                 Some(0),
                 primary_path.clone(),
                 &t,
-                0,
-                0,
+                &DumpFilter::default(),
+                DumpFormat::Debug,
             )
             .is_err()
             {
@@ -220,4 +211,38 @@ mod test {
             )
         );
     }
+
+    #[tokio::test]
+    async fn db_dump_with_filter() -> Result<(), anyhow::Error> {
+        let path = tempfile::tempdir()?.into_path();
+        let (committee, _) = Committee::new_simple_test_committee();
+        let _store = CommitteeStore::new(path.clone(), &committee, None);
+        let dump = |key_prefix: Vec<u8>, limit, format| {
+            let filter = DumpFilter {
+                key_prefix,
+                skip: 0,
+                limit,
+            };
+            dump_table(
+                StoreName::Epoch,
+                None,
+                path.clone(),
+                "committee_map",
+                &filter,
+                format,
+            )
+        };
+
+        // Epochs are stored as big endian u64 keys.
+        let entries = dump(vec![0; 8], 10, DumpFormat::BcsHex)?;
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].0, "0000000000000000");
+        assert!(entries[0].1.chars().all(|c| c.is_ascii_hexdigit()));
+
+        let entries = dump(vec![], 10, DumpFormat::Json)?;
+        assert_eq!(entries[0].0, "0");
+        assert!(dump(vec![1], 10, DumpFormat::Debug)?.is_empty());
+        assert!(dump(vec![], 0, DumpFormat::Debug)?.is_empty());
+        Ok(())
+    }
 }
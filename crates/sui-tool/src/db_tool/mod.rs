@@ -2,10 +2,14 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use self::db_dump::{dump_table, duplicate_objects_summary, list_tables, table_summary, StoreName};
-use clap::Parser;
+use clap::{Parser, ValueEnum};
 use std::path::PathBuf;
+use std::str::FromStr;
 use sui_types::base_types::EpochId;
+use typed_store::rocks::{DumpFilter, DumpFormat};
 
+pub mod commit_log;
+pub mod db_check;
 pub mod db_dump;
 
 #[derive(Parser)]
@@ -26,12 +30,20 @@ pub struct Dump {
     /// The name of the table to dump
     #[clap(long = "table-name")]
     table_name: String,
-    /// The size of page to dump. This is a u16
-    #[clap(long = "page-size")]
+    /// The size of page to dump, which is the maximum number of entries printed. This is a u16
+    #[clap(long = "page-size", alias = "limit")]
     page_size: u16,
     /// The page number to dump
-    #[clap(long = "page-num")]
+    #[clap(long = "page-num", default_value = "0")]
     page_number: usize,
+    /// Only dump the entries whose key, as stored in the table, starts with these bytes in hex,
+    /// e.g. the ID of an object in the `objects` table
+    #[clap(long = "key-prefix")]
+    key_prefix: Option<HexBytes>,
+    /// How to print keys and values: `debug`, `json`, or `bcs-hex` for keys as stored in the
+    /// table and BCS encoded values in hex
+    #[clap(long = "format", value_enum, default_value = "debug")]
+    format: OutputFormat,
 
     // TODO: We should load this automatically from the system object in AuthorityPerpetualTables.
     // This is very difficult to do right now because you can't share code between
@@ -41,17 +53,60 @@ pub struct Dump {
     epoch: Option<EpochId>,
 }
 
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum OutputFormat {
+    Debug,
+    Json,
+    BcsHex,
+}
+
+impl From<OutputFormat> for DumpFormat {
+    fn from(format: OutputFormat) -> Self {
+        match format {
+            OutputFormat::Debug => DumpFormat::Debug,
+            OutputFormat::Json => DumpFormat::Json,
+            OutputFormat::BcsHex => DumpFormat::BcsHex,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct HexBytes(Vec<u8>);
+
+impl FromStr for HexBytes {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.strip_prefix("0x").unwrap_or(s);
+        if !s.is_ascii() || s.len() % 2 != 0 {
+            anyhow::bail!("{s} is not an even number of hex digits");
+        }
+        (0..s.len())
+            .step_by(2)
+            .map(|i| Ok(u8::from_str_radix(&s[i..i + 2], 16)?))
+            .collect::<anyhow::Result<_>>()
+            .map(Self)
+    }
+}
+
 pub fn execute_db_tool_command(db_path: PathBuf, cmd: DbToolCommand) -> anyhow::Result<()> {
     match cmd {
         DbToolCommand::ListTables => print_db_all_tables(db_path),
-        DbToolCommand::Dump(d) => print_all_entries(
-            d.store_name,
-            d.epoch,
-            db_path,
-            &d.table_name,
-            d.page_size,
-            d.page_number,
-        ),
+        DbToolCommand::Dump(d) => {
+            let filter = DumpFilter {
+                key_prefix: d.key_prefix.map(|prefix| prefix.0).unwrap_or_default(),
+                skip: d.page_number * d.page_size as usize,
+                limit: d.page_size as usize,
+            };
+            print_all_entries(
+                d.store_name,
+                d.epoch,
+                db_path,
+                &d.table_name,
+                &filter,
+                d.format.into(),
+            )
+        }
         DbToolCommand::TableSummary(d) => {
             print_db_table_summary(d.store_name, d.epoch, db_path, &d.table_name)
         }
@@ -110,11 +165,16 @@ pub fn print_all_entries(
     epoch: Option<EpochId>,
     path: PathBuf,
     table_name: &str,
-    page_size: u16,
-    page_number: usize,
+    filter: &DumpFilter,
+    format: DumpFormat,
 ) -> anyhow::Result<()> {
-    for (k, v) in dump_table(store, epoch, path, table_name, page_size, page_number)? {
-        println!("{:>100?}: {:?}", k, v);
+    for (k, v) in dump_table(store, epoch, path, table_name, filter, format)? {
+        match format {
+            DumpFormat::Debug => println!("{:>100?}: {:?}", k, v),
+            // One JSON object or key-value pair per line, to be processed by other tools.
+            DumpFormat::Json => println!("{{\"key\":{k},\"value\":{v}}}"),
+            DumpFormat::BcsHex => println!("{k} {v}"),
+        }
     }
     Ok(())
 }
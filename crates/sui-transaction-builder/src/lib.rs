@@ -15,7 +15,10 @@ use move_core_types::language_storage::TypeTag;
 
 use sui_adapter::adapter::resolve_and_type_check;
 use sui_adapter::execution_mode::ExecutionMode;
-use sui_json::{resolve_move_function_args, SuiJsonCallArg, SuiJsonValue};
+use sui_json::{
+    resolve_move_function_args, resolve_move_function_args_with_results, SuiJsonCallArg,
+    SuiJsonValue,
+};
 use sui_json_rpc_types::{
     RPCTransactionRequestParams, SuiData, SuiObjectDataOptions, SuiObjectInfo, SuiObjectResponse,
     SuiTypeTag,
@@ -26,8 +29,8 @@ use sui_types::coin::{Coin, LockedCoin};
 use sui_types::error::UserInputError;
 use sui_types::gas_coin::GasCoin;
 use sui_types::messages::{
-    CallArg, InputObjectKind, MoveCall, ObjectArg, SingleTransactionKind, TransactionData,
-    TransactionKind, TransferObject,
+    CallArg, InputObjectKind, MoveCall, ObjectArg, ProgrammableTransaction, SingleTransactionKind,
+    TransactionData, TransactionKind, TransferObject,
 };
 
 use sui_types::governance::{
@@ -86,7 +89,14 @@ impl<Mode: ExecutionMode> TransactionBuilder<Mode> {
                 .filter(|obj| obj.type_ == GasCoin::type_().to_string());
             let required_gas_amount = (budget as u128) * (gas_price as u128);
 
+            // Pick the smallest coin covering the budget, keeping the larger ones for larger
+            // budgets, among those not used as inputs.
+            let mut selected: Option<(u64, ObjectRef)> = None;
+            let mut total = 0u128;
             for obj in gas_objs {
+                if input_objects.contains(&obj.object_id) {
+                    continue;
+                }
                 let response = self
                     .0
                     .get_object_with_options(obj.object_id, SuiObjectDataOptions::new().with_bcs())
@@ -100,13 +110,18 @@ impl<Mode: ExecutionMode> TransactionBuilder<Mode> {
                         .ok_or_else(|| anyhow!("Cannot parse move object to gas object"))?
                         .bcs_bytes,
                 )?;
-                if !input_objects.contains(&obj.object_id)
-                    && (gas.value() as u128) >= required_gas_amount
+                total += gas.value() as u128;
+                if (gas.value() as u128) >= required_gas_amount
+                    && selected.map_or(true, |(value, _)| gas.value() < value)
                 {
-                    return Ok(obj.object_ref());
+                    selected = Some((gas.value(), obj.object_ref()));
                 }
             }
-            Err(anyhow!("Cannot find gas coin for signer address [{signer}] with amount sufficient for the required gas amount [{required_gas_amount}]."))
+            match selected {
+                Some((_, gas)) => Ok(gas),
+                None if total >= required_gas_amount => Err(anyhow!("Cannot find gas coin for signer address [{signer}] with amount sufficient for the required gas amount [{required_gas_amount}], but its coins add up to [{total}]. Merge them with `sui client gas merge-all`.")),
+                None => Err(anyhow!("Cannot find gas coin for signer address [{signer}] with amount sufficient for the required gas amount [{required_gas_amount}].")),
+            }
         }
     }
 
@@ -365,23 +380,7 @@ impl<Mode: ExecutionMode> TransactionBuilder<Mode> {
         type_args: &[TypeTag],
         json_args: Vec<SuiJsonValue>,
     ) -> Result<Vec<CallArg>, anyhow::Error> {
-        let object = self
-            .0
-            .get_object_with_options(package_id, SuiObjectDataOptions::bcs_lossless())
-            .await?
-            .into_object()?;
-        let package = object
-            .bcs
-            .ok_or_else(|| anyhow!("Bcs field in object [{}] is missing.", package_id))?
-            .try_as_package()
-            .cloned()
-            .ok_or_else(|| anyhow!("Object [{}] is not a move package.", package_id))?;
-        let package: MovePackage = MovePackage::new(
-            package.id,
-            object.version,
-            &package.module_map,
-            ProtocolConfig::get_for_min_version().max_move_package_size(),
-        )?;
+        let package = self.get_move_package(package_id).await?;
 
         let json_args = resolve_move_function_args(
             &package,
@@ -394,19 +393,7 @@ impl<Mode: ExecutionMode> TransactionBuilder<Mode> {
         let mut args = Vec::new();
         let mut objects = BTreeMap::new();
         for arg in json_args {
-            args.push(match arg {
-                SuiJsonCallArg::Object(id) => {
-                    CallArg::Object(self.get_object_arg(id, &mut objects).await?)
-                }
-                SuiJsonCallArg::Pure(p) => CallArg::Pure(p),
-                SuiJsonCallArg::ObjVec(v) => {
-                    let mut object_ids = vec![];
-                    for id in v {
-                        object_ids.push(self.get_object_arg(id, &mut objects).await?);
-                    }
-                    CallArg::ObjVec(object_ids)
-                }
-            })
+            args.push(self.resolve_json_call_arg(arg, &mut objects).await?)
         }
         let compiled_module = package.deserialize_module(module)?;
 
@@ -423,6 +410,108 @@ impl<Mode: ExecutionMode> TransactionBuilder<Mode> {
         Ok(args)
     }
 
+    /// Resolves the JSON args of a Move call made by a programmable transaction. The args that
+    /// are `None` are results of earlier commands of the transaction, and are left for the caller
+    /// to fill in. As those are only known once the transaction executes, the args are not type
+    /// checked here.
+    pub async fn programmable_move_call_args(
+        &self,
+        package_id: ObjectID,
+        module: &Identifier,
+        function: &Identifier,
+        type_args: &[TypeTag],
+        json_args: Vec<Option<SuiJsonValue>>,
+    ) -> anyhow::Result<Vec<Option<CallArg>>> {
+        let package = self.get_move_package(package_id).await?;
+
+        let json_args = resolve_move_function_args_with_results(
+            &package,
+            module.clone(),
+            function.clone(),
+            type_args,
+            json_args,
+            Mode::allow_arbitrary_function_calls(),
+        )?;
+        let mut args = Vec::new();
+        let mut objects = BTreeMap::new();
+        for arg in json_args {
+            args.push(match arg {
+                Some(arg) => Some(self.resolve_json_call_arg(arg, &mut objects).await?),
+                None => None,
+            })
+        }
+        Ok(args)
+    }
+
+    /// The argument passing the object `id` to a transaction, shared objects being passed by
+    /// mutable reference.
+    pub async fn object_arg(&self, id: ObjectID) -> anyhow::Result<ObjectArg> {
+        self.get_object_arg(id, &mut BTreeMap::new()).await
+    }
+
+    pub async fn programmable(
+        &self,
+        signer: SuiAddress,
+        pt: ProgrammableTransaction,
+        gas: Option<ObjectID>,
+        gas_budget: u64,
+    ) -> anyhow::Result<TransactionData> {
+        let input_objects = pt
+            .input_objects()?
+            .iter()
+            .flat_map(|obj| match obj {
+                InputObjectKind::ImmOrOwnedMoveObject((id, _, _)) => Some(*id),
+                _ => None,
+            })
+            .collect();
+        let gas_price = self.0.get_reference_gas_price().await?;
+        let gas = self
+            .select_gas(signer, gas, gas_budget, input_objects, gas_price)
+            .await?;
+
+        Ok(TransactionData::new_programmable(
+            signer, gas, pt, gas_budget, gas_price,
+        ))
+    }
+
+    async fn resolve_json_call_arg(
+        &self,
+        arg: SuiJsonCallArg,
+        objects: &mut BTreeMap<ObjectID, Object>,
+    ) -> anyhow::Result<CallArg> {
+        Ok(match arg {
+            SuiJsonCallArg::Object(id) => CallArg::Object(self.get_object_arg(id, objects).await?),
+            SuiJsonCallArg::Pure(p) => CallArg::Pure(p),
+            SuiJsonCallArg::ObjVec(v) => {
+                let mut object_ids = vec![];
+                for id in v {
+                    object_ids.push(self.get_object_arg(id, objects).await?);
+                }
+                CallArg::ObjVec(object_ids)
+            }
+        })
+    }
+
+    async fn get_move_package(&self, package_id: ObjectID) -> anyhow::Result<MovePackage> {
+        let object = self
+            .0
+            .get_object_with_options(package_id, SuiObjectDataOptions::bcs_lossless())
+            .await?
+            .into_object()?;
+        let package = object
+            .bcs
+            .ok_or_else(|| anyhow!("Bcs field in object [{}] is missing.", package_id))?
+            .try_as_package()
+            .cloned()
+            .ok_or_else(|| anyhow!("Object [{}] is not a move package.", package_id))?;
+        Ok(MovePackage::new(
+            package.id,
+            object.version,
+            &package.module_map,
+            ProtocolConfig::get_for_min_version().max_move_package_size(),
+        )?)
+    }
+
     pub async fn publish(
         &self,
         sender: SuiAddress,
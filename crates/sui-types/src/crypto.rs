@@ -87,6 +87,20 @@ pub fn generate_proof_of_possession<K: KeypairTraits>(
     keypair.sign(&domain_with_pk[..])
 }
 
+// Verifies a proof generated by generate_proof_of_possession, that the keypair of public_key is
+// possessed and that the proof is bound to address.
+pub fn verify_proof_of_possession<P: VerifyingKey>(
+    proof: &P::Sig,
+    public_key: &P,
+    address: SuiAddress,
+) -> Result<(), FastCryptoError> {
+    let mut domain_with_pk: Vec<u8> = Vec::new();
+    domain_with_pk.extend_from_slice(PROOF_OF_POSSESSION_DOMAIN);
+    domain_with_pk.extend_from_slice(public_key.as_bytes());
+    domain_with_pk.extend_from_slice(address.as_ref());
+    public_key.verify(&domain_with_pk[..], proof)
+}
+
 ///////////////////////////////////////////////
 /// Account Keys
 ///
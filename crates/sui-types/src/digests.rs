@@ -587,6 +587,16 @@ impl fmt::UpperHex for ObjectDigest {
     }
 }
 
+impl std::str::FromStr for ObjectDigest {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut result = [0; 32];
+        result.copy_from_slice(&Base58::decode(s).map_err(|e| anyhow::anyhow!(e))?);
+        Ok(ObjectDigest::new(result))
+    }
+}
+
 impl TryFrom<&[u8]> for ObjectDigest {
     type Error = crate::error::SuiError;
 
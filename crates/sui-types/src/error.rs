@@ -180,6 +180,18 @@ pub enum UserInputError {
 
     #[error("Feature is not yet supported: {0}")]
     Unsupported(String),
+
+    #[error("Invalid programmable transaction: {error}")]
+    MalformedProgrammableTransaction { error: String },
+
+    #[error("Transactions from or sponsored by address {address} are denied by this validator")]
+    AddressDenied { address: SuiAddress },
+
+    #[error("Calls into package {package_id} are denied by this validator")]
+    PackageDenied { package_id: ObjectID },
+
+    #[error("Transaction is not valid BCS: {error}")]
+    TransactionDeserializationError { error: String },
 }
 
 /// Custom error type for Sui.
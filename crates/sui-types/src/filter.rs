@@ -3,11 +3,17 @@
 
 use move_core_types::identifier::Identifier;
 use move_core_types::language_storage::StructTag;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
 use crate::base_types::SuiAddress;
 use crate::event::EventType;
 use crate::event::{Event, EventEnvelope};
+use crate::messages::{
+    Command, SingleTransactionKind, TransactionData, TransactionDataAPI, TransactionEffects,
+    TransactionEffectsAPI,
+};
 use crate::object::Owner;
 use crate::ObjectID;
 
@@ -27,6 +33,7 @@ pub enum EventFilter {
     ObjectId(ObjectID),
     MatchAll(Vec<EventFilter>),
     MatchAny(Vec<EventFilter>),
+    Not(Box<EventFilter>),
 }
 
 impl EventFilter {
@@ -57,6 +64,7 @@ impl EventFilter {
             EventFilter::EventType(type_) => &item.event.event_type() == type_,
             EventFilter::MatchAll(filters) => filters.iter().all(|f| f.matches(item)),
             EventFilter::MatchAny(filters) => filters.iter().any(|f| f.matches(item)),
+            EventFilter::Not(filter) => !filter.matches(item),
             EventFilter::Recipient(recipient) => {
                 matches!(item.event.recipient(), Some(event_recipient) if event_recipient == recipient)
             }
@@ -68,6 +76,78 @@ impl EventFilter {
     }
 }
 
+/// A transaction together with the effects of its execution, as streamed to the subscribers of a
+/// [`TransactionFilter`].
+#[derive(Clone, Debug)]
+pub struct ExecutedTransaction {
+    pub data: TransactionData,
+    pub effects: TransactionEffects,
+}
+
+#[derive(Clone, Debug, JsonSchema, Serialize, Deserialize)]
+pub enum TransactionFilter {
+    /// Transactions sent by the address.
+    FromAddress(SuiAddress),
+    /// Transactions that leave an object owned by the address, e.g. by transferring it a coin.
+    ToAddress(SuiAddress),
+    /// Transactions that take the object as input.
+    InputObject(ObjectID),
+    /// Transactions that call a function of the package.
+    Package(ObjectID),
+    /// Transactions that call a function of the module of the package.
+    MoveModule { package: ObjectID, module: String },
+    /// Transactions that match any of the filters.
+    Any(Vec<TransactionFilter>),
+}
+
+impl Filter<ExecutedTransaction> for TransactionFilter {
+    fn matches(&self, item: &ExecutedTransaction) -> bool {
+        match self {
+            TransactionFilter::FromAddress(address) => item.data.sender() == *address,
+            TransactionFilter::ToAddress(address) => item
+                .effects
+                .all_mutated()
+                .iter()
+                .any(|(_, owner, _)| matches!(owner, Owner::AddressOwner(a) if a == address)),
+            TransactionFilter::InputObject(object_id) => match item.data.input_objects() {
+                Ok(inputs) => inputs.iter().any(|o| o.object_id() == *object_id),
+                Err(_) => false,
+            },
+            TransactionFilter::Package(package) => move_calls(&item.data)
+                .iter()
+                .any(|(call_package, _)| *call_package == package),
+            TransactionFilter::MoveModule { package, module } => {
+                move_calls(&item.data)
+                    .iter()
+                    .any(|(call_package, call_module)| {
+                        *call_package == package && call_module.as_str() == module
+                    })
+            }
+            TransactionFilter::Any(filters) => filters.iter().any(|f| f.matches(item)),
+        }
+    }
+}
+
+/// The package and module of the functions the transaction calls, whether in a legacy move call or
+/// in a command of a programmable transaction.
+fn move_calls(data: &TransactionData) -> Vec<(&ObjectID, &Identifier)> {
+    data.kind()
+        .single_transactions()
+        .flat_map(|single| match single {
+            SingleTransactionKind::Call(call) => vec![(&call.package, &call.module)],
+            SingleTransactionKind::ProgrammableTransaction(programmable) => programmable
+                .commands
+                .iter()
+                .filter_map(|command| match command {
+                    Command::MoveCall(call) => Some((&call.package, &call.module)),
+                    _ => None,
+                })
+                .collect(),
+            _ => vec![],
+        })
+        .collect()
+}
+
 impl Filter<EventEnvelope> for EventFilter {
     fn matches(&self, item: &EventEnvelope) -> bool {
         self.try_matches(item).unwrap_or_default()
@@ -27,6 +27,10 @@ use sui_cost_tables::{
 };
 use sui_protocol_config::*;
 
+pub use sui_cost_tables::gas_profiler::{
+    GasProfile, GasProfileEntry, SharedGasProfile, ENTRY_FRAME, NATIVE_INSTRUCTION,
+};
+
 pub type GasUnits = GasQuantity<GasUnit>;
 pub enum GasPriceUnit {}
 pub enum SuiGasUnit {}
@@ -312,6 +316,11 @@ impl<'a> SuiGasStatus<'a> {
         &mut self.gas_status
     }
 
+    /// Profile the gas charged by Move execution from now on, see `GasStatus::enable_profiling`.
+    pub fn enable_profiling(&mut self) -> SharedGasProfile {
+        self.gas_status.enable_profiling()
+    }
+
     pub fn charge_vm_gas(&mut self) -> Result<(), ExecutionError> {
         // Disable flat fee for now
         // self.deduct_computation_cost(&VM_FLAT_FEE.to_unit())
@@ -479,6 +488,31 @@ pub fn check_gas_balance(
         });
     }
 
+    check_gas_budget(gas_budget, gas_price, cost_table)?;
+
+    let mut gas_balance = get_gas_balance(gas_object)? as u128;
+    let gas_budget_amount = (gas_budget as u128) * (gas_price as u128);
+    for extra_obj in more_gas_objs {
+        gas_balance += get_gas_balance(&extra_obj)? as u128;
+    }
+    ok_or_gas_balance_error!(gas_balance, gas_budget_amount)?;
+
+    let mut total_balance = gas_balance;
+    for extra_obj in extra_objs {
+        total_balance += get_gas_balance(&extra_obj)? as u128;
+    }
+
+    let total_amount = (gas_budget as u128) + (extra_amount as u128);
+    ok_or_gas_balance_error!(total_balance, total_amount)
+}
+
+/// Check that `gas_budget` is within the bounds of the cost table at `gas_price`. This needs none
+/// of the gas objects, so it can run before they are loaded.
+pub fn check_gas_budget(
+    gas_budget: u64,
+    gas_price: u64,
+    cost_table: &SuiCostTable,
+) -> UserInputResult {
     let max_gas_budget = cost_table.max_gas_budget as u128 * gas_price as u128;
     let min_gas_budget = cost_table.min_gas_budget_external() as u128 * gas_price as u128;
 
@@ -495,21 +529,7 @@ pub fn check_gas_balance(
             min_budget: cost_table.min_gas_budget_external(),
         });
     }
-
-    let mut gas_balance = get_gas_balance(gas_object)? as u128;
-    let gas_budget_amount = (gas_budget as u128) * (gas_price as u128);
-    for extra_obj in more_gas_objs {
-        gas_balance += get_gas_balance(&extra_obj)? as u128;
-    }
-    ok_or_gas_balance_error!(gas_balance, gas_budget_amount)?;
-
-    let mut total_balance = gas_balance;
-    for extra_obj in extra_objs {
-        total_balance += get_gas_balance(&extra_obj)? as u128;
-    }
-
-    let total_amount = (gas_budget as u128) + (extra_amount as u128);
-    ok_or_gas_balance_error!(total_balance, total_amount)
+    Ok(())
 }
 
 /// Create a new gas status with the given `gas_budget`, and charge the transaction flat fee.
@@ -39,6 +39,7 @@ pub mod governance;
 pub mod id;
 pub mod in_memory_storage;
 pub mod intent;
+pub mod merkle;
 pub mod message_envelope;
 pub mod messages;
 pub mod messages_checkpoint;
@@ -0,0 +1,168 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Binary Merkle trees over the execution digests of the transactions of a checkpoint, in order.
+//! Leaves and inner nodes are hashed with distinct prefixes, and the last node of a level with an
+//! odd number of nodes is promoted to the next level as is.
+
+use crate::base_types::ExecutionDigests;
+use fastcrypto::hash::{Digest, HashFunction, Sha3_256};
+use serde::{Deserialize, Serialize};
+
+const LEAF_PREFIX: u8 = 0;
+const NODE_PREFIX: u8 = 1;
+
+pub fn leaf_hash(digests: &ExecutionDigests) -> Digest<32> {
+    let mut hasher = Sha3_256::default();
+    hasher.update([LEAF_PREFIX]);
+    hasher.update(digests.transaction);
+    hasher.update(digests.effects);
+    hasher.finalize()
+}
+
+fn node_hash(left: &Digest<32>, right: &Digest<32>) -> Digest<32> {
+    let mut hasher = Sha3_256::default();
+    hasher.update([NODE_PREFIX]);
+    hasher.update(left.digest);
+    hasher.update(right.digest);
+    hasher.finalize()
+}
+
+pub struct MerkleTree {
+    /// The hashes of each level, from the leaves to the root.
+    levels: Vec<Vec<Digest<32>>>,
+}
+
+impl MerkleTree {
+    pub fn new<'a>(leaves: impl IntoIterator<Item = &'a ExecutionDigests>) -> Self {
+        let mut levels = vec![leaves.into_iter().map(leaf_hash).collect::<Vec<_>>()];
+        while levels.last().unwrap().len() > 1 {
+            let level = levels
+                .last()
+                .unwrap()
+                .chunks(2)
+                .map(|pair| match pair {
+                    [left, right] => node_hash(left, right),
+                    [promoted] => promoted.clone(),
+                    _ => unreachable!(),
+                })
+                .collect();
+            levels.push(level);
+        }
+        Self { levels }
+    }
+
+    /// The root of the tree, or the hash of nothing if it has no leaves.
+    pub fn root(&self) -> Digest<32> {
+        self.levels[self.levels.len() - 1]
+            .first()
+            .cloned()
+            .unwrap_or_else(|| Sha3_256::digest(b""))
+    }
+
+    /// The proof that the leaf at `index` is in the tree, if there is such a leaf.
+    pub fn proof(&self, index: usize) -> Option<MerkleProof> {
+        let leaf_count = self.levels[0].len();
+        if index >= leaf_count {
+            return None;
+        }
+        let mut siblings = vec![];
+        let mut position = index;
+        for level in &self.levels[..self.levels.len() - 1] {
+            if let Some(sibling) = level.get(position ^ 1) {
+                siblings.push(sibling.clone());
+            }
+            position /= 2;
+        }
+        Some(MerkleProof {
+            index: index as u64,
+            leaf_count: leaf_count as u64,
+            siblings,
+        })
+    }
+}
+
+/// The path from a leaf of a [`MerkleTree`] to its root.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct MerkleProof {
+    pub index: u64,
+    pub leaf_count: u64,
+    /// The siblings of the nodes on the path, from the leaf up. The promoted nodes have none.
+    pub siblings: Vec<Digest<32>>,
+}
+
+impl MerkleProof {
+    /// The root of the tree that `leaf` is in, according to the proof, or `None` if the proof is
+    /// malformed.
+    pub fn root_of(&self, leaf: &ExecutionDigests) -> Option<Digest<32>> {
+        if self.index >= self.leaf_count {
+            return None;
+        }
+        let mut hash = leaf_hash(leaf);
+        let mut siblings = self.siblings.iter();
+        let (mut position, mut count) = (self.index, self.leaf_count);
+        while count > 1 {
+            let promoted = position % 2 == 0 && position + 1 == count;
+            if !promoted {
+                let sibling = siblings.next()?;
+                hash = if position % 2 == 0 {
+                    node_hash(&hash, sibling)
+                } else {
+                    node_hash(sibling, &hash)
+                };
+            }
+            position /= 2;
+            count = (count + 1) / 2;
+        }
+        siblings.next().is_none().then_some(hash)
+    }
+
+    pub fn verify(&self, root: &Digest<32>, leaf: &ExecutionDigests) -> bool {
+        self.root_of(leaf).as_ref() == Some(root)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_proofs() {
+        for leaf_count in 1..=9 {
+            let leaves: Vec<_> = (0..leaf_count)
+                .map(|_| ExecutionDigests::random())
+                .collect();
+            let tree = MerkleTree::new(&leaves);
+            let root = tree.root();
+            for (index, leaf) in leaves.iter().enumerate() {
+                let proof = tree.proof(index).unwrap();
+                assert!(proof.verify(&root, leaf));
+                // The proof of a leaf does not prove any other leaf.
+                let other = &leaves[(index + 1) % leaves.len()];
+                assert_eq!(proof.verify(&root, other), leaf_count == 1);
+                assert!(!proof.verify(&root, &ExecutionDigests::random()));
+            }
+            assert!(tree.proof(leaf_count).is_none());
+        }
+    }
+
+    #[test]
+    fn test_malformed_proofs() {
+        let leaves: Vec<_> = (0..5).map(|_| ExecutionDigests::random()).collect();
+        let tree = MerkleTree::new(&leaves);
+        let root = tree.root();
+        let proof = tree.proof(1).unwrap();
+
+        let mut extra_sibling = proof.clone();
+        extra_sibling.siblings.push(root.clone());
+        assert!(!extra_sibling.verify(&root, &leaves[1]));
+
+        let mut missing_sibling = proof.clone();
+        missing_sibling.siblings.pop();
+        assert!(!missing_sibling.verify(&root, &leaves[1]));
+
+        let mut out_of_range = proof;
+        out_of_range.index = out_of_range.leaf_count;
+        assert!(!out_of_range.verify(&root, &leaves[1]));
+    }
+}
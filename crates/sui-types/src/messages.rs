@@ -4052,3 +4052,27 @@ pub struct SystemStateRequest {
     // This is needed to make gRPC happy.
     pub _unused: bool,
 }
+
+/// A read of an object from the gRPC service of a fullnode.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct GetObjectRequest {
+    pub object_id: ObjectID,
+    /// The version of the object to read, or its latest version if `None`.
+    pub version: Option<SequenceNumber>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct GetObjectResponse {
+    /// `None` if the object does not exist at the version, or has been deleted or pruned.
+    pub object: Option<Object>,
+}
+
+/// An executed transaction read from the gRPC service of a fullnode.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct GetTransactionResponse {
+    pub transaction: Transaction,
+    pub effects: TransactionEffects,
+    pub events: TransactionEvents,
+    /// The checkpoint the transaction is in, if it is in one yet.
+    pub checkpoint: Option<CheckpointSequenceNumber>,
+}
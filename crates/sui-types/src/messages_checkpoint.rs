@@ -13,6 +13,7 @@ use crate::crypto::{AuthoritySignInfo, AuthoritySignInfoTrait, AuthorityStrongQu
 use crate::error::SuiResult;
 use crate::gas::GasCostSummary;
 use crate::intent::{Intent, IntentScope};
+use crate::merkle::{MerkleProof, MerkleTree};
 use crate::messages::TransactionEffectsAPI;
 use crate::signature::GenericSignature;
 use crate::storage::ReadStore;
@@ -74,10 +75,19 @@ impl Default for ECMHLiveObjectSetDigest {
     }
 }
 
+/// The root of the [`MerkleTree`] over the execution digests of the transactions of a checkpoint,
+/// which the transactions are proven to be in the checkpoint against.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+pub struct TransactionsMerkleRoot {
+    #[schemars(with = "[u8; 32]")]
+    pub digest: Digest<32>,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
 pub enum CheckpointCommitment {
     ECMHLiveObjectSetDigest(ECMHLiveObjectSetDigest),
-    // Other commitment types (e.g. merkle roots) go here.
+    TransactionsMerkleRoot(TransactionsMerkleRoot),
+    // Other commitment types go here.
 }
 
 impl From<ECMHLiveObjectSetDigest> for CheckpointCommitment {
@@ -86,6 +96,12 @@ impl From<ECMHLiveObjectSetDigest> for CheckpointCommitment {
     }
 }
 
+impl From<TransactionsMerkleRoot> for CheckpointCommitment {
+    fn from(root: TransactionsMerkleRoot) -> Self {
+        Self::TransactionsMerkleRoot(root)
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
 pub struct EndOfEpochData {
     /// next_epoch_committee is `Some` if and only if the current checkpoint is
@@ -175,6 +191,30 @@ impl CheckpointSummary {
     pub fn timestamp(&self) -> SystemTime {
         UNIX_EPOCH + Duration::from_millis(self.timestamp_ms)
     }
+
+    /// The Merkle root of the transactions, committed to from the protocol version that enables
+    /// it.
+    pub fn transactions_merkle_root(&self) -> Option<&TransactionsMerkleRoot> {
+        self.checkpoint_commitments
+            .iter()
+            .find_map(|commitment| match commitment {
+                CheckpointCommitment::TransactionsMerkleRoot(root) => Some(root),
+                _ => None,
+            })
+    }
+
+    /// The digest of the live object set at the end of the epoch, committed to by the last
+    /// checkpoint of the epoch from the protocol version that enables it.
+    pub fn root_state_digest(&self) -> Option<&ECMHLiveObjectSetDigest> {
+        self.end_of_epoch_data
+            .as_ref()?
+            .epoch_commitments
+            .iter()
+            .find_map(|commitment| match commitment {
+                CheckpointCommitment::ECMHLiveObjectSetDigest(digest) => Some(digest),
+                _ => None,
+            })
+    }
 }
 
 impl Display for CheckpointSummary {
@@ -535,6 +575,85 @@ impl CheckpointContents {
     pub fn digest(&self) -> CheckpointContentsDigest {
         CheckpointContentsDigest::new(sha3_hash(self))
     }
+
+    pub fn merkle_tree(&self) -> MerkleTree {
+        MerkleTree::new(&self.transactions)
+    }
+
+    pub fn transactions_merkle_root(&self) -> TransactionsMerkleRoot {
+        TransactionsMerkleRoot {
+            digest: self.merkle_tree().root(),
+        }
+    }
+}
+
+/// How a transaction is proven to be in a checkpoint.
+#[allow(clippy::large_enum_variant)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum TransactionInclusion {
+    /// The path to the Merkle root of the transactions that the checkpoint commits to.
+    MerkleProof(MerkleProof),
+    /// All the contents of the checkpoint, for checkpoints without a Merkle root.
+    Contents(CheckpointContents),
+}
+
+/// A proof that a transaction was executed in a certified checkpoint, which clients that do not
+/// run a node verify against the committee of the epoch of the checkpoint.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TransactionInclusionProof {
+    pub checkpoint: CertifiedCheckpointSummary,
+    pub inclusion: TransactionInclusion,
+}
+
+impl TransactionInclusionProof {
+    /// The proof that the transaction with `digests` is in `checkpoint` with `contents`, if it is.
+    pub fn new(
+        checkpoint: CertifiedCheckpointSummary,
+        contents: CheckpointContents,
+        digests: &ExecutionDigests,
+    ) -> Option<Self> {
+        let index = contents.iter().position(|executed| executed == digests)?;
+        let inclusion = if checkpoint.summary.transactions_merkle_root().is_some() {
+            TransactionInclusion::MerkleProof(contents.merkle_tree().proof(index)?)
+        } else {
+            TransactionInclusion::Contents(contents)
+        };
+        Some(Self {
+            checkpoint,
+            inclusion,
+        })
+    }
+
+    /// Checks that the checkpoint is certified by `committee`, and that the transaction with
+    /// `digests` is in it.
+    pub fn verify(&self, committee: &Committee, digests: &ExecutionDigests) -> SuiResult {
+        let contents = match &self.inclusion {
+            TransactionInclusion::Contents(contents) => Some(contents),
+            TransactionInclusion::MerkleProof(_) => None,
+        };
+        self.checkpoint.verify(committee, contents)?;
+        let included = match &self.inclusion {
+            TransactionInclusion::Contents(contents) => {
+                contents.iter().any(|executed| executed == digests)
+            }
+            TransactionInclusion::MerkleProof(proof) => self
+                .checkpoint
+                .summary
+                .transactions_merkle_root()
+                .map_or(false, |root| proof.verify(&root.digest, digests)),
+        };
+        fp_ensure!(
+            included,
+            SuiError::GenericAuthorityError {
+                error: format!(
+                    "Transaction {} is not in checkpoint {}",
+                    digests.transaction,
+                    self.checkpoint.sequence_number()
+                )
+            }
+        );
+        Ok(())
+    }
 }
 
 /// Same as CheckpointContents, but contains full contents of all Transactions and
@@ -788,4 +907,60 @@ mod tests {
 
         assert!(CertifiedCheckpointSummary::aggregate(signed_checkpoints, &committee).is_err());
     }
+
+    #[test]
+    fn test_transaction_inclusion_proof() {
+        let mut rng = StdRng::from_seed(RNG_SEED);
+        let (keys, committee) = make_committee_key(&mut rng);
+        let transactions: Vec<_> = (0..3).map(|_| ExecutionDigests::random()).collect();
+        let set = CheckpointContents::new_with_causally_ordered_transactions(transactions.clone());
+
+        for commit_to_merkle_root in [false, true] {
+            let mut summary = CheckpointSummary::new(
+                committee.epoch,
+                1,
+                0,
+                &set,
+                None,
+                GasCostSummary::default(),
+                None,
+                0,
+            );
+            if commit_to_merkle_root {
+                summary
+                    .checkpoint_commitments
+                    .push(set.transactions_merkle_root().into());
+            }
+            let signed_checkpoints: Vec<_> = keys
+                .iter()
+                .map(|k| {
+                    SignedCheckpointSummary::new_from_summary(summary.clone(), k.public().into(), k)
+                })
+                .collect();
+            let checkpoint_cert =
+                CertifiedCheckpointSummary::aggregate(signed_checkpoints, &committee).unwrap();
+
+            let proof = TransactionInclusionProof::new(
+                checkpoint_cert.clone(),
+                set.clone(),
+                &transactions[1],
+            )
+            .unwrap();
+            assert_eq!(
+                matches!(proof.inclusion, TransactionInclusion::MerkleProof(_)),
+                commit_to_merkle_root
+            );
+            assert!(proof.verify(&committee, &transactions[1]).is_ok());
+            assert!(proof.verify(&committee, &transactions[0]).is_err());
+            assert!(proof
+                .verify(&committee, &ExecutionDigests::random())
+                .is_err());
+            assert!(TransactionInclusionProof::new(
+                checkpoint_cert,
+                set.clone(),
+                &ExecutionDigests::random()
+            )
+            .is_none());
+        }
+    }
 }
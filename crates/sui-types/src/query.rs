@@ -7,6 +7,7 @@ use serde::Serialize;
 
 use crate::base_types::{SuiAddress, TransactionDigest};
 use crate::event::EventType;
+use crate::messages_checkpoint::CheckpointSequenceNumber;
 use crate::object::Owner;
 use crate::ObjectID;
 
@@ -28,6 +29,16 @@ pub enum TransactionQuery {
     FromAddress(SuiAddress),
     /// Query by recipient address.
     ToAddress(SuiAddress),
+    /// Query by the time the transaction was indexed at by the node, in [start_time, end_time)
+    #[serde(rename_all = "camelCase")]
+    TimeRange {
+        /// left endpoint of time interval, milliseconds since epoch, inclusive
+        start_time: u64,
+        /// right endpoint of time interval, milliseconds since epoch, exclusive
+        end_time: u64,
+    },
+    /// Query by all of the given queries, e.g. the calls of a Move function by a sender.
+    MatchAll(Vec<TransactionQuery>),
 }
 
 #[derive(Clone, Debug, JsonSchema, Serialize, Deserialize)]
@@ -66,4 +77,12 @@ pub enum EventQuery {
         /// right endpoint of time interval, milliseconds since epoch, exclusive
         end_time: u64,
     },
+    /// Return events emitted by the transactions of checkpoints [start_checkpoint, end_checkpoint)
+    #[serde(rename_all = "camelCase")]
+    Checkpoint {
+        /// first checkpoint of the range, inclusive
+        start_checkpoint: CheckpointSequenceNumber,
+        /// last checkpoint of the range, exclusive
+        end_checkpoint: CheckpointSequenceNumber,
+    },
 }
@@ -0,0 +1,157 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::BTreeMap;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::base_types::SuiAddress;
+use crate::committee::EpochId;
+
+use super::sui_system_state_summary::SuiSystemStateSummary;
+
+/// Mirrors `sui::voting_power::TOTAL_VOTING_POWER`.
+pub const TOTAL_VOTING_POWER: u64 = 10_000;
+/// Mirrors `sui::voting_power::QUORUM_THRESHOLD`.
+pub const QUORUM_THRESHOLD: u64 = 6_667;
+
+/// A gas price a validator has asked to use starting from the next epoch, weighted by its
+/// voting power.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema)]
+pub struct SuiGasPriceQuote {
+    pub sui_address: SuiAddress,
+    pub name: String,
+    pub voting_power: u64,
+    pub gas_price: u64,
+    pub next_epoch_gas_price: u64,
+}
+
+/// This is the JSON-RPC type for the gas price survey of the current epoch.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema)]
+pub struct SuiGasPriceSurvey {
+    pub epoch: EpochId,
+    /// The reference gas price in effect for the current epoch.
+    pub reference_gas_price: u64,
+    /// The reference gas price the next epoch would start with, if no validator changes its
+    /// quote before the end of the current epoch.
+    pub next_epoch_reference_gas_price: u64,
+    pub quotes: Vec<SuiGasPriceQuote>,
+}
+
+/// Aggregates the gas price quotes submitted by validators during an epoch.
+#[derive(Debug, Default, Clone)]
+pub struct GasPriceSurvey {
+    // validator address -> (voting power, gas price)
+    quotes: BTreeMap<SuiAddress, (u64, u64)>,
+}
+
+impl GasPriceSurvey {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records `gas_price` as the quote of `validator`, replacing any earlier quote.
+    pub fn record_quote(&mut self, validator: SuiAddress, voting_power: u64, gas_price: u64) {
+        self.quotes.insert(validator, (voting_power, gas_price));
+    }
+
+    pub fn len(&self) -> usize {
+        self.quotes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.quotes.is_empty()
+    }
+
+    /// Computes the stake weighted reference gas price from the recorded quotes, the same way
+    /// `sui::validator_set::derive_reference_gas_price` does on-chain: quotes are visited from
+    /// the highest to the lowest price, and the reference gas price is the price at which the
+    /// accumulated voting power first reaches `TOTAL_VOTING_POWER - QUORUM_THRESHOLD`.
+    pub fn reference_gas_price(&self) -> u64 {
+        let mut quotes: Vec<_> = self.quotes.values().copied().collect();
+        quotes.sort_by(|(_, a), (_, b)| b.cmp(a));
+
+        let threshold = TOTAL_VOTING_POWER - QUORUM_THRESHOLD;
+        let mut sum = 0;
+        let mut result = 0;
+        for (voting_power, gas_price) in quotes {
+            if sum >= threshold {
+                break;
+            }
+            result = gas_price;
+            sum += voting_power;
+        }
+        result
+    }
+}
+
+impl SuiSystemStateSummary {
+    /// Surveys the next epoch gas price quotes of the active validators.
+    pub fn gas_price_survey(&self) -> SuiGasPriceSurvey {
+        let mut survey = GasPriceSurvey::new();
+        let quotes = self
+            .active_validators
+            .iter()
+            .map(|v| {
+                survey.record_quote(v.sui_address, v.voting_power, v.next_epoch_gas_price);
+                SuiGasPriceQuote {
+                    sui_address: v.sui_address,
+                    name: v.name.clone(),
+                    voting_power: v.voting_power,
+                    gas_price: v.gas_price,
+                    next_epoch_gas_price: v.next_epoch_gas_price,
+                }
+            })
+            .collect();
+        SuiGasPriceSurvey {
+            epoch: self.epoch,
+            reference_gas_price: self.reference_gas_price,
+            next_epoch_reference_gas_price: survey.reference_gas_price(),
+            quotes,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::base_types::dbg_addr;
+
+    fn survey(quotes: &[(u64, u64)]) -> GasPriceSurvey {
+        let mut survey = GasPriceSurvey::new();
+        for (i, (voting_power, gas_price)) in quotes.iter().enumerate() {
+            survey.record_quote(dbg_addr(i as u8), *voting_power, *gas_price);
+        }
+        survey
+    }
+
+    #[test]
+    fn test_reference_gas_price_equal_power() {
+        // The highest quote alone is below the 3333 threshold, the two highest are above it.
+        let s = survey(&[(2500, 10), (2500, 20), (2500, 30), (2500, 40)]);
+        assert_eq!(s.reference_gas_price(), 30);
+    }
+
+    #[test]
+    fn test_reference_gas_price_dominant_validator() {
+        let s = survey(&[(7000, 5), (1000, 100), (1000, 200), (1000, 300)]);
+        assert_eq!(s.reference_gas_price(), 5);
+
+        let s = survey(&[(4000, 500), (2000, 5), (2000, 6), (2000, 7)]);
+        assert_eq!(s.reference_gas_price(), 500);
+    }
+
+    #[test]
+    fn test_reference_gas_price_requote() {
+        let mut s = survey(&[(2500, 10), (2500, 20), (2500, 30), (2500, 40)]);
+        s.record_quote(dbg_addr(3), 2500, 1);
+        assert_eq!(s.len(), 4);
+        assert_eq!(s.reference_gas_price(), 20);
+    }
+
+    #[test]
+    fn test_reference_gas_price_empty() {
+        assert_eq!(GasPriceSurvey::new().reference_gas_price(), 0);
+    }
+}
@@ -22,6 +22,7 @@ use self::sui_system_state_inner_v1::{SuiSystemStateInnerV1, ValidatorMetadataV1
 use self::sui_system_state_summary::SuiSystemStateSummary;
 
 pub mod epoch_start_sui_system_state;
+pub mod gas_price_survey;
 pub mod sui_system_state_inner_v1;
 pub mod sui_system_state_summary;
 
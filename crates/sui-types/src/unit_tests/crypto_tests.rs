@@ -66,6 +66,11 @@ fn test_proof_of_possession() {
     println!("Pubkey: {:?}", Hex::encode(kp.public().as_bytes()));
     println!("Proof of possession: {:?}", Hex::encode(&pop));
     assert!(kp.public().verify(&msg, &pop).is_ok());
+    assert!(verify_proof_of_possession(&pop, kp.public(), address).is_ok());
+
+    // The proof is bound to the address it was generated for.
+    let other_address = SuiAddress::random_for_testing_only();
+    assert!(verify_proof_of_possession(&pop, kp.public(), other_address).is_err());
 }
 
 proptest! {
@@ -7,12 +7,14 @@ use move_core_types::ident_str;
 use move_core_types::identifier::Identifier;
 use serde_json::json;
 
-use crate::base_types::{ObjectDigest, SuiAddress, TransactionDigest};
+use crate::base_types::{random_object_ref, ObjectDigest, SuiAddress, TransactionDigest};
 use crate::event::EventType;
 use crate::event::{Event, EventEnvelope};
-use crate::filter::{EventFilter, Filter};
+use crate::filter::{EventFilter, ExecutedTransaction, Filter, TransactionFilter};
 use crate::gas_coin::GasCoin;
+use crate::messages::{CallArg, TransactionData, TransactionEffects, TransactionEffectsV1};
 use crate::object::{Owner, OBJECT_START_VERSION};
+use crate::programmable_transaction_builder::ProgrammableTransactionBuilder;
 use crate::{ObjectID, MOVE_STDLIB_ADDRESS, SUI_FRAMEWORK_ADDRESS};
 
 #[test]
@@ -113,6 +115,54 @@ fn test_transfer_filter() {
     }
 }
 
+#[test]
+fn test_combined_filters() {
+    let sender = SuiAddress::random_for_testing_only();
+    let move_event = Event::TransferObject {
+        package_id: ObjectID::from(SUI_FRAMEWORK_ADDRESS),
+        transaction_module: Identifier::from(ident_str!("test_module")),
+        sender,
+        recipient: Owner::AddressOwner(SuiAddress::random_for_testing_only()),
+        object_type: "0x2::example::Object".into(),
+        object_id: ObjectID::random(),
+        version: Default::default(),
+    };
+    let envelope = EventEnvelope {
+        timestamp: 0,
+        tx_digest: TransactionDigest::random(),
+        seq_num: 1,
+        event_num: 0,
+        event: move_event,
+        move_struct_json_value: None,
+    };
+
+    let from_sender = EventFilter::SenderAddress(sender);
+    let from_other_sender = EventFilter::SenderAddress(SuiAddress::random_for_testing_only());
+    assert!(!EventFilter::Not(Box::new(from_sender.clone())).matches(&envelope));
+    assert!(EventFilter::Not(Box::new(from_other_sender.clone())).matches(&envelope));
+
+    // Move events of the test module, or transfers, but not from the sender.
+    let filter = EventFilter::MatchAll(vec![
+        EventFilter::MatchAny(vec![
+            EventFilter::EventType(EventType::MoveEvent).and(EventFilter::Module(
+                Identifier::from(ident_str!("test_module")),
+            )),
+            EventFilter::EventType(EventType::TransferObject),
+        ]),
+        EventFilter::Not(Box::new(from_sender)),
+    ]);
+    assert!(!filter.matches(&envelope));
+
+    let filter = EventFilter::MatchAll(vec![
+        EventFilter::MatchAny(vec![
+            EventFilter::EventType(EventType::MoveEvent),
+            EventFilter::EventType(EventType::TransferObject),
+        ]),
+        EventFilter::Not(Box::new(from_other_sender)),
+    ]);
+    assert!(filter.matches(&envelope));
+}
+
 #[test]
 fn test_publish_filter() {
     let package_id = ObjectID::random();
@@ -268,3 +318,128 @@ fn test_checkpoint_filter() {
     };
     assert!(EventFilter::EventType(EventType::Checkpoint).matches(&envelope))
 }
+
+#[test]
+fn test_transaction_filter() {
+    let sender = SuiAddress::random_for_testing_only();
+    let recipient = SuiAddress::random_for_testing_only();
+    let object_ref = random_object_ref();
+    let gas_ref = random_object_ref();
+    let transaction = ExecutedTransaction {
+        data: TransactionData::new_transfer(recipient, object_ref, sender, gas_ref, 10000, 1),
+        effects: TransactionEffects::V1(TransactionEffectsV1 {
+            mutated: vec![
+                (object_ref, Owner::AddressOwner(recipient)),
+                (gas_ref, Owner::AddressOwner(sender)),
+            ],
+            ..Default::default()
+        }),
+    };
+
+    let filters = vec![
+        TransactionFilter::FromAddress(sender),
+        TransactionFilter::ToAddress(recipient),
+        TransactionFilter::InputObject(object_ref.0),
+        TransactionFilter::InputObject(gas_ref.0),
+    ];
+    for filter in &filters {
+        assert!(filter.matches(&transaction), "filter = {:?}", filter);
+    }
+
+    let other_address = SuiAddress::random_for_testing_only();
+    let false_filters = vec![
+        TransactionFilter::FromAddress(recipient),
+        TransactionFilter::ToAddress(other_address),
+        TransactionFilter::InputObject(ObjectID::random()),
+        // A transfer does not call any package.
+        TransactionFilter::Package(ObjectID::from(SUI_FRAMEWORK_ADDRESS)),
+    ];
+    for filter in &false_filters {
+        assert!(!filter.matches(&transaction), "filter = {:?}", filter);
+    }
+
+    assert!(!TransactionFilter::Any(false_filters.clone()).matches(&transaction));
+    let mut filters = false_filters;
+    filters.push(TransactionFilter::ToAddress(recipient));
+    assert!(TransactionFilter::Any(filters).matches(&transaction));
+}
+
+#[test]
+fn test_move_module_transaction_filter() {
+    let sender = SuiAddress::random_for_testing_only();
+    let package = ObjectID::random();
+    let transaction = ExecutedTransaction {
+        data: TransactionData::new_move_call(
+            sender,
+            package,
+            ident_str!("counter").to_owned(),
+            ident_str!("increment").to_owned(),
+            vec![],
+            random_object_ref(),
+            vec![],
+            10000,
+            1,
+        ),
+        effects: TransactionEffects::V1(TransactionEffectsV1::default()),
+    };
+
+    assert!(TransactionFilter::Package(package).matches(&transaction));
+    assert!(TransactionFilter::MoveModule {
+        package,
+        module: "counter".to_string()
+    }
+    .matches(&transaction));
+
+    assert!(!TransactionFilter::MoveModule {
+        package,
+        module: "other".to_string()
+    }
+    .matches(&transaction));
+    assert!(!TransactionFilter::MoveModule {
+        package: ObjectID::random(),
+        module: "counter".to_string()
+    }
+    .matches(&transaction));
+}
+
+#[test]
+fn test_programmable_transaction_move_call_filters() {
+    let sender = SuiAddress::random_for_testing_only();
+    let package = ObjectID::random();
+    let mut builder = ProgrammableTransactionBuilder::new();
+    builder.transfer_sui(sender, Some(1));
+    builder
+        .move_call(
+            package,
+            ident_str!("counter").to_owned(),
+            ident_str!("increment").to_owned(),
+            vec![],
+            vec![CallArg::Pure(bcs::to_bytes(&1u64).unwrap())],
+        )
+        .unwrap();
+    let transaction = ExecutedTransaction {
+        data: TransactionData::new_programmable(
+            sender,
+            random_object_ref(),
+            builder.finish(),
+            10000,
+            1,
+        ),
+        effects: TransactionEffects::V1(TransactionEffectsV1::default()),
+    };
+
+    // The package is called by a command of the programmable transaction.
+    assert!(TransactionFilter::Package(package).matches(&transaction));
+    assert!(!TransactionFilter::Package(ObjectID::random()).matches(&transaction));
+
+    assert!(TransactionFilter::MoveModule {
+        package,
+        module: "counter".to_string()
+    }
+    .matches(&transaction));
+    assert!(!TransactionFilter::MoveModule {
+        package,
+        module: "other".to_string()
+    }
+    .matches(&transaction));
+}
@@ -4,51 +4,79 @@
 use core::fmt;
 use std::sync::Arc;
 use std::{
-    collections::BTreeSet,
+    collections::{BTreeMap, BTreeSet},
     fmt::{Debug, Display, Formatter, Write},
     path::{Path, PathBuf},
+    str::FromStr,
     time::Instant,
 };
 
+use crate::client_ptb::{
+    build_programmable_transaction, parse_move_call_target, read_ptb_file, PtbCommand,
+};
 use crate::config::{Config, PersistedConfig, SuiClientConfig, SuiEnv};
+use crate::faucet::{request_gas, FaucetCoin};
+use crate::keytool::combine_partial_sigs;
+use crate::replay::{replay_transaction, ReplayResult};
 use anyhow::{anyhow, ensure};
 use bip32::DerivationPath;
 use clap::*;
 use colored::Colorize;
 use fastcrypto::{
     encoding::{Base64, Encoding},
-    traits::ToFromBytes,
+    traits::{KeyPair, ToFromBytes},
 };
+use futures::StreamExt;
+use move_binary_format::compatibility::Compatibility;
+use move_binary_format::file_format::CompiledModule;
+use move_binary_format::normalized;
 use move_core_types::language_storage::TypeTag;
 use move_package::BuildConfig as MoveBuildConfig;
 use prettytable::Table;
 use prettytable::{row, table};
 use serde::Serialize;
 use serde_json::{json, Value};
+use sui_config::SUI_KEYSTORE_FILENAME;
 use sui_framework::build_move_package;
 use sui_move::build::resolve_lock_file_path;
+use sui_protocol_config::ProtocolConfig;
 use sui_source_validation::{BytecodeSourceVerifier, SourceMode};
 use sui_types::error::SuiError;
 
 use sui_framework_build::compiled_package::BuildConfig;
 use sui_json::SuiJsonValue;
 use sui_json_rpc_types::{
-    DynamicFieldPage, SuiObjectData, SuiObjectInfo, SuiObjectResponse, SuiRawData,
-    SuiTransactionEffectsAPI, SuiTransactionResponse,
+    CheckpointId, DevInspectArgs, DevInspectResults, DryRunTransactionResponse, DynamicFieldPage,
+    SuiEvent, SuiGasProfileEntry, SuiObjectData, SuiObjectInfo, SuiObjectResponse, SuiParsedData,
+    SuiRawData, SuiTransactionEffectsAPI, SuiTransactionResponse, SuiTypeTag,
+};
+use sui_json_rpc_types::{SuiEventFilter, SuiExecutionStatus, SuiObjectDataOptions};
+use sui_keys::address_book::{address_book_path, AddressBook, AddressOrAlias};
+use sui_keys::keypair_file::{read_authority_keypair_from_file, read_keypair_from_file};
+use sui_keys::keystore::{AccountKeystore, FileBasedKeystore, Keystore};
+use sui_keys::ledger::LedgerKeystore;
+use sui_sdk::{SuiClient, SuiClientBuilder};
+use sui_types::crypto::{
+    generate_proof_of_possession, PublicKey, Signature, SignatureScheme, SuiKeyPair,
 };
-use sui_json_rpc_types::{SuiExecutionStatus, SuiObjectDataOptions};
-use sui_keys::keystore::AccountKeystore;
-use sui_sdk::SuiClient;
-use sui_types::crypto::SignatureScheme;
 use sui_types::dynamic_field::DynamicFieldType;
+use sui_types::filter::TransactionFilter;
 use sui_types::intent::Intent;
+use sui_types::multisig::{ThresholdUnit, WeightUnit};
 use sui_types::signature::GenericSignature;
+use sui_types::sui_system_state::sui_system_state_summary::SuiValidatorSummary;
+use sui_types::sui_system_state::SUI_SYSTEM_MODULE_NAME;
 use sui_types::{
-    base_types::{ObjectID, ObjectRef, SuiAddress},
+    base_types::{ObjectID, ObjectRef, SuiAddress, TransactionDigest},
     gas_coin::GasCoin,
-    messages::{Transaction, VerifiedTransaction},
+    messages::{
+        SingleTransactionKind, Transaction, TransactionData, TransactionDataAPI, TransactionKind,
+        VerifiedTransaction,
+    },
     object::Owner,
-    parse_sui_type_tag, SUI_FRAMEWORK_ADDRESS,
+    parse_sui_type_tag,
+    programmable_transaction_builder::ProgrammableTransactionBuilder,
+    SUI_FRAMEWORK_ADDRESS, SUI_SYSTEM_STATE_OBJECT_ID,
 };
 use tokio::sync::RwLock;
 use tracing::{info, warn};
@@ -64,12 +92,12 @@ pub enum SuiClientCommands {
     /// Switch active address and network(e.g., devnet, local rpc server)
     #[clap(name = "switch")]
     Switch {
-        /// An Sui address to be used as the active address for subsequent
+        /// An Sui address or alias to be used as the active address for subsequent
         /// commands.
         #[clap(long)]
-        address: Option<SuiAddress>,
-        /// The RPC server URL (e.g., local rpc server, devnet rpc server, etc) to be
-        /// used for subsequent commands.
+        address: Option<AddressOrAlias>,
+        /// The environment (e.g., localnet, devnet, etc) to be used for subsequent commands,
+        /// with its keystore and the active address it last had.
         #[clap(long)]
         env: Option<String>,
     },
@@ -82,6 +110,13 @@ pub enum SuiClientCommands {
         rpc: String,
         #[clap(long, value_hint = ValueHint::Url)]
         ws: Option<String>,
+        /// The URL of the faucet of the network.
+        #[clap(long, value_hint = ValueHint::Url)]
+        faucet: Option<String>,
+        /// A keystore file used in this environment only, instead of the keystore shared by the
+        /// environments. It is created if it does not exist.
+        #[clap(long, value_hint = ValueHint::FilePath)]
+        keystore: Option<PathBuf>,
     },
     /// List all Sui environments
     Envs,
@@ -106,6 +141,21 @@ pub enum SuiClientCommands {
         bcs: bool,
     },
 
+    /// Show an object in one view: its type, owner and version, its contents as JSON and as BCS,
+    /// its Display rendered, and a page of its dynamic fields
+    #[clap(name = "inspect")]
+    Inspect {
+        /// Object ID of the object to inspect
+        #[clap(name = "object_id")]
+        id: ObjectID,
+        /// Paging cursor of the dynamic fields, the next cursor of the previous page
+        #[clap(long)]
+        cursor: Option<ObjectID>,
+        /// Maximum dynamic fields shown
+        #[clap(long, default_value = "50")]
+        limit: usize,
+    },
+
     /// Publish Move modules
     #[clap(name = "publish")]
     Publish {
@@ -139,6 +189,31 @@ pub enum SuiClientCommands {
         /// Also publish transitive dependencies that have not already been published.
         #[clap(long)]
         with_unpublished_dependencies: bool,
+
+        #[clap(flatten)]
+        options: TransactionOptions,
+    },
+
+    /// Upgrade a published Move package, checking that the new version is compatible with the one
+    /// on-chain
+    #[clap(name = "upgrade")]
+    Upgrade {
+        /// Path to directory containing the new version of the Move package
+        #[clap(
+            name = "package_path",
+            global = true,
+            parse(from_os_str),
+            default_value = "."
+        )]
+        package_path: PathBuf,
+
+        /// Package build options
+        #[clap(flatten)]
+        build_config: MoveBuildConfig,
+
+        /// ID of the on-chain package to upgrade
+        #[clap(long)]
+        package_id: ObjectID,
     },
 
     /// Verify local Move packages against on-chain packages, and optionally their dependencies.
@@ -171,6 +246,33 @@ pub enum SuiClientCommands {
         address_override: Option<ObjectID>,
     },
 
+    /// Compile a local Move package and compare its bytecode, module by module, with the on-chain
+    /// package at the given address, reporting every mismatch.
+    #[clap(name = "verify-bytecode")]
+    VerifyBytecode {
+        /// Path to directory containing a Move package
+        #[clap(
+            name = "package_path",
+            global = true,
+            parse(from_os_str),
+            default_value = "."
+        )]
+        package_path: PathBuf,
+
+        /// Package build options
+        #[clap(flatten)]
+        build_config: MoveBuildConfig,
+
+        /// ID of the on-chain package to compare with. If the package manifest has a published
+        /// address, it must be this one.
+        #[clap(long)]
+        package_id: ObjectID,
+
+        /// Also compare the package's dependencies with their on-chain versions.
+        #[clap(long)]
+        verify_deps: bool,
+    },
+
     /// Call Move function
     #[clap(name = "call")]
     Call {
@@ -203,14 +305,73 @@ pub enum SuiClientCommands {
         /// Gas budget for this call
         #[clap(long)]
         gas_budget: u64,
+        #[clap(flatten)]
+        options: TransactionOptions,
+    },
+
+    /// Run several commands in a single programmable transaction, in order. Commands are
+    /// `move-call <package>::<module>::<function>[<type args>] <args>...`,
+    /// `split-coins <coin> <amounts>...`, `merge-coins <coin> <coins>...`,
+    /// `transfer-objects <recipient> <objects>...` and `publish <package path>`. An argument can be
+    /// `gas` for the gas coin, `@N` for the result of the Nth command (counting from 0) or `@N.M`
+    /// for its Mth result. The transaction is dry run first, and only executed if it succeeds.
+    #[clap(name = "ptb")]
+    #[clap(group(ArgGroup::new("ptb").required(true).args(&["commands", "file"])))]
+    Ptb {
+        /// A command, quoted, as in `--command "split-coins gas 1000"`. Repeat for each command.
+        #[clap(long = "command", short = 'c')]
+        commands: Vec<PtbCommand>,
+
+        /// Path to a file of commands, one per line. Lines starting with `#` are skipped.
+        #[clap(long)]
+        file: Option<PathBuf>,
+
+        /// ID of the gas object for gas payment, in 20 bytes Hex string
+        /// If not provided, a gas object with at least gas_budget value will be selected
+        #[clap(long)]
+        gas: Option<ObjectID>,
+
+        /// Gas budget for this transaction
+        #[clap(long)]
+        gas_budget: u64,
+
+        #[clap(flatten)]
+        options: TransactionOptions,
+    },
+
+    /// Run a Move function in dev-inspect mode, without executing it, and show the gas its Move
+    /// execution charges, broken down by instruction and by function
+    #[clap(name = "profile-gas")]
+    ProfileGas {
+        /// The function to profile, as `<package>::<module>::<function>`, optionally followed by
+        /// type args in angle brackets, as in `0x2::coin::value<0x2::sui::SUI>`
+        #[clap(long)]
+        function: String,
+        /// Type args of the function, if not given with it
+        #[clap(
+        long,
+        parse(try_from_str = parse_sui_type_tag),
+        multiple_occurrences = false,
+        multiple_values = true
+        )]
+        type_args: Vec<TypeTag>,
+        /// Simplified ordered args like in the function syntax, as with `sui client call`
+        #[clap(long, multiple_occurrences = false, multiple_values = true)]
+        args: Vec<SuiJsonValue>,
+        /// Gas budget of the run. Defaults to the maximum gas of a transaction
+        #[clap(long)]
+        gas_budget: Option<u64>,
+        /// How many of the most expensive instructions and functions to show
+        #[clap(long, default_value = "20")]
+        top: usize,
     },
 
     /// Transfer object
     #[clap(name = "transfer")]
     Transfer {
-        /// Recipient address
+        /// Recipient address or alias
         #[clap(long)]
-        to: SuiAddress,
+        to: AddressOrAlias,
 
         /// Object to transfer, in 20 bytes Hex string
         #[clap(long)]
@@ -224,15 +385,18 @@ pub enum SuiClientCommands {
         /// Gas budget for this transfer
         #[clap(long)]
         gas_budget: u64,
+
+        #[clap(flatten)]
+        options: TransactionOptions,
     },
     /// Transfer SUI, and pay gas with the same SUI coin object.
     /// If amount is specified, only the amount is transferred; otherwise the entire object
     /// is transferred.
     #[clap(name = "transfer-sui")]
     TransferSui {
-        /// Recipient address
+        /// Recipient address or alias
         #[clap(long)]
-        to: SuiAddress,
+        to: AddressOrAlias,
 
         /// Sui coin object to transfer, ID in 20 bytes Hex string. This is also the gas object.
         #[clap(long)]
@@ -245,6 +409,9 @@ pub enum SuiClientCommands {
         /// The amount to transfer, if not specified, the entire coin object will be transferred.
         #[clap(long)]
         amount: Option<u64>,
+
+        #[clap(flatten)]
+        options: TransactionOptions,
     },
     /// Pay coins to recipients following specified amounts, with input coins.
     /// Length of recipients must be the same as that of amounts.
@@ -254,9 +421,9 @@ pub enum SuiClientCommands {
         #[clap(long, multiple_occurrences = false, multiple_values = true)]
         input_coins: Vec<ObjectID>,
 
-        /// The recipient addresses, must be of same length as amounts
+        /// The recipient addresses or aliases, must be of same length as amounts
         #[clap(long, multiple_occurrences = false, multiple_values = true)]
-        recipients: Vec<SuiAddress>,
+        recipients: Vec<AddressOrAlias>,
 
         /// The amounts to be paid, following the order of recipients.
         #[clap(long, multiple_occurrences = false, multiple_values = true)]
@@ -270,6 +437,9 @@ pub enum SuiClientCommands {
         /// Gas budget for this transaction
         #[clap(long)]
         gas_budget: u64,
+
+        #[clap(flatten)]
+        options: TransactionOptions,
     },
 
     /// Pay SUI coins to recipients following following specified amounts, with input coins.
@@ -281,9 +451,9 @@ pub enum SuiClientCommands {
         #[clap(long, multiple_occurrences = false, multiple_values = true)]
         input_coins: Vec<ObjectID>,
 
-        /// The recipient addresses, must be of same length as amounts.
+        /// The recipient addresses or aliases, must be of same length as amounts.
         #[clap(long, multiple_occurrences = false, multiple_values = true)]
-        recipients: Vec<SuiAddress>,
+        recipients: Vec<AddressOrAlias>,
 
         /// The amounts to be paid, following the order of recipients.
         #[clap(long, multiple_occurrences = false, multiple_values = true)]
@@ -292,6 +462,9 @@ pub enum SuiClientCommands {
         /// Gas budget for this transaction
         #[clap(long)]
         gas_budget: u64,
+
+        #[clap(flatten)]
+        options: TransactionOptions,
     },
 
     /// Pay all residual SUI coins to the recipient with input coins, after deducting the gas cost.
@@ -302,13 +475,42 @@ pub enum SuiClientCommands {
         #[clap(long, multiple_occurrences = false, multiple_values = true)]
         input_coins: Vec<ObjectID>,
 
-        /// The recipient address.
+        /// The recipient address or alias.
         #[clap(long, multiple_occurrences = false)]
-        recipient: SuiAddress,
+        recipient: AddressOrAlias,
 
         /// Gas budget for this transaction
         #[clap(long)]
         gas_budget: u64,
+
+        #[clap(flatten)]
+        options: TransactionOptions,
+    },
+
+    /// Pay SUI to the recipients listed in a CSV file of `<address>,<amount>` rows, from the gas
+    /// coins of the sender. The rows are paid in order, by as many programmable transactions as the
+    /// limit on their commands requires. A first row of column names, empty lines and lines
+    /// starting with `#` are skipped. Payments stop at the first transaction that fails, and the
+    /// summary shows the lines left to pay.
+    #[clap(name = "pay-batch")]
+    PayBatch {
+        /// Path to the CSV file of recipients and amounts
+        #[clap(long)]
+        csv: PathBuf,
+
+        /// ID of the coin paying both the amounts and the gas of every transaction.
+        /// If not provided, the smallest coin covering each transaction is selected
+        #[clap(long)]
+        gas: Option<ObjectID>,
+
+        /// Gas budget of each transaction
+        #[clap(long)]
+        gas_budget: u64,
+
+        /// The most recipients to pay per transaction. Defaults to the most a programmable
+        /// transaction can pay
+        #[clap(long)]
+        batch_size: Option<usize>,
     },
 
     /// Obtain the Addresses managed by the client.
@@ -323,21 +525,32 @@ pub enum SuiClientCommands {
         derivation_path: Option<DerivationPath>,
     },
 
+    /// Use the accounts of a Ledger device running the Sui app, instead of the keystore, so that
+    /// transactions are signed on the device. Use `new-address ed25519` to add more of its accounts.
+    #[clap(name = "ledger")]
+    Ledger {
+        /// Number of accounts of the device to use, at m/44'/784'/{0..accounts}'/0'/0'.
+        #[clap(long, default_value = "1")]
+        accounts: u32,
+    },
+
     /// Obtain all objects owned by the address
     #[clap(name = "objects")]
     Objects {
-        /// Address owning the objects
+        /// Address or alias owning the objects
         /// Shows all objects owned by `sui client active-address` if no argument is passed
         #[clap(name = "owner_address")]
-        address: Option<SuiAddress>,
+        address: Option<AddressOrAlias>,
     },
 
     /// Obtain all gas objects owned by the address.
     #[clap(name = "gas")]
     Gas {
-        /// Address owning the objects
+        /// Address or alias owning the objects
         #[clap(name = "owner_address")]
-        address: Option<SuiAddress>,
+        address: Option<AddressOrAlias>,
+        #[clap(subcommand)]
+        cmd: Option<GasCommand>,
     },
 
     /// Query a dynamic field by its address.
@@ -373,6 +586,8 @@ pub enum SuiClientCommands {
         /// Gas budget for this call
         #[clap(long)]
         gas_budget: u64,
+        #[clap(flatten)]
+        options: TransactionOptions,
     },
 
     /// Merge two coin objects into one coin
@@ -390,8 +605,54 @@ pub enum SuiClientCommands {
         /// Gas budget for this call
         #[clap(long)]
         gas_budget: u64,
+        #[clap(flatten)]
+        options: TransactionOptions,
+    },
+
+    /// Stake SUI with a validator, to earn a share of its rewards. The stake is active from the
+    /// next epoch.
+    #[clap(group(ArgGroup::new("stake").required(true).multiple(true).args(&["coins", "amount"])))]
+    Stake {
+        /// Address or alias of the validator, see `sui client validators`
+        #[clap(long)]
+        validator: AddressOrAlias,
+        /// Coins to stake, in 20 bytes Hex string. If not provided, coins of the active address
+        /// adding up to `amount` are selected.
+        #[clap(long, multiple_occurrences = false, multiple_values = true)]
+        coins: Vec<ObjectID>,
+        /// Amount of SUI to stake out of the coins. All of them are staked if not provided.
+        #[clap(long)]
+        amount: Option<u64>,
+        /// ID of the gas object for gas payment, in 20 bytes Hex string
+        /// If not provided, a gas object with at least gas_budget value will be selected
+        #[clap(long)]
+        gas: Option<ObjectID>,
+        /// Gas budget for this call
+        #[clap(long)]
+        gas_budget: u64,
+        #[clap(flatten)]
+        options: TransactionOptions,
     },
 
+    /// Withdraw a stake and its rewards. The SUI is returned at the end of the epoch.
+    Unstake {
+        /// The StakedSui object of the stake, in 20 bytes Hex string
+        #[clap(long)]
+        staked_sui: ObjectID,
+        /// ID of the gas object for gas payment, in 20 bytes Hex string
+        /// If not provided, a gas object with at least gas_budget value will be selected
+        #[clap(long)]
+        gas: Option<ObjectID>,
+        /// Gas budget for this call
+        #[clap(long)]
+        gas_budget: u64,
+        #[clap(flatten)]
+        options: TransactionOptions,
+    },
+
+    /// List the active validators, with their stake, commission rate and estimated APY.
+    Validators,
+
     /// Create an example NFT
     #[clap(name = "create-example-nft")]
     CreateExampleNFT {
@@ -415,14 +676,61 @@ pub enum SuiClientCommands {
         /// Gas budget for this transfer
         #[clap(long)]
         gas_budget: Option<u64>,
+
+        #[clap(flatten)]
+        options: TransactionOptions,
+    },
+
+    /// Register a new network key for the validator of the active address (or of the owner of
+    /// the gas object). Other validators switch to it at the start of the next epoch, so by then
+    /// the node of the validator must have the new key pair as `next-network-key-pair`.
+    #[clap(name = "rotate-network-key")]
+    RotateNetworkKey {
+        /// Path to the new Ed25519 network key pair, Base64 encoded `flag || privkey`
+        #[clap(long)]
+        network_key_file: PathBuf,
+
+        /// ID of the gas object for gas payment, in 20 bytes Hex string
+        /// If not provided, a gas object with at least gas_budget value will be selected
+        #[clap(long)]
+        gas: Option<ObjectID>,
+
+        /// Gas budget for this call
+        #[clap(long)]
+        gas_budget: u64,
+
+        #[clap(flatten)]
+        options: TransactionOptions,
+    },
+
+    /// Register a new protocol key for the validator of the active address (or of the owner of
+    /// the gas object), which it signs with from the start of the next epoch. By then the node of
+    /// the validator must have the new key pair as `next-protocol-key-pair`.
+    #[clap(name = "rotate-protocol-key")]
+    RotateProtocolKey {
+        /// Path to the new BLS12381 protocol key pair, as written by `sui keytool generate bls12381`
+        #[clap(long)]
+        protocol_key_file: PathBuf,
+
+        /// ID of the gas object for gas payment, in 20 bytes Hex string
+        /// If not provided, a gas object with at least gas_budget value will be selected
+        #[clap(long)]
+        gas: Option<ObjectID>,
+
+        /// Gas budget for this call
+        #[clap(long)]
+        gas_budget: u64,
+
+        #[clap(flatten)]
+        options: TransactionOptions,
     },
 
     /// Serialize a transfer that can be signed. This is useful when user prefers to take the data to sign elsewhere.
     #[clap(name = "serialize-transfer-sui")]
     SerializeTransferSui {
-        /// Recipient address
+        /// Recipient address or alias
         #[clap(long)]
-        to: SuiAddress,
+        to: AddressOrAlias,
 
         /// Sui coin object to transfer, ID in 20 bytes Hex string. This is also the gas object.
         #[clap(long)]
@@ -447,6 +755,150 @@ pub enum SuiClientCommands {
         #[clap(long)]
         signatures: Vec<String>,
     },
+
+    /// Combine the signatures of the parties of a MultiSig address into its MultiSig, and execute
+    /// the transaction it signs. The parties sign the transaction with `sui keytool sign`, and the
+    /// MultiSig is described as in `sui keytool multi-sig-address`.
+    ExecuteCombined {
+        /// BCS serialized transaction data bytes without its type tag, as base-64 encoded string,
+        /// whose sender is the MultiSig address.
+        #[clap(long)]
+        tx_bytes: String,
+
+        /// The signatures of the parties, `flag || signature || pubkey` encoded in Base64.
+        #[clap(long, multiple_occurrences = false, multiple_values = true)]
+        sigs: Vec<Signature>,
+
+        /// The public keys of all the parties, `flag || pk` encoded in Base64.
+        #[clap(long, multiple_occurrences = false, multiple_values = true)]
+        pks: Vec<PublicKey>,
+
+        /// The weights of the parties, in the order of `pks`.
+        #[clap(long, multiple_occurrences = false, multiple_values = true)]
+        weights: Vec<WeightUnit>,
+
+        /// The total weight of signatures needed.
+        #[clap(long)]
+        threshold: ThresholdUnit,
+    },
+
+    /// Request gas coins from the faucet of the active environment.
+    Faucet {
+        /// Address or alias to send the coins to, by default the active address
+        #[clap(long)]
+        address: Option<AddressOrAlias>,
+
+        /// The URL of the faucet, by default that of the active environment.
+        #[clap(long, value_hint = ValueHint::Url)]
+        url: Option<String>,
+
+        /// Request coins until they add up to at least this amount of MIST. By default, the
+        /// faucet is requested once.
+        #[clap(long)]
+        amount: Option<u64>,
+    },
+
+    /// Print the events emitted by a package, or the transactions calling it, as they happen.
+    /// Subscribes through the websocket URL of the active environment, until interrupted.
+    Watch {
+        /// ID of the package to watch
+        #[clap(long)]
+        package: ObjectID,
+
+        /// Only watch the events emitted by, or the transactions calling, this module of the
+        /// package
+        #[clap(long)]
+        module: Option<String>,
+
+        /// Watch the transactions calling the package instead of its events
+        #[clap(long)]
+        transactions: bool,
+
+        /// Stop after this many events or transactions
+        #[clap(long)]
+        limit: Option<usize>,
+    },
+
+    /// Fetch a transaction and the objects it read from a fullnode, execute it again locally with
+    /// the protocol config of the network, and compare its effects and gas costs with those it
+    /// had on chain.
+    Replay {
+        /// Digest of the transaction to replay
+        digest: TransactionDigest,
+
+        /// The RPC URL of the fullnode to fetch the transaction from, by default that of the
+        /// active environment.
+        #[clap(long, value_hint = ValueHint::Url)]
+        rpc: Option<String>,
+
+        /// The protocol version to execute the transaction with, by default that of the current
+        /// epoch of the network.
+        #[clap(long)]
+        protocol_version: Option<u64>,
+    },
+}
+
+/// Manage the gas coins of the address of `sui client gas`.
+#[derive(Subcommand)]
+#[clap(rename_all = "kebab-case")]
+pub enum GasCommand {
+    /// Merge all the gas coins of the address into its largest one, so that it can pay for larger
+    /// gas budgets. Takes several transactions when there are more coins than one can merge.
+    MergeAll {
+        /// Gas budget of each merge transaction
+        #[clap(long)]
+        gas_budget: u64,
+    },
+}
+
+/// Options of the commands that send a transaction, to stop short of signing and executing it.
+#[derive(Args, Debug, Default, Clone, Copy)]
+pub struct TransactionOptions {
+    /// Print the unsigned transaction as Base64 encoded BCS bytes instead of signing and
+    /// executing it
+    #[clap(long, conflicts_with_all = &["dry-run", "dev-inspect"])]
+    pub serialize_unsigned_transaction: bool,
+
+    /// Only dry run the transaction, showing its effects, the balance changes it would make and
+    /// the gas it would use, without executing it
+    #[clap(long, conflicts_with = "dev-inspect")]
+    pub dry_run: bool,
+
+    /// Only run the transaction in dev-inspect mode, showing its effects and the values returned
+    /// by its Move calls, without executing it
+    #[clap(long)]
+    pub dev_inspect: bool,
+}
+
+impl TransactionOptions {
+    /// The result of the command when the options ask to stop short of signing and executing the
+    /// transaction `data` of `signer`, or `None` when it should be executed.
+    pub(crate) async fn preview(
+        &self,
+        context: &WalletContext,
+        signer: SuiAddress,
+        data: &TransactionData,
+    ) -> Result<Option<SuiClientCommandResult>, anyhow::Error> {
+        if self.serialize_unsigned_transaction {
+            return Ok(Some(SuiClientCommandResult::SerializedUnsignedTransaction(
+                Base64::encode(bcs::to_bytes(data)?),
+            )));
+        }
+        if self.dry_run {
+            let client = context.get_client().await?;
+            let response = client.read_api().dry_run_transaction(data.clone()).await?;
+            return Ok(Some(SuiClientCommandResult::DryRun(response)));
+        }
+        if self.dev_inspect {
+            let client = context.get_client().await?;
+            let results = client
+                .read_api()
+                .dev_inspect_transaction(signer, data.kind().clone(), Some(data.gas_price()), None)
+                .await?;
+            return Ok(Some(SuiClientCommandResult::DevInspect(results)));
+        }
+        Ok(None)
+    }
 }
 
 impl SuiClientCommands {
@@ -462,6 +914,7 @@ impl SuiClientCommands {
                 gas_budget,
                 skip_dependency_verification,
                 with_unpublished_dependencies,
+                options,
             } => {
                 let sender = context.try_get_object_owner(&gas).await?;
                 let sender = sender.unwrap_or(context.active_address()?);
@@ -513,6 +966,9 @@ impl SuiClientCommands {
                     .transaction_builder()
                     .publish(sender, compiled_modules, gas, gas_budget)
                     .await?;
+                if let Some(preview) = options.preview(context, sender, &data).await? {
+                    return Ok(preview);
+                }
                 let signature =
                     context
                         .config
@@ -528,6 +984,53 @@ impl SuiClientCommands {
                 SuiClientCommandResult::Publish(response)
             }
 
+            SuiClientCommands::Upgrade {
+                package_path,
+                build_config,
+                package_id,
+            } => {
+                let build_config =
+                    resolve_lock_file_path(build_config, Some(package_path.clone()))?;
+
+                let compiled_package = build_move_package(
+                    &package_path,
+                    BuildConfig {
+                        config: build_config,
+                        run_bytecode_verifier: true,
+                        print_diags_to_stderr: true,
+                    },
+                )?;
+
+                let client = context.get_client().await?;
+                let object = client
+                    .read_api()
+                    .get_object_with_options(package_id, SuiObjectDataOptions::new().with_bcs())
+                    .await?
+                    .into_object()?;
+                let Some(SuiRawData::Package(package)) = object.bcs else {
+                    return Err(anyhow!("Object {package_id} is not a package"));
+                };
+
+                check_upgrade_compatibility(
+                    package_id,
+                    &package.module_map,
+                    compiled_package.get_modules(),
+                )?;
+                eprintln!(
+                    "{}",
+                    format!("Package is compatible with the on-chain version of {package_id}.")
+                        .bold()
+                        .green(),
+                );
+
+                // Publishing does not hand out an `UpgradeCap` yet, and programmable transactions
+                // have no command to publish a new version of a package with an upgrade ticket.
+                return Err(anyhow!(
+                    "Upgrading user packages is not supported by this network yet, \
+                     nothing was submitted"
+                ));
+            }
+
             SuiClientCommands::Object { id, bcs } => {
                 // Fetch the object ref
                 let client = context.get_client().await?;
@@ -546,6 +1049,26 @@ impl SuiClientCommands {
                 }
             }
 
+            SuiClientCommands::Inspect { id, cursor, limit } => {
+                let client = context.get_client().await?;
+                let options = SuiObjectDataOptions::full_content()
+                    .with_bcs()
+                    .with_display();
+                let object = client
+                    .read_api()
+                    .get_object_with_options(id, options)
+                    .await?
+                    .into_object()?;
+                let dynamic_fields = client
+                    .read_api()
+                    .get_dynamic_fields(id, cursor, Some(limit))
+                    .await?;
+                SuiClientCommandResult::Inspect(ObjectInspection {
+                    object,
+                    dynamic_fields,
+                })
+            }
+
             SuiClientCommands::DynamicFieldQuery { id, cursor, limit } => {
                 let client = context.get_client().await?;
                 let df_read = client
@@ -563,20 +1086,123 @@ impl SuiClientCommands {
                 gas,
                 gas_budget,
                 args,
+                options,
             } => {
-                let response = call_move(
+                let (sender, data) = move_call_data(
                     package, &module, &function, type_args, gas, gas_budget, args, context,
                 )
                 .await?;
+                if let Some(preview) = options.preview(context, sender, &data).await? {
+                    return Ok(preview);
+                }
+                let response = execute_move_call(sender, data, context).await?;
                 SuiClientCommandResult::Call(response)
             }
 
+            SuiClientCommands::Ptb {
+                commands,
+                file,
+                gas,
+                gas_budget,
+                options,
+            } => {
+                let commands = match file {
+                    Some(file) => read_ptb_file(&file)?,
+                    None => commands,
+                };
+                let sender = context.try_get_object_owner(&gas).await?;
+                let sender = sender.unwrap_or(context.active_address()?);
+
+                let client = context.get_client().await?;
+                let pt = build_programmable_transaction(&client, commands).await?;
+                let data = client
+                    .transaction_builder()
+                    .programmable(sender, pt, gas, gas_budget)
+                    .await?;
+                if let Some(preview) = options.preview(context, sender, &data).await? {
+                    return Ok(preview);
+                }
+                let preview = client.read_api().dry_run_transaction(data.clone()).await?;
+                if let SuiExecutionStatus::Failure { error } = preview.effects.status() {
+                    return Err(anyhow!(
+                        "Transaction failed in dry run, so it was not executed: {error}"
+                    ));
+                }
+
+                let signature =
+                    context
+                        .config
+                        .keystore
+                        .sign_secure(&sender, &data, Intent::default())?;
+                let response = context
+                    .execute_transaction(
+                        Transaction::from_data(data, Intent::default(), vec![signature])
+                            .verify()?,
+                    )
+                    .await?;
+                let effects = &response.effects;
+                if matches!(effects.status(), SuiExecutionStatus::Failure { .. }) {
+                    return Err(anyhow!(
+                        "Error executing programmable transaction: {:#?}",
+                        effects.status()
+                    ));
+                }
+                SuiClientCommandResult::Ptb(response)
+            }
+
+            SuiClientCommands::ProfileGas {
+                function,
+                type_args,
+                args,
+                gas_budget,
+                top,
+            } => {
+                let (package, module, function, mut all_type_args) =
+                    parse_move_call_target(&function)?;
+                all_type_args.extend(type_args);
+                let args = args
+                    .into_iter()
+                    .map(|value| SuiJsonValue::new(convert_number_to_string(value.to_json_value())))
+                    .collect::<Result<_, _>>()?;
+
+                let sender = context.active_address()?;
+                let client = context.get_client().await?;
+                let call = client
+                    .transaction_builder()
+                    .single_move_call(
+                        package,
+                        module.as_str(),
+                        function.as_str(),
+                        all_type_args.into_iter().map(SuiTypeTag::from).collect(),
+                        args,
+                    )
+                    .await?;
+                let args = DevInspectArgs {
+                    gas_budget,
+                    profile_gas: Some(true),
+                    ..Default::default()
+                };
+                let results = client
+                    .read_api()
+                    .dev_inspect_transaction_with_args(
+                        sender,
+                        TransactionKind::Single(SingleTransactionKind::Call(call)),
+                        None,
+                        None,
+                        Some(args),
+                    )
+                    .await?;
+                SuiClientCommandResult::ProfileGas(top, results)
+            }
+
             SuiClientCommands::Transfer {
                 to,
                 object_id,
                 gas,
                 gas_budget,
+                options,
             } => {
+                let to = context.resolve_address(&to)?;
                 let from = context.get_object_owner(&object_id).await?;
                 let time_start = Instant::now();
 
@@ -585,6 +1211,9 @@ impl SuiClientCommands {
                     .transaction_builder()
                     .transfer_object(from, object_id, gas, gas_budget, to)
                     .await?;
+                if let Some(preview) = options.preview(context, from, &data).await? {
+                    return Ok(preview);
+                }
                 let signature =
                     context
                         .config
@@ -612,7 +1241,9 @@ impl SuiClientCommands {
                 sui_coin_object_id: object_id,
                 gas_budget,
                 amount,
+                options,
             } => {
+                let to = context.resolve_address(&to)?;
                 let from = context.get_object_owner(&object_id).await?;
 
                 let client = context.get_client().await?;
@@ -620,6 +1251,9 @@ impl SuiClientCommands {
                     .transaction_builder()
                     .transfer_sui(from, object_id, gas_budget, to, amount)
                     .await?;
+                if let Some(preview) = options.preview(context, from, &data).await? {
+                    return Ok(preview);
+                }
                 let signature =
                     context
                         .config
@@ -644,6 +1278,7 @@ impl SuiClientCommands {
                 amounts,
                 gas,
                 gas_budget,
+                options,
             } => {
                 ensure!(
                     !input_coins.is_empty(),
@@ -661,12 +1296,16 @@ impl SuiClientCommands {
                         amounts.len()
                     ),
                 );
+                let recipients = context.resolve_addresses(&recipients)?;
                 let from = context.get_object_owner(&input_coins[0]).await?;
                 let client = context.get_client().await?;
                 let data = client
                     .transaction_builder()
                     .pay(from, input_coins, recipients, amounts, gas, gas_budget)
                     .await?;
+                if let Some(preview) = options.preview(context, from, &data).await? {
+                    return Ok(preview);
+                }
                 let signature =
                     context
                         .config
@@ -693,6 +1332,7 @@ impl SuiClientCommands {
                 recipients,
                 amounts,
                 gas_budget,
+                options,
             } => {
                 ensure!(
                     !input_coins.is_empty(),
@@ -710,12 +1350,16 @@ impl SuiClientCommands {
                         amounts.len()
                     ),
                 );
+                let recipients = context.resolve_addresses(&recipients)?;
                 let signer = context.get_object_owner(&input_coins[0]).await?;
                 let client = context.get_client().await?;
                 let data = client
                     .transaction_builder()
                     .pay_sui(signer, input_coins, recipients, amounts, gas_budget)
                     .await?;
+                if let Some(preview) = options.preview(context, signer, &data).await? {
+                    return Ok(preview);
+                }
                 let signature =
                     context
                         .config
@@ -737,15 +1381,76 @@ impl SuiClientCommands {
                 SuiClientCommandResult::PaySui(response)
             }
 
+            SuiClientCommands::PayBatch {
+                csv,
+                gas,
+                gas_budget,
+                batch_size,
+            } => {
+                let rows = read_pay_batch_csv(&csv)?;
+                ensure!(!rows.is_empty(), "No recipients in {}", csv.display());
+                // Each payment takes two commands, splitting the coin and transferring the split.
+                let max_commands =
+                    ProtocolConfig::get_for_max_version().max_programmable_tx_commands() as usize;
+                let max_batch_size = (max_commands - 1) / 2;
+                let batch_size = batch_size.unwrap_or(max_batch_size);
+                ensure!(
+                    (1..=max_batch_size).contains(&batch_size),
+                    "Batch size must be between 1 and {max_batch_size}"
+                );
+
+                let sender = context.try_get_object_owner(&gas).await?;
+                let sender = sender.unwrap_or(context.active_address()?);
+                let client = context.get_client().await?;
+                let gas_price = client.read_api().get_reference_gas_price().await?;
+
+                let total_amount = rows.iter().map(|row| row.amount as u128).sum::<u128>();
+                let batches = rows.chunks(batch_size).len() as u128;
+                let required = total_amount + batches * gas_budget as u128 * gas_price as u128;
+                let available = context
+                    .gas_objects(sender)
+                    .await?
+                    .iter()
+                    .filter(|(_, object, _)| gas.map_or(true, |gas| gas == object.object_id))
+                    .map(|(value, ..)| *value as u128)
+                    .sum::<u128>();
+                ensure!(
+                    available >= required,
+                    "The amounts and gas budgets add up to [{required}], more than the [{available}] available to pay them"
+                );
+
+                let mut summary = PayBatchSummary {
+                    recipients: rows.len(),
+                    total_amount,
+                    transactions: vec![],
+                    error: None,
+                };
+                for batch in rows.chunks(batch_size) {
+                    match pay_batch(context, sender, gas, batch, gas_budget, gas_price).await {
+                        Ok(transaction) => summary.transactions.push(transaction),
+                        Err(e) => {
+                            summary.error = Some(format!(
+                                "Lines {} onwards were not paid: {e}",
+                                batch[0].line
+                            ));
+                            break;
+                        }
+                    }
+                }
+                SuiClientCommandResult::PayBatch(summary)
+            }
+
             SuiClientCommands::PayAllSui {
                 input_coins,
                 recipient,
                 gas_budget,
+                options,
             } => {
                 ensure!(
                     !input_coins.is_empty(),
                     "PayAllSui transaction requires a non-empty list of input coins"
                 );
+                let recipient = context.resolve_address(&recipient)?;
                 let signer = context.get_object_owner(&input_coins[0]).await?;
                 let client = context.get_client().await?;
                 let data = client
@@ -753,6 +1458,9 @@ impl SuiClientCommands {
                     .pay_all_sui(signer, input_coins, recipient, gas_budget)
                     .await?;
 
+                if let Some(preview) = options.preview(context, signer, &data).await? {
+                    return Ok(preview);
+                }
                 let signature =
                     context
                         .config
@@ -780,7 +1488,7 @@ impl SuiClientCommands {
             ),
 
             SuiClientCommands::Objects { address } => {
-                let address = address.unwrap_or(context.active_address()?);
+                let address = context.resolve_address_or_active(address.as_ref())?;
                 let client = context.get_client().await?;
                 let address_object = client
                     .read_api()
@@ -797,10 +1505,65 @@ impl SuiClientCommands {
                     .config
                     .keystore
                     .generate_and_add_new_key(key_scheme, derivation_path)?;
+                if let Keystore::Ledger(_) = context.config.keystore {
+                    // Ledger accounts are kept in the client config.
+                    context.config.save()?;
+                }
                 SuiClientCommandResult::NewAddress((address, phrase, scheme))
             }
-            SuiClientCommands::Gas { address } => {
-                let address = address.unwrap_or(context.active_address()?);
+            SuiClientCommands::Ledger { accounts } => {
+                ensure!(accounts > 0, "At least one account is needed");
+                let mut keystore = LedgerKeystore::default();
+                let addresses = (0..accounts)
+                    .map(|_| keystore.add_account(None))
+                    .collect::<Result<Vec<_>, _>>()?;
+                context.config.keystore = Keystore::from(keystore);
+                context.config.active_address = addresses.first().copied();
+                context.config.save()?;
+                SuiClientCommandResult::Ledger(addresses)
+            }
+            SuiClientCommands::Gas {
+                address,
+                cmd: Some(GasCommand::MergeAll { gas_budget }),
+            } => {
+                let address = context.resolve_address_or_active(address.as_ref())?;
+                let mut coins = context.gas_objects(address).await?;
+                // Merge into the largest coin, which pays for the merges.
+                coins.sort_by_key(|(value, ..)| std::cmp::Reverse(*value));
+                let mut coins = coins.into_iter().map(|(_, object, _)| object.object_id);
+                let primary = coins
+                    .next()
+                    .ok_or_else(|| anyhow!("No gas coins found for address [{address}]"))?;
+                let coins: Vec<_> = coins.collect();
+                let max_coins = ProtocolConfig::get_for_max_version().max_coins() as usize;
+                let client = context.get_client().await?;
+                let mut responses = vec![];
+                for chunk in coins.chunks(max_coins - 1) {
+                    let input_coins = std::iter::once(primary)
+                        .chain(chunk.iter().copied())
+                        .collect();
+                    let data = client
+                        .transaction_builder()
+                        .pay_all_sui(address, input_coins, address, gas_budget)
+                        .await?;
+                    let signature =
+                        context
+                            .config
+                            .keystore
+                            .sign_secure(&address, &data, Intent::default())?;
+                    responses.push(
+                        context
+                            .execute_transaction(
+                                Transaction::from_data(data, Intent::default(), vec![signature])
+                                    .verify()?,
+                            )
+                            .await?,
+                    );
+                }
+                SuiClientCommandResult::MergeAllGas(primary, coins.len(), responses)
+            }
+            SuiClientCommands::Gas { address, cmd: None } => {
+                let address = context.resolve_address_or_active(address.as_ref())?;
                 let coins = context
                     .gas_objects(address)
                     .await?
@@ -816,6 +1579,7 @@ impl SuiClientCommands {
                 count,
                 gas,
                 gas_budget,
+                options,
             } => {
                 let signer = context.get_object_owner(&coin_id).await?;
                 let client = context.get_client().await?;
@@ -839,6 +1603,9 @@ impl SuiClientCommands {
                         return Err(anyhow!("Exactly one of `count` and `amounts` must be present for split-coin command."));
                     }
                 };
+                if let Some(preview) = options.preview(context, signer, &data).await? {
+                    return Ok(preview);
+                }
                 let signature =
                     context
                         .config
@@ -857,6 +1624,7 @@ impl SuiClientCommands {
                 coin_to_merge,
                 gas,
                 gas_budget,
+                options,
             } => {
                 let client = context.get_client().await?;
                 let signer = context.get_object_owner(&primary_coin).await?;
@@ -864,6 +1632,9 @@ impl SuiClientCommands {
                     .transaction_builder()
                     .merge_coins(signer, primary_coin, coin_to_merge, gas, gas_budget)
                     .await?;
+                if let Some(preview) = options.preview(context, signer, &data).await? {
+                    return Ok(preview);
+                }
                 let signature =
                     context
                         .config
@@ -878,20 +1649,140 @@ impl SuiClientCommands {
 
                 SuiClientCommandResult::MergeCoin(response)
             }
+            SuiClientCommands::Stake {
+                validator,
+                coins,
+                amount,
+                gas,
+                gas_budget,
+                options,
+            } => {
+                let validator = context.resolve_address(&validator)?;
+                let signer = match coins.first() {
+                    Some(coin) => context.get_object_owner(coin).await?,
+                    None => context.active_address()?,
+                };
+                let client = context.get_client().await?;
+                let coins = match (coins.is_empty(), amount) {
+                    (true, Some(amount)) => client
+                        .coin_read_api()
+                        .select_coins(
+                            signer,
+                            None,
+                            amount as u128,
+                            None,
+                            gas.into_iter().collect(),
+                        )
+                        .await?
+                        .into_iter()
+                        .map(|coin| coin.coin_object_id)
+                        .collect(),
+                    _ => coins,
+                };
+                let data = client
+                    .transaction_builder()
+                    .request_add_delegation(signer, coins, amount, validator, gas, gas_budget)
+                    .await?;
+                if let Some(preview) = options.preview(context, signer, &data).await? {
+                    return Ok(preview);
+                }
+                let signature =
+                    context
+                        .config
+                        .keystore
+                        .sign_secure(&signer, &data, Intent::default())?;
+                let response = context
+                    .execute_transaction(
+                        Transaction::from_data(data, Intent::default(), vec![signature])
+                            .verify()?,
+                    )
+                    .await?;
+                SuiClientCommandResult::Stake(response)
+            }
+            SuiClientCommands::Unstake {
+                staked_sui,
+                gas,
+                gas_budget,
+                options,
+            } => {
+                let signer = context.get_object_owner(&staked_sui).await?;
+                let client = context.get_client().await?;
+                // The delegation argument is no longer used, the StakedSui object is enough.
+                let data = client
+                    .transaction_builder()
+                    .request_withdraw_delegation(signer, staked_sui, staked_sui, gas, gas_budget)
+                    .await?;
+                if let Some(preview) = options.preview(context, signer, &data).await? {
+                    return Ok(preview);
+                }
+                let signature =
+                    context
+                        .config
+                        .keystore
+                        .sign_secure(&signer, &data, Intent::default())?;
+                let response = context
+                    .execute_transaction(
+                        Transaction::from_data(data, Intent::default(), vec![signature])
+                            .verify()?,
+                    )
+                    .await?;
+                SuiClientCommandResult::Unstake(response)
+            }
+            SuiClientCommands::Validators => {
+                let client = context.get_client().await?;
+                let system_state = client
+                    .governance_api()
+                    .get_latest_sui_system_state()
+                    .await?;
+                // The average epoch duration since genesis, if the genesis checkpoint is available.
+                let genesis = client
+                    .read_api()
+                    .get_checkpoint(CheckpointId::SequenceNumber(0))
+                    .await
+                    .ok();
+                let epoch_duration_ms = genesis.and_then(|genesis| {
+                    system_state
+                        .epoch_start_timestamp_ms
+                        .checked_sub(genesis.timestamp_ms)?
+                        .checked_div(system_state.epoch)
+                });
+                let validators = system_state
+                    .active_validators
+                    .into_iter()
+                    .map(|validator| {
+                        let apy = estimate_apy(&validator, system_state.epoch, epoch_duration_ms);
+                        (validator, apy)
+                    })
+                    .collect();
+                SuiClientCommandResult::Validators(validators)
+            }
             SuiClientCommands::Switch { address, env } => {
-                match (address, &env) {
-                    (None, Some(env)) => {
-                        Self::switch_env(&mut context.config, env)?;
-                    }
-                    (Some(addr), None) => {
-                        if !context.config.keystore.addresses().contains(&addr) {
-                            return Err(anyhow!("Address {} not managed by wallet", addr));
-                        }
-                        context.config.active_address = Some(addr);
+                if address.is_none() && env.is_none() {
+                    return Err(anyhow!("No address or env specified. Please Specify one."));
+                }
+                let previous_address = context.config.active_address;
+                if let Some(env) = &env {
+                    Self::switch_env(&mut context.config, env)?;
+                    // The client of the previous environment cannot be reused.
+                    context.client.write().await.take();
+                }
+                // Aliases are resolved in the address book of the environment switched to.
+                let address = address
+                    .map(|address| context.resolve_address(&address))
+                    .transpose()?;
+                if let Some(addr) = address {
+                    if !context.config.keystore.addresses().contains(&addr) {
+                        return Err(anyhow!("Address {} not managed by wallet", addr));
                     }
-                    _ => return Err(anyhow!("No address or env specified. Please Specify one.")),
+                    context.config.active_address = Some(addr);
                 }
                 context.config.save()?;
+                let address = address.or_else(|| {
+                    context
+                        .config
+                        .active_address
+                        .filter(|address| Some(*address) != previous_address)
+                });
                 SuiClientCommandResult::Switch(SwitchResponse { address, env })
             }
             SuiClientCommands::ActiveAddress => {
@@ -903,6 +1794,7 @@ impl SuiClientCommands {
                 url,
                 gas,
                 gas_budget,
+                options,
             } => {
                 let args_json = json!([
                     unwrap_or(&name, EXAMPLE_NFT_NAME),
@@ -913,7 +1805,7 @@ impl SuiClientCommands {
                 for a in args_json.as_array().unwrap() {
                     args.push(SuiJsonValue::new(a.clone()).unwrap());
                 }
-                let response = call_move(
+                let (sender, data) = move_call_data(
                     ObjectID::from(SUI_FRAMEWORK_ADDRESS),
                     "devnet_nft",
                     "mint",
@@ -924,6 +1816,10 @@ impl SuiClientCommands {
                     context,
                 )
                 .await?;
+                if let Some(preview) = options.preview(context, sender, &data).await? {
+                    return Ok(preview);
+                }
+                let response = execute_move_call(sender, data, context).await?;
                 let nft_id = response
                     .effects
                     .created()
@@ -939,12 +1835,81 @@ impl SuiClientCommands {
                 SuiClientCommandResult::CreateExampleNFT(object_read)
             }
 
+            SuiClientCommands::RotateNetworkKey {
+                network_key_file,
+                gas,
+                gas_budget,
+                options,
+            } => {
+                let network_pubkey = match read_keypair_from_file(&network_key_file)? {
+                    SuiKeyPair::Ed25519(key_pair) => key_pair.public().as_bytes().to_vec(),
+                    _ => return Err(anyhow!("Network keys must be Ed25519 keys")),
+                };
+                let args = vec![
+                    SuiJsonValue::from_object_id(SUI_SYSTEM_STATE_OBJECT_ID),
+                    SuiJsonValue::new(json!(network_pubkey))?,
+                ];
+                let (sender, data) = move_call_data(
+                    ObjectID::from(SUI_FRAMEWORK_ADDRESS),
+                    SUI_SYSTEM_MODULE_NAME.as_str(),
+                    "update_validator_next_epoch_network_pubkey",
+                    vec![],
+                    gas,
+                    gas_budget,
+                    args,
+                    context,
+                )
+                .await?;
+                if let Some(preview) = options.preview(context, sender, &data).await? {
+                    return Ok(preview);
+                }
+                let response = execute_move_call(sender, data, context).await?;
+                SuiClientCommandResult::Call(response)
+            }
+
+            SuiClientCommands::RotateProtocolKey {
+                protocol_key_file,
+                gas,
+                gas_budget,
+                options,
+            } => {
+                let key_pair = read_authority_keypair_from_file(&protocol_key_file)?;
+                // The proof of possession binds the key to the address of the validator.
+                let sender = context
+                    .try_get_object_owner(&gas)
+                    .await?
+                    .unwrap_or(context.active_address()?);
+                let proof_of_possession = generate_proof_of_possession(&key_pair, sender);
+                let args = vec![
+                    SuiJsonValue::from_object_id(SUI_SYSTEM_STATE_OBJECT_ID),
+                    SuiJsonValue::new(json!(key_pair.public().as_bytes().to_vec()))?,
+                    SuiJsonValue::new(json!(proof_of_possession.as_bytes().to_vec()))?,
+                ];
+                let (sender, data) = move_call_data(
+                    ObjectID::from(SUI_FRAMEWORK_ADDRESS),
+                    SUI_SYSTEM_MODULE_NAME.as_str(),
+                    "update_validator_next_epoch_protocol_pubkey",
+                    vec![],
+                    gas,
+                    gas_budget,
+                    args,
+                    context,
+                )
+                .await?;
+                if let Some(preview) = options.preview(context, sender, &data).await? {
+                    return Ok(preview);
+                }
+                let response = execute_move_call(sender, data, context).await?;
+                SuiClientCommandResult::Call(response)
+            }
+
             SuiClientCommands::SerializeTransferSui {
                 to,
                 sui_coin_object_id: object_id,
                 gas_budget,
                 amount,
             } => {
+                let to = context.resolve_address(&to)?;
                 let from = context.get_object_owner(&object_id).await?;
                 let client = context.get_client().await?;
                 let data = client
@@ -985,13 +1950,162 @@ impl SuiClientCommands {
                 let response = context.execute_transaction(verified).await?;
                 SuiClientCommandResult::ExecuteSignedTx(response)
             }
-            SuiClientCommands::NewEnv { alias, rpc, ws } => {
+            SuiClientCommands::ExecuteCombined {
+                tx_bytes,
+                sigs,
+                pks,
+                weights,
+                threshold,
+            } => {
+                let data: TransactionData = bcs::from_bytes(
+                    &Base64::try_from(tx_bytes)
+                        .map_err(|e| anyhow!(e))?
+                        .to_vec()
+                        .map_err(|e| anyhow!(e))?,
+                )?;
+                let (address, multisig) = combine_partial_sigs(sigs, pks, weights, threshold)?;
+                ensure!(
+                    data.sender() == address,
+                    "The transaction is sent by {}, not by the MultiSig address {address}",
+                    data.sender()
+                );
+                let verified = Transaction::from_generic_sig_data(
+                    data,
+                    Intent::default(),
+                    vec![multisig.into()],
+                )
+                .verify()?;
+
+                let response = context.execute_transaction(verified).await?;
+                SuiClientCommandResult::ExecuteSignedTx(response)
+            }
+            SuiClientCommands::Faucet {
+                address,
+                url,
+                amount,
+            } => {
+                let address = context.resolve_address_or_active(address.as_ref())?;
+                let url = match url {
+                    Some(url) => url,
+                    None => context
+                        .config
+                        .get_active_env()?
+                        .faucet
+                        .clone()
+                        .ok_or_else(|| {
+                            anyhow!(
+                                "The active environment has no faucet, give its URL with --url or \
+                             add an environment with one with `sui client new-env --faucet`"
+                            )
+                        })?,
+                };
+                let mut coins = vec![];
+                loop {
+                    let sent = request_gas(&url, address).await?;
+                    ensure!(
+                        !sent.is_empty() || !coins.is_empty(),
+                        "The faucet did not send any coins"
+                    );
+                    let done = sent.is_empty()
+                        || coins.iter().chain(&sent).map(|c| c.amount).sum::<u64>()
+                            >= amount.unwrap_or_default();
+                    coins.extend(sent);
+                    if done {
+                        break;
+                    }
+                }
+                SuiClientCommandResult::Faucet(address, coins)
+            }
+            SuiClientCommands::Watch {
+                package,
+                module,
+                transactions,
+                limit,
+            } => {
+                ensure!(
+                    context.config.get_active_env()?.ws.is_some(),
+                    "The active environment has no websocket URL to subscribe with, add one \
+                     with `sui client new-env --ws`"
+                );
+                let client = context.get_client().await?;
+                let limit = limit.unwrap_or(usize::MAX);
+                let mut count = 0;
+                if transactions {
+                    let filter = match module {
+                        Some(module) => TransactionFilter::MoveModule { package, module },
+                        None => TransactionFilter::Package(package),
+                    };
+                    let mut stream = client
+                        .event_api()
+                        .subscribe_transaction(filter)
+                        .await?
+                        .take(limit);
+                    while let Some(effects) = stream.next().await {
+                        println!("{}\n", effects?);
+                        count += 1;
+                    }
+                } else {
+                    let filter = match module {
+                        Some(module) => SuiEventFilter::All(vec![
+                            SuiEventFilter::Package(package),
+                            SuiEventFilter::Module(module),
+                        ]),
+                        None => SuiEventFilter::Package(package),
+                    };
+                    let mut stream = client
+                        .event_api()
+                        .subscribe_event(filter)
+                        .await?
+                        .take(limit);
+                    while let Some(envelope) = stream.next().await {
+                        let envelope = envelope?;
+                        println!(
+                            "[{}] Transaction {}\n{}\n",
+                            envelope.timestamp,
+                            envelope.tx_digest,
+                            serde_json::to_string_pretty(&envelope.event)?
+                        );
+                        count += 1;
+                    }
+                }
+                SuiClientCommandResult::Watch(count)
+            }
+            SuiClientCommands::Replay {
+                digest,
+                rpc,
+                protocol_version,
+            } => {
+                let client = match rpc {
+                    Some(rpc) => SuiClientBuilder::default().build(rpc).await?,
+                    None => context.get_client().await?,
+                };
+                let result = replay_transaction(&client, digest, protocol_version).await?;
+                SuiClientCommandResult::Replay(result)
+            }
+            SuiClientCommands::NewEnv {
+                alias,
+                rpc,
+                ws,
+                faucet,
+                keystore,
+            } => {
                 if context.config.envs.iter().any(|env| env.alias == alias) {
                     return Err(anyhow!(
                         "Environment config with name [{alias}] already exists."
                     ));
                 }
-                let env = SuiEnv { alias, rpc, ws };
+                if let Some(keystore) = &keystore {
+                    // Check the keystore can be read.
+                    FileBasedKeystore::new(keystore)?;
+                }
+                let env = SuiEnv {
+                    alias,
+                    rpc,
+                    ws,
+                    faucet,
+                    keystore,
+                    active_address: None,
+                };
 
                 // Check urls are valid and server is reachable
                 env.create_rpc_client(None).await?;
@@ -1047,15 +2161,51 @@ impl SuiClientCommands {
 
                 SuiClientCommandResult::VerifySource
             }
+
+            SuiClientCommands::VerifyBytecode {
+                package_path,
+                build_config,
+                package_id,
+                verify_deps,
+            } => {
+                let build_config =
+                    resolve_lock_file_path(build_config, Some(package_path.clone()))?;
+
+                let compiled_package = build_move_package(
+                    &package_path,
+                    BuildConfig {
+                        config: build_config,
+                        run_bytecode_verifier: true,
+                        print_diags_to_stderr: true,
+                    },
+                )?;
+
+                // Packages published from the manifest's address are compared as they are,
+                // unpublished ones once their modules are given the package's address.
+                let source_mode = match compiled_package.published_root_module() {
+                    Some(module) if ObjectID::from(*module.self_id().address()) != package_id => {
+                        return Err(anyhow!(
+                            "The package manifest publishes it at {}, not at {package_id}",
+                            module.self_id().address()
+                        ));
+                    }
+                    Some(_) => SourceMode::Verify,
+                    None => SourceMode::VerifyAt(package_id.into()),
+                };
+
+                let client = context.get_client().await?;
+                BytecodeSourceVerifier::new(client.read_api(), true)
+                    .verify_package(&compiled_package.package, verify_deps, source_mode)
+                    .await?;
+
+                SuiClientCommandResult::VerifyBytecode
+            }
         });
         ret
     }
 
     pub fn switch_env(config: &mut SuiClientConfig, env: &str) -> Result<(), anyhow::Error> {
-        let env = Some(env.into());
-        ensure!(config.get_env(&env).is_some(), "Environment config not found for [{env:?}], add new environment config using the `sui client new-env` command.");
-        config.active_env = env;
-        Ok(())
+        config.switch_env(env)
     }
 }
 
@@ -1101,7 +2251,7 @@ impl WalletContext {
 
             if let Err(e) = client.check_api_version() {
                 warn!("{e}");
-                println!("{}", format!("[warn] {e}").yellow().bold());
+                eprintln!("{}", format!("[warn] {e}").yellow().bold());
             }
             self.client.write().await.insert(client).clone()
         })
@@ -1125,6 +2275,47 @@ impl WalletContext {
         Ok(self.config.active_address.unwrap())
     }
 
+    /// The address book of the keystore, stored alongside it, or alongside the client config for
+    /// keystores that are not files.
+    pub fn address_book(&self) -> Result<AddressBook, anyhow::Error> {
+        let keystore_path = match &self.config.keystore {
+            Keystore::File(keystore) => keystore.path().map(Path::to_path_buf),
+            Keystore::InMem(_) | Keystore::Ledger(_) => None,
+        };
+        let keystore_path = keystore_path
+            .unwrap_or_else(|| self.config.path().with_file_name(SUI_KEYSTORE_FILENAME));
+        AddressBook::load(&address_book_path(&keystore_path))
+    }
+
+    /// The address `address` stands for, looking aliases up in the address book.
+    pub fn resolve_address(&self, address: &AddressOrAlias) -> Result<SuiAddress, anyhow::Error> {
+        match address {
+            AddressOrAlias::Address(address) => Ok(*address),
+            AddressOrAlias::Alias(_) => self.address_book()?.resolve(address),
+        }
+    }
+
+    pub fn resolve_addresses(
+        &self,
+        addresses: &[AddressOrAlias],
+    ) -> Result<Vec<SuiAddress>, anyhow::Error> {
+        addresses
+            .iter()
+            .map(|address| self.resolve_address(address))
+            .collect()
+    }
+
+    /// The address `address` stands for, or the active address if there is none.
+    pub fn resolve_address_or_active(
+        &mut self,
+        address: Option<&AddressOrAlias>,
+    ) -> Result<SuiAddress, anyhow::Error> {
+        match address {
+            Some(address) => self.resolve_address(address),
+            None => self.active_address(),
+        }
+    }
+
     /// Get the latest object reference given a object id
     pub async fn get_object_ref(&self, object_id: ObjectID) -> Result<ObjectRef, anyhow::Error> {
         let client = self.get_client().await?;
@@ -1201,10 +2392,17 @@ impl WalletContext {
         budget: u64,
         forbidden_gas_objects: BTreeSet<ObjectID>,
     ) -> Result<(u64, SuiObjectData), anyhow::Error> {
-        for o in self.gas_objects(address).await.unwrap() {
-            if o.0 >= budget && !forbidden_gas_objects.contains(&o.1.object_id) {
-                return Ok((o.0, o.1));
-            }
+        // The smallest sufficient gas object, to keep the larger ones for larger budgets.
+        if let Some((value, object, _)) = self
+            .gas_objects(address)
+            .await?
+            .into_iter()
+            .filter(|(value, object, _)| {
+                *value >= budget && !forbidden_gas_objects.contains(&object.object_id)
+            })
+            .min_by_key(|(value, ..)| *value)
+        {
+            return Ok((value, object));
         }
         Err(anyhow!(
             "No non-argument gas objects found with value >= budget {budget}"
@@ -1261,6 +2459,109 @@ impl Display for SuiClientCommandResult {
             SuiClientCommandResult::Call(response) => {
                 write!(writer, "{}", write_transaction_response(response)?)?;
             }
+            SuiClientCommandResult::Ptb(response) => {
+                write!(writer, "{}", write_transaction_response(response)?)?;
+            }
+            SuiClientCommandResult::DryRun(response) => {
+                writeln!(writer, "{}", "----- Dry Run Effects ----".bold())?;
+                write!(writer, "{}", response.effects)?;
+                writeln!(writer, "Events: {}", response.events.data.len())?;
+
+                writeln!(writer, "{}", "----- Balance Changes ----".bold())?;
+                let mut balance_changes = BTreeMap::<(String, String), i128>::new();
+                for event in &response.events.data {
+                    if let SuiEvent::CoinBalanceChange {
+                        owner,
+                        coin_type,
+                        amount,
+                        ..
+                    } = event
+                    {
+                        *balance_changes
+                            .entry((owner.to_string(), coin_type.clone()))
+                            .or_default() += amount;
+                    }
+                }
+                if balance_changes.is_empty() {
+                    writeln!(writer, "No balance changes")?;
+                } else {
+                    let mut table: Table = table!(["Owner", "Coin Type", "Amount"]);
+                    for ((owner, coin_type), amount) in balance_changes {
+                        table.add_row(row![owner, coin_type, amount]);
+                    }
+                    write!(writer, "{table}")?;
+                }
+
+                writeln!(writer, "{}", "----- Gas Estimate ----".bold())?;
+                let gas = response.effects.gas_used();
+                writeln!(writer, "Computation cost: {}", gas.computation_cost)?;
+                writeln!(writer, "Storage cost: {}", gas.storage_cost)?;
+                writeln!(writer, "Storage rebate: {}", gas.storage_rebate)?;
+                writeln!(
+                    writer,
+                    "Net gas cost: {}",
+                    (gas.computation_cost + gas.storage_cost) as i128 - gas.storage_rebate as i128
+                )?;
+            }
+            SuiClientCommandResult::DevInspect(results) => {
+                writeln!(writer, "{}", "----- Dev Inspect Effects ----".bold())?;
+                write!(writer, "{}", results.effects)?;
+                writeln!(writer, "Events: {}", results.events.data.len())?;
+
+                writeln!(writer, "{}", "----- Return Values ----".bold())?;
+                match &results.results {
+                    Ok(results) => {
+                        let mut table: Table = table!(["Command", "Type", "BCS Bytes"]);
+                        for (command, result) in results {
+                            for (bytes, type_) in &result.return_values {
+                                table.add_row(row![command, type_, format!("{bytes:?}")]);
+                            }
+                        }
+                        write!(writer, "{table}")?;
+                    }
+                    Err(error) => writeln!(writer, "Execution failed: {error}")?,
+                }
+            }
+            SuiClientCommandResult::ProfileGas(top, results) => {
+                writeln!(writer, "{}", "----- Gas Profile ----".bold())?;
+                match results.effects.status() {
+                    SuiExecutionStatus::Success => writeln!(writer, "Status: Success")?,
+                    SuiExecutionStatus::Failure { error } => {
+                        writeln!(writer, "Status: Failure, the profile stops at it: {error}")?
+                    }
+                }
+                writeln!(
+                    writer,
+                    "Computation cost: {}",
+                    results.effects.gas_used().computation_cost
+                )?;
+                match &results.gas_profile {
+                    Some(profile) => {
+                        // Internal gas units are a thousandth of a gas unit.
+                        let total = profile.total_internal_gas();
+                        writeln!(
+                            writer,
+                            "Move execution: {total} internal gas units ({} gas units)",
+                            total / 1000
+                        )?;
+                        writeln!(writer, "{}", "----- Instructions ----".bold())?;
+                        let instructions = &profile.instructions;
+                        let table =
+                            gas_profile_table(["Instruction", "Count"], instructions, total, *top);
+                        write!(writer, "{table}")?;
+                        writeln!(writer, "{}", "----- Functions ----".bold())?;
+                        writeln!(
+                            writer,
+                            "The gas of a function leaves out that of the functions it calls."
+                        )?;
+                        let functions = &profile.functions;
+                        let table =
+                            gas_profile_table(["Function", "Calls"], functions, total, *top);
+                        write!(writer, "{table}")?;
+                    }
+                    None => writeln!(writer, "The full node did not return a gas profile")?,
+                }
+            }
             SuiClientCommandResult::Transfer(time_elapsed, response) => {
                 writeln!(writer, "Transfer confirmed after {} us", time_elapsed)?;
                 write!(writer, "{}", write_transaction_response(response)?)?;
@@ -1277,6 +2578,9 @@ impl Display for SuiClientCommandResult {
             SuiClientCommandResult::PayAllSui(response) => {
                 write!(writer, "{}", write_transaction_response(response)?)?;
             }
+            SuiClientCommandResult::PayBatch(summary) => {
+                writeln!(writer, "{summary}")?;
+            }
             SuiClientCommandResult::Addresses(addresses, active_address) => {
                 writeln!(writer, "Showing {} results.", addresses.len())?;
                 for address in addresses {
@@ -1314,34 +2618,15 @@ impl Display for SuiClientCommandResult {
                 writeln!(writer, "Showing {} results.", object_refs.len())?;
             }
             SuiClientCommandResult::DynamicFieldQuery(df_refs) => {
-                let mut table: Table = table!([
-                    "Name",
-                    "Type",
-                    "Object Type",
-                    "Object Id",
-                    "Version",
-                    "Digest"
-                ]);
-                for df_ref in df_refs.data.iter() {
-                    let df_type = match df_ref.type_ {
-                        DynamicFieldType::DynamicField => "DynamicField",
-                        DynamicFieldType::DynamicObject => "DynamicObject",
-                    };
-                    table.add_row(row![
-                        df_ref.name,
-                        df_type,
-                        df_ref.object_type,
-                        df_ref.object_id,
-                        df_ref.version.value(),
-                        Base64::encode(df_ref.digest)
-                    ]);
-                }
-                write!(writer, "{table}")?;
+                write!(writer, "{}", dynamic_fields_table(df_refs))?;
                 writeln!(writer, "Showing {} results.", df_refs.data.len())?;
                 if let Some(cursor) = df_refs.next_cursor {
                     writeln!(writer, "Next cursor: {cursor}")?;
                 }
             }
+            SuiClientCommandResult::Inspect(inspection) => {
+                write!(writer, "{inspection}")?;
+            }
             SuiClientCommandResult::SyncClientState => {
                 writeln!(writer, "Client state sync complete.")?;
             }
@@ -1352,7 +2637,15 @@ impl Display for SuiClientCommandResult {
                     "Created new keypair for address with scheme {:?}: [{address}]",
                     scheme
                 );
-                println!("Secret Recovery Phrase : [{recovery_phrase}]");
+                if !recovery_phrase.is_empty() {
+                    println!("Secret Recovery Phrase : [{recovery_phrase}]");
+                }
+            }
+            SuiClientCommandResult::Ledger(addresses) => {
+                writeln!(writer, "Using {} Ledger accounts.", addresses.len())?;
+                for address in addresses {
+                    writeln!(writer, "{}", address)?;
+                }
             }
             SuiClientCommandResult::Gas(gases) => {
                 // TODO: generalize formatting of CLI
@@ -1365,6 +2658,37 @@ impl Display for SuiClientCommandResult {
                     writeln!(writer, " {0: ^42} | {1: ^11}", gas.id(), gas.value())?;
                 }
             }
+            SuiClientCommandResult::MergeAllGas(coin, count, responses) => {
+                writeln!(
+                    writer,
+                    "Merged {count} gas coins into {coin} in {} transactions",
+                    responses.len()
+                )?;
+                for response in responses {
+                    writeln!(
+                        writer,
+                        "Transaction Digest: {}",
+                        response.effects.transaction_digest()
+                    )?;
+                }
+            }
+            SuiClientCommandResult::Stake(response) | SuiClientCommandResult::Unstake(response) => {
+                write!(writer, "{}", write_transaction_response(response)?)?;
+            }
+            SuiClientCommandResult::Validators(validators) => {
+                let mut table: Table = table!(["Name", "Address", "Stake", "Commission", "APY"]);
+                for (validator, apy) in validators {
+                    table.add_row(row![
+                        validator.name,
+                        validator.sui_address,
+                        validator.staking_pool_sui_balance,
+                        format!("{:.2}%", validator.commission_rate as f64 / 100.0),
+                        apy.map_or_else(|| "-".to_string(), |apy| format!("{:.2}%", apy * 100.0))
+                    ]);
+                }
+                write!(writer, "{table}")?;
+                writeln!(writer, "Showing {} validators.", validators.len())?;
+            }
             SuiClientCommandResult::SplitCoin(response) => {
                 write!(writer, "{}", write_transaction_response(response)?)?;
             }
@@ -1392,6 +2716,9 @@ impl Display for SuiClientCommandResult {
             SuiClientCommandResult::SerializeTransferSui(data) => {
                 writeln!(writer, "Raw tx_bytes to execute: {}", data)?;
             }
+            SuiClientCommandResult::SerializedUnsignedTransaction(data) => {
+                writeln!(writer, "Raw tx_bytes to sign and execute: {}", data)?;
+            }
             SuiClientCommandResult::ActiveEnv(env) => {
                 write!(writer, "{}", env.as_deref().unwrap_or("None"))?;
             }
@@ -1401,6 +2728,9 @@ impl Display for SuiClientCommandResult {
             SuiClientCommandResult::Envs(envs, active) => {
                 for env in envs {
                     write!(writer, "{} => {}", env.alias, env.rpc)?;
+                    if let Some(keystore) = &env.keystore {
+                        write!(writer, " [keystore: {}]", keystore.display())?;
+                    }
                     if Some(env.alias.as_str()) == active.as_deref() {
                         write!(writer, " (active)")?;
                     }
@@ -1410,11 +2740,91 @@ impl Display for SuiClientCommandResult {
             SuiClientCommandResult::VerifySource => {
                 writeln!(writer, "Source verification succeeded!")?;
             }
+            SuiClientCommandResult::VerifyBytecode => {
+                writeln!(writer, "Bytecode verification succeeded!")?;
+            }
+            SuiClientCommandResult::Replay(result) => {
+                write!(writer, "{result}")?;
+            }
+            SuiClientCommandResult::Watch(count) => {
+                writeln!(writer, "Stopped watching after {count} updates.")?;
+            }
+            SuiClientCommandResult::Faucet(address, coins) => {
+                writeln!(writer, "Received {} coins for {address}:", coins.len())?;
+                let mut table: Table = table!(["Coin ID", "Amount", "Transfer transaction"]);
+                for coin in coins {
+                    table.add_row(row![coin.id, coin.amount, coin.transfer_tx_digest]);
+                }
+                write!(writer, "{table}")?;
+            }
         }
         write!(f, "{}", writer.trim_end_matches('\n'))
     }
 }
 
+/// Estimates the APY of staking with `validator` from the growth of the SUI value of its staking
+/// pool tokens, issued at par when the pool was activated, over epochs of `epoch_duration_ms`.
+fn estimate_apy(
+    validator: &SuiValidatorSummary,
+    epoch: u64,
+    epoch_duration_ms: Option<u64>,
+) -> Option<f64> {
+    const YEAR_MS: f64 = 365.0 * 24.0 * 60.0 * 60.0 * 1000.0;
+    let epochs = epoch.checked_sub(validator.staking_pool_activation_epoch?)?;
+    let duration_ms = epochs.checked_mul(epoch_duration_ms?)?;
+    if duration_ms == 0 || validator.pool_token_balance == 0 {
+        return None;
+    }
+    let exchange_rate =
+        validator.staking_pool_sui_balance as f64 / validator.pool_token_balance as f64;
+    Some(exchange_rate.powf(YEAR_MS / duration_ms as f64) - 1.0)
+}
+
+/// Checks that `new_modules`, compiled with their own package at address 0x0, can replace the
+/// modules of the package at `package_id`: every on-chain module must still exist, keeping the
+/// layout and abilities of its structs and the signatures of its public functions.
+fn check_upgrade_compatibility<'a>(
+    package_id: ObjectID,
+    on_chain_modules: &BTreeMap<String, Vec<u8>>,
+    new_modules: impl Iterator<Item = &'a CompiledModule>,
+) -> Result<(), anyhow::Error> {
+    let mut new_modules: BTreeMap<_, _> = new_modules
+        .map(|module| {
+            let mut module = module.clone();
+            let address_idx = module.self_handle().address;
+            module.address_identifiers[address_idx.0 as usize] = package_id.into();
+            (
+                module.self_id().name().to_string(),
+                normalized::Module::new(&module),
+            )
+        })
+        .collect();
+
+    let compatibility = Compatibility::new(
+        /* check_struct_and_pub_function_linking */ true, /* check_struct_layout */ true,
+        /* check_friend_linking */ false,
+    );
+    let mut errors = vec![];
+    for (name, bytes) in on_chain_modules {
+        let module = CompiledModule::deserialize(bytes)
+            .map_err(|e| anyhow!("Cannot deserialize on-chain module {name}: {e}"))?;
+        let Some(new_module) = new_modules.remove(name) else {
+            errors.push(format!("Module {name} is missing"));
+            continue;
+        };
+        if let Err(e) = compatibility.check(&normalized::Module::new(&module), &new_module) {
+            errors.push(format!("Module {name} is incompatible: {e:?}"));
+        }
+    }
+
+    ensure!(
+        errors.is_empty(),
+        "Package is not compatible with the on-chain version of {package_id}:\n{}",
+        errors.join("\n")
+    );
+    Ok(())
+}
+
 pub async fn call_move(
     package: ObjectID,
     module: &str,
@@ -1425,6 +2835,166 @@ pub async fn call_move(
     args: Vec<SuiJsonValue>,
     context: &mut WalletContext,
 ) -> Result<SuiTransactionResponse, anyhow::Error> {
+    let (sender, data) = move_call_data(
+        package, module, function, type_args, gas, gas_budget, args, context,
+    )
+    .await?;
+    execute_move_call(sender, data, context).await
+}
+
+/// Builds the transaction of a Move call, sent by the owner of `gas`, or the active address.
+/// A row of the CSV file of `sui client pay-batch`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PayBatchRow {
+    /// The line of the row in the file, counting from 1
+    pub line: usize,
+    pub recipient: SuiAddress,
+    pub amount: u64,
+}
+
+/// Reads the `<address>,<amount>` rows of a `pay-batch` CSV file, skipping a first row of column
+/// names, empty lines and lines starting with `#`.
+pub fn read_pay_batch_csv(path: &Path) -> Result<Vec<PayBatchRow>, anyhow::Error> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| anyhow!("Cannot read recipients from {}: {e}", path.display()))?;
+    let mut rows = vec![];
+    let mut first_row = true;
+    for (i, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let number = i + 1;
+        let fields: Vec<_> = line
+            .split(',')
+            .map(|f| f.trim().trim_matches('"'))
+            .collect();
+        let [recipient, amount] = fields.as_slice() else {
+            return Err(anyhow!("Expected <address>,<amount> on line {number}, got [{line}]"));
+        };
+        let is_name = |field: &str| field.chars().all(|c| c.is_alphabetic() || c == '_');
+        if std::mem::take(&mut first_row) && is_name(recipient) && is_name(amount) {
+            continue;
+        }
+        let recipient = SuiAddress::from_str(recipient)
+            .map_err(|e| anyhow!("Invalid address on line {number}: {e}"))?;
+        let amount: u64 = amount
+            .parse()
+            .map_err(|e| anyhow!("Invalid amount on line {number}: {e}"))?;
+        ensure!(amount > 0, "Amount on line {number} must be positive");
+        rows.push(PayBatchRow {
+            line: number,
+            recipient,
+            amount,
+        });
+    }
+    Ok(rows)
+}
+
+/// Pays the rows of `batch` in a single programmable transaction, splitting the amounts off the
+/// gas coin.
+async fn pay_batch(
+    context: &mut WalletContext,
+    sender: SuiAddress,
+    gas: Option<ObjectID>,
+    batch: &[PayBatchRow],
+    gas_budget: u64,
+    gas_price: u64,
+) -> Result<PayBatchTransaction, anyhow::Error> {
+    let amount: u128 = batch.iter().map(|row| row.amount as u128).sum();
+    let gas = match gas {
+        Some(gas) => context.get_object_ref(gas).await?,
+        None => {
+            let required = amount + gas_budget as u128 * gas_price as u128;
+            context
+                .gas_objects(sender)
+                .await?
+                .into_iter()
+                .filter(|(value, ..)| *value as u128 >= required)
+                .min_by_key(|(value, ..)| *value)
+                .map(|(_, object, _)| object.object_ref())
+                .ok_or_else(|| {
+                    anyhow!(
+                        "No gas coin of [{sender}] covers the [{required}] of the amounts and gas budget of a transaction. Merge them with `sui client gas merge-all`, or pay fewer recipients per transaction."
+                    )
+                })?
+        }
+    };
+
+    let mut builder = ProgrammableTransactionBuilder::new();
+    builder.pay_sui(
+        batch.iter().map(|row| row.recipient).collect(),
+        batch.iter().map(|row| row.amount).collect(),
+    )?;
+    let data =
+        TransactionData::new_programmable(sender, gas, builder.finish(), gas_budget, gas_price);
+    let signature = context
+        .config
+        .keystore
+        .sign_secure(&sender, &data, Intent::default())?;
+    let response = context
+        .execute_transaction(
+            Transaction::from_data(data, Intent::default(), vec![signature]).verify()?,
+        )
+        .await?;
+    let effects = &response.effects;
+    if let SuiExecutionStatus::Failure { error } = effects.status() {
+        return Err(anyhow!(
+            "Transaction {} failed: {error}",
+            effects.transaction_digest()
+        ));
+    }
+    let gas_used = effects.gas_used();
+    Ok(PayBatchTransaction {
+        digest: *effects.transaction_digest(),
+        first_line: batch[0].line,
+        last_line: batch[batch.len() - 1].line,
+        recipients: batch.len(),
+        amount,
+        gas_cost: gas_used.computation_cost as i128 + gas_used.storage_cost as i128
+            - gas_used.storage_rebate as i128,
+    })
+}
+
+/// A table of the `top` most expensive of the `entries` of a gas profile, with their share of
+/// `total`.
+fn gas_profile_table(
+    [name, count]: [&str; 2],
+    entries: &[SuiGasProfileEntry],
+    total: u64,
+    top: usize,
+) -> Table {
+    let mut table: Table = table!([name, count, "Internal Gas", "Share"]);
+    for entry in entries.iter().take(top) {
+        let share = entry.internal_gas as f64 * 100.0 / std::cmp::max(total, 1) as f64;
+        table.add_row(row![
+            entry.name,
+            entry.count,
+            entry.internal_gas,
+            format!("{share:.1}%")
+        ]);
+    }
+    if entries.len() > top {
+        table.add_row(row![
+            format!("... {} more", entries.len() - top),
+            "",
+            "",
+            ""
+        ]);
+    }
+    table
+}
+
+pub(crate) async fn move_call_data(
+    package: ObjectID,
+    module: &str,
+    function: &str,
+    type_args: Vec<TypeTag>,
+    gas: Option<ObjectID>,
+    gas_budget: u64,
+    args: Vec<SuiJsonValue>,
+    context: &mut WalletContext,
+) -> Result<(SuiAddress, TransactionData), anyhow::Error> {
     // Convert all numeric input to String, this will allow number input from the CLI without failing SuiJSON's checks.
     let args = args
         .into_iter()
@@ -1451,6 +3021,14 @@ pub async fn call_move(
             gas_budget,
         )
         .await?;
+    Ok((sender, data))
+}
+
+pub(crate) async fn execute_move_call(
+    sender: SuiAddress,
+    data: TransactionData,
+    context: &mut WalletContext,
+) -> Result<SuiTransactionResponse, anyhow::Error> {
     let signature = context
         .config
         .keystore
@@ -1465,7 +3043,7 @@ pub async fn call_move(
     Ok(response)
 }
 
-fn convert_number_to_string(value: Value) -> Value {
+pub(crate) fn convert_number_to_string(value: Value) -> Value {
     match value {
         Value::Number(n) => Value::String(n.to_string()),
         Value::Array(a) => Value::Array(a.into_iter().map(convert_number_to_string).collect()),
@@ -1520,11 +3098,13 @@ fn unwrap_err_to_string<T: Display, F: FnOnce() -> Result<T, anyhow::Error>>(fun
 
 impl SuiClientCommandResult {
     pub fn print(&self, pretty: bool) {
-        let line = if pretty {
-            format!("{self}")
-        } else {
-            format!("{:?}", self)
-        };
+        if !pretty {
+            // JSON is printed as is, to be parsed, and kept out of the logs as it includes the
+            // recovery phrases of new addresses.
+            println!("{:?}", self);
+            return;
+        }
+        let line = format!("{self}");
         // Log line by line
         for line in line.lines() {
             // Logs write to a file on the side.  Print to stdout and also log to file, for tests to pass.
@@ -1532,6 +3112,28 @@ impl SuiClientCommandResult {
             info!("{line}")
         }
     }
+
+    /// The response of the transaction executed by the command, or of the last one when it
+    /// executed several.
+    pub fn transaction_response(&self) -> Option<&SuiTransactionResponse> {
+        match self {
+            SuiClientCommandResult::Publish(response)
+            | SuiClientCommandResult::Call(response)
+            | SuiClientCommandResult::Ptb(response)
+            | SuiClientCommandResult::Transfer(_, response)
+            | SuiClientCommandResult::TransferSui(response)
+            | SuiClientCommandResult::Pay(response)
+            | SuiClientCommandResult::PaySui(response)
+            | SuiClientCommandResult::PayAllSui(response)
+            | SuiClientCommandResult::Stake(response)
+            | SuiClientCommandResult::Unstake(response)
+            | SuiClientCommandResult::SplitCoin(response)
+            | SuiClientCommandResult::MergeCoin(response)
+            | SuiClientCommandResult::ExecuteSignedTx(response) => Some(response),
+            SuiClientCommandResult::MergeAllGas(_, _, responses) => responses.last(),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Serialize)]
@@ -1539,9 +3141,18 @@ impl SuiClientCommandResult {
 pub enum SuiClientCommandResult {
     Publish(SuiTransactionResponse),
     VerifySource,
+    VerifyBytecode,
     Object(SuiObjectResponse),
     RawObject(SuiObjectResponse),
     Call(SuiTransactionResponse),
+    Ptb(SuiTransactionResponse),
+    DryRun(DryRunTransactionResponse),
+    DevInspect(DevInspectResults),
+    ProfileGas(
+        // Skipping serialisation for the number of entries to display.
+        #[serde(skip)] usize,
+        DevInspectResults,
+    ),
     Transfer(
         // Skipping serialisation for elapsed time.
         #[serde(skip)] u128,
@@ -1551,12 +3162,19 @@ pub enum SuiClientCommandResult {
     Pay(SuiTransactionResponse),
     PaySui(SuiTransactionResponse),
     PayAllSui(SuiTransactionResponse),
+    PayBatch(PayBatchSummary),
     Addresses(Vec<SuiAddress>, Option<SuiAddress>),
     Objects(Vec<SuiObjectInfo>),
     DynamicFieldQuery(DynamicFieldPage),
+    Inspect(ObjectInspection),
     SyncClientState,
     NewAddress((SuiAddress, String, SignatureScheme)),
+    Ledger(Vec<SuiAddress>),
     Gas(Vec<GasCoin>),
+    MergeAllGas(ObjectID, usize, Vec<SuiTransactionResponse>),
+    Stake(SuiTransactionResponse),
+    Unstake(SuiTransactionResponse),
+    Validators(Vec<(SuiValidatorSummary, Option<f64>)>),
     SplitCoin(SuiTransactionResponse),
     MergeCoin(SuiTransactionResponse),
     Switch(SwitchResponse),
@@ -1565,8 +3183,182 @@ pub enum SuiClientCommandResult {
     Envs(Vec<SuiEnv>, Option<String>),
     CreateExampleNFT(SuiObjectResponse),
     SerializeTransferSui(String),
+    SerializedUnsignedTransaction(String),
     ExecuteSignedTx(SuiTransactionResponse),
     NewEnv(SuiEnv),
+    Replay(ReplayResult),
+    Watch(usize),
+    Faucet(SuiAddress, Vec<FaucetCoin>),
+}
+
+/// An object, with a page of its dynamic fields, as shown by `sui client inspect`.
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ObjectInspection {
+    pub object: SuiObjectData,
+    pub dynamic_fields: DynamicFieldPage,
+}
+
+impl Display for ObjectInspection {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut writer = String::new();
+        // The contents are shown as JSON below rather than as the fields listed by the object.
+        let object = SuiObjectData {
+            content: None,
+            ..self.object.clone()
+        };
+        write!(writer, "{object}")?;
+        writeln!(
+            writer,
+            "{}: {}",
+            "Digest".bold().bright_black(),
+            object.digest
+        )?;
+
+        writeln!(writer, "{}", "----- JSON -----".bold())?;
+        match &self.object.content {
+            Some(SuiParsedData::MoveObject(o)) => {
+                let json = serde_json::to_string_pretty(&o.fields.clone().to_json_value())
+                    .map_err(|_| std::fmt::Error)?;
+                writeln!(writer, "{json}")?;
+            }
+            Some(SuiParsedData::Package(p)) => {
+                let modules: Vec<_> = p.disassembled.keys().collect();
+                writeln!(writer, "Package of modules {modules:?}")?;
+            }
+            None => writeln!(writer, "No contents returned")?,
+        }
+
+        writeln!(writer, "{}", "----- BCS -----".bold())?;
+        match &self.object.bcs {
+            Some(SuiRawData::MoveObject(o)) => {
+                writeln!(writer, "{}", Base64::encode(&o.bcs_bytes))?;
+                writeln!(writer, "Number of bytes: {}", o.bcs_bytes.len())?;
+            }
+            Some(SuiRawData::Package(p)) => {
+                for (module, bytes) in &p.module_map {
+                    writeln!(writer, "{module}: {} bytes", bytes.len())?;
+                }
+            }
+            None => writeln!(writer, "No BCS returned")?,
+        }
+
+        writeln!(writer, "{}", "----- Display -----".bold())?;
+        match &self.object.display {
+            Some(display) if !display.is_empty() => {
+                for (key, value) in display {
+                    writeln!(writer, "{}: {value}", key.bold().bright_black())?;
+                }
+            }
+            _ => writeln!(writer, "No Display is defined for the type of the object")?,
+        }
+
+        writeln!(writer, "{}", "----- Dynamic Fields -----".bold())?;
+        let page = &self.dynamic_fields;
+        if page.data.is_empty() {
+            writeln!(writer, "No dynamic fields")?;
+        } else {
+            write!(writer, "{}", dynamic_fields_table(page))?;
+            writeln!(writer, "Showing {} dynamic fields.", page.data.len())?;
+        }
+        if let (true, Some(cursor)) = (page.has_next_page, page.next_cursor) {
+            writeln!(writer, "More with --cursor {cursor}")?;
+        }
+        write!(f, "{}", writer.trim_end_matches('\n'))
+    }
+}
+
+fn dynamic_fields_table(page: &DynamicFieldPage) -> Table {
+    let mut table: Table = table!([
+        "Name",
+        "Type",
+        "Object Type",
+        "Object Id",
+        "Version",
+        "Digest"
+    ]);
+    for df_ref in page.data.iter() {
+        let df_type = match df_ref.type_ {
+            DynamicFieldType::DynamicField => "DynamicField",
+            DynamicFieldType::DynamicObject => "DynamicObject",
+        };
+        table.add_row(row![
+            df_ref.name,
+            df_type,
+            df_ref.object_type,
+            df_ref.object_id,
+            df_ref.version.value(),
+            Base64::encode(df_ref.digest)
+        ]);
+    }
+    table
+}
+
+/// The payments made by `sui client pay-batch`.
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PayBatchSummary {
+    /// The number of recipients in the CSV file
+    pub recipients: usize,
+    /// The sum of the amounts in the CSV file
+    pub total_amount: u128,
+    /// The transactions executed, in order
+    pub transactions: Vec<PayBatchTransaction>,
+    /// The error that stopped the payments before all of them were made
+    pub error: Option<String>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PayBatchTransaction {
+    pub digest: TransactionDigest,
+    /// The lines of the CSV file paid by the transaction
+    pub first_line: usize,
+    pub last_line: usize,
+    pub recipients: usize,
+    pub amount: u128,
+    /// The gas paid, net of storage rebates
+    pub gas_cost: i128,
+}
+
+impl Display for PayBatchSummary {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut writer = String::new();
+        let recipients: usize = self.transactions.iter().map(|t| t.recipients).sum();
+        let amount: u128 = self.transactions.iter().map(|t| t.amount).sum();
+        let gas_cost: i128 = self.transactions.iter().map(|t| t.gas_cost).sum();
+        writeln!(writer, "{}", "----- Pay Batch ----".bold())?;
+        writeln!(
+            writer,
+            "Paid {recipients} of {} recipients, {amount} of {}, in {} transactions costing {gas_cost} gas",
+            self.recipients,
+            self.total_amount,
+            self.transactions.len()
+        )?;
+        if !self.transactions.is_empty() {
+            let mut table: Table = table!([
+                "Transaction Digest",
+                "Lines",
+                "Recipients",
+                "Amount",
+                "Gas Cost"
+            ]);
+            for transaction in &self.transactions {
+                table.add_row(row![
+                    transaction.digest,
+                    format!("{}-{}", transaction.first_line, transaction.last_line),
+                    transaction.recipients,
+                    transaction.amount,
+                    transaction.gas_cost,
+                ]);
+            }
+            write!(writer, "{table}")?;
+        }
+        if let Some(error) = &self.error {
+            writeln!(writer, "{}", error.red())?;
+        }
+        write!(f, "{}", writer.trim_end_matches('\n'))
+    }
 }
 
 #[derive(Serialize, Clone, Debug)]
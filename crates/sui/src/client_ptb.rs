@@ -0,0 +1,401 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! The commands of `sui client ptb`, which are run in order by a single programmable transaction
+//! and can take the results of the commands before them as arguments.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use anyhow::{anyhow, bail, ensure};
+use move_core_types::identifier::Identifier;
+use move_core_types::language_storage::TypeTag;
+use move_package::BuildConfig as MoveBuildConfig;
+use sui_framework::build_move_package;
+use sui_framework_build::compiled_package::BuildConfig;
+use sui_json::SuiJsonValue;
+use sui_move::build::resolve_lock_file_path;
+use sui_sdk::SuiClient;
+use sui_types::base_types::{ObjectID, SuiAddress};
+use sui_types::messages::{Argument, Command, ProgrammableMoveCall, ProgrammableTransaction};
+use sui_types::parse_sui_type_tag;
+use sui_types::programmable_transaction_builder::ProgrammableTransactionBuilder;
+
+use crate::client_commands::convert_number_to_string;
+
+/// An argument of a command.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PtbArg {
+    /// `gas`, the coin paying for the transaction.
+    Gas,
+    /// `@N`, the result of the `N`th command (counting from 0), or `@N.M`, its `M`th result.
+    Result(u16, Option<u16>),
+    /// Any other value, read according to where it is passed: an object ID where the command takes
+    /// an object, an address for a recipient, a number for an amount, and a JSON value for the
+    /// args of a Move call, as with `sui client call`.
+    Value(String),
+}
+
+/// A command of a programmable transaction, written as `<command> <args>...`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PtbCommand {
+    /// `move-call <package>::<module>::<function>[<type args>] <args>...`
+    MoveCall {
+        package: ObjectID,
+        module: Identifier,
+        function: Identifier,
+        type_args: Vec<TypeTag>,
+        args: Vec<PtbArg>,
+    },
+    /// `split-coins <coin> <amounts>...`, whose results are the new coins, one per amount.
+    SplitCoins { coin: PtbArg, amounts: Vec<PtbArg> },
+    /// `merge-coins <coin> <coins to merge>...`
+    MergeCoins { coin: PtbArg, coins: Vec<PtbArg> },
+    /// `transfer-objects <recipient> <objects>...`
+    TransferObjects {
+        recipient: PtbArg,
+        objects: Vec<PtbArg>,
+    },
+    /// `publish <package path>`
+    Publish { package_path: PathBuf },
+}
+
+impl FromStr for PtbArg {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s == "gas" {
+            return Ok(Self::Gas);
+        }
+        let Some(result) = s.strip_prefix('@') else {
+            return Ok(Self::Value(s.to_string()));
+        };
+        let (command, nested) = match result.split_once('.') {
+            Some((command, nested)) => (command, Some(nested.parse()?)),
+            None => (result, None),
+        };
+        Ok(Self::Result(command.parse()?, nested))
+    }
+}
+
+impl FromStr for PtbCommand {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let words = shell_words::split(s)?;
+        let Some((name, args)) = words.split_first() else {
+            bail!("Empty command");
+        };
+        let mut args = args
+            .iter()
+            .map(|arg| arg.parse())
+            .collect::<Result<Vec<PtbArg>, _>>()
+            .map_err(|e| anyhow!("Invalid argument in command [{s}]: {e}"))?
+            .into_iter();
+        Ok(match name.as_str() {
+            "move-call" => {
+                let target = words
+                    .get(1)
+                    .ok_or_else(|| anyhow!("move-call requires a function to call"))?;
+                let (package, module, function, type_args) = parse_move_call_target(target)?;
+                // The function called is not one of its args.
+                args.next();
+                Self::MoveCall {
+                    package,
+                    module,
+                    function,
+                    type_args,
+                    args: args.collect(),
+                }
+            }
+            "split-coins" => {
+                let coin = args
+                    .next()
+                    .ok_or_else(|| anyhow!("split-coins requires a coin to split"))?;
+                let amounts: Vec<_> = args.collect();
+                ensure!(!amounts.is_empty(), "split-coins requires amounts to split");
+                Self::SplitCoins { coin, amounts }
+            }
+            "merge-coins" => {
+                let coin = args
+                    .next()
+                    .ok_or_else(|| anyhow!("merge-coins requires a coin to merge into"))?;
+                let coins: Vec<_> = args.collect();
+                ensure!(!coins.is_empty(), "merge-coins requires coins to merge");
+                Self::MergeCoins { coin, coins }
+            }
+            "transfer-objects" => {
+                let recipient = args
+                    .next()
+                    .ok_or_else(|| anyhow!("transfer-objects requires a recipient"))?;
+                let objects: Vec<_> = args.collect();
+                ensure!(
+                    !objects.is_empty(),
+                    "transfer-objects requires objects to transfer"
+                );
+                Self::TransferObjects { recipient, objects }
+            }
+            "publish" => match words.as_slice() {
+                [_, package_path] => Self::Publish {
+                    package_path: package_path.into(),
+                },
+                _ => bail!("publish requires exactly one package path"),
+            },
+            _ => bail!(
+                "Unknown command [{name}], expected one of move-call, split-coins, merge-coins, \
+                 transfer-objects or publish"
+            ),
+        })
+    }
+}
+
+/// Reads the commands of a file, one per line. Empty lines and lines starting with `#` are
+/// skipped.
+pub fn read_ptb_file(path: &Path) -> Result<Vec<PtbCommand>, anyhow::Error> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| anyhow!("Cannot read commands from {}: {e}", path.display()))?;
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(str::parse)
+        .collect()
+}
+
+/// Builds the programmable transaction running `commands`, looking up the objects they take.
+pub async fn build_programmable_transaction(
+    client: &SuiClient,
+    commands: Vec<PtbCommand>,
+) -> Result<ProgrammableTransaction, anyhow::Error> {
+    let mut builder = PtbBuilder {
+        client,
+        builder: ProgrammableTransactionBuilder::new(),
+        results: vec![],
+    };
+    for (i, command) in commands.into_iter().enumerate() {
+        let results = builder
+            .command(command)
+            .await
+            .map_err(|e| anyhow!("Error in command {i}: {e}"))?;
+        builder.results.push(results);
+    }
+    Ok(builder.builder.finish())
+}
+
+/// The values resulting from a command, which the commands after it can take.
+enum PtbResults {
+    None,
+    /// The result of a Move call, with one nested result per returned value.
+    Call(Argument),
+    Coins(Vec<Argument>),
+}
+
+struct PtbBuilder<'a> {
+    client: &'a SuiClient,
+    builder: ProgrammableTransactionBuilder,
+    results: Vec<PtbResults>,
+}
+
+impl PtbBuilder<'_> {
+    async fn command(&mut self, command: PtbCommand) -> Result<PtbResults, anyhow::Error> {
+        Ok(match command {
+            PtbCommand::MoveCall {
+                package,
+                module,
+                function,
+                type_args,
+                args,
+            } => {
+                // Convert all numeric input to String, as `sui client call` does.
+                let json_args = args
+                    .iter()
+                    .map(|arg| match arg {
+                        PtbArg::Value(value) => {
+                            Ok(Some(SuiJsonValue::new(convert_number_to_string(
+                                SuiJsonValue::from_str(value)?.to_json_value(),
+                            ))?))
+                        }
+                        PtbArg::Gas | PtbArg::Result(..) => Ok(None),
+                    })
+                    .collect::<Result<Vec<_>, anyhow::Error>>()?;
+                let call_args = self
+                    .client
+                    .transaction_builder()
+                    .programmable_move_call_args(package, &module, &function, &type_args, json_args)
+                    .await?;
+                let mut arguments = vec![];
+                for (arg, call_arg) in args.iter().zip(call_args) {
+                    arguments.push(match call_arg {
+                        Some(call_arg) => self.builder.input(call_arg)?,
+                        None => self.single(arg)?,
+                    });
+                }
+                PtbResults::Call(self.builder.command(Command::MoveCall(Box::new(
+                    ProgrammableMoveCall {
+                        package,
+                        module,
+                        function,
+                        type_arguments: type_args,
+                        arguments,
+                    },
+                ))))
+            }
+            PtbCommand::SplitCoins { coin, amounts } => {
+                let coin = self.object(&coin).await?;
+                let mut coins = vec![];
+                for amount in &amounts {
+                    let amount = match amount {
+                        PtbArg::Value(amount) => self.builder.pure(
+                            amount
+                                .parse::<u64>()
+                                .map_err(|e| anyhow!("Invalid amount [{amount}]: {e}"))?,
+                        )?,
+                        _ => self.single(amount)?,
+                    };
+                    coins.push(self.builder.command(Command::SplitCoin(coin, amount)));
+                }
+                PtbResults::Coins(coins)
+            }
+            PtbCommand::MergeCoins { coin, coins } => {
+                let coin = self.object(&coin).await?;
+                let mut merged = vec![];
+                for arg in &coins {
+                    merged.extend(self.objects(arg).await?);
+                }
+                self.builder.command(Command::MergeCoins(coin, merged));
+                PtbResults::None
+            }
+            PtbCommand::TransferObjects { recipient, objects } => {
+                let recipient = match recipient {
+                    PtbArg::Value(recipient) => self.builder.pure(
+                        SuiAddress::from_str(&recipient)
+                            .map_err(|e| anyhow!("Invalid recipient [{recipient}]: {e}"))?,
+                    )?,
+                    _ => self.single(&recipient)?,
+                };
+                let mut transferred = vec![];
+                for arg in &objects {
+                    transferred.extend(self.objects(arg).await?);
+                }
+                self.builder
+                    .command(Command::TransferObjects(transferred, recipient));
+                PtbResults::None
+            }
+            PtbCommand::Publish { package_path } => {
+                let build_config =
+                    resolve_lock_file_path(MoveBuildConfig::default(), Some(package_path.clone()))?;
+                let compiled_package = build_move_package(
+                    &package_path,
+                    BuildConfig {
+                        config: build_config,
+                        run_bytecode_verifier: true,
+                        print_diags_to_stderr: true,
+                    },
+                )?;
+                self.builder
+                    .command(Command::Publish(compiled_package.get_package_bytes(false)));
+                PtbResults::None
+            }
+        })
+    }
+
+    /// The arguments passing an object, or all the results of a command.
+    async fn objects(&mut self, arg: &PtbArg) -> Result<Vec<Argument>, anyhow::Error> {
+        match arg {
+            PtbArg::Value(_) => Ok(vec![self.object(arg).await?]),
+            PtbArg::Result(command, None) => match self.results.get(*command as usize) {
+                Some(PtbResults::Coins(coins)) => Ok(coins.clone()),
+                _ => Ok(vec![self.single(arg)?]),
+            },
+            _ => Ok(vec![self.single(arg)?]),
+        }
+    }
+
+    async fn object(&mut self, arg: &PtbArg) -> Result<Argument, anyhow::Error> {
+        match arg {
+            PtbArg::Value(id) => {
+                let id =
+                    ObjectID::from_str(id).map_err(|e| anyhow!("Invalid object [{id}]: {e}"))?;
+                let object_arg = self.client.transaction_builder().object_arg(id).await?;
+                Ok(self.builder.obj(object_arg))
+            }
+            _ => self.single(arg),
+        }
+    }
+
+    /// The argument referring to the gas coin or to a single result of a command.
+    fn single(&self, arg: &PtbArg) -> Result<Argument, anyhow::Error> {
+        let (command, nested) = match *arg {
+            PtbArg::Gas => return Ok(Argument::GasCoin),
+            PtbArg::Result(command, nested) => (command, nested),
+            PtbArg::Value(_) => bail!("Expected gas or a result of an earlier command"),
+        };
+        let results = self
+            .results
+            .get(command as usize)
+            .ok_or_else(|| anyhow!("@{command} does not refer to an earlier command"))?;
+        match (results, nested) {
+            (PtbResults::Call(result), None) => Ok(*result),
+            (PtbResults::Call(Argument::Result(i)), Some(nested)) => {
+                Ok(Argument::NestedResult(*i, nested))
+            }
+            (PtbResults::Coins(coins), None) if coins.len() == 1 => Ok(coins[0]),
+            (PtbResults::Coins(coins), None) => Err(anyhow!(
+                "Command {command} results in {} coins, pick one with @{command}.<index>",
+                coins.len()
+            )),
+            (PtbResults::Coins(coins), Some(nested)) => coins
+                .get(nested as usize)
+                .copied()
+                .ok_or_else(|| anyhow!("Command {command} results in {} coins", coins.len())),
+            _ => Err(anyhow!("Command {command} has no result")),
+        }
+    }
+}
+
+/// Parses `<package>::<module>::<function>`, optionally followed by type args in angle brackets,
+/// as in `0x2::coin::value<0x2::sui::SUI>`.
+pub(crate) fn parse_move_call_target(
+    target: &str,
+) -> Result<(ObjectID, Identifier, Identifier, Vec<TypeTag>), anyhow::Error> {
+    let (path, type_args) = match target.split_once('<') {
+        Some((path, type_args)) => {
+            let type_args = type_args
+                .strip_suffix('>')
+                .ok_or_else(|| anyhow!("Unclosed type arguments in [{target}]"))?;
+            (path, split_type_args(type_args)?)
+        }
+        None => (target, vec![]),
+    };
+    let parts: Vec<_> = path.split("::").collect();
+    let [package, module, function] = parts.as_slice() else {
+        bail!("Expected a function as <package>::<module>::<function>, got [{target}]");
+    };
+    Ok((
+        ObjectID::from_str(package)?,
+        Identifier::from_str(module)?,
+        Identifier::from_str(function)?,
+        type_args,
+    ))
+}
+
+/// Splits type args at the commas that are not nested in the type args of a type.
+fn split_type_args(type_args: &str) -> Result<Vec<TypeTag>, anyhow::Error> {
+    let mut tags = vec![];
+    let mut depth = 0usize;
+    let mut start = 0;
+    for (i, c) in type_args.char_indices() {
+        match c {
+            '<' => depth += 1,
+            '>' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                tags.push(parse_sui_type_tag(type_args[start..i].trim())?);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    tags.push(parse_sui_type_tag(type_args[start..].trim())?);
+    Ok(tags)
+}
@@ -3,6 +3,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use std::fmt::{Display, Formatter, Write};
+use std::path::PathBuf;
 
 use anyhow::anyhow;
 use serde::{Deserialize, Serialize};
@@ -11,9 +12,9 @@ use serde_with::serde_as;
 pub use sui_config::utils;
 pub use sui_config::Config;
 pub use sui_config::PersistedConfig;
-use sui_config::SUI_DEV_NET_URL;
+use sui_config::{SUI_DEV_NET_FAUCET_URL, SUI_DEV_NET_URL};
 use sui_keys::keystore::AccountKeystore;
-use sui_keys::keystore::Keystore;
+use sui_keys::keystore::{FileBasedKeystore, Keystore};
 use sui_sdk::{SuiClient, SuiClientBuilder};
 use sui_types::base_types::*;
 
@@ -24,6 +25,10 @@ pub struct SuiClientConfig {
     pub envs: Vec<SuiEnv>,
     pub active_env: Option<String>,
     pub active_address: Option<SuiAddress>,
+    /// The keystore shared by the environments without their own, set aside while the active
+    /// environment uses its own keystore.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shared_keystore: Option<Keystore>,
 }
 
 impl SuiClientConfig {
@@ -33,6 +38,7 @@ impl SuiClientConfig {
             envs: vec![],
             active_env: None,
             active_address: None,
+            shared_keystore: None,
         }
     }
 
@@ -62,6 +68,39 @@ impl SuiClientConfig {
             self.envs.push(env)
         }
     }
+
+    /// Makes `alias` the active environment, using its keystore and its active address from when
+    /// it was last active. The active address of the environment left is remembered.
+    pub fn switch_env(&mut self, alias: &str) -> Result<(), anyhow::Error> {
+        let next = self
+            .envs
+            .iter()
+            .position(|env| env.alias == alias)
+            .ok_or_else(|| anyhow!("Environment config not found for [{alias}], add new environment config using the `sui client new-env` command."))?;
+
+        let active_address = self.active_address;
+        if let Some(current) = self.active_env.clone() {
+            if let Some(env) = self.envs.iter_mut().find(|env| env.alias == current) {
+                env.active_address = active_address;
+            }
+        }
+        if let Some(shared) = self.shared_keystore.take() {
+            self.keystore = shared;
+        }
+        if let Some(path) = &self.envs[next].keystore {
+            let keystore = Keystore::from(FileBasedKeystore::new(path)?);
+            self.shared_keystore = Some(std::mem::replace(&mut self.keystore, keystore));
+        }
+        self.active_env = Some(alias.to_string());
+
+        let addresses = self.keystore.addresses();
+        self.active_address = self.envs[next]
+            .active_address
+            .filter(|address| addresses.contains(address))
+            .or_else(|| active_address.filter(|address| addresses.contains(address)))
+            .or_else(|| addresses.first().copied());
+        Ok(())
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -69,6 +108,15 @@ pub struct SuiEnv {
     pub alias: String,
     pub rpc: String,
     pub ws: Option<String>,
+    /// The URL of the faucet of the network, if it has one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub faucet: Option<String>,
+    /// The keystore used in this environment, instead of the keystore shared by the environments.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub keystore: Option<PathBuf>,
+    /// The active address of the environment, remembered while another one is active.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub active_address: Option<SuiAddress>,
 }
 
 impl SuiEnv {
@@ -91,6 +139,9 @@ impl SuiEnv {
             alias: "devnet".to_string(),
             rpc: SUI_DEV_NET_URL.into(),
             ws: None,
+            faucet: Some(SUI_DEV_NET_FAUCET_URL.into()),
+            keystore: None,
+            active_address: None,
         }
     }
 }
@@ -104,6 +155,14 @@ impl Display for SuiEnv {
             writeln!(writer)?;
             write!(writer, "Websocket URL: {ws}")?;
         }
+        if let Some(faucet) = &self.faucet {
+            writeln!(writer)?;
+            write!(writer, "Faucet URL: {faucet}")?;
+        }
+        if let Some(keystore) = &self.keystore {
+            writeln!(writer)?;
+            write!(writer, "Keystore: {}", keystore.display())?;
+        }
         write!(f, "{}", writer)
     }
 }
@@ -10,6 +10,8 @@ use clap::CommandFactory;
 use clap::FromArgMatches;
 use clap::Parser;
 use colored::Colorize;
+use sui_json_rpc_types::{SuiObjectDataOptions, SuiTransactionEffectsAPI, SuiTransactionResponse};
+use sui_types::base_types::{ObjectID, SuiAddress};
 
 use crate::client_commands::SwitchResponse;
 use crate::client_commands::{SuiClientCommandResult, SuiClientCommands, WalletContext};
@@ -17,6 +19,26 @@ use crate::shell::{
     install_shell_plugins, AsyncHandler, CacheKey, CommandStructure, CompletionCache, Shell,
 };
 
+/// The file next to the client config where the console keeps its command history.
+const HISTORY_FILE: &str = "console_history";
+
+/// Flags completed with the addresses of the keystore.
+const ADDRESS_FLAGS: &[&str] = &["--address", "--to", "--recipient", "--recipients"];
+
+/// Flags completed with the objects owned by the active address.
+const OBJECT_FLAGS: &[&str] = &[
+    "--id",
+    "--object-id",
+    "--gas",
+    "--coin-id",
+    "--coins",
+    "--input-coins",
+    "--primary-coin",
+    "--coin-to-merge",
+    "--sui-coin-object-id",
+    "--staked-sui",
+];
+
 const SUI: &str = "   _____       _    ______                       __   
   / ___/__  __(_)  / ____/___  ____  _________  / /__ 
   \\__ \\/ / / / /  / /   / __ \\/ __ \\/ ___/ __ \\/ / _ \\
@@ -34,7 +56,7 @@ pub struct ConsoleOpts {
 }
 
 pub async fn start_console(
-    context: WalletContext,
+    mut context: WalletContext,
     out: &mut (dyn Write + Send),
     err: &mut (dyn Write + Send),
 ) -> Result<(), anyhow::Error> {
@@ -77,12 +99,26 @@ pub async fn start_console(
     writeln!(out, "Welcome to the Sui interactive console.")?;
     writeln!(out)?;
 
+    let history_file = context
+        .config
+        .path()
+        .parent()
+        .map(|dir| dir.join(HISTORY_FILE));
+    let completion_cache = CompletionCache::default();
+    if let Err(e) = refresh_completion_cache(&mut context, &completion_cache).await {
+        writeln!(err, "{}", format!("Cannot fetch completions: {e}").yellow())?;
+    }
+
     let mut shell = Shell::new(
         "sui>-$ ",
         context,
         ClientCommandHandler,
         CommandStructure::from_clap(&install_shell_plugins(app)),
-    );
+    )
+    .with_completion_cache(completion_cache);
+    if let Some(history_file) = history_file {
+        shell = shell.with_history_file(history_file);
+    }
 
     shell.run_async(out, err).await
 }
@@ -123,31 +159,28 @@ async fn handle_command(
     let result = wallet_opts.command.execute(context).await?;
 
     // Update completion cache
-    // TODO: Completion data are keyed by strings, are there ways to make it more error proof?
-    if let Ok(mut cache) = completion_cache.write() {
-        match result {
-            SuiClientCommandResult::Addresses(ref addresses, _) => {
-                let addresses = addresses
-                    .iter()
-                    .map(|addr| format!("{addr}"))
-                    .collect::<Vec<_>>();
-                cache.insert(CacheKey::flag("--address"), addresses.clone());
-                cache.insert(CacheKey::flag("--to"), addresses);
-            }
-            SuiClientCommandResult::Objects(ref objects) => {
-                let objects = objects
-                    .iter()
-                    .map(|oref| format!("{}", oref.object_id))
-                    .collect::<Vec<_>>();
-                cache.insert(CacheKey::new("object", "--id"), objects.clone());
-                cache.insert(CacheKey::flag("--gas"), objects.clone());
-                cache.insert(CacheKey::flag("--coin-object-id"), objects);
-            }
-            _ => {}
+    match result {
+        SuiClientCommandResult::Addresses(ref addresses, _) => {
+            cache_addresses(&completion_cache, addresses);
         }
+        SuiClientCommandResult::NewAddress(_) => {
+            cache_addresses(&completion_cache, &context.config.keystore.addresses());
+        }
+        SuiClientCommandResult::Objects(ref objects) => {
+            cache_objects(&completion_cache, objects.iter().map(|o| o.object_id));
+        }
+        _ => {}
     }
     result.print(!wallet_opts.json);
 
+    if let Some(response) = result.transaction_response() {
+        if !wallet_opts.json {
+            print_changed_objects(context, response).await?;
+        }
+        // The transaction may have created objects, or transferred some away.
+        refresh_completion_cache(context, &completion_cache).await?;
+    }
+
     // Quit shell after RPC switch
     if matches!(
         result,
@@ -158,3 +191,80 @@ async fn handle_command(
     }
     Ok(false)
 }
+
+/// Fills the completion cache with the addresses of the keystore and the objects owned by the
+/// active address.
+async fn refresh_completion_cache(
+    context: &mut WalletContext,
+    completion_cache: &CompletionCache,
+) -> Result<(), anyhow::Error> {
+    cache_addresses(completion_cache, &context.config.keystore.addresses());
+    let address = context.active_address()?;
+    let objects = context
+        .get_client()
+        .await?
+        .read_api()
+        .get_objects_owned_by_address(address)
+        .await?;
+    cache_objects(completion_cache, objects.iter().map(|o| o.object_id));
+    Ok(())
+}
+
+// TODO: Completion data are keyed by strings, are there ways to make it more error proof?
+fn cache_addresses(completion_cache: &CompletionCache, addresses: &[SuiAddress]) {
+    let addresses = addresses
+        .iter()
+        .map(|addr| format!("{addr}"))
+        .collect::<Vec<_>>();
+    cache_values(completion_cache, ADDRESS_FLAGS, addresses);
+}
+
+fn cache_objects(completion_cache: &CompletionCache, objects: impl Iterator<Item = ObjectID>) {
+    let objects = objects.map(|id| format!("{id}")).collect::<Vec<_>>();
+    cache_values(completion_cache, OBJECT_FLAGS, objects);
+}
+
+fn cache_values(completion_cache: &CompletionCache, flags: &[&str], values: Vec<String>) {
+    if let Ok(mut cache) = completion_cache.write() {
+        for flag in flags {
+            cache.insert(CacheKey::flag(flag), values.clone());
+        }
+    }
+}
+
+/// Prints the current contents of the objects created or changed by a transaction, other than its
+/// gas coin.
+async fn print_changed_objects(
+    context: &WalletContext,
+    response: &SuiTransactionResponse,
+) -> Result<(), anyhow::Error> {
+    let effects = &response.effects;
+    if effects.status().is_err() {
+        return Ok(());
+    }
+    let changed = effects
+        .created()
+        .iter()
+        .chain(effects.unwrapped())
+        .cloned()
+        .chain(effects.mutated_excluding_gas())
+        .map(|o| o.reference.object_id)
+        .collect::<Vec<_>>();
+    if changed.is_empty() {
+        return Ok(());
+    }
+
+    let client = context.get_client().await?;
+    println!("----- Changed Objects ----");
+    for id in changed {
+        let object = client
+            .read_api()
+            .get_object_with_options(id, SuiObjectDataOptions::full_content())
+            .await?;
+        match object.object() {
+            Ok(object) => println!("{object}"),
+            Err(e) => println!("{}", format!("Cannot display object {id}: {e}").yellow()),
+        }
+    }
+    Ok(())
+}
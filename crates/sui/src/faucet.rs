@@ -0,0 +1,202 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Requests of gas coins to the faucet of a network, through its v1 API, which queues requests,
+//! or through its `/gas` endpoint when it does not have one.
+
+use std::time::Duration;
+
+use anyhow::{anyhow, bail};
+use colored::Colorize;
+use reqwest::{RequestBuilder, Response, StatusCode};
+use serde::{Deserialize, Serialize};
+use sui_types::base_types::{ObjectID, SuiAddress, TransactionDigest};
+use tokio::time::sleep;
+
+/// Attempts of a request that fails for a reason that can go away, e.g. the faucet being
+/// overloaded, before giving up. Requests for gas are only retried when the faucet did not act on
+/// them.
+const MAX_ATTEMPTS: u32 = 5;
+/// The delay before the first retry, doubled for each of the next ones.
+const RETRY_DELAY: Duration = Duration::from_secs(1);
+/// How often, and how many times, the status of a queued request is polled.
+const STATUS_POLL_INTERVAL: Duration = Duration::from_secs(1);
+const MAX_STATUS_POLLS: u32 = 120;
+
+#[derive(Serialize)]
+enum FaucetRequest {
+    FixedAmountRequest { recipient: SuiAddress },
+}
+
+#[derive(Deserialize)]
+struct FaucetResponse {
+    #[serde(default)]
+    transferred_gas_objects: Vec<FaucetCoin>,
+    error: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct BatchFaucetResponse {
+    task: Option<String>,
+    error: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct BatchStatusFaucetResponse {
+    status: Option<BatchSendStatus>,
+    error: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct BatchSendStatus {
+    status: BatchSendStatusType,
+    transferred_gas_objects: Option<FaucetReceipt>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+enum BatchSendStatusType {
+    Inprogress,
+    Succeeded,
+    Discarded,
+}
+
+#[derive(Deserialize)]
+struct FaucetReceipt {
+    sent: Vec<FaucetCoin>,
+}
+
+/// A coin sent by the faucet.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct FaucetCoin {
+    pub amount: u64,
+    pub id: ObjectID,
+    pub transfer_tx_digest: TransactionDigest,
+}
+
+/// Requests gas coins for `recipient` from the faucet at `url`, the URL of its `/gas` endpoint or
+/// the root of its API.
+pub async fn request_gas(
+    url: &str,
+    recipient: SuiAddress,
+) -> Result<Vec<FaucetCoin>, anyhow::Error> {
+    let root = url.trim_end_matches('/').trim_end_matches("/gas");
+    let client = reqwest::Client::new();
+    let request = FaucetRequest::FixedAmountRequest { recipient };
+
+    let response = send(
+        || client.post(format!("{root}/v1/gas")).json(&request),
+        false,
+    )
+    .await?;
+    if response.status() == StatusCode::NOT_FOUND {
+        return request_gas_legacy(&client, root, &request).await;
+    }
+    let status = response.status();
+    let response: BatchFaucetResponse = response
+        .json()
+        .await
+        .map_err(|e| anyhow!("The faucet responded with {status}: {e}"))?;
+    let task = match response {
+        BatchFaucetResponse {
+            task: Some(task), ..
+        } => task,
+        BatchFaucetResponse { error, .. } => bail!(
+            "The faucet did not accept the request: {}",
+            error.unwrap_or_else(|| status.to_string())
+        ),
+    };
+
+    for _ in 0..MAX_STATUS_POLLS {
+        let response = send(|| client.get(format!("{root}/v1/status/{task}")), true).await?;
+        let status = response.status();
+        let response: BatchStatusFaucetResponse = response
+            .json()
+            .await
+            .map_err(|e| anyhow!("The faucet responded with {status}: {e}"))?;
+        let Some(send_status) = response.status else {
+            bail!(
+                "Cannot get the status of faucet request {task}: {}",
+                response.error.unwrap_or_else(|| status.to_string())
+            );
+        };
+        match send_status.status {
+            BatchSendStatusType::Inprogress => sleep(STATUS_POLL_INTERVAL).await,
+            BatchSendStatusType::Succeeded => {
+                return Ok(send_status
+                    .transferred_gas_objects
+                    .map(|receipt| receipt.sent)
+                    .unwrap_or_default())
+            }
+            BatchSendStatusType::Discarded => {
+                bail!("The faucet could not serve request {task}, try again later")
+            }
+        }
+    }
+    bail!("Faucet request {task} is still in progress, check the balance of {recipient} later")
+}
+
+async fn request_gas_legacy(
+    client: &reqwest::Client,
+    root: &str,
+    request: &FaucetRequest,
+) -> Result<Vec<FaucetCoin>, anyhow::Error> {
+    let response = send(|| client.post(format!("{root}/gas")).json(request), false).await?;
+    let status = response.status();
+    let response: FaucetResponse = response
+        .json()
+        .await
+        .map_err(|e| anyhow!("The faucet responded with {status}: {e}"))?;
+    match response.error {
+        Some(error) => bail!("The faucet could not serve the request: {error}"),
+        None => Ok(response.transferred_gas_objects),
+    }
+}
+
+/// Sends the request built by `request`, again after a delay while it fails for a reason that can
+/// go away. Requests that are not `idempotent` are only sent again when the faucet turned them
+/// away before acting on them. The response of the last attempt is returned whatever its status,
+/// for its error.
+async fn send(
+    request: impl Fn() -> RequestBuilder,
+    idempotent: bool,
+) -> Result<Response, anyhow::Error> {
+    let mut delay = RETRY_DELAY;
+    let mut attempt = 1;
+    loop {
+        let error = match request().send().await {
+            Ok(response)
+                if attempt < MAX_ATTEMPTS && is_transient(response.status(), idempotent) =>
+            {
+                format!("the faucet responded with {}", response.status())
+            }
+            Ok(response) => return Ok(response),
+            // A request that timed out may still be served.
+            Err(e)
+                if attempt < MAX_ATTEMPTS && (e.is_connect() || idempotent && e.is_timeout()) =>
+            {
+                e.to_string()
+            }
+            Err(e) => return Err(anyhow!("Cannot reach the faucet: {e}")),
+        };
+        eprintln!(
+            "{}",
+            format!("[warn] Retrying in {delay:?}, as {error}").yellow()
+        );
+        sleep(delay).await;
+        delay *= 2;
+        attempt += 1;
+    }
+}
+
+/// Whether a request answered with `status` may succeed later. Requests over the limits of the
+/// faucet, or turned away while it is overloaded, were not acted on.
+fn is_transient(status: StatusCode, idempotent: bool) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS
+        || status == StatusCode::SERVICE_UNAVAILABLE
+        || idempotent && status.is_server_error()
+}
+
+#[cfg(test)]
+#[path = "unit_tests/faucet_tests.rs"]
+mod faucet_tests;
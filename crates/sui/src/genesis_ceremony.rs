@@ -6,16 +6,19 @@ use camino::Utf8PathBuf;
 use clap::Parser;
 use fastcrypto::encoding::{Encoding, Hex};
 use multiaddr::Multiaddr;
-use std::path::PathBuf;
+use prettytable::{row, table, Table};
+use std::fs;
+use std::path::{Path, PathBuf};
 use sui_config::{
-    genesis::{Builder, GenesisTuple},
+    genesis::{Builder, GenesisTuple, GenesisValidatorInfo},
     SUI_GENESIS_FILENAME,
 };
 use sui_types::{
     base_types::{ObjectID, SuiAddress},
     committee::ProtocolVersion,
     crypto::{
-        generate_proof_of_possession, AuthorityKeyPair, KeypairTraits, NetworkKeyPair, SuiKeyPair,
+        generate_proof_of_possession, AuthorityKeyPair, AuthorityPublicKeyBytes, KeypairTraits,
+        NetworkKeyPair, SuiKeyPair,
     },
     object::Object,
 };
@@ -44,35 +47,40 @@ impl Ceremony {
 
 #[derive(Parser)]
 pub enum CeremonyCommand {
+    /// Create the builder directory shared by the participants of the ceremony.
     Init,
 
-    AddValidator {
-        #[clap(long)]
-        name: String,
-        #[clap(long)]
-        validator_key_file: PathBuf,
-        #[clap(long)]
-        worker_key_file: PathBuf,
-        #[clap(long)]
-        account_key_file: PathBuf,
-        #[clap(long)]
-        network_key_file: PathBuf,
+    /// Create a file with the information of a validator, and a proof of possession of its
+    /// protocol key, to contribute to a ceremony without access to its builder directory.
+    CreateValidatorInfo {
+        #[clap(flatten)]
+        info: ValidatorInfoArgs,
         #[clap(long)]
-        network_address: Multiaddr,
-        #[clap(long)]
-        p2p_address: Multiaddr,
-        #[clap(long)]
-        narwhal_primary_address: Multiaddr,
-        #[clap(long)]
-        narwhal_worker_address: Multiaddr,
-        #[clap(long)]
-        description: String,
+        file: PathBuf,
+    },
+
+    /// Check a file created with `create-validator-info`, and print the information in it.
+    VerifyValidatorInfo {
         #[clap(long)]
-        image_url: String,
+        file: PathBuf,
+    },
+
+    /// Add the validator of a file created with `create-validator-info`.
+    AddValidatorInfo {
         #[clap(long)]
-        project_url: String,
+        file: PathBuf,
+    },
+
+    /// Add a validator from its key files, as `create-validator-info` then `add-validator-info`
+    /// would.
+    AddValidator {
+        #[clap(flatten)]
+        info: ValidatorInfoArgs,
     },
 
+    /// List the validators added so far.
+    ListValidators,
+
     AddGasObject {
         #[clap(long)]
         address: SuiAddress,
@@ -82,16 +90,89 @@ pub enum CeremonyCommand {
         value: u64,
     },
 
+    /// Build the unsigned genesis checkpoint, once every validator and object has been added.
     BuildUnsignedCheckpoint,
 
+    /// Check that the unsigned genesis checkpoint was built from the contents of the builder
+    /// directory, and print a summary of it.
+    VerifyUnsignedCheckpoint,
+
+    /// Verify the unsigned genesis checkpoint and sign it with the protocol key of a validator.
     VerifyAndSign {
         #[clap(long)]
         key_file: PathBuf,
     },
 
+    /// Check the signatures over the unsigned genesis checkpoint, and list the validators yet to
+    /// sign it.
+    VerifySignatures,
+
+    /// Aggregate the signatures of every validator into the genesis certificate, and write the
+    /// genesis blob.
     Finalize,
 }
 
+#[derive(Parser)]
+pub struct ValidatorInfoArgs {
+    #[clap(long)]
+    name: String,
+    #[clap(long)]
+    validator_key_file: PathBuf,
+    #[clap(long)]
+    worker_key_file: PathBuf,
+    #[clap(long)]
+    account_key_file: PathBuf,
+    #[clap(long)]
+    network_key_file: PathBuf,
+    #[clap(long)]
+    network_address: Multiaddr,
+    #[clap(long)]
+    p2p_address: Multiaddr,
+    #[clap(long)]
+    narwhal_primary_address: Multiaddr,
+    #[clap(long)]
+    narwhal_worker_address: Multiaddr,
+    #[clap(long)]
+    description: String,
+    #[clap(long)]
+    image_url: String,
+    #[clap(long)]
+    project_url: String,
+}
+
+impl ValidatorInfoArgs {
+    fn into_genesis_validator_info(self) -> Result<GenesisValidatorInfo> {
+        let keypair: AuthorityKeyPair = read_authority_keypair_from_file(self.validator_key_file)?;
+        let account_keypair: SuiKeyPair = read_keypair_from_file(self.account_key_file)?;
+        let worker_keypair: NetworkKeyPair = read_network_keypair_from_file(self.worker_key_file)?;
+        let network_keypair: NetworkKeyPair =
+            read_network_keypair_from_file(self.network_key_file)?;
+        let proof_of_possession =
+            generate_proof_of_possession(&keypair, (&account_keypair.public()).into());
+        let info = GenesisValidatorInfo {
+            info: sui_config::ValidatorInfo {
+                name: self.name,
+                protocol_key: keypair.public().into(),
+                worker_key: worker_keypair.public().clone(),
+                account_key: account_keypair.public(),
+                network_key: network_keypair.public().clone(),
+                gas_price: 1,
+                commission_rate: 0,
+                network_address: self.network_address,
+                p2p_address: self.p2p_address,
+                narwhal_primary_address: self.narwhal_primary_address,
+                narwhal_worker_address: self.narwhal_worker_address,
+                description: self.description,
+                image_url: self.image_url,
+                project_url: self.project_url,
+            },
+            proof_of_possession,
+        };
+        info.validate()?;
+        Ok(info)
+    }
+}
+
 pub fn run(cmd: Ceremony) -> Result<()> {
     let dir = if let Some(path) = cmd.path {
         path
@@ -111,46 +192,56 @@ pub fn run(cmd: Ceremony) -> Result<()> {
             builder.save(dir)?;
         }
 
-        CeremonyCommand::AddValidator {
-            name,
-            validator_key_file,
-            worker_key_file,
-            account_key_file,
-            network_key_file,
-            network_address,
-            p2p_address,
-            narwhal_primary_address,
-            narwhal_worker_address,
-            description,
-            image_url,
-            project_url,
-        } => {
-            let mut builder = Builder::load(&dir)?;
-            let keypair: AuthorityKeyPair = read_authority_keypair_from_file(validator_key_file)?;
-            let account_keypair: SuiKeyPair = read_keypair_from_file(account_key_file)?;
-            let worker_keypair: NetworkKeyPair = read_network_keypair_from_file(worker_key_file)?;
-            let network_keypair: NetworkKeyPair = read_network_keypair_from_file(network_key_file)?;
-            let pop = generate_proof_of_possession(&keypair, (&account_keypair.public()).into());
-            builder = builder.add_validator(
-                sui_config::ValidatorInfo {
-                    name,
-                    protocol_key: keypair.public().into(),
-                    worker_key: worker_keypair.public().clone(),
-                    account_key: account_keypair.public(),
-                    network_key: network_keypair.public().clone(),
-                    gas_price: 1,
-                    commission_rate: 0,
-                    network_address,
-                    p2p_address,
-                    narwhal_primary_address,
-                    narwhal_worker_address,
-                    description,
-                    image_url,
-                    project_url,
-                },
-                pop,
+        CeremonyCommand::CreateValidatorInfo { info, file } => {
+            let info = info.into_genesis_validator_info()?;
+            fs::write(&file, serde_yaml::to_vec(&info)?)?;
+            println!(
+                "Successfully created the information of validator {} in {}",
+                info.info.name(),
+                file.display()
             );
-            builder.save(dir)?;
+        }
+
+        CeremonyCommand::VerifyValidatorInfo { file } => {
+            let info = read_validator_info(&file)?;
+            println!(
+                "Successfully verified the information of validator {}",
+                info.info.name()
+            );
+            println!("{}", serde_yaml::to_string(&info.info)?);
+        }
+
+        CeremonyCommand::AddValidatorInfo { file } => {
+            let info = read_validator_info(&file)?;
+            let name = info.info.name().to_owned();
+            add_validator(Builder::load(&dir)?, info)?.save(dir)?;
+            println!("Successfully added validator {name}");
+        }
+
+        CeremonyCommand::AddValidator { info } => {
+            let info = info.into_genesis_validator_info()?;
+            add_validator(Builder::load(&dir)?, info)?.save(dir)?;
+        }
+
+        CeremonyCommand::ListValidators => {
+            let builder = Builder::load(&dir)?;
+            let mut table: Table = table!([
+                "Name",
+                "Account Address",
+                "Protocol Key",
+                "Network Address",
+                "Signed"
+            ]);
+            for (key, validator) in builder.validators() {
+                table.add_row(row![
+                    validator.info.name(),
+                    validator.info.sui_address(),
+                    key,
+                    validator.info.network_address(),
+                    builder.signatures().contains_key(key)
+                ]);
+            }
+            table.printstd();
         }
 
         CeremonyCommand::AddGasObject {
@@ -159,6 +250,7 @@ pub fn run(cmd: Ceremony) -> Result<()> {
             value,
         } => {
             let mut builder = Builder::load(&dir)?;
+            check_not_built(&builder)?;
 
             let object_id = object_id.unwrap_or_else(ObjectID::random);
             let object = Object::with_id_owner_gas_for_testing(object_id, address, value);
@@ -169,8 +261,8 @@ pub fn run(cmd: Ceremony) -> Result<()> {
 
         CeremonyCommand::BuildUnsignedCheckpoint => {
             let mut builder = Builder::load(&dir)?;
-            let GenesisTuple(_, unsigned_checkpoint, ..) =
-                builder.build_unsigned_genesis_checkpoint();
+            builder.validate_validators()?;
+            let GenesisTuple(unsigned_checkpoint, ..) = builder.build_unsigned_genesis_checkpoint();
             println!(
                 "Successfully built unsigned checkpoint: {}",
                 unsigned_checkpoint.digest()
@@ -179,12 +271,34 @@ pub fn run(cmd: Ceremony) -> Result<()> {
             builder.save(dir)?;
         }
 
+        CeremonyCommand::VerifyUnsignedCheckpoint => {
+            // Loading the builder checks that the unsigned checkpoint is the one built from the
+            // parameters, validators and objects in the directory.
+            let builder = Builder::load(&dir)?;
+            check_protocol_version(&builder, protocol_version)?;
+            builder.validate_validators()?;
+            let Some(GenesisTuple(checkpoint, ..)) = builder.unsigned_genesis_checkpoint() else {
+                return Err(anyhow::anyhow!(
+                    "Unable to verify genesis checkpoint; it hasn't been built yet"
+                ));
+            };
+
+            println!(
+                "Successfully verified unsigned checkpoint: {}",
+                checkpoint.digest()
+            );
+            println!("Protocol version: {}", builder.protocol_version().as_u64());
+            println!("Validators: {}", builder.validators().len());
+            println!("Objects: {}", builder.objects().len());
+        }
+
         CeremonyCommand::VerifyAndSign { key_file } => {
             let keypair: AuthorityKeyPair = read_authority_keypair_from_file(key_file)?;
 
             let mut builder = Builder::load(&dir)?;
 
             check_protocol_version(&builder, protocol_version)?;
+            builder.validate_validators()?;
 
             // Don't sign unless the unsigned checkpoint has already been created
             if builder.unsigned_genesis_checkpoint().is_none() {
@@ -193,8 +307,15 @@ pub fn run(cmd: Ceremony) -> Result<()> {
                 ));
             }
 
+            let name: AuthorityPublicKeyBytes = keypair.public().into();
+            if !builder.validators().contains_key(&name) {
+                return Err(anyhow::anyhow!(
+                    "Unable to sign genesis checkpoint; key {name} is not the protocol key of a validator"
+                ));
+            }
+
             builder = builder.add_validator_signature(&keypair);
-            let checkpoint = builder.unsigned_genesis_checkpoint().unwrap().1;
+            let checkpoint = builder.unsigned_genesis_checkpoint().unwrap().0;
             builder.save(dir)?;
 
             println!(
@@ -203,9 +324,35 @@ pub fn run(cmd: Ceremony) -> Result<()> {
             );
         }
 
+        CeremonyCommand::VerifySignatures => {
+            let builder = Builder::load(&dir)?;
+            builder.validate_signatures()?;
+            let checkpoint = builder.unsigned_genesis_checkpoint().unwrap().0;
+
+            println!(
+                "Successfully verified {} of {} signatures over genesis checkpoint: {}",
+                builder.signatures().len(),
+                builder.validators().len(),
+                checkpoint.digest()
+            );
+            for name in missing_signatures(&builder) {
+                println!("Missing signature from validator {name}");
+            }
+        }
+
         CeremonyCommand::Finalize => {
             let builder = Builder::load(&dir)?;
             check_protocol_version(&builder, protocol_version)?;
+            builder.validate_validators()?;
+            builder.validate_signatures()?;
+
+            let missing = missing_signatures(&builder);
+            if !missing.is_empty() {
+                return Err(anyhow::anyhow!(
+                    "Unable to finalize genesis; missing signatures from validators: {}",
+                    missing.join(", ")
+                ));
+            }
 
             let genesis = builder.build();
 
@@ -222,6 +369,46 @@ pub fn run(cmd: Ceremony) -> Result<()> {
     Ok(())
 }
 
+fn read_validator_info(file: &Path) -> Result<GenesisValidatorInfo> {
+    let info: GenesisValidatorInfo = serde_yaml::from_slice(&fs::read(file)?)?;
+    info.validate()?;
+    Ok(info)
+}
+
+fn add_validator(builder: Builder, info: GenesisValidatorInfo) -> Result<Builder> {
+    check_not_built(&builder)?;
+    if let Some((key, _)) = builder
+        .validators()
+        .iter()
+        .find(|(key, v)| v.info.name() == info.info.name() && **key != info.info.protocol_key())
+    {
+        return Err(anyhow::anyhow!(
+            "Validator name {} is already taken by validator with protocol key {key}",
+            info.info.name()
+        ));
+    }
+    Ok(builder.add_validator(info.info, info.proof_of_possession))
+}
+
+fn check_not_built(builder: &Builder) -> Result<()> {
+    // The unsigned checkpoint would no longer match the contents of the builder directory.
+    if builder.unsigned_genesis_checkpoint().is_some() {
+        return Err(anyhow::anyhow!(
+            "Unable to modify genesis; the unsigned checkpoint has already been built"
+        ));
+    }
+    Ok(())
+}
+
+fn missing_signatures(builder: &Builder) -> Vec<String> {
+    builder
+        .validators()
+        .iter()
+        .filter(|(key, _)| !builder.signatures().contains_key(key))
+        .map(|(_, v)| v.info.name().to_owned())
+        .collect()
+}
+
 fn check_protocol_version(builder: &Builder, protocol_version: ProtocolVersion) -> Result<()> {
     // It is entirely possible for the user to sign a genesis blob with an unknown
     // protocol version, but if this happens there is almost certainly some confusion
@@ -305,15 +492,76 @@ mod test {
         };
         command.run()?;
 
-        // Add the validators
-        for (key_file, worker_key_file, network_key_file, account_key_file, validator) in
-            &validators
+        // Add the validators, half of them from the validator info files they contributed
+        for (i, (key_file, worker_key_file, network_key_file, account_key_file, validator)) in
+            validators.iter().enumerate()
         {
-            let command = Ceremony {
-                path: Some(dir.path().into()),
-                protocol_version: None,
-                command: CeremonyCommand::AddValidator {
-                    name: validator.name().to_owned(),
+            let info = ValidatorInfoArgs {
+                name: validator.name().to_owned(),
+                validator_key_file: key_file.into(),
+                worker_key_file: worker_key_file.into(),
+                network_key_file: network_key_file.into(),
+                account_key_file: account_key_file.into(),
+                network_address: validator.network_address().to_owned(),
+                p2p_address: validator.p2p_address().to_owned(),
+                narwhal_primary_address: validator.narwhal_primary_address.clone(),
+                narwhal_worker_address: validator.narwhal_worker_address.clone(),
+                description: String::new(),
+                image_url: String::new(),
+                project_url: String::new(),
+            };
+            if i % 2 == 0 {
+                let command = Ceremony {
+                    path: Some(dir.path().into()),
+                    protocol_version: None,
+                    command: CeremonyCommand::AddValidator { info },
+                };
+                command.run()?;
+                continue;
+            }
+
+            let file = dir.path().join(format!("{}.info", validator.name()));
+            for command in [
+                CeremonyCommand::CreateValidatorInfo {
+                    info,
+                    file: file.clone(),
+                },
+                CeremonyCommand::VerifyValidatorInfo { file: file.clone() },
+                CeremonyCommand::AddValidatorInfo { file },
+            ] {
+                let command = Ceremony {
+                    path: Some(dir.path().into()),
+                    protocol_version: None,
+                    command,
+                };
+                command.run()?;
+            }
+        }
+
+        let command = Ceremony {
+            path: Some(dir.path().into()),
+            protocol_version: None,
+            command: CeremonyCommand::ListValidators,
+        };
+        command.run()?;
+
+        // Build the unsigned checkpoint
+        let command = Ceremony {
+            path: Some(dir.path().into()),
+            protocol_version: None,
+            command: CeremonyCommand::BuildUnsignedCheckpoint,
+        };
+        command.run()?;
+
+        // Validators cannot be added once the unsigned checkpoint is built
+        let (key_file, worker_key_file, network_key_file, account_key_file, validator) =
+            &validators[0];
+        let command = Ceremony {
+            path: Some(dir.path().into()),
+            protocol_version: None,
+            command: CeremonyCommand::AddValidator {
+                info: ValidatorInfoArgs {
+                    name: "late-validator".to_owned(),
                     validator_key_file: key_file.into(),
                     worker_key_file: worker_key_file.into(),
                     network_key_file: network_key_file.into(),
@@ -326,20 +574,20 @@ mod test {
                     image_url: String::new(),
                     project_url: String::new(),
                 },
-            };
-            command.run()?;
-        }
+            },
+        };
+        assert!(command.run().is_err());
 
-        // Build the unsigned checkpoint
         let command = Ceremony {
             path: Some(dir.path().into()),
             protocol_version: None,
-            command: CeremonyCommand::BuildUnsignedCheckpoint,
+            command: CeremonyCommand::VerifyUnsignedCheckpoint,
         };
         command.run()?;
 
-        // Have all the validators verify and sign genesis
-        for (key, _worker_key, _network_key, _account_key, _validator) in &validators {
+        // Have all the validators but the last verify and sign genesis
+        let (last, signers) = validators.split_last().unwrap();
+        for (key, _worker_key, _network_key, _account_key, _validator) in signers {
             let command = Ceremony {
                 path: Some(dir.path().into()),
                 protocol_version: None,
@@ -350,6 +598,30 @@ mod test {
             command.run()?;
         }
 
+        let command = Ceremony {
+            path: Some(dir.path().into()),
+            protocol_version: None,
+            command: CeremonyCommand::VerifySignatures,
+        };
+        command.run()?;
+
+        // Genesis cannot be finalized without the signature of every validator
+        let command = Ceremony {
+            path: Some(dir.path().into()),
+            protocol_version: None,
+            command: CeremonyCommand::Finalize,
+        };
+        assert!(command.run().is_err());
+
+        let command = Ceremony {
+            path: Some(dir.path().into()),
+            protocol_version: None,
+            command: CeremonyCommand::VerifyAndSign {
+                key_file: last.0.clone(),
+            },
+        };
+        command.run()?;
+
         // Finalize the Ceremony and build the Genesis object
         let command = Ceremony {
             path: Some(dir.path().into()),
@@ -360,4 +632,65 @@ mod test {
 
         Ok(())
     }
+
+    #[test]
+    #[cfg_attr(msim, ignore)]
+    fn validator_info_with_invalid_proof_of_possession() -> Result<()> {
+        let dir = tempfile::TempDir::new().unwrap();
+
+        let keypair: AuthorityKeyPair = get_key_pair_from_rng(&mut rand::rngs::OsRng).1;
+        let account_keypair: AccountKeyPair = get_key_pair_from_rng(&mut rand::rngs::OsRng).1;
+        let other_account_keypair: AccountKeyPair = get_key_pair_from_rng(&mut rand::rngs::OsRng).1;
+        let worker_keypair: NetworkKeyPair = get_key_pair_from_rng(&mut rand::rngs::OsRng).1;
+        let network_keypair: NetworkKeyPair = get_key_pair_from_rng(&mut rand::rngs::OsRng).1;
+
+        // A proof of possession bound to another account than the validator's
+        let info = GenesisValidatorInfo {
+            info: ValidatorInfo {
+                name: "validator".to_owned(),
+                protocol_key: keypair.public().into(),
+                worker_key: worker_keypair.public().clone(),
+                account_key: account_keypair.public().clone().into(),
+                network_key: network_keypair.public().clone(),
+                gas_price: 1,
+                commission_rate: 0,
+                network_address: utils::new_tcp_network_address(),
+                p2p_address: utils::new_udp_network_address(),
+                narwhal_primary_address: utils::new_udp_network_address(),
+                narwhal_worker_address: utils::new_udp_network_address(),
+                description: String::new(),
+                image_url: String::new(),
+                project_url: String::new(),
+            },
+            proof_of_possession: generate_proof_of_possession(
+                &keypair,
+                other_account_keypair.public().into(),
+            ),
+        };
+        let file = dir.path().join("validator.info");
+        fs::write(&file, serde_yaml::to_vec(&info)?)?;
+
+        let command = Ceremony {
+            path: Some(dir.path().into()),
+            protocol_version: None,
+            command: CeremonyCommand::VerifyValidatorInfo { file: file.clone() },
+        };
+        assert!(command.run().is_err());
+
+        let command = Ceremony {
+            path: Some(dir.path().into()),
+            protocol_version: None,
+            command: CeremonyCommand::Init,
+        };
+        command.run()?;
+
+        let command = Ceremony {
+            path: Some(dir.path().into()),
+            protocol_version: None,
+            command: CeremonyCommand::AddValidatorInfo { file },
+        };
+        assert!(command.run().is_err());
+
+        Ok(())
+    }
 }
@@ -5,6 +5,9 @@ use bip32::DerivationPath;
 use clap::*;
 use fastcrypto::encoding::{decode_bytes_hex, Base64, Encoding};
 use fastcrypto::traits::KeyPair;
+use serde::Serialize;
+use std::collections::BTreeSet;
+use std::fmt::{Display, Formatter, Write};
 use std::fs;
 use std::path::{Path, PathBuf};
 use sui_keys::key_derive::generate_new_key;
@@ -17,11 +20,17 @@ use sui_types::intent::{Intent, IntentMessage};
 use sui_types::messages::TransactionData;
 use sui_types::multisig::{MultiSig, MultiSigPublicKey, ThresholdUnit, WeightUnit};
 use sui_types::signature::GenericSignature;
-use tracing::info;
 
-use sui_keys::keystore::{AccountKeystore, Keystore};
+use sui_keys::address_book::{address_book_path, AddressBook};
+use sui_keys::encryption::read_new_passphrase;
+use sui_keys::keystore::{AccountKeystore, FileBasedKeystore, Keystore};
 use sui_types::base_types::SuiAddress;
 use sui_types::crypto::{get_authority_key_pair, EncodeDecodeBase64, SignatureScheme, SuiKeyPair};
+
+#[cfg(feature = "zk-login-test-utils")]
+use crate::zk_login::{generate_ephemeral_key, issue_test_jwt, EphemeralKey, TestJwt};
+#[cfg(feature = "zk-login-test-utils")]
+use sui_types::committee::EpochId;
 #[cfg(test)]
 #[path = "unit_tests/keytool_tests.rs"]
 mod keytool_tests;
@@ -80,6 +89,20 @@ pub enum KeyToolCommand {
     /// the network keypair, worker keypair, protocol keypair as Base64 encoded `privkey`.
     LoadKeypair { file: PathBuf },
 
+    /// Encrypt the keystore with a passphrase, prompted for or read from the
+    /// `SUI_KEYSTORE_PASSPHRASE` environment variable. The key pairs are encrypted with AES-GCM,
+    /// under a key derived from the passphrase with Argon2. The passphrase is then asked for when
+    /// signing. Changes the passphrase of an encrypted keystore.
+    Encrypt,
+
+    /// Decrypt the keystore, storing its key pairs in plaintext again.
+    Decrypt,
+
+    /// Move the key pairs of another keystore file, in either format, into the keystore, which
+    /// encrypts them if it is encrypted. The other file is left as is, and is best deleted once
+    /// the key pairs are moved.
+    Migrate { file: PathBuf },
+
     /// To MultiSig Sui Address. Pass in a list of all public keys `flag || pk` in Base64.
     /// See `keytool list` for example public keys.
     MultiSigAddress {
@@ -92,8 +115,9 @@ pub enum KeyToolCommand {
     },
 
     /// Provides a list of signatures (`flag || sig || pk` encoded in Base64), threshold, a list of public keys.
-    /// Returns a valid MultiSig and its sender address. The result can be used as signature field for `sui client execute-signed-tx`.
-    /// The number of sigs must be greater than the threshold. The number of sigs must be smaller than the number of pks.
+    /// Returns a valid MultiSig and its sender address. The result can be used as signature field for `sui client execute-signed-tx`,
+    /// or `sui client execute-combined` can combine and execute at once.
+    /// The total weight of the sigs must reach the threshold. The number of sigs must be smaller than the number of pks.
     MultiSigCombinePartialSig {
         #[clap(long, multiple_occurrences = false, multiple_values = true)]
         sigs: Vec<Signature>,
@@ -104,11 +128,167 @@ pub enum KeyToolCommand {
         #[clap(long)]
         threshold: ThresholdUnit,
     },
+
+    /// Generate an Ed25519 ephemeral key pair for a zkLogin flow, valid until the end of
+    /// `max-epoch`, with the randomness for the nonce of its JWT. Only built with the
+    /// `zk-login-test-utils` feature.
+    #[cfg(feature = "zk-login-test-utils")]
+    ZkLoginEphemeralKey {
+        #[clap(long)]
+        max_epoch: EpochId,
+    },
+
+    /// Issue a JWT of a test OpenID provider for the user `sub` of the client `aud`, committing
+    /// to `nonce`, and print it with the JWK of the provider. The private key of the provider is
+    /// public, so its JWTs must only be trusted on localnet. Only built with the
+    /// `zk-login-test-utils` feature.
+    #[cfg(feature = "zk-login-test-utils")]
+    ZkLoginTestJwt {
+        #[clap(long)]
+        sub: String,
+        #[clap(long)]
+        aud: String,
+        #[clap(long)]
+        nonce: String,
+    },
+
+    /// Manage the address book of the keystore, whose aliases can be given instead of addresses
+    /// to `sui client` commands. The aliases are stored alongside the keystore, in a file with
+    /// the `.aliases` extension.
+    Alias {
+        #[clap(subcommand)]
+        cmd: AliasCommand,
+    },
+}
+
+#[derive(Subcommand)]
+#[clap(rename_all = "kebab-case")]
+pub enum AliasCommand {
+    /// Give an address an alias, made of letters, digits, `-` and `_` and starting with a letter.
+    /// An existing alias is reassigned to the address.
+    Set { alias: String, address: SuiAddress },
+    /// Remove an alias from the address book.
+    Remove { alias: String },
+    /// List the aliases of the address book, with the addresses they are for.
+    List,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Key {
+    sui_address: SuiAddress,
+    public_base64_key: String,
+    key_scheme: String,
+    flag: u8,
+    /// The file the key pair was written to.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    file: Option<PathBuf>,
+}
+
+impl Key {
+    fn new(public_key: &PublicKey, file: Option<PathBuf>) -> Self {
+        Self {
+            sui_address: public_key.into(),
+            public_base64_key: public_key.encode_base64(),
+            key_scheme: public_key.scheme().to_string(),
+            flag: public_key.flag(),
+            file,
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoadedKeypair {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    account_keypair: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    network_keypair: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    worker_keypair: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    protocol_keypair: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignData {
+    sui_address: SuiAddress,
+    /// Base64 encoded BCS `TransactionData`.
+    raw_tx_data: String,
+    intent: Intent,
+    /// Base64 encoded BCS `IntentMessage` of the transaction, as signed.
+    raw_intent_msg: String,
+    /// `flag || sig || pk` encoded in Base64.
+    sui_signature: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AddressAlias {
+    alias: String,
+    sui_address: SuiAddress,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MultiSigParty {
+    sui_address: SuiAddress,
+    public_base64_key: String,
+    weight: WeightUnit,
+}
+
+/// The result of a keytool command, printed as text, or as JSON with `--json`.
+#[derive(Serialize)]
+#[serde(untagged)]
+pub enum CommandOutput {
+    Generate(Key),
+    Show(Key),
+    Unpack(Key),
+    List(Vec<Key>),
+    Sign(SignData),
+    Import(Key),
+    LoadKeypair(LoadedKeypair),
+    #[serde(rename_all = "camelCase")]
+    Encryption {
+        encrypted: bool,
+    },
+    #[serde(rename_all = "camelCase")]
+    Migrate {
+        file: PathBuf,
+        moved_keys: usize,
+    },
+    #[serde(rename_all = "camelCase")]
+    MultiSigAddress {
+        multisig_address: SuiAddress,
+        threshold: ThresholdUnit,
+        parties: Vec<MultiSigParty>,
+    },
+    #[serde(rename_all = "camelCase")]
+    MultiSigCombinePartialSig {
+        multisig_address: SuiAddress,
+        /// `flag || multisig` encoded in Base64.
+        multisig_serialized: String,
+    },
+    #[cfg(feature = "zk-login-test-utils")]
+    ZkLoginEphemeralKey(EphemeralKey),
+    #[cfg(feature = "zk-login-test-utils")]
+    ZkLoginTestJwt(TestJwt),
+    #[serde(rename_all = "camelCase")]
+    AliasSet {
+        alias: String,
+        sui_address: SuiAddress,
+        /// The address the alias was for before, if any.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        replaced: Option<SuiAddress>,
+    },
+    AliasRemove(AddressAlias),
+    AliasList(Vec<AddressAlias>),
 }
 
 impl KeyToolCommand {
-    pub fn execute(self, keystore: &mut Keystore) -> Result<(), anyhow::Error> {
-        match self {
+    pub fn execute(self, keystore: &mut Keystore) -> Result<CommandOutput, anyhow::Error> {
+        Ok(match self {
             KeyToolCommand::Generate {
                 key_scheme,
                 derivation_path,
@@ -118,82 +298,70 @@ impl KeyToolCommand {
                     // The saved keypair is encoded `privkey || pubkey` without the scheme flag.
                     let (address, keypair) = get_authority_key_pair();
                     let file_name = format!("bls-{address}.key");
-                    write_authority_keypair_to_file(&keypair, file_name)?;
+                    write_authority_keypair_to_file(&keypair, &file_name)?;
+                    CommandOutput::Generate(Key {
+                        sui_address: address,
+                        public_base64_key: keypair.public().encode_base64(),
+                        key_scheme: key_scheme.to_string(),
+                        flag: key_scheme.flag(),
+                        file: Some(file_name.into()),
+                    })
                 } else {
-                    let (address, kp, scheme, _) = generate_new_key(key_scheme, derivation_path)?;
-                    let file = format!("{address}.key");
+                    let (address, kp, _, _) = generate_new_key(key_scheme, derivation_path)?;
+                    let file = PathBuf::from(format!("{address}.key"));
                     write_keypair_to_file(&kp, &file)?;
-                    println!(
-                        "Keypair wrote to file path: {:?} with scheme: {:?}",
-                        file, scheme
-                    );
+                    CommandOutput::Generate(Key::new(&kp.public(), Some(file)))
                 }
             }
-            KeyToolCommand::Show { file } => {
-                let res = read_keypair_from_file(&file);
-                match res {
-                    Ok(keypair) => {
-                        println!("Public Key: {}", keypair.public().encode_base64());
-                        println!("Flag: {}", keypair.public().flag());
-                    }
-                    Err(_) => {
-                        let res = read_authority_keypair_from_file(&file);
-                        match res {
-                            Ok(keypair) => {
-                                println!("Public Key: {}", keypair.public().encode_base64());
-                                println!("Flag: {}", SignatureScheme::BLS12381);
-                            }
-                            Err(e) => {
-                                println!("Failed to read keypair at path {:?} err: {:?}", file, e)
-                            }
-                        }
-                    }
+            KeyToolCommand::Show { file } => match read_keypair_from_file(&file) {
+                Ok(keypair) => CommandOutput::Show(Key::new(&keypair.public(), None)),
+                Err(_) => {
+                    let keypair = read_authority_keypair_from_file(&file).map_err(|e| {
+                        anyhow!("Failed to read keypair at path {:?} err: {:?}", file, e)
+                    })?;
+                    CommandOutput::Show(Key {
+                        sui_address: SuiAddress::from(keypair.public()),
+                        public_base64_key: keypair.public().encode_base64(),
+                        key_scheme: SignatureScheme::BLS12381.to_string(),
+                        flag: SignatureScheme::BLS12381.flag(),
+                        file: None,
+                    })
                 }
-            }
+            },
 
             KeyToolCommand::Unpack { keypair } => {
-                store_and_print_keypair((&keypair.public()).into(), keypair)
-            }
-            KeyToolCommand::List => {
-                println!(
-                    " {0: ^42} | {1: ^45} | {2: ^6}",
-                    "Sui Address", "Public Key (Base64)", "Scheme"
-                );
-                println!("{}", ["-"; 100].join(""));
-                for pub_key in keystore.keys() {
-                    println!(
-                        " {0: ^42} | {1: ^45} | {2: ^6}",
-                        Into::<SuiAddress>::into(&pub_key),
-                        pub_key.encode_base64(),
-                        pub_key.scheme().to_string()
-                    );
-                }
+                let file = store_keypair(&keypair)?;
+                CommandOutput::Unpack(Key::new(&keypair.public(), Some(file)))
             }
+            KeyToolCommand::List => CommandOutput::List(
+                keystore
+                    .keys()
+                    .iter()
+                    .map(|pub_key| Key::new(pub_key, None))
+                    .collect(),
+            ),
             KeyToolCommand::Sign {
                 address,
                 data,
                 intent,
             } => {
-                println!("Signer address: {}", address);
-                println!("Raw tx_bytes to execute: {}", data);
                 let intent = intent.unwrap_or_default();
-                println!("Intent: {:?}", intent);
                 let msg: TransactionData =
                     bcs::from_bytes(&Base64::decode(&data).map_err(|e| {
                         anyhow!("Cannot deserialize data as TransactionData {:?}", e)
                     })?)?;
-                let intent_msg = IntentMessage::new(intent, msg);
-                println!(
-                    "Intent message to sign: {:?}",
-                    Base64::encode(bcs::to_bytes(&intent_msg)?)
-                );
+                let intent_msg = IntentMessage::new(intent.clone(), msg);
+                let raw_intent_msg = Base64::encode(bcs::to_bytes(&intent_msg)?);
 
                 let sui_signature =
                     keystore.sign_secure(&address, &intent_msg.value, intent_msg.intent)?;
-                println!(
-                    "Serialized signature (`flag || sig || pk` in Base64): {:?}",
-                    sui_signature.encode_base64()
-                );
+                CommandOutput::Sign(SignData {
+                    sui_address: address,
+                    raw_tx_data: data,
+                    intent,
+                    raw_intent_msg,
+                    sui_signature: sui_signature.encode_base64(),
+                })
             }
             KeyToolCommand::Import {
                 mnemonic_phrase,
@@ -202,7 +370,12 @@ impl KeyToolCommand {
             } => {
                 let address =
                     keystore.import_from_mnemonic(&mnemonic_phrase, key_scheme, derivation_path)?;
-                info!("Key imported for address [{address}]");
+                let public_key = keystore
+                    .keys()
+                    .into_iter()
+                    .find(|pk| SuiAddress::from(pk) == address)
+                    .ok_or_else(|| anyhow!("Cannot find key for address: [{address}]"))?;
+                CommandOutput::Import(Key::new(&public_key, None))
             }
 
             KeyToolCommand::LoadKeypair { file } => {
@@ -210,45 +383,71 @@ impl KeyToolCommand {
                     Ok(keypair) => {
                         // Account keypair is encoded with the key scheme flag {},
                         // and network and worker keypair are not.
-                        println!("Account Keypair: {}", keypair.encode_base64());
-                        if let SuiKeyPair::Ed25519(kp) = keypair {
-                            println!("Network Keypair: {}", kp.encode_base64());
-                            println!("Worker Keypair: {}", kp.encode_base64());
+                        let account_keypair = Some(keypair.encode_base64());
+                        let network_keypair = match keypair {
+                            SuiKeyPair::Ed25519(kp) => Some(kp.encode_base64()),
+                            _ => None,
                         };
+                        CommandOutput::LoadKeypair(LoadedKeypair {
+                            account_keypair,
+                            worker_keypair: network_keypair.clone(),
+                            network_keypair,
+                            protocol_keypair: None,
+                        })
                     }
                     Err(_) => {
                         // Authority keypair file is not stored with the flag, it will try read as BLS keypair..
-                        match read_authority_keypair_from_file(&file) {
-                            Ok(kp) => println!("Protocol Keypair: {}", kp.encode_base64()),
-                            Err(e) => {
-                                println!("Failed to read keypair at path {:?} err: {:?}", file, e)
-                            }
-                        }
+                        let kp = read_authority_keypair_from_file(&file).map_err(|e| {
+                            anyhow!("Failed to read keypair at path {:?} err: {:?}", file, e)
+                        })?;
+                        CommandOutput::LoadKeypair(LoadedKeypair {
+                            account_keypair: None,
+                            network_keypair: None,
+                            worker_keypair: None,
+                            protocol_keypair: Some(kp.encode_base64()),
+                        })
                     }
                 }
             }
+            KeyToolCommand::Encrypt => {
+                let keystore = file_keystore(keystore)?;
+                if keystore.is_encrypted() {
+                    // Unlock with the current passphrase before asking for the new one.
+                    keystore.key_pairs()?;
+                }
+                keystore.encrypt(&read_new_passphrase()?)?;
+                CommandOutput::Encryption { encrypted: true }
+            }
+            KeyToolCommand::Decrypt => {
+                file_keystore(keystore)?.decrypt()?;
+                CommandOutput::Encryption { encrypted: false }
+            }
+            KeyToolCommand::Migrate { file } => {
+                let key_pairs = FileBasedKeystore::new(&file)?.into_key_pairs()?;
+                let moved_keys = key_pairs.len();
+                for key_pair in key_pairs {
+                    keystore.add_key(key_pair)?;
+                }
+                CommandOutput::Migrate { file, moved_keys }
+            }
             KeyToolCommand::MultiSigAddress {
                 threshold,
                 pks,
                 weights,
             } => {
                 let multisig_pk = MultiSigPublicKey::new(pks.clone(), weights.clone(), threshold)?;
-                let address: SuiAddress = multisig_pk.into();
-                println!("MultiSig address: {address}");
-
-                println!("Participating parties:");
-                println!(
-                    " {0: ^42} | {1: ^50} | {2: ^6}",
-                    "Sui Address", "Public Key (Base64)", "Weight"
-                );
-                println!("{}", ["-"; 100].join(""));
-                for (pk, w) in pks.into_iter().zip(weights.into_iter()) {
-                    println!(
-                        " {0: ^42} | {1: ^45} | {2: ^6}",
-                        Into::<SuiAddress>::into(&pk),
-                        pk.encode_base64(),
-                        w
-                    );
+                CommandOutput::MultiSigAddress {
+                    multisig_address: multisig_pk.into(),
+                    threshold,
+                    parties: pks
+                        .into_iter()
+                        .zip(weights.into_iter())
+                        .map(|(pk, weight)| MultiSigParty {
+                            sui_address: (&pk).into(),
+                            public_base64_key: pk.encode_base64(),
+                            weight,
+                        })
+                        .collect(),
                 }
             }
             KeyToolCommand::MultiSigCombinePartialSig {
@@ -257,30 +456,287 @@ impl KeyToolCommand {
                 weights,
                 threshold,
             } => {
-                let multisig_pk = MultiSigPublicKey::new(pks, weights, threshold)?;
-                let address: SuiAddress = multisig_pk.clone().into();
-                let multisig = MultiSig::combine(sigs, multisig_pk)?;
+                let (address, multisig) = combine_partial_sigs(sigs, pks, weights, threshold)?;
                 let generic_sig: GenericSignature = multisig.into();
-                println!("MultiSig address: {address}");
-                println!("MultiSig parsed: {:?}", generic_sig);
-                println!("MultiSig serialized: {:?}", generic_sig.encode_base64());
+                CommandOutput::MultiSigCombinePartialSig {
+                    multisig_address: address,
+                    multisig_serialized: generic_sig.encode_base64(),
+                }
+            }
+            #[cfg(feature = "zk-login-test-utils")]
+            KeyToolCommand::ZkLoginEphemeralKey { max_epoch } => {
+                CommandOutput::ZkLoginEphemeralKey(generate_ephemeral_key(max_epoch)?)
+            }
+            #[cfg(feature = "zk-login-test-utils")]
+            KeyToolCommand::ZkLoginTestJwt { sub, aud, nonce } => {
+                CommandOutput::ZkLoginTestJwt(issue_test_jwt(&sub, &aud, &nonce)?)
             }
+            KeyToolCommand::Alias { cmd } => {
+                let mut address_book = address_book(keystore)?;
+                match cmd {
+                    AliasCommand::Set { alias, address } => {
+                        let replaced = address_book.set(&alias, address)?;
+                        address_book.save()?;
+                        CommandOutput::AliasSet {
+                            alias,
+                            sui_address: address,
+                            replaced,
+                        }
+                    }
+                    AliasCommand::Remove { alias } => {
+                        let sui_address = address_book.remove(&alias)?;
+                        address_book.save()?;
+                        CommandOutput::AliasRemove(AddressAlias { alias, sui_address })
+                    }
+                    AliasCommand::List => CommandOutput::AliasList(
+                        address_book
+                            .aliases()
+                            .map(|(alias, address)| AddressAlias {
+                                alias: alias.to_string(),
+                                sui_address: *address,
+                            })
+                            .collect(),
+                    ),
+                }
+            }
+        })
+    }
+}
+
+impl Display for CommandOutput {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut writer = String::new();
+        match self {
+            CommandOutput::Generate(key) | CommandOutput::Unpack(key) => {
+                writeln!(
+                    writer,
+                    "Keypair of {} with scheme {} written to {:?}",
+                    key.sui_address,
+                    key.key_scheme,
+                    key.file.as_deref().unwrap_or_else(|| Path::new(""))
+                )?;
+            }
+            CommandOutput::Show(key) => {
+                writeln!(writer, "Public Key: {}", key.public_base64_key)?;
+                writeln!(writer, "Flag: {}", key.flag)?;
+            }
+            CommandOutput::List(keys) => {
+                writeln!(
+                    writer,
+                    " {0: ^42} | {1: ^45} | {2: ^6}",
+                    "Sui Address", "Public Key (Base64)", "Scheme"
+                )?;
+                writeln!(writer, "{}", ["-"; 100].join(""))?;
+                for key in keys {
+                    writeln!(
+                        writer,
+                        " {0: ^42} | {1: ^45} | {2: ^6}",
+                        key.sui_address, key.public_base64_key, key.key_scheme
+                    )?;
+                }
+            }
+            CommandOutput::Sign(data) => {
+                writeln!(writer, "Signer address: {}", data.sui_address)?;
+                writeln!(writer, "Raw tx_bytes to execute: {}", data.raw_tx_data)?;
+                writeln!(writer, "Intent: {:?}", data.intent)?;
+                writeln!(writer, "Intent message to sign: {}", data.raw_intent_msg)?;
+                writeln!(
+                    writer,
+                    "Serialized signature (`flag || sig || pk` in Base64): {}",
+                    data.sui_signature
+                )?;
+            }
+            CommandOutput::Import(key) => {
+                writeln!(writer, "Key imported for address [{}]", key.sui_address)?;
+            }
+            CommandOutput::LoadKeypair(keypair) => {
+                if let Some(kp) = &keypair.account_keypair {
+                    writeln!(writer, "Account Keypair: {kp}")?;
+                }
+                if let Some(kp) = &keypair.network_keypair {
+                    writeln!(writer, "Network Keypair: {kp}")?;
+                }
+                if let Some(kp) = &keypair.worker_keypair {
+                    writeln!(writer, "Worker Keypair: {kp}")?;
+                }
+                if let Some(kp) = &keypair.protocol_keypair {
+                    writeln!(writer, "Protocol Keypair: {kp}")?;
+                }
+            }
+            CommandOutput::Encryption { encrypted } => {
+                if *encrypted {
+                    writeln!(writer, "Keystore encrypted")?;
+                } else {
+                    writeln!(writer, "Keystore decrypted")?;
+                }
+            }
+            CommandOutput::Migrate { file, moved_keys } => {
+                writeln!(
+                    writer,
+                    "Moved {moved_keys} key pairs from {:?} into the keystore",
+                    file
+                )?;
+            }
+            CommandOutput::MultiSigAddress {
+                multisig_address,
+                threshold,
+                parties,
+            } => {
+                writeln!(writer, "MultiSig address: {multisig_address}")?;
+                writeln!(writer, "Threshold: {threshold}")?;
+                writeln!(writer, "Participating parties:")?;
+                writeln!(
+                    writer,
+                    " {0: ^42} | {1: ^50} | {2: ^6}",
+                    "Sui Address", "Public Key (Base64)", "Weight"
+                )?;
+                writeln!(writer, "{}", ["-"; 100].join(""))?;
+                for party in parties {
+                    writeln!(
+                        writer,
+                        " {0: ^42} | {1: ^45} | {2: ^6}",
+                        party.sui_address, party.public_base64_key, party.weight
+                    )?;
+                }
+            }
+            CommandOutput::MultiSigCombinePartialSig {
+                multisig_address,
+                multisig_serialized,
+            } => {
+                writeln!(writer, "MultiSig address: {multisig_address}")?;
+                writeln!(writer, "MultiSig serialized: {multisig_serialized}")?;
+            }
+            #[cfg(feature = "zk-login-test-utils")]
+            CommandOutput::ZkLoginEphemeralKey(key) => {
+                writeln!(writer, "Ephemeral keypair: {}", key.keypair)?;
+                writeln!(writer, "Ephemeral public key: {}", key.public_base64_key)?;
+                writeln!(writer, "Max epoch: {}", key.max_epoch)?;
+                writeln!(writer, "Randomness: {}", key.randomness)?;
+            }
+            #[cfg(feature = "zk-login-test-utils")]
+            CommandOutput::ZkLoginTestJwt(jwt) => {
+                writeln!(writer, "JWT: {}", jwt.jwt)?;
+                writeln!(writer, "JWK: {}", jwt.jwk)?;
+            }
+            CommandOutput::AliasSet {
+                alias,
+                sui_address,
+                replaced,
+            } => {
+                writeln!(writer, "Alias {alias} set for address {sui_address}")?;
+                if let Some(replaced) = replaced {
+                    writeln!(writer, "It was an alias for address {replaced}")?;
+                }
+            }
+            CommandOutput::AliasRemove(alias) => {
+                writeln!(
+                    writer,
+                    "Alias {} of address {} removed",
+                    alias.alias, alias.sui_address
+                )?;
+            }
+            CommandOutput::AliasList(aliases) => {
+                writeln!(writer, " {0: ^24} | {1: ^66}", "Alias", "Sui Address")?;
+                writeln!(writer, "{}", ["-"; 94].join(""))?;
+                for alias in aliases {
+                    writeln!(
+                        writer,
+                        " {0: ^24} | {1: ^66}",
+                        alias.alias, alias.sui_address
+                    )?;
+                }
+            }
+        }
+        write!(f, "{}", writer.trim_end_matches('\n'))
+    }
+}
+
+impl CommandOutput {
+    pub fn print(&self, pretty: bool) {
+        if pretty {
+            println!("{self}");
+        } else {
+            match serde_json::to_string_pretty(self) {
+                Ok(json) => println!("{json}"),
+                Err(e) => eprintln!("Cannot serialize output: {e}"),
+            }
+        }
+    }
+}
+
+/// Combines the signatures of some of the parties of a MultiSig into the MultiSig, checking that
+/// their weight reaches the threshold. Returns it with the MultiSig address.
+pub(crate) fn combine_partial_sigs(
+    sigs: Vec<Signature>,
+    pks: Vec<PublicKey>,
+    weights: Vec<WeightUnit>,
+    threshold: ThresholdUnit,
+) -> Result<(SuiAddress, MultiSig), anyhow::Error> {
+    let multisig_pk = MultiSigPublicKey::new(pks, weights, threshold)?;
+    let mut signers = BTreeSet::new();
+    for sig in &sigs {
+        let pk = sig.to_public_key()?;
+        let index = multisig_pk.get_index(pk.clone()).ok_or_else(|| {
+            anyhow!(
+                "Signer {} is not a party of the MultiSig",
+                pk.encode_base64()
+            )
+        })?;
+        if !signers.insert(index) {
+            return Err(anyhow!(
+                "Signer {} signed more than once",
+                pk.encode_base64()
+            ));
+        }
+    }
+    let weight: ThresholdUnit = signers
+        .iter()
+        .map(|i| multisig_pk.pubkeys()[*i as usize].1 as ThresholdUnit)
+        .sum();
+    if weight < threshold {
+        return Err(anyhow!(
+            "The signatures weigh {weight}, less than the threshold {threshold}"
+        ));
+    }
+    let address: SuiAddress = multisig_pk.clone().into();
+    Ok((address, MultiSig::combine(sigs, multisig_pk)?))
+}
+
+/// The address book stored alongside the file keystore `keystore`.
+fn address_book(keystore: &Keystore) -> Result<AddressBook, anyhow::Error> {
+    match keystore {
+        Keystore::File(keystore) => {
+            let path = keystore
+                .path()
+                .ok_or_else(|| anyhow!("The keystore is not stored in a file"))?;
+            AddressBook::load(&address_book_path(path))
         }
+        Keystore::InMem(_) | Keystore::Ledger(_) => {
+            Err(anyhow!("Only file keystores have an address book"))
+        }
+    }
+}
 
-        Ok(())
+fn file_keystore(keystore: &mut Keystore) -> Result<&mut FileBasedKeystore, anyhow::Error> {
+    match keystore {
+        Keystore::File(keystore) => Ok(keystore),
+        Keystore::InMem(_) | Keystore::Ledger(_) => {
+            Err(anyhow!("Only file keystores can be encrypted"))
+        }
     }
 }
 
-fn store_and_print_keypair(address: SuiAddress, keypair: SuiKeyPair) {
-    let path_str = format!("{}.key", address).to_lowercase();
-    let path = Path::new(&path_str);
-    let address = format!("{}", address);
-    let kp = keypair.encode_base64();
-    let flag = keypair.public().flag();
-    let out_str = format!("address: {}\nkeypair: {}\nflag: {}", address, kp, flag);
-    fs::write(path, out_str).unwrap();
-    println!(
-        "Address, keypair and key scheme written to {}",
-        path.to_str().unwrap()
+/// Writes `keypair` to `<address>.key` in the current directory, with its address and scheme
+/// flag, and returns the path of the file.
+fn store_keypair(keypair: &SuiKeyPair) -> Result<PathBuf, anyhow::Error> {
+    let address = SuiAddress::from(&keypair.public());
+    let path = PathBuf::from(format!("{}.key", address).to_lowercase());
+    let out_str = format!(
+        "address: {}\nkeypair: {}\nflag: {}",
+        address,
+        keypair.encode_base64(),
+        keypair.public().flag()
     );
+    fs::write(&path, out_str)?;
+    Ok(path)
 }
@@ -3,10 +3,16 @@
 // SPDX-License-Identifier: Apache-2.0
 
 pub mod client_commands;
+pub mod client_ptb;
 pub mod config;
 pub mod console;
+pub mod faucet;
 pub mod keytool;
+pub mod replay;
 pub mod shell;
 pub mod sui_commands;
+pub mod validator_commands;
+#[cfg(feature = "zk-login-test-utils")]
+pub mod zk_login;
 
 pub mod genesis_ceremony;
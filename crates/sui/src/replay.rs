@@ -0,0 +1,465 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Re-execution of a transaction of the network against the objects it read, fetched from a
+//! fullnode, to compare the effects it has locally with those it had on chain.
+
+use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::{Display, Formatter, Write};
+use std::sync::Arc;
+
+use anyhow::{anyhow, bail};
+use prettytable::table;
+use serde::Serialize;
+use serde_json::Value;
+use sui_adapter::{adapter, execution_engine, execution_mode};
+use sui_json_rpc_types::{
+    SuiObjectData, SuiObjectDataOptions, SuiObjectHistoryEntry, SuiPastObjectResponse,
+    SuiTransactionEffects, SuiTransactionEffectsAPI, SuiTransactionResponseOptions,
+};
+use sui_protocol_config::{ProtocolConfig, ProtocolVersion};
+use sui_sdk::SuiClient;
+use sui_types::base_types::{ObjectID, ObjectRef, SequenceNumber, TransactionDigest};
+use sui_types::epoch_data::EpochData;
+use sui_types::error::{SuiError, SuiResult};
+use sui_types::gas::{start_gas_metering, SuiCostTable, SuiGasStatus};
+use sui_types::messages::{
+    InputObjectKind, InputObjects, SenderSignedData, TransactionData, TransactionDataAPI,
+    TransactionEffects,
+};
+use sui_types::object::{Object, Owner};
+use sui_types::storage::{BackingPackageStore, ChildObjectResolver, ParentSync};
+use sui_types::temporary_store::TemporaryStore;
+use sui_types::{
+    MOVE_STDLIB_ADDRESS, MOVE_STDLIB_OBJECT_ID, SUI_FRAMEWORK_ADDRESS, SUI_FRAMEWORK_OBJECT_ID,
+};
+
+/// What replaying a transaction found.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplayResult {
+    pub digest: TransactionDigest,
+    pub protocol_version: u64,
+    pub on_chain_effects: SuiTransactionEffects,
+    pub local_effects: SuiTransactionEffects,
+    /// The fields of the effects that differ, by their path in the JSON of the effects.
+    pub differences: Vec<EffectsDifference>,
+    /// The ways the replay could differ from the execution on chain for reasons of its own.
+    pub warnings: Vec<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EffectsDifference {
+    pub field: String,
+    pub on_chain: Value,
+    pub local: Value,
+}
+
+/// Fetches the transaction `digest` and the objects it read from the fullnode of `client`, and
+/// executes it again with the protocol config of `protocol_version`, or of the current epoch of
+/// the network when not given.
+pub async fn replay_transaction(
+    client: &SuiClient,
+    digest: TransactionDigest,
+    protocol_version: Option<u64>,
+) -> Result<ReplayResult, anyhow::Error> {
+    let mut warnings = vec![];
+
+    let result = client
+        .read_api()
+        .multi_get_transactions_with_options(
+            vec![digest],
+            SuiTransactionResponseOptions::new()
+                .with_effects()
+                .with_raw_input(),
+        )
+        .await?
+        .pop()
+        .ok_or_else(|| anyhow!("The fullnode did not return transaction {digest}"))?;
+    if let Some(error) = result.error {
+        bail!("Cannot read transaction {digest}: {error}");
+    }
+    let raw_transaction = result
+        .raw_transaction
+        .ok_or_else(|| anyhow!("The fullnode did not return the input of transaction {digest}"))?;
+    let on_chain_effects = result.effects.ok_or_else(|| {
+        anyhow!("The fullnode did not return the effects of transaction {digest}")
+    })?;
+    let data = bcs::from_bytes::<SenderSignedData>(&raw_transaction)?
+        .intent_message
+        .value;
+
+    let system_state = client
+        .governance_api()
+        .get_latest_sui_system_state()
+        .await?;
+    let epoch = on_chain_effects.executed_epoch();
+    let (protocol_version, epoch_start_timestamp_ms) = if epoch == system_state.epoch {
+        (
+            protocol_version.unwrap_or(system_state.protocol_version),
+            system_state.epoch_start_timestamp_ms,
+        )
+    } else {
+        if protocol_version.is_none() {
+            warnings.push(format!(
+                "The transaction was executed in epoch {epoch}, it is replayed with the protocol \
+                 version {} of the current epoch {}",
+                system_state.protocol_version, system_state.epoch
+            ));
+        }
+        warnings.push(format!(
+            "The start of epoch {epoch} is not known, it is replayed with a start timestamp of 0"
+        ));
+        (protocol_version.unwrap_or(system_state.protocol_version), 0)
+    };
+    if !(ProtocolVersion::MIN.as_u64()..=ProtocolVersion::MAX.as_u64()).contains(&protocol_version)
+    {
+        bail!(
+            "Protocol version {protocol_version} is not supported by this binary, which supports \
+             versions {} to {}",
+            ProtocolVersion::MIN.as_u64(),
+            ProtocolVersion::MAX.as_u64()
+        );
+    }
+    let protocol_config = ProtocolConfig::get_for_version(ProtocolVersion::new(protocol_version));
+    let epoch_data = EpochData::new(epoch, epoch_start_timestamp_ms, Default::default());
+
+    let fetcher = ObjectFetcher { client };
+    let mut input_objects = vec![];
+    for kind in data.input_objects()? {
+        let object = match kind {
+            InputObjectKind::MovePackage(id) => fetcher.package(id).await?,
+            InputObjectKind::ImmOrOwnedMoveObject((id, version, _)) => {
+                fetcher.object_at(id, version).await?
+            }
+            InputObjectKind::SharedMoveObject { id, .. } => {
+                let shared = on_chain_effects
+                    .shared_objects()
+                    .iter()
+                    .find(|o| o.object_id == id)
+                    .ok_or_else(|| anyhow!("The effects do not list shared object {id}"))?;
+                fetcher.object_at(id, shared.version).await?
+            }
+        };
+        input_objects.push((kind, object));
+    }
+    // The objects the transaction reads besides its inputs are at the latest version before the
+    // one it gives the objects it writes.
+    let lamport_version =
+        SequenceNumber::lamport_increment(input_objects.iter().map(|(_, o)| o.version()));
+
+    let mut store = ReplayStore::default();
+    for id in [MOVE_STDLIB_OBJECT_ID, SUI_FRAMEWORK_OBJECT_ID] {
+        store.insert(fetcher.package(id).await?);
+    }
+
+    // The packages and child objects the transaction loads are only known once it runs, so it is
+    // executed again with those the previous execution missed until it misses none.
+    let local_effects = loop {
+        let effects = execute(
+            &store,
+            input_objects.clone(),
+            &data,
+            digest,
+            &epoch_data,
+            &protocol_config,
+        )?;
+
+        let missing = store.missing.take();
+        if missing.is_empty() {
+            break effects;
+        }
+        for (id, is_package) in missing {
+            let object = if is_package {
+                Some(fetcher.package(id).await?)
+            } else {
+                fetcher.latest_before(id, lamport_version).await?
+            };
+            match object {
+                Some(object) => store.insert(object),
+                None => {
+                    store.absent.insert(id);
+                }
+            }
+        }
+    };
+    let local_effects = SuiTransactionEffects::try_from(local_effects)?;
+
+    let mut differences = vec![];
+    diff_values(
+        "",
+        &serde_json::to_value(&on_chain_effects)?,
+        &serde_json::to_value(&local_effects)?,
+        &mut differences,
+    );
+
+    Ok(ReplayResult {
+        digest,
+        protocol_version,
+        on_chain_effects,
+        local_effects,
+        differences,
+        warnings,
+    })
+}
+
+fn execute(
+    store: &ReplayStore,
+    input_objects: Vec<(InputObjectKind, Object)>,
+    data: &TransactionData,
+    digest: TransactionDigest,
+    epoch_data: &EpochData,
+    protocol_config: &ProtocolConfig,
+) -> Result<TransactionEffects, anyhow::Error> {
+    let input_objects = InputObjects::new(input_objects);
+    let shared_object_refs = input_objects.filter_shared_objects();
+    let transaction_dependencies = input_objects.transaction_dependencies();
+    let temporary_store = TemporaryStore::new(store, input_objects, digest, protocol_config);
+    let gas_status = if data.is_system_tx() {
+        SuiGasStatus::new_unmetered()
+    } else {
+        start_gas_metering(
+            data.gas_budget(),
+            data.gas_price(),
+            protocol_config.storage_gas_price(),
+            SuiCostTable::new(protocol_config),
+        )?
+    };
+    let move_vm = Arc::new(adapter::new_move_vm(
+        sui_framework::natives::all_natives(MOVE_STDLIB_ADDRESS, SUI_FRAMEWORK_ADDRESS),
+        protocol_config,
+    )?);
+    let (kind, signer, gas) = data.execution_parts();
+    let (_, effects, _) =
+        execution_engine::execute_transaction_to_effects::<execution_mode::Normal, _>(
+            shared_object_refs,
+            temporary_store,
+            kind,
+            signer,
+            &gas,
+            digest,
+            transaction_dependencies,
+            &move_vm,
+            gas_status,
+            epoch_data,
+            protocol_config,
+        );
+    Ok(effects)
+}
+
+/// Lists the fields at which `on_chain` and `local` differ, going into objects but comparing
+/// arrays as a whole, as the same elements in another order are a difference too.
+fn diff_values(
+    path: &str,
+    on_chain: &Value,
+    local: &Value,
+    differences: &mut Vec<EffectsDifference>,
+) {
+    match (on_chain, local) {
+        (Value::Object(on_chain), Value::Object(local)) => {
+            let keys: BTreeSet<_> = on_chain.keys().chain(local.keys()).collect();
+            for key in keys {
+                let field = if path.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{path}.{key}")
+                };
+                diff_values(
+                    &field,
+                    on_chain.get(key).unwrap_or(&Value::Null),
+                    local.get(key).unwrap_or(&Value::Null),
+                    differences,
+                );
+            }
+        }
+        _ if on_chain != local => differences.push(EffectsDifference {
+            field: path.to_string(),
+            on_chain: on_chain.clone(),
+            local: local.clone(),
+        }),
+        _ => {}
+    }
+}
+
+struct ObjectFetcher<'a> {
+    client: &'a SuiClient,
+}
+
+impl ObjectFetcher<'_> {
+    async fn package(&self, id: ObjectID) -> Result<Object, anyhow::Error> {
+        let object = self
+            .client
+            .read_api()
+            .get_object_with_options(id, SuiObjectDataOptions::bcs_lossless())
+            .await?
+            .into_object()?;
+        to_object(object)
+    }
+
+    async fn object_at(
+        &self,
+        id: ObjectID,
+        version: SequenceNumber,
+    ) -> Result<Object, anyhow::Error> {
+        match self
+            .client
+            .read_api()
+            .try_get_parsed_past_object(id, version, SuiObjectDataOptions::bcs_lossless())
+            .await?
+        {
+            SuiPastObjectResponse::VersionFound(object) => to_object(object),
+            response => Err(anyhow!(
+                "Cannot read object {id} at version {version}: {response:?}"
+            )),
+        }
+    }
+
+    /// The latest version of object `id` before `version`, if it existed then.
+    async fn latest_before(
+        &self,
+        id: ObjectID,
+        version: SequenceNumber,
+    ) -> Result<Option<Object>, anyhow::Error> {
+        let end = SequenceNumber::from_u64(version.value() - 1);
+        let mut start = SequenceNumber::MIN;
+        let mut latest = None;
+        loop {
+            let mut entries = self
+                .client
+                .read_api()
+                .try_get_past_object_history(id, start, end, SuiObjectDataOptions::bcs_lossless())
+                .await?;
+            let Some(entry) = entries.pop() else {
+                break;
+            };
+            let version = match &entry {
+                SuiObjectHistoryEntry::VersionFound(object) => object.version,
+                SuiObjectHistoryEntry::ObjectDeleted(object_ref) => object_ref.version,
+                SuiObjectHistoryEntry::VersionPruned(_, version) => *version,
+            };
+            start = SequenceNumber::from_u64(version.value() + 1);
+            latest = Some(entry);
+        }
+        match latest {
+            Some(SuiObjectHistoryEntry::VersionFound(object)) => Ok(Some(to_object(object)?)),
+            Some(SuiObjectHistoryEntry::VersionPruned(_, version)) => Err(anyhow!(
+                "Object {id} at version {version} read by the transaction is pruned by the fullnode"
+            )),
+            Some(SuiObjectHistoryEntry::ObjectDeleted(_)) | None => Ok(None),
+        }
+    }
+}
+
+fn to_object(object: SuiObjectData) -> Result<Object, anyhow::Error> {
+    object.try_into()
+}
+
+/// The objects fetched for the replay, which records the packages and child objects the execution
+/// asks for that have not been fetched yet.
+#[derive(Default)]
+struct ReplayStore {
+    objects: BTreeMap<ObjectID, Object>,
+    /// Objects that did not exist when the transaction was executed.
+    absent: BTreeSet<ObjectID>,
+    /// The objects to fetch, and whether they are packages.
+    missing: RefCell<BTreeMap<ObjectID, bool>>,
+}
+
+impl ReplayStore {
+    fn insert(&mut self, object: Object) {
+        self.objects.insert(object.id(), object);
+    }
+
+    fn get(&self, id: &ObjectID, is_package: bool) -> Option<Object> {
+        let object = self.objects.get(id).cloned();
+        if object.is_none() && !self.absent.contains(id) {
+            self.missing.borrow_mut().insert(*id, is_package);
+        }
+        object
+    }
+}
+
+impl BackingPackageStore for ReplayStore {
+    fn get_package(&self, package_id: &ObjectID) -> SuiResult<Option<Object>> {
+        Ok(self.get(package_id, true))
+    }
+}
+
+impl ChildObjectResolver for ReplayStore {
+    fn read_child_object(&self, parent: &ObjectID, child: &ObjectID) -> SuiResult<Option<Object>> {
+        let Some(child_object) = self.get(child, false) else {
+            return Ok(None);
+        };
+        let parent = *parent;
+        if child_object.owner != Owner::ObjectOwner(parent.into()) {
+            return Err(SuiError::InvalidChildObjectAccess {
+                object: *child,
+                given_parent: parent,
+                actual_owner: child_object.owner,
+            });
+        }
+        Ok(Some(child_object))
+    }
+}
+
+impl ParentSync for ReplayStore {
+    fn get_latest_parent_entry_ref(&self, object_id: ObjectID) -> SuiResult<Option<ObjectRef>> {
+        Ok(self
+            .objects
+            .get(&object_id)
+            .map(|object| object.compute_object_reference()))
+    }
+}
+
+impl Display for ReplayResult {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut writer = String::new();
+        writeln!(
+            writer,
+            "Replayed transaction {} with protocol version {}",
+            self.digest, self.protocol_version
+        )?;
+        for warning in &self.warnings {
+            writeln!(writer, "Warning: {warning}")?;
+        }
+
+        let on_chain = self.on_chain_effects.gas_used();
+        let local = self.local_effects.gas_used();
+        let table = table!(
+            ["Gas", "On chain", "Local"],
+            [
+                "Computation cost",
+                on_chain.computation_cost,
+                local.computation_cost
+            ],
+            ["Storage cost", on_chain.storage_cost, local.storage_cost],
+            [
+                "Storage rebate",
+                on_chain.storage_rebate,
+                local.storage_rebate
+            ]
+        );
+        write!(writer, "{table}")?;
+
+        if self.differences.is_empty() {
+            writeln!(
+                writer,
+                "The effects match those of the transaction on chain."
+            )?;
+        } else {
+            writeln!(
+                writer,
+                "The effects differ from those of the transaction on chain in {} fields:",
+                self.differences.len()
+            )?;
+            for difference in &self.differences {
+                writeln!(writer, "{}", difference.field)?;
+                writeln!(writer, "  on chain: {}", difference.on_chain)?;
+                writeln!(writer, "  local:    {}", difference.local)?;
+            }
+        }
+        write!(f, "{}", writer.trim_end_matches('\n'))
+    }
+}
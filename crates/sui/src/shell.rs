@@ -9,6 +9,7 @@ use std::collections::BTreeMap;
 use std::env;
 use std::fmt::Display;
 use std::io::Write;
+use std::path::PathBuf;
 use std::sync::{Arc, RwLock};
 
 use async_trait::async_trait;
@@ -33,6 +34,8 @@ pub struct Shell<P: Display, S, H> {
     state: S,
     handler: H,
     command: CommandStructure,
+    completion_cache: CompletionCache,
+    history_file: Option<PathBuf>,
 }
 
 impl<P: Display, S: Send, H: AsyncHandler<S>> Shell<P, S, H> {
@@ -51,9 +54,25 @@ impl<P: Display, S: Send, H: AsyncHandler<S>> Shell<P, S, H> {
             state,
             handler,
             command,
+            completion_cache: Arc::new(RwLock::new(BTreeMap::new())),
+            history_file: None,
         }
     }
 
+    /// Loads the command history from `path` when the shell starts, and saves it there when the
+    /// shell exits, to keep it across sessions.
+    pub fn with_history_file(mut self, path: PathBuf) -> Self {
+        self.history_file = Some(path);
+        self
+    }
+
+    /// Uses `cache` for the completion values shared with the handler, e.g. to start the shell
+    /// with completions already in it.
+    pub fn with_completion_cache(mut self, cache: CompletionCache) -> Self {
+        self.completion_cache = cache;
+        self
+    }
+
     pub async fn run_async(
         &mut self,
         out: &mut (dyn Write + Send),
@@ -67,13 +86,22 @@ impl<P: Display, S: Send, H: AsyncHandler<S>> Shell<P, S, H> {
 
         let mut rl = Editor::with_config(config);
 
-        let completion_cache = Arc::new(RwLock::new(BTreeMap::new()));
+        let completion_cache = self.completion_cache.clone();
 
         rl.set_helper(Some(ShellHelper {
             command: self.command.clone(),
             completion_cache: completion_cache.clone(),
         }));
 
+        if let Some(path) = &self.history_file {
+            if path.exists() {
+                if let Err(e) = rl.load_history(path) {
+                    let message = format!("Cannot load history from {}: {e}", path.display());
+                    writeln!(err, "{}", message.yellow())?;
+                }
+            }
+        }
+
         loop {
             // Read a line
             let readline = rl.readline(&self.prompt.to_string());
@@ -135,6 +163,13 @@ impl<P: Display, S: Send, H: AsyncHandler<S>> Shell<P, S, H> {
                 Err(e) => writeln!(err, "{}", e.to_string().red())?,
             }
         }
+
+        if let Some(path) = &self.history_file {
+            if let Err(e) = rl.save_history(path) {
+                let message = format!("Cannot save history to {}: {e}", path.display());
+                writeln!(err, "{}", message.yellow())?;
+            }
+        }
         Ok(())
     }
 }
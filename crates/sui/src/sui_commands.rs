@@ -21,6 +21,7 @@ use sui_config::{
 };
 use sui_keys::keystore::{AccountKeystore, FileBasedKeystore, Keystore};
 use sui_swarm::memory::Swarm;
+use sui_types::base_types::ObjectID;
 use sui_types::crypto::{SignatureScheme, SuiKeyPair};
 
 use crate::client_commands::{SuiClientCommands, WalletContext};
@@ -28,6 +29,7 @@ use crate::config::{SuiClientConfig, SuiEnv};
 use crate::console::start_console;
 use crate::genesis_ceremony::{run, Ceremony};
 use crate::keytool::KeyToolCommand;
+use crate::validator_commands::SuiValidatorCommand;
 use sui_move::{self, execute_move_command};
 
 #[allow(clippy::large_enum_variant)]
@@ -70,6 +72,9 @@ pub enum SuiCommand {
     KeyTool {
         #[clap(long)]
         keystore_path: Option<PathBuf>,
+        /// Return command outputs in json format.
+        #[clap(long, global = true)]
+        json: bool,
         /// Subcommands.
         #[clap(subcommand)]
         cmd: KeyToolCommand,
@@ -96,6 +101,48 @@ pub enum SuiCommand {
         accept_defaults: bool,
     },
 
+    /// Operate a validator from its account: register as a candidate, join or leave the
+    /// committee, update its metadata, quote its gas price, or report other validators.
+    #[clap(name = "validator")]
+    Validator {
+        /// Sets the file storing the state of our user accounts (an empty one will be created if missing)
+        #[clap(long = "client.config")]
+        config: Option<PathBuf>,
+        #[clap(subcommand)]
+        cmd: Option<SuiValidatorCommand>,
+        /// Return command outputs in json format.
+        #[clap(long, global = true)]
+        json: bool,
+        #[clap(short = 'y', long = "yes")]
+        accept_defaults: bool,
+    },
+
+    /// Compile a local Move package and compare its bytecode, module by module, with the on-chain
+    /// package at the given address. Same as `sui client verify-bytecode`.
+    #[clap(name = "verify-source")]
+    VerifySource {
+        /// Sets the file storing the state of our user accounts (an empty one will be created if missing)
+        #[clap(long = "client.config")]
+        config: Option<PathBuf>,
+        /// Path to directory containing a Move package
+        #[clap(parse(from_os_str), default_value = ".")]
+        package_path: PathBuf,
+        /// Package build options
+        #[clap(flatten)]
+        build_config: BuildConfig,
+        /// ID of the on-chain package to compare with.
+        #[clap(long)]
+        package_id: ObjectID,
+        /// Also compare the package's dependencies with their on-chain versions.
+        #[clap(long)]
+        verify_deps: bool,
+        /// Return command outputs in json format.
+        #[clap(long)]
+        json: bool,
+        #[clap(short = 'y', long = "yes")]
+        accept_defaults: bool,
+    },
+
     /// Tool to build and test Move applications.
     #[clap(name = "move")]
     Move {
@@ -198,11 +245,16 @@ impl SuiCommand {
                 write_config,
             } => genesis(from_config, write_config, working_dir, force).await,
             SuiCommand::GenesisCeremony(cmd) => run(cmd),
-            SuiCommand::KeyTool { keystore_path, cmd } => {
+            SuiCommand::KeyTool {
+                keystore_path,
+                json,
+                cmd,
+            } => {
                 let keystore_path =
                     keystore_path.unwrap_or(sui_config_dir()?.join(SUI_KEYSTORE_FILENAME));
                 let mut keystore = Keystore::from(FileBasedKeystore::new(&keystore_path)?);
-                cmd.execute(&mut keystore)
+                cmd.execute(&mut keystore)?.print(!json);
+                Ok(())
             }
             SuiCommand::Console { config } => {
                 let config = config.unwrap_or(sui_config_dir()?.join(SUI_CLIENT_CONFIG));
@@ -229,6 +281,48 @@ impl SuiCommand {
                 }
                 Ok(())
             }
+            SuiCommand::Validator {
+                config,
+                cmd,
+                json,
+                accept_defaults,
+            } => {
+                let config_path = config.unwrap_or(sui_config_dir()?.join(SUI_CLIENT_CONFIG));
+                prompt_if_no_config(&config_path, accept_defaults).await?;
+                let mut context = WalletContext::new(&config_path, None).await?;
+                if let Some(cmd) = cmd {
+                    cmd.execute(&mut context).await?.print(!json);
+                } else {
+                    // Print help
+                    let mut app: Command = SuiCommand::command();
+                    app.build();
+                    app.find_subcommand_mut("validator").unwrap().print_help()?;
+                }
+                Ok(())
+            }
+            SuiCommand::VerifySource {
+                config,
+                package_path,
+                build_config,
+                package_id,
+                verify_deps,
+                json,
+                accept_defaults,
+            } => {
+                let config_path = config.unwrap_or(sui_config_dir()?.join(SUI_CLIENT_CONFIG));
+                prompt_if_no_config(&config_path, accept_defaults).await?;
+                let mut context = WalletContext::new(&config_path, None).await?;
+                SuiClientCommands::VerifyBytecode {
+                    package_path,
+                    build_config,
+                    package_id,
+                    verify_deps,
+                }
+                .execute(&mut context)
+                .await?
+                .print(!json);
+                Ok(())
+            }
             SuiCommand::Move {
                 package_path,
                 build_config,
@@ -386,6 +480,9 @@ async fn genesis(
         alias: "localnet".to_string(),
         rpc: format!("http://{}", fullnode_config.json_rpc_address),
         ws: None,
+        faucet: None,
+        keystore: None,
+        active_address: None,
     });
     client_config.add_env(SuiEnv::devnet());
 
@@ -410,6 +507,9 @@ async fn prompt_if_no_config(
                 alias: "custom".to_string(),
                 rpc: v.into_string().unwrap(),
                 ws: None,
+                faucet: None,
+                keystore: None,
+                active_address: None,
             }),
             None => {
                 if accept_defaults {
@@ -445,6 +545,9 @@ async fn prompt_if_no_config(
                             alias,
                             rpc: url,
                             ws: None,
+                            faucet: None,
+                            keystore: None,
+                            active_address: None,
                         }
                     })
                 } else {
@@ -481,6 +584,7 @@ async fn prompt_if_no_config(
                 envs: vec![env],
                 active_address: Some(new_address),
                 active_env: Some(alias),
+                shared_keystore: None,
             }
             .persisted(wallet_conf_path)
             .save()?;
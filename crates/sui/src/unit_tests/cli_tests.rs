@@ -5,15 +5,21 @@ use std::str::FromStr;
 use std::{fmt::Write, fs::read_dir, path::PathBuf, str, thread, time::Duration};
 
 use anyhow::anyhow;
+use fastcrypto::encoding::{Base64, Encoding};
 use move_package::BuildConfig;
 use serde_json::json;
 use tokio::time::sleep;
 
 use sui::client_commands::SwitchResponse;
+use sui::client_ptb::PtbCommand;
 use sui::{
-    client_commands::{SuiClientCommandResult, SuiClientCommands, WalletContext},
+    client_commands::{
+        read_pay_batch_csv, GasCommand, PayBatchRow, SuiClientCommandResult, SuiClientCommands,
+        TransactionOptions, WalletContext,
+    },
     config::SuiClientConfig,
     sui_commands::SuiCommand,
+    validator_commands::{GasArgs, MetadataUpdate, SuiValidatorCommand},
 };
 use sui_config::genesis_config::{AccountConfig, GenesisConfig, ObjectConfig};
 use sui_config::{
@@ -22,14 +28,22 @@ use sui_config::{
 };
 use sui_json::SuiJsonValue;
 use sui_json_rpc_types::{
-    SuiObjectData, SuiObjectDataOptions, SuiObjectResponse, SuiTransactionEffectsAPI,
+    SuiExecutionStatus, SuiObjectData, SuiObjectDataOptions, SuiObjectResponse,
+    SuiTransactionEffectsAPI,
 };
+use sui_keys::address_book::AddressOrAlias;
+use sui_keys::keypair_file::{write_authority_keypair_to_file, write_keypair_to_file};
 use sui_keys::keystore::AccountKeystore;
 use sui_macros::sim_test;
 use sui_types::base_types::{ObjectType, SuiAddress};
 use sui_types::crypto::{
-    Ed25519SuiSignature, Secp256k1SuiSignature, SignatureScheme, SuiKeyPair, SuiSignatureInner,
+    get_authority_key_pair, Ed25519SuiSignature, EncodeDecodeBase64, Secp256k1SuiSignature,
+    SignatureScheme, SuiKeyPair, SuiSignatureInner,
 };
+use sui_types::intent::Intent;
+use sui_types::messages::{TransactionData, TransactionDataAPI};
+use sui_types::multisig::MultiSigPublicKey;
+use sui_types::object::Owner;
 use sui_types::{base_types::ObjectID, crypto::get_key_pair, gas_coin::GasCoin};
 use sui_types::{sui_framework_address_concat_string, SUI_FRAMEWORK_ADDRESS};
 use test_utils::messages::make_transactions_with_wallet_context;
@@ -133,7 +147,7 @@ async fn test_objects_command() -> Result<(), anyhow::Error> {
 
     // Print objects owned by `address`
     SuiClientCommands::Objects {
-        address: Some(address),
+        address: Some(address.into()),
     }
     .execute(context)
     .await?
@@ -155,7 +169,7 @@ async fn test_regression_6546() -> Result<(), anyhow::Error> {
     let context = &mut test_cluster.wallet;
 
     let SuiClientCommandResult::Objects(coins) = SuiClientCommands::Objects {
-        address: Some(address),
+        address: Some(address.into()),
     }
         .execute(context)
         .await? else{
@@ -195,6 +209,7 @@ async fn test_create_example_nft_command() {
         url: None,
         gas: None,
         gas_budget: None,
+        options: TransactionOptions::default(),
     }
     .execute(context)
     .await
@@ -246,7 +261,7 @@ async fn test_custom_genesis() -> Result<(), anyhow::Error> {
 
     // Print objects owned by `address`
     SuiClientCommands::Objects {
-        address: Some(address),
+        address: Some(address.into()),
     }
     .execute(context)
     .await?
@@ -306,7 +321,8 @@ async fn test_gas_command() -> Result<(), anyhow::Error> {
     let object_to_send = object_refs.get(1).unwrap().object_id;
 
     SuiClientCommands::Gas {
-        address: Some(address),
+        address: Some(address.into()),
+        cmd: None,
     }
     .execute(context)
     .await?
@@ -316,17 +332,19 @@ async fn test_gas_command() -> Result<(), anyhow::Error> {
 
     // Send an object
     SuiClientCommands::Transfer {
-        to: SuiAddress::random_for_testing_only(),
+        to: SuiAddress::random_for_testing_only().into(),
         object_id: object_to_send,
         gas: Some(object_id),
         gas_budget: 50000,
+        options: TransactionOptions::default(),
     }
     .execute(context)
     .await?;
 
     // Fetch gas again
     SuiClientCommands::Gas {
-        address: Some(address),
+        address: Some(address.into()),
+        cmd: None,
     }
     .execute(context)
     .await?
@@ -360,6 +378,7 @@ async fn test_move_call_args_linter_command() -> Result<(), anyhow::Error> {
         gas_budget: 20_000,
         skip_dependency_verification: false,
         with_unpublished_dependencies: false,
+        options: TransactionOptions::default(),
     }
     .execute(context)
     .await?;
@@ -372,7 +391,7 @@ async fn test_move_call_args_linter_command() -> Result<(), anyhow::Error> {
 
     // Print objects owned by `address1`
     SuiClientCommands::Objects {
-        address: Some(address1),
+        address: Some(address1.into()),
     }
     .execute(context)
     .await?
@@ -406,6 +425,7 @@ async fn test_move_call_args_linter_command() -> Result<(), anyhow::Error> {
         args,
         gas: None,
         gas_budget: 20_000,
+        options: TransactionOptions::default(),
     }
     .execute(context)
     .await?;
@@ -437,6 +457,7 @@ async fn test_move_call_args_linter_command() -> Result<(), anyhow::Error> {
         args: args.to_vec(),
         gas: Some(gas),
         gas_budget: 20_000,
+        options: TransactionOptions::default(),
     }
     .execute(context)
     .await;
@@ -461,6 +482,7 @@ async fn test_move_call_args_linter_command() -> Result<(), anyhow::Error> {
         args: args.to_vec(),
         gas: Some(gas),
         gas_budget: 20_000,
+        options: TransactionOptions::default(),
     }
     .execute(context)
     .await;
@@ -486,6 +508,7 @@ async fn test_move_call_args_linter_command() -> Result<(), anyhow::Error> {
         args: args.to_vec(),
         gas: Some(gas),
         gas_budget: 20_000,
+        options: TransactionOptions::default(),
     }
     .execute(context)
     .await?;
@@ -519,6 +542,7 @@ async fn test_package_publish_command() -> Result<(), anyhow::Error> {
         gas_budget: 20_000,
         skip_dependency_verification: false,
         with_unpublished_dependencies: false,
+        options: TransactionOptions::default(),
     }
     .execute(context)
     .await?;
@@ -545,6 +569,116 @@ async fn test_package_publish_command() -> Result<(), anyhow::Error> {
     Ok(())
 }
 
+#[sim_test]
+async fn test_package_upgrade_command() -> Result<(), anyhow::Error> {
+    let mut test_cluster = TestClusterBuilder::new().build().await?;
+    let context = &mut test_cluster.wallet;
+
+    let mut package_path = PathBuf::from(TEST_DATA_DIR);
+    package_path.push("dummy_modules_publish");
+    let resp = SuiClientCommands::Publish {
+        package_path: package_path.clone(),
+        build_config: BuildConfig::default(),
+        gas: None,
+        gas_budget: 20_000,
+        skip_dependency_verification: false,
+        with_unpublished_dependencies: false,
+        options: TransactionOptions::default(),
+    }
+    .execute(context)
+    .await?;
+    let SuiClientCommandResult::Publish(response) = resp else {
+        panic!("Command failed")
+    };
+    let package_id = response
+        .effects
+        .created()
+        .iter()
+        .find(|refe| refe.owner == Owner::Immutable)
+        .unwrap()
+        .reference
+        .object_id;
+
+    // The framework does not have the modules of the package.
+    let err = SuiClientCommands::Upgrade {
+        package_path: package_path.clone(),
+        build_config: BuildConfig::default(),
+        package_id: ObjectID::from(SUI_FRAMEWORK_ADDRESS),
+    }
+    .execute(context)
+    .await
+    .unwrap_err();
+    assert!(err.to_string().contains("is missing"), "{err}");
+
+    // The package is compatible with itself, but cannot be upgraded yet.
+    let err = SuiClientCommands::Upgrade {
+        package_path,
+        build_config: BuildConfig::default(),
+        package_id,
+    }
+    .execute(context)
+    .await
+    .unwrap_err();
+    assert!(err.to_string().contains("not supported"), "{err}");
+
+    Ok(())
+}
+
+#[sim_test]
+async fn test_verify_bytecode_command() -> Result<(), anyhow::Error> {
+    let mut test_cluster = TestClusterBuilder::new().build().await?;
+    let context = &mut test_cluster.wallet;
+
+    let mut package_path = PathBuf::from(TEST_DATA_DIR);
+    package_path.push("dummy_modules_publish");
+    let resp = SuiClientCommands::Publish {
+        package_path: package_path.clone(),
+        build_config: BuildConfig::default(),
+        gas: None,
+        gas_budget: 20_000,
+        skip_dependency_verification: false,
+        with_unpublished_dependencies: false,
+        options: TransactionOptions::default(),
+    }
+    .execute(context)
+    .await?;
+    let SuiClientCommandResult::Publish(response) = resp else {
+        panic!("Command failed")
+    };
+    let package_id = response
+        .effects
+        .created()
+        .iter()
+        .find(|refe| refe.owner == Owner::Immutable)
+        .unwrap()
+        .reference
+        .object_id;
+
+    let resp = SuiClientCommands::VerifyBytecode {
+        package_path: package_path.clone(),
+        build_config: BuildConfig::default(),
+        package_id,
+        verify_deps: true,
+    }
+    .execute(context)
+    .await?;
+    assert!(matches!(resp, SuiClientCommandResult::VerifyBytecode));
+
+    // The framework does not have the modules of the package.
+    let err = SuiClientCommands::VerifyBytecode {
+        package_path,
+        build_config: BuildConfig::default(),
+        package_id: ObjectID::from(SUI_FRAMEWORK_ADDRESS),
+        verify_deps: false,
+    }
+    .execute(context)
+    .await
+    .unwrap_err();
+    assert!(err.to_string().contains("was not found"), "{err}");
+
+    Ok(())
+}
+
 #[sim_test]
 async fn test_native_transfer() -> Result<(), anyhow::Error> {
     let mut test_cluster = TestClusterBuilder::new().build().await?;
@@ -564,9 +698,10 @@ async fn test_native_transfer() -> Result<(), anyhow::Error> {
 
     let resp = SuiClientCommands::Transfer {
         gas: Some(gas_obj_id),
-        to: recipient,
+        to: recipient.into(),
         object_id: obj_id,
         gas_budget: 50000,
+        options: TransactionOptions::default(),
     }
     .execute(context)
     .await?;
@@ -640,9 +775,10 @@ async fn test_native_transfer() -> Result<(), anyhow::Error> {
 
     let resp = SuiClientCommands::Transfer {
         gas: None,
-        to: recipient,
+        to: recipient.into(),
         object_id: obj_id,
         gas_budget: 50000,
+        options: TransactionOptions::default(),
     }
     .execute(context)
     .await?;
@@ -718,7 +854,7 @@ async fn test_switch_command() -> Result<(), anyhow::Error> {
 
     // Switch the address
     let resp = SuiClientCommands::Switch {
-        address: Some(addr2),
+        address: Some(addr2.into()),
         env: None,
     }
     .execute(context)
@@ -755,7 +891,7 @@ async fn test_switch_command() -> Result<(), anyhow::Error> {
     // Check that we can switch to this address
     // Switch the address
     let resp = SuiClientCommands::Switch {
-        address: Some(new_addr),
+        address: Some(new_addr.into()),
         env: None,
     }
     .execute(context)
@@ -774,6 +910,82 @@ async fn test_switch_command() -> Result<(), anyhow::Error> {
     Ok(())
 }
 
+#[sim_test]
+async fn test_switch_env_keystore() -> Result<(), anyhow::Error> {
+    let mut cluster = TestClusterBuilder::new().build().await?;
+    let addr2 = cluster.get_address_1();
+    let context = cluster.wallet_mut();
+    let temp_dir = tempfile::tempdir()?;
+    let keystore_path = temp_dir.path().join("other.keystore");
+    let localnet = context.config.get_active_env()?.clone();
+    let shared_addresses = context.config.keystore.addresses();
+
+    SuiClientCommands::NewEnv {
+        alias: "other".to_string(),
+        rpc: localnet.rpc.clone(),
+        ws: None,
+        faucet: None,
+        keystore: Some(keystore_path.clone()),
+    }
+    .execute(context)
+    .await?;
+    SuiClientCommands::Switch {
+        address: Some(addr2.into()),
+        env: None,
+    }
+    .execute(context)
+    .await?;
+
+    // The environment starts with its own, empty, keystore.
+    SuiClientCommands::Switch {
+        address: None,
+        env: Some("other".to_string()),
+    }
+    .execute(context)
+    .await?;
+    assert!(context.config.keystore.addresses().is_empty());
+    assert!(context.config.active_address.is_none());
+    let SuiClientCommandResult::NewAddress((new_addr, _, _)) = SuiClientCommands::NewAddress {
+        key_scheme: SignatureScheme::ED25519,
+        derivation_path: None,
+    }
+    .execute(context)
+    .await? else {
+        panic!("Command failed")
+    };
+    assert_eq!(new_addr, context.active_address()?);
+    assert!(!shared_addresses.contains(&new_addr));
+
+    // Switching back restores the shared keystore and the active address.
+    SuiClientCommands::Switch {
+        address: None,
+        env: Some(localnet.alias.clone()),
+    }
+    .execute(context)
+    .await?;
+    assert_eq!(context.config.keystore.addresses(), shared_addresses);
+    assert_eq!(addr2, context.active_address()?);
+
+    let resp = SuiClientCommands::Switch {
+        address: None,
+        env: Some("other".to_string()),
+    }
+    .execute(context)
+    .await?;
+    assert_eq!(vec![new_addr], context.config.keystore.addresses());
+    assert_eq!(
+        format!("{resp}"),
+        format!(
+            "{}",
+            SuiClientCommandResult::Switch(SwitchResponse {
+                address: Some(new_addr),
+                env: Some("other".to_string())
+            })
+        )
+    );
+    Ok(())
+}
+
 #[sim_test]
 async fn test_new_address_command_by_flag() -> Result<(), anyhow::Error> {
     let mut cluster = TestClusterBuilder::new().build().await?;
@@ -833,7 +1045,7 @@ async fn test_active_address_command() -> Result<(), anyhow::Error> {
 
     let addr2 = context.config.keystore.addresses().get(1).cloned().unwrap();
     let resp = SuiClientCommands::Switch {
-        address: Some(addr2),
+        address: Some(addr2.into()),
         env: None,
     }
     .execute(context)
@@ -903,6 +1115,7 @@ async fn test_merge_coin() -> Result<(), anyhow::Error> {
         coin_to_merge,
         gas: Some(gas),
         gas_budget: 20_000,
+        options: TransactionOptions::default(),
     }
     .execute(context)
     .await?;
@@ -943,6 +1156,7 @@ async fn test_merge_coin() -> Result<(), anyhow::Error> {
         coin_to_merge,
         gas: None,
         gas_budget: 10_000,
+        options: TransactionOptions::default(),
     }
     .execute(context)
     .await?;
@@ -994,6 +1208,7 @@ async fn test_split_coin() -> Result<(), anyhow::Error> {
         coin_id: coin,
         amounts: Some(vec![1000, 10]),
         count: None,
+        options: TransactionOptions::default(),
     }
     .execute(context)
     .await?;
@@ -1045,6 +1260,7 @@ async fn test_split_coin() -> Result<(), anyhow::Error> {
         coin_id: coin,
         amounts: None,
         count: Some(3),
+        options: TransactionOptions::default(),
     }
     .execute(context)
     .await?;
@@ -1099,6 +1315,7 @@ async fn test_split_coin() -> Result<(), anyhow::Error> {
         coin_id: coin,
         amounts: Some(vec![1000, 10]),
         count: None,
+        options: TransactionOptions::default(),
     }
     .execute(context)
     .await?;
@@ -1132,6 +1349,192 @@ async fn test_split_coin() -> Result<(), anyhow::Error> {
     Ok(())
 }
 
+#[sim_test]
+async fn test_ptb() -> Result<(), anyhow::Error> {
+    let mut test_cluster = TestClusterBuilder::new().build().await?;
+    let address = test_cluster.get_address_0();
+    let recipient = test_cluster.get_address_1();
+    let context = &mut test_cluster.wallet;
+    let client = context.get_client().await?;
+    let object_refs = client
+        .read_api()
+        .get_objects_owned_by_address(address)
+        .await?;
+
+    let gas = object_refs.first().unwrap().object_id;
+    let coin = object_refs.get(1).unwrap().object_id;
+    let orig_value = get_gas_value(&get_object(coin, context).await.unwrap());
+
+    // Split two coins off and transfer both of them, in one transaction.
+    let commands: Vec<PtbCommand> = vec![
+        format!("split-coins {coin} 1000 10").parse()?,
+        format!("transfer-objects {recipient} @0").parse()?,
+    ];
+
+    // A dry run shows the effects without executing the transaction.
+    let resp = SuiClientCommands::Ptb {
+        commands: commands.clone(),
+        file: None,
+        gas: Some(gas),
+        gas_budget: 20_000,
+        options: TransactionOptions {
+            dry_run: true,
+            ..Default::default()
+        },
+    }
+    .execute(context)
+    .await?;
+    let SuiClientCommandResult::DryRun(preview) = resp else {
+        panic!("Command failed")
+    };
+    assert_eq!(preview.effects.created().len(), 2);
+    assert_eq!(
+        get_gas_value(&get_object(coin, context).await.unwrap()),
+        orig_value
+    );
+
+    let resp = SuiClientCommands::Ptb {
+        commands,
+        file: None,
+        gas: Some(gas),
+        gas_budget: 20_000,
+        options: TransactionOptions::default(),
+    }
+    .execute(context)
+    .await?;
+    let SuiClientCommandResult::Ptb(response) = resp else {
+        panic!("Command failed")
+    };
+    let created = response.effects.created();
+    assert_eq!(created.len(), 2);
+    for obj in created {
+        assert_eq!(obj.owner, Owner::AddressOwner(recipient));
+        let value = get_gas_value(&get_object(obj.reference.object_id, context).await.unwrap());
+        assert!(value == 1000 || value == 10);
+    }
+    assert_eq!(
+        get_gas_value(&get_object(coin, context).await.unwrap()) + 1000 + 10,
+        orig_value
+    );
+
+    // Results can only be taken from earlier commands.
+    let resp = SuiClientCommands::Ptb {
+        commands: vec![format!("transfer-objects {recipient} @0").parse()?],
+        file: None,
+        gas: Some(gas),
+        gas_budget: 20_000,
+        options: TransactionOptions::default(),
+    }
+    .execute(context)
+    .await;
+    assert!(resp.is_err());
+    Ok(())
+}
+
+#[sim_test]
+async fn test_pay_batch() -> Result<(), anyhow::Error> {
+    let mut test_cluster = TestClusterBuilder::new().build().await?;
+    let recipient = test_cluster.get_address_1();
+    let context = &mut test_cluster.wallet;
+    let other = SuiAddress::random_for_testing_only();
+
+    let temp_dir = tempfile::tempdir()?;
+    let csv = temp_dir.path().join("recipients.csv");
+    std::fs::write(
+        &csv,
+        format!("address,amount\n# comment\n{recipient},1000\n\n{other},2000\n{recipient},3000\n"),
+    )?;
+
+    let resp = SuiClientCommands::PayBatch {
+        csv,
+        gas: None,
+        gas_budget: 20_000,
+        batch_size: Some(2),
+    }
+    .execute(context)
+    .await?;
+    let SuiClientCommandResult::PayBatch(summary) = resp else {
+        panic!("Command failed")
+    };
+    assert_eq!(summary.error, None);
+    assert_eq!(summary.recipients, 3);
+    assert_eq!(summary.total_amount, 6000);
+    let lines: Vec<_> = summary
+        .transactions
+        .iter()
+        .map(|t| (t.first_line, t.last_line, t.recipients, t.amount))
+        .collect();
+    assert_eq!(lines, vec![(3, 5, 2, 3000), (6, 6, 1, 3000)]);
+
+    let values: Vec<_> = context
+        .gas_objects(other)
+        .await?
+        .iter()
+        .map(|(value, ..)| *value)
+        .collect();
+    assert_eq!(values, vec![2000]);
+    let values: Vec<_> = context
+        .gas_objects(recipient)
+        .await?
+        .iter()
+        .map(|(value, ..)| *value)
+        .collect();
+    assert!(values.contains(&1000) && values.contains(&3000));
+    Ok(())
+}
+
+#[test]
+fn test_read_pay_batch_csv() -> Result<(), anyhow::Error> {
+    let address = SuiAddress::random_for_testing_only();
+    let temp_dir = tempfile::tempdir()?;
+    let csv = temp_dir.path().join("recipients.csv");
+
+    // Quoted fields and Windows line endings are read, and there need not be column names.
+    std::fs::write(&csv, format!("\"{address}\", \"10\"\r\n{address},20\r\n"))?;
+    let rows = read_pay_batch_csv(&csv)?;
+    assert_eq!(
+        rows,
+        vec![
+            PayBatchRow {
+                line: 1,
+                recipient: address,
+                amount: 10
+            },
+            PayBatchRow {
+                line: 2,
+                recipient: address,
+                amount: 20
+            }
+        ]
+    );
+
+    // Errors point at the line of the invalid row.
+    for (content, error) in [
+        (
+            format!("{address},10\n{address},ten"),
+            "Invalid amount on line 2",
+        ),
+        (format!("{address},0"), "must be positive"),
+        (
+            format!("{address}"),
+            "Expected <address>,<amount> on line 1",
+        ),
+        (
+            format!("{address},10,20"),
+            "Expected <address>,<amount> on line 1",
+        ),
+        (
+            "address,amount\nrecipient,amount".to_string(),
+            "Invalid address on line 2",
+        ),
+    ] {
+        std::fs::write(&csv, content)?;
+        let err = read_pay_batch_csv(&csv).unwrap_err();
+        assert!(err.to_string().contains(error), "{err}");
+    }
+    Ok(())
+}
+
 #[sim_test]
 async fn test_signature_flag() -> Result<(), anyhow::Error> {
     let res = SignatureScheme::from_flag("0");
@@ -1168,6 +1571,357 @@ async fn test_execute_signed_tx() -> Result<(), anyhow::Error> {
     Ok(())
 }
 
+#[sim_test]
+async fn test_serialize_sign_execute_signed_tx() -> Result<(), anyhow::Error> {
+    let mut test_cluster = TestClusterBuilder::new().build().await?;
+    let address = test_cluster.get_address_0();
+    let recipient = test_cluster.get_address_1();
+    let context = &mut test_cluster.wallet;
+
+    let coin = context
+        .gas_objects(address)
+        .await?
+        .first()
+        .unwrap()
+        .1
+        .object_id;
+    let resp = SuiClientCommands::TransferSui {
+        to: recipient.into(),
+        sui_coin_object_id: coin,
+        gas_budget: 1000,
+        amount: Some(1),
+        options: TransactionOptions {
+            serialize_unsigned_transaction: true,
+            ..Default::default()
+        },
+    }
+    .execute(context)
+    .await?;
+    let SuiClientCommandResult::SerializedUnsignedTransaction(tx_bytes) = resp else {
+        panic!("Command failed")
+    };
+
+    // The transaction is sent by the owner of the coin, who signs it elsewhere.
+    let data: TransactionData = bcs::from_bytes(&Base64::decode(&tx_bytes).unwrap())?;
+    assert_eq!(data.sender(), address);
+
+    // Sign as `sui keytool sign --data` does, then execute.
+    let signature = context
+        .config
+        .keystore
+        .sign_secure(&address, &data, Intent::default())?;
+    let resp = SuiClientCommands::ExecuteSignedTx {
+        tx_bytes,
+        signatures: vec![signature.encode_base64()],
+    }
+    .execute(context)
+    .await?;
+    let SuiClientCommandResult::ExecuteSignedTx(response) = resp else {
+        panic!("Command failed")
+    };
+    assert_eq!(response.effects.status(), &SuiExecutionStatus::Success);
+
+    Ok(())
+}
+
+#[sim_test]
+async fn test_address_aliases() -> Result<(), anyhow::Error> {
+    let mut test_cluster = TestClusterBuilder::new().build().await?;
+    let address = test_cluster.get_address_0();
+    let recipient = test_cluster.get_address_1();
+    let context = &mut test_cluster.wallet;
+
+    let mut address_book = context.address_book()?;
+    address_book.set("bob", recipient)?;
+    address_book.save()?;
+
+    let coin = context
+        .gas_objects(address)
+        .await?
+        .first()
+        .unwrap()
+        .1
+        .object_id;
+    let resp = SuiClientCommands::TransferSui {
+        to: AddressOrAlias::from_str("bob")?,
+        sui_coin_object_id: coin,
+        gas_budget: 1000,
+        amount: Some(1),
+        options: TransactionOptions {
+            serialize_unsigned_transaction: true,
+            ..Default::default()
+        },
+    }
+    .execute(context)
+    .await?;
+    let SuiClientCommandResult::SerializedUnsignedTransaction(tx_bytes) = resp else {
+        panic!("Command failed")
+    };
+    let data: TransactionData = bcs::from_bytes(&Base64::decode(&tx_bytes).unwrap())?;
+    let expected = context
+        .get_client()
+        .await?
+        .transaction_builder()
+        .transfer_sui(address, coin, 1000, recipient, Some(1))
+        .await?;
+    assert_eq!(data, expected);
+
+    let resp = SuiClientCommands::Switch {
+        address: Some(AddressOrAlias::from_str("bob")?),
+        env: None,
+    }
+    .execute(context)
+    .await?;
+    let SuiClientCommandResult::Switch(SwitchResponse { address, .. }) = resp else {
+        panic!("Command failed")
+    };
+    assert_eq!(address, Some(recipient));
+
+    // Unknown aliases are errors rather than being taken for some address.
+    let result = SuiClientCommands::Gas {
+        address: Some(AddressOrAlias::from_str("carol")?),
+        cmd: None,
+    }
+    .execute(context)
+    .await;
+    assert!(result.is_err());
+
+    Ok(())
+}
+
+#[sim_test]
+async fn test_dry_run_and_dev_inspect_transfer() -> Result<(), anyhow::Error> {
+    let mut test_cluster = TestClusterBuilder::new().build().await?;
+    let address = test_cluster.get_address_0();
+    let recipient = test_cluster.get_address_1();
+    let context = &mut test_cluster.wallet;
+
+    let object_id = context
+        .gas_objects(address)
+        .await?
+        .first()
+        .unwrap()
+        .1
+        .object_id;
+
+    let resp = SuiClientCommands::Transfer {
+        to: recipient.into(),
+        object_id,
+        gas: None,
+        gas_budget: 1000,
+        options: TransactionOptions {
+            dry_run: true,
+            ..Default::default()
+        },
+    }
+    .execute(context)
+    .await?;
+    let SuiClientCommandResult::DryRun(response) = resp else {
+        panic!("Command failed")
+    };
+    assert_eq!(response.effects.status(), &SuiExecutionStatus::Success);
+    assert!(response.effects.gas_used().computation_cost > 0);
+
+    let resp = SuiClientCommands::Transfer {
+        to: recipient.into(),
+        object_id,
+        gas: None,
+        gas_budget: 1000,
+        options: TransactionOptions {
+            dev_inspect: true,
+            ..Default::default()
+        },
+    }
+    .execute(context)
+    .await?;
+    let SuiClientCommandResult::DevInspect(results) = resp else {
+        panic!("Command failed")
+    };
+    assert_eq!(results.effects.status(), &SuiExecutionStatus::Success);
+
+    // Neither executed the transfer.
+    assert_eq!(context.get_object_owner(&object_id).await?, address);
+    Ok(())
+}
+
+#[sim_test]
+async fn test_profile_gas() -> Result<(), anyhow::Error> {
+    let mut test_cluster = TestClusterBuilder::new().build().await?;
+    let address = test_cluster.get_address_0();
+    let context = &mut test_cluster.wallet;
+
+    let coins = context.gas_objects(address).await?;
+    let (balance, object, _) = coins.first().unwrap();
+
+    let resp = SuiClientCommands::ProfileGas {
+        function: "0x2::coin::value<0x2::sui::SUI>".to_string(),
+        type_args: vec![],
+        args: vec![SuiJsonValue::new(json!(object.object_id))?],
+        gas_budget: None,
+        top: 20,
+    }
+    .execute(context)
+    .await?;
+    let SuiClientCommandResult::ProfileGas(_, results) = resp else {
+        panic!("Command failed")
+    };
+    assert_eq!(results.effects.status(), &SuiExecutionStatus::Success);
+    let Ok(values) = &results.results else {
+        panic!("Command failed")
+    };
+    let (bytes, _) = &values[0].1.return_values[0];
+    assert_eq!(bcs::from_bytes::<u64>(bytes)?, *balance);
+
+    let profile = results.gas_profile.unwrap();
+    assert!(profile.total_internal_gas() > 0);
+    let balance_value = profile
+        .functions
+        .iter()
+        .find(|f| f.name.ends_with("::balance::value"))
+        .unwrap();
+    assert_eq!(balance_value.count, 1);
+    // The display only shows the most expensive entries.
+    let display = format!("{}", SuiClientCommandResult::ProfileGas(1, results.clone()));
+    assert!(display.contains("more"));
+    Ok(())
+}
+
+#[sim_test]
+async fn test_inspect_object() -> Result<(), anyhow::Error> {
+    let mut test_cluster = TestClusterBuilder::new().build().await?;
+    let address = test_cluster.get_address_0();
+    let context = &mut test_cluster.wallet;
+
+    let coins = context.gas_objects(address).await?;
+    let (balance, object, _) = coins.first().unwrap();
+
+    let resp = SuiClientCommands::Inspect {
+        id: object.object_id,
+        cursor: None,
+        limit: 50,
+    }
+    .execute(context)
+    .await?;
+    let SuiClientCommandResult::Inspect(inspection) = resp else {
+        panic!("Command failed")
+    };
+    assert_eq!(inspection.object.object_id, object.object_id);
+    assert_eq!(inspection.object.owner, Some(Owner::AddressOwner(address)));
+    assert!(inspection.object.content.is_some());
+    assert!(inspection.object.bcs.is_some());
+    assert!(inspection.dynamic_fields.data.is_empty());
+
+    let display = format!("{inspection}");
+    for section in ["JSON", "BCS", "Display", "Dynamic Fields"] {
+        assert!(display.contains(&format!("----- {section} -----")));
+    }
+    assert!(display.contains(&balance.to_string()));
+    Ok(())
+}
+
+#[sim_test]
+async fn test_gas_merge_all() -> Result<(), anyhow::Error> {
+    let mut test_cluster = TestClusterBuilder::new().build().await?;
+    let address = test_cluster.get_address_0();
+    let context = &mut test_cluster.wallet;
+
+    let coins = context.gas_objects(address).await?;
+    assert!(coins.len() > 1);
+    let (largest, ..) = coins.iter().max_by_key(|(value, ..)| *value).unwrap();
+    let total: u64 = coins.iter().map(|(value, ..)| value).sum();
+
+    let resp = SuiClientCommands::Gas {
+        address: Some(address.into()),
+        cmd: Some(GasCommand::MergeAll { gas_budget: 1000 }),
+    }
+    .execute(context)
+    .await?;
+    let SuiClientCommandResult::MergeAllGas(_, merged, responses) = resp else {
+        panic!("Command failed")
+    };
+    assert_eq!(merged, coins.len() - 1);
+    assert!(responses
+        .iter()
+        .all(|response| response.effects.status().is_ok()));
+
+    let coins = context.gas_objects(address).await?;
+    assert_eq!(coins.len(), 1);
+    assert!(coins[0].0 > *largest && coins[0].0 < total);
+    Ok(())
+}
+
+#[sim_test]
+async fn test_execute_combined() -> Result<(), anyhow::Error> {
+    let mut test_cluster = TestClusterBuilder::new().build().await?;
+    let address = test_cluster.get_address_0();
+    let address1 = test_cluster.get_address_1();
+    let context = &mut test_cluster.wallet;
+    let pks = vec![
+        context.config.keystore.get_key(&address)?.public(),
+        context.config.keystore.get_key(&address1)?.public(),
+    ];
+    let multisig_address: SuiAddress = MultiSigPublicKey::new(pks.clone(), vec![1, 1], 2)?.into();
+
+    // Fund the MultiSig address.
+    let client = context.get_client().await?;
+    let object_refs = client
+        .read_api()
+        .get_objects_owned_by_address(address)
+        .await?;
+    let coin = object_refs.get(1).unwrap().object_id;
+    SuiClientCommands::Transfer {
+        to: multisig_address.into(),
+        object_id: coin,
+        gas: None,
+        gas_budget: 1000,
+        options: TransactionOptions::default(),
+    }
+    .execute(context)
+    .await?;
+
+    let data = client
+        .transaction_builder()
+        .transfer_sui(multisig_address, coin, 1000, address1, Some(1))
+        .await?;
+    let tx_bytes = Base64::encode(bcs::to_bytes(&data)?);
+    let sigs = [address, address1]
+        .iter()
+        .map(|a| {
+            context
+                .config
+                .keystore
+                .sign_secure(a, &data, Intent::default())
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // One signature does not reach the threshold.
+    assert!(SuiClientCommands::ExecuteCombined {
+        tx_bytes: tx_bytes.clone(),
+        sigs: sigs[..1].to_vec(),
+        pks: pks.clone(),
+        weights: vec![1, 1],
+        threshold: 2,
+    }
+    .execute(context)
+    .await
+    .is_err());
+
+    let resp = SuiClientCommands::ExecuteCombined {
+        tx_bytes,
+        sigs,
+        pks,
+        weights: vec![1, 1],
+        threshold: 2,
+    }
+    .execute(context)
+    .await?;
+    let SuiClientCommandResult::ExecuteSignedTx(response) = resp else {
+        panic!("Command failed")
+    };
+    assert!(response.effects.status().is_ok());
+    Ok(())
+}
+
 #[sim_test]
 async fn test_serialize_tx() -> Result<(), anyhow::Error> {
     let mut test_cluster = TestClusterBuilder::new().build().await?;
@@ -1182,7 +1936,7 @@ async fn test_serialize_tx() -> Result<(), anyhow::Error> {
     let coin = object_refs.get(1).unwrap().object_id;
 
     SuiClientCommands::SerializeTransferSui {
-        to: address1,
+        to: address1.into(),
         sui_coin_object_id: coin,
         gas_budget: 1000,
         amount: Some(1),
@@ -1291,6 +2045,227 @@ async fn test_delegation_with_u64_amount() -> Result<(), anyhow::Error> {
     Ok(())
 }
 
+#[sim_test]
+async fn test_validator_become_candidate() -> Result<(), anyhow::Error> {
+    let mut test_cluster = TestClusterBuilder::new().build().await?;
+    let context = &mut test_cluster.wallet;
+    let temp_dir = tempfile::tempdir()?;
+    let protocol_key_file = temp_dir.path().join("protocol.key");
+    let network_key_file = temp_dir.path().join("network.key");
+    let worker_key_file = temp_dir.path().join("worker.key");
+    write_authority_keypair_to_file(&get_authority_key_pair().1, &protocol_key_file)?;
+    write_keypair_to_file(&SuiKeyPair::Ed25519(get_key_pair().1), &network_key_file)?;
+    write_keypair_to_file(&SuiKeyPair::Ed25519(get_key_pair().1), &worker_key_file)?;
+
+    let gas = GasArgs {
+        gas: None,
+        gas_budget: 100_000,
+        options: TransactionOptions::default(),
+    };
+    let become_candidate =
+        |commission_rate, network_key_file: &PathBuf| SuiValidatorCommand::BecomeCandidate {
+            protocol_key_file: protocol_key_file.clone(),
+            network_key_file: network_key_file.clone(),
+            worker_key_file: worker_key_file.clone(),
+            name: "validator".to_string(),
+            description: String::new(),
+            image_url: String::new(),
+            project_url: String::new(),
+            network_address: "/dns/localhost/tcp/8080/http".parse().unwrap(),
+            p2p_address: "/dns/localhost/udp/8084".parse().unwrap(),
+            primary_address: "/dns/localhost/udp/8081".parse().unwrap(),
+            worker_address: "/dns/localhost/udp/8082".parse().unwrap(),
+            gas_price: 1,
+            commission_rate,
+            gas,
+        };
+
+    // Invalid arguments are rejected before sending anything.
+    assert!(become_candidate(10_001, &network_key_file)
+        .execute(context)
+        .await
+        .is_err());
+    assert!(become_candidate(100, &protocol_key_file)
+        .execute(context)
+        .await
+        .is_err());
+
+    let resp = become_candidate(100, &network_key_file)
+        .execute(context)
+        .await?;
+    let SuiClientCommandResult::Call(response) = resp else {
+        panic!("Command failed")
+    };
+    assert_eq!(response.effects.status(), &SuiExecutionStatus::Success);
+
+    // The candidate is not a validator of the committee yet.
+    for cmd in [
+        SuiValidatorCommand::SetGasPrice { gas_price: 10, gas },
+        SuiValidatorCommand::LeaveCommittee { gas },
+        SuiValidatorCommand::ReportValidator {
+            reportee: SuiAddress::random_for_testing_only().into(),
+            undo: false,
+            gas,
+        },
+    ] {
+        let err = cmd.execute(context).await.unwrap_err();
+        assert!(err.to_string().contains("is not an active validator"));
+    }
+    let result = SuiValidatorCommand::UpdateMetadata {
+        metadata: MetadataUpdate::Name {
+            name: "v".repeat(200),
+        },
+        gas,
+    }
+    .execute(context)
+    .await;
+    assert!(result.is_err());
+    Ok(())
+}
+
+#[sim_test]
+async fn test_stake_command() -> Result<(), anyhow::Error> {
+    let mut test_cluster = TestClusterBuilder::new().build().await?;
+    let address = test_cluster.get_address_0();
+    let context = &mut test_cluster.wallet;
+
+    let resp = SuiClientCommands::Validators.execute(context).await?;
+    let SuiClientCommandResult::Validators(validators) = resp else {
+        panic!("Command failed")
+    };
+    assert!(!validators.is_empty());
+    let validator = validators.first().unwrap().0.sui_address;
+
+    // Coins adding up to the amount are selected.
+    let resp = SuiClientCommands::Stake {
+        validator: validator.into(),
+        coins: vec![],
+        amount: Some(10000),
+        gas: None,
+        gas_budget: 10000,
+        options: TransactionOptions::default(),
+    }
+    .execute(context)
+    .await?;
+    let SuiClientCommandResult::Stake(response) = resp else {
+        panic!("Command failed")
+    };
+    assert!(response.effects.status().is_ok());
+
+    let client = context.get_client().await?;
+    let stake = client
+        .governance_api()
+        .get_delegated_stakes(address)
+        .await?;
+    assert_eq!(1, stake.len());
+    assert_eq!(10000, stake.first().unwrap().staked_sui.principal());
+    Ok(())
+}
+
+#[sim_test]
+async fn test_replay_command() -> Result<(), anyhow::Error> {
+    let mut test_cluster = TestClusterBuilder::new().build().await?;
+    let context = &mut test_cluster.wallet;
+
+    let resp = SuiClientCommands::Validators.execute(context).await?;
+    let SuiClientCommandResult::Validators(validators) = resp else {
+        panic!("Command failed")
+    };
+    let validator = validators.first().unwrap().0.sui_address;
+
+    // Staking reads the shared system state and its child objects.
+    let resp = SuiClientCommands::Stake {
+        validator: validator.into(),
+        coins: vec![],
+        amount: Some(10000),
+        gas: None,
+        gas_budget: 10000,
+        options: TransactionOptions::default(),
+    }
+    .execute(context)
+    .await?;
+    let SuiClientCommandResult::Stake(response) = resp else {
+        panic!("Command failed")
+    };
+    let digest = *response.effects.transaction_digest();
+
+    let resp = SuiClientCommands::Replay {
+        digest,
+        rpc: None,
+        protocol_version: None,
+    }
+    .execute(context)
+    .await?;
+    let SuiClientCommandResult::Replay(result) = resp else {
+        panic!("Command failed")
+    };
+    assert!(result.differences.is_empty());
+    assert!(result.warnings.is_empty());
+    assert_eq!(
+        result.on_chain_effects.gas_used(),
+        result.local_effects.gas_used()
+    );
+    Ok(())
+}
+
+#[sim_test]
+async fn test_watch_command() -> Result<(), anyhow::Error> {
+    let mut test_cluster = TestClusterBuilder::new()
+        .enable_fullnode_events()
+        .build()
+        .await?;
+    let config_path = test_cluster.swarm.dir().join(SUI_CLIENT_CONFIG);
+    let mut watcher = WalletContext::new(&config_path, None).await?;
+    let context = &mut test_cluster.wallet;
+
+    let watch = SuiClientCommands::Watch {
+        package: ObjectID::from(SUI_FRAMEWORK_ADDRESS),
+        module: Some("devnet_nft".to_string()),
+        transactions: false,
+        limit: Some(1),
+    }
+    .execute(&mut watcher);
+    let mint = async {
+        // Let the subscription start before the event is emitted.
+        sleep(Duration::from_secs(1)).await;
+        SuiClientCommands::CreateExampleNFT {
+            name: None,
+            description: None,
+            url: None,
+            gas: None,
+            gas_budget: None,
+            options: TransactionOptions::default(),
+        }
+        .execute(context)
+        .await
+    };
+    let (resp, minted) = tokio::join!(tokio::time::timeout(Duration::from_secs(10), watch), mint);
+    minted?;
+    let SuiClientCommandResult::Watch(count) = resp?? else {
+        panic!("Command failed")
+    };
+    assert_eq!(count, 1);
+    Ok(())
+}
+
+#[sim_test]
+async fn test_faucet_command_without_faucet() -> Result<(), anyhow::Error> {
+    let mut test_cluster = TestClusterBuilder::new().build().await?;
+    let context = &mut test_cluster.wallet;
+
+    // The environment of the test cluster has no faucet.
+    let err = SuiClientCommands::Faucet {
+        address: None,
+        url: None,
+        amount: None,
+    }
+    .execute(context)
+    .await
+    .unwrap_err();
+    assert!(err.to_string().contains("has no faucet"), "{err}");
+    Ok(())
+}
+
 async fn test_with_sui_binary(args: &[&str]) -> Result<(), anyhow::Error> {
     let mut cmd = assert_cmd::Command::cargo_bin("sui").unwrap();
     let args = args.iter().map(|s| s.to_string()).collect::<Vec<_>>();
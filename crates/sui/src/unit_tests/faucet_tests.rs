@@ -0,0 +1,116 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use axum::extract::Path;
+use axum::http::StatusCode as HttpStatusCode;
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use serde_json::{json, Value};
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// Serves `app` on a local port, and returns its URL.
+fn start_faucet(app: Router) -> String {
+    let server =
+        axum::Server::bind(&SocketAddr::from(([127, 0, 0, 1], 0))).serve(app.into_make_service());
+    let url = format!("http://{}", server.local_addr());
+    tokio::spawn(server);
+    url
+}
+
+fn coin(amount: u64) -> Value {
+    json!({
+        "amount": amount,
+        "id": ObjectID::ZERO,
+        "transfer_tx_digest": TransactionDigest::genesis(),
+    })
+}
+
+#[tokio::test]
+async fn test_request_gas() {
+    // The faucet is overloaded at first, then queues the request and serves it.
+    let requests = Arc::new(AtomicUsize::new(0));
+    let polls = Arc::new(AtomicUsize::new(0));
+    let app = Router::new()
+        .route(
+            "/v1/gas",
+            post({
+                let requests = requests.clone();
+                move || async move {
+                    if requests.fetch_add(1, Ordering::SeqCst) == 0 {
+                        return (HttpStatusCode::TOO_MANY_REQUESTS, Json(json!({})));
+                    }
+                    (HttpStatusCode::ACCEPTED, Json(json!({ "task": "task" })))
+                }
+            }),
+        )
+        .route(
+            "/v1/status/:task",
+            get({
+                let polls = polls.clone();
+                move |Path(task): Path<String>| async move {
+                    assert_eq!(task, "task");
+                    let status = if polls.fetch_add(1, Ordering::SeqCst) == 0 {
+                        json!({ "status": "INPROGRESS" })
+                    } else {
+                        json!({
+                            "status": "SUCCEEDED",
+                            "transferred_gas_objects": { "sent": [coin(10), coin(20)] },
+                        })
+                    };
+                    Json(json!({ "status": status }))
+                }
+            }),
+        );
+    let url = start_faucet(app);
+
+    let coins = request_gas(&url, SuiAddress::ZERO).await.unwrap();
+    assert_eq!(
+        coins.iter().map(|coin| coin.amount).collect::<Vec<_>>(),
+        vec![10, 20]
+    );
+    assert_eq!(requests.load(Ordering::SeqCst), 2);
+    assert_eq!(polls.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn test_request_gas_legacy() {
+    // A faucet without the v1 API.
+    let app = Router::new().route(
+        "/gas",
+        post(|| async { Json(json!({ "transferred_gas_objects": [coin(10)] })) }),
+    );
+    let url = start_faucet(app);
+
+    let coins = request_gas(&format!("{url}/gas"), SuiAddress::ZERO)
+        .await
+        .unwrap();
+    assert_eq!(coins.len(), 1);
+    assert_eq!(coins[0].amount, 10);
+}
+
+#[tokio::test]
+async fn test_request_gas_is_not_sent_again_after_server_error() {
+    // The faucet may have acted on the request before failing.
+    let requests = Arc::new(AtomicUsize::new(0));
+    let app = Router::new().route(
+        "/v1/gas",
+        post({
+            let requests = requests.clone();
+            move || async move {
+                requests.fetch_add(1, Ordering::SeqCst);
+                (
+                    HttpStatusCode::INTERNAL_SERVER_ERROR,
+                    Json(json!({ "error": "failed" })),
+                )
+            }
+        }),
+    );
+    let url = start_faucet(app);
+
+    let err = request_gas(&url, SuiAddress::ZERO).await.unwrap_err();
+    assert!(err.to_string().contains("failed"), "{err}");
+    assert_eq!(requests.load(Ordering::SeqCst), 1);
+}
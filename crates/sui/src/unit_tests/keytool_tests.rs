@@ -7,11 +7,13 @@ use crate::keytool::read_authority_keypair_from_file;
 use crate::keytool::read_keypair_from_file;
 
 use super::write_keypair_to_file;
+use super::AliasCommand;
 use super::KeyToolCommand;
 use fastcrypto::encoding::Base64;
 use fastcrypto::encoding::Encoding;
 use rand::rngs::StdRng;
 use rand::SeedableRng;
+use sui_keys::address_book::{AddressBook, AddressOrAlias};
 use sui_keys::keystore::{AccountKeystore, FileBasedKeystore, InMemKeystore, Keystore};
 use sui_types::base_types::ObjectDigest;
 use sui_types::base_types::ObjectID;
@@ -379,3 +381,111 @@ fn test_sign_command() -> Result<(), anyhow::Error> {
     .execute(&mut keystore)?;
     Ok(())
 }
+
+#[test]
+fn test_json_output() -> Result<(), anyhow::Error> {
+    let mut keystore = Keystore::from(InMemKeystore::new(1));
+    let pk = keystore.keys().pop().unwrap();
+    let sender = SuiAddress::from(&pk);
+
+    let output = serde_json::to_value(KeyToolCommand::List.execute(&mut keystore)?)?;
+    assert_eq!(
+        output,
+        serde_json::json!([{
+            "suiAddress": sender,
+            "publicBase64Key": pk.encode_base64(),
+            "keyScheme": "ed25519",
+            "flag": SignatureScheme::ED25519.flag(),
+        }])
+    );
+
+    let gas = (
+        ObjectID::random(),
+        SequenceNumber::new(),
+        ObjectDigest::random(),
+    );
+    let tx_data = TransactionData::new_pay_sui_with_dummy_gas_price(
+        sender,
+        vec![gas],
+        vec![SuiAddress::random_for_testing_only()],
+        vec![10000],
+        gas,
+        1000,
+    );
+    let output = serde_json::to_value(
+        KeyToolCommand::Sign {
+            address: sender,
+            data: Base64::encode(bcs::to_bytes(&tx_data)?),
+            intent: None,
+        }
+        .execute(&mut keystore)?,
+    )?;
+    let signature = keystore.sign_secure(&sender, &tx_data, Intent::default())?;
+    assert_eq!(output["suiSignature"], signature.encode_base64());
+    Ok(())
+}
+
+#[test]
+fn test_alias_commands() -> Result<(), anyhow::Error> {
+    let temp_dir = TempDir::new().unwrap();
+    let keystore_path = temp_dir.path().join("sui.keystore");
+    let mut keystore = Keystore::from(FileBasedKeystore::new(&keystore_path)?);
+    let alice = SuiAddress::random_for_testing_only();
+    let bob = SuiAddress::random_for_testing_only();
+
+    let set = |alias: &str, address| KeyToolCommand::Alias {
+        cmd: AliasCommand::Set {
+            alias: alias.to_string(),
+            address,
+        },
+    };
+    set("alice", alice).execute(&mut keystore)?;
+    set("bob", alice).execute(&mut keystore)?;
+    let output = serde_json::to_value(set("bob", bob).execute(&mut keystore)?)?;
+    assert_eq!(
+        output,
+        serde_json::json!({ "alias": "bob", "suiAddress": bob, "replaced": alice })
+    );
+
+    // Aliases that could be mistaken for an address are rejected.
+    assert!(set("0xb0b", bob).execute(&mut keystore).is_err());
+    assert!(set(&bob.to_string(), bob).execute(&mut keystore).is_err());
+    assert!(set("bob smith", bob).execute(&mut keystore).is_err());
+
+    let address_book = AddressBook::load(&temp_dir.path().join("sui.aliases"))?;
+    assert_eq!(address_book.get("alice"), Some(alice));
+    assert_eq!(address_book.aliases_of(&bob), vec!["bob"]);
+    assert_eq!(
+        address_book.resolve(&AddressOrAlias::from_str("bob")?)?,
+        bob
+    );
+    assert_eq!(
+        address_book.resolve(&AddressOrAlias::from_str(&alice.to_string())?)?,
+        alice
+    );
+    assert!(address_book
+        .resolve(&AddressOrAlias::from_str("carol")?)
+        .is_err());
+
+    KeyToolCommand::Alias {
+        cmd: AliasCommand::Remove {
+            alias: "alice".to_string(),
+        },
+    }
+    .execute(&mut keystore)?;
+    let output = serde_json::to_value(
+        KeyToolCommand::Alias {
+            cmd: AliasCommand::List,
+        }
+        .execute(&mut keystore)?,
+    )?;
+    assert_eq!(
+        output,
+        serde_json::json!([{ "alias": "bob", "suiAddress": bob }])
+    );
+
+    // Only file keystores have an address book.
+    let mut keystore = Keystore::from(InMemKeystore::new(1));
+    assert!(set("alice", alice).execute(&mut keystore).is_err());
+    Ok(())
+}
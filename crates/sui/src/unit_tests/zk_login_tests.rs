@@ -0,0 +1,57 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use ring::signature::{RsaPublicKeyComponents, RSA_PKCS1_2048_8192_SHA256};
+
+fn decode_base64url(encoded: &str) -> Vec<u8> {
+    base64::decode_config(encoded, base64::URL_SAFE_NO_PAD).unwrap()
+}
+
+#[test]
+fn test_ephemeral_key() {
+    let key = generate_ephemeral_key(10).unwrap();
+    let keypair = SuiKeyPair::decode_base64(&key.keypair).unwrap();
+    assert!(matches!(keypair, SuiKeyPair::Ed25519(_)));
+    assert_eq!(keypair.public().encode_base64(), key.public_base64_key);
+    assert_eq!(key.max_epoch, 10);
+    key.randomness.parse::<u128>().unwrap();
+}
+
+#[test]
+fn test_jwt_is_signed_by_test_key() {
+    let TestJwt { jwt, jwk } = issue_test_jwt("user", "client", "nonce").unwrap();
+    let parts: Vec<_> = jwt.split('.').collect();
+    assert_eq!(parts.len(), 3);
+
+    let header: serde_json::Value = serde_json::from_slice(&decode_base64url(parts[0])).unwrap();
+    assert_eq!(header["alg"], "RS256");
+    assert_eq!(header["kid"], jwk["kid"]);
+    let claims: serde_json::Value = serde_json::from_slice(&decode_base64url(parts[1])).unwrap();
+    assert_eq!(claims["iss"], TEST_ISSUER);
+    assert_eq!(claims["aud"], "client");
+    assert_eq!(claims["sub"], "user");
+    assert_eq!(claims["nonce"], "nonce");
+
+    // The JWT verifies with the published JWK.
+    let public_key = RsaPublicKeyComponents {
+        n: decode_base64url(jwk["n"].as_str().unwrap()),
+        e: decode_base64url(jwk["e"].as_str().unwrap()),
+    };
+    let message = format!("{}.{}", parts[0], parts[1]);
+    public_key
+        .verify(
+            &RSA_PKCS1_2048_8192_SHA256,
+            message.as_bytes(),
+            &decode_base64url(parts[2]),
+        )
+        .unwrap();
+    // And not with a tampered payload.
+    assert!(public_key
+        .verify(
+            &RSA_PKCS1_2048_8192_SHA256,
+            format!("{message}x").as_bytes(),
+            &decode_base64url(parts[2]),
+        )
+        .is_err());
+}
@@ -0,0 +1,457 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, ensure};
+use clap::*;
+use fastcrypto::traits::{KeyPair, ToFromBytes};
+use multiaddr::Multiaddr;
+use serde_json::json;
+use sui_json::SuiJsonValue;
+use sui_keys::address_book::AddressOrAlias;
+use sui_keys::keypair_file::{read_authority_keypair_from_file, read_keypair_from_file};
+use sui_types::base_types::{ObjectID, SuiAddress};
+use sui_types::crypto::{generate_proof_of_possession, SuiKeyPair};
+use sui_types::sui_system_state::sui_system_state_summary::SuiSystemStateSummary;
+use sui_types::sui_system_state::SUI_SYSTEM_MODULE_NAME;
+use sui_types::{SUI_FRAMEWORK_ADDRESS, SUI_SYSTEM_STATE_OBJECT_ID};
+
+use crate::client_commands::{
+    execute_move_call, move_call_data, SuiClientCommandResult, TransactionOptions, WalletContext,
+};
+
+/// The highest commission rate, in basis points.
+pub const MAX_COMMISSION_RATE: u64 = 10_000;
+
+// The longest values the system state accepts for the metadata of a validator.
+const MAX_NAME_LENGTH: usize = 128;
+const MAX_DESCRIPTION_LENGTH: usize = 150;
+const MAX_ADDRESS_LENGTH: usize = 128;
+
+/// Commands for validator operators, sent from the account of the validator: the active address,
+/// or the owner of the gas object.
+#[allow(clippy::large_enum_variant)]
+#[derive(Parser)]
+#[clap(rename_all = "kebab-case")]
+pub enum SuiValidatorCommand {
+    /// Register the account as a validator candidate, which can then be staked with. The key
+    /// pairs are read from files as written by `sui keytool generate`.
+    #[clap(name = "become-candidate")]
+    BecomeCandidate {
+        /// Path to the BLS12381 protocol key pair, as written by `sui keytool generate bls12381`
+        #[clap(long)]
+        protocol_key_file: PathBuf,
+        /// Path to the Ed25519 network key pair, Base64 encoded `flag || privkey`
+        #[clap(long)]
+        network_key_file: PathBuf,
+        /// Path to the Ed25519 worker key pair, Base64 encoded `flag || privkey`
+        #[clap(long)]
+        worker_key_file: PathBuf,
+        #[clap(long)]
+        name: String,
+        #[clap(long, default_value = "")]
+        description: String,
+        #[clap(long, default_value = "")]
+        image_url: String,
+        #[clap(long, default_value = "")]
+        project_url: String,
+        /// Network address of the validator, such as `/dns/validator.example.com/tcp/8080/http`
+        #[clap(long)]
+        network_address: Multiaddr,
+        /// Address of the peer-to-peer network, such as `/dns/validator.example.com/udp/8084`
+        #[clap(long)]
+        p2p_address: Multiaddr,
+        /// Address of the Narwhal primary
+        #[clap(long)]
+        primary_address: Multiaddr,
+        /// Address of the Narwhal worker
+        #[clap(long)]
+        worker_address: Multiaddr,
+        /// The gas price quoted by the validator, in MIST
+        #[clap(long)]
+        gas_price: u64,
+        /// The commission rate on the rewards of the stake, in basis points (10000 is 100%)
+        #[clap(long)]
+        commission_rate: u64,
+        #[clap(flatten)]
+        gas: GasArgs,
+    },
+
+    /// Withdraw the candidacy of the account.
+    #[clap(name = "leave-candidacy")]
+    LeaveCandidacy {
+        #[clap(flatten)]
+        gas: GasArgs,
+    },
+
+    /// Join the committee from the next epoch, once the candidate has at least the minimum
+    /// validator stake.
+    #[clap(name = "join-committee")]
+    JoinCommittee {
+        #[clap(flatten)]
+        gas: GasArgs,
+    },
+
+    /// Leave the committee at the end of the epoch.
+    #[clap(name = "leave-committee")]
+    LeaveCommittee {
+        #[clap(flatten)]
+        gas: GasArgs,
+    },
+
+    /// Update the metadata of the validator. Network addresses and keys take effect from the
+    /// next epoch.
+    #[clap(name = "update-metadata")]
+    UpdateMetadata {
+        #[clap(subcommand)]
+        metadata: MetadataUpdate,
+        #[clap(flatten)]
+        gas: GasArgs,
+    },
+
+    /// Quote the gas price the validator is to use from the next epoch, which the reference gas
+    /// price is computed from.
+    #[clap(name = "set-gas-price")]
+    SetGasPrice {
+        /// The gas price, in MIST
+        gas_price: u64,
+        #[clap(flatten)]
+        gas: GasArgs,
+    },
+
+    /// Report another validator of the committee as misbehaving or not performing, which
+    /// withholds its rewards when a quorum of validators reports it.
+    #[clap(name = "report-validator")]
+    ReportValidator {
+        /// Address or alias of the validator to report
+        reportee: AddressOrAlias,
+        /// Withdraw a report made before instead
+        #[clap(long)]
+        undo: bool,
+        #[clap(flatten)]
+        gas: GasArgs,
+    },
+}
+
+#[derive(Subcommand)]
+#[clap(rename_all = "kebab-case")]
+pub enum MetadataUpdate {
+    Name {
+        name: String,
+    },
+    Description {
+        description: String,
+    },
+    ImageUrl {
+        image_url: String,
+    },
+    ProjectUrl {
+        project_url: String,
+    },
+    NetworkAddress {
+        network_address: Multiaddr,
+    },
+    P2pAddress {
+        p2p_address: Multiaddr,
+    },
+    PrimaryAddress {
+        primary_address: Multiaddr,
+    },
+    WorkerAddress {
+        worker_address: Multiaddr,
+    },
+    /// Path to the new BLS12381 protocol key pair, as written by `sui keytool generate bls12381`
+    ProtocolKey {
+        file: PathBuf,
+    },
+    /// Path to the new Ed25519 network key pair, Base64 encoded `flag || privkey`
+    NetworkKey {
+        file: PathBuf,
+    },
+    /// Path to the new Ed25519 worker key pair, Base64 encoded `flag || privkey`
+    WorkerKey {
+        file: PathBuf,
+    },
+}
+
+/// The gas payment of a validator command, whose owner is the validator account sending it.
+#[derive(Args, Debug, Clone, Copy)]
+pub struct GasArgs {
+    /// ID of the gas object for gas payment, in 20 bytes Hex string
+    /// If not provided, a gas object with at least gas_budget value will be selected
+    #[clap(long)]
+    pub gas: Option<ObjectID>,
+
+    /// Gas budget for this call
+    #[clap(long)]
+    pub gas_budget: u64,
+
+    #[clap(flatten)]
+    pub options: TransactionOptions,
+}
+
+impl SuiValidatorCommand {
+    pub async fn execute(
+        self,
+        context: &mut WalletContext,
+    ) -> Result<SuiClientCommandResult, anyhow::Error> {
+        let (gas, function, args) = match self {
+            SuiValidatorCommand::BecomeCandidate {
+                protocol_key_file,
+                network_key_file,
+                worker_key_file,
+                name,
+                description,
+                image_url,
+                project_url,
+                network_address,
+                p2p_address,
+                primary_address,
+                worker_address,
+                gas_price,
+                commission_rate,
+                gas,
+            } => {
+                ensure!(
+                    commission_rate <= MAX_COMMISSION_RATE,
+                    "The commission rate is in basis points, at most {MAX_COMMISSION_RATE}"
+                );
+                let sender = validator_address(context, &gas).await?;
+                let system_state = get_system_state(context).await?;
+                ensure!(
+                    !is_active_validator(&system_state, sender),
+                    "{sender} is already an active validator"
+                );
+                let (protocol_pubkey, proof_of_possession) =
+                    protocol_key(&protocol_key_file, sender)?;
+                let args = vec![
+                    bytes_arg(&protocol_pubkey)?,
+                    bytes_arg(&ed25519_public_key(&network_key_file, "Network")?)?,
+                    bytes_arg(&ed25519_public_key(&worker_key_file, "Worker")?)?,
+                    bytes_arg(&proof_of_possession)?,
+                    bytes_arg(&text("name", &name, MAX_NAME_LENGTH)?)?,
+                    bytes_arg(&text("description", &description, MAX_DESCRIPTION_LENGTH)?)?,
+                    bytes_arg(&text("image URL", &image_url, usize::MAX)?)?,
+                    bytes_arg(&text("project URL", &project_url, usize::MAX)?)?,
+                    bytes_arg(&multiaddr("network", &network_address)?)?,
+                    bytes_arg(&multiaddr("p2p", &p2p_address)?)?,
+                    bytes_arg(&multiaddr("primary", &primary_address)?)?,
+                    bytes_arg(&multiaddr("worker", &worker_address)?)?,
+                    SuiJsonValue::new(json!(gas_price))?,
+                    SuiJsonValue::new(json!(commission_rate))?,
+                ];
+                (gas, "request_add_validator_candidate", args)
+            }
+            SuiValidatorCommand::LeaveCandidacy { gas } => {
+                (gas, "request_remove_validator_candidate", vec![])
+            }
+            SuiValidatorCommand::JoinCommittee { gas } => {
+                let sender = validator_address(context, &gas).await?;
+                let system_state = get_system_state(context).await?;
+                ensure!(
+                    !is_active_validator(&system_state, sender),
+                    "{sender} is already an active validator"
+                );
+                (gas, "request_add_validator", vec![])
+            }
+            SuiValidatorCommand::LeaveCommittee { gas } => {
+                ensure_active_validator(context, &gas).await?;
+                (gas, "request_remove_validator", vec![])
+            }
+            SuiValidatorCommand::UpdateMetadata { metadata, gas } => {
+                let (function, args) = match metadata {
+                    MetadataUpdate::Name { name } => (
+                        "update_validator_name",
+                        vec![bytes_arg(&text("name", &name, MAX_NAME_LENGTH)?)?],
+                    ),
+                    MetadataUpdate::Description { description } => (
+                        "update_validator_description",
+                        vec![bytes_arg(&text(
+                            "description",
+                            &description,
+                            MAX_DESCRIPTION_LENGTH,
+                        )?)?],
+                    ),
+                    MetadataUpdate::ImageUrl { image_url } => (
+                        "update_validator_image_url",
+                        vec![bytes_arg(&text("image URL", &image_url, usize::MAX)?)?],
+                    ),
+                    MetadataUpdate::ProjectUrl { project_url } => (
+                        "update_validator_project_url",
+                        vec![bytes_arg(&text("project URL", &project_url, usize::MAX)?)?],
+                    ),
+                    MetadataUpdate::NetworkAddress { network_address } => (
+                        "update_validator_next_epoch_network_address",
+                        vec![bytes_arg(&multiaddr("network", &network_address)?)?],
+                    ),
+                    MetadataUpdate::P2pAddress { p2p_address } => (
+                        "update_validator_next_epoch_p2p_address",
+                        vec![bytes_arg(&multiaddr("p2p", &p2p_address)?)?],
+                    ),
+                    MetadataUpdate::PrimaryAddress { primary_address } => (
+                        "update_validator_next_epoch_primary_address",
+                        vec![bytes_arg(&multiaddr("primary", &primary_address)?)?],
+                    ),
+                    MetadataUpdate::WorkerAddress { worker_address } => (
+                        "update_validator_next_epoch_worker_address",
+                        vec![bytes_arg(&multiaddr("worker", &worker_address)?)?],
+                    ),
+                    MetadataUpdate::ProtocolKey { file } => {
+                        let sender = validator_address(context, &gas).await?;
+                        let (pubkey, proof_of_possession) = protocol_key(&file, sender)?;
+                        (
+                            "update_validator_next_epoch_protocol_pubkey",
+                            vec![bytes_arg(&pubkey)?, bytes_arg(&proof_of_possession)?],
+                        )
+                    }
+                    MetadataUpdate::NetworkKey { file } => (
+                        "update_validator_next_epoch_network_pubkey",
+                        vec![bytes_arg(&ed25519_public_key(&file, "Network")?)?],
+                    ),
+                    MetadataUpdate::WorkerKey { file } => (
+                        "update_validator_next_epoch_worker_pubkey",
+                        vec![bytes_arg(&ed25519_public_key(&file, "Worker")?)?],
+                    ),
+                };
+                (gas, function, args)
+            }
+            SuiValidatorCommand::SetGasPrice { gas_price, gas } => {
+                ensure_active_validator(context, &gas).await?;
+                let args = vec![SuiJsonValue::new(json!(gas_price))?];
+                (gas, "request_set_gas_price", args)
+            }
+            SuiValidatorCommand::ReportValidator {
+                reportee,
+                undo,
+                gas,
+            } => {
+                let reportee = context.resolve_address(&reportee)?;
+                let (sender, system_state) = ensure_active_validator(context, &gas).await?;
+                ensure!(sender != reportee, "Validators cannot report themselves");
+                ensure!(
+                    is_active_validator(&system_state, reportee),
+                    "{reportee} is not an active validator"
+                );
+                let args = vec![SuiJsonValue::new(json!(reportee))?];
+                let function = if undo {
+                    "undo_report_validator"
+                } else {
+                    "report_validator"
+                };
+                (gas, function, args)
+            }
+        };
+        send(context, gas, function, args).await
+    }
+}
+
+/// Calls `function` of the system state module with the system state object and `args`.
+async fn send(
+    context: &mut WalletContext,
+    gas: GasArgs,
+    function: &str,
+    args: Vec<SuiJsonValue>,
+) -> Result<SuiClientCommandResult, anyhow::Error> {
+    let args = std::iter::once(SuiJsonValue::from_object_id(SUI_SYSTEM_STATE_OBJECT_ID))
+        .chain(args)
+        .collect();
+    let (sender, data) = move_call_data(
+        ObjectID::from(SUI_FRAMEWORK_ADDRESS),
+        SUI_SYSTEM_MODULE_NAME.as_str(),
+        function,
+        vec![],
+        gas.gas,
+        gas.gas_budget,
+        args,
+        context,
+    )
+    .await?;
+    if let Some(preview) = gas.options.preview(context, sender, &data).await? {
+        return Ok(preview);
+    }
+    let response = execute_move_call(sender, data, context).await?;
+    Ok(SuiClientCommandResult::Call(response))
+}
+
+/// The validator account sending the command.
+async fn validator_address(
+    context: &mut WalletContext,
+    gas: &GasArgs,
+) -> Result<SuiAddress, anyhow::Error> {
+    match context.try_get_object_owner(&gas.gas).await? {
+        Some(owner) => Ok(owner),
+        None => context.active_address(),
+    }
+}
+
+async fn get_system_state(context: &WalletContext) -> Result<SuiSystemStateSummary, anyhow::Error> {
+    let client = context.get_client().await?;
+    Ok(client
+        .governance_api()
+        .get_latest_sui_system_state()
+        .await?)
+}
+
+fn is_active_validator(system_state: &SuiSystemStateSummary, address: SuiAddress) -> bool {
+    system_state
+        .active_validators
+        .iter()
+        .any(|validator| validator.sui_address == address)
+}
+
+/// Checks that the sender of the command is an active validator, returning it with the system
+/// state.
+async fn ensure_active_validator(
+    context: &mut WalletContext,
+    gas: &GasArgs,
+) -> Result<(SuiAddress, SuiSystemStateSummary), anyhow::Error> {
+    let sender = validator_address(context, gas).await?;
+    let system_state = get_system_state(context).await?;
+    ensure!(
+        is_active_validator(&system_state, sender),
+        "{sender} is not an active validator"
+    );
+    Ok((sender, system_state))
+}
+
+/// The public key of the BLS12381 protocol key pair in `file`, with its proof of possession for
+/// the account `sender`.
+fn protocol_key(file: &Path, sender: SuiAddress) -> Result<(Vec<u8>, Vec<u8>), anyhow::Error> {
+    let key_pair = read_authority_keypair_from_file(file)?;
+    let proof_of_possession = generate_proof_of_possession(&key_pair, sender);
+    Ok((
+        key_pair.public().as_bytes().to_vec(),
+        proof_of_possession.as_bytes().to_vec(),
+    ))
+}
+
+fn ed25519_public_key(file: &Path, key: &str) -> Result<Vec<u8>, anyhow::Error> {
+    match read_keypair_from_file(file)? {
+        SuiKeyPair::Ed25519(key_pair) => Ok(key_pair.public().as_bytes().to_vec()),
+        _ => Err(anyhow!("{key} keys must be Ed25519 keys")),
+    }
+}
+
+/// The bytes of a text field of the metadata, which must be ASCII.
+fn text(field: &str, value: &str, max_length: usize) -> Result<Vec<u8>, anyhow::Error> {
+    ensure!(value.is_ascii(), "The {field} must only contain ASCII");
+    ensure!(
+        value.len() <= max_length,
+        "The {field} must be at most {max_length} characters long"
+    );
+    Ok(value.as_bytes().to_vec())
+}
+
+fn multiaddr(field: &str, address: &Multiaddr) -> Result<Vec<u8>, anyhow::Error> {
+    let bytes = address.to_vec();
+    ensure!(
+        bytes.len() <= MAX_ADDRESS_LENGTH,
+        "The {field} address must be at most {MAX_ADDRESS_LENGTH} bytes long"
+    );
+    Ok(bytes)
+}
+
+fn bytes_arg(bytes: &[u8]) -> Result<SuiJsonValue, anyhow::Error> {
+    SuiJsonValue::new(json!(bytes))
+}
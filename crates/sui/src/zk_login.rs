@@ -0,0 +1,148 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Helpers for exercising zkLogin flows against localnet without an OpenID provider, built with
+//! the `zk-login-test-utils` feature: ephemeral keys, and JWTs signed by a test provider. The
+//! private key of the test provider is published with this crate, so its JWTs must only ever be
+//! trusted in tests.
+
+use anyhow::anyhow;
+use fastcrypto::encoding::{Base64, Encoding};
+use ring::rand::{SecureRandom, SystemRandom};
+use ring::signature::{RsaKeyPair, RSA_PKCS1_SHA256};
+use serde::Serialize;
+use serde_json::json;
+use std::time::{SystemTime, UNIX_EPOCH};
+use sui_types::committee::EpochId;
+use sui_types::crypto::{get_key_pair, EncodeDecodeBase64, SuiKeyPair};
+
+#[cfg(test)]
+#[path = "unit_tests/zk_login_tests.rs"]
+mod zk_login_tests;
+
+/// The issuer of the JWTs of the test provider.
+pub const TEST_ISSUER: &str = "https://oauth.sui.io";
+/// The ID of the key of the test provider, in the header of its JWTs and in its JWK.
+pub const TEST_KEY_ID: &str = "sui-test-key";
+/// The modulus of the key of the test provider, Base64url encoded as in its JWK.
+const TEST_RSA_MODULUS: &str = concat!(
+    "yxkt74yfV3-3dU6aE_EJqZHjRx0jN6Zcn2_ctWQ1oTq-3w2umwyUYaHj0DbzP-pn6JF2IHcjnNdEbH62W7b4Og2Z",
+    "3OXeh7JqxIiyd7n_hd8tEFDPCpjNaYN_9odkbqnkIC14_1AKAHnQ0lnj9SRzpWOAVnoQw4rr7MLxaFyYuQzX7t-",
+    "KJEg7gKjSzkte_URTyr5roofr4jS9mF335o9TIbzTMkySu1pFiV4IWR3dExx1SDEHvWQDI9jTpudJYJ0QtN9jY43",
+    "1JR_l88UygjgzXjZG9OWFf99fIyjlsl8Qtxw0ORk7MYbmvUSNqO1wmFv-3xR-yzcaiGMGH5BluT8dEQ",
+);
+/// The public exponent of the key of the test provider, Base64url encoded as in its JWK.
+const TEST_RSA_EXPONENT: &str = "AQAB";
+/// The PKCS#8 private key of the test provider, Base64 encoded.
+const TEST_RSA_PRIVATE_KEY: &str = concat!(
+    "MIIEvgIBADANBgkqhkiG9w0BAQEFAASCBKgwggSkAgEAAoIBAQDLGS3vjJ9Xf7d1TpoT8QmpkeNHHSM3plyfb9y1",
+    "ZDWhOr7fDa6bDJRhoePQNvM/6mfokXYgdyOc10RsfrZbtvg6DZnc5d6HsmrEiLJ3uf+F3y0QUM8KmM1pg3/2h2Ru",
+    "qeQgLXj/UAoAedDSWeP1JHOlY4BWehDDiuvswvFoXJi5DNfu34okSDuAqNLOS179RFPKvmuih+viNL2YXffmj1Mh",
+    "vNMyTJK7WkWJXghZHd0THHVIMQe9ZAMj2NOm50lgnRC032NjjfUlH+XzxTKCODNeNkb05YV/318jKOWyXxC3HDQ5",
+    "GTsxhua9RI2o7XCYW/7fFH7LNxqIYwYfkGW5Px0RAgMBAAECggEAYIEH4zYB9r0girR9IPGdAj5obL+LHuPFShsI",
+    "mAZUN1anIGyrZVAuZ0/Z5HNf1SSIGCmaJCYIPXvKLvVsqBrCSy9wR1bQjND5y4YaqyotoMphasnC/0mI/VR3JsjB",
+    "r5W5aJLC1ioNfpljYLhGyZJ3AwTLFAwvUAMARIGC3PB2vyJiqAUCnbHYTSlpF6l67vz8t8xqMtfL7kxsELo6AWsm",
+    "sivi1YFHriS0gUCZanCYP0HAU7yTSQKtej3K9aLW4y/95RCSbxFOGiCR7r1J5Nw5X6mu9Sxw7bfb+lzbVQwBLjf3",
+    "qH+oFIZFvpef7b/XfAQ/T7FJT8vpHKqckPLaxKk9awKBgQDw8f5YFMSik3dbLh9bAAWKRz3KqU3tP1KzVAw+CdNd",
+    "U4IylY3d9X/aPiaT1P03HMCdG1U4EBBYDb0HwIsuL6Xr2XqXz/4sZ2JOkAQp35rlouBEAfuIki18vwlkeb/fpZx5",
+    "Ka5yx8NOiSg8X054kHDSJWg0fUT/bRe4sBGZu9Ca0wKBgQDXyc+HJftZkZhjwm551KSy5EwYASwHhaz3u1mZghDP",
+    "bqx+ZOI11PjiWyj2701VxhHcUINY0vU3KD/qw/QI5DvAAf+4bdOBeLFQJ2H6xQR/W4vzi1Urht3dMZhhw9rYFaMM",
+    "XUDTzCbgAEaAnrIkWKpla6o8KfxwsE15l9TSWT/yCwKBgBY9L82vdQrQc6bN1gUPNnOx6akoAVJRku+MZWjgfUrS",
+    "52SF20dOXpOlQuhIM2r/02Ns32vCR8/ozLbHddE4gqcHNsKGZx5wGGnJ7yhyRBjH9M0yJaZQX7Rw6YwFlMkuvweL",
+    "6lcbyxIXpMgVv+du5Yrv+vNFULYGurbIQYegBzwTAoGBAM5fFldlTsYxhTFbILbpU/42ckO+0zH6eRqPlbhG+Pa5",
+    "HMTgjKr5HHG2xy2qXSa8ABbY9VT8pEH0/3PxpbDfPfl6r+rdNhsrp0BF+eGPjKaUkfsAdRrkp8jEhrxxSBnCT6Yi",
+    "hT5Jj6BJAyQ9IZe7KHNIjY1uhhvrsJyqRYJKkupTAoGBAJgv8pb1P7KhmJaC5uSi08NKWMCgSLAUG6zoAHsncDRh",
+    "KLuyQOvTIZokZ262rMttzqiAOjp6l5nR85ZaPq6LoaJ319alBuHP64t6pwjW/KYn8/BSTFfgKIAB6DDkuaJIuQZq",
+    "FDDh79ZkSsP2SlVFPTlgYaI82tQEmBjXLnXKy7Fp",
+);
+/// How long the JWTs of the test provider are valid for, in seconds.
+const TEST_JWT_VALIDITY_SECS: u64 = 3600;
+
+/// An ephemeral key pair, with the last epoch it is valid in and the randomness that the nonce
+/// of the JWT of the flow is derived from, along with both.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EphemeralKey {
+    /// `flag || privkey` encoded in Base64.
+    pub keypair: String,
+    /// `flag || pk` encoded in Base64.
+    pub public_base64_key: String,
+    pub max_epoch: EpochId,
+    /// 128 random bits, in decimal.
+    pub randomness: String,
+}
+
+/// A JWT of the test provider, with the JWK to verify it with.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TestJwt {
+    pub jwt: String,
+    pub jwk: serde_json::Value,
+}
+
+/// Generates an Ed25519 ephemeral key pair, valid until the end of `max_epoch`.
+pub fn generate_ephemeral_key(max_epoch: EpochId) -> Result<EphemeralKey, anyhow::Error> {
+    let keypair = SuiKeyPair::Ed25519(get_key_pair().1);
+    let mut randomness = [0u8; 16];
+    SystemRandom::new()
+        .fill(&mut randomness)
+        .map_err(|_| anyhow!("Failed to generate randomness"))?;
+    Ok(EphemeralKey {
+        public_base64_key: keypair.public().encode_base64(),
+        keypair: keypair.encode_base64(),
+        max_epoch,
+        randomness: u128::from_be_bytes(randomness).to_string(),
+    })
+}
+
+/// Issues a JWT of the test provider for the user `sub` of the client `aud`, committing to
+/// `nonce`, valid for an hour from now.
+pub fn issue_test_jwt(sub: &str, aud: &str, nonce: &str) -> Result<TestJwt, anyhow::Error> {
+    let issued_at = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+    let header = json!({ "alg": "RS256", "kid": TEST_KEY_ID, "typ": "JWT" });
+    let claims = json!({
+        "iss": TEST_ISSUER,
+        "aud": aud,
+        "sub": sub,
+        "nonce": nonce,
+        "iat": issued_at,
+        "exp": issued_at + TEST_JWT_VALIDITY_SECS,
+    });
+    let message = format!(
+        "{}.{}",
+        base64url(&serde_json::to_vec(&header)?),
+        base64url(&serde_json::to_vec(&claims)?)
+    );
+
+    let key_pair = RsaKeyPair::from_pkcs8(&Base64::decode(TEST_RSA_PRIVATE_KEY)?)
+        .map_err(|e| anyhow!("Invalid key of the test provider: {e}"))?;
+    let mut signature = vec![0; key_pair.public_modulus_len()];
+    key_pair
+        .sign(
+            &RSA_PKCS1_SHA256,
+            &SystemRandom::new(),
+            message.as_bytes(),
+            &mut signature,
+        )
+        .map_err(|_| anyhow!("Failed to sign the JWT"))?;
+    Ok(TestJwt {
+        jwt: format!("{message}.{}", base64url(&signature)),
+        jwk: test_jwk(),
+    })
+}
+
+/// The JWK of the key of the test provider, as OpenID providers publish them.
+pub fn test_jwk() -> serde_json::Value {
+    json!({
+        "kty": "RSA",
+        "kid": TEST_KEY_ID,
+        "alg": "RS256",
+        "use": "sig",
+        "n": TEST_RSA_MODULUS,
+        "e": TEST_RSA_EXPONENT,
+    })
+}
+
+fn base64url(bytes: &[u8]) -> String {
+    base64::encode_config(bytes, base64::URL_SAFE_NO_PAD)
+}
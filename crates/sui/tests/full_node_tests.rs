@@ -11,14 +11,18 @@ use move_core_types::parser::parse_struct_tag;
 use move_core_types::value::MoveStructLayout;
 use mysten_metrics::RegistryService;
 use prometheus::Registry;
-use sui::client_commands::{SuiClientCommandResult, SuiClientCommands, WalletContext};
+use sui::client_commands::{
+    SuiClientCommandResult, SuiClientCommands, TransactionOptions, WalletContext,
+};
+use sui_config::utils::new_tcp_network_address;
 use sui_json_rpc_types::{
-    type_and_fields_from_move_struct, EventPage, SuiEvent, SuiEventEnvelope, SuiEventFilter,
-    SuiExecutionStatus, SuiMoveStruct, SuiMoveValue, SuiTransactionEffectsAPI,
+    type_and_fields_from_move_struct, EventPage, SuiCheckpointData, SuiEvent, SuiEventEnvelope,
+    SuiEventFilter, SuiExecutionStatus, SuiMoveStruct, SuiMoveValue, SuiTransactionEffectsAPI,
     SuiTransactionResponse,
 };
 use sui_keys::keystore::AccountKeystore;
 use sui_macros::*;
+use sui_network::api::FullnodeClient;
 use sui_node::SuiNode;
 use sui_types::base_types::{ObjectRef, SequenceNumber};
 use sui_types::crypto::{get_key_pair, SuiKeyPair};
@@ -27,8 +31,10 @@ use sui_types::event::Event;
 use sui_types::message_envelope::Message;
 use sui_types::messages::{
     ExecuteTransactionRequest, ExecuteTransactionRequestType, ExecuteTransactionResponse, GasData,
-    QuorumDriverResponse, SingleTransactionKind, TransactionData, TransactionKind, TransferObject,
+    GetObjectRequest, QuorumDriverResponse, SingleTransactionKind, TransactionData,
+    TransactionEffectsAPI, TransactionKind, TransferObject,
 };
+use sui_types::messages_checkpoint::CheckpointRequest;
 use sui_types::object::{Object, ObjectRead, Owner, PastObjectRead};
 use sui_types::query::{EventQuery, TransactionQuery};
 use sui_types::utils::to_sender_signed_transaction_with_multi_signers;
@@ -525,6 +531,7 @@ async fn test_full_node_sync_flood() -> Result<(), anyhow::Error> {
                         coin_id: object_to_split.0,
                         gas: Some(gas_object_id),
                         gas_budget: 50000,
+                        options: TransactionOptions::default(),
                     }
                     .execute(context)
                     .await
@@ -676,6 +683,104 @@ async fn test_full_node_sub_and_query_move_event_ok() -> Result<(), anyhow::Erro
     Ok(())
 }
 
+#[sim_test]
+async fn test_full_node_sub_checkpoint_data_ok() -> Result<(), anyhow::Error> {
+    let mut test_cluster = TestClusterBuilder::new().build().await?;
+    let fullnode = test_cluster.start_fullnode().await?;
+    let node = fullnode.sui_node;
+    let ws_client = fullnode.ws_client;
+
+    let mut sub: Subscription<SuiCheckpointData> = ws_client
+        .subscribe(
+            "sui_subscribeCheckpointData",
+            rpc_params![0],
+            "sui_unsubscribeCheckpointData",
+        )
+        .await
+        .unwrap();
+
+    let context = &mut test_cluster.wallet;
+    let (transferred_object, _, _, digest, _, _) = transfer_coin(context).await?;
+    wait_for_tx(digest, node.state().clone()).await;
+
+    // Checkpoints are streamed in order from genesis until the one with the transfer.
+    let mut expected_seq = 0;
+    loop {
+        let data = match timeout(Duration::from_secs(60), sub.next()).await {
+            Ok(Some(Ok(data))) => data,
+            other => panic!("Failed to get checkpoint data, but {:?}", other),
+        };
+        assert_eq!(data.checkpoint.sequence_number, expected_seq);
+        assert_eq!(data.checkpoint.transactions.len(), data.transactions.len());
+        expected_seq += 1;
+
+        let Some(tx) = data
+            .transactions
+            .iter()
+            .find(|tx| tx.effects.transaction_digest() == &digest) else {
+            continue;
+        };
+        assert!(tx
+            .input_objects
+            .iter()
+            .any(|o| o.object_id == transferred_object));
+        assert!(tx
+            .output_objects
+            .iter()
+            .any(|o| o.object_id == transferred_object));
+        break;
+    }
+
+    Ok(())
+}
+
+#[sim_test]
+async fn test_full_node_grpc_service_ok() -> Result<(), anyhow::Error> {
+    let mut test_cluster = TestClusterBuilder::new().build().await?;
+    let address = new_tcp_network_address();
+    let mut config = test_cluster.fullnode_config_builder().build().unwrap();
+    config.fullnode_grpc_address = Some(address.clone());
+    let node = start_fullnode_from_config(config).await.unwrap().sui_node;
+
+    let context = &mut test_cluster.wallet;
+    let (transferred_object, _, receiver, digest, _, _) = transfer_coin(context).await?;
+    wait_for_tx(digest, node.state().clone()).await;
+
+    let channel = mysten_network::client::connect(&address).await?;
+    let mut client = FullnodeClient::new(channel);
+
+    let response = client
+        .get_transaction(TransactionInfoRequest {
+            transaction_digest: digest,
+        })
+        .await?
+        .into_inner();
+    assert_eq!(response.effects.transaction_digest(), &digest);
+
+    let response = client
+        .get_object(GetObjectRequest {
+            object_id: transferred_object,
+            version: None,
+        })
+        .await?
+        .into_inner();
+    assert_eq!(
+        response.object.unwrap().owner,
+        Owner::AddressOwner(receiver)
+    );
+
+    let response = client
+        .get_checkpoint(CheckpointRequest {
+            sequence_number: Some(0),
+            request_content: false,
+        })
+        .await?
+        .into_inner();
+    assert_eq!(response.checkpoint.unwrap().summary.sequence_number, 0);
+
+    Ok(())
+}
+
 // Test fullnode has event read jsonrpc endpoints working
 #[sim_test]
 async fn test_full_node_event_read_api_ok() {
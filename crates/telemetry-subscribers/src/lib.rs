@@ -34,6 +34,7 @@
 //! ## Features
 //! - `json` - Bunyan formatter - JSON log output, optional
 //! - `tokio-console` - [Tokio-console](https://github.com/tokio-rs/console) subscriber, optional
+//! - `otlp` - export spans to an [OpenTelemetry](https://opentelemetry.io) collector over OTLP, optional
 //!
 //! ### Stdout vs file output
 //!
@@ -64,6 +65,13 @@
 //! separate from `RUST_LOG`, so that you can separately control the logging verbosity from the level of
 //! spans that are to be recorded and traced.
 //!
+//! ### Distributed tracing with OpenTelemetry
+//!
+//! With the `otlp` feature enabled, spans can be exported to any collector that speaks OTLP over gRPC
+//! (Jaeger, Tempo, the OpenTelemetry collector, ...) by setting `otlp` in the config, see [`OtlpConfig`].
+//! The same `span_level` filter applies as for span latencies.  Spans still buffered for export are
+//! flushed when the [`TelemetryGuards`] are dropped.
+//!
 //! ### Live async inspection / Tokio Console
 //!
 //! [Tokio-console](https://github.com/tokio-rs/console) is an awesome CLI tool designed to analyze and help debug Rust apps using Tokio, in real time!  It relies on a special subscriber.
@@ -129,6 +137,20 @@ pub struct TelemetryConfig {
     pub crash_on_panic: bool,
     /// Optional Prometheus registry - if present, all enabled span latencies are measured
     pub prom_registry: Option<prometheus::Registry>,
+    /// If defined, export spans over OTLP.  Requires the `otlp` crate feature
+    pub otlp: Option<OtlpConfig>,
+}
+
+/// Where and how to export spans over OTLP
+#[derive(Clone, Debug)]
+pub struct OtlpConfig {
+    /// gRPC endpoint of the collector, ex http://localhost:4317
+    pub endpoint: String,
+    /// Fraction of traces to sample, between 0.0 and 1.0.  Traces continued from a remote parent
+    /// follow the sampling decision of the parent
+    pub sample_ratio: f64,
+    /// Reported as the `service.name` resource attribute
+    pub service_name: String,
 }
 
 #[must_use]
@@ -137,6 +159,14 @@ pub struct TelemetryGuards {
     worker_guard: WorkerGuard,
 }
 
+impl Drop for TelemetryGuards {
+    fn drop(&mut self) {
+        // Export the spans still buffered in the batch span processor.
+        #[cfg(feature = "otlp")]
+        opentelemetry::global::shutdown_tracer_provider();
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct FilterHandle(reload::Handle<EnvFilter, Registry>);
 
@@ -214,6 +244,7 @@ impl TelemetryConfig {
             panic_hook: true,
             crash_on_panic: false,
             prom_registry: None,
+            otlp: None,
         }
     }
 
@@ -242,6 +273,11 @@ impl TelemetryConfig {
         self
     }
 
+    pub fn with_otlp(mut self, otlp: OtlpConfig) -> Self {
+        self.otlp = Some(otlp);
+        self
+    }
+
     pub fn with_env(mut self) -> Self {
         if env::var("CRASH_ON_PANIC").is_ok() {
             self.crash_on_panic = true
@@ -304,6 +340,18 @@ impl TelemetryConfig {
             layers.push(span_lat_layer.with_filter(span_filter.clone()).boxed());
         }
 
+        // OpenTelemetry layer
+        // The batch exporter runs on the Tokio runtime, so this must be called from within one.
+        #[cfg(feature = "otlp")]
+        if let Some(otlp) = &config.otlp {
+            layers.push(
+                tracing_opentelemetry::layer()
+                    .with_tracer(otlp_tracer(otlp))
+                    .with_filter(span_filter.clone())
+                    .boxed(),
+            );
+        }
+
         let (nb_output, worker_guard) = get_output(config.log_file.clone());
         if config.json_log_output {
             // Output to file or to stderr in a newline-delimited JSON format
@@ -339,6 +387,33 @@ impl TelemetryConfig {
     }
 }
 
+#[cfg(feature = "otlp")]
+fn otlp_tracer(otlp: &OtlpConfig) -> opentelemetry::sdk::trace::Tracer {
+    use opentelemetry::sdk::trace::{self, Sampler};
+    use opentelemetry::sdk::Resource;
+    use opentelemetry::KeyValue;
+    use opentelemetry_otlp::WithExportConfig;
+
+    let sampler = Sampler::ParentBased(Box::new(Sampler::TraceIdRatioBased(otlp.sample_ratio)));
+    opentelemetry_otlp::new_pipeline()
+        .tracing()
+        .with_exporter(
+            opentelemetry_otlp::new_exporter()
+                .tonic()
+                .with_endpoint(otlp.endpoint.clone()),
+        )
+        .with_trace_config(
+            trace::config()
+                .with_sampler(sampler)
+                .with_resource(Resource::new(vec![KeyValue::new(
+                    "service.name",
+                    otlp.service_name.clone(),
+                )])),
+        )
+        .install_batch(opentelemetry::runtime::Tokio)
+        .expect("Could not initialize OTLP span exporter")
+}
+
 /// Globally set a tracing subscriber suitable for testing environments
 pub fn init_for_testing() {
     use once_cell::sync::Lazy;
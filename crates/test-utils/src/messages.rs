@@ -50,7 +50,8 @@ pub async fn get_account_and_gas_coins(
     let accounts = context.config.keystore.addresses();
     for address in accounts {
         let result = SuiClientCommands::Gas {
-            address: Some(address),
+            address: Some(address.into()),
+            cmd: None,
         }
         .execute(context)
         .await?;
@@ -304,6 +304,9 @@ impl TestClusterBuilder {
             alias: "localnet".to_string(),
             rpc: fullnode_handle.rpc_url.clone(),
             ws: Some(fullnode_handle.ws_url.clone()),
+            faucet: None,
+            keystore: None,
+            active_address: None,
         });
         wallet_conf.active_env = Some("localnet".to_string());
 
@@ -367,6 +370,7 @@ impl TestClusterBuilder {
             envs: Default::default(),
             active_address,
             active_env: Default::default(),
+            shared_keystore: None,
         }
         .save(wallet_path)?;
 
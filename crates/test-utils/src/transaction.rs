@@ -7,7 +7,7 @@ use std::path::PathBuf;
 use tracing::{debug, info};
 
 use sui::client_commands::WalletContext;
-use sui::client_commands::{SuiClientCommandResult, SuiClientCommands};
+use sui::client_commands::{SuiClientCommandResult, SuiClientCommands, TransactionOptions};
 use sui_config::ValidatorInfo;
 use sui_core::authority_client::AuthorityAPI;
 pub use sui_core::test_utils::{compile_basics_package, wait_for_all_txes, wait_for_tx};
@@ -242,6 +242,7 @@ pub async fn create_devnet_nft(
         url: Some("https://sui.io/_nuxt/img/sui-logo.8d3c44e.svg".into()),
         gas: Some(*gas_object),
         gas_budget: Some(GAS_BUDGET),
+        options: TransactionOptions::default(),
     }
     .execute(context)
     .await?;
@@ -280,10 +281,11 @@ pub async fn transfer_sui(
         .unwrap();
 
     let res = SuiClientCommands::TransferSui {
-        to: receiver,
+        to: receiver.into(),
         amount: None,
         sui_coin_object_id: gas_ref.0,
         gas_budget: GAS_BUDGET,
+        options: TransactionOptions::default(),
     }
     .execute(context)
     .await?;
@@ -325,10 +327,11 @@ pub async fn transfer_coin(
         object_to_send, sender, receiver
     );
     let res = SuiClientCommands::Transfer {
-        to: receiver,
+        to: receiver.into(),
         object_id: object_to_send,
         gas: None,
         gas_budget: GAS_BUDGET,
+        options: TransactionOptions::default(),
     }
     .execute(context)
     .await?;
@@ -361,6 +364,7 @@ pub async fn split_coin_with_wallet_context(context: &mut WalletContext, coin_id
         count: Some(2),
         gas: None,
         gas_budget: MAX_GAS,
+        options: TransactionOptions::default(),
     }
     .execute(context)
     .await
@@ -421,6 +421,10 @@ pub fn derive_dbmap_utils_general(input: TokenStream) -> TokenStream {
                             )*
                         ]
                     };
+                    let opt_cfs = opt_cfs.map(|(table, options)| {
+                        let options = typed_store::rocks::table_options::apply_table_options_overrides(stringify!(#name), &table, options);
+                        (table, options)
+                    });
                     // Safe to call unwrap because we will have at least one field_name entry in the struct
                     let rwopt_cfs: std::collections::HashMap<String, typed_store::rocks::ReadWriteOptions> = opt_cfs.iter().map(|q| (q.0.as_str().to_string(), q.1.rw_options.clone())).collect();
                     let opt_cfs: Vec<_> = opt_cfs.iter().map(|q| (q.0.as_str(), &q.1.options)).collect();
@@ -493,6 +497,25 @@ pub fn derive_dbmap_utils_general(input: TokenStream) -> TokenStream {
                 }
             }
 
+            /// Opens a set of tables as a secondary instance of the DB at `primary_path`
+            /// Any number of processes can do this, also while another process has the DB open in read-write mode
+            /// Writes fail, and writes made through the primary only become visible after `try_catch_up_with_primary`
+            /// `secondary_path` holds the files of the secondary instance, and must not be shared with another instance
+            #[allow(unused_parens)]
+            pub fn open_tables_secondary(
+                primary_path: std::path::PathBuf,
+                secondary_path: std::path::PathBuf,
+                metric_conf: typed_store::rocks::MetricConf,
+                global_db_options_override: Option<rocksdb::Options>,
+            ) -> Self {
+                let inner = #intermediate_db_map_struct_name::open_tables_impl(primary_path, Some(secondary_path), false, metric_conf, global_db_options_override, None);
+                Self {
+                    #(
+                        #field_names: #post_process_fn(inner.#field_names),
+                    )*
+                }
+            }
+
             /// Returns a list of the tables name and type pairs
             pub fn describe_tables() -> std::collections::BTreeMap<String, (String, String)> {
                 vec![#(
@@ -569,6 +592,22 @@ pub fn derive_dbmap_utils_general(input: TokenStream) -> TokenStream {
                 })
             }
 
+            /// Dump the key-value pairs of the given table selected by `filter`, formatted as `format`
+            /// Tables must be opened in read only mode using `open_tables_read_only`
+            pub fn dump_entries(&self, table_name: &str, filter: &typed_store::rocks::DumpFilter,
+                format: typed_store::rocks::DumpFormat) -> eyre::Result<Vec<(String, String)>> {
+                match table_name {
+                    #(
+                        stringify!(#field_names) => {
+                            typed_store::traits::Map::try_catch_up_with_primary(&self.#field_names)?;
+                            typed_store::rocks::dump_entries(typed_store::traits::Map::iter(&self.#field_names), filter, format)
+                        }
+                    )*
+
+                    _ => eyre::bail!("No such table name: {}", table_name),
+                }
+            }
+
             /// Get key value sizes from the db
             /// Tables must be opened in read only mode using `open_tables_read_only`
             pub fn table_summary(&self, table_name: &str) -> eyre::Result<typed_store::traits::TableSummary> {
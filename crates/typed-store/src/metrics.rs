@@ -86,6 +86,10 @@ pub struct ColumnFamilyMetrics {
     pub rocksdb_estimate_oldest_key_time: IntGaugeVec,
     pub rocskdb_background_errors: IntGaugeVec,
     pub rocksdb_estimated_num_keys: IntGaugeVec,
+    pub rocksdb_estimate_pending_compaction_bytes: IntGaugeVec,
+    pub rocksdb_live_sst_files_size: IntGaugeVec,
+    pub rocksdb_cur_size_active_mem_table: IntGaugeVec,
+    pub rocksdb_num_immutable_mem_table: IntGaugeVec,
 }
 
 impl ColumnFamilyMetrics {
@@ -220,6 +224,37 @@ impl ColumnFamilyMetrics {
                 registry,
             )
             .unwrap(),
+            rocksdb_estimate_pending_compaction_bytes: register_int_gauge_vec_with_registry!(
+                "rocksdb_estimate_pending_compaction_bytes",
+                "Estimated total number of bytes compaction needs to rewrite to get all levels
+                down to under target size. Writes are slowed down, then stopped, when it grows
+                past the soft and hard pending compaction bytes limits.",
+                &["cf_name"],
+                registry,
+            )
+            .unwrap(),
+            rocksdb_live_sst_files_size: register_int_gauge_vec_with_registry!(
+                "rocksdb_live_sst_files_size",
+                "The total size of the SST files of the latest version of the column family,
+                which excludes the files kept for snapshots and iterators.",
+                &["cf_name"],
+                registry,
+            )
+            .unwrap(),
+            rocksdb_cur_size_active_mem_table: register_int_gauge_vec_with_registry!(
+                "rocksdb_cur_size_active_mem_table",
+                "The approximate size of the active memtable of the column family, in bytes.",
+                &["cf_name"],
+                registry,
+            )
+            .unwrap(),
+            rocksdb_num_immutable_mem_table: register_int_gauge_vec_with_registry!(
+                "rocksdb_num_immutable_mem_table",
+                "The number of immutable memtables of the column family waiting to be flushed.",
+                &["cf_name"],
+                registry,
+            )
+            .unwrap(),
 
         }
     }
@@ -807,6 +842,11 @@ pub struct DBMetrics {
     pub rocksdb_mem_table_usage: IntGaugeVec,
     pub rocksdb_unflushed_mem_table_usage: IntGaugeVec,
     pub rocksdb_table_readers_usage: IntGaugeVec,
+    pub rocksdb_block_cache_hit: IntGaugeVec,
+    pub rocksdb_block_cache_miss: IntGaugeVec,
+    pub rocksdb_stall_micros: IntGaugeVec,
+    pub rocksdb_compact_read_bytes: IntGaugeVec,
+    pub rocksdb_compact_write_bytes: IntGaugeVec,
 }
 
 static ONCE: OnceCell<Arc<DBMetrics>> = OnceCell::new();
@@ -839,6 +879,42 @@ impl DBMetrics {
                 registry,
             )
             .unwrap(),
+            rocksdb_block_cache_hit: register_int_gauge_vec_with_registry!(
+                "rocksdb_block_cache_hit",
+                "The accumulated number of reads served from the block cache of the db",
+                &["db_name"],
+                registry,
+            )
+            .unwrap(),
+            rocksdb_block_cache_miss: register_int_gauge_vec_with_registry!(
+                "rocksdb_block_cache_miss",
+                "The accumulated number of reads that missed the block cache of the db",
+                &["db_name"],
+                registry,
+            )
+            .unwrap(),
+            rocksdb_stall_micros: register_int_gauge_vec_with_registry!(
+                "rocksdb_stall_micros",
+                "The accumulated time writes to the db were stalled, waiting for flushes or
+                compactions, in microseconds",
+                &["db_name"],
+                registry,
+            )
+            .unwrap(),
+            rocksdb_compact_read_bytes: register_int_gauge_vec_with_registry!(
+                "rocksdb_compact_read_bytes",
+                "The accumulated number of bytes read by compactions of the db",
+                &["db_name"],
+                registry,
+            )
+            .unwrap(),
+            rocksdb_compact_write_bytes: register_int_gauge_vec_with_registry!(
+                "rocksdb_compact_write_bytes",
+                "The accumulated number of bytes written by compactions of the db",
+                &["db_name"],
+                registry,
+            )
+            .unwrap(),
         }
     }
     pub fn init(registry: &Registry) -> &'static Arc<DBMetrics> {
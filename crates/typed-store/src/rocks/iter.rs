@@ -85,6 +85,13 @@ impl<'a, K: Serialize, V> Iter<'a, K, V> {
         Ok(self)
     }
 
+    /// Skips all the elements whose serialized key is smaller than `raw_key`, for seeking to a
+    /// prefix of the serialized keys.
+    pub fn skip_to_raw(mut self, raw_key: &[u8]) -> Self {
+        self.db_iter.seek(raw_key);
+        self
+    }
+
     /// Moves the iterator the element given or
     /// the one prior to it if it does not exist. If there is
     /// no element prior to it, it returns an empty iterator.
@@ -3,6 +3,7 @@
 mod errors;
 pub(crate) mod iter;
 pub(crate) mod keys;
+pub mod table_options;
 pub mod util;
 pub(crate) mod values;
 
@@ -21,12 +22,15 @@ use rocksdb::{
 use serde::{de::DeserializeOwned, Serialize};
 use std::{
     borrow::Borrow,
-    collections::BTreeMap,
+    collections::{BTreeMap, HashMap},
     env, fs,
     marker::PhantomData,
     path::{Path, PathBuf},
-    sync::Arc,
-    time::Duration,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
 };
 use tap::TapFallible;
 use tokio::sync::oneshot;
@@ -46,6 +50,17 @@ const DEFAULT_DB_WRITE_BUFFER_SIZE: usize = 512;
 const ENV_VAR_DB_WAL_SIZE: &str = "MYSTEN_DB_WAL_SIZE_MB";
 const DEFAULT_DB_WAL_SIZE: usize = 1024;
 
+// Statistics tickers of RocksDB reported as metrics, see `report_metrics`.
+const BLOCK_CACHE_HIT_TICKER: &str = "rocksdb.block.cache.hit";
+const BLOCK_CACHE_MISS_TICKER: &str = "rocksdb.block.cache.miss";
+const STALL_MICROS_TICKER: &str = "rocksdb.stall.micros";
+const COMPACT_READ_BYTES_TICKER: &str = "rocksdb.compact.read.bytes";
+const COMPACT_WRITE_BYTES_TICKER: &str = "rocksdb.compact.write.bytes";
+
+// Whether the dbs opened with the default options collect statistics, see
+// `set_db_statistics_enabled`.
+static DB_STATISTICS_ENABLED: AtomicBool = AtomicBool::new(false);
+
 #[cfg(test)]
 mod tests;
 
@@ -168,6 +183,7 @@ pub struct DBWithThreadModeWrapper {
     pub underlying: rocksdb::DBWithThreadMode<MultiThreaded>,
     pub metric_conf: MetricConf,
     pub db_path: PathBuf,
+    statistics_reported_at: Mutex<Option<Instant>>,
 }
 
 #[derive(Debug)]
@@ -175,6 +191,7 @@ pub struct OptimisticTransactionDBWrapper {
     pub underlying: rocksdb::OptimisticTransactionDB<MultiThreaded>,
     pub metric_conf: MetricConf,
     pub db_path: PathBuf,
+    statistics_reported_at: Mutex<Option<Instant>>,
 }
 
 /// Thin wrapper to unify interface across different db types
@@ -217,6 +234,10 @@ impl RocksDB {
         delegate_call!(self.multi_get_cf_opt(keys, readopts))
     }
 
+    pub fn property_value(&self, name: impl CStrLike) -> Result<Option<String>, rocksdb::Error> {
+        delegate_call!(self.property_value(name))
+    }
+
     pub fn property_int_value_cf(
         &self,
         cf: &impl AsColumnFamilyRef,
@@ -377,6 +398,25 @@ impl RocksDB {
         delegate_call!(self.compact_range_cf(cf, start, end))
     }
 
+    /// Compacts the whole of column family `cf_name`, blocking until it is done.
+    pub fn compact_cf(&self, cf_name: &str) -> Result<(), TypedStoreError> {
+        let cf = self
+            .cf_handle(cf_name)
+            .ok_or_else(|| TypedStoreError::UnregisteredColumn(cf_name.to_owned()))?;
+        self.compact_range_cf(&cf, None::<Vec<u8>>, None);
+        Ok(())
+    }
+
+    /// The total size of the SST files of column family `cf_name`, most of its size on disk.
+    pub fn cf_sst_files_size(&self, cf_name: &str) -> Result<u64, TypedStoreError> {
+        let cf = self
+            .cf_handle(cf_name)
+            .ok_or_else(|| TypedStoreError::UnregisteredColumn(cf_name.to_owned()))?;
+        self.property_int_value_cf(&cf, properties::TOTAL_SST_FILES_SIZE)
+            .map(Option::unwrap_or_default)
+            .map_err(|e| TypedStoreError::RocksDBError(e.into_string()))
+    }
+
     pub fn flush(&self) -> Result<(), rocksdb::Error> {
         delegate_call!(self.flush())
     }
@@ -457,6 +497,26 @@ impl RocksDB {
         }
     }
 
+    /// The statistics of the db, when it collects them, at most once per report period of the
+    /// metrics of its column families, however many of them report metrics.
+    fn statistics_to_report(&self) -> Option<String> {
+        let reported_at = match self {
+            Self::DBWithThreadMode(d) => &d.statistics_reported_at,
+            Self::OptimisticTransactionDB(d) => &d.statistics_reported_at,
+        };
+        {
+            let mut reported_at = reported_at.lock().unwrap();
+            let period = Duration::from_millis(CF_METRICS_REPORT_PERIOD_MILLIS / 2);
+            if matches!(*reported_at, Some(at) if at.elapsed() < period) {
+                return None;
+            }
+            *reported_at = Some(Instant::now());
+        }
+        self.property_value(properties::OPTIONS_STATISTICS)
+            .ok()
+            .flatten()
+    }
+
     fn default_db_name(&self) -> String {
         self.path()
             .file_name()
@@ -860,7 +920,42 @@ impl<K, V> DBMap<K, V> {
                 Self::get_int_property(rocksdb, &cf, properties::BACKGROUND_ERRORS)
                     .unwrap_or(METRICS_ERROR),
             );
+        db_metrics
+            .cf_metrics
+            .rocksdb_estimate_pending_compaction_bytes
+            .with_label_values(&[cf_name])
+            .set(
+                Self::get_int_property(rocksdb, &cf, properties::ESTIMATE_PENDING_COMPACTION_BYTES)
+                    .unwrap_or(METRICS_ERROR),
+            );
+        db_metrics
+            .cf_metrics
+            .rocksdb_live_sst_files_size
+            .with_label_values(&[cf_name])
+            .set(
+                Self::get_int_property(rocksdb, &cf, properties::LIVE_SST_FILES_SIZE)
+                    .unwrap_or(METRICS_ERROR),
+            );
+        db_metrics
+            .cf_metrics
+            .rocksdb_cur_size_active_mem_table
+            .with_label_values(&[cf_name])
+            .set(
+                Self::get_int_property(rocksdb, &cf, properties::CUR_SIZE_ACTIVE_MEM_TABLE)
+                    .unwrap_or(METRICS_ERROR),
+            );
+        db_metrics
+            .cf_metrics
+            .rocksdb_num_immutable_mem_table
+            .with_label_values(&[cf_name])
+            .set(
+                Self::get_int_property(rocksdb, &cf, properties::NUM_IMMUTABLE_MEM_TABLE)
+                    .unwrap_or(METRICS_ERROR),
+            );
         let db_name = rocksdb.db_name();
+        if let Some(statistics) = rocksdb.statistics_to_report() {
+            Self::report_statistics(&statistics, &db_name, db_metrics);
+        }
         if let RocksDB::DBWithThreadMode(ref rocksdb) = **rocksdb {
             let mem_usage_stats =
                 rocksdb::perf::get_memory_usage_stats(Some(&[&rocksdb.underlying]), None);
@@ -894,6 +989,30 @@ impl<K, V> DBMap<K, V> {
         }
     }
 
+    fn report_statistics(statistics: &str, db_name: &str, db_metrics: &Arc<DBMetrics>) {
+        let tickers = statistics_tickers(statistics);
+        for (gauge, ticker) in [
+            (&db_metrics.rocksdb_block_cache_hit, BLOCK_CACHE_HIT_TICKER),
+            (
+                &db_metrics.rocksdb_block_cache_miss,
+                BLOCK_CACHE_MISS_TICKER,
+            ),
+            (&db_metrics.rocksdb_stall_micros, STALL_MICROS_TICKER),
+            (
+                &db_metrics.rocksdb_compact_read_bytes,
+                COMPACT_READ_BYTES_TICKER,
+            ),
+            (
+                &db_metrics.rocksdb_compact_write_bytes,
+                COMPACT_WRITE_BYTES_TICKER,
+            ),
+        ] {
+            gauge
+                .with_label_values(&[db_name])
+                .set(tickers.get(ticker).copied().unwrap_or(METRICS_ERROR));
+        }
+    }
+
     pub fn transaction(&self) -> Result<DBTransaction<'_>, TypedStoreError> {
         DBTransaction::new(&self.rocksdb)
     }
@@ -1795,6 +1914,25 @@ pub struct DBOptions {
     pub rw_options: ReadWriteOptions,
 }
 
+/// Reads the counts of the tickers of the statistics of a db, formatted by RocksDB with a line
+/// per ticker, as in `rocksdb.block.cache.hit COUNT : 42`. Histograms are left out.
+fn statistics_tickers(statistics: &str) -> HashMap<&str, i64> {
+    statistics
+        .lines()
+        .filter_map(|line| {
+            let (ticker, count) = line.split_once(" COUNT : ")?;
+            Some((ticker, count.trim().parse().ok()?))
+        })
+        .collect()
+}
+
+/// Makes the dbs opened from now on in the process with the default options collect the
+/// statistics of RocksDB, which are reported as metrics. Collecting them slows every operation
+/// of the dbs down a little, so they are not collected unless enabled.
+pub fn set_db_statistics_enabled(enabled: bool) {
+    DB_STATISTICS_ENABLED.store(enabled, Ordering::Relaxed);
+}
+
 /// Creates a default RocksDB option, to be used when RocksDB option is not specified..
 pub fn default_db_options() -> DBOptions {
     let mut opt = rocksdb::Options::default();
@@ -1819,6 +1957,10 @@ pub fn default_db_options() -> DBOptions {
     // According to docs, we almost certainly want to set this to number of cores to not be bottlenecked
     // by rocksdb
     opt.increase_parallelism((num_cpus::get() as i32) / 8);
+    // Statistics are reported as metrics, to relate latencies to cache misses and write stalls.
+    if DB_STATISTICS_ENABLED.load(Ordering::Relaxed) {
+        opt.enable_statistics();
+    }
     DBOptions {
         options: opt,
         rw_options: ReadWriteOptions::default(),
@@ -1900,6 +2042,7 @@ pub fn open_cf_opts<P: AsRef<Path>>(
                 underlying: rocksdb,
                 metric_conf,
                 db_path: PathBuf::from(path),
+                statistics_reported_at: Mutex::new(None),
             },
         )))
     })
@@ -1929,6 +2072,7 @@ pub fn open_cf_opts_transactional<P: AsRef<Path>>(
                 underlying: rocksdb,
                 metric_conf,
                 db_path: PathBuf::from(path),
+                statistics_reported_at: Mutex::new(None),
             },
         )))
     })
@@ -1991,6 +2135,7 @@ pub fn open_cf_opts_secondary<P: AsRef<Path>>(
                 underlying: rocksdb,
                 metric_conf,
                 db_path: secondary_path,
+                statistics_reported_at: Mutex::new(None),
             },
         )))
     })
@@ -2041,6 +2186,66 @@ impl DBMapTableConfigMap {
     }
 }
 
+/// How [`dump_entries`] formats keys and values.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DumpFormat {
+    /// With their `Debug` representation.
+    Debug,
+    /// As JSON.
+    Json,
+    /// In hex, keys as stored in the table and values BCS encoded.
+    BcsHex,
+}
+
+/// Selects the entries of a table to dump.
+#[derive(Clone, Debug, Default)]
+pub struct DumpFilter {
+    /// Only dumps the entries whose key, as stored in the table, starts with these bytes.
+    pub key_prefix: Vec<u8>,
+    /// The number of matching entries to skip.
+    pub skip: usize,
+    /// The maximum number of entries to dump.
+    pub limit: usize,
+}
+
+/// Dumps the entries of `iter` selected by `filter`, in the order of the table.
+pub fn dump_entries<K, V>(
+    iter: Iter<'_, K, V>,
+    filter: &DumpFilter,
+    format: DumpFormat,
+) -> eyre::Result<Vec<(String, String)>>
+where
+    K: Serialize + DeserializeOwned + std::fmt::Debug,
+    V: Serialize + DeserializeOwned + std::fmt::Debug,
+{
+    let mut entries = vec![];
+    for (key, value) in iter.skip_to_raw(&filter.key_prefix) {
+        if entries.len() >= filter.skip + filter.limit {
+            break;
+        }
+        let raw_key = be_fix_int_ser(&key)?;
+        if !raw_key.starts_with(&filter.key_prefix) {
+            break;
+        }
+        entries.push((raw_key, key, value));
+    }
+    entries
+        .into_iter()
+        .skip(filter.skip)
+        .map(|(raw_key, key, value)| {
+            Ok(match format {
+                DumpFormat::Debug => (format!("{key:?}"), format!("{value:?}")),
+                DumpFormat::Json => (serde_json::to_string(&key)?, serde_json::to_string(&value)?),
+                DumpFormat::BcsHex => (to_hex(&raw_key), to_hex(&bcs::to_bytes(&value)?)),
+            })
+        })
+        .collect()
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
 pub enum RocksDBAccessType {
     Primary,
     Secondary(Option<PathBuf>),
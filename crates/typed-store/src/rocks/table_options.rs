@@ -0,0 +1,152 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Overrides of the RocksDB options of tables, read from a file so that tables can be tuned
+//! without rebuilding. The file maps the names of the structs deriving `DBMapUtils` to their
+//! tables, as in:
+//!
+//! ```yaml
+//! AuthorityPerpetualTables:
+//!   objects:
+//!     block-cache-size-mb: 1024
+//!     compression: zstd
+//!     compaction-style: universal
+//! ```
+//!
+//! Once installed with [`TableOptionsOverrides::install`], the overrides are applied on top of
+//! the default options of the tables whenever they are opened.
+
+use super::DBOptions;
+use once_cell::sync::Lazy;
+use rocksdb::{BlockBasedOptions, Cache, DBCompactionStyle, DBCompressionType};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::RwLock;
+
+static INSTALLED_OVERRIDES: Lazy<RwLock<TableOptionsOverrides>> = Lazy::new(Default::default);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Compression {
+    None,
+    Snappy,
+    Lz4,
+    Zstd,
+    Zlib,
+}
+
+impl From<Compression> for DBCompressionType {
+    fn from(compression: Compression) -> Self {
+        match compression {
+            Compression::None => DBCompressionType::None,
+            Compression::Snappy => DBCompressionType::Snappy,
+            Compression::Lz4 => DBCompressionType::Lz4,
+            Compression::Zstd => DBCompressionType::Zstd,
+            Compression::Zlib => DBCompressionType::Zlib,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum CompactionStyle {
+    Level,
+    Universal,
+    Fifo,
+}
+
+impl From<CompactionStyle> for DBCompactionStyle {
+    fn from(style: CompactionStyle) -> Self {
+        match style {
+            CompactionStyle::Level => DBCompactionStyle::Level,
+            CompactionStyle::Universal => DBCompactionStyle::Universal,
+            CompactionStyle::Fifo => DBCompactionStyle::Fifo,
+        }
+    }
+}
+
+/// The options of a table to override, the others keep their default.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct TableOptions {
+    /// Size of a block cache dedicated to the table. It replaces the block based table options
+    /// of the table, so `bloom-filter-bits-per-key` must be set again if they had a bloom filter.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_cache_size_mb: Option<usize>,
+    /// Only applies along with `block-cache-size-mb`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bloom_filter_bits_per_key: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compression: Option<Compression>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compaction_style: Option<CompactionStyle>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub write_buffer_size_mb: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_write_buffer_number: Option<i32>,
+}
+
+impl TableOptions {
+    pub fn apply(&self, mut options: DBOptions) -> DBOptions {
+        if let Some(size_mb) = self.block_cache_size_mb {
+            let mut block_options = BlockBasedOptions::default();
+            block_options.set_block_cache(&Cache::new_lru_cache(size_mb << 20));
+            if let Some(bits_per_key) = self.bloom_filter_bits_per_key {
+                block_options.set_bloom_filter(bits_per_key, false);
+            }
+            options
+                .options
+                .set_block_based_table_factory(&block_options);
+        }
+        if let Some(compression) = self.compression {
+            options.options.set_compression_type(compression.into());
+        }
+        if let Some(style) = self.compaction_style {
+            options.options.set_compaction_style(style.into());
+        }
+        if let Some(size_mb) = self.write_buffer_size_mb {
+            options.options.set_write_buffer_size(size_mb << 20);
+        }
+        if let Some(number) = self.max_write_buffer_number {
+            options.options.set_max_write_buffer_number(number);
+        }
+        options
+    }
+}
+
+/// The options to override per table, per struct of tables.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+#[serde(transparent)]
+pub struct TableOptionsOverrides(BTreeMap<String, BTreeMap<String, TableOptions>>);
+
+impl TableOptionsOverrides {
+    pub fn load(path: &Path) -> eyre::Result<Self> {
+        let content = std::fs::read_to_string(path)?;
+        serde_yaml::from_str(&content)
+            .map_err(|e| eyre::eyre!("Invalid DB options file {}: {e}", path.display()))
+    }
+
+    pub fn get(&self, db_name: &str, table_name: &str) -> Option<&TableOptions> {
+        self.0.get(db_name)?.get(table_name)
+    }
+
+    /// Applies the overrides to the tables opened from now on in the process, replacing the
+    /// overrides installed before.
+    pub fn install(self) {
+        *INSTALLED_OVERRIDES.write().unwrap() = self;
+    }
+}
+
+/// Applies the installed overrides of table `table_name` of `db_name`, the struct holding the
+/// table, to `options`.
+pub fn apply_table_options_overrides(
+    db_name: &str,
+    table_name: &str,
+    options: DBOptions,
+) -> DBOptions {
+    match INSTALLED_OVERRIDES.read().unwrap().get(db_name, table_name) {
+        Some(overrides) => overrides.apply(options),
+        None => options,
+    }
+}
@@ -1048,6 +1048,41 @@ async fn refcount_with_compaction_test() {
     assert_eq!(value.value, object.value);
 }
 
+#[test]
+fn test_statistics_tickers() {
+    let statistics = "rocksdb.block.cache.miss COUNT : 12
+rocksdb.block.cache.hit COUNT : 345
+rocksdb.block.cache.index.hit COUNT : 6
+rocksdb.db.get.micros P50 : 1.000000 P95 : 2.000000 COUNT : 7 SUM : 8
+";
+    let tickers = statistics_tickers(statistics);
+    assert_eq!(tickers.get(BLOCK_CACHE_HIT_TICKER), Some(&345));
+    assert_eq!(tickers.get(BLOCK_CACHE_MISS_TICKER), Some(&12));
+    assert_eq!(tickers.get(STALL_MICROS_TICKER), None);
+    assert_eq!(tickers.get("rocksdb.db.get.micros"), None);
+}
+
+#[tokio::test]
+async fn test_statistics_are_reported_once_per_db() {
+    set_db_statistics_enabled(true);
+    // Opened without tables, whose metrics would be reported in the background.
+    let db = open_cf(temp_dir(), None, MetricConf::default(), &["table"]).unwrap();
+    set_db_statistics_enabled(false);
+    let cf = db.cf_handle("table").unwrap();
+    db.put_cf(&cf, [1], [1], &WriteOptions::default())
+        .expect("Failed to put");
+    db.flush().expect("Failed to flush");
+    db.get_pinned_cf(&cf, [1], &ReadOptions::default())
+        .expect("Failed to get");
+
+    let statistics = db
+        .statistics_to_report()
+        .expect("Statistics should be enabled");
+    assert!(statistics_tickers(&statistics).contains_key(BLOCK_CACHE_MISS_TICKER));
+    // Another column family of the db reporting its metrics right after.
+    assert_eq!(db.statistics_to_report(), None);
+}
+
 fn open_map<P: AsRef<Path>, K, V>(
     path: P,
     opt_cf: Option<&str>,
@@ -12,6 +12,7 @@ use std::sync::Mutex;
 use std::time::Duration;
 use typed_store::metrics::SamplingInterval;
 use typed_store::rocks::list_tables;
+use typed_store::rocks::table_options::{CompactionStyle, Compression, TableOptionsOverrides};
 use typed_store::rocks::DBMap;
 use typed_store::rocks::RocksDBAccessType;
 use typed_store::rocks::{be_fix_int_ser, MetricConf};
@@ -270,6 +271,39 @@ async fn macro_transactional_test() {
     assert_eq!(tables.table1.get(&key), Ok(Some("1".to_string())));
 }
 
+#[tokio::test]
+async fn macro_secondary_test() {
+    let primary_path = temp_dir();
+    let primary =
+        Tables::open_tables_read_write(primary_path.clone(), MetricConf::default(), None, None);
+    primary
+        .table1
+        .insert(&"1".to_string(), &"1".to_string())
+        .unwrap();
+
+    let secondary =
+        Tables::open_tables_secondary(primary_path, temp_dir(), MetricConf::default(), None);
+    assert_eq!(
+        secondary.table1.get(&"1".to_string()),
+        Ok(Some("1".to_string()))
+    );
+    assert!(secondary
+        .table1
+        .insert(&"2".to_string(), &"2".to_string())
+        .is_err());
+
+    // Writes of the primary show up after catching up.
+    primary
+        .table1
+        .insert(&"3".to_string(), &"3".to_string())
+        .unwrap();
+    secondary.table1.try_catch_up_with_primary().unwrap();
+    assert_eq!(
+        secondary.table1.get(&"3".to_string()),
+        Ok(Some("3".to_string()))
+    );
+}
+
 /// We show that custom functions can be applied
 #[derive(DBMapUtils)]
 struct TablesCustomOptions {
@@ -439,3 +473,52 @@ async fn test_sampling_time() {
     tokio::task::yield_now().await;
     assert!(sampling_interval.sample());
 }
+
+/// The tables are tuned by a DB options file
+#[derive(DBMapUtils)]
+struct TablesTunedByOptionsFile {
+    tuned: DBMap<String, String>,
+    untuned: DBMap<String, String>,
+}
+
+#[tokio::test]
+async fn macro_test_table_options_file() {
+    let options_path = temp_dir().join("db-options.yaml");
+    std::fs::write(
+        &options_path,
+        "TablesTunedByOptionsFile:\n  tuned:\n    block-cache-size-mb: 8\n    \
+        bloom-filter-bits-per-key: 10\n    compression: zstd\n    compaction-style: universal\n",
+    )
+    .unwrap();
+    let overrides = TableOptionsOverrides::load(&options_path).unwrap();
+    let tuned = overrides.get("TablesTunedByOptionsFile", "tuned").unwrap();
+    assert_eq!(tuned.block_cache_size_mb, Some(8));
+    assert_eq!(tuned.compression, Some(Compression::Zstd));
+    assert_eq!(tuned.compaction_style, Some(CompactionStyle::Universal));
+    assert!(overrides
+        .get("TablesTunedByOptionsFile", "untuned")
+        .is_none());
+    overrides.install();
+
+    let db = TablesTunedByOptionsFile::open_tables_read_write(
+        temp_dir(),
+        MetricConf::default(),
+        None,
+        None,
+    );
+    db.tuned
+        .insert(&"key".to_string(), &"value".to_string())
+        .unwrap();
+    assert_eq!(
+        db.tuned.get(&"key".to_string()).unwrap(),
+        Some("value".to_string())
+    );
+
+    // Unknown options are rejected rather than ignored.
+    std::fs::write(
+        &options_path,
+        "TablesTunedByOptionsFile:\n  tuned:\n    block-cache-size: 8\n",
+    )
+    .unwrap();
+    assert!(TableOptionsOverrides::load(&options_path).is_err());
+}
@@ -47,7 +47,7 @@ if [[ "$NODE_TYPE" = "primary" ]]; then
   --workers $WORKERS_PATH \
   --store "${DATA_PATH}/validator-$VALIDATOR_ID/db-primary" \
   --parameters $PARAMETERS_PATH \
-  primary $CONSENSUS_DISABLED
+  primary --protocol-version $PROTOCOL_VERSION $CONSENSUS_DISABLED
 elif [[ "$NODE_TYPE" = "worker" ]]; then
   echo "Bootstrapping new worker node with id $WORKER_ID"
 
@@ -46,18 +46,19 @@ class CommandMaker:
         return f'./narwhal-node generate_network_keys --filename {filename}'
      
     @staticmethod
-    def run_primary(primary_keys, primary_network_keys, worker_keys, committee, workers, store, parameters, debug=False):
+    def run_primary(primary_keys, primary_network_keys, worker_keys, committee, workers, store, parameters, protocol_version, debug=False):
         assert isinstance(primary_keys, str)
         assert isinstance(primary_network_keys, str)
         assert isinstance(worker_keys, str)
         assert isinstance(committee, str)
         assert isinstance(workers, str)
         assert isinstance(parameters, str)
+        assert isinstance(protocol_version, int)
         assert isinstance(debug, bool)
         v = '-vvv' if debug else '-vv'
         return (f'./narwhal-node {v} run --primary-keys {primary_keys} --primary-network-keys {primary_network_keys} '
                 f'--worker-keys {worker_keys} --committee {committee} --workers {workers} --store {store} '
-                f'--parameters {parameters} primary')
+                f'--parameters {parameters} primary --protocol-version {protocol_version}')
 
     @staticmethod
     def run_no_consensus_primary(
@@ -68,6 +69,7 @@ class CommandMaker:
         workers,
         store,
         parameters,
+        protocol_version,
         debug=False
     ):
         assert isinstance(primary_keys, str)
@@ -76,11 +78,12 @@ class CommandMaker:
         assert isinstance(committee, str)
         assert isinstance(workers, str)
         assert isinstance(parameters, str)
+        assert isinstance(protocol_version, int)
         assert isinstance(debug, bool)
         v = '-vvv' if debug else '-vv'
         return (f'./narwhal-node {v} run --primary-keys {primary_keys} --primary-network-keys {primary_network_keys} '
                 f'--worker-keys {worker_keys} --committee {committee} --workers {workers} --store {store} '
-                f'--parameters {parameters} primary --consensus-disabled')
+                f'--parameters {parameters} primary --protocol-version {protocol_version} --consensus-disabled')
 
     @staticmethod
     def run_worker(primary_keys, primary_network_keys, worker_keys, committee, workers, store, parameters, id, debug=False):
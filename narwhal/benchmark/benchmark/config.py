@@ -286,6 +286,8 @@ class BenchParameters:
                 self.failpoints = False
 
             self.runs = int(json['runs']) if 'runs' in json else 1
+
+            self.protocol_version = int(json['protocol_version'])
         except KeyError as e:
             raise ConfigError(f'Malformed bench parameters: missing key {e}')
 
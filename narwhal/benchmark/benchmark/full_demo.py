@@ -140,6 +140,7 @@ class Demo:
                     PathMaker.workers_file(),
                     PathMaker.db_path(i),
                     PathMaker.parameters_file(),
+                    self.protocol_version,
                     debug=debug
                 )
                 log_file = PathMaker.primary_log_file(i)
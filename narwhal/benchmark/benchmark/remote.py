@@ -300,6 +300,7 @@ class Bench:
                 PathMaker.workers_file(),
                 PathMaker.db_path(i),
                 PathMaker.parameters_file(),
+                bench_parameters.protocol_version,
                 debug=debug
             )
             log_file = PathMaker.primary_log_file(i)
@@ -23,6 +23,7 @@ def local(ctx, debug=True):
         'rate': 50_000,
         'tx_size': 512,
         'duration': 60,
+        'protocol_version': 3,
     }
     node_params = {
         'header_num_of_batches_threshold': 32,
@@ -72,6 +73,7 @@ def smoke(ctx, debug=True, release=False):
         'rate': 50_000,
         'tx_size': 512,
         'duration': 10,
+        'protocol_version': 3,
     }
     node_params = {
         'header_num_of_batches_threshold': 32,
@@ -122,6 +124,7 @@ def failpoints(ctx, debug=True):
         'rate': 50_000,
         'tx_size': 512,
         'duration': 20,
+        'protocol_version': 3,
     }
     node_params = {
         'header_num_of_batches_threshold': 32,
@@ -172,6 +175,7 @@ def demo(ctx, debug=True):
         'rate': 50_000,
         'tx_size': 512,
         'duration': 10,
+        'protocol_version': 3,
     }
     node_params = {
         "batch_size": 500000,
@@ -223,6 +227,7 @@ def seed(ctx, starting_data_port):
         'rate': 50_000,
         'tx_size': 512,
         'duration': 20,
+        'protocol_version': 3,
     }
     try:
         SeedData(bench_params).run(int(starting_data_port))
@@ -295,6 +300,7 @@ def remote(ctx, debug=False):
         'rate': [10_000, 110_000],
         'tx_size': 512,
         'duration': 300,
+        'protocol_version': 3,
         'runs': 2,
     }
     node_params = {
@@ -4,6 +4,7 @@
 use consensus::{
     bullshark::Bullshark,
     consensus::{ConsensusProtocol, ConsensusState},
+    leader_schedule::LeaderSchedule,
     metrics::ConsensusMetrics,
 };
 use criterion::{
@@ -14,6 +15,7 @@ use narwhal_consensus as consensus;
 use pprof::criterion::{Output, PProfProfiler};
 use prometheus::Registry;
 use std::{collections::BTreeSet, sync::Arc};
+use sui_protocol_config::ProtocolConfig;
 use test_utils::{make_consensus_store, make_optimal_certificates, temp_dir, CommitteeFixture};
 use tokio::time::Instant;
 use types::{Certificate, Round};
@@ -61,6 +63,10 @@ pub fn process_certificates(c: &mut Criterion) {
             last_leader_election: Default::default(),
             max_inserted_certificate_round: 0,
             num_sub_dags_per_schedule: 100,
+            leader_schedule: LeaderSchedule::new(
+                committee.clone(),
+                &ProtocolConfig::get_for_max_version(),
+            ),
         };
         consensus_group.bench_with_input(
             BenchmarkId::new("batched", certificates.len()),
@@ -1,9 +1,10 @@
 // Copyright (c) 2021, Facebook, Inc. and its affiliates
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
+use crate::leader_schedule::LeaderSchedule;
 use crate::metrics::ConsensusMetrics;
 use crate::{
-    consensus::{ConsensusProtocol, ConsensusState, Dag},
+    consensus::{ConsensusProtocol, ConsensusState},
     utils, ConsensusError, Outcome,
 };
 use config::{Committee, Stake};
@@ -11,8 +12,9 @@ use crypto::PublicKey;
 use fastcrypto::hash::Hash;
 use fastcrypto::traits::EncodeDecodeBase64;
 use std::sync::Arc;
+use sui_protocol_config::ProtocolConfig;
 use tokio::time::Instant;
-use tracing::{debug, error_span};
+use tracing::{debug, error_span, info};
 use types::{
     Certificate, CertificateDigest, CommittedSubDag, ConsensusStore, ReputationScores, Round,
 };
@@ -54,6 +56,8 @@ pub struct Bullshark {
     /// The number of committed subdags that will trigger the schedule change and reputation
     /// score reset.
     pub num_sub_dags_per_schedule: u64,
+    /// The leader of each round, swapped by the reputation of the authorities.
+    pub leader_schedule: LeaderSchedule,
 }
 
 impl ConsensusProtocol for Bullshark {
@@ -106,7 +110,9 @@ impl ConsensusProtocol for Bullshark {
         if leader_round <= state.last_committed_round {
             return Ok((Outcome::LeaderBelowCommitRound, Vec::new()));
         }
-        let (leader_digest, leader) = match Self::leader(&self.committee, leader_round, &state.dag)
+        let (leader_digest, leader) = match self
+            .leader_schedule
+            .leader_certificate(leader_round, &state.dag)
         {
             Some(x) => x,
             None => {
@@ -120,14 +126,7 @@ impl ConsensusProtocol for Bullshark {
         };
 
         // Check if the leader has f+1 support from its children (ie. round r+1).
-        let stake: Stake = state
-            .dag
-            .get(&round)
-            .expect("We should have the whole history by now")
-            .values()
-            .filter(|(_, x)| x.header.parents.contains(leader_digest))
-            .map(|(_, x)| self.committee.stake(&x.origin()))
-            .sum();
+        let stake = self.leader_support(state, leader_digest, round);
 
         self.last_leader_election = LastRound {
             leader_found: true,
@@ -150,10 +149,8 @@ impl ConsensusProtocol for Bullshark {
         let mut total_committed_certificates = 0;
 
         // TODO: duplicated in tusk.rs
-        for leader in utils::order_leaders(&self.committee, leader, state, Self::leader)
-            .iter()
-            .rev()
-        {
+        let mut leaders = self.order_leaders(leader, state);
+        while let Some(leader) = leaders.pop() {
             let sub_dag_index = state.latest_sub_dag_index + 1;
             let _span = error_span!("bullshark_process_sub_dag", sub_dag_index);
 
@@ -163,7 +160,7 @@ impl ConsensusProtocol for Bullshark {
             let mut sequence = Vec::new();
 
             // Starting from the oldest leader, flatten the sub-dag referenced by the leader.
-            for x in utils::order_dag(self.gc_depth, leader, state) {
+            for x in utils::order_dag(self.gc_depth, &leader, state) {
                 // Update and clean up internal state.
                 state.update(&x, self.gc_depth);
 
@@ -180,9 +177,12 @@ impl ConsensusProtocol for Bullshark {
             // We update the reputation score stored in state
             let reputation_score = self.update_reputation_score(state, &sequence, sub_dag_index);
 
+            // A new leader schedule takes effect right after the commit with the final scores.
+            let schedule_changed = self.update_leader_schedule(&reputation_score);
+
             let sub_dag = CommittedSubDag {
                 certificates: sequence,
-                leader: leader.clone(),
+                leader,
                 sub_dag_index,
                 reputation_score,
             };
@@ -196,6 +196,23 @@ impl ConsensusProtocol for Bullshark {
             state.last_committed_leader = Some(sub_dag.leader.digest());
 
             committed_sub_dags.push(sub_dag);
+
+            // The remaining leaders were elected by the previous schedule, so elect them again,
+            // starting over from the leader of the round that triggered the commits.
+            if schedule_changed && !leaders.is_empty() {
+                leaders = match self
+                    .leader_schedule
+                    .leader_certificate(leader_round, &state.dag)
+                {
+                    Some((digest, leader))
+                        if self.leader_support(state, digest, round)
+                            >= self.committee.validity_threshold() =>
+                    {
+                        self.order_leaders(leader, state)
+                    }
+                    _ => Vec::new(),
+                };
+            }
         }
 
         // record the last time we got a successful leader election
@@ -246,7 +263,17 @@ impl Bullshark {
         gc_depth: Round,
         metrics: Arc<ConsensusMetrics>,
         num_sub_dags_per_schedule: u64,
+        protocol_config: &ProtocolConfig,
     ) -> Self {
+        let leader_schedule = LeaderSchedule::from_store(
+            committee.clone(),
+            protocol_config,
+            &store,
+            num_sub_dags_per_schedule,
+        );
+        metrics
+            .leader_schedule_swapped_authorities
+            .set(leader_schedule.swap_table().bad_nodes().len() as i64);
         Self {
             committee,
             store,
@@ -256,6 +283,7 @@ impl Bullshark {
             max_inserted_certificate_round: 0,
             metrics,
             num_sub_dags_per_schedule,
+            leader_schedule,
         }
     }
 
@@ -274,20 +302,49 @@ impl Bullshark {
         }
     }
 
-    // TODO: duplicated in tusk.rs
-    /// Returns the certificate (and the certificate's digest) originated by the leader of the
-    /// specified round (if any).
-    fn leader<'a>(
-        committee: &Committee,
+    /// Returns the stake of the certificates of `round` that have the leader as a parent.
+    fn leader_support(
+        &self,
+        state: &ConsensusState,
+        leader_digest: &CertificateDigest,
         round: Round,
-        dag: &'a Dag,
-    ) -> Option<&'a (CertificateDigest, Certificate)> {
-        // Note: this function is often called with even rounds only. While we do not aim at random selection
-        // yet (see issue #10), repeated calls to this function should still pick from the whole roster of leaders.
-        let leader = Self::leader_authority(committee, round);
-
-        // Return its certificate and the certificate's digest.
-        dag.get(&round).and_then(|x| x.get(&leader))
+    ) -> Stake {
+        state
+            .dag
+            .get(&round)
+            .expect("We should have the whole history by now")
+            .values()
+            .filter(|(_, x)| x.header.parents.contains(leader_digest))
+            .map(|(_, x)| self.committee.stake(&x.origin()))
+            .sum()
+    }
+
+    /// Returns the past leaders linked to `leader` that are yet to be committed, and `leader`,
+    /// from the most recent one.
+    fn order_leaders(&self, leader: &Certificate, state: &ConsensusState) -> Vec<Certificate> {
+        let leader_schedule = &self.leader_schedule;
+        utils::order_leaders(&self.committee, leader, state, |_, round, dag| {
+            leader_schedule.leader_certificate(round, dag)
+        })
+    }
+
+    /// Changes the leader schedule with the final scores of a schedule, and returns whether the
+    /// leaders changed.
+    fn update_leader_schedule(&mut self, reputation_score: &ReputationScores) -> bool {
+        if !reputation_score.final_of_schedule || !self.leader_schedule.update(reputation_score) {
+            return false;
+        }
+        let swap_table = self.leader_schedule.swap_table();
+        info!(
+            good_nodes = ?swap_table.good_nodes(),
+            bad_nodes = ?swap_table.bad_nodes(),
+            "Leader schedule changed"
+        );
+        self.metrics.leader_schedule_changes.inc();
+        self.metrics
+            .leader_schedule_swapped_authorities
+            .set(swap_table.bad_nodes().len() as i64);
+        true
     }
 
     /// Updates and calculates the reputation score for the current commit managing any internal state.
@@ -299,8 +356,6 @@ impl Bullshark {
         sub_dag_index: u64,
     ) -> ReputationScores {
         // we reset the scores for every schedule change window.
-        // TODO: when schedule change is implemented we should probably change a little bit
-        // this logic here.
         if sub_dag_index % self.num_sub_dags_per_schedule == 0 {
             state.last_consensus_reputation_score = ReputationScores::new(&self.committee)
         }
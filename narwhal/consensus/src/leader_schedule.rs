@@ -0,0 +1,210 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+use crate::bullshark::Bullshark;
+use crate::consensus::Dag;
+use config::{Committee, Stake};
+use crypto::PublicKey;
+use std::collections::BTreeSet;
+use sui_protocol_config::ProtocolConfig;
+use tracing::warn;
+use types::{Certificate, CertificateDigest, ConsensusStore, ReputationScores, Round};
+
+#[cfg(test)]
+#[path = "tests/leader_schedule_tests.rs"]
+pub mod leader_schedule_tests;
+
+/// The share of the total stake, in percent, of the authorities with the lowest reputation scores
+/// that are swapped out of the leader schedule, and of the ones with the highest scores that are
+/// swapped in for them. A third of the stake at most, as much as may be faulty.
+pub const BAD_NODES_STAKE_THRESHOLD: u64 = 33;
+
+/// The authorities swapped out of the leader schedule for their low reputation scores over the
+/// last schedule, and the ones elected in their place.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LeaderSwapTable {
+    /// The authorities with the highest scores, ranked from the highest.
+    good_nodes: Vec<PublicKey>,
+    /// The authorities with the lowest scores, which are never elected.
+    bad_nodes: BTreeSet<PublicKey>,
+}
+
+impl LeaderSwapTable {
+    /// Builds the table from the final `reputation_scores` of a schedule, swapping up to
+    /// `bad_nodes_stake_threshold` percent of the stake.
+    pub fn new(
+        committee: &Committee,
+        reputation_scores: &ReputationScores,
+        bad_nodes_stake_threshold: u64,
+    ) -> Self {
+        // Rank the authorities by score, breaking ties by key so that every authority ranks them
+        // the same.
+        let mut ranked: Vec<(PublicKey, u64)> = committee
+            .authorities()
+            .map(|(name, _)| {
+                let score = reputation_scores
+                    .scores_per_authority
+                    .get(name)
+                    .copied()
+                    .unwrap_or_default();
+                (name.clone(), score)
+            })
+            .collect();
+        ranked.sort_by(|(a, a_score), (b, b_score)| b_score.cmp(a_score).then_with(|| a.cmp(b)));
+
+        // Nobody did worse than anybody else, e.g. on the first schedule.
+        if ranked.first().map(|(_, score)| score) == ranked.last().map(|(_, score)| score) {
+            return Self::default();
+        }
+
+        let total_stake: Stake = committee.authorities().map(|(_, a)| a.stake).sum();
+        let max_stake = total_stake * bad_nodes_stake_threshold / 100;
+        let good_nodes: Vec<_> =
+            Self::retrieve_first_nodes(committee, ranked.iter().map(|(name, _)| name), max_stake);
+        let bad_nodes: BTreeSet<_> = Self::retrieve_first_nodes(
+            committee,
+            ranked.iter().rev().map(|(name, _)| name),
+            max_stake,
+        );
+        if good_nodes.is_empty() || bad_nodes.is_empty() {
+            return Self::default();
+        }
+
+        Self {
+            good_nodes,
+            bad_nodes,
+        }
+    }
+
+    /// The authority elected in place of `leader` for `round`, if `leader` is swapped out.
+    pub fn swap(&self, leader: &PublicKey, round: Round) -> Option<PublicKey> {
+        if !self.bad_nodes.contains(leader) {
+            return None;
+        }
+        // Only even rounds have leaders, so go through the good nodes every other round.
+        let index = (round / 2) as usize % self.good_nodes.len();
+        Some(self.good_nodes[index].clone())
+    }
+
+    pub fn good_nodes(&self) -> &[PublicKey] {
+        &self.good_nodes
+    }
+
+    pub fn bad_nodes(&self) -> &BTreeSet<PublicKey> {
+        &self.bad_nodes
+    }
+
+    /// The first `nodes` in order whose stake adds up to at most `max_stake`.
+    fn retrieve_first_nodes<'a, C>(
+        committee: &Committee,
+        nodes: impl Iterator<Item = &'a PublicKey>,
+        max_stake: Stake,
+    ) -> C
+    where
+        C: FromIterator<PublicKey>,
+    {
+        let mut stake = 0;
+        nodes
+            .take_while(|name| {
+                stake += committee.stake(name);
+                stake <= max_stake
+            })
+            .cloned()
+            .collect()
+    }
+}
+
+/// Elects the leader of every even round. The leader is a stake-weighted choice seeded by the
+/// round, which, once the protocol enables it, is swapped for one of the authorities with the
+/// best reputation if it has one of the worst. The reputation of the authorities is the number of
+/// times their certificates voted for the previous leader, over the last schedule, i.e. the last
+/// `num_sub_dags_per_schedule` commits, so authorities that are offline or too slow to keep up
+/// with the leaders are rarely elected.
+///
+/// The schedule only changes with the commits, so every authority elects the same leaders for the
+/// rounds that are yet to be committed.
+#[derive(Clone, Debug)]
+pub struct LeaderSchedule {
+    committee: Committee,
+    /// Whether the leaders are swapped by reputation.
+    reputation_based: bool,
+    swap_table: LeaderSwapTable,
+}
+
+impl LeaderSchedule {
+    pub fn new(committee: Committee, protocol_config: &ProtocolConfig) -> Self {
+        Self {
+            committee,
+            reputation_based: protocol_config.narwhal_reputation_based_leader_schedule(),
+            swap_table: LeaderSwapTable::default(),
+        }
+    }
+
+    /// Recovers the schedule from the final scores of the last schedule committed in `store`.
+    pub fn from_store(
+        committee: Committee,
+        protocol_config: &ProtocolConfig,
+        store: &ConsensusStore,
+        num_sub_dags_per_schedule: u64,
+    ) -> Self {
+        let mut schedule = Self::new(committee, protocol_config);
+        if !schedule.reputation_based {
+            return schedule;
+        }
+
+        // The scores are final on the last sub dag of each schedule.
+        let latest_sub_dag_index = store.get_latest_sub_dag_index();
+        if latest_sub_dag_index + 1 < num_sub_dags_per_schedule {
+            return schedule;
+        }
+        let final_sub_dag_index =
+            latest_sub_dag_index - (latest_sub_dag_index + 1) % num_sub_dags_per_schedule;
+        match store.read_committed_sub_dag(&final_sub_dag_index) {
+            Ok(Some(sub_dag)) => {
+                schedule.update(&sub_dag.reputation_score);
+            }
+            Ok(None) => {}
+            Err(e) => {
+                warn!(
+                    "Cannot read sub dag {final_sub_dag_index} to recover the leader schedule: {e}"
+                )
+            }
+        }
+        schedule
+    }
+
+    /// The authority elected as the leader of `round`.
+    pub fn leader(&self, round: Round) -> PublicKey {
+        let leader = Bullshark::leader_authority(&self.committee, round);
+        self.swap_table.swap(&leader, round).unwrap_or(leader)
+    }
+
+    /// Returns the certificate (and the certificate's digest) originated by the leader of the
+    /// specified round (if any).
+    pub fn leader_certificate<'a>(
+        &self,
+        round: Round,
+        dag: &'a Dag,
+    ) -> Option<&'a (CertificateDigest, Certificate)> {
+        dag.get(&round).and_then(|x| x.get(&self.leader(round)))
+    }
+
+    /// Swaps the authorities by the final `reputation_scores` of a schedule, and returns whether
+    /// the leaders changed.
+    pub fn update(&mut self, reputation_scores: &ReputationScores) -> bool {
+        if !self.reputation_based {
+            return false;
+        }
+        let swap_table = LeaderSwapTable::new(
+            &self.committee,
+            reputation_scores,
+            BAD_NODES_STAKE_THRESHOLD,
+        );
+        let changed = swap_table != self.swap_table;
+        self.swap_table = swap_table;
+        changed
+    }
+
+    pub fn swap_table(&self) -> &LeaderSwapTable {
+        &self.swap_table
+    }
+}
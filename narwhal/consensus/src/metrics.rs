@@ -43,6 +43,10 @@ pub struct ConsensusMetrics {
     pub leader_election: IntCounterVec,
     /// Count leader certificates committed, and whether the leader has strong support.
     pub leader_commits: IntCounterVec,
+    /// The number of times the leader schedule changed by the reputation of the authorities
+    pub leader_schedule_changes: IntCounter,
+    /// The number of authorities swapped out of the current leader schedule
+    pub leader_schedule_swapped_authorities: IntGauge,
 }
 
 impl ConsensusMetrics {
@@ -117,6 +121,16 @@ impl ConsensusMetrics {
                 &["type"],
                 registry
             ).unwrap(),
+            leader_schedule_changes: register_int_counter_with_registry!(
+                "leader_schedule_changes",
+                "The number of times the leader schedule changed by the reputation of the authorities",
+                registry
+            ).unwrap(),
+            leader_schedule_swapped_authorities: register_int_gauge_with_registry!(
+                "leader_schedule_swapped_authorities",
+                "The number of authorities with low reputation swapped out of the current leader schedule",
+                registry
+            ).unwrap(),
         }
     }
 }
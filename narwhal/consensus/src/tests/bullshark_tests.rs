@@ -11,7 +11,7 @@ use fastcrypto::traits::KeyPair;
 use prometheus::Registry;
 #[cfg(test)]
 use std::collections::{BTreeSet, VecDeque};
-use test_utils::CommitteeFixture;
+use test_utils::{latest_protocol_version, CommitteeFixture};
 #[allow(unused_imports)]
 use tokio::sync::mpsc::channel;
 use tokio::sync::watch;
@@ -61,6 +61,7 @@ async fn commit_one() {
         gc_depth,
         metrics.clone(),
         NUM_SUB_DAGS_PER_SCHEDULE,
+        &latest_protocol_version(),
     );
 
     let _consensus_handle = Consensus::spawn(
@@ -139,6 +140,7 @@ async fn dead_node() {
         gc_depth,
         metrics.clone(),
         NUM_SUB_DAGS_PER_SCHEDULE,
+        &latest_protocol_version(),
     );
 
     let _consensus_handle = Consensus::spawn(
@@ -295,6 +297,7 @@ async fn not_enough_support() {
         gc_depth,
         metrics.clone(),
         NUM_SUB_DAGS_PER_SCHEDULE,
+        &latest_protocol_version(),
     );
 
     let _consensus_handle = Consensus::spawn(
@@ -415,6 +418,7 @@ async fn missing_leader() {
         gc_depth,
         metrics.clone(),
         NUM_SUB_DAGS_PER_SCHEDULE,
+        &latest_protocol_version(),
     );
 
     let _consensus_handle = Consensus::spawn(
@@ -497,6 +501,7 @@ async fn committed_round_after_restart() {
             gc_depth,
             metrics.clone(),
             NUM_SUB_DAGS_PER_SCHEDULE,
+            &latest_protocol_version(),
         );
 
         let handle = Consensus::spawn(
@@ -587,6 +592,7 @@ async fn delayed_certificates_are_rejected() {
         gc_depth,
         metrics,
         NUM_SUB_DAGS_PER_SCHEDULE,
+        &latest_protocol_version(),
     );
 
     // Populate DAG with the rounds up to round 5 so we trigger commits
@@ -639,6 +645,7 @@ async fn submitting_equivocating_certificate_should_error() {
         gc_depth,
         metrics,
         NUM_SUB_DAGS_PER_SCHEDULE,
+        &latest_protocol_version(),
     );
 
     // Populate DAG with all the certificates
@@ -702,6 +709,7 @@ async fn reset_consensus_scores_on_every_schedule_change() {
         gc_depth,
         metrics,
         NUM_SUB_DAGS_PER_SCHEDULE,
+        &latest_protocol_version(),
     );
 
     // Populate DAG with the rounds up to round 50 so we trigger commits
@@ -774,6 +782,7 @@ async fn restart_with_new_committee() {
             gc_depth,
             metrics.clone(),
             NUM_SUB_DAGS_PER_SCHEDULE,
+            &latest_protocol_version(),
         );
 
         let handle = Consensus::spawn(
@@ -844,3 +853,79 @@ async fn restart_with_new_committee() {
         handle.await.unwrap();
     }
 }
+
+// The leader of every round never votes for itself, so it has the lowest reputation at the end of
+// the first schedule, and is swapped out of the leader schedule from then on.
+#[tokio::test]
+async fn swap_leader_with_low_reputation() {
+    const NUM_SUB_DAGS_PER_SCHEDULE: u64 = 5;
+
+    let fixture = CommitteeFixture::builder().build();
+    let committee = fixture.committee();
+    let keys: Vec<_> = fixture.authorities().map(|a| a.public_key()).collect();
+    let slow_leader = Bullshark::leader_authority(&committee, 2);
+
+    // Make certificates for rounds 1 to 20, where the certificates of the slow leader do not have
+    // its own certificate of the round before as a parent.
+    let mut certificates = Vec::new();
+    let mut parents = Certificate::genesis(&committee)
+        .iter()
+        .map(|x| x.digest())
+        .collect::<BTreeSet<_>>();
+    let mut slow_leader_digest = None;
+    for round in 1..=20 {
+        let mut next_parents = BTreeSet::new();
+        for key in &keys {
+            let mut certificate_parents = parents.clone();
+            if *key == slow_leader {
+                if let Some(digest) = slow_leader_digest {
+                    certificate_parents.remove(&digest);
+                }
+            }
+            let (digest, certificate) =
+                test_utils::mock_certificate(&committee, key.clone(), round, certificate_parents);
+            if *key == slow_leader {
+                slow_leader_digest = Some(digest);
+            }
+            next_parents.insert(digest);
+            certificates.push(certificate);
+        }
+        parents = next_parents;
+    }
+
+    let mut protocol_config = latest_protocol_version();
+    protocol_config.set_narwhal_reputation_based_leader_schedule_for_testing(true);
+    let store = make_consensus_store(&test_utils::temp_dir());
+    let metrics = Arc::new(ConsensusMetrics::new(&Registry::new()));
+    let mut state = ConsensusState::new(metrics.clone(), &committee);
+    let mut bullshark = Bullshark::new(
+        committee,
+        store,
+        50,
+        metrics.clone(),
+        NUM_SUB_DAGS_PER_SCHEDULE,
+        &protocol_config,
+    );
+
+    // Populate DAG with the rounds up to round 20, committing the leaders of rounds 2 to 18.
+    let mut all_subdags = Vec::new();
+    for certificate in certificates {
+        let (_, committed_subdags) = bullshark
+            .process_certificate(&mut state, certificate)
+            .unwrap();
+        all_subdags.extend(committed_subdags);
+    }
+    assert_eq!(all_subdags.len(), 9);
+
+    // The scores are final on the 4th commit, which changes the schedule for the next ones.
+    for sub_dag in &all_subdags {
+        if sub_dag.sub_dag_index < NUM_SUB_DAGS_PER_SCHEDULE {
+            assert_eq!(sub_dag.leader.origin(), slow_leader);
+        } else {
+            assert_ne!(sub_dag.leader.origin(), slow_leader);
+        }
+    }
+    // The slow leader still has the lowest score at the end of the second schedule.
+    assert_eq!(metrics.leader_schedule_changes.get(), 1);
+    assert_eq!(metrics.leader_schedule_swapped_authorities.get(), 1);
+}
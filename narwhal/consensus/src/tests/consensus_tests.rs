@@ -9,7 +9,7 @@ use std::collections::BTreeSet;
 use std::sync::Arc;
 use storage::NodeStorage;
 use telemetry_subscribers::TelemetryGuards;
-use test_utils::{temp_dir, CommitteeFixture};
+use test_utils::{latest_protocol_version, temp_dir, CommitteeFixture};
 use tokio::sync::watch;
 
 use crate::bullshark::Bullshark;
@@ -68,6 +68,7 @@ async fn test_consensus_recovery_with_bullshark() {
         gc_depth,
         metrics.clone(),
         NUM_SUB_DAGS_PER_SCHEDULE,
+        &latest_protocol_version(),
     );
 
     let consensus_handle = Consensus::spawn(
@@ -163,6 +164,7 @@ async fn test_consensus_recovery_with_bullshark() {
         gc_depth,
         metrics.clone(),
         NUM_SUB_DAGS_PER_SCHEDULE,
+        &latest_protocol_version(),
     );
 
     let consensus_handle = Consensus::spawn(
@@ -232,6 +234,7 @@ async fn test_consensus_recovery_with_bullshark() {
         gc_depth,
         metrics.clone(),
         NUM_SUB_DAGS_PER_SCHEDULE,
+        &latest_protocol_version(),
     );
 
     let _consensus_handle = Consensus::spawn(
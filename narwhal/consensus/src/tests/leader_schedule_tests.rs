@@ -0,0 +1,77 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+use super::*;
+
+use std::num::NonZeroUsize;
+use test_utils::{latest_protocol_version, CommitteeFixture};
+
+#[test]
+fn swap_table_from_scores() {
+    let fixture = CommitteeFixture::builder()
+        .committee_size(NonZeroUsize::new(10).unwrap())
+        .build();
+    let committee = fixture.committee();
+
+    // The authorities, ordered by their keys, score the lower the later they come.
+    let keys: Vec<_> = committee
+        .authorities()
+        .map(|(name, _)| name.clone())
+        .collect();
+    let mut scores = ReputationScores::new(&committee);
+    for (i, key) in keys.iter().enumerate() {
+        scores.add_score(key.clone(), 10 - i as u64);
+    }
+
+    // A third of the stake is 3 authorities.
+    let table = LeaderSwapTable::new(&committee, &scores, BAD_NODES_STAKE_THRESHOLD);
+    assert_eq!(table.good_nodes(), &keys[..3]);
+    assert_eq!(
+        table.bad_nodes(),
+        &keys[7..].iter().cloned().collect::<BTreeSet<_>>()
+    );
+
+    // The bad nodes are swapped for every good node in turn, and the others are kept.
+    for round in (2..=12).step_by(2) {
+        let swapped = table.swap(&keys[9], round).unwrap();
+        assert_eq!(swapped, keys[(round / 2) as usize % 3]);
+        assert_eq!(table.swap(&keys[5], round), None);
+        assert_eq!(table.swap(&keys[0], round), None);
+    }
+
+    // Nobody is swapped when the scores are all the same.
+    let table = LeaderSwapTable::new(
+        &committee,
+        &ReputationScores::new(&committee),
+        BAD_NODES_STAKE_THRESHOLD,
+    );
+    assert_eq!(table, LeaderSwapTable::default());
+    assert_eq!(table.swap(&keys[9], 2), None);
+}
+
+#[test]
+fn leader_schedule_follows_protocol_config() {
+    let fixture = CommitteeFixture::builder().build();
+    let committee = fixture.committee();
+
+    // The leader of every round in tests has the lowest score.
+    let leader = Bullshark::leader_authority(&committee, 2);
+    let mut scores = ReputationScores::new(&committee);
+    for (name, _) in committee.authorities() {
+        if *name != leader {
+            scores.add_score(name.clone(), 1);
+        }
+    }
+
+    let mut protocol_config = latest_protocol_version();
+    protocol_config.set_narwhal_reputation_based_leader_schedule_for_testing(false);
+    let mut schedule = LeaderSchedule::new(committee.clone(), &protocol_config);
+    assert!(!schedule.update(&scores));
+    assert_eq!(schedule.leader(2), leader);
+
+    protocol_config.set_narwhal_reputation_based_leader_schedule_for_testing(true);
+    let mut schedule = LeaderSchedule::new(committee, &protocol_config);
+    assert!(schedule.update(&scores));
+    assert_ne!(schedule.leader(2), leader);
+    // Nothing changes with the same scores.
+    assert!(!schedule.update(&scores));
+}
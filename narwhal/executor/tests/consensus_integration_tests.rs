@@ -13,7 +13,7 @@ use std::collections::BTreeSet;
 use std::sync::Arc;
 use storage::NodeStorage;
 use telemetry_subscribers::TelemetryGuards;
-use test_utils::{cluster::Cluster, temp_dir, CommitteeFixture};
+use test_utils::{cluster::Cluster, latest_protocol_version, temp_dir, CommitteeFixture};
 use tokio::sync::watch;
 
 use types::{Certificate, PreSubscribedBroadcastSender, Round, TransactionProto};
@@ -64,6 +64,7 @@ async fn test_recovery() {
         GC_DEPTH,
         metrics.clone(),
         NUM_SUB_DAGS_PER_SCHEDULE,
+        &latest_protocol_version(),
     );
 
     let _consensus_handle = Consensus::spawn(
@@ -28,6 +28,7 @@ use sui_keys::keypair_file::{
     read_authority_keypair_from_file, read_network_keypair_from_file,
     write_authority_keypair_to_file, write_keypair_to_file,
 };
+use sui_protocol_config::{ProtocolConfig, ProtocolVersion, SupportedProtocolVersions};
 use sui_types::crypto::{get_key_pair_from_rng, AuthorityKeyPair, SuiKeyPair};
 use telemetry_subscribers::TelemetryGuards;
 use tokio::sync::mpsc::channel;
@@ -72,6 +73,7 @@ async fn main() -> Result<(), eyre::Report> {
                 .subcommand(SubCommand::with_name("primary")
                     .about("Run a single primary")
                     .args_from_usage("-d, --consensus-disabled 'Provide this flag to run a primary node without Tusk'")
+                    .args_from_usage("--protocol-version=<INT> 'The protocol version of the chain the primary runs consensus for'")
                 )
                 .subcommand(
                     SubCommand::with_name("worker")
@@ -274,6 +276,19 @@ async fn run(
     let (primary, worker) = match matches.subcommand() {
         // Spawn the primary and consensus core.
         ("primary", Some(sub_matches)) => {
+            let protocol_version = sub_matches
+                .value_of("protocol-version")
+                .unwrap()
+                .parse::<u64>()
+                .map(ProtocolVersion::new)
+                .context("The protocol version must be a positive integer")?;
+            // All the primaries of a committee must run consensus with the same protocol config.
+            eyre::ensure!(
+                SupportedProtocolVersions::SYSTEM_DEFAULT.is_version_supported(protocol_version),
+                "Unsupported protocol version {}",
+                protocol_version.as_u64()
+            );
+
             let primary = PrimaryNode::new(
                 parameters.clone(),
                 !sub_matches.is_present("consensus-disabled"),
@@ -287,6 +302,7 @@ async fn run(
                     committee,
                     worker_cache,
                     &store,
+                    ProtocolConfig::get_for_version(protocol_version),
                     Arc::new(SimpleExecutionState::new(_tx_transaction_confirmation)),
                 )
                 .await?;
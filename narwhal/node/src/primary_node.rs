@@ -16,6 +16,7 @@ use prometheus::{IntGauge, Registry};
 use std::sync::Arc;
 use std::time::Instant;
 use storage::NodeStorage;
+use sui_protocol_config::ProtocolConfig;
 use tokio::sync::{oneshot, watch, RwLock};
 use tokio::task::JoinHandle;
 use tracing::{debug, info, instrument};
@@ -64,6 +65,8 @@ impl PrimaryNodeInner {
         worker_cache: WorkerCache,
         // The node's store //TODO: replace this by a path so the method can open and independent storage
         store: &NodeStorage,
+        // The protocol config of the epoch.
+        protocol_config: ProtocolConfig,
         // The state used by the client to execute transactions.
         execution_state: Arc<State>,
     ) -> Result<(), NodeError>
@@ -88,6 +91,7 @@ impl PrimaryNodeInner {
             worker_cache,
             store,
             self.parameters.clone(),
+            protocol_config,
             self.internal_consensus,
             execution_state,
             &registry,
@@ -178,6 +182,8 @@ impl PrimaryNodeInner {
         store: &NodeStorage,
         // The configuration parameters.
         parameters: Parameters,
+        // The protocol config of the epoch.
+        protocol_config: ProtocolConfig,
         // Whether to run consensus (and an executor client) or not.
         // If true, an internal consensus will be used, else an external consensus will be used.
         // If an external consensus will be used, then this bool will also ensure that the
@@ -238,6 +244,7 @@ impl PrimaryNodeInner {
                 committee.clone(),
                 store,
                 parameters.clone(),
+                protocol_config,
                 execution_state,
                 tx_shutdown.subscribe_n(3),
                 rx_new_certificates,
@@ -288,6 +295,7 @@ impl PrimaryNodeInner {
         committee: Committee,
         store: &NodeStorage,
         parameters: Parameters,
+        protocol_config: ProtocolConfig,
         execution_state: State,
         mut shutdown_receivers: Vec<ConditionalBroadcastReceiver>,
         rx_new_certificates: metered_channel::Receiver<Certificate>,
@@ -330,6 +338,7 @@ impl PrimaryNodeInner {
             parameters.gc_depth,
             consensus_metrics.clone(),
             Self::CONSENSUS_SCHEDULE_CHANGE_SUB_DAGS,
+            &protocol_config,
         );
         let consensus_handles = Consensus::spawn(
             committee.clone(),
@@ -402,6 +411,8 @@ impl PrimaryNode {
         worker_cache: WorkerCache,
         // The node's store //TODO: replace this by a path so the method can open and independent storage
         store: &NodeStorage,
+        // The protocol config of the epoch.
+        protocol_config: ProtocolConfig,
         // The state used by the client to execute transactions.
         execution_state: Arc<State>,
     ) -> Result<(), NodeError>
@@ -416,6 +427,7 @@ impl PrimaryNode {
                 committee,
                 worker_cache,
                 store,
+                protocol_config,
                 execution_state,
             )
             .await
@@ -3,6 +3,7 @@
 
 use config::Export;
 use std::time::{Duration, Instant};
+use sui_protocol_config::ProtocolVersion;
 use test_utils::{temp_dir, CommitteeFixture};
 
 const TEST_DURATION: Duration = Duration::from_secs(3);
@@ -68,6 +69,8 @@ fn test_primary_no_consensus() {
             "--store",
             &db_path,
             "primary",
+            "--protocol-version",
+            &ProtocolVersion::MAX.as_u64().to_string(),
             "--consensus-disabled",
         ])
         .spawn()
@@ -152,6 +155,8 @@ fn test_primary_with_consensus() {
             "--store",
             &db_path,
             "primary",
+            "--protocol-version",
+            &ProtocolVersion::MAX.as_u64().to_string(),
             //no arg : default of with_consensus
         ])
         .spawn()
@@ -12,7 +12,7 @@ use std::num::NonZeroUsize;
 use std::sync::Arc;
 use std::time::Duration;
 use storage::NodeStorage;
-use test_utils::{temp_dir, CommitteeFixture};
+use test_utils::{latest_protocol_version, temp_dir, CommitteeFixture};
 use tokio::sync::mpsc::channel;
 use tokio::time::sleep;
 use worker::TrivialTransactionValidator;
@@ -49,6 +49,7 @@ async fn simple_primary_worker_node_start_stop() {
             committee.clone(),
             worker_cache.clone(),
             &store,
+            latest_protocol_version(),
             execution_state,
         )
         .await
@@ -128,6 +129,7 @@ async fn primary_node_restart() {
             committee.clone(),
             worker_cache.clone(),
             &store,
+            latest_protocol_version(),
             execution_state.clone(),
         )
         .await
@@ -148,6 +150,7 @@ async fn primary_node_restart() {
             committee.clone(),
             worker_cache.clone(),
             &store,
+            latest_protocol_version(),
             execution_state,
         )
         .await
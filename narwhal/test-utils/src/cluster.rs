@@ -1,6 +1,6 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
-use crate::{temp_dir, CommitteeFixture};
+use crate::{latest_protocol_version, temp_dir, CommitteeFixture};
 use config::{Committee, Parameters, WorkerCache, WorkerId};
 use crypto::{KeyPair, NetworkKeyPair, PublicKey};
 use executor::SerializedTransaction;
@@ -361,6 +361,7 @@ impl PrimaryNodeDetails {
                 self.committee.clone(),
                 self.worker_cache.clone(),
                 &primary_store,
+                latest_protocol_version(),
                 Arc::new(SimpleExecutionState::new(tx_transaction_confirmation)),
             )
             .await
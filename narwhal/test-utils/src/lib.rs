@@ -27,6 +27,7 @@ use std::{
 use store::rocks::MetricConf;
 use store::rocks::ReadWriteOptions;
 use store::{reopen, rocks, rocks::DBMap, Store};
+use sui_protocol_config::ProtocolConfig;
 use tokio::sync::mpsc::{channel, Receiver, Sender};
 use tracing::info;
 use types::{
@@ -55,6 +56,11 @@ pub fn temp_dir() -> std::path::PathBuf {
         .into_path()
 }
 
+/// The protocol config of the latest protocol version, which the tests run with.
+pub fn latest_protocol_version() -> ProtocolConfig {
+    ProtocolConfig::get_for_max_version()
+}
+
 pub fn ensure_test_environment() {
     // One common issue when running tests on Mac is that the default ulimit is too low,
     // leading to I/O errors such as "Too many open files". Raising fdlimit to bypass it.
@@ -212,6 +212,14 @@ impl ConsensusStore {
             .map(|(_, subdag)| subdag)
     }
 
+    /// Load the sub dag committed with the sequence number `index`, if any.
+    pub fn read_committed_sub_dag(
+        &self,
+        index: &SequenceNumber,
+    ) -> StoreResult<Option<CommittedSubDagShell>> {
+        self.committed_sub_dags_by_index.get(index)
+    }
+
     /// Load all the sub dags committed with sequence number of at least `from`.
     pub fn read_committed_sub_dags_from(
         &self,
@@ -33,6 +33,7 @@ import {
   PaginatedEvents,
   FaucetResponse,
   Order,
+  DevInspectArgs,
   DevInspectResults,
   CoinMetadata,
   isValidTransactionDigest,
@@ -49,6 +50,7 @@ import {
   SuiSystemState,
   CoinBalance,
   CoinSupply,
+  PaginatedBalanceChanges,
   CheckpointDigest,
   Checkpoint,
   CommitteeInfo,
@@ -264,6 +266,30 @@ export class JsonRpcProvider extends Provider {
     }
   }
 
+  async getBalanceChanges(
+    owner: SuiAddress,
+    coinType: string | null = null,
+    cursor: TransactionDigest | null = null,
+    limit: number | null = null,
+    order: Order = 'descending',
+  ): Promise<PaginatedBalanceChanges> {
+    try {
+      if (!owner || !isValidSuiAddress(normalizeSuiAddress(owner))) {
+        throw new Error('Invalid Sui address');
+      }
+      return await this.client.requestWithType(
+        'sui_getBalanceChanges',
+        [owner, coinType, cursor, limit, order === 'descending'],
+        PaginatedBalanceChanges,
+        this.options.skipDataValidation,
+      );
+    } catch (err) {
+      throw new Error(
+        `Error getting balance changes for owner ${owner}: ${err}`,
+      );
+    }
+  }
+
   async getCoinMetadata(coinType: string): Promise<CoinMetadata> {
     try {
       return await this.client.requestWithType(
@@ -808,6 +834,7 @@ export class JsonRpcProvider extends Provider {
     tx: Transaction | UnserializedSignableTransaction | string | Uint8Array,
     gasPrice: number | null = null,
     epoch: number | null = null,
+    additionalArgs: DevInspectArgs | null = null,
   ): Promise<DevInspectResults> {
     try {
       let devInspectTxBytes;
@@ -828,7 +855,7 @@ export class JsonRpcProvider extends Provider {
 
       const resp = await this.client.requestWithType(
         'sui_devInspectTransaction',
-        [sender, devInspectTxBytes, gasPrice, epoch],
+        [sender, devInspectTxBytes, gasPrice, epoch, additionalArgs],
         DevInspectResults,
         this.options.skipDataValidation,
       );
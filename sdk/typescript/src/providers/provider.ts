@@ -32,12 +32,14 @@ import {
   FaucetResponse,
   Order,
   CoinMetadata,
+  DevInspectArgs,
   DevInspectResults,
   SuiSystemState,
   DelegatedStake,
   ValidatorMetaData,
   PaginatedCoins,
   CoinBalance,
+  PaginatedBalanceChanges,
   CoinSupply,
   CheckpointDigest,
   Checkpoint,
@@ -121,6 +123,21 @@ export abstract class Provider {
    */
   abstract getAllBalances(owner: SuiAddress): Promise<CoinBalance[]>;
 
+  /**
+   * Get the net change each transaction made to the balance of each coin type owned by an address.
+   * @param coinType optional fully qualified type names for the coin (e.g., 0x168da5bf1f48dafc111b0a488fa454aca95e0b5e::usdc::USDC), default to all coin types if not specified.
+   * @param cursor optional paging cursor
+   * @param limit maximum number of transactions per page
+   * @param order query result ordering
+   */
+  abstract getBalanceChanges(
+    owner: SuiAddress,
+    coinType: string | null,
+    cursor: TransactionDigest | null,
+    limit: number | null,
+    order: Order,
+  ): Promise<PaginatedBalanceChanges>;
+
   /**
    * Fetch CoinMetadata for a given coin type
    * @param coinType fully qualified type names for the coin (e.g.,
@@ -330,12 +347,15 @@ export abstract class Provider {
    * in the Sui System State object
    * @param epoch optional. Default to use the current epoch number stored
    * in the Sui System State object
+   * @param additionalArgs optional. Overrides the gas budget, skips checks
+   * and asks for the raw BCS transaction data and effects
    */
   abstract devInspectTransaction(
     sender: SuiAddress,
     txn: Transaction | UnserializedSignableTransaction | string | Uint8Array,
     gasPrice: number | null,
     epoch: number | null,
+    additionalArgs?: DevInspectArgs | null,
   ): Promise<DevInspectResults>;
 
   /**
@@ -30,12 +30,14 @@ import {
   FaucetResponse,
   Order,
   CoinMetadata,
+  DevInspectArgs,
   DevInspectResults,
   SuiSystemState,
   DelegatedStake,
   ValidatorMetaData,
   PaginatedCoins,
   CoinBalance,
+  PaginatedBalanceChanges,
   CoinSupply,
   CheckpointDigest,
   CommitteeInfo,
@@ -121,6 +123,16 @@ export class VoidProvider extends Provider {
     throw this.newError('getAllBalances');
   }
 
+  async getBalanceChanges(
+    _owner: string,
+    _coinType: string | null,
+    _cursor: TransactionDigest | null,
+    _limit: number | null,
+    _order: Order,
+  ): Promise<PaginatedBalanceChanges> {
+    throw this.newError('getBalanceChanges');
+  }
+
   async getCoinMetadata(_coinType: string): Promise<CoinMetadata> {
     throw new Error('getCoinMetadata');
   }
@@ -196,6 +208,7 @@ export class VoidProvider extends Provider {
     _txn: Transaction | UnserializedSignableTransaction | string | Uint8Array,
     _gasPrice: number | null = null,
     _epoch: number | null = null,
+    _additionalArgs: DevInspectArgs | null = null,
   ): Promise<DevInspectResults> {
     throw this.newError('devInspectTransaction');
   }
@@ -16,6 +16,7 @@ import {
   generateTransactionDigest,
   getTotalGasUsedUpperBound,
   SuiAddress,
+  DevInspectArgs,
   DevInspectResults,
   DryRunTransactionResponse,
   SuiTransactionResponse,
@@ -210,14 +211,23 @@ export abstract class SignerWithProvider implements Signer {
    * in the Sui System State object
    * @param epoch optional. Default to use the current epoch number stored
    * in the Sui System State object
+   * @param additionalArgs optional. Overrides the gas budget, skips checks
+   * and asks for the raw BCS transaction data and effects
    */
   async devInspectTransaction(
     tx: Transaction | UnserializedSignableTransaction | string | Uint8Array,
     gasPrice: number | null = null,
     epoch: number | null = null,
+    additionalArgs: DevInspectArgs | null = null,
   ): Promise<DevInspectResults> {
     const address = await this.getAddress();
-    return this.provider.devInspectTransaction(address, tx, gasPrice, epoch);
+    return this.provider.devInspectTransaction(
+      address,
+      tx,
+      gasPrice,
+      epoch,
+      additionalArgs,
+    );
   }
 
   /**
@@ -3,6 +3,7 @@
 
 import {
   array,
+  boolean,
   Infer,
   literal,
   nullable,
@@ -29,6 +30,7 @@ export type CoinStruct = Infer<typeof CoinStruct>;
 export const PaginatedCoins = object({
   data: array(CoinStruct),
   nextCursor: union([ObjectId, literal(null)]),
+  hasNextPage: boolean(),
 });
 
 export type PaginatedCoins = Infer<typeof PaginatedCoins>;
@@ -41,10 +43,29 @@ export const CoinBalance = object({
     epochId: optional(number()),
     number: optional(number()),
   }),
+  totalLockedBalance: optional(number()),
+  largestCoin: optional(nullable(CoinStruct)),
 });
 
 export type CoinBalance = Infer<typeof CoinBalance>;
 
+export const BalanceChange = object({
+  txDigest: TransactionDigest,
+  timestampMs: number(),
+  coinType: string(),
+  amount: number(),
+});
+
+export type BalanceChange = Infer<typeof BalanceChange>;
+
+export const PaginatedBalanceChanges = object({
+  data: array(BalanceChange),
+  nextCursor: union([TransactionDigest, literal(null)]),
+  hasNextPage: boolean(),
+});
+
+export type PaginatedBalanceChanges = Infer<typeof PaginatedBalanceChanges>;
+
 export const CoinSupply = object({
   value: number(),
 });
@@ -10,6 +10,7 @@ import {
   literal,
   Infer,
   array,
+  boolean,
   record,
   any,
   optional,
@@ -145,7 +146,8 @@ export type EventQuery =
   | { Sender: SuiAddress }
   | { Recipient: ObjectOwner }
   | { Object: ObjectId }
-  | { TimeRange: { start_time: number; end_time: number } };
+  | { TimeRange: { start_time: number; end_time: number } }
+  | { Checkpoint: { startCheckpoint: number; endCheckpoint: number } };
 
 export const EventId = object({
   txDigest: TransactionDigest,
@@ -192,6 +194,7 @@ export type SuiEvents = SuiEventEnvelope[];
 export const PaginatedEvents = object({
   data: array(SuiEventEnvelope),
   nextCursor: union([EventId, literal(null)]),
+  hasNextPage: boolean(),
 });
 export type PaginatedEvents = Infer<typeof PaginatedEvents>;
 
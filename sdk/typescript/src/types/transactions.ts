@@ -232,6 +232,18 @@ const ExecutionResultType = object({
 });
 const DevInspectResultTupleType = tuple([number(), ExecutionResultType]);
 
+const GasProfileEntry = object({
+  name: string(),
+  count: number(),
+  internalGas: number(),
+});
+
+export const GasProfile = object({
+  instructions: array(GasProfileEntry),
+  functions: array(GasProfileEntry),
+});
+export type GasProfile = Infer<typeof GasProfile>;
+
 const DevInspectResultsType = union([
   object({ Ok: array(DevInspectResultTupleType) }),
   object({ Err: string() }),
@@ -241,9 +253,23 @@ export const DevInspectResults = object({
   effects: TransactionEffects,
   events: TransactionEvents,
   results: DevInspectResultsType,
+  rawTxnData: optional(array(number())),
+  rawEffects: optional(array(number())),
+  gasProfile: optional(GasProfile),
 });
 export type DevInspectResults = Infer<typeof DevInspectResults>;
 
+export type DevInspectArgs = {
+  /** The gas budget of the run, up to the maximum gas of a transaction, which it defaults to */
+  gasBudget?: number;
+  /** Skips the validity checks of the transaction, e.g. of its size limits */
+  skipChecks?: boolean;
+  /** Returns the BCS bytes of the transaction data and effects of the run too */
+  showRawTxnDataAndEffects?: boolean;
+  /** Breaks down the gas charged by Move execution by instruction and by function */
+  profileGas?: boolean;
+};
+
 export type GatewayTxSeqNumber = number;
 
 export const GetTxnDigestsResponse = array(TransactionDigest);
@@ -252,6 +278,7 @@ export type GetTxnDigestsResponse = Infer<typeof GetTxnDigestsResponse>;
 export const PaginatedTransactionDigests = object({
   data: array(TransactionDigest),
   nextCursor: union([TransactionDigest, literal(null)]),
+  hasNextPage: boolean(),
 });
 export type PaginatedTransactionDigests = Infer<
   typeof PaginatedTransactionDigests